@@ -12,7 +12,13 @@ fn setup_opengl() {
     let mut file_gl = File::create(&Path::new(&out_dir).join("bindings.rs")).unwrap();
 
     use gl_generator::{Api, DebugStructGenerator, Fallbacks, Profile, Registry, StructGenerator};
-    let registry = Registry::new(Api::Gl, (4, 3), Profile::Core, Fallbacks::All, []);
+    let registry = Registry::new(
+        Api::Gl,
+        (4, 3),
+        Profile::Core,
+        Fallbacks::All,
+        ["GL_ARB_clip_control"],
+    );
 
     if env::var("CARGO_FEATURE_DEBUG").is_ok() {
         registry
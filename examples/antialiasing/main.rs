@@ -0,0 +1,229 @@
+use three_d::core::*;
+use three_d::*;
+
+// Compares no anti-aliasing, FXAA and SMAA on a fan of thin rotated bars, which have long nearly horizontal and vertical edges that are very jagged without anti-aliasing.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Anti-aliasing!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 6.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // A fan of thin bars with slightly different angles and a thin frame behind them
+    let mut bars = Vec::new();
+    for i in 0..24 {
+        let bar = Model::new_with_material(
+            &context,
+            &CPUMesh::cube(),
+            PhysicalMaterial {
+                albedo: Color::new_opaque(40 + (i * 9) as u8, 80, 220 - (i * 7) as u8),
+                roughness: 0.8,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        bars.push((bar, i as f32 * 7.5));
+    }
+    let mut frame = Vec::new();
+    for i in 0..4 {
+        let mut side = Model::new_with_material(
+            &context,
+            &CPUMesh::cube(),
+            PhysicalMaterial {
+                albedo: Color::new_opaque(240, 200, 60),
+                roughness: 0.8,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        side.set_transformation(
+            Mat4::from_angle_z(degrees(90.0 * i as f32 + 3.0))
+                * Mat4::from_translation(vec3(0.0, 2.0, -1.0))
+                * Mat4::from_nonuniform_scale(2.0, 0.01, 0.01),
+        );
+        frame.push(side);
+    }
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.5,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::WHITE,
+            &vec3(0.0, -0.5, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // The scene is rendered into a color and depth target which follows the size of the viewport, except when anti-aliasing is disabled
+    let mut scene_target = ResizableTarget::<u8>::new(
+        &context,
+        |context, width, height| {
+            Texture2D::new_empty(
+                context,
+                width,
+                height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )
+        },
+        |context, width, height| {
+            DepthTargetTexture2D::new(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )
+        },
+    );
+    let fxaa = FXAAEffect::new(&context).unwrap();
+    let mut smaa = SmaaEffect::new(&context).unwrap();
+
+    #[derive(PartialEq)]
+    enum AntiAliasing {
+        None,
+        FXAA,
+        SMAA,
+    }
+    let mut anti_aliasing = AntiAliasing::SMAA;
+    let mut animate = true;
+    let mut time = 0.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut anti_aliasing, AntiAliasing::None, "No anti-aliasing");
+                    ui.radio_value(&mut anti_aliasing, AntiAliasing::FXAA, "FXAA");
+                    ui.radio_value(&mut anti_aliasing, AntiAliasing::SMAA, "SMAA");
+                    ui.separator();
+                    ui.label("SMAA");
+                    ui.radio_value(
+                        &mut smaa.edge_detection,
+                        SmaaEdgeDetection::Luma,
+                        "Luma edges",
+                    );
+                    ui.radio_value(
+                        &mut smaa.edge_detection,
+                        SmaaEdgeDetection::Color,
+                        "Color edges",
+                    );
+                    ui.add(Slider::new(&mut smaa.threshold, 0.02..=0.5).text("Threshold"));
+                    ui.add(
+                        Slider::new(&mut smaa.max_search_steps, 1..=64).text("Max search steps"),
+                    );
+                    ui.add(
+                        Slider::new(&mut smaa.max_diagonal_search_steps, 1..=20)
+                            .text("Max diagonal search steps"),
+                    );
+                    ui.add(
+                        Slider::new(&mut smaa.corner_rounding, 0.0..=100.0).text("Corner rounding"),
+                    );
+                    ui.separator();
+                    ui.checkbox(&mut animate, "Animate");
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            if animate {
+                time += 0.002 * frame_input.elapsed_time as f32;
+            }
+            for (bar, angle) in bars.iter_mut() {
+                bar.set_transformation(
+                    Mat4::from_angle_z(degrees(*angle + time))
+                        * Mat4::from_translation(vec3(1.2, 0.0, 0.0))
+                        * Mat4::from_nonuniform_scale(1.0, 0.015, 0.015),
+                );
+            }
+            let render_scene = |camera: &Camera| -> ThreeDResult<()> {
+                for (bar, _) in bars.iter() {
+                    bar.render(camera, &lights)?;
+                }
+                for side in frame.iter() {
+                    side.render(camera, &lights)?;
+                }
+                Ok(())
+            };
+            let clear_state = ClearState::color_and_depth(0.95, 0.95, 0.95, 1.0, 1.0);
+
+            if anti_aliasing == AntiAliasing::None {
+                Screen::write(&context, clear_state, || {
+                    render_scene(&camera)?;
+                    gui.render()?;
+                    Ok(())
+                })
+                .unwrap();
+            } else {
+                // The scene is rendered into a target with the size of the viewport, so the viewport of the camera is moved to the origin of the target
+                camera
+                    .set_viewport(Viewport::new_at_origo(viewport.width, viewport.height))
+                    .unwrap();
+                scene_target
+                    .get(viewport.width, viewport.height)
+                    .unwrap()
+                    .write(clear_state, || render_scene(&camera))
+                    .unwrap();
+                let color_texture = scene_target.color_texture().unwrap();
+                Screen::write(&context, ClearState::none(), || {
+                    if anti_aliasing == AntiAliasing::SMAA {
+                        smaa.apply(viewport, color_texture)?;
+                    } else {
+                        fxaa.apply(viewport, color_texture)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                })
+                .unwrap();
+            }
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
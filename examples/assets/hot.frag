@@ -0,0 +1,17 @@
+// Edit this file while the hot_reload example is running to see the changes immediately
+
+uniform float time;
+uniform vec4 baseColor;
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec3 normal = normalize(nor);
+    float light = 0.3 + 0.7 * max(dot(normal, normalize(vec3(1.0, 1.0, 0.5))), 0.0);
+    float stripes = 0.5 + 0.5 * sin(10.0 * pos.y + 3.0 * time);
+    outColor = vec4(light * mix(baseColor.rgb, vec3(1.0), 0.3 * stripes), 1.0);
+}
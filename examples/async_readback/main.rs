@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+use three_d::*;
+
+// Renders a scene into a texture and reads the pixels back to the CPU every frame, which is a stress test of the synchronization between the CPU and GPU.
+// With asynchronous reads, the pixels are read a few frames later when the fence inserted after the read is signaled and the frame time is stable,
+// while blocking reads stall the CPU until the GPU has finished rendering, which gives spikes in the frame time.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Async readback!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 15.0, 30.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+
+    let mut cubes = InstancedModel::new_with_material(
+        &context,
+        &[],
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(200, 120, 60),
+            roughness: 0.6,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let cube_instances = |time: f32| {
+        (0..1000)
+            .map(|i| {
+                let angle = i as f32 * 2.4;
+                let distance = 1.0 + 0.4 * (i as f32).sqrt();
+                ModelInstance {
+                    geometry_transform: Mat4::from_translation(vec3(
+                        distance * (angle + 0.2 * time).cos(),
+                        (0.5 * distance + time).sin(),
+                        distance * (angle + 0.2 * time).sin(),
+                    )) * Mat4::from_angle_y(radians(angle + time))
+                        * Mat4::from_scale(0.3),
+                    ..Default::default()
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut blocking = false;
+    let mut time = 0.0;
+    let mut textures: Option<(Texture2D<u8>, DepthTargetTexture2D)> = None;
+    // The reads which the GPU has not finished yet
+    let mut pending = VecDeque::new();
+    let mut latest_color = [0u8; 4];
+    let mut latency = 0;
+    let mut frame_index = 0u32;
+    let mut frame_times = VecDeque::new();
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            frame_times.push_back(frame_input.elapsed_time);
+            if frame_times.len() > 60 {
+                frame_times.pop_front();
+            }
+            let average_frame_time = frame_times.iter().sum::<f64>() / frame_times.len() as f64;
+            let max_frame_time = frame_times.iter().cloned().fold(0.0, f64::max);
+
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut blocking, false, "Asynchronous reads");
+                    ui.radio_value(&mut blocking, true, "Blocking reads");
+                    ui.label(format!("Average frame time: {:.1} ms", average_frame_time));
+                    ui.label(format!("Max frame time: {:.1} ms", max_frame_time));
+                    ui.label(format!("Read latency: {} frames", latency));
+                    ui.label(format!("Pending reads: {}", pending.len()));
+                    ui.label(format!(
+                        "Center pixel: {}, {}, {}",
+                        latest_color[0], latest_color[1], latest_color[2]
+                    ));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            time += 0.001 * frame_input.elapsed_time as f32;
+            cubes.set_instances(&cube_instances(time));
+
+            // Recreate the textures when the size of the viewport changes
+            if textures
+                .as_ref()
+                .map(|(color, _)| {
+                    color.width() != viewport.width || color.height() != viewport.height
+                })
+                .unwrap_or(true)
+            {
+                pending.clear();
+                textures = Some((
+                    Texture2D::new_empty(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Interpolation::Nearest,
+                        Interpolation::Nearest,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                    .unwrap(),
+                    DepthTargetTexture2D::new(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                    .unwrap(),
+                ));
+            }
+            let (color_texture, depth_texture) = textures.as_mut().unwrap();
+            let texture_viewport = Viewport::new_at_origo(viewport.width, viewport.height);
+            camera.set_viewport(texture_viewport).unwrap();
+            RenderTarget::new(&context, color_texture, depth_texture)
+                .unwrap()
+                .write(ClearState::color_and_depth(0.2, 0.2, 0.3, 1.0, 1.0), || {
+                    cubes.render(&camera, &lights)
+                })
+                .unwrap();
+
+            // Read the pixels of the frame just rendered, either by waiting for the GPU or by inserting a fence and reading them when it is signaled
+            let center = |pixels: &[u8]| {
+                let i = 4
+                    * (viewport.width as usize * (viewport.height as usize / 2)
+                        + viewport.width as usize / 2);
+                [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+            };
+            if blocking {
+                pending.clear();
+                context.finish();
+                latest_color = center(&color_texture.read(texture_viewport).unwrap());
+                latency = 0;
+            } else {
+                pending.push_back((
+                    frame_index,
+                    color_texture.read_async(texture_viewport).unwrap(),
+                ));
+                while let Some(pixels) = pending.front().and_then(|(_, handle)| handle.try_get()) {
+                    let (index, _) = pending.pop_front().unwrap();
+                    latest_color = center(&pixels);
+                    latency = frame_index - index;
+                }
+            }
+            frame_index += 1;
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0),
+                || Ok(()),
+            )
+            .unwrap();
+            Screen::copy_from(
+                &context,
+                Some(&*color_texture),
+                None,
+                viewport,
+                WriteMask::COLOR,
+            )
+            .unwrap();
+            Screen::write(&context, ClearState::none(), || gui.render()).unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,231 @@
+use three_d::*;
+
+// Walks the camera from a dark room lit by a single dim lamp out through the door to the bright sky outside and back again,
+// while the auto exposure of the HDR pipeline adapts to the brightness like the eye does.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Auto exposure!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 1.5, -3.0),
+        vec3(0.0, 1.5, -6.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+
+    let skybox = Loading::new(
+        &context,
+        &["examples/assets/chinese_garden_4k.hdr"], // Source: https://polyhaven.com/
+        move |context, mut loaded| {
+            Skybox::new_with_texture(
+                &context,
+                TextureCubeMap::<f16>::new_from_equirectangular(
+                    &context,
+                    &loaded.hdr_image("chinese_garden_4k")?,
+                )?,
+            )
+        },
+    );
+
+    // A room in the range [-3..3] x [0..3] x [-6..0] with a door in the wall at z = 0
+    let material = PhysicalMaterial {
+        albedo: Color::new_opaque(200, 190, 170),
+        roughness: 0.9,
+        ..Default::default()
+    };
+    let boxes = [
+        (vec3(0.0, -0.1, -3.0), vec3(3.2, 0.1, 3.2)),
+        (vec3(0.0, 3.1, -3.0), vec3(3.2, 0.1, 3.2)),
+        (vec3(-3.1, 1.5, -3.0), vec3(0.1, 1.5, 3.2)),
+        (vec3(3.1, 1.5, -3.0), vec3(0.1, 1.5, 3.2)),
+        (vec3(0.0, 1.5, -6.1), vec3(3.2, 1.5, 0.1)),
+        (vec3(-1.95, 1.5, 0.1), vec3(1.15, 1.5, 0.1)),
+        (vec3(1.95, 1.5, 0.1), vec3(1.15, 1.5, 0.1)),
+        (vec3(0.0, 2.6, 0.1), vec3(0.8, 0.4, 0.1)),
+    ];
+    let walls: Vec<_> = boxes
+        .iter()
+        .map(|(center, half_size)| {
+            let mut wall =
+                Model::new_with_material(&context, &CPUMesh::cube(), material.clone()).unwrap();
+            wall.set_transformation(
+                Mat4::from_translation(*center)
+                    * Mat4::from_nonuniform_scale(half_size.x, half_size.y, half_size.z),
+            );
+            wall
+        })
+        .collect();
+    let room_lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.01,
+            ..Default::default()
+        }),
+        point: vec![PointLight::new(
+            &context,
+            0.5,
+            Color::new_opaque(255, 200, 150),
+            &vec3(0.0, 2.7, -4.0),
+            0.5,
+            0.2,
+            0.1,
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut hdr_pipeline = HdrPipeline::new(&context).unwrap();
+    hdr_pipeline.auto_exposure = Some(AutoExposure::new(&context).unwrap());
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut use_auto_exposure = true;
+    let mut walk_automatically = true;
+    let mut outside = false;
+    // The position along the walk, where 0 is inside and 1 is outside
+    let mut walk = 0.0f32;
+    let mut time_since_walk = 0.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    if ui
+                        .button(if outside { "Go inside" } else { "Go outside" })
+                        .clicked()
+                    {
+                        outside = !outside;
+                    }
+                    ui.checkbox(&mut walk_automatically, "Walk automatically");
+                    ui.checkbox(&mut use_auto_exposure, "Auto exposure");
+                    if let Some(ref mut auto_exposure) = hdr_pipeline.auto_exposure {
+                        ui.add(
+                            Slider::new(&mut auto_exposure.compensation, -4.0..=4.0)
+                                .text("Compensation (EV)"),
+                        );
+                        ui.add(Slider::new(&mut auto_exposure.min_ev, -12.0..=0.0).text("Min EV"));
+                        ui.add(Slider::new(&mut auto_exposure.max_ev, 0.0..=12.0).text("Max EV"));
+                        ui.add(
+                            Slider::new(&mut auto_exposure.brighten_time, 0.0..=3.0)
+                                .text("Brighten time (s)"),
+                        );
+                        ui.add(
+                            Slider::new(&mut auto_exposure.darken_time, 0.0..=3.0)
+                                .text("Darken time (s)"),
+                        );
+                        ui.label("Metering");
+                        ui.radio_value(
+                            &mut auto_exposure.metering,
+                            MeteringMode::Average,
+                            "Average",
+                        );
+                        ui.radio_value(
+                            &mut auto_exposure.metering,
+                            MeteringMode::CenterWeighted,
+                            "Center weighted",
+                        );
+                        ui.radio_value(
+                            &mut auto_exposure.metering,
+                            MeteringMode::Spot(0.3),
+                            "Spot",
+                        );
+                    } else {
+                        ui.add(
+                            Slider::new(&mut hdr_pipeline.tone_mapping.exposure, 0.0..=4.0)
+                                .text("Exposure"),
+                        );
+                    }
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            if use_auto_exposure != hdr_pipeline.auto_exposure.is_some() {
+                hdr_pipeline.auto_exposure = if use_auto_exposure {
+                    Some(AutoExposure::new(&context).unwrap())
+                } else {
+                    None
+                };
+            }
+
+            // Walks through the door in one and a half second and turns around on the way
+            let seconds = frame_input.elapsed_time as f32 / 1000.0;
+            time_since_walk += seconds;
+            if walk_automatically && time_since_walk > 6.0 {
+                outside = !outside;
+            }
+            let goal = if outside { 1.0 } else { 0.0 };
+            if walk != goal {
+                time_since_walk = 0.0;
+                walk = if goal > walk {
+                    (walk + seconds / 1.5).min(goal)
+                } else {
+                    (walk - seconds / 1.5).max(goal)
+                };
+            }
+            let t = walk * walk * (3.0 - 2.0 * walk);
+            let yaw = std::f32::consts::PI * (1.0 - t);
+            let position = vec3(0.0, 1.5, -3.0 + 8.0 * t);
+            let direction = vec3(yaw.sin(), 0.4 * t, yaw.cos());
+            camera
+                .set_view(position, position + direction, vec3(0.0, 1.0, 0.0))
+                .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            if let Some(Ok(ref skybox)) = *skybox.borrow() {
+                hdr_pipeline
+                    .write(&camera, |camera| {
+                        skybox.render(camera)?;
+                        render_pass(camera, &walls, &room_lights)
+                    })
+                    .unwrap();
+                hdr_pipeline
+                    .auto_exposure_pass(frame_input.elapsed_time)
+                    .unwrap();
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+                || {
+                    if skybox.is_loaded() {
+                        hdr_pipeline.tone_mapping_pass(viewport)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
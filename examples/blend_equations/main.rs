@@ -0,0 +1,114 @@
+use three_d::*;
+
+// Draws a rectangle given by its minimum and maximum corner in normalized device coordinates
+const VERTEX_SHADER: &str = "
+uniform vec4 rect;
+const vec2 corners[6] = vec2[6](vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0), vec2(1.0, 1.0), vec2(0.0, 1.0), vec2(0.0, 0.0));
+void main()
+{
+    gl_Position = vec4(mix(rect.xy, rect.zw, corners[gl_VertexID]), 0.0, 1.0);
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+uniform vec4 color;
+layout (location = 0) out vec4 outColor;
+void main()
+{
+    outColor = color;
+}
+";
+
+fn main() {
+    let viewport = Viewport::new_at_origo(64, 64);
+
+    // Create a headless graphics context
+    let context = Context::new().unwrap();
+    let mut texture = Texture2D::<u8>::new_empty(
+        &context,
+        viewport.width,
+        viewport.height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+    .unwrap();
+
+    let first = [0.8, 0.2, 0.5, 0.4];
+    let second = [0.3, 0.6, 0.2, 0.9];
+    let max = [0.8, 0.6, 0.5, 0.9];
+    let min = [0.3, 0.2, 0.2, 0.4];
+    let mut tests = vec![
+        ("max", Blend::MAX, None, 0.0, max),
+        ("min", Blend::MIN, None, 1.0, min),
+        ("subtract", Blend::SUBTRACT, None, 1.0, [0.0, 0.2, 0.3, 0.0]),
+    ];
+    if context.capabilities().draw_buffers_blend {
+        // The blend of the first color texture overrides the blend used for all color textures
+        let mut attachment_blends = [Blend::Disabled; MAX_BLEND_ATTACHMENTS];
+        attachment_blends[0] = Blend::MIN;
+        tests.push((
+            "attachment min",
+            Blend::MAX,
+            Some(attachment_blends),
+            1.0,
+            min,
+        ));
+    } else {
+        println!("Blending each color texture individually is not supported");
+    }
+
+    for (name, blend, attachment_blends, clear_value, expected) in tests {
+        // Draw two overlapping rectangles, the first covering the left and the second the right two thirds of the texture
+        texture
+            .write(
+                ClearState::color(clear_value, clear_value, clear_value, clear_value),
+                || {
+                    context.program(VERTEX_SHADER, FRAGMENT_SHADER, |program| {
+                        let render_states = RenderStates {
+                            write_mask: WriteMask::COLOR,
+                            blend,
+                            attachment_blends,
+                            ..Default::default()
+                        };
+                        for (rect, color) in [
+                            (vec4(-1.0, -1.0, 1.0 / 3.0, 1.0), first),
+                            (vec4(-1.0 / 3.0, -1.0, 1.0, 1.0), second),
+                        ] {
+                            program.use_uniform_vec4("rect", &rect)?;
+                            program.use_uniform_vec4(
+                                "color",
+                                &vec4(color[0], color[1], color[2], color[3]),
+                            )?;
+                            program.draw_arrays(render_states, viewport, 6);
+                        }
+                        Ok(())
+                    })
+                },
+            )
+            .unwrap();
+
+        // Read back the pixel in the center, where the rectangles overlap
+        let pixels = texture.read(viewport).unwrap();
+        let center = 4 * (viewport.width * viewport.height / 2 + viewport.width / 2) as usize;
+        let result = &pixels[center..center + 4];
+        let matches = result
+            .iter()
+            .zip(expected.iter())
+            .all(|(r, e)| (*r as f32 - e * 255.0).abs() <= 2.0);
+        println!(
+            "{}: {:?}, expected {:?}, {}",
+            name,
+            result,
+            expected
+                .iter()
+                .map(|e| (e * 255.0).round() as u8)
+                .collect::<Vec<_>>(),
+            if matches { "ok" } else { "FAILED" }
+        );
+        assert!(matches);
+    }
+}
@@ -0,0 +1,28 @@
+use three_d::*;
+
+// Saves files in an asset bundle which can be loaded in a single request, for example
+// cargo run --example bundle -- examples/assets/statues.bundle examples/assets/COLOMBE.obj examples/assets/COLOMBE.mtl examples/assets/COLOMBE.png
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        println!("Usage: bundle <output> <files>...");
+        return;
+    }
+    let output = &args[1];
+    let paths = &args[2..];
+    Saver::save_bundle(paths, output).unwrap();
+
+    // Load each file from the bundle again to check that it is identical to the original file
+    let mut loaded = Loaded::from_bundle(&std::fs::read(output).unwrap()).unwrap();
+    for path in paths {
+        let bytes = loaded.get_bytes(path).unwrap();
+        assert_eq!(bytes, &std::fs::read(path).unwrap()[..]);
+        println!("{}: {} bytes", path, bytes.len());
+    }
+    println!(
+        "Saved {} files in {} ({} bytes)",
+        paths.len(),
+        output,
+        std::fs::metadata(output).unwrap().len()
+    );
+}
@@ -0,0 +1,158 @@
+use three_d::core::*;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Camera path!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(-60.0, 50.0, 60.0),
+        vec3(0.0, 15.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // Model from http://texturedmesh.isti.cnr.it/
+    let statue = Loading::new(
+        &context,
+        &[
+            "examples/assets/COLOMBE.obj",
+            "examples/assets/COLOMBE.mtl",
+            "examples/assets/COLOMBE.png",
+        ],
+        move |context, mut loaded| {
+            let (cpu_mesh, cpu_material) = loaded.obj("examples/assets/COLOMBE.obj")?.remove(0);
+            let mut material = PhysicalMaterial::new(&context, &cpu_material.unwrap())?;
+            material.opaque_render_states.cull = Cull::Back;
+            Model::new_with_material(&context, &cpu_mesh, material)
+        },
+    );
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let target = vec3(0.0, 15.0, 0.0);
+    let mut path = CameraPath::default();
+    path.closed = true;
+    for i in 0..6 {
+        let angle = i as f32 * 2.0 * std::f32::consts::PI / 6.0;
+        let distance = if i % 2 == 0 { 80.0 } else { 50.0 };
+        path.add_keyframe(
+            format!("View {}", i + 1),
+            Pose {
+                position: vec3(
+                    angle.cos() * distance,
+                    30.0 + 20.0 * angle.sin(),
+                    angle.sin() * distance,
+                ),
+                target,
+                up: vec3(0.0, 1.0, 0.0),
+                field_of_view_y: Some(degrees(if i % 2 == 0 { 35.0 } else { 50.0 }).into()),
+            },
+        );
+    }
+
+    // main loop
+    let mut time = 0.0;
+    let mut playing = true;
+    let mut tween: Option<CameraTween> = None;
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Timeline");
+                    ui.checkbox(&mut playing, "Play");
+                    if ui
+                        .add(Slider::new(&mut time, 0.0..=1.0).text("Time"))
+                        .changed()
+                    {
+                        playing = false;
+                        tween = None;
+                    }
+                    ui.heading("Saved views");
+                    for (name, pose) in path.keyframes() {
+                        if ui.button(name).clicked() {
+                            playing = false;
+                            tween =
+                                Some(CameraTween::to(&camera, *pose, 1000.0, Easing::EaseInOut));
+                        }
+                    }
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            if let Some(ref mut t) = tween {
+                let finished = t.update(frame_input.elapsed_time);
+                camera.set_pose(&t.pose()).unwrap();
+                if finished {
+                    tween = None;
+                }
+            } else {
+                if playing {
+                    time = (time + 0.00005 * frame_input.elapsed_time as f32).fract();
+                }
+                camera.set_pose(&path.sample(time).unwrap()).unwrap();
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.7, 1.0, 1.0),
+                || {
+                    if let Some(ref statue) = *statue.borrow() {
+                        statue.as_ref().unwrap().render(&camera, &lights)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,124 @@
+use three_d::*;
+
+// Two cubes next to each other 10 million units from the origin, seen from a camera orbiting closely around them.
+// Without camera relative rendering, the vertices are rounded to whole units in single precision world space and the cubes jitter and tear apart,
+// while with camera relative rendering they are transformed to view space in double precision and stay still.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Camera relative!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let center = dvec3(1.0e7, 0.0, 0.0);
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+
+    let cube = |color: Color, offset: f64| {
+        let mut model = Model::new_with_material(
+            &context,
+            &CPUMesh::cube(),
+            PhysicalMaterial {
+                albedo: color,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        model.set_transformation_f64(
+            DMat4::from_translation(center + dvec3(offset, 0.0, 0.0)) * DMat4::from_scale(0.5),
+        );
+        model
+    };
+    let models = vec![
+        cube(Color::new_opaque(200, 50, 50), -0.5),
+        cube(Color::new_opaque(50, 50, 200), 0.5),
+    ];
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -0.5),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut camera_relative = true;
+    let mut distance: f64 = 3.0;
+    let mut time: f64 = 0.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut camera_relative, "Camera relative");
+                    ui.add(Slider::new(&mut distance, 1.5..=20.0).text("Distance"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            camera.set_camera_relative(camera_relative);
+
+            // The camera position is computed in double precision, since it would otherwise be rounded to whole units as well
+            time += 0.0005 * frame_input.elapsed_time;
+            let position = center + distance * dvec3(time.sin(), 0.4, time.cos());
+            camera
+                .set_view_f64(position, center, vec3(0.0, 1.0, 0.0))
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &models, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,200 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Clustered lights!".to_string(),
+        max_size: Some((1280, 720)),
+        // Measure the actual frame rate instead of the refresh rate of the monitor
+        vsync: false,
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let forward_pipeline = ForwardPipeline::new(&context).unwrap();
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 25.0, 40.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        200.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 150.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    let mut plane = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(200, 200, 200),
+            roughness: 0.8,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    plane.set_transformation(Mat4::from_scale(40.0) * Mat4::from_angle_x(degrees(-90.0)));
+
+    // A field of 40x40 cubes of varying height
+    let mut instances = Vec::new();
+    for x in -20..20 {
+        for z in -20..20 {
+            let height = 0.5 + 1.5 * ((x * 7 + z * 13) as f32).sin().abs();
+            instances.push(ModelInstance {
+                geometry_transform: Mat4::from_translation(vec3(
+                    2.0 * x as f32 + 1.0,
+                    height,
+                    2.0 * z as f32 + 1.0,
+                )) * Mat4::from_nonuniform_scale(0.5, height, 0.5),
+                ..Default::default()
+            });
+        }
+    }
+    let cubes = InstancedModel::new_with_material(
+        &context,
+        &instances,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::WHITE,
+            roughness: 0.5,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // 500 small point lights which only affect the cubes close to them
+    let colors = [
+        Color::RED,
+        Color::GREEN,
+        Color::BLUE,
+        Color::new_opaque(255, 255, 0),
+        Color::new_opaque(0, 255, 255),
+        Color::new_opaque(255, 0, 255),
+    ];
+    let light_count = 500;
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            color: Color::WHITE,
+            intensity: 0.05,
+            ..Default::default()
+        }),
+        point: (0..light_count)
+            .map(|i| {
+                PointLight::new(
+                    &context,
+                    1.0,
+                    colors[i % colors.len()],
+                    &vec3(0.0, 0.0, 0.0),
+                    1.0,
+                    0.5,
+                    2.0,
+                )
+                .unwrap()
+            })
+            .collect(),
+        ..Default::default()
+    }
+    .clustered(LightClustering::default());
+
+    let mut clustered = true;
+    let mut heatmap = false;
+    let mut fps = 0.0;
+
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = frame_input.viewport.width;
+            if frame_input.elapsed_time > 0.0 {
+                fps = 0.9 * fps + 0.1 * 1000.0 / frame_input.elapsed_time;
+            }
+            let dropped_light_count = lights
+                .clustering
+                .as_ref()
+                .map(|c| c.dropped_light_count())
+                .unwrap_or(0);
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut clustered, "Clustered lights");
+                    ui.checkbox(&mut heatmap, "Heatmap");
+                    ui.label(format!("FPS: {:.0}", fps));
+                    ui.label(format!("Dropped lights: {}", dropped_light_count));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // Changing the clustering changes the shaders, so only do it when the settings change
+            if clustered != lights.clustering.is_some() {
+                lights.clustering = if clustered {
+                    Some(LightClustering::default())
+                } else {
+                    None
+                };
+            }
+            if let Some(ref mut clustering) = lights.clustering {
+                if clustering.heatmap() != heatmap {
+                    clustering.set_heatmap(heatmap);
+                }
+            }
+
+            let time = 0.001 * frame_input.accumulated_time as f32;
+            for (i, light) in lights.point.iter_mut().enumerate() {
+                let radius = 2.0 + 36.0 * (i as f32 / light_count as f32);
+                let speed = if i % 2 == 0 { 0.1 } else { -0.07 };
+                let angle = time * speed + i as f32 * 2.4;
+                light.set_position(&vec3(
+                    radius * angle.cos(),
+                    1.0 + 0.8 * (time + i as f32).sin(),
+                    radius * angle.sin(),
+                ));
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+                || {
+                    forward_pipeline.render_pass(
+                        &camera,
+                        &[&plane as &dyn Object, &cubes],
+                        &lights,
+                    )?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
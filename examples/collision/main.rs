@@ -0,0 +1,223 @@
+use three_d::*;
+
+struct Ball {
+    position: Vec3,
+    velocity: Vec3,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Collision!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(-300.0, 250.0, 300.0),
+        vec3(0.0, 50.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        10000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 100.0, 2000.0);
+
+    // Model from http://texturedmesh.isti.cnr.it/
+    let scene = Loading::new(
+        &context,
+        &[
+            "examples/assets/COLOMBE.obj",
+            "examples/assets/COLOMBE.mtl",
+            "examples/assets/COLOMBE.png",
+        ],
+        move |context, mut loaded| {
+            let (cpu_mesh, cpu_material) =
+                loaded.obj("examples/assets/COLOMBE.obj").unwrap().remove(0);
+            let mut material = PhysicalMaterial::new(&context, &cpu_material.unwrap()).unwrap();
+            material.opaque_render_states.cull = Cull::Back;
+            let transformation = Mat4::from_scale(10.0);
+            let mut statue = Model::new_with_material(&context, &cpu_mesh, material)?;
+            statue.set_transformation(transformation);
+            let statue_collider = TriMeshCollider::new(&cpu_mesh, transformation)?;
+
+            // A ground plane below the statue
+            let aabb = statue.aabb();
+            let ground_transformation = Mat4::from_translation(vec3(0.0, aabb.min().y, 0.0))
+                * Mat4::from_scale(2.0 * aabb.size().x.max(aabb.size().z))
+                * Mat4::from_angle_x(degrees(-90.0));
+            let mut ground = Model::new_with_material(
+                &context,
+                &CPUMesh::square(),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(180, 180, 170),
+                    roughness: 0.8,
+                    ..Default::default()
+                },
+            )?;
+            ground.set_transformation(ground_transformation);
+            let ground_collider = TriMeshCollider::new(&CPUMesh::square(), ground_transformation)?;
+
+            let radius = 0.03 * aabb.size().y;
+            let mut ball = Model::new_with_material(
+                &context,
+                &CPUMesh::sphere(16),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(200, 50, 50),
+                    metallic: 0.5,
+                    roughness: 0.3,
+                    ..Default::default()
+                },
+            )?;
+            ball.set_transformation(Mat4::from_scale(radius));
+            Ok((
+                vec![statue, ground],
+                vec![statue_collider, ground_collider],
+                ball,
+                radius,
+                aabb,
+            ))
+        },
+    );
+
+    let lights = Lights {
+        ambient: Some(AmbientLight::new_hemisphere(
+            0.4,
+            Color::new_opaque(180, 210, 255),
+            Color::new_opaque(120, 100, 80),
+        )),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::new_opaque(204, 178, 127),
+            &vec3(0.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // main loop
+    let mut balls: Vec<Ball> = Vec::new();
+    let mut spawned = 0;
+    let mut time_since_spawn = 0.0;
+    window
+        .render_loop(move |mut frame_input| {
+            camera.set_viewport(frame_input.viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            if let Some(Ok((ref models, ref colliders, ref mut ball, radius, ref aabb))) =
+                *scene.borrow_mut()
+            {
+                // Drop a new ball above the statue every now and then
+                time_since_spawn += frame_input.elapsed_time;
+                if time_since_spawn > 300.0 && balls.len() < 50 {
+                    time_since_spawn = 0.0;
+                    spawned += 1;
+                    let angle = spawned as f32 * 2.4;
+                    let offset = 0.3 * (spawned as f32 * 0.7).sin();
+                    balls.push(Ball {
+                        position: aabb.center()
+                            + vec3(
+                                angle.cos() * offset * aabb.size().x,
+                                0.8 * aabb.size().y,
+                                angle.sin() * offset * aabb.size().z,
+                            ),
+                        velocity: vec3(0.0, 0.0, 0.0),
+                    });
+                }
+
+                // Move the balls in a few fixed time steps per frame to keep them from tunneling when the frame rate is low
+                let steps = 4;
+                let dt = (frame_input.elapsed_time as f32 * 0.001).min(0.05) / steps as f32;
+                for _ in 0..steps {
+                    for b in balls.iter_mut() {
+                        step(b, colliders, radius, dt);
+                    }
+                }
+                // Remove the balls which rolled off the ground
+                balls.retain(|b| b.position.y > aabb.min().y - aabb.size().y);
+
+                Screen::write(
+                    &context,
+                    ClearState::color_and_depth(0.8, 0.8, 0.7, 1.0, 1.0),
+                    || {
+                        for model in models.iter() {
+                            model.render(&camera, &lights)?;
+                        }
+                        for b in balls.iter() {
+                            ball.set_transformation(
+                                Mat4::from_translation(b.position) * Mat4::from_scale(radius),
+                            );
+                            ball.render(&camera, &lights)?;
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            }
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+fn step(ball: &mut Ball, colliders: &[TriMeshCollider], radius: f32, dt: f32) {
+    let gravity = vec3(0.0, -9.82 * 30.0, 0.0);
+    let skin = 0.01 * radius;
+    ball.velocity += gravity * dt;
+    let mut motion = ball.velocity * dt;
+
+    // Move until the first contact, then slide along the surface with the rest of the motion
+    for _ in 0..3 {
+        let hit = colliders
+            .iter()
+            .filter_map(|collider| collider.sphere_cast(ball.position, radius, motion))
+            .min_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+        match hit {
+            Some(hit) => {
+                ball.position += motion * hit.time + hit.normal * skin;
+                let normal_speed = ball.velocity.dot(hit.normal);
+                if normal_speed < 0.0 {
+                    // Remove the velocity into the surface and bounce back a little
+                    ball.velocity -= hit.normal * normal_speed * 1.3;
+                }
+                ball.velocity *= 0.995;
+                motion *= 1.0 - hit.time;
+                motion -= hit.normal * motion.dot(hit.normal).min(0.0);
+            }
+            None => {
+                ball.position += motion;
+                break;
+            }
+        }
+    }
+
+    // Push the ball out of the surfaces if it ends up slightly inside them
+    for collider in colliders.iter() {
+        let (closest, distance) = collider.closest_point(ball.position);
+        if distance > 0.0 && distance < radius + skin {
+            ball.position += (ball.position - closest) * ((radius + skin - distance) / distance);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use three_d::*;
+
+// A 50% gray given as an sRGB vertex color, the same gray given as a linear vertex color and as an sRGB material color,
+// which must all end up as the same pixel value on the screen, since the colors are converted to linear space exactly once before the lighting
+// and back to sRGB exactly once when written to the screen. The pixel values are read back from the screen and shown in the panel.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Color spaces!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_orthographic(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        2.0,
+        0.1,
+        10.0,
+    )
+    .unwrap();
+
+    // 50% gray in sRGB is 128, which is 0.2158 in linear space, ie. 55
+    let gray_square = |value: u8, color_space: ColorSpace| {
+        let mut cpu_mesh = CPUMesh::square();
+        cpu_mesh.colors = Some([value, value, value, 255].repeat(cpu_mesh.positions.len() / 3));
+        cpu_mesh.color_space = color_space;
+        cpu_mesh
+    };
+    let mut squares = vec![
+        Model::new(&context, &gray_square(128, ColorSpace::Srgb)).unwrap(),
+        Model::new(&context, &gray_square(55, ColorSpace::Linear)).unwrap(),
+        Model::new_with_material(
+            &context,
+            &CPUMesh::square(),
+            ColorMaterial {
+                color: Color::new_opaque(128, 128, 128),
+                color_space: ColorSpace::Srgb,
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+    ];
+    let centers = [
+        vec3(-0.7, 0.0, 0.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.7, 0.0, 0.0),
+    ];
+    for (square, center) in squares.iter_mut().zip(centers.iter()) {
+        square.set_transformation(Mat4::from_translation(*center) * Mat4::from_scale(0.3));
+    }
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut pixel_values = [0u8; 3];
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label(format!("sRGB vertex color: {}", pixel_values[0]));
+                    ui.label(format!("Linear vertex color: {}", pixel_values[1]));
+                    ui.label(format!("sRGB material color: {}", pixel_values[2]));
+                    // The linear value 55 is not exactly 50% gray, so allow a difference of one
+                    let min = pixel_values.iter().min().unwrap();
+                    let max = pixel_values.iter().max().unwrap();
+                    ui.label(if max - min <= 1 && (*min as i32 - 128).abs() <= 1 {
+                        "The values match"
+                    } else {
+                        "The values do not match!"
+                    });
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &squares, &Lights::default())?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            // Read back the pixel at the center of each square
+            for (value, center) in pixel_values.iter_mut().zip(centers.iter()) {
+                let (u, v) = camera.uv_coordinates_at_position(*center);
+                let pixel = Screen::read_color(
+                    &context,
+                    Viewport {
+                        x: viewport.x + (u * viewport.width as f32) as i32,
+                        y: viewport.y + (v * viewport.height as f32) as i32,
+                        width: 1,
+                        height: 1,
+                    },
+                )
+                .unwrap();
+                *value = pixel[0];
+            }
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
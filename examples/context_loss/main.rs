@@ -0,0 +1,85 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Context loss!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    // All GPU resources are created in this function, so that they can be recreated when the context is restored
+    let create_scene =
+        |context: &Context, viewport: Viewport| -> ThreeDResult<(Camera, Model<ColorMaterial>)> {
+            let camera = Camera::new_perspective(
+                context,
+                viewport,
+                vec3(0.0, 0.0, 4.0),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                degrees(45.0),
+                0.1,
+                10.0,
+            )?;
+            let mut cpu_mesh = CPUMesh::cube();
+            cpu_mesh.colors = Some(
+                cpu_mesh
+                    .positions
+                    .chunks(3)
+                    .flat_map(|p| {
+                        vec![
+                            (127.0 + 128.0 * p[0]) as u8,
+                            (127.0 + 128.0 * p[1]) as u8,
+                            (127.0 + 128.0 * p[2]) as u8,
+                            255,
+                        ]
+                    })
+                    .collect(),
+            );
+            let model = Model::new(context, &cpu_mesh)?;
+            Ok((camera, model))
+        };
+    let (mut camera, mut model) = create_scene(&context, window.viewport().unwrap()).unwrap();
+
+    window
+        .render_loop(move |frame_input| {
+            for event in frame_input.events.iter() {
+                if let Event::ContextRestored = event {
+                    // The old resources are invalid after the context has been lost
+                    let scene = create_scene(&context, frame_input.viewport).unwrap();
+                    camera = scene.0;
+                    model = scene.1;
+                }
+            }
+            camera.set_viewport(frame_input.viewport).unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    let time = 0.001 * frame_input.accumulated_time as f32;
+                    model.set_transformation(
+                        Mat4::from_angle_y(radians(time)) * Mat4::from_angle_x(radians(0.7 * time)),
+                    );
+                    model.render(&camera, &Lights::default())?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,229 @@
+use rand::prelude::*;
+use three_d::core::*;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Crowd!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(30.0, 20.0, 30.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 200.0);
+
+    // A simple character with a body attached to the hip joint and a leg attached to each leg joint
+    let hip = vec3(0.0, 1.0, 0.0);
+    let skeleton = Skeleton {
+        joints: vec![
+            Joint {
+                name: "Hip".to_string(),
+                translation: hip,
+                inverse_bind_matrix: Mat4::from_translation(-hip),
+                ..Default::default()
+            },
+            Joint {
+                name: "Left leg".to_string(),
+                parent: Some(0),
+                translation: vec3(-0.2, 0.0, 0.0),
+                inverse_bind_matrix: Mat4::from_translation(-hip - vec3(-0.2, 0.0, 0.0)),
+                ..Default::default()
+            },
+            Joint {
+                name: "Right leg".to_string(),
+                parent: Some(0),
+                translation: vec3(0.2, 0.0, 0.0),
+                inverse_bind_matrix: Mat4::from_translation(-hip - vec3(0.2, 0.0, 0.0)),
+                ..Default::default()
+            },
+        ],
+    };
+    let mut cpu_mesh = CPUMesh::default();
+    add_box(
+        &mut cpu_mesh,
+        Mat4::from_translation(vec3(0.0, 1.5, 0.0)) * Mat4::from_nonuniform_scale(0.35, 0.5, 0.2),
+        0,
+    );
+    add_box(
+        &mut cpu_mesh,
+        Mat4::from_translation(vec3(-0.2, 0.5, 0.0)) * Mat4::from_nonuniform_scale(0.1, 0.5, 0.1),
+        1,
+    );
+    add_box(
+        &mut cpu_mesh,
+        Mat4::from_translation(vec3(0.2, 0.5, 0.0)) * Mat4::from_nonuniform_scale(0.1, 0.5, 0.1),
+        2,
+    );
+
+    // A walk cycle of one second where the legs swing back and forth and the hip bounces
+    let times = vec![0.0, 0.25, 0.5, 0.75, 1.0];
+    let swing = |angle: f32| {
+        Some(
+            [angle, 0.0, -angle, 0.0, angle]
+                .iter()
+                .map(|a| Quat::from_angle_x(degrees(*a)))
+                .collect(),
+        )
+    };
+    let clip = AnimationClip {
+        name: "Walk".to_string(),
+        joint_animations: vec![
+            JointAnimation {
+                joint: 0,
+                key_frames: KeyFrames {
+                    times: times.clone(),
+                    translations: Some(
+                        [0.0, 0.08, 0.0, 0.08, 0.0]
+                            .iter()
+                            .map(|y| hip + vec3(0.0, *y, 0.0))
+                            .collect(),
+                    ),
+                    ..Default::default()
+                },
+            },
+            JointAnimation {
+                joint: 1,
+                key_frames: KeyFrames {
+                    times: times.clone(),
+                    rotations: swing(30.0),
+                    ..Default::default()
+                },
+            },
+            JointAnimation {
+                joint: 2,
+                key_frames: KeyFrames {
+                    times,
+                    rotations: swing(-30.0),
+                    ..Default::default()
+                },
+            },
+        ],
+    };
+
+    // 500 characters walking out of phase, with different speeds, directions and colors
+    let mut rng = rand::thread_rng();
+    let instances = (0..500)
+        .map(|i| AnimatedModelInstance {
+            geometry_transform: Mat4::from_translation(vec3(
+                2.0 * (i % 25) as f32 - 24.0,
+                0.0,
+                2.0 * (i / 25) as f32 - 19.0,
+            )) * Mat4::from_angle_y(degrees(rng.gen::<f32>() * 360.0)),
+            color: Color::new_opaque(rng.gen(), rng.gen(), rng.gen()),
+            animation_time_offset: rng.gen::<f32>(),
+            animation_speed: 0.8 + 0.4 * rng.gen::<f32>(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let mut crowd = AnimatedInstancedModel::new_with_material(
+        &context,
+        &instances,
+        &cpu_mesh,
+        &skeleton,
+        &clip,
+        30.0,
+        PhysicalMaterial {
+            albedo: Color::WHITE,
+            roughness: 0.7,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(100, 120, 100),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(30.0));
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            camera.set_viewport(frame_input.viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+            crowd.set_animation_time(0.001 * frame_input.accumulated_time as f32);
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    ground.render(&camera, &lights)?;
+                    crowd.render(&camera, &lights)?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// Adds a transformed cube to the mesh where all vertices are attached to the given joint
+fn add_box(cpu_mesh: &mut CPUMesh, transformation: Mat4, joint: u16) {
+    let mut cube = CPUMesh::cube();
+    cube.transform(&transformation);
+    let vertex_count = cube.positions.len() / 3;
+    cpu_mesh.positions.extend(cube.positions);
+    cpu_mesh
+        .normals
+        .get_or_insert_with(Vec::new)
+        .extend(cube.normals.unwrap());
+    cpu_mesh
+        .joint_indices
+        .get_or_insert_with(Vec::new)
+        .extend((0..vertex_count).flat_map(|_| vec![joint, 0, 0, 0]));
+    cpu_mesh
+        .joint_weights
+        .get_or_insert_with(Vec::new)
+        .extend((0..vertex_count).flat_map(|_| vec![1.0, 0.0, 0.0, 0.0]));
+}
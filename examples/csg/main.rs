@@ -0,0 +1,259 @@
+use three_d::*;
+
+// Constructive solid geometry with a cube and a sphere, which can be dragged on the horizontal plane with the left mouse button.
+// The result is computed again when an operand is released or the settings change, so it also works in debug builds where it is slow,
+// and the panel shows whether the result is watertight.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "CSG!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(4.0, 4.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 50.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    let operands = [CPUMesh::cube(), CPUMesh::sphere(16)];
+    let mut translations = [vec3(0.0, 0.0, 0.0), vec3(0.8, 0.5, 0.6)];
+    let scales = [1.0, 1.2];
+    let transformation = move |i: usize, translations: &[Vec3; 2]| {
+        Mat4::from_translation(translations[i]) * Mat4::from_scale(scales[i])
+    };
+
+    // The operands are transparent, so the result is visible inside them
+    let mut operand_models = [
+        Model::new_with_material(
+            &context,
+            &operands[0],
+            PhysicalMaterial {
+                albedo: Color::new(100, 130, 200, 60),
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+        Model::new_with_material(
+            &context,
+            &operands[1],
+            PhysicalMaterial {
+                albedo: Color::new(200, 120, 80, 60),
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+    ];
+    let result_material = PhysicalMaterial {
+        albedo: Color::new_opaque(220, 220, 220),
+        roughness: 0.4,
+        ..Default::default()
+    };
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -0.5),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut op = BooleanOp::Difference;
+    let mut epsilon = BOOLEAN_EPSILON;
+    let mut show_operands = true;
+    let mut result: Option<(Model<PhysicalMaterial>, usize, bool)> = None;
+    let mut error: Option<String> = None;
+    let mut changed = true;
+    // The index of the dragged operand, the point where it was grabbed and its translation at that time
+    let mut drag: Option<(usize, Vec3, Vec3)> = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    let previous = (op, epsilon);
+                    ui.radio_value(&mut op, BooleanOp::Union, "Union");
+                    ui.radio_value(&mut op, BooleanOp::Difference, "Difference");
+                    ui.radio_value(&mut op, BooleanOp::Intersection, "Intersection");
+                    ui.add(
+                        Slider::new(&mut epsilon, 1e-7..=1e-2)
+                            .logarithmic(true)
+                            .text("Epsilon"),
+                    );
+                    changed |= previous != (op, epsilon);
+                    ui.checkbox(&mut show_operands, "Show operands");
+                    if let Some((_, triangles, closed)) = result {
+                        ui.label(format!("Triangles: {}", triangles));
+                        ui.label(format!("Closed manifold: {}", closed));
+                    }
+                    if let Some(ref error) = error {
+                        ui.label(format!("Error: {}", error));
+                    }
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            // Dragging an operand moves it on the horizontal plane through the point where it was grabbed,
+            // while dragging elsewhere rotates the camera
+            let screen_height = frame_input.viewport.height;
+            for event in frame_input.events.iter_mut() {
+                match event {
+                    Event::MousePress {
+                        button: MouseButton::Left,
+                        position,
+                        handled,
+                        ..
+                    } if !*handled => {
+                        let pixel = position.to_viewport_pixel(viewport, screen_height);
+                        let eye = *camera.position();
+                        let mut closest: Option<(usize, Vec3)> = None;
+                        for (i, model) in operand_models.iter().enumerate() {
+                            if let Some(point) = pick(&context, &camera, pixel, &[model]).unwrap() {
+                                if closest.map_or(true, |(_, p)| {
+                                    (point - eye).magnitude() < (p - eye).magnitude()
+                                }) {
+                                    closest = Some((i, point));
+                                }
+                            }
+                        }
+                        if let Some((i, point)) = closest {
+                            drag = Some((i, point, translations[i]));
+                            *handled = true;
+                        }
+                    }
+                    Event::MouseMotion {
+                        position, handled, ..
+                    } => {
+                        if let Some((i, grab, start)) = drag {
+                            let pixel = position.to_viewport_pixel(viewport, screen_height);
+                            let origin = camera.position_at_pixel(pixel);
+                            let direction = camera.view_direction_at_pixel(pixel);
+                            if direction.y.abs() > 0.0001 {
+                                let t = (grab.y - origin.y) / direction.y;
+                                if t > 0.0 {
+                                    translations[i] = start + (origin + direction * t - grab);
+                                    operand_models[i]
+                                        .set_transformation(transformation(i, &translations));
+                                }
+                            }
+                            *handled = true;
+                        }
+                    }
+                    Event::MouseRelease {
+                        button: MouseButton::Left,
+                        handled,
+                        ..
+                    } => {
+                        if drag.take().is_some() {
+                            changed = true;
+                            *handled = true;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            if changed {
+                changed = false;
+                for (i, model) in operand_models.iter_mut().enumerate() {
+                    model.set_transformation(transformation(i, &translations));
+                }
+                match operands[0].boolean_with_epsilon(
+                    &operands[1],
+                    op,
+                    transformation(0, &translations),
+                    transformation(1, &translations),
+                    epsilon,
+                ) {
+                    Ok(cpu_mesh) => {
+                        let triangles = cpu_mesh
+                            .indices
+                            .as_ref()
+                            .map_or(0, |indices| indices.into_u32().len() / 3);
+                        // An empty result, for example the intersection of operands which do not overlap, encloses nothing
+                        let closed = cpu_mesh.positions.is_empty() || cpu_mesh.is_closed_manifold();
+                        result = Some((
+                            Model::new_with_material(&context, &cpu_mesh, result_material.clone())
+                                .unwrap(),
+                            triangles,
+                            closed,
+                        ));
+                        error = None;
+                    }
+                    Err(e) => {
+                        result = None;
+                        error = Some(e.to_string());
+                    }
+                }
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    if let Some((ref model, _, _)) = result {
+                        model.render(&camera, &lights)?;
+                    }
+                    if show_operands {
+                        for model in operand_models.iter() {
+                            model.render(&camera, &lights)?;
+                        }
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,192 @@
+use three_d::*;
+
+// A debug overlay which draws a ring exactly under the cursor using the physical position of the mouse events
+// and a small sphere where the ray through the cursor hits the scene, which is rendered in a viewport below a panel at the top of the window.
+// Both markers should stay centered on the tip of the cursor at any scale factor, which can be tested on web by zooming the browser to 150% and 200%
+// and on desktop by moving the window to a screen with another scale factor or for example by setting WINIT_X11_SCALE_FACTOR=1.5 on X11.
+// The scale of the GUI can also be overridden to test that the GUI responds to the cursor at the same scales.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Cursor marker!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(4.0, 4.0, 6.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(150, 150, 150),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_scale(10.0) * Mat4::from_angle_x(degrees(-90.0)));
+    let mut cube = Model::new_with_material(
+        &context,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(100, 130, 200),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    cube.set_transformation(Mat4::from_translation(vec3(0.0, 1.0, 0.0)));
+    let mut pick_marker = Model::new_with_material(
+        &context,
+        &CPUMesh::sphere(16),
+        PhysicalMaterial {
+            albedo: Color::RED,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // The ring is drawn as a filled circle with a smaller circle on top
+    let mut outer_ring = Circle::new_with_material(
+        &context,
+        vec2(0.0, 0.0),
+        12.0,
+        ColorMaterial {
+            color: Color::GREEN,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut inner_ring = Circle::new_with_material(
+        &context,
+        vec2(0.0, 0.0),
+        9.0,
+        ColorMaterial {
+            color: Color::BLACK,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut gui_scale: Option<f32> = None;
+    let mut cursor: Option<PointerPosition> = None;
+    let mut picked = false;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_height = 0;
+            let device_pixel_ratio = frame_input.device_pixel_ratio;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                TopBottomPanel::top("top_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label(format!("Device pixel ratio: {}", device_pixel_ratio));
+                    if let Some(cursor) = cursor {
+                        ui.label(format!(
+                            "Logical: ({:.1}, {:.1}) Physical: ({:.1}, {:.1})",
+                            cursor.logical.0,
+                            cursor.logical.1,
+                            cursor.physical.0,
+                            cursor.physical.1
+                        ));
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("GUI scale");
+                        ui.radio_value(&mut gui_scale, None, "Device");
+                        ui.radio_value(&mut gui_scale, Some(1.0), "1.0");
+                        ui.radio_value(&mut gui_scale, Some(1.5), "1.5");
+                        ui.radio_value(&mut gui_scale, Some(2.0), "2.0");
+                    });
+                });
+                panel_height = (gui_context.used_size().y * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            gui.set_pixels_per_point(gui_scale);
+
+            // The viewport of the camera does not start at the top of the window, so the pixel for picking is given by to_viewport_pixel
+            let viewport = Viewport {
+                x: 0,
+                y: 0,
+                width: frame_input.viewport.width,
+                height: frame_input.viewport.height - panel_height.min(frame_input.viewport.height),
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            for event in frame_input.events.iter() {
+                if let Event::MouseMotion { position, .. } = event {
+                    cursor = Some(*position);
+                    let center = vec2(position.physical.0, position.physical.1);
+                    outer_ring.set_center(center);
+                    inner_ring.set_center(center);
+                    let pixel = frame_input.to_viewport_pixel(position, viewport);
+                    let hit = pick(&context, &camera, pixel, &[&ground, &cube]).unwrap();
+                    picked = hit.is_some();
+                    if let Some(point) = hit {
+                        pick_marker.set_transformation(
+                            Mat4::from_translation(point) * Mat4::from_scale(0.05),
+                        );
+                    }
+                }
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    ground.render(&camera, &lights)?;
+                    cube.render(&camera, &lights)?;
+                    if picked {
+                        pick_marker.render(&camera, &lights)?;
+                    }
+                    // The ring is positioned relative to the top left corner of the window, so it is rendered in the viewport of the whole window
+                    if cursor.is_some() {
+                        outer_ring.render(frame_input.viewport)?;
+                        inner_ring.render(frame_input.viewport)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
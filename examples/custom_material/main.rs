@@ -0,0 +1,207 @@
+use three_d::core::*;
+use three_d::*;
+
+// A custom material written against the ShaderSourceBuilder, which is lit by the lights in the scene, adds a rim light along the silhouette
+// and optionally stripes in world space. The same material is used for a model and an instanced model,
+// where the vertex shader matching the inputs declared by the material is generated for each of them.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Custom material!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 4.0, 10.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    let material = RimMaterial {
+        color: Color::new_opaque(60, 120, 200),
+        rim_color: Color::new_opaque(255, 220, 150),
+        rim_power: 3.0,
+        stripes: None,
+    };
+    let mut sphere =
+        Model::new_with_material(&context, &CPUMesh::sphere(32), material.clone()).unwrap();
+    sphere.set_transformation(Mat4::from_scale(1.5));
+    let instances: Vec<_> = (0..12)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::PI / 6.0;
+            ModelInstance {
+                geometry_transform: Mat4::from_translation(vec3(
+                    4.0 * angle.cos(),
+                    0.0,
+                    4.0 * angle.sin(),
+                )) * Mat4::from_angle_y(radians(angle))
+                    * Mat4::from_scale(0.5),
+                ..Default::default()
+            }
+        })
+        .collect();
+    let mut cubes =
+        InstancedModel::new_with_material(&context, &instances, &CPUMesh::cube(), material)
+            .unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.2,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut use_stripes = false;
+    let mut stripe_count = 8;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(
+                        Slider::new(&mut sphere.material.rim_power, 0.5..=8.0).text("Rim power"),
+                    );
+                    ui.checkbox(&mut use_stripes, "Stripes");
+                    ui.add(Slider::new(&mut stripe_count, 1..=32).text("Stripe count"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            // The stripe count is a define, so a new shader program is compiled (and cached) for each count
+            sphere.material.stripes = if use_stripes {
+                Some(stripe_count)
+            } else {
+                None
+            };
+            cubes.material = sphere.material.clone();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &[&sphere as &dyn Object, &cubes], &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+#[derive(Clone)]
+struct RimMaterial {
+    color: Color,
+    rim_color: Color,
+    rim_power: f32,
+    // The number of stripes per unit in world space, if any
+    stripes: Option<u32>,
+}
+
+impl Material for RimMaterial {
+    fn fragment_shader_source(&self, _use_vertex_colors: bool, lights: &Lights) -> String {
+        let mut builder = ShaderSourceBuilder::new();
+        if let Some(stripes) = self.stripes {
+            builder.define_value("STRIPE_COUNT", stripes);
+        }
+        // The position and normal are interpolated in world space and available as `pos` and `nor`
+        builder
+            .input(ShaderInput::Position)
+            .input(ShaderInput::Normal)
+            // Adds `calculate_lighting` and `encode_output` among others
+            .lights(lights)
+            .block(
+                "rim_material",
+                "
+                uniform vec4 surfaceColor;
+                uniform vec4 rimColor;
+                uniform float rimPower;
+
+                layout (location = 0) out vec4 outColor;
+
+                void main()
+                {
+                    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+                    vec3 color = rgb_from_srgb(surfaceColor.rgb);
+                #ifdef STRIPE_COUNT
+                    color *= 0.5 + 0.5 * step(0.5, fract(pos.y * float(STRIPE_COUNT)));
+                #endif
+                    vec3 lit = calculate_lighting(color, pos, normal, 0.0, 0.8, 1.0);
+                    float rim = pow(1.0 - max(dot(normal, normalize(eyePosition - pos)), 0.0), rimPower);
+                    outColor = vec4(encode_output(lit + rim * rgb_from_srgb(rimColor.rgb)), 1.0);
+                }
+                ",
+            );
+        builder.fragment_source()
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        lights.use_uniforms(program, camera)?;
+        program.use_uniform_vec4("surfaceColor", &self.color.to_vec4())?;
+        program.use_uniform_vec4("rimColor", &self.rim_color.to_vec4())?;
+        program.use_uniform_float("rimPower", &self.rim_power)
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn is_transparent(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let frame_count = if args.len() > 1 {
+        args[1].parse().unwrap()
+    } else {
+        100
+    };
+    let viewport = Viewport::new_at_origo(640, 480);
+
+    // Create a headless graphics context
+    let context = Context::new().unwrap();
+
+    let mut camera = Camera::perspective(&context, viewport)
+        .position(vec3(0.0, 2.0, 5.0))
+        .target(vec3(0.0, 0.0, 0.0))
+        .fov(degrees(60.0))
+        .near(0.1)
+        .far(10.0)
+        .build()
+        .unwrap();
+
+    // Create the scene - a few colored shapes on a plane
+    let mut models = vec![
+        Model::new_with_material(
+            &context,
+            &CPUMesh::cube(),
+            PhysicalMaterial::builder()
+                .albedo(Color::new_opaque(200, 50, 50))
+                .build(&context)
+                .unwrap(),
+        )
+        .unwrap(),
+        Model::new_with_material(
+            &context,
+            &CPUMesh::sphere(32),
+            PhysicalMaterial::builder()
+                .albedo(Color::new_opaque(50, 200, 50))
+                .build(&context)
+                .unwrap(),
+        )
+        .unwrap(),
+        Model::new_with_material(
+            &context,
+            &CPUMesh::square(),
+            PhysicalMaterial::builder()
+                .albedo(Color::new_opaque(180, 180, 180))
+                .build(&context)
+                .unwrap(),
+        )
+        .unwrap(),
+    ];
+    models[0]
+        .set_transformation(Mat4::from_translation(vec3(-1.5, 0.0, 0.0)) * Mat4::from_scale(0.7));
+    models[1].set_transformation(Mat4::from_translation(vec3(1.5, 0.0, 0.0)));
+    models[2].set_transformation(
+        Mat4::from_translation(vec3(0.0, -1.0, 0.0))
+            * Mat4::from_scale(5.0)
+            * Mat4::from_angle_x(degrees(-90.0)),
+    );
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            color: Color::WHITE,
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::builder(&context)
+            .direction(vec3(-1.0, -1.0, -1.0))
+            .intensity(2.0)
+            .build()
+            .unwrap()],
+        ..Default::default()
+    };
+    let depth_material = DepthMaterial::default();
+
+    // Create a color and a depth texture to render into, the depth is stored as the distance to the camera in a floating point texture
+    let mut color_texture = Texture2D::<u8>::new_empty(
+        &context,
+        viewport.width,
+        viewport.height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+    .unwrap();
+    let mut distance_texture = Texture2D::<f32>::new_empty(
+        &context,
+        viewport.width,
+        viewport.height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::R,
+    )
+    .unwrap();
+    let mut depth_texture = DepthTargetTexture2D::new(
+        &context,
+        viewport.width,
+        viewport.height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )
+    .unwrap();
+
+    // The reads which are not yet saved to disk
+    let mut pending = VecDeque::new();
+    for frame_index in 0..frame_count {
+        let angle = frame_index as f32 * 2.0 * std::f32::consts::PI / frame_count as f32;
+        camera
+            .set_view(
+                vec3(5.0 * angle.sin(), 2.0, 5.0 * angle.cos()),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+            )
+            .unwrap();
+
+        RenderTarget::new(&context, &mut color_texture, &mut depth_texture)
+            .unwrap()
+            .write(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0), || {
+                for model in models.iter() {
+                    model.render(&camera, &lights)?;
+                }
+                Ok(())
+            })
+            .unwrap();
+        RenderTarget::new(&context, &mut distance_texture, &mut depth_texture)
+            .unwrap()
+            .write(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0), || {
+                for model in models.iter() {
+                    model.render_with_material(&depth_material, &camera, &lights)?;
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        // Start reading the result of this frame without waiting for the GPU to finish rendering
+        pending.push_back((
+            frame_index,
+            color_texture.read_async(viewport).unwrap(),
+            distance_texture.read_async(viewport).unwrap(),
+        ));
+
+        // Save the frames which the GPU has finished, usually the previous frames
+        while pending
+            .front()
+            .map(|(_, color, distance)| color.is_ready() && distance.is_ready())
+            .unwrap_or(false)
+        {
+            let (index, color, distance) = pending.pop_front().unwrap();
+            save(index, &color, &distance);
+        }
+    }
+
+    // Wait for the remaining frames
+    for (index, color, distance) in pending {
+        while !color.is_ready() || !distance.is_ready() {
+            std::thread::yield_now();
+        }
+        save(index, &color, &distance);
+    }
+}
+
+///
+/// Saves the color as a PNG image and the distances as raw little endian 32 bit floats, one per pixel starting with the top row.
+///
+fn save(frame_index: u32, color: &ReadbackHandle<u8>, distance: &ReadbackHandle<f32>) {
+    Saver::save_pixels(
+        format!("color-{}.png", frame_index),
+        &color.try_get().unwrap(),
+        color.width(),
+        color.height(),
+    )
+    .unwrap();
+    let bytes = distance
+        .try_get_flipped()
+        .unwrap()
+        .iter()
+        .flat_map(|d| d.to_le_bytes().to_vec())
+        .collect::<Vec<_>>();
+    Saver::save_file(format!("distance-{}.bin", frame_index), &bytes).unwrap();
+}
@@ -0,0 +1,173 @@
+use rand::prelude::*;
+use three_d::*;
+
+// Generates a dataset of frames from random camera poses around a few shapes, where each frame is saved as
+// a color image, an OpenEXR image with the depth and normals, the object id of each pixel and the camera parameters.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let frame_count = if args.len() > 1 {
+        args[1].parse().unwrap()
+    } else {
+        100
+    };
+    let (width, height) = (640, 480);
+
+    // Create a headless graphics context
+    let context = Context::new().unwrap();
+
+    let mut camera = Camera::perspective(&context, Viewport::new_at_origo(width, height))
+        .fov(degrees(60.0))
+        .near(0.1)
+        .far(30.0)
+        .build()
+        .unwrap();
+
+    // The shapes with the ids 1 to 4, where 0 is used where nothing is visible
+    let shape = |cpu_mesh: &CPUMesh, color: Color, transformation: Mat4| {
+        let mut model = Model::new_with_material(
+            &context,
+            cpu_mesh,
+            PhysicalMaterial::builder()
+                .albedo(color)
+                .build(&context)
+                .unwrap(),
+        )
+        .unwrap();
+        model.set_transformation(transformation);
+        model
+    };
+    let objects = vec![
+        (
+            shape(
+                &CPUMesh::square(),
+                Color::new_opaque(180, 180, 180),
+                Mat4::from_translation(vec3(0.0, -1.0, 0.0))
+                    * Mat4::from_scale(6.0)
+                    * Mat4::from_angle_x(degrees(-90.0)),
+            ),
+            1,
+        ),
+        (
+            shape(
+                &CPUMesh::cube(),
+                Color::new_opaque(200, 50, 50),
+                Mat4::from_translation(vec3(-1.5, -0.3, 0.0)) * Mat4::from_scale(0.7),
+            ),
+            2,
+        ),
+        (
+            shape(
+                &CPUMesh::sphere(32),
+                Color::new_opaque(50, 200, 50),
+                Mat4::from_translation(vec3(1.5, 0.0, 0.0)),
+            ),
+            3,
+        ),
+        (
+            shape(
+                &CPUMesh::cylinder(32),
+                Color::new_opaque(50, 50, 200),
+                Mat4::from_translation(vec3(0.0, -1.0, -1.5))
+                    * Mat4::from_angle_z(degrees(90.0))
+                    * Mat4::from_nonuniform_scale(1.5, 0.5, 0.5),
+            ),
+            4,
+        ),
+    ];
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            color: Color::WHITE,
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::builder(&context)
+            .direction(vec3(-1.0, -1.0, -1.0))
+            .intensity(2.0)
+            .build()
+            .unwrap()],
+        ..Default::default()
+    };
+
+    // The color is anti-aliased with 3x3 samples per pixel, which does not affect the depth, ids and normals
+    let mut renderer = DatasetRenderer::new(&context, width, height).unwrap();
+    renderer.color_options.samples_per_pixel = 3;
+    renderer.color_options.clear_state = ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0);
+
+    // A fixed seed, so the same dataset is generated each time
+    let mut rng = StdRng::seed_from_u64(42);
+    for frame_index in 0..frame_count {
+        let azimuth = rng.gen_range(0.0, 2.0 * std::f32::consts::PI);
+        let elevation = rng.gen_range(0.1, 1.2f32);
+        let distance = rng.gen_range(4.0, 8.0f32);
+        let position = distance
+            * vec3(
+                elevation.cos() * azimuth.sin(),
+                elevation.sin(),
+                elevation.cos() * azimuth.cos(),
+            );
+        let target = vec3(
+            rng.gen_range(-0.5, 0.5),
+            rng.gen_range(-0.5, 0.5),
+            rng.gen_range(-0.5, 0.5),
+        );
+        camera
+            .set_view(position, target, vec3(0.0, 1.0, 0.0))
+            .unwrap();
+
+        renderer.render(&camera, &objects, &lights).unwrap();
+        let frame = renderer.read_all().unwrap();
+        save(frame_index, &frame);
+
+        // The surface at the center pixel, which is found from the depth and the camera parameters, is close to the target
+        if let Some(p) = frame.view_position(width / 2, height / 2) {
+            let p = frame.view.invert().unwrap() * p.extend(1.0);
+            println!(
+                "Frame {}: the center pixel shows object {} at {:?}",
+                frame_index,
+                frame.ids[(height / 2 * width + width / 2) as usize],
+                p.truncate()
+            );
+        }
+    }
+}
+
+///
+/// Saves the color as a PNG image, the depth and normals as an OpenEXR image, the ids as raw little endian 32 bit integers, one per pixel starting with the top row,
+/// and the intrinsic camera parameters and the view matrix as text.
+///
+fn save(frame_index: u32, frame: &DatasetFrame) {
+    // The frame starts with the top row, while the pixels given to save_pixels start with the bottom row
+    let color = frame
+        .color
+        .chunks(frame.width as usize * 4)
+        .rev()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+    Saver::save_pixels(
+        format!("color-{:04}.png", frame_index),
+        &color,
+        frame.width,
+        frame.height,
+    )
+    .unwrap();
+    Saver::save_file(format!("data-{:04}.exr", frame_index), &frame.encode_exr()).unwrap();
+    let ids = frame
+        .ids
+        .iter()
+        .flat_map(|id| id.to_le_bytes().to_vec())
+        .collect::<Vec<_>>();
+    Saver::save_file(format!("ids-{:04}.bin", frame_index), &ids).unwrap();
+    let intrinsics = frame.intrinsics.unwrap();
+    let view: &[f32; 16] = frame.view.as_ref();
+    Saver::save_file(
+        format!("camera-{:04}.txt", frame_index),
+        format!(
+            "fx {}\nfy {}\ncx {}\ncy {}\nview {:?}\n",
+            intrinsics.fx, intrinsics.fy, intrinsics.cx, intrinsics.cy, view
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+}
@@ -0,0 +1,220 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Depth of field!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 2.0, 8.0),
+        vec3(0.0, 0.0, -10.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(vec3(0.0, 0.0, -10.0), 1.0, 50.0);
+
+    // Two rows of spheres going into the distance
+    let mut models = Vec::new();
+    for i in 0..10 {
+        for side in [-1.5, 1.5].iter() {
+            let mut model = Model::new_with_material(
+                &context,
+                &CPUMesh::sphere(32),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(50 + 20 * i as u8, 100, 250 - 20 * i as u8),
+                    roughness: 0.3,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            model.set_transformation(Mat4::from_translation(vec3(
+                *side,
+                0.0,
+                5.0 - 3.0 * i as f32,
+            )));
+            models.push(model);
+        }
+    }
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(150, 150, 150),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(
+        Mat4::from_translation(vec3(0.0, -1.0, -10.0))
+            * Mat4::from_scale(30.0)
+            * Mat4::from_angle_x(degrees(-90.0)),
+    );
+    models.push(ground);
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut dof_effect = DofEffect::new(&context).unwrap();
+    dof_effect.focus_distance = 10.0;
+    dof_effect.focal_length = 0.5;
+    dof_effect.f_number = 2.8;
+    let mut dof_enabled = true;
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut color_texture: Option<Texture2D<u8>> = None;
+    let mut depth_texture: Option<DepthTargetTexture2D> = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut dof_enabled, "Depth of field");
+                    ui.add(
+                        Slider::new(&mut dof_effect.focus_distance, 0.5..=40.0)
+                            .text("Focus distance"),
+                    );
+                    ui.add(
+                        Slider::new(&mut dof_effect.focal_length, 0.05..=1.0).text("Focal length"),
+                    );
+                    ui.add(Slider::new(&mut dof_effect.f_number, 1.0..=22.0).text("F-number"));
+                    ui.add(
+                        Slider::new(&mut dof_effect.max_blur_radius, 1.0..=32.0)
+                            .text("Max blur radius"),
+                    );
+                    ui.label("Click on the scene to focus on the point under the cursor");
+                });
+            })
+            .unwrap();
+
+            camera.set_viewport(frame_input.viewport).unwrap();
+
+            // Focus on the point under the cursor when clicking outside the panel
+            for event in frame_input.events.iter() {
+                if let Event::MousePress {
+                    button: MouseButton::Left,
+                    position,
+                    handled: false,
+                    ..
+                } = event
+                {
+                    let pixel = frame_input.to_viewport_pixel(position, camera.viewport());
+                    if let Some(point) = pick(&context, &camera, pixel, &models).unwrap() {
+                        dof_effect.focus_distance = camera.position().distance(point);
+                    }
+                }
+            }
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // Render the scene into a color and depth texture, recreated when the window is resized
+            let viewport = frame_input.viewport;
+            if color_texture
+                .as_ref()
+                .map(|t| t.width() != viewport.width || t.height() != viewport.height)
+                .unwrap_or(true)
+            {
+                color_texture = Some(
+                    Texture2D::new_empty(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Interpolation::Nearest,
+                        Interpolation::Nearest,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                    .unwrap(),
+                );
+                depth_texture = Some(
+                    DepthTargetTexture2D::new(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                    .unwrap(),
+                );
+            }
+            let color_texture = color_texture.as_mut().unwrap();
+            let depth_texture = depth_texture.as_mut().unwrap();
+            RenderTarget::new(&context, color_texture, depth_texture)
+                .unwrap()
+                .write(ClearState::color_and_depth(0.9, 0.9, 0.9, 1.0, 1.0), || {
+                    render_pass(&camera, &models, &lights)
+                })
+                .unwrap();
+            let color_texture = &*color_texture;
+            let depth_texture = &*depth_texture;
+
+            if dof_enabled {
+                dof_effect
+                    .blur_pass(&camera, color_texture, depth_texture)
+                    .unwrap();
+            } else {
+                Screen::copy_from(
+                    &context,
+                    Some(color_texture),
+                    None,
+                    viewport,
+                    WriteMask::default(),
+                )
+                .unwrap();
+            }
+
+            Screen::write(&context, ClearState::none(), || {
+                if dof_enabled {
+                    dof_effect.apply(&camera, color_texture, depth_texture)?;
+                }
+                gui.render()?;
+                Ok(())
+            })
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,165 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Dynamic resolution!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(8.0, 6.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    let mut spheres = Vec::new();
+    for x in -3..=3 {
+        for z in -3..=3 {
+            let mut sphere = Model::new_with_material(
+                &context,
+                &CPUMesh::sphere(32),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque((128 + 18 * x) as u8, 100, (128 + 18 * z) as u8),
+                    roughness: 0.3,
+                    metallic: 0.5,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            sphere.set_transformation(
+                Mat4::from_translation(vec3(x as f32 * 1.5, 0.0, z as f32 * 1.5))
+                    * Mat4::from_scale(0.6),
+            );
+            spheres.push(sphere);
+        }
+    }
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            3.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -0.5),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut dynamic_resolution = DynamicResolution::new(&context).unwrap();
+    dynamic_resolution.min_scale = 0.25;
+    let mut frame_times = std::collections::VecDeque::new();
+    let mut load = 0.0;
+    let mut sharpen = true;
+    let mut sharpness = 0.5;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            frame_times.push_back(frame_input.elapsed_time);
+            if frame_times.len() > 30 {
+                frame_times.pop_front();
+            }
+            let average_frame_time = frame_times.iter().sum::<f64>() / frame_times.len() as f64;
+
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut load, 0.0..=50.0).text("Artificial load (ms)"));
+                    ui.add(
+                        Slider::new(&mut dynamic_resolution.target_frame_time, 5.0..=50.0)
+                            .text("Target frame time (ms)"),
+                    );
+                    ui.add(
+                        Slider::new(&mut dynamic_resolution.min_scale, 0.1..=1.0).text("Min scale"),
+                    );
+                    ui.add(
+                        Slider::new(&mut dynamic_resolution.max_scale, 0.1..=1.0).text("Max scale"),
+                    );
+                    ui.checkbox(&mut sharpen, "Sharpen");
+                    ui.add(Slider::new(&mut sharpness, 0.0..=1.0).text("Sharpness"));
+                    ui.label(format!("Frame time: {:.1} ms", average_frame_time));
+                    ui.label(format!("Scale: {:.0}%", 100.0 * dynamic_resolution.scale()));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            let history = frame_times.iter().cloned().collect::<Vec<_>>();
+            let render_viewport = dynamic_resolution.begin_frame(&history, viewport);
+
+            // The artificial load is proportional to the number of rendered pixels, like a scene limited by fragment shading
+            let pixel_fraction = (render_viewport.width * render_viewport.height) as f64
+                / (viewport.width * viewport.height).max(1) as f64;
+            std::thread::sleep(std::time::Duration::from_secs_f64(
+                0.001 * load * pixel_fraction,
+            ));
+
+            dynamic_resolution
+                .render_pass(&camera, &spheres, &lights)
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0),
+                || {
+                    dynamic_resolution.resolve_to_screen(
+                        viewport,
+                        if sharpen {
+                            UpscaleFilter::Sharpen(sharpness)
+                        } else {
+                            UpscaleFilter::Linear
+                        },
+                    )?;
+                    // The GUI is rendered at full resolution
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
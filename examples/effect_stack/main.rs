@@ -0,0 +1,349 @@
+use three_d::core::*;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Effect stack!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 3.0, 12.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        200.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // Two rows of pillars disappearing into the fog with glowing spheres in between
+    let mut models = Vec::new();
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(90, 100, 80),
+            roughness: 1.0,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_scale(100.0) * Mat4::from_angle_x(degrees(-90.0)));
+    models.push(ground);
+    for i in 0..10 {
+        let z = -6.0 * i as f32;
+        for x in [-3.0, 3.0].iter() {
+            let mut pillar = Model::new_with_material(
+                &context,
+                &CPUMesh::cube(),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(160, 150, 140),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            pillar.set_transformation(
+                Mat4::from_translation(vec3(*x, 2.0, z))
+                    * Mat4::from_nonuniform_scale(0.4, 2.0, 0.4),
+            );
+            models.push(pillar);
+        }
+        let mut sphere = Model::new_with_material(
+            &context,
+            &CPUMesh::sphere(16),
+            PhysicalMaterial {
+                albedo: Color::BLACK,
+                emissive: Color::new_opaque(255, 200, 120),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        sphere.set_transformation(
+            Mat4::from_translation(vec3(0.0, 1.0, z - 3.0)) * Mat4::from_scale(0.3),
+        );
+        models.push(sphere);
+    }
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // The scene is rendered into a color and depth target which follows the size of the window
+    let mut scene_target = ResizableTarget::<f16>::new(
+        &context,
+        |context, width, height| {
+            Texture2D::new_empty(
+                context,
+                width,
+                height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )
+        },
+        |context, width, height| {
+            DepthTargetTexture2D::new(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )
+        },
+    );
+
+    let mut fog = FogEffect::new(&context).unwrap();
+    fog.density = 0.04;
+    fog.color = vec3(0.6, 0.65, 0.7);
+    let mut effect_stack = EffectStack::new(&context).unwrap();
+    effect_stack.push(fog);
+    effect_stack.push(BloomEffect::new(&context).unwrap());
+    effect_stack.push(FXAAEffect::new(&context).unwrap());
+    let names = ["Fog", "Bloom", "FXAA"];
+    let mut enabled = [true; 3];
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    for (name, enabled) in names.iter().zip(enabled.iter_mut()) {
+                        ui.checkbox(enabled, *name);
+                    }
+                    ui.label("The effects are applied from top to bottom");
+                });
+            })
+            .unwrap();
+            for (effect, enabled) in effect_stack.effects.iter_mut().zip(enabled.iter()) {
+                effect.set_enabled(*enabled);
+            }
+
+            let viewport = frame_input.viewport;
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            scene_target
+                .get(viewport.width, viewport.height)
+                .unwrap()
+                .write(
+                    ClearState::color_and_depth(0.6, 0.65, 0.7, 1.0, 1.0),
+                    || {
+                        for model in models.iter() {
+                            model.render(&camera, &lights)?;
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            // The effect stack resizes its intermediate targets and the targets of the bloom effect to the size of the output
+            let inputs = EffectInputs {
+                depth_texture: scene_target.depth_texture(),
+                time: frame_input.accumulated_time as f32,
+                ..EffectInputs::new(&camera, scene_target.color_texture().unwrap())
+            };
+            effect_stack
+                .apply(&inputs, EffectOutput::screen(&context, viewport))
+                .unwrap();
+            Screen::write(&context, ClearState::none(), || gui.render()).unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+///
+/// A custom post effect which makes bright parts of the image glow by blurring them at half the resolution and adding the result to the image.
+///
+struct BloomEffect {
+    enabled: bool,
+    threshold: f32,
+    intensity: f32,
+    bright_target: ResizableTarget<f16>,
+    blur_target: ResizableTarget<f16>,
+    bright_pass: ImageEffect,
+    blur_pass: ImageEffect,
+    composite_pass: ImageEffect,
+}
+
+impl BloomEffect {
+    fn new(context: &Context) -> ThreeDResult<Self> {
+        let new_target = || {
+            let mut target = ResizableTarget::new_color(context, |context, width, height| {
+                Texture2D::new_empty(
+                    context,
+                    width,
+                    height,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                    Format::RGBA,
+                )
+            });
+            target.scale_factor = 0.5;
+            target
+        };
+        Ok(Self {
+            enabled: true,
+            threshold: 0.8,
+            intensity: 1.5,
+            bright_target: new_target(),
+            blur_target: new_target(),
+            bright_pass: ImageEffect::new(
+                context,
+                "
+                uniform sampler2D colorMap;
+                uniform float threshold;
+                in vec2 uv;
+                layout (location = 0) out vec4 color;
+                void main()
+                {
+                    vec3 c = texture(colorMap, uv).rgb;
+                    float brightness = max(c.r, max(c.g, c.b));
+                    color = vec4(c * max(brightness - threshold, 0.0) / max(brightness, 0.0001), 1.0);
+                }",
+            )?,
+            blur_pass: ImageEffect::new(
+                context,
+                "
+                uniform sampler2D colorMap;
+                uniform vec2 direction;
+                in vec2 uv;
+                layout (location = 0) out vec4 color;
+                const float weights[5] = float[5](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+                void main()
+                {
+                    vec3 result = texture(colorMap, uv).rgb * weights[0];
+                    for (int i = 1; i < 5; i++) {
+                        result += texture(colorMap, uv + float(i) * direction).rgb * weights[i];
+                        result += texture(colorMap, uv - float(i) * direction).rgb * weights[i];
+                    }
+                    color = vec4(result, 1.0);
+                }",
+            )?,
+            composite_pass: ImageEffect::new(
+                context,
+                "
+                uniform sampler2D colorMap;
+                uniform sampler2D bloomMap;
+                uniform float intensity;
+                in vec2 uv;
+                layout (location = 0) out vec4 color;
+                void main()
+                {
+                    vec4 c = texture(colorMap, uv);
+                    color = vec4(c.rgb + intensity * texture(bloomMap, uv).rgb, c.a);
+                }",
+            )?,
+        })
+    }
+}
+
+impl PostEffect for BloomEffect {
+    fn inputs(&self) -> EffectInputMask {
+        EffectInputMask::COLOR
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn resize(&mut self, width: u32, height: u32) -> ThreeDResult<()> {
+        self.bright_target.resize(width, height)?;
+        self.blur_target.resize(width, height)?;
+        Ok(())
+    }
+
+    fn apply(&mut self, inputs: &EffectInputs, output: &mut EffectOutput) -> ThreeDResult<()> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        };
+        let viewport = output.viewport();
+        let (width, height) = (viewport.width, viewport.height);
+        let half_viewport = self.bright_target.viewport();
+
+        // Extract the bright parts of the image at half the resolution
+        let bright_pass = &self.bright_pass;
+        bright_pass.use_texture("colorMap", &inputs.color_texture)?;
+        bright_pass.use_uniform("threshold", self.threshold)?;
+        self.bright_target
+            .get(width, height)?
+            .write(ClearState::none(), || {
+                bright_pass.apply(render_states, half_viewport)
+            })?;
+
+        // Blur horizontally into the blur target and then vertically back into the bright target
+        let blur_pass = &self.blur_pass;
+        blur_pass.use_texture("colorMap", self.bright_target.color_texture().unwrap())?;
+        blur_pass.use_uniform("direction", vec2(1.0 / half_viewport.width as f32, 0.0))?;
+        self.blur_target
+            .get(width, height)?
+            .write(ClearState::none(), || {
+                blur_pass.apply(render_states, half_viewport)
+            })?;
+        blur_pass.use_texture("colorMap", self.blur_target.color_texture().unwrap())?;
+        blur_pass.use_uniform("direction", vec2(0.0, 1.0 / half_viewport.height as f32))?;
+        self.bright_target
+            .get(width, height)?
+            .write(ClearState::none(), || {
+                blur_pass.apply(render_states, half_viewport)
+            })?;
+
+        // Add the blurred bright parts to the image
+        let composite_pass = &self.composite_pass;
+        composite_pass.use_texture("colorMap", &inputs.color_texture)?;
+        composite_pass.use_texture("bloomMap", self.bright_target.color_texture().unwrap())?;
+        composite_pass.use_uniform("intensity", self.intensity)?;
+        output.write(|| composite_pass.apply(render_states, viewport))
+    }
+}
@@ -63,6 +63,7 @@ fn main() {
     )
     .unwrap();
     let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut hdr_pipeline = HdrPipeline::new(&context).unwrap();
 
     // main loop
     let mut color = [1.0; 4];
@@ -76,8 +77,56 @@ fn main() {
                     ui.add(Slider::new(&mut model.material.metallic, 0.0..=1.0).text("Metallic"));
                     ui.add(Slider::new(&mut model.material.roughness, 0.0..=1.0).text("Roughness"));
                     ui.color_edit_button_rgba_unmultiplied(&mut color);
+                    ui.add(
+                        Slider::new(&mut hdr_pipeline.tone_mapping.exposure, 0.0..=4.0)
+                            .text("Exposure"),
+                    );
+                    ui.add(
+                        Slider::new(&mut hdr_pipeline.scale_factor, 0.5..=2.0).text("Resolution"),
+                    );
+                    ui.label("Tone mapping");
+                    ui.radio_value(
+                        &mut hdr_pipeline.tone_mapping.operator,
+                        ToneMappingOperator::AcesFilmic,
+                        "ACES filmic",
+                    );
+                    ui.radio_value(
+                        &mut hdr_pipeline.tone_mapping.operator,
+                        ToneMappingOperator::Reinhard,
+                        "Reinhard",
+                    );
+                    ui.radio_value(
+                        &mut hdr_pipeline.tone_mapping.operator,
+                        ToneMappingOperator::Linear,
+                        "Linear",
+                    );
+                    ui.collapsing("Capabilities", |ui| {
+                        let capabilities = context.capabilities();
+                        ui.label(format!("Renderer: {}", capabilities.renderer));
+                        ui.label(format!("Version: {}", capabilities.version));
+                        ui.label(format!(
+                            "Max texture size: {}",
+                            capabilities.max_texture_size
+                        ));
+                        ui.label(format!("Max samples: {}", capabilities.max_samples));
+                        ui.label(format!(
+                            "Max vertex attributes: {}",
+                            capabilities.max_vertex_attribs
+                        ));
+                        ui.label(format!(
+                            "Max anisotropy: {}",
+                            capabilities
+                                .max_anisotropy
+                                .map(|a| a.to_string())
+                                .unwrap_or("Not supported".to_string())
+                        ));
+                        ui.label(format!(
+                            "Float render targets: {}",
+                            capabilities.color_buffer_float
+                        ));
+                    });
                 });
-                panel_width = gui_context.used_size().x as u32;
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
             })
             .unwrap();
             model.material.albedo = Color::from_rgba_slice(&color);
@@ -90,17 +139,30 @@ fn main() {
             };
             camera.set_viewport(viewport).unwrap();
             control
-                .handle_events(&mut camera, &mut frame_input.events)
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
                 .unwrap();
 
+            if let Some(ref scene) = *scene.borrow() {
+                let (skybox, lights) = scene.as_ref().unwrap();
+                hdr_pipeline
+                    .write(&camera, |camera| {
+                        skybox.render(camera)?;
+                        model.render(camera, lights)?;
+                        Ok(())
+                    })
+                    .unwrap();
+            }
+
             Screen::write(
                 &context,
                 ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0),
                 || {
-                    if let Some(ref scene) = *scene.borrow() {
-                        let (skybox, lights) = scene.as_ref().unwrap();
-                        skybox.render(&camera)?;
-                        model.render(&camera, lights)?;
+                    if scene.is_loaded() {
+                        hdr_pipeline.tone_mapping_pass(viewport)?;
                     }
                     gui.render()?;
                     Ok(())
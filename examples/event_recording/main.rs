@@ -0,0 +1,170 @@
+use three_d::*;
+
+// Record a session with `cargo run --example event_recording -- record events.bin` and stop the recording by pressing escape.
+// Play back the session without a window with `cargo run --example event_recording -- replay events.bin screenshot.png`,
+// which saves the last frame, so running it twice produces the exact same image.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() > 3 && args[1] == "replay" {
+        let context = Context::new().unwrap();
+        let mut player = EventPlayer::load(&args[2]).unwrap();
+        player.run_headless(&context, scene(&context)).unwrap();
+
+        let (width, height) = context
+            .offscreen_screen_size()
+            .expect("the recording does not contain any frames");
+        let pixels = Screen::read_color(&context, Viewport::new_at_origo(width, height)).unwrap();
+        Saver::save_pixels(&args[3], &pixels, width, height).unwrap();
+        return;
+    }
+
+    let window = Window::new(WindowSettings {
+        title: "Event recording!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+    let mut render = scene(&context);
+
+    let record_path = if args.len() > 2 && args[1] == "record" {
+        Some(args[2].clone())
+    } else {
+        None
+    };
+    let screenshot_path = if record_path.is_none() && args.len() > 1 {
+        Some(args[1].clone())
+    } else {
+        None
+    };
+    let mut recorder = EventRecorder::new();
+
+    // main loop
+    window
+        .render_loop(move |frame_input| {
+            let stop_recording = frame_input.events.iter().any(|event| {
+                if let Event::KeyPress { kind, .. } = event {
+                    *kind == Key::Escape
+                } else {
+                    false
+                }
+            });
+            if let Some(ref path) = record_path {
+                recorder.record(&frame_input);
+                if stop_recording {
+                    recorder.save(path).unwrap();
+                    return FrameOutput {
+                        exit: true,
+                        ..Default::default()
+                    };
+                }
+            }
+
+            let frame_output = render(frame_input);
+            if let Some(ref path) = screenshot_path {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(path.into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                frame_output
+            }
+        })
+        .unwrap();
+}
+
+// The render loop callback which is used both when recording and when playing back,
+// everything that changes over time must depend on the frame input only for the playback to be reproducible.
+fn scene(context: &Context) -> impl FnMut(FrameInput) -> FrameOutput {
+    let context = context.clone();
+    let mut camera = Camera::new_perspective(
+        &context,
+        Viewport::new_at_origo(1, 1),
+        vec3(4.0, 3.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    let mut cube = Model::new_with_material(
+        &context,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(100, 130, 200),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut rotating = true;
+    let mut angle = 0.0;
+
+    move |mut frame_input| {
+        let mut panel_width = 0;
+        gui.update(&mut frame_input, |gui_context| {
+            use three_d::egui::*;
+            SidePanel::left("side_panel").show(gui_context, |ui| {
+                ui.heading("Debug Panel");
+                ui.checkbox(&mut rotating, "Rotate");
+            });
+            panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+        })
+        .unwrap();
+
+        // The time is taken from the frame input, so the rotation is the same when played back
+        if rotating {
+            angle += 0.001 * frame_input.elapsed_time as f32;
+        }
+        cube.set_transformation(Mat4::from_angle_y(radians(angle)));
+
+        camera
+            .set_viewport(Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            })
+            .unwrap();
+        control
+            .handle_events(
+                &mut camera,
+                &mut frame_input.events,
+                frame_input.elapsed_time,
+            )
+            .unwrap();
+
+        Screen::write(
+            &context,
+            ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+            || {
+                cube.render(&camera, &lights)?;
+                gui.render()?;
+                Ok(())
+            },
+        )
+        .unwrap();
+        FrameOutput::default()
+    }
+}
@@ -29,10 +29,10 @@ fn main() {
         &context,
         &["examples/assets/suzanne.obj", "examples/assets/suzanne.mtl"],
         move |context, mut loaded| {
-            let (meshes, materials) = loaded.obj("suzanne.obj")?;
-            let mut monkey_material = PhysicalMaterial::new(&context, &materials[0])?;
+            let (cpu_mesh, cpu_material) = loaded.obj("suzanne.obj")?.remove(0);
+            let mut monkey_material = PhysicalMaterial::new(&context, &cpu_material.unwrap())?;
             monkey_material.opaque_render_states.cull = Cull::Back;
-            Model::new_with_material(&context, &meshes[0], monkey_material)
+            Model::new_with_material(&context, &cpu_mesh, monkey_material)
         },
     );
 
@@ -64,7 +64,11 @@ fn main() {
             let mut change = frame_input.first_frame;
             change |= camera.set_viewport(frame_input.viewport).unwrap();
             change |= control
-                .handle_events(&mut camera, &mut frame_input.events)
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
                 .unwrap();
             if !loaded && monkey.is_loaded() {
                 change = true;
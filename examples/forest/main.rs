@@ -1,5 +1,7 @@
 use three_d::*;
 
+// A forest of half a million trees rendered in full detail, surrounded by imposters, which is a benchmark of the different ways of culling the instances
+// outside the camera frustum or hidden behind other trees.
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
@@ -34,47 +36,35 @@ fn main() {
         ],
         move |context, mut loaded| {
             // Tree
-            let (mut meshes, materials) = loaded.obj("examples/assets/Tree1.obj").unwrap();
-            let mut tree_cpu_mesh = meshes
+            let mut meshes = loaded.obj("examples/assets/Tree1.obj").unwrap();
+            let (mut tree_cpu_mesh, tree_cpu_material) = meshes
                 .iter()
-                .position(|m| m.name == "tree.001_Mesh.002")
+                .position(|(m, _)| m.name == "tree.001_Mesh.002")
                 .map(|index| meshes.remove(index))
                 .unwrap();
             tree_cpu_mesh.compute_normals();
             let mut tree_mesh = Model::new_with_material(
                 &context,
                 &tree_cpu_mesh,
-                PhysicalMaterial::new(
-                    &context,
-                    &materials
-                        .iter()
-                        .find(|m| Some(&m.name) == tree_cpu_mesh.material_name.as_ref())
-                        .unwrap(),
-                )
-                .unwrap(),
+                PhysicalMaterial::new(&context, &tree_cpu_material.unwrap()).unwrap(),
             )
             .unwrap();
             tree_mesh.material.transparent_render_states.cull = Cull::Back;
 
-            let mut leaves_cpu_mesh = meshes
+            let (mut leaves_cpu_mesh, leaves_cpu_material) = meshes
                 .iter()
-                .position(|m| m.name == "leaves.001")
+                .position(|(m, _)| m.name == "leaves.001")
                 .map(|index| meshes.remove(index))
                 .unwrap();
             leaves_cpu_mesh.compute_normals();
-            let leaves_mesh = Model::new_with_material(
+            let mut leaves_mesh = Model::new_with_material(
                 &context,
                 &leaves_cpu_mesh,
-                PhysicalMaterial::new(
-                    &context,
-                    &materials
-                        .iter()
-                        .find(|m| Some(&m.name) == leaves_cpu_mesh.material_name.as_ref())
-                        .unwrap(),
-                )
-                .unwrap(),
+                PhysicalMaterial::new(&context, &leaves_cpu_material.unwrap()).unwrap(),
             )
             .unwrap();
+            // The leaves are thin geometry which should be visible and lit from both sides
+            leaves_mesh.material.double_sided = true;
 
             // Lights
             let lights = Lights {
@@ -107,12 +97,24 @@ fn main() {
                 )
                 .unwrap();
 
-            let t = 100;
+            // The 708x708 trees closest to the origin are rendered in full detail and the rest as imposters
+            let t: i32 = 400;
+            let near = 354;
+            let mut instances = Vec::new();
             let mut positions = Vec::new();
             let mut angles = Vec::new();
             for x in -t..t {
                 for y in -t..t {
-                    if x != 0 || y != 0 {
+                    if x.abs() < near && y.abs() < near {
+                        instances.push(ModelInstance {
+                            geometry_transform: Mat4::from_translation(vec3(
+                                10.0 * x as f32,
+                                0.0,
+                                10.0 * y as f32,
+                            )),
+                            ..Default::default()
+                        });
+                    } else {
                         positions.push(10.0 * x as f32);
                         positions.push(0.0);
                         positions.push(10.0 * y as f32);
@@ -122,6 +124,24 @@ fn main() {
             }
             imposters.update_positions(&positions, &angles);
 
+            // Only draw the trees which are inside the camera frustum and not hidden behind other trees
+            let mut trees = InstancedModel::new_with_material(
+                &context,
+                &instances,
+                &tree_cpu_mesh,
+                tree_mesh.material.clone(),
+            )
+            .unwrap();
+            trees.set_gpu_culling(true)?;
+            let mut leaves = InstancedModel::new_with_material(
+                &context,
+                &instances,
+                &leaves_cpu_mesh,
+                leaves_mesh.material.clone(),
+            )
+            .unwrap();
+            leaves.set_gpu_culling(true)?;
+
             // Plane
             let mut plane = Model::new_with_material(
                 &context,
@@ -141,42 +161,121 @@ fn main() {
             )
             .unwrap();
             plane.material.opaque_render_states.cull = Cull::Back;
-            Ok((plane, tree_mesh, leaves_mesh, imposters, lights))
+            Ok((plane, trees, leaves, imposters, lights))
         },
     );
 
+    // The depth of the tree trunks in the previous frame, which the trees are tested against when culling hidden trees.
+    // The leaves are not included since the transparent parts of the leaves would hide the trees behind them.
+    let mut depth_texture: Option<DepthTargetTexture2D> = None;
+    let mut depth_pyramid = DepthPyramid::new(&context).unwrap();
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    // 0: no culling, 1: culling on the CPU, 2: culling on the GPU, 3: culling of hidden trees on the GPU
+    let mut culling = 3;
+    let mut fps = 0.0;
+
     // main loop
-    let mut loaded = false;
     window
         .render_loop(move |mut frame_input| {
-            let mut redraw = frame_input.first_frame;
-            if !loaded && scene.is_loaded() {
-                redraw = true;
-                loaded = true;
-            }
-            redraw |= camera.set_viewport(frame_input.viewport).unwrap();
-
-            redraw |= control
-                .handle_events(&mut camera, &mut frame_input.events)
-                .unwrap();
+            let mut culling_changed = false;
+            let drawn_trees = scene
+                .borrow()
+                .as_ref()
+                .map(|s| {
+                    s.as_ref()
+                        .map(|(_, trees, ..)| trees.drawn_instance_count())
+                        .unwrap_or(0)
+                })
+                .unwrap_or(0);
+            fps = 0.9 * fps + 0.1 * 1000.0 / frame_input.elapsed_time.max(1.0);
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label("Culling");
+                    culling_changed |= ui.radio_value(&mut culling, 0, "None").clicked();
+                    culling_changed |= ui.radio_value(&mut culling, 1, "CPU").clicked();
+                    culling_changed |= ui.radio_value(&mut culling, 2, "GPU").clicked();
+                    culling_changed |= ui
+                        .radio_value(&mut culling, 3, "GPU with hidden trees")
+                        .clicked();
+                    ui.label(format!("Drawn trees: {}", drawn_trees));
+                    ui.label(format!("FPS: {:.0}", fps));
+                });
+            })
+            .unwrap();
 
-            if redraw {
-                Screen::write(
-                    &context,
-                    ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
-                    || {
-                        if let Some(ref scene) = *scene.borrow() {
-                            let (plane, tree_mesh, leaves_mesh, imposters, lights) =
-                                scene.as_ref().unwrap();
-                            render_pass(&camera, &[&plane, &tree_mesh, &leaves_mesh], &lights)?;
-                            imposters.render(&camera)?;
-                        }
-                        Ok(())
-                    },
+            // The panel is drawn on top of the forest, so the depth texture covers the same viewport as the camera
+            let viewport = frame_input.viewport;
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
                 )
                 .unwrap();
+
+            if let Some(Ok((_, ref mut trees, ref mut leaves, ..))) = *scene.borrow_mut() {
+                if culling_changed {
+                    for model in [&mut *trees, &mut *leaves].iter_mut() {
+                        model.set_culling(culling == 1);
+                        model.set_gpu_culling(culling >= 2).unwrap();
+                    }
+                }
+
+                if culling == 3 {
+                    trees.cull_occluded(&camera, &depth_pyramid).unwrap();
+                    leaves.cull_occluded(&camera, &depth_pyramid).unwrap();
+
+                    if depth_texture
+                        .as_ref()
+                        .map(|t| (t.width(), t.height()) != (viewport.width, viewport.height))
+                        .unwrap_or(true)
+                    {
+                        depth_texture = Some(
+                            DepthTargetTexture2D::new(
+                                &context,
+                                viewport.width,
+                                viewport.height,
+                                Wrapping::ClampToEdge,
+                                Wrapping::ClampToEdge,
+                                DepthFormat::Depth32F,
+                            )
+                            .unwrap(),
+                        );
+                    }
+                    let depth_texture = depth_texture.as_mut().unwrap();
+                    depth_texture
+                        .write(Some(1.0), || {
+                            trees.render_with_material(
+                                &DepthMaterial::default(),
+                                &camera,
+                                &Lights::default(),
+                            )
+                        })
+                        .unwrap();
+                    depth_pyramid.update(depth_texture).unwrap();
+                }
             }
 
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    if let Some(Ok((ref plane, ref trees, ref leaves, ref imposters, ref lights))) =
+                        *scene.borrow()
+                    {
+                        render_pass(&camera, &[plane as &dyn Object, trees, leaves], lights)?;
+                        imposters.render(&camera)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
             if args.len() > 1 {
                 // To automatically generate screenshots of the examples, can safely be ignored.
                 FrameOutput {
@@ -185,10 +284,7 @@ fn main() {
                     ..Default::default()
                 }
             } else {
-                FrameOutput {
-                    swap_buffers: redraw,
-                    ..Default::default()
-                }
+                FrameOutput::default()
             }
         })
         .unwrap();
@@ -0,0 +1,234 @@
+use three_d::*;
+
+// An RTS style scene with 200 units walking around between some walls, each with a health bar which always faces the camera and has the same size on the screen.
+// The health bars are hidden by the walls, unless the occluded bars are shown with a reduced alpha, and can fade out far away from the camera.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Health bars!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 30.0, 40.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 5.0, 200.0);
+
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(110, 130, 90),
+            roughness: 1.0,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_scale(40.0) * Mat4::from_angle_x(degrees(-90.0)));
+
+    let wall_instances = (0..6)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::PI / 3.0;
+            ModelInstance {
+                geometry_transform: Mat4::from_translation(vec3(
+                    15.0 * angle.cos(),
+                    2.0,
+                    15.0 * angle.sin(),
+                )) * Mat4::from_angle_y(radians(-angle))
+                    * Mat4::from_nonuniform_scale(0.5, 2.0, 5.0),
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+    let walls = InstancedModel::new_with_material(
+        &context,
+        &wall_instances,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(160, 150, 140),
+            roughness: 0.8,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Each unit walks in a circle around its own center
+    let unit_count = 200;
+    let unit_paths = (0..unit_count)
+        .map(|i| {
+            let angle = i as f32 * 2.4;
+            let distance = 4.0 + 30.0 * (i as f32 / unit_count as f32).sqrt();
+            (
+                vec3(distance * angle.cos(), 0.5, distance * angle.sin()),
+                1.0 + (i % 5) as f32,
+                0.2 + 0.1 * (i % 3) as f32,
+            )
+        })
+        .collect::<Vec<_>>();
+    let unit_positions = move |time: f32| {
+        unit_paths
+            .iter()
+            .map(|(center, radius, speed)| {
+                let angle = *speed * time;
+                center + *radius * vec3(angle.cos(), 0.0, angle.sin())
+            })
+            .collect::<Vec<_>>()
+    };
+    let unit_instances = |positions: &[Vec3]| {
+        positions
+            .iter()
+            .map(|p| ModelInstance {
+                geometry_transform: Mat4::from_translation(*p) * Mat4::from_scale(0.5),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>()
+    };
+    let health_bars = |positions: &[Vec3], second: u32| {
+        positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                // A health which changes every second and is different for each unit
+                let health = 0.5 + 0.5 * ((i as u32 * 17 + second * 7) as f32).sin();
+                WorldSpaceBar {
+                    position: *p + vec3(0.0, 0.5, 0.0),
+                    fraction: health,
+                    color: if health > 0.6 {
+                        Color::new_opaque(80, 220, 80)
+                    } else if health > 0.3 {
+                        Color::new_opaque(230, 200, 60)
+                    } else {
+                        Color::new_opaque(220, 60, 50)
+                    },
+                    size: (30.0, 4.0),
+                    offset: 12.0,
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let positions = unit_positions(0.0);
+    let mut units = InstancedModel::new_with_material(
+        &context,
+        &unit_instances(&positions),
+        &CPUMesh::sphere(16),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(60, 90, 200),
+            roughness: 0.5,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut bars = WorldSpaceBars::new(&context, &health_bars(&positions, 0)).unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut show_occluded = false;
+    let mut fade = false;
+    let mut fade_distance = 60.0;
+    let mut time = 0.0;
+    let mut second = 0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut show_occluded, "Show occluded bars");
+                    ui.checkbox(&mut fade, "Fade with distance");
+                    ui.add(Slider::new(&mut fade_distance, 10.0..=150.0).text("Fade distance"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            bars.occluded_alpha = if show_occluded { Some(0.3) } else { None };
+            bars.fade_distances = if fade {
+                Some((fade_distance - 10.0, fade_distance))
+            } else {
+                None
+            };
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // The bars follow the units every frame, while the health only changes once a second
+            time += 0.001 * frame_input.elapsed_time as f32;
+            let positions = unit_positions(time);
+            units.set_instances(&unit_instances(&positions));
+            if time as u32 != second {
+                second = time as u32;
+                bars.set_bars(&health_bars(&positions, second));
+            } else {
+                let bar_positions = positions
+                    .iter()
+                    .map(|p| p + vec3(0.0, 0.5, 0.0))
+                    .collect::<Vec<_>>();
+                bars.set_positions(&bar_positions).unwrap();
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.6, 0.7, 0.8, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &[&ground as &dyn Object, &walls, &units], &lights)?;
+                    bars.render(&camera)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
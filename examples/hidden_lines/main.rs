@@ -0,0 +1,252 @@
+use three_d::*;
+
+// Two meshing gears rendered either shaded or as a technical drawing,
+// where the visible edges are solid black lines and the edges hidden by the gears are dashed gray lines.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Hidden lines!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(2.5, 3.0, 5.0),
+        vec3(0.6, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 50.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    let large_gear = gear(16, 1.0, 0.25);
+    let small_gear = gear(10, 0.65, 0.25);
+    let mut gears = vec![
+        Model::new_with_material(
+            &context,
+            &large_gear,
+            PhysicalMaterial {
+                albedo: Color::new_opaque(200, 200, 210),
+                metallic: 0.8,
+                roughness: 0.35,
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+        Model::new_with_material(
+            &context,
+            &small_gear,
+            PhysicalMaterial {
+                albedo: Color::new_opaque(220, 170, 80),
+                metallic: 0.8,
+                roughness: 0.35,
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+    ];
+    let mut edges = vec![
+        FeatureEdges::new(&context, &large_gear, degrees(30.0)).unwrap(),
+        FeatureEdges::new(&context, &small_gear, degrees(30.0)).unwrap(),
+    ];
+    let mut hidden_lines = HiddenLineRenderer::new(&context).unwrap();
+    let face_material = ColorMaterial {
+        color: Color::WHITE,
+        ..Default::default()
+    };
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut technical_drawing = true;
+    let mut crease_angle = 30.0;
+    let mut animate = true;
+    let mut time = 0.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut technical_drawing, false, "Shaded");
+                    ui.radio_value(&mut technical_drawing, true, "Hidden lines");
+                    ui.separator();
+                    ui.checkbox(&mut hidden_lines.show_hidden, "Show hidden edges");
+                    ui.add(Slider::new(&mut hidden_lines.line_width, 0.5..=6.0).text("Line width"));
+                    ui.add(
+                        Slider::new(&mut hidden_lines.dash_length, 1.0..=20.0).text("Dash length"),
+                    );
+                    ui.add(
+                        Slider::new(&mut hidden_lines.gap_length, 1.0..=20.0).text("Gap length"),
+                    );
+                    ui.add(Slider::new(&mut crease_angle, 1.0..=90.0).text("Crease angle"));
+                    ui.checkbox(&mut animate, "Animate");
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            if animate {
+                time += 0.0005 * frame_input.elapsed_time as f32;
+            }
+            // The small gear turns faster in the opposite direction and is rotated half a tooth, so the teeth mesh
+            let transformations = [
+                Mat4::from_angle_z(radians(time)),
+                Mat4::from_translation(vec3(1.52, 0.0, 0.0))
+                    * Mat4::from_angle_z(radians(-1.6 * time + std::f32::consts::PI / 10.0)),
+            ];
+            let crease: Radians = degrees(crease_angle).into();
+            for ((gear, edge), transformation) in gears
+                .iter_mut()
+                .zip(edges.iter_mut())
+                .zip(transformations.iter())
+            {
+                gear.set_transformation(*transformation);
+                edge.set_transformation(*transformation);
+                if edge.crease_angle() != crease {
+                    edge.set_crease_angle(crease);
+                }
+            }
+
+            if technical_drawing {
+                hidden_lines.depth_pass(&camera, &gears).unwrap();
+            }
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0),
+                || {
+                    if technical_drawing {
+                        for gear in gears.iter() {
+                            gear.render_with_material(&face_material, &camera, &lights)?;
+                        }
+                        hidden_lines.render(&camera, &edges.iter().collect::<Vec<_>>())?;
+                    } else {
+                        for gear in gears.iter() {
+                            gear.render(&camera, &lights)?;
+                        }
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// A gear with the given number of teeth and outer radius, centered at the origin in the xy-plane with a hole for the axle.
+// Each face has its own vertices, so the gear is flat shaded.
+fn gear(teeth: usize, radius: f32, thickness: f32) -> CPUMesh {
+    let root_radius = radius - 0.15;
+    let hole_radius = 0.25 * radius;
+    let period = 2.0 * std::f32::consts::PI / teeth as f32;
+    let mut outer = Vec::new();
+    let mut inner = Vec::new();
+    for tooth in 0..teeth {
+        for (fraction, r) in [
+            (0.0, root_radius),
+            (0.15, radius),
+            (0.4, radius),
+            (0.55, root_radius),
+        ]
+        .iter()
+        {
+            let angle = period * (tooth as f32 + fraction);
+            outer.push(vec2(r * angle.cos(), r * angle.sin()));
+            inner.push(vec2(hole_radius * angle.cos(), hole_radius * angle.sin()));
+        }
+    }
+
+    let z = 0.5 * thickness;
+    let mut positions = Vec::new();
+    let mut quad = |a: Vec3, b: Vec3, c: Vec3, d: Vec3| {
+        for p in [a, b, c, a, c, d].iter() {
+            positions.extend_from_slice(&[p.x, p.y, p.z]);
+        }
+    };
+    let n = outer.len();
+    for i in 0..n {
+        let j = (i + 1) % n;
+        let (o0, o1, i0, i1) = (outer[i], outer[j], inner[i], inner[j]);
+        // Front and back
+        quad(
+            vec3(i0.x, i0.y, z),
+            vec3(o0.x, o0.y, z),
+            vec3(o1.x, o1.y, z),
+            vec3(i1.x, i1.y, z),
+        );
+        quad(
+            vec3(i1.x, i1.y, -z),
+            vec3(o1.x, o1.y, -z),
+            vec3(o0.x, o0.y, -z),
+            vec3(i0.x, i0.y, -z),
+        );
+        // Outside with the teeth and the inside of the hole
+        quad(
+            vec3(o0.x, o0.y, -z),
+            vec3(o1.x, o1.y, -z),
+            vec3(o1.x, o1.y, z),
+            vec3(o0.x, o0.y, z),
+        );
+        quad(
+            vec3(i1.x, i1.y, -z),
+            vec3(i0.x, i0.y, -z),
+            vec3(i0.x, i0.y, z),
+            vec3(i1.x, i1.y, z),
+        );
+    }
+    let mut mesh = CPUMesh {
+        positions,
+        ..Default::default()
+    };
+    mesh.compute_normals();
+    mesh
+}
@@ -0,0 +1,186 @@
+use three_d::*;
+
+// Renders the current view at a resolution much larger than the window and saves it as a PNG image when the button in the panel is pressed.
+// The scene consists of smooth gradients from a point light on a large ground plane and on glossy spheres,
+// where any seam between the tiles of the high resolution image would be easy to spot.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "High resolution screenshot!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(6.0, 4.0, 8.0),
+        vec3(0.0, 0.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 50.0);
+
+    let mut models = Vec::new();
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(200, 200, 200),
+            roughness: 0.6,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_scale(20.0) * Mat4::from_angle_x(degrees(-90.0)));
+    models.push(ground);
+    for i in 0..5 {
+        let angle = i as f32 * 2.0 * std::f32::consts::PI / 5.0;
+        let mut sphere = Model::new_with_material(
+            &context,
+            &CPUMesh::sphere(64),
+            PhysicalMaterial {
+                albedo: Color::new_opaque(
+                    (100 + 30 * i) as u8,
+                    (220 - 30 * i) as u8,
+                    (150 + 20 * i) as u8,
+                ),
+                metallic: 0.2 * i as f32,
+                roughness: 0.3,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        sphere.set_transformation(
+            Mat4::from_translation(vec3(2.5 * angle.cos(), 0.8, 2.5 * angle.sin()))
+                * Mat4::from_scale(0.8),
+        );
+        models.push(sphere);
+    }
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.2,
+            ..Default::default()
+        }),
+        point: vec![PointLight::new(
+            &context,
+            3.0,
+            Color::new_opaque(255, 220, 180),
+            &vec3(0.0, 3.0, 0.0),
+            0.3,
+            0.1,
+            0.05,
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // The effects are applied to each tile, where the overlap must cover the pixels sampled by the anti-aliasing
+    let mut effect_stack = EffectStack::new(&context).unwrap();
+    effect_stack.push(FXAAEffect::new(&context).unwrap());
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut width = 8000;
+    let mut height = 6000;
+    let mut options = HighResOptions {
+        samples_per_pixel: 2,
+        tile_overlap: 8,
+        clear_state: ClearState::color_and_depth(0.3, 0.35, 0.45, 1.0, 1.0),
+        ..Default::default()
+    };
+    let mut save = false;
+    let mut status = String::new();
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut width, 256..=16000).text("Width"));
+                    ui.add(Slider::new(&mut height, 256..=16000).text("Height"));
+                    ui.add(
+                        Slider::new(&mut options.samples_per_pixel, 1..=4)
+                            .text("Samples per pixel"),
+                    );
+                    ui.add(Slider::new(&mut options.tile_overlap, 0..=32).text("Tile overlap"));
+                    ui.add(
+                        Slider::new(&mut options.max_tile_size, 256..=4096).text("Max tile size"),
+                    );
+                    save = ui.button("Save high_res.png").clicked();
+                    ui.label(&status);
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // The aspect ratio of the image is given by the chosen size, while the vertical field of view is the same as on the screen
+            if save {
+                let start = std::time::Instant::now();
+                let image = render_high_res_with_options(
+                    &context,
+                    &camera,
+                    &models,
+                    &lights,
+                    width,
+                    height,
+                    options,
+                    Some(&mut effect_stack),
+                )
+                .unwrap();
+                Saver::save_pixels("high_res.png", &image.data, image.width, image.height).unwrap();
+                status = format!(
+                    "Saved {}x{} pixels in {:.1} s",
+                    image.width,
+                    image.height,
+                    start.elapsed().as_secs_f32()
+                );
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.3, 0.35, 0.45, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &models, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
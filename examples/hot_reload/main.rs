@@ -0,0 +1,82 @@
+use three_d::*;
+
+// Run with `cargo run --example hot_reload --features hot-reload` and edit examples/assets/hot.frag while it is running
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Hot reload!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(4.0, 3.0, 4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    let mut material = HotReloadMaterial::new(&context, "examples/assets/hot.frag").unwrap();
+    material.uniforms.insert(
+        "baseColor".to_string(),
+        UniformValue::Vec4(Color::new_opaque(50, 120, 200).to_vec4()),
+    );
+    let mut cube = Model::new_with_material(&context, &CPUMesh::cube(), material).unwrap();
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            camera.set_viewport(frame_input.viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            let last_error = cube.material.error().map(|e| e.to_string());
+            if cube.material.reload_if_changed() {
+                println!("Reloaded {}", cube.material.path().display());
+            } else if let Some(error) = cube.material.error() {
+                if last_error.as_deref() != Some(error) {
+                    println!("{}", error);
+                }
+            }
+            cube.material.uniforms.insert(
+                "time".to_string(),
+                UniformValue::Float(0.001 * frame_input.accumulated_time as f32),
+            );
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    cube.render(&camera, &Lights::default())?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,318 @@
+use std::rc::Rc;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "HUD!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(8.0, 6.0, 10.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    // A grid of cubes which can be selected by dragging a rectangle with the right mouse button
+    let mut cubes = Vec::new();
+    for x in -2..3 {
+        for z in -2..3 {
+            let mut cube = Model::new_with_material(
+                &context,
+                &CPUMesh::cube(),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(100, 130, 200),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            cube.set_transformation(
+                Mat4::from_translation(vec3(3.0 * x as f32, 0.0, 3.0 * z as f32))
+                    * Mat4::from_scale(0.5),
+            );
+            cubes.push(cube);
+        }
+    }
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // The minimap is rendered once from above into a texture, since the cubes do not move
+    let minimap_size = 200;
+    let minimap_extent = 24.0;
+    let mut minimap_texture = Texture2D::<u8>::new_empty(
+        &context,
+        minimap_size,
+        minimap_size,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+    .unwrap();
+    let mut minimap_depth_texture = DepthTargetTexture2D::new(
+        &context,
+        minimap_size,
+        minimap_size,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )
+    .unwrap();
+    let minimap_camera = Camera::new_orthographic(
+        &context,
+        Viewport::new_at_origo(minimap_size, minimap_size),
+        vec3(0.0, 10.0, 0.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 0.0, -1.0),
+        minimap_extent,
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    RenderTarget::new(&context, &mut minimap_texture, &mut minimap_depth_texture)
+        .unwrap()
+        .write(ClearState::color_and_depth(0.2, 0.3, 0.2, 1.0, 1.0), || {
+            for cube in cubes.iter() {
+                cube.render(&minimap_camera, &lights)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+    // The HUD is anchored to the corners and the center of the screen, so it stays in place when the window is resized
+    let margin = 20.0;
+    let minimap_center = vec2(
+        -margin - 0.5 * minimap_size as f32,
+        margin + 0.5 * minimap_size as f32,
+    );
+    let mut minimap_frame = Rectangle::new_with_material(
+        &context,
+        minimap_center,
+        degrees(0.0),
+        minimap_size as f32 + 8.0,
+        minimap_size as f32 + 8.0,
+        ColorMaterial {
+            color: Color::new(0, 0, 0, 200),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    minimap_frame.set_anchor(Anchor::TopRight);
+    let mut minimap = Rectangle::new_with_material(
+        &context,
+        minimap_center,
+        degrees(0.0),
+        minimap_size as f32,
+        minimap_size as f32,
+        ColorMaterial {
+            texture: Some(Rc::new(minimap_texture)),
+            // The first row of the rendered texture is the bottom of the image, while the y-coordinate of the overlay increases downwards
+            texture_transform: Mat3::from_translation(vec2(0.0, 1.0))
+                * Mat3::from_nonuniform_scale(1.0, -1.0),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    minimap.set_anchor(Anchor::TopRight);
+    let mut minimap_marker = Circle::new_with_material(
+        &context,
+        minimap_center,
+        5.0,
+        ColorMaterial {
+            color: Color::RED,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    minimap_marker.set_anchor(Anchor::TopRight);
+
+    let mut crosshair_horizontal = Line::new_with_material(
+        &context,
+        vec2(-12.0, 0.0),
+        vec2(12.0, 0.0),
+        2.0,
+        ColorMaterial {
+            color: Color::WHITE,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    crosshair_horizontal.set_anchor(Anchor::Center);
+    let mut crosshair_vertical = Line::new_with_material(
+        &context,
+        vec2(0.0, -12.0),
+        vec2(0.0, 12.0),
+        2.0,
+        ColorMaterial {
+            color: Color::WHITE,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    crosshair_vertical.set_anchor(Anchor::Center);
+
+    let mut selection = Rectangle::new_with_material(
+        &context,
+        vec2(0.0, 0.0),
+        degrees(0.0),
+        1.0,
+        1.0,
+        ColorMaterial {
+            color: Color::new(120, 180, 255, 80),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut selection_start = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            // The rubber band rectangle spans from the position where the right mouse button was pressed to the cursor
+            for event in frame_input.events.iter_mut() {
+                match event {
+                    Event::MousePress {
+                        button: MouseButton::Right,
+                        position,
+                        handled,
+                        ..
+                    } => {
+                        let pixel = vec2(position.physical.0, position.physical.1);
+                        selection_start = Some(pixel);
+                        selection.set_center(pixel);
+                        selection.set_size(1.0, 1.0);
+                        *handled = true;
+                    }
+                    Event::MouseMotion {
+                        position, handled, ..
+                    } => {
+                        if let Some(start) = selection_start {
+                            let pixel = vec2(position.physical.0, position.physical.1);
+                            selection.set_center(0.5 * (start + pixel));
+                            selection
+                                .set_size((pixel.x - start.x).abs(), (pixel.y - start.y).abs());
+                            *handled = true;
+                        }
+                    }
+                    Event::MouseRelease {
+                        button: MouseButton::Right,
+                        handled,
+                        ..
+                    } => {
+                        if selection_start.take().is_some() {
+                            select(&camera, &mut cubes, &selection);
+                            *handled = true;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            camera.set_viewport(frame_input.viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // The marker shows where the camera is seen from above
+            let position = camera.position();
+            minimap_marker.set_center(
+                minimap_center
+                    + (minimap_size as f32 / minimap_extent) * vec2(position.x, position.z),
+            );
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    for cube in cubes.iter() {
+                        cube.render(&camera, &lights)?;
+                    }
+                    let mut overlay: Vec<(i32, &dyn Object2D)> = vec![
+                        (0, &minimap_frame),
+                        (1, &minimap),
+                        (2, &minimap_marker),
+                        (0, &crosshair_horizontal),
+                        (0, &crosshair_vertical),
+                    ];
+                    if selection_start.is_some() {
+                        overlay.push((3, &selection));
+                    }
+                    render_overlay(frame_input.viewport, &overlay)?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// Colors the cubes which centers are inside the selection rectangle given in pixels relative to the top left corner of the viewport
+fn select(
+    camera: &Camera,
+    cubes: &mut [Model<PhysicalMaterial>],
+    selection: &Rectangle<ColorMaterial>,
+) {
+    let viewport = camera.viewport();
+    let (width, height) = selection.size();
+    let min = *selection.center() - 0.5 * vec2(width, height);
+    let max = *selection.center() + 0.5 * vec2(width, height);
+    for cube in cubes.iter_mut() {
+        let center = cube.transformation().w;
+        let clip = camera.projection() * camera.view() * center;
+        let pixel = vec2(
+            0.5 * (clip.x / clip.w + 1.0) * viewport.width as f32,
+            0.5 * (1.0 - clip.y / clip.w) * viewport.height as f32,
+        );
+        let selected = clip.w > 0.0
+            && pixel.x >= min.x
+            && pixel.x <= max.x
+            && pixel.y >= min.y
+            && pixel.y <= max.y;
+        cube.material.albedo = if selected {
+            Color::new_opaque(255, 180, 60)
+        } else {
+            Color::new_opaque(100, 130, 200)
+        };
+    }
+}
@@ -13,10 +13,11 @@ fn main() {
     let context = window.gl().unwrap();
     let image_effect = ImageEffect::new(&context, include_str!("shader.frag")).unwrap();
 
-    let image = Loading::new(
+    // Upload the large HDR image incrementally over several frames to avoid a hitch
+    let mut image = Loading::new_incremental(
         &context,
         &["examples/assets/syferfontein_18d_clear_4k.hdr"], // Source: https://polyhaven.com/
-        move |context, mut loaded| Texture2D::new(&context, &loaded.hdr_image("")?),
+        move |context, mut loaded| Texture2D::new_incremental(&context, loaded.hdr_image("")?),
     );
 
     let mut gui = GUI::new(&context).unwrap();
@@ -25,14 +26,35 @@ fn main() {
     let mut tone_mapping = 1.0;
     window
         .render_loop(move |mut frame_input| {
+            let progress = image.progress(std::time::Duration::from_millis(4));
+            let download = image.load_progress();
+            let error = match *image.borrow() {
+                Some(Err(ref e)) => Some(e.to_string()),
+                _ => None,
+            };
             let mut panel_width = 0;
             gui.update(&mut frame_input, |gui_context| {
                 use three_d::egui::*;
                 SidePanel::left("side_panel").show(gui_context, |ui| {
                     ui.heading("Debug Panel");
                     ui.add(Slider::new(&mut tone_mapping, 0.0..=50.0).text("Tone mapping"));
+                    if let Some(ref error) = error {
+                        ui.label(format!("Failed to load the image: {}", error));
+                    } else if progress == 0.0 {
+                        // The upload starts when the download is done
+                        ui.label(format!(
+                            "Downloading image ({} kB)",
+                            download.loaded_bytes / 1000
+                        ));
+                        if let Some(fraction) = download.fraction() {
+                            ui.label(format!("{:.0} %", 100.0 * fraction));
+                        }
+                    } else if progress < 1.0 {
+                        ui.label("Uploading image");
+                        ui.label(format!("{:.0} %", 100.0 * progress));
+                    }
                 });
-                panel_width = gui_context.used_size().x as u32;
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
             })
             .unwrap();
 
@@ -44,8 +66,7 @@ fn main() {
             };
 
             Screen::write(&context, ClearState::default(), || {
-                if let Some(ref image) = *image.borrow() {
-                    let image = image.as_ref().unwrap();
+                if let Some(Ok(ref image)) = *image.borrow() {
                     image_effect.use_texture("image", &image)?;
                     image_effect.use_uniform("parameter", tone_mapping)?;
                     image_effect.apply(RenderStates::default(), viewport)?;
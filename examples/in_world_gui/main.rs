@@ -0,0 +1,218 @@
+use std::rc::Rc;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "In-world GUI!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(1.5, 1.0, 4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 20.0);
+
+    // The GUI on the screen and the GUI on the computer screen in the world are independent
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut screen_gui = three_d::GUI::new(&context).unwrap();
+    screen_gui.set_pixels_per_point(Some(2.0));
+    let (screen_width, screen_height) = (800, 600);
+
+    // A quad with the same aspect ratio as the texture which the GUI is rendered into
+    let screen_mesh = CPUMesh::square();
+    let screen_transformation =
+        Mat4::from_nonuniform_scale(screen_width as f32 / screen_height as f32, 1.0, 1.0);
+    let mut screen = Model::new_with_material(
+        &context,
+        &screen_mesh,
+        ColorMaterial {
+            texture: Some(Rc::new(
+                Texture2D::new_empty(
+                    &context,
+                    screen_width,
+                    screen_height,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    Some(Interpolation::Linear),
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                    Format::RGBA,
+                )
+                .unwrap(),
+            )),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    screen.set_transformation(screen_transformation);
+    let collider = TriMeshCollider::new(&screen_mesh, screen_transformation).unwrap();
+    let mut triangle_uvs = Vec::new();
+    screen_mesh.for_each_triangle(|i0, i1, i2| {
+        triangle_uvs.push([
+            screen_mesh.uv(i0).unwrap(),
+            screen_mesh.uv(i1).unwrap(),
+            screen_mesh.uv(i2).unwrap(),
+        ])
+    });
+
+    let mut stand = Model::new_with_material(
+        &context,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(60, 60, 60),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    stand.set_transformation(
+        Mat4::from_translation(vec3(0.0, -0.05, -0.06))
+            * Mat4::from_nonuniform_scale(1.45, 1.1, 0.05),
+    );
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut clicks = 0;
+    let mut text = "Type here".to_string();
+    let mut value = 0.5;
+    let mut color = [40, 40, 60];
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label(format!(
+                        "The button on the screen is clicked {} times",
+                        clicks
+                    ));
+                    ui.add(Slider::new(&mut value, 0.0..=1.0).text("Value"));
+                    ui.horizontal(|ui| {
+                        ui.label("Background");
+                        ui.color_edit_button_srgb(&mut color);
+                    });
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            // The mouse events are mapped onto the screen by intersecting the ray through the mouse position with the quad
+            let window_height = frame_input.viewport.height;
+            let screen_to_uv = |position: PointerPosition| {
+                let pixel = position.to_viewport_pixel(camera.viewport(), window_height);
+                collider
+                    .raycast(
+                        camera.position_at_pixel(pixel),
+                        camera.view_direction_at_pixel(pixel),
+                        100.0,
+                    )
+                    .map(|hit| {
+                        let uvs = triangle_uvs[hit.triangle_index];
+                        uvs[0] * hit.barycentric.x
+                            + uvs[1] * hit.barycentric.y
+                            + uvs[2] * hit.barycentric.z
+                    })
+            };
+            screen_gui
+                .update_in_world(
+                    &mut frame_input,
+                    screen_width,
+                    screen_height,
+                    screen_to_uv,
+                    |gui_context| {
+                        use three_d::egui::*;
+                        CentralPanel::default().show(gui_context, |ui| {
+                            ui.heading("Computer screen");
+                            if ui.button("Click me").clicked() {
+                                clicks += 1;
+                            }
+                            ui.add(Slider::new(&mut value, 0.0..=1.0).text("Value"));
+                            ui.text_edit_singleline(&mut text);
+                        });
+                    },
+                )
+                .unwrap();
+
+            // The remaining events control the camera
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // Render the GUI into the texture of the screen
+            let texture = Rc::get_mut(screen.material.texture.as_mut().unwrap()).unwrap();
+            texture
+                .write(
+                    ClearState::color(
+                        color[0] as f32 / 255.0,
+                        color[1] as f32 / 255.0,
+                        color[2] as f32 / 255.0,
+                        1.0,
+                    ),
+                    || screen_gui.render(),
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.6, 0.6, 0.6, 1.0, 1.0),
+                || {
+                    stand.render(&camera, &lights)?;
+                    screen.render(&camera, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,302 @@
+use three_d::*;
+
+// Shows how the mouse buttons, keys and modifiers which control the camera are rebound using the input map of the camera controllers.
+// The bindings of each controller can be edited in the side panel, for example to orbit with the middle mouse button and pan with the right mouse button
+// like in many CAD applications, which is available as a preset.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Input map!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(8.0, 8.0, 12.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut orbit_control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+    let mut fly_control = FlyControl::new(0.05);
+    let mut first_person_control = FirstPersonControl::new(0.05);
+    let mut pan_zoom_control = PanZoomControl::new(*camera.target(), 1.0, 100.0);
+
+    let mut models = Vec::new();
+    for x in -3..=3 {
+        for z in -3..=3 {
+            let mut model = Model::new_with_material(
+                &context,
+                &CPUMesh::cube(),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque((128 + 30 * x) as u8, 160, (128 + 30 * z) as u8),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            model.set_transformation(
+                Mat4::from_translation(vec3(3.0 * x as f32, 0.5, 3.0 * z as f32))
+                    * Mat4::from_scale(0.5),
+            );
+            models.push(model);
+        }
+    }
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(200, 200, 200),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(12.0));
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -0.5),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let controller_names = ["Orbit", "Fly", "First person", "Pan and zoom"];
+    let mut controller = 0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ComboBox::from_label("Controller")
+                        .selected_text(controller_names[controller])
+                        .show_ui(ui, |ui| {
+                            for (i, name) in controller_names.iter().enumerate() {
+                                ui.selectable_value(&mut controller, i, *name);
+                            }
+                        });
+                    let (input_map, default_input_map) = match controller {
+                        0 => (
+                            &mut orbit_control.input_map,
+                            OrbitControl::default_input_map(),
+                        ),
+                        1 => (&mut fly_control.input_map, FlyControl::default_input_map()),
+                        2 => (
+                            &mut first_person_control.input_map,
+                            FirstPersonControl::default_input_map(),
+                        ),
+                        _ => (
+                            &mut pan_zoom_control.input_map,
+                            PanZoomControl::default_input_map(),
+                        ),
+                    };
+                    ui.horizontal(|ui| {
+                        if ui.button("Defaults").clicked() {
+                            input_map.bindings = default_input_map.bindings;
+                        }
+                        if ui.button("CAD").clicked() {
+                            input_map.bindings = cad_bindings();
+                        }
+                        if ui.button("Add").clicked() {
+                            input_map
+                                .bindings
+                                .push(InputBinding::drag(MouseButton::Left, ControlAction::Pan));
+                        }
+                    });
+                    let mut removed = None;
+                    for (i, binding) in input_map.bindings.iter_mut().enumerate() {
+                        ui.separator();
+                        if binding_ui(ui, i, binding) {
+                            removed = Some(i);
+                        }
+                    }
+                    if let Some(i) = removed {
+                        input_map.bindings.remove(i);
+                    }
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            let control: &mut dyn CameraController = match controller {
+                0 => &mut orbit_control,
+                1 => &mut fly_control,
+                2 => &mut first_person_control,
+                _ => &mut pan_zoom_control,
+            };
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    for model in models.iter() {
+                        model.render(&camera, &lights)?;
+                    }
+                    ground.render(&camera, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// Orbit with the middle mouse button or alt + left, pan with the right mouse button or shift + middle and zoom by scrolling
+fn cad_bindings() -> Vec<InputBinding> {
+    let alt = Modifiers {
+        alt: true,
+        ..Default::default()
+    };
+    let shift = Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+    vec![
+        InputBinding::drag(MouseButton::Middle, ControlAction::Orbit),
+        InputBinding::drag(MouseButton::Left, ControlAction::Orbit).with_modifiers(alt),
+        InputBinding::drag(MouseButton::Right, ControlAction::Pan),
+        InputBinding::drag(MouseButton::Middle, ControlAction::Pan).with_modifiers(shift),
+        InputBinding::scroll(ControlAction::Dolly),
+    ]
+}
+
+// The triggers which can be chosen in the side panel, where the letter keys act like scrolling up and the arrow keys like dragging in their direction
+fn triggers() -> Vec<(&'static str, InputTrigger)> {
+    let key = |key, rate| InputTrigger::Keys {
+        keys: vec![key],
+        rate,
+    };
+    vec![
+        ("Left drag", InputTrigger::Drag(vec![MouseButton::Left])),
+        ("Middle drag", InputTrigger::Drag(vec![MouseButton::Middle])),
+        ("Right drag", InputTrigger::Drag(vec![MouseButton::Right])),
+        (
+            "Left + right drag",
+            InputTrigger::Drag(vec![MouseButton::Left, MouseButton::Right]),
+        ),
+        ("Scroll", InputTrigger::Scroll),
+        ("W", key(Key::W, (0.0, 100.0))),
+        ("S", key(Key::S, (0.0, 100.0))),
+        ("A", key(Key::A, (0.0, 100.0))),
+        ("D", key(Key::D, (0.0, 100.0))),
+        ("Q", key(Key::Q, (0.0, 100.0))),
+        ("E", key(Key::E, (0.0, 100.0))),
+        ("Arrow left", key(Key::ArrowLeft, (-200.0, 0.0))),
+        ("Arrow right", key(Key::ArrowRight, (200.0, 0.0))),
+        ("Arrow up", key(Key::ArrowUp, (0.0, -200.0))),
+        ("Arrow down", key(Key::ArrowDown, (0.0, 200.0))),
+        (
+            "W + E",
+            InputTrigger::Keys {
+                keys: vec![Key::W, Key::E],
+                rate: (0.0, 300.0),
+            },
+        ),
+    ]
+}
+
+// Shows the controls for editing the given binding and returns whether or not it should be removed
+fn binding_ui(ui: &mut three_d::egui::Ui, index: usize, binding: &mut InputBinding) -> bool {
+    use three_d::egui::*;
+    let actions = [
+        ("Orbit", ControlAction::Orbit),
+        ("Pan", ControlAction::Pan),
+        ("Dolly", ControlAction::Dolly),
+        ("Look around", ControlAction::LookAround),
+        ("Move forward", ControlAction::MoveForward),
+        ("Move backward", ControlAction::MoveBackward),
+        ("Move left", ControlAction::MoveLeft),
+        ("Move right", ControlAction::MoveRight),
+        ("Move up", ControlAction::MoveUp),
+        ("Move down", ControlAction::MoveDown),
+    ];
+    let triggers = triggers();
+    let trigger_name = triggers
+        .iter()
+        .find(|(_, t)| *t == binding.trigger)
+        .map(|(name, _)| *name)
+        .unwrap_or("Custom");
+    ComboBox::from_id_source(("trigger", index))
+        .selected_text(trigger_name)
+        .show_ui(ui, |ui| {
+            for (name, trigger) in triggers.iter() {
+                ui.selectable_value(&mut binding.trigger, trigger.clone(), *name);
+            }
+        });
+    let action_name = actions
+        .iter()
+        .find(|(_, a)| *a == binding.action)
+        .map(|(name, _)| *name)
+        .unwrap();
+    ComboBox::from_id_source(("action", index))
+        .selected_text(action_name)
+        .show_ui(ui, |ui| {
+            for (name, action) in actions.iter() {
+                ui.selectable_value(&mut binding.action, *action, *name);
+            }
+        });
+    let mut any_modifiers = binding.modifiers.is_none();
+    let mut removed = false;
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut any_modifiers, "Any modifiers");
+        removed = ui.button("Remove").clicked();
+    });
+    if any_modifiers {
+        binding.modifiers = None;
+    } else {
+        let modifiers = binding
+            .modifiers
+            .get_or_insert_with(three_d::Modifiers::default);
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut modifiers.alt, "Alt");
+            ui.checkbox(&mut modifiers.ctrl, "Ctrl");
+            ui.checkbox(&mut modifiers.shift, "Shift");
+        });
+    }
+    removed
+}
@@ -0,0 +1,138 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Instance selection!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(60.0, 50.0, 60.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 500.0);
+
+    // A cone pointing upwards as a simple tree
+    let mut tree_mesh = CPUMesh::cone(16);
+    tree_mesh.transform(
+        &(Mat4::from_angle_z(degrees(90.0)) * Mat4::from_nonuniform_scale(6.0, 1.5, 1.5)),
+    );
+
+    // A forest of 10,000 trees, left click on a tree to toggle its selection and press delete to remove the selected trees
+    let mut instances = Vec::new();
+    for x in -50..50 {
+        for z in -50..50 {
+            instances.push(ModelInstance {
+                geometry_transform: Mat4::from_translation(vec3(
+                    4.0 * x as f32,
+                    0.0,
+                    4.0 * z as f32,
+                )),
+                ..Default::default()
+            });
+        }
+    }
+    let mut trees = InstancedModel::new_with_material(
+        &context,
+        &instances,
+        &tree_mesh,
+        PhysicalMaterial {
+            albedo: Color::new_opaque(40, 120, 40),
+            roughness: 0.8,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut redraw = frame_input.first_frame;
+            redraw |= camera.set_viewport(frame_input.viewport).unwrap();
+
+            for event in frame_input.events.iter() {
+                match event {
+                    Event::MousePress {
+                        button, position, ..
+                    } => {
+                        if *button == MouseButton::Left {
+                            let pixel = frame_input.to_viewport_pixel(position, camera.viewport());
+                            if let Some(index) = trees.instance_at_pixel(&camera, pixel).unwrap() {
+                                trees.toggle_selected(index);
+                                redraw = true;
+                            }
+                        }
+                    }
+                    Event::KeyPress { kind, .. } => {
+                        if *kind == Key::Delete || *kind == Key::Backspace {
+                            trees.remove_selected_instances();
+                            redraw = true;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            redraw |= control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            if redraw {
+                Screen::write(
+                    &context,
+                    ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                    || {
+                        trees.render(&camera, &lights)?;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            }
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput {
+                    swap_buffers: redraw,
+                    ..Default::default()
+                }
+            }
+        })
+        .unwrap();
+}
@@ -110,11 +110,55 @@ fn main() {
         ..Default::default()
     };
 
+    let mut halos = vec![
+        LightHalo::new(&context, 0.3).unwrap(),
+        LightHalo::new(&context, 0.3).unwrap(),
+        LightHalo::new(&context, 0.3).unwrap(),
+    ];
+
     // main loop
     let mut shadows_enabled = true;
+    let shadow_qualities = [
+        ("Hard", ShadowQuality::Hard),
+        (
+            "PCF 3x3",
+            ShadowQuality::Pcf {
+                kernel: PcfKernel::Box3x3,
+            },
+        ),
+        (
+            "PCF 5x5",
+            ShadowQuality::Pcf {
+                kernel: PcfKernel::Box5x5,
+            },
+        ),
+        (
+            "PCF Poisson disk",
+            ShadowQuality::Pcf {
+                kernel: PcfKernel::PoissonDisk,
+            },
+        ),
+        ("PCSS", ShadowQuality::Pcss { light_size: 0.05 }),
+    ];
+    let mut shadow_quality = 2;
     let mut directional_intensity = lights.directional[0].intensity();
     let mut spot_intensity = lights.spot[0].intensity();
     let mut point_intensity = lights.point[0].intensity();
+    let mut spot_enabled = true;
+    let mut flicker = false;
+
+    // Compile the programs used by the forward pipeline up front instead of while rendering the first frame
+    for light in lights.directional.iter_mut() {
+        light.set_shadow_quality(shadow_qualities[shadow_quality].1);
+        light
+            .generate_shadow_map(4.0, 2048, 2048, &[&plane])
+            .unwrap();
+    }
+    lights.spot[0].set_shadow_quality(shadow_qualities[shadow_quality].1);
+    lights.spot[0].generate_shadow_map(2048, &[&plane]).unwrap();
+    context
+        .warm_up_programs(&[&plane.material], &[&lights], &[false])
+        .unwrap();
 
     let mut current_pipeline = Pipeline::Forward;
 
@@ -179,13 +223,25 @@ fn main() {
                         );
                         lights.point[0].set_intensity(point_intensity);
                         lights.point[1].set_intensity(point_intensity);
-                        if ui.checkbox(&mut shadows_enabled, "Shadows").clicked() {
-                            if !shadows_enabled {
-                                lights.spot[0].clear_shadow_map();
-                                lights.directional[0].clear_shadow_map();
-                                lights.directional[1].clear_shadow_map();
-                            }
-                        }
+                        ui.checkbox(&mut spot_enabled, "Spot light");
+                        ui.checkbox(&mut flicker, "Flicker spot light");
+                        // Pausing the shadows keeps the shadow maps, so no programs are compiled when toggling the shadows or the lights
+                        ui.checkbox(&mut shadows_enabled, "Shadows");
+                        lights.spot[0].set_shadow_enabled(shadows_enabled);
+                        lights.directional[0].set_shadow_enabled(shadows_enabled);
+                        lights.directional[1].set_shadow_enabled(shadows_enabled);
+                        ComboBox::from_label("Shadow quality")
+                            .selected_text(shadow_qualities[shadow_quality].0)
+                            .show_ui(ui, |ui| {
+                                for (i, (name, _)) in shadow_qualities.iter().enumerate() {
+                                    ui.selectable_value(&mut shadow_quality, i, *name);
+                                }
+                            });
+                        lights.spot[0].set_shadow_quality(shadow_qualities[shadow_quality].1);
+                        lights.directional[0]
+                            .set_shadow_quality(shadow_qualities[shadow_quality].1);
+                        lights.directional[1]
+                            .set_shadow_quality(shadow_qualities[shadow_quality].1);
 
                         ui.label("Lighting model");
                         ui.radio_value(&mut lights.lighting_model, LightingModel::Phong, "Phong");
@@ -242,8 +298,18 @@ fn main() {
                             "Depth",
                         );
                         ui.radio_value(&mut deferred_pipeline.debug_type, DebugType::ORM, "ORM");
+
+                        let statistics = context.program_cache_statistics();
+                        ui.label(format!(
+                            "Programs: {} hits, {} misses, {} compiled in {:.0} ms",
+                            statistics.hits,
+                            statistics.misses,
+                            statistics.compilations,
+                            statistics.compile_time.as_secs_f64() * 1000.0
+                        ));
                     });
-                    panel_width = gui_context.used_size().x as u32;
+                    panel_width =
+                        (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
                 })
                 .unwrap();
 
@@ -255,7 +321,11 @@ fn main() {
             };
             change |= camera.set_viewport(viewport).unwrap();
             change |= control
-                .handle_events(&mut camera, &mut frame_input.events)
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
                 .unwrap();
 
             let time = 0.001 * frame_input.accumulated_time;
@@ -267,19 +337,27 @@ fn main() {
             lights.spot[0].set_direction(&-vec3(3.0 + c, 5.0 + s, 3.0 - s));
             lights.point[0].set_position(&vec3(-5.0 * c, 5.0, -5.0 * s));
             lights.point[1].set_position(&vec3(5.0 * c, 5.0, 5.0 * s));
+            // A disabled light stays in the shader, so toggling the light every frame does not compile any programs
+            if flicker {
+                spot_enabled = !spot_enabled;
+            }
+            lights.spot[0].set_enabled(spot_enabled);
+            halos[0].set_from_spot_light(&lights.spot[0]);
+            halos[1].set_from_point_light(&lights.point[0]);
+            halos[2].set_from_point_light(&lights.point[1]);
 
             // Draw
             if let Some(ref model) = *model.borrow() {
                 let model = model.as_ref().unwrap();
                 if shadows_enabled {
                     lights.directional[0]
-                        .generate_shadow_map(4.0, 1024, 1024, &[model, &plane])
+                        .generate_shadow_map(4.0, 2048, 2048, &[model, &plane])
                         .unwrap();
                     lights.directional[1]
-                        .generate_shadow_map(4.0, 1024, 1024, &[model, &plane])
+                        .generate_shadow_map(4.0, 2048, 2048, &[model, &plane])
                         .unwrap();
                     lights.spot[0]
-                        .generate_shadow_map(1024, &[model, &plane])
+                        .generate_shadow_map(2048, &[model, &plane])
                         .unwrap();
                 }
 
@@ -306,6 +384,13 @@ fn main() {
                         .unwrap();
                 }
 
+                // The halos fade out when the lights are behind the geometry in the deferred depth texture
+                let depth_texture = if current_pipeline == Pipeline::Deferred {
+                    Some(deferred_pipeline.geometry_pass_depth_texture())
+                } else {
+                    None
+                };
+
                 // Light pass
                 Screen::write(&context, ClearState::default(), || {
                     match current_pipeline {
@@ -389,6 +474,13 @@ fn main() {
                             deferred_pipeline.lighting_pass(&camera, &lights)?;
                         }
                     }
+                    for halo in halos.iter() {
+                        if let Some(ref depth_texture) = depth_texture {
+                            halo.render_with_depth(&camera, depth_texture)?;
+                        } else {
+                            halo.render(&camera)?;
+                        }
+                    }
                     gui.render()?;
                     Ok(())
                 })
@@ -0,0 +1,218 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Lightmap!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(5.0, 4.0, 7.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 2.0, 30.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // A small room with a floor, two walls and a few objects, given by a mesh, a transformation and a color
+    let room = vec![
+        (
+            CPUMesh::square(),
+            Mat4::from_scale(4.0) * Mat4::from_angle_x(degrees(-90.0)),
+            Color::new_opaque(200, 200, 200),
+        ),
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(0.0, 1.5, -4.1))
+                * Mat4::from_nonuniform_scale(4.2, 1.5, 0.1),
+            Color::new_opaque(220, 200, 170),
+        ),
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(-4.1, 1.5, 0.0))
+                * Mat4::from_nonuniform_scale(0.1, 1.5, 4.2),
+            Color::new_opaque(220, 200, 170),
+        ),
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(1.0, 0.75, -1.0))
+                * Mat4::from_angle_y(degrees(30.0))
+                * Mat4::from_scale(0.75),
+            Color::new_opaque(60, 120, 200),
+        ),
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(-2.5, 1.5, -2.5))
+                * Mat4::from_nonuniform_scale(0.3, 1.5, 0.3),
+            Color::new_opaque(240, 240, 240),
+        ),
+        (
+            CPUMesh::sphere(32),
+            Mat4::from_translation(vec3(-1.0, 0.7, 1.5)) * Mat4::from_scale(0.7),
+            Color::new_opaque(200, 60, 60),
+        ),
+    ];
+
+    // The lightmaps use a second set of uv coordinates, since the uv coordinates of the cube overlap
+    let room = room
+        .into_iter()
+        .map(|(mut cpu_mesh, transformation, color)| {
+            cpu_mesh
+                .generate_uvs2(UvMethod::Charts {
+                    max_angle: degrees(45.0),
+                    texture_size: 256,
+                    padding: 4,
+                })
+                .unwrap();
+            (cpu_mesh, transformation, color)
+        })
+        .collect::<Vec<_>>();
+
+    let realtime_models = room
+        .iter()
+        .map(|(cpu_mesh, transformation, color)| {
+            let mut model = Model::new_with_material(
+                &context,
+                cpu_mesh,
+                PhysicalMaterial {
+                    albedo: *color,
+                    roughness: 1.0,
+                    metallic: 0.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            model.set_transformation(*transformation);
+            model
+        })
+        .collect::<Vec<_>>();
+
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            color: Color::WHITE,
+            intensity: 0.2,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::new_opaque(255, 240, 220),
+            &vec3(1.0, -2.0, -0.5),
+        )
+        .unwrap()],
+        spot: vec![SpotLight::new(
+            &context,
+            3.0,
+            Color::new_opaque(255, 200, 150),
+            &vec3(2.5, 3.5, 2.5),
+            &vec3(-1.0, -1.5, -1.0),
+            degrees(35.0),
+            0.1,
+            0.001,
+            0.0001,
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+    lights.directional[0]
+        .generate_shadow_map(12.0, 2048, 2048, &realtime_models)
+        .unwrap();
+    lights.spot[0]
+        .generate_shadow_map(2048, &realtime_models)
+        .unwrap();
+
+    // Bake the lighting once and render the room with the much cheaper lightmapped material afterwards
+    let bake_start = std::time::Instant::now();
+    let lightmapped_models = room
+        .iter()
+        .zip(realtime_models.iter())
+        .map(|((cpu_mesh, transformation, color), realtime_model)| {
+            let lightmap =
+                bake_lightmap(&context, realtime_model, &lights, 256, 256, 16, 4).unwrap();
+            let mut material = LightmappedMaterial::new(cpu_mesh, std::rc::Rc::new(lightmap));
+            material.albedo = *color;
+            let mut model = Model::new_with_material(&context, cpu_mesh, material).unwrap();
+            model.set_transformation(*transformation);
+            model
+        })
+        .collect::<Vec<_>>();
+    let bake_time = bake_start.elapsed().as_secs_f64() * 1000.0;
+
+    let mut baked = true;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            let frame_time = frame_input.elapsed_time;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut baked, true, "Baked lighting");
+                    ui.radio_value(&mut baked, false, "Real-time lighting");
+                    ui.label(format!("Baking took {:.0} ms", bake_time));
+                    ui.label(format!("Frame time {:.1} ms", frame_time));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0),
+                || {
+                    if baked {
+                        for model in lightmapped_models.iter() {
+                            model.render(&camera, &lights)?;
+                        }
+                    } else {
+                        for model in realtime_models.iter() {
+                            model.render(&camera, &lights)?;
+                        }
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,181 @@
+use three_d::*;
+
+// A grid of objects which switch between a detailed and a simplified version at a distance from the camera.
+// Within the fade window around the switch distance, the two versions are cross-faded using dithered transparency,
+// where the simplified version uses the inverted pattern, so the two versions together cover each pixel exactly once without sorting.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "LOD fade!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 4.0, 20.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        200.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    let mut detailed_mesh = CPUMesh::sphere(48);
+    detailed_mesh.transform(&Mat4::from_nonuniform_scale(1.0, 1.6, 1.0));
+    let simplified_mesh = detailed_mesh.simplify(0.02, false);
+    let mut objects = Vec::new();
+    for x in -3..=3 {
+        for z in -6..=2 {
+            let transformation = Mat4::from_translation(vec3(4.0 * x as f32, 0.0, 4.0 * z as f32));
+            let mut detailed = Model::new_with_material(
+                &context,
+                &detailed_mesh,
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(220, 180, 120),
+                    roughness: 0.4,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            detailed.set_transformation(transformation);
+            let mut simplified = Model::new_with_material(
+                &context,
+                &simplified_mesh,
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(220, 180, 120),
+                    roughness: 0.4,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            simplified.set_transformation(transformation);
+            objects.push((detailed, simplified));
+        }
+    }
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut fade = true;
+    let mut switch_distance = 20.0;
+    let mut fade_window = 6.0;
+    let mut animate_pattern = false;
+    let mut color_levels = true;
+    let mut frame = 0u32;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut fade, false, "Pop");
+                    ui.radio_value(&mut fade, true, "Dithered fade");
+                    ui.add(Slider::new(&mut switch_distance, 5.0..=50.0).text("Switch distance"));
+                    ui.add(Slider::new(&mut fade_window, 0.5..=20.0).text("Fade window"));
+                    ui.checkbox(&mut animate_pattern, "Animate pattern");
+                    ui.checkbox(&mut color_levels, "Color levels");
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+            if animate_pattern {
+                frame = frame.wrapping_add(1);
+            }
+
+            // The fraction of the simplified version is 0 before the fade window and 1 after it
+            let mut visible = Vec::new();
+            for (detailed, simplified) in objects.iter_mut() {
+                let distance = camera.position().distance(detailed.aabb().center());
+                let t = if fade {
+                    ((distance - switch_distance) / fade_window + 0.5)
+                        .max(0.0)
+                        .min(1.0)
+                } else if distance > switch_distance {
+                    1.0
+                } else {
+                    0.0
+                };
+                detailed.material.albedo.a = (255.0 * (1.0 - t)).round() as u8;
+                detailed.material.alpha_mode = AlphaMode::Dither {
+                    frame,
+                    inverted: false,
+                };
+                simplified.material.albedo = if color_levels {
+                    Color::new(120, 180, 220, (255.0 * t).round() as u8)
+                } else {
+                    Color::new(220, 180, 120, (255.0 * t).round() as u8)
+                };
+                simplified.material.alpha_mode = AlphaMode::Dither {
+                    frame,
+                    inverted: true,
+                };
+                if t < 1.0 {
+                    visible.push(&*detailed);
+                }
+                if t > 0.0 {
+                    visible.push(&*simplified);
+                }
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &visible, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
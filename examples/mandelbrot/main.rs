@@ -92,10 +92,7 @@ fn main() {
                         delta, position, ..
                     } => {
                         let distance = camera.position().z.abs();
-                        let pixel = (
-                            (frame_input.device_pixel_ratio * position.0) as f32,
-                            (frame_input.device_pixel_ratio * position.1) as f32,
-                        );
+                        let pixel = frame_input.to_viewport_pixel(position, camera.viewport());
                         let mut target = camera.position_at_pixel(pixel);
                         target.z = 0.0;
                         camera
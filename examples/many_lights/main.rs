@@ -0,0 +1,191 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Many lights!".to_string(),
+        max_size: Some((1280, 720)),
+        // Measure the actual frame rate instead of the refresh rate of the monitor
+        vsync: false,
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+    let surface_settings = window.surface_settings();
+
+    let forward_pipeline = ForwardPipeline::new(&context).unwrap();
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 15.0, 25.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    let mut plane = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(200, 200, 200),
+            roughness: 0.8,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    plane.set_transformation(Mat4::from_scale(20.0) * Mat4::from_angle_x(degrees(-90.0)));
+
+    let mut spheres = Vec::new();
+    for i in 0..5 {
+        for j in 0..5 {
+            let mut sphere = Model::new_with_material(
+                &context,
+                &CPUMesh::sphere(16),
+                PhysicalMaterial {
+                    albedo: Color::WHITE,
+                    roughness: 0.4,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            sphere.set_transformation(Mat4::from_translation(vec3(
+                (i as f32 - 2.0) * 6.0,
+                1.0,
+                (j as f32 - 2.0) * 6.0,
+            )));
+            spheres.push(sphere);
+        }
+    }
+
+    let colors = [
+        Color::RED,
+        Color::GREEN,
+        Color::BLUE,
+        Color::new_opaque(255, 255, 0),
+        Color::new_opaque(0, 255, 255),
+        Color::new_opaque(255, 0, 255),
+    ];
+    let max_light_count = 200;
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            color: Color::WHITE,
+            intensity: 0.05,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+    .packed(true);
+    let mut light_count = max_light_count;
+    let mut packed = true;
+    let mut vsync = surface_settings.vsync;
+    let mut fps = 0.0;
+
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = frame_input.viewport.width;
+            let mut set_vsync = None;
+            if frame_input.elapsed_time > 0.0 {
+                fps = 0.9 * fps + 0.1 * 1000.0 / frame_input.elapsed_time;
+            }
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut light_count, 0..=max_light_count).text("Lights"));
+                    ui.checkbox(&mut packed, "Packed lights");
+                    ui.label("Adding or removing packed lights does not recompile the shaders.");
+                    ui.label(format!("FPS: {:.0}", fps));
+                    if ui.checkbox(&mut vsync, "VSync").clicked() {
+                        set_vsync = Some(vsync);
+                    }
+                    ui.label(format!(
+                        "Screen: {}x MSAA, {} bit depth",
+                        surface_settings.multisamples, surface_settings.depth_bits
+                    ));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            lights.packed = Some(packed);
+            lights.point.truncate(light_count);
+            while lights.point.len() < light_count {
+                let i = lights.point.len();
+                lights.point.push(
+                    PointLight::new(
+                        &context,
+                        1.0,
+                        colors[i % colors.len()],
+                        &vec3(0.0, 0.0, 0.0),
+                        0.5,
+                        0.5,
+                        0.5,
+                    )
+                    .unwrap(),
+                );
+            }
+
+            let time = 0.001 * frame_input.accumulated_time as f32;
+            for (i, light) in lights.point.iter_mut().enumerate() {
+                let radius = 2.0 + 16.0 * (i as f32 / max_light_count as f32);
+                let speed = if i % 2 == 0 { 0.3 } else { -0.2 };
+                let angle = time * speed + i as f32 * 2.4;
+                light.set_position(&vec3(
+                    radius * angle.cos(),
+                    0.5 + 0.4 * (time + i as f32).sin(),
+                    radius * angle.sin(),
+                ));
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+                || {
+                    let mut objects: Vec<&dyn Object> = vec![&plane];
+                    for sphere in spheres.iter() {
+                        objects.push(sphere);
+                    }
+                    forward_pipeline.render_pass(&camera, &objects, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput {
+                    set_vsync,
+                    ..Default::default()
+                }
+            }
+        })
+        .unwrap();
+}
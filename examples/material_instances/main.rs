@@ -0,0 +1,206 @@
+use std::rc::Rc;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Material instances!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(40.0, 30.0, 40.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 500.0);
+
+    // Two checker textures which are used by the textured spheres
+    let checker = |color: [u8; 3]| {
+        let size = 64;
+        let mut data = Vec::with_capacity(size * size * 4);
+        for y in 0..size {
+            for x in 0..size {
+                let shade = if (x / 8 + y / 8) % 2 == 0 { 1.0 } else { 0.5 };
+                data.extend_from_slice(&[
+                    (color[0] as f32 * shade) as u8,
+                    (color[1] as f32 * shade) as u8,
+                    (color[2] as f32 * shade) as u8,
+                    255,
+                ]);
+            }
+        }
+        Rc::new(
+            Texture2D::new(
+                &context,
+                &CPUTexture {
+                    data,
+                    width: size as u32,
+                    height: size as u32,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        )
+    };
+    let textures = [checker([230, 200, 120]), checker([120, 200, 230])];
+
+    // Two base materials, one without and one with an albedo texture, so all spheres are rendered with two programs
+    let plain_base = PhysicalMaterial {
+        roughness: 0.5,
+        ..Default::default()
+    };
+    let textured_base = PhysicalMaterial {
+        albedo_texture: Some(textures[0].clone()),
+        roughness: 0.8,
+        ..Default::default()
+    };
+
+    // A grid of 1000 spheres which are rendered either with a full material each or with an instance of one of the base materials
+    let cpu_mesh = CPUMesh::sphere(16);
+    let mut full_spheres = Vec::new();
+    let mut instanced_spheres = Vec::new();
+    for i in 0..1000 {
+        let transformation = Mat4::from_translation(vec3(
+            3.0 * (i % 10) as f32 - 13.5,
+            3.0 * ((i / 10) % 10) as f32 - 13.5,
+            3.0 * (i / 100) as f32 - 13.5,
+        ));
+        let mut instance = if i % 2 == 0 {
+            let mut instance = plain_base.instance();
+            instance.set_albedo(Color::new_opaque(
+                (100 + i % 150) as u8,
+                130,
+                (200 - i % 150) as u8,
+            ));
+            instance.set_metallic((i % 10) as f32 / 10.0);
+            instance
+        } else {
+            let mut instance = textured_base.instance();
+            instance
+                .set_albedo_texture(textures[(i / 2) % 2].clone())
+                .unwrap();
+            instance
+        };
+        instance.set_roughness(0.2 + ((i / 10) % 10) as f32 / 12.5);
+
+        let mut full_sphere = Model::new_with_material(
+            &context,
+            &cpu_mesh,
+            PhysicalMaterial {
+                albedo: instance.albedo(),
+                albedo_texture: if i % 2 == 0 {
+                    None
+                } else {
+                    Some(textures[(i / 2) % 2].clone())
+                },
+                metallic: instance.metallic(),
+                roughness: instance.roughness(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        full_sphere.set_transformation(transformation);
+        full_spheres.push(full_sphere);
+
+        let mut instanced_sphere = Model::new_with_material(&context, &cpu_mesh, instance).unwrap();
+        instanced_sphere.set_transformation(transformation);
+        instanced_spheres.push(instanced_sphere);
+    }
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut use_instances = true;
+    let mut statistics = context.program_cache_statistics();
+
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut use_instances, false, "Full materials");
+                    ui.radio_value(&mut use_instances, true, "Material instances");
+                    ui.label(format!(
+                        "Program lookups: {}",
+                        statistics.hits + statistics.misses
+                    ));
+                    ui.label(format!("Program compilations: {}", statistics.compilations));
+                    ui.label(format!("Uniform updates: {}", statistics.uniform_updates));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    // Only the statistics of rendering the spheres are shown, not the ones of the GUI
+                    context.reset_program_cache_statistics();
+                    if use_instances {
+                        let models = instanced_spheres.iter().collect::<Vec<_>>();
+                        render_material_instances(&camera, &lights, &models)?;
+                    } else {
+                        render_pass(&camera, &full_spheres, &lights)?;
+                    }
+                    statistics = context.program_cache_statistics();
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
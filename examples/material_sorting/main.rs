@@ -0,0 +1,143 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Material sorting!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(40.0, 30.0, 40.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 500.0);
+
+    // A grid of 1000 cubes where every other cube is rendered with a different material and therefore with a different program
+    let mut physical_cubes = Vec::new();
+    let mut color_cubes = Vec::new();
+    for i in 0..1000 {
+        let transformation = Mat4::from_translation(vec3(
+            3.0 * (i % 10) as f32 - 13.5,
+            3.0 * ((i / 10) % 10) as f32 - 13.5,
+            3.0 * (i / 100) as f32 - 13.5,
+        )) * Mat4::from_scale(0.5);
+        if i % 2 == 0 {
+            let mut cube = Model::new_with_material(
+                &context,
+                &CPUMesh::cube(),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(100, 130, 200),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            cube.set_transformation(transformation);
+            physical_cubes.push(cube);
+        } else {
+            let mut cube = Model::new_with_material(
+                &context,
+                &CPUMesh::cube(),
+                ColorMaterial {
+                    color: Color::new_opaque(200, 130, 100),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            cube.set_transformation(transformation);
+            color_cubes.push(cube);
+        }
+    }
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let pipeline = ForwardPipeline::new(&context).unwrap();
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut options = RenderPassOptions::default();
+    let mut program_binds = 0;
+
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut options.sort_by_material, "Sort by material");
+                    ui.label(format!("Program binds: {}", program_binds));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    // Only the program binds of the render pass are counted, not the ones of the GUI
+                    context.reset_program_cache_statistics();
+                    // The two kinds of cubes are interleaved, so the order given is the worst case for the shader program switches
+                    let objects: Vec<&dyn Object> = physical_cubes
+                        .iter()
+                        .zip(color_cubes.iter())
+                        .flat_map(|(a, b)| vec![a as &dyn Object, b as &dyn Object])
+                        .collect();
+                    pipeline.render_pass_with_options(&camera, &objects, &lights, options)?;
+                    program_binds = context.program_cache_statistics().program_binds;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,298 @@
+use three_d::*;
+
+enum Mode {
+    Distance,
+    Angle,
+    Area,
+}
+
+enum Measurement {
+    Distance(DistanceMeasurement),
+    Angle(AngleMeasurement),
+    Area(AreaMeasurement),
+}
+
+impl Measurement {
+    fn object(&self) -> &dyn Object {
+        match self {
+            Measurement::Distance(m) => m,
+            Measurement::Angle(m) => m,
+            Measurement::Area(m) => m,
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Measurement::Distance(m) => format!("Distance: {}", m.label()),
+            Measurement::Angle(m) => format!("Angle: {}", m.label()),
+            Measurement::Area(m) => format!("Area: {}", m.label()),
+        }
+    }
+
+    fn set_format(&mut self, format: MeasurementFormat) -> ThreeDResult<()> {
+        match self {
+            Measurement::Distance(m) => m.set_format(format),
+            Measurement::Angle(m) => m.set_format(MeasurementFormat {
+                precision: format.precision,
+                ..m.format().clone()
+            }),
+            Measurement::Area(m) => m.set_format(format),
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Measurement!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(-60.0, 50.0, 60.0),
+        vec3(0.0, 15.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 500.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // Model from http://texturedmesh.isti.cnr.it/
+    let statue = Loading::new(
+        &context,
+        &[
+            "examples/assets/COLOMBE.obj",
+            "examples/assets/COLOMBE.mtl",
+            "examples/assets/COLOMBE.png",
+        ],
+        move |context, mut loaded| {
+            let (cpu_mesh, cpu_material) = loaded.obj("examples/assets/COLOMBE.obj")?.remove(0);
+            let mut statue = Model::new_with_material(
+                &context,
+                &cpu_mesh,
+                PhysicalMaterial::new(&context, &cpu_material.unwrap())?,
+            )?;
+            statue.material.opaque_render_states.cull = Cull::Back;
+            statue.set_transformation(Mat4::from_scale(1.5));
+            Ok(statue)
+        },
+    );
+
+    let mut point_marker = Model::new_with_material(
+        &context,
+        &CPUMesh::sphere(16),
+        ColorMaterial {
+            color: Color::new_opaque(255, 200, 0),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut mode = Mode::Distance;
+    let mut format = MeasurementFormat {
+        unit: "cm".to_string(),
+        ..Default::default()
+    };
+    let mut measurements: Vec<Measurement> = Vec::new();
+    let mut points: Vec<Vec3> = Vec::new();
+    let mut press_position = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            let mut undo = false;
+            let mut finish_polygon = false;
+            let mut delete = None;
+            let mut format_changed = false;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label("Click on the statue to place points");
+                    if ui
+                        .radio(matches!(mode, Mode::Distance), "Distance")
+                        .clicked()
+                    {
+                        mode = Mode::Distance;
+                        points.clear();
+                    }
+                    if ui.radio(matches!(mode, Mode::Angle), "Angle").clicked() {
+                        mode = Mode::Angle;
+                        points.clear();
+                    }
+                    if ui.radio(matches!(mode, Mode::Area), "Area").clicked() {
+                        mode = Mode::Area;
+                        points.clear();
+                    }
+                    if matches!(mode, Mode::Area) {
+                        finish_polygon = ui.button("Finish polygon").clicked();
+                    }
+                    undo = ui.button("Undo").clicked();
+                    format_changed |= ui
+                        .add(Slider::new(&mut format.unit_scale, 0.1..=10.0).text("Unit scale"))
+                        .changed();
+                    format_changed |= ui
+                        .add(Slider::new(&mut format.precision, 0..=4).text("Precision"))
+                        .changed();
+
+                    ui.separator();
+                    for (i, measurement) in measurements.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(measurement.description());
+                            if ui.button("Delete").clicked() {
+                                delete = Some(i);
+                            }
+                        });
+                    }
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            if format_changed {
+                for measurement in measurements.iter_mut() {
+                    measurement.set_format(format.clone()).unwrap();
+                }
+            }
+            if let Some(i) = delete {
+                measurements.remove(i);
+            }
+            if undo && points.pop().is_none() {
+                measurements.pop();
+            }
+
+            // A click places a point, while dragging rotates the camera
+            for event in frame_input.events.iter() {
+                match event {
+                    Event::MousePress {
+                        button,
+                        position,
+                        handled,
+                        ..
+                    } => {
+                        if *button == MouseButton::Left && !*handled {
+                            press_position = Some(*position);
+                        }
+                    }
+                    Event::MouseRelease {
+                        button,
+                        position,
+                        handled,
+                        ..
+                    } => {
+                        if *button == MouseButton::Left && !*handled {
+                            let is_click = press_position.map_or(false, |p| {
+                                (p.logical.0 - position.logical.0).abs() < 3.0
+                                    && (p.logical.1 - position.logical.1).abs() < 3.0
+                            });
+                            if is_click {
+                                let pixel =
+                                    frame_input.to_viewport_pixel(position, camera.viewport());
+                                if let Some(Ok(ref statue)) = *statue.borrow() {
+                                    if let Some(pick) =
+                                        pick(&context, &camera, pixel, &[statue]).unwrap()
+                                    {
+                                        points.push(pick);
+                                    }
+                                }
+                            }
+                        }
+                        press_position = None;
+                    }
+                    _ => {}
+                }
+            }
+
+            let measurement = match mode {
+                Mode::Distance if points.len() == 2 => Some(Measurement::Distance(
+                    DistanceMeasurement::new(&context, points[0], points[1]).unwrap(),
+                )),
+                Mode::Angle if points.len() == 3 => Some(Measurement::Angle(
+                    AngleMeasurement::new(&context, points[0], points[1], points[2]).unwrap(),
+                )),
+                Mode::Area if finish_polygon && points.len() >= 3 => Some(Measurement::Area(
+                    AreaMeasurement::new(&context, &points).unwrap(),
+                )),
+                _ => None,
+            };
+            if let Some(mut measurement) = measurement {
+                measurement.set_format(format.clone()).unwrap();
+                measurements.push(measurement);
+                points.clear();
+            }
+
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.9, 0.9, 0.9, 1.0, 1.0),
+                || {
+                    if let Some(Ok(ref statue)) = *statue.borrow() {
+                        let mut objects: Vec<&dyn Object> = vec![statue];
+                        objects.extend(measurements.iter().map(|m| m.object()));
+                        render_pass(&camera, &objects, &lights)?;
+                    }
+                    for point in points.iter() {
+                        point_marker.set_transformation(
+                            Mat4::from_translation(*point) * Mat4::from_scale(0.3),
+                        );
+                        point_marker.render(&camera, &lights)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
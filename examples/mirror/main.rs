@@ -0,0 +1,137 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Mirror!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 1.5, 4.0),
+        vec3(0.0, 1.0, -2.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+
+    let material = |r, g, b| PhysicalMaterial {
+        albedo: Color::new_opaque(r, g, b),
+        roughness: 0.6,
+        opaque_render_states: RenderStates {
+            cull: Cull::Back,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut models = Vec::new();
+    let mut add = |cpu_mesh: &CPUMesh, material: PhysicalMaterial, transformation: Mat4| {
+        let mut model = Model::new_with_material(&context, cpu_mesh, material).unwrap();
+        model.set_transformation(transformation);
+        models.push(model);
+    };
+    // Floor and the wall which the mirror hangs on
+    add(
+        &CPUMesh::square(),
+        material(180, 180, 170),
+        Mat4::from_scale(5.0) * Mat4::from_angle_x(degrees(-90.0)),
+    );
+    add(
+        &CPUMesh::square(),
+        material(120, 150, 180),
+        Mat4::from_translation(vec3(0.0, 2.5, -2.0)) * Mat4::from_scale(5.0),
+    );
+    // Objects in front of the mirror
+    add(
+        &CPUMesh::sphere(32),
+        material(200, 50, 50),
+        Mat4::from_translation(vec3(-1.0, 0.5, 0.0)) * Mat4::from_scale(0.5),
+    );
+    add(
+        &CPUMesh::cube(),
+        material(50, 200, 50),
+        Mat4::from_translation(vec3(1.0, 0.4, 0.5))
+            * Mat4::from_angle_y(degrees(30.0))
+            * Mat4::from_scale(0.4),
+    );
+    // A sphere sticking through the wall and mirror, only the part in front of the mirror should be reflected
+    add(
+        &CPUMesh::sphere(32),
+        material(220, 200, 50),
+        Mat4::from_translation(vec3(0.6, 1.2, -1.95)) * Mat4::from_scale(0.3),
+    );
+
+    let mut mirror = Mirror::new(
+        &context,
+        Mat4::from_translation(vec3(0.0, 1.5, -1.95)) * Mat4::from_nonuniform_scale(1.2, 0.8, 1.0),
+    )
+    .unwrap();
+    mirror.clear_state = ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0);
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        point: vec![PointLight::new(
+            &context,
+            1.0,
+            Color::WHITE,
+            &vec3(0.0, 3.0, 2.0),
+            0.5,
+            0.05,
+            0.005,
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // main loop
+    window
+        .render_loop(move |frame_input| {
+            camera.set_viewport(frame_input.viewport).unwrap();
+
+            // Walk past the mirror while looking at it
+            let time = 0.0005 * frame_input.accumulated_time as f32;
+            camera
+                .set_view(
+                    vec3(3.0 * time.sin(), 1.5, 3.0),
+                    vec3(0.0, 1.2, -2.0),
+                    vec3(0.0, 1.0, 0.0),
+                )
+                .unwrap();
+
+            mirror.render_reflection(&camera, &models, &lights).unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &models, &lights)?;
+                    mirror.render(&camera)?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
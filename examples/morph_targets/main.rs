@@ -0,0 +1,159 @@
+use three_d::core::*;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Morph targets!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    // Two simple expressions of a head, one surprised (stretched) and one with puffed cheeks
+    let mut cpu_mesh = CPUMesh::sphere(32);
+    let surprised = morph_target(&cpu_mesh, "Surprised", |p| {
+        vec3(-0.1 * p.x, 0.4 * p.y, -0.1 * p.z)
+    });
+    let puffed = morph_target(&cpu_mesh, "Puffed cheeks", |p| {
+        let cheeks = (1.0 - (p.y + 0.2).abs() * 2.0).max(0.0) * p.x.abs();
+        vec3(0.5 * cheeks * p.x, 0.0, 0.3 * cheeks * p.z)
+    });
+    cpu_mesh.morph_targets = vec![surprised, puffed];
+
+    let mut head = Model::new_with_material(
+        &context,
+        &cpu_mesh,
+        PhysicalMaterial {
+            albedo: Color::new_opaque(230, 180, 150),
+            roughness: 0.6,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            color: Color::WHITE,
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut weights = [0.0f32; 2];
+
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut weights[0], 0.0..=1.0).text("Surprised"));
+                    ui.add(Slider::new(&mut weights[1], 0.0..=1.0).text("Puffed cheeks"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            head.set_morph_weights(&weights);
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    head.render(&camera, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+///
+/// Creates a morph target by moving each position of the mesh by the given delta
+/// and computes the normal deltas from the normals of the deformed mesh.
+///
+fn morph_target(cpu_mesh: &CPUMesh, name: &str, delta: impl Fn(Vec3) -> Vec3) -> MorphTarget {
+    let mut positions = Vec::new();
+    let mut deformed = CPUMesh {
+        positions: cpu_mesh.positions.clone(),
+        indices: cpu_mesh
+            .indices
+            .as_ref()
+            .map(|i| Indices::U32(i.into_u32())),
+        ..Default::default()
+    };
+    for i in 0..cpu_mesh.positions.len() / 3 {
+        let d = delta(cpu_mesh.position(i));
+        positions.extend_from_slice(&[d.x, d.y, d.z]);
+        deformed.positions[i * 3] += d.x;
+        deformed.positions[i * 3 + 1] += d.y;
+        deformed.positions[i * 3 + 2] += d.z;
+    }
+    deformed.compute_normals();
+    let normals = cpu_mesh.normals.as_ref().map(|normals| {
+        normals
+            .iter()
+            .zip(deformed.normals.unwrap().iter())
+            .map(|(n, m)| m - n)
+            .collect()
+    });
+    MorphTarget {
+        name: name.to_string(),
+        positions,
+        normals,
+        weight: 0.0,
+    }
+}
@@ -0,0 +1,194 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Motion blur!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(-60.0, 50.0, 60.0),
+        vec3(0.0, 15.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 500.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // Model from http://texturedmesh.isti.cnr.it/
+    let statue = Loading::new(
+        &context,
+        &[
+            "examples/assets/COLOMBE.obj",
+            "examples/assets/COLOMBE.mtl",
+            "examples/assets/COLOMBE.png",
+        ],
+        move |context, mut loaded| {
+            let (cpu_mesh, cpu_material) = loaded.obj("examples/assets/COLOMBE.obj")?.remove(0);
+            let mut statue = Model::new_with_material(
+                &context,
+                &cpu_mesh,
+                PhysicalMaterial::new(&context, &cpu_material.unwrap())?,
+            )?;
+            statue.material.opaque_render_states.cull = Cull::Back;
+            statue.set_transformation(Mat4::from_scale(1.5));
+            Ok(statue)
+        },
+    );
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut motion_blur_effect = MotionBlurEffect::new(&context).unwrap();
+    let mut motion_blur_enabled = true;
+    let mut orbit_speed = 90.0;
+
+    let mut color_texture: Option<Texture2D<u8>> = None;
+    let mut depth_texture: Option<DepthTargetTexture2D> = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut motion_blur_enabled, "Motion blur");
+                    ui.add(
+                        Slider::new(&mut orbit_speed, 0.0..=360.0)
+                            .text("Orbit speed (degrees per second)"),
+                    );
+                    ui.add(Slider::new(&mut motion_blur_effect.samples, 2..=32).text("Samples"));
+                    ui.add(
+                        Slider::new(&mut motion_blur_effect.max_blur_radius, 1.0..=64.0)
+                            .text("Max blur radius"),
+                    );
+                    ui.add(
+                        Slider::new(&mut motion_blur_effect.shutter_angle, 0.0..=360.0)
+                            .text("Shutter angle"),
+                    );
+                    ui.label("When the camera is still, nothing is blurred");
+                });
+            })
+            .unwrap();
+
+            camera.set_viewport(frame_input.viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // Orbit the camera around the statue
+            let target = *camera.target();
+            let angle = degrees(orbit_speed * frame_input.elapsed_time as f32 * 0.001);
+            let position = target + Mat3::from_angle_y(angle) * (camera.position() - target);
+            let up = *camera.up();
+            camera.set_view(position, target, up).unwrap();
+
+            // Render the scene into a color and depth texture, recreated when the window is resized
+            let viewport = frame_input.viewport;
+            if color_texture
+                .as_ref()
+                .map(|t| t.width() != viewport.width || t.height() != viewport.height)
+                .unwrap_or(true)
+            {
+                color_texture = Some(
+                    Texture2D::new_empty(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Interpolation::Linear,
+                        Interpolation::Linear,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                    .unwrap(),
+                );
+                depth_texture = Some(
+                    DepthTargetTexture2D::new(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                    .unwrap(),
+                );
+            }
+            let color_texture = color_texture.as_mut().unwrap();
+            let depth_texture = depth_texture.as_mut().unwrap();
+            RenderTarget::new(&context, color_texture, depth_texture)
+                .unwrap()
+                .write(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0), || {
+                    if let Some(Ok(ref statue)) = *statue.borrow() {
+                        statue.render(&camera, &lights)?;
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            let color_texture = &*color_texture;
+            let depth_texture = &*depth_texture;
+
+            if !motion_blur_enabled {
+                motion_blur_effect.reset();
+                Screen::copy_from(
+                    &context,
+                    Some(color_texture),
+                    None,
+                    viewport,
+                    WriteMask::default(),
+                )
+                .unwrap();
+            }
+
+            // The motion blur is applied before the GUI is rendered, so the GUI is not blurred
+            Screen::write(&context, ClearState::none(), || {
+                if motion_blur_enabled {
+                    motion_blur_effect.apply(&camera, color_texture, depth_texture)?;
+                }
+                gui.render()?;
+                Ok(())
+            })
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,150 @@
+use three_d::*;
+
+// Transparent spheres nested inside each other and inside a large glass dome, which blend correctly from all camera angles
+// when the spheres are rendered in two passes, back faces first and then front faces. Zoom into the dome to see that it must be
+// rendered before the objects inside it when the camera is inside it, which is forced by giving it a lower render order.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Nested transparency!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 4.0, 16.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    let sphere = |color: Color, center: Vec3, radius: f32| {
+        let mut model = Model::new_with_material(
+            &context,
+            &CPUMesh::sphere(32),
+            PhysicalMaterial {
+                albedo: color,
+                roughness: 0.2,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        model.set_transformation(Mat4::from_translation(center) * Mat4::from_scale(radius));
+        model
+    };
+    let mut dome = sphere(Color::new(200, 230, 255, 60), vec3(0.0, 0.0, 0.0), 6.0);
+    let mut spheres = vec![
+        sphere(Color::new(255, 80, 80, 120), vec3(-1.5, 0.0, 0.0), 2.5),
+        sphere(Color::new(80, 255, 80, 150), vec3(-1.5, 0.0, 0.0), 1.5),
+        sphere(Color::new(80, 80, 255, 200), vec3(-1.5, 0.0, 0.0), 0.7),
+        sphere(Color::new(255, 255, 80, 120), vec3(2.5, 0.5, 1.0), 1.5),
+    ];
+    let mut floor = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(120, 120, 120),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    floor.set_transformation(
+        Mat4::from_translation(vec3(0.0, -6.0, 0.0))
+            * Mat4::from_scale(10.0)
+            * Mat4::from_angle_x(degrees(-90.0)),
+    );
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut two_pass = true;
+    let mut dome_first = true;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut two_pass, "Two pass transparency");
+                    ui.checkbox(&mut dome_first, "Render dome first when inside");
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            let transparency_mode = if two_pass {
+                TransparencyMode::TwoPass
+            } else {
+                TransparencyMode::SinglePass
+            };
+            for model in spheres.iter_mut().chain(std::iter::once(&mut dome)) {
+                model.material.transparency_mode = transparency_mode;
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+            let inside_dome = camera.position().magnitude() < 6.0;
+            dome.set_render_order(if dome_first && inside_dome { -1 } else { 0 });
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.3, 0.3, 0.3, 1.0, 1.0),
+                || {
+                    let mut objects = vec![&floor, &dome];
+                    objects.extend(spheres.iter());
+                    render_pass(&camera, &objects, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,168 @@
+use std::rc::Rc;
+use three_d::*;
+
+// Panels of different sizes which share one texture and are resized with nine-slice scaling,
+// so the corners and edges of the frame keep their proportions while the panels are resized with the sliders.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Nine slice!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 6.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 2.0, 30.0);
+
+    // A frame with cut corners, brackets in the corners and a transparent center, where the borders are 16 texels wide
+    let size = 64;
+    let border = 16;
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x.min(size - 1 - x);
+            let dy = y.min(size - 1 - y);
+            let in_corner = dx < border && dy < border;
+            data.extend_from_slice(&if dx + dy < 6 {
+                [0, 0, 0, 0]
+            } else if dx.min(dy) < 2 || dx + dy < 8 {
+                [80, 220, 255, 255]
+            } else if in_corner && (dx == 4 || dy == 4) && dx.max(dy) < 12 {
+                [255, 180, 60, 255]
+            } else {
+                [20, 40, 60, 170]
+            });
+        }
+    }
+    let texture = Rc::new(
+        Texture2D::new(
+            &context,
+            &CPUTexture {
+                data,
+                width: size as u32,
+                height: size as u32,
+                mip_map_filter: None,
+                wrap_s: Wrapping::ClampToEdge,
+                wrap_t: Wrapping::ClampToEdge,
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+    );
+
+    // The base size and the position of each panel
+    let layout = [
+        (vec2(2.0, 1.2), vec3(-2.0, 0.8, 0.0), Color::WHITE),
+        (
+            vec2(1.2, 2.4),
+            vec3(1.0, 0.0, -1.0),
+            Color::new_opaque(255, 200, 200),
+        ),
+        (
+            vec2(3.0, 0.5),
+            vec3(-0.5, -1.5, 0.5),
+            Color::new_opaque(200, 255, 200),
+        ),
+    ];
+    let mut panels = layout
+        .iter()
+        .map(|(size, position, tint)| {
+            let mut panel = NineSliceQuad::new(
+                &context,
+                texture.clone(),
+                NineSliceInsets::uniform(16.0),
+                *size,
+            )
+            .unwrap();
+            panel.set_transformation(Mat4::from_translation(*position));
+            panel.set_tint(*tint);
+            panel
+        })
+        .collect::<Vec<_>>();
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut width_scale = 1.0;
+    let mut height_scale = 1.0;
+    let mut screen_borders = false;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut width_scale, 0.2..=3.0).text("Width"));
+                    ui.add(Slider::new(&mut height_scale, 0.2..=3.0).text("Height"));
+                    ui.checkbox(
+                        &mut screen_borders,
+                        "Constant border thickness on the screen",
+                    );
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // Only the positions of the vertices are updated, the borders are never stretched
+            for (panel, (size, _, _)) in panels.iter_mut().zip(layout.iter()) {
+                panel.set_size(vec2(size.x * width_scale, size.y * height_scale));
+                panel.set_border_sizing(if screen_borders {
+                    BorderSizing::Screen
+                } else {
+                    BorderSizing::World { texel_size: 0.01 }
+                });
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.05, 0.05, 0.1, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &panels, &Lights::default())?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
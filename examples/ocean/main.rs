@@ -0,0 +1,178 @@
+use three_d::core::*;
+use three_d::*;
+
+// An ocean surface where the waves are computed in the vertex shader of the material.
+// With dynamic normals, the normals are recomputed from the displaced positions each frame,
+// so the specular highlights of the sun move over the waves instead of staying as on a flat plane.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Ocean!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 6.0, 20.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 200.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // The plane is in the xy-plane, so the waves displace the local z coordinate and the model is rotated to lie in the xz-plane
+    let cpu_mesh = CPUMesh::plane_subdivided(60.0, 60.0, 256, 256);
+    let mut ocean = Model::new_with_material(
+        &context,
+        &cpu_mesh,
+        WaveMaterial {
+            physical: PhysicalMaterial {
+                albedo: Color::new_opaque(20, 60, 110),
+                roughness: 0.15,
+                metallic: 0.0,
+                ..Default::default()
+            },
+            time: 0.0,
+            amplitude: 0.6,
+        },
+    )
+    .unwrap();
+    ocean.set_transformation(Mat4::from_angle_x(degrees(-90.0)));
+    ocean.enable_dynamic_normals(&cpu_mesh, true).unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            3.0,
+            Color::new_opaque(255, 240, 220),
+            &vec3(0.0, -0.3, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut dynamic_normals = true;
+    let mut speed = 1.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut dynamic_normals, "Dynamic normals");
+                    ui.add(Slider::new(&mut ocean.material.amplitude, 0.0..=2.0).text("Amplitude"));
+                    ui.add(Slider::new(&mut speed, 0.0..=3.0).text("Speed"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            if dynamic_normals != ocean.dynamic_normals_enabled() {
+                ocean
+                    .enable_dynamic_normals(&cpu_mesh, dynamic_normals)
+                    .unwrap();
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            ocean.material.time += speed * 0.001 * frame_input.elapsed_time as f32;
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.6, 0.75, 0.9, 1.0, 1.0),
+                || {
+                    ocean.render(&camera, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// A physical material which displaces the vertices by a sum of waves travelling in different directions
+struct WaveMaterial {
+    physical: PhysicalMaterial,
+    time: f32,
+    amplitude: f32,
+}
+
+impl Material for WaveMaterial {
+    fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
+        self.physical
+            .fragment_shader_source(use_vertex_colors, lights)
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.physical.use_uniforms(program, camera, lights)
+    }
+    fn render_states(&self) -> RenderStates {
+        self.physical.render_states()
+    }
+    fn is_transparent(&self) -> bool {
+        self.physical.is_transparent()
+    }
+    fn vertex_displacement_source(&self) -> Option<String> {
+        Some(
+            "uniform float waveTime;
+            uniform float waveAmplitude;
+
+            vec3 displace_position(vec3 position)
+            {
+                float height = 0.5 * sin(0.4 * position.x + 1.3 * waveTime)
+                    + 0.3 * sin(0.7 * position.y - 0.5 * position.x + 1.9 * waveTime)
+                    + 0.2 * sin(1.3 * position.x + 1.1 * position.y + 2.7 * waveTime);
+                return position + vec3(0.0, 0.0, waveAmplitude * height);
+            }"
+            .to_string(),
+        )
+    }
+    fn use_vertex_uniforms(&self, program: &Program) -> ThreeDResult<()> {
+        program.use_uniform_float("waveTime", &self.time)?;
+        program.use_uniform_float("waveAmplitude", &self.amplitude)
+    }
+}
@@ -0,0 +1,246 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Painting!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(3.0, 2.0, 3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    // Paint with the left mouse button and orbit with the right mouse button
+    let target = *camera.target();
+    let mut control = CameraControl {
+        right_drag_horizontal: CameraAction::OrbitLeft { target, speed: 0.5 },
+        right_drag_vertical: CameraAction::OrbitUp { target, speed: 0.5 },
+        scroll_vertical: CameraAction::Zoom {
+            target,
+            speed: 0.1,
+            min: 2.0,
+            max: 20.0,
+        },
+        ..Default::default()
+    };
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // The uv coordinates of all sides of the default cube overlap, so they are placed side by side in a 3x2 grid instead
+    let mut cube = CPUMesh::cube();
+    let uvs = cube.uvs.as_mut().unwrap();
+    for (i, uv) in uvs.chunks_mut(2).enumerate() {
+        let side = i / 6;
+        uv[0] = ((side % 3) as f32 + 0.05 + 0.9 * uv[0]) / 3.0;
+        uv[1] = ((side / 3) as f32 + 0.05 + 0.9 * uv[1]) / 2.0;
+    }
+    let meshes = [CPUMesh::sphere(32), cube];
+
+    // The loaded mesh has no uv coordinates, so they are generated
+    let suzanne = Loading::new(
+        &context,
+        &["examples/assets/suzanne.obj", "examples/assets/suzanne.mtl"],
+        move |_, mut loaded| {
+            let mut cpu_mesh = loaded.obj("examples/assets/suzanne.obj")?.remove(0).0;
+            if cpu_mesh.uvs.is_none() {
+                cpu_mesh.generate_uvs(UvMethod::Charts {
+                    max_angle: degrees(60.0),
+                    texture_size: 1024,
+                    padding: 4,
+                })?;
+            }
+            Ok(cpu_mesh)
+        },
+    );
+
+    let painting_context = context.clone();
+    let new_painted = move |cpu_mesh: &CPUMesh| {
+        let context = &painting_context;
+        let texture = PaintableTexture::new(
+            context,
+            cpu_mesh,
+            &CPUTexture {
+                data: vec![240; 1024 * 1024 * 4],
+                width: 1024,
+                height: 1024,
+                format: Format::RGBA,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let model = Model::new_with_material(
+            context,
+            cpu_mesh,
+            PhysicalMaterial {
+                albedo_texture: Some(texture.texture().clone()),
+                roughness: 0.7,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let collider = TriMeshCollider::new(cpu_mesh, Mat4::identity()).unwrap();
+        (texture, model, collider)
+    };
+    let mut painted = meshes.iter().map(&new_painted).collect::<Vec<_>>();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut mesh_index = 0;
+    let mut world_space = true;
+    let mut radius = 0.2;
+    let mut hardness = 0.5;
+    let mut color = [200, 30, 30];
+    let mut dilation = 4;
+    let mut painting = false;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            if let Some(cpu_mesh) = suzanne.borrow_mut().take() {
+                painted.push(new_painted(&cpu_mesh.unwrap()));
+            }
+
+            let mut panel_width = 0;
+            let mut undo = false;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut mesh_index, 0, "Sphere");
+                    ui.radio_value(&mut mesh_index, 1, "Cube");
+                    if painted.len() > 2 {
+                        ui.radio_value(&mut mesh_index, 2, "Suzanne (generated uvs)");
+                    }
+                    ui.checkbox(&mut world_space, "World space brush");
+                    ui.add(Slider::new(&mut radius, 0.01..=1.0).text("Brush size"));
+                    ui.add(Slider::new(&mut hardness, 0.0..=1.0).text("Hardness"));
+                    ui.horizontal(|ui| {
+                        ui.label("Color");
+                        ui.color_edit_button_srgb(&mut color);
+                    });
+                    ui.add(Slider::new(&mut dilation, 0..=16).text("Dilation"));
+                    undo = ui.button("Undo").clicked();
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            let (ref mut texture, ref model, ref collider) = painted[mesh_index];
+            if texture.dilation() != dilation {
+                texture.set_dilation(dilation).unwrap();
+            }
+            if undo {
+                texture.undo();
+            }
+            let brush = Brush {
+                radius: if world_space {
+                    BrushRadius::World(radius)
+                } else {
+                    BrushRadius::Texture(0.25 * radius)
+                },
+                color: Color::new_opaque(color[0], color[1], color[2]),
+                hardness,
+            };
+
+            for event in frame_input.events.iter() {
+                let position = match event {
+                    Event::MousePress {
+                        button: MouseButton::Left,
+                        position,
+                        handled: false,
+                        ..
+                    } => {
+                        painting = true;
+                        texture.begin_stroke();
+                        Some(position)
+                    }
+                    Event::MouseMotion {
+                        button: Some(MouseButton::Left),
+                        position,
+                        ..
+                    } if painting => Some(position),
+                    Event::MouseRelease {
+                        button: MouseButton::Left,
+                        ..
+                    } => {
+                        painting = false;
+                        texture.end_stroke();
+                        None
+                    }
+                    _ => None,
+                };
+                if let Some(position) = position {
+                    let pixel = frame_input.to_viewport_pixel(position, camera.viewport());
+                    if let Some(hit) = collider.raycast(
+                        camera.position_at_pixel(pixel),
+                        camera.view_direction_at_pixel(pixel),
+                        100.0,
+                    ) {
+                        texture.paint(&brush, &hit).unwrap();
+                    }
+                }
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.3, 0.3, 0.3, 1.0, 1.0),
+                || {
+                    model.render(&camera, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,169 @@
+use std::rc::Rc;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Parallax!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 3.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    // Procedural cobblestone textures
+    let size = 256;
+    let cells = 4;
+    let mut heights = vec![0.0f32; size * size];
+    for y in 0..size {
+        for x in 0..size {
+            let cell_size = size as f32 / cells as f32;
+            let fx = (x as f32 % cell_size) / cell_size - 0.5;
+            let fy = (y as f32 % cell_size) / cell_size - 0.5;
+            let d = fx.abs().max(fy.abs()) * 2.0;
+            heights[y * size + x] = (1.0 - d * d * d * d).max(0.0).sqrt();
+        }
+    }
+    let height_at = |x: i32, y: i32| {
+        let x = x.rem_euclid(size as i32) as usize;
+        let y = y.rem_euclid(size as i32) as usize;
+        heights[y * size + x]
+    };
+    let mut albedo_data = Vec::with_capacity(size * size * 4);
+    let mut normal_data = Vec::with_capacity(size * size * 4);
+    let mut height_data = Vec::with_capacity(size * size * 4);
+    for y in 0..size as i32 {
+        for x in 0..size as i32 {
+            let h = height_at(x, y);
+            let shade = (100.0 + 100.0 * h) as u8;
+            albedo_data.extend_from_slice(&[
+                shade,
+                (shade as f32 * 0.9) as u8,
+                (shade as f32 * 0.8) as u8,
+                255,
+            ]);
+            let dx = (height_at(x + 1, y) - height_at(x - 1, y)) * 4.0;
+            let dy = (height_at(x, y + 1) - height_at(x, y - 1)) * 4.0;
+            let n = vec3(-dx, -dy, 1.0).normalize();
+            normal_data.extend_from_slice(&[
+                ((n.x * 0.5 + 0.5) * 255.0) as u8,
+                ((n.y * 0.5 + 0.5) * 255.0) as u8,
+                ((n.z * 0.5 + 0.5) * 255.0) as u8,
+                255,
+            ]);
+            let hb = (h * 255.0) as u8;
+            height_data.extend_from_slice(&[hb, hb, hb, 255]);
+        }
+    }
+    let texture = |data: Vec<u8>| {
+        Rc::new(
+            Texture2D::new(
+                &context,
+                &CPUTexture {
+                    data,
+                    width: size as u32,
+                    height: size as u32,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        )
+    };
+    let albedo_texture = texture(albedo_data);
+    let normal_texture = texture(normal_data);
+    let height_texture = texture(height_data);
+
+    let cube = CPUMesh::cube();
+    let mut flat = Model::new_with_material(
+        &context,
+        &cube,
+        TexturedMaterial {
+            albedo_texture: Some(albedo_texture.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let normal_mapped = Model::new_with_material(
+        &context,
+        &cube,
+        TexturedMaterial {
+            albedo_texture: Some(albedo_texture.clone()),
+            normal_texture: Some(normal_texture.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut parallax_mapped = Model::new_with_material(
+        &context,
+        &cube,
+        TexturedMaterial {
+            albedo_texture: Some(albedo_texture),
+            normal_texture: Some(normal_texture),
+            height_texture: Some(height_texture),
+            height_scale: 0.05,
+            parallax_steps: 32,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    flat.set_transformation(Mat4::from_translation(vec3(-3.0, 0.0, 0.0)));
+    parallax_mapped.set_transformation(Mat4::from_translation(vec3(3.0, 0.0, 0.0)));
+    let models = vec![flat, normal_mapped, parallax_mapped];
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut redraw = frame_input.first_frame;
+            redraw |= camera.set_viewport(frame_input.viewport).unwrap();
+            redraw |= control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            if redraw {
+                Screen::write(
+                    &context,
+                    ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                    || {
+                        for model in models.iter() {
+                            model.render(&camera, &Lights::default())?;
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            }
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput {
+                    swap_buffers: redraw,
+                    ..Default::default()
+                }
+            }
+        })
+        .unwrap();
+}
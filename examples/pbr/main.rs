@@ -78,7 +78,7 @@ fn main() {
                     ui.checkbox(&mut occlusion_map_enabled, "Occlusion map");
                     ui.checkbox(&mut emissive_map_enabled, "Emissive map");
                 });
-                panel_width = gui_context.used_size().x as u32;
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
             })
             .unwrap();
 
@@ -90,7 +90,11 @@ fn main() {
             };
             camera.set_viewport(viewport).unwrap();
             control
-                .handle_events(&mut camera, &mut frame_input.events)
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
                 .unwrap();
 
             Screen::write(
@@ -108,6 +112,8 @@ fn main() {
                             } else {
                                 None
                             },
+                            albedo_texture_transform: model.material.albedo_texture_transform,
+                            albedo_texture_uv_set: model.material.albedo_texture_uv_set,
                             metallic: model.material.metallic,
                             roughness: model.material.roughness,
                             metallic_roughness_texture: if metallic_roughness_enabled {
@@ -115,18 +121,28 @@ fn main() {
                             } else {
                                 None
                             },
+                            metallic_roughness_texture_transform: model
+                                .material
+                                .metallic_roughness_texture_transform,
+                            metallic_roughness_texture_uv_set: model
+                                .material
+                                .metallic_roughness_texture_uv_set,
                             normal_scale: model.material.normal_scale,
                             normal_texture: if normal_map_enabled {
                                 model.material.normal_texture.clone()
                             } else {
                                 None
                             },
+                            normal_texture_transform: model.material.normal_texture_transform,
+                            normal_texture_uv_set: model.material.normal_texture_uv_set,
                             occlusion_strength: model.material.occlusion_strength,
                             occlusion_texture: if occlusion_map_enabled {
                                 model.material.occlusion_texture.clone()
                             } else {
                                 None
                             },
+                            occlusion_texture_transform: model.material.occlusion_texture_transform,
+                            occlusion_texture_uv_set: model.material.occlusion_texture_uv_set,
                             emissive: if emissive_map_enabled {
                                 model.material.emissive
                             } else {
@@ -137,8 +153,13 @@ fn main() {
                             } else {
                                 None
                             },
+                            emissive_texture_transform: model.material.emissive_texture_transform,
+                            emissive_texture_uv_set: model.material.emissive_texture_uv_set,
                             opaque_render_states: model.material.opaque_render_states,
                             transparent_render_states: model.material.transparent_render_states,
+                            double_sided: model.material.double_sided,
+                            alpha_mode: model.material.alpha_mode,
+                            transparency_mode: model.material.transparency_mode,
                         };
                         model.render_with_material(&material, &camera, lights)?;
                     }
@@ -55,11 +55,12 @@ fn main() {
         &context,
         &["examples/assets/suzanne.obj", "examples/assets/suzanne.mtl"],
         move |context, mut loaded| {
-            let (meshes, materials) = loaded.obj("examples/assets/suzanne.obj").unwrap();
+            let (cpu_mesh, cpu_material) =
+                loaded.obj("examples/assets/suzanne.obj").unwrap().remove(0);
             let mut monkey = Model::new_with_material(
                 &context,
-                &meshes[0],
-                PhysicalMaterial::new(&context, &materials[0]).unwrap(),
+                &cpu_mesh,
+                PhysicalMaterial::new(&context, &cpu_material.unwrap()).unwrap(),
             )
             .unwrap();
             monkey.material.opaque_render_states.cull = Cull::Back;
@@ -84,10 +85,7 @@ fn main() {
                         button, position, ..
                     } => {
                         if *button == MouseButton::Left {
-                            let pixel = (
-                                (frame_input.device_pixel_ratio * position.0) as f32,
-                                (frame_input.device_pixel_ratio * position.1) as f32,
-                            );
+                            let pixel = frame_input.to_viewport_pixel(position, camera.viewport());
                             if let Some(ref monkey) = *monkey.borrow() {
                                 let monkey = monkey.as_ref().unwrap();
                                 if let Some(pick) =
@@ -104,7 +102,11 @@ fn main() {
             }
 
             change |= control
-                .handle_events(&mut camera, &mut frame_input.events)
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
                 .unwrap();
 
             // draw
@@ -0,0 +1,186 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Point cloud!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 2.0, 6.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 50.0);
+
+    // A million points on a torus knot saved as a binary .ply file and then loaded again,
+    // in the same way as a scanned point cloud would be loaded.
+    let mut loaded = Loaded::new();
+    loaded.insert_bytes("torus_knot.ply", torus_knot_ply(1_000_000));
+    let cpu_mesh = loaded.ply("torus_knot.ply").unwrap();
+    let mut point_cloud = PointCloud::new_with_cpu_mesh(&context, &cpu_mesh, 2.0).unwrap();
+    let positions = cpu_mesh
+        .positions
+        .chunks(3)
+        .map(|p| vec3(p[0], p[1], p[2]))
+        .collect::<Vec<_>>();
+    let colors = cpu_mesh
+        .colors
+        .as_ref()
+        .unwrap()
+        .chunks(4)
+        .map(|c| Color::new(c[0], c[1], c[2], c[3]))
+        .collect::<Vec<_>>();
+
+    let mut cube = Model::new_with_material(
+        &context,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(100, 100, 100),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    cube.set_transformation(Mat4::from_scale(0.4));
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut size = 2.0;
+    let mut current = (point_cloud.sizing, size);
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label(format!("{} points", point_cloud.count()));
+                    ui.label("Shape");
+                    ui.radio_value(&mut point_cloud.shape, MarkerShape::Circle, "Circle");
+                    ui.radio_value(&mut point_cloud.shape, MarkerShape::Square, "Square");
+                    ui.radio_value(&mut point_cloud.shape, MarkerShape::Cross, "Cross");
+                    ui.label("Sizing");
+                    ui.radio_value(&mut point_cloud.sizing, MarkerSizing::Screen, "Pixels");
+                    ui.radio_value(&mut point_cloud.sizing, MarkerSizing::World, "World");
+                    ui.add(Slider::new(&mut size, 1.0..=10.0).text("Size"));
+                    ui.checkbox(&mut point_cloud.depth_test, "Depth test");
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            // Updating the points reuses the buffers since the number of points is the same
+            if current != (point_cloud.sizing, size) {
+                current = (point_cloud.sizing, size);
+                let size = match point_cloud.sizing {
+                    MarkerSizing::Screen => size,
+                    MarkerSizing::World => 0.005 * size,
+                };
+                point_cloud
+                    .set_points(&positions, &colors, &vec![size; positions.len()])
+                    .unwrap();
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0),
+                || {
+                    cube.render(&camera, &lights)?;
+                    point_cloud.render(&camera)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// Creates a binary .ply file with the given number of colored points spread around a torus knot
+fn torus_knot_ply(count: usize) -> Vec<u8> {
+    let mut bytes = format!(
+        "ply\nformat binary_little_endian 1.0\nelement vertex {}\n\
+        property float x\nproperty float y\nproperty float z\n\
+        property uchar red\nproperty uchar green\nproperty uchar blue\nend_header\n",
+        count
+    )
+    .into_bytes();
+    // A simple linear congruential generator to avoid a dependency on a random number crate
+    let mut seed = 1u32;
+    let mut random = || {
+        seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        seed as f32 / u32::MAX as f32
+    };
+    for _ in 0..count {
+        let t = random() * 2.0 * std::f32::consts::PI;
+        let r = 1.0 + 0.4 * (3.0 * t).cos();
+        let center = vec3(
+            r * (2.0 * t).cos(),
+            0.4 * (3.0 * t).sin(),
+            r * (2.0 * t).sin(),
+        );
+        let offset = vec3(random() - 0.5, random() - 0.5, random() - 0.5) * 0.3;
+        let p = center + offset;
+        for v in [p.x, p.y, p.z].iter() {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[
+            (255.0 * t / (2.0 * std::f32::consts::PI)) as u8,
+            (255.0 * (offset.magnitude() / 0.26).min(1.0)) as u8,
+            200,
+        ]);
+    }
+    bytes
+}
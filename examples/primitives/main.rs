@@ -0,0 +1,156 @@
+use std::rc::Rc;
+use three_d::*;
+
+// A gallery of the primitive meshes in CPUMesh, rendered with a checkerboard texture to show the uv coordinates.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Primitives!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 5.0, 12.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    // A checkerboard with a red and a green corner at uv coordinates (1, 0) and (0, 1), so the orientation of the uv coordinates is visible
+    let size = 256;
+    let mut data: Vec<u8> = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            let checker = ((x / 32) + (y / 32)) % 2 == 0;
+            let value = if checker { 230 } else { 60 };
+            let color = if x >= 224 && y < 32 {
+                [230, 40, 40]
+            } else if x < 32 && y >= 224 {
+                [40, 230, 40]
+            } else {
+                [value, value, value]
+            };
+            data.extend_from_slice(&[color[0], color[1], color[2], 255]);
+        }
+    }
+    let checkerboard = Rc::new(
+        Texture2D::new(
+            &context,
+            &CPUTexture {
+                data,
+                width: size,
+                height: size,
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+    );
+
+    let mut meshes = vec![
+        (CPUMesh::sphere(16), Mat4::identity()),
+        (CPUMesh::cube(), Mat4::identity()),
+        (
+            CPUMesh::cylinder(16),
+            Mat4::from_translation(vec3(-1.0, 0.0, 0.0))
+                * Mat4::from_nonuniform_scale(2.0, 1.0, 1.0),
+        ),
+        (
+            CPUMesh::cone_with_options(1.0, 2.0, 16, true),
+            Mat4::from_translation(vec3(0.0, -1.0, 0.0)) * Mat4::from_angle_z(degrees(90.0)),
+        ),
+        (
+            CPUMesh::arrow_with_options(0.3, 0.7, 0.35, 16),
+            Mat4::from_translation(vec3(-1.0, 0.0, 0.0)) * Mat4::from_scale(2.0),
+        ),
+        (
+            CPUMesh::capsule(0.7, 1.0, 16, 8),
+            Mat4::from_angle_z(degrees(90.0)),
+        ),
+        (CPUMesh::rounded_cube(2.0, 0.4, 4), Mat4::identity()),
+        (
+            CPUMesh::plane_subdivided(2.0, 2.0, 8, 8),
+            Mat4::from_angle_x(degrees(-45.0)),
+        ),
+    ];
+    let mut models = Vec::new();
+    for (i, (mesh, transformation)) in meshes.iter_mut().enumerate() {
+        mesh.transform(transformation);
+        let position = vec3(3.0 * (i % 4) as f32 - 4.5, 0.0, 3.0 * (i / 4) as f32 - 1.5);
+        let mut model = Model::new_with_material(
+            &context,
+            mesh,
+            PhysicalMaterial {
+                albedo_texture: Some(checkerboard.clone()),
+                roughness: 0.7,
+                opaque_render_states: RenderStates {
+                    cull: Cull::Back,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        model.set_transformation(Mat4::from_translation(position));
+        models.push(model);
+    }
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            camera.set_viewport(frame_input.viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &models, &lights)?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,172 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Probes!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 3.8),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 10.0);
+
+    // A Cornell box, ie. a box with a red wall to the left, a green wall to the right and an open front, with white objects inside
+    let material = |r, g, b| PhysicalMaterial {
+        albedo: Color::new_opaque(r, g, b),
+        roughness: 1.0,
+        metallic: 0.0,
+        ..Default::default()
+    };
+    let mut models = Vec::new();
+    let mut add = |cpu_mesh: &CPUMesh, material: PhysicalMaterial, transformation: Mat4| {
+        let mut model = Model::new_with_material(&context, cpu_mesh, material).unwrap();
+        model.set_transformation(transformation);
+        models.push(model);
+    };
+    let square = CPUMesh::square();
+    add(
+        &square,
+        material(220, 220, 220),
+        Mat4::from_translation(vec3(0.0, -1.0, 0.0)) * Mat4::from_angle_x(degrees(-90.0)),
+    );
+    add(
+        &square,
+        material(220, 220, 220),
+        Mat4::from_translation(vec3(0.0, 1.0, 0.0)) * Mat4::from_angle_x(degrees(90.0)),
+    );
+    add(
+        &square,
+        material(220, 220, 220),
+        Mat4::from_translation(vec3(0.0, 0.0, -1.0)),
+    );
+    add(
+        &square,
+        material(200, 30, 30),
+        Mat4::from_translation(vec3(-1.0, 0.0, 0.0)) * Mat4::from_angle_y(degrees(90.0)),
+    );
+    add(
+        &square,
+        material(30, 200, 30),
+        Mat4::from_translation(vec3(1.0, 0.0, 0.0)) * Mat4::from_angle_y(degrees(-90.0)),
+    );
+    add(
+        &CPUMesh::cube(),
+        material(220, 220, 220),
+        Mat4::from_translation(vec3(0.35, -0.6, -0.3))
+            * Mat4::from_angle_y(degrees(20.0))
+            * Mat4::from_nonuniform_scale(0.3, 0.4, 0.3),
+    );
+    add(
+        &CPUMesh::sphere(32),
+        material(220, 220, 220),
+        Mat4::from_translation(vec3(-0.4, -0.65, 0.3)) * Mat4::from_scale(0.35),
+    );
+
+    let mut lights = Lights {
+        spot: vec![SpotLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(0.0, 0.95, 0.0),
+            &vec3(0.0, -1.0, 0.0),
+            degrees(70.0),
+            1.0,
+            0.1,
+            0.1,
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+    lights.spot[0].generate_shadow_map(1024, &models).unwrap();
+
+    // Bake the light bouncing off the walls into a grid of probes inside the box.
+    // The probes inside the cube see its back faces and are skipped when interpolating.
+    let aabb = AxisAlignedBoundingBox::new_with_positions(&[-0.95, -0.95, -0.95, 0.95, 0.95, 0.95]);
+    let mut probe_grid = ProbeGrid::new(&context, aabb, (6, 6, 6)).unwrap();
+    probe_grid.bake(&models, &lights, 16).unwrap();
+    // Baking again with the first grid in the lights adds a second bounce of light
+    lights.probe_grid = Some(probe_grid);
+    let mut probe_grid = ProbeGrid::new(&context, aabb, (6, 6, 6)).unwrap();
+    probe_grid.bake(&models, &lights, 16).unwrap();
+    lights.probe_grid = Some(probe_grid);
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut use_probes = true;
+    let mut intensity = 1.0;
+    let mut disabled_probe_grid = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut use_probes, "Probe grid");
+                    ui.add(Slider::new(&mut intensity, 0.0..=3.0).text("Intensity"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            if use_probes != lights.probe_grid.is_some() {
+                std::mem::swap(&mut lights.probe_grid, &mut disabled_probe_grid);
+            }
+            if let Some(ref mut probe_grid) = lights.probe_grid {
+                probe_grid.intensity = intensity;
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &models, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
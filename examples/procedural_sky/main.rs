@@ -0,0 +1,147 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Procedural sky!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(8.0, 3.0, 8.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    let mut models = Vec::new();
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(120, 140, 90),
+            roughness: 0.9,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(20.0));
+    models.push(ground);
+    for i in 0..5 {
+        let mut pillar = Model::new_with_material(
+            &context,
+            &CPUMesh::cube(),
+            PhysicalMaterial {
+                albedo: Color::new_opaque(220, 220, 220),
+                roughness: 0.5,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let angle = i as f32 * 2.0 * std::f32::consts::PI / 5.0;
+        pillar.set_transformation(
+            Mat4::from_translation(vec3(4.0 * angle.cos(), 1.5, 4.0 * angle.sin()))
+                * Mat4::from_nonuniform_scale(0.4, 1.5, 0.4),
+        );
+        models.push(pillar);
+    }
+
+    let mut sky = ProceduralSky::new(&context, &vec3(0.0, -1.0, 0.0), 2.5).unwrap();
+    let mut lights = Lights::default();
+    lights
+        .directional
+        .push(DirectionalLight::new(&context, 1.0, Color::WHITE, &sky.sun_direction()).unwrap());
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut time_of_day = 9.0;
+    let mut turbidity = sky.turbidity();
+    let mut current = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut time_of_day, 0.0..=24.0).text("Time of day"));
+                    ui.add(Slider::new(&mut turbidity, 1.7..=10.0).text("Turbidity"));
+                    ui.add(Slider::new(&mut sky.exposure, 0.1..=4.0).text("Exposure"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            // The sun rises at 6 and sets at 18, when the sun moves, the light, the shadows and the ambient light from the sky are updated
+            if current != Some((time_of_day, turbidity)) {
+                current = Some((time_of_day, turbidity));
+                let angle = (time_of_day - 6.0) / 12.0 * std::f32::consts::PI;
+                sky.set_sun_direction(&-vec3(angle.cos(), angle.sin(), 0.4));
+                sky.set_turbidity(turbidity);
+
+                let sun = &mut lights.directional[0];
+                sun.set_direction(&sky.sun_direction());
+                sun.set_color(sky.sun_color());
+                sun.set_intensity(sky.sun_intensity());
+                sun.generate_shadow_map(30.0, 2048, 2048, &models).unwrap();
+                lights.ambient = Some(AmbientLight {
+                    intensity: 1.0,
+                    color: Color::WHITE,
+                    environment: Some(
+                        Environment::new(&context, &sky.bake_to_cubemap(64).unwrap()).unwrap(),
+                    ),
+                    ground_color: None,
+                    sdf_ao: None,
+                    enabled: true,
+                });
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(&context, ClearState::depth(1.0), || {
+                for model in models.iter() {
+                    model.render(&camera, &lights)?;
+                }
+                sky.render(&camera, &lights)?;
+                gui.render()?;
+                Ok(())
+            })
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
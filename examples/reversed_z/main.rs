@@ -0,0 +1,184 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Reversed Z!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+    context.set_depth_mode(DepthMode::ReversedZ);
+
+    // A planet with a radius of a million units seen from far away with a near plane at 0.1,
+    // which is a ratio between the far and the near plane of 10^8
+    let radius = 1_000_000.0;
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 2.5 * radius),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        10.0 * radius,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.2 * radius, 5.0 * radius);
+
+    // Two shells which are at most a few hundred units apart, the ocean and the terrain slightly above and below it
+    let mut ocean_mesh = CPUMesh::sphere(128);
+    ocean_mesh.transform(&Mat4::from_scale(radius));
+    let ocean = Model::new_with_material(
+        &context,
+        &ocean_mesh,
+        PhysicalMaterial {
+            albedo: Color::new_opaque(30, 70, 160),
+            roughness: 0.3,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut terrain_mesh = CPUMesh::sphere(128);
+    for i in 0..terrain_mesh.positions.len() / 3 {
+        let p = terrain_mesh.position(i);
+        let height = 300.0 * (7.0 * p.x).sin() * (5.0 * p.y).sin() * (6.0 * p.z).sin();
+        let p = (radius + height) * p;
+        terrain_mesh.positions[i * 3] = p.x;
+        terrain_mesh.positions[i * 3 + 1] = p.y;
+        terrain_mesh.positions[i * 3 + 2] = p.z;
+    }
+    terrain_mesh.compute_normals();
+    let terrain = Model::new_with_material(
+        &context,
+        &terrain_mesh,
+        PhysicalMaterial {
+            albedo: Color::new_opaque(90, 140, 60),
+            roughness: 0.9,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.2,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -0.5, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut reversed_z = true;
+    let clip_control = context.capabilities().clip_control;
+
+    // The default framebuffer usually has a 24 bit fixed point depth buffer,
+    // so the scene is rendered into a floating point depth texture, which is needed for the precision of the reversed depth mode
+    let mut color_texture: Option<Texture2D<u8>> = None;
+    let mut depth_texture: Option<DepthTargetTexture2D> = None;
+
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut reversed_z, "Reversed Z");
+                    if !clip_control {
+                        ui.label("Clip control is not supported, so the reversed depth mode does not improve the precision");
+                    }
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            context.set_depth_mode(if reversed_z {
+                DepthMode::ReversedZ
+            } else {
+                DepthMode::Standard
+            });
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(&mut camera, &mut frame_input.events, frame_input.elapsed_time)
+                .unwrap();
+
+            if color_texture
+                .as_ref()
+                .map(|t| t.width() != viewport.width || t.height() != viewport.height)
+                .unwrap_or(true)
+            {
+                color_texture = Some(
+                    Texture2D::new_empty(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Interpolation::Nearest,
+                        Interpolation::Nearest,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                    .unwrap(),
+                );
+                depth_texture = Some(
+                    DepthTargetTexture2D::new(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                    .unwrap(),
+                );
+            }
+            let color_texture = color_texture.as_mut().unwrap();
+            RenderTarget::new(&context, color_texture, depth_texture.as_mut().unwrap())
+                .unwrap()
+                .write(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0), || {
+                    ocean.render(&camera, &lights)?;
+                    terrain.render(&camera, &lights)?;
+                    Ok(())
+                })
+                .unwrap();
+
+            Screen::copy_from(
+                &context,
+                Some(&*color_texture),
+                None,
+                viewport,
+                WriteMask::COLOR,
+            )
+            .unwrap();
+            Screen::write(&context, ClearState::none(), || gui.render()).unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
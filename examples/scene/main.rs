@@ -0,0 +1,117 @@
+use three_d::*;
+
+const SCENE_PATH: &str = "examples/scene/scene.json";
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Scene!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let (mut description, mut scene) = load_scene(&context, window.viewport().unwrap());
+    let mut control = OrbitControl::new(*scene.camera.target(), 1.0, 100.0);
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            let mut save = false;
+            let mut load = false;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    if let Some(ref mut ambient) = scene.lights.ambient {
+                        ui.add(Slider::new(&mut ambient.intensity, 0.0..=1.0).text("Ambient"));
+                    }
+                    for (i, light) in scene.lights.directional.iter_mut().enumerate() {
+                        let mut intensity = light.intensity();
+                        ui.add(
+                            Slider::new(&mut intensity, 0.0..=5.0)
+                                .text(format!("Directional {}", i)),
+                        );
+                        light.set_intensity(intensity);
+                    }
+                    save = ui.button("Save scene").clicked();
+                    load = ui.button("Load scene").clicked();
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+
+            if save {
+                // The objects are not changed, so only the lights and camera need to be updated before saving
+                description.ambient_light = scene.lights.ambient.as_ref().map(|l| l.into());
+                description.directional_lights =
+                    scene.lights.directional.iter().map(|l| l.into()).collect();
+                description.spot_lights = scene.lights.spot.iter().map(|l| l.into()).collect();
+                description.point_lights = scene.lights.point.iter().map(|l| l.into()).collect();
+                description.camera = (&scene.camera).into();
+                description.save(SCENE_PATH).unwrap();
+            }
+            if load {
+                let (d, s) = load_scene(&context, viewport);
+                description = d;
+                scene = s;
+                control = OrbitControl::new(*scene.camera.target(), 1.0, 100.0);
+            }
+
+            scene.camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut scene.camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    render_pass(&scene.camera, &scene.models, &scene.lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+fn load_scene(context: &Context, viewport: Viewport) -> (SceneDescription, Scene) {
+    let description = SceneDescription::load(SCENE_PATH).unwrap();
+    let mut loaded = Loaded::new();
+    for path in description.asset_paths(SCENE_PATH) {
+        loaded.insert_bytes(&path, std::fs::read(&path).unwrap());
+    }
+    let scene = description
+        .instantiate(context, SCENE_PATH, &mut loaded, viewport)
+        .unwrap();
+    (description, scene)
+}
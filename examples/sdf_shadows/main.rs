@@ -0,0 +1,242 @@
+use std::rc::Rc;
+use three_d::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum ShadowMode {
+    ShadowMap,
+    Sdf,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "SDF shadows!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(8.0, 7.0, 10.0),
+        vec3(0.0, 0.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 50.0);
+
+    // A small diorama of closed meshes on a thick ground plate, the meshes are also merged into one mesh for computing the signed distance field
+    let transformations = [
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(0.0, -0.25, 0.0))
+                * Mat4::from_nonuniform_scale(5.0, 0.25, 5.0),
+            Color::new_opaque(180, 170, 150),
+        ),
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(-1.5, 1.0, -1.0))
+                * Mat4::from_nonuniform_scale(0.5, 1.0, 0.5),
+            Color::new_opaque(200, 80, 60),
+        ),
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(1.5, 0.25, 1.0))
+                * Mat4::from_angle_y(degrees(30.0))
+                * Mat4::from_nonuniform_scale(1.5, 0.25, 0.5),
+            Color::new_opaque(80, 120, 200),
+        ),
+        (
+            CPUMesh::sphere(16),
+            Mat4::from_translation(vec3(0.5, 0.8, -1.5)) * Mat4::from_scale(0.8),
+            Color::new_opaque(220, 200, 80),
+        ),
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(-1.0, 1.6, 1.5))
+                * Mat4::from_nonuniform_scale(1.0, 0.1, 1.0),
+            Color::new_opaque(90, 170, 90),
+        ),
+    ];
+    let mut models = Vec::new();
+    let mut scene_positions = Vec::new();
+    for (cpu_mesh, transformation, color) in transformations.iter() {
+        let mut model = Model::new_with_material(
+            &context,
+            cpu_mesh,
+            PhysicalMaterial {
+                albedo: *color,
+                roughness: 0.8,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        model.set_transformation(*transformation);
+        models.push(model);
+
+        cpu_mesh.for_each_triangle(|i0, i1, i2| {
+            for i in [i0, i1, i2].iter() {
+                let p = (transformation * cpu_mesh.position(*i).extend(1.0)).truncate();
+                scene_positions.extend_from_slice(&[p.x, p.y, p.z]);
+            }
+        });
+    }
+    let scene_mesh = CPUMesh {
+        positions: scene_positions,
+        ..Default::default()
+    };
+
+    // The signed distance field covers the diorama with a small margin
+    let bounds = AxisAlignedBoundingBox::new_with_positions(&[-5.5, -1.0, -5.5, 5.5, 3.0, 5.5]);
+    let sdf = Rc::new(
+        SignedDistanceField::new(
+            &context,
+            &scene_mesh.compute_sdf((64, 24, 64), bounds),
+            bounds,
+        )
+        .unwrap(),
+    );
+    let mut sdf_shadows = SdfShadows::new(sdf.clone());
+    let mut sdf_ao = SdfAmbientOcclusion::new(sdf);
+
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.5,
+            Color::WHITE,
+            &vec3(-1.0, -1.5, -0.7),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut shadow_mode = ShadowMode::Sdf;
+    let mut ao_enabled = true;
+    let mut shadow_map_size = 1024;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            let mut changed = frame_input.first_frame;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label("Shadows");
+                    changed |= ui
+                        .radio_value(&mut shadow_mode, ShadowMode::ShadowMap, "Shadow map")
+                        .changed();
+                    changed |= ui
+                        .radio_value(&mut shadow_mode, ShadowMode::Sdf, "Signed distance field")
+                        .changed();
+                    match shadow_mode {
+                        ShadowMode::ShadowMap => {
+                            changed |= ui
+                                .add(
+                                    Slider::new(&mut shadow_map_size, 128..=4096)
+                                        .text("Shadow map size"),
+                                )
+                                .changed();
+                        }
+                        ShadowMode::Sdf => {
+                            changed |= ui
+                                .add(
+                                    Slider::new(&mut sdf_shadows.softness, 0.005..=0.3)
+                                        .text("Softness"),
+                                )
+                                .changed();
+                            changed |= ui
+                                .add(Slider::new(&mut sdf_shadows.steps, 4..=128).text("Steps"))
+                                .changed();
+                        }
+                    }
+                    ui.label("Ambient occlusion");
+                    changed |= ui
+                        .checkbox(&mut ao_enabled, "SDF ambient occlusion")
+                        .changed();
+                    changed |= ui
+                        .add(Slider::new(&mut sdf_ao.distance, 0.05..=2.0).text("Distance"))
+                        .changed();
+                    changed |= ui
+                        .add(Slider::new(&mut sdf_ao.strength, 0.0..=2.0).text("Strength"))
+                        .changed();
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            // The shadow map must be regenerated when its size changes, while the SDF shadows only use a few uniforms
+            if changed {
+                let light = &mut lights.directional[0];
+                match shadow_mode {
+                    ShadowMode::ShadowMap => {
+                        light.set_sdf_shadows(None);
+                        light
+                            .generate_shadow_map(12.0, shadow_map_size, shadow_map_size, &models)
+                            .unwrap();
+                    }
+                    ShadowMode::Sdf => {
+                        light.clear_shadow_map();
+                        light.set_sdf_shadows(Some(sdf_shadows.clone()));
+                    }
+                }
+                lights.ambient.as_mut().unwrap().sdf_ao = if ao_enabled {
+                    Some(sdf_ao.clone())
+                } else {
+                    None
+                };
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.7, 0.8, 0.9, 1.0, 1.0),
+                || {
+                    for model in models.iter() {
+                        model.render(&camera, &lights)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,188 @@
+// Embeds three-d in an application where the window, the OpenGL context and the event loop are owned by SDL2.
+// Run with `cargo run --example sdl2 --features sdl2`, which requires the SDL2 library to be installed.
+use sdl2::event::{Event as SdlEvent, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod};
+use three_d::*;
+
+fn main() {
+    let sdl = sdl2::init().unwrap();
+    let video = sdl.video().unwrap();
+    let gl_attr = video.gl_attr();
+    gl_attr.set_context_profile(sdl2::video::GLProfile::Core);
+    gl_attr.set_context_version(3, 3);
+    gl_attr.set_depth_size(24);
+    let window = video
+        .window("Embedded in SDL2!", 1280, 720)
+        .opengl()
+        .resizable()
+        .allow_highdpi()
+        .build()
+        .unwrap();
+    let _gl_context = window.gl_create_context().unwrap();
+
+    // Create a three-d context from the OpenGL context owned by SDL2
+    let context = Context::from_gl_loader(|name| {
+        video.gl_get_proc_address(name) as *const std::os::raw::c_void
+    });
+
+    let (width, height) = window.drawable_size();
+    let mut camera = Camera::new_perspective(
+        &context,
+        Viewport::new_at_origo(width, height),
+        vec3(4.0, 3.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+    let cube = Model::new_with_material(
+        &context,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(100, 130, 200),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // Translates the SDL2 events to the frame input expected by the camera controls and the GUI
+    let mut frame_input_generator = FrameInputGenerator::new();
+    let mut event_pump = sdl.event_pump().unwrap();
+    'running: loop {
+        for event in event_pump.poll_iter() {
+            match event {
+                SdlEvent::Quit { .. } => break 'running,
+                SdlEvent::MouseMotion { x, y, .. } => {
+                    frame_input_generator.cursor_moved((x as f64, y as f64))
+                }
+                SdlEvent::MouseButtonDown { mouse_btn, .. } => {
+                    if let Some(button) = translate_mouse_button(mouse_btn) {
+                        frame_input_generator.mouse_button(button, true);
+                    }
+                }
+                SdlEvent::MouseButtonUp { mouse_btn, .. } => {
+                    if let Some(button) = translate_mouse_button(mouse_btn) {
+                        frame_input_generator.mouse_button(button, false);
+                    }
+                }
+                SdlEvent::MouseWheel { x, y, .. } => {
+                    let line_height = 24.0;
+                    frame_input_generator
+                        .mouse_wheel((x as f64 * line_height, y as f64 * line_height));
+                }
+                SdlEvent::KeyDown {
+                    keycode, keymod, ..
+                } => {
+                    frame_input_generator.modifiers_changed(translate_modifiers(keymod));
+                    if let Some(key) = keycode.and_then(translate_keycode) {
+                        frame_input_generator.key(key, true);
+                    }
+                }
+                SdlEvent::KeyUp {
+                    keycode, keymod, ..
+                } => {
+                    frame_input_generator.modifiers_changed(translate_modifiers(keymod));
+                    if let Some(key) = keycode.and_then(translate_keycode) {
+                        frame_input_generator.key(key, false);
+                    }
+                }
+                SdlEvent::TextInput { text, .. } => frame_input_generator.text(&text),
+                SdlEvent::Window { win_event, .. } => match win_event {
+                    WindowEvent::Enter => frame_input_generator.cursor_entered(),
+                    WindowEvent::Leave => frame_input_generator.cursor_left(),
+                    WindowEvent::FocusLost => frame_input_generator.focus_lost(),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+
+        let (physical_width, physical_height) = window.drawable_size();
+        let device_pixel_ratio = physical_width as f64 / window.size().0 as f64;
+        let mut frame_input =
+            frame_input_generator.generate(physical_width, physical_height, device_pixel_ratio);
+
+        // Another renderer might have changed the OpenGL state since three-d rendered last time
+        context.restore_state_assumptions();
+
+        camera.set_viewport(frame_input.viewport).unwrap();
+        control
+            .handle_events(
+                &mut camera,
+                &mut frame_input.events,
+                frame_input.elapsed_time,
+            )
+            .unwrap();
+        Screen::write(
+            &context,
+            ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+            || cube.render(&camera, &lights),
+        )
+        .unwrap();
+
+        window.gl_swap_window();
+    }
+}
+
+fn translate_mouse_button(button: sdl2::mouse::MouseButton) -> Option<MouseButton> {
+    match button {
+        sdl2::mouse::MouseButton::Left => Some(MouseButton::Left),
+        sdl2::mouse::MouseButton::Middle => Some(MouseButton::Middle),
+        sdl2::mouse::MouseButton::Right => Some(MouseButton::Right),
+        _ => None,
+    }
+}
+
+fn translate_modifiers(keymod: Mod) -> Modifiers {
+    let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+    let gui = keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD);
+    Modifiers {
+        alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+        ctrl,
+        shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        command: if cfg!(target_os = "macos") { gui } else { ctrl },
+    }
+}
+
+fn translate_keycode(keycode: Keycode) -> Option<Key> {
+    Some(match keycode {
+        Keycode::Down => Key::ArrowDown,
+        Keycode::Left => Key::ArrowLeft,
+        Keycode::Right => Key::ArrowRight,
+        Keycode::Up => Key::ArrowUp,
+        Keycode::Escape => Key::Escape,
+        Keycode::Tab => Key::Tab,
+        Keycode::Backspace => Key::Backspace,
+        Keycode::Return => Key::Enter,
+        Keycode::Space => Key::Space,
+        Keycode::Insert => Key::Insert,
+        Keycode::Delete => Key::Delete,
+        Keycode::Home => Key::Home,
+        Keycode::End => Key::End,
+        Keycode::PageUp => Key::PageUp,
+        Keycode::PageDown => Key::PageDown,
+        Keycode::A => Key::A,
+        Keycode::C => Key::C,
+        Keycode::V => Key::V,
+        Keycode::X => Key::X,
+        Keycode::Z => Key::Z,
+        _ => return None,
+    })
+}
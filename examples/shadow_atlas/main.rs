@@ -0,0 +1,175 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Shadow atlas!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 14.0, 18.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    // A ground plane and a grid of cubes which all cast shadows
+    let mut models = Vec::new();
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(180, 180, 180),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(12.0));
+    models.push(ground);
+    for x in -3..=3 {
+        for z in -3..=3 {
+            let mut cube = Model::new_with_material(
+                &context,
+                &CPUMesh::cube(),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(100, 130, 200),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            cube.set_transformation(
+                Mat4::from_translation(vec3(3.0 * x as f32, 0.5, 3.0 * z as f32))
+                    * Mat4::from_scale(0.5),
+            );
+            models.push(cube);
+        }
+    }
+
+    // 16 spot lights on a grid pointing down, all with shadow maps in the same atlas
+    let colors = [Color::RED, Color::GREEN, Color::BLUE, Color::WHITE];
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.1,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    for i in 0..16 {
+        let position = vec3(5.0 * (i % 4) as f32 - 7.5, 6.0, 5.0 * (i / 4) as f32 - 7.5);
+        lights.spot.push(
+            SpotLight::new(
+                &context,
+                1.5,
+                colors[i % 4],
+                &position,
+                &vec3(0.0, -1.0, 0.0),
+                degrees(50.0),
+                0.1,
+                0.01,
+                0.001,
+            )
+            .unwrap(),
+        );
+    }
+    // The lights closest to the center gets larger tiles
+    let tile_sizes = (0..16)
+        .map(|i| {
+            if (i % 4 == 1 || i % 4 == 2) && (i / 4 == 1 || i / 4 == 2) {
+                1024
+            } else {
+                512
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut atlas = ShadowAtlas::new(&context, 4096, &tile_sizes).unwrap();
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut budget = 2;
+    let mut moving = true;
+    let mut time = 0.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut budget, 0..=16).text("Shadow maps per frame"));
+                    ui.checkbox(&mut moving, "Move light");
+                    ui.label(format!(
+                        "{} shadow maps out of date",
+                        atlas.out_of_date_count()
+                    ));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            // Only one light moves, so only its shadow map is rendered again
+            if moving {
+                time += 0.001 * frame_input.elapsed_time;
+                lights.spot[0].set_position(&vec3(
+                    -7.5 + 2.0 * time.cos() as f32,
+                    6.0,
+                    -7.5 + 2.0 * time.sin() as f32,
+                ));
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            atlas
+                .update(&mut lights.spot, &models, &camera, budget)
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0),
+                || {
+                    for model in models.iter() {
+                        model.render(&camera, &lights)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
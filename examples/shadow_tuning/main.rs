@@ -0,0 +1,268 @@
+use three_d::*;
+
+// Tuning of the shadow biases of a directional light and a spot light, where the shadow map of the chosen light is shown in the corner
+// and the frustum covered by the shadow map is shown using lines.
+// A too small depth bias results in shadow acne, ie. stripes on the lit surfaces, while a too large depth or normal offset bias
+// results in peter-panning, ie. the shadows are detached from the objects casting them.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Shadow tuning!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(8.0, 6.0, 10.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 50.0);
+
+    let material = PhysicalMaterial {
+        albedo: Color::new_opaque(200, 200, 200),
+        roughness: 0.7,
+        ..Default::default()
+    };
+    let mut models = Vec::new();
+    let mut ground =
+        Model::new_with_material(&context, &CPUMesh::square(), material.clone()).unwrap();
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(6.0));
+    models.push(ground);
+    let mut cube = Model::new_with_material(&context, &CPUMesh::cube(), material.clone()).unwrap();
+    cube.set_transformation(Mat4::from_translation(vec3(-2.0, 1.0, 0.0)));
+    models.push(cube);
+    let mut sphere =
+        Model::new_with_material(&context, &CPUMesh::sphere(32), material.clone()).unwrap();
+    sphere.set_transformation(Mat4::from_translation(vec3(2.0, 1.0, 1.0)));
+    models.push(sphere);
+    // A thin wall which touches the ground, where peter-panning is easy to spot
+    let mut wall = Model::new_with_material(&context, &CPUMesh::cube(), material).unwrap();
+    wall.set_transformation(
+        Mat4::from_translation(vec3(0.0, 0.75, -2.5))
+            * Mat4::from_nonuniform_scale(2.5, 0.75, 0.05),
+    );
+    models.push(wall);
+
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.2,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::WHITE,
+            &vec3(-1.0, -0.6, -0.8),
+        )
+        .unwrap()],
+        spot: vec![SpotLight::new(
+            &context,
+            2.0,
+            Color::new_opaque(255, 220, 180),
+            &vec3(3.0, 6.0, 4.0),
+            &vec3(-3.0, -6.0, -4.0),
+            degrees(30.0),
+            0.1,
+            0.001,
+            0.0001,
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // The frustum covered by the shadow map of the chosen light, drawn as thin cylinders along its twelve edges
+    let mut frustum = InstancedModel::new_with_material(
+        &context,
+        &[],
+        &CPUMesh::cylinder(8),
+        ColorMaterial {
+            color: Color::new_opaque(255, 50, 50),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut debug_quad = DebugTextureQuad::new(&context).unwrap();
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let resolutions = [128u32, 256, 512, 1024, 2048];
+    let mut resolution = 2;
+    let mut spot = false;
+    let mut show_shadow_map = true;
+    let mut linearize_depth = true;
+    let mut show_frustum = true;
+    let mut value_range = (0.0, 1.0);
+    let mut generate = true;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    if ui
+                        .radio_value(&mut spot, false, "Directional light")
+                        .clicked()
+                        | ui.radio_value(&mut spot, true, "Spot light").clicked()
+                    {
+                        value_range = if spot && linearize_depth {
+                            lights.spot[0].shadow_depth_range()
+                        } else {
+                            (0.0, 1.0)
+                        };
+                    }
+                    let (mut depth_bias, mut normal_offset_bias) = if spot {
+                        lights.spot[0].shadow_bias()
+                    } else {
+                        lights.directional[0].shadow_bias()
+                    };
+                    ui.add(Slider::new(&mut depth_bias, 0.0..=0.02).text("Depth bias"));
+                    ui.add(
+                        Slider::new(&mut normal_offset_bias, 0.0..=0.2).text("Normal offset bias"),
+                    );
+                    if spot {
+                        lights.spot[0].set_shadow_bias(depth_bias, normal_offset_bias);
+                    } else {
+                        lights.directional[0].set_shadow_bias(depth_bias, normal_offset_bias);
+                    }
+                    ComboBox::from_label("Resolution")
+                        .selected_text(format!("{}", resolutions[resolution]))
+                        .show_ui(ui, |ui| {
+                            for (i, r) in resolutions.iter().enumerate() {
+                                generate |= ui
+                                    .selectable_value(&mut resolution, i, format!("{}", r))
+                                    .clicked();
+                            }
+                        });
+                    ui.checkbox(&mut show_frustum, "Show frustum");
+                    ui.checkbox(&mut show_shadow_map, "Show shadow map");
+                    if spot
+                        && ui
+                            .checkbox(&mut linearize_depth, "Linearize depth")
+                            .clicked()
+                    {
+                        value_range = if linearize_depth {
+                            lights.spot[0].shadow_depth_range()
+                        } else {
+                            (0.0, 1.0)
+                        };
+                    }
+                    let max = if spot && linearize_depth {
+                        lights.spot[0].shadow_depth_range().1
+                    } else {
+                        1.0
+                    };
+                    ui.add(Slider::new(&mut value_range.0, 0.0..=max).text("Black"));
+                    ui.add(Slider::new(&mut value_range.1, 0.0..=max).text("White"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            if generate {
+                generate = false;
+                let size = resolutions[resolution];
+                lights.directional[0]
+                    .generate_shadow_map(14.0, size, size, &models)
+                    .unwrap();
+                lights.spot[0].generate_shadow_map(size, &models).unwrap();
+                if spot && linearize_depth {
+                    value_range = lights.spot[0].shadow_depth_range();
+                }
+            }
+
+            let corners = if spot {
+                lights.spot[0].shadow_frustum_corners()
+            } else {
+                lights.directional[0].shadow_frustum_corners()
+            };
+            frustum.set_instances(&frustum_edges(&corners, 0.03));
+            debug_quad.value_range = value_range;
+            debug_quad.depth_range = if spot && linearize_depth {
+                Some(lights.spot[0].shadow_depth_range())
+            } else {
+                None
+            };
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &models, &lights)?;
+                    if show_frustum {
+                        frustum.render(&camera, &Lights::default())?;
+                    }
+                    let shadow_map = if spot {
+                        lights.spot[0].shadow_map()
+                    } else {
+                        lights.directional[0].shadow_map()
+                    };
+                    if let (true, Some(shadow_map)) = (show_shadow_map, shadow_map) {
+                        debug_quad.render_depth(viewport, shadow_map)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// Cylinders along the edges between the corners whose indices differ in one bit, which are the twelve edges of the frustum
+fn frustum_edges(corners: &[Vec3; 8], thickness: f32) -> Vec<ModelInstance> {
+    let mut instances = Vec::new();
+    for i in 0..8 {
+        for bit in [1, 2, 4].iter() {
+            if i & bit == 0 {
+                let start = corners[i];
+                let edge = corners[i | bit] - start;
+                let rotation = Quat::from_arc(vec3(1.0, 0.0, 0.0), edge.normalize(), None);
+                instances.push(ModelInstance {
+                    geometry_transform: Mat4::from_translation(start)
+                        * Mat4::from(rotation)
+                        * Mat4::from_nonuniform_scale(edge.magnitude(), thickness, thickness),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    instances
+}
@@ -11,46 +11,33 @@ fn main() {
     .unwrap();
     let context = window.gl().unwrap();
 
-    let mut camera = Camera::new_perspective(
-        &context,
-        window.viewport().unwrap(),
-        vec3(5.0, 2.0, 2.5),
-        vec3(0.0, 0.0, -0.5),
-        vec3(0.0, 1.0, 0.0),
-        degrees(45.0),
-        0.1,
-        1000.0,
-    )
-    .unwrap();
+    let mut camera = Camera::perspective(&context, window.viewport().unwrap())
+        .position(vec3(5.0, 2.0, 2.5))
+        .target(vec3(0.0, 0.0, -0.5))
+        .fov(degrees(45.0))
+        .near(0.1)
+        .far(1000.0)
+        .build()
+        .unwrap();
     let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
 
     let mut sphere = Model::new_with_material(
         &context,
         &CPUMesh::sphere(16),
-        PhysicalMaterial {
-            albedo: Color {
-                r: 255,
-                g: 0,
-                b: 0,
-                a: 200,
-            },
-            ..Default::default()
-        },
+        PhysicalMaterial::builder()
+            .albedo(Color::new(255, 0, 0, 200))
+            .build(&context)
+            .unwrap(),
     )
     .unwrap();
     sphere.set_transformation(Mat4::from_translation(vec3(0.0, 1.3, 0.0)) * Mat4::from_scale(0.2));
     let mut cylinder = Model::new_with_material(
         &context,
         &CPUMesh::cylinder(16),
-        PhysicalMaterial {
-            albedo: Color {
-                r: 0,
-                g: 255,
-                b: 0,
-                a: 200,
-            },
-            ..Default::default()
-        },
+        PhysicalMaterial::builder()
+            .albedo(Color::new(0, 255, 0, 200))
+            .build(&context)
+            .unwrap(),
     )
     .unwrap();
     cylinder
@@ -58,15 +45,10 @@ fn main() {
     let mut cube = Model::new_with_material(
         &context,
         &CPUMesh::cube(),
-        PhysicalMaterial {
-            albedo: Color {
-                r: 0,
-                g: 0,
-                b: 255,
-                a: 100,
-            },
-            ..Default::default()
-        },
+        PhysicalMaterial::builder()
+            .albedo(Color::new(0, 0, 255, 100))
+            .build(&context)
+            .unwrap(),
     )
     .unwrap();
     cube.set_transformation(Mat4::from_translation(vec3(0.0, 0.0, 1.3)) * Mat4::from_scale(0.2));
@@ -98,12 +80,25 @@ fn main() {
         },
     )
     .unwrap();
+    let bounding_sphere_cube = BoundingSphereObject::new_with_material(
+        &context,
+        cube.bounding_sphere(),
+        ColorMaterial {
+            color: Color::new(0, 0, 0, 50),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     window
         .render_loop(move |mut frame_input: FrameInput| {
             camera.set_viewport(frame_input.viewport).unwrap();
             control
-                .handle_events(&mut camera, &mut frame_input.events)
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
                 .unwrap();
 
             Screen::write(
@@ -112,18 +107,12 @@ fn main() {
                 || {
                     let lights = Lights {
                         directional: vec![
-                            DirectionalLight::new(
-                                &context,
-                                1.0,
-                                Color::WHITE,
-                                &vec3(0.0, -0.5, -0.5),
-                            )?,
-                            DirectionalLight::new(
-                                &context,
-                                1.0,
-                                Color::WHITE,
-                                &vec3(0.0, 0.5, 0.5),
-                            )?,
+                            DirectionalLight::builder(&context)
+                                .direction(vec3(0.0, -0.5, -0.5))
+                                .build()?,
+                            DirectionalLight::builder(&context)
+                                .direction(vec3(0.0, 0.5, 0.5))
+                                .build()?,
                         ],
                         ..Default::default()
                     };
@@ -138,6 +127,7 @@ fn main() {
                             &bounding_box_sphere,
                             &bounding_box_cube,
                             &bounding_box_cylinder,
+                            &bounding_sphere_cube,
                         ],
                         &lights,
                     )?;
@@ -58,10 +58,7 @@ fn main() {
                         modifiers,
                         ..
                     } => {
-                        let pos = vec2(
-                            (frame_input.device_pixel_ratio * position.0) as f32,
-                            (frame_input.device_pixel_ratio * position.1) as f32,
-                        );
+                        let pos = vec2(position.physical.0, position.physical.1);
                         if *button == MouseButton::Left && !modifiers.ctrl {
                             rectangle.set_center(pos);
                         }
@@ -0,0 +1,270 @@
+use rand::prelude::*;
+use std::rc::Rc;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Smoke!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(12.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(vec3(0.0, 1.0, 0.0), 1.0, 50.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(100, 120, 80),
+            roughness: 1.0,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_scale(30.0) * Mat4::from_angle_x(degrees(-90.0)));
+    let mut pillar = Model::new_with_material(
+        &context,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(150, 140, 130),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    pillar.set_transformation(
+        Mat4::from_translation(vec3(1.5, 2.0, -1.0)) * Mat4::from_nonuniform_scale(0.4, 2.0, 0.4),
+    );
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    // A soft round puff of smoke
+    let size = 64;
+    let mut puff = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let u = 2.0 * (x as f32 + 0.5) / size as f32 - 1.0;
+            let v = 2.0 * (y as f32 + 0.5) / size as f32 - 1.0;
+            let falloff = (1.0 - (u * u + v * v)).max(0.0);
+            puff.extend_from_slice(&[255, 255, 255, (255.0 * falloff * falloff) as u8]);
+        }
+    }
+    let smoke_material = ColorMaterial {
+        color: Color::new(200, 200, 210, 120),
+        texture: Some(Rc::new(
+            Texture2D::new(
+                &context,
+                &CPUTexture {
+                    data: puff,
+                    width: size as u32,
+                    height: size as u32,
+                    wrap_s: Wrapping::ClampToEdge,
+                    wrap_t: Wrapping::ClampToEdge,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        )),
+        ..Default::default()
+    };
+
+    // A plume of smoke rising from the ground, the particles drift slowly back and forth
+    let mut rng = rand::thread_rng();
+    let mut square = CPUMesh::square();
+    square.transform(&Mat4::from_scale(1.5));
+    let mut smoke = Particles::new(&context, &square).unwrap();
+    smoke.acceleration = vec3(0.0, 0.0, 0.0);
+    smoke.soft_particles = Some(SoftParticles::default());
+    smoke.update(
+        &(0..300)
+            .map(|_| {
+                let height = 6.0 * rng.gen::<f32>();
+                let radius = 0.5 + 0.4 * height;
+                ParticleData {
+                    start_position: vec3(
+                        radius * (rng.gen::<f32>() - 0.5),
+                        height,
+                        radius * (rng.gen::<f32>() - 0.5),
+                    ),
+                    start_velocity: 0.1
+                        * vec3(
+                            rng.gen::<f32>() - 0.5,
+                            rng.gen::<f32>(),
+                            rng.gen::<f32>() - 0.5,
+                        ),
+                }
+            })
+            .collect::<Vec<_>>(),
+    );
+    let mut soft_particles = SoftParticles::default();
+    let mut soft_particles_enabled = true;
+    let mut fly_through = true;
+    let mut time = 0.0;
+
+    let mut color_texture: Option<Texture2D<u8>> = None;
+    let mut depth_texture: Option<DepthTargetTexture2D> = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut soft_particles_enabled, "Soft particles");
+                    ui.add(
+                        Slider::new(&mut soft_particles.fade_distance, 0.01..=5.0)
+                            .text("Fade distance"),
+                    );
+                    ui.add(
+                        Slider::new(&mut soft_particles.near_fade_distance, 0.01..=5.0)
+                            .text("Near fade distance"),
+                    );
+                    ui.checkbox(&mut fly_through, "Fly through the smoke");
+                });
+            })
+            .unwrap();
+
+            camera.set_viewport(frame_input.viewport).unwrap();
+            time += frame_input.elapsed_time as f32 * 0.001;
+            if fly_through {
+                // Fly along a circle which passes through the plume, looking in the direction of the movement
+                let angle = 0.3 * time;
+                let center = vec3(6.0, 1.0, 0.0);
+                let position = center + 6.0 * vec3(angle.cos(), 0.0, angle.sin());
+                let direction = vec3(-angle.sin(), 0.0, angle.cos());
+                camera
+                    .set_view(position, position + direction, vec3(0.0, 1.0, 0.0))
+                    .unwrap();
+            } else {
+                control
+                    .handle_events(
+                        &mut camera,
+                        &mut frame_input.events,
+                        frame_input.elapsed_time,
+                    )
+                    .unwrap();
+            }
+            smoke.time = 5.0 * (0.2 * time).sin();
+            smoke.soft_particles = if soft_particles_enabled {
+                Some(soft_particles)
+            } else {
+                None
+            };
+
+            // Render the scene into a color and depth texture, recreated when the window is resized
+            let viewport = frame_input.viewport;
+            if color_texture
+                .as_ref()
+                .map(|t| t.width() != viewport.width || t.height() != viewport.height)
+                .unwrap_or(true)
+            {
+                color_texture = Some(
+                    Texture2D::new_empty(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Interpolation::Nearest,
+                        Interpolation::Nearest,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                    .unwrap(),
+                );
+                depth_texture = Some(
+                    DepthTargetTexture2D::new(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                    .unwrap(),
+                );
+            }
+            let color_texture = color_texture.as_mut().unwrap();
+            let depth_texture = depth_texture.as_mut().unwrap();
+            RenderTarget::new(&context, color_texture, depth_texture)
+                .unwrap()
+                .write(ClearState::color_and_depth(0.6, 0.7, 0.8, 1.0, 1.0), || {
+                    ground.render(&camera, &lights)?;
+                    pillar.render(&camera, &lights)?;
+                    Ok(())
+                })
+                .unwrap();
+
+            if soft_particles_enabled {
+                // The depth texture is sampled when rendering the soft particles, so it cannot be used for depth testing at the same time
+                let scene_depth = &*depth_texture;
+                color_texture
+                    .write(ClearState::none(), || {
+                        smoke.render_with_scene_depth(
+                            &smoke_material,
+                            &camera,
+                            &lights,
+                            Some(scene_depth),
+                        )
+                    })
+                    .unwrap();
+            } else {
+                RenderTarget::new(&context, color_texture, depth_texture)
+                    .unwrap()
+                    .write(ClearState::none(), || {
+                        smoke.render_with_material(&smoke_material, &camera, &lights)
+                    })
+                    .unwrap();
+            }
+
+            Screen::copy_from(
+                &context,
+                Some(&*color_texture),
+                None,
+                viewport,
+                WriteMask::default(),
+            )
+            .unwrap();
+            Screen::write(&context, ClearState::none(), || gui.render()).unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
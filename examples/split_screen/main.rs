@@ -0,0 +1,193 @@
+use three_d::*;
+
+// Two players looking at the same scene in their own half of the window, each with their own orbit control.
+// The shadow maps are generated once per frame and shared by both views, which the counters in the top panel show.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Split screen!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut cameras = [
+        Camera::new_perspective(
+            &context,
+            window.viewport().unwrap(),
+            vec3(-10.0, 8.0, 10.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            100.0,
+        )
+        .unwrap(),
+        Camera::new_perspective(
+            &context,
+            window.viewport().unwrap(),
+            vec3(10.0, 8.0, -10.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            100.0,
+        )
+        .unwrap(),
+    ];
+    let mut controls = [
+        OrbitControl::new(*cameras[0].target(), 1.0, 100.0),
+        OrbitControl::new(*cameras[1].target(), 1.0, 100.0),
+    ];
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // A ground plane and a ring of pillars which all cast shadows
+    let mut models = Vec::new();
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(180, 180, 180),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(10.0));
+    models.push(ground);
+    for i in 0..8 {
+        let angle = degrees(45.0 * i as f32);
+        let mut pillar = Model::new_with_material(
+            &context,
+            &CPUMesh::cube(),
+            PhysicalMaterial {
+                albedo: Color::new_opaque(200, 120, 80),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pillar.set_transformation(
+            Mat4::from_angle_y(angle)
+                * Mat4::from_translation(vec3(5.0, 1.5, 0.0))
+                * Mat4::from_nonuniform_scale(0.5, 1.5, 0.5),
+        );
+        models.push(pillar);
+    }
+
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -0.5),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut pipeline = SplitScreenPipeline::new(&context).unwrap();
+    pipeline.shadow_texture_size = 2048;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            pipeline.new_frame();
+
+            let mut panel_height = 0;
+            let frame = pipeline.frame();
+            let shadow_map_generations = pipeline.shadow_map_generations();
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                TopBottomPanel::top("top_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label(format!(
+                        "Frames: {} Shadow map generations: {}",
+                        frame, shadow_map_generations
+                    ));
+                });
+                panel_height = (gui_context.used_size().y * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            // The left half of the window below the panel is the view of the first player and the right half is the view of the second player
+            let height =
+                frame_input.viewport.height - panel_height.min(frame_input.viewport.height);
+            let half_width = frame_input.viewport.width / 2;
+            let viewports = [
+                Viewport {
+                    x: 0,
+                    y: 0,
+                    width: half_width,
+                    height,
+                },
+                Viewport {
+                    x: half_width as i32,
+                    y: 0,
+                    width: frame_input.viewport.width - half_width,
+                    height,
+                },
+            ];
+
+            // Each player only controls their own camera with the mouse events inside their viewport
+            for ((camera, control), viewport) in cameras
+                .iter_mut()
+                .zip(controls.iter_mut())
+                .zip(viewports.iter())
+            {
+                camera.set_viewport(*viewport).unwrap();
+                let mut events = frame_input
+                    .events
+                    .iter()
+                    .filter(|event| is_inside(event, viewport))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                control
+                    .handle_events(camera, &mut events, frame_input.elapsed_time)
+                    .unwrap();
+            }
+
+            pipeline
+                .render_pass(
+                    &[&cameras[0], &cameras[1]],
+                    ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                    &models,
+                    &mut lights,
+                )
+                .unwrap();
+            // The shadow maps are generated exactly once per frame no matter the number of cameras
+            debug_assert_eq!(pipeline.shadow_map_generations(), pipeline.frame());
+
+            // The GUI covers the whole window, so it is rendered once after both views
+            Screen::write(&context, ClearState::none(), || gui.render()).unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// Whether the event is a mouse event with a position inside the given viewport
+fn is_inside(event: &Event, viewport: &Viewport) -> bool {
+    let position = match event {
+        Event::MousePress { position, .. }
+        | Event::MouseRelease { position, .. }
+        | Event::MouseMotion { position, .. }
+        | Event::MouseWheel { position, .. } => position,
+        _ => return false,
+    };
+    let x = position.physical.0 as i32;
+    x >= viewport.x && x < viewport.x + viewport.width as i32
+}
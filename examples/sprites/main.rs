@@ -0,0 +1,260 @@
+use three_d::*;
+
+// A 2D tile map with an animated character drawn using Sprite2D on top of a 3D scene, with the GUI on top of both.
+// The tiles and the animation frames are stored in small texture atlases which are generated in code.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Sprites!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let tiles = Texture2D::new(&context, &tile_atlas()).unwrap();
+    let character = Texture2D::new(&context, &character_atlas()).unwrap();
+    let mut sprites = Sprite2D::new(&context).unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut cube = Model::new_with_material(
+        &context,
+        &CPUMesh::cube(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(220, 180, 60),
+            metallic: 0.8,
+            roughness: 0.3,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut logical_pixels = true;
+    let mut tile_size = 32.0;
+    let mut linear = false;
+    let mut spin = true;
+    let mut tint = false;
+    let mut time = 0.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut logical_pixels, "Logical pixels");
+                    ui.add(Slider::new(&mut tile_size, 8.0..=64.0).text("Tile size"));
+                    ui.checkbox(&mut linear, "Linear filtering");
+                    ui.checkbox(&mut spin, "Spin the character");
+                    ui.checkbox(&mut tint, "Tint the character");
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            time += 0.001 * frame_input.elapsed_time as f32;
+            cube.set_transformation(
+                Mat4::from_angle_y(radians(time))
+                    * Mat4::from_angle_x(radians(0.7 * time))
+                    * Mat4::from_scale(0.5),
+            );
+            sprites.device_pixel_ratio = if logical_pixels {
+                frame_input.device_pixel_ratio
+            } else {
+                1.0
+            };
+            let interpolation = if linear {
+                Interpolation::Linear
+            } else {
+                Interpolation::Nearest
+            };
+
+            // The whole tile map is drawn in a single instanced draw call
+            let scale = sprites.device_pixel_ratio as f32;
+            let columns = (viewport.width as f32 / scale / tile_size).ceil() as u32;
+            let rows = (viewport.height as f32 / scale / tile_size).ceil() as u32;
+            let mut tile_sprites = Vec::new();
+            for row in 0..rows {
+                for column in 0..columns {
+                    let tile = tile_at(column, row);
+                    tile_sprites.push((
+                        PixelRect::new(
+                            column as f32 * tile_size,
+                            row as f32 * tile_size,
+                            tile_size,
+                            tile_size,
+                        ),
+                        SpriteOptions {
+                            source: Some(PixelRect::new(16.0 * tile as f32, 0.0, 16.0, 16.0)),
+                            blend: Blend::Disabled,
+                            interpolation,
+                            ..Default::default()
+                        },
+                    ));
+                }
+            }
+
+            // The character walks back and forth, turns around at the ends and plays a four frame animation
+            let width = viewport.width as f32 / scale - 2.0 * tile_size;
+            let phase = (3.0 * tile_size * time / width.max(1.0)) % 2.0;
+            let walking_right = phase < 1.0;
+            let x = if walking_right { phase } else { 2.0 - phase } * width;
+            let frame = (time * 8.0) as u32 % 4;
+            let character_options = SpriteOptions {
+                source: Some(PixelRect::new(16.0 * frame as f32, 0.0, 16.0, 16.0)),
+                rotation: radians(if spin { 0.5 * time.sin() } else { 0.0 }),
+                pivot: vec2(0.5, 1.0),
+                tint: if tint {
+                    Color::new_opaque(255, 120, 120)
+                } else {
+                    Color::WHITE
+                },
+                flip_x: !walking_right,
+                interpolation,
+                ..Default::default()
+            };
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+                || {
+                    sprites.draw_batch(viewport, &tiles, &tile_sprites)?;
+                    cube.render(&camera, &lights)?;
+                    sprites.draw(
+                        viewport,
+                        &character,
+                        PixelRect::new(
+                            x,
+                            viewport.height as f32 / scale - 3.0 * tile_size,
+                            2.0 * tile_size,
+                            2.0 * tile_size,
+                        ),
+                        &character_options,
+                    )?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// The index of the tile in the tile atlas at the given position of the map, ie. grass with a lake and a path of sand and some stones
+fn tile_at(column: u32, row: u32) -> u32 {
+    let (dx, dy) = (column as f32 - 12.0, row as f32 - 6.0);
+    if dx * dx + 2.0 * dy * dy < 30.0 {
+        1
+    } else if row == 12 || column == 3 {
+        2
+    } else if (column * 7 + row * 13) % 17 == 0 {
+        3
+    } else {
+        0
+    }
+}
+
+// Four tiles of 16x16 texels next to each other, ie. grass, water, sand and stone
+fn tile_atlas() -> CPUTexture<u8> {
+    let colors = [
+        ([70, 140, 50], [90, 170, 60]),
+        ([40, 90, 190], [70, 130, 220]),
+        ([200, 180, 120], [220, 200, 140]),
+        ([110, 110, 110], [150, 150, 150]),
+    ];
+    let mut data = Vec::new();
+    for y in 0..16u32 {
+        for (tile, (base, detail)) in colors.iter().enumerate() {
+            for x in 0..16u32 {
+                let pattern = match tile {
+                    1 => (x + 2 * (y / 4)) % 8 < 2 && y % 4 == 0,
+                    3 => (x as i32 - 8).pow(2) + (y as i32 - 8).pow(2) < 30,
+                    _ => (x * 5 + y * 3 + x * y) % 11 == 0,
+                };
+                let color = if pattern { detail } else { base };
+                data.extend_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+    }
+    CPUTexture {
+        data,
+        width: 64,
+        height: 16,
+        ..Default::default()
+    }
+}
+
+// Four animation frames of 16x16 texels next to each other of a slime squashing and stretching, with a transparent background
+fn character_atlas() -> CPUTexture<u8> {
+    let mut data = Vec::new();
+    for y in 0..16 {
+        for frame in 0..4 {
+            let squash = [0.0, 1.5, 3.0, 1.5][frame];
+            for x in 0..16 {
+                let (rx, ry) = (6.0 + squash, 7.0 - squash);
+                let dx = (x as f32 + 0.5 - 8.0) / rx;
+                let dy = (y as f32 + 0.5 - (16.0 - ry)) / ry;
+                let color = if dx * dx + dy * dy > 1.0 {
+                    [0, 0, 0, 0]
+                } else if dy < -0.2 && dy > -0.6 && (dx.abs() - 0.35).abs() < 0.1 {
+                    [20, 20, 20, 255]
+                } else {
+                    [120, 200, 90, 255]
+                };
+                data.extend_from_slice(&color);
+            }
+        }
+    }
+    CPUTexture {
+        data,
+        width: 64,
+        height: 16,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,148 @@
+use three_d::*;
+
+// A camera flying through a large field of pillars, where the shadow map of the sun is fitted to the part of the scene seen by the camera each frame.
+// The fitted shadow map keeps its size and moves in steps of whole texels, so the shadow edges stay still while the camera moves,
+// and it is much sharper than a shadow map covering the whole scene with the same resolution.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Stable shadows!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 4.0, 30.0),
+        vec3(0.0, 2.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        200.0,
+    )
+    .unwrap();
+
+    let material = PhysicalMaterial {
+        albedo: Color::new_opaque(200, 200, 200),
+        roughness: 0.8,
+        ..Default::default()
+    };
+    let mut models = Vec::new();
+    let mut ground =
+        Model::new_with_material(&context, &CPUMesh::square(), material.clone()).unwrap();
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(100.0));
+    models.push(ground);
+    for i in -8..=8 {
+        for j in -8..=8 {
+            let height = 1.0 + ((i * 7 + j * 13) as f32).sin().abs() * 3.0;
+            let mut pillar =
+                Model::new_with_material(&context, &CPUMesh::cube(), material.clone()).unwrap();
+            pillar.set_transformation(
+                Mat4::from_translation(vec3(i as f32 * 10.0, height, j as f32 * 10.0))
+                    * Mat4::from_nonuniform_scale(0.5, height, 0.5),
+            );
+            models.push(pillar);
+        }
+    }
+
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::new_opaque(255, 240, 220),
+            &vec3(-1.0, -1.5, -0.5),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut fitted = true;
+    let mut max_shadow_distance = 40.0;
+    let mut moving = true;
+    let mut time = 0.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut moving, "Move camera");
+                    ui.radio_value(&mut fitted, true, "Fitted to the camera");
+                    ui.radio_value(&mut fitted, false, "Covering the whole scene");
+                    ui.add(
+                        Slider::new(&mut max_shadow_distance, 10.0..=200.0)
+                            .text("Max shadow distance"),
+                    );
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            // The camera flies along a circle between the pillars while looking around
+            if moving {
+                time += 0.001 * frame_input.elapsed_time as f32;
+            }
+            let angle = 0.1 * time;
+            let position = vec3(35.0 * angle.cos(), 4.0 + angle.sin(), 35.0 * angle.sin());
+            let look_angle = angle + 1.8 + 0.3 * (0.5 * time).sin();
+            let target = position + vec3(look_angle.cos(), -0.2, look_angle.sin());
+            camera
+                .set_view(position, target, vec3(0.0, 1.0, 0.0))
+                .unwrap();
+
+            let light = &mut lights.directional[0];
+            if fitted {
+                light.set_max_shadow_distance(Some(max_shadow_distance));
+                light
+                    .generate_shadow_map_fitted(&camera, &models, 2048)
+                    .unwrap();
+            } else {
+                light
+                    .generate_shadow_map(250.0, 2048, 2048, &models)
+                    .unwrap();
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.6, 0.7, 0.9, 1.0, 1.0),
+                || {
+                    render_pass(&camera, &models, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -41,71 +41,73 @@ fn main() {
     );
 
     // Models from http://texturedmesh.isti.cnr.it/
-    let scene = Loading::new(
-        &context,
-        &[
+    // Build with THREE_D_ASSET_BUNDLE set to the path of an asset bundle containing these files, created using the bundle example,
+    // to load all of the files in a single request
+    let paths = if let Some(bundle) = option_env!("THREE_D_ASSET_BUNDLE") {
+        vec![bundle]
+    } else {
+        vec![
             "examples/assets/COLOMBE.obj",
             "examples/assets/COLOMBE.mtl",
             "examples/assets/COLOMBE.png",
             "examples/assets/pfboy.obj",
             "examples/assets/pfboy.mtl",
             "examples/assets/pfboy.png",
-        ],
-        move |context, mut loaded| {
-            let (statue_cpu_meshes, statue_cpu_materials) =
-                loaded.obj("examples/assets/COLOMBE.obj").unwrap();
-            let mut statue_material =
-                PhysicalMaterial::new(&context, &statue_cpu_materials[0]).unwrap();
-            statue_material.opaque_render_states.cull = Cull::Back;
-            let mut statue =
-                Model::new_with_material(&context, &statue_cpu_meshes[0], statue_material).unwrap();
+        ]
+    };
+    let scene = Loading::new(&context, &paths, move |context, mut loaded| {
+        let (statue_cpu_mesh, statue_cpu_material) =
+            loaded.obj("examples/assets/COLOMBE.obj").unwrap().remove(0);
+        let mut statue_material =
+            PhysicalMaterial::new(&context, &statue_cpu_material.unwrap()).unwrap();
+        statue_material.opaque_render_states.cull = Cull::Back;
+        let mut statue =
+            Model::new_with_material(&context, &statue_cpu_mesh, statue_material).unwrap();
 
-            let mut models = Vec::new();
-            let scale = Mat4::from_scale(10.0);
-            for i in 0..8 {
-                let angle = i as f32 * 2.0 * std::f32::consts::PI / 8.0;
-                let rotation = Mat4::from_angle_y(radians(0.8 * std::f32::consts::PI - angle));
-                let dist = 300.0;
-                let translation = Mat4::from_translation(vec3(
-                    angle.cos() * dist,
-                    (1.2 * std::f32::consts::PI - angle).cos() * 21.0 - 33.0,
-                    angle.sin() * dist,
-                ));
-                statue.set_transformation(translation * scale * rotation);
-                models.push(statue.clone());
-            }
+        let mut models = Vec::new();
+        let scale = Mat4::from_scale(10.0);
+        for i in 0..8 {
+            let angle = i as f32 * 2.0 * std::f32::consts::PI / 8.0;
+            let rotation = Mat4::from_angle_y(radians(0.8 * std::f32::consts::PI - angle));
+            let dist = 300.0;
+            let translation = Mat4::from_translation(vec3(
+                angle.cos() * dist,
+                (1.2 * std::f32::consts::PI - angle).cos() * 21.0 - 33.0,
+                angle.sin() * dist,
+            ));
+            statue.set_transformation(translation * scale * rotation);
+            models.push(statue.clone());
+        }
 
-            let (fountain_cpu_meshes, fountain_cpu_materials) =
-                loaded.obj("examples/assets/pfboy.obj").unwrap();
-            let mut fountain_material =
-                PhysicalMaterial::new(&context, &fountain_cpu_materials[0]).unwrap();
-            fountain_material.opaque_render_states.cull = Cull::Back;
-            let mut fountain =
-                Model::new_with_material(&context, &fountain_cpu_meshes[0], fountain_material)
-                    .unwrap();
-            fountain.set_transformation(Mat4::from_angle_x(degrees(-90.0)));
-            models.push(fountain);
+        let (fountain_cpu_mesh, fountain_cpu_material) =
+            loaded.obj("examples/assets/pfboy.obj").unwrap().remove(0);
+        let mut fountain_material =
+            PhysicalMaterial::new(&context, &fountain_cpu_material.unwrap()).unwrap();
+        fountain_material.opaque_render_states.cull = Cull::Back;
+        let mut fountain =
+            Model::new_with_material(&context, &fountain_cpu_mesh, fountain_material).unwrap();
+        fountain.set_transformation(Mat4::from_angle_x(degrees(-90.0)));
+        models.push(fountain);
 
-            let mut lights = Lights {
-                ambient: Some(AmbientLight {
-                    intensity: 0.4,
-                    ..Default::default()
-                }),
-                directional: vec![DirectionalLight::new(
-                    &context,
-                    10.0,
-                    Color::new_opaque(204, 178, 127),
-                    &vec3(0.0, -1.0, -1.0),
-                )
-                .unwrap()],
+        let mut lights = Lights {
+            ambient: Some(AmbientLight {
+                intensity: 0.4,
                 ..Default::default()
-            };
-            lights.directional[0]
-                .generate_shadow_map(1000.0, 1024, 1024, &models)
-                .unwrap();
-            Ok((models, lights))
-        },
-    );
+            }),
+            directional: vec![DirectionalLight::new(
+                &context,
+                10.0,
+                Color::new_opaque(204, 178, 127),
+                &vec3(0.0, -1.0, -1.0),
+            )
+            .unwrap()],
+            ..Default::default()
+        };
+        lights.directional[0]
+            .generate_shadow_map(1000.0, 1024, 1024, &models)
+            .unwrap();
+        Ok((models, lights))
+    });
 
     // main loop
     let mut is_primary_camera = true;
@@ -119,8 +121,21 @@ fn main() {
             }
             redraw |= primary_camera.set_viewport(frame_input.viewport).unwrap();
             redraw |= secondary_camera.set_viewport(frame_input.viewport).unwrap();
+            // Rotate around and zoom towards the point on the statues under the cursor
             redraw |= control
-                .handle_events(&mut primary_camera, &mut frame_input.events)
+                .handle_events_with_picking(
+                    &mut primary_camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                    frame_input.viewport.height,
+                    |camera, pixel| {
+                        if let Some(Ok((ref models, _))) = *scene.borrow() {
+                            pick(&context, camera, pixel, models)
+                        } else {
+                            Ok(None)
+                        }
+                    },
+                )
                 .unwrap();
 
             for event in frame_input.events.iter() {
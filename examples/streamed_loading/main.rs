@@ -0,0 +1,222 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use three_d::*;
+
+// Streams a point cloud with four million points from a .ply file or a point chunks file and shows the points parsed so far while the rest is loading.
+// The files are generated the first time the example runs.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Streamed loading!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 2.0, 6.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 50.0);
+
+    let ply_path = "streamed_torus_knot.ply";
+    let point_chunks_path = "streamed_torus_knot.3dpc";
+    if !std::path::Path::new(ply_path).exists() || !std::path::Path::new(point_chunks_path).exists()
+    {
+        let bytes = torus_knot_ply(4_000_000);
+        Saver::save_file(ply_path, &bytes).unwrap();
+        let mut loaded = Loaded::new();
+        loaded.insert_bytes(ply_path, bytes);
+        Saver::save_point_chunks(point_chunks_path, &loaded.ply(ply_path).unwrap(), 100_000)
+            .unwrap();
+    }
+
+    // Each part of the point cloud is uploaded as soon as it is parsed
+    let point_clouds = Rc::new(RefCell::new(Vec::new()));
+    let mut ply_loading: Option<StreamedLoading<PlyStreamParser>> = None;
+    let mut point_chunks_loading: Option<StreamedLoading<PointChunksParser>> = None;
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut use_ply = true;
+    let mut status = String::new();
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            // Only parse for a few milliseconds each frame to keep the frame rate up
+            let time_budget = std::time::Duration::from_millis(8);
+            let progress = if let Some(ref mut loading) = ply_loading {
+                Some((loading.progress(time_budget), loading.is_done()))
+            } else if let Some(ref mut loading) = point_chunks_loading {
+                Some((loading.progress(time_budget), loading.is_done()))
+            } else {
+                None
+            };
+            if let Some((progress, true)) = progress {
+                let result = if let Some(mut loading) = ply_loading.take() {
+                    loading.take_result().unwrap()
+                } else {
+                    point_chunks_loading.take().unwrap().take_result().unwrap()
+                };
+                status = match result {
+                    Ok(cpu_mesh) => format!(
+                        "Loaded {} points from {:.1} MB",
+                        cpu_mesh.positions.len() / 3,
+                        progress.loaded_bytes as f64 / 1_000_000.0
+                    ),
+                    Err(e) => format!("{}", e),
+                };
+            }
+
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut use_ply, true, ".ply");
+                    ui.radio_value(&mut use_ply, false, "Point chunks");
+                    if let Some((progress, false)) = progress {
+                        ui.label(format!(
+                            "Loaded {:.1} MB",
+                            progress.loaded_bytes as f64 / 1_000_000.0
+                        ));
+                        if let Some(fraction) = progress.fraction() {
+                            ui.label(format!("{:.0} %", 100.0 * fraction));
+                        }
+                        if ui.button("Cancel").clicked() {
+                            // The points parsed so far are still shown
+                            if let Some(ref mut loading) = ply_loading {
+                                loading.cancel();
+                            }
+                            if let Some(ref mut loading) = point_chunks_loading {
+                                loading.cancel();
+                            }
+                        }
+                    } else if ui.button("Load").clicked() {
+                        point_clouds.borrow_mut().clear();
+                        status.clear();
+                        let context = context.clone();
+                        let point_clouds = point_clouds.clone();
+                        let upload = move |cpu_mesh: Option<CPUMesh>| {
+                            if let Some(cpu_mesh) = cpu_mesh {
+                                point_clouds.borrow_mut().push(
+                                    PointCloud::new_with_cpu_mesh(&context, &cpu_mesh, 2.0)
+                                        .unwrap(),
+                                );
+                            }
+                        };
+                        if use_ply {
+                            ply_loading = Some(Loader::load_streamed(
+                                ply_path,
+                                PlyStreamParser::new(),
+                                move |parser, _| upload(parser.take_new_vertices()),
+                            ));
+                        } else {
+                            point_chunks_loading = Some(Loader::load_streamed(
+                                point_chunks_path,
+                                PointChunksParser::new(),
+                                move |parser, _| upload(parser.take_new_vertices()),
+                            ));
+                        }
+                    }
+                    ui.label(format!(
+                        "{} points shown",
+                        point_clouds
+                            .borrow()
+                            .iter()
+                            .map(|p| p.count())
+                            .sum::<usize>()
+                    ));
+                    ui.label(&status);
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0),
+                || {
+                    for point_cloud in point_clouds.borrow().iter() {
+                        point_cloud.render(&camera)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// Creates a binary .ply file with the given number of colored points spread around a torus knot
+fn torus_knot_ply(count: usize) -> Vec<u8> {
+    let mut bytes = format!(
+        "ply\nformat binary_little_endian 1.0\nelement vertex {}\n\
+        property float x\nproperty float y\nproperty float z\n\
+        property uchar red\nproperty uchar green\nproperty uchar blue\nend_header\n",
+        count
+    )
+    .into_bytes();
+    // A simple linear congruential generator to avoid a dependency on a random number crate
+    let mut seed = 1u32;
+    let mut random = || {
+        seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        seed as f32 / u32::MAX as f32
+    };
+    for _ in 0..count {
+        let t = random() * 2.0 * std::f32::consts::PI;
+        let r = 1.0 + 0.4 * (3.0 * t).cos();
+        let center = vec3(
+            r * (2.0 * t).cos(),
+            0.4 * (3.0 * t).sin(),
+            r * (2.0 * t).sin(),
+        );
+        let offset = vec3(random() - 0.5, random() - 0.5, random() - 0.5) * 0.3;
+        let p = center + offset;
+        for v in [p.x, p.y, p.z].iter() {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[
+            (255.0 * t / (2.0 * std::f32::consts::PI)) as u8,
+            (255.0 * (offset.magnitude() / 0.26).min(1.0)) as u8,
+            200,
+        ]);
+    }
+    bytes
+}
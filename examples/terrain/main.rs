@@ -0,0 +1,200 @@
+use three_d::*;
+
+// A hilly terrain shaded by a procedural material without any textures, where rock covers the steep slopes and grass or snow covers the flat areas.
+// All of the material parameters can be changed live, and the terrain can be compared to a plain physical material.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Terrain!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(60.0, 40.0, 60.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 10.0, 400.0);
+
+    let terrain_mesh = terrain_mesh(200, 100.0);
+    let mut procedural_terrain =
+        Model::new_with_material(&context, &terrain_mesh, ProceduralMaterial::default()).unwrap();
+    let physical_terrain = Model::new_with_material(
+        &context,
+        &terrain_mesh,
+        PhysicalMaterial {
+            albedo: Color::new_opaque(100, 120, 70),
+            roughness: 0.9,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.5,
+            Color::new_opaque(255, 245, 230),
+            &vec3(-1.0, -0.7, -0.3),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut procedural = true;
+    let mut snow = false;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.radio_value(&mut procedural, true, "Procedural material");
+                    ui.radio_value(&mut procedural, false, "Physical material");
+                    let material = &mut procedural_terrain.material;
+                    if ui.checkbox(&mut snow, "Snow instead of grass").changed() {
+                        if snow {
+                            material.second_layer.color = Color::new_opaque(230, 235, 245);
+                            material.second_layer.contrast = 0.15;
+                            material.height_range = (4.0, f32::MAX);
+                        } else {
+                            material.second_layer.color = Color::new_opaque(70, 120, 40);
+                            material.second_layer.contrast = 0.4;
+                            material.height_range = (f32::MIN, f32::MAX);
+                        }
+                    }
+                    ui.label("Rock layer");
+                    layer_ui(ui, &mut material.first_layer);
+                    ui.label(if snow { "Snow layer" } else { "Grass layer" });
+                    layer_ui(ui, &mut material.second_layer);
+                    ui.add(Slider::new(&mut material.slope_range.0, 0.0..=90.0).text("Full slope"));
+                    ui.add(Slider::new(&mut material.slope_range.1, 0.0..=90.0).text("No slope"));
+                    ui.add(
+                        Slider::new(&mut material.height_blend, 0.0..=10.0).text("Height blend"),
+                    );
+                    ui.add(Slider::new(&mut material.seed, 0..=100).text("Seed"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.6, 0.75, 0.9, 1.0, 1.0),
+                || {
+                    if procedural {
+                        procedural_terrain.render(&camera, &lights)?;
+                    } else {
+                        physical_terrain.render(&camera, &lights)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+fn layer_ui(ui: &mut three_d::egui::Ui, layer: &mut NoiseLayer) {
+    use three_d::egui::*;
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut layer.noise_type, NoiseType::Value, "Value");
+        ui.radio_value(&mut layer.noise_type, NoiseType::Gradient, "Gradient");
+        ui.radio_value(&mut layer.noise_type, NoiseType::Cellular, "Cellular");
+    });
+    ui.add(Slider::new(&mut layer.scale, 0.1..=20.0).text("Scale"));
+    ui.add(Slider::new(&mut layer.octaves, 1..=8).text("Octaves"));
+    ui.add(Slider::new(&mut layer.contrast, 0.0..=1.0).text("Contrast"));
+}
+
+// A square grid of the given number of cells along each side, where the height is a sum of a few waves giving hills and mountains
+fn terrain_mesh(cells: u32, size: f32) -> CPUMesh {
+    let height = |x: f32, z: f32| {
+        let mut h = 0.0;
+        let mut amplitude = 8.0;
+        let mut frequency = 0.04;
+        for i in 0..5 {
+            let angle = i as f32 * 1.7;
+            let u = x * angle.cos() + z * angle.sin();
+            let v = z * angle.cos() - x * angle.sin();
+            h += amplitude * (frequency * u).sin() * (frequency * 1.3 * v + i as f32).cos();
+            amplitude *= 0.45;
+            frequency *= 2.1;
+        }
+        h
+    };
+    let mut positions = Vec::new();
+    for j in 0..=cells {
+        for i in 0..=cells {
+            let x = size * (i as f32 / cells as f32 - 0.5);
+            let z = size * (j as f32 / cells as f32 - 0.5);
+            positions.extend_from_slice(&[x, height(x, z), z]);
+        }
+    }
+    let mut indices = Vec::new();
+    for j in 0..cells {
+        for i in 0..cells {
+            let v = j * (cells + 1) + i;
+            indices.extend_from_slice(&[
+                v,
+                v + cells + 1,
+                v + 1,
+                v + 1,
+                v + cells + 1,
+                v + cells + 2,
+            ]);
+        }
+    }
+    let mut mesh = CPUMesh {
+        positions,
+        indices: Some(Indices::U32(indices)),
+        ..Default::default()
+    };
+    mesh.compute_normals();
+    mesh
+}
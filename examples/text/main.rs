@@ -0,0 +1,158 @@
+use three_d::*;
+
+// Text in several languages and with emoji rendered in the 3D world, where the glyphs which are missing from the primary font are found in the fallback fonts.
+// Zoom in to see that the text stays crisp at any scale, since the glyphs are stored as signed distance fields.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Text!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(3.0, -1.0, 8.0),
+        vec3(3.0, -1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.01,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 0.1, 50.0);
+
+    let texts = Loading::new(
+        &context,
+        &[
+            "examples/assets/fonts/Ubuntu-Light.ttf",
+            "examples/assets/fonts/NotoEmoji-Regular.ttf",
+            "examples/assets/fonts/emoji-icon-font.ttf",
+        ],
+        move |context, mut loaded| {
+            // The primary font followed by the fallback fonts
+            let atlas = GlyphAtlas::new(
+                &context,
+                &[
+                    Font::new(loaded.remove_bytes("Ubuntu-Light.ttf")?)?,
+                    Font::new(loaded.remove_bytes("NotoEmoji-Regular.ttf")?)?,
+                    Font::new(loaded.remove_bytes("emoji-icon-font.ttf")?)?,
+                ],
+            )?;
+            let mut heading = Text::new(&context, &atlas, "three-d ✨ text", 1.0)?;
+            heading.set_color(Color::new_opaque(255, 200, 50));
+            heading.set_outline(Color::new_opaque(60, 20, 0), 0.05, 0.0);
+            let mut body = Text::new(
+                &context,
+                &atlas,
+                // Combining marks are placed on top of the preceding character: "Crème brûlée" spelled with combining accents
+                "Hello, world! 👋\nGrüß Gott! Crème brûlée, Cre\u{300}me bru\u{302}le\u{301}e\nΓειά σου κόσμε! 🌍\nПривет, мир! 🚀🎉\nLatin, Ελληνικά, Кириллица ★ ☂ ♫",
+                0.4,
+            )?;
+            body.set_color(Color::WHITE);
+            body.set_transformation(Mat4::from_translation(vec3(0.0, -1.0, 0.0)));
+            let mut label = Text::new(&context, &atlas, "A label with a halo 💡", 0.3)?;
+            label.set_color(Color::BLACK);
+            label.set_outline(Color::new(255, 255, 255, 200), 0.15, 0.1);
+            label.set_transformation(Mat4::from_translation(vec3(0.0, -4.0, 0.0)));
+            Ok((atlas, heading, body, label))
+        },
+    );
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut outline_width = 0.15;
+    let mut outline_softness = 0.1;
+    let mut text = "Edit me! ✏ Ünïcödé 😀".to_string();
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            let mut text_changed = false;
+            let mut outline_changed = false;
+            let page_count = texts
+                .borrow()
+                .as_ref()
+                .map(|t| {
+                    t.as_ref()
+                        .map(|(atlas, ..)| atlas.page_count())
+                        .unwrap_or(0)
+                })
+                .unwrap_or(0);
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label("Label text");
+                    text_changed = ui.text_edit_singleline(&mut text).changed();
+                    ui.label("Label halo");
+                    outline_changed |= ui
+                        .add(Slider::new(&mut outline_width, 0.0..=0.2).text("Width"))
+                        .changed();
+                    outline_changed |= ui
+                        .add(Slider::new(&mut outline_softness, 0.0..=0.2).text("Softness"))
+                        .changed();
+                    ui.label(format!("Atlas pages: {}", page_count));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            if let Some(Ok((_, _, _, ref mut label))) = *texts.borrow_mut() {
+                if text_changed {
+                    label.set_text(&text).unwrap();
+                }
+                if outline_changed {
+                    label.set_outline(
+                        Color::new(255, 255, 255, 200),
+                        outline_width,
+                        outline_softness,
+                    );
+                }
+            }
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.2, 0.3, 0.4, 1.0, 1.0),
+                || {
+                    if let Some(Ok((_, ref heading, ref body, ref label))) = *texts.borrow() {
+                        render_pass(&camera, &[heading, body, label], &Lights::default())?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -37,7 +37,7 @@ fn main() {
         move |context, mut loaded| {
             Skybox::new(
                 &context,
-                &loaded.cube_image("right", "left", "top", "top", "front", "back")?,
+                &loaded.cube_map(&["right", "left", "top", "top", "front", "back"])?,
             )
         },
     );
@@ -63,11 +63,12 @@ fn main() {
                 },
             )?;
             box_object.material.opaque_render_states.cull = Cull::Back;
-            let (penguin_cpu_meshes, penguin_cpu_materials) = loaded.obj("PenguinBaseMesh.obj")?;
+            let (penguin_cpu_mesh, penguin_cpu_material) =
+                loaded.obj("PenguinBaseMesh.obj")?.remove(0);
             let mut penguin_object = Model::new_with_material(
                 &context,
-                &penguin_cpu_meshes[0],
-                PhysicalMaterial::new(&context, &penguin_cpu_materials[0])?,
+                &penguin_cpu_mesh,
+                PhysicalMaterial::new(&context, &penguin_cpu_material.unwrap())?,
             )?;
             penguin_object.set_transformation(Mat4::from_translation(vec3(0.0, 1.0, 0.5)));
             penguin_object.material.opaque_render_states.cull = Cull::Back;
@@ -98,7 +99,11 @@ fn main() {
             let mut redraw = frame_input.first_frame;
             redraw |= camera.set_viewport(frame_input.viewport).unwrap();
             redraw |= control
-                .handle_events(&mut camera, &mut frame_input.events)
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
                 .unwrap();
             if !loaded && objects.is_loaded() && skybox.is_loaded() {
                 redraw = true;
@@ -0,0 +1,201 @@
+use std::rc::Rc;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Texture atlas!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(40.0, 30.0, 40.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 500.0);
+
+    // Procedural texture atlas with four rock variants in a 2 by 2 grid, each with its own color and pattern of spots
+    let size = 512;
+    let half = size / 2;
+    let colors = [
+        [120, 110, 100],
+        [90, 85, 80],
+        [150, 120, 90],
+        [100, 110, 95],
+    ];
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let variant = (x / half) + 2 * (y / half);
+            let (u, v) = (x % half, y % half);
+            let spot = (u * 7 + v * 13 + variant * 31) % (11 + 4 * variant) == 0
+                || ((u / (4 + variant)) + (v / (6 + variant))) % 5 == 0;
+            let shade = if spot { 0.7 } else { 1.0 };
+            let color = colors[variant];
+            data.extend_from_slice(&[
+                (color[0] as f32 * shade) as u8,
+                (color[1] as f32 * shade) as u8,
+                (color[2] as f32 * shade) as u8,
+                255,
+            ]);
+        }
+    }
+    let atlas = Rc::new(
+        Texture2D::new(
+            &context,
+            &CPUTexture {
+                data,
+                width: size as u32,
+                height: size as u32,
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+    );
+
+    // A field of 1,000 rocks, where each rock uses one of the four regions of the atlas picked by the index of the instance
+    let mut instances = Vec::new();
+    for i in 0..1000 {
+        let x = (i % 40) as f32 - 20.0;
+        let z = (i / 40) as f32 - 12.5;
+        let r = ((i * 7919) % 1000) as f32 / 1000.0;
+        let variant = i % 4;
+        instances.push(ModelInstance {
+            geometry_transform: Mat4::from_translation(vec3(2.0 * x + r, 0.0, 2.0 * z - r))
+                * Mat4::from_angle_y(radians(r * std::f32::consts::TAU))
+                * Mat4::from_nonuniform_scale(0.5 + 0.5 * r, 0.3 + 0.3 * r, 0.6),
+            uv_offset_scale: Some(vec4(
+                0.5 * (variant % 2) as f32,
+                0.5 * (variant / 2) as f32,
+                0.5,
+                0.5,
+            )),
+            ..Default::default()
+        });
+    }
+    let mut rocks = InstancedModel::new_with_material(
+        &context,
+        &instances,
+        &CPUMesh::sphere(8),
+        PhysicalMaterial {
+            albedo_texture: Some(atlas),
+            roughness: 0.9,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(80, 100, 60),
+            roughness: 1.0,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_scale(100.0) * Mat4::from_angle_x(degrees(-90.0)));
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.4,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut use_variants = true;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            let mut changed = false;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    changed |= ui.checkbox(&mut use_variants, "Atlas variants").changed();
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            // Without the regions, each rock uses the whole atlas
+            if changed {
+                rocks.set_instances(
+                    &instances
+                        .iter()
+                        .map(|instance| ModelInstance {
+                            uv_offset_scale: if use_variants {
+                                instance.uv_offset_scale
+                            } else {
+                                None
+                            },
+                            ..*instance
+                        })
+                        .collect::<Vec<_>>(),
+                );
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.6, 0.8, 1.0, 1.0, 1.0),
+                || {
+                    ground.render(&camera, &lights)?;
+                    // All rocks are rendered in one draw call
+                    rocks.render(&camera, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
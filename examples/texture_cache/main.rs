@@ -0,0 +1,242 @@
+use three_d::*;
+
+// The camera moves through a long row of rooms where each wall, floor and ceiling has its own 1024x1024 texture,
+// in total almost a gigabyte of textures, while a texture cache keeps the textures on the GPU within a budget of 256 MB.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Texture cache!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 0.0, 0.0),
+        vec3(1.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    const ROOM_COUNT: usize = 40;
+    const SURFACES_PER_ROOM: usize = 6;
+    const ROOM_SIZE: f32 = 4.0;
+    let mut cache = TextureCache::new(&context, 256 * 1024 * 1024);
+
+    // The font texture of a GUI or an environment map should never be evicted
+    cache
+        .insert_pinned(
+            usize::MAX,
+            TextureSource::CPUTexture(room_texture(usize::MAX, 256)),
+        )
+        .unwrap();
+    for index in 0..ROOM_COUNT * SURFACES_PER_ROOM {
+        // Recreating the pixels simulates loading them from disk again after the texture has been evicted
+        cache
+            .insert(
+                index,
+                TextureSource::recreate(move || Ok(room_texture(index, 1024))),
+            )
+            .unwrap();
+    }
+
+    // A square for each surface of a room, the square faces the positive z direction before it is transformed
+    let half_size = 0.5 * ROOM_SIZE;
+    let surface_transformations = [
+        Mat4::from_translation(vec3(0.0, -half_size, 0.0)) * Mat4::from_angle_x(degrees(-90.0)),
+        Mat4::from_translation(vec3(0.0, half_size, 0.0)) * Mat4::from_angle_x(degrees(90.0)),
+        Mat4::from_translation(vec3(0.0, 0.0, -half_size)),
+        Mat4::from_translation(vec3(0.0, 0.0, half_size)) * Mat4::from_angle_y(degrees(180.0)),
+        Mat4::from_translation(vec3(-half_size, 0.0, 0.0)) * Mat4::from_angle_y(degrees(90.0)),
+        Mat4::from_translation(vec3(half_size, 0.0, 0.0)) * Mat4::from_angle_y(degrees(-90.0)),
+    ];
+    let mut surface = Model::new(&context, &CPUMesh::square()).unwrap();
+    let mut sign = Model::new(&context, &CPUMesh::square()).unwrap();
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 1.0,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let mut speed = 2.0;
+    let mut position = 0.0;
+    let mut max_resident_bytes = 0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let stats = cache.new_frame();
+            max_resident_bytes = max_resident_bytes.max(stats.resident_bytes);
+
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut speed, 0.0..=20.0).text("Speed"));
+                    ui.checkbox(&mut cache.downgrade_before_evict, "Downgrade before evict");
+                    ui.separator();
+                    ui.label(format!("Budget: {} MB", cache.budget() / (1024 * 1024)));
+                    ui.label(format!(
+                        "Resident: {:.1} MB in {} textures",
+                        stats.resident_bytes as f64 / (1024.0 * 1024.0),
+                        stats.resident_textures
+                    ));
+                    ui.label(format!(
+                        "Max resident: {:.1} MB",
+                        max_resident_bytes as f64 / (1024.0 * 1024.0)
+                    ));
+                    ui.label(format!("Hits: {}", stats.hits));
+                    ui.label(format!("Misses: {}", stats.misses));
+                    ui.label(format!("Evictions: {}", stats.evictions));
+                    ui.label(format!("Downgrades: {}", stats.downgrades));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+
+            // Move back and forth through the rows of rooms
+            let length = ROOM_SIZE * (ROOM_COUNT - 1) as f32;
+            position =
+                (position + speed * 0.001 * frame_input.elapsed_time as f32) % (2.0 * length);
+            let x = if position < length {
+                position
+            } else {
+                2.0 * length - position
+            };
+            camera
+                .set_view(
+                    vec3(x, 0.0, 0.0),
+                    vec3(x + 1.0, 0.0, 0.0),
+                    vec3(0.0, 1.0, 0.0),
+                )
+                .unwrap();
+
+            // Only the current room and the neighbouring rooms are visible through the open doors
+            let current_room = (x / ROOM_SIZE).round() as usize;
+            let visible_rooms = current_room.saturating_sub(1)..(current_room + 2).min(ROOM_COUNT);
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+                || {
+                    for room in visible_rooms.clone() {
+                        let center = vec3(room as f32 * ROOM_SIZE, 0.0, 0.0);
+                        for (surface_index, transformation) in
+                            surface_transformations.iter().enumerate()
+                        {
+                            // The front and back walls have doors, so they are left out except at the ends of the row
+                            if (surface_index == 4 && room > 0)
+                                || (surface_index == 5 && room < ROOM_COUNT - 1)
+                            {
+                                continue;
+                            }
+                            // The texture is fetched each frame instead of stored in the material, so evicted textures are freed
+                            let texture = cache.get(&(room * SURFACES_PER_ROOM + surface_index))?;
+                            surface.set_transformation(
+                                Mat4::from_translation(center)
+                                    * transformation
+                                    * Mat4::from_scale(half_size),
+                            );
+                            surface.render_with_material(
+                                &PhysicalMaterial {
+                                    albedo_texture: Some(texture),
+                                    ..Default::default()
+                                },
+                                &camera,
+                                &lights,
+                            )?;
+                        }
+                    }
+                    sign.set_transformation(
+                        Mat4::from_translation(vec3(x + 3.0, 1.5, 0.0))
+                            * Mat4::from_angle_y(degrees(-90.0))
+                            * Mat4::from_scale(0.3),
+                    );
+                    sign.render_with_material(
+                        &PhysicalMaterial {
+                            albedo_texture: Some(cache.get(&usize::MAX)?),
+                            ..Default::default()
+                        },
+                        &camera,
+                        &lights,
+                    )?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// A checkerboard texture with a color that depends on the index, so each surface looks different
+fn room_texture(index: usize, size: u32) -> CPUTexture<u8> {
+    let hue = (index.wrapping_mul(2654435761) % 360) as f32;
+    let color = hsv_to_rgb(hue, 0.6, 0.9);
+    let mut data = Vec::with_capacity((size * size * 3) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let checker = ((x / 64) + (y / 64)) % 2 == 0;
+            let shade = if checker { 1.0 } else { 0.5 };
+            data.extend_from_slice(&[
+                (color.0 * shade * 255.0) as u8,
+                (color.1 * shade * 255.0) as u8,
+                (color.2 * shade * 255.0) as u8,
+            ]);
+        }
+    }
+    CPUTexture {
+        data,
+        width: size,
+        height: size,
+        format: Format::RGB,
+        ..Default::default()
+    }
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (f32, f32, f32) {
+    let c = value * saturation;
+    let h = hue / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = value - c;
+    (r + m, g + m, b + m)
+}
@@ -0,0 +1,156 @@
+use std::rc::Rc;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Texture filtering!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    // The camera is close to the ground looking towards the horizon, so the ground is viewed at a grazing angle
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 1.0, 10.0),
+        vec3(0.0, 0.0, -100.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = FlyControl::new(0.1);
+
+    // Procedural checkerboard texture
+    let size = 512;
+    let cells = 8;
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let shade = if (x * cells / size + y * cells / size) % 2 == 0 {
+                230
+            } else {
+                30
+            };
+            data.extend_from_slice(&[shade, shade, shade, 255]);
+        }
+    }
+
+    // A long ground plane where the texture is repeated many times
+    let extent = 500.0;
+    let repeats = 250.0;
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh {
+            positions: vec![
+                -extent, 0.0, extent, extent, 0.0, extent, extent, 0.0, -extent, -extent, 0.0,
+                -extent,
+            ],
+            indices: Some(Indices::U8(vec![0, 1, 2, 2, 3, 0])),
+            uvs: Some(vec![0.0, 0.0, repeats, 0.0, repeats, repeats, 0.0, repeats]),
+            ..Default::default()
+        },
+        ColorMaterial {
+            texture: Some(Rc::new(
+                Texture2D::new(
+                    &context,
+                    &CPUTexture {
+                        data,
+                        width: size as u32,
+                        height: size as u32,
+                        ..Default::default()
+                    },
+                )
+                .unwrap(),
+            )),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let max_anisotropy = context.capabilities().max_anisotropy;
+    let mut anisotropy_enabled = false;
+    let mut mip_maps_enabled = true;
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            let mut filtering_changed = false;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    if let Some(max_anisotropy) = max_anisotropy {
+                        filtering_changed |= ui
+                            .checkbox(
+                                &mut anisotropy_enabled,
+                                format!("16x anisotropy (max {}x)", max_anisotropy),
+                            )
+                            .clicked();
+                    } else {
+                        ui.label("Anisotropic filtering is not supported");
+                    }
+                    filtering_changed |= ui.checkbox(&mut mip_maps_enabled, "Mip maps").clicked();
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            if filtering_changed {
+                // The texture is only owned by the material, so it can be changed in place
+                let texture = Rc::get_mut(ground.material.texture.as_mut().unwrap()).unwrap();
+                texture.set_anisotropy(if anisotropy_enabled { 16 } else { 1 });
+                texture.set_mip_map_filter(if mip_maps_enabled {
+                    Some(Interpolation::Linear)
+                } else {
+                    None
+                });
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.6, 0.8, 1.0, 1.0, 1.0),
+                || {
+                    ground.render(&camera, &Lights::default())?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,161 @@
+use std::rc::Rc;
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Texture transform!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 4.0, 12.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    // Procedural detail texture with diagonal stripes which is used as an occlusion map
+    let size = 256;
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let shade = if ((x + y) / 16) % 4 == 0 { 60 } else { 255 };
+            data.extend_from_slice(&[shade, shade, shade, 255]);
+        }
+    }
+    let detail_texture = Rc::new(
+        Texture2D::new(
+            &context,
+            &CPUTexture {
+                data,
+                width: size as u32,
+                height: size as u32,
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+    );
+
+    // A ground plane with uv coordinates in the range [0..1], so the tiling is done by the texture transforms
+    let ground = Loading::new(
+        &context,
+        &["examples/assets/test_texture.jpg"],
+        move |context, mut loaded| {
+            let mut ground = Model::new_with_material(
+                &context,
+                &CPUMesh::square(),
+                PhysicalMaterial {
+                    albedo_texture: Some(Rc::new(Texture2D::new(
+                        &context,
+                        &loaded.image("test_texture")?,
+                    )?)),
+                    occlusion_texture: Some(detail_texture),
+                    ..Default::default()
+                },
+            )?;
+            ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(20.0));
+            Ok(ground)
+        },
+    );
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.6,
+            color: Color::WHITE,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.5,
+            Color::WHITE,
+            &vec3(0.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut tiling = 20.0;
+    let mut detail_tiling = 6.0;
+    let mut detail_rotation = 30.0;
+    let mut detail_offset = 0.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut tiling, 1.0..=50.0).text("Albedo tiling"));
+                    ui.add(Slider::new(&mut detail_tiling, 1.0..=50.0).text("Detail tiling"));
+                    ui.add(Slider::new(&mut detail_rotation, 0.0..=360.0).text("Detail rotation"));
+                    ui.add(Slider::new(&mut detail_offset, 0.0..=1.0).text("Detail offset"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            // Each texture of the material has its own transformation of the uv coordinates
+            if let Some(ref mut ground) = *ground.borrow_mut() {
+                let material = &mut ground.as_mut().unwrap().material;
+                material.albedo_texture_transform = Mat3::from_scale(tiling);
+                material.occlusion_texture_transform =
+                    Mat3::from_translation(vec2(detail_offset, 0.0))
+                        * Mat3::from_angle_z(degrees(detail_rotation))
+                        * Mat3::from_scale(detail_tiling);
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.6, 0.8, 1.0, 1.0, 1.0),
+                || {
+                    if let Some(ref ground) = *ground.borrow() {
+                        ground.as_ref().unwrap().render(&camera, &lights)?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,173 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Toon!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let pipeline = ForwardPipeline::new(&context).unwrap();
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(-60.0, 50.0, 60.0),
+        vec3(0.0, 15.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 500.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // Model from http://texturedmesh.isti.cnr.it/
+    let statue = Loading::new(
+        &context,
+        &[
+            "examples/assets/COLOMBE.obj",
+            "examples/assets/COLOMBE.mtl",
+            "examples/assets/COLOMBE.png",
+        ],
+        move |context, mut loaded| {
+            let (cpu_mesh, cpu_material) = loaded.obj("examples/assets/COLOMBE.obj")?.remove(0);
+            let mut material = ToonMaterial::new(&context, &cpu_material.unwrap())?;
+            material.rim_color = Color::new_opaque(60, 60, 80);
+            material.opaque_render_states.cull = Cull::Back;
+            let mut statue = Model::new_with_material(&context, &cpu_mesh, material)?;
+            statue.set_transformation(Mat4::from_scale(1.5));
+            Ok(statue)
+        },
+    );
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.3,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            3.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        point: vec![PointLight::new(
+            &context,
+            2.0,
+            Color::new_opaque(255, 180, 120),
+            &vec3(30.0, 30.0, 30.0),
+            0.0,
+            0.01,
+            0.0,
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut outline = EdgeOutlineEffect::new(&context).unwrap();
+    let mut bands = 3;
+    let mut specular = 0.5;
+    let mut rim = true;
+    let mut outlines = true;
+    let mut normal_outlines = true;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut bands, 1..=8).text("Bands"));
+                    ui.add(Slider::new(&mut specular, 0.0..=2.0).text("Specular"));
+                    ui.checkbox(&mut rim, "Rim light");
+                    ui.checkbox(&mut outlines, "Outlines");
+                    ui.add(Slider::new(&mut outline.thickness, 0.5..=5.0).text("Thickness"));
+                    ui.checkbox(&mut normal_outlines, "Outlines at creases");
+                    ui.add(
+                        Slider::new(&mut outline.normal_threshold, 0.05..=1.0)
+                            .text("Crease threshold"),
+                    );
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            if let Some(Ok(ref mut statue)) = *statue.borrow_mut() {
+                statue.material.bands = bands;
+                statue.material.specular = specular;
+                statue.material.rim_size = if rim { 0.3 } else { 0.0 };
+            }
+
+            // The depth and normals of the scene are used to find the outlines
+            let outline_textures = if outlines {
+                if let Some(Ok(ref statue)) = *statue.borrow() {
+                    if normal_outlines {
+                        let (depth_texture, normal_texture) = pipeline
+                            .depth_and_normal_pass_textures(&camera, &[statue])
+                            .unwrap();
+                        Some((depth_texture, Some(normal_texture)))
+                    } else {
+                        Some((
+                            pipeline.depth_pass_texture(&camera, &[statue]).unwrap(),
+                            None,
+                        ))
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.9, 0.85, 0.7, 1.0, 1.0),
+                || {
+                    if let Some(Ok(ref statue)) = *statue.borrow() {
+                        statue.render(&camera, &lights)?;
+                    }
+                    if let Some((ref depth_texture, ref normal_texture)) = outline_textures {
+                        outline.apply(&camera, depth_texture, normal_texture.as_ref())?;
+                    }
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -0,0 +1,84 @@
+use three_d::*;
+
+// Loads an .obj file and exports a five second turntable animation of it as an animated GIF without opening a window.
+// Run with the path of the .obj file and the output path as arguments, for example
+// `cargo run --example turntable --features gif-export -- examples/assets/suzanne.obj turntable.gif`
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let obj_path = args
+        .get(1)
+        .cloned()
+        .unwrap_or("examples/assets/suzanne.obj".to_string());
+    let output_path = args.get(2).cloned().unwrap_or("turntable.gif".to_string());
+    // The material file is loaded too, if there is one next to the .obj file
+    let mtl_path = std::path::Path::new(&obj_path).with_extension("mtl");
+
+    // Create a headless graphics context
+    let context = Context::new().unwrap();
+
+    Loader::load(
+        &[obj_path.clone(), mtl_path.to_str().unwrap().to_string()],
+        move |mut loaded| {
+            let models = loaded
+                .obj(&obj_path)
+                .unwrap()
+                .into_iter()
+                .map(|(cpu_mesh, cpu_material)| {
+                    let material = if let Some(cpu_material) = cpu_material {
+                        PhysicalMaterial::new(&context, &cpu_material).unwrap()
+                    } else {
+                        PhysicalMaterial::default()
+                    };
+                    Model::new_with_material(&context, &cpu_mesh, material).unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            // Frame the models by circling around the center of their bounding box at a distance where all of it is visible
+            let mut aabb = AxisAlignedBoundingBox::EMPTY;
+            for model in models.iter() {
+                aabb.expand_with_aabb(&model.aabb());
+            }
+            let center = aabb.center();
+            let radius = 1.2 * 0.5 * aabb.size().magnitude() / (0.5 * 45.0f32.to_radians()).sin();
+
+            let lights = Lights {
+                ambient: Some(AmbientLight {
+                    intensity: 0.4,
+                    ..Default::default()
+                }),
+                directional: vec![DirectionalLight::new(
+                    &context,
+                    2.0,
+                    Color::WHITE,
+                    &vec3(-1.0, -1.0, -1.0),
+                )
+                .unwrap()],
+                ..Default::default()
+            };
+
+            let fps = 25.0;
+            export_turntable_with_options(
+                &context,
+                &models,
+                &lights,
+                center,
+                radius,
+                5 * fps as u32,
+                (400, 400),
+                &TurntableOutput::Gif(output_path.clone().into()),
+                TurntableOptions {
+                    fps,
+                    high_res_options: HighResOptions {
+                        samples_per_pixel: 2,
+                        clear_state: ClearState::color_and_depth(0.9, 0.9, 0.9, 1.0, 1.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                None,
+            )
+            .unwrap();
+            println!("Saved the turntable animation to {}", output_path);
+        },
+    );
+}
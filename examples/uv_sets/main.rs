@@ -0,0 +1,165 @@
+use std::rc::Rc;
+use three_d::*;
+
+// A plane with two sets of uv coordinates, where the albedo texture is a fine checkerboard mapped with the first set
+// and the occlusion texture consists of stripes mapped with the second set, which is rotated 90 degrees compared to the first set.
+// When the occlusion texture uses the first set instead, the stripes turn 90 degrees and become four times as dense, like the checkerboard.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "UV sets!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(0.0, 6.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 50.0);
+
+    let texture = |pattern: &dyn Fn(usize, usize) -> [u8; 3]| {
+        let size = 128;
+        let mut data = Vec::with_capacity(size * size * 4);
+        for y in 0..size {
+            for x in 0..size {
+                let color = pattern(x, y);
+                data.extend_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+        Rc::new(
+            Texture2D::new(
+                &context,
+                &CPUTexture {
+                    data,
+                    width: size as u32,
+                    height: size as u32,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        )
+    };
+    let checkerboard = texture(&|x, y| {
+        if (x / 64 + y / 64) % 2 == 0 {
+            [230, 140, 80]
+        } else {
+            [80, 140, 230]
+        }
+    });
+    let stripes = texture(&|x, _| {
+        if (x / 16) % 2 == 0 {
+            [40, 40, 40]
+        } else {
+            [255, 255, 255]
+        }
+    });
+
+    // The first set repeats the checkerboard four times, while the second set covers the plane once and is rotated
+    let mut cpu_mesh = CPUMesh::square();
+    let uvs = cpu_mesh.uvs.clone().unwrap();
+    cpu_mesh.uvs2 = Some(
+        uvs.chunks(2)
+            .flat_map(|uv| vec![1.0 - uv[1], uv[0]])
+            .collect(),
+    );
+    cpu_mesh.uvs = Some(uvs.iter().map(|uv| 4.0 * uv).collect());
+    let mut plane = Model::new_with_material(
+        &context,
+        &cpu_mesh,
+        PhysicalMaterial {
+            albedo_texture: Some(checkerboard),
+            occlusion_texture: Some(stripes),
+            occlusion_texture_uv_set: 1,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    plane.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(4.0));
+
+    let lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.8,
+            ..Default::default()
+        }),
+        directional: vec![DirectionalLight::new(
+            &context,
+            1.0,
+            Color::WHITE,
+            &vec3(-1.0, -1.0, -1.0),
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.label("Occlusion uv set");
+                    ui.radio_value(&mut plane.material.occlusion_texture_uv_set, 0, "First");
+                    ui.radio_value(&mut plane.material.occlusion_texture_uv_set, 1, "Second");
+                    ui.add(
+                        Slider::new(&mut plane.material.occlusion_strength, 0.0..=1.0)
+                            .text("Occlusion strength"),
+                    );
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+                || {
+                    plane.render(&camera, &lights)?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
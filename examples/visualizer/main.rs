@@ -0,0 +1,122 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use three_d::core::*;
+use three_d::*;
+
+// A music visualizer where a fake frequency spectrum and beat are fed into a shader toy style image effect through a uniform provider,
+// together with the time and resolution from the built-in time uniforms.
+// In a real visualizer, the spectrum is computed from the audio with a fast Fourier transform.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Visualizer!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let time = Rc::new(TimeUniforms::new());
+    let spectrum = Rc::new(Spectrum::default());
+    let mut effect = ImageEffect::new(&context, include_str!("shader.frag")).unwrap();
+    effect.add_provider(time.clone()).unwrap();
+    effect.add_provider(spectrum.clone()).unwrap();
+
+    let mut gui = GUI::new(&context).unwrap();
+    let mut tempo = 120.0;
+    let mut gain = 1.0;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut tempo, 60.0..=180.0).text("Tempo (bpm)"));
+                    ui.add(Slider::new(&mut gain, 0.0..=2.0).text("Gain"));
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            time.update(frame_input.elapsed_time, viewport);
+            spectrum.update(time.time(), tempo, gain);
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+                || {
+                    effect.apply(
+                        RenderStates {
+                            depth_test: DepthTest::Always,
+                            ..Default::default()
+                        },
+                        viewport,
+                    )?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+const BAND_COUNT: usize = 32;
+
+// The magnitude of each frequency band in the range [0..1] and the strength of the current beat, which fades out until the next beat
+#[derive(Default)]
+struct Spectrum {
+    bands: RefCell<[f32; BAND_COUNT]>,
+    beat: Cell<f32>,
+}
+
+impl Spectrum {
+    // Generates a fake spectrum with a kick on the low frequencies on each beat
+    fn update(&self, time: f32, tempo: f32, gain: f32) {
+        let beat = (-6.0 * (time * tempo / 60.0).fract()).exp();
+        self.beat.set(beat);
+        for (i, band) in self.bands.borrow_mut().iter_mut().enumerate() {
+            let frequency = i as f32 / BAND_COUNT as f32;
+            let melody = 0.5 + 0.5 * (time * (1.0 + 0.37 * i as f32) + i as f32).sin();
+            let kick = beat * (1.0 - 4.0 * frequency).max(0.0);
+            let value = gain * (0.6 * melody * (-2.0 * frequency).exp() + kick);
+            // Rises immediately and falls slowly, like the meters of a music player
+            *band = value.min(1.0).max(0.95 * *band);
+        }
+    }
+}
+
+impl UniformProvider for Spectrum {
+    fn fragment_shader_source(&self) -> String {
+        format!(
+            "#define BAND_COUNT {}\nuniform float bands[BAND_COUNT];\nuniform float beat;\n",
+            BAND_COUNT
+        )
+    }
+
+    fn provide(&self, program: &Program) -> ThreeDResult<()> {
+        program.use_uniform_array("bands", &self.bands.borrow()[..])?;
+        program.use_uniform_float("beat", &self.beat.get())
+    }
+}
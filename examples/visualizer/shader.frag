@@ -0,0 +1,23 @@
+// The uniforms iTime and iResolution are declared by the time uniforms and BAND_COUNT, bands and beat by the spectrum
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 p = (uv - 0.5) * iResolution.xy / iResolution.y;
+
+    // A ring around the center which pulses with the beat
+    float radius = 0.25 + 0.05 * beat;
+    float ring = smoothstep(0.02, 0.0, abs(length(p) - radius));
+
+    // A bar for each band along the bottom of the screen
+    float band_position = uv.x * float(BAND_COUNT);
+    int band = min(int(band_position), BAND_COUNT - 1);
+    float bar = step(uv.y, 0.5 * bands[band]) * step(0.1, fract(band_position));
+
+    vec3 color = 0.5 + 0.5 * cos(iTime + uv.xyx * 3.0 + vec3(0.0, 2.0, 4.0));
+    vec3 background = vec3(0.02, 0.02, 0.05) * (1.0 + 2.0 * beat);
+    outColor = vec4(background + color * max(bar, ring), 1.0);
+}
@@ -0,0 +1,228 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Volumetric light!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(-60.0, 30.0, 70.0),
+        vec3(0.0, 20.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 500.0);
+    let mut gui = three_d::GUI::new(&context).unwrap();
+
+    // Model from http://texturedmesh.isti.cnr.it/
+    let statue = Loading::new(
+        &context,
+        &[
+            "examples/assets/COLOMBE.obj",
+            "examples/assets/COLOMBE.mtl",
+            "examples/assets/COLOMBE.png",
+        ],
+        move |context, mut loaded| {
+            let (cpu_mesh, cpu_material) = loaded.obj("examples/assets/COLOMBE.obj")?.remove(0);
+            let mut statue = Model::new_with_material(
+                &context,
+                &cpu_mesh,
+                PhysicalMaterial::new(&context, &cpu_material.unwrap())?,
+            )?;
+            statue.material.opaque_render_states.cull = Cull::Back;
+            statue.set_transformation(Mat4::from_scale(1.5));
+            Ok(statue)
+        },
+    );
+    let mut ground = Model::new_with_material(
+        &context,
+        &CPUMesh::square(),
+        PhysicalMaterial {
+            albedo: Color::new_opaque(120, 110, 100),
+            roughness: 1.0,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    ground.set_transformation(Mat4::from_scale(200.0) * Mat4::from_angle_x(degrees(-90.0)));
+
+    // A dim room lit by a single spot light shining down on the statue from above
+    let mut lights = Lights {
+        ambient: Some(AmbientLight {
+            intensity: 0.05,
+            ..Default::default()
+        }),
+        spot: vec![SpotLight::new(
+            &context,
+            3.0,
+            Color::new_opaque(255, 240, 210),
+            &vec3(30.0, 90.0, -20.0),
+            &vec3(-30.0, -70.0, 20.0),
+            degrees(20.0),
+            0.1,
+            0.001,
+            0.0001,
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+    let mut shadows_enabled = true;
+    let mut shadow_map_generated = false;
+
+    let mut volumetric_light_effect = VolumetricLightEffect::new(&context).unwrap();
+    volumetric_light_effect.density = 0.01;
+    volumetric_light_effect.max_distance = 300.0;
+    let mut volumetric_light_enabled = true;
+
+    let mut color_texture: Option<Texture2D<u8>> = None;
+    let mut depth_texture: Option<DepthTargetTexture2D> = None;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut volumetric_light_enabled, "Volumetric light");
+                    ui.checkbox(&mut shadows_enabled, "Shadows");
+                    ui.add(
+                        Slider::new(&mut volumetric_light_effect.density, 0.0..=0.05)
+                            .text("Density"),
+                    );
+                    ui.add(
+                        Slider::new(&mut volumetric_light_effect.anisotropy, -0.9..=0.9)
+                            .text("Anisotropy"),
+                    );
+                    ui.add(Slider::new(&mut volumetric_light_effect.steps, 4..=128).text("Steps"));
+                    ui.add(
+                        Slider::new(&mut volumetric_light_effect.resolution_scale, 0.1..=1.0)
+                            .text("Resolution scale"),
+                    );
+                    ui.add(
+                        Slider::new(&mut volumetric_light_effect.intensity, 0.0..=5.0)
+                            .text("Intensity"),
+                    );
+                });
+            })
+            .unwrap();
+
+            camera.set_viewport(frame_input.viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            // The shadow map is generated when the statue is loaded and removed when the shadows are disabled
+            if let Some(Ok(ref statue)) = *statue.borrow() {
+                if shadows_enabled && !shadow_map_generated {
+                    lights.spot[0]
+                        .generate_shadow_map(1024, &[statue, &ground])
+                        .unwrap();
+                    shadow_map_generated = true;
+                } else if !shadows_enabled && shadow_map_generated {
+                    lights.spot[0].clear_shadow_map();
+                    shadow_map_generated = false;
+                }
+            }
+
+            // Render the scene into a color and depth texture, recreated when the window is resized
+            let viewport = frame_input.viewport;
+            if color_texture
+                .as_ref()
+                .map(|t| t.width() != viewport.width || t.height() != viewport.height)
+                .unwrap_or(true)
+            {
+                color_texture = Some(
+                    Texture2D::new_empty(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Interpolation::Nearest,
+                        Interpolation::Nearest,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                    .unwrap(),
+                );
+                depth_texture = Some(
+                    DepthTargetTexture2D::new(
+                        &context,
+                        viewport.width,
+                        viewport.height,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                    .unwrap(),
+                );
+            }
+            let color_texture = color_texture.as_mut().unwrap();
+            let depth_texture = depth_texture.as_mut().unwrap();
+            RenderTarget::new(&context, color_texture, depth_texture)
+                .unwrap()
+                .write(
+                    ClearState::color_and_depth(0.02, 0.02, 0.03, 1.0, 1.0),
+                    || {
+                        ground.render(&camera, &lights)?;
+                        if let Some(Ok(ref statue)) = *statue.borrow() {
+                            statue.render(&camera, &lights)?;
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            let color_texture = &*color_texture;
+            let depth_texture = &*depth_texture;
+
+            if volumetric_light_enabled {
+                volumetric_light_effect
+                    .scattering_pass(&camera, depth_texture, &lights)
+                    .unwrap();
+            }
+            Screen::copy_from(
+                &context,
+                Some(color_texture),
+                None,
+                viewport,
+                WriteMask::default(),
+            )
+            .unwrap();
+            Screen::write(&context, ClearState::none(), || {
+                if volumetric_light_enabled {
+                    volumetric_light_effect.apply(&camera, depth_texture)?;
+                }
+                gui.render()?;
+                Ok(())
+            })
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
@@ -30,13 +30,12 @@ fn main() {
         &context,
         &["examples/assets/suzanne.obj", "examples/assets/suzanne.mtl"],
         move |context, mut loaded| {
-            let (mut meshes, materials) = loaded.obj("suzanne.obj").unwrap();
-            let mut cpu_mesh = meshes.remove(0);
+            let (mut cpu_mesh, cpu_material) = loaded.obj("suzanne.obj").unwrap().remove(0);
             cpu_mesh.transform(&Mat4::from_translation(vec3(0.0, 2.0, 0.0)));
             let mut model = Model::new_with_material(
                 &context,
                 &cpu_mesh,
-                PhysicalMaterial::new(&context, &materials[0]).unwrap(),
+                PhysicalMaterial::new(&context, &cpu_material.unwrap()).unwrap(),
             )
             .unwrap();
             model.material.opaque_render_states.cull = Cull::Back;
@@ -97,7 +96,11 @@ fn main() {
             }
             redraw |= camera.set_viewport(frame_input.viewport).unwrap();
             redraw |= control
-                .handle_events(&mut camera, &mut frame_input.events)
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
                 .unwrap();
 
             if redraw {
@@ -0,0 +1,150 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "X-ray!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let mut camera = Camera::new_perspective(
+        &context,
+        window.viewport().unwrap(),
+        vec3(4.0, 2.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut control = OrbitControl::new(*camera.target(), 1.0, 100.0);
+
+    // A single mesh consisting of a semi-transparent shell around a more opaque internal structure, each part with its own vertex colors
+    let cpu_mesh = merge(&[
+        (
+            CPUMesh::sphere(32),
+            Mat4::from_scale(2.0),
+            Color::new(120, 180, 255, 60),
+        ),
+        (
+            CPUMesh::cylinder(16),
+            Mat4::from_translation(vec3(-1.2, 0.0, 0.0))
+                * Mat4::from_nonuniform_scale(2.4, 0.3, 0.3),
+            Color::new(255, 80, 80, 180),
+        ),
+        (
+            CPUMesh::cube(),
+            Mat4::from_translation(vec3(0.0, 0.8, 0.0)) * Mat4::from_scale(0.5),
+            Color::new(80, 255, 80, 160),
+        ),
+        (
+            CPUMesh::sphere(16),
+            Mat4::from_translation(vec3(0.0, -0.8, 0.0)) * Mat4::from_scale(0.6),
+            Color::new(255, 220, 80, 200),
+        ),
+    ]);
+    // The alpha value below 255 makes the material transparent, so the alpha values of the vertex colors are used for blending
+    let mut model = Model::new_with_material(
+        &context,
+        &cpu_mesh,
+        ColorMaterial {
+            color: Color::new(255, 255, 255, 254),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    model
+        .set_transparency_sorting(&cpu_mesh, TriangleSorting::BackToFront)
+        .unwrap();
+
+    let mut gui = three_d::GUI::new(&context).unwrap();
+    let mut sorting = true;
+
+    // main loop
+    window
+        .render_loop(move |mut frame_input| {
+            let mut panel_width = 0;
+            gui.update(&mut frame_input, |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.checkbox(&mut sorting, "Sort triangles");
+                });
+                panel_width = (gui_context.used_size().x * gui_context.pixels_per_point()) as u32;
+            })
+            .unwrap();
+            let wanted_sorting = if sorting {
+                TriangleSorting::BackToFront
+            } else {
+                TriangleSorting::None
+            };
+            if model.transparency_sorting() != wanted_sorting {
+                model
+                    .set_transparency_sorting(&cpu_mesh, wanted_sorting)
+                    .unwrap();
+            }
+
+            let viewport = Viewport {
+                x: panel_width as i32,
+                y: 0,
+                width: frame_input.viewport.width - panel_width,
+                height: frame_input.viewport.height,
+            };
+            camera.set_viewport(viewport).unwrap();
+            control
+                .handle_events(
+                    &mut camera,
+                    &mut frame_input.events,
+                    frame_input.elapsed_time,
+                )
+                .unwrap();
+
+            Screen::write(
+                &context,
+                ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0),
+                || {
+                    model.render(&camera, &Lights::default())?;
+                    gui.render()?;
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
+
+// Merges the given meshes into one unindexed mesh where each mesh is transformed and colored by the given transformation and color
+fn merge(meshes: &[(CPUMesh, Mat4, Color)]) -> CPUMesh {
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    for (mesh, transformation, color) in meshes {
+        mesh.for_each_triangle(|i0, i1, i2| {
+            for i in [i0, i1, i2].iter() {
+                let position = (transformation * mesh.position(*i).extend(1.0)).truncate();
+                positions.extend_from_slice(&[position.x, position.y, position.z]);
+                colors.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+            }
+        });
+    }
+    CPUMesh {
+        positions,
+        colors: Some(colors),
+        ..Default::default()
+    }
+}
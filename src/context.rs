@@ -25,6 +25,7 @@ pub use wgl2::*;
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ShaderType {
     Vertex,
+    Geometry,
     Fragment,
 }
 
@@ -41,7 +42,7 @@ pub enum DataType {
 }
 
 impl DataType {
-    fn byte_size(&self) -> u32 {
+    pub(crate) fn byte_size(&self) -> u32 {
         match self {
             DataType::HalfFloat => 2,
             DataType::Float => std::mem::size_of::<f32>() as u32,
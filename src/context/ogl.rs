@@ -23,6 +23,8 @@ pub struct Framebuffer(u32);
 pub struct Texture(u32);
 #[derive(Copy, Clone, Debug)]
 pub struct VertexArrayObject(u32);
+#[derive(Copy, Clone, Debug)]
+pub struct Query(u32);
 
 pub type Sync = consts::types::GLsync;
 
@@ -55,6 +57,7 @@ impl ActiveInfo {
 #[derive(Clone)]
 pub struct GLContext {
     inner: Rc<InnerGl>,
+    default_vertex_array: VertexArrayObject,
 }
 
 impl GLContext {
@@ -62,10 +65,12 @@ impl GLContext {
     where
         for<'r> F: FnMut(&'r str) -> *const consts::types::GLvoid,
     {
-        let gl = Self {
+        let mut gl = Self {
             inner: Rc::new(InnerGl::load_with(loadfn)),
+            default_vertex_array: VertexArrayObject(0),
         };
-        gl.bind_vertex_array(&gl.create_vertex_array().unwrap());
+        gl.default_vertex_array = gl.create_vertex_array().unwrap();
+        gl.unbind_vertex_array();
         gl.enable(consts::TEXTURE_CUBE_MAP_SEAMLESS);
         gl
     }
@@ -76,6 +81,10 @@ impl GLContext {
         }
     }
 
+    pub fn is_context_lost(&self) -> bool {
+        false
+    }
+
     pub fn create_shader(&self, type_: ShaderType) -> Option<Shader> {
         let id = unsafe { self.inner.CreateShader(type_.to_const()) };
         Some(Shader(id))
@@ -144,6 +153,53 @@ impl GLContext {
         out as u32
     }
 
+    pub fn get_integer(&self, pname: u32) -> i32 {
+        let mut out = 0;
+        unsafe {
+            self.inner.GetIntegerv(pname, &mut out);
+        }
+        out
+    }
+
+    pub fn get_float(&self, pname: u32) -> f32 {
+        let mut out = 0.0;
+        unsafe {
+            self.inner.GetFloatv(pname, &mut out);
+        }
+        out
+    }
+
+    pub fn get_string(&self, pname: u32) -> String {
+        unsafe {
+            let ptr = self.inner.GetString(pname);
+            if ptr.is_null() {
+                String::new()
+            } else {
+                std::ffi::CStr::from_ptr(ptr as *const _)
+                    .to_string_lossy()
+                    .into_owned()
+            }
+        }
+    }
+
+    pub fn get_supported_extensions(&self) -> Vec<String> {
+        let count = self.get_integer(consts::NUM_EXTENSIONS);
+        (0..count.max(0) as u32)
+            .filter_map(|i| unsafe {
+                let ptr = self.inner.GetStringi(consts::EXTENSIONS, i);
+                if ptr.is_null() {
+                    None
+                } else {
+                    Some(
+                        std::ffi::CStr::from_ptr(ptr as *const _)
+                            .to_string_lossy()
+                            .into_owned(),
+                    )
+                }
+            })
+            .collect()
+    }
+
     pub fn get_active_attrib(&self, program: &Program, index: u32) -> ActiveInfo {
         let mut length = 128;
         let mut size = 0;
@@ -207,6 +263,7 @@ impl GLContext {
             consts::ARRAY_BUFFER => consts::ARRAY_BUFFER_BINDING,
             consts::ELEMENT_ARRAY_BUFFER => consts::ELEMENT_ARRAY_BUFFER_BINDING,
             consts::UNIFORM_BUFFER => consts::UNIFORM_BUFFER_BINDING,
+            consts::TRANSFORM_FEEDBACK_BUFFER => consts::TRANSFORM_FEEDBACK_BUFFER_BINDING,
             _ => unreachable!(),
         };
 
@@ -221,6 +278,12 @@ impl GLContext {
         }
     }
 
+    pub fn unbind_buffer_base(&self, target: u32, index: u32) {
+        unsafe {
+            self.inner.BindBufferBase(target, index, 0);
+        }
+    }
+
     pub fn bind_buffer(&self, target: u32, buffer: &Buffer) {
         unsafe {
             self.inner.BindBuffer(target, buffer.0);
@@ -299,6 +362,94 @@ impl GLContext {
         }
     }
 
+    pub fn buffer_sub_data_u8(&self, target: u32, offset_in_bytes: u32, data: &[u8]) {
+        unsafe {
+            self.inner.BufferSubData(
+                target,
+                offset_in_bytes as consts::types::GLintptr, // offset into the buffer in bytes
+                (data.len() * std::mem::size_of::<u8>()) as consts::types::GLsizeiptr, // size of data in bytes
+                data.as_ptr() as *const consts::types::GLvoid, // pointer to data
+            );
+        }
+    }
+
+    pub fn buffer_sub_data_u16(&self, target: u32, offset_in_bytes: u32, data: &[u16]) {
+        unsafe {
+            self.inner.BufferSubData(
+                target,
+                offset_in_bytes as consts::types::GLintptr, // offset into the buffer in bytes
+                (data.len() * std::mem::size_of::<u16>()) as consts::types::GLsizeiptr, // size of data in bytes
+                data.as_ptr() as *const consts::types::GLvoid, // pointer to data
+            );
+        }
+    }
+
+    pub fn buffer_sub_data_u32(&self, target: u32, offset_in_bytes: u32, data: &[u32]) {
+        unsafe {
+            self.inner.BufferSubData(
+                target,
+                offset_in_bytes as consts::types::GLintptr, // offset into the buffer in bytes
+                (data.len() * std::mem::size_of::<u32>()) as consts::types::GLsizeiptr, // size of data in bytes
+                data.as_ptr() as *const consts::types::GLvoid, // pointer to data
+            );
+        }
+    }
+
+    pub fn buffer_sub_data_f32(&self, target: u32, offset_in_bytes: u32, data: &[f32]) {
+        unsafe {
+            self.inner.BufferSubData(
+                target,
+                offset_in_bytes as consts::types::GLintptr, // offset into the buffer in bytes
+                (data.len() * std::mem::size_of::<f32>()) as consts::types::GLsizeiptr, // size of data in bytes
+                data.as_ptr() as *const consts::types::GLvoid, // pointer to data
+            );
+        }
+    }
+
+    pub fn get_buffer_sub_data_u8(&self, target: u32, offset_in_bytes: u32, dst_data: &mut [u8]) {
+        unsafe {
+            self.inner.GetBufferSubData(
+                target,
+                offset_in_bytes as consts::types::GLintptr, // offset into the buffer in bytes
+                (dst_data.len() * std::mem::size_of::<u8>()) as consts::types::GLsizeiptr, // size of data in bytes
+                dst_data.as_mut_ptr() as *mut consts::types::GLvoid, // pointer to destination
+            );
+        }
+    }
+
+    pub fn get_buffer_sub_data_u16(&self, target: u32, offset_in_bytes: u32, dst_data: &mut [u16]) {
+        unsafe {
+            self.inner.GetBufferSubData(
+                target,
+                offset_in_bytes as consts::types::GLintptr, // offset into the buffer in bytes
+                (dst_data.len() * std::mem::size_of::<u16>()) as consts::types::GLsizeiptr, // size of data in bytes
+                dst_data.as_mut_ptr() as *mut consts::types::GLvoid, // pointer to destination
+            );
+        }
+    }
+
+    pub fn get_buffer_sub_data_u32(&self, target: u32, offset_in_bytes: u32, dst_data: &mut [u32]) {
+        unsafe {
+            self.inner.GetBufferSubData(
+                target,
+                offset_in_bytes as consts::types::GLintptr, // offset into the buffer in bytes
+                (dst_data.len() * std::mem::size_of::<u32>()) as consts::types::GLsizeiptr, // size of data in bytes
+                dst_data.as_mut_ptr() as *mut consts::types::GLvoid, // pointer to destination
+            );
+        }
+    }
+
+    pub fn get_buffer_sub_data_f32(&self, target: u32, offset_in_bytes: u32, dst_data: &mut [f32]) {
+        unsafe {
+            self.inner.GetBufferSubData(
+                target,
+                offset_in_bytes as consts::types::GLintptr, // offset into the buffer in bytes
+                (dst_data.len() * std::mem::size_of::<f32>()) as consts::types::GLsizeiptr, // size of data in bytes
+                dst_data.as_mut_ptr() as *mut consts::types::GLvoid, // pointer to destination
+            );
+        }
+    }
+
     pub fn create_vertex_array(&self) -> Option<VertexArrayObject> {
         let mut id: u32 = 0;
         unsafe {
@@ -313,10 +464,83 @@ impl GLContext {
         }
     }
 
+    pub fn unbind_vertex_array(&self) {
+        self.bind_vertex_array(&self.default_vertex_array);
+    }
+
+    pub fn delete_vertex_array(&self, array: &VertexArrayObject) {
+        unsafe {
+            self.inner.DeleteVertexArrays(1, [array.0].as_ptr());
+        }
+    }
+
     pub fn create_program(&self) -> Program {
         unsafe { Program(self.inner.CreateProgram()) }
     }
 
+    pub fn program_binary_retrievable_hint(&self, program: &Program) {
+        unsafe {
+            self.inner.ProgramParameteri(
+                program.0,
+                consts::PROGRAM_BINARY_RETRIEVABLE_HINT,
+                consts::TRUE as i32,
+            );
+        }
+    }
+
+    pub fn get_program_binary(&self, program: &Program) -> Option<(u32, Vec<u8>)> {
+        let length = self.get_program_parameter(program, consts::PROGRAM_BINARY_LENGTH);
+        if length == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; length as usize];
+        let mut written = 0;
+        let mut format = 0;
+        unsafe {
+            self.inner.GetProgramBinary(
+                program.0,
+                length as i32,
+                &mut written,
+                &mut format,
+                data.as_mut_ptr() as *mut _,
+            );
+        }
+        data.truncate(written.max(0) as usize);
+        if data.is_empty() {
+            None
+        } else {
+            Some((format, data))
+        }
+    }
+
+    pub fn program_binary(&self, program: &Program, format: u32, data: &[u8]) -> bool {
+        unsafe {
+            self.inner.ProgramBinary(
+                program.0,
+                format,
+                data.as_ptr() as *const _,
+                data.len() as i32,
+            );
+        }
+        self.get_program_parameter(program, consts::LINK_STATUS) == 1
+    }
+
+    pub fn transform_feedback_varyings(&self, program: &Program, varyings: &[&str], mode: u32) {
+        let c_strs = varyings
+            .iter()
+            .map(|v| std::ffi::CString::new(*v).unwrap())
+            .collect::<Vec<_>>();
+        let pointers = c_strs.iter().map(|c| c.as_ptr()).collect::<Vec<_>>();
+        unsafe {
+            self.inner.TransformFeedbackVaryings(
+                program.0,
+                pointers.len() as i32,
+                pointers.as_ptr(),
+                mode,
+            );
+        }
+    }
+
     pub fn link_program(&self, program: &Program) -> bool {
         unsafe {
             self.inner.LinkProgram(program.0);
@@ -414,6 +638,25 @@ impl GLContext {
         }
     }
 
+    pub fn vertex_attrib_i_pointer(
+        &self,
+        location: AttributeLocation,
+        size: u32,
+        data_type: DataType,
+        stride: u32,
+        offset: u32,
+    ) {
+        unsafe {
+            self.inner.VertexAttribIPointer(
+                location.0 as consts::types::GLuint, // index of the generic vertex attribute
+                size as consts::types::GLint, // the number of components per generic vertex attribute
+                data_type.to_const(),         // data type
+                (stride * data_type.byte_size()) as consts::types::GLint, // stride (byte offset between consecutive attributes)
+                (offset * data_type.byte_size()) as *const consts::types::GLvoid, // offset of the first component
+            );
+        }
+    }
+
     pub fn vertex_attrib_divisor(&self, location: AttributeLocation, divisor: u32) {
         unsafe {
             self.inner.VertexAttribDivisor(
@@ -577,6 +820,43 @@ impl GLContext {
         }
     }
 
+    pub fn check_error(&self) -> Result<(), String> {
+        let error = unsafe { self.inner.GetError() };
+        error_name(error).map_or(Ok(()), Err)
+    }
+
+    pub fn get_vertex_attrib_parameter(&self, location: AttributeLocation, pname: u32) -> i32 {
+        let mut out = 0;
+        unsafe {
+            self.inner.GetVertexAttribiv(location.0, pname, &mut out);
+        }
+        out
+    }
+
+    ///
+    /// Enables or disables logging of the messages from the driver using the `KHR_debug` callback.
+    /// Returns false if the callback is not supported, for example if the context supports less than OpenGL 4.3 and not the `KHR_debug` extension.
+    /// Most drivers only send messages if the context is created with the debug flag.
+    ///
+    pub fn set_debug_output(&self, enabled: bool) -> bool {
+        if !self.inner.DebugMessageCallback.is_loaded() {
+            return false;
+        }
+        unsafe {
+            if enabled {
+                self.inner.Enable(consts::DEBUG_OUTPUT);
+                self.inner.Enable(consts::DEBUG_OUTPUT_SYNCHRONOUS);
+                self.inner
+                    .DebugMessageCallback(Some(debug_message_callback), std::ptr::null());
+            } else {
+                self.inner.Disable(consts::DEBUG_OUTPUT);
+                self.inner.Disable(consts::DEBUG_OUTPUT_SYNCHRONOUS);
+                self.inner.DebugMessageCallback(None, std::ptr::null());
+            }
+        }
+        true
+    }
+
     pub fn blit_framebuffer(
         &self,
         src_x0: u32,
@@ -642,6 +922,12 @@ impl GLContext {
         }
     }
 
+    pub fn pixel_storei(&self, pname: u32, param: i32) {
+        unsafe {
+            self.inner.PixelStorei(pname, param);
+        }
+    }
+
     pub fn blend_func(&self, sfactor: u32, dfactor: u32) {
         unsafe {
             self.inner.BlendFunc(sfactor, dfactor);
@@ -655,6 +941,44 @@ impl GLContext {
         }
     }
 
+    pub fn enable_draw_buffer(&self, cap: u32, draw_buffer: u32) {
+        unsafe {
+            self.inner.Enablei(cap, draw_buffer);
+        }
+    }
+
+    pub fn disable_draw_buffer(&self, cap: u32, draw_buffer: u32) {
+        unsafe {
+            self.inner.Disablei(cap, draw_buffer);
+        }
+    }
+
+    pub fn blend_func_separate_draw_buffer(
+        &self,
+        draw_buffer: u32,
+        src_rgb: u32,
+        dst_rgb: u32,
+        src_alpha: u32,
+        dst_alpha: u32,
+    ) {
+        unsafe {
+            self.inner
+                .BlendFuncSeparatei(draw_buffer, src_rgb, dst_rgb, src_alpha, dst_alpha);
+        }
+    }
+
+    pub fn blend_equation_separate_draw_buffer(
+        &self,
+        draw_buffer: u32,
+        mode_rgb: u32,
+        mode_alpha: u32,
+    ) {
+        unsafe {
+            self.inner
+                .BlendEquationSeparatei(draw_buffer, mode_rgb, mode_alpha);
+        }
+    }
+
     pub fn blend_equation(&self, mode: u32) {
         unsafe {
             self.inner.BlendEquation(mode);
@@ -673,6 +997,12 @@ impl GLContext {
         }
     }
 
+    pub fn front_face(&self, mode: u32) {
+        unsafe {
+            self.inner.FrontFace(mode);
+        }
+    }
+
     pub fn scissor(&self, x: i32, y: i32, width: i32, height: i32) {
         unsafe {
             self.inner.Scissor(x, y, width, height);
@@ -685,6 +1015,12 @@ impl GLContext {
         }
     }
 
+    pub fn clip_control(&self, origin: u32, depth: u32) {
+        unsafe {
+            self.inner.ClipControl(origin, depth);
+        }
+    }
+
     pub fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool) {
         unsafe {
             self.inner.ColorMask(
@@ -960,6 +1296,130 @@ impl GLContext {
         }
     }
 
+    pub fn tex_sub_image_3d_with_u8_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: DataType,
+        pixels: &[u8],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type.to_const(),
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
+    pub fn tex_sub_image_3d_with_u16_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: DataType,
+        pixels: &[u16],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type.to_const(),
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
+    pub fn tex_sub_image_3d_with_u32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: DataType,
+        pixels: &[u32],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type.to_const(),
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
+    pub fn tex_sub_image_3d_with_f32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: DataType,
+        pixels: &[f32],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type.to_const(),
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
     pub fn tex_image_3d(
         &self,
         target: u32,
@@ -1022,6 +1482,12 @@ impl GLContext {
         }
     }
 
+    pub fn tex_parameterf(&self, target: u32, pname: u32, param: f32) {
+        unsafe {
+            self.inner.TexParameterf(target, pname, param);
+        }
+    }
+
     pub fn delete_texture(&self, texture: &Texture) {
         unsafe {
             self.inner.DeleteTextures(1, &texture.0);
@@ -1204,6 +1670,28 @@ impl GLContext {
         }
     }
 
+    pub fn read_pixels_to_pixel_pack_buffer(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        format: u32,
+        data_type: DataType,
+    ) {
+        unsafe {
+            self.inner.ReadPixels(
+                x as i32,
+                y as i32,
+                width as i32,
+                height as i32,
+                format,
+                data_type.to_const(),
+                std::ptr::null_mut(), // offset into the pixel pack buffer
+            )
+        }
+    }
+
     pub fn flush(&self) {
         unsafe {
             self.inner.Flush();
@@ -1223,6 +1711,83 @@ impl GLContext {
             self.inner.DeleteSync(*sync);
         }
     }
+
+    pub fn begin_transform_feedback(&self, primitive_mode: u32) {
+        unsafe {
+            self.inner.BeginTransformFeedback(primitive_mode);
+        }
+    }
+
+    pub fn end_transform_feedback(&self) {
+        unsafe {
+            self.inner.EndTransformFeedback();
+        }
+    }
+
+    pub fn create_query(&self) -> Option<Query> {
+        let mut id: u32 = 0;
+        unsafe {
+            self.inner.GenQueries(1, &mut id);
+        }
+        Some(Query(id))
+    }
+
+    pub fn delete_query(&self, query: &Query) {
+        unsafe {
+            self.inner.DeleteQueries(1, [query.0].as_ptr());
+        }
+    }
+
+    pub fn begin_query(&self, target: u32, query: &Query) {
+        unsafe {
+            self.inner.BeginQuery(target, query.0);
+        }
+    }
+
+    pub fn end_query(&self, target: u32) {
+        unsafe {
+            self.inner.EndQuery(target);
+        }
+    }
+
+    pub fn get_query_parameter(&self, query: &Query, pname: u32) -> u32 {
+        let mut result = 0;
+        unsafe {
+            self.inner.GetQueryObjectuiv(query.0, pname, &mut result);
+        }
+        result
+    }
+}
+
+fn error_name(error: u32) -> Option<String> {
+    match error {
+        consts::NO_ERROR => None,
+        consts::INVALID_ENUM => Some("INVALID_ENUM".to_string()),
+        consts::INVALID_VALUE => Some("INVALID_VALUE".to_string()),
+        consts::INVALID_OPERATION => Some("INVALID_OPERATION".to_string()),
+        consts::INVALID_FRAMEBUFFER_OPERATION => Some("INVALID_FRAMEBUFFER_OPERATION".to_string()),
+        consts::OUT_OF_MEMORY => Some("OUT_OF_MEMORY".to_string()),
+        _ => Some(format!("Unknown error {:#x}", error)),
+    }
+}
+
+extern "system" fn debug_message_callback(
+    _source: u32,
+    _type: u32,
+    id: u32,
+    severity: u32,
+    _length: i32,
+    message: *const consts::types::GLchar,
+    _user_param: *mut std::os::raw::c_void,
+) {
+    let message = unsafe { std::ffi::CStr::from_ptr(message) }.to_string_lossy();
+    match severity {
+        consts::DEBUG_SEVERITY_HIGH => log::error!("OpenGL ({}): {}", id, message),
+        consts::DEBUG_SEVERITY_MEDIUM | consts::DEBUG_SEVERITY_LOW => {
+            log::warn!("OpenGL ({}): {}", id, message)
+        }
+        _ => log::debug!("OpenGL ({}): {}", id, message),
+    }
 }
 
 fn create_whitespace_cstring_with_len(len: usize) -> std::ffi::CString {
@@ -1238,6 +1803,7 @@ impl ShaderType {
     fn to_const(&self) -> u32 {
         match self {
             ShaderType::Vertex => consts::VERTEX_SHADER,
+            ShaderType::Geometry => consts::GEOMETRY_SHADER,
             ShaderType::Fragment => consts::FRAGMENT_SHADER,
         }
     }
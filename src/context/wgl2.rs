@@ -9,6 +9,7 @@ pub use web_sys::WebGlActiveInfo as ActiveInfo;
 pub use web_sys::WebGlBuffer as Buffer;
 pub use web_sys::WebGlFramebuffer as Framebuffer;
 pub use web_sys::WebGlProgram as Program;
+pub use web_sys::WebGlQuery as Query;
 pub use web_sys::WebGlShader as Shader;
 pub use web_sys::WebGlSync as Sync;
 pub use web_sys::WebGlTexture as Texture;
@@ -31,10 +32,22 @@ impl GLContext {
         self.inner.finish();
     }
 
+    pub fn flush(&self) {
+        self.inner.flush();
+    }
+
+    pub fn is_context_lost(&self) -> bool {
+        self.inner.is_context_lost()
+    }
+
     pub fn bind_buffer_base(&self, target: u32, index: u32, buffer: &Buffer) {
         self.inner.bind_buffer_base(target, index, Some(buffer));
     }
 
+    pub fn unbind_buffer_base(&self, target: u32, index: u32) {
+        self.inner.bind_buffer_base(target, index, None);
+    }
+
     pub fn bind_buffer(&self, target: u32, buffer: &Buffer) {
         self.inner.bind_buffer(target, Some(buffer));
     }
@@ -98,6 +111,124 @@ impl GLContext {
             .buffer_data_with_array_buffer_view(target, &array, usage);
     }
 
+    pub fn buffer_sub_data_u8(&self, target: u32, offset_in_bytes: u32, data: &[u8]) {
+        self.inner
+            .buffer_sub_data_with_i32_and_u8_array(target, offset_in_bytes as i32, data)
+    }
+
+    pub fn buffer_sub_data_u16(&self, target: u32, offset_in_bytes: u32, data: &[u16]) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = data.as_ptr() as u32 / 2;
+        let array = js_sys::Uint16Array::new(&memory_buffer)
+            .subarray(data_location, data_location + data.len() as u32);
+
+        self.inner.buffer_sub_data_with_i32_and_array_buffer_view(
+            target,
+            offset_in_bytes as i32,
+            &array,
+        );
+    }
+
+    pub fn buffer_sub_data_u32(&self, target: u32, offset_in_bytes: u32, data: &[u32]) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = data.as_ptr() as u32 / 4;
+        let array = js_sys::Uint32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + data.len() as u32);
+
+        self.inner.buffer_sub_data_with_i32_and_array_buffer_view(
+            target,
+            offset_in_bytes as i32,
+            &array,
+        );
+    }
+
+    pub fn buffer_sub_data_f32(&self, target: u32, offset_in_bytes: u32, data: &[f32]) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = data.as_ptr() as u32 / 4;
+        let array = js_sys::Float32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + data.len() as u32);
+
+        self.inner.buffer_sub_data_with_i32_and_array_buffer_view(
+            target,
+            offset_in_bytes as i32,
+            &array,
+        );
+    }
+
+    pub fn get_buffer_sub_data_u8(&self, target: u32, offset_in_bytes: u32, dst_data: &mut [u8]) {
+        self.inner.get_buffer_sub_data_with_i32_and_u8_array(
+            target,
+            offset_in_bytes as i32,
+            dst_data,
+        );
+    }
+
+    pub fn get_buffer_sub_data_u16(&self, target: u32, offset_in_bytes: u32, dst_data: &mut [u16]) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = dst_data.as_ptr() as u32 / 2;
+        let array = js_sys::Uint16Array::new(&memory_buffer)
+            .subarray(data_location, data_location + dst_data.len() as u32);
+
+        self.inner
+            .get_buffer_sub_data_with_i32_and_array_buffer_view(
+                target,
+                offset_in_bytes as i32,
+                &array,
+            );
+    }
+
+    pub fn get_buffer_sub_data_u32(&self, target: u32, offset_in_bytes: u32, dst_data: &mut [u32]) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = dst_data.as_ptr() as u32 / 4;
+        let array = js_sys::Uint32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + dst_data.len() as u32);
+
+        self.inner
+            .get_buffer_sub_data_with_i32_and_array_buffer_view(
+                target,
+                offset_in_bytes as i32,
+                &array,
+            );
+    }
+
+    pub fn get_buffer_sub_data_f32(&self, target: u32, offset_in_bytes: u32, dst_data: &mut [f32]) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = dst_data.as_ptr() as u32 / 4;
+        let array = js_sys::Float32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + dst_data.len() as u32);
+
+        self.inner
+            .get_buffer_sub_data_with_i32_and_array_buffer_view(
+                target,
+                offset_in_bytes as i32,
+                &array,
+            );
+    }
+
     pub fn create_shader(&self, type_: ShaderType) -> Option<Shader> {
         self.inner.create_shader(type_.to_const())
     }
@@ -125,6 +256,15 @@ impl GLContext {
         self.inner.create_program().unwrap()
     }
 
+    pub fn transform_feedback_varyings(&self, program: &Program, varyings: &[&str], mode: u32) {
+        let array = varyings
+            .iter()
+            .map(|v| wasm_bindgen::JsValue::from_str(v))
+            .collect::<js_sys::Array>();
+        self.inner
+            .transform_feedback_varyings(program, &array.into(), mode);
+    }
+
     pub fn link_program(&self, program: &Program) -> bool {
         self.inner.link_program(program);
         self.inner
@@ -133,10 +273,22 @@ impl GLContext {
             .unwrap_or(false)
     }
 
+    pub fn create_vertex_array(&self) -> Option<VertexArrayObject> {
+        self.inner.create_vertex_array()
+    }
+
     pub fn bind_vertex_array(&self, array: &VertexArrayObject) {
         self.inner.bind_vertex_array(Some(array));
     }
 
+    pub fn unbind_vertex_array(&self) {
+        self.inner.bind_vertex_array(None);
+    }
+
+    pub fn delete_vertex_array(&self, array: &VertexArrayObject) {
+        self.inner.delete_vertex_array(Some(array));
+    }
+
     pub fn delete_texture(&self, texture: &Texture) {
         self.inner.delete_texture(Some(texture));
     }
@@ -207,6 +359,157 @@ impl GLContext {
             .unwrap();
     }
 
+    pub fn tex_sub_image_3d_with_u8_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: DataType,
+        pixels: &[u8],
+    ) {
+        self.inner
+            .tex_sub_image_3d_with_opt_u8_array(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type.to_const(),
+                Some(pixels),
+            )
+            .unwrap();
+    }
+
+    pub fn tex_sub_image_3d_with_u16_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: DataType,
+        pixels: &[u16],
+    ) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = pixels.as_ptr() as u32 / 2;
+        let array = js_sys::Uint16Array::new(&memory_buffer)
+            .subarray(data_location, data_location + pixels.len() as u32);
+
+        self.inner
+            .tex_sub_image_3d_with_opt_array_buffer_view(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type.to_const(),
+                Some(&array),
+            )
+            .unwrap();
+    }
+
+    pub fn tex_sub_image_3d_with_u32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: DataType,
+        pixels: &[u32],
+    ) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = pixels.as_ptr() as u32 / 4;
+        let array = js_sys::Uint32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + pixels.len() as u32);
+
+        self.inner
+            .tex_sub_image_3d_with_opt_array_buffer_view(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type.to_const(),
+                Some(&array),
+            )
+            .unwrap();
+    }
+
+    pub fn tex_sub_image_3d_with_f32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: DataType,
+        pixels: &[f32],
+    ) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = pixels.as_ptr() as u32 / 4;
+        let array = js_sys::Float32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + pixels.len() as u32);
+
+        self.inner
+            .tex_sub_image_3d_with_opt_array_buffer_view(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type.to_const(),
+                Some(&array),
+            )
+            .unwrap();
+    }
+
     pub fn tex_sub_image_2d_with_u8_data(
         &self,
         target: u32,
@@ -477,6 +780,28 @@ impl GLContext {
         );
     }
 
+    pub fn read_pixels_to_pixel_pack_buffer(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        format: u32,
+        data_type: DataType,
+    ) {
+        self.inner
+            .read_pixels_with_i32(
+                x as i32,
+                y as i32,
+                width as i32,
+                height as i32,
+                format,
+                data_type.to_const(),
+                0, // offset into the pixel pack buffer
+            )
+            .unwrap()
+    }
+
     pub fn read_pixels_with_u8_data(
         &self,
         x: u32,
@@ -627,8 +952,12 @@ impl GLContext {
     }
 
     pub fn draw_elements(&self, mode: u32, count: u32, data_type: DataType, offset: u32) {
-        self.inner
-            .draw_elements_with_i32(mode, count as i32, data_type.to_const(), offset as i32);
+        self.inner.draw_elements_with_i32(
+            mode,
+            count as i32,
+            data_type.to_const(),
+            (offset * data_type.byte_size()) as i32, // the offset in bytes of the first index
+        );
     }
 
     pub fn draw_elements_instanced(
@@ -643,7 +972,7 @@ impl GLContext {
             mode,
             count as i32,
             data_type.to_const(),
-            offset as i32,
+            (offset * data_type.byte_size()) as i32, // the offset in bytes of the first index
             instance_count as i32,
         );
     }
@@ -707,6 +1036,29 @@ impl GLContext {
         }
     }
 
+    pub fn check_error(&self) -> Result<(), String> {
+        match self.inner.get_error() {
+            consts::NO_ERROR => Ok(()),
+            consts::INVALID_ENUM => Err("INVALID_ENUM".to_string()),
+            consts::INVALID_VALUE => Err("INVALID_VALUE".to_string()),
+            consts::INVALID_OPERATION => Err("INVALID_OPERATION".to_string()),
+            consts::INVALID_FRAMEBUFFER_OPERATION => {
+                Err("INVALID_FRAMEBUFFER_OPERATION".to_string())
+            }
+            consts::OUT_OF_MEMORY => Err("OUT_OF_MEMORY".to_string()),
+            consts::CONTEXT_LOST_WEBGL => Err("CONTEXT_LOST_WEBGL".to_string()),
+            error => Err(format!("Unknown error {:#x}", error)),
+        }
+    }
+
+    pub fn get_vertex_attrib_parameter(&self, location: AttributeLocation, pname: u32) -> i32 {
+        self.inner
+            .get_vertex_attrib(location, pname)
+            .ok()
+            .and_then(|v| v.as_f64().or_else(|| v.as_bool().map(|b| b as i32 as f64)))
+            .unwrap_or(0.0) as i32
+    }
+
     pub fn uniform1i(&self, location: &UniformLocation, data: i32) {
         self.inner.uniform1i(Some(location), data);
     }
@@ -769,11 +1121,63 @@ impl GLContext {
         );
     }
 
+    pub fn vertex_attrib_i_pointer(
+        &self,
+        location: AttributeLocation,
+        size: u32,
+        data_type: DataType,
+        stride: u32,
+        offset: u32,
+    ) {
+        self.inner.vertex_attrib_i_pointer_with_i32(
+            location,
+            size as i32,
+            data_type.to_const(),
+            (stride * data_type.byte_size()) as i32,
+            (offset * data_type.byte_size()) as i32,
+        );
+    }
+
     pub fn get_program_parameter(&self, program: &Program, pname: u32) -> u32 {
         let result = self.inner.get_program_parameter(program, pname);
         result.as_f64().unwrap() as u32
     }
 
+    pub fn get_integer(&self, pname: u32) -> i32 {
+        self.inner
+            .get_parameter(pname)
+            .ok()
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as i32
+    }
+
+    pub fn get_float(&self, pname: u32) -> f32 {
+        self.inner
+            .get_parameter(pname)
+            .ok()
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32
+    }
+
+    pub fn get_string(&self, pname: u32) -> String {
+        self.inner
+            .get_parameter(pname)
+            .ok()
+            .and_then(|v| v.as_string())
+            .unwrap_or_default()
+    }
+
+    pub fn get_supported_extensions(&self) -> Vec<String> {
+        self.inner
+            .get_supported_extensions()
+            .map(|extensions| extensions.iter().filter_map(|e| e.as_string()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn enable_extension(&self, name: &str) -> bool {
+        self.inner.get_extension(name).ok().flatten().is_some()
+    }
+
     pub fn get_active_attrib(&self, program: &Program, index: u32) -> ActiveInfo {
         self.inner.get_active_attrib(program, index).unwrap()
     }
@@ -795,6 +1199,66 @@ impl GLContext {
     pub fn delete_sync(&self, sync: &Sync) {
         self.inner.delete_sync(Some(sync));
     }
+
+    pub fn begin_transform_feedback(&self, primitive_mode: u32) {
+        self.inner.begin_transform_feedback(primitive_mode);
+    }
+
+    pub fn end_transform_feedback(&self) {
+        self.inner.end_transform_feedback();
+    }
+
+    pub fn create_query(&self) -> Option<Query> {
+        self.inner.create_query()
+    }
+
+    pub fn delete_query(&self, query: &Query) {
+        self.inner.delete_query(Some(query));
+    }
+
+    pub fn begin_query(&self, target: u32, query: &Query) {
+        self.inner.begin_query(target, query);
+    }
+
+    pub fn end_query(&self, target: u32) {
+        self.inner.end_query(target);
+    }
+
+    // The result is null until it is available, which on web is never before control is returned to the browser
+    pub fn get_query_parameter(&self, query: &Query, pname: u32) -> u32 {
+        let result = self.inner.get_query_parameter(query, pname);
+        if let Some(available) = result.as_bool() {
+            available as u32
+        } else {
+            result.as_f64().unwrap_or(0.0) as u32
+        }
+    }
+
+    // WebGL2 does not support changing the clip control, see Capabilities::clip_control
+    pub fn clip_control(&self, _origin: u32, _depth: u32) {}
+
+    // WebGL2 does not support blending each draw buffer individually, see Capabilities::draw_buffers_blend
+    pub fn enable_draw_buffer(&self, _cap: u32, _draw_buffer: u32) {}
+
+    pub fn disable_draw_buffer(&self, _cap: u32, _draw_buffer: u32) {}
+
+    pub fn blend_func_separate_draw_buffer(
+        &self,
+        _draw_buffer: u32,
+        _src_rgb: u32,
+        _dst_rgb: u32,
+        _src_alpha: u32,
+        _dst_alpha: u32,
+    ) {
+    }
+
+    pub fn blend_equation_separate_draw_buffer(
+        &self,
+        _draw_buffer: u32,
+        _mode_rgb: u32,
+        _mode_alpha: u32,
+    ) {
+    }
 }
 
 impl std::ops::Deref for GLContext {
@@ -809,6 +1273,8 @@ impl ShaderType {
     fn to_const(&self) -> u32 {
         match self {
             ShaderType::Vertex => consts::VERTEX_SHADER,
+            // WebGL2 does not support geometry shaders, so creating one fails, see Capabilities::geometry_shader
+            ShaderType::Geometry => 0,
             ShaderType::Fragment => consts::FRAGMENT_SHADER,
         }
     }
@@ -181,7 +181,12 @@ pub(in crate::core) mod internal_uniform {
 }
 
 use crate::context::GLContext;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+
+// Defined by the ARB_clip_control extension
+const CLIP_CONTROL_LOWER_LEFT: u32 = 0x8CA1;
+const CLIP_CONTROL_NEGATIVE_ONE_TO_ONE: u32 = 0x935E;
+const CLIP_CONTROL_ZERO_TO_ONE: u32 = 0x935F;
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -194,7 +199,22 @@ pub struct Context {
     programs: Rc<RefCell<HashMap<String, Program>>>,
     effects: Rc<RefCell<HashMap<String, ImageEffect>>>,
     camera2d: Rc<RefCell<Option<Camera>>>,
-    dummy_tex: Rc<RefCell<Option<Texture2D<u8>>>>,
+    data_textures: Rc<RefCell<HashMap<String, (Vec<f32>, Texture2D<f32>)>>>,
+    hdr_output: Rc<Cell<bool>>,
+    depth_mode: Rc<Cell<DepthMode>>,
+    capabilities: Rc<Capabilities>,
+    program_cache_statistics: Rc<Cell<ProgramCacheStatistics>>,
+    vertex_array_bound: Rc<Cell<bool>>,
+    bound_program: Rc<Cell<Option<u64>>>,
+    draw_framebuffer: Rc<RefCell<Option<crate::context::Framebuffer>>>,
+    next_id: Rc<Cell<u64>>,
+    offscreen_screen: Rc<RefCell<Option<(Texture2D<u8>, DepthTargetTexture2D)>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    program_binary_directory: Rc<RefCell<Option<std::path::PathBuf>>>,
+    validation: Rc<Cell<bool>>,
+    validation_error: Rc<RefCell<Option<CoreError>>>,
+    #[cfg(debug_assertions)]
+    fences: Rc<Cell<u64>>,
 }
 
 impl Context {
@@ -202,15 +222,227 @@ impl Context {
     /// Creates a new context from a [OpenGL/WebGL context](GLContext).
     ///
     pub fn from_gl_context(context: GLContext) -> Self {
-        Self {
+        // Filter across the edges of the cube map sides to avoid visible seams, which is always the case on web
+        #[cfg(not(target_arch = "wasm32"))]
+        context.enable(crate::context::consts::TEXTURE_CUBE_MAP_SEAMLESS);
+        let context = Self {
+            capabilities: Rc::new(Capabilities::new(&context)),
             context,
             programs: Rc::new(RefCell::new(HashMap::new())),
             effects: Rc::new(RefCell::new(HashMap::new())),
             camera2d: Rc::new(RefCell::new(None)),
-            dummy_tex: Rc::new(RefCell::new(None)),
+            data_textures: Rc::new(RefCell::new(HashMap::new())),
+            hdr_output: Rc::new(Cell::new(false)),
+            depth_mode: Rc::new(Cell::new(DepthMode::Standard)),
+            program_cache_statistics: Rc::new(Cell::new(ProgramCacheStatistics::default())),
+            vertex_array_bound: Rc::new(Cell::new(false)),
+            bound_program: Rc::new(Cell::new(None)),
+            draw_framebuffer: Rc::new(RefCell::new(None)),
+            next_id: Rc::new(Cell::new(0)),
+            offscreen_screen: Rc::new(RefCell::new(None)),
+            #[cfg(not(target_arch = "wasm32"))]
+            program_binary_directory: Rc::new(RefCell::new(None)),
+            validation: Rc::new(Cell::new(false)),
+            validation_error: Rc::new(RefCell::new(None)),
+            #[cfg(debug_assertions)]
+            fences: Rc::new(Cell::new(0)),
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        if std::env::var_os("THREE_D_DEBUG").is_some() {
+            context.set_validation(true);
+        }
+        context
+    }
+
+    ///
+    /// Creates a new context from an OpenGL context created outside of `three-d`, for example by another windowing library like SDL2,
+    /// where the given loader returns the address of the OpenGL function with the given name.
+    /// The OpenGL context must be current and support at least OpenGL 3.3 core.
+    /// If the OpenGL context is also used by another renderer, call [Context::restore_state_assumptions] before rendering with `three-d`.
+    /// Only available on desktop.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_gl_loader(mut loader: impl FnMut(&str) -> *const std::os::raw::c_void) -> Self {
+        Self::from_gl_context(GLContext::load_with(|name| loader(name)))
+    }
+
+    ///
+    /// Creates a new context from a WebGL2 context created outside of `three-d`, for example from a canvas owned by another library.
+    /// If the WebGL2 context is also used by another renderer, call [Context::restore_state_assumptions] before rendering with `three-d`.
+    /// Only available on web.
+    ///
+    /// # Errors
+    /// Will return an error if the extensions needed for rendering to floating point textures are not supported.
+    ///
+    #[cfg(target_arch = "wasm32")]
+    pub fn from_webgl2_context(context: web_sys::WebGl2RenderingContext) -> ThreeDResult<Self> {
+        for extension in [
+            "EXT_color_buffer_float",
+            "OES_texture_float",
+            "OES_texture_float_linear",
+        ]
+        .iter()
+        {
+            context
+                .get_extension(extension)
+                .map_err(|e| CoreError::ExtensionNotSupported(format!("{}: {:?}", extension, e)))?;
+        }
+        Ok(Self::from_gl_context(GLContext::new(context)))
+    }
+
+    ///
+    /// Sets the parts of the OpenGL/WebGL state which `three-d` assumes are unchanged between its draw calls
+    /// and resets the cached [RenderStates] and viewport, such that they are set again before the next draw call.
+    /// Call this before rendering with `three-d` when the context is shared with another renderer which might have changed the state,
+    /// for example when `three-d` is embedded in an application with its own renderer, see [Context::from_gl_loader].
+    ///
+    /// The state which is set is:
+    /// - No program, array buffer or element array buffer is bound and the default vertex array is bound.
+    /// - Texture unit 0 is active.
+    /// - Scissor, stencil, polygon offset and alpha to coverage tests are disabled and so is rasterizer discard.
+    /// - Blending, culling and depth testing are disabled, all channels are written and the depth test is `Less`, which is what the cached render states expect.
+    /// - Counter-clockwise triangles are front facing.
+    /// - The pack and unpack alignment is 4 bytes.
+    /// - Seamless filtering of cube maps is enabled (desktop only).
+    /// - The clip control matches the depth mode, see [Context::set_depth_mode] (desktop only).
+    ///
+    /// Everything else, for example the bound framebuffer, textures and uniform buffers, is set by `three-d` when needed.
+    ///
+    pub fn restore_state_assumptions(&self) {
+        use crate::context::consts;
+        self.context.unuse_program();
+        self.set_bound_program(None);
+        self.context.unbind_buffer(consts::ARRAY_BUFFER);
+        self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
+        self.context.unbind_vertex_array();
+        self.set_vertex_array_bound(false);
+        self.context.active_texture(consts::TEXTURE0);
+        self.context.disable(consts::STENCIL_TEST);
+        self.context.disable(consts::POLYGON_OFFSET_FILL);
+        self.context.disable(consts::SAMPLE_ALPHA_TO_COVERAGE);
+        self.context.disable(consts::RASTERIZER_DISCARD);
+        Program::reset_states(self);
+        self.set_clockwise_front_face(false);
+        self.context.pixel_storei(consts::PACK_ALIGNMENT, 4);
+        self.context.pixel_storei(consts::UNPACK_ALIGNMENT, 4);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.context.enable(consts::TEXTURE_CUBE_MAP_SEAMLESS);
+        self.set_clip_control();
+    }
+
+    ///
+    /// Sets how the distance from the camera is mapped to the depth values in the depth buffer for everything rendered with this context afterwards.
+    /// Use [DepthMode::ReversedZ] to avoid z-fighting in scenes with a very large ratio between the far and the near plane of the camera.
+    /// The projection of all cameras, the depth tests and the depth clear values follow the depth mode,
+    /// while shadow maps are always rendered in the standard depth mode.
+    ///
+    pub fn set_depth_mode(&self, depth_mode: DepthMode) {
+        if self.depth_mode.get() != depth_mode {
+            self.depth_mode.set(depth_mode);
+            self.set_clip_control();
+        }
+    }
+
+    ///
+    /// Returns the depth mode, see [Context::set_depth_mode].
+    ///
+    pub fn depth_mode(&self) -> DepthMode {
+        self.depth_mode.get()
+    }
+
+    ///
+    /// Calls the given callback in the standard depth mode and sets the depth mode back afterwards,
+    /// which is used for rendering depth textures that are sampled independently of the depth mode, for example shadow maps.
+    ///
+    pub(crate) fn with_standard_depth_mode<T>(&self, callback: impl FnOnce() -> T) -> T {
+        let depth_mode = self.depth_mode();
+        self.set_depth_mode(DepthMode::Standard);
+        let result = callback();
+        self.set_depth_mode(depth_mode);
+        result
+    }
+
+    // Maps the normalized device coordinates to the depth range [0..1] in the reversed depth mode if clip control is supported
+    fn set_clip_control(&self) {
+        if self.capabilities.clip_control {
+            self.context.clip_control(
+                CLIP_CONTROL_LOWER_LEFT,
+                if self.depth_mode() == DepthMode::ReversedZ {
+                    CLIP_CONTROL_ZERO_TO_ONE
+                } else {
+                    CLIP_CONTROL_NEGATIVE_ONE_TO_ONE
+                },
+            );
+        }
+    }
+
+    ///
+    /// Enables or disables the validation mode, which is meant for debugging and is disabled by default.
+    /// It is also enabled by [WindowSettings::debug](crate::WindowSettings::debug) and, on desktop, by setting the `THREE_D_DEBUG` environment variable.
+    ///
+    /// When enabled, [Context::check_errors] reports OpenGL/WebGL errors and each draw call checks that all active attributes of the program
+    /// are given a buffer, so for example a missing attribute is reported as an error instead of rendering nothing.
+    /// Uniforms which are used by the program but never set are logged as warnings together with their declaration in the shader source.
+    /// On desktop, the messages from the driver are also logged using the `KHR_debug` callback if it is supported.
+    /// Enable the validation before creating any programs, since only the uniforms set while it is enabled are tracked.
+    ///
+    /// When disabled, the only overhead is checking whether it is enabled.
+    ///
+    pub fn set_validation(&self, enabled: bool) {
+        self.validation.set(enabled);
+        *self.validation_error.borrow_mut() = None;
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.context.set_debug_output(enabled) && enabled {
+            log::info!("the driver messages are not logged since KHR_debug is not supported");
+        }
+    }
+
+    ///
+    /// Returns whether or not the validation mode is enabled, see [Context::set_validation].
+    ///
+    #[inline]
+    pub fn is_validation_enabled(&self) -> bool {
+        self.validation.get()
+    }
+
+    ///
+    /// Returns an error if a draw call since the last check failed the validation or if an OpenGL/WebGL error has occurred since the last check,
+    /// where the given operation, for example the name of the function, is used in the error message.
+    /// Does nothing unless the validation mode is enabled, see [Context::set_validation].
+    ///
+    #[inline]
+    pub fn check_errors(&self, operation: &str) -> ThreeDResult<()> {
+        if self.validation.get() {
+            self.check_errors_internal(operation)?;
+        }
+        Ok(())
+    }
+
+    fn check_errors_internal(&self, operation: &str) -> ThreeDResult<()> {
+        if let Some(error) = self.validation_error.borrow_mut().take() {
+            Err(error)?;
+        }
+        self.context
+            .check_error()
+            .map_err(|error| CoreError::GLError(error, operation.to_string()))?;
+        Ok(())
+    }
+
+    // Stores the first validation error of a draw call, which is returned by the next call to check_errors
+    pub(crate) fn set_validation_error(&self, error: CoreError) {
+        let mut validation_error = self.validation_error.borrow_mut();
+        if validation_error.is_none() {
+            *validation_error = Some(error);
         }
     }
 
+    ///
+    /// Returns the capabilities and limits of the graphics context, which are queried once when the context is created.
+    ///
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
     ///
     /// Compiles a [Program] with the given vertex and fragment shader source and stores it for later use.
     /// If it has already been created, then it is just returned.
@@ -221,11 +453,15 @@ impl Context {
         fragment_shader_source: &str,
         callback: impl FnOnce(&Program) -> ThreeDResult<()>,
     ) -> ThreeDResult<()> {
+        let fragment_shader_source = self.fragment_shader_source(fragment_shader_source);
         let key = format!("{}{}", vertex_shader_source, fragment_shader_source);
-        if !self.programs.borrow().contains_key(&key) {
+        if self.programs.borrow().contains_key(&key) {
+            self.update_program_cache_statistics(|s| s.hits += 1);
+        } else {
+            self.update_program_cache_statistics(|s| s.misses += 1);
             self.programs.borrow_mut().insert(
                 key.clone(),
-                Program::from_source(self, vertex_shader_source, fragment_shader_source)?,
+                Program::from_source(self, vertex_shader_source, &fragment_shader_source)?,
             );
         };
         callback(self.programs.borrow().get(&key).unwrap())
@@ -240,13 +476,159 @@ impl Context {
         fragment_shader_source: &str,
         callback: impl FnOnce(&ImageEffect) -> ThreeDResult<()>,
     ) -> ThreeDResult<()> {
-        if !self.effects.borrow().contains_key(fragment_shader_source) {
+        let fragment_shader_source = self.fragment_shader_source(fragment_shader_source);
+        if self.effects.borrow().contains_key(&fragment_shader_source) {
+            self.update_program_cache_statistics(|s| s.hits += 1);
+        } else {
+            self.update_program_cache_statistics(|s| s.misses += 1);
             self.effects.borrow_mut().insert(
-                fragment_shader_source.to_string(),
-                ImageEffect::new(self, fragment_shader_source)?,
+                fragment_shader_source.clone(),
+                ImageEffect::new(self, &fragment_shader_source)?,
             );
         };
-        callback(self.effects.borrow().get(fragment_shader_source).unwrap())
+        callback(self.effects.borrow().get(&fragment_shader_source).unwrap())
+    }
+
+    ///
+    /// Redirects everything written to and read from the [Screen] into an offscreen color and depth texture with the given width and height,
+    /// or back to the default framebuffer if `None` is given.
+    /// This makes it possible to run code written for a window with a headless context, where there is no default framebuffer to render into.
+    /// The textures are only created again if the size changes.
+    ///
+    pub fn set_offscreen_screen_size(&self, size: Option<(u32, u32)>) -> ThreeDResult<()> {
+        let mut offscreen_screen = self.offscreen_screen.borrow_mut();
+        match size {
+            Some((width, height)) => {
+                if offscreen_screen
+                    .as_ref()
+                    .map(|(color, _)| color.width() != width || color.height() != height)
+                    .unwrap_or(true)
+                {
+                    *offscreen_screen = Some((
+                        Texture2D::new_empty(
+                            self,
+                            width,
+                            height,
+                            Interpolation::Nearest,
+                            Interpolation::Nearest,
+                            None,
+                            Wrapping::ClampToEdge,
+                            Wrapping::ClampToEdge,
+                            Format::RGBA,
+                        )?,
+                        DepthTargetTexture2D::new(
+                            self,
+                            width,
+                            height,
+                            Wrapping::ClampToEdge,
+                            Wrapping::ClampToEdge,
+                            DepthFormat::Depth32F,
+                        )?,
+                    ));
+                }
+            }
+            None => *offscreen_screen = None,
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the width and height of the offscreen textures replacing the default framebuffer, if any, see [Context::set_offscreen_screen_size].
+    ///
+    pub fn offscreen_screen_size(&self) -> Option<(u32, u32)> {
+        self.offscreen_screen
+            .borrow()
+            .as_ref()
+            .map(|(color, _)| (color.width(), color.height()))
+    }
+
+    ///
+    /// Returns the color and depth texture which replaces the default framebuffer, see [Context::set_offscreen_screen_size].
+    ///
+    pub(crate) fn offscreen_screen(
+        &self,
+    ) -> std::cell::Ref<'_, Option<(Texture2D<u8>, DepthTargetTexture2D)>> {
+        self.offscreen_screen.borrow()
+    }
+
+    ///
+    /// Specifies whether or not the programs and effects returned by [Context::program] and [Context::effect]
+    /// should output linear high dynamic range colors instead of tone mapped sRGB colors, see [HdrPipeline](crate::HdrPipeline).
+    ///
+    pub(crate) fn set_hdr_output(&self, hdr_output: bool) {
+        self.hdr_output.set(hdr_output);
+    }
+
+    ///
+    /// Returns whether or not a [VertexArray] is bound, in which case the attributes are recorded in the vertex array
+    /// and should not be disabled after drawing.
+    ///
+    pub(crate) fn is_vertex_array_bound(&self) -> bool {
+        self.vertex_array_bound.get()
+    }
+
+    pub(crate) fn set_vertex_array_bound(&self, bound: bool) {
+        self.vertex_array_bound.set(bound);
+    }
+
+    ///
+    /// Returns the unique id of the currently bound [Program] or `None` if no program is bound.
+    ///
+    pub(crate) fn bound_program(&self) -> Option<u64> {
+        self.bound_program.get()
+    }
+
+    pub(crate) fn set_bound_program(&self, program: Option<u64>) {
+        self.bound_program.set(program);
+    }
+
+    ///
+    /// Binds the given framebuffer, or the default framebuffer if `None`, as the draw framebuffer while calling the `render` closure
+    /// and binds the previous draw framebuffer again afterwards, so a render target can be written to in the render closure of another render target.
+    ///
+    pub(crate) fn write_framebuffer<T>(
+        &self,
+        framebuffer: Option<&crate::context::Framebuffer>,
+        render: impl FnOnce() -> ThreeDResult<T>,
+    ) -> ThreeDResult<T> {
+        self.context
+            .bind_framebuffer(crate::context::consts::DRAW_FRAMEBUFFER, framebuffer);
+        let previous = self.draw_framebuffer.replace(framebuffer.cloned());
+        let result = render();
+        self.context
+            .bind_framebuffer(crate::context::consts::DRAW_FRAMEBUFFER, previous.as_ref());
+        self.draw_framebuffer.replace(previous);
+        result
+    }
+
+    ///
+    /// Specifies whether the front facing triangles are the triangles with clockwise instead of counter-clockwise winding order.
+    /// Must be enabled when rendering with a mirrored camera, since a reflection reverses the winding order,
+    /// otherwise back faces are culled instead of front faces, see [Camera::is_mirrored].
+    ///
+    pub(crate) fn set_clockwise_front_face(&self, clockwise: bool) {
+        self.context.front_face(if clockwise {
+            crate::context::consts::CW
+        } else {
+            crate::context::consts::CCW
+        });
+    }
+
+    ///
+    /// Returns a new id which is unique for this context, used for identifying programs and buffers.
+    ///
+    pub(crate) fn next_id(&self) -> u64 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    fn fragment_shader_source(&self, fragment_shader_source: &str) -> String {
+        if self.hdr_output.get() {
+            format!("#define HDR_OUTPUT\n{}", fragment_shader_source)
+        } else {
+            fragment_shader_source.to_string()
+        }
     }
 
     ///
@@ -256,48 +638,136 @@ impl Context {
         &self,
         viewport: Viewport,
         callback: impl FnOnce(&Camera) -> ThreeDResult<()>,
+    ) -> ThreeDResult<()> {
+        self.camera2d_at(viewport, Anchor::TopLeft, callback)
+    }
+
+    // Calls the callback with a 2D camera with the y-coordinate increasing downwards and the origin at the given anchor, see Camera::new_2d
+    pub(crate) fn camera2d_at(
+        &self,
+        viewport: Viewport,
+        anchor: Anchor,
+        callback: impl FnOnce(&Camera) -> ThreeDResult<()>,
     ) -> ThreeDResult<()> {
         if self.camera2d.borrow().is_none() {
-            *self.camera2d.borrow_mut() = Some(Camera::new_orthographic(
-                self,
-                viewport,
-                vec3(0.0, 0.0, -1.0),
-                vec3(0.0, 0.0, 0.0),
-                vec3(0.0, -1.0, 0.0),
-                1.0,
-                0.0,
-                10.0,
-            )?)
+            *self.camera2d.borrow_mut() = Some(Camera::new_2d(self, viewport, YDirection::Down)?)
         }
         let mut camera2d = self.camera2d.borrow_mut();
-        camera2d.as_mut().unwrap().set_viewport(viewport)?;
-        camera2d.as_mut().unwrap().set_orthographic_projection(
-            viewport.height as f32,
-            0.0,
-            10.0,
-        )?;
-        camera2d.as_mut().unwrap().set_view(
-            vec3(
-                viewport.width as f32 * 0.5,
-                viewport.height as f32 * 0.5,
-                -1.0,
-            ),
-            vec3(
-                viewport.width as f32 * 0.5,
-                viewport.height as f32 * 0.5,
-                0.0,
-            ),
-            vec3(0.0, -1.0, 0.0),
-        )?;
-        callback(camera2d.as_ref().unwrap())
-    }
-
-    pub(crate) fn use_texture_dummy(&self, program: &Program, name: &str) -> ThreeDResult<()> {
-        if self.dummy_tex.borrow().is_none() {
-            *self.dummy_tex.borrow_mut() =
-                Some(Texture2D::new(&self, &CPUTexture::<u8>::default())?);
-        }
-        program.use_texture(name, (*self.dummy_tex.borrow()).as_ref().unwrap())
+        let camera2d = camera2d.as_mut().unwrap();
+        camera2d.set_viewport(viewport)?;
+        if camera2d.view_2d() != Some((YDirection::Down, anchor)) {
+            camera2d.set_view_2d(YDirection::Down, anchor)?;
+        }
+        callback(camera2d)
+    }
+
+    ///
+    /// Sends all of the commands issued so far to the GPU without waiting for them to finish.
+    ///
+    pub fn flush(&self) {
+        self.context.flush();
+    }
+
+    ///
+    /// Blocks until the GPU has finished all of the commands issued so far.
+    /// This stalls the CPU, so use a [FenceSync] constructed using [Context::fence] instead when possible.
+    ///
+    pub fn finish(&self) {
+        self.context.finish();
+        self.count_fence();
+    }
+
+    ///
+    /// Inserts a fence after the commands issued so far, which is signaled when the GPU has finished those commands, see [FenceSync].
+    /// The commands are flushed, so the fence is signaled eventually.
+    ///
+    pub fn fence(&self) -> FenceSync {
+        FenceSync::new(self)
+    }
+
+    // Called when the CPU waits for or inserts a fence, after which resources written before are synchronized, see SyncPoint
+    pub(in crate::core) fn count_fence(&self) {
+        #[cfg(debug_assertions)]
+        self.fences.set(self.fences.get() + 1);
+    }
+
+    #[cfg(debug_assertions)]
+    pub(in crate::core) fn sync_point(&self) -> SyncPoint {
+        SyncPoint::new(self.fences.get())
+    }
+
+    ///
+    /// Removes all cached programs, effects and textures.
+    /// Must be called when the graphics context has been restored after it was lost,
+    /// since the cached resources are no longer valid. This is done automatically by the default canvas window.
+    ///
+    pub fn clear_cache(&self) {
+        self.programs.borrow_mut().clear();
+        self.effects.borrow_mut().clear();
+        *self.camera2d.borrow_mut() = None;
+        self.data_textures.borrow_mut().clear();
+    }
+
+    ///
+    /// Removes the cached programs and effects which shader source contains the given source,
+    /// for example when the shader source of a material has been replaced, so the programs compiled with the old source are not kept in the cache.
+    ///
+    pub fn remove_programs_containing(&self, source: &str) {
+        self.programs
+            .borrow_mut()
+            .retain(|key, _| !key.contains(source));
+        self.effects
+            .borrow_mut()
+            .retain(|key, _| !key.contains(source));
+    }
+
+    ///
+    /// Returns an error if the graphics context is lost, see [Event::ContextLost](crate::Event::ContextLost).
+    ///
+    pub(crate) fn check_context_lost(&self) -> ThreeDResult<()> {
+        if self.is_context_lost() {
+            Err(CoreError::ContextLost)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Uses the given data, laid out as rows of `width` RGBA texels, as a float texture with the given name.
+    /// The texture is only reallocated if the number of rows changes and only updated if the data changes.
+    ///
+    pub(crate) fn use_data_texture(
+        &self,
+        program: &Program,
+        name: &str,
+        width: u32,
+        data: &[f32],
+    ) -> ThreeDResult<()> {
+        let height = data.len() as u32 / (4 * width);
+        let mut data_textures = self.data_textures.borrow_mut();
+        if data_textures
+            .get(name)
+            .map(|(_, texture)| texture.height() != height)
+            .unwrap_or(true)
+        {
+            let texture = Texture2D::new_empty(
+                self,
+                width,
+                height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )?;
+            data_textures.insert(name.to_string(), (Vec::new(), texture));
+        }
+        let (uploaded_data, texture) = data_textures.get_mut(name).unwrap();
+        if uploaded_data.as_slice() != data {
+            texture.fill(data)?;
+            *uploaded_data = data.to_vec();
+        }
+        program.use_texture(name, texture)
     }
 }
 
@@ -317,10 +787,29 @@ pub use math::*;
 pub mod texture;
 pub use texture::*;
 
+pub mod collision;
+pub use collision::*;
+
 mod cpu_mesh;
 #[doc(inline)]
 pub use cpu_mesh::*;
 
+mod skeleton;
+#[doc(inline)]
+pub use skeleton::*;
+
+mod mesh_simplification;
+
+mod sdf_generation;
+
+mod uv_unwrapping;
+#[doc(inline)]
+pub use uv_unwrapping::*;
+
+mod mesh_boolean;
+#[doc(inline)]
+pub use mesh_boolean::*;
+
 mod mesh;
 #[doc(inline)]
 pub use mesh::*;
@@ -339,6 +828,10 @@ mod camera;
 #[doc(inline)]
 pub use camera::*;
 
+mod camera_path;
+#[doc(inline)]
+pub use camera_path::*;
+
 mod image_effect;
 #[doc(inline)]
 pub use image_effect::*;
@@ -347,14 +840,42 @@ mod image_cube_effect;
 #[doc(inline)]
 pub use image_cube_effect::*;
 
+mod uniform_provider;
+#[doc(inline)]
+pub use uniform_provider::*;
+
 mod program;
 #[doc(inline)]
 pub use program::*;
 
+mod vertex_array;
+#[doc(inline)]
+pub use vertex_array::*;
+
+mod program_cache;
+#[doc(inline)]
+pub use program_cache::*;
+
 mod aabb;
 #[doc(inline)]
 pub use aabb::*;
 
+mod bounding_sphere;
+#[doc(inline)]
+pub use bounding_sphere::*;
+
+mod plane;
+#[doc(inline)]
+pub use plane::*;
+
+mod spherical_harmonics;
+#[doc(inline)]
+pub use spherical_harmonics::*;
+
+mod capabilities;
+#[doc(inline)]
+pub use capabilities::*;
+
 mod color;
 #[doc(inline)]
 pub use color::*;
@@ -363,6 +884,14 @@ mod viewport;
 #[doc(inline)]
 pub use viewport::*;
 
+mod upload;
+#[doc(inline)]
+pub use upload::*;
+
+mod fence_sync;
+#[doc(inline)]
+pub use fence_sync::*;
+
 pub use crate::ThreeDResult;
 use thiserror::Error;
 ///
@@ -387,6 +916,8 @@ pub enum CoreError {
     RenderTargetCopy(String, String),
     #[error("cannot read color from anything else but an RGBA texture")]
     ReadWrongFormat,
+    #[error("cannot read {0} color values on this graphics context")]
+    ReadUnsupportedDataType(String),
     #[error("failed creating a new texture")]
     TextureCreation,
     #[error("invalid size of texture data (got {0} pixels but expected {1} pixels)")]
@@ -415,4 +946,64 @@ pub enum CoreError {
     NegativeDistance,
     #[error("a minimum must be smaller than a maximum")]
     MinimumLargerThanMaximum,
+    #[error("the upload is already done")]
+    UploadAlreadyDone,
+    #[error("the graphics context is lost")]
+    ContextLost,
+    #[error("failed creating a new buffer")]
+    BufferCreation,
+    #[error("failed creating a new vertex array")]
+    VertexArrayCreation,
+    #[error("the texture size {0}x{1} exceeds the maximum supported texture size of {2}")]
+    TextureTooLarge(u32, u32, u32),
+    #[error(
+        "the texture has {0} layers which exceeds the maximum supported number of layers of {1}"
+    )]
+    TooManyTextureLayers(u32, u32),
+    #[error("the shader program uses {0} vertex attributes which exceeds the maximum supported number of vertex attributes of {1}")]
+    TooManyVertexAttributes(u32, u32),
+    #[error("the morph target {0} has {1} deltas but the mesh has {2} positions")]
+    InvalidMorphTargetLength(String, usize, usize),
+    #[error("the point cloud has {1} {0} but {2} positions")]
+    InvalidPointCloudLength(String, usize, usize),
+    #[error("the range of {1} elements starting at element {0} is outside the element buffer with {2} elements")]
+    InvalidElementRange(u32, u32, usize),
+    #[error("the shadow map tiles do not fit into the {0}x{0} shadow atlas")]
+    ShadowAtlasTooSmall(u32),
+    #[error("{0} lights are given but the shadow atlas only has {1} tiles")]
+    TooManyShadowAtlasLights(usize, usize),
+    #[error("the extension {0} is not supported")]
+    ExtensionNotSupported(String),
+    #[error("the {0} uv charts do not fit into a {1}x{1} texture with a padding of {2} texels")]
+    TooManyUvCharts(usize, u32, u32),
+    #[error(
+        "the 3D texture size {0}x{1}x{2} exceeds the maximum supported 3D texture size of {3}"
+    )]
+    Texture3DTooLarge(u32, u32, u32, u32),
+    #[error("reading the result of transform feedback is not supported")]
+    TransformFeedbackNotSupported,
+    #[error("the {0} transform feedback outputs are not matched by {1} buffers")]
+    InvalidTransformFeedbackOutputs(usize, usize),
+    #[error("failed creating a new query")]
+    QueryCreation,
+    #[error(
+        "the near plane distance {0} must be positive and smaller than the far plane distance {1}"
+    )]
+    InvalidDepthRange(f32, f32),
+    #[error("the field of view of {0} degrees must be between 0 and 180 degrees")]
+    InvalidFieldOfView(f32),
+    #[error("a camera at {0:?} looking at {1:?} has no view direction or a view direction parallel to the up direction {2:?}")]
+    InvalidViewDirection(Vec3, Vec3, Vec3),
+    #[error("no texture is registered with the key {0} in the texture cache")]
+    TextureCacheMissingKey(String),
+    #[error("{0} bytes of textures do not fit within the texture cache budget of {1} bytes")]
+    TextureCacheBudgetExceeded(usize, usize),
+    #[error("OpenGL error {0} in {1}")]
+    GLError(String, String),
+    #[error("the attribute {0} is active in the shader program but no buffer was provided")]
+    MissingAttributeBuffer(String),
+    #[error("the {0} mesh '{1}' of the boolean operation is not a closed manifold")]
+    NotClosedManifold(String, String),
+    #[error("the transformation cannot be inverted, for example because it has a zero scale")]
+    SingularTransformation,
 }
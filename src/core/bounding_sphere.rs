@@ -0,0 +1,196 @@
+use crate::core::*;
+
+///
+/// A bounding sphere defined by a center and a radius.
+/// Compared to an [AxisAlignedBoundingBox], it does not grow when rotated and it is cheaper to test against the camera frustum.
+///
+#[derive(Debug, Copy, Clone)]
+pub struct BoundingSphere {
+    center: Vec3,
+    radius: f32,
+}
+
+impl BoundingSphere {
+    /// An empty bounding sphere.
+    pub const EMPTY: Self = Self {
+        center: vec3(0.0, 0.0, 0.0),
+        radius: std::f32::NEG_INFINITY,
+    };
+
+    /// An infinitely large bounding sphere.
+    pub const INFINITE: Self = Self {
+        center: vec3(0.0, 0.0, 0.0),
+        radius: std::f32::INFINITY,
+    };
+
+    ///
+    /// Constructs a new bounding sphere with the given center and radius.
+    ///
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    ///
+    /// Constructs a new bounding sphere which contains all of the given positions using Ritter's algorithm.
+    /// The result is not necessarily the smallest possible bounding sphere, but it is at most a few percent larger.
+    /// A position consisting of an x, y and z coordinate corresponds to three consecutive value in the positions array.
+    ///
+    pub fn new_with_positions(positions: &[f32]) -> Self {
+        let count = positions.len() / 3;
+        if count == 0 {
+            return Self::EMPTY;
+        }
+        let position =
+            |i: usize| vec3(positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]);
+        let farthest_from = |p: Vec3| {
+            (0..count)
+                .map(position)
+                .fold((p, 0.0), |(best, best_distance2), q| {
+                    let distance2 = p.distance2(q);
+                    if distance2 > best_distance2 {
+                        (q, distance2)
+                    } else {
+                        (best, best_distance2)
+                    }
+                })
+                .0
+        };
+
+        // Initial sphere spanned by two points which are approximately farthest apart
+        let x = farthest_from(position(0));
+        let y = farthest_from(x);
+        let mut sphere = Self::new(0.5 * (x + y), 0.5 * x.distance(y));
+
+        // Grow the sphere to include the points which are outside
+        for i in 0..count {
+            sphere.expand(position(i));
+        }
+        sphere
+    }
+
+    ///
+    /// Constructs a new bounding sphere which contains the given bounding box.
+    ///
+    pub fn new_with_aabb(aabb: &AxisAlignedBoundingBox) -> Self {
+        if aabb.is_empty() {
+            Self::EMPTY
+        } else if aabb.is_infinite() {
+            Self::INFINITE
+        } else {
+            Self::new(aabb.center(), 0.5 * aabb.size().magnitude())
+        }
+    }
+
+    ///
+    /// Returns true if the bounding sphere is empty (ie. constructed by [BoundingSphere::EMPTY]).
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.radius < 0.0
+    }
+
+    ///
+    /// Returns true if the bounding sphere is infinitely large (ie. constructed by [BoundingSphere::INFINITE]).
+    ///
+    pub fn is_infinite(&self) -> bool {
+        self.radius == f32::INFINITY
+    }
+
+    ///
+    /// Get the center of the bounding sphere.
+    ///
+    pub fn center(&self) -> Vec3 {
+        self.center
+    }
+
+    ///
+    /// Get the radius of the bounding sphere.
+    ///
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    ///
+    /// Expands the bounding sphere such that it contains the given position.
+    ///
+    pub fn expand(&mut self, position: Vec3) {
+        if self.is_empty() {
+            *self = Self::new(position, 0.0);
+            return;
+        }
+        let distance = self.center.distance(position);
+        if distance > self.radius {
+            let radius = 0.5 * (self.radius + distance);
+            self.center += (position - self.center) * ((radius - self.radius) / distance);
+            self.radius = radius;
+        }
+    }
+
+    ///
+    /// Transforms the bounding sphere by the given transformation.
+    /// The radius is scaled by the largest scale along any of the axes of the transformation,
+    /// so the sphere is still a bounding sphere if the transformation contains a non-uniform scale.
+    ///
+    pub fn transform(&mut self, transformation: &Mat4) {
+        if self.is_empty() || self.is_infinite() {
+            return;
+        }
+        self.center = (transformation * self.center.extend(1.0)).truncate();
+        let scale = transformation
+            .x
+            .truncate()
+            .magnitude()
+            .max(transformation.y.truncate().magnitude())
+            .max(transformation.z.truncate().magnitude());
+        self.radius *= scale;
+    }
+
+    ///
+    /// Returns whether or not the given position is inside this bounding sphere.
+    ///
+    pub fn contains(&self, position: &Vec3) -> bool {
+        self.center.distance2(*position) <= self.radius * self.radius
+    }
+
+    ///
+    /// Returns whether or not this bounding sphere intersects the other bounding sphere.
+    ///
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return false;
+        }
+        self.center.distance(other.center) <= self.radius + other.radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cube() {
+        let sphere = CPUMesh::cube().compute_bounding_sphere();
+        assert!(
+            (sphere.radius() - 3.0f32.sqrt()).abs() < 0.0001,
+            "{:?}",
+            sphere
+        );
+        assert!(sphere.center().magnitude() < 0.0001, "{:?}", sphere);
+    }
+
+    #[test]
+    fn rotation_and_translation() {
+        let mut sphere = CPUMesh::cube().compute_bounding_sphere();
+        let radius = sphere.radius();
+        sphere.transform(
+            &(Mat4::from_translation(vec3(1.0, -2.0, 3.0))
+                * Mat4::from_angle_y(degrees(30.0))
+                * Mat4::from_angle_x(degrees(45.0))),
+        );
+        assert!((sphere.radius() - radius).abs() < 0.0001, "{:?}", sphere);
+        assert!(
+            (sphere.center() - vec3(1.0, -2.0, 3.0)).magnitude() < 0.0001,
+            "{:?}",
+            sphere
+        );
+    }
+}
@@ -23,6 +23,7 @@ pub(crate) mod internal {
 
     pub trait BufferDataTypeExtension: Clone {
         fn buffer_data(context: &Context, target: u32, data: &[Self], usage: u32);
+        fn buffer_sub_data(context: &Context, target: u32, offset: usize, data: &[Self]);
         fn data_type() -> DataType;
     }
 
@@ -30,24 +31,73 @@ pub(crate) mod internal {
         fn buffer_data(context: &Context, target: u32, data: &[Self], usage: u32) {
             context.buffer_data_u8(target, data, usage);
         }
+        fn buffer_sub_data(context: &Context, target: u32, offset: usize, data: &[Self]) {
+            context.buffer_sub_data_u8(target, (offset * std::mem::size_of::<Self>()) as u32, data);
+        }
         fn data_type() -> DataType {
             DataType::UnsignedByte
         }
     }
 
+    impl BufferDataTypeExtension for i8 {
+        fn buffer_data(context: &Context, target: u32, data: &[Self], usage: u32) {
+            context.buffer_data_u8(target, as_unsigned(data), usage);
+        }
+        fn buffer_sub_data(context: &Context, target: u32, offset: usize, data: &[Self]) {
+            context.buffer_sub_data_u8(
+                target,
+                (offset * std::mem::size_of::<Self>()) as u32,
+                as_unsigned(data),
+            );
+        }
+        fn data_type() -> DataType {
+            DataType::Byte
+        }
+    }
+
     impl BufferDataTypeExtension for u16 {
         fn buffer_data(context: &Context, target: u32, data: &[Self], usage: u32) {
             context.buffer_data_u16(target, data, usage);
         }
+        fn buffer_sub_data(context: &Context, target: u32, offset: usize, data: &[Self]) {
+            context.buffer_sub_data_u16(
+                target,
+                (offset * std::mem::size_of::<Self>()) as u32,
+                data,
+            );
+        }
         fn data_type() -> DataType {
             DataType::UnsignedShort
         }
     }
 
+    impl BufferDataTypeExtension for i16 {
+        fn buffer_data(context: &Context, target: u32, data: &[Self], usage: u32) {
+            context.buffer_data_u16(target, as_unsigned(data), usage);
+        }
+        fn buffer_sub_data(context: &Context, target: u32, offset: usize, data: &[Self]) {
+            context.buffer_sub_data_u16(
+                target,
+                (offset * std::mem::size_of::<Self>()) as u32,
+                as_unsigned(data),
+            );
+        }
+        fn data_type() -> DataType {
+            DataType::Short
+        }
+    }
+
     impl BufferDataTypeExtension for f32 {
         fn buffer_data(context: &Context, target: u32, data: &[Self], usage: u32) {
             context.buffer_data_f32(target, data, usage);
         }
+        fn buffer_sub_data(context: &Context, target: u32, offset: usize, data: &[Self]) {
+            context.buffer_sub_data_f32(
+                target,
+                (offset * std::mem::size_of::<Self>()) as u32,
+                data,
+            );
+        }
         fn data_type() -> DataType {
             DataType::Float
         }
@@ -57,8 +107,21 @@ pub(crate) mod internal {
         fn buffer_data(context: &Context, target: u32, data: &[Self], usage: u32) {
             context.buffer_data_u32(target, data, usage);
         }
+        fn buffer_sub_data(context: &Context, target: u32, offset: usize, data: &[Self]) {
+            context.buffer_sub_data_u32(
+                target,
+                (offset * std::mem::size_of::<Self>()) as u32,
+                data,
+            );
+        }
         fn data_type() -> DataType {
             DataType::UnsignedInt
         }
     }
+
+    // The signed data is sent to the GPU as the unsigned data with the same bits and size, the type is given when the data is used
+    fn as_unsigned<S, U>(data: &[S]) -> &[U] {
+        debug_assert_eq!(std::mem::size_of::<S>(), std::mem::size_of::<U>());
+        unsafe { std::slice::from_raw_parts(data.as_ptr() as *const U, data.len()) }
+    }
 }
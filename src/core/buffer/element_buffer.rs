@@ -43,7 +43,8 @@ impl ElementBuffer {
     /// Creates a new empty element buffer.
     ///
     pub fn new<T: ElementBufferDataType>(context: &Context) -> ThreeDResult<ElementBuffer> {
-        let id = context.create_buffer().unwrap();
+        context.check_context_lost()?;
+        let id = context.create_buffer().ok_or(CoreError::BufferCreation)?;
         Ok(ElementBuffer {
             context: context.clone(),
             id,
@@ -89,6 +90,36 @@ impl ElementBuffer {
         Ok(())
     }
 
+    ///
+    /// Allocates room for the given number of indices, which must be divisable by 3, without filling the buffer with data.
+    /// Use [fill_subset](ElementBuffer::fill_subset) afterwards to fill the buffer piece by piece.
+    ///
+    pub(crate) fn allocate<T: ElementBufferDataType>(&mut self, count: usize) -> ThreeDResult<()> {
+        if count % 3 != 0 {
+            Err(CoreError::InvalidBufferLength("index".to_string(), count))?;
+        }
+        self.bind();
+        self.context.buffer_data(
+            consts::ELEMENT_ARRAY_BUFFER,
+            (count * std::mem::size_of::<T>()) as u32,
+            consts::STATIC_DRAW,
+        );
+        self.data_type = T::data_type();
+        self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
+        self.count = count;
+        Ok(())
+    }
+
+    ///
+    /// Fills the part of the buffer starting at the given index offset with the given indices.
+    /// The buffer must be allocated beforehand, see [allocate](ElementBuffer::allocate).
+    ///
+    pub(crate) fn fill_subset<T: ElementBufferDataType>(&mut self, offset: usize, data: &[T]) {
+        self.bind();
+        T::buffer_sub_data(&self.context, consts::ELEMENT_ARRAY_BUFFER, offset, data);
+        self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
+    }
+
     ///
     /// The number of elements in the buffer.
     ///
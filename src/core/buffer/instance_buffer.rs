@@ -19,6 +19,7 @@ pub struct InstanceBuffer {
     id: crate::context::Buffer,
     count: usize,
     data_type: DataType,
+    uid: u64,
 }
 
 impl InstanceBuffer {
@@ -26,11 +27,13 @@ impl InstanceBuffer {
     /// Creates a new empty instance buffer.
     ///
     pub fn new(context: &Context) -> ThreeDResult<Self> {
+        context.check_context_lost()?;
         Ok(Self {
             context: context.clone(),
-            id: context.create_buffer().unwrap(),
+            id: context.create_buffer().ok_or(CoreError::BufferCreation)?,
             count: 0,
             data_type: DataType::Float,
+            uid: context.next_id(),
         })
     }
 
@@ -65,6 +68,7 @@ impl InstanceBuffer {
             data,
             consts::STATIC_DRAW,
         );
+        self.update_uid(T::data_type(), data.len());
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ARRAY_BUFFER);
         self.count = data.len();
@@ -101,11 +105,39 @@ impl InstanceBuffer {
             data,
             consts::DYNAMIC_DRAW,
         );
+        self.update_uid(T::data_type(), data.len());
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ARRAY_BUFFER);
         self.count = data.len();
     }
 
+    ///
+    /// Allocates room for the given number of elements without filling the buffer with data.
+    /// Use [fill_subset](InstanceBuffer::fill_subset) afterwards to fill the buffer piece by piece.
+    ///
+    pub(crate) fn allocate<T: InstanceBufferDataType>(&mut self, count: usize) {
+        self.bind();
+        self.context.buffer_data(
+            consts::ARRAY_BUFFER,
+            (count * std::mem::size_of::<T>()) as u32,
+            consts::DYNAMIC_DRAW,
+        );
+        self.update_uid(T::data_type(), count);
+        self.data_type = T::data_type();
+        self.context.unbind_buffer(consts::ARRAY_BUFFER);
+        self.count = count;
+    }
+
+    ///
+    /// Fills the part of the buffer starting at the given element offset with the given data.
+    /// The buffer must be allocated beforehand, see [allocate](InstanceBuffer::allocate).
+    ///
+    pub(crate) fn fill_subset<T: InstanceBufferDataType>(&mut self, offset: usize, data: &[T]) {
+        self.bind();
+        T::buffer_sub_data(&self.context, consts::ARRAY_BUFFER, offset, data);
+        self.context.unbind_buffer(consts::ARRAY_BUFFER);
+    }
+
     ///
     /// The number of elements in the buffer.
     ///
@@ -113,13 +145,32 @@ impl InstanceBuffer {
         self.count
     }
 
+    ///
+    /// Returns an id which is unique for this buffer and changes when the type of data in the buffer changes or the buffer becomes empty or non-empty,
+    /// ie. when the attributes recorded in a [VertexArray] using this buffer are no longer valid.
+    ///
+    pub fn uid(&self) -> u64 {
+        self.uid
+    }
+
     pub(crate) fn data_type(&self) -> DataType {
         self.data_type
     }
 
+    fn update_uid(&mut self, data_type: DataType, count: usize) {
+        if data_type != self.data_type || (count == 0) != (self.count == 0) {
+            self.uid = self.context.next_id();
+        }
+    }
+
     pub(crate) fn bind(&self) {
         self.context.bind_buffer(consts::ARRAY_BUFFER, &self.id);
     }
+
+    pub(in crate::core) fn bind_as_transform_feedback_output(&self, index: u32) {
+        self.context
+            .bind_buffer_base(consts::TRANSFORM_FEEDBACK_BUFFER, index, &self.id);
+    }
 }
 
 impl Drop for InstanceBuffer {
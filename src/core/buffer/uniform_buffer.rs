@@ -20,7 +20,8 @@ impl UniformBuffer {
     /// The variables are initialized to 0.
     ///
     pub fn new(context: &Context, sizes: &[u32]) -> ThreeDResult<UniformBuffer> {
-        let id = context.create_buffer().unwrap();
+        context.check_context_lost()?;
+        let id = context.create_buffer().ok_or(CoreError::BufferCreation)?;
 
         let mut offsets = Vec::new();
         let mut length = 0;
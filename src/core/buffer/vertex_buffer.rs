@@ -7,19 +7,47 @@ pub trait VertexBufferDataType:
 {
 }
 impl VertexBufferDataType for u8 {}
+impl VertexBufferDataType for i8 {}
 impl VertexBufferDataType for u16 {}
+impl VertexBufferDataType for i16 {}
+impl VertexBufferDataType for u32 {}
 impl VertexBufferDataType for f32 {}
 
+///
+/// Defines how the integer values in a [VertexBuffer] are sent to a shader program, see [VertexBuffer::set_format].
+/// The format is ignored if the buffer contains `f32` values.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VertexAttributeFormat {
+    /// The values are converted to floating point values as they are, for example the `u16` value `1000` becomes `1000.0`.
+    /// The attribute must be of a floating point type in the shader, for example `vec3`.
+    Float,
+    ///
+    /// The values are converted to floating point values in the range `[0..1]` for unsigned types and `[-1..1]` for signed types,
+    /// for example the `u8` value `255` becomes `1.0` and the `i8` value `-127` becomes `-1.0`.
+    /// The attribute must be of a floating point type in the shader, for example `vec3`.
+    ///
+    Normalized,
+    /// The values are sent as integers, so the attribute must be of an integer type in the shader, for example `ivec4` or `uvec4`.
+    Integer,
+}
+
 ///
 /// A buffer containing per vertex data, for example positions, normals, uv coordinates or colors.
-/// Can send between 1 and 4 values of [InstanceBufferDataType] to a shader program for each vertex.
+/// Can send between 1 and 4 values of [VertexBufferDataType] to a shader program for each vertex.
 /// Bind this using the [Program::use_attribute], [Program::use_attribute_vec2], etc. functionality.
 ///
+/// Integer values use less memory than `f32` values, for example normals fit in normalized `i8` values and colors in `u8` values.
+/// How integer values are sent to the shader is defined by the [VertexAttributeFormat] of the buffer.
+///
 pub struct VertexBuffer {
     context: Context,
     id: crate::context::Buffer,
     count: usize,
     data_type: DataType,
+    format: VertexAttributeFormat,
+    stride: u32,
+    uid: u64,
 }
 
 impl VertexBuffer {
@@ -27,11 +55,15 @@ impl VertexBuffer {
     /// Creates a new empty vertex buffer.
     ///
     pub fn new(context: &Context) -> ThreeDResult<VertexBuffer> {
+        context.check_context_lost()?;
         Ok(VertexBuffer {
             context: context.clone(),
-            id: context.create_buffer().unwrap(),
+            id: context.create_buffer().ok_or(CoreError::BufferCreation)?,
             count: 0,
             data_type: DataType::Float,
+            format: VertexAttributeFormat::Float,
+            stride: 0,
+            uid: context.next_id(),
         })
     }
 
@@ -51,6 +83,34 @@ impl VertexBuffer {
         Ok(buffer)
     }
 
+    ///
+    /// Creates a new vertex buffer and fills it with the given integer data which is converted to floating point values in the range `[0..1]` or `[-1..1]`
+    /// when sent to the shader, see [VertexAttributeFormat::Normalized].
+    /// The data must contain between 1 and 4 contiguous values for each vertex.
+    ///
+    pub fn new_with_static_normalized<T: VertexBufferDataType>(
+        context: &Context,
+        data: &[T],
+    ) -> ThreeDResult<VertexBuffer> {
+        let mut buffer = Self::new_with_static(context, data)?;
+        buffer.set_format(VertexAttributeFormat::Normalized);
+        Ok(buffer)
+    }
+
+    ///
+    /// Creates a new vertex buffer and fills it with the given integer data which is sent to the shader as integers,
+    /// see [VertexAttributeFormat::Integer].
+    /// The data must contain between 1 and 4 contiguous values for each vertex.
+    ///
+    pub fn new_with_static_integer<T: VertexBufferDataType>(
+        context: &Context,
+        data: &[T],
+    ) -> ThreeDResult<VertexBuffer> {
+        let mut buffer = Self::new_with_static(context, data)?;
+        buffer.set_format(VertexAttributeFormat::Integer);
+        Ok(buffer)
+    }
+
     ///
     /// Fills the vertex buffer with the given data which must contain between 1 and 4 contiguous values for each vertex.
     /// Use this method instead of [fill_with_dynamic](VertexBuffer::fill_with_dynamic)
@@ -64,6 +124,7 @@ impl VertexBuffer {
             data,
             consts::STATIC_DRAW,
         );
+        self.update_uid(T::data_type(), data.len());
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ARRAY_BUFFER);
         self.count = data.len();
@@ -98,11 +159,39 @@ impl VertexBuffer {
             data,
             consts::DYNAMIC_DRAW,
         );
+        self.update_uid(T::data_type(), data.len());
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ARRAY_BUFFER);
         self.count = data.len();
     }
 
+    ///
+    /// Allocates room for the given number of elements without filling the buffer with data.
+    /// Use [fill_subset](VertexBuffer::fill_subset) afterwards to fill the buffer piece by piece.
+    ///
+    pub(crate) fn allocate<T: VertexBufferDataType>(&mut self, count: usize) {
+        self.bind();
+        self.context.buffer_data(
+            consts::ARRAY_BUFFER,
+            (count * std::mem::size_of::<T>()) as u32,
+            consts::STATIC_DRAW,
+        );
+        self.update_uid(T::data_type(), count);
+        self.data_type = T::data_type();
+        self.context.unbind_buffer(consts::ARRAY_BUFFER);
+        self.count = count;
+    }
+
+    ///
+    /// Fills the part of the buffer starting at the given element offset with the given data.
+    /// The buffer must be allocated beforehand, see [allocate](VertexBuffer::allocate).
+    ///
+    pub(crate) fn fill_subset<T: VertexBufferDataType>(&mut self, offset: usize, data: &[T]) {
+        self.bind();
+        T::buffer_sub_data(&self.context, consts::ARRAY_BUFFER, offset, data);
+        self.context.unbind_buffer(consts::ARRAY_BUFFER);
+    }
+
     ///
     /// The number of elements in the buffer.
     ///
@@ -110,13 +199,77 @@ impl VertexBuffer {
         self.count
     }
 
+    ///
+    /// The size of the data in the buffer in bytes.
+    ///
+    pub fn size_in_bytes(&self) -> usize {
+        self.count * self.data_type.byte_size() as usize
+    }
+
+    ///
+    /// Returns how the values in the buffer are sent to the shader.
+    ///
+    pub fn format(&self) -> VertexAttributeFormat {
+        self.format
+    }
+
+    ///
+    /// Sets how the values in the buffer are sent to the shader, see [VertexAttributeFormat].
+    /// The default is [VertexAttributeFormat::Float].
+    ///
+    pub fn set_format(&mut self, format: VertexAttributeFormat) {
+        if self.format != format {
+            self.format = format;
+            self.uid = self.context.next_id();
+        }
+    }
+
+    ///
+    /// Returns the number of values between the first value of one vertex and the first value of the next vertex, see [VertexBuffer::set_stride].
+    ///
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    ///
+    /// Sets the number of values between the first value of one vertex and the first value of the next vertex.
+    /// This is only needed if the buffer contains more values for each vertex than the shader uses,
+    /// for example if three `i8` values are padded to four values to align each vertex to four bytes.
+    /// The default is 0, which means that the values are tightly packed.
+    ///
+    pub fn set_stride(&mut self, stride: u32) {
+        if self.stride != stride {
+            self.stride = stride;
+            self.uid = self.context.next_id();
+        }
+    }
+
+    ///
+    /// Returns an id which is unique for this buffer and changes when the type, format or stride of the data in the buffer changes or the buffer becomes empty or non-empty,
+    /// ie. when the attributes recorded in a [VertexArray] using this buffer are no longer valid.
+    ///
+    pub fn uid(&self) -> u64 {
+        self.uid
+    }
+
     pub(crate) fn data_type(&self) -> DataType {
         self.data_type
     }
 
+    fn update_uid(&mut self, data_type: DataType, count: usize) {
+        if data_type != self.data_type || (count == 0) != (self.count == 0) {
+            self.uid = self.context.next_id();
+        }
+    }
+
     pub(crate) fn bind(&self) {
         self.context.bind_buffer(consts::ARRAY_BUFFER, &self.id);
     }
+
+    pub(in crate::core) fn bind_as_transform_feedback_output(&self, index: u32) {
+        self.context
+            .bind_buffer_base(consts::TRANSFORM_FEEDBACK_BUFFER, index, &self.id);
+    }
 }
 
 impl Drop for VertexBuffer {
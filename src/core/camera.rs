@@ -16,10 +16,84 @@ pub enum ProjectionType {
     },
 }
 
+///
+/// The direction in which the y-coordinate of the pixel coordinates used by a 2D camera increases, see [Camera::new_2d].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum YDirection {
+    /// The y-coordinate increases downwards, which is the usual convention for pixel coordinates, for example the positions of the mouse events.
+    Down,
+    /// The y-coordinate increases upwards, which is the convention used by OpenGL, for example for the [Viewport].
+    Up,
+}
+
+///
+/// A point on the border or in the center of a viewport which is used as the origin of the pixel coordinates of a 2D camera, see [Camera::set_view_2d].
+/// Anything positioned relative to an anchor stays at the same distance from the anchor in pixels when the viewport is resized,
+/// so for example something anchored to the top right corner stays in the top right corner.
+///
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    ///
+    /// Returns the position of the anchor in pixels relative to the top left corner of the given viewport, with the y-coordinate increasing downwards.
+    ///
+    pub fn position(&self, viewport: Viewport) -> Vec2 {
+        let (x, y) = match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        };
+        vec2(x * viewport.width as f32, y * viewport.height as f32)
+    }
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Self::TopLeft
+    }
+}
+
+///
+/// The intrinsic parameters of a perspective camera in pixels, see [Camera::intrinsics].
+/// The pixel coordinates start in the top left corner of the viewport with the y-coordinate increasing downwards
+/// and the center of the top left pixel is at (0, 0), which is the convention used in computer vision, for example by OpenCV.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraIntrinsics {
+    /// The focal length in the horizontal direction in pixels.
+    pub fx: f32,
+    /// The focal length in the vertical direction in pixels.
+    pub fy: f32,
+    /// The horizontal pixel coordinate of the principal point, ie. where the view direction intersects the image.
+    pub cx: f32,
+    /// The vertical pixel coordinate of the principal point, ie. where the view direction intersects the image.
+    pub cy: f32,
+}
+
 ///
 /// Used in a render call to define how to view the 3D world.
 ///
 pub struct Camera {
+    context: Context,
     viewport: Viewport,
     projection_type: ProjectionType,
     z_near: f32,
@@ -28,10 +102,18 @@ pub struct Camera {
     target: Vec3,
     up: Vec3,
     view: Mat4,
+    view_f64: DMat4,
+    camera_relative: bool,
     projection: Mat4,
+    projection_without_offset: Mat4,
+    lens_shift: Vec2,
+    projection_jitter: Option<Vec2>,
     screen2ray: Mat4,
     uniform_buffer: UniformBuffer,
+    reversed_uniform_buffer: UniformBuffer,
     frustrum: [Vec4; 6],
+    mirrored: bool,
+    view_2d: Option<(YDirection, Anchor)>,
 }
 
 impl Camera {
@@ -75,6 +157,96 @@ impl Camera {
         Ok(camera)
     }
 
+    ///
+    /// Returns a builder for a camera which projects the world with a perspective projection, see [CameraBuilder].
+    /// This is an alternative to [Camera::new_perspective] where each parameter is named and has a default value.
+    ///
+    /// ```no_run
+    /// # use three_d::*;
+    /// # let context = Context::new().unwrap();
+    /// let camera = Camera::perspective(&context, Viewport::new_at_origo(1280, 720))
+    ///     .position(vec3(0.0, 2.0, 5.0))
+    ///     .target(vec3(0.0, 0.0, 0.0))
+    ///     .fov(degrees(45.0))
+    ///     .near(0.1)
+    ///     .far(100.0)
+    ///     .build()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    pub fn perspective(context: &Context, viewport: Viewport) -> CameraBuilder {
+        CameraBuilder {
+            context: context.clone(),
+            viewport,
+            position: vec3(0.0, 0.0, 5.0),
+            target: vec3(0.0, 0.0, 0.0),
+            up: vec3(0.0, 1.0, 0.0),
+            field_of_view_y: degrees(45.0).into(),
+            z_near: 0.1,
+            z_far: 1000.0,
+        }
+    }
+
+    ///
+    /// New camera which projects the world with a perspective projection defined by the parameters of a physical camera,
+    /// ie. the focal length of the lens and the height of the sensor, both in millimeters, and the lens shift, see [Camera::set_lens_shift].
+    /// The width of the sensor is given by the aspect ratio of the viewport.
+    ///
+    pub fn new_perspective_physical(
+        context: &Context,
+        viewport: Viewport,
+        position: Vec3,
+        target: Vec3,
+        up: Vec3,
+        focal_length_mm: f32,
+        sensor_height_mm: f32,
+        shift: Vec2,
+        z_near: f32,
+        z_far: f32,
+    ) -> ThreeDResult<Camera> {
+        let mut camera = Camera::new(context, viewport)?;
+        camera.lens_shift = shift;
+        camera.set_view(position, target, up)?;
+        camera.set_perspective_projection(
+            radians(2.0 * (0.5 * sensor_height_mm / focal_length_mm).atan()),
+            z_near,
+            z_far,
+        )?;
+        Ok(camera)
+    }
+
+    ///
+    /// New 2D camera which maps one unit to one physical pixel of the viewport, for example to render 2D shapes in pixel coordinates on top of a 3D scene.
+    /// The origin of the pixel coordinates is the top left corner of the viewport when the y-coordinate increases downwards
+    /// and the bottom left corner when it increases upwards. Use [Camera::set_view_2d] to anchor the origin elsewhere.
+    /// The camera keeps mapping to pixels when the viewport is changed using [Camera::set_viewport]. Anything with a z-coordinate between -1 and 9 is visible.
+    ///
+    pub fn new_2d(
+        context: &Context,
+        viewport: Viewport,
+        y_direction: YDirection,
+    ) -> ThreeDResult<Camera> {
+        let mut camera = Camera::new(context, viewport)?;
+        camera.set_view_2d(
+            y_direction,
+            match y_direction {
+                YDirection::Down => Anchor::TopLeft,
+                YDirection::Up => Anchor::BottomLeft,
+            },
+        )?;
+        Ok(camera)
+    }
+
+    ///
+    /// New camera which views the world reflected in the given plane, ie. how the given camera sees the world in a planar mirror.
+    /// The near plane of the projection is replaced by the mirror plane, so everything behind the mirror is clipped, see [Camera::set_mirrored].
+    ///
+    pub fn new_mirrored(&self, context: &Context, plane: &Plane) -> ThreeDResult<Camera> {
+        let mut camera = Camera::new(context, self.viewport)?;
+        camera.set_mirrored(self, plane)?;
+        Ok(camera)
+    }
+
     ///
     /// Specify the camera to use perspective projection with the given field of view in the y-direction and near and far plane.
     ///
@@ -87,15 +259,17 @@ impl Camera {
         if z_near < 0.0 || z_near > z_far {
             panic!("Wrong perspective camera parameters")
         };
+        self.view_2d = None;
         self.z_near = z_near;
         self.z_far = z_far;
         let field_of_view_y = field_of_view_y.into();
         self.projection_type = ProjectionType::Perspective { field_of_view_y };
-        self.projection = perspective(field_of_view_y, self.viewport.aspect(), z_near, z_far);
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.set_projection(perspective(
+            field_of_view_y,
+            self.viewport.aspect(),
+            z_near,
+            z_far,
+        ))
     }
 
     ///
@@ -113,22 +287,54 @@ impl Camera {
         if z_near > z_far {
             panic!("Wrong orthographic camera parameters")
         };
+        self.view_2d = None;
         self.z_near = z_near;
         self.z_far = z_far;
         let width = height * self.viewport.aspect();
         self.projection_type = ProjectionType::Orthographic { height };
-        self.projection = ortho(
+        self.set_projection(ortho(
             -0.5 * width,
             0.5 * width,
             -0.5 * height,
             0.5 * height,
             z_near,
             z_far,
-        );
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        ))
+    }
+
+    ///
+    /// Shifts the lens of the camera parallel to the sensor, which moves the image without changing the perspective,
+    /// for example to keep vertical lines vertical when photographing a tall building (known as two-point perspective).
+    /// The shift is given in units of the height of the image, so a shift of `(0.0, 0.5)` moves the image half its height down
+    /// and thereby shows what is above the original image. The resulting view frustum is asymmetric.
+    ///
+    pub fn set_lens_shift(&mut self, shift: Vec2) -> ThreeDResult<()> {
+        self.lens_shift = shift;
+        self.set_projection(self.projection_without_offset)
+    }
+
+    ///
+    /// Returns the lens shift, see [Camera::set_lens_shift].
+    ///
+    pub fn lens_shift(&self) -> Vec2 {
+        self.lens_shift
+    }
+
+    ///
+    /// Offsets the projection by the given amount of pixels, usually a different sub-pixel offset each frame,
+    /// which is used by temporal techniques like temporal anti-aliasing to sample different positions within each pixel over time.
+    /// The jitter is removed by giving `None`.
+    ///
+    pub fn set_projection_jitter(&mut self, jitter: Option<Vec2>) -> ThreeDResult<()> {
+        self.projection_jitter = jitter;
+        self.set_projection(self.projection_without_offset)
+    }
+
+    ///
+    /// Returns the projection jitter in pixels, see [Camera::set_projection_jitter].
+    ///
+    pub fn projection_jitter(&self) -> Option<Vec2> {
+        self.projection_jitter
     }
 
     ///
@@ -138,6 +344,10 @@ impl Camera {
     pub fn set_viewport(&mut self, viewport: Viewport) -> ThreeDResult<bool> {
         if self.viewport != viewport {
             self.viewport = viewport;
+            if let Some((y_direction, anchor)) = self.view_2d {
+                self.set_view_2d(y_direction, anchor)?;
+                return Ok(true);
+            }
             match self.projection_type {
                 ProjectionType::Orthographic { height } => {
                     self.set_orthographic_projection(height, self.z_near, self.z_far)?;
@@ -157,20 +367,161 @@ impl Camera {
     /// The camera is placed at the given position, looking at the given target and with the given up direction.
     ///
     pub fn set_view(&mut self, position: Vec3, target: Vec3, up: Vec3) -> ThreeDResult<()> {
+        self.view_2d = None;
         self.position = position;
         self.target = target;
         self.up = up;
+        self.mirrored = false;
         self.view = Mat4::look_at_rh(
             Point::from_vec(self.position),
             Point::from_vec(self.target),
             self.up,
         );
+        self.view_f64 = self.view.cast().unwrap();
         self.update_screen2ray();
         self.update_uniform_buffer()?;
         self.update_frustrum();
         Ok(())
     }
 
+    ///
+    /// Change the view of the camera like [Camera::set_view], but where the position and target are given in double precision.
+    /// Use this together with [Camera::set_camera_relative] to place the camera precisely far away from the origin,
+    /// where a single precision position is rounded to for example whole units at a distance of 10 million units.
+    /// The methods which move the camera, like [Camera::rotate_around] and [Camera::translate], compute the new position in single precision.
+    ///
+    pub fn set_view_f64(&mut self, position: DVec3, target: DVec3, up: Vec3) -> ThreeDResult<()> {
+        self.set_view(position.cast().unwrap(), target.cast().unwrap(), up)?;
+        self.view_f64 = DMat4::look_at_rh(
+            cgmath::Point3::from_vec(position),
+            cgmath::Point3::from_vec(target),
+            up.cast().unwrap(),
+        );
+        Ok(())
+    }
+
+    ///
+    /// Returns the view matrix in double precision, see [Camera::set_view_f64].
+    ///
+    pub fn view_f64(&self) -> &DMat4 {
+        &self.view_f64
+    }
+
+    ///
+    /// Enables or disables camera relative rendering, which removes the jitter of objects far away from the origin, for example in a planet sized world.
+    /// Normally, a vertex is transformed to world space and then to view space on the GPU in single precision,
+    /// so the large translations of the object and the camera, which cancel out for objects close to the camera, are rounded before they cancel.
+    /// When enabled, a [Model] instead computes its transformation to view space in double precision on the CPU, see [Camera::model_view_f64],
+    /// so the vertices close to the camera are as precise as close to the origin.
+    /// Use [Model::set_transformation_f64] and [Camera::set_view_f64] to give the positions in double precision.
+    ///
+    /// **Note:** Only the transformation to the screen is camera relative. The shading, for example lighting and shadows, still uses single precision world positions.
+    ///
+    pub fn set_camera_relative(&mut self, camera_relative: bool) {
+        self.camera_relative = camera_relative;
+    }
+
+    ///
+    /// Returns whether or not camera relative rendering is enabled, see [Camera::set_camera_relative].
+    ///
+    pub fn is_camera_relative(&self) -> bool {
+        self.camera_relative
+    }
+
+    ///
+    /// Returns the transformation from the local space of an object with the given transformation to the view space of this camera,
+    /// computed in double precision before converting to single precision, which is used when rendering with [Camera::set_camera_relative] enabled.
+    ///
+    pub fn model_view_f64(&self, transformation: &DMat4) -> Mat4 {
+        (self.view_f64 * transformation).cast().unwrap()
+    }
+
+    ///
+    /// Change the camera into a 2D camera which maps one unit to one physical pixel of the viewport, see [Camera::new_2d],
+    /// where the origin of the pixel coordinates is at the given anchor and the y-coordinate increases in the given direction.
+    /// The view and projection are updated to keep the mapping when the viewport is changed,
+    /// until the view or projection is changed by any other method.
+    ///
+    pub fn set_view_2d(&mut self, y_direction: YDirection, anchor: Anchor) -> ThreeDResult<()> {
+        let origin = anchor.position(self.viewport);
+        let x = 0.5 * self.viewport.width as f32 - origin.x;
+        let y = 0.5 * self.viewport.height as f32 - origin.y;
+        match y_direction {
+            YDirection::Down => {
+                self.set_view(vec3(x, y, -1.0), vec3(x, y, 0.0), vec3(0.0, -1.0, 0.0))?
+            }
+            YDirection::Up => {
+                self.set_view(vec3(x, -y, 1.0), vec3(x, -y, 0.0), vec3(0.0, 1.0, 0.0))?
+            }
+        }
+        self.set_orthographic_projection(self.viewport.height as f32, 0.0, 10.0)?;
+        self.view_2d = Some((y_direction, anchor));
+        Ok(())
+    }
+
+    ///
+    /// Returns the direction of the y-coordinate and the anchor of the origin if this is a 2D camera, see [Camera::set_view_2d].
+    ///
+    pub fn view_2d(&self) -> Option<(YDirection, Anchor)> {
+        self.view_2d
+    }
+
+    ///
+    /// Change the camera such that it views the world reflected in the given plane, ie. how the given camera sees the world in a planar mirror.
+    /// The position, target and up direction are reflected in the plane and the projection is copied from the given camera,
+    /// except that the near plane is replaced by the mirror plane using an oblique projection,
+    /// so everything behind the mirror (seen from the given camera) is clipped by the projection itself.
+    /// The viewport of this camera is kept and should have the same aspect ratio as the viewport of the given camera.
+    ///
+    /// **Note:** The reflection reverses the winding order of the triangles, see [Camera::is_mirrored].
+    ///
+    pub fn set_mirrored(&mut self, camera: &Camera, plane: &Plane) -> ThreeDResult<()> {
+        self.view_2d = None;
+        self.projection_type = match camera.projection_type {
+            ProjectionType::Orthographic { height } => ProjectionType::Orthographic { height },
+            ProjectionType::Perspective { field_of_view_y } => {
+                ProjectionType::Perspective { field_of_view_y }
+            }
+        };
+        self.z_near = camera.z_near;
+        self.z_far = camera.z_far;
+        self.position = plane.reflect_position(camera.position);
+        self.target = plane.reflect_position(camera.target);
+        self.up = plane.reflect_direction(camera.up);
+        self.view = camera.view * plane.reflection();
+        self.view_f64 = camera.view_f64 * plane.reflection().cast().unwrap();
+        self.mirrored = !camera.mirrored;
+        self.camera_relative = camera.camera_relative;
+
+        // The mirror plane in view space oriented towards the side of the given camera,
+        // which means that the reflected camera is on the back side of the plane
+        let mut clip_plane = plane.to_vec4();
+        if plane.signed_distance(camera.position) < 0.0 {
+            clip_plane = -clip_plane;
+        }
+        let clip_plane = self.view.invert().unwrap().transpose() * clip_plane;
+
+        // Replace the near plane with the clip plane, see Eric Lengyel, "Oblique View Frustum Depth Projection and Clipping"
+        let mut projection = camera.projection;
+        let q = projection.invert().unwrap()
+            * vec4(clip_plane.x.signum(), clip_plane.y.signum(), 1.0, 1.0);
+        let c = clip_plane * (2.0 / clip_plane.dot(q));
+        projection.x.z = c.x - projection.x.w;
+        projection.y.z = c.y - projection.y.w;
+        projection.z.z = c.z - projection.z.w;
+        projection.w.z = c.w - projection.w.w;
+        self.set_projection(projection)
+    }
+
+    ///
+    /// Returns whether or not the camera views the world mirrored, see [Camera::set_mirrored] and [Camera::mirror_in_xz_plane].
+    /// A mirrored view reverses the winding order of the triangles, so the front faces are clockwise instead of counter-clockwise
+    /// when rendered with a mirrored camera. This is handled automatically by for example [Mirror](crate::Mirror).
+    ///
+    pub fn is_mirrored(&self) -> bool {
+        self.mirrored
+    }
+
     ///
     /// Change the camera view such that it is mirrored in the xz-plane.
     ///
@@ -178,12 +529,45 @@ impl Camera {
         self.view[1][0] = -self.view[1][0];
         self.view[1][1] = -self.view[1][1];
         self.view[1][2] = -self.view[1][2];
+        self.view_f64[1][0] = -self.view_f64[1][0];
+        self.view_f64[1][1] = -self.view_f64[1][1];
+        self.view_f64[1][2] = -self.view_f64[1][2];
+        self.mirrored = !self.mirrored;
         self.update_screen2ray();
         self.update_uniform_buffer()?;
         self.update_frustrum();
         Ok(())
     }
 
+    ///
+    /// Returns whether or not the given bounding sphere is within the camera frustum.
+    /// It returns false if it is fully outside and true if it is inside or intersects.
+    /// This is cheaper than [Camera::in_frustum] but less precise.
+    ///
+    pub fn sphere_in_frustum(&self, sphere: &BoundingSphere) -> bool {
+        if sphere.is_infinite() {
+            return true;
+        }
+        if sphere.is_empty() {
+            return false;
+        }
+        let center = sphere.center().extend(1.0);
+        for plane in self.frustrum.iter() {
+            if plane.dot(center) < -sphere.radius() * plane.truncate().magnitude() {
+                return false;
+            }
+        }
+        true
+    }
+
+    ///
+    /// Returns the six planes of the camera frustum in world space, where a position `p` is on the inside of a plane if `plane.dot(p.extend(1.0))` is positive.
+    /// The planes are not normalized, so the distance to a plane is `plane.dot(p.extend(1.0)) / plane.truncate().magnitude()`.
+    ///
+    pub fn frustum_planes(&self) -> [Vec4; 6] {
+        self.frustrum
+    }
+
     ///
     /// Returns whether or not the given bounding box is within the camera frustum.
     /// It returns false if it is fully outside and true if it is inside or intersects.
@@ -235,10 +619,12 @@ impl Camera {
     ///
     pub fn position_at_pixel(&self, pixel: (f32, f32)) -> Vec3 {
         match self.projection_type() {
-            ProjectionType::Orthographic { height } => {
+            ProjectionType::Orthographic { .. } => {
+                // Unproject the pixel onto the near plane and move it to the plane through the camera position
                 let coords = self.uv_coordinates_at_pixel(pixel);
-                let width = height * self.viewport.aspect();
-                self.position() + vec3((coords.0 - 0.5) * width, (0.5 - coords.1) * height, 0.0)
+                let screen_pos = vec4(2. * coords.0 - 1., 1. - 2. * coords.1, -1., 1.);
+                let p = (self.projection * self.view).invert().unwrap() * screen_pos;
+                p.truncate() / p.w - self.view_direction() * self.z_near
             }
             ProjectionType::Perspective { .. } => *self.position(),
         }
@@ -286,6 +672,28 @@ impl Camera {
         )
     }
 
+    ///
+    /// Returns the intrinsic parameters of this camera derived from the projection, including the lens shift, and the size of the viewport,
+    /// or `None` if the projection is orthographic.
+    /// A pixel at the pixel coordinates `(u, v)` (see [CameraIntrinsics]) with the depth `d`, ie. the distance to the camera along the view direction,
+    /// is at the position `((u - cx) * d / fx, -(v - cy) * d / fy, -d)` in view space, see [Camera::view].
+    ///
+    pub fn intrinsics(&self) -> Option<CameraIntrinsics> {
+        match self.projection_type {
+            ProjectionType::Orthographic { .. } => None,
+            ProjectionType::Perspective { .. } => {
+                let width = self.viewport.width as f32;
+                let height = self.viewport.height as f32;
+                Some(CameraIntrinsics {
+                    fx: 0.5 * width * self.projection.x.x,
+                    fy: 0.5 * height * self.projection.y.y,
+                    cx: 0.5 * width * (1.0 - self.projection.z.x) - 0.5,
+                    cy: 0.5 * height * (1.0 + self.projection.z.y) - 0.5,
+                })
+            }
+        }
+    }
+
     ///
     /// Returns the type of projection (orthographic or perspective) including parameters.
     ///
@@ -302,6 +710,7 @@ impl Camera {
 
     ///
     /// Returns the projection matrix, ie. the matrix that projects objects in view space onto this cameras image plane.
+    /// This is always the projection in the standard depth mode, see [Camera::uniform_buffer] for the projection used when rendering.
     ///
     pub fn projection(&self) -> &Mat4 {
         &self.projection
@@ -379,27 +788,100 @@ impl Camera {
     /// } camera;
     /// ```
     ///
+    /// In the [DepthMode::ReversedZ] depth mode, the projection in the buffer maps the near plane to the depth value 1 and the far plane to 0.
+    ///
     pub fn uniform_buffer(&self) -> &UniformBuffer {
-        &self.uniform_buffer
+        if self.context.depth_mode() == DepthMode::ReversedZ {
+            &self.reversed_uniform_buffer
+        } else {
+            &self.uniform_buffer
+        }
+    }
+
+    ///
+    /// Returns the inverse of the view projection matrix, which transforms a position in normalized device coordinates to world space,
+    /// where the z-coordinate is `2 * depth - 1` for a depth value read from a depth texture rendered with this camera.
+    /// In contrast to the inverse of [Camera::projection] times [Camera::view], this takes the depth mode of the context into account, see [Context::set_depth_mode].
+    ///
+    pub fn view_projection_inverse(&self) -> Mat4 {
+        let inverse = (self.projection * self.view).invert().unwrap();
+        if self.context.depth_mode() == DepthMode::ReversedZ {
+            // The depth values are one minus the standard depth values, ie. the z-coordinate is negated
+            inverse * Mat4::from_nonuniform_scale(1.0, 1.0, -1.0)
+        } else {
+            inverse
+        }
+    }
+
+    ///
+    /// Returns the z-coordinate of the far plane in normalized device coordinates in the depth mode of the context,
+    /// which is used for placing for example a skybox at the far plane.
+    ///
+    pub(crate) fn far_plane_z(&self) -> f32 {
+        if self.context.depth_mode() == DepthMode::ReversedZ {
+            if self.context.capabilities().clip_control {
+                0.0
+            } else {
+                -1.0
+            }
+        } else {
+            1.0
+        }
+    }
+
+    ///
+    /// Returns the depth value of the far plane in the depth mode of the context, which is also the value of the pixels where nothing is rendered.
+    ///
+    pub(crate) fn far_plane_depth(&self) -> f32 {
+        if self.context.depth_mode() == DepthMode::ReversedZ {
+            0.0
+        } else {
+            1.0
+        }
     }
 
     fn new(context: &Context, viewport: Viewport) -> ThreeDResult<Camera> {
         Ok(Camera {
+            context: context.clone(),
             viewport,
             projection_type: ProjectionType::Orthographic { height: 1.0 },
             z_near: 0.0,
             z_far: 0.0,
             uniform_buffer: UniformBuffer::new(context, &[16, 16, 16, 3, 1])?,
+            reversed_uniform_buffer: UniformBuffer::new(context, &[16, 16, 16, 3, 1])?,
             frustrum: [vec4(0.0, 0.0, 0.0, 0.0); 6],
             position: vec3(0.0, 0.0, 5.0),
             target: vec3(0.0, 0.0, 0.0),
             up: vec3(0.0, 1.0, 0.0),
             view: Mat4::identity(),
+            view_f64: DMat4::identity(),
+            camera_relative: false,
             projection: Mat4::identity(),
+            projection_without_offset: Mat4::identity(),
+            lens_shift: vec2(0.0, 0.0),
+            projection_jitter: None,
             screen2ray: Mat4::identity(),
+            mirrored: false,
+            view_2d: None,
         })
     }
 
+    // Sets the projection matrix after applying the lens shift and projection jitter, which are translations in normalized device coordinates
+    fn set_projection(&mut self, projection: Mat4) -> ThreeDResult<()> {
+        self.projection_without_offset = projection;
+        let jitter = self.projection_jitter.unwrap_or(vec2(0.0, 0.0));
+        let offset = vec2(
+            2.0 * jitter.x / self.viewport.width as f32
+                - 2.0 * self.lens_shift.x / self.viewport.aspect(),
+            2.0 * jitter.y / self.viewport.height as f32 - 2.0 * self.lens_shift.y,
+        );
+        self.projection = Mat4::from_translation(offset.extend(0.0)) * projection;
+        self.update_screen2ray();
+        self.update_uniform_buffer()?;
+        self.update_frustrum();
+        Ok(())
+    }
+
     fn update_screen2ray(&mut self) {
         let mut v = self.view;
         v[3] = vec4(0.0, 0.0, 0.0, 1.0);
@@ -412,9 +894,38 @@ impl Camera {
         self.uniform_buffer.update(1, &self.view.as_array())?;
         self.uniform_buffer.update(2, &self.projection.as_array())?;
         self.uniform_buffer.update(3, &self.position.as_array())?;
+
+        let reversed_projection = self.reversed_projection();
+        self.reversed_uniform_buffer
+            .update(0, &(reversed_projection * self.view).as_array())?;
+        self.reversed_uniform_buffer
+            .update(1, &self.view.as_array())?;
+        self.reversed_uniform_buffer
+            .update(2, &reversed_projection.as_array())?;
+        self.reversed_uniform_buffer
+            .update(3, &self.position.as_array())?;
         Ok(())
     }
 
+    // The projection in the reversed depth mode, which maps the near plane to the depth value 1 and the far plane to 0.
+    // With clip control, the z-coordinate is mapped to [1..0] in normalized device coordinates and directly used as depth value,
+    // otherwise it is negated and mapped to the depth value as usual.
+    fn reversed_projection(&self) -> Mat4 {
+        let mut projection = self.projection;
+        if self.context.capabilities().clip_control {
+            projection.x.z = 0.5 * (projection.x.w - projection.x.z);
+            projection.y.z = 0.5 * (projection.y.w - projection.y.z);
+            projection.z.z = 0.5 * (projection.z.w - projection.z.z);
+            projection.w.z = 0.5 * (projection.w.w - projection.w.z);
+        } else {
+            projection.x.z = -projection.x.z;
+            projection.y.z = -projection.y.z;
+            projection.z.z = -projection.z.z;
+            projection.w.z = -projection.w.z;
+        }
+        projection
+    }
+
     fn update_frustrum(&mut self) {
         let m = self.projection * self.view;
         self.frustrum = [
@@ -557,3 +1068,104 @@ impl Camera {
         Ok(())
     }
 }
+
+///
+/// A builder for a [Camera] with a perspective projection, constructed using [Camera::perspective].
+/// The parameters are validated when the camera is built, so an invalid parameter gives a descriptive error instead of a camera with a broken projection.
+///
+pub struct CameraBuilder {
+    context: Context,
+    viewport: Viewport,
+    position: Vec3,
+    target: Vec3,
+    up: Vec3,
+    field_of_view_y: Radians,
+    z_near: f32,
+    z_far: f32,
+}
+
+impl CameraBuilder {
+    ///
+    /// The position of the camera. The default is `(0, 0, 5)`.
+    ///
+    pub fn position(mut self, position: Vec3) -> Self {
+        self.position = position;
+        self
+    }
+
+    ///
+    /// The point the camera is looking at. The default is the origin.
+    ///
+    pub fn target(mut self, target: Vec3) -> Self {
+        self.target = target;
+        self
+    }
+
+    ///
+    /// The up direction of the camera, which must not be parallel to the view direction. The default is the positive y-axis.
+    ///
+    pub fn up(mut self, up: Vec3) -> Self {
+        self.up = up;
+        self
+    }
+
+    ///
+    /// The vertical field of view, which must be between 0 and 180 degrees. The default is 45 degrees.
+    ///
+    pub fn fov(mut self, field_of_view_y: impl Into<Radians>) -> Self {
+        self.field_of_view_y = field_of_view_y.into();
+        self
+    }
+
+    ///
+    /// The distance to the near plane, which must be positive. The default is 0.1.
+    ///
+    pub fn near(mut self, z_near: f32) -> Self {
+        self.z_near = z_near;
+        self
+    }
+
+    ///
+    /// The distance to the far plane, which must be larger than the distance to the near plane. The default is 1000.
+    ///
+    pub fn far(mut self, z_far: f32) -> Self {
+        self.z_far = z_far;
+        self
+    }
+
+    ///
+    /// Validates the parameters and builds the camera.
+    ///
+    /// # Errors
+    /// Will return an error if the near plane is not positive or not in front of the far plane,
+    /// if the field of view is not between 0 and 180 degrees,
+    /// or if the target equals the position or the up direction is parallel to the view direction.
+    ///
+    pub fn build(self) -> ThreeDResult<Camera> {
+        if !(self.z_near > 0.0 && self.z_near < self.z_far && self.z_far.is_finite()) {
+            Err(CoreError::InvalidDepthRange(self.z_near, self.z_far))?;
+        }
+        let field_of_view_y = Degrees::from(self.field_of_view_y).0;
+        if !(field_of_view_y > 0.0 && field_of_view_y < 180.0) {
+            Err(CoreError::InvalidFieldOfView(field_of_view_y))?;
+        }
+        let view_direction = self.target - self.position;
+        if !(view_direction.cross(self.up).magnitude2() > 0.0) {
+            Err(CoreError::InvalidViewDirection(
+                self.position,
+                self.target,
+                self.up,
+            ))?;
+        }
+        Camera::new_perspective(
+            &self.context,
+            self.viewport,
+            self.position,
+            self.target,
+            self.up,
+            self.field_of_view_y,
+            self.z_near,
+            self.z_far,
+        )
+    }
+}
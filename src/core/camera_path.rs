@@ -0,0 +1,329 @@
+use crate::core::*;
+
+///
+/// A camera pose, ie. the position, target and up direction of a camera and optionally the field of view.
+/// Use [Camera::pose] to get the current pose of a camera and [Camera::set_pose] to apply a pose to a camera.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Pose {
+    /// The position of the camera.
+    pub position: Vec3,
+    /// The point the camera looks towards.
+    pub target: Vec3,
+    /// The up direction of the camera.
+    pub up: Vec3,
+    /// The field of view angle in the vertical direction. If `None`, the projection of the camera is not changed when applying the pose.
+    pub field_of_view_y: Option<Radians>,
+}
+
+impl Pose {
+    ///
+    /// Interpolates linearly between this and the other pose, where `t = 0` returns this pose and `t = 1` returns the other pose.
+    /// The orientation is interpolated using spherical linear interpolation.
+    ///
+    pub fn interpolate(&self, other: &Pose, t: f32) -> Pose {
+        let position = self.position.lerp(other.position, t);
+        let target = self.target.lerp(other.target, t);
+        Pose {
+            position,
+            target,
+            up: interpolate_up(self, other, target - position, t),
+            field_of_view_y: interpolate_field_of_view(
+                self.field_of_view_y,
+                other.field_of_view_y,
+                t,
+            ),
+        }
+    }
+}
+
+///
+/// The easing function used by a [CameraTween] to map the elapsed fraction of the duration to the interpolation parameter.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slow and accelerates.
+    EaseIn,
+    /// Starts fast and decelerates.
+    EaseOut,
+    /// Starts slow, accelerates and then decelerates.
+    EaseInOut,
+}
+
+impl Easing {
+    ///
+    /// Applies the easing function to the given value which is clamped to the range `[0, 1]`.
+    ///
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.max(0.0).min(1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Self::EaseInOut
+    }
+}
+
+///
+/// A path through a set of [Pose] keyframes.
+/// Positions and targets are interpolated using Catmull-Rom splines and the orientation using spherical linear interpolation.
+///
+#[derive(Debug, Clone, Default)]
+pub struct CameraPath {
+    keyframes: Vec<(String, Pose)>,
+    /// Whether or not the path loops back from the last keyframe to the first keyframe.
+    pub closed: bool,
+}
+
+impl CameraPath {
+    ///
+    /// Constructs a new camera path through the given keyframes.
+    ///
+    pub fn new(keyframes: &[Pose], closed: bool) -> Self {
+        Self {
+            keyframes: keyframes
+                .iter()
+                .enumerate()
+                .map(|(i, pose)| (format!("{}", i), *pose))
+                .collect(),
+            closed,
+        }
+    }
+
+    ///
+    /// Adds a keyframe with the given name to the end of the path.
+    ///
+    pub fn add_keyframe(&mut self, name: impl Into<String>, pose: Pose) {
+        self.keyframes.push((name.into(), pose));
+    }
+
+    ///
+    /// Removes the keyframe with the given name and returns it if it exists.
+    ///
+    pub fn remove_keyframe(&mut self, name: &str) -> Option<Pose> {
+        let index = self.keyframes.iter().position(|(n, _)| n == name)?;
+        Some(self.keyframes.remove(index).1)
+    }
+
+    ///
+    /// Returns the keyframe with the given name if it exists.
+    ///
+    pub fn keyframe(&self, name: &str) -> Option<&Pose> {
+        self.keyframes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, pose)| pose)
+    }
+
+    ///
+    /// Returns the keyframes and their names in the order they are visited.
+    ///
+    pub fn keyframes(&self) -> &[(String, Pose)] {
+        &self.keyframes
+    }
+
+    ///
+    /// Samples the path at the given parameter `t` which is clamped to the range `[0, 1]`, or wrapped if the path is closed.
+    /// All segments between keyframes are given the same share of the parameter range.
+    /// Returns `None` if the path has no keyframes.
+    ///
+    pub fn sample(&self, t: f32) -> Option<Pose> {
+        let count = self.keyframes.len();
+        if count == 0 {
+            return None;
+        }
+        if count == 1 {
+            return Some(self.keyframes[0].1);
+        }
+        let segments = if self.closed { count } else { count - 1 };
+        let t = if self.closed {
+            t - t.floor()
+        } else {
+            t.max(0.0).min(1.0)
+        };
+        let s = t * segments as f32;
+        let segment = (s.floor() as usize).min(segments - 1);
+        let local_t = s - segment as f32;
+
+        let p0 = self.pose_at(segment as i64 - 1);
+        let p1 = self.pose_at(segment as i64);
+        let p2 = self.pose_at(segment as i64 + 1);
+        let p3 = self.pose_at(segment as i64 + 2);
+
+        let position = catmull_rom(p0.position, p1.position, p2.position, p3.position, local_t);
+        let target = catmull_rom(p0.target, p1.target, p2.target, p3.target, local_t);
+        Some(Pose {
+            position,
+            target,
+            up: interpolate_up(p1, p2, target - position, local_t),
+            field_of_view_y: interpolate_field_of_view(
+                p1.field_of_view_y,
+                p2.field_of_view_y,
+                local_t,
+            ),
+        })
+    }
+
+    fn pose_at(&self, index: i64) -> &Pose {
+        let count = self.keyframes.len() as i64;
+        let index = if self.closed {
+            index.rem_euclid(count)
+        } else {
+            index.max(0).min(count - 1)
+        };
+        &self.keyframes[index as usize].1
+    }
+}
+
+///
+/// A smooth transition of a camera from its current pose to a target pose over a given duration, for example to focus on an object.
+/// Call [CameraTween::update] each frame and apply the resulting [CameraTween::pose] to the camera using [Camera::set_pose].
+///
+#[derive(Debug, Clone)]
+pub struct CameraTween {
+    start: Pose,
+    end: Pose,
+    duration: f64,
+    elapsed: f64,
+    easing: Easing,
+}
+
+impl CameraTween {
+    ///
+    /// Constructs a new transition from the current pose of the given camera to the target pose.
+    /// The duration is in milliseconds, like the elapsed time in [FrameInput](crate::FrameInput).
+    ///
+    pub fn to(camera: &Camera, target_pose: Pose, duration: f64, easing: Easing) -> Self {
+        let mut start = camera.pose();
+        if target_pose.field_of_view_y.is_none() {
+            start.field_of_view_y = None;
+        }
+        Self {
+            start,
+            end: target_pose,
+            duration,
+            elapsed: 0.0,
+            easing,
+        }
+    }
+
+    ///
+    /// Advances the transition by the given time in milliseconds.
+    /// Returns whether or not the transition is finished.
+    ///
+    pub fn update(&mut self, delta_time: f64) -> bool {
+        self.elapsed = (self.elapsed + delta_time).min(self.duration);
+        self.is_finished()
+    }
+
+    ///
+    /// Returns whether or not the transition is finished.
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    ///
+    /// Returns the current pose of the transition.
+    ///
+    pub fn pose(&self) -> Pose {
+        let t = if self.duration > 0.0 {
+            (self.elapsed / self.duration) as f32
+        } else {
+            1.0
+        };
+        self.start.interpolate(&self.end, self.easing.apply(t))
+    }
+}
+
+impl Camera {
+    ///
+    /// Returns the current pose of this camera.
+    /// The field of view is only specified if this camera uses a perspective projection.
+    ///
+    pub fn pose(&self) -> Pose {
+        Pose {
+            position: *self.position(),
+            target: *self.target(),
+            up: *self.up(),
+            field_of_view_y: match self.projection_type() {
+                ProjectionType::Perspective { field_of_view_y } => Some(*field_of_view_y),
+                ProjectionType::Orthographic { .. } => None,
+            },
+        }
+    }
+
+    ///
+    /// Applies the given pose to this camera.
+    /// If the pose specifies a field of view, the camera is changed to use a perspective projection with that field of view.
+    ///
+    pub fn set_pose(&mut self, pose: &Pose) -> ThreeDResult<()> {
+        self.set_view(pose.position, pose.target, pose.up)?;
+        if let Some(field_of_view_y) = pose.field_of_view_y {
+            let z_near = self.z_near();
+            let z_far = self.z_far();
+            self.set_perspective_projection(field_of_view_y, z_near, z_far)?;
+        }
+        Ok(())
+    }
+}
+
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    // Uniform parameterization, the tangents are well defined even for coinciding keyframes
+    let m1 = 0.5 * (p2 - p0);
+    let m2 = 0.5 * (p3 - p1);
+    let t2 = t * t;
+    let t3 = t2 * t;
+    p1 * (2.0 * t3 - 3.0 * t2 + 1.0)
+        + m1 * (t3 - 2.0 * t2 + t)
+        + p2 * (-2.0 * t3 + 3.0 * t2)
+        + m2 * (t3 - t2)
+}
+
+fn interpolate_up(pose0: &Pose, pose1: &Pose, direction: Vec3, t: f32) -> Vec3 {
+    let direction0 = pose0.target - pose0.position;
+    let direction1 = pose1.target - pose1.position;
+    let up = if direction0.magnitude2() > 0.0 && direction1.magnitude2() > 0.0 {
+        let q0 = Quat::look_at(direction0, pose0.up);
+        let q1 = Quat::look_at(direction1, pose1.up);
+        let q = if q0.dot(q1) < 0.0 {
+            q0.slerp(-q1, t)
+        } else {
+            q0.slerp(q1, t)
+        };
+        q.conjugate() * vec3(0.0, 1.0, 0.0)
+    } else {
+        pose0.up.lerp(pose1.up, t)
+    };
+    // Make sure the up direction is valid for the interpolated view direction
+    if up.magnitude2() > 0.0
+        && direction.magnitude2() > 0.0
+        && up.cross(direction).magnitude2() > 0.0
+    {
+        up.normalize()
+    } else {
+        pose0.up
+    }
+}
+
+fn interpolate_field_of_view(
+    field_of_view_y0: Option<Radians>,
+    field_of_view_y1: Option<Radians>,
+    t: f32,
+) -> Option<Radians> {
+    match (field_of_view_y0, field_of_view_y1) {
+        (Some(a), Some(b)) => Some(radians(a.0 + (b.0 - a.0) * t)),
+        (Some(a), None) => Some(a),
+        (None, Some(b)) => Some(b),
+        (None, None) => None,
+    }
+}
@@ -0,0 +1,155 @@
+use crate::context::{consts, GLContext};
+
+// Defined by the EXT_texture_filter_anisotropic extension
+const MAX_TEXTURE_MAX_ANISOTROPY_EXT: u32 = 0x84FF;
+
+///
+/// The capabilities and limits of the graphics context, for example the maximum texture size.
+/// Queried once when the [Context](crate::Context) is created, use [Context::capabilities](crate::Context::capabilities) to access them.
+///
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// The name of the vendor of the graphics driver.
+    pub vendor: String,
+    /// The name of the renderer, usually the graphics card.
+    pub renderer: String,
+    /// The version of OpenGL or WebGL.
+    pub version: String,
+    /// The maximum width and height of a 2D texture.
+    pub max_texture_size: u32,
+    /// The maximum width and height of each side of a cube map texture.
+    pub max_cube_map_texture_size: u32,
+    /// The maximum number of layers of an array texture.
+    pub max_array_texture_layers: u32,
+    /// The maximum width, height and depth of a 3D texture.
+    pub max_3d_texture_size: u32,
+    /// The maximum number of samples when using multisample anti-aliasing.
+    pub max_samples: u32,
+    /// The maximum number of vertex attributes, including instance attributes, in a shader program.
+    pub max_vertex_attribs: u32,
+    /// The maximum number of textures that can be used in a fragment shader.
+    pub max_texture_image_units: u32,
+    /// The maximum number of color textures that can be written to in one render pass.
+    pub max_color_attachments: u32,
+    /// The maximum size of a uniform block in bytes.
+    pub max_uniform_block_size: u32,
+    /// The maximum degree of anisotropic filtering or `None` if anisotropic filtering is not supported.
+    pub max_anisotropy: Option<f32>,
+    /// Whether or not it is possible to render to floating point textures.
+    pub color_buffer_float: bool,
+    /// Whether or not floating point textures can be sampled with linear interpolation.
+    pub float_texture_linear: bool,
+    /// Whether or not the depth range of the normalized device coordinates can be changed from `[-1..1]` to `[0..1]`,
+    /// which is needed for the best depth precision in the [DepthMode::ReversedZ](crate::DepthMode::ReversedZ) depth mode.
+    /// Never supported on web.
+    pub clip_control: bool,
+    ///
+    /// Whether or not each color texture of a render target can use a different blend, see [RenderStates::attachment_blends](crate::RenderStates::attachment_blends).
+    /// Never supported on web, since WebGL2 only supports it through the `OES_draw_buffers_indexed` extension which is not exposed.
+    ///
+    pub draw_buffers_blend: bool,
+    ///
+    /// Whether or not the outputs of a vertex shader can be written to buffers with [Program::transform_feedback],
+    /// which is needed for computing the normals of [DynamicNormals](crate::DynamicNormals) on the GPU.
+    /// Also supported on web, since transform feedback is part of both OpenGL 3.3 and WebGL2.
+    ///
+    pub transform_feedback: bool,
+    ///
+    /// Whether or not geometry shaders are supported and the number of points written by transform feedback can be read right after writing them,
+    /// which is needed for [Program::transform_feedback_instanced] and thereby for culling the instances of an [InstancedModel](crate::InstancedModel) on the GPU,
+    /// see [InstancedModel::set_gpu_culling](crate::InstancedModel::set_gpu_culling).
+    /// Never supported on web, since WebGL2 does not have geometry shaders and the result of a query is not available before control is returned to the browser.
+    ///
+    pub geometry_shader: bool,
+    /// The names of all supported extensions.
+    pub extensions: Vec<String>,
+}
+
+impl Capabilities {
+    pub(crate) fn new(context: &GLContext) -> Self {
+        let extensions = context.get_supported_extensions();
+        let supports = |name: &str| {
+            extensions
+                .iter()
+                .any(|e| e.trim_start_matches("GL_") == name)
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let (color_buffer_float, float_texture_linear) = (true, true);
+        #[cfg(target_arch = "wasm32")]
+        let (color_buffer_float, float_texture_linear) = (
+            supports("EXT_color_buffer_float"),
+            supports("OES_texture_float_linear"),
+        );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let clip_control = supports("ARB_clip_control");
+        #[cfg(target_arch = "wasm32")]
+        let clip_control = false;
+
+        // Blending each draw buffer individually is core functionality from OpenGL 4.0
+        #[cfg(not(target_arch = "wasm32"))]
+        let draw_buffers_blend = supports("ARB_draw_buffers_blend")
+            || context
+                .get_string(consts::VERSION)
+                .trim_start()
+                .chars()
+                .next()
+                .and_then(|major| major.to_digit(10))
+                .map(|major| major >= 4)
+                .unwrap_or(false);
+        #[cfg(target_arch = "wasm32")]
+        let draw_buffers_blend = false;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let geometry_shader = true;
+        #[cfg(target_arch = "wasm32")]
+        let geometry_shader = false;
+
+        #[cfg(target_arch = "wasm32")]
+        let anisotropic_filtering = supports("EXT_texture_filter_anisotropic")
+            && context.enable_extension("EXT_texture_filter_anisotropic");
+        #[cfg(not(target_arch = "wasm32"))]
+        let anisotropic_filtering = supports("EXT_texture_filter_anisotropic")
+            || supports("ARB_texture_filter_anisotropic");
+
+        Self {
+            vendor: context.get_string(consts::VENDOR),
+            renderer: context.get_string(consts::RENDERER),
+            version: context.get_string(consts::VERSION),
+            max_texture_size: context.get_integer(consts::MAX_TEXTURE_SIZE) as u32,
+            max_cube_map_texture_size: context.get_integer(consts::MAX_CUBE_MAP_TEXTURE_SIZE)
+                as u32,
+            max_array_texture_layers: context.get_integer(consts::MAX_ARRAY_TEXTURE_LAYERS) as u32,
+            max_3d_texture_size: context.get_integer(consts::MAX_3D_TEXTURE_SIZE) as u32,
+            max_samples: context.get_integer(consts::MAX_SAMPLES) as u32,
+            max_vertex_attribs: context.get_integer(consts::MAX_VERTEX_ATTRIBS) as u32,
+            max_texture_image_units: context.get_integer(consts::MAX_TEXTURE_IMAGE_UNITS) as u32,
+            max_color_attachments: context.get_integer(consts::MAX_COLOR_ATTACHMENTS) as u32,
+            max_uniform_block_size: context.get_integer(consts::MAX_UNIFORM_BLOCK_SIZE) as u32,
+            max_anisotropy: if anisotropic_filtering {
+                Some(context.get_float(MAX_TEXTURE_MAX_ANISOTROPY_EXT))
+            } else {
+                None
+            },
+            color_buffer_float,
+            float_texture_linear,
+            clip_control,
+            draw_buffers_blend,
+            transform_feedback: true,
+            geometry_shader,
+            extensions,
+        }
+    }
+
+    ///
+    /// Returns whether or not the extension with the given name, for example `EXT_texture_filter_anisotropic`, is supported.
+    /// The `GL_` prefix used by OpenGL is optional.
+    ///
+    pub fn supports_extension(&self, name: &str) -> bool {
+        let name = name.trim_start_matches("GL_");
+        self.extensions
+            .iter()
+            .any(|e| e.trim_start_matches("GL_") == name)
+    }
+}
@@ -0,0 +1,570 @@
+//!
+//! Collision queries against triangle meshes, for example for dropping objects onto loaded geometry
+//! or moving a character along walls, see [TriMeshCollider].
+//!
+
+use crate::core::*;
+
+const MAX_LEAF_SIZE: usize = 4;
+const MAX_DEPTH: usize = 64;
+
+///
+/// The result of a collision query against a [TriMeshCollider].
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    /// The point of contact on the mesh in world space.
+    pub position: Vec3,
+    /// The unit normal at the point of contact in world space, pointing towards the ray or sphere.
+    pub normal: Vec3,
+    /// The distance travelled by the ray or sphere before the hit.
+    pub distance: f32,
+    /// The fraction of the maximum distance or motion travelled before the hit, ie. the time of impact in the range `[0..1]`.
+    pub time: f32,
+    /// The index of the triangle that was hit, in the same order as [CPUMesh::for_each_triangle].
+    pub triangle_index: usize,
+    /// The barycentric coordinates of the point of contact in the triangle that was hit,
+    /// ie. the weights of the three vertices of the triangle in the order given by [CPUMesh::for_each_triangle].
+    /// Use them to interpolate vertex attributes, for example the uv coordinates, at the point of contact.
+    pub barycentric: Vec3,
+}
+
+///
+/// A collider for a triangle mesh which supports closest point queries, raycasts and sphere casts,
+/// for example for simple physics like dropping objects onto loaded geometry or moving a character along walls.
+///
+/// The triangles are stored in a bounding volume hierarchy (BVH) in the local space of the mesh,
+/// so changing the transformation does not rebuild the hierarchy, instead the queries are transformed into the local space.
+/// Closest point queries and sphere casts assume that the transformation has uniform scale,
+/// only raycasts are exact for transformations with non-uniform scale.
+///
+pub struct TriMeshCollider {
+    triangles: Vec<[Vec3; 3]>,
+    triangle_indices: Vec<u32>,
+    nodes: Vec<Node>,
+    transformation: Mat4,
+    inverse_transformation: Mat4,
+    normal_transformation: Mat3,
+    scale: f32,
+}
+
+// A node in the bounding volume hierarchy, the left child of an internal node is always the next node
+#[derive(Clone, Copy)]
+struct Node {
+    min: Vec3,
+    max: Vec3,
+    // The index of the right child for an internal node or the index of the first triangle for a leaf
+    offset: u32,
+    // The number of triangles in a leaf or zero for an internal node
+    count: u32,
+}
+
+impl TriMeshCollider {
+    ///
+    /// Constructs a new collider for the triangles in the given mesh placed in the world by the given transformation.
+    /// Returns an error if the transformation cannot be inverted, see [TriMeshCollider::set_transformation].
+    ///
+    pub fn new(cpu_mesh: &CPUMesh, transformation: Mat4) -> ThreeDResult<Self> {
+        let mut triangles = Vec::new();
+        cpu_mesh.for_each_triangle(|i0, i1, i2| {
+            triangles.push([
+                cpu_mesh.position(i0),
+                cpu_mesh.position(i1),
+                cpu_mesh.position(i2),
+            ]);
+        });
+        let centroids = triangles
+            .iter()
+            .map(|t| (t[0] + t[1] + t[2]) / 3.0)
+            .collect::<Vec<_>>();
+        let mut order = (0..triangles.len() as u32).collect::<Vec<_>>();
+        let mut nodes = Vec::with_capacity(2 * triangles.len() / MAX_LEAF_SIZE + 1);
+        if !triangles.is_empty() {
+            build(&mut nodes, &triangles, &centroids, &mut order, 0, 0);
+        }
+        let triangles = order.iter().map(|i| triangles[*i as usize]).collect();
+
+        let mut collider = Self {
+            triangles,
+            triangle_indices: order,
+            nodes,
+            transformation: Mat4::identity(),
+            inverse_transformation: Mat4::identity(),
+            normal_transformation: Mat3::identity(),
+            scale: 1.0,
+        };
+        collider.set_transformation(transformation)?;
+        Ok(collider)
+    }
+
+    ///
+    /// Set the local to world transformation of the mesh. This is cheap since the bounding volume hierarchy is not rebuilt.
+    /// Returns an error and keeps the previous transformation if the transformation cannot be inverted, for example if it has a zero scale,
+    /// since the queries are transformed into the local space of the mesh.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) -> ThreeDResult<()> {
+        let m = Mat3::from_cols(
+            transformation.x.truncate(),
+            transformation.y.truncate(),
+            transformation.z.truncate(),
+        );
+        let (inverse_transformation, inverse_normal_transformation) =
+            match (transformation.invert(), m.invert()) {
+                (Some(inverse), Some(inverse_normal)) => (inverse, inverse_normal),
+                _ => Err(CoreError::SingularTransformation)?,
+            };
+        self.transformation = transformation;
+        self.inverse_transformation = inverse_transformation;
+        self.normal_transformation = inverse_normal_transformation.transpose();
+        self.scale = m.determinant().abs().cbrt();
+        Ok(())
+    }
+
+    ///
+    /// Returns the local to world transformation of the mesh.
+    ///
+    pub fn transformation(&self) -> &Mat4 {
+        &self.transformation
+    }
+
+    ///
+    /// Returns the point on the mesh closest to the given position and the distance to it.
+    /// Returns the position itself and an infinite distance if the mesh is empty.
+    ///
+    pub fn closest_point(&self, position: Vec3) -> (Vec3, f32) {
+        if self.nodes.is_empty() {
+            return (position, f32::INFINITY);
+        }
+        let p = self.to_local(position);
+        let mut best = (p, f32::INFINITY);
+        let mut stack = [0u32; MAX_DEPTH];
+        let mut stack_size = 1;
+        while stack_size > 0 {
+            stack_size -= 1;
+            let node = &self.nodes[stack[stack_size] as usize];
+            if distance2_to_box(p, node.min, node.max) >= best.1 {
+                continue;
+            }
+            if node.count > 0 {
+                for tri in self.leaf_triangles(node) {
+                    let closest = closest_point_on_triangle(p, tri);
+                    let distance2 = (closest - p).magnitude2();
+                    if distance2 < best.1 {
+                        best = (closest, distance2);
+                    }
+                }
+            } else {
+                // Visit the closest child first
+                let left = stack[stack_size] + 1;
+                let right = node.offset;
+                let left_node = &self.nodes[left as usize];
+                let right_node = &self.nodes[right as usize];
+                let (first, second) = if distance2_to_box(p, left_node.min, left_node.max)
+                    <= distance2_to_box(p, right_node.min, right_node.max)
+                {
+                    (left, right)
+                } else {
+                    (right, left)
+                };
+                stack[stack_size] = second;
+                stack[stack_size + 1] = first;
+                stack_size += 2;
+            }
+        }
+        let closest = self.to_world(best.0);
+        (closest, closest.distance(position))
+    }
+
+    ///
+    /// Returns the first hit between the mesh and the ray starting at the given origin in the given direction within the given maximum distance,
+    /// or `None` if the ray does not hit the mesh.
+    ///
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<Hit> {
+        if self.nodes.is_empty() || direction.magnitude2() == 0.0 {
+            return None;
+        }
+        let direction = direction.normalize();
+        // The local ray is parameterized such that the end is at one
+        let o = self.to_local(origin);
+        let d = self.to_local(origin + direction * max_distance) - o;
+        let inv_d = vec3(1.0 / d.x, 1.0 / d.y, 1.0 / d.z);
+
+        let mut best: Option<(f32, usize)> = None;
+        let mut stack = [0u32; MAX_DEPTH];
+        let mut stack_size = 1;
+        while stack_size > 0 {
+            stack_size -= 1;
+            let index = stack[stack_size];
+            let node = &self.nodes[index as usize];
+            let t_max = best.map(|b| b.0).unwrap_or(1.0);
+            if !ray_intersects_box(o, inv_d, node.min, node.max, t_max) {
+                continue;
+            }
+            if node.count > 0 {
+                for i in node.offset..node.offset + node.count {
+                    if let Some(t) = ray_triangle(o, d, &self.triangles[i as usize]) {
+                        if t <= best.map(|b| b.0).unwrap_or(1.0) {
+                            best = Some((t, i as usize));
+                        }
+                    }
+                }
+            } else {
+                stack[stack_size] = node.offset;
+                stack[stack_size + 1] = index + 1;
+                stack_size += 2;
+            }
+        }
+
+        best.map(|(t, i)| {
+            let normal = self.to_world_normal(triangle_normal(&self.triangles[i]));
+            Hit {
+                position: origin + direction * t * max_distance,
+                normal: if normal.dot(direction) > 0.0 {
+                    -normal
+                } else {
+                    normal
+                },
+                distance: t * max_distance,
+                time: t,
+                triangle_index: self.triangle_indices[i] as usize,
+                barycentric: barycentric(o + d * t, &self.triangles[i]),
+            }
+        })
+    }
+
+    ///
+    /// Returns the first hit between the mesh and a sphere with the given center and radius moving by the given motion,
+    /// or `None` if the sphere does not hit the mesh during the motion.
+    /// The time of impact is given by [Hit::time] and the contact normal by [Hit::normal],
+    /// which can be used to slide along the mesh by removing the motion along the normal.
+    /// If the sphere already intersects the mesh, the time of impact is zero.
+    ///
+    pub fn sphere_cast(&self, center: Vec3, radius: f32, motion: Vec3) -> Option<Hit> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let c = self.to_local(center);
+        let m = self.to_local(center + motion) - c;
+        let r = radius / self.scale;
+        let inv_m = vec3(1.0 / m.x, 1.0 / m.y, 1.0 / m.z);
+        let expand = vec3(r, r, r);
+
+        let mut best: Option<(f32, Vec3, usize)> = None;
+        let mut stack = [0u32; MAX_DEPTH];
+        let mut stack_size = 1;
+        while stack_size > 0 {
+            stack_size -= 1;
+            let index = stack[stack_size];
+            let node = &self.nodes[index as usize];
+            let t_max = best.map(|b| b.0).unwrap_or(1.0);
+            let (min, max) = (node.min - expand, node.max + expand);
+            if distance2_to_box(c, min, max) > 0.0 && !ray_intersects_box(c, inv_m, min, max, t_max)
+            {
+                continue;
+            }
+            if node.count > 0 {
+                for i in node.offset..node.offset + node.count {
+                    if let Some((t, contact)) =
+                        sphere_triangle(c, r, m, &self.triangles[i as usize])
+                    {
+                        if t <= best.map(|b| b.0).unwrap_or(1.0) {
+                            best = Some((t, contact, i as usize));
+                        }
+                    }
+                }
+            } else {
+                stack[stack_size] = node.offset;
+                stack[stack_size + 1] = index + 1;
+                stack_size += 2;
+            }
+        }
+
+        best.map(|(t, contact, i)| {
+            let center_at_impact = c + m * t;
+            let normal = if (center_at_impact - contact).magnitude2() > 0.0 {
+                self.to_world_normal(center_at_impact - contact)
+            } else {
+                let normal = self.to_world_normal(triangle_normal(&self.triangles[i]));
+                if normal.dot(motion) > 0.0 {
+                    -normal
+                } else {
+                    normal
+                }
+            };
+            Hit {
+                position: self.to_world(contact),
+                normal,
+                distance: t * motion.magnitude(),
+                time: t,
+                triangle_index: self.triangle_indices[i] as usize,
+                barycentric: barycentric(contact, &self.triangles[i]),
+            }
+        })
+    }
+
+    fn leaf_triangles<'a>(&'a self, node: &Node) -> &'a [[Vec3; 3]] {
+        &self.triangles[node.offset as usize..(node.offset + node.count) as usize]
+    }
+
+    fn to_local(&self, position: Vec3) -> Vec3 {
+        (self.inverse_transformation * position.extend(1.0)).truncate()
+    }
+
+    fn to_world(&self, position: Vec3) -> Vec3 {
+        (self.transformation * position.extend(1.0)).truncate()
+    }
+
+    fn to_world_normal(&self, normal: Vec3) -> Vec3 {
+        (self.normal_transformation * normal).normalize()
+    }
+}
+
+fn build(
+    nodes: &mut Vec<Node>,
+    triangles: &[[Vec3; 3]],
+    centroids: &[Vec3],
+    order: &mut [u32],
+    offset: usize,
+    depth: usize,
+) -> usize {
+    let index = nodes.len();
+    let mut min = vec3(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    let mut max = vec3(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+    let mut centroid_min = min;
+    let mut centroid_max = max;
+    for i in order.iter() {
+        for p in triangles[*i as usize].iter() {
+            min = min_by_component(min, *p);
+            max = max_by_component(max, *p);
+        }
+        centroid_min = min_by_component(centroid_min, centroids[*i as usize]);
+        centroid_max = max_by_component(centroid_max, centroids[*i as usize]);
+    }
+    nodes.push(Node {
+        min,
+        max,
+        offset: offset as u32,
+        count: order.len() as u32,
+    });
+
+    // Split at the median along the longest axis of the centroids, which gives a balanced tree
+    let extent = centroid_max - centroid_min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+    if order.len() <= MAX_LEAF_SIZE || extent[axis] <= 0.0 || depth + 2 >= MAX_DEPTH {
+        return index;
+    }
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by(mid, |a, b| {
+        centroids[*a as usize][axis]
+            .partial_cmp(&centroids[*b as usize][axis])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let (left, right) = order.split_at_mut(mid);
+    build(nodes, triangles, centroids, left, offset, depth + 1);
+    let right_index = build(nodes, triangles, centroids, right, offset + mid, depth + 1);
+    nodes[index].offset = right_index as u32;
+    nodes[index].count = 0;
+    index
+}
+
+fn min_by_component(a: Vec3, b: Vec3) -> Vec3 {
+    vec3(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))
+}
+
+fn max_by_component(a: Vec3, b: Vec3) -> Vec3 {
+    vec3(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+}
+
+fn triangle_normal(triangle: &[Vec3; 3]) -> Vec3 {
+    (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0])
+}
+
+// Returns the barycentric coordinates of the given point projected onto the plane of the triangle
+fn barycentric(p: Vec3, triangle: &[Vec3; 3]) -> Vec3 {
+    let [a, b, c] = *triangle;
+    let v0 = b - a;
+    let v1 = c - a;
+    let v2 = p - a;
+    let d00 = v0.dot(v0);
+    let d01 = v0.dot(v1);
+    let d11 = v1.dot(v1);
+    let d20 = v2.dot(v0);
+    let d21 = v2.dot(v1);
+    let denominator = d00 * d11 - d01 * d01;
+    if denominator == 0.0 {
+        return vec3(1.0, 0.0, 0.0);
+    }
+    let v = (d11 * d20 - d01 * d21) / denominator;
+    let w = (d00 * d21 - d01 * d20) / denominator;
+    vec3(1.0 - v - w, v, w)
+}
+
+fn distance2_to_box(p: Vec3, min: Vec3, max: Vec3) -> f32 {
+    let d = max_by_component(max_by_component(min - p, p - max), vec3(0.0, 0.0, 0.0));
+    d.magnitude2()
+}
+
+// Returns whether the ray intersects the box before the given maximum parameter value
+fn ray_intersects_box(origin: Vec3, inv_direction: Vec3, min: Vec3, max: Vec3, t_max: f32) -> bool {
+    let t0 = (min - origin).mul_element_wise(inv_direction);
+    let t1 = (max - origin).mul_element_wise(inv_direction);
+    let near = min_by_component(t0, t1);
+    let far = max_by_component(t0, t1);
+    let t_near = near.x.max(near.y).max(near.z).max(0.0);
+    let t_far = far.x.min(far.y).min(far.z).min(t_max);
+    t_near <= t_far
+}
+
+// Möller-Trumbore ray triangle intersection, returns the ray parameter of the intersection
+fn ray_triangle(origin: Vec3, direction: Vec3, triangle: &[Vec3; 3]) -> Option<f32> {
+    let e1 = triangle[1] - triangle[0];
+    let e2 = triangle[2] - triangle[0];
+    let p = direction.cross(e2);
+    let det = e1.dot(p);
+    if det == 0.0 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = origin - triangle[0];
+    let u = s.dot(p) * inv_det;
+    if u < 0.0 || u > 1.0 {
+        return None;
+    }
+    let q = s.cross(e1);
+    let v = direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = e2.dot(q) * inv_det;
+    if t >= 0.0 {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+// From Christer Ericson, "Real-Time Collision Detection"
+fn closest_point_on_triangle(p: Vec3, triangle: &[Vec3; 3]) -> Vec3 {
+    let [a, b, c] = *triangle;
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+// Returns the time of impact in the range [0..1] and the point of contact between a moving sphere and a triangle
+fn sphere_triangle(
+    center: Vec3,
+    radius: f32,
+    motion: Vec3,
+    triangle: &[Vec3; 3],
+) -> Option<(f32, Vec3)> {
+    let closest = closest_point_on_triangle(center, triangle);
+    if (center - closest).magnitude2() <= radius * radius {
+        return Some((0.0, closest));
+    }
+
+    // The sphere touches the inside of the triangle, which is always the first contact if it happens
+    let normal = triangle_normal(triangle);
+    if normal.magnitude2() > 0.0 {
+        let normal = normal.normalize();
+        let distance = (center - triangle[0]).dot(normal);
+        let n = if distance < 0.0 { -normal } else { normal };
+        let speed = motion.dot(n);
+        if speed < 0.0 {
+            let t = (distance.abs() - radius) / -speed;
+            if t >= 0.0 && t <= 1.0 {
+                let contact = center + motion * t - n * radius;
+                let inside = (0..3).all(|i| {
+                    (triangle[(i + 1) % 3] - triangle[i])
+                        .cross(contact - triangle[i])
+                        .dot(normal)
+                        >= 0.0
+                });
+                if inside {
+                    return Some((t, contact));
+                }
+            }
+        }
+    }
+
+    let mut best: Option<(f32, Vec3)> = None;
+    let a = motion.magnitude2();
+    if a == 0.0 {
+        return None;
+    }
+
+    // The sphere touches an edge, which is found by intersecting the path of the center with a cylinder around the edge
+    for i in 0..3 {
+        let p = triangle[i];
+        let e = triangle[(i + 1) % 3] - p;
+        let e2 = e.magnitude2();
+        let s = center - p;
+        let em = e.dot(motion);
+        let es = e.dot(s);
+        let qa = e2 * a - em * em;
+        let qb = e2 * s.dot(motion) - es * em;
+        let qc = e2 * (s.magnitude2() - radius * radius) - es * es;
+        let discriminant = qb * qb - qa * qc;
+        if qa > 0.0 && discriminant >= 0.0 {
+            let t = (-qb - discriminant.sqrt()) / qa;
+            if t >= 0.0 && t <= best.map(|b| b.0).unwrap_or(1.0) {
+                let f = (es + em * t) / e2;
+                if f >= 0.0 && f <= 1.0 {
+                    best = Some((t, p + e * f));
+                }
+            }
+        }
+    }
+
+    // The sphere touches a corner
+    for v in triangle.iter() {
+        let s = center - v;
+        let b = s.dot(motion);
+        let c = s.magnitude2() - radius * radius;
+        let discriminant = b * b - a * c;
+        if discriminant >= 0.0 {
+            let t = (-b - discriminant.sqrt()) / a;
+            if t >= 0.0 && t <= best.map(|b| b.0).unwrap_or(1.0) {
+                best = Some((t, *v));
+            }
+        }
+    }
+    best
+}
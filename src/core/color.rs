@@ -108,3 +108,81 @@ impl Default for Color {
         Color::WHITE
     }
 }
+
+///
+/// The color space of color values, which determines how they are converted to the linear color space where the lighting is computed.
+/// The result is converted to sRGB exactly once when it is written to the screen.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// The values are gamma encoded in the sRGB color space, like colors picked in an image editor or typed by hand, for example `Color::new(255, 128, 0, 255)`,
+    /// and the vertex colors of most .ply files.
+    Srgb,
+    /// The values are proportional to the intensity of the light, like the vertex colors of glTF files.
+    Linear,
+}
+
+impl Default for ColorSpace {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl ColorSpace {
+    ///
+    /// Converts the given color in this color space to a [`Vec4`] in the linear color space, where each component is in the range `0.0..=1.0`.
+    /// The alpha component is never converted.
+    ///
+    pub fn to_linear(&self, color: Color) -> Vec4 {
+        let c = color.to_vec4();
+        match self {
+            Self::Srgb => vec4(
+                rgb_from_srgb(c.x),
+                rgb_from_srgb(c.y),
+                rgb_from_srgb(c.z),
+                c.w,
+            ),
+            Self::Linear => c,
+        }
+    }
+
+    ///
+    /// Converts the given colors, where four contiguous bytes define a color `(r, g, b, a)`, from this color space to the given color space.
+    /// The alpha components are never converted.
+    ///
+    pub fn convert_colors(&self, colors: &[u8], color_space: ColorSpace) -> Vec<u8> {
+        let convert: fn(f32) -> f32 = match (self, color_space) {
+            (Self::Srgb, Self::Linear) => rgb_from_srgb,
+            (Self::Linear, Self::Srgb) => srgb_from_rgb,
+            _ => return colors.to_vec(),
+        };
+        colors
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                if i % 4 == 3 {
+                    *c
+                } else {
+                    (convert(*c as f32 / 255.0) * 255.0).round() as u8
+                }
+            })
+            .collect()
+    }
+}
+
+// The same conversions as in the shaders, see shared.frag
+fn rgb_from_srgb(srgb: f32) -> f32 {
+    if srgb < 0.04045 {
+        srgb / 12.92
+    } else {
+        ((srgb + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn srgb_from_rgb(rgb: f32) -> f32 {
+    if rgb < 0.0031308 {
+        rgb * 12.92
+    } else {
+        1.055 * rgb.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -7,6 +7,7 @@ use crate::core::*;
 /// A CPU-side version of a material.
 /// Can be constructed manually or loaded via [io](crate::io).
 ///
+#[derive(Clone)]
 pub struct CPUMaterial {
     /// Name. Used for matching geometry and material.
     pub name: String,
@@ -14,6 +15,10 @@ pub struct CPUMaterial {
     pub albedo: Color,
     /// Texture with albedo base colors, also called diffuse color. Assumed to be in sRGB with or without an alpha channel.
     pub albedo_texture: Option<CPUTexture<u8>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::albedo_texture], for example to repeat the texture.
+    pub albedo_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::albedo_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub albedo_texture_uv_set: u8,
     /// A value in the range `[0..1]` specifying how metallic the material is.
     pub metallic: f32,
     /// A value in the range `[0..1]` specifying how rough the material surface is.
@@ -26,22 +31,64 @@ pub struct CPUMaterial {
     /// The metallic values are sampled from the blue channel and the roughness from the green channel.
     /// Can be combined with occlusion into one texture, see [Self::occlusion_metallic_roughness_texture].
     pub metallic_roughness_texture: Option<CPUTexture<u8>>,
+    /// The transformation applied to the uv coordinates before sampling the metallic and roughness parameters
+    /// from the [Self::metallic_roughness_texture] or the [Self::occlusion_metallic_roughness_texture].
+    pub metallic_roughness_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the metallic and roughness parameters, ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub metallic_roughness_texture_uv_set: u8,
     /// A scalar multiplier controlling the amount of occlusion applied from the [Self::occlusion_texture]. A value of 0.0 means no occlusion. A value of 1.0 means full occlusion.
     pub occlusion_strength: f32,
     /// An occlusion map. Higher values indicate areas that should receive full indirect lighting and lower values indicate no indirect lighting.
     /// The occlusion values are sampled from the red channel.
     /// Can be combined with metallic and roughness into one texture, see [Self::occlusion_metallic_roughness_texture].
     pub occlusion_texture: Option<CPUTexture<u8>>,
+    /// The transformation applied to the uv coordinates before sampling the occlusion values
+    /// from the [Self::occlusion_texture] or the [Self::occlusion_metallic_roughness_texture].
+    pub occlusion_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the occlusion values, ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    /// Baked ambient occlusion is often mapped using the second set of uv coordinates, since it must not overlap.
+    pub occlusion_texture_uv_set: u8,
     /// A scalar multiplier applied to each normal vector of the [Self::normal_texture].
     pub normal_scale: f32,
     /// A tangent space normal map, also known as bump map.
     pub normal_texture: Option<CPUTexture<u8>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::normal_texture].
+    pub normal_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::normal_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub normal_texture_uv_set: u8,
     /// Color of light shining from an object.
     pub emissive: Color,
     /// Texture with color of light shining from an object.
     pub emissive_texture: Option<CPUTexture<u8>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::emissive_texture].
+    pub emissive_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::emissive_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub emissive_texture_uv_set: u8,
     /// Alpha cutout value for transparency in deferred rendering pipeline.
     pub alpha_cutout: Option<f32>,
+    /// Whether or not the back side of the geometry is rendered. If true, the back side is lit using the flipped normal, otherwise the back faces are culled. The default is true.
+    pub double_sided: bool,
+}
+
+impl CPUMaterial {
+    ///
+    /// Sets the [CPUTexture::anisotropy] of all of the textures of this material,
+    /// for example to improve the quality of a loaded floor material when viewed at a grazing angle.
+    ///
+    pub fn set_anisotropy(&mut self, anisotropy: Option<u32>) {
+        for texture in [
+            &mut self.albedo_texture,
+            &mut self.occlusion_metallic_roughness_texture,
+            &mut self.metallic_roughness_texture,
+            &mut self.occlusion_texture,
+            &mut self.normal_texture,
+            &mut self.emissive_texture,
+        ] {
+            if let Some(texture) = texture {
+                texture.anisotropy = anisotropy;
+            }
+        }
+    }
 }
 
 impl Default for CPUMaterial {
@@ -50,17 +97,28 @@ impl Default for CPUMaterial {
             name: "default".to_string(),
             albedo: Color::WHITE,
             albedo_texture: None,
+            albedo_texture_transform: Mat3::identity(),
+            albedo_texture_uv_set: 0,
             occlusion_metallic_roughness_texture: None,
             metallic_roughness_texture: None,
+            metallic_roughness_texture_transform: Mat3::identity(),
+            metallic_roughness_texture_uv_set: 0,
             occlusion_texture: None,
+            occlusion_texture_transform: Mat3::identity(),
+            occlusion_texture_uv_set: 0,
             metallic: 0.0,
             roughness: 1.0,
             occlusion_strength: 1.0,
             normal_texture: None,
+            normal_texture_transform: Mat3::identity(),
+            normal_texture_uv_set: 0,
             normal_scale: 1.0,
             emissive: Color::BLACK,
             emissive_texture: None,
+            emissive_texture_transform: Mat3::identity(),
+            emissive_texture_uv_set: 0,
             alpha_cutout: None,
+            double_sided: true,
         }
     }
 }
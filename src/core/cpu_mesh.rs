@@ -26,6 +26,22 @@ impl Indices {
     }
 }
 
+///
+/// A morph target (also called a blend shape) which deforms a [CPUMesh], for example into a facial expression.
+/// The deltas are added to the positions and normals of the mesh, scaled by the weight of the morph target.
+///
+#[derive(Default, Debug, Clone)]
+pub struct MorphTarget {
+    /// Name.
+    pub name: String,
+    /// The position deltas of the vertices. Three contiguous floats defines a delta `(x, y, z)`, therefore the length must be the same as the length of [CPUMesh::positions].
+    pub positions: Vec<f32>,
+    /// The normal deltas of the vertices. Three contiguous floats defines a delta `(x, y, z)`, therefore the length must be the same as the length of [CPUMesh::positions].
+    pub normals: Option<Vec<f32>>,
+    /// The default weight of this morph target.
+    pub weight: f32,
+}
+
 ///
 /// A CPU-side version of a triangle mesh.
 /// Can be constructed manually or loaded via [io](crate::io)
@@ -44,13 +60,28 @@ pub struct CPUMesh {
     pub indices: Option<Indices>,
     /// The normals of the vertices. Three contiguous floats defines a normal `(x, y, z)`, therefore the length must be divisable by 3.
     pub normals: Option<Vec<f32>>,
-    /// The tangents of the vertices, orthogonal direction to the normal. Three contiguous floats defines a tangent `(x, y, z)`, therefore the length must be divisable by 3.
+    /// The tangents of the vertices, orthogonal direction to the normal. Four contiguous floats defines a tangent `(x, y, z, w)`, where `w` is the handedness of the tangent space, ie. 1 or -1,
+    /// therefore the length must be divisable by 4.
     pub tangents: Option<Vec<f32>>,
     /// The uv coordinates of the vertices. Two contiguous floats defines a coordinate `(u, v)`, therefore the length must be divisable by 2.
     pub uvs: Option<Vec<f32>>,
+    /// A second set of uv coordinates of the vertices, usually used for a texture where each part of the mesh must be mapped to a unique part of the texture, for example a lightmap.
+    /// Two contiguous floats defines a coordinate `(u, v)`, therefore the length must be divisable by 2.
+    pub uvs2: Option<Vec<f32>>,
     /// The colors of the vertices. Four contiguous bytes defines a color `(r, g, b, a)`, therefore the length must be divisable by 4.
-    /// The colors are assumed to be in linear space.
+    /// The color space of the colors is given by [CPUMesh::color_space].
     pub colors: Option<Vec<u8>>,
+    /// The color space of [CPUMesh::colors], which the loaders set according to the file format.
+    /// The colors are converted to linear space in the shaders if they are in sRGB. The default is [ColorSpace::Linear].
+    pub color_space: ColorSpace,
+    /// The morph targets which can deform this mesh, see [MorphTarget].
+    pub morph_targets: Vec<MorphTarget>,
+    /// The indices of the joints of a [Skeleton] which deform each vertex when the mesh is animated.
+    /// Four contiguous indices defines the joints of a vertex, therefore the length must be divisable by 4.
+    pub joint_indices: Option<Vec<u16>>,
+    /// The weights of the joints given by [CPUMesh::joint_indices]. Four contiguous floats defines the weights of a vertex, which should sum to one,
+    /// therefore the length must be the same as the length of [CPUMesh::joint_indices].
+    pub joint_weights: Option<Vec<f32>>,
 }
 
 impl CPUMesh {
@@ -66,6 +97,16 @@ impl CPUMesh {
         })
     }
 
+    ///
+    /// Converts the vertex colors, if any, to the given color space and sets [CPUMesh::color_space] accordingly.
+    ///
+    pub fn convert_color_space(&mut self, color_space: ColorSpace) {
+        if let Some(ref mut colors) = self.colors {
+            *colors = self.color_space.convert_colors(colors, color_space);
+        }
+        self.color_space = color_space;
+    }
+
     ///
     /// Transforms the mesh by the given transformation.
     ///
@@ -96,17 +137,17 @@ impl CPUMesh {
         }
 
         if let Some(ref mut tangents) = self.tangents {
-            for i in 0..tangents.len() / 3 {
+            for i in 0..tangents.len() / 4 {
                 let t = normal_transform
                     * vec4(
-                        tangents[i * 3],
-                        tangents[i * 3 + 1],
-                        tangents[i * 3 + 2],
+                        tangents[i * 4],
+                        tangents[i * 4 + 1],
+                        tangents[i * 4 + 2],
                         1.0,
                     );
-                tangents[i * 3] = t.x;
-                tangents[i * 3 + 1] = t.y;
-                tangents[i * 3 + 2] = t.z;
+                tangents[i * 4] = t.x;
+                tangents[i * 4 + 1] = t.y;
+                tangents[i * 4 + 2] = t.z;
             }
         }
     }
@@ -172,72 +213,67 @@ impl CPUMesh {
 
     ///
     /// Returns a sphere mesh with radius 1 and center in `(0, 0, 0)`.
+    /// The uv coordinates map the longitude to the u coordinate and the latitude to the v coordinate,
+    /// so the vertices along the seam and at the poles are duplicated.
     ///
     pub fn sphere(angle_subdivisions: u32) -> Self {
         let mut positions = Vec::new();
         let mut indices = Vec::new();
         let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let segments = angle_subdivisions * 2;
 
-        positions.push(0.0);
-        positions.push(0.0);
-        positions.push(1.0);
-
-        normals.push(0.0);
-        normals.push(0.0);
-        normals.push(1.0);
-
-        for j in 0..angle_subdivisions * 2 {
-            let j1 = (j + 1) % (angle_subdivisions * 2);
-            indices.push(0);
-            indices.push((1 + j) as u16);
-            indices.push((1 + j1) as u16);
+        for j in 0..segments {
+            positions.extend_from_slice(&[0.0, 0.0, 1.0]);
+            normals.extend_from_slice(&[0.0, 0.0, 1.0]);
+            uvs.extend_from_slice(&[(j as f32 + 0.5) / segments as f32, 1.0]);
         }
 
         for i in 0..angle_subdivisions - 1 {
             let theta = std::f32::consts::PI * (i + 1) as f32 / angle_subdivisions as f32;
             let sin_theta = theta.sin();
             let cos_theta = theta.cos();
-            let i0 = 1 + i * angle_subdivisions * 2;
-            let i1 = 1 + (i + 1) * angle_subdivisions * 2;
-
-            for j in 0..angle_subdivisions * 2 {
+            for j in 0..segments + 1 {
                 let phi = std::f32::consts::PI * j as f32 / angle_subdivisions as f32;
                 let x = sin_theta * phi.cos();
                 let y = sin_theta * phi.sin();
                 let z = cos_theta;
-                positions.push(x);
-                positions.push(y);
-                positions.push(z);
-
-                normals.push(x);
-                normals.push(y);
-                normals.push(z);
-
-                if i != angle_subdivisions - 2 {
-                    let j1 = (j + 1) % (angle_subdivisions * 2);
-                    indices.push((i0 + j) as u16);
-                    indices.push((i1 + j1) as u16);
-                    indices.push((i0 + j1) as u16);
-                    indices.push((i1 + j1) as u16);
-                    indices.push((i0 + j) as u16);
-                    indices.push((i1 + j) as u16);
-                }
+                positions.extend_from_slice(&[x, y, z]);
+                normals.extend_from_slice(&[x, y, z]);
+                uvs.extend_from_slice(&[
+                    j as f32 / segments as f32,
+                    1.0 - (i + 1) as f32 / angle_subdivisions as f32,
+                ]);
+            }
+        }
+
+        for j in 0..segments {
+            positions.extend_from_slice(&[0.0, 0.0, -1.0]);
+            normals.extend_from_slice(&[0.0, 0.0, -1.0]);
+            uvs.extend_from_slice(&[(j as f32 + 0.5) / segments as f32, 0.0]);
+        }
+
+        let ring = |i: u32, j: u32| (segments + i * (segments + 1) + j) as u16;
+        for j in 0..segments {
+            indices.push(j as u16);
+            indices.push(ring(0, j));
+            indices.push(ring(0, j + 1));
+        }
+        for i in 0..angle_subdivisions - 2 {
+            for j in 0..segments {
+                indices.push(ring(i, j));
+                indices.push(ring(i + 1, j + 1));
+                indices.push(ring(i, j + 1));
+                indices.push(ring(i + 1, j + 1));
+                indices.push(ring(i, j));
+                indices.push(ring(i + 1, j));
             }
         }
-        positions.push(0.0);
-        positions.push(0.0);
-        positions.push(-1.0);
-
-        normals.push(0.0);
-        normals.push(0.0);
-        normals.push(-1.0);
-
-        let i = 1 + (angle_subdivisions - 2) * angle_subdivisions * 2;
-        for j in 0..angle_subdivisions * 2 {
-            let j1 = (j + 1) % (angle_subdivisions * 2);
-            indices.push((i + j) as u16);
-            indices.push(((angle_subdivisions - 1) * angle_subdivisions * 2 + 1) as u16);
-            indices.push((i + j1) as u16);
+        let south_pole = segments + (angle_subdivisions - 1) * (segments + 1);
+        for j in 0..segments {
+            indices.push(ring(angle_subdivisions - 2, j));
+            indices.push((south_pole + j) as u16);
+            indices.push(ring(angle_subdivisions - 2, j + 1));
         }
 
         CPUMesh {
@@ -245,6 +281,7 @@ impl CPUMesh {
             indices: Some(Indices::U16(indices)),
             positions,
             normals: Some(normals),
+            uvs: Some(uvs),
             ..Default::default()
         }
     }
@@ -281,110 +318,280 @@ impl CPUMesh {
 
     ///
     /// Returns a cylinder mesh around the x-axis in the range `[0..1]` and with radius 1.
+    /// The uv coordinates map the angle around the x-axis to the u coordinate and the x coordinate to the v coordinate,
+    /// so the vertices along the seam are duplicated.
     ///
     pub fn cylinder(angle_subdivisions: u32) -> Self {
-        let length_subdivisions = 1;
+        let normal = vec2(0.0, 1.0);
+        revolve(
+            "cylinder",
+            &[vec![
+                ProfilePoint::new(0.0, 1.0, normal),
+                ProfilePoint::new(1.0, 1.0, normal),
+            ]],
+            angle_subdivisions,
+        )
+    }
+
+    ///
+    /// Returns a cone mesh around the x-axis in the range `[0..1]` and with radius 1 at 0.0.
+    ///
+    pub fn cone(angle_subdivisions: u32) -> Self {
+        Self::cone_with_options(1.0, 1.0, angle_subdivisions, false)
+    }
+
+    ///
+    /// Returns a cone mesh around the x-axis in the range `[0..height]` with the given radius at 0.0 and the tip at `height`,
+    /// optionally with a flat cap at the base, where the vertices along the rim are duplicated to give a hard edge.
+    /// The uv coordinates map the angle around the x-axis to the u coordinate and the distance along the surface from the center of the base to the tip to the v coordinate.
+    ///
+    pub fn cone_with_options(
+        radius: f32,
+        height: f32,
+        angle_subdivisions: u32,
+        capped: bool,
+    ) -> Self {
+        let mut strips = Vec::new();
+        if capped {
+            let normal = vec2(-1.0, 0.0);
+            strips.push(vec![
+                ProfilePoint::new(0.0, 0.0, normal),
+                ProfilePoint::new(0.0, radius, normal),
+            ]);
+        }
+        let normal = vec2(radius, height).normalize();
+        strips.push(vec![
+            ProfilePoint::new(0.0, radius, normal),
+            ProfilePoint::new(height, 0.0, normal),
+        ]);
+        revolve("cone", &strips, angle_subdivisions)
+    }
+
+    ///
+    /// Returns an arrow mesh around the x-axis in the range `[0..1]` and with radius 1.
+    /// The tail length and radius should be in the range `]0..1[`.
+    ///
+    pub fn arrow(tail_length: f32, tail_radius: f32, angle_subdivisions: u32) -> Self {
+        Self::arrow_with_options(tail_radius, 1.0, 1.0 - tail_length, angle_subdivisions)
+    }
+
+    ///
+    /// Returns an arrow mesh around the x-axis in the range `[0..1]` consisting of a shaft with the given radius and a conical head with the given radius,
+    /// where the head takes up the given fraction of the length. Useful for example for gizmos or for displaying a vector field using instancing.
+    /// The end of the shaft and the back of the head are closed with hard edges.
+    ///
+    pub fn arrow_with_options(
+        shaft_radius: f32,
+        head_radius: f32,
+        head_fraction: f32,
+        angle_subdivisions: u32,
+    ) -> Self {
+        let shaft_length = 1.0 - head_fraction;
+        let back = vec2(-1.0, 0.0);
+        let side = vec2(0.0, 1.0);
+        let head = vec2(head_radius, head_fraction).normalize();
+        revolve(
+            "arrow",
+            &[
+                vec![
+                    ProfilePoint::new(0.0, 0.0, back),
+                    ProfilePoint::new(0.0, shaft_radius, back),
+                ],
+                vec![
+                    ProfilePoint::new(0.0, shaft_radius, side),
+                    ProfilePoint::new(shaft_length, shaft_radius, side),
+                ],
+                vec![
+                    ProfilePoint::new(shaft_length, shaft_radius, back),
+                    ProfilePoint::new(shaft_length, head_radius, back),
+                ],
+                vec![
+                    ProfilePoint::new(shaft_length, head_radius, head),
+                    ProfilePoint::new(1.0, 0.0, head),
+                ],
+            ],
+            angle_subdivisions,
+        )
+    }
+
+    ///
+    /// Returns a capsule mesh around the x-axis with center in `(0, 0, 0)`, consisting of a cylinder with the given radius and height
+    /// and a hemisphere with the same radius at each end, so the total length is `height + 2 * radius`.
+    /// The hemispheres are divided into the given number of rings and the surface is smooth everywhere.
+    /// The uv coordinates map the angle around the x-axis to the u coordinate and the distance along the surface from one end to the other to the v coordinate.
+    ///
+    pub fn capsule(radius: f32, height: f32, angle_subdivisions: u32, rings: u32) -> Self {
+        let mut profile = Vec::new();
+        for k in 0..rings + 1 {
+            let angle = 0.5 * std::f32::consts::PI * k as f32 / rings as f32;
+            let normal = vec2(-angle.cos(), angle.sin());
+            profile.push(ProfilePoint::new(
+                -0.5 * height + radius * normal.x,
+                radius * normal.y,
+                normal,
+            ));
+        }
+        for k in 0..rings + 1 {
+            let angle = 0.5 * std::f32::consts::PI * k as f32 / rings as f32;
+            let normal = vec2(angle.sin(), angle.cos());
+            profile.push(ProfilePoint::new(
+                0.5 * height + radius * normal.x,
+                radius * normal.y,
+                normal,
+            ));
+        }
+        revolve("capsule", &[profile], angle_subdivisions)
+    }
+
+    ///
+    /// Returns an axis aligned cube mesh with center in `(0, 0, 0)` and the given side length, where the edges and corners are rounded with the given radius.
+    /// The rounded edges are divided into the given number of segments and are smooth, while a radius of zero gives a cube with hard edges.
+    /// Each side is mapped to the full `[0..1]` range of uv coordinates, so the vertices where the sides meet are duplicated.
+    ///
+    pub fn rounded_cube(size: f32, corner_radius: f32, corner_segments: u32) -> Self {
+        let half_size = 0.5 * size;
+        let radius = corner_radius.max(0.0).min(half_size);
+        let inner = half_size - radius;
+
+        // The coordinates of the grid on each side, which are spaced such that the angles of the normals on the rounded edges are evenly spaced
+        let mut coordinates = Vec::new();
+        if radius > 0.0 && corner_segments > 0 {
+            for k in 0..corner_segments + 1 {
+                let angle = 0.25 * std::f32::consts::PI * (corner_segments - k) as f32
+                    / corner_segments as f32;
+                coordinates.push(-inner - radius * angle.tan());
+            }
+            // Avoids a flat part without area when the cube is a sphere
+            let start = if inner > 0.0 { 0 } else { 1 };
+            for k in start..corner_segments + 1 {
+                let angle = 0.25 * std::f32::consts::PI * k as f32 / corner_segments as f32;
+                coordinates.push(inner + radius * angle.tan());
+            }
+        } else {
+            coordinates.push(-half_size);
+            coordinates.push(half_size);
+        }
+
+        let sides = [
+            (
+                vec3(1.0, 0.0, 0.0),
+                vec3(0.0, 0.0, -1.0),
+                vec3(0.0, 1.0, 0.0),
+            ),
+            (
+                vec3(-1.0, 0.0, 0.0),
+                vec3(0.0, 0.0, 1.0),
+                vec3(0.0, 1.0, 0.0),
+            ),
+            (
+                vec3(0.0, 1.0, 0.0),
+                vec3(1.0, 0.0, 0.0),
+                vec3(0.0, 0.0, -1.0),
+            ),
+            (
+                vec3(0.0, -1.0, 0.0),
+                vec3(1.0, 0.0, 0.0),
+                vec3(0.0, 0.0, 1.0),
+            ),
+            (
+                vec3(0.0, 0.0, 1.0),
+                vec3(1.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+            ),
+            (
+                vec3(0.0, 0.0, -1.0),
+                vec3(-1.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+            ),
+        ];
+        let n = coordinates.len() as u32;
         let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
         let mut indices = Vec::new();
-        for i in 0..length_subdivisions + 1 {
-            let x = i as f32 / length_subdivisions as f32;
-            for j in 0..angle_subdivisions {
-                let angle = 2.0 * std::f32::consts::PI * j as f32 / angle_subdivisions as f32;
-
-                positions.push(x);
-                positions.push(angle.cos());
-                positions.push(angle.sin());
+        for (side, (normal, u_direction, v_direction)) in sides.iter().enumerate() {
+            for v in coordinates.iter() {
+                for u in coordinates.iter() {
+                    let point = normal * half_size + u_direction * *u + v_direction * *v;
+                    let clamped = vec3(
+                        point.x.max(-inner).min(inner),
+                        point.y.max(-inner).min(inner),
+                        point.z.max(-inner).min(inner),
+                    );
+                    let offset = point - clamped;
+                    let direction = if offset.magnitude() > 0.0 {
+                        offset.normalize()
+                    } else {
+                        *normal
+                    };
+                    let p = clamped + direction * radius;
+                    positions.extend_from_slice(&[p.x, p.y, p.z]);
+                    normals.extend_from_slice(&[direction.x, direction.y, direction.z]);
+                    uvs.extend_from_slice(&[(u + half_size) / size, (v + half_size) / size]);
+                }
             }
-        }
-        for i in 0..length_subdivisions {
-            for j in 0..angle_subdivisions {
-                indices.push((i * angle_subdivisions + j) as u16);
-                indices.push((i * angle_subdivisions + (j + 1) % angle_subdivisions) as u16);
-                indices.push(((i + 1) * angle_subdivisions + (j + 1) % angle_subdivisions) as u16);
-
-                indices.push((i * angle_subdivisions + j) as u16);
-                indices.push(((i + 1) * angle_subdivisions + (j + 1) % angle_subdivisions) as u16);
-                indices.push(((i + 1) * angle_subdivisions + j) as u16);
+            let offset = side as u32 * n * n;
+            for j in 0..n - 1 {
+                for i in 0..n - 1 {
+                    let index = offset + j * n + i;
+                    indices.extend_from_slice(&[index, index + 1, index + n + 1]);
+                    indices.extend_from_slice(&[index, index + n + 1, index + n]);
+                }
             }
         }
-        let mut mesh = Self {
-            name: "cylinder".to_string(),
+        CPUMesh {
+            name: "rounded cube".to_string(),
             positions,
-            indices: Some(Indices::U16(indices)),
+            indices: Some(Indices::U32(indices)),
+            normals: Some(normals),
+            uvs: Some(uvs),
             ..Default::default()
-        };
-        mesh.compute_normals();
-        mesh
+        }
     }
 
     ///
-    /// Returns a cone mesh around the x-axis in the range `[0..1]` and with radius 1 at -1.0.
+    /// Returns a plane mesh spanning the xy-plane with center in `(0, 0, 0)` and the given width and height,
+    /// which is divided into the given number of quads along the x and y axes, for example to be displaced by a height map.
+    /// The uv coordinates are in the range `[0..1]` across the plane.
     ///
-    pub fn cone(angle_subdivisions: u32) -> Self {
-        let length_subdivisions = 1;
+    pub fn plane_subdivided(
+        width: f32,
+        height: f32,
+        subdivisions_x: u32,
+        subdivisions_y: u32,
+    ) -> Self {
         let mut positions = Vec::new();
-        let mut indices = Vec::new();
-        for i in 0..length_subdivisions + 1 {
-            let x = i as f32 / length_subdivisions as f32;
-            for j in 0..angle_subdivisions {
-                let angle = 2.0 * std::f32::consts::PI * j as f32 / angle_subdivisions as f32;
-
-                positions.push(x);
-                positions.push(angle.cos() * (1.0 - x));
-                positions.push(angle.sin() * (1.0 - x));
+        let mut normals = Vec::new();
+        let mut tangents = Vec::new();
+        let mut uvs = Vec::new();
+        for j in 0..subdivisions_y + 1 {
+            let v = j as f32 / subdivisions_y as f32;
+            for i in 0..subdivisions_x + 1 {
+                let u = i as f32 / subdivisions_x as f32;
+                positions.extend_from_slice(&[(u - 0.5) * width, (v - 0.5) * height, 0.0]);
+                normals.extend_from_slice(&[0.0, 0.0, 1.0]);
+                tangents.extend_from_slice(&[1.0, 0.0, 0.0, 1.0]);
+                uvs.extend_from_slice(&[u, v]);
             }
         }
-        for i in 0..length_subdivisions {
-            for j in 0..angle_subdivisions {
-                indices.push((i * angle_subdivisions + j) as u16);
-                indices.push((i * angle_subdivisions + (j + 1) % angle_subdivisions) as u16);
-                indices.push(((i + 1) * angle_subdivisions + (j + 1) % angle_subdivisions) as u16);
-
-                indices.push((i * angle_subdivisions + j) as u16);
-                indices.push(((i + 1) * angle_subdivisions + (j + 1) % angle_subdivisions) as u16);
-                indices.push(((i + 1) * angle_subdivisions + j) as u16);
+        let mut indices = Vec::new();
+        let row = subdivisions_x + 1;
+        for j in 0..subdivisions_y {
+            for i in 0..subdivisions_x {
+                let index = j * row + i;
+                indices.extend_from_slice(&[index, index + 1, index + row + 1]);
+                indices.extend_from_slice(&[index + row + 1, index + row, index]);
             }
         }
-        let mut mesh = Self {
-            name: "cone".to_string(),
+        CPUMesh {
+            name: "plane".to_string(),
+            indices: Some(Indices::U32(indices)),
             positions,
-            indices: Some(Indices::U16(indices)),
+            normals: Some(normals),
+            tangents: Some(tangents),
+            uvs: Some(uvs),
             ..Default::default()
-        };
-        mesh.compute_normals();
-        mesh
-    }
-
-    ///
-    /// Returns an arrow mesh around the x-axis in the range `[0..1]` and with radius 1.
-    /// The tail length and radius should be in the range `]0..1[`.
-    ///
-    pub fn arrow(tail_length: f32, tail_radius: f32, angle_subdivisions: u32) -> Self {
-        let mut arrow = Self::cylinder(angle_subdivisions);
-        arrow.transform(&Mat4::from_nonuniform_scale(
-            tail_length,
-            tail_radius,
-            tail_radius,
-        ));
-        arrow.name = "arrow".to_string();
-        let mut cone = Self::cone(angle_subdivisions);
-        cone.transform(
-            &(Mat4::from_translation(vec3(tail_length, 0.0, 0.0))
-                * Mat4::from_nonuniform_scale(1.0 - tail_length, 1.0, 1.0)),
-        );
-        let mut indices = arrow.indices.unwrap().into_u32();
-        let cone_indices = cone.indices.unwrap().into_u32();
-        let offset = indices.iter().max().unwrap() + 1;
-        indices.extend(cone_indices.iter().map(|i| i + offset));
-        arrow.indices = Some(Indices::U16(indices.iter().map(|i| *i as u16).collect()));
-
-        arrow.positions.extend(cone.positions);
-        arrow
-            .normals
-            .as_mut()
-            .unwrap()
-            .extend(cone.normals.as_ref().unwrap());
-        arrow
+        }
     }
 
     ///
@@ -556,6 +763,15 @@ impl CPUMesh {
             .map(|uvs| vec2(uvs[2 * vertex_index], uvs[2 * vertex_index + 1]))
     }
 
+    ///
+    /// Returns the second set of uv coordinates of the vertex with the given index, see [CPUMesh::uvs2].
+    ///
+    pub fn uv2(&self, vertex_index: usize) -> Option<Vec2> {
+        self.uvs2
+            .as_ref()
+            .map(|uvs| vec2(uvs[2 * vertex_index], uvs[2 * vertex_index + 1]))
+    }
+
     ///
     /// Computes the axis aligned bounding box of the mesh.
     ///
@@ -563,6 +779,13 @@ impl CPUMesh {
         AxisAlignedBoundingBox::new_with_positions(&self.positions)
     }
 
+    ///
+    /// Computes a bounding sphere of the mesh, see [BoundingSphere::new_with_positions].
+    ///
+    pub fn compute_bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::new_with_positions(&self.positions)
+    }
+
     ///
     /// Returns an error if the mesh is not valid.
     ///
@@ -609,6 +832,30 @@ impl CPUMesh {
                     ))?;
                 }
             }
+            if let Some(ref data) = self.uvs2 {
+                if data.len() % 2 != 0 {
+                    Err(CoreError::InvalidBufferLength(
+                        "second uv coordinate".to_string(),
+                        index_count,
+                    ))?;
+                }
+            }
+            if let Some(ref data) = self.joint_indices {
+                if data.len() % 4 != 0 {
+                    Err(CoreError::InvalidBufferLength(
+                        "joint index".to_string(),
+                        index_count,
+                    ))?;
+                }
+            }
+            if let Some(ref data) = self.joint_weights {
+                if data.len() % 4 != 0 {
+                    Err(CoreError::InvalidBufferLength(
+                        "joint weight".to_string(),
+                        index_count,
+                    ))?;
+                }
+            }
             if cfg!(debug) {
                 let indices_valid = match indices {
                     Indices::U8(ind) => {
@@ -633,6 +880,268 @@ impl CPUMesh {
                 Err(CoreError::InvalidPositionBuffer(self.positions.len()))?;
             }
         };
+        for morph_target in self.morph_targets.iter() {
+            if morph_target.positions.len() != self.positions.len() {
+                Err(CoreError::InvalidMorphTargetLength(
+                    morph_target.name.clone(),
+                    morph_target.positions.len(),
+                    self.positions.len(),
+                ))?;
+            }
+            if let Some(ref normals) = morph_target.normals {
+                if normals.len() != self.positions.len() {
+                    Err(CoreError::InvalidMorphTargetLength(
+                        morph_target.name.clone(),
+                        normals.len(),
+                        self.positions.len(),
+                    ))?;
+                }
+            }
+        }
         Ok(())
     }
 }
+
+// A point on the profile of a surface of revolution around the x-axis, with the normal given in the plane spanned by the x-axis and the radius
+#[derive(Clone, Copy)]
+struct ProfilePoint {
+    x: f32,
+    radius: f32,
+    normal: Vec2,
+}
+
+impl ProfilePoint {
+    fn new(x: f32, radius: f32, normal: Vec2) -> Self {
+        Self { x, radius, normal }
+    }
+}
+
+// Revolves the strips of profile points around the x-axis, where the points within a strip are connected by a smooth surface
+// and the vertices where two strips meet are duplicated to give a hard edge. The outside of the surface is to the right of the profile,
+// so the profile goes from the center of the end at the lowest x coordinate towards the center of the end at the highest x coordinate.
+// The u coordinate is the angle around the x-axis and the v coordinate is the distance along the profile, both in the range [0..1].
+fn revolve(name: &str, strips: &[Vec<ProfilePoint>], angle_subdivisions: u32) -> CPUMesh {
+    let mut distances = Vec::new();
+    let mut distance = 0.0;
+    let mut previous: Option<&ProfilePoint> = None;
+    for point in strips.iter().flatten() {
+        if let Some(p) = previous {
+            distance += vec2(point.x - p.x, point.radius - p.radius).magnitude();
+        }
+        distances.push(distance);
+        previous = Some(point);
+    }
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    let ring_size = angle_subdivisions + 1;
+    let mut point_index = 0;
+    for strip in strips.iter() {
+        for (k, point) in strip.iter().enumerate() {
+            let ring = (positions.len() / 3) as u32;
+            let v = if distance > 0.0 {
+                distances[point_index] / distance
+            } else {
+                0.0
+            };
+            for j in 0..ring_size {
+                let u = j as f32 / angle_subdivisions as f32;
+                let angle = 2.0 * std::f32::consts::PI * u;
+                let (sin, cos) = angle.sin_cos();
+                positions.extend_from_slice(&[point.x, point.radius * cos, point.radius * sin]);
+                let normal =
+                    vec3(point.normal.x, point.normal.y * cos, point.normal.y * sin).normalize();
+                normals.extend_from_slice(&[normal.x, normal.y, normal.z]);
+                uvs.extend_from_slice(&[u, v]);
+            }
+            // Connects this ring to the previous ring in the strip, leaving out the triangles which are collapsed at the x-axis
+            if k > 0 {
+                let previous_ring = ring - ring_size;
+                for j in 0..angle_subdivisions {
+                    if strip[k - 1].radius > 0.0 {
+                        indices.extend_from_slice(&[
+                            previous_ring + j,
+                            previous_ring + j + 1,
+                            ring + j + 1,
+                        ]);
+                    }
+                    if point.radius > 0.0 {
+                        indices.extend_from_slice(&[previous_ring + j, ring + j + 1, ring + j]);
+                    }
+                }
+            }
+            point_index += 1;
+        }
+    }
+    CPUMesh {
+        name: name.to_string(),
+        positions,
+        indices: Some(Indices::U32(indices)),
+        normals: Some(normals),
+        uvs: Some(uvs),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Asserts that the mesh is valid and has the given number of vertices and triangles and the given bounding box
+    fn assert_mesh(
+        mesh: &CPUMesh,
+        vertex_count: usize,
+        triangle_count: usize,
+        min: Vec3,
+        max: Vec3,
+    ) {
+        mesh.validate().unwrap();
+        assert_eq!(mesh.positions.len() / 3, vertex_count, "{}", mesh.name);
+        let mut triangles = 0;
+        mesh.for_each_triangle(|_, _, _| triangles += 1);
+        assert_eq!(triangles, triangle_count, "{}", mesh.name);
+        let aabb = mesh.compute_aabb();
+        assert!(
+            (aabb.min() - min).magnitude() < 0.0001,
+            "{} {:?}",
+            mesh.name,
+            aabb
+        );
+        assert!(
+            (aabb.max() - max).magnitude() < 0.0001,
+            "{} {:?}",
+            mesh.name,
+            aabb
+        );
+        let uvs = mesh.uvs.as_ref().unwrap();
+        assert!(
+            uvs.iter().all(|uv| *uv >= 0.0 && *uv <= 1.0),
+            "{}",
+            mesh.name
+        );
+    }
+
+    #[test]
+    fn square() {
+        assert_mesh(
+            &CPUMesh::square(),
+            4,
+            2,
+            vec3(-1.0, -1.0, 0.0),
+            vec3(1.0, 1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn sphere() {
+        let n = 8;
+        assert_mesh(
+            &CPUMesh::sphere(n),
+            (4 * n + (n - 1) * (2 * n + 1)) as usize,
+            (4 * n * (n - 1)) as usize,
+            vec3(-1.0, -1.0, -1.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn cube() {
+        assert_mesh(
+            &CPUMesh::cube(),
+            36,
+            12,
+            vec3(-1.0, -1.0, -1.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn cylinder() {
+        let n = 16;
+        assert_mesh(
+            &CPUMesh::cylinder(n),
+            (2 * (n + 1)) as usize,
+            (2 * n) as usize,
+            vec3(0.0, -1.0, -1.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn cone() {
+        let n = 12;
+        assert_mesh(
+            &CPUMesh::cone_with_options(0.5, 2.0, n, false),
+            (2 * (n + 1)) as usize,
+            n as usize,
+            vec3(0.0, -0.5, -0.5),
+            vec3(2.0, 0.5, 0.5),
+        );
+        // The rim is duplicated to give a hard edge between the side and the cap
+        assert_mesh(
+            &CPUMesh::cone_with_options(0.5, 2.0, n, true),
+            (4 * (n + 1)) as usize,
+            (2 * n) as usize,
+            vec3(0.0, -0.5, -0.5),
+            vec3(2.0, 0.5, 0.5),
+        );
+    }
+
+    #[test]
+    fn arrow() {
+        let n = 8;
+        assert_mesh(
+            &CPUMesh::arrow_with_options(0.2, 0.4, 0.25, n),
+            (8 * (n + 1)) as usize,
+            (6 * n) as usize,
+            vec3(0.0, -0.4, -0.4),
+            vec3(1.0, 0.4, 0.4),
+        );
+    }
+
+    #[test]
+    fn capsule() {
+        let (n, rings) = (16, 4);
+        assert_mesh(
+            &CPUMesh::capsule(0.5, 2.0, n, rings),
+            (2 * (rings + 1) * (n + 1)) as usize,
+            (4 * rings * n) as usize,
+            vec3(-1.5, -0.5, -0.5),
+            vec3(1.5, 0.5, 0.5),
+        );
+    }
+
+    #[test]
+    fn rounded_cube() {
+        let segments = 3;
+        let n = 2 * (segments + 1) as usize;
+        assert_mesh(
+            &CPUMesh::rounded_cube(2.0, 0.25, segments),
+            6 * n * n,
+            12 * (n - 1) * (n - 1),
+            vec3(-1.0, -1.0, -1.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+        // Without rounding, the cube has hard edges
+        assert_mesh(
+            &CPUMesh::rounded_cube(2.0, 0.0, segments),
+            24,
+            12,
+            vec3(-1.0, -1.0, -1.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn plane_subdivided() {
+        assert_mesh(
+            &CPUMesh::plane_subdivided(4.0, 2.0, 8, 3),
+            9 * 4,
+            2 * 8 * 3,
+            vec3(-2.0, -1.0, 0.0),
+            vec3(2.0, 1.0, 0.0),
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::context::consts;
+use crate::core::*;
+use std::time::Duration;
+
+///
+/// A fence in the stream of commands sent to the GPU, constructed using [Context::fence].
+/// The fence is signaled when the GPU has finished all of the commands sent before the fence was inserted,
+/// which makes it possible to check whether some work is done, for example rendering into a texture or reading pixels into a buffer,
+/// without stalling the CPU until all of the work sent to the GPU is done like [Context::finish] does.
+///
+/// A resource written by the GPU should not be read by the CPU in the same frame unless the fence inserted after the write is signaled,
+/// since the read otherwise blocks until the write is done. [Texture2D::read_async] and the incremental uploads, see [Upload], use fences to avoid this.
+///
+pub struct FenceSync {
+    context: Context,
+    sync: crate::context::Sync,
+}
+
+impl FenceSync {
+    pub(in crate::core) fn new(context: &Context) -> Self {
+        let sync = context.fence_sync();
+        // Make sure the commands are sent to the GPU, otherwise the fence is never signaled
+        context.flush();
+        context.count_fence();
+        Self {
+            context: context.clone(),
+            sync,
+        }
+    }
+
+    ///
+    /// Returns whether or not the GPU has finished all of the commands sent before the fence was inserted. Never blocks.
+    ///
+    pub fn is_signaled(&self) -> bool {
+        self.client_wait(0)
+    }
+
+    ///
+    /// Blocks until the GPU has finished all of the commands sent before the fence was inserted or the timeout has passed,
+    /// whichever comes first, and returns whether or not the fence is signaled.
+    /// The timeout is limited to about four seconds.
+    ///
+    /// **Note:** On web, blocking is not allowed, so this returns immediately like [FenceSync::is_signaled].
+    ///
+    pub fn wait(&self, timeout: Duration) -> bool {
+        // The maximum timeout is zero on web
+        let timeout = if cfg!(target_arch = "wasm32") {
+            0
+        } else {
+            timeout.as_nanos().min(u32::MAX as u128) as u32
+        };
+        self.client_wait(timeout)
+    }
+
+    fn client_wait(&self, timeout_in_nanoseconds: u32) -> bool {
+        let status = self
+            .context
+            .client_wait_sync(&self.sync, 0, timeout_in_nanoseconds);
+        status == consts::ALREADY_SIGNALED || status == consts::CONDITION_SATISFIED
+    }
+}
+
+impl Drop for FenceSync {
+    fn drop(&mut self) {
+        self.context.delete_sync(&self.sync);
+    }
+}
+
+// The number of frames started by the render loop of the window on this thread, zero when not rendering inside a render loop.
+// Only used in debug builds for detecting reads of resources written in the same frame, see SyncPoint.
+#[cfg(debug_assertions)]
+thread_local! {
+    static FRAME: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+// Called by the render loop of the window before each frame.
+pub(crate) fn start_frame() {
+    #[cfg(debug_assertions)]
+    FRAME.with(|frame| frame.set(frame.get() + 1));
+}
+
+// The frame and the number of fences inserted so far in that frame when a resource was written, only tracked in debug builds.
+// Reading the resource at the same sync point stalls the CPU until the GPU has finished the write,
+// which is not detected outside of a render loop, since there are no frames to compare with.
+#[cfg(debug_assertions)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct SyncPoint {
+    frame: u64,
+    fences: u64,
+}
+
+#[cfg(debug_assertions)]
+impl SyncPoint {
+    pub(in crate::core) fn new(fences: u64) -> Self {
+        Self {
+            frame: FRAME.with(|frame| frame.get()),
+            fences,
+        }
+    }
+
+    // Whether a resource written at this sync point is read without waiting for a fence when read at the given sync point
+    pub(in crate::core) fn is_unsynchronized(&self, read: SyncPoint) -> bool {
+        self.frame != 0 && *self == read
+    }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_in_the_frame_of_the_write_without_a_fence() {
+        // Outside of a render loop
+        let written = SyncPoint::new(0);
+        assert!(!written.is_unsynchronized(SyncPoint::new(0)));
+
+        start_frame();
+        let written = SyncPoint::new(0);
+        assert!(written.is_unsynchronized(SyncPoint::new(0)));
+        assert!(!written.is_unsynchronized(SyncPoint::new(1)));
+
+        start_frame();
+        assert!(!written.is_unsynchronized(SyncPoint::new(0)));
+    }
+}
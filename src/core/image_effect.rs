@@ -3,11 +3,13 @@ use crate::core::*;
 ///
 /// A customizable 2D effect.
 /// Can for example be used for adding an effect on top of a rendered image.
+/// The effect is drawn as a single triangle covering the whole viewport, where the vertices are computed from `gl_VertexID` in the vertex shader,
+/// so no vertex buffers are needed. The fragment shader gets the uv coordinates in the range `[0..1]` across the viewport as `in vec2 uv;`.
 ///
 pub struct ImageEffect {
     program: Program,
-    positions: VertexBuffer,
-    uvs: VertexBuffer,
+    fragment_shader: String,
+    providers: UniformProviders,
 }
 
 impl ImageEffect {
@@ -15,29 +17,44 @@ impl ImageEffect {
     /// Creates a new image effect which applies the calculations defined in the given fragment shader source when calling the [ImageEffect::apply] function.
     ///
     pub fn new(context: &Context, fragment_shader: &str) -> ThreeDResult<Self> {
-        let program = Program::from_source(
+        Ok(Self {
+            program: Self::program(context, fragment_shader)?,
+            fragment_shader: fragment_shader.to_string(),
+            providers: UniformProviders::new(),
+        })
+    }
+
+    fn program(context: &Context, fragment_shader: &str) -> ThreeDResult<Program> {
+        Program::from_source(
             &context,
-            "in vec3 position;
-                                                    in vec2 uv_coordinate;
-                                                    out vec2 uv;
+            "out vec2 uv;
                                                     void main()
                                                     {
-                                                        uv = uv_coordinate;
-                                                        gl_Position = vec4(position, 1.0);
+                                                        uv = vec2(gl_VertexID == 1 ? 2.0 : 0.0, gl_VertexID == 2 ? 2.0 : 0.0);
+                                                        gl_Position = vec4(2.0 * uv - 1.0, 0.0, 1.0);
                                                     }",
             fragment_shader,
-        )?;
-
-        let positions = vec![-3.0, -1.0, 0.0, 3.0, -1.0, 0.0, 0.0, 2.0, 0.0];
-        let uvs = vec![-1.0, 0.0, 2.0, 0.0, 0.5, 1.5];
-        let positions = VertexBuffer::new_with_static(&context, &positions).unwrap();
-        let uvs = VertexBuffer::new_with_static(&context, &uvs).unwrap();
+        )
+    }
 
-        Ok(Self {
-            program,
-            positions,
-            uvs,
-        })
+    ///
+    /// Adds a provider of uniform values, for example [TimeUniforms], which are sent each time the effect is applied.
+    /// The uniform declarations of the provider are added in front of the fragment shader source given at construction,
+    /// so the fragment shader can use the uniforms without declaring them. The program is therefore compiled again.
+    ///
+    pub fn add_provider(&mut self, provider: std::rc::Rc<dyn UniformProvider>) -> ThreeDResult<()> {
+        let mut providers = self.providers.clone();
+        providers.add(provider);
+        self.program = Self::program(
+            self.program.context(),
+            &format!(
+                "{}{}",
+                providers.fragment_shader_source(),
+                self.fragment_shader
+            ),
+        )?;
+        self.providers = providers;
+        Ok(())
     }
 
     ///
@@ -46,10 +63,7 @@ impl ImageEffect {
     /// for example in the callback function of [Screen::write].
     ///
     pub fn render(&self, render_states: RenderStates, viewport: Viewport) -> ThreeDResult<()> {
-        self.program
-            .use_attribute_vec3("position", &self.positions)?;
-        self.program
-            .use_attribute_vec2("uv_coordinate", &self.uvs)?;
+        self.providers.provide(&self.program)?;
         self.program.draw_arrays(render_states, viewport, 3);
         Ok(())
     }
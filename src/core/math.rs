@@ -22,6 +22,8 @@ pub type Point = Point3<f32>;
 pub type Degrees = Deg<f32>;
 pub type Radians = Rad<f32>;
 pub type Quat = Quaternion<f32>;
+pub type DVec3 = Vector3<f64>;
+pub type DMat4 = Matrix4<f64>;
 
 pub const fn vec2(x: f32, y: f32) -> Vec2 {
     Vector2::new(x, y)
@@ -35,6 +37,10 @@ pub const fn vec4(x: f32, y: f32, z: f32, w: f32) -> Vec4 {
     Vector4::new(x, y, z, w)
 }
 
+pub const fn dvec3(x: f64, y: f64, z: f64) -> DVec3 {
+    Vector3::new(x, y, z)
+}
+
 pub trait Vec2Ext {
     fn as_array(&self) -> [f32; 2];
 }
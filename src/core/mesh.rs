@@ -1,4 +1,5 @@
 use crate::core::*;
+use std::time::Duration;
 
 ///
 /// A triangle mesh where the mesh data is transfered to the GPU.
@@ -8,37 +9,84 @@ pub struct Mesh {
     pub position_buffer: VertexBuffer,
     /// Buffer with the normal data, ie. `(x, y, z)` for each vertex.
     pub normal_buffer: Option<VertexBuffer>,
-    /// Buffer with the tangent data, ie. `(x, y, z)` for each vertex.
+    /// Buffer with the tangent data, ie. `(x, y, z, w)` for each vertex where `w` is the handedness.
     pub tangent_buffer: Option<VertexBuffer>,
     /// Buffer with the uv coordinate data, ie. `(u, v)` for each vertex.
     pub uv_buffer: Option<VertexBuffer>,
-    /// Buffer with the color data, ie. `(r, g, b)` for each vertex.
+    /// Buffer with the second set of uv coordinate data, ie. `(u, v)` for each vertex, see [CPUMesh::uvs2].
+    pub uv2_buffer: Option<VertexBuffer>,
+    /// Buffer with the color data, ie. `(r, g, b, a)` for each vertex.
     pub color_buffer: Option<VertexBuffer>,
+    /// The color space of the data in [Mesh::color_buffer], see [CPUMesh::color_space].
+    pub color_space: ColorSpace,
     /// Buffer with the index data, ie. three contiguous integers define the triangle where each integer is and index into the other vertex buffers.
     pub index_buffer: Option<ElementBuffer>,
+    ///
+    /// Texture with the position and normal deltas of the morph targets, see [MorphTarget].
+    /// The texture is [MORPH_TARGET_TEXTURE_WIDTH] RGBA texels wide and the deltas are stored contiguously,
+    /// such that texel `(target * vertex_count + vertex) * 2` contains the position delta and the next texel the normal delta.
+    ///
+    pub morph_target_texture: Option<Texture2D<f32>>,
     /// Optional name of the mesh.
     pub name: String,
+    vertex_arrays: VertexArrays,
 }
 
+///
+/// Settings for how the per vertex data of a [CPUMesh] is stored on the GPU, see [Mesh::new_with_settings].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MeshSettings {
+    ///
+    /// Whether the per vertex data is stored in compact integer formats instead of `f32` values, which reduces the memory used by the mesh by up to half.
+    /// The normals and tangents are stored as four normalized `i8` values and the uv coordinates as normalized `u16` values if they are in the range `[0..1]`,
+    /// while the positions are kept as `f32` values. The colors are always stored as `u8` values.
+    /// The rendering is the same within the precision of the compact formats.
+    /// The default is false, which keeps the exact `f32` values.
+    ///
+    pub compact_attributes: bool,
+}
+
+///
+/// The width of [Mesh::morph_target_texture].
+///
+pub const MORPH_TARGET_TEXTURE_WIDTH: u32 = 1024;
+
 impl Mesh {
     ///
     /// Copies the per vertex data defined in the given [CPUMesh](crate::CPUMesh) to the GPU, thereby
     /// making it possible to render the mesh.
     ///
     pub fn new(context: &Context, cpu_mesh: &CPUMesh) -> ThreeDResult<Self> {
+        Self::new_with_settings(context, cpu_mesh, MeshSettings::default())
+    }
+
+    ///
+    /// Copies the per vertex data defined in the given [CPUMesh](crate::CPUMesh) to the GPU using the given settings, thereby
+    /// making it possible to render the mesh. See [MeshSettings] for the possible settings.
+    ///
+    pub fn new_with_settings(
+        context: &Context,
+        cpu_mesh: &CPUMesh,
+        settings: MeshSettings,
+    ) -> ThreeDResult<Self> {
         cpu_mesh.validate()?;
 
         let position_buffer = VertexBuffer::new_with_static(context, &cpu_mesh.positions)?;
-        let normal_buffer = if let Some(ref normals) = cpu_mesh.normals {
-            Some(VertexBuffer::new_with_static(context, normals)?)
-        } else {
-            None
-        };
-        let tangent_buffer = if let Some(ref tangents) = cpu_mesh.tangents {
-            Some(VertexBuffer::new_with_static(context, tangents)?)
-        } else {
-            None
-        };
+        let new_direction_buffer =
+            |data: &Option<Vec<f32>>, components: usize| -> ThreeDResult<Option<VertexBuffer>> {
+                Ok(if let Some(ref data) = data {
+                    Some(if settings.compact_attributes {
+                        new_compact_direction_buffer(context, data, components)?
+                    } else {
+                        VertexBuffer::new_with_static(context, data)?
+                    })
+                } else {
+                    None
+                })
+            };
+        let normal_buffer = new_direction_buffer(&cpu_mesh.normals, 3)?;
+        let tangent_buffer = new_direction_buffer(&cpu_mesh.tangents, 4)?;
         let index_buffer = if let Some(ref indices) = cpu_mesh.indices {
             Some(match indices {
                 Indices::U8(ind) => ElementBuffer::new_with(context, ind)?,
@@ -48,11 +96,25 @@ impl Mesh {
         } else {
             None
         };
-        let uv_buffer = if let Some(ref uvs) = cpu_mesh.uvs {
-            Some(VertexBuffer::new_with_static(context, uvs)?)
-        } else {
-            None
+        let new_uv_buffer = |data: &Option<Vec<f32>>| -> ThreeDResult<Option<VertexBuffer>> {
+            Ok(if let Some(ref data) = data {
+                Some(
+                    if settings.compact_attributes && data.iter().all(|v| *v >= 0.0 && *v <= 1.0) {
+                        let data = data
+                            .iter()
+                            .map(|v| (v * u16::MAX as f32).round() as u16)
+                            .collect::<Vec<_>>();
+                        VertexBuffer::new_with_static_normalized(context, &data)?
+                    } else {
+                        VertexBuffer::new_with_static(context, data)?
+                    },
+                )
+            } else {
+                None
+            })
         };
+        let uv2_buffer = new_uv_buffer(&cpu_mesh.uvs2)?;
+        let uv_buffer = new_uv_buffer(&cpu_mesh.uvs)?;
         let color_buffer = if let Some(ref colors) = cpu_mesh.colors {
             Some(VertexBuffer::new_with_static(context, colors)?)
         } else {
@@ -64,8 +126,360 @@ impl Mesh {
             tangent_buffer,
             index_buffer,
             uv_buffer,
+            uv2_buffer,
             color_buffer,
+            color_space: cpu_mesh.color_space,
+            morph_target_texture: morph_target_texture(context, cpu_mesh)?,
             name: cpu_mesh.name.clone(),
+            vertex_arrays: VertexArrays::new(context),
         })
     }
+
+    ///
+    /// Starts an incremental upload of the per vertex data defined in the given [CPUMesh](crate::CPUMesh) to the GPU, see [MeshUpload].
+    /// Use this instead of [Mesh::new] when the mesh is so large that uploading it at once causes a visible hitch.
+    /// The morph targets, if any, are uploaded at once.
+    ///
+    pub fn new_incremental(context: &Context, cpu_mesh: CPUMesh) -> ThreeDResult<MeshUpload> {
+        cpu_mesh.validate()?;
+
+        let mut position_buffer = VertexBuffer::new(context)?;
+        position_buffer.allocate::<f32>(cpu_mesh.positions.len());
+        let mut total = cpu_mesh.positions.len();
+        let mut allocate = |data: &Option<Vec<f32>>| -> ThreeDResult<Option<VertexBuffer>> {
+            Ok(if let Some(ref data) = data {
+                let mut buffer = VertexBuffer::new(context)?;
+                buffer.allocate::<f32>(data.len());
+                total += data.len();
+                Some(buffer)
+            } else {
+                None
+            })
+        };
+        let normal_buffer = allocate(&cpu_mesh.normals)?;
+        let tangent_buffer = allocate(&cpu_mesh.tangents)?;
+        let uv_buffer = allocate(&cpu_mesh.uvs)?;
+        let uv2_buffer = allocate(&cpu_mesh.uvs2)?;
+        let color_buffer = if let Some(ref colors) = cpu_mesh.colors {
+            let mut buffer = VertexBuffer::new(context)?;
+            buffer.allocate::<u8>(colors.len());
+            total += colors.len();
+            Some(buffer)
+        } else {
+            None
+        };
+        let index_buffer = if let Some(ref indices) = cpu_mesh.indices {
+            let mut buffer;
+            match indices {
+                Indices::U8(ind) => {
+                    buffer = ElementBuffer::new::<u8>(context)?;
+                    buffer.allocate::<u8>(ind.len())?;
+                }
+                Indices::U16(ind) => {
+                    buffer = ElementBuffer::new::<u16>(context)?;
+                    buffer.allocate::<u16>(ind.len())?;
+                }
+                Indices::U32(ind) => {
+                    buffer = ElementBuffer::new::<u32>(context)?;
+                    buffer.allocate::<u32>(ind.len())?;
+                }
+            };
+            total += buffer.count();
+            Some(buffer)
+        } else {
+            None
+        };
+        Ok(MeshUpload {
+            context: context.clone(),
+            mesh: Some(Self {
+                position_buffer,
+                normal_buffer,
+                tangent_buffer,
+                index_buffer,
+                uv_buffer,
+                uv2_buffer,
+                color_buffer,
+                color_space: cpu_mesh.color_space,
+                morph_target_texture: morph_target_texture(context, &cpu_mesh)?,
+                name: cpu_mesh.name.clone(),
+                vertex_arrays: VertexArrays::new(context),
+            }),
+            cpu_mesh,
+            stage: 0,
+            offset: 0,
+            chunk_size: 4096,
+            uploaded: 0,
+            total,
+            fence: None,
+        })
+    }
+
+    ///
+    /// Returns the define which makes the vertex shader convert the vertex colors to linear space if they are in the sRGB color space, otherwise an empty string.
+    ///
+    pub(crate) fn color_space_define(&self) -> &'static str {
+        if self.color_buffer.is_some() && self.color_space == ColorSpace::Srgb {
+            "#define SRGB_VERTEX_COLORS\n"
+        } else {
+            ""
+        }
+    }
+
+    ///
+    /// Binds the [VertexArray] recorded for the given program and the buffers of this mesh, such that the next draw call of the program uses the recorded attributes.
+    /// The attributes are recorded by calling the `use_attributes` closure the first time the program is used with this mesh
+    /// and again if any of the buffers of this mesh or any of the given ids of additional buffers, for example instance buffers, have changed.
+    /// See [VertexArrays::use_attributes] for more information.
+    ///
+    pub fn use_attributes(
+        &self,
+        program: &Program,
+        additional_buffer_ids: &[u64],
+        use_attributes: impl FnOnce() -> ThreeDResult<()>,
+    ) -> ThreeDResult<()> {
+        let uid =
+            |buffer: &Option<VertexBuffer>| buffer.as_ref().map(|b| b.uid()).unwrap_or(u64::MAX);
+        let mut buffer_ids = vec![
+            self.position_buffer.uid(),
+            uid(&self.normal_buffer),
+            uid(&self.tangent_buffer),
+            uid(&self.uv_buffer),
+            uid(&self.uv2_buffer),
+            uid(&self.color_buffer),
+        ];
+        buffer_ids.extend_from_slice(additional_buffer_ids);
+        self.vertex_arrays
+            .use_attributes(program, &buffer_ids, use_attributes)
+    }
+}
+
+///
+/// An incremental upload of the data in a [CPUMesh] to a [Mesh], constructed using [Mesh::new_incremental].
+/// Each call to [Upload::progress] uploads a chunk of one or more of the buffers until all of the data is uploaded.
+/// The mesh is only returned when it is completely uploaded, so a partially uploaded mesh can never be used.
+/// A fence is inserted after each call, see [FenceSync], and the next chunk is not uploaded before the GPU has finished the previous chunk,
+/// so the upload never stalls the CPU. The mesh is therefore returned by a call after the call uploading the last chunk.
+///
+pub struct MeshUpload {
+    context: Context,
+    mesh: Option<Mesh>,
+    cpu_mesh: CPUMesh,
+    stage: usize,
+    offset: usize,
+    chunk_size: usize,
+    uploaded: usize,
+    total: usize,
+    fence: Option<FenceSync>,
+}
+
+impl MeshUpload {
+    ///
+    /// Uploads the next chunk of the current buffer and returns the number of uploaded elements.
+    /// Moves on to the next buffer when the current buffer is done.
+    ///
+    fn upload_chunk(&mut self) -> usize {
+        let mesh = self.mesh.as_mut().unwrap();
+        let cpu_mesh = &self.cpu_mesh;
+        let offset = self.offset;
+        let chunk_size = self.chunk_size;
+        let count = match self.stage {
+            0 => fill_chunk(
+                &mut mesh.position_buffer,
+                &cpu_mesh.positions,
+                offset,
+                chunk_size,
+            ),
+            1 => fill_optional_chunk(
+                mesh.normal_buffer.as_mut(),
+                cpu_mesh.normals.as_ref(),
+                offset,
+                chunk_size,
+            ),
+            2 => fill_optional_chunk(
+                mesh.tangent_buffer.as_mut(),
+                cpu_mesh.tangents.as_ref(),
+                offset,
+                chunk_size,
+            ),
+            3 => fill_optional_chunk(
+                mesh.uv_buffer.as_mut(),
+                cpu_mesh.uvs.as_ref(),
+                offset,
+                chunk_size,
+            ),
+            4 => fill_optional_chunk(
+                mesh.color_buffer.as_mut(),
+                cpu_mesh.colors.as_ref(),
+                offset,
+                chunk_size,
+            ),
+            5 => fill_optional_chunk(
+                mesh.uv2_buffer.as_mut(),
+                cpu_mesh.uvs2.as_ref(),
+                offset,
+                chunk_size,
+            ),
+            _ => {
+                if let (Some(buffer), Some(indices)) =
+                    (mesh.index_buffer.as_mut(), cpu_mesh.indices.as_ref())
+                {
+                    match indices {
+                        Indices::U8(ind) => fill_index_chunk(buffer, ind, offset, chunk_size),
+                        Indices::U16(ind) => fill_index_chunk(buffer, ind, offset, chunk_size),
+                        Indices::U32(ind) => fill_index_chunk(buffer, ind, offset, chunk_size),
+                    }
+                } else {
+                    0
+                }
+            }
+        };
+        self.offset += count;
+        self.uploaded += count;
+        if count < chunk_size {
+            self.stage += 1;
+            self.offset = 0;
+        }
+        count
+    }
+}
+
+impl Upload for MeshUpload {
+    type Output = Mesh;
+
+    fn progress(&mut self, time_budget: Duration) -> ThreeDResult<UploadStatus<Mesh>> {
+        if self.mesh.is_none() {
+            Err(CoreError::UploadAlreadyDone)?;
+        }
+        let progress = self.uploaded as f32 / self.total.max(1) as f32;
+        if let Some(ref fence) = self.fence {
+            if !fence.is_signaled() {
+                return Ok(UploadStatus::InProgress(progress));
+            }
+            self.fence = None;
+        }
+        if self.stage > 6 {
+            self.cpu_mesh = CPUMesh::default();
+            return Ok(UploadStatus::Done(self.mesh.take().unwrap()));
+        }
+
+        let timer = Timer::start();
+        while self.stage <= 6 {
+            let chunk_timer = Timer::start();
+            let count = self.upload_chunk();
+            if count > 0 {
+                self.chunk_size = next_chunk_size(count, chunk_timer.elapsed(), time_budget);
+            }
+            if timer.elapsed() >= time_budget {
+                break;
+            }
+        }
+        self.fence = Some(self.context.fence());
+        Ok(UploadStatus::InProgress(
+            self.uploaded as f32 / self.total.max(1) as f32,
+        ))
+    }
+}
+
+// The directions are normalized and stored as four normalized i8 values, where the directions have the given number of components,
+// ie. three for normals and four for tangents where the fourth component is the handedness, and the fourth value is 0 for directions with three components
+fn new_compact_direction_buffer(
+    context: &Context,
+    directions: &[f32],
+    components: usize,
+) -> ThreeDResult<VertexBuffer> {
+    let mut data = Vec::with_capacity(directions.len() / components * 4);
+    for direction in directions.chunks(components) {
+        let w = direction.get(3).copied().unwrap_or(0.0);
+        let direction = vec3(direction[0], direction[1], direction[2]);
+        let direction = if direction.magnitude2() > 0.0 {
+            direction.normalize()
+        } else {
+            direction
+        };
+        for i in 0..3 {
+            data.push((direction[i] * i8::MAX as f32).round() as i8);
+        }
+        data.push((w.max(-1.0).min(1.0) * i8::MAX as f32).round() as i8);
+    }
+    let mut buffer = VertexBuffer::new_with_static_normalized(context, &data)?;
+    buffer.set_stride(4);
+    Ok(buffer)
+}
+
+fn morph_target_texture(
+    context: &Context,
+    cpu_mesh: &CPUMesh,
+) -> ThreeDResult<Option<Texture2D<f32>>> {
+    if cpu_mesh.morph_targets.is_empty() {
+        return Ok(None);
+    }
+    let vertex_count = cpu_mesh.positions.len() / 3;
+    let mut data = Vec::with_capacity(cpu_mesh.morph_targets.len() * vertex_count * 8);
+    for morph_target in cpu_mesh.morph_targets.iter() {
+        for i in 0..vertex_count {
+            data.extend_from_slice(&morph_target.positions[i * 3..i * 3 + 3]);
+            data.push(0.0);
+            if let Some(ref normals) = morph_target.normals {
+                data.extend_from_slice(&normals[i * 3..i * 3 + 3]);
+            } else {
+                data.extend_from_slice(&[0.0; 3]);
+            }
+            data.push(0.0);
+        }
+    }
+    let width = MORPH_TARGET_TEXTURE_WIDTH;
+    let height = (data.len() as u32 / 4 + width - 1) / width;
+    data.resize((width * height * 4) as usize, 0.0);
+    let mut texture = Texture2D::new_empty(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )?;
+    texture.fill(&data)?;
+    Ok(Some(texture))
+}
+
+fn fill_chunk<T: VertexBufferDataType>(
+    buffer: &mut VertexBuffer,
+    data: &[T],
+    offset: usize,
+    chunk_size: usize,
+) -> usize {
+    let end = (offset + chunk_size).min(data.len());
+    if end > offset {
+        buffer.fill_subset(offset, &data[offset..end]);
+    }
+    end.max(offset) - offset
+}
+
+fn fill_optional_chunk<T: VertexBufferDataType>(
+    buffer: Option<&mut VertexBuffer>,
+    data: Option<&Vec<T>>,
+    offset: usize,
+    chunk_size: usize,
+) -> usize {
+    if let (Some(buffer), Some(data)) = (buffer, data) {
+        fill_chunk(buffer, data, offset, chunk_size)
+    } else {
+        0
+    }
+}
+
+fn fill_index_chunk<T: ElementBufferDataType>(
+    buffer: &mut ElementBuffer,
+    data: &[T],
+    offset: usize,
+    chunk_size: usize,
+) -> usize {
+    let end = (offset + chunk_size).min(data.len());
+    if end > offset {
+        buffer.fill_subset(offset, &data[offset..end]);
+    }
+    end.max(offset) - offset
 }
@@ -0,0 +1,887 @@
+use crate::core::*;
+use cgmath::{Matrix3, Matrix4, Vector2, Vector3};
+use std::collections::HashMap;
+
+///
+/// The boolean operation computed by [CPUMesh::boolean].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// The volume which is inside either of the two meshes.
+    Union,
+    /// The volume which is inside the first mesh and outside the second mesh.
+    Difference,
+    /// The volume which is inside both meshes.
+    Intersection,
+}
+
+///
+/// The default tolerance of [CPUMesh::boolean] and [CPUMesh::is_closed_manifold] relative to the size of the meshes,
+/// see [CPUMesh::boolean_with_epsilon].
+///
+pub const BOOLEAN_EPSILON: f32 = 1e-5;
+
+impl CPUMesh {
+    ///
+    /// Returns the mesh enclosing the volume given by the boolean operation (constructive solid geometry) on the volumes enclosed by this mesh and the other mesh,
+    /// where the meshes are first transformed by the given transformations, so the result is in the space which both meshes are transformed into.
+    /// Uses the default tolerance [BOOLEAN_EPSILON], see [CPUMesh::boolean_with_epsilon].
+    ///
+    /// The triangles of each mesh are split by the planes of the triangles of the other mesh using binary space partitioning trees
+    /// and the parts inside or outside the other mesh are kept depending on the operation.
+    /// The result contains the positions, the uv coordinates and the normals interpolated from the input meshes,
+    /// where a mesh without normals gets the normals of its triangles and, if only one of the meshes has uv coordinates,
+    /// the other mesh gets uv coordinates by projecting its triangles onto the coordinate plane closest to the plane of the triangle.
+    /// The other vertex attributes are not kept. The vertices of the result are merged and the vertices on the edges of neighbouring triangles
+    /// are added to those triangles, which may leave some triangles without area, such that the result is a closed manifold without cracks.
+    /// In rare cases where the surfaces of the meshes touch at a very small angle, the result may still not be closed, which can be checked with [CPUMesh::is_closed_manifold].
+    ///
+    /// # Errors
+    /// Returns an error if one of the meshes is not a closed manifold, see [CPUMesh::is_closed_manifold].
+    ///
+    pub fn boolean(
+        &self,
+        other: &CPUMesh,
+        op: BooleanOp,
+        self_transform: Mat4,
+        other_transform: Mat4,
+    ) -> ThreeDResult<CPUMesh> {
+        self.boolean_with_epsilon(other, op, self_transform, other_transform, BOOLEAN_EPSILON)
+    }
+
+    ///
+    /// Same as [CPUMesh::boolean], except that the tolerance is given by `epsilon` relative to the diagonal of the bounding box of both transformed meshes.
+    /// Vertices which are closer than the tolerance to each other are merged and vertices which are closer than a tenth of the tolerance to a plane are considered to be in the plane.
+    /// Increase it if the result has missing or extra triangles where the surfaces of the meshes almost coincide
+    /// and decrease it if small details of the meshes disappear.
+    ///
+    /// # Errors
+    /// Returns an error if one of the meshes is not a closed manifold, see [CPUMesh::is_closed_manifold].
+    ///
+    pub fn boolean_with_epsilon(
+        &self,
+        other: &CPUMesh,
+        op: BooleanOp,
+        self_transform: Mat4,
+        other_transform: Mat4,
+        epsilon: f32,
+    ) -> ThreeDResult<CPUMesh> {
+        let use_uvs = self.uvs.is_some() || other.uvs.is_some();
+        let (a_polygons, a_corners) = polygons(self, self_transform, use_uvs);
+        let (b_polygons, b_corners) = polygons(other, other_transform, use_uvs);
+        let tolerance = tolerance(a_corners.iter().chain(b_corners.iter()), epsilon);
+        if !is_closed_manifold(&a_corners, tolerance) {
+            Err(CoreError::NotClosedManifold(
+                "first".to_string(),
+                self.name.clone(),
+            ))?;
+        }
+        if !is_closed_manifold(&b_corners, tolerance) {
+            Err(CoreError::NotClosedManifold(
+                "second".to_string(),
+                other.name.clone(),
+            ))?;
+        }
+
+        // Where the surfaces meet at a small angle, a vertex within the tolerance of a plane can be far from where the plane crosses the surface,
+        // so the tolerance for splitting is smaller than the tolerance for merging vertices, which then closes the cracks left by the splitting
+        let mut a = Bsp::new(a_polygons, 0.1 * tolerance);
+        let mut b = Bsp::new(b_polygons, 0.1 * tolerance);
+        match op {
+            BooleanOp::Union => {
+                a.clip_to(&b);
+                b.clip_to(&a);
+                b.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.build(b.into_polygons());
+            }
+            BooleanOp::Difference => {
+                a.invert();
+                a.clip_to(&b);
+                b.clip_to(&a);
+                b.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.build(b.into_polygons());
+                a.invert();
+            }
+            BooleanOp::Intersection => {
+                a.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.clip_to(&b);
+                b.clip_to(&a);
+                a.build(b.into_polygons());
+                a.invert();
+            }
+        }
+        Ok(to_cpu_mesh(
+            a.into_polygons(),
+            tolerance,
+            use_uvs,
+            self.name.clone(),
+        ))
+    }
+
+    ///
+    /// Returns whether this mesh is a closed manifold, ie. watertight, which means that each edge is shared by exactly two triangles which are oriented consistently.
+    /// Vertices within [BOOLEAN_EPSILON] relative to the diagonal of the bounding box of the mesh are considered to be the same vertex,
+    /// so a mesh with duplicated vertices, for example at uv seams, can still be closed.
+    /// Triangles where two of the vertices are the same are ignored.
+    ///
+    pub fn is_closed_manifold(&self) -> bool {
+        let (_, corners) = polygons(self, Mat4::identity(), false);
+        is_closed_manifold(&corners, tolerance(corners.iter(), BOOLEAN_EPSILON))
+    }
+}
+
+// The absolute tolerance given by the tolerance relative to the diagonal of the bounding box of the given positions
+fn tolerance<'a>(positions: impl Iterator<Item = &'a Vector3<f64>>, epsilon: f32) -> f64 {
+    let mut min = Vector3::new(f64::MAX, f64::MAX, f64::MAX);
+    let mut max = Vector3::new(f64::MIN, f64::MIN, f64::MIN);
+    for p in positions {
+        min = Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+        max = Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+    }
+    let diagonal = if min.x <= max.x {
+        (max - min).magnitude()
+    } else {
+        0.0
+    };
+    (epsilon.max(0.0) as f64 * diagonal.max(1e-6)).max(1e-12)
+}
+
+// Whether the triangles given by each three consecutive positions form a closed manifold, see CPUMesh::is_closed_manifold
+fn is_closed_manifold(corners: &[Vector3<f64>], tolerance: f64) -> bool {
+    let (_, ids) = weld(corners, tolerance);
+    let mut edges: HashMap<(usize, usize), u32> = HashMap::new();
+    for triangle in ids.chunks(3) {
+        let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
+        if a == b || b == c || c == a {
+            continue;
+        }
+        for edge in [(a, b), (b, c), (c, a)].iter() {
+            *edges.entry(*edge).or_insert(0) += 1;
+        }
+    }
+    !edges.is_empty()
+        && edges
+            .iter()
+            .all(|(&(a, b), &count)| count == 1 && edges.get(&(b, a)) == Some(&1))
+}
+
+// Merges the positions which are within the tolerance of each other, and the positions which are connected through such positions,
+// using a grid with cells of the size of the tolerance. Returns the merged positions and the index of the merged position for each of the given positions.
+fn weld(positions: &[Vector3<f64>], tolerance: f64) -> (Vec<Vector3<f64>>, Vec<usize>) {
+    fn root(parents: &mut [usize], mut index: usize) -> usize {
+        while parents[index] != index {
+            parents[index] = parents[parents[index]];
+            index = parents[index];
+        }
+        index
+    }
+    let cell = |p: &Vector3<f64>| {
+        (
+            (p.x / tolerance).floor() as i64,
+            (p.y / tolerance).floor() as i64,
+            (p.z / tolerance).floor() as i64,
+        )
+    };
+    let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+    let mut parents = (0..positions.len()).collect::<Vec<_>>();
+    for (index, p) in positions.iter().enumerate() {
+        let (x, y, z) = cell(p);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if let Some(indices) = grid.get(&(x + dx, y + dy, z + dz)) {
+                        for &other in indices {
+                            if (positions[other] - p).magnitude2() <= tolerance * tolerance {
+                                let (a, b) = (root(&mut parents, index), root(&mut parents, other));
+                                parents[a.max(b)] = a.min(b);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        grid.entry((x, y, z)).or_default().push(index);
+    }
+
+    // The merged position is the first of the positions which are merged
+    let mut merged = Vec::new();
+    let mut merged_indices = HashMap::new();
+    let ids = (0..positions.len())
+        .map(|index| {
+            let root = root(&mut parents, index);
+            *merged_indices.entry(root).or_insert_with(|| {
+                merged.push(positions[root]);
+                merged.len() - 1
+            })
+        })
+        .collect();
+    (merged, ids)
+}
+
+#[derive(Clone)]
+struct Vertex {
+    position: Vector3<f64>,
+    normal: Vector3<f64>,
+    uv: Vector2<f64>,
+}
+
+impl Vertex {
+    fn interpolate(&self, other: &Vertex, t: f64) -> Vertex {
+        Vertex {
+            position: self.position + (other.position - self.position) * t,
+            normal: self.normal + (other.normal - self.normal) * t,
+            uv: self.uv + (other.uv - self.uv) * t,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Plane {
+    normal: Vector3<f64>,
+    w: f64,
+}
+
+impl Plane {
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+}
+
+// A convex polygon which is a part of a triangle of one of the meshes and lies in the plane of that triangle
+#[derive(Clone)]
+struct Polygon {
+    vertices: Vec<Vertex>,
+    plane: Plane,
+}
+
+impl Polygon {
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        for vertex in self.vertices.iter_mut() {
+            vertex.normal = -vertex.normal;
+        }
+        self.plane.flip();
+    }
+}
+
+// Returns the transformed triangles of the mesh as polygons, where triangles without area are left out,
+// and the transformed positions of the corners of all triangles
+fn polygons(
+    mesh: &CPUMesh,
+    transformation: Mat4,
+    use_uvs: bool,
+) -> (Vec<Polygon>, Vec<Vector3<f64>>) {
+    let transformation: Matrix4<f64> = transformation.cast().unwrap();
+    let linear = Matrix3::from_cols(
+        transformation.x.truncate(),
+        transformation.y.truncate(),
+        transformation.z.truncate(),
+    );
+    let normal_transformation = linear
+        .invert()
+        .map(|m| m.transpose())
+        .unwrap_or_else(Matrix3::identity);
+    // A transformation which mirrors the mesh also reverses the orientation of the triangles
+    let mirrored = linear.determinant() < 0.0;
+    let position = |index: usize| {
+        let p = mesh.position(index).cast::<f64>().unwrap();
+        (transformation * p.extend(1.0)).truncate()
+    };
+    let mut polygons = Vec::new();
+    let mut corners = Vec::new();
+    mesh.for_each_triangle(|i0, i1, i2| {
+        let indices = if mirrored { [i0, i2, i1] } else { [i0, i1, i2] };
+        let positions = [
+            position(indices[0]),
+            position(indices[1]),
+            position(indices[2]),
+        ];
+        corners.extend_from_slice(&positions);
+        let normal = (positions[1] - positions[0]).cross(positions[2] - positions[0]);
+        if normal.magnitude2() == 0.0 {
+            return;
+        }
+        let normal = normal.normalize();
+        let vertices = indices
+            .iter()
+            .zip(positions.iter())
+            .map(|(&index, &position)| Vertex {
+                position,
+                normal: mesh
+                    .normals
+                    .as_ref()
+                    .map(|normals| {
+                        (normal_transformation
+                            * vec3(
+                                normals[3 * index],
+                                normals[3 * index + 1],
+                                normals[3 * index + 2],
+                            )
+                            .cast::<f64>()
+                            .unwrap())
+                        .normalize()
+                    })
+                    .unwrap_or(normal),
+                uv: match mesh.uvs {
+                    Some(ref uvs) => Vector2::new(uvs[2 * index] as f64, uvs[2 * index + 1] as f64),
+                    None if use_uvs => planar_uv(position, normal),
+                    None => Vector2::new(0.0, 0.0),
+                },
+            })
+            .collect();
+        polygons.push(Polygon {
+            vertices,
+            plane: Plane {
+                normal,
+                w: normal.dot(positions[0]),
+            },
+        });
+    });
+    (polygons, corners)
+}
+
+// The uv coordinates of a position projected onto the coordinate plane which is closest to the plane with the given normal
+fn planar_uv(position: Vector3<f64>, normal: Vector3<f64>) -> Vector2<f64> {
+    let n = Vector3::new(normal.x.abs(), normal.y.abs(), normal.z.abs());
+    if n.x >= n.y && n.x >= n.z {
+        Vector2::new(position.z, position.y)
+    } else if n.y >= n.z {
+        Vector2::new(position.x, position.z)
+    } else {
+        Vector2::new(position.x, position.y)
+    }
+}
+
+const COPLANAR: u8 = 0;
+const FRONT: u8 = 1;
+const BACK: u8 = 2;
+const SPANNING: u8 = 3;
+
+// The parts of polygons which are split by a plane
+#[derive(Default)]
+struct Split {
+    coplanar_front: Vec<Polygon>,
+    coplanar_back: Vec<Polygon>,
+    front: Vec<Polygon>,
+    back: Vec<Polygon>,
+}
+
+// Splits the polygon by the plane, where the polygons in the plane are coplanar front or coplanar back depending on their orientation
+fn split_polygon(plane: &Plane, polygon: Polygon, tolerance: f64, split: &mut Split) {
+    let types = polygon
+        .vertices
+        .iter()
+        .map(|v| {
+            let t = plane.normal.dot(v.position) - plane.w;
+            if t < -tolerance {
+                BACK
+            } else if t > tolerance {
+                FRONT
+            } else {
+                COPLANAR
+            }
+        })
+        .collect::<Vec<_>>();
+    match types.iter().fold(COPLANAR, |a, t| a | t) {
+        COPLANAR => {
+            if plane.normal.dot(polygon.plane.normal) > 0.0 {
+                split.coplanar_front.push(polygon);
+            } else {
+                split.coplanar_back.push(polygon);
+            }
+        }
+        FRONT => split.front.push(polygon),
+        BACK => split.back.push(polygon),
+        _ => {
+            let count = polygon.vertices.len();
+            let mut front = Vec::with_capacity(count + 1);
+            let mut back = Vec::with_capacity(count + 1);
+            for i in 0..count {
+                let j = (i + 1) % count;
+                let (ti, tj) = (types[i], types[j]);
+                let (vi, vj) = (&polygon.vertices[i], &polygon.vertices[j]);
+                if ti != BACK {
+                    front.push(vi.clone());
+                }
+                if ti != FRONT {
+                    back.push(vi.clone());
+                }
+                if ti | tj == SPANNING {
+                    let v = intersection(plane, vi, vj);
+                    front.push(v.clone());
+                    back.push(v);
+                }
+            }
+            if front.len() >= 3 {
+                split.front.push(Polygon {
+                    vertices: front,
+                    plane: polygon.plane,
+                });
+            }
+            if back.len() >= 3 {
+                split.back.push(Polygon {
+                    vertices: back,
+                    plane: polygon.plane,
+                });
+            }
+        }
+    }
+}
+
+// The intersection between the plane and the edge between the two vertices, which are on each side of the plane.
+// The vertices are ordered such that the neighbouring polygon which has the same edge in the opposite direction gets exactly the same position.
+fn intersection(plane: &Plane, a: &Vertex, b: &Vertex) -> Vertex {
+    let (a, b) = if [a.position.x, a.position.y, a.position.z]
+        <= [b.position.x, b.position.y, b.position.z]
+    {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let t = (plane.w - plane.normal.dot(a.position)) / plane.normal.dot(b.position - a.position);
+    a.interpolate(b, t)
+}
+
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<usize>,
+    back: Option<usize>,
+    polygons: Vec<Polygon>,
+}
+
+// A binary space partitioning tree where the nodes are stored in a vector, with the root first, to avoid recursion on deep trees
+struct Bsp {
+    nodes: Vec<Node>,
+    tolerance: f64,
+}
+
+impl Bsp {
+    fn new(polygons: Vec<Polygon>, tolerance: f64) -> Self {
+        let mut bsp = Self {
+            nodes: vec![Node::default()],
+            tolerance,
+        };
+        bsp.build(polygons);
+        bsp
+    }
+
+    // Adds the polygons to the tree, where the plane of the first polygon reaching a leaf becomes the plane of a new node
+    fn build(&mut self, polygons: Vec<Polygon>) {
+        if polygons.is_empty() {
+            return;
+        }
+        let mut stack = vec![(0, polygons)];
+        while let Some((index, polygons)) = stack.pop() {
+            let plane = *self.nodes[index].plane.get_or_insert(polygons[0].plane);
+            let mut split = Split::default();
+            for polygon in polygons {
+                split_polygon(&plane, polygon, self.tolerance, &mut split);
+            }
+            let node = &mut self.nodes[index];
+            node.polygons.extend(split.coplanar_front);
+            node.polygons.extend(split.coplanar_back);
+            if !split.front.is_empty() {
+                stack.push((self.child(index, true), split.front));
+            }
+            if !split.back.is_empty() {
+                stack.push((self.child(index, false), split.back));
+            }
+        }
+    }
+
+    fn child(&mut self, index: usize, front: bool) -> usize {
+        let existing = if front {
+            self.nodes[index].front
+        } else {
+            self.nodes[index].back
+        };
+        existing.unwrap_or_else(|| {
+            self.nodes.push(Node::default());
+            let child = self.nodes.len() - 1;
+            if front {
+                self.nodes[index].front = Some(child);
+            } else {
+                self.nodes[index].back = Some(child);
+            }
+            child
+        })
+    }
+
+    // Turns the solid inside out
+    fn invert(&mut self) {
+        for node in self.nodes.iter_mut() {
+            for polygon in node.polygons.iter_mut() {
+                polygon.flip();
+            }
+            if let Some(ref mut plane) = node.plane {
+                plane.flip();
+            }
+            std::mem::swap(&mut node.front, &mut node.back);
+        }
+    }
+
+    // Returns the parts of the polygons which are outside the solid represented by this tree
+    fn clip_polygons(&self, polygons: Vec<Polygon>) -> Vec<Polygon> {
+        let mut result = Vec::new();
+        let mut stack = vec![(0, polygons)];
+        while let Some((index, polygons)) = stack.pop() {
+            let node = &self.nodes[index];
+            let plane = match node.plane {
+                Some(plane) => plane,
+                None => {
+                    result.extend(polygons);
+                    continue;
+                }
+            };
+            let mut split = Split::default();
+            for polygon in polygons {
+                split_polygon(&plane, polygon, self.tolerance, &mut split);
+            }
+            let mut front = split.front;
+            front.extend(split.coplanar_front);
+            let mut back = split.back;
+            back.extend(split.coplanar_back);
+            match node.front {
+                Some(child) => stack.push((child, front)),
+                None => result.extend(front),
+            }
+            if let Some(child) = node.back {
+                stack.push((child, back));
+            }
+        }
+        result
+    }
+
+    // Removes the parts of the polygons in this tree which are inside the solid represented by the other tree
+    fn clip_to(&mut self, other: &Bsp) {
+        for node in self.nodes.iter_mut() {
+            let polygons = std::mem::take(&mut node.polygons);
+            node.polygons = other.clip_polygons(polygons);
+        }
+    }
+
+    fn into_polygons(self) -> Vec<Polygon> {
+        self.nodes.into_iter().flat_map(|n| n.polygons).collect()
+    }
+}
+
+// Merges the vertices of the polygons, adds the vertices which lie on the edges of neighbouring polygons (T-junctions) to those polygons,
+// such that the result has no cracks, and triangulates the polygons
+fn to_cpu_mesh(polygons: Vec<Polygon>, tolerance: f64, use_uvs: bool, name: String) -> CPUMesh {
+    let positions = polygons
+        .iter()
+        .flat_map(|p| p.vertices.iter().map(|v| v.position))
+        .collect::<Vec<_>>();
+    let (merged, ids) = weld(&positions, tolerance);
+
+    let mut loops = Vec::with_capacity(polygons.len());
+    let mut offset = 0;
+    for polygon in polygons {
+        let count = polygon.vertices.len();
+        let mut polygon_loop = polygon
+            .vertices
+            .into_iter()
+            .zip(ids[offset..offset + count].iter().copied())
+            .map(|(vertex, id)| (id, vertex))
+            .collect::<Vec<_>>();
+        offset += count;
+        remove_spikes(&mut polygon_loop);
+        if polygon_loop.len() >= 3 {
+            loops.push(polygon_loop);
+        }
+    }
+    repair_t_junctions(&mut loops, &merged, tolerance);
+
+    let mut mesh_positions = Vec::new();
+    let mut mesh_normals = Vec::new();
+    let mut mesh_uvs = Vec::new();
+    let mut triangles = Vec::new();
+    let mut vertex_indices: HashMap<(usize, [u64; 5]), u32> = HashMap::new();
+    for polygon_loop in loops {
+        let mut index = |(id, vertex): &(usize, Vertex)| {
+            let normal = vertex.normal.normalize();
+            let key = (
+                *id,
+                [
+                    normal.x.to_bits(),
+                    normal.y.to_bits(),
+                    normal.z.to_bits(),
+                    vertex.uv.x.to_bits(),
+                    vertex.uv.y.to_bits(),
+                ],
+            );
+            *vertex_indices.entry(key).or_insert_with(|| {
+                let p = merged[*id];
+                mesh_positions.extend_from_slice(&[p.x as f32, p.y as f32, p.z as f32]);
+                mesh_normals.extend_from_slice(&[
+                    normal.x as f32,
+                    normal.y as f32,
+                    normal.z as f32,
+                ]);
+                mesh_uvs.extend_from_slice(&[vertex.uv.x as f32, vertex.uv.y as f32]);
+                (mesh_positions.len() / 3 - 1) as u32
+            })
+        };
+        let first = index(&polygon_loop[0]);
+        for i in 1..polygon_loop.len() - 1 {
+            triangles.push((
+                [polygon_loop[0].0, polygon_loop[i].0, polygon_loop[i + 1].0],
+                [first, index(&polygon_loop[i]), index(&polygon_loop[i + 1])],
+            ));
+        }
+    }
+
+    // Two triangles with the same positions in the opposite order is a wall without thickness, which is left out
+    let key = |ids: [usize; 3]| {
+        let i = (0..3).min_by_key(|i| ids[*i]).unwrap();
+        [ids[i], ids[(i + 1) % 3], ids[(i + 2) % 3]]
+    };
+    let mut unpaired: HashMap<[usize; 3], Vec<usize>> = HashMap::new();
+    let mut removed = vec![false; triangles.len()];
+    for (index, (ids, _)) in triangles.iter().enumerate() {
+        let k = key(*ids);
+        let opposite = [k[0], k[2], k[1]];
+        match unpaired
+            .get_mut(&opposite)
+            .and_then(|indices| indices.pop())
+        {
+            Some(other) => {
+                removed[index] = true;
+                removed[other] = true;
+            }
+            None => unpaired.entry(k).or_default().push(index),
+        }
+    }
+    let indices = triangles
+        .into_iter()
+        .zip(removed)
+        .filter(|(_, removed)| !removed)
+        .flat_map(|((_, indices), _)| indices)
+        .collect();
+    CPUMesh {
+        name,
+        positions: mesh_positions,
+        indices: Some(Indices::U32(indices)),
+        normals: Some(mesh_normals),
+        uvs: if use_uvs { Some(mesh_uvs) } else { None },
+        ..Default::default()
+    }
+}
+
+// Closes the cracks where an edge of a polygon is not shared by a neighbouring polygon, because the neighbours have a vertex on the edge.
+// The vertex is added to the edge if it is the end of another edge which is not shared and it is within the tolerance of the edge,
+// which is repeated until no more vertices are added.
+fn repair_t_junctions(
+    loops: &mut Vec<Vec<(usize, Vertex)>>,
+    merged: &[Vector3<f64>],
+    tolerance: f64,
+) {
+    loop {
+        // The number of times each edge is used minus the number of times it is used in the opposite direction
+        let mut balance: HashMap<(usize, usize), i32> = HashMap::new();
+        for polygon_loop in loops.iter() {
+            for i in 0..polygon_loop.len() {
+                let (a, b) = (
+                    polygon_loop[i].0,
+                    polygon_loop[(i + 1) % polygon_loop.len()].0,
+                );
+                *balance.entry((a, b)).or_insert(0) += 1;
+                *balance.entry((b, a)).or_insert(0) -= 1;
+            }
+        }
+        let mut neighbours: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (&(a, b), _) in balance.iter().filter(|(_, count)| **count > 0) {
+            neighbours.entry(a).or_default().push(b);
+            neighbours.entry(b).or_default().push(a);
+        }
+        if neighbours.is_empty() {
+            break;
+        }
+
+        let mut changed = false;
+        for polygon_loop in loops.iter_mut() {
+            let mut i = 0;
+            while i < polygon_loop.len() {
+                let j = (i + 1) % polygon_loop.len();
+                let (ia, ib) = (polygon_loop[i].0, polygon_loop[j].0);
+                if balance.get(&(ia, ib)).copied().unwrap_or(0) > 0 {
+                    let (a, b) = (merged[ia], merged[ib]);
+                    let d = b - a;
+                    let closest = neighbours[&ia]
+                        .iter()
+                        .chain(neighbours[&ib].iter())
+                        .filter(|k| **k != ia && **k != ib)
+                        .filter_map(|&k| {
+                            let t = (merged[k] - a).dot(d) / d.magnitude2();
+                            if t > 0.0
+                                && t < 1.0
+                                && (a + d * t - merged[k]).magnitude2() <= tolerance * tolerance
+                            {
+                                Some((t, k))
+                            } else {
+                                None
+                            }
+                        })
+                        .min_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+                    if let Some((t, k)) = closest {
+                        let vertex = polygon_loop[i].1.interpolate(&polygon_loop[j].1, t);
+                        polygon_loop.insert(i + 1, (k, vertex));
+                        changed = true;
+                        i += 1;
+                    }
+                }
+                i += 1;
+            }
+        }
+        if !changed {
+            break;
+        }
+        // A vertex close to two edges, for example near a corner where the polygon is almost straight, can be added to both edges,
+        // which leaves a spike going to the vertex and back again
+        for polygon_loop in loops.iter_mut() {
+            remove_spikes(polygon_loop);
+        }
+        loops.retain(|polygon_loop| polygon_loop.len() >= 3);
+    }
+}
+
+// Removes the consecutive vertices which are the same and the spikes where the loop goes from a vertex to another and back again
+fn remove_spikes(vertices: &mut Vec<(usize, Vertex)>) {
+    let mut i = 0;
+    while vertices.len() >= 3 && i < vertices.len() {
+        let count = vertices.len();
+        let next = (i + 1) % count;
+        if vertices[i].0 == vertices[next].0 {
+            vertices.remove(next);
+        } else if vertices[i].0 == vertices[(i + 2) % count].0 {
+            // Removes the tip of the spike and one of the two vertices at its base
+            let (first, second) = (next, (i + 2) % count);
+            vertices.remove(first.max(second));
+            vertices.remove(first.min(second));
+        } else {
+            i += 1;
+            continue;
+        }
+        i = i.saturating_sub(2);
+    }
+    if vertices.len() == 2 && vertices[0].0 == vertices[1].0 {
+        vertices.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Asserts that each edge between two different positions is used once in each direction by the triangles of the mesh
+    fn assert_closed(mesh: &CPUMesh) {
+        let mut position_ids: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut id = |index: usize| {
+            let p = mesh.position(index);
+            let count = position_ids.len();
+            *position_ids
+                .entry([p.x.to_bits(), p.y.to_bits(), p.z.to_bits()])
+                .or_insert(count)
+        };
+        let mut edges: HashMap<(usize, usize), u32> = HashMap::new();
+        let mut triangle_count = 0;
+        mesh.for_each_triangle(|i0, i1, i2| {
+            let (a, b, c) = (id(i0), id(i1), id(i2));
+            if a != b && b != c && c != a {
+                triangle_count += 1;
+                for edge in [(a, b), (b, c), (c, a)].iter() {
+                    *edges.entry(*edge).or_insert(0) += 1;
+                }
+            }
+        });
+        assert!(triangle_count > 0);
+        for (&(a, b), &count) in edges.iter() {
+            assert_eq!(count, 1, "the edge {:?} is used {} times", (a, b), count);
+            assert_eq!(
+                edges.get(&(b, a)),
+                Some(&1),
+                "the edge {:?} has no opposite edge",
+                (a, b)
+            );
+        }
+    }
+
+    fn assert_aabb(mesh: &CPUMesh, min: Vec3, max: Vec3, tolerance: f32) {
+        let aabb = mesh.compute_aabb();
+        assert!(
+            (aabb.min() - min).magnitude() < tolerance
+                && (aabb.max() - max).magnitude() < tolerance,
+            "expected the bounding box {:?} - {:?} but got {:?} - {:?}",
+            min,
+            max,
+            aabb.min(),
+            aabb.max()
+        );
+    }
+
+    #[test]
+    fn cube_minus_sphere() {
+        // The sphere cuts through the faces of the cube but not the corners, which are left as eight separate pieces
+        let result = CPUMesh::cube()
+            .boolean(
+                &CPUMesh::sphere(16),
+                BooleanOp::Difference,
+                Mat4::identity(),
+                Mat4::from_scale(1.3),
+            )
+            .unwrap();
+        assert_closed(&result);
+        assert!(result.is_closed_manifold());
+        assert_aabb(&result, vec3(-1.0, -1.0, -1.0), vec3(1.0, 1.0, 1.0), 1e-4);
+        result.validate().unwrap();
+    }
+
+    #[test]
+    fn cube_union_sphere() {
+        let result = CPUMesh::cube()
+            .boolean(
+                &CPUMesh::sphere(16),
+                BooleanOp::Union,
+                Mat4::identity(),
+                Mat4::from_translation(vec3(1.5, 0.0, 0.0)),
+            )
+            .unwrap();
+        assert_closed(&result);
+        assert_aabb(&result, vec3(-1.0, -1.0, -1.0), vec3(2.5, 1.0, 1.0), 1e-2);
+    }
+
+    #[test]
+    fn cube_intersection_cube() {
+        let result = CPUMesh::cube()
+            .boolean(
+                &CPUMesh::cube(),
+                BooleanOp::Intersection,
+                Mat4::identity(),
+                Mat4::from_translation(vec3(1.0, 0.5, -0.5)),
+            )
+            .unwrap();
+        assert_closed(&result);
+        assert_aabb(&result, vec3(0.0, -0.5, -1.0), vec3(1.0, 1.0, 0.5), 1e-4);
+    }
+
+    #[test]
+    fn open_mesh_is_rejected() {
+        assert!(!CPUMesh::square().is_closed_manifold());
+        assert!(CPUMesh::square()
+            .boolean(
+                &CPUMesh::cube(),
+                BooleanOp::Union,
+                Mat4::identity(),
+                Mat4::identity()
+            )
+            .is_err());
+    }
+}
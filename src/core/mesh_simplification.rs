@@ -0,0 +1,704 @@
+use crate::core::*;
+use cgmath::{Matrix3, Vector3};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+impl CPUMesh {
+    ///
+    /// Returns a simplified version of this mesh with approximately `target_ratio` times the number of triangles,
+    /// which is useful for generating lower detailed versions of a mesh, for example for rendering at a distance.
+    ///
+    /// The mesh is simplified by repeatedly collapsing the edge which changes the surface the least, measured by quadric error metrics.
+    /// The vertex attributes (normals, tangents, uv coordinates, colors and morph targets) are interpolated at the collapsed vertices.
+    /// The joint indices and weights cannot be interpolated and are therefore not part of the simplified mesh.
+    /// Collapses which flip or fold a triangle are skipped and vertices on the boundary of the mesh are never moved,
+    /// so the boundary is preserved. Vertices on non-manifold edges are also never moved.
+    /// If `preserve_seams` is true, the vertices on seams, ie. positions which has more than one set of attributes,
+    /// for example where the uv coordinates wrap around, are also kept unchanged.
+    /// Otherwise, vertices with the same position are merged and the attributes of one of them is used.
+    ///
+    /// The simplification stops before the target is reached if there are no more edges which can safely be collapsed.
+    ///
+    pub fn simplify(&self, target_ratio: f32, preserve_seams: bool) -> CPUMesh {
+        let mut simplifier = Simplifier::new(self, preserve_seams);
+        let target = (target_ratio.max(0.0) * simplifier.face_count as f32).ceil() as usize;
+        simplifier.simplify(target);
+        simplifier.to_cpu_mesh(self)
+    }
+}
+
+// A symmetric 4x4 matrix stored as the upper triangle, used for measuring the squared distance to a set of planes
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(n: Vector3<f64>, d: f64, weight: f64) -> Self {
+        Quadric([
+            weight * n.x * n.x,
+            weight * n.x * n.y,
+            weight * n.x * n.z,
+            weight * n.x * d,
+            weight * n.y * n.y,
+            weight * n.y * n.z,
+            weight * n.y * d,
+            weight * n.z * n.z,
+            weight * n.z * d,
+            weight * d * d,
+        ])
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let mut q = [0.0; 10];
+        for i in 0..10 {
+            q[i] = self.0[i] + other.0[i];
+        }
+        Quadric(q)
+    }
+
+    fn error(&self, p: Vector3<f64>) -> f64 {
+        let q = &self.0;
+        q[0] * p.x * p.x
+            + 2.0 * q[1] * p.x * p.y
+            + 2.0 * q[2] * p.x * p.z
+            + 2.0 * q[3] * p.x
+            + q[4] * p.y * p.y
+            + 2.0 * q[5] * p.y * p.z
+            + 2.0 * q[6] * p.y
+            + q[7] * p.z * p.z
+            + 2.0 * q[8] * p.z
+            + q[9]
+    }
+
+    // Returns the position with the minimal error, if the quadric is not singular
+    fn optimal_position(&self) -> Option<Vector3<f64>> {
+        let q = &self.0;
+        let a = Matrix3::new(q[0], q[1], q[2], q[1], q[4], q[5], q[2], q[5], q[7]);
+        let scale = q[0].abs() + q[4].abs() + q[7].abs();
+        if a.determinant().abs() <= 1e-12 * scale * scale * scale {
+            return None;
+        }
+        a.invert()
+            .map(|inv| -(inv * Vector3::new(q[3], q[6], q[8])))
+    }
+}
+
+struct Vertex {
+    position: Vector3<f64>,
+    quadric: Quadric,
+    // The faces which uses this vertex
+    faces: Vec<usize>,
+    // The position cannot be moved, but other vertices can be collapsed into this one
+    fixed: bool,
+    // The vertex cannot take part in any collapse
+    locked: bool,
+    removed: bool,
+    version: u32,
+}
+
+struct Face {
+    // Indices into the vertices
+    vertices: [usize; 3],
+    // Indices into the attributes
+    corners: [usize; 3],
+    removed: bool,
+}
+
+struct Collapse {
+    error: f64,
+    keep: usize,
+    remove: usize,
+    position: Vector3<f64>,
+    // The interpolation weight of the attributes of the kept vertex
+    weight: f32,
+}
+
+struct Simplifier {
+    vertices: Vec<Vertex>,
+    faces: Vec<Face>,
+    face_count: usize,
+    // The interleaved attributes, see Simplifier::attribute_stride
+    attributes: Vec<f32>,
+    stride: usize,
+    // The attribute index of each vertex or None if the vertex has more than one set of attributes
+    vertex_attribute: Vec<Option<usize>>,
+    // The vertex index of each set of attributes
+    attribute_vertex: Vec<usize>,
+}
+
+impl Simplifier {
+    fn new(mesh: &CPUMesh, preserve_seams: bool) -> Self {
+        let stride = Self::attribute_stride(mesh);
+        let vertex_count = mesh.positions.len() / 3;
+
+        // Merge the vertices with the same position and attributes into one set of attributes
+        // and the vertices with the same position into one vertex
+        let mut attribute_map = HashMap::new();
+        let mut position_map = HashMap::new();
+        let mut attributes = Vec::new();
+        let mut attribute_vertex = Vec::new();
+        let mut vertex_attributes: Vec<Vec<usize>> = Vec::new();
+        let mut positions = Vec::new();
+        let mut mesh_vertex_to_attribute = Vec::with_capacity(vertex_count);
+        for i in 0..vertex_count {
+            let p = mesh.position(i);
+            let position_key = [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()];
+            let vertex = *position_map.entry(position_key).or_insert_with(|| {
+                positions.push(Vector3::new(p.x as f64, p.y as f64, p.z as f64));
+                vertex_attributes.push(Vec::new());
+                positions.len() - 1
+            });
+            let mut a = Vec::with_capacity(stride);
+            Self::push_attributes(mesh, i, &mut a);
+            let mut attribute_key: Vec<u32> = a.iter().map(|v| v.to_bits()).collect();
+            attribute_key.extend_from_slice(&position_key);
+            let attribute = *attribute_map.entry(attribute_key).or_insert_with(|| {
+                attributes.extend_from_slice(&a);
+                attribute_vertex.push(vertex);
+                vertex_attributes[vertex].push(attribute_vertex.len() - 1);
+                attribute_vertex.len() - 1
+            });
+            mesh_vertex_to_attribute.push(attribute);
+        }
+        let mut vertices = positions
+            .into_iter()
+            .enumerate()
+            .map(|(i, position)| Vertex {
+                position,
+                quadric: Quadric::default(),
+                faces: Vec::new(),
+                fixed: false,
+                locked: preserve_seams && vertex_attributes[i].len() > 1,
+                removed: false,
+                version: 0,
+            })
+            .collect::<Vec<_>>();
+
+        let mut faces = Vec::new();
+        mesh.for_each_triangle(|i0, i1, i2| {
+            let mut corners = [
+                mesh_vertex_to_attribute[i0],
+                mesh_vertex_to_attribute[i1],
+                mesh_vertex_to_attribute[i2],
+            ];
+            let face_vertices = [
+                attribute_vertex[corners[0]],
+                attribute_vertex[corners[1]],
+                attribute_vertex[corners[2]],
+            ];
+            // Skip degenerate triangles
+            if face_vertices[0] == face_vertices[1]
+                || face_vertices[1] == face_vertices[2]
+                || face_vertices[2] == face_vertices[0]
+            {
+                return;
+            }
+            if !preserve_seams {
+                for c in 0..3 {
+                    corners[c] = vertex_attributes[face_vertices[c]][0];
+                }
+            }
+            let face = faces.len();
+            for v in face_vertices.iter() {
+                vertices[*v].faces.push(face);
+            }
+            faces.push(Face {
+                vertices: face_vertices,
+                corners,
+                removed: false,
+            });
+        });
+
+        let vertex_attribute = vertex_attributes
+            .iter()
+            .map(|a| {
+                if !preserve_seams || a.len() == 1 {
+                    Some(a[0])
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // Fix the vertices on the boundary and lock the vertices on non-manifold edges
+        let mut edges: HashMap<(usize, usize), u32> = HashMap::new();
+        for face in faces.iter() {
+            for c in 0..3 {
+                let a = face.vertices[c];
+                let b = face.vertices[(c + 1) % 3];
+                *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        for ((a, b), count) in edges {
+            if count == 1 {
+                vertices[a].fixed = true;
+                vertices[b].fixed = true;
+            } else if count > 2 {
+                vertices[a].locked = true;
+                vertices[b].locked = true;
+            }
+        }
+
+        // Accumulate the area weighted quadrics of the planes of the faces
+        for face in faces.iter() {
+            let p0 = vertices[face.vertices[0]].position;
+            let p1 = vertices[face.vertices[1]].position;
+            let p2 = vertices[face.vertices[2]].position;
+            let n = (p1 - p0).cross(p2 - p0);
+            let length = n.magnitude();
+            if length > 0.0 {
+                let n = n / length;
+                let quadric = Quadric::from_plane(n, -n.dot(p0), 0.5 * length);
+                for v in face.vertices.iter() {
+                    vertices[*v].quadric = vertices[*v].quadric.add(&quadric);
+                }
+            }
+        }
+
+        let face_count = faces.len();
+        Self {
+            vertices,
+            faces,
+            face_count,
+            attributes,
+            stride,
+            vertex_attribute,
+            attribute_vertex,
+        }
+    }
+
+    fn attribute_stride(mesh: &CPUMesh) -> usize {
+        let mut stride = 0;
+        if mesh.normals.is_some() {
+            stride += 3;
+        }
+        if mesh.tangents.is_some() {
+            stride += 4;
+        }
+        if mesh.uvs.is_some() {
+            stride += 2;
+        }
+        if mesh.uvs2.is_some() {
+            stride += 2;
+        }
+        if mesh.colors.is_some() {
+            stride += 4;
+        }
+        for morph_target in mesh.morph_targets.iter() {
+            stride += 3;
+            if morph_target.normals.is_some() {
+                stride += 3;
+            }
+        }
+        stride
+    }
+
+    fn push_attributes(mesh: &CPUMesh, i: usize, attributes: &mut Vec<f32>) {
+        if let Some(ref normals) = mesh.normals {
+            attributes.extend_from_slice(&normals[3 * i..3 * i + 3]);
+        }
+        if let Some(ref tangents) = mesh.tangents {
+            attributes.extend_from_slice(&tangents[4 * i..4 * i + 4]);
+        }
+        if let Some(ref uvs) = mesh.uvs {
+            attributes.extend_from_slice(&uvs[2 * i..2 * i + 2]);
+        }
+        if let Some(ref uvs) = mesh.uvs2 {
+            attributes.extend_from_slice(&uvs[2 * i..2 * i + 2]);
+        }
+        if let Some(ref colors) = mesh.colors {
+            attributes.extend(colors[4 * i..4 * i + 4].iter().map(|c| *c as f32));
+        }
+        for morph_target in mesh.morph_targets.iter() {
+            attributes.extend_from_slice(&morph_target.positions[3 * i..3 * i + 3]);
+            if let Some(ref normals) = morph_target.normals {
+                attributes.extend_from_slice(&normals[3 * i..3 * i + 3]);
+            }
+        }
+    }
+
+    fn simplify(&mut self, target: usize) {
+        // Collapses can be skipped because they are unsafe at the time they are tried,
+        // so continue with another pass over all edges as long as the previous pass made progress
+        while self.face_count > target {
+            let mut heap = BinaryHeap::new();
+            for face in self.faces.iter().filter(|f| !f.removed) {
+                for c in 0..3 {
+                    let a = face.vertices[c];
+                    let b = face.vertices[(c + 1) % 3];
+                    if a < b {
+                        self.push_edge(&mut heap, a, b);
+                    }
+                }
+            }
+            let face_count = self.face_count;
+            while self.face_count > target {
+                let Reverse((_, a, b, version_a, version_b)) = match heap.pop() {
+                    Some(entry) => entry,
+                    None => break,
+                };
+                if self.vertices[a].removed
+                    || self.vertices[b].removed
+                    || self.vertices[a].version != version_a
+                    || self.vertices[b].version != version_b
+                {
+                    continue;
+                }
+                if let Some(collapse) = self.evaluate(a, b) {
+                    if self.is_safe(&collapse) {
+                        let keep = collapse.keep;
+                        self.collapse(collapse);
+                        for n in self.neighbours(keep) {
+                            self.push_edge(&mut heap, keep, n);
+                        }
+                    }
+                }
+            }
+            if self.face_count == face_count {
+                break;
+            }
+        }
+    }
+
+    fn push_edge(
+        &self,
+        heap: &mut BinaryHeap<Reverse<(u64, usize, usize, u32, u32)>>,
+        a: usize,
+        b: usize,
+    ) {
+        if let Some(collapse) = self.evaluate(a, b) {
+            heap.push(Reverse((
+                collapse.error.max(0.0).to_bits(),
+                a,
+                b,
+                self.vertices[a].version,
+                self.vertices[b].version,
+            )));
+        }
+    }
+
+    // Finds the position and error of collapsing the edge between the two vertices or None if the edge cannot be collapsed
+    fn evaluate(&self, a: usize, b: usize) -> Option<Collapse> {
+        let va = &self.vertices[a];
+        let vb = &self.vertices[b];
+        if va.locked || vb.locked || (va.fixed && vb.fixed) {
+            return None;
+        }
+        let quadric = va.quadric.add(&vb.quadric);
+        let (keep, remove) = if va.fixed { (a, b) } else { (b, a) };
+        let p_keep = self.vertices[keep].position;
+        let p_remove = self.vertices[remove].position;
+
+        let mut candidates = vec![p_keep];
+        if !self.vertices[keep].fixed {
+            candidates.push(p_remove);
+            candidates.push(0.5 * (p_keep + p_remove));
+            if let Some(p) = quadric.optimal_position() {
+                // Only use the optimal position if it is close to the edge, otherwise the mesh might fold
+                let length = (p_keep - p_remove).magnitude();
+                if (p - 0.5 * (p_keep + p_remove)).magnitude() <= length {
+                    candidates.push(p);
+                }
+            }
+        }
+        let (error, position) = candidates.into_iter().map(|p| (quadric.error(p), p)).fold(
+            None,
+            |best: Option<(f64, Vector3<f64>)>, c| match best {
+                Some(b) if b.0 <= c.0 => Some(b),
+                _ => Some(c),
+            },
+        )?;
+
+        let edge = p_keep - p_remove;
+        let length2 = edge.magnitude2();
+        let weight = if length2 > 0.0 {
+            ((position - p_remove).dot(edge) / length2)
+                .max(0.0)
+                .min(1.0) as f32
+        } else {
+            1.0
+        };
+        Some(Collapse {
+            error,
+            keep,
+            remove,
+            position,
+            weight,
+        })
+    }
+
+    fn neighbours(&self, v: usize) -> Vec<usize> {
+        let mut neighbours = Vec::new();
+        for f in self.vertices[v].faces.iter() {
+            for n in self.faces[*f].vertices.iter() {
+                if *n != v && !neighbours.contains(n) {
+                    neighbours.push(*n);
+                }
+            }
+        }
+        neighbours
+    }
+
+    fn is_safe(&self, collapse: &Collapse) -> bool {
+        let keep = collapse.keep;
+        let remove = collapse.remove;
+        let shared_faces = self.vertices[remove]
+            .faces
+            .iter()
+            .filter(|f| self.faces[**f].vertices.contains(&keep))
+            .count();
+        if shared_faces == 0 {
+            return false;
+        }
+
+        // The link condition, the only common neighbours must be the opposite vertices of the faces sharing the edge,
+        // otherwise the collapse changes the topology
+        let keep_neighbours = self.neighbours(keep);
+        let common = self
+            .neighbours(remove)
+            .iter()
+            .filter(|n| keep_neighbours.contains(n))
+            .count();
+        if common != shared_faces {
+            return false;
+        }
+
+        // Do not flip, fold or degenerate any of the remaining faces, where a face is folded if it is turned by more than about 75 degrees,
+        // since a face can otherwise be turned a little by each collapse until it is perpendicular to the surface
+        for v in [keep, remove].iter() {
+            for f in self.vertices[*v].faces.iter() {
+                let face = &self.faces[*f];
+                if face.vertices.contains(&keep) && face.vertices.contains(&remove) {
+                    continue;
+                }
+                let p = |i: usize| self.vertices[face.vertices[i]].position;
+                let before = (p(1) - p(0)).cross(p(2) - p(0));
+                let q = |i: usize| {
+                    if face.vertices[i] == keep || face.vertices[i] == remove {
+                        collapse.position
+                    } else {
+                        p(i)
+                    }
+                };
+                let after = (q(1) - q(0)).cross(q(2) - q(0));
+                if after.magnitude2() <= 1e-12 * before.magnitude2()
+                    || before.dot(after) <= 0.25 * before.magnitude() * after.magnitude()
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn collapse(&mut self, collapse: Collapse) {
+        let keep = collapse.keep;
+        let remove = collapse.remove;
+
+        // Interpolate the attributes of the kept vertex, the attributes of the removed vertex are replaced
+        let keep_attribute = self.vertex_attribute[keep];
+        let remove_attribute = self.vertex_attribute[remove];
+        if let (Some(ka), Some(ra)) = (keep_attribute, remove_attribute) {
+            let w = collapse.weight;
+            for i in 0..self.stride {
+                self.attributes[ka * self.stride + i] = w * self.attributes[ka * self.stride + i]
+                    + (1.0 - w) * self.attributes[ra * self.stride + i];
+            }
+        }
+
+        let faces = std::mem::take(&mut self.vertices[remove].faces);
+        for f in faces {
+            if self.faces[f].vertices.contains(&keep) {
+                self.faces[f].removed = true;
+                self.face_count -= 1;
+                for v in self.faces[f].vertices {
+                    if v != remove {
+                        self.vertices[v].faces.retain(|face| *face != f);
+                    }
+                }
+            } else {
+                let face = &mut self.faces[f];
+                for c in 0..3 {
+                    if face.vertices[c] == remove {
+                        face.vertices[c] = keep;
+                        if let Some(ka) = keep_attribute {
+                            face.corners[c] = ka;
+                        }
+                    }
+                }
+                self.vertices[keep].faces.push(f);
+            }
+        }
+
+        let quadric = self.vertices[keep]
+            .quadric
+            .add(&self.vertices[remove].quadric);
+        let keep_vertex = &mut self.vertices[keep];
+        keep_vertex.position = collapse.position;
+        keep_vertex.quadric = quadric;
+        keep_vertex.version += 1;
+        self.vertices[remove].removed = true;
+    }
+
+    fn to_cpu_mesh(&self, mesh: &CPUMesh) -> CPUMesh {
+        let mut attribute_to_index = vec![None; self.attribute_vertex.len()];
+        let mut used_attributes = Vec::new();
+        let mut indices = Vec::with_capacity(3 * self.face_count);
+        for face in self.faces.iter().filter(|f| !f.removed) {
+            for a in face.corners.iter() {
+                let index = *attribute_to_index[*a].get_or_insert_with(|| {
+                    used_attributes.push(*a);
+                    used_attributes.len() - 1
+                });
+                indices.push(index as u32);
+            }
+        }
+
+        let mut positions = Vec::with_capacity(3 * used_attributes.len());
+        for a in used_attributes.iter() {
+            let p = self.vertices[self.attribute_vertex[*a]].position;
+            positions.extend_from_slice(&[p.x as f32, p.y as f32, p.z as f32]);
+        }
+
+        let mut offset = 0;
+        let mut extract = |size: usize| {
+            let mut data = Vec::with_capacity(size * used_attributes.len());
+            for a in used_attributes.iter() {
+                let start = a * self.stride + offset;
+                data.extend_from_slice(&self.attributes[start..start + size]);
+            }
+            offset += size;
+            data
+        };
+        let normals = mesh.normals.as_ref().map(|_| {
+            let mut normals = extract(3);
+            normalize(&mut normals, 3);
+            normals
+        });
+        let tangents = mesh.tangents.as_ref().map(|_| {
+            let mut tangents = extract(4);
+            normalize(&mut tangents, 4);
+            for i in 0..tangents.len() / 4 {
+                tangents[4 * i + 3] = tangents[4 * i + 3].signum();
+            }
+            tangents
+        });
+        let uvs = mesh.uvs.as_ref().map(|_| extract(2));
+        let uvs2 = mesh.uvs2.as_ref().map(|_| extract(2));
+        let colors = mesh.colors.as_ref().map(|_| {
+            extract(4)
+                .into_iter()
+                .map(|c| c.round().max(0.0).min(255.0) as u8)
+                .collect()
+        });
+        let morph_targets = mesh
+            .morph_targets
+            .iter()
+            .map(|morph_target| MorphTarget {
+                name: morph_target.name.clone(),
+                positions: extract(3),
+                normals: morph_target.normals.as_ref().map(|_| extract(3)),
+                weight: morph_target.weight,
+            })
+            .collect();
+
+        CPUMesh {
+            name: mesh.name.clone(),
+            material_name: mesh.material_name.clone(),
+            positions,
+            indices: Some(Indices::U32(indices)),
+            normals,
+            tangents,
+            uvs,
+            uvs2,
+            colors,
+            color_space: mesh.color_space,
+            morph_targets,
+            joint_indices: None,
+            joint_weights: None,
+        }
+    }
+}
+
+fn normalize(data: &mut [f32], stride: usize) {
+    for i in 0..data.len() / stride {
+        let v = vec3(data[stride * i], data[stride * i + 1], data[stride * i + 2]);
+        let length = v.magnitude();
+        if length > 0.0 {
+            data[stride * i] = v.x / length;
+            data[stride * i + 1] = v.y / length;
+            data[stride * i + 2] = v.z / length;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle_count(mesh: &CPUMesh) -> usize {
+        let mut count = 0;
+        mesh.for_each_triangle(|_, _, _| count += 1);
+        count
+    }
+
+    #[test]
+    fn simplify_sphere() {
+        let sphere = CPUMesh::sphere(64);
+        let original_count = triangle_count(&sphere);
+        let simplified = sphere.simplify(0.1, false);
+        simplified.validate().unwrap();
+
+        let count = triangle_count(&simplified);
+        let target = (0.1 * original_count as f32).ceil() as usize;
+        assert!(
+            count <= target && count > target / 2,
+            "simplified {} triangles to {} triangles with a target of {}",
+            original_count,
+            count,
+            target
+        );
+
+        // The triangles have area and are oriented outwards, and the surface stays close to the unit sphere
+        simplified.for_each_triangle(|i0, i1, i2| {
+            let (a, b, c) = (
+                simplified.position(i0),
+                simplified.position(i1),
+                simplified.position(i2),
+            );
+            let normal = (b - a).cross(c - a);
+            assert!(normal.magnitude() > 0.0);
+            let center = (a + b + c) / 3.0;
+            assert!(normal.dot(center) > 0.0);
+            for p in [a, b, c].iter() {
+                assert!((p.magnitude() - 1.0).abs() < 0.01, "{:?}", p);
+            }
+            assert!((center.magnitude() - 1.0).abs() < 0.05, "{:?}", center);
+        });
+        let normals = simplified.normals.as_ref().unwrap();
+        assert_eq!(normals.len(), simplified.positions.len());
+        assert!(normals.iter().all(|n| n.is_finite()));
+    }
+
+    #[test]
+    fn simplify_plane_keeps_boundary() {
+        let plane = CPUMesh::plane_subdivided(2.0, 1.0, 16, 8);
+        let simplified = plane.simplify(0.25, true);
+        simplified.validate().unwrap();
+        assert!(triangle_count(&simplified) < triangle_count(&plane));
+
+        // The boundary vertices are never moved, so the plane covers the same area
+        let aabb = simplified.compute_aabb();
+        assert_eq!(aabb.min(), vec3(-1.0, -0.5, 0.0));
+        assert_eq!(aabb.max(), vec3(1.0, 0.5, 0.0));
+        let mut area = 0.0;
+        simplified.for_each_triangle(|i0, i1, i2| {
+            let (a, b, c) = (
+                simplified.position(i0),
+                simplified.position(i1),
+                simplified.position(i2),
+            );
+            area += 0.5 * (b - a).cross(c - a).z;
+        });
+        assert!((area - 2.0).abs() < 1e-4, "{}", area);
+    }
+}
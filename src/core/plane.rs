@@ -0,0 +1,94 @@
+use crate::core::*;
+
+///
+/// An infinite plane defined by a unit normal and the signed distance from the origin along the normal,
+/// ie. the positions `p` on the plane satisfies `normal.dot(p) == distance`.
+/// The side of the plane which the normal points towards is called the front side.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Plane {
+    normal: Vec3,
+    distance: f32,
+}
+
+impl Plane {
+    ///
+    /// Constructs a new plane which goes through the given point and has the given normal, which does not need to be normalized.
+    ///
+    pub fn new(point: Vec3, normal: Vec3) -> Self {
+        let normal = normal.normalize();
+        Self {
+            normal,
+            distance: normal.dot(point),
+        }
+    }
+
+    ///
+    /// Returns the unit normal of the plane.
+    ///
+    pub fn normal(&self) -> Vec3 {
+        self.normal
+    }
+
+    ///
+    /// Returns the signed distance from the origin to the plane along the normal.
+    ///
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    ///
+    /// Returns the signed distance from the plane to the given position, which is positive on the front side of the plane.
+    ///
+    pub fn signed_distance(&self, position: Vec3) -> f32 {
+        self.normal.dot(position) - self.distance
+    }
+
+    ///
+    /// Returns the given position reflected in the plane.
+    ///
+    pub fn reflect_position(&self, position: Vec3) -> Vec3 {
+        position - 2.0 * self.signed_distance(position) * self.normal
+    }
+
+    ///
+    /// Returns the given direction reflected in the plane.
+    ///
+    pub fn reflect_direction(&self, direction: Vec3) -> Vec3 {
+        direction - 2.0 * self.normal.dot(direction) * self.normal
+    }
+
+    ///
+    /// Returns the transformation which reflects positions in the plane.
+    ///
+    pub fn reflection(&self) -> Mat4 {
+        let n = self.normal;
+        let d = self.distance;
+        Mat4::new(
+            1.0 - 2.0 * n.x * n.x,
+            -2.0 * n.x * n.y,
+            -2.0 * n.x * n.z,
+            0.0,
+            -2.0 * n.y * n.x,
+            1.0 - 2.0 * n.y * n.y,
+            -2.0 * n.y * n.z,
+            0.0,
+            -2.0 * n.z * n.x,
+            -2.0 * n.z * n.y,
+            1.0 - 2.0 * n.z * n.z,
+            0.0,
+            2.0 * d * n.x,
+            2.0 * d * n.y,
+            2.0 * d * n.z,
+            1.0,
+        )
+    }
+
+    ///
+    /// Returns the plane as a four dimensional vector `(normal, -distance)`,
+    /// such that the dot product with a position `(x, y, z, 1)` is the signed distance to the plane.
+    ///
+    pub fn to_vec4(&self) -> Vec4 {
+        self.normal.extend(-self.distance)
+    }
+}
@@ -1,7 +1,7 @@
-use crate::context::{consts, AttributeLocation, ShaderType};
+use crate::context::{consts, AttributeLocation, DataType, ShaderType};
 use crate::core::*;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 ///
 /// A shader program consisting of a programmable vertex shader followed by a programmable fragment shader.
@@ -12,10 +12,21 @@ use std::collections::HashMap;
 pub struct Program {
     context: Context,
     id: crate::context::Program,
+    uid: u64,
     vertex_attributes: HashMap<String, AttributeLocation>,
     textures: RefCell<HashMap<String, u32>>,
     uniforms: HashMap<String, crate::context::UniformLocation>,
     uniform_blocks: RefCell<HashMap<String, (u32, u32)>>,
+    transform_feedback_outputs: usize,
+    validation: Option<RefCell<ProgramValidation>>,
+}
+
+// The information used for validating the draw calls of a program, which is only kept when the validation mode is enabled, see Context::set_validation
+struct ProgramValidation {
+    sources: [(&'static str, String); 2],
+    attribute_types: HashMap<String, u32>,
+    set_uniforms: HashSet<String>,
+    reported: HashSet<String>,
 }
 
 impl Program {
@@ -27,24 +38,121 @@ impl Program {
         vertex_shader_source: &str,
         fragment_shader_source: &str,
     ) -> ThreeDResult<Program> {
+        Self::new(
+            context,
+            vertex_shader_source,
+            None,
+            fragment_shader_source,
+            &[],
+        )
+    }
+
+    ///
+    /// Creates a new shader program from the given vertex and optional geometry glsl shader source,
+    /// where the outputs with the given names of the last of the two shaders are written to buffers when calling [Program::transform_feedback_instanced].
+    /// Each output is written to a separate buffer, so at most four outputs are guaranteed to be supported.
+    /// Nothing is rasterized, so the program does not need a fragment shader.
+    ///
+    /// A geometry shader can emit any number of points for each input point, for example none for the instances which should be discarded.
+    /// Geometry shaders require [Capabilities::geometry_shader].
+    ///
+    pub fn from_source_with_transform_feedback(
+        context: &Context,
+        vertex_shader_source: &str,
+        geometry_shader_source: Option<&str>,
+        outputs: &[&str],
+    ) -> ThreeDResult<Program> {
+        if geometry_shader_source.is_some() && !context.capabilities().geometry_shader {
+            Err(CoreError::TransformFeedbackNotSupported)?;
+        }
+        Self::new(
+            context,
+            vertex_shader_source,
+            geometry_shader_source,
+            "void main() {}",
+            outputs,
+        )
+    }
+
+    fn new(
+        context: &Context,
+        vertex_shader_source: &str,
+        geometry_shader_source: Option<&str>,
+        fragment_shader_source: &str,
+        transform_feedback_outputs: &[&str],
+    ) -> ThreeDResult<Program> {
+        context.check_context_lost()?;
+        let timer = Timer::start();
+        // The transform feedback outputs are part of the linked program, so those programs are not cached by their source
+        #[cfg(not(target_arch = "wasm32"))]
+        if transform_feedback_outputs.is_empty() {
+            if let Some(id) =
+                context.load_program_binary(vertex_shader_source, fragment_shader_source)
+            {
+                context.update_program_cache_statistics(|s| {
+                    s.binary_loads += 1;
+                    s.compile_time += timer.elapsed();
+                });
+                return Ok(Self::from_linked_program(
+                    context,
+                    id,
+                    0,
+                    vertex_shader_source,
+                    fragment_shader_source,
+                ));
+            }
+        }
+
         let vert_shader = context
             .create_shader(ShaderType::Vertex)
             .ok_or(CoreError::ShaderCreation)?;
+        let geom_shader = if geometry_shader_source.is_some() {
+            Some(
+                context
+                    .create_shader(ShaderType::Geometry)
+                    .ok_or(CoreError::ShaderCreation)?,
+            )
+        } else {
+            None
+        };
         let frag_shader = context
             .create_shader(ShaderType::Fragment)
             .ok_or(CoreError::ShaderCreation)?;
         context.compile_shader(vertex_shader_source, &vert_shader);
+        if let (Some(source), Some(shader)) = (geometry_shader_source, geom_shader.as_ref()) {
+            context.compile_shader(source, shader);
+        }
         context.compile_shader(fragment_shader_source, &frag_shader);
 
         let id = context.create_program();
         context.attach_shader(&id, &vert_shader);
+        if let Some(ref shader) = geom_shader {
+            context.attach_shader(&id, shader);
+        }
         context.attach_shader(&id, &frag_shader);
+        #[cfg(not(target_arch = "wasm32"))]
+        if context.uses_program_binaries() && transform_feedback_outputs.is_empty() {
+            context.program_binary_retrievable_hint(&id);
+        }
+        if !transform_feedback_outputs.is_empty() {
+            context.transform_feedback_varyings(
+                &id,
+                transform_feedback_outputs,
+                consts::SEPARATE_ATTRIBS,
+            );
+        }
         let success = context.link_program(&id);
 
         if !success {
             if let Some(log) = context.get_shader_info_log(&vert_shader) {
                 Err(CoreError::ShaderCompilation("vertex".to_string(), log))?;
             }
+            if let Some(log) = geom_shader
+                .as_ref()
+                .and_then(|shader| context.get_shader_info_log(shader))
+            {
+                Err(CoreError::ShaderCompilation("geometry".to_string(), log))?;
+            }
             if let Some(log) = context.get_shader_info_log(&frag_shader) {
                 Err(CoreError::ShaderCompilation("fragment".to_string(), log))?;
             }
@@ -58,15 +166,46 @@ impl Program {
         context.detach_shader(&id, &frag_shader);
         context.delete_shader(Some(&vert_shader));
         context.delete_shader(Some(&frag_shader));
+        if let Some(ref shader) = geom_shader {
+            context.detach_shader(&id, shader);
+            context.delete_shader(Some(shader));
+        }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if transform_feedback_outputs.is_empty() {
+            context.save_program_binary(vertex_shader_source, fragment_shader_source, &id);
+        }
+        context.update_program_cache_statistics(|s| {
+            s.compilations += 1;
+            s.compile_time += timer.elapsed();
+        });
+        context.check_errors("Program::from_source")?;
+        Ok(Self::from_linked_program(
+            context,
+            id,
+            transform_feedback_outputs.len(),
+            vertex_shader_source,
+            fragment_shader_source,
+        ))
+    }
+
+    fn from_linked_program(
+        context: &Context,
+        id: crate::context::Program,
+        transform_feedback_outputs: usize,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> Self {
         // Init vertex attributes
         let num_attribs = context.get_program_parameter(&id, consts::ACTIVE_ATTRIBUTES);
         let mut vertex_attributes = HashMap::new();
+        let mut attribute_types = HashMap::new();
         for i in 0..num_attribs {
             let info = context.get_active_attrib(&id, i);
             let location = context.get_attrib_location(&id, &info.name()).unwrap();
             //println!("Attribute location: {}, name: {}, type: {}, size: {}", location, info.name(), info.type_(), info.size());
             vertex_attributes.insert(info.name(), location);
+            attribute_types.insert(info.name(), info.type_());
         }
 
         // Init uniforms
@@ -90,14 +229,29 @@ impl Program {
             }
         }
 
-        Ok(Program {
+        Program {
             context: context.clone(),
             id,
+            uid: context.next_id(),
             vertex_attributes,
             uniforms,
             uniform_blocks: RefCell::new(HashMap::new()),
             textures: RefCell::new(HashMap::new()),
-        })
+            transform_feedback_outputs,
+            validation: if context.is_validation_enabled() {
+                Some(RefCell::new(ProgramValidation {
+                    sources: [
+                        ("vertex", vertex_shader_source.to_string()),
+                        ("fragment", fragment_shader_source.to_string()),
+                    ],
+                    attribute_types,
+                    set_uniforms: HashSet::new(),
+                    reported: HashSet::new(),
+                }))
+            } else {
+                None
+            },
+        }
     }
 
     ///
@@ -108,7 +262,6 @@ impl Program {
     pub fn use_uniform<T: UniformDataType>(&self, name: &str, data: T) -> ThreeDResult<()> {
         let location = self.get_uniform_location(name)?;
         data.send(&self.context, location);
-        self.context.unuse_program();
         Ok(())
     }
 
@@ -124,7 +277,6 @@ impl Program {
     ) -> ThreeDResult<()> {
         let location = self.get_uniform_location(name)?;
         T::send_array(data, &self.context, location);
-        self.context.unuse_program();
         Ok(())
     }
 
@@ -206,6 +358,14 @@ impl Program {
             .uniforms
             .get(name)
             .ok_or_else(|| CoreError::UnusedUniform(name.to_string()))?;
+        if let Some(ref validation) = self.validation {
+            let mut validation = validation.borrow_mut();
+            if !validation.set_uniforms.contains(name) {
+                validation.set_uniforms.insert(name.to_string());
+            }
+        }
+        self.context
+            .update_program_cache_statistics(|s| s.uniform_updates += 1);
         Ok(loc)
     }
 
@@ -242,6 +402,21 @@ impl Program {
         Ok(())
     }
 
+    ///
+    /// Use the given [Texture3D] in this shader program and associate it with the given named variable.
+    /// The glsl shader variable must be of type `uniform sampler3D` and can only be accessed in the fragment shader.
+    ///
+    pub fn use_texture_3d<T: TextureDataType>(
+        &self,
+        name: &str,
+        texture: &Texture3D<T>,
+    ) -> ThreeDResult<()> {
+        let index = self.get_texture_index(name);
+        texture.bind(index);
+        self.use_uniform_int(name, &(index as i32))?;
+        Ok(())
+    }
+
     fn get_texture_index(&self, name: &str) -> u32 {
         if !self.textures.borrow().contains_key(name) {
             let mut map = self.textures.borrow_mut();
@@ -272,19 +447,10 @@ impl Program {
     /// Uses the given [VertexBuffer] in this shader program and associates it with the given named variable.
     /// Each value in the buffer is used when rendering one vertex using the [Program::draw_arrays] or [Program::draw_elements] methods.
     /// Therefore the buffer must contain the same number of values as the number of vertices specified in those draw calls.
+    /// See [VertexAttributeFormat] for how integer values in the buffer are sent to the shader.
     ///
     pub fn use_attribute(&self, name: &str, buffer: &VertexBuffer) -> ThreeDResult<()> {
-        if buffer.count() > 0 {
-            buffer.bind();
-            let loc = self.location(name)?;
-            self.context.enable_vertex_attrib_array(loc);
-            self.context
-                .vertex_attrib_pointer(loc, 1, buffer.data_type(), false, 0, 0);
-            self.context.vertex_attrib_divisor(loc, 0);
-            self.context.unbind_buffer(consts::ARRAY_BUFFER);
-            self.context.unuse_program();
-        }
-        Ok(())
+        self.use_vertex_attribute(name, buffer, 1)
     }
 
     ///
@@ -301,7 +467,6 @@ impl Program {
                 .vertex_attrib_pointer(loc, 1, buffer.data_type(), false, 0, 0);
             self.context.vertex_attrib_divisor(loc, 1);
             self.context.unbind_buffer(consts::ARRAY_BUFFER);
-            self.context.unuse_program();
         }
         Ok(())
     }
@@ -312,17 +477,7 @@ impl Program {
     /// Therefore the buffer must contain 2 times the number of values as the number of vertices specified in those draw calls.
     ///
     pub fn use_attribute_vec2(&self, name: &str, buffer: &VertexBuffer) -> ThreeDResult<()> {
-        if buffer.count() > 0 {
-            buffer.bind();
-            let loc = self.location(name)?;
-            self.context.enable_vertex_attrib_array(loc);
-            self.context
-                .vertex_attrib_pointer(loc, 2, buffer.data_type(), false, 0, 0);
-            self.context.vertex_attrib_divisor(loc, 0);
-            self.context.unbind_buffer(consts::ARRAY_BUFFER);
-            self.context.unuse_program();
-        }
-        Ok(())
+        self.use_vertex_attribute(name, buffer, 2)
     }
 
     ///
@@ -343,7 +498,6 @@ impl Program {
                 .vertex_attrib_pointer(loc, 2, buffer.data_type(), false, 0, 0);
             self.context.vertex_attrib_divisor(loc, 1);
             self.context.unbind_buffer(consts::ARRAY_BUFFER);
-            self.context.unuse_program();
         }
         Ok(())
     }
@@ -354,17 +508,7 @@ impl Program {
     /// Therefore the buffer must contain 3 times the number of values as the number of instances specified in those draw calls.
     ///
     pub fn use_attribute_vec3(&self, name: &str, buffer: &VertexBuffer) -> ThreeDResult<()> {
-        if buffer.count() > 0 {
-            buffer.bind();
-            let loc = self.location(&name)?;
-            self.context.enable_vertex_attrib_array(loc);
-            self.context
-                .vertex_attrib_pointer(loc, 3, buffer.data_type(), false, 0, 0);
-            self.context.vertex_attrib_divisor(loc, 0);
-            self.context.unbind_buffer(consts::ARRAY_BUFFER);
-            self.context.unuse_program();
-        }
-        Ok(())
+        self.use_vertex_attribute(name, buffer, 3)
     }
 
     ///
@@ -385,7 +529,6 @@ impl Program {
                 .vertex_attrib_pointer(loc, 3, buffer.data_type(), false, 0, 0);
             self.context.vertex_attrib_divisor(loc, 1);
             self.context.unbind_buffer(consts::ARRAY_BUFFER);
-            self.context.unuse_program();
         }
         Ok(())
     }
@@ -396,17 +539,7 @@ impl Program {
     /// Therefore the buffer must contain 4 times the number of values as the number of instances specified in those draw calls.
     ///
     pub fn use_attribute_vec4(&self, name: &str, buffer: &VertexBuffer) -> ThreeDResult<()> {
-        if buffer.count() > 0 {
-            buffer.bind();
-            let loc = self.location(name)?;
-            self.context.enable_vertex_attrib_array(loc);
-            self.context
-                .vertex_attrib_pointer(loc, 4, buffer.data_type(), false, 0, 0);
-            self.context.vertex_attrib_divisor(loc, 0);
-            self.context.unbind_buffer(consts::ARRAY_BUFFER);
-            self.context.unuse_program();
-        }
-        Ok(())
+        self.use_vertex_attribute(name, buffer, 4)
     }
 
     ///
@@ -427,31 +560,40 @@ impl Program {
                 .vertex_attrib_pointer(loc, 4, buffer.data_type(), false, 0, 0);
             self.context.vertex_attrib_divisor(loc, 1);
             self.context.unbind_buffer(consts::ARRAY_BUFFER);
-            self.context.unuse_program();
         }
         Ok(())
     }
 
     ///
-    /// Draws `count` number of triangles with the given render states and viewport using this shader program.
+    /// Draws `count` number of vertices as triangles with the given render states and viewport using this shader program.
     /// Requires that all attributes and uniforms have been defined using the use_attribute and use_uniform methods.
     /// Assumes that the data for the three vertices in a triangle is defined contiguous in each vertex buffer.
     /// If you want to use an [ElementBuffer], see [Program::draw_elements].
     ///
+    /// The program does not need to use any attributes, in which case the vertex shader must compute the vertices from `gl_VertexID`,
+    /// which runs from `0` to `count - 1`. For example, a triangle covering the whole viewport is drawn with a `count` of 3 and the vertex shader
+    /// ```glsl
+    /// out vec2 uv;
+    /// void main()
+    /// {
+    ///     uv = vec2(gl_VertexID == 1 ? 2.0 : 0.0, gl_VertexID == 2 ? 2.0 : 0.0);
+    ///     gl_Position = vec4(2.0 * uv - 1.0, 0.0, 1.0);
+    /// }
+    /// ```
+    ///
     pub fn draw_arrays(&self, render_states: RenderStates, viewport: Viewport, count: u32) {
         Self::set_viewport(&self.context, viewport);
         Self::set_states(&self.context, render_states);
         self.set_used();
+        self.validate_draw();
         self.context.draw_arrays(consts::TRIANGLES, 0, count);
-        for location in self.vertex_attributes.values() {
-            self.context.disable_vertex_attrib_array(*location);
-        }
-        self.context.unuse_program();
+        self.unuse_attributes();
     }
 
     ///
     /// Same as [Program::draw_arrays] except it renders 'instance_count' instances of the same set of triangles.
-    /// Use the [Program::use_attribute_instanced], [Program::use_attribute_vec2_instanced], [Program::use_attribute_vec3_instanced] and [Program::use_attribute_vec4_instanced] methods to send unique data for each instance to the shader.
+    /// Use the [Program::use_attribute_instanced], [Program::use_attribute_vec2_instanced], [Program::use_attribute_vec3_instanced] and [Program::use_attribute_vec4_instanced] methods to send unique data for each instance to the shader,
+    /// or use `gl_InstanceID` in the vertex shader.
     ///
     pub fn draw_arrays_instanced(
         &self,
@@ -463,19 +605,121 @@ impl Program {
         Self::set_viewport(&self.context, viewport);
         Self::set_states(&self.context, render_states);
         self.set_used();
+        self.validate_draw();
         self.context
             .draw_arrays_instanced(consts::TRIANGLES, 0, count, instance_count);
         self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
-        for location in self.vertex_attributes.values() {
-            self.context.disable_vertex_attrib_array(*location);
+        self.unuse_attributes();
+    }
+
+    ///
+    /// Runs the shaders of a program created with [Program::from_source_with_transform_feedback] once for each of the `instance_count` instances
+    /// and writes the outputs given at construction to the given buffers in the same order, without rasterizing anything.
+    /// Each instance is a single point, so send the data of each instance to the vertex shader with the instanced attribute methods,
+    /// for example [Program::use_attribute_vec4_instanced], or use `gl_InstanceID`.
+    /// The buffers must have room for the outputs of all instances.
+    ///
+    /// Returns the number of points written to each buffer, which is less than the number of instances if the geometry shader does not emit a point for each instance.
+    /// Reading the number waits for the GPU to finish the transform feedback.
+    ///
+    /// # Errors
+    /// Will return an error if geometry shaders are not supported, see [Capabilities::geometry_shader],
+    /// or if the number of buffers does not match the number of outputs given at construction.
+    ///
+    pub fn transform_feedback_instanced(
+        &self,
+        instance_count: u32,
+        outputs: &mut [&mut InstanceBuffer],
+    ) -> ThreeDResult<u32> {
+        if !self.context.capabilities().geometry_shader {
+            Err(CoreError::TransformFeedbackNotSupported)?;
         }
-        self.context.unuse_program();
+        if outputs.len() != self.transform_feedback_outputs || outputs.is_empty() {
+            Err(CoreError::InvalidTransformFeedbackOutputs(
+                self.transform_feedback_outputs,
+                outputs.len(),
+            ))?;
+        }
+        let query = self
+            .context
+            .create_query()
+            .ok_or(CoreError::QueryCreation)?;
+        self.set_used();
+        for (index, buffer) in outputs.iter().enumerate() {
+            buffer.bind_as_transform_feedback_output(index as u32);
+            self.context
+                .unbind_buffer(consts::TRANSFORM_FEEDBACK_BUFFER);
+        }
+        self.context.enable(consts::RASTERIZER_DISCARD);
+        self.context
+            .begin_query(consts::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN, &query);
+        self.context.begin_transform_feedback(consts::POINTS);
+        self.context
+            .draw_arrays_instanced(consts::POINTS, 0, 1, instance_count);
+        self.context.end_transform_feedback();
+        self.context
+            .end_query(consts::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN);
+        self.context.disable(consts::RASTERIZER_DISCARD);
+        for index in 0..outputs.len() {
+            self.context
+                .unbind_buffer_base(consts::TRANSFORM_FEEDBACK_BUFFER, index as u32);
+        }
+        self.unuse_attributes();
+        let count = self
+            .context
+            .get_query_parameter(&query, consts::QUERY_RESULT);
+        self.context.delete_query(&query);
+        Ok(count)
+    }
+
+    ///
+    /// Runs the shaders of a program created with [Program::from_source_with_transform_feedback] once for each of the `count` vertices
+    /// and writes the outputs given at construction to the given buffers in the same order, without rasterizing anything.
+    /// Each vertex is a single point, so send the data of each vertex to the vertex shader with the attribute methods,
+    /// for example [Program::use_attribute_vec3], or use `gl_VertexID`.
+    /// The buffers must have room for the outputs of all vertices, after which they can be used as vertex buffers when drawing.
+    ///
+    /// # Errors
+    /// Will return an error if transform feedback is not supported, see [Capabilities::transform_feedback],
+    /// or if the number of buffers does not match the number of outputs given at construction.
+    ///
+    pub fn transform_feedback(
+        &self,
+        count: u32,
+        outputs: &mut [&mut VertexBuffer],
+    ) -> ThreeDResult<()> {
+        if !self.context.capabilities().transform_feedback {
+            Err(CoreError::TransformFeedbackNotSupported)?;
+        }
+        if outputs.len() != self.transform_feedback_outputs || outputs.is_empty() {
+            Err(CoreError::InvalidTransformFeedbackOutputs(
+                self.transform_feedback_outputs,
+                outputs.len(),
+            ))?;
+        }
+        self.set_used();
+        for (index, buffer) in outputs.iter().enumerate() {
+            buffer.bind_as_transform_feedback_output(index as u32);
+            self.context
+                .unbind_buffer(consts::TRANSFORM_FEEDBACK_BUFFER);
+        }
+        self.context.enable(consts::RASTERIZER_DISCARD);
+        self.context.begin_transform_feedback(consts::POINTS);
+        self.context.draw_arrays(consts::POINTS, 0, count);
+        self.context.end_transform_feedback();
+        self.context.disable(consts::RASTERIZER_DISCARD);
+        for index in 0..outputs.len() {
+            self.context
+                .unbind_buffer_base(consts::TRANSFORM_FEEDBACK_BUFFER, index as u32);
+        }
+        self.unuse_attributes();
+        Ok(())
     }
 
     ///
     /// Draws the triangles defined by the given [ElementBuffer] with the given render states and viewport using this shader program.
     /// Requires that all attributes and uniforms have been defined using the use_attribute and use_uniform methods.
-    /// If you do not want to use an [ElementBuffer], see [Program::draw_arrays]. If you only want to draw a subset of the triangles in the given [ElementBuffer], see [Program::draw_subset_of_elements].
+    /// If you do not want to use an [ElementBuffer], see [Program::draw_arrays]. If you only want to draw a subset of the triangles in the given [ElementBuffer], see [Program::draw_elements_subset].
     ///
     pub fn draw_elements(
         &self,
@@ -483,20 +727,45 @@ impl Program {
         viewport: Viewport,
         element_buffer: &ElementBuffer,
     ) {
-        self.draw_subset_of_elements(
+        self.draw_elements_range(
             render_states,
             viewport,
             element_buffer,
             0,
             element_buffer.count() as u32,
+            None,
         );
     }
 
+    ///
+    /// Draws the `count` elements starting at element `first` in the given [ElementBuffer] as triangles with the given render states and viewport using this shader program,
+    /// for example to draw one of several meshes which are stored in the same buffers.
+    /// Requires that all attributes and uniforms have been defined using the use_attribute and use_uniform methods.
+    /// Note that the indices are not offset by a base vertex, since that is not supported on WebGL 2,
+    /// so the indices of each mesh must already point to the vertices of that mesh in the vertex buffers.
+    ///
+    /// # Errors
+    /// Will return an error if the range is not inside the element buffer.
+    ///
+    pub fn draw_elements_subset(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        element_buffer: &ElementBuffer,
+        first: u32,
+        count: u32,
+    ) -> ThreeDResult<()> {
+        Self::check_range(element_buffer, first, count)?;
+        self.draw_elements_range(render_states, viewport, element_buffer, first, count, None);
+        Ok(())
+    }
+
     ///
     /// Draws a subset of the triangles defined by the given [ElementBuffer] with the given render states and viewport using this shader program.
     /// Requires that all attributes and uniforms have been defined using the use_attribute and use_uniform methods.
     /// If you do not want to use an [ElementBuffer], see [Program::draw_arrays].
     ///
+    #[deprecated = "use draw_elements_subset instead"]
     pub fn draw_subset_of_elements(
         &self,
         render_states: RenderStates,
@@ -505,18 +774,7 @@ impl Program {
         first: u32,
         count: u32,
     ) {
-        Self::set_viewport(&self.context, viewport);
-        Self::set_states(&self.context, render_states);
-        self.set_used();
-        element_buffer.bind();
-        self.context
-            .draw_elements(consts::TRIANGLES, count, element_buffer.data_type(), first);
-        self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
-
-        for location in self.vertex_attributes.values() {
-            self.context.disable_vertex_attrib_array(*location);
-        }
-        self.context.unuse_program();
+        self.draw_elements_range(render_states, viewport, element_buffer, first, count, None);
     }
 
     ///
@@ -529,23 +787,140 @@ impl Program {
         viewport: Viewport,
         element_buffer: &ElementBuffer,
         count: u32,
+    ) {
+        self.draw_elements_range(
+            render_states,
+            viewport,
+            element_buffer,
+            0,
+            element_buffer.count() as u32,
+            Some(count),
+        );
+    }
+
+    ///
+    /// Same as [Program::draw_elements_subset] except it renders 'instance_count' instances of the same subset of triangles.
+    ///
+    /// # Errors
+    /// Will return an error if the range is not inside the element buffer.
+    ///
+    pub fn draw_elements_instanced_subset(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        element_buffer: &ElementBuffer,
+        first: u32,
+        count: u32,
+        instance_count: u32,
+    ) -> ThreeDResult<()> {
+        Self::check_range(element_buffer, first, count)?;
+        self.draw_elements_range(
+            render_states,
+            viewport,
+            element_buffer,
+            first,
+            count,
+            Some(instance_count),
+        );
+        Ok(())
+    }
+
+    fn check_range(element_buffer: &ElementBuffer, first: u32, count: u32) -> ThreeDResult<()> {
+        if first as usize + count as usize > element_buffer.count() {
+            Err(CoreError::InvalidElementRange(
+                first,
+                count,
+                element_buffer.count(),
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn draw_elements_range(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        element_buffer: &ElementBuffer,
+        first: u32,
+        count: u32,
+        instance_count: Option<u32>,
     ) {
         Self::set_viewport(&self.context, viewport);
         Self::set_states(&self.context, render_states);
         self.set_used();
+        self.validate_draw();
         element_buffer.bind();
-        self.context.draw_elements_instanced(
-            consts::TRIANGLES,
-            element_buffer.count() as u32,
-            element_buffer.data_type(),
-            0,
-            count,
-        );
+        if let Some(instance_count) = instance_count {
+            self.context.draw_elements_instanced(
+                consts::TRIANGLES,
+                count,
+                element_buffer.data_type(),
+                first,
+                instance_count,
+            );
+        } else {
+            self.context
+                .draw_elements(consts::TRIANGLES, count, element_buffer.data_type(), first);
+        }
         self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
-        for location in self.vertex_attributes.values() {
-            self.context.disable_vertex_attrib_array(*location);
+        self.unuse_attributes();
+    }
+
+    pub(crate) fn context(&self) -> &Context {
+        &self.context
+    }
+
+    ///
+    /// Returns an id which is unique for this program, used for identifying the [VertexArray] recorded for this program.
+    ///
+    pub(crate) fn uid(&self) -> u64 {
+        self.uid
+    }
+
+    // The values are converted to floats unless the format of the buffer is integer, in which case integer data is sent as integers
+    fn use_vertex_attribute(
+        &self,
+        name: &str,
+        buffer: &VertexBuffer,
+        size: u32,
+    ) -> ThreeDResult<()> {
+        if buffer.count() > 0 {
+            buffer.bind();
+            let loc = self.location(name)?;
+            self.context.enable_vertex_attrib_array(loc);
+            let data_type = buffer.data_type();
+            let stride = buffer.stride() * data_type.byte_size();
+            match buffer.format() {
+                VertexAttributeFormat::Integer if data_type != DataType::Float => {
+                    self.context
+                        .vertex_attrib_i_pointer(loc, size, data_type, stride, 0);
+                }
+                format => {
+                    self.context.vertex_attrib_pointer(
+                        loc,
+                        size,
+                        data_type,
+                        format == VertexAttributeFormat::Normalized,
+                        stride,
+                        0,
+                    );
+                }
+            }
+            self.context.vertex_attrib_divisor(loc, 0);
+            self.context.unbind_buffer(consts::ARRAY_BUFFER);
+        }
+        Ok(())
+    }
+
+    fn unuse_attributes(&self) {
+        if self.context.is_vertex_array_bound() {
+            // The attributes are recorded in the bound vertex array, so they are kept enabled for the next draw call
+            VertexArray::unbind(&self.context);
+        } else {
+            for location in self.vertex_attributes.values() {
+                self.context.disable_vertex_attrib_array(*location);
+            }
         }
-        self.context.unuse_program();
     }
 
     ///
@@ -562,6 +937,13 @@ impl Program {
         self.vertex_attributes.contains_key(name)
     }
 
+    ///
+    /// Returns the number of vertex attributes, including instance attributes, used by this program.
+    ///
+    pub fn attribute_count(&self) -> u32 {
+        self.vertex_attributes.len() as u32
+    }
+
     fn location(&self, name: &str) -> ThreeDResult<AttributeLocation> {
         self.set_used();
         let location = self
@@ -571,26 +953,97 @@ impl Program {
         Ok(*location)
     }
 
+    // Checks that all active attributes are given a buffer of the declared size and that all uniforms have been set, see Context::set_validation.
+    // A missing buffer is stored as an error in the context, while the other problems are logged as warnings once per program.
+    fn validate_draw(&self) {
+        let validation = match self.validation {
+            Some(ref validation) if self.context.is_validation_enabled() => validation,
+            _ => return,
+        };
+        let mut validation = validation.borrow_mut();
+        for (name, location) in self.vertex_attributes.iter() {
+            if name.starts_with("gl_") {
+                continue;
+            }
+            let enabled = self
+                .context
+                .get_vertex_attrib_parameter(*location, consts::VERTEX_ATTRIB_ARRAY_ENABLED)
+                != 0;
+            let size = self
+                .context
+                .get_vertex_attrib_parameter(*location, consts::VERTEX_ATTRIB_ARRAY_SIZE);
+            if let Some(error) = validation.check_attribute(name, enabled, size) {
+                self.context.set_validation_error(error);
+            }
+        }
+        for name in self.uniforms.keys() {
+            if !validation.set_uniforms.contains(name) && !validation.reported.contains(name) {
+                log::warn!(
+                    "the uniform {} is used by the shader program but has never been set\n{}",
+                    name,
+                    validation.declaration(name)
+                );
+                validation.reported.insert(name.clone());
+            }
+        }
+    }
+
+    // The program stays bound after drawing, so it is only bound again when another program has been used in between
     fn set_used(&self) {
-        self.context.use_program(&self.id);
+        if self.context.bound_program() != Some(self.uid) {
+            self.context.use_program(&self.id);
+            self.context.set_bound_program(Some(self.uid));
+            self.context
+                .update_program_cache_statistics(|s| s.program_binds += 1);
+        }
+    }
+
+    ///
+    /// Sets the render states and the viewport to the initial values of the cached states below,
+    /// such that the cache is valid again after the states have been changed outside of `three-d`.
+    ///
+    pub(crate) fn reset_states(context: &Context) {
+        unsafe {
+            context.disable(consts::SCISSOR_TEST);
+            CURRENT_CLIP = Clip::Disabled;
+            CURRENT_VIEWPORT = Viewport::new_at_origo(0, 0);
+            context.disable(consts::CULL_FACE);
+            CURRENT_CULL = Cull::None;
+            context.disable(consts::BLEND);
+            CURRENT_BLEND = Blend::Disabled;
+            CURRENT_ATTACHMENT_BLENDS = None;
+            context.color_mask(true, true, true, true);
+            CURRENT_COLOR_MASK = WriteMask::COLOR_AND_DEPTH;
+            context.disable(consts::DEPTH_TEST);
+            CURRENT_DEPTH_ENABLE = false;
+            context.depth_mask(true);
+            CURRENT_DEPTH_MASK = true;
+            context.depth_func(consts::LESS);
+            CURRENT_DEPTH_TEST = DepthTest::Less;
+        }
     }
 
     fn set_states(context: &Context, render_states: RenderStates) {
         Self::set_cull(context, render_states.cull);
         Self::set_write_mask(context, render_states.write_mask);
         Self::set_clip(context, render_states.clip);
-        Self::set_depth(
+        // The depth values are reversed in the reversed depth mode, so the depth test is reversed too
+        let depth_test = if context.depth_mode() == DepthMode::ReversedZ {
+            render_states.depth_test.reversed()
+        } else {
+            render_states.depth_test
+        };
+        Self::set_depth(context, Some(depth_test), render_states.write_mask.depth);
+        Self::set_blend(
             context,
-            Some(render_states.depth_test),
-            render_states.write_mask.depth,
+            render_states.blend,
+            render_states.attachment_blends,
         );
-        Self::set_blend(context, render_states.blend);
     }
 
-    fn set_clip(context: &Context, clip: Clip) {
+    pub(crate) fn set_clip(context: &Context, clip: Clip) {
         unsafe {
-            static mut CURRENT: Clip = Clip::Disabled;
-            if clip != CURRENT {
+            if clip != CURRENT_CLIP {
                 if let Clip::Enabled {
                     x,
                     y,
@@ -603,19 +1056,13 @@ impl Program {
                 } else {
                     context.disable(consts::SCISSOR_TEST);
                 }
-                CURRENT = clip;
+                CURRENT_CLIP = clip;
             }
         }
     }
 
     fn set_viewport(context: &Context, viewport: Viewport) {
         unsafe {
-            static mut CURRENT_VIEWPORT: Viewport = Viewport {
-                x: 0,
-                y: 0,
-                width: 0,
-                height: 0,
-            };
             if viewport != CURRENT_VIEWPORT {
                 context.viewport(
                     viewport.x,
@@ -630,7 +1077,6 @@ impl Program {
 
     fn set_cull(context: &Context, cull: Cull) {
         unsafe {
-            static mut CURRENT_CULL: Cull = Cull::None;
             if cull != CURRENT_CULL {
                 match cull {
                     Cull::None => {
@@ -654,35 +1100,81 @@ impl Program {
         }
     }
 
-    fn set_blend(context: &Context, blend: Blend) {
+    fn set_blend(
+        context: &Context,
+        blend: Blend,
+        attachment_blends: Option<[Blend; MAX_BLEND_ATTACHMENTS]>,
+    ) {
+        let attachment_blends = attachment_blends.filter(|_| {
+            let supported = context.capabilities().draw_buffers_blend;
+            if !supported {
+                log::error!("blending each color texture individually is not supported on this graphics context, using the same blend for all color textures instead");
+            }
+            supported
+        });
         unsafe {
-            static mut CURRENT: Blend = Blend::Disabled;
-            if blend != CURRENT {
-                if let Blend::Enabled {
-                    source_rgb_multiplier,
-                    source_alpha_multiplier,
-                    destination_rgb_multiplier,
-                    destination_alpha_multiplier,
-                    rgb_equation,
-                    alpha_equation,
-                } = blend
-                {
-                    context.enable(consts::BLEND);
-                    context.blend_func_separate(
-                        Self::blend_const_from_multiplier(source_rgb_multiplier),
-                        Self::blend_const_from_multiplier(destination_rgb_multiplier),
-                        Self::blend_const_from_multiplier(source_alpha_multiplier),
-                        Self::blend_const_from_multiplier(destination_alpha_multiplier),
-                    );
-                    context.blend_equation_separate(
-                        Self::blend_const_from_equation(rgb_equation),
-                        Self::blend_const_from_equation(alpha_equation),
-                    );
-                } else {
-                    context.disable(consts::BLEND);
+            let current_attachment_blends = CURRENT_ATTACHMENT_BLENDS;
+            if let Some(blends) = attachment_blends {
+                if Some(blends) != current_attachment_blends {
+                    for (i, blend) in blends.iter().enumerate() {
+                        Self::apply_blend(context, *blend, Some(i as u32));
+                    }
+                    CURRENT_ATTACHMENT_BLENDS = Some(blends);
                 }
-                CURRENT = blend;
+            } else if blend != CURRENT_BLEND || current_attachment_blends.is_some() {
+                // Setting the blend for all draw buffers also overrides any blends set for individual draw buffers
+                Self::apply_blend(context, blend, None);
+                CURRENT_BLEND = blend;
+                CURRENT_ATTACHMENT_BLENDS = None;
+            }
+        }
+    }
+
+    // Applies the blend to the given draw buffer or to all draw buffers if none
+    fn apply_blend(context: &Context, blend: Blend, draw_buffer: Option<u32>) {
+        if let Blend::Enabled {
+            source_rgb_multiplier,
+            source_alpha_multiplier,
+            destination_rgb_multiplier,
+            destination_alpha_multiplier,
+            rgb_equation,
+            alpha_equation,
+        } = blend
+        {
+            let multipliers = (
+                Self::blend_const_from_multiplier(source_rgb_multiplier),
+                Self::blend_const_from_multiplier(destination_rgb_multiplier),
+                Self::blend_const_from_multiplier(source_alpha_multiplier),
+                Self::blend_const_from_multiplier(destination_alpha_multiplier),
+            );
+            let equations = (
+                Self::blend_const_from_equation(rgb_equation),
+                Self::blend_const_from_equation(alpha_equation),
+            );
+            if let Some(draw_buffer) = draw_buffer {
+                context.enable_draw_buffer(consts::BLEND, draw_buffer);
+                context.blend_func_separate_draw_buffer(
+                    draw_buffer,
+                    multipliers.0,
+                    multipliers.1,
+                    multipliers.2,
+                    multipliers.3,
+                );
+                context.blend_equation_separate_draw_buffer(draw_buffer, equations.0, equations.1);
+            } else {
+                context.enable(consts::BLEND);
+                context.blend_func_separate(
+                    multipliers.0,
+                    multipliers.1,
+                    multipliers.2,
+                    multipliers.3,
+                );
+                context.blend_equation_separate(equations.0, equations.1);
             }
+        } else if let Some(draw_buffer) = draw_buffer {
+            context.disable_draw_buffer(consts::BLEND, draw_buffer);
+        } else {
+            context.disable(consts::BLEND);
         }
     }
 
@@ -714,7 +1206,6 @@ impl Program {
 
     pub(crate) fn set_write_mask(context: &Context, write_mask: WriteMask) {
         unsafe {
-            static mut CURRENT_COLOR_MASK: WriteMask = WriteMask::COLOR_AND_DEPTH;
             if write_mask != CURRENT_COLOR_MASK {
                 context.color_mask(
                     write_mask.red,
@@ -730,10 +1221,6 @@ impl Program {
 
     fn set_depth(context: &Context, depth_test: Option<DepthTest>, depth_mask: bool) {
         unsafe {
-            static mut CURRENT_DEPTH_ENABLE: bool = false;
-            static mut CURRENT_DEPTH_MASK: bool = true;
-            static mut CURRENT_DEPTH_TEST: DepthTest = DepthTest::Less;
-
             if depth_mask == false && depth_test == Some(DepthTest::Always) {
                 if CURRENT_DEPTH_ENABLE {
                     context.disable(consts::DEPTH_TEST);
@@ -785,8 +1272,140 @@ impl Program {
     }
 }
 
+// The render states which are currently set, such that only the states which change are set before each draw call
+static mut CURRENT_CLIP: Clip = Clip::Disabled;
+static mut CURRENT_VIEWPORT: Viewport = Viewport {
+    x: 0,
+    y: 0,
+    width: 0,
+    height: 0,
+};
+static mut CURRENT_CULL: Cull = Cull::None;
+static mut CURRENT_BLEND: Blend = Blend::Disabled;
+static mut CURRENT_ATTACHMENT_BLENDS: Option<[Blend; MAX_BLEND_ATTACHMENTS]> = None;
+static mut CURRENT_COLOR_MASK: WriteMask = WriteMask::COLOR_AND_DEPTH;
+static mut CURRENT_DEPTH_ENABLE: bool = false;
+static mut CURRENT_DEPTH_MASK: bool = true;
+static mut CURRENT_DEPTH_TEST: DepthTest = DepthTest::Less;
+
 impl Drop for Program {
     fn drop(&mut self) {
+        if self.context.bound_program() == Some(self.uid) {
+            self.context.unuse_program();
+            self.context.set_bound_program(None);
+        }
         self.context.delete_program(&self.id);
     }
 }
+
+impl ProgramValidation {
+    // Checks the buffer of the attribute with the given name, where enabled is whether a buffer is provided and size is its number of components per vertex.
+    // Returns the error for a missing buffer, while a buffer of the wrong size is logged as a warning. Each attribute is only reported once.
+    fn check_attribute(&mut self, name: &str, enabled: bool, size: i32) -> Option<CoreError> {
+        if self.reported.contains(name) {
+            return None;
+        }
+        if !enabled {
+            log::error!(
+                "the attribute {} is active but no buffer was provided\n{}",
+                name,
+                self.declaration(name)
+            );
+            self.reported.insert(name.to_string());
+            return Some(CoreError::MissingAttributeBuffer(name.to_string()));
+        }
+        if let Some(expected_size) = attribute_size(self.attribute_types[name]) {
+            if size != expected_size {
+                log::warn!(
+                    "the attribute {} has {} components but the buffer has {} components per vertex\n{}",
+                    name,
+                    expected_size,
+                    size,
+                    self.declaration(name)
+                );
+                self.reported.insert(name.to_string());
+            }
+        }
+        None
+    }
+
+    // The line in the shader source declaring the attribute or uniform with the given name, used as a snippet in the validation messages
+    fn declaration(&self, name: &str) -> String {
+        for (stage, source) in self.sources.iter() {
+            for (index, line) in source.lines().enumerate() {
+                let is_declaration = line.contains("uniform ") || line.contains("in ");
+                if is_declaration
+                    && line
+                        .split(|c: char| !c.is_alphanumeric() && c != '_')
+                        .any(|word| word == name)
+                {
+                    return format!("{} shader line {}: {}", stage, index + 1, line.trim());
+                }
+            }
+        }
+        format!(
+            "the declaration of {} is not found in the shader source",
+            name
+        )
+    }
+}
+
+// The number of components of an attribute of the given type or None for matrices, which use more than one location
+fn attribute_size(type_: u32) -> Option<i32> {
+    match type_ {
+        consts::FLOAT | consts::INT | consts::UNSIGNED_INT => Some(1),
+        consts::FLOAT_VEC2 | consts::INT_VEC2 | consts::UNSIGNED_INT_VEC2 => Some(2),
+        consts::FLOAT_VEC3 | consts::INT_VEC3 | consts::UNSIGNED_INT_VEC3 => Some(3),
+        consts::FLOAT_VEC4 | consts::INT_VEC4 | consts::UNSIGNED_INT_VEC4 => Some(4),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validation() -> ProgramValidation {
+        ProgramValidation {
+            sources: [
+                (
+                    "vertex",
+                    "in vec3 position;\nin vec2 a_uv;\nvoid main() {}".to_string(),
+                ),
+                ("fragment", "void main() {}".to_string()),
+            ],
+            attribute_types: [
+                ("position".to_string(), consts::FLOAT_VEC3),
+                ("a_uv".to_string(), consts::FLOAT_VEC2),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+            set_uniforms: HashSet::new(),
+            reported: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn missing_attribute_buffer_is_reported_once() {
+        let mut validation = validation();
+        assert!(validation.check_attribute("position", true, 3).is_none());
+        match validation.check_attribute("a_uv", false, 0) {
+            Some(CoreError::MissingAttributeBuffer(name)) => assert_eq!(name, "a_uv"),
+            error => panic!("unexpected result: {:?}", error),
+        }
+        assert!(validation.check_attribute("a_uv", false, 0).is_none());
+        assert!(!validation.reported.contains("position"));
+        assert_eq!(
+            validation.declaration("a_uv"),
+            "vertex shader line 2: in vec2 a_uv;"
+        );
+    }
+
+    #[test]
+    fn wrong_attribute_buffer_size_is_only_a_warning() {
+        let mut validation = validation();
+        assert!(validation.check_attribute("position", true, 4).is_none());
+        assert!(validation.reported.contains("position"));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::core::*;
+use std::time::Duration;
+
+///
+/// Statistics of the programs requested through [Context::program] and [Context::effect], see [Context::program_cache_statistics].
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct ProgramCacheStatistics {
+    /// The number of requested programs which were already created.
+    pub hits: u32,
+    /// The number of requested programs which had to be created.
+    pub misses: u32,
+    /// The number of programs compiled from source.
+    pub compilations: u32,
+    /// The number of programs loaded from a binary stored on disk, see [Context::set_program_binary_directory].
+    pub binary_loads: u32,
+    /// The total time spent compiling programs or loading program binaries.
+    pub compile_time: Duration,
+    /// The number of times a program was bound, which only happens when it is used right after another program,
+    /// so sorting the objects by material, see [RenderPassOptions](crate::RenderPassOptions), reduces this number.
+    pub program_binds: u32,
+    /// The number of uniform values, including textures, sent to programs,
+    /// which is reduced by rendering models with material instances using [render_material_instances](crate::render_material_instances).
+    pub uniform_updates: u32,
+}
+
+impl Context {
+    ///
+    /// Returns the statistics of the program cache since the context was created or the statistics were reset.
+    /// For example, if the number of misses increases when rendering the first frame after [Context::warm_up_programs](crate::Context::warm_up_programs),
+    /// not all programs were warmed up.
+    ///
+    pub fn program_cache_statistics(&self) -> ProgramCacheStatistics {
+        self.program_cache_statistics.get()
+    }
+
+    ///
+    /// Resets the statistics returned by [Context::program_cache_statistics].
+    ///
+    pub fn reset_program_cache_statistics(&self) {
+        self.program_cache_statistics
+            .set(ProgramCacheStatistics::default());
+    }
+
+    ///
+    /// Stores the binaries of all programs compiled from now on in the given directory and loads them from there instead of compiling them the next time,
+    /// for example when the application is started again.
+    /// The binaries are keyed by a hash of the shader source and the graphics driver, so they are automatically recompiled if either changes.
+    /// Set to `None` to disable the binary cache.
+    ///
+    /// **Note:** Only available on native, WebGL does not support program binaries.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_program_binary_directory(&self, directory: Option<std::path::PathBuf>) {
+        *self.program_binary_directory.borrow_mut() = directory;
+    }
+
+    pub(in crate::core) fn update_program_cache_statistics(
+        &self,
+        update: impl FnOnce(&mut ProgramCacheStatistics),
+    ) {
+        let mut statistics = self.program_cache_statistics.get();
+        update(&mut statistics);
+        self.program_cache_statistics.set(statistics);
+    }
+
+    ///
+    /// Returns a linked program loaded from the program binary directory if a binary for the given shader source exists.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(in crate::core) fn load_program_binary(
+        &self,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> Option<crate::context::Program> {
+        let path = self.program_binary_path(vertex_shader_source, fragment_shader_source)?;
+        let bytes = std::fs::read(path).ok()?;
+        if bytes.len() <= 4 {
+            return None;
+        }
+        let format = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let id = self.create_program();
+        if self.program_binary(&id, format, &bytes[4..]) {
+            Some(id)
+        } else {
+            // The binary is invalid, for example if the driver is updated without changing the version string
+            self.delete_program(&id);
+            None
+        }
+    }
+
+    ///
+    /// Stores the binary of the given linked program in the program binary directory, if any.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(in crate::core) fn save_program_binary(
+        &self,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+        id: &crate::context::Program,
+    ) {
+        if let Some(path) = self.program_binary_path(vertex_shader_source, fragment_shader_source) {
+            if let Some((format, data)) = self.get_program_binary(id) {
+                let mut bytes = format.to_le_bytes().to_vec();
+                bytes.extend_from_slice(&data);
+                if let Some(directory) = path.parent() {
+                    std::fs::create_dir_all(directory).ok();
+                }
+                // Failing to write the cache is not an error, the program is just compiled again next time
+                std::fs::write(path, bytes).ok();
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(in crate::core) fn uses_program_binaries(&self) -> bool {
+        self.program_binary_directory.borrow().is_some()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn program_binary_path(
+        &self,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> Option<std::path::PathBuf> {
+        let directory = self.program_binary_directory.borrow().clone()?;
+        let capabilities = self.capabilities();
+        let hash = [
+            vertex_shader_source,
+            fragment_shader_source,
+            &capabilities.vendor,
+            &capabilities.renderer,
+            &capabilities.version,
+        ]
+        .iter()
+        .fold(FNV_OFFSET_BASIS, |hash, s| fnv1a(hash, s.as_bytes()));
+        Some(directory.join(format!("{:016x}.bin", hash)))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+///
+/// The FNV-1a hash which, contrary to the hasher in the standard library, is guaranteed to be the same across platforms and compiler versions.
+///
+#[cfg(not(target_arch = "wasm32"))]
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -32,6 +32,16 @@ pub struct RenderStates {
     ///
     pub blend: Blend,
 
+    ///
+    /// Defines the blending of each color texture of a render target with several color textures individually,
+    /// where the blend at index `i` is used for the color output at location `i` in the fragment shader.
+    /// If `None`, [RenderStates::blend] is used for all color textures.
+    ///
+    /// Requires [Capabilities::draw_buffers_blend](crate::Capabilities::draw_buffers_blend), which is never supported on web (WebGL2).
+    /// If not supported, an error is logged and [RenderStates::blend] is used for all color textures instead.
+    ///
+    pub attachment_blends: Option<[Blend; MAX_BLEND_ATTACHMENTS]>,
+
     ///
     /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped in a render call.
     ///
@@ -44,12 +54,18 @@ impl Default for RenderStates {
             write_mask: WriteMask::default(),
             depth_test: DepthTest::default(),
             blend: Blend::default(),
+            attachment_blends: None,
             clip: Clip::default(),
             cull: Cull::default(),
         }
     }
 }
 
+///
+/// The number of color textures which can be blended individually, see [RenderStates::attachment_blends].
+///
+pub const MAX_BLEND_ATTACHMENTS: usize = 8;
+
 ///
 /// Defines whether the triangles that are backfacing, frontfacing, both or none should be rendered in a render call.
 ///
@@ -96,6 +112,44 @@ impl Default for DepthTest {
     }
 }
 
+///
+/// Defines how the distance from the camera is mapped to the depth values stored in the depth buffer, see [Context::set_depth_mode](crate::Context::set_depth_mode).
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DepthMode {
+    /// The near plane is mapped to the depth value 0 and the far plane to 1.
+    Standard,
+    ///
+    /// The near plane is mapped to the depth value 1 and the far plane to 0, which distributes the precision of a floating point depth buffer
+    /// much more evenly and avoids z-fighting in scenes with a very large ratio between the far and the near plane.
+    /// The depth tests and the clear values are reversed accordingly, so [RenderStates] and [ClearState](crate::ClearState) are specified as in the standard mode.
+    /// Depth values read from a depth texture or the screen are one minus the depth values in the standard mode.
+    ///
+    /// The best precision requires [Capabilities::clip_control](crate::Capabilities::clip_control) and a floating point depth buffer, for example [DepthFormat::Depth32F](crate::DepthFormat::Depth32F).
+    /// Without clip control, which is never supported on web, the depth ordering is reversed but the precision is similar to the standard mode.
+    ///
+    ReversedZ,
+}
+
+impl Default for DepthMode {
+    fn default() -> Self {
+        Self::Standard
+    }
+}
+
+impl DepthTest {
+    // The depth test which gives the same result when the depth values are reversed
+    pub(crate) fn reversed(self) -> Self {
+        match self {
+            Self::Less => Self::Greater,
+            Self::LessOrEqual => Self::GreaterOrEqual,
+            Self::Greater => Self::Less,
+            Self::GreaterOrEqual => Self::LessOrEqual,
+            depth_test => depth_test,
+        }
+    }
+}
+
 ///
 /// Defines the rectangle of pixels to write to in a render call.
 ///
@@ -244,6 +298,42 @@ impl Blend {
         rgb_equation: BlendEquationType::Add,
         alpha_equation: BlendEquationType::Add,
     };
+
+    ///
+    /// Keeps the minimum of the color of the render target and the output color of the render call for each channel.
+    ///
+    pub const MIN: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::One,
+        destination_rgb_multiplier: BlendMultiplierType::One,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Min,
+        alpha_equation: BlendEquationType::Min,
+    };
+
+    ///
+    /// Keeps the maximum of the color of the render target and the output color of the render call for each channel.
+    ///
+    pub const MAX: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::One,
+        destination_rgb_multiplier: BlendMultiplierType::One,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Max,
+        alpha_equation: BlendEquationType::Max,
+    };
+
+    ///
+    /// Subtracts the output color of the render call from the color of the render target.
+    ///
+    pub const SUBTRACT: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::One,
+        destination_rgb_multiplier: BlendMultiplierType::One,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::ReverseSubtract,
+        alpha_equation: BlendEquationType::ReverseSubtract,
+    };
 }
 
 impl Default for Blend {
@@ -272,7 +362,8 @@ pub enum BlendMultiplierType {
 }
 
 ///
-/// How the source and target color or alpha value are combined in [Blend].
+/// How the source and target color or alpha value, each multiplied by its [BlendMultiplierType], are combined in [Blend].
+/// Note that the multipliers are ignored by the [BlendEquationType::Min] and [BlendEquationType::Max] equations.
 ///
 #[allow(missing_docs)]
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -18,6 +18,10 @@ mod render_target_cube_map;
 #[doc(inline)]
 pub use render_target_cube_map::*;
 
+mod resizable_target;
+#[doc(inline)]
+pub use resizable_target::*;
+
 use crate::context::consts;
 use crate::core::*;
 
@@ -36,7 +40,8 @@ pub struct ClearState {
     pub blue: Option<f32>,
     /// Defines the clear value for the alpha channel.
     pub alpha: Option<f32>,
-    /// Defines the clear value for the depth channel. A value of 1 means a depth value equal to the far plane and 0 means a depth value equal to the near plane.
+    /// Defines the clear value for the depth channel. A value of 1 means a depth value equal to the far plane and 0 means a depth value equal to the near plane,
+    /// also in the [DepthMode::ReversedZ] depth mode where the value is reversed before clearing.
     pub depth: Option<f32>,
 }
 
@@ -117,6 +122,7 @@ pub enum CopyDestination<'a, 'b, 'c, 'd, T: TextureDataType> {
 pub(in crate::core) fn new_framebuffer(
     context: &Context,
 ) -> ThreeDResult<crate::context::Framebuffer> {
+    context.check_context_lost()?;
     Ok(context
         .create_framebuffer()
         .ok_or(CoreError::RenderTargetCreation)?)
@@ -129,6 +135,67 @@ fn check(context: &Context) -> ThreeDResult<()> {
         .or_else(|status| Err(CoreError::RenderTargetCreation))
 }
 
+///
+/// Clears the part of the currently bound render target inside the given viewport based on the given clear state and leaves the rest untouched,
+/// for example the viewport of one player in a split screen.
+/// Must be called in a render target render function, for example in the callback function of [Screen::write] with [ClearState::none].
+///
+pub fn clear_partially(context: &Context, clear_state: ClearState, viewport: Viewport) {
+    Program::set_clip(
+        context,
+        Clip::Enabled {
+            x: viewport.x.max(0) as u32,
+            y: viewport.y.max(0) as u32,
+            width: viewport.width,
+            height: viewport.height,
+        },
+    );
+    clear(context, &clear_state);
+    Program::set_clip(context, Clip::Disabled);
+}
+
+// A texture which can be one of the color textures written by write_multiple
+pub(crate) trait ColorTarget {
+    fn bind_as_color_target(&self, channel: u32);
+}
+
+impl<T: TextureDataType> ColorTarget for Texture2D<T> {
+    fn bind_as_color_target(&self, channel: u32) {
+        Texture2D::bind_as_color_target(self, channel);
+        self.mark_written();
+    }
+}
+
+// Renders whatever rendered in the render closure into the given color textures and depth texture at the same time, also known as multiple render targets,
+// where output at location i in the fragment shader is written to the ith color texture. In contrast to RenderTargetArray, the color textures can have different data types and formats.
+// The color textures must not have mip maps, since they are not generated.
+pub(crate) fn write_multiple(
+    context: &Context,
+    color_textures: &[&dyn ColorTarget],
+    depth_texture: &DepthTargetTexture2D,
+    clear_state: ClearState,
+    render: impl FnOnce() -> ThreeDResult<()>,
+) -> ThreeDResult<()> {
+    let id = new_framebuffer(context)?;
+    let result = context.write_framebuffer(Some(&id), || {
+        context.draw_buffers(
+            &(0..color_textures.len() as u32)
+                .map(|channel| consts::COLOR_ATTACHMENT0 + channel)
+                .collect::<Vec<u32>>(),
+        );
+        for (channel, texture) in color_textures.iter().enumerate() {
+            texture.bind_as_color_target(channel as u32);
+        }
+        depth_texture.bind_as_depth_target();
+        #[cfg(feature = "debug")]
+        check(context)?;
+        clear(context, &clear_state);
+        render()
+    });
+    context.delete_framebuffer(Some(&id));
+    result
+}
+
 fn clear(context: &Context, clear_state: &ClearState) {
     Program::set_write_mask(
         context,
@@ -153,7 +220,11 @@ fn clear(context: &Context, clear_state: &ClearState) {
         );
     }
     if let Some(depth) = clear_state.depth {
-        context.clear_depth(depth);
+        context.clear_depth(if context.depth_mode() == DepthMode::ReversedZ {
+            1.0 - depth
+        } else {
+            depth
+        });
     }
     context.clear(if clear_color && clear_state.depth.is_some() {
         consts::COLOR_BUFFER_BIT | consts::DEPTH_BUFFER_BIT
@@ -8,8 +8,8 @@ use crate::core::render_target::*;
 pub struct RenderTarget<'a, 'b, T: TextureDataType> {
     context: Context,
     id: crate::context::Framebuffer,
-    color_texture: Option<&'a mut Texture2D<T>>,
-    depth_texture: Option<&'b mut DepthTargetTexture2D>,
+    color_texture: Option<&'a Texture2D<T>>,
+    depth_texture: Option<&'b DepthTargetTexture2D>,
 }
 
 impl<'a, 'b> RenderTarget<'a, 'b, u8> {
@@ -61,9 +61,34 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
         })
     }
 
+    pub(in crate::core) fn new_internal(
+        context: &Context,
+        color_texture: &'a Texture2D<T>,
+        depth_texture: &'b DepthTargetTexture2D,
+    ) -> ThreeDResult<Self> {
+        Ok(Self {
+            context: context.clone(),
+            id: new_framebuffer(context)?,
+            color_texture: Some(color_texture),
+            depth_texture: Some(depth_texture),
+        })
+    }
+
+    pub(crate) fn new_color_internal(
+        context: &Context,
+        color_texture: &'a Texture2D<T>,
+    ) -> ThreeDResult<Self> {
+        Ok(Self {
+            context: context.clone(),
+            id: new_framebuffer(context)?,
+            color_texture: Some(color_texture),
+            depth_texture: None,
+        })
+    }
+
     pub(crate) fn new_depth_internal(
         context: &Context,
-        depth_texture: &'b mut DepthTargetTexture2D,
+        depth_texture: &'b DepthTargetTexture2D,
     ) -> ThreeDResult<Self> {
         Ok(Self {
             context: context.clone(),
@@ -82,22 +107,25 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
         clear_state: ClearState,
         render: impl FnOnce() -> ThreeDResult<()>,
     ) -> ThreeDResult<()> {
-        self.bind(consts::DRAW_FRAMEBUFFER)?;
-        clear(
-            &self.context,
-            &ClearState {
-                red: self.color_texture.as_ref().and(clear_state.red),
-                green: self.color_texture.as_ref().and(clear_state.green),
-                blue: self.color_texture.as_ref().and(clear_state.blue),
-                alpha: self.color_texture.as_ref().and(clear_state.alpha),
-                depth: self.depth_texture.as_ref().and(clear_state.depth),
-            },
-        );
-        render()?;
-        if let Some(ref color_texture) = self.color_texture {
-            color_texture.generate_mip_maps();
-        }
-        Ok(())
+        self.context.write_framebuffer(Some(&self.id), || {
+            self.bind_attachments()?;
+            clear(
+                &self.context,
+                &ClearState {
+                    red: self.color_texture.as_ref().and(clear_state.red),
+                    green: self.color_texture.as_ref().and(clear_state.green),
+                    blue: self.color_texture.as_ref().and(clear_state.blue),
+                    alpha: self.color_texture.as_ref().and(clear_state.alpha),
+                    depth: self.depth_texture.as_ref().and(clear_state.depth),
+                },
+            );
+            render()?;
+            if let Some(ref color_texture) = self.color_texture {
+                color_texture.generate_mip_maps();
+                color_texture.mark_written();
+            }
+            Ok(())
+        })
     }
 
     ///
@@ -209,6 +237,10 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
 
     pub(in crate::core) fn bind(&self, target: u32) -> ThreeDResult<()> {
         self.context.bind_framebuffer(target, Some(&self.id));
+        self.bind_attachments()
+    }
+
+    fn bind_attachments(&self) -> ThreeDResult<()> {
         if let Some(ref tex) = self.color_texture {
             self.context.draw_buffers(&[consts::COLOR_ATTACHMENT0]);
             tex.bind_as_color_target(0);
@@ -89,22 +89,24 @@ impl<'a, 'b, T: TextureDataType> RenderTargetArray<'a, 'b, T> {
         clear_state: ClearState,
         render: impl FnOnce() -> ThreeDResult<()>,
     ) -> ThreeDResult<()> {
-        self.bind(Some(color_layers), Some(depth_layer))?;
-        clear(
-            &self.context,
-            &ClearState {
-                red: self.color_texture.and(clear_state.red),
-                green: self.color_texture.and(clear_state.green),
-                blue: self.color_texture.and(clear_state.blue),
-                alpha: self.color_texture.and(clear_state.alpha),
-                depth: self.depth_texture.and(clear_state.depth),
-            },
-        );
-        render()?;
-        if let Some(color_texture) = self.color_texture {
-            color_texture.generate_mip_maps();
-        }
-        Ok(())
+        self.context.write_framebuffer(Some(&self.id), || {
+            self.bind(Some(color_layers), Some(depth_layer))?;
+            clear(
+                &self.context,
+                &ClearState {
+                    red: self.color_texture.and(clear_state.red),
+                    green: self.color_texture.and(clear_state.green),
+                    blue: self.color_texture.and(clear_state.blue),
+                    alpha: self.color_texture.and(clear_state.alpha),
+                    depth: self.depth_texture.and(clear_state.depth),
+                },
+            );
+            render()?;
+            if let Some(color_texture) = self.color_texture {
+                color_texture.generate_mip_maps();
+            }
+            Ok(())
+        })
     }
 
     ///
@@ -182,8 +184,6 @@ impl<'a, 'b, T: TextureDataType> RenderTargetArray<'a, 'b, T> {
     }
 
     fn bind(&self, color_layers: Option<&[u32]>, depth_layer: Option<u32>) -> ThreeDResult<()> {
-        self.context
-            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, Some(&self.id));
         if let Some(color_texture) = self.color_texture {
             if let Some(color_layers) = color_layers {
                 self.context.draw_buffers(
@@ -94,30 +94,29 @@ impl<'a, 'b, T: TextureDataType> RenderTargetCubeMap<'a, 'b, T> {
         clear_state: ClearState,
         render: impl FnOnce() -> ThreeDResult<()>,
     ) -> ThreeDResult<()> {
-        self.context
-            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, Some(&self.id));
-        if let Some(ref color_texture) = self.color_texture {
-            self.context.draw_buffers(&[consts::COLOR_ATTACHMENT0]);
-            color_texture.bind_as_color_target(side, 0, mip_level);
-        }
-        if let Some(ref depth_texture) = self.depth_texture {
-            depth_texture.bind_as_depth_target(side);
-        }
-        #[cfg(feature = "debug")]
-        check(&self.context)?;
+        self.context.write_framebuffer(Some(&self.id), || {
+            if let Some(ref color_texture) = self.color_texture {
+                self.context.draw_buffers(&[consts::COLOR_ATTACHMENT0]);
+                color_texture.bind_as_color_target(side, 0, mip_level);
+            }
+            if let Some(ref depth_texture) = self.depth_texture {
+                depth_texture.bind_as_depth_target(side);
+            }
+            #[cfg(feature = "debug")]
+            check(&self.context)?;
 
-        clear(
-            &self.context,
-            &ClearState {
-                red: self.color_texture.as_ref().and(clear_state.red),
-                green: self.color_texture.as_ref().and(clear_state.green),
-                blue: self.color_texture.as_ref().and(clear_state.blue),
-                alpha: self.color_texture.as_ref().and(clear_state.alpha),
-                depth: self.depth_texture.as_ref().and(clear_state.depth),
-            },
-        );
-        render()?;
-        Ok(())
+            clear(
+                &self.context,
+                &ClearState {
+                    red: self.color_texture.as_ref().and(clear_state.red),
+                    green: self.color_texture.as_ref().and(clear_state.green),
+                    blue: self.color_texture.as_ref().and(clear_state.blue),
+                    alpha: self.color_texture.as_ref().and(clear_state.alpha),
+                    depth: self.depth_texture.as_ref().and(clear_state.depth),
+                },
+            );
+            render()
+        })
     }
 }
 
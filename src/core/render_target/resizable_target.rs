@@ -0,0 +1,364 @@
+use crate::core::render_target::*;
+
+type ColorCreation<T> = Box<dyn Fn(&Context, u32, u32) -> ThreeDResult<Texture2D<T>>>;
+type DepthCreation = Box<dyn Fn(&Context, u32, u32) -> ThreeDResult<DepthTargetTexture2D>>;
+type ColorArrayCreation<T> = Box<dyn Fn(&Context, u32, u32) -> ThreeDResult<Texture2DArray<T>>>;
+type DepthArrayCreation =
+    Box<dyn Fn(&Context, u32, u32) -> ThreeDResult<DepthTargetTexture2DArray>>;
+
+///
+/// Owns a color and/or depth texture that is rendered into through a [RenderTarget] and that follows the size of for example the window.
+/// The textures are created using the closures given at construction the first time they are needed
+/// and recreated whenever the requested size changes, so the same code works for any size without manually checking for changes.
+/// The old textures are dropped before the new ones are created, so resizing does not increase the amount of used GPU memory, see [ResizableTarget::byte_size].
+///
+pub struct ResizableTarget<T: TextureDataType> {
+    context: Context,
+    ///
+    /// The size of the textures relative to the requested size, for example 0.5 to render at half the resolution.
+    /// The changed scale is used from the next call to [ResizableTarget::get] or [ResizableTarget::resize].
+    ///
+    pub scale_factor: f32,
+    ///
+    /// The size of the textures is rounded up to a multiple of this value, which means that the textures are not recreated
+    /// each time the requested size changes by a single pixel, for example during a live resize of the window.
+    /// If larger than 1, the content only covers the [ResizableTarget::viewport] of the textures.
+    ///
+    pub granularity: u32,
+    create_color: Option<ColorCreation<T>>,
+    create_depth: Option<DepthCreation>,
+    color_texture: Option<Texture2D<T>>,
+    depth_texture: Option<DepthTargetTexture2D>,
+    viewport: Viewport,
+}
+
+impl ResizableTarget<u8> {
+    ///
+    /// Constructs a new resizable target with a depth texture created by the given closure, which is given the context and the size of the texture.
+    ///
+    pub fn new_depth(
+        context: &Context,
+        create_depth: impl Fn(&Context, u32, u32) -> ThreeDResult<DepthTargetTexture2D> + 'static,
+    ) -> Self {
+        Self::new_internal(context, None, Some(Box::new(create_depth)))
+    }
+}
+
+impl<T: TextureDataType> ResizableTarget<T> {
+    ///
+    /// Constructs a new resizable target with a color and a depth texture created by the given closures, which are given the context and the size of the texture.
+    /// The textures are not created until the first call to [ResizableTarget::get] or [ResizableTarget::resize].
+    ///
+    pub fn new(
+        context: &Context,
+        create_color: impl Fn(&Context, u32, u32) -> ThreeDResult<Texture2D<T>> + 'static,
+        create_depth: impl Fn(&Context, u32, u32) -> ThreeDResult<DepthTargetTexture2D> + 'static,
+    ) -> Self {
+        Self::new_internal(
+            context,
+            Some(Box::new(create_color)),
+            Some(Box::new(create_depth)),
+        )
+    }
+
+    ///
+    /// Constructs a new resizable target with a color texture created by the given closure, which is given the context and the size of the texture.
+    ///
+    pub fn new_color(
+        context: &Context,
+        create_color: impl Fn(&Context, u32, u32) -> ThreeDResult<Texture2D<T>> + 'static,
+    ) -> Self {
+        Self::new_internal(context, Some(Box::new(create_color)), None)
+    }
+
+    fn new_internal(
+        context: &Context,
+        create_color: Option<ColorCreation<T>>,
+        create_depth: Option<DepthCreation>,
+    ) -> Self {
+        Self {
+            context: context.clone(),
+            scale_factor: 1.0,
+            granularity: 1,
+            create_color,
+            create_depth,
+            color_texture: None,
+            depth_texture: None,
+            viewport: Viewport::new_at_origo(1, 1),
+        }
+    }
+
+    ///
+    /// Resizes the textures to match the given size, see [ResizableTarget::resize],
+    /// and returns a render target which renders into them.
+    /// The [ResizableTarget::viewport] should be used when rendering into the returned render target.
+    ///
+    pub fn get(&mut self, width: u32, height: u32) -> ThreeDResult<RenderTarget<'_, '_, T>> {
+        self.resize(width, height)?;
+        match (self.color_texture.as_mut(), self.depth_texture.as_mut()) {
+            (Some(color_texture), Some(depth_texture)) => {
+                RenderTarget::new(&self.context, color_texture, depth_texture)
+            }
+            (Some(color_texture), None) => RenderTarget::new_color(&self.context, color_texture),
+            (None, Some(depth_texture)) => {
+                RenderTarget::new_depth_internal(&self.context, depth_texture)
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    ///
+    /// Recreates the textures if the given size, multiplied by the [ResizableTarget::scale_factor] and rounded up to the [ResizableTarget::granularity],
+    /// is different from the size of the current textures. Returns whether or not the textures were recreated, in which case the content is lost.
+    ///
+    pub fn resize(&mut self, width: u32, height: u32) -> ThreeDResult<bool> {
+        let (viewport, texture_width, texture_height) =
+            target_size(width, height, self.scale_factor, self.granularity);
+        self.viewport = viewport;
+        let current = self
+            .color_texture
+            .as_ref()
+            .map(|t| (t.width(), t.height()))
+            .or_else(|| self.depth_texture.as_ref().map(|t| (t.width(), t.height())));
+        if current == Some((texture_width, texture_height)) {
+            return Ok(false);
+        }
+        // Drop the old textures before creating new ones, so at most one set of textures is allocated at any time
+        self.color_texture = None;
+        self.depth_texture = None;
+        if let Some(ref create_color) = self.create_color {
+            self.color_texture = Some(create_color(&self.context, texture_width, texture_height)?);
+        }
+        if let Some(ref create_depth) = self.create_depth {
+            self.depth_texture = Some(create_depth(&self.context, texture_width, texture_height)?);
+        }
+        Ok(true)
+    }
+
+    ///
+    /// Returns the part of the textures which contains the content, ie. the requested size multiplied by the [ResizableTarget::scale_factor],
+    /// which is equal to the entire texture unless the [ResizableTarget::granularity] is larger than 1.
+    ///
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    ///
+    /// Returns the color texture or `None` if it has not been created yet or this target has no color texture.
+    ///
+    pub fn color_texture(&self) -> Option<&Texture2D<T>> {
+        self.color_texture.as_ref()
+    }
+
+    ///
+    /// Returns the depth texture or `None` if it has not been created yet or this target has no depth texture.
+    ///
+    pub fn depth_texture(&self) -> Option<&DepthTargetTexture2D> {
+        self.depth_texture.as_ref()
+    }
+
+    ///
+    /// Returns the number of bytes of GPU memory used by the currently allocated textures, not counting mip maps.
+    /// This stays constant while the size is unchanged and can be used to verify that resizing does not leak textures.
+    ///
+    pub fn byte_size(&self) -> usize {
+        self.color_texture.as_ref().map_or(0, |t| {
+            color_byte_size::<T>(t.width(), t.height(), 1, t.format())
+        }) + self.depth_texture.as_ref().map_or(0, |t| {
+            depth_byte_size(t.width(), t.height(), 1, t.depth_format())
+        })
+    }
+}
+
+///
+/// The same as [ResizableTarget] except that it owns a [Texture2DArray] and/or a [DepthTargetTexture2DArray]
+/// which are rendered into through a [RenderTargetArray].
+///
+pub struct ResizableTargetArray<T: TextureDataType> {
+    context: Context,
+    ///
+    /// The size of the textures relative to the requested size, see [ResizableTarget::scale_factor].
+    ///
+    pub scale_factor: f32,
+    ///
+    /// The size of the textures is rounded up to a multiple of this value, see [ResizableTarget::granularity].
+    ///
+    pub granularity: u32,
+    create_color: Option<ColorArrayCreation<T>>,
+    create_depth: Option<DepthArrayCreation>,
+    color_texture: Option<Texture2DArray<T>>,
+    depth_texture: Option<DepthTargetTexture2DArray>,
+    viewport: Viewport,
+}
+
+impl ResizableTargetArray<u8> {
+    ///
+    /// Constructs a new resizable target with a depth texture array created by the given closure, which is given the context and the size of the texture.
+    ///
+    pub fn new_depth(
+        context: &Context,
+        create_depth: impl Fn(&Context, u32, u32) -> ThreeDResult<DepthTargetTexture2DArray> + 'static,
+    ) -> Self {
+        Self::new_internal(context, None, Some(Box::new(create_depth)))
+    }
+}
+
+impl<T: TextureDataType> ResizableTargetArray<T> {
+    ///
+    /// Constructs a new resizable target with a color and a depth texture array created by the given closures, which are given the context and the size of the texture.
+    /// The textures are not created until the first call to [ResizableTargetArray::get] or [ResizableTargetArray::resize].
+    ///
+    pub fn new(
+        context: &Context,
+        create_color: impl Fn(&Context, u32, u32) -> ThreeDResult<Texture2DArray<T>> + 'static,
+        create_depth: impl Fn(&Context, u32, u32) -> ThreeDResult<DepthTargetTexture2DArray> + 'static,
+    ) -> Self {
+        Self::new_internal(
+            context,
+            Some(Box::new(create_color)),
+            Some(Box::new(create_depth)),
+        )
+    }
+
+    ///
+    /// Constructs a new resizable target with a color texture array created by the given closure, which is given the context and the size of the texture.
+    ///
+    pub fn new_color(
+        context: &Context,
+        create_color: impl Fn(&Context, u32, u32) -> ThreeDResult<Texture2DArray<T>> + 'static,
+    ) -> Self {
+        Self::new_internal(context, Some(Box::new(create_color)), None)
+    }
+
+    fn new_internal(
+        context: &Context,
+        create_color: Option<ColorArrayCreation<T>>,
+        create_depth: Option<DepthArrayCreation>,
+    ) -> Self {
+        Self {
+            context: context.clone(),
+            scale_factor: 1.0,
+            granularity: 1,
+            create_color,
+            create_depth,
+            color_texture: None,
+            depth_texture: None,
+            viewport: Viewport::new_at_origo(1, 1),
+        }
+    }
+
+    ///
+    /// Resizes the textures to match the given size, see [ResizableTargetArray::resize],
+    /// and returns a render target which renders into them.
+    ///
+    pub fn get(&mut self, width: u32, height: u32) -> ThreeDResult<RenderTargetArray<'_, '_, T>> {
+        self.resize(width, height)?;
+        match (self.color_texture.as_ref(), self.depth_texture.as_ref()) {
+            (Some(color_texture), Some(depth_texture)) => {
+                RenderTargetArray::new(&self.context, color_texture, depth_texture)
+            }
+            (Some(color_texture), None) => {
+                RenderTargetArray::new_color(&self.context, color_texture)
+            }
+            (None, Some(depth_texture)) => {
+                RenderTargetArray::new_depth_internal(&self.context, depth_texture)
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    ///
+    /// Recreates the textures if the given size, multiplied by the [ResizableTargetArray::scale_factor] and rounded up to the [ResizableTargetArray::granularity],
+    /// is different from the size of the current textures. Returns whether or not the textures were recreated, in which case the content is lost.
+    ///
+    pub fn resize(&mut self, width: u32, height: u32) -> ThreeDResult<bool> {
+        let (viewport, texture_width, texture_height) =
+            target_size(width, height, self.scale_factor, self.granularity);
+        self.viewport = viewport;
+        let current = self
+            .color_texture
+            .as_ref()
+            .map(|t| (t.width(), t.height()))
+            .or_else(|| self.depth_texture.as_ref().map(|t| (t.width(), t.height())));
+        if current == Some((texture_width, texture_height)) {
+            return Ok(false);
+        }
+        self.color_texture = None;
+        self.depth_texture = None;
+        if let Some(ref create_color) = self.create_color {
+            self.color_texture = Some(create_color(&self.context, texture_width, texture_height)?);
+        }
+        if let Some(ref create_depth) = self.create_depth {
+            self.depth_texture = Some(create_depth(&self.context, texture_width, texture_height)?);
+        }
+        Ok(true)
+    }
+
+    ///
+    /// Returns the part of the textures which contains the content, see [ResizableTarget::viewport].
+    ///
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    ///
+    /// Returns the color texture array or `None` if it has not been created yet or this target has no color texture.
+    ///
+    pub fn color_texture(&self) -> Option<&Texture2DArray<T>> {
+        self.color_texture.as_ref()
+    }
+
+    ///
+    /// Returns the depth texture array or `None` if it has not been created yet or this target has no depth texture.
+    ///
+    pub fn depth_texture(&self) -> Option<&DepthTargetTexture2DArray> {
+        self.depth_texture.as_ref()
+    }
+
+    ///
+    /// Returns the number of bytes of GPU memory used by the currently allocated textures, see [ResizableTarget::byte_size].
+    ///
+    pub fn byte_size(&self) -> usize {
+        self.color_texture.as_ref().map_or(0, |t| {
+            color_byte_size::<T>(t.width(), t.height(), t.depth(), t.format())
+        }) + self.depth_texture.as_ref().map_or(0, |t| {
+            depth_byte_size(t.width(), t.height(), t.depth(), t.depth_format())
+        })
+    }
+}
+
+// Returns the viewport containing the content and the size of the textures
+fn target_size(
+    width: u32,
+    height: u32,
+    scale_factor: f32,
+    granularity: u32,
+) -> (Viewport, u32, u32) {
+    let scale = |size: u32| ((size as f32 * scale_factor).round() as u32).max(1);
+    let (width, height) = (scale(width), scale(height));
+    let granularity = granularity.max(1);
+    let round_up = |size: u32| ((size + granularity - 1) / granularity) * granularity;
+    (
+        Viewport::new_at_origo(width, height),
+        round_up(width),
+        round_up(height),
+    )
+}
+
+fn color_byte_size<T: TextureDataType>(
+    width: u32,
+    height: u32,
+    depth: u32,
+    format: Format,
+) -> usize {
+    (width * height * depth * format.color_channel_count()) as usize * std::mem::size_of::<T>()
+}
+
+fn depth_byte_size(width: u32, height: u32, depth: u32, format: DepthFormat) -> usize {
+    let bytes = match format {
+        DepthFormat::Depth16 => 2,
+        DepthFormat::Depth24 => 3,
+        DepthFormat::Depth32F => 4,
+    };
+    (width * height * depth) as usize * bytes
+}
@@ -12,16 +12,21 @@ impl Screen {
     /// Call this function and make a render call (for example one of the draw functions on [Program])
     /// in the `render` closure to render something to the screen.
     /// Before writing, the screen is cleared based on the given clear state.
+    /// Renders into the offscreen textures instead if [Context::set_offscreen_screen_size] has been called.
     ///
     pub fn write<F: FnOnce() -> ThreeDResult<()>>(
         context: &Context,
         clear_state: ClearState,
         render: F,
     ) -> ThreeDResult<()> {
-        context.bind_framebuffer(consts::DRAW_FRAMEBUFFER, None);
-        clear(context, &clear_state);
-        render()?;
-        Ok(())
+        if let Some((ref color_texture, ref depth_texture)) = *context.offscreen_screen() {
+            return RenderTarget::new_internal(context, color_texture, depth_texture)?
+                .write(clear_state, render);
+        }
+        context.write_framebuffer(None, || {
+            clear(context, &clear_state);
+            render()
+        })
     }
 
     ///
@@ -29,36 +34,39 @@ impl Screen {
     ///
     pub fn read_color(context: &Context, viewport: Viewport) -> ThreeDResult<Vec<u8>> {
         let mut pixels = vec![0u8; viewport.width as usize * viewport.height as usize * 4];
-        context.bind_framebuffer(consts::READ_FRAMEBUFFER, None);
-        context.read_pixels_with_u8_data(
-            viewport.x as u32,
-            viewport.y as u32,
-            viewport.width,
-            viewport.height,
-            consts::RGBA,
-            DataType::UnsignedByte,
-            &mut pixels,
-        );
+        read(context, || {
+            context.read_pixels_with_u8_data(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width,
+                viewport.height,
+                consts::RGBA,
+                DataType::UnsignedByte,
+                &mut pixels,
+            );
+        })?;
         Ok(pixels)
     }
 
     ///
-    /// Returns the depth values from the screen as a list of 32-bit floats.
+    /// Returns the depth values from the screen as a list of 32-bit floats,
+    /// which are reversed in the [DepthMode::ReversedZ] depth mode.
     /// Only available on desktop.
     ///
     #[cfg(not(target_arch = "wasm32"))]
     pub fn read_depth(context: &Context, viewport: Viewport) -> ThreeDResult<Vec<f32>> {
         let mut pixels = vec![0f32; viewport.width as usize * viewport.height as usize];
-        context.bind_framebuffer(consts::READ_FRAMEBUFFER, None);
-        context.read_pixels_with_f32_data(
-            viewport.x as u32,
-            viewport.y as u32,
-            viewport.width,
-            viewport.height,
-            consts::DEPTH_COMPONENT,
-            DataType::Float,
-            &mut pixels,
-        );
+        read(context, || {
+            context.read_pixels_with_f32_data(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width,
+                viewport.height,
+                consts::DEPTH_COMPONENT,
+                DataType::Float,
+                &mut pixels,
+            );
+        })?;
         Ok(pixels)
     }
 
@@ -94,3 +102,16 @@ impl Screen {
         })
     }
 }
+
+// Binds the default framebuffer, or the offscreen textures replacing it, for reading while the given closure is called
+fn read(context: &Context, read: impl FnOnce()) -> ThreeDResult<()> {
+    if let Some((ref color_texture, ref depth_texture)) = *context.offscreen_screen() {
+        let render_target = RenderTarget::new_internal(context, color_texture, depth_texture)?;
+        render_target.bind(consts::READ_FRAMEBUFFER)?;
+        read();
+    } else {
+        context.bind_framebuffer(consts::READ_FRAMEBUFFER, None);
+        read();
+    }
+    Ok(())
+}
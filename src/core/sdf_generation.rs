@@ -0,0 +1,145 @@
+use crate::core::*;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+impl CPUMesh {
+    ///
+    /// Computes a signed distance field of this mesh inside the given bounds, ie. a 3D texture with the given resolution where each texel contains
+    /// the distance from the center of the texel to the closest point on the surface of the mesh, which is negative inside the mesh.
+    /// The texel centers are evenly spaced in the bounds, so the texture coordinates `(0, 0, 0)` and `(1, 1, 1)` are the minimum and maximum corner of the bounds.
+    ///
+    /// The sign is given by the winding number of the mesh around the texel center, so the mesh should be closed
+    /// and the triangles should be wound counter-clockwise when seen from the outside.
+    /// The distance to every triangle is computed for every texel, so this is only suitable for small scenes and low resolutions.
+    /// On native, the slices of the texture are computed in parallel.
+    ///
+    pub fn compute_sdf(
+        &self,
+        resolution: (u32, u32, u32),
+        bounds: AxisAlignedBoundingBox,
+    ) -> CPUTexture3D<f32> {
+        let (width, height, depth) = (
+            resolution.0.max(1),
+            resolution.1.max(1),
+            resolution.2.max(1),
+        );
+        let mut triangles = Vec::new();
+        self.for_each_triangle(|i0, i1, i2| {
+            triangles.push([self.position(i0), self.position(i1), self.position(i2)]);
+        });
+        let min = bounds.min();
+        let size = bounds.size();
+        let texel_center = |x: u32, y: u32, z: u32| {
+            min + vec3(
+                size.x * (x as f32 + 0.5) / width as f32,
+                size.y * (y as f32 + 0.5) / height as f32,
+                size.z * (z as f32 + 0.5) / depth as f32,
+            )
+        };
+        let compute_slice = |z: u32| {
+            let mut slice = Vec::with_capacity((width * height) as usize);
+            for y in 0..height {
+                for x in 0..width {
+                    slice.push(signed_distance(&triangles, texel_center(x, y, z)));
+                }
+            }
+            slice
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let slices = (0..depth)
+            .into_par_iter()
+            .map(compute_slice)
+            .collect::<Vec<_>>();
+        #[cfg(target_arch = "wasm32")]
+        let slices = (0..depth).map(compute_slice).collect::<Vec<_>>();
+
+        CPUTexture3D {
+            data: slices.concat(),
+            width,
+            height,
+            depth,
+            format: Format::R,
+            ..Default::default()
+        }
+    }
+}
+
+// The distance from the point to the closest triangle, which is negative if the winding number of the triangles around the point is more than a half
+fn signed_distance(triangles: &[[Vec3; 3]], p: Vec3) -> f32 {
+    if triangles.is_empty() {
+        return std::f32::MAX;
+    }
+    let mut distance2 = std::f32::MAX;
+    let mut solid_angle = 0.0;
+    for [a, b, c] in triangles.iter() {
+        distance2 = distance2.min(p.distance2(closest_point_on_triangle(p, *a, *b, *c)));
+        solid_angle += triangle_solid_angle(*a - p, *b - p, *c - p);
+    }
+    let distance = distance2.sqrt();
+    if solid_angle / (4.0 * std::f32::consts::PI) > 0.5 {
+        -distance
+    } else {
+        distance
+    }
+}
+
+// The signed solid angle of the triangle seen from the origin (Van Oosterom and Strackee)
+fn triangle_solid_angle(a: Vec3, b: Vec3, c: Vec3) -> f32 {
+    let (la, lb, lc) = (a.magnitude(), b.magnitude(), c.magnitude());
+    let numerator = a.dot(b.cross(c));
+    let denominator = la * lb * lc + a.dot(b) * lc + a.dot(c) * lb + b.dot(c) * la;
+    2.0 * numerator.atan2(denominator)
+}
+
+// The closest point on the triangle to the given point (Real-Time Collision Detection, Ericson)
+fn closest_point_on_triangle(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denominator = va + vb + vc;
+    if denominator <= 0.0 {
+        // A degenerate triangle, where the closest vertex is good enough
+        return [a, b, c]
+            .iter()
+            .cloned()
+            .min_by(|x, y| {
+                p.distance2(*x)
+                    .partial_cmp(&p.distance2(*y))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap();
+    }
+    let v = vb / denominator;
+    let w = vc / denominator;
+    a + ab * v + ac * w
+}
@@ -38,6 +38,26 @@ vec3 reinhard_tone_mapping(vec3 color) {
     return color / (color + vec3(1.0));
 }
 
+// Converts a linear color to sRGB, unless HDR_OUTPUT is defined,
+// in which case the linear color is tone mapped and converted afterwards (see HdrPipeline).
+vec3 encode_output(vec3 rgb) {
+#ifdef HDR_OUTPUT
+    return rgb;
+#else
+    return srgb_from_rgb(rgb);
+#endif
+}
+
+// Tone maps a linear high dynamic range color and converts it to sRGB, unless HDR_OUTPUT is defined,
+// in which case the linear color is tone mapped and converted afterwards (see HdrPipeline).
+vec3 tone_map_and_encode_output(vec3 rgb) {
+#ifdef HDR_OUTPUT
+    return rgb;
+#else
+    return srgb_from_rgb(reinhard_tone_mapping(rgb));
+#endif
+}
+
 // http://holger.dammertz.org/stuff/notes_HammersleyOnHemisphere.html
 // efficient VanDerCorpus calculation.
 float RadicalInverse_VdC(uint bits) 
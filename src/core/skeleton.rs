@@ -0,0 +1,193 @@
+use crate::core::*;
+
+///
+/// A joint (also called a bone) of a [Skeleton].
+/// The local transformation of the joint relative to its parent is given by the translation, rotation and scale,
+/// which is the rest pose of the joint when it is not animated by an [AnimationClip].
+///
+#[derive(Debug, Clone)]
+pub struct Joint {
+    /// Name.
+    pub name: String,
+    /// The index of the parent joint in [Skeleton::joints] or `None` if this is a root joint.
+    pub parent: Option<usize>,
+    /// The translation of the rest pose relative to the parent.
+    pub translation: Vec3,
+    /// The rotation of the rest pose relative to the parent.
+    pub rotation: Quat,
+    /// The scale of the rest pose relative to the parent.
+    pub scale: Vec3,
+    /// The transformation from the space of the mesh to the space of the joint when the mesh is bound to the skeleton.
+    pub inverse_bind_matrix: Mat4,
+}
+
+impl Default for Joint {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            parent: None,
+            translation: vec3(0.0, 0.0, 0.0),
+            rotation: Quat::one(),
+            scale: vec3(1.0, 1.0, 1.0),
+            inverse_bind_matrix: Mat4::identity(),
+        }
+    }
+}
+
+///
+/// A hierarchy of joints which deforms a [CPUMesh] with [CPUMesh::joint_indices] and [CPUMesh::joint_weights], also known as skinning.
+/// Each vertex is transformed by the weighted sum of the skinning matrices of its joints, see [Skeleton::skinning_matrices].
+///
+#[derive(Debug, Clone, Default)]
+pub struct Skeleton {
+    /// The joints, where a parent must be placed before its children.
+    pub joints: Vec<Joint>,
+}
+
+impl Skeleton {
+    ///
+    /// Returns the skinning matrix of each joint at the given time of the given animation clip,
+    /// ie. the transformation from the bind pose of the mesh to the animated pose.
+    /// The joints which are not animated by the clip are in their rest pose.
+    ///
+    pub fn skinning_matrices(&self, clip: &AnimationClip, time: f32) -> Vec<Mat4> {
+        let mut global_transformations: Vec<Mat4> = Vec::with_capacity(self.joints.len());
+        for (index, joint) in self.joints.iter().enumerate() {
+            let mut translation = joint.translation;
+            let mut rotation = joint.rotation;
+            let mut scale = joint.scale;
+            for joint_animation in clip.joint_animations.iter().filter(|a| a.joint == index) {
+                let key_frames = &joint_animation.key_frames;
+                if let Some(t) = key_frames.translation(time) {
+                    translation = t;
+                }
+                if let Some(r) = key_frames.rotation(time) {
+                    rotation = r;
+                }
+                if let Some(s) = key_frames.scale(time) {
+                    scale = s;
+                }
+            }
+            let local = Mat4::from_translation(translation)
+                * Mat4::from(rotation)
+                * Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z);
+            let global = match joint.parent {
+                Some(parent) => global_transformations[parent] * local,
+                None => local,
+            };
+            global_transformations.push(global);
+        }
+        global_transformations
+            .iter()
+            .zip(self.joints.iter())
+            .map(|(global, joint)| global * joint.inverse_bind_matrix)
+            .collect()
+    }
+}
+
+///
+/// Key frames of the local transformation of a joint, ie. the translation, rotation and scale at specific points in time, which are linearly interpolated.
+/// Each of the translations, rotations and scales are either `None` or contains one value per time.
+///
+#[derive(Debug, Clone, Default)]
+pub struct KeyFrames {
+    /// The points in time in seconds in increasing order.
+    pub times: Vec<f32>,
+    /// The translations at each point in time.
+    pub translations: Option<Vec<Vec3>>,
+    /// The rotations at each point in time, which are interpolated using spherical linear interpolation.
+    pub rotations: Option<Vec<Quat>>,
+    /// The scales at each point in time.
+    pub scales: Option<Vec<Vec3>>,
+}
+
+impl KeyFrames {
+    ///
+    /// Returns the interpolated translation at the given time or `None` if there are no translations.
+    ///
+    pub fn translation(&self, time: f32) -> Option<Vec3> {
+        self.translations.as_ref().and_then(|values| {
+            self.interpolate(time, values, |a: &Vec3, b: &Vec3, t| a.lerp(*b, t))
+        })
+    }
+
+    ///
+    /// Returns the interpolated rotation at the given time or `None` if there are no rotations.
+    ///
+    pub fn rotation(&self, time: f32) -> Option<Quat> {
+        self.rotations.as_ref().and_then(|values| {
+            self.interpolate(time, values, |a: &Quat, b: &Quat, t| a.slerp(*b, t))
+        })
+    }
+
+    ///
+    /// Returns the interpolated scale at the given time or `None` if there are no scales.
+    ///
+    pub fn scale(&self, time: f32) -> Option<Vec3> {
+        self.scales.as_ref().and_then(|values| {
+            self.interpolate(time, values, |a: &Vec3, b: &Vec3, t| a.lerp(*b, t))
+        })
+    }
+
+    ///
+    /// Returns the time of the last key frame.
+    ///
+    pub fn end_time(&self) -> f32 {
+        self.times.last().cloned().unwrap_or(0.0)
+    }
+
+    // Interpolates the values at the key frames before and after the given time, the first or last value is used outside the time span
+    fn interpolate<T: Copy>(
+        &self,
+        time: f32,
+        values: &[T],
+        lerp: impl Fn(&T, &T, f32) -> T,
+    ) -> Option<T> {
+        if values.is_empty() || self.times.is_empty() {
+            return None;
+        }
+        let next = self.times.iter().position(|t| *t > time);
+        Some(match next {
+            Some(0) => values[0],
+            Some(i) if i < values.len() => {
+                let t = (time - self.times[i - 1]) / (self.times[i] - self.times[i - 1]);
+                lerp(&values[i - 1], &values[i], t)
+            }
+            _ => values[values.len().min(self.times.len()) - 1],
+        })
+    }
+}
+
+///
+/// The animation of a single joint of a [Skeleton] within an [AnimationClip].
+///
+#[derive(Debug, Clone, Default)]
+pub struct JointAnimation {
+    /// The index of the animated joint in [Skeleton::joints].
+    pub joint: usize,
+    /// The key frames of the local transformation of the joint.
+    pub key_frames: KeyFrames,
+}
+
+///
+/// An animation of the joints of a [Skeleton], for example a walk cycle.
+///
+#[derive(Debug, Clone, Default)]
+pub struct AnimationClip {
+    /// Name.
+    pub name: String,
+    /// The animations of the animated joints.
+    pub joint_animations: Vec<JointAnimation>,
+}
+
+impl AnimationClip {
+    ///
+    /// Returns the duration of the clip in seconds, ie. the time of the last key frame.
+    ///
+    pub fn duration(&self) -> f32 {
+        self.joint_animations
+            .iter()
+            .map(|a| a.key_frames.end_time())
+            .fold(0.0, f32::max)
+    }
+}
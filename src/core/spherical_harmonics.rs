@@ -0,0 +1,231 @@
+use crate::core::*;
+
+// The constant factors of the real spherical harmonics basis functions of the first three bands
+const Y0: f32 = 0.282095;
+const Y1: f32 = 0.488603;
+const Y2: f32 = 1.092548;
+const Y20: f32 = 0.315392;
+const Y22: f32 = 0.546274;
+
+// The convolution of each band with a clamped cosine lobe divided by PI, see Ramamoorthi and Hanrahan, "An Efficient Representation for Irradiance Environment Maps"
+const A0: f32 = 1.0;
+const A1: f32 = 2.0 / 3.0;
+const A2: f32 = 0.25;
+
+///
+/// Spherical harmonics with 9 coefficients, ie. the first three bands, for each color channel.
+/// This is a compact approximation of the low frequency part of the light arriving from all directions
+/// which is accurate enough to represent diffuse lighting, see for example [ProbeGrid](crate::ProbeGrid).
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SH9 {
+    /// The RGB coefficients ordered by band `l` and then `m`, ie. `(0, 0), (1, -1), (1, 0), (1, 1), (2, -2), (2, -1), (2, 0), (2, 1), (2, 2)`.
+    pub coefficients: [Vec3; 9],
+}
+
+impl SH9 {
+    ///
+    /// Projects the light given by the equirectangular texture into spherical harmonics on the CPU.
+    /// The texture uses the same mapping as [TextureCubeMap::new_from_equirectangular].
+    ///
+    pub fn from_equirectangular(cpu_texture: &CPUTexture<f32>) -> ThreeDResult<Self> {
+        let channels = cpu_texture.format.color_channel_count() as usize;
+        let (width, height) = (cpu_texture.width as usize, cpu_texture.height as usize);
+        if cpu_texture.data.len() != width * height * channels {
+            Err(CoreError::InvalidTextureLength(
+                cpu_texture.data.len() / channels,
+                width * height,
+            ))?;
+        }
+        let mut sh = Self::default();
+        let mut total_weight = 0.0;
+        for row in 0..height {
+            let latitude = std::f32::consts::PI * (0.5 - (row as f32 + 0.5) / height as f32);
+            // The solid angle of a pixel is proportional to the cosine of the latitude
+            let weight = latitude.cos();
+            for column in 0..width {
+                let longitude =
+                    2.0 * std::f32::consts::PI * ((column as f32 + 0.5) / width as f32 - 0.5);
+                let direction = vec3(
+                    latitude.cos() * longitude.cos(),
+                    latitude.sin(),
+                    latitude.cos() * longitude.sin(),
+                );
+                let i = (row * width + column) * channels;
+                let color = if channels >= 3 {
+                    vec3(
+                        cpu_texture.data[i],
+                        cpu_texture.data[i + 1],
+                        cpu_texture.data[i + 2],
+                    )
+                } else {
+                    vec3(
+                        cpu_texture.data[i],
+                        cpu_texture.data[i],
+                        cpu_texture.data[i],
+                    )
+                };
+                sh.add_sample(direction, color, weight);
+                total_weight += weight;
+            }
+        }
+        sh.normalize(total_weight);
+        Ok(sh)
+    }
+
+    ///
+    /// Projects the light given by the cube map into spherical harmonics.
+    /// Each side of the cube map is rendered into a texture with the given resolution on the GPU,
+    /// which is then read back and integrated on the CPU, so a small resolution like 32 is usually enough.
+    ///
+    /// # Errors
+    /// Will return an error if `f32` values cannot be read on the current context, see [Texture2D::read].
+    ///
+    pub fn from_cube_map(
+        context: &Context,
+        cube_map: &impl TextureCube,
+        resolution: u32,
+    ) -> ThreeDResult<Self> {
+        let mut texture = Texture2D::<f32>::new_empty(
+            context,
+            resolution,
+            resolution,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        let viewport = Viewport::new_at_origo(resolution, resolution);
+        // One effect outputs the color and the other the position on the unit cube, so the direction of each pixel does not depend on the layout of the sides
+        let color_effect = ImageCubeEffect::new(
+            context,
+            "uniform samplerCube environmentMap;
+            in vec3 pos;
+            layout (location = 0) out vec4 outColor;
+            void main()
+            {
+                outColor = vec4(texture(environmentMap, pos).rgb, 1.0);
+            }",
+        )?;
+        let position_effect = ImageCubeEffect::new(
+            context,
+            "in vec3 pos;
+            layout (location = 0) out vec4 outColor;
+            void main()
+            {
+                outColor = vec4(pos, 1.0);
+            }",
+        )?;
+
+        let mut sh = Self::default();
+        let mut total_weight = 0.0;
+        for side in CubeMapSide::iter() {
+            texture.write(ClearState::default(), || {
+                color_effect.use_texture_cube("environmentMap", cube_map)?;
+                color_effect.render(side, RenderStates::default(), viewport)
+            })?;
+            let colors = texture.read_internal(viewport)?;
+            texture.write(ClearState::default(), || {
+                position_effect.render(side, RenderStates::default(), viewport)
+            })?;
+            let positions = texture.read_internal(viewport)?;
+            for i in (0..colors.len()).step_by(4) {
+                let position = vec3(positions[i], positions[i + 1], positions[i + 2]);
+                // The solid angle of a pixel on the unit cube falls off with the cube of the distance to the center
+                let weight = 1.0 / position.magnitude().powi(3);
+                sh.add_sample(
+                    position.normalize(),
+                    vec3(colors[i], colors[i + 1], colors[i + 2]),
+                    weight,
+                );
+                total_weight += weight;
+            }
+        }
+        sh.normalize(total_weight);
+        Ok(sh)
+    }
+
+    ///
+    /// Adds the light with the given color arriving from the given direction weighted by the solid angle it covers.
+    /// The direction must be normalized.
+    ///
+    pub fn add_sample(&mut self, direction: Vec3, color: Vec3, weight: f32) {
+        for (coefficient, basis) in self.coefficients.iter_mut().zip(basis(direction).iter()) {
+            *coefficient += color * (basis * weight);
+        }
+    }
+
+    ///
+    /// Returns the approximated light arriving from the given normalized direction.
+    ///
+    pub fn radiance(&self, direction: Vec3) -> Vec3 {
+        self.coefficients
+            .iter()
+            .zip(basis(direction).iter())
+            .fold(vec3(0.0, 0.0, 0.0), |sum, (coefficient, basis)| {
+                sum + coefficient * *basis
+            })
+    }
+
+    ///
+    /// Returns the cosine weighted average of the light arriving at a surface with the given normalized normal,
+    /// ie. the irradiance divided by PI, which multiplied by the surface color gives the diffusely reflected light.
+    ///
+    pub fn irradiance(&self, normal: Vec3) -> Vec3 {
+        self.irradiance_coefficients()
+            .iter()
+            .zip(basis(normal).iter())
+            .fold(vec3(0.0, 0.0, 0.0), |sum, (coefficient, basis)| {
+                sum + coefficient * *basis
+            })
+    }
+
+    ///
+    /// Returns the coefficients convolved with a clamped cosine lobe divided by PI, see [SH9::irradiance].
+    ///
+    pub(crate) fn irradiance_coefficients(&self) -> [Vec3; 9] {
+        let mut coefficients = self.coefficients;
+        coefficients[0] *= A0;
+        for c in coefficients[1..4].iter_mut() {
+            *c *= A1;
+        }
+        for c in coefficients[4..9].iter_mut() {
+            *c *= A2;
+        }
+        coefficients
+    }
+
+    // Scales the coefficients such that the weights sum to the solid angle of the sphere
+    fn normalize(&mut self, total_weight: f32) {
+        if total_weight > 0.0 {
+            let scale = 4.0 * std::f32::consts::PI / total_weight;
+            for coefficient in self.coefficients.iter_mut() {
+                *coefficient *= scale;
+            }
+        }
+    }
+}
+
+impl Default for SH9 {
+    fn default() -> Self {
+        Self {
+            coefficients: [vec3(0.0, 0.0, 0.0); 9],
+        }
+    }
+}
+
+fn basis(d: Vec3) -> [f32; 9] {
+    [
+        Y0,
+        Y1 * d.y,
+        Y1 * d.z,
+        Y1 * d.x,
+        Y2 * d.x * d.y,
+        Y2 * d.y * d.z,
+        Y20 * (3.0 * d.z * d.z - 1.0),
+        Y2 * d.x * d.z,
+        Y22 * (d.x * d.x - d.y * d.y),
+    ]
+}
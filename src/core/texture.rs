@@ -25,6 +25,18 @@ mod depth_target_texture_cube_map;
 #[doc(inline)]
 pub use depth_target_texture_cube_map::*;
 
+mod texture3d;
+#[doc(inline)]
+pub use texture3d::*;
+
+mod readback;
+#[doc(inline)]
+pub use readback::*;
+
+mod texture_cache;
+#[doc(inline)]
+pub use texture_cache::*;
+
 ///
 /// Possible modes of interpolation which determines the texture output between texture pixels.
 ///
@@ -87,6 +99,7 @@ impl Format {
 /// Can be constructed manually or loaded via [Loader](crate::Loader).
 ///
 #[allow(missing_docs)]
+#[derive(Clone)]
 pub struct CPUTexture<T: TextureDataType> {
     pub data: Vec<T>,
     pub width: u32,
@@ -99,6 +112,9 @@ pub struct CPUTexture<T: TextureDataType> {
     pub mip_map_filter: Option<Interpolation>,
     pub wrap_s: Wrapping,
     pub wrap_t: Wrapping,
+    /// The number of samples used for anisotropic filtering, which improves the quality of textures viewed at a grazing angle, or `None` to disable anisotropic filtering.
+    /// See [Texture2D::set_anisotropy].
+    pub anisotropy: Option<u32>,
 }
 
 impl<T: TextureDataType> CPUTexture<T> {
@@ -141,6 +157,7 @@ impl<T: TextureDataType> Default for CPUTexture<T> {
             mip_map_filter: Some(Interpolation::Linear),
             wrap_s: Wrapping::Repeat,
             wrap_t: Wrapping::Repeat,
+            anisotropy: None,
         }
     }
 }
@@ -157,6 +174,7 @@ impl<T: TextureDataType> std::fmt::Debug for CPUTexture<T> {
             .field("mip_map_filter", &self.mip_map_filter)
             .field("wrap_s", &self.wrap_s)
             .field("wrap_t", &self.wrap_t)
+            .field("anisotropy", &self.anisotropy)
             .finish()
     }
 }
@@ -232,6 +250,111 @@ impl<T: TextureDataType> std::fmt::Debug for CPUTextureCube<T> {
     }
 }
 
+///
+/// A CPU-side version of a [Texture2DArray]. All layers must have the same dimensions.
+/// Can be constructed manually or loaded via [Loader](crate::Loader).
+///
+#[allow(missing_docs)]
+pub struct CPUTexture2DArray<T: TextureDataType> {
+    /// The pixel data for each layer
+    pub layers: Vec<Vec<T>>,
+    /// The width of each layer
+    pub width: u32,
+    /// The height of each layer
+    pub height: u32,
+    pub format: Format,
+    pub min_filter: Interpolation,
+    pub mag_filter: Interpolation,
+    /// Specifies whether mipmaps should be created for this texture and what type of interpolation to use between the two closest mipmaps.
+    /// Note, however, that the mipmaps only will be created if the width and height of the texture are power of two.
+    pub mip_map_filter: Option<Interpolation>,
+    pub wrap_s: Wrapping,
+    pub wrap_t: Wrapping,
+}
+
+impl<T: TextureDataType> Default for CPUTexture2DArray<T> {
+    fn default() -> Self {
+        Self {
+            layers: vec![],
+            width: 1,
+            height: 1,
+            format: Format::RGBA,
+            min_filter: Interpolation::Linear,
+            mag_filter: Interpolation::Linear,
+            mip_map_filter: Some(Interpolation::Linear),
+            wrap_s: Wrapping::Repeat,
+            wrap_t: Wrapping::Repeat,
+        }
+    }
+}
+
+impl<T: TextureDataType> std::fmt::Debug for CPUTexture2DArray<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CPUTexture2DArray")
+            .field("format", &self.format)
+            .field("layers", &self.layers.len())
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("min_filter", &self.min_filter)
+            .field("mag_filter", &self.mag_filter)
+            .field("mip_map_filter", &self.mip_map_filter)
+            .field("wrap_s", &self.wrap_s)
+            .field("wrap_t", &self.wrap_t)
+            .finish()
+    }
+}
+
+///
+/// A CPU-side version of a [Texture3D].
+///
+#[allow(missing_docs)]
+pub struct CPUTexture3D<T: TextureDataType> {
+    /// The pixel data, where the texels are ordered by x, then y and then z.
+    pub data: Vec<T>,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub format: Format,
+    pub min_filter: Interpolation,
+    pub mag_filter: Interpolation,
+    pub wrap_s: Wrapping,
+    pub wrap_t: Wrapping,
+    pub wrap_r: Wrapping,
+}
+
+impl<T: TextureDataType> Default for CPUTexture3D<T> {
+    fn default() -> Self {
+        Self {
+            data: vec![],
+            width: 1,
+            height: 1,
+            depth: 1,
+            format: Format::RGBA,
+            min_filter: Interpolation::Linear,
+            mag_filter: Interpolation::Linear,
+            wrap_s: Wrapping::ClampToEdge,
+            wrap_t: Wrapping::ClampToEdge,
+            wrap_r: Wrapping::ClampToEdge,
+        }
+    }
+}
+
+impl<T: TextureDataType> std::fmt::Debug for CPUTexture3D<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CPUTexture3D")
+            .field("format", &self.format)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("depth", &self.depth)
+            .field("min_filter", &self.min_filter)
+            .field("mag_filter", &self.mag_filter)
+            .field("wrap_s", &self.wrap_s)
+            .field("wrap_t", &self.wrap_t)
+            .field("wrap_r", &self.wrap_r)
+            .finish()
+    }
+}
+
 pub(in crate::core) mod internal {
     use crate::context::{consts, DataType};
     use crate::core::*;
@@ -245,8 +368,32 @@ pub(in crate::core) mod internal {
             height: u32,
             format: Format,
             data: &[Self],
+        ) {
+            Self::fill_sub_image(context, target, 0, 0, width, height, format, data);
+        }
+        fn fill_sub_image(
+            context: &Context,
+            target: u32,
+            x_offset: u32,
+            y_offset: u32,
+            width: u32,
+            height: u32,
+            format: Format,
+            data: &[Self],
+        );
+        fn fill_layers(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
         );
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]);
+        fn is_readable(capabilities: &Capabilities) -> bool;
+        fn read_to_pixel_pack_buffer(context: &Context, viewport: Viewport, format: Format);
+        fn read_from_pixel_pack_buffer(context: &Context, pixels: &mut [Self]);
         fn is_max(value: Self) -> bool;
         fn bits_per_channel() -> u8;
     }
@@ -261,9 +408,11 @@ pub(in crate::core) mod internal {
             })
         }
 
-        fn fill(
+        fn fill_sub_image(
             context: &Context,
             target: u32,
+            x_offset: u32,
+            y_offset: u32,
             width: u32,
             height: u32,
             format: Format,
@@ -272,10 +421,34 @@ pub(in crate::core) mod internal {
             context.tex_sub_image_2d_with_u8_data(
                 target,
                 0,
+                x_offset,
+                y_offset,
+                width,
+                height,
+                format_from(format),
+                DataType::UnsignedByte,
+                data,
+            );
+        }
+
+        fn fill_layers(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_u8_data(
+                target,
+                0,
+                0,
                 0,
                 0,
                 width,
                 height,
+                depth,
                 format_from(format),
                 DataType::UnsignedByte,
                 data,
@@ -294,6 +467,25 @@ pub(in crate::core) mod internal {
             );
         }
 
+        fn is_readable(_capabilities: &Capabilities) -> bool {
+            true
+        }
+
+        fn read_to_pixel_pack_buffer(context: &Context, viewport: Viewport, format: Format) {
+            context.read_pixels_to_pixel_pack_buffer(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width,
+                viewport.height,
+                format_from(format),
+                DataType::UnsignedByte,
+            );
+        }
+
+        fn read_from_pixel_pack_buffer(context: &Context, pixels: &mut [Self]) {
+            context.get_buffer_sub_data_u8(consts::PIXEL_PACK_BUFFER, 0, pixels);
+        }
+
         fn is_max(value: Self) -> bool {
             value == 255u8
         }
@@ -313,9 +505,11 @@ pub(in crate::core) mod internal {
             })
         }
 
-        fn fill(
+        fn fill_sub_image(
             context: &Context,
             target: u32,
+            x_offset: u32,
+            y_offset: u32,
             width: u32,
             height: u32,
             format: Format,
@@ -324,10 +518,34 @@ pub(in crate::core) mod internal {
             context.tex_sub_image_2d_with_u16_data(
                 target,
                 0,
+                x_offset,
+                y_offset,
+                width,
+                height,
+                format_from(format),
+                DataType::UnsignedShort,
+                data,
+            );
+        }
+
+        fn fill_layers(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_u16_data(
+                target,
+                0,
+                0,
                 0,
                 0,
                 width,
                 height,
+                depth,
                 format_from(format),
                 DataType::UnsignedShort,
                 data,
@@ -346,6 +564,25 @@ pub(in crate::core) mod internal {
             );
         }
 
+        fn is_readable(_capabilities: &Capabilities) -> bool {
+            false
+        }
+
+        fn read_to_pixel_pack_buffer(context: &Context, viewport: Viewport, format: Format) {
+            context.read_pixels_to_pixel_pack_buffer(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width,
+                viewport.height,
+                format_from(format),
+                DataType::UnsignedShort,
+            );
+        }
+
+        fn read_from_pixel_pack_buffer(context: &Context, pixels: &mut [Self]) {
+            context.get_buffer_sub_data_u16(consts::PIXEL_PACK_BUFFER, 0, pixels);
+        }
+
         fn is_max(value: Self) -> bool {
             value == std::u16::MAX
         }
@@ -365,9 +602,11 @@ pub(in crate::core) mod internal {
             })
         }
 
-        fn fill(
+        fn fill_sub_image(
             context: &Context,
             target: u32,
+            x_offset: u32,
+            y_offset: u32,
             width: u32,
             height: u32,
             format: Format,
@@ -376,16 +615,41 @@ pub(in crate::core) mod internal {
             context.tex_sub_image_2d_with_u16_data(
                 target,
                 0,
+                x_offset,
+                y_offset,
+                width,
+                height,
+                format_from(format),
+                DataType::HalfFloat,
+                &data.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+            );
+        }
+
+        fn fill_layers(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_u16_data(
+                target,
+                0,
+                0,
                 0,
                 0,
                 width,
                 height,
+                depth,
                 format_from(format),
                 DataType::HalfFloat,
                 &data.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
             );
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
             let mut pixels_temp = vec![0u16; pixels.len()];
             context.read_pixels_with_u16_data(
@@ -402,6 +666,71 @@ pub(in crate::core) mod internal {
             }
         }
 
+        #[cfg(target_arch = "wasm32")]
+        fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
+            // WebGL only guarantees that floating point color buffers can be read as 32 bit floats
+            let mut pixels_temp = vec![0f32; pixels.len()];
+            context.read_pixels_with_f32_data(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width as u32,
+                viewport.height as u32,
+                format_from(format),
+                DataType::Float,
+                &mut pixels_temp,
+            );
+            for i in 0..pixels.len() {
+                pixels[i] = f16::from_f32(pixels_temp[i]);
+            }
+        }
+
+        fn is_readable(capabilities: &Capabilities) -> bool {
+            capabilities.color_buffer_float
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        fn read_to_pixel_pack_buffer(context: &Context, viewport: Viewport, format: Format) {
+            context.read_pixels_to_pixel_pack_buffer(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width,
+                viewport.height,
+                format_from(format),
+                DataType::HalfFloat,
+            );
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        fn read_from_pixel_pack_buffer(context: &Context, pixels: &mut [Self]) {
+            let mut pixels_temp = vec![0u16; pixels.len()];
+            context.get_buffer_sub_data_u16(consts::PIXEL_PACK_BUFFER, 0, &mut pixels_temp);
+            for i in 0..pixels.len() {
+                pixels[i] = f16::from_bits(pixels_temp[i]);
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        fn read_to_pixel_pack_buffer(context: &Context, viewport: Viewport, format: Format) {
+            // WebGL only guarantees that floating point color buffers can be read as 32 bit floats
+            context.read_pixels_to_pixel_pack_buffer(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width,
+                viewport.height,
+                format_from(format),
+                DataType::Float,
+            );
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        fn read_from_pixel_pack_buffer(context: &Context, pixels: &mut [Self]) {
+            let mut pixels_temp = vec![0f32; pixels.len()];
+            context.get_buffer_sub_data_f32(consts::PIXEL_PACK_BUFFER, 0, &mut pixels_temp);
+            for i in 0..pixels.len() {
+                pixels[i] = f16::from_f32(pixels_temp[i]);
+            }
+        }
+
         fn is_max(value: Self) -> bool {
             value > f16::from_f32(0.99)
         }
@@ -421,21 +750,47 @@ pub(in crate::core) mod internal {
             })
         }
 
-        fn fill(
+        fn fill_sub_image(
             context: &Context,
             target: u32,
+            x_offset: u32,
+            y_offset: u32,
             width: u32,
             height: u32,
             format: Format,
             data: &[Self],
         ) {
             context.tex_sub_image_2d_with_f32_data(
+                target,
+                0,
+                x_offset,
+                y_offset,
+                width,
+                height,
+                format_from(format),
+                DataType::Float,
+                data,
+            );
+        }
+
+        fn fill_layers(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_f32_data(
                 target,
                 0,
                 0,
                 0,
+                0,
                 width,
                 height,
+                depth,
                 format_from(format),
                 DataType::Float,
                 data,
@@ -454,6 +809,25 @@ pub(in crate::core) mod internal {
             );
         }
 
+        fn is_readable(capabilities: &Capabilities) -> bool {
+            capabilities.color_buffer_float
+        }
+
+        fn read_to_pixel_pack_buffer(context: &Context, viewport: Viewport, format: Format) {
+            context.read_pixels_to_pixel_pack_buffer(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width,
+                viewport.height,
+                format_from(format),
+                DataType::Float,
+            );
+        }
+
+        fn read_from_pixel_pack_buffer(context: &Context, pixels: &mut [Self]) {
+            context.get_buffer_sub_data_f32(consts::PIXEL_PACK_BUFFER, 0, pixels);
+        }
+
         fn is_max(value: Self) -> bool {
             value > 0.99
         }
@@ -473,21 +847,47 @@ pub(in crate::core) mod internal {
             })
         }
 
-        fn fill(
+        fn fill_sub_image(
             context: &Context,
             target: u32,
+            x_offset: u32,
+            y_offset: u32,
             width: u32,
             height: u32,
             format: Format,
             data: &[Self],
         ) {
             context.tex_sub_image_2d_with_u32_data(
+                target,
+                0,
+                x_offset,
+                y_offset,
+                width,
+                height,
+                format_from(format),
+                DataType::UnsignedInt,
+                data,
+            );
+        }
+
+        fn fill_layers(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_u32_data(
                 target,
                 0,
                 0,
                 0,
+                0,
                 width,
                 height,
+                depth,
                 format_from(format),
                 DataType::UnsignedInt,
                 data,
@@ -505,6 +905,25 @@ pub(in crate::core) mod internal {
             );
         }
 
+        fn is_readable(_capabilities: &Capabilities) -> bool {
+            false
+        }
+
+        fn read_to_pixel_pack_buffer(context: &Context, viewport: Viewport, format: Format) {
+            context.read_pixels_to_pixel_pack_buffer(
+                viewport.x as u32,
+                viewport.y as u32,
+                viewport.width,
+                viewport.height,
+                format_from(format),
+                DataType::UnsignedInt,
+            );
+        }
+
+        fn read_from_pixel_pack_buffer(context: &Context, pixels: &mut [Self]) {
+            context.get_buffer_sub_data_u32(consts::PIXEL_PACK_BUFFER, 0, pixels);
+        }
+
         fn is_max(_value: Self) -> bool {
             true
         }
@@ -685,11 +1104,45 @@ impl<T: TextureCube + ?Sized> TextureCube for &mut T {
 
 // COMMON TEXTURE FUNCTIONS
 fn generate(context: &Context) -> ThreeDResult<crate::context::Texture> {
+    context.check_context_lost()?;
     Ok(context
         .create_texture()
         .ok_or_else(|| CoreError::TextureCreation)?)
 }
 
+fn check_size(context: &Context, width: u32, height: u32) -> ThreeDResult<()> {
+    let max_size = context.capabilities().max_texture_size;
+    if width > max_size || height > max_size {
+        Err(CoreError::TextureTooLarge(width, height, max_size))?;
+    }
+    Ok(())
+}
+
+fn check_cube_map_size(context: &Context, width: u32, height: u32) -> ThreeDResult<()> {
+    let max_size = context.capabilities().max_cube_map_texture_size;
+    if width > max_size || height > max_size {
+        Err(CoreError::TextureTooLarge(width, height, max_size))?;
+    }
+    Ok(())
+}
+
+fn check_array_size(context: &Context, width: u32, height: u32, layers: u32) -> ThreeDResult<()> {
+    check_size(context, width, height)?;
+    let max_layers = context.capabilities().max_array_texture_layers;
+    if layers > max_layers {
+        Err(CoreError::TooManyTextureLayers(layers, max_layers))?;
+    }
+    Ok(())
+}
+
+fn check_3d_size(context: &Context, width: u32, height: u32, depth: u32) -> ThreeDResult<()> {
+    let max_size = context.capabilities().max_3d_texture_size;
+    if width > max_size || height > max_size || depth > max_size {
+        Err(CoreError::Texture3DTooLarge(width, height, depth, max_size))?;
+    }
+    Ok(())
+}
+
 fn bind_at(context: &Context, id: &crate::context::Texture, target: u32, location: u32) {
     context.active_texture(consts::TEXTURE0 + location);
     context.bind_texture(target, id);
@@ -707,6 +1160,20 @@ fn set_parameters(
     wrap_r: Option<Wrapping>,
 ) {
     context.bind_texture(target, id);
+    set_filtering_parameters(context, target, min_filter, mag_filter, mip_map_filter);
+    set_wrapping_parameters(context, target, wrap_s, wrap_t, wrap_r);
+}
+
+///
+/// Sets the filtering parameters of the texture currently bound to the given target.
+///
+fn set_filtering_parameters(
+    context: &Context,
+    target: u32,
+    min_filter: Interpolation,
+    mag_filter: Interpolation,
+    mip_map_filter: Option<Interpolation>,
+) {
     match mip_map_filter {
         None => context.tex_parameteri(
             target,
@@ -749,6 +1216,18 @@ fn set_parameters(
         consts::TEXTURE_MAG_FILTER,
         interpolation_from(mag_filter),
     );
+}
+
+///
+/// Sets the wrapping parameters of the texture currently bound to the given target.
+///
+fn set_wrapping_parameters(
+    context: &Context,
+    target: u32,
+    wrap_s: Wrapping,
+    wrap_t: Wrapping,
+    wrap_r: Option<Wrapping>,
+) {
     context.tex_parameteri(target, consts::TEXTURE_WRAP_S, wrapping_from(wrap_s));
     context.tex_parameteri(target, consts::TEXTURE_WRAP_T, wrapping_from(wrap_t));
     if let Some(r) = wrap_r {
@@ -756,6 +1235,27 @@ fn set_parameters(
     }
 }
 
+///
+/// Sets the degree of anisotropic filtering of the texture currently bound to the given target, clamped to the maximum supported degree.
+/// Does nothing except logging a warning if anisotropic filtering is not supported.
+///
+fn set_anisotropy_parameter(context: &Context, target: u32, samples: u32) {
+    if let Some(max_anisotropy) = context.capabilities().max_anisotropy {
+        context.tex_parameterf(
+            target,
+            TEXTURE_MAX_ANISOTROPY_EXT,
+            (samples as f32).max(1.0).min(max_anisotropy),
+        );
+    } else {
+        log::warn!(
+            "Anisotropic filtering is not supported on this device, so the anisotropy is ignored."
+        );
+    }
+}
+
+// Defined by the EXT_texture_filter_anisotropic extension
+const TEXTURE_MAX_ANISOTROPY_EXT: u32 = 0x84FE;
+
 fn calculate_number_of_mip_maps(
     mip_map_filter: Option<Interpolation>,
     width: u32,
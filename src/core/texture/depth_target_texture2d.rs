@@ -24,6 +24,7 @@ pub struct DepthTargetTexture2D {
     id: crate::context::Texture,
     width: u32,
     height: u32,
+    format: DepthFormat,
 }
 
 impl DepthTargetTexture2D {
@@ -38,6 +39,7 @@ impl DepthTargetTexture2D {
         wrap_t: Wrapping,
         format: DepthFormat,
     ) -> ThreeDResult<Self> {
+        check_size(context, width, height)?;
         let id = generate(context)?;
         set_parameters(
             context,
@@ -62,9 +64,17 @@ impl DepthTargetTexture2D {
             id,
             width,
             height,
+            format,
         })
     }
 
+    ///
+    /// Returns the format of the depth values stored in this texture.
+    ///
+    pub fn depth_format(&self) -> DepthFormat {
+        self.format
+    }
+
     ///
     /// Write the depth of whatever rendered in the `render` closure into the texture.
     /// Before writing, the texture is cleared based on the given clear state.
@@ -103,6 +113,35 @@ impl DepthTargetTexture2D {
         )
     }
 
+    ///
+    /// Enables hardware depth comparison using the given interpolation, which is needed when the texture is sampled using a `sampler2DShadow`,
+    /// or disables it if `None`. Linear interpolation results in 2x2 percentage-closer filtering of the comparison results.
+    ///
+    pub(crate) fn set_depth_comparison(&self, interpolation: Option<Interpolation>) {
+        self.context.bind_texture(consts::TEXTURE_2D, &self.id);
+        let (mode, filter) = match interpolation {
+            Some(interpolation) => (
+                consts::COMPARE_REF_TO_TEXTURE as i32,
+                interpolation_from(interpolation),
+            ),
+            None => (
+                consts::NONE as i32,
+                interpolation_from(Interpolation::Nearest),
+            ),
+        };
+        self.context
+            .tex_parameteri(consts::TEXTURE_2D, consts::TEXTURE_COMPARE_MODE, mode);
+        self.context.tex_parameteri(
+            consts::TEXTURE_2D,
+            consts::TEXTURE_COMPARE_FUNC,
+            consts::LEQUAL as i32,
+        );
+        self.context
+            .tex_parameteri(consts::TEXTURE_2D, consts::TEXTURE_MIN_FILTER, filter);
+        self.context
+            .tex_parameteri(consts::TEXTURE_2D, consts::TEXTURE_MAG_FILTER, filter);
+    }
+
     pub(in crate::core) fn bind_as_depth_target(&self) {
         self.context.framebuffer_texture_2d(
             consts::FRAMEBUFFER,
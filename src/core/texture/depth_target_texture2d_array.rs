@@ -11,6 +11,7 @@ pub struct DepthTargetTexture2DArray {
     width: u32,
     height: u32,
     depth: u32,
+    format: DepthFormat,
 }
 
 impl DepthTargetTexture2DArray {
@@ -26,6 +27,7 @@ impl DepthTargetTexture2DArray {
         wrap_t: Wrapping,
         format: DepthFormat,
     ) -> ThreeDResult<Self> {
+        check_array_size(context, width, height, depth)?;
         let id = generate(context)?;
         set_parameters(
             context,
@@ -53,9 +55,17 @@ impl DepthTargetTexture2DArray {
             width,
             height,
             depth,
+            format,
         })
     }
 
+    ///
+    /// Returns the format of the depth values stored in this texture.
+    ///
+    pub fn depth_format(&self) -> DepthFormat {
+        self.format
+    }
+
     ///
     /// Writes the depth of whatever rendered in the `render` closure into the depth texture defined by the input parameter `depth_layer`.
     /// Before writing, the texture is cleared based on the given clear state.
@@ -25,6 +25,7 @@ impl DepthTargetTextureCubeMap {
         wrap_r: Wrapping,
         format: DepthFormat,
     ) -> ThreeDResult<Self> {
+        check_cube_map_size(context, width, height)?;
         let id = generate(context)?;
         set_parameters(
             context,
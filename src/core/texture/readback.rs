@@ -0,0 +1,141 @@
+use crate::context::consts;
+use crate::core::*;
+
+///
+/// A pending read of the pixels of a texture, constructed using [Texture2D::read_async].
+/// The pixels are copied into a pixel buffer object on the GPU so that reading them does not stall the CPU,
+/// use [ReadbackHandle::try_get] to get the pixels when the GPU has finished.
+///
+pub struct ReadbackHandle<T: TextureDataType> {
+    context: Context,
+    width: u32,
+    height: u32,
+    format: Format,
+    buffer: crate::context::Buffer,
+    fence: FenceSync,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: TextureDataType> ReadbackHandle<T> {
+    ///
+    /// Starts reading the pixels inside the viewport of the framebuffer currently bound for reading.
+    ///
+    pub(in crate::core) fn new(
+        context: &Context,
+        viewport: Viewport,
+        format: Format,
+    ) -> ThreeDResult<Self> {
+        // On web, half floats are read as 32 bit floats, see TextureDataType::read
+        let value_size = if cfg!(target_arch = "wasm32") && T::bits_per_channel() == 16 {
+            std::mem::size_of::<f32>()
+        } else {
+            std::mem::size_of::<T>()
+        };
+        let buffer = context.create_buffer().ok_or(CoreError::BufferCreation)?;
+        context.bind_buffer(consts::PIXEL_PACK_BUFFER, &buffer);
+        context.buffer_data(
+            consts::PIXEL_PACK_BUFFER,
+            viewport.width * viewport.height * 4 * value_size as u32,
+            consts::STREAM_READ,
+        );
+        T::read_to_pixel_pack_buffer(context, viewport, Format::RGBA);
+        context.unbind_buffer(consts::PIXEL_PACK_BUFFER);
+        Ok(Self {
+            context: context.clone(),
+            width: viewport.width,
+            height: viewport.height,
+            format,
+            buffer,
+            fence: context.fence(),
+            _marker: std::marker::PhantomData,
+        })
+    }
+
+    ///
+    /// The width of the read pixels.
+    ///
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    ///
+    /// The height of the read pixels.
+    ///
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    ///
+    /// Returns whether or not the GPU has finished reading the pixels, ie. whether [ReadbackHandle::try_get] returns the pixels.
+    ///
+    pub fn is_ready(&self) -> bool {
+        self.fence.is_signaled()
+    }
+
+    ///
+    /// Returns the pixels in the same layout as [Texture2D::read], ie. with the bottom row first,
+    /// or `None` if the GPU has not finished reading the pixels yet.
+    ///
+    pub fn try_get(&self) -> Option<Vec<T>> {
+        if !self.is_ready() {
+            return None;
+        }
+        let mut pixels = vec![T::default(); self.width as usize * self.height as usize * 4];
+        self.context
+            .bind_buffer(consts::PIXEL_PACK_BUFFER, &self.buffer);
+        T::read_from_pixel_pack_buffer(&self.context, &mut pixels);
+        self.context.unbind_buffer(consts::PIXEL_PACK_BUFFER);
+        Some(rgba_to_format(pixels, self.format))
+    }
+
+    ///
+    /// Returns the pixels in the same layout as [Texture2D::read_flipped], ie. with the top row first,
+    /// or `None` if the GPU has not finished reading the pixels yet.
+    ///
+    pub fn try_get_flipped(&self) -> Option<Vec<T>> {
+        self.try_get().map(|pixels| {
+            flip_rows(
+                pixels,
+                self.width as usize * self.format.color_channel_count() as usize,
+            )
+        })
+    }
+}
+
+impl<T: TextureDataType> Drop for ReadbackHandle<T> {
+    fn drop(&mut self) {
+        self.context.delete_buffer(&self.buffer);
+    }
+}
+
+///
+/// Removes the channels not in the given format from the given RGBA pixels.
+///
+pub(in crate::core) fn rgba_to_format<T: TextureDataType>(
+    pixels: Vec<T>,
+    format: Format,
+) -> Vec<T> {
+    let channel_count = format.color_channel_count() as usize;
+    if channel_count == 4 {
+        pixels
+    } else {
+        pixels
+            .chunks(4)
+            .flat_map(|pixel| pixel[..channel_count].to_vec())
+            .collect()
+    }
+}
+
+///
+/// Reverses the order of the rows of the given pixels.
+///
+pub(in crate::core) fn flip_rows<T: TextureDataType>(pixels: Vec<T>, row_length: usize) -> Vec<T> {
+    if row_length == 0 {
+        return pixels;
+    }
+    pixels
+        .chunks(row_length)
+        .rev()
+        .flat_map(|row| row.to_vec())
+        .collect()
+}
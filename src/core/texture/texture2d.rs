@@ -1,4 +1,5 @@
 use crate::core::texture::*;
+use std::time::Duration;
 
 ///
 /// A 2D texture, basically an image that is transferred to the GPU.
@@ -10,7 +11,12 @@ pub struct Texture2D<T: TextureDataType> {
     height: u32,
     format: Format,
     number_of_mip_maps: u32,
+    min_filter: Interpolation,
+    mag_filter: Interpolation,
+    mip_map_filter: Option<Interpolation>,
     transparent: bool,
+    #[cfg(debug_assertions)]
+    written: std::cell::Cell<Option<SyncPoint>>,
     _dummy: T,
 }
 
@@ -30,10 +36,51 @@ impl<T: TextureDataType> Texture2D<T> {
             cpu_texture.wrap_t,
             cpu_texture.format,
         )?;
+        if let Some(anisotropy) = cpu_texture.anisotropy {
+            texture.set_anisotropy(anisotropy);
+        }
         texture.fill(&cpu_texture.data)?;
         Ok(texture)
     }
 
+    ///
+    /// Starts an incremental upload of the given data to a new texture, see [TextureUpload].
+    /// Use this instead of [Texture2D::new] when the texture is so large that uploading it at once causes a visible hitch.
+    ///
+    pub fn new_incremental(
+        context: &Context,
+        cpu_texture: CPUTexture<T>,
+    ) -> ThreeDResult<TextureUpload<T>> {
+        check_data_length(
+            cpu_texture.width,
+            cpu_texture.height,
+            1,
+            cpu_texture.format,
+            cpu_texture.data.len(),
+        )?;
+        let mut texture = Self::new_empty(
+            context,
+            cpu_texture.width,
+            cpu_texture.height,
+            cpu_texture.min_filter,
+            cpu_texture.mag_filter,
+            cpu_texture.mip_map_filter,
+            cpu_texture.wrap_s,
+            cpu_texture.wrap_t,
+            cpu_texture.format,
+        )?;
+        if let Some(anisotropy) = cpu_texture.anisotropy {
+            texture.set_anisotropy(anisotropy);
+        }
+        Ok(TextureUpload {
+            texture: Some(texture),
+            data: cpu_texture.data,
+            next_row: 0,
+            rows_per_chunk: 8,
+            fence: None,
+        })
+    }
+
     ///
     /// Constructs a new empty 2D texture.
     ///
@@ -48,19 +95,21 @@ impl<T: TextureDataType> Texture2D<T> {
         wrap_t: Wrapping,
         format: Format,
     ) -> ThreeDResult<Self> {
+        check_size(context, width, height)?;
         let id = generate(context)?;
         let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height);
+        let mip_map_filter = if number_of_mip_maps == 1 {
+            None
+        } else {
+            mip_map_filter
+        };
         set_parameters(
             context,
             &id,
             consts::TEXTURE_2D,
             min_filter,
             mag_filter,
-            if number_of_mip_maps == 1 {
-                None
-            } else {
-                mip_map_filter
-            },
+            mip_map_filter,
             wrap_s,
             wrap_t,
             None,
@@ -78,11 +127,17 @@ impl<T: TextureDataType> Texture2D<T> {
             width,
             height,
             number_of_mip_maps,
+            min_filter,
+            mag_filter,
+            mip_map_filter,
             format,
             transparent: format == Format::RGBA,
+            #[cfg(debug_assertions)]
+            written: std::cell::Cell::new(None),
             _dummy: T::default(),
         };
         texture.generate_mip_maps();
+        context.check_errors("Texture2D::new_empty")?;
         Ok(texture)
     }
 
@@ -94,18 +149,7 @@ impl<T: TextureDataType> Texture2D<T> {
     ///
     pub fn fill(&mut self, data: &[T]) -> ThreeDResult<()> {
         check_data_length(self.width, self.height, 1, self.format, data.len())?;
-        self.transparent = if self.format == Format::RGBA {
-            let mut transparent = false;
-            for i in 0..self.width as usize * self.height as usize {
-                if !T::is_max(data[i * 4 + 3]) {
-                    transparent = true;
-                    break;
-                }
-            }
-            transparent
-        } else {
-            false
-        };
+        self.transparent = is_transparent(self.format, data);
         self.context.bind_texture(consts::TEXTURE_2D, &self.id);
         T::fill(
             &self.context,
@@ -116,7 +160,60 @@ impl<T: TextureDataType> Texture2D<T> {
             data,
         );
         self.generate_mip_maps();
-        Ok(())
+        self.context.check_errors("Texture2D::fill")
+    }
+
+    ///
+    /// Sets the interpolation used when sampling the texture at a higher (`min_filter`) or lower (`mag_filter`) resolution than the texture.
+    /// The mip map filter is unchanged, see [Texture2D::set_mip_map_filter].
+    ///
+    pub fn set_filtering(&mut self, min_filter: Interpolation, mag_filter: Interpolation) {
+        self.min_filter = min_filter;
+        self.mag_filter = mag_filter;
+        self.update_filtering();
+    }
+
+    ///
+    /// Sets the interpolation between the two closest mip maps or `None` to only sample the full resolution texture.
+    /// The mip maps are kept up to date when they are not used, so they can be enabled again at any time.
+    /// Does nothing if the texture was constructed without mip maps, since they cannot be added after construction.
+    ///
+    pub fn set_mip_map_filter(&mut self, mip_map_filter: Option<Interpolation>) {
+        if self.number_of_mip_maps > 1 {
+            self.mip_map_filter = mip_map_filter;
+            self.update_filtering();
+        }
+    }
+
+    ///
+    /// Sets how the texture is sampled outside the `[0..1]` range of the texture coordinates.
+    ///
+    pub fn set_wrapping(&mut self, wrap_s: Wrapping, wrap_t: Wrapping) {
+        self.context.bind_texture(consts::TEXTURE_2D, &self.id);
+        set_wrapping_parameters(&self.context, consts::TEXTURE_2D, wrap_s, wrap_t, None);
+    }
+
+    ///
+    /// Sets the number of samples used for anisotropic filtering, which reduces the blurring of textures viewed at a grazing angle, for example a floor.
+    /// The number of samples is clamped to [Capabilities::max_anisotropy] and a value of 1 disables anisotropic filtering.
+    /// Anisotropic filtering works best in combination with mip maps.
+    ///
+    /// **Note:** Does nothing except logging a warning if anisotropic filtering is not supported, ie. if [Capabilities::max_anisotropy] is `None`.
+    ///
+    pub fn set_anisotropy(&mut self, samples: u32) {
+        self.context.bind_texture(consts::TEXTURE_2D, &self.id);
+        set_anisotropy_parameter(&self.context, consts::TEXTURE_2D, samples);
+    }
+
+    fn update_filtering(&self) {
+        self.context.bind_texture(consts::TEXTURE_2D, &self.id);
+        set_filtering_parameters(
+            &self.context,
+            consts::TEXTURE_2D,
+            self.min_filter,
+            self.mag_filter,
+            self.mip_map_filter,
+        );
     }
 
     ///
@@ -157,16 +254,82 @@ impl<T: TextureDataType> Texture2D<T> {
     }
 
     ///
-    /// Returns the color values of the pixels in this color texture inside the given viewport.
+    /// Returns the color values of the pixels in this texture inside the given viewport.
+    /// The pixels are ordered row by row starting with the bottom row, and each pixel has as many values as the [Format] of this texture has channels.
+    /// Use [Texture2D::read_flipped] to get the pixels starting with the top row, which is the order most image formats expects.
     ///
-    /// **Note:** Only works for the RGBA format.
+    /// **Note:** This blocks until the GPU has finished all rendering into this texture, so reading a texture in the same frame as it is written stalls the CPU.
+    /// Use [Texture2D::read_async] instead, or only read the texture when a fence inserted after the write is signaled, see [Context::fence].
+    /// In debug builds, reading a texture inside a render loop in the same frame as it is written, without inserting a fence or calling [Context::finish] in between, panics.
     ///
     /// # Errors
-    /// Will return an error if the color texture is not RGBA format.
+    /// Will return an error if the values cannot be read on the current context,
+    /// for example `f16` and `f32` values on web if the `EXT_color_buffer_float` extension is not supported, see [Capabilities::color_buffer_float].
+    /// Integer textures, ie. `u16` and `u32` values, cannot be read.
     ///
     pub fn read(&self, viewport: Viewport) -> ThreeDResult<Vec<T>> {
-        if self.format != Format::RGBA {
-            Err(CoreError::ReadWrongFormat)?;
+        #[cfg(debug_assertions)]
+        if let Some(written) = self.written.get() {
+            debug_assert!(
+                !written.is_unsynchronized(self.context.sync_point()),
+                "The texture is read in the same frame as it is written without a fence, which stalls the CPU until the GPU has finished the write. Use Texture2D::read_async or Context::fence instead."
+            );
+        }
+        self.read_internal(viewport)
+    }
+
+    // Reads the pixels like read without checking whether the read stalls the CPU, used when stalling is expected, for example when picking
+    pub(crate) fn read_internal(&self, viewport: Viewport) -> ThreeDResult<Vec<T>> {
+        let id = self.bind_for_reading()?;
+        // Always read RGBA, since that is guaranteed to be supported for any format and it avoids any row alignment padding
+        let mut pixels = vec![T::default(); viewport.width as usize * viewport.height as usize * 4];
+        T::read(&self.context, viewport, Format::RGBA, &mut pixels);
+        self.context.delete_framebuffer(Some(&id));
+        Ok(super::readback::rgba_to_format(pixels, self.format))
+    }
+
+    ///
+    /// Returns the color values of the pixels in this texture inside the given viewport like [Texture2D::read],
+    /// except that the pixels are ordered starting with the top row.
+    ///
+    /// # Errors
+    /// See [Texture2D::read].
+    ///
+    pub fn read_flipped(&self, viewport: Viewport) -> ThreeDResult<Vec<T>> {
+        Ok(super::readback::flip_rows(
+            self.read(viewport)?,
+            viewport.width as usize * self.format.color_channel_count() as usize,
+        ))
+    }
+
+    // Reads the pixels like read_flipped without checking whether the read stalls the CPU, see read_internal
+    pub(crate) fn read_flipped_internal(&self, viewport: Viewport) -> ThreeDResult<Vec<T>> {
+        Ok(super::readback::flip_rows(
+            self.read_internal(viewport)?,
+            viewport.width as usize * self.format.color_channel_count() as usize,
+        ))
+    }
+
+    ///
+    /// Starts reading the color values of the pixels in this texture inside the given viewport without waiting for the GPU to finish rendering.
+    /// Use [ReadbackHandle::try_get] on the returned handle to get the pixels when they are ready,
+    /// for example in the next frame, which avoids stalling the CPU as [Texture2D::read] does.
+    ///
+    /// # Errors
+    /// See [Texture2D::read].
+    ///
+    pub fn read_async(&self, viewport: Viewport) -> ThreeDResult<ReadbackHandle<T>> {
+        let id = self.bind_for_reading()?;
+        let handle = ReadbackHandle::new(&self.context, viewport, self.format);
+        self.context.delete_framebuffer(Some(&id));
+        handle
+    }
+
+    fn bind_for_reading(&self) -> ThreeDResult<crate::context::Framebuffer> {
+        if !T::is_readable(self.context.capabilities()) {
+            Err(CoreError::ReadUnsupportedDataType(
+                std::any::type_name::<T>().to_string(),
+            ))?;
         }
         let id = crate::core::render_target::new_framebuffer(&self.context)?;
 
@@ -182,15 +345,22 @@ impl<T: TextureDataType> Texture2D<T> {
 
         #[cfg(feature = "debug")]
         check(&self.context)?;
+        Ok(id)
+    }
 
-        let mut pixels = vec![
-            T::default();
-            viewport.width as usize
-                * viewport.height as usize
-                * self.format.color_channel_count() as usize
-        ];
-        T::read(&self.context, viewport, self.format, &mut pixels);
-        Ok(pixels)
+    pub(crate) fn fill_sub_image_internal(&self, viewport: Viewport, data: &[T]) {
+        self.context.bind_texture(consts::TEXTURE_2D, &self.id);
+        T::fill_sub_image(
+            &self.context,
+            consts::TEXTURE_2D,
+            viewport.x as u32,
+            viewport.y as u32,
+            viewport.width,
+            viewport.height,
+            self.format,
+            data,
+        );
+        self.generate_mip_maps();
     }
 
     pub(crate) fn generate_mip_maps(&self) {
@@ -200,6 +370,12 @@ impl<T: TextureDataType> Texture2D<T> {
         }
     }
 
+    // Records that this texture is written in the current frame, see SyncPoint
+    pub(in crate::core) fn mark_written(&self) {
+        #[cfg(debug_assertions)]
+        self.written.set(Some(self.context.sync_point()));
+    }
+
     pub(in crate::core) fn bind_as_color_target(&self, channel: u32) {
         self.context.framebuffer_texture_2d(
             consts::FRAMEBUFFER,
@@ -235,6 +411,80 @@ impl<T: TextureDataType> Drop for Texture2D<T> {
     }
 }
 
+///
+/// An incremental upload of the data to a [Texture2D], constructed using [Texture2D::new_incremental].
+/// Each call to [Upload::progress] uploads a range of rows of the texture until all of the data is uploaded.
+/// The texture is only returned when it is completely uploaded, so a partially uploaded texture can never be used.
+/// A fence is inserted after each call, see [FenceSync], and the next chunk is not uploaded before the GPU has finished the previous chunk,
+/// so the upload never stalls the CPU. The texture is therefore returned by a call after the call uploading the last chunk.
+///
+pub struct TextureUpload<T: TextureDataType> {
+    texture: Option<Texture2D<T>>,
+    data: Vec<T>,
+    next_row: u32,
+    rows_per_chunk: u32,
+    fence: Option<FenceSync>,
+}
+
+impl<T: TextureDataType> Upload for TextureUpload<T> {
+    type Output = Texture2D<T>;
+
+    fn progress(&mut self, time_budget: Duration) -> ThreeDResult<UploadStatus<Texture2D<T>>> {
+        let timer = Timer::start();
+        let texture = self.texture.as_mut().ok_or(CoreError::UploadAlreadyDone)?;
+        let progress = self.next_row as f32 / texture.height as f32;
+        if let Some(ref fence) = self.fence {
+            if !fence.is_signaled() {
+                return Ok(UploadStatus::InProgress(progress));
+            }
+            self.fence = None;
+        }
+        if self.next_row >= texture.height {
+            self.data = Vec::new();
+            return Ok(UploadStatus::Done(self.texture.take().unwrap()));
+        }
+
+        let row_length = texture.width as usize * texture.format.color_channel_count() as usize;
+        while self.next_row < texture.height {
+            let chunk_timer = Timer::start();
+            let rows = self.rows_per_chunk.min(texture.height - self.next_row);
+            let start = self.next_row as usize * row_length;
+            let end = start + rows as usize * row_length;
+            texture
+                .context
+                .bind_texture(consts::TEXTURE_2D, &texture.id);
+            T::fill_sub_image(
+                &texture.context,
+                consts::TEXTURE_2D,
+                0,
+                self.next_row,
+                texture.width,
+                rows,
+                texture.format,
+                &self.data[start..end],
+            );
+            self.next_row += rows;
+            self.rows_per_chunk =
+                next_chunk_size(rows as usize, chunk_timer.elapsed(), time_budget) as u32;
+            if timer.elapsed() + chunk_timer.elapsed() >= time_budget {
+                break;
+            }
+        }
+        if self.next_row >= texture.height {
+            texture.transparent = is_transparent(texture.format, &self.data);
+            texture.generate_mip_maps();
+        }
+        self.fence = Some(texture.context.fence());
+        Ok(UploadStatus::InProgress(
+            self.next_row as f32 / texture.height as f32,
+        ))
+    }
+}
+
+fn is_transparent<T: TextureDataType>(format: Format, data: &[T]) -> bool {
+    format == Format::RGBA && data.chunks(4).any(|pixel| !T::is_max(pixel[3]))
+}
+
 ///
 /// A 2D color texture that can be rendered into and read from.
 ///
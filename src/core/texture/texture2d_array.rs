@@ -29,40 +29,29 @@ pub struct Texture2DArray<T: TextureDataType> {
 
 impl<T: TextureDataType> Texture2DArray<T> {
     ///
-    /// Creates a new array of 2D textures.
+    /// Constructs a new array of 2D textures with the given data, one layer per element in [CPUTexture2DArray::layers].
     ///
-    pub fn new_empty(
-        context: &Context,
-        width: u32,
-        height: u32,
-        depth: u32,
-        min_filter: Interpolation,
-        mag_filter: Interpolation,
-        mip_map_filter: Option<Interpolation>,
-        wrap_s: Wrapping,
-        wrap_t: Wrapping,
-        format: Format,
-    ) -> ThreeDResult<Self> {
-        #[allow(deprecated)]
-        Self::new(
+    pub fn new(context: &Context, cpu_texture: &CPUTexture2DArray<T>) -> ThreeDResult<Self> {
+        let mut texture = Self::new_empty(
             context,
-            width,
-            height,
-            depth,
-            min_filter,
-            mag_filter,
-            mip_map_filter,
-            wrap_s,
-            wrap_t,
-            format,
-        )
+            cpu_texture.width,
+            cpu_texture.height,
+            cpu_texture.layers.len() as u32,
+            cpu_texture.min_filter,
+            cpu_texture.mag_filter,
+            cpu_texture.mip_map_filter,
+            cpu_texture.wrap_s,
+            cpu_texture.wrap_t,
+            cpu_texture.format,
+        )?;
+        texture.fill(&cpu_texture.layers.concat())?;
+        Ok(texture)
     }
 
     ///
     /// Creates a new array of 2D textures.
     ///
-    #[deprecated = "Use new_empty instead"]
-    pub fn new(
+    pub fn new_empty(
         context: &Context,
         width: u32,
         height: u32,
@@ -74,6 +63,7 @@ impl<T: TextureDataType> Texture2DArray<T> {
         wrap_t: Wrapping,
         format: Format,
     ) -> ThreeDResult<Self> {
+        check_array_size(context, width, height, depth)?;
         let id = generate(context)?;
         let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height);
         set_parameters(
@@ -112,6 +102,29 @@ impl<T: TextureDataType> Texture2DArray<T> {
         })
     }
 
+    ///
+    /// Fills all layers of the texture array with the given data, which must contain the data of the layers one after the other.
+    ///
+    /// # Errors
+    /// Returns an error if the length of the data does not correspond to the width, height, number of layers and format specified at construction.
+    ///
+    pub fn fill(&mut self, data: &[T]) -> ThreeDResult<()> {
+        check_data_length(self.width, self.height, self.depth, self.format, data.len())?;
+        self.context
+            .bind_texture(consts::TEXTURE_2D_ARRAY, &self.id);
+        T::fill_layers(
+            &self.context,
+            consts::TEXTURE_2D_ARRAY,
+            self.width,
+            self.height,
+            self.depth,
+            self.format,
+            data,
+        );
+        self.generate_mip_maps();
+        Ok(())
+    }
+
     ///
     /// Renders whatever rendered in the `render` closure into the textures defined by the input parameters `color_layers`.
     /// Output at location *i* defined in the fragment shader is written to the color texture layer at the *ith* index in `color_layers`.
@@ -0,0 +1,138 @@
+use crate::core::texture::*;
+use crate::core::*;
+
+///
+/// A 3D color texture, ie. a volume of texels which are interpolated in all three dimensions when sampled in a shader,
+/// for example a [signed distance field](crate::CPUMesh::compute_sdf).
+/// Can be sampled in a fragment shader using [Program::use_texture_3d].
+///
+pub struct Texture3D<T: TextureDataType> {
+    context: Context,
+    id: crate::context::Texture,
+    width: u32,
+    height: u32,
+    depth: u32,
+    format: Format,
+    _dummy: T,
+}
+
+impl<T: TextureDataType> Texture3D<T> {
+    ///
+    /// Constructs a new 3D texture with the given data.
+    ///
+    pub fn new(context: &Context, cpu_texture: &CPUTexture3D<T>) -> ThreeDResult<Self> {
+        let mut texture = Self::new_empty(
+            context,
+            cpu_texture.width,
+            cpu_texture.height,
+            cpu_texture.depth,
+            cpu_texture.min_filter,
+            cpu_texture.mag_filter,
+            cpu_texture.wrap_s,
+            cpu_texture.wrap_t,
+            cpu_texture.wrap_r,
+            cpu_texture.format,
+        )?;
+        texture.fill(&cpu_texture.data)?;
+        Ok(texture)
+    }
+
+    ///
+    /// Creates a new empty 3D texture.
+    ///
+    pub fn new_empty(
+        context: &Context,
+        width: u32,
+        height: u32,
+        depth: u32,
+        min_filter: Interpolation,
+        mag_filter: Interpolation,
+        wrap_s: Wrapping,
+        wrap_t: Wrapping,
+        wrap_r: Wrapping,
+        format: Format,
+    ) -> ThreeDResult<Self> {
+        check_3d_size(context, width, height, depth)?;
+        let id = generate(context)?;
+        set_parameters(
+            context,
+            &id,
+            consts::TEXTURE_3D,
+            min_filter,
+            mag_filter,
+            None,
+            wrap_s,
+            wrap_t,
+            Some(wrap_r),
+        );
+        context.bind_texture(consts::TEXTURE_3D, &id);
+        context.tex_storage_3d(
+            consts::TEXTURE_3D,
+            1,
+            T::internal_format(format)?,
+            width,
+            height,
+            depth,
+        );
+        Ok(Self {
+            context: context.clone(),
+            id,
+            width,
+            height,
+            depth,
+            format,
+            _dummy: T::default(),
+        })
+    }
+
+    ///
+    /// Fills the texture with the given data, where the texels are ordered by x, then y and then z.
+    ///
+    /// # Errors
+    /// Returns an error if the length of the data does not correspond to the width, height, depth and format specified at construction.
+    ///
+    pub fn fill(&mut self, data: &[T]) -> ThreeDResult<()> {
+        check_data_length(self.width, self.height, self.depth, self.format, data.len())?;
+        self.context.bind_texture(consts::TEXTURE_3D, &self.id);
+        T::fill_layers(
+            &self.context,
+            consts::TEXTURE_3D,
+            self.width,
+            self.height,
+            self.depth,
+            self.format,
+            data,
+        );
+        Ok(())
+    }
+
+    /// The width of this texture.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of this texture.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The depth of this texture.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// The format of this texture.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub(in crate::core) fn bind(&self, location: u32) {
+        bind_at(&self.context, &self.id, consts::TEXTURE_3D, location);
+    }
+}
+
+impl<T: TextureDataType> Drop for Texture3D<T> {
+    fn drop(&mut self) {
+        self.context.delete_texture(&self.id);
+    }
+}
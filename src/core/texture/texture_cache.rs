@@ -0,0 +1,373 @@
+use crate::core::texture::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+///
+/// Where a [TextureCache] gets the pixels from when a texture is uploaded, either the first time or after it has been evicted.
+///
+pub enum TextureSource<T: TextureDataType> {
+    /// The pixels are kept in CPU memory, which makes re-uploading fast but keeps a copy of every texture in CPU memory.
+    CPUTexture(CPUTexture<T>),
+    /// The pixels are recreated by calling the closure each time the texture is uploaded, for example by reading and decoding an image file.
+    Recreate(Box<dyn Fn() -> ThreeDResult<CPUTexture<T>>>),
+}
+
+impl<T: TextureDataType> TextureSource<T> {
+    ///
+    /// Constructs a source which recreates the pixels by calling the given closure.
+    ///
+    pub fn recreate(recreate: impl Fn() -> ThreeDResult<CPUTexture<T>> + 'static) -> Self {
+        Self::Recreate(Box::new(recreate))
+    }
+
+    fn cpu_texture(&self) -> ThreeDResult<std::borrow::Cow<'_, CPUTexture<T>>> {
+        Ok(match self {
+            Self::CPUTexture(cpu_texture) => std::borrow::Cow::Borrowed(cpu_texture),
+            Self::Recreate(recreate) => std::borrow::Cow::Owned(recreate()?),
+        })
+    }
+}
+
+///
+/// Statistics of a [TextureCache], see [TextureCache::stats] and [TextureCache::new_frame].
+/// The number of hits, misses, evictions and downgrades are counted since the last call to [TextureCache::new_frame],
+/// while the resident bytes and textures are the current values.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextureCacheStats {
+    /// The number of calls to [TextureCache::get] where the texture was already resident on the GPU.
+    pub hits: u32,
+    /// The number of calls to [TextureCache::get] where the texture had to be uploaded again.
+    pub misses: u32,
+    /// The number of textures deleted from the GPU to stay within the budget.
+    pub evictions: u32,
+    /// The number of textures replaced by a texture with half the width and height to stay within the budget, see [TextureCache::downgrade_before_evict].
+    pub downgrades: u32,
+    /// The number of bytes of GPU memory used by the resident textures, including mip maps.
+    pub resident_bytes: usize,
+    /// The number of textures resident on the GPU.
+    pub resident_textures: usize,
+}
+
+struct TextureCacheEntry<T: TextureDataType> {
+    source: TextureSource<T>,
+    texture: Option<Rc<Texture2D<T>>>,
+    byte_size: usize,
+    full_byte_size: usize,
+    downgraded: bool,
+    pinned: bool,
+    last_use: u64,
+}
+
+///
+/// Keeps a set of [Texture2D]s on the GPU within a budget of GPU memory.
+/// Each texture is registered with a key and a [TextureSource], and when uploading a texture would exceed the budget,
+/// the least recently used textures that are not pinned are deleted from the GPU until it fits.
+/// A later call to [TextureCache::get] with the key of an evicted texture transparently uploads it again.
+///
+/// **Note:** [TextureCache::get] returns a reference counted texture and the GPU memory of an evicted texture is not freed until all clones are dropped,
+/// so get the texture from the cache each frame instead of storing it, for example in a material.
+///
+pub struct TextureCache<K: Hash + Eq + Clone + std::fmt::Debug, T: TextureDataType> {
+    context: Context,
+    budget: usize,
+    ///
+    /// Whether to replace the least recently used texture with a texture with half the width and height, ie. drop the top mip level,
+    /// before evicting it completely. The full resolution texture is uploaded again in [TextureCache::get] when it fits within the budget.
+    ///
+    pub downgrade_before_evict: bool,
+    entries: HashMap<K, TextureCacheEntry<T>>,
+    use_counter: u64,
+    stats: TextureCacheStats,
+}
+
+impl<K: Hash + Eq + Clone + std::fmt::Debug, T: TextureDataType> TextureCache<K, T> {
+    ///
+    /// Constructs a new empty cache which keeps the textures within the given number of bytes of GPU memory.
+    ///
+    pub fn new(context: &Context, budget: usize) -> Self {
+        Self {
+            context: context.clone(),
+            budget,
+            downgrade_before_evict: false,
+            entries: HashMap::new(),
+            use_counter: 0,
+            stats: TextureCacheStats::default(),
+        }
+    }
+
+    ///
+    /// Returns the number of bytes of GPU memory the textures are kept within.
+    ///
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    ///
+    /// Sets the number of bytes of GPU memory the textures are kept within and evicts textures until the resident textures fit.
+    ///
+    /// # Errors
+    /// Will return an error if the pinned textures do not fit within the new budget.
+    ///
+    pub fn set_budget(&mut self, budget: usize) -> ThreeDResult<()> {
+        self.budget = budget;
+        self.make_room(0)
+    }
+
+    ///
+    /// Registers a texture with the given key and uploads it to the GPU, evicting the least recently used textures if necessary.
+    /// An existing texture with the same key is replaced.
+    ///
+    /// # Errors
+    /// Will return an error if the texture does not fit within the budget even when all textures that are not pinned are evicted.
+    ///
+    pub fn insert(&mut self, key: K, source: TextureSource<T>) -> ThreeDResult<()> {
+        self.insert_internal(key, source, false)
+    }
+
+    ///
+    /// Registers a texture like [TextureCache::insert], except that the texture is pinned which means it is never evicted,
+    /// for example the font texture of the GUI or an environment map, see [TextureCache::set_pinned].
+    ///
+    /// # Errors
+    /// See [TextureCache::insert].
+    ///
+    pub fn insert_pinned(&mut self, key: K, source: TextureSource<T>) -> ThreeDResult<()> {
+        self.insert_internal(key, source, true)
+    }
+
+    fn insert_internal(
+        &mut self,
+        key: K,
+        source: TextureSource<T>,
+        pinned: bool,
+    ) -> ThreeDResult<()> {
+        self.remove(&key);
+        let cpu_texture = source.cpu_texture()?;
+        let byte_size = byte_size(&cpu_texture);
+        self.make_room(byte_size)?;
+        let texture = Rc::new(Texture2D::new(&self.context, &cpu_texture)?);
+        drop(cpu_texture);
+        self.use_counter += 1;
+        self.entries.insert(
+            key,
+            TextureCacheEntry {
+                source,
+                texture: Some(texture),
+                byte_size,
+                full_byte_size: byte_size,
+                downgraded: false,
+                pinned,
+                last_use: self.use_counter,
+            },
+        );
+        self.stats.resident_bytes += byte_size;
+        self.stats.resident_textures += 1;
+        Ok(())
+    }
+
+    ///
+    /// Returns the texture with the given key and marks it as the most recently used texture.
+    /// If the texture has been evicted, it is uploaded again, evicting the least recently used textures if necessary.
+    ///
+    /// # Errors
+    /// Will return an error if no texture is registered with the given key or if the texture does not fit within the budget.
+    ///
+    pub fn get(&mut self, key: &K) -> ThreeDResult<Rc<Texture2D<T>>> {
+        self.use_counter += 1;
+        let use_counter = self.use_counter;
+        let entry = self
+            .entries
+            .get_mut(key)
+            .ok_or_else(|| CoreError::TextureCacheMissingKey(format!("{:?}", key)))?;
+        entry.last_use = use_counter;
+        if entry.texture.is_some() {
+            self.stats.hits += 1;
+            // Restore the full resolution of a downgraded texture if it fits without evicting other textures
+            if entry.downgraded
+                && self.stats.resident_bytes - entry.byte_size + entry.full_byte_size <= self.budget
+            {
+                self.upload(key, false)?;
+            }
+        } else {
+            self.stats.misses += 1;
+            let full_byte_size = entry.full_byte_size;
+            self.make_room(full_byte_size)?;
+            self.upload(key, false)?;
+        }
+        Ok(self.entries[key].texture.clone().unwrap())
+    }
+
+    ///
+    /// Returns whether a texture is registered with the given key and currently resident on the GPU.
+    ///
+    pub fn is_resident(&self, key: &K) -> bool {
+        self.entries
+            .get(key)
+            .map_or(false, |entry| entry.texture.is_some())
+    }
+
+    ///
+    /// Returns whether a texture is registered with the given key, regardless of whether it is resident on the GPU.
+    ///
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    ///
+    /// Pins or unpins the texture with the given key. A pinned texture is never evicted or downgraded.
+    /// If the texture is evicted when it is pinned, it is uploaded the next time it is used.
+    ///
+    pub fn set_pinned(&mut self, key: &K, pinned: bool) {
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.pinned = pinned;
+        }
+    }
+
+    ///
+    /// Removes the texture with the given key from the cache and deletes it from the GPU.
+    ///
+    pub fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.remove(key) {
+            if entry.texture.is_some() {
+                self.stats.resident_bytes -= entry.byte_size;
+                self.stats.resident_textures -= 1;
+            }
+        }
+    }
+
+    ///
+    /// Returns the number of bytes of GPU memory used by the resident textures, including mip maps.
+    ///
+    pub fn resident_bytes(&self) -> usize {
+        self.stats.resident_bytes
+    }
+
+    ///
+    /// Returns the statistics since the last call to [TextureCache::new_frame].
+    ///
+    pub fn stats(&self) -> TextureCacheStats {
+        self.stats
+    }
+
+    ///
+    /// Resets the number of hits, misses, evictions and downgrades and returns the statistics of the frame that just ended.
+    /// Call this once per frame, for example at the beginning of the render loop.
+    ///
+    pub fn new_frame(&mut self) -> TextureCacheStats {
+        let stats = self.stats;
+        self.stats.hits = 0;
+        self.stats.misses = 0;
+        self.stats.evictions = 0;
+        self.stats.downgrades = 0;
+        stats
+    }
+
+    fn upload(&mut self, key: &K, downgrade: bool) -> ThreeDResult<()> {
+        let entry = self.entries.get_mut(key).unwrap();
+        if entry.texture.take().is_some() {
+            self.stats.resident_bytes -= entry.byte_size;
+            self.stats.resident_textures -= 1;
+        }
+        let mut cpu_texture = entry.source.cpu_texture()?;
+        if downgrade {
+            cpu_texture = std::borrow::Cow::Owned(half_size(&cpu_texture));
+        }
+        entry.byte_size = byte_size(&cpu_texture);
+        entry.downgraded = downgrade;
+        entry.texture = Some(Rc::new(Texture2D::new(&self.context, &cpu_texture)?));
+        self.stats.resident_bytes += entry.byte_size;
+        self.stats.resident_textures += 1;
+        Ok(())
+    }
+
+    fn make_room(&mut self, byte_size: usize) -> ThreeDResult<()> {
+        while self.stats.resident_bytes + byte_size > self.budget {
+            let least_recently_used = self
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.texture.is_some() && !entry.pinned)
+                .min_by_key(|(_, entry)| entry.last_use)
+                .map(|(key, entry)| (key.clone(), entry.downgraded));
+            match least_recently_used {
+                Some((key, false)) if self.downgrade_before_evict && self.can_downgrade(&key) => {
+                    self.upload(&key, true)?;
+                    self.stats.downgrades += 1;
+                }
+                Some((key, _)) => {
+                    let entry = self.entries.get_mut(&key).unwrap();
+                    entry.texture = None;
+                    entry.downgraded = false;
+                    self.stats.resident_bytes -= entry.byte_size;
+                    self.stats.resident_textures -= 1;
+                    self.stats.evictions += 1;
+                }
+                None => Err(CoreError::TextureCacheBudgetExceeded(
+                    self.stats.resident_bytes + byte_size,
+                    self.budget,
+                ))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn can_downgrade(&self, key: &K) -> bool {
+        let entry = &self.entries[key];
+        entry
+            .texture
+            .as_ref()
+            .map_or(false, |texture| texture.width() > 1 || texture.height() > 1)
+    }
+}
+
+///
+/// Returns the number of bytes of GPU memory used by the texture created from the given CPU texture, including mip maps.
+///
+fn byte_size<T: TextureDataType>(cpu_texture: &CPUTexture<T>) -> usize {
+    let levels = calculate_number_of_mip_maps(
+        cpu_texture.mip_map_filter,
+        cpu_texture.width,
+        cpu_texture.height,
+    );
+    (0..levels)
+        .map(|level| {
+            let width = (cpu_texture.width >> level).max(1) as usize;
+            let height = (cpu_texture.height >> level).max(1) as usize;
+            width
+                * height
+                * cpu_texture.format.color_channel_count() as usize
+                * std::mem::size_of::<T>()
+        })
+        .sum()
+}
+
+///
+/// Returns a copy of the given texture with half the width and height, where each pixel is the lower left pixel of the corresponding 2x2 pixels.
+///
+fn half_size<T: TextureDataType>(cpu_texture: &CPUTexture<T>) -> CPUTexture<T> {
+    let channels = cpu_texture.format.color_channel_count() as usize;
+    let width = (cpu_texture.width / 2).max(1);
+    let height = (cpu_texture.height / 2).max(1);
+    let step_x = (cpu_texture.width / width) as usize;
+    let step_y = (cpu_texture.height / height) as usize;
+    let mut data = Vec::with_capacity(width as usize * height as usize * channels);
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let index = (y * step_y * cpu_texture.width as usize + x * step_x) * channels;
+            data.extend_from_slice(&cpu_texture.data[index..index + channels]);
+        }
+    }
+    CPUTexture {
+        data,
+        width,
+        height,
+        format: cpu_texture.format,
+        min_filter: cpu_texture.min_filter,
+        mag_filter: cpu_texture.mag_filter,
+        mip_map_filter: cpu_texture.mip_map_filter,
+        wrap_s: cpu_texture.wrap_s,
+        wrap_t: cpu_texture.wrap_t,
+        anisotropy: cpu_texture.anisotropy,
+    }
+}
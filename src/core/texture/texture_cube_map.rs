@@ -169,6 +169,7 @@ impl<T: TextureDataType> TextureCubeMap<T> {
         wrap_r: Wrapping,
         format: Format,
     ) -> ThreeDResult<Self> {
+        check_cube_map_size(context, width, height)?;
         let id = generate(context)?;
         let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height);
         set_parameters(
@@ -256,19 +257,39 @@ impl<T: TextureDataType> TextureCubeMap<T> {
 
     ///
     /// Creates a new cube texture generated from the equirectangular texture given as input.
+    /// The size of each side of the cube map is a quarter of the width of the equirectangular texture and mip maps are generated,
+    /// use [TextureCubeMap::new_from_equirectangular_with_resolution] to specify otherwise.
     ///
     pub fn new_from_equirectangular<U: TextureDataType>(
         context: &Context,
         cpu_texture: &CPUTexture<U>,
     ) -> ThreeDResult<Self> {
-        let texture_size = cpu_texture.width / 4;
+        Self::new_from_equirectangular_with_resolution(
+            context,
+            cpu_texture,
+            cpu_texture.width / 4,
+            Some(Interpolation::Linear),
+        )
+    }
+
+    ///
+    /// Creates a new cube texture generated from the equirectangular texture given as input,
+    /// where each side of the cube map has the width and height given by `resolution`.
+    /// Mip maps are generated if a mip map filter is specified.
+    ///
+    pub fn new_from_equirectangular_with_resolution<U: TextureDataType>(
+        context: &Context,
+        cpu_texture: &CPUTexture<U>,
+        resolution: u32,
+        mip_map_filter: Option<Interpolation>,
+    ) -> ThreeDResult<Self> {
         let mut texture = Self::new_empty(
             &context,
-            texture_size,
-            texture_size,
+            resolution,
+            resolution,
             Interpolation::Linear,
             Interpolation::Linear,
-            Some(Interpolation::Linear),
+            mip_map_filter,
             Wrapping::ClampToEdge,
             Wrapping::ClampToEdge,
             Wrapping::ClampToEdge,
@@ -276,37 +297,50 @@ impl<T: TextureDataType> TextureCubeMap<T> {
         )?;
 
         {
-            let map = Texture2D::new(context, cpu_texture)?;
+            // Repeat horizontally to avoid a seam where the longitude wraps around,
+            // and no mip maps since the texture coordinates are discontinuous at the same place
+            let mut map = Texture2D::new_empty(
+                context,
+                cpu_texture.width,
+                cpu_texture.height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::Repeat,
+                Wrapping::ClampToEdge,
+                cpu_texture.format,
+            )?;
+            map.fill(&cpu_texture.data)?;
             let fragment_shader_source = "uniform sampler2D equirectangularMap;
-            const vec2 invAtan = vec2(0.1591, 0.3183);
-            
+            const float PI = 3.14159265359;
+
             in vec3 pos;
             layout (location = 0) out vec4 outColor;
-            
+
             vec2 sample_spherical_map(vec3 v)
             {
-                vec2 uv = vec2(atan(v.z, v.x), asin(v.y));
-                uv *= invAtan;
+                vec2 uv = vec2(atan(v.z, v.x) / (2.0 * PI), asin(clamp(v.y, -1.0, 1.0)) / PI);
                 uv += 0.5;
                 return vec2(uv.x, 1.0 - uv.y);
             }
-            
+
             void main()
-            {		
+            {
                 vec2 uv = sample_spherical_map(normalize(pos));
-                outColor = vec4(texture(equirectangularMap, uv).rgb, 1.0);
+                outColor = vec4(textureLod(equirectangularMap, uv, 0.0).rgb, 1.0);
             }";
             let effect = ImageCubeEffect::new(context, fragment_shader_source)?;
             let render_target = RenderTargetCubeMap::new_color(context, &mut texture)?;
 
+            let viewport = Viewport::new_at_origo(resolution, resolution);
             for side in CubeMapSide::iter() {
                 effect.use_texture("equirectangularMap", &map)?;
-                let viewport = Viewport::new_at_origo(texture_size, texture_size);
-                render_target.write(side, ClearState::default(), || {
+                render_target.write_to_mip_level(side, 0, ClearState::default(), || {
                     effect.render(side, RenderStates::default(), viewport)
                 })?;
             }
         }
+        texture.generate_mip_maps();
         Ok(texture)
     }
 
@@ -346,7 +380,7 @@ impl<T: TextureDataType> TextureCubeMap<T> {
         )
     }
 
-    pub(in crate::core) fn generate_mip_maps(&self) {
+    pub(crate) fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.context
                 .bind_texture(consts::TEXTURE_CUBE_MAP, &self.id);
@@ -0,0 +1,151 @@
+use crate::core::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+///
+/// Provides uniform values from outside the renderer to the programs used by the materials and effects it is added to,
+/// for example the frequency bands of a music track, see [UniformProviders].
+/// Since the provider is shared through an [Rc], the values are usually stored in a [Cell] or [RefCell](std::cell::RefCell) so they can be updated each frame.
+///
+pub trait UniformProvider {
+    ///
+    /// Returns the declarations of the uniforms provided, for example `uniform float bands[16];`, which are added to the fragment shader source.
+    ///
+    fn fragment_shader_source(&self) -> String;
+
+    ///
+    /// Sends the uniform values to the given program. Called after the material or effect has sent its own uniforms.
+    /// Note that the shader compiler removes uniforms that are not used, so use [Program::requires_uniform] to skip those.
+    ///
+    fn provide(&self, program: &Program) -> ThreeDResult<()>;
+}
+
+///
+/// The uniform providers added to a material or effect, see for example [ImageEffect::add_provider].
+///
+#[derive(Clone, Default)]
+pub struct UniformProviders {
+    providers: Vec<Rc<dyn UniformProvider>>,
+}
+
+impl UniformProviders {
+    ///
+    /// Constructs an empty set of uniform providers.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Adds the given provider.
+    ///
+    pub fn add(&mut self, provider: Rc<dyn UniformProvider>) {
+        self.providers.push(provider);
+    }
+
+    ///
+    /// Returns whether or not any providers are added.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    ///
+    /// Returns the uniform declarations of all of the providers.
+    ///
+    pub fn fragment_shader_source(&self) -> String {
+        self.providers
+            .iter()
+            .map(|p| p.fragment_shader_source())
+            .collect()
+    }
+
+    ///
+    /// Sends the uniform values of all of the providers to the given program.
+    ///
+    pub fn provide(&self, program: &Program) -> ThreeDResult<()> {
+        for provider in self.providers.iter() {
+            provider.provide(program)?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Provides the time and resolution uniforms known from shader toys, so shader toy style effects can be used with an [ImageEffect] directly:
+/// - `uniform float iTime;` the time in seconds since the first update
+/// - `uniform float iTimeDelta;` the time in seconds since the previous update
+/// - `uniform int iFrame;` the number of updates before the current one
+/// - `uniform vec3 iResolution;` the width and height of the viewport in pixels and the pixel aspect ratio, which is always 1
+///
+/// Call [TimeUniforms::update] once each frame.
+///
+#[derive(Debug, Default)]
+pub struct TimeUniforms {
+    time: Cell<f32>,
+    delta: Cell<f32>,
+    frame: Cell<i32>,
+    resolution: Cell<(u32, u32)>,
+    updated: Cell<bool>,
+}
+
+impl TimeUniforms {
+    ///
+    /// Constructs new time uniforms starting at time zero.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Advances the time by the given number of milliseconds, usually [FrameInput::elapsed_time](crate::FrameInput::elapsed_time), and sets the resolution to the size of the given viewport.
+    ///
+    pub fn update(&self, elapsed_time: f64, viewport: Viewport) {
+        let delta = 0.001 * elapsed_time as f32;
+        if self.updated.get() {
+            self.frame.set(self.frame.get() + 1);
+            self.time.set(self.time.get() + delta);
+        }
+        self.updated.set(true);
+        self.delta.set(delta);
+        self.resolution.set((viewport.width, viewport.height));
+    }
+
+    ///
+    /// Returns the time in seconds since the first update.
+    ///
+    pub fn time(&self) -> f32 {
+        self.time.get()
+    }
+
+    ///
+    /// Returns the number of updates before the latest one.
+    ///
+    pub fn frame(&self) -> i32 {
+        self.frame.get()
+    }
+}
+
+impl UniformProvider for TimeUniforms {
+    fn fragment_shader_source(&self) -> String {
+        "uniform float iTime;\nuniform float iTimeDelta;\nuniform int iFrame;\nuniform vec3 iResolution;\n"
+            .to_string()
+    }
+
+    fn provide(&self, program: &Program) -> ThreeDResult<()> {
+        if program.requires_uniform("iTime") {
+            program.use_uniform_float("iTime", &self.time.get())?;
+        }
+        if program.requires_uniform("iTimeDelta") {
+            program.use_uniform_float("iTimeDelta", &self.delta.get())?;
+        }
+        if program.requires_uniform("iFrame") {
+            program.use_uniform_int("iFrame", &self.frame.get())?;
+        }
+        if program.requires_uniform("iResolution") {
+            let (width, height) = self.resolution.get();
+            program.use_uniform_vec3("iResolution", &vec3(width as f32, height as f32, 1.0))?;
+        }
+        Ok(())
+    }
+}
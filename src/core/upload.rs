@@ -0,0 +1,83 @@
+use crate::core::*;
+use std::time::Duration;
+
+///
+/// The status of an incremental upload of a resource to the GPU, see for example [Texture2D::new_incremental] and [Mesh::new_incremental].
+///
+pub enum UploadStatus<T> {
+    /// The upload is still in progress. Contains the fraction in the range `[0..1]` of the data that has been uploaded so far.
+    InProgress(f32),
+    /// The upload is done and the resource is ready to use.
+    Done(T),
+}
+
+///
+/// An incremental upload of a resource to the GPU which is spread over several calls to [Upload::progress], typically one each frame,
+/// to avoid stalling the render loop when uploading large resources.
+/// The resource cannot be used before the upload is done.
+///
+pub trait Upload {
+    /// The type of the resource when it is uploaded.
+    type Output;
+
+    ///
+    /// Uploads the next part of the data, but not more than can be uploaded within approximately the given time budget.
+    /// Returns the uploaded resource when all of the data has been uploaded.
+    ///
+    /// # Errors
+    /// Will return an error if the upload is already done.
+    ///
+    fn progress(&mut self, time_budget: Duration) -> ThreeDResult<UploadStatus<Self::Output>>;
+}
+
+impl<U: Upload + ?Sized> Upload for Box<U> {
+    type Output = U::Output;
+    fn progress(&mut self, time_budget: Duration) -> ThreeDResult<UploadStatus<Self::Output>> {
+        self.as_mut().progress(time_budget)
+    }
+}
+
+///
+/// Measures the elapsed time on both desktop and web.
+///
+pub(crate) struct Timer {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+    #[cfg(target_arch = "wasm32")]
+    start: f64,
+}
+
+impl Timer {
+    pub fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+            #[cfg(target_arch = "wasm32")]
+            start: js_sys::Date::now(),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.start.elapsed()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            Duration::from_secs_f64((js_sys::Date::now() - self.start).max(0.0) / 1000.0)
+        }
+    }
+}
+
+///
+/// Returns the number of units to upload in the next chunk such that a chunk takes approximately a quarter of the time budget,
+/// given that the last chunk of `count` units took `elapsed` time.
+///
+pub(crate) fn next_chunk_size(count: usize, elapsed: Duration, time_budget: Duration) -> usize {
+    let elapsed = elapsed.as_secs_f64();
+    if elapsed > 0.0 {
+        ((count as f64 * 0.25 * time_budget.as_secs_f64() / elapsed) as usize).max(1)
+    } else {
+        count * 2
+    }
+}
@@ -0,0 +1,653 @@
+use crate::core::*;
+use std::collections::{HashMap, VecDeque};
+
+///
+/// The method used by [CPUMesh::generate_uvs] to divide a mesh into charts, ie. parts of the mesh which are flattened into a connected region of the texture.
+/// The charts are packed into the texture without overlapping and with at least `padding` texels between them and to the border of the texture,
+/// given that the texture has a size of `texture_size` times `texture_size` texels.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UvMethod {
+    ///
+    /// Each triangle is projected onto its own plane and is a chart on its own.
+    /// This is fast and never distorts the triangles, but all edges are seams.
+    ///
+    Triangles {
+        /// The width and height of the texture which the padding is given for.
+        texture_size: u32,
+        /// The minimum number of texels between the charts.
+        padding: u32,
+    },
+    ///
+    /// The charts are grown over connected triangles which normals are within `max_angle` of the normal of the first triangle in the chart
+    /// and each chart is projected onto the plane orthogonal to that normal.
+    /// A triangle is not added to a chart if its projection overlaps the projection of the chart, so the charts never overlap themselves.
+    /// Gives fewer seams and less wasted texture space than [UvMethod::Triangles], especially for flat and smooth surfaces.
+    ///
+    Charts {
+        /// The maximum angle between the normal of a triangle and the normal of the chart. Clamped to be below 90 degrees.
+        max_angle: Degrees,
+        /// The width and height of the texture which the padding is given for.
+        texture_size: u32,
+        /// The minimum number of texels between the charts.
+        padding: u32,
+    },
+}
+
+impl CPUMesh {
+    ///
+    /// Generates uv coordinates for this mesh, for example for meshes loaded without uv coordinates which should be painted or lightmapped.
+    /// The mesh is divided into charts as specified by the given method and the charts are packed into the unit square without overlapping.
+    ///
+    /// The vertices on the boundary between two charts are duplicated, since they have different uv coordinates in each chart,
+    /// and the indices are rebuilt. All other vertex attributes are kept, except the tangents which depend on the uv coordinates
+    /// and therefore are removed, use [CPUMesh::compute_tangents] to compute them again.
+    /// Existing uv coordinates are replaced.
+    ///
+    /// # Errors
+    /// Returns an error if the charts do not fit into the texture with the given padding, in which case the mesh is unchanged.
+    ///
+    pub fn generate_uvs(&mut self, method: UvMethod) -> ThreeDResult<()> {
+        let uvs = self.unwrap(method)?;
+        self.tangents = None;
+        self.uvs = Some(uvs);
+        Ok(())
+    }
+
+    ///
+    /// Generates a second set of uv coordinates for this mesh in the same way as [CPUMesh::generate_uvs],
+    /// for example for lightmapping a mesh which uses tiled or overlapping uv coordinates for its material textures.
+    /// Contrary to [CPUMesh::generate_uvs], the uv coordinates and tangents are kept. Existing second uv coordinates are replaced.
+    ///
+    /// # Errors
+    /// Returns an error if the charts do not fit into the texture with the given padding, in which case the mesh is unchanged.
+    ///
+    pub fn generate_uvs2(&mut self, method: UvMethod) -> ThreeDResult<()> {
+        let uvs = self.unwrap(method)?;
+        self.uvs2 = Some(uvs);
+        Ok(())
+    }
+
+    // Divides the mesh into charts, duplicates the vertices on the chart boundaries and returns the generated uv coordinates
+    fn unwrap(&mut self, method: UvMethod) -> ThreeDResult<Vec<f32>> {
+        let mut triangles = Vec::new();
+        self.for_each_triangle(|i0, i1, i2| triangles.push([i0, i1, i2]));
+        let (texture_size, padding) = match method {
+            UvMethod::Triangles {
+                texture_size,
+                padding,
+            }
+            | UvMethod::Charts {
+                texture_size,
+                padding,
+                ..
+            } => (texture_size.max(1), padding),
+        };
+
+        let unwrapper = Unwrapper::new(self, &triangles);
+        let charts = match method {
+            UvMethod::Triangles { .. } => unwrapper.triangle_charts(),
+            UvMethod::Charts { max_angle, .. } => unwrapper.grown_charts(max_angle),
+        };
+        let (scale, offsets) = pack(&charts, padding as f32 / texture_size as f32).ok_or(
+            CoreError::TooManyUvCharts(charts.len(), texture_size, padding),
+        )?;
+
+        // Each vertex is duplicated once for each chart it is part of
+        let mut chart_of = vec![0; triangles.len()];
+        let mut local_uvs = vec![[vec2(0.0, 0.0); 3]; triangles.len()];
+        for (c, chart) in charts.iter().enumerate() {
+            for (triangle, uvs) in chart.triangles.iter().zip(chart.uvs.iter()) {
+                chart_of[*triangle] = c;
+                local_uvs[*triangle] = *uvs;
+            }
+        }
+        let mut new_index = HashMap::new();
+        let mut vertices = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::with_capacity(triangles.len() * 3);
+        for (t, triangle) in triangles.iter().enumerate() {
+            for (corner, vertex) in triangle.iter().enumerate() {
+                let index = *new_index.entry((chart_of[t], *vertex)).or_insert_with(|| {
+                    let uv = offsets[chart_of[t]] + local_uvs[t][corner] * scale;
+                    vertices.push(*vertex);
+                    uvs.push(uv.x);
+                    uvs.push(uv.y);
+                    vertices.len() as u32 - 1
+                });
+                indices.push(index);
+            }
+        }
+
+        self.positions = remap(&self.positions, 3, &vertices);
+        self.normals = self.normals.as_ref().map(|n| remap(n, 3, &vertices));
+        self.tangents = self.tangents.as_ref().map(|t| remap(t, 4, &vertices));
+        self.uvs = self.uvs.as_ref().map(|u| remap(u, 2, &vertices));
+        self.uvs2 = self.uvs2.as_ref().map(|u| remap(u, 2, &vertices));
+        self.colors = self.colors.as_ref().map(|c| remap(c, 4, &vertices));
+        self.joint_indices = self.joint_indices.as_ref().map(|j| remap(j, 4, &vertices));
+        self.joint_weights = self.joint_weights.as_ref().map(|w| remap(w, 4, &vertices));
+        for morph_target in self.morph_targets.iter_mut() {
+            morph_target.positions = remap(&morph_target.positions, 3, &vertices);
+            morph_target.normals = morph_target
+                .normals
+                .as_ref()
+                .map(|n| remap(n, 3, &vertices));
+        }
+        self.indices = Some(Indices::U32(indices));
+        Ok(uvs)
+    }
+}
+
+fn remap<T: Copy>(data: &[T], stride: usize, vertices: &[usize]) -> Vec<T> {
+    vertices
+        .iter()
+        .flat_map(|v| data[v * stride..(v + 1) * stride].iter().cloned())
+        .collect()
+}
+
+// A set of triangles and their uv coordinates, which are placed with the minimum at the origin and the maximum at the size
+struct Chart {
+    triangles: Vec<usize>,
+    uvs: Vec<[Vec2; 3]>,
+    size: Vec2,
+}
+
+impl Chart {
+    fn new(triangles: Vec<usize>, mut uvs: Vec<[Vec2; 3]>) -> Self {
+        // The chart is rotated to the angle with the smallest bounding rectangle, which wastes less space when packed
+        let bounds = |uvs: &[[Vec2; 3]], angle: f32| {
+            let (sin, cos) = angle.sin_cos();
+            let mut min = vec2(std::f32::INFINITY, std::f32::INFINITY);
+            let mut max = vec2(std::f32::NEG_INFINITY, std::f32::NEG_INFINITY);
+            for uv in uvs.iter().flat_map(|t| t.iter()) {
+                let p = vec2(cos * uv.x - sin * uv.y, sin * uv.x + cos * uv.y);
+                min = vec2(min.x.min(p.x), min.y.min(p.y));
+                max = vec2(max.x.max(p.x), max.y.max(p.y));
+            }
+            (min, max)
+        };
+        let steps = 16;
+        let angle = (0..steps)
+            .map(|i| 0.5 * std::f32::consts::PI * i as f32 / steps as f32)
+            .min_by(|a, b| {
+                let area = |angle| {
+                    let (min, max) = bounds(&uvs, angle);
+                    (max.x - min.x) * (max.y - min.y)
+                };
+                area(*a).partial_cmp(&area(*b)).unwrap()
+            })
+            .unwrap();
+        let (min, max) = bounds(&uvs, angle);
+        let (sin, cos) = angle.sin_cos();
+        for uv in uvs.iter_mut().flat_map(|t| t.iter_mut()) {
+            *uv = vec2(cos * uv.x - sin * uv.y, sin * uv.x + cos * uv.y) - min;
+        }
+        Self {
+            triangles,
+            uvs,
+            size: max - min,
+        }
+    }
+}
+
+struct Unwrapper {
+    positions: Vec<[Vec3; 3]>,
+    normals: Vec<Vec3>,
+    areas: Vec<f32>,
+    // The triangles which share an edge with each triangle
+    neighbours: Vec<Vec<usize>>,
+    // The average edge length, used as the cell size when testing for overlap
+    edge_length: f32,
+}
+
+impl Unwrapper {
+    fn new(mesh: &CPUMesh, triangles: &[[usize; 3]]) -> Self {
+        let positions = triangles
+            .iter()
+            .map(|t| {
+                [
+                    mesh.position(t[0]),
+                    mesh.position(t[1]),
+                    mesh.position(t[2]),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut normals = Vec::with_capacity(triangles.len());
+        let mut areas = Vec::with_capacity(triangles.len());
+        let mut edge_length = 0.0;
+        for p in positions.iter() {
+            let normal = (p[1] - p[0]).cross(p[2] - p[0]);
+            let length = normal.magnitude();
+            areas.push(0.5 * length);
+            normals.push(if length > 0.0 {
+                normal / length
+            } else {
+                vec3(0.0, 0.0, 0.0)
+            });
+            edge_length += p[0].distance(p[1]) + p[1].distance(p[2]) + p[2].distance(p[0]);
+        }
+        let edge_length = edge_length / (3 * positions.len()).max(1) as f32;
+
+        // Vertices at the same position are welded, so triangles are connected even if the mesh has no indices or has seams
+        let mut welded = HashMap::new();
+        let mut weld = |p: Vec3| {
+            let count = welded.len();
+            *welded
+                .entry((p.x.to_bits(), p.y.to_bits(), p.z.to_bits()))
+                .or_insert(count)
+        };
+        let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (t, p) in positions.iter().enumerate() {
+            let ids = [weld(p[0]), weld(p[1]), weld(p[2])];
+            for i in 0..3 {
+                let (a, b) = (ids[i], ids[(i + 1) % 3]);
+                edges.entry((a.min(b), a.max(b))).or_default().push(t);
+            }
+        }
+        let mut neighbours = vec![Vec::new(); positions.len()];
+        for triangles in edges.values() {
+            for t0 in triangles.iter() {
+                for t1 in triangles.iter() {
+                    if t0 != t1 {
+                        neighbours[*t0].push(*t1);
+                    }
+                }
+            }
+        }
+        Self {
+            positions,
+            normals,
+            areas,
+            neighbours,
+            edge_length,
+        }
+    }
+
+    fn triangle_charts(&self) -> Vec<Chart> {
+        (0..self.positions.len())
+            .map(|t| {
+                let (u, v) = basis(self.normals[t]);
+                Chart::new(vec![t], vec![self.project(t, u, v)])
+            })
+            .collect()
+    }
+
+    fn grown_charts(&self, max_angle: Degrees) -> Vec<Chart> {
+        let min_cos = Radians::from(degrees(max_angle.0.min(89.0))).0.cos();
+        let cell_size = if self.edge_length > 0.0 {
+            self.edge_length
+        } else {
+            1.0
+        };
+        // The largest triangles are used as the first triangle of the charts, since they are the most important to keep undistorted
+        let mut order = (0..self.positions.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| self.areas[*b].partial_cmp(&self.areas[*a]).unwrap());
+
+        let mut assigned = vec![false; self.positions.len()];
+        let mut charts = Vec::new();
+        for seed in order {
+            if assigned[seed] {
+                continue;
+            }
+            let normal = self.normals[seed];
+            let (u, v) = basis(normal);
+            assigned[seed] = true;
+            let mut triangles = vec![seed];
+            let mut uvs = vec![self.project(seed, u, v)];
+            let mut grid = OverlapGrid::new(cell_size);
+            grid.insert(&uvs[0], 0);
+            let mut queue = self.neighbours[seed]
+                .iter()
+                .cloned()
+                .collect::<VecDeque<_>>();
+            while let Some(t) = queue.pop_front() {
+                if assigned[t] || self.normals[t].dot(normal) < min_cos {
+                    continue;
+                }
+                let projected = self.project(t, u, v);
+                if grid.overlaps(&projected, &uvs) {
+                    continue;
+                }
+                assigned[t] = true;
+                grid.insert(&projected, uvs.len());
+                triangles.push(t);
+                uvs.push(projected);
+                queue.extend(self.neighbours[t].iter().filter(|n| !assigned[**n]));
+            }
+            charts.push(Chart::new(triangles, uvs));
+        }
+        charts
+    }
+
+    fn project(&self, triangle: usize, u: Vec3, v: Vec3) -> [Vec2; 3] {
+        let p = &self.positions[triangle];
+        [
+            vec2(p[0].dot(u), p[0].dot(v)),
+            vec2(p[1].dot(u), p[1].dot(v)),
+            vec2(p[2].dot(u), p[2].dot(v)),
+        ]
+    }
+}
+
+// Returns two directions which together with the normal forms an orthonormal right-handed basis
+fn basis(normal: Vec3) -> (Vec3, Vec3) {
+    let normal = if normal.magnitude2() > 0.0 {
+        normal
+    } else {
+        vec3(0.0, 0.0, 1.0)
+    };
+    let up = if normal.x.abs() < 0.9 {
+        vec3(1.0, 0.0, 0.0)
+    } else {
+        vec3(0.0, 1.0, 0.0)
+    };
+    let u = up.cross(normal).normalize();
+    (u, normal.cross(u))
+}
+
+// A uniform grid of the projected triangles of a chart, used for finding the triangles which might overlap a new triangle
+struct OverlapGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl OverlapGrid {
+    fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cells(&self, triangle: &[Vec2; 3]) -> impl Iterator<Item = (i32, i32)> {
+        let cell = |v: f32| (v / self.cell_size).floor() as i32;
+        let min_x = cell(triangle[0].x.min(triangle[1].x).min(triangle[2].x));
+        let max_x = cell(triangle[0].x.max(triangle[1].x).max(triangle[2].x));
+        let min_y = cell(triangle[0].y.min(triangle[1].y).min(triangle[2].y));
+        let max_y = cell(triangle[0].y.max(triangle[1].y).max(triangle[2].y));
+        (min_x..=max_x).flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+    }
+
+    fn insert(&mut self, triangle: &[Vec2; 3], index: usize) {
+        for cell in self.cells(triangle).collect::<Vec<_>>() {
+            self.cells.entry(cell).or_default().push(index);
+        }
+    }
+
+    fn overlaps(&self, triangle: &[Vec2; 3], triangles: &[[Vec2; 3]]) -> bool {
+        let epsilon = 0.0001 * self.cell_size;
+        self.cells(triangle).any(|cell| {
+            self.cells.get(&cell).map_or(false, |indices| {
+                indices
+                    .iter()
+                    .any(|i| triangles_overlap(triangle, &triangles[*i], epsilon))
+            })
+        })
+    }
+}
+
+// Returns whether the interiors of the two triangles overlap by more than epsilon, using the separating axis theorem,
+// so triangles which only share an edge or a vertex do not overlap
+fn triangles_overlap(a: &[Vec2; 3], b: &[Vec2; 3], epsilon: f32) -> bool {
+    let separated = |edges: &[Vec2; 3]| {
+        (0..3).any(|i| {
+            let edge = edges[(i + 1) % 3] - edges[i];
+            let axis = vec2(-edge.y, edge.x);
+            let length = axis.magnitude();
+            if length == 0.0 {
+                return false;
+            }
+            let axis = axis / length;
+            let project = |t: &[Vec2; 3]| {
+                let d = [t[0].dot(axis), t[1].dot(axis), t[2].dot(axis)];
+                (d[0].min(d[1]).min(d[2]), d[0].max(d[1]).max(d[2]))
+            };
+            let (min_a, max_a) = project(a);
+            let (min_b, max_b) = project(b);
+            max_a <= min_b + epsilon || max_b <= min_a + epsilon
+        })
+    };
+    !separated(a) && !separated(b)
+}
+
+// Packs the charts into rows in the unit square with the given padding and returns the largest scale from chart space to uv space
+// which fits and the offset of each chart, or None if the charts do not fit even at a tiny scale
+fn pack(charts: &[Chart], padding: f32) -> Option<(f32, Vec<Vec2>)> {
+    let mut order = (0..charts.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| charts[*b].size.y.partial_cmp(&charts[*a].size.y).unwrap());
+    let try_pack = |scale: f32| {
+        let mut offsets = vec![vec2(0.0, 0.0); charts.len()];
+        let (mut x, mut y, mut row_height) = (padding, padding, 0.0f32);
+        for i in order.iter() {
+            let size = charts[*i].size * scale;
+            if x + size.x + padding > 1.0 {
+                x = padding;
+                y += row_height + padding;
+                row_height = 0.0;
+            }
+            if x + size.x + padding > 1.0 || y + size.y + padding > 1.0 {
+                return None;
+            }
+            offsets[*i] = vec2(x, y);
+            x += size.x + padding;
+            row_height = row_height.max(size.y);
+        }
+        Some(offsets)
+    };
+
+    // The scale is found by bisection, starting from the scale where the charts cover the entire square
+    let area = charts.iter().map(|c| c.size.x * c.size.y).sum::<f32>();
+    let max_size = charts
+        .iter()
+        .map(|c| c.size.x.max(c.size.y))
+        .fold(0.0f32, f32::max);
+    let mut high = if area > 0.0 { 1.0 / area.sqrt() } else { 1.0 };
+    if max_size > 0.0 {
+        high = high.min(1.0 / max_size);
+    }
+    let mut low = 0.0;
+    if try_pack(high).is_some() {
+        low = high;
+    } else {
+        for _ in 0..24 {
+            let middle = 0.5 * (low + high);
+            if try_pack(middle).is_some() {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+    }
+    try_pack(low).map(|offsets| (low, offsets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXTURE_SIZE: u32 = 512;
+    const PADDING: u32 = 2;
+
+    // Returns the uv coordinates of each triangle and the chart of each triangle, where the charts are found as the triangles
+    // connected by corners with the same position and uv coordinate, since such corners are never shared between charts
+    fn charts(mesh: &CPUMesh) -> (Vec<[Vec2; 3]>, Vec<usize>) {
+        let uvs = mesh.uvs.as_ref().unwrap();
+        let uv = |i: usize| vec2(uvs[2 * i], uvs[2 * i + 1]);
+        let mut welded = HashMap::new();
+        let mut weld = |i: usize| {
+            let (p, uv) = (mesh.position(i), uv(i));
+            let count = welded.len();
+            *welded
+                .entry([
+                    p.x.to_bits(),
+                    p.y.to_bits(),
+                    p.z.to_bits(),
+                    uv.x.to_bits(),
+                    uv.y.to_bits(),
+                ])
+                .or_insert(count)
+        };
+        let mut triangles = Vec::new();
+        let mut corners = Vec::new();
+        mesh.for_each_triangle(|i0, i1, i2| {
+            triangles.push([uv(i0), uv(i1), uv(i2)]);
+            corners.push([weld(i0), weld(i1), weld(i2)]);
+        });
+
+        fn root(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+        let mut parents = (0..welded.len()).collect::<Vec<_>>();
+        for c in corners.iter() {
+            for i in [c[1], c[2]].iter() {
+                let (a, b) = (root(&mut parents, c[0]), root(&mut parents, *i));
+                parents[a] = b;
+            }
+        }
+        let chart_of = corners.iter().map(|c| root(&mut parents, c[0])).collect();
+        (triangles, chart_of)
+    }
+
+    fn triangle_count(mesh: &CPUMesh) -> usize {
+        let mut count = 0;
+        mesh.for_each_triangle(|_, _, _| count += 1);
+        count
+    }
+
+    fn assert_valid(mesh: &CPUMesh) {
+        let (triangles, chart_of) = charts(mesh);
+        let padding = PADDING as f32 / TEXTURE_SIZE as f32;
+        let epsilon = 0.00001;
+
+        // The uv coordinates are inside the unit square with padding to the border
+        for uv in triangles.iter().flat_map(|t| t.iter()) {
+            assert!(uv.x >= padding - epsilon && uv.x <= 1.0 - padding + epsilon);
+            assert!(uv.y >= padding - epsilon && uv.y <= 1.0 - padding + epsilon);
+        }
+
+        // No triangles overlap, neither within a chart nor between charts
+        for a in 0..triangles.len() {
+            for b in a + 1..triangles.len() {
+                assert!(
+                    !triangles_overlap(&triangles[a], &triangles[b], epsilon),
+                    "the triangles {} and {} overlap",
+                    a,
+                    b
+                );
+            }
+        }
+
+        // The bounding rectangles of the charts are separated by at least the padding
+        let mut bounds: HashMap<usize, (Vec2, Vec2)> = HashMap::new();
+        for (triangle, chart) in triangles.iter().zip(chart_of.iter()) {
+            let entry = bounds.entry(*chart).or_insert((
+                vec2(std::f32::INFINITY, std::f32::INFINITY),
+                vec2(std::f32::NEG_INFINITY, std::f32::NEG_INFINITY),
+            ));
+            for uv in triangle.iter() {
+                entry.0 = vec2(entry.0.x.min(uv.x), entry.0.y.min(uv.y));
+                entry.1 = vec2(entry.1.x.max(uv.x), entry.1.y.max(uv.y));
+            }
+        }
+        let bounds = bounds.values().collect::<Vec<_>>();
+        for a in 0..bounds.len() {
+            for b in a + 1..bounds.len() {
+                let (min_a, max_a) = bounds[a];
+                let (min_b, max_b) = bounds[b];
+                let gap_x = (min_b.x - max_a.x).max(min_a.x - max_b.x);
+                let gap_y = (min_b.y - max_a.y).max(min_a.y - max_b.y);
+                assert!(gap_x.max(gap_y) >= padding - epsilon);
+            }
+        }
+    }
+
+    fn meshes() -> Vec<CPUMesh> {
+        let mut cylinder = CPUMesh::cylinder(12);
+        cylinder.compute_normals();
+        vec![
+            CPUMesh::cube(),
+            CPUMesh::sphere(8),
+            cylinder,
+            CPUMesh::arrow(0.8, 0.5, 10),
+        ]
+    }
+
+    #[test]
+    fn triangle_charts() {
+        for mut mesh in meshes() {
+            let count = triangle_count(&mesh);
+            mesh.generate_uvs(UvMethod::Triangles {
+                texture_size: TEXTURE_SIZE,
+                padding: PADDING,
+            })
+            .unwrap();
+            assert_eq!(triangle_count(&mesh), count);
+            assert_eq!(mesh.positions.len() / 3, 3 * count);
+            assert_valid(&mesh);
+        }
+    }
+
+    #[test]
+    fn grown_charts() {
+        for mut mesh in meshes() {
+            let count = triangle_count(&mesh);
+            mesh.generate_uvs(UvMethod::Charts {
+                max_angle: degrees(30.0),
+                texture_size: TEXTURE_SIZE,
+                padding: PADDING,
+            })
+            .unwrap();
+            assert_eq!(triangle_count(&mesh), count);
+            assert_valid(&mesh);
+        }
+
+        // The six sides of a cube are six charts
+        let mut cube = CPUMesh::cube();
+        cube.generate_uvs(UvMethod::Charts {
+            max_angle: degrees(30.0),
+            texture_size: TEXTURE_SIZE,
+            padding: PADDING,
+        })
+        .unwrap();
+        let (_, chart_of) = charts(&cube);
+        let mut chart_ids = chart_of.clone();
+        chart_ids.sort_unstable();
+        chart_ids.dedup();
+        assert_eq!(chart_ids.len(), 6);
+    }
+
+    #[test]
+    fn second_uvs_keep_the_other_attributes() {
+        let mut mesh = CPUMesh::sphere(8);
+        mesh.compute_tangents().unwrap();
+        mesh.generate_uvs2(UvMethod::Charts {
+            max_angle: degrees(45.0),
+            texture_size: TEXTURE_SIZE,
+            padding: PADDING,
+        })
+        .unwrap();
+        let vertex_count = mesh.positions.len() / 3;
+        assert_eq!(mesh.uvs.as_ref().unwrap().len(), 2 * vertex_count);
+        assert_eq!(mesh.uvs2.as_ref().unwrap().len(), 2 * vertex_count);
+        assert_eq!(mesh.tangents.as_ref().unwrap().len(), 4 * vertex_count);
+        mesh.validate().unwrap();
+    }
+
+    #[test]
+    fn too_many_charts() {
+        let mut mesh = CPUMesh::sphere(32);
+        let positions = mesh.positions.clone();
+        assert!(mesh
+            .generate_uvs(UvMethod::Triangles {
+                texture_size: 16,
+                padding: 4,
+            })
+            .is_err());
+        assert_eq!(mesh.positions, positions);
+    }
+}
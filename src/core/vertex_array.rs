@@ -0,0 +1,124 @@
+use crate::core::*;
+use std::cell::RefCell;
+
+///
+/// A vertex array object which records the attributes of a [Program], ie. which buffer is used for which attribute and how the data in the buffer is interpreted.
+/// Binding a vertex array is a lot cheaper than specifying each attribute again,
+/// so the attributes only need to be specified once for each combination of program and buffers.
+/// See [VertexArrays] for a cache of vertex arrays which handles this automatically.
+///
+pub struct VertexArray {
+    context: Context,
+    id: crate::context::VertexArrayObject,
+}
+
+impl VertexArray {
+    ///
+    /// Creates a new vertex array without any recorded attributes.
+    ///
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        context.check_context_lost()?;
+        Ok(Self {
+            context: context.clone(),
+            id: context
+                .create_vertex_array()
+                .ok_or(CoreError::VertexArrayCreation)?,
+        })
+    }
+
+    ///
+    /// Binds this vertex array. The following calls to the use_attribute functionality of a [Program] are recorded in this vertex array
+    /// and the next draw call of the program uses the attributes recorded in this vertex array.
+    /// The default vertex array is bound again after the draw call.
+    ///
+    pub fn bind(&self) {
+        self.context.bind_vertex_array(&self.id);
+        self.context.set_vertex_array_bound(true);
+    }
+
+    ///
+    /// Binds the default vertex array again, which is only necessary if a vertex array is bound without drawing afterwards.
+    ///
+    pub fn unbind(context: &Context) {
+        context.unbind_vertex_array();
+        context.set_vertex_array_bound(false);
+    }
+}
+
+impl Drop for VertexArray {
+    fn drop(&mut self) {
+        self.context.delete_vertex_array(&self.id);
+    }
+}
+
+///
+/// The maximum number of vertex arrays kept by [VertexArrays], where the least recently used vertex array is deleted when a new one is recorded,
+/// for example when a program is recreated after reloading a shader or changing a material.
+///
+pub const MAX_VERTEX_ARRAYS: usize = 16;
+
+///
+/// A cache of [VertexArray]s, one for each [Program] used for drawing the same buffers, for example the buffers of a [Mesh].
+/// A vertex array is recorded the first time a program is used and when the buffers are recreated or change type,
+/// otherwise drawing only requires binding the recorded vertex array.
+/// At most [MAX_VERTEX_ARRAYS] vertex arrays are kept, so the vertex arrays recorded for programs or buffers which are no longer used are eventually deleted
+/// and finding the vertex array of a program is fast.
+///
+pub struct VertexArrays {
+    context: Context,
+    vertex_arrays: RefCell<Vec<(u64, Vec<u64>, VertexArray)>>,
+}
+
+impl VertexArrays {
+    ///
+    /// Creates a new empty cache of vertex arrays.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            vertex_arrays: RefCell::new(Vec::new()),
+        }
+    }
+
+    ///
+    /// Binds the vertex array recorded for the given program and buffers, such that the next draw call of the program uses the recorded attributes.
+    /// The buffers are identified by their unique ids, see for example [VertexBuffer::uid].
+    /// If no vertex array is recorded for the program or the ids differ from when it was recorded,
+    /// the attributes are recorded by calling the `use_attributes` closure, which should call the use_attribute functionality of the program.
+    ///
+    pub fn use_attributes(
+        &self,
+        program: &Program,
+        buffer_ids: &[u64],
+        use_attributes: impl FnOnce() -> ThreeDResult<()>,
+    ) -> ThreeDResult<()> {
+        let mut vertex_arrays = self.vertex_arrays.borrow_mut();
+        if let Some(index) = vertex_arrays
+            .iter()
+            .position(|(program_id, _, _)| *program_id == program.uid())
+        {
+            if vertex_arrays[index].1.as_slice() == buffer_ids {
+                // The vertex arrays are ordered from least to most recently used
+                let entry = vertex_arrays.remove(index);
+                entry.2.bind();
+                vertex_arrays.push(entry);
+                return Ok(());
+            }
+            // Start from a new vertex array so no attributes from the old buffers are left enabled
+            vertex_arrays.remove(index);
+        }
+        if vertex_arrays.len() >= MAX_VERTEX_ARRAYS {
+            vertex_arrays.remove(0);
+        }
+        let vertex_array = VertexArray::new(&self.context)?;
+        vertex_array.bind();
+        vertex_arrays.push((program.uid(), buffer_ids.to_vec(), vertex_array));
+        let result = use_attributes();
+        if result.is_err() {
+            // The vertex array is only partially recorded, so it needs to be recorded again next time
+            VertexArray::unbind(&self.context);
+            vertex_arrays.retain(|(program_id, _, _)| *program_id != program.uid());
+        }
+        result
+    }
+}
@@ -2,6 +2,7 @@
 /// Defines the part of the screen/render target that is rendered to.
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
 pub struct Viewport {
     /// The distance in pixels from the left edge of the screen/render target.
     pub x: i32,
@@ -3,18 +3,32 @@ use crate::window::*;
 use crate::ThreeDResult;
 #[doc(hidden)]
 pub use egui;
+use std::cell::{Cell, RefCell};
 
 ///
 /// Integration of [egui](https://crates.io/crates/egui), an immediate mode GUI.
+/// Each GUI has its own egui context and textures, so several GUIs can be used at the same time,
+/// for example a GUI on the screen and a GUI rendered into a texture in the 3D world, see [GUI::update_in_world].
 ///
 pub struct GUI {
     context: Context,
     egui_context: egui::CtxRef,
-    width: u32,
-    height: u32,
+    viewport: Viewport,
+    pixels_per_point: f32,
+    pixels_per_point_override: Option<f32>,
     program: Program,
-    texture_version: u64,
-    texture: Option<Texture2D<u8>>,
+    texture_version: Cell<u64>,
+    texture: RefCell<Option<Texture2D<u8>>>,
+    buffers: RefCell<MeshBuffers>,
+    vertex_arrays: VertexArrays,
+}
+
+// The buffers containing the meshes of all of the clipping rectangles of a frame
+struct MeshBuffers {
+    position_buffer: VertexBuffer,
+    uv_buffer: VertexBuffer,
+    color_buffer: VertexBuffer,
+    index_buffer: ElementBuffer,
 }
 
 impl GUI {
@@ -25,10 +39,18 @@ impl GUI {
         Ok(GUI {
             egui_context: egui::CtxRef::default(),
             context: context.clone(),
-            width: 0,
-            height: 0,
-            texture_version: 0,
-            texture: None,
+            viewport: Viewport::new_at_origo(1, 1),
+            pixels_per_point: 1.0,
+            pixels_per_point_override: None,
+            texture_version: Cell::new(0),
+            texture: RefCell::new(None),
+            buffers: RefCell::new(MeshBuffers {
+                position_buffer: VertexBuffer::new(context)?,
+                uv_buffer: VertexBuffer::new(context)?,
+                color_buffer: VertexBuffer::new(context)?,
+                index_buffer: ElementBuffer::new::<u32>(context)?,
+            }),
+            vertex_arrays: VertexArrays::new(context),
             program: Program::from_source(
                 context,
                 &format!(
@@ -45,6 +67,23 @@ impl GUI {
         })
     }
 
+    ///
+    /// Overrides the scale of the GUI, ie. the number of physical pixels for each egui point, independent of the device pixel ratio of the screen,
+    /// for example to make the GUI larger for accessibility. Set to `None` to use the device pixel ratio given by [FrameInput::device_pixel_ratio] (the default).
+    /// The new scale is used from the next call to [update](Self::update).
+    ///
+    pub fn set_pixels_per_point(&mut self, pixels_per_point: Option<f32>) {
+        self.pixels_per_point_override = pixels_per_point;
+    }
+
+    ///
+    /// Returns the number of physical pixels for each egui point used in the last call to [update](Self::update).
+    /// Multiply sizes given by egui, for example the width of a panel, with this value to get the size in physical pixels, ie. in the same space as [FrameInput::viewport].
+    ///
+    pub fn pixels_per_point(&self) -> f32 {
+        self.pixels_per_point
+    }
+
     ///
     /// Initialises a new frame of the GUI and handles events.
     /// Construct the GUI (Add panels, widgets etc.) using the [egui::CtxRef](egui::CtxRef) in the callback function.
@@ -55,14 +94,164 @@ impl GUI {
         frame_input: &mut FrameInput,
         callback: F,
     ) -> ThreeDResult<bool> {
-        self.width = frame_input.window_width;
-        self.height = frame_input.window_height;
-        let input_state = construct_input_state(frame_input);
+        // Everything given to egui is in points, which are converted from the physical size of the screen,
+        // so the GUI matches the screen even if the window size is rounded or the device pixel ratio changes
+        self.viewport = frame_input.viewport;
+        self.pixels_per_point = self
+            .pixels_per_point_override
+            .unwrap_or(frame_input.device_pixel_ratio as f32);
+        // Scroll deltas are in logical pixels which are converted to points, these are only different if the scale is overridden
+        let input_state = construct_input_state(
+            &frame_input.events,
+            frame_input.device_pixel_ratio as f32 / self.pixels_per_point,
+            self.viewport,
+            self.pixels_per_point,
+            frame_input.accumulated_time,
+        );
+        self.egui_context.begin_frame(input_state);
+        callback(&self.egui_context);
+        Ok(self.handle_events(&mut frame_input.events))
+    }
+
+    ///
+    /// Same as [update](Self::update), except that the GUI is rendered into a render target with the given size in physical pixels instead of the screen,
+    /// for example a texture which is mapped onto a screen in the 3D world, see [update_in_world](Self::update_in_world).
+    /// The physical positions of the given events are in physical pixels of the render target with the origin in the top left corner, see [PointerPosition::physical].
+    /// The scale of the GUI is given by [set_pixels_per_point](Self::set_pixels_per_point) and is one physical pixel for each egui point by default.
+    /// Call [render](Self::render) in the render function of the render target afterwards, for example in the callback function of [Texture2D::write].
+    ///
+    pub fn update_offscreen<F: FnOnce(&egui::CtxRef)>(
+        &mut self,
+        events: &mut [Event],
+        accumulated_time: f64,
+        width: u32,
+        height: u32,
+        callback: F,
+    ) -> ThreeDResult<bool> {
+        self.viewport = Viewport::new_at_origo(width.max(1), height.max(1));
+        self.pixels_per_point = self.pixels_per_point_override.unwrap_or(1.0);
+        let input_state = construct_input_state(
+            events,
+            1.0 / self.pixels_per_point,
+            self.viewport,
+            self.pixels_per_point,
+            accumulated_time,
+        );
         self.egui_context.begin_frame(input_state);
         callback(&self.egui_context);
+        Ok(self.handle_events(events))
+    }
 
+    ///
+    /// Updates a GUI which is rendered into a texture with the given size in physical pixels which is mapped onto a surface in the 3D world, see [update_offscreen](Self::update_offscreen).
+    /// The mouse events in the frame input are mapped onto the texture using the `screen_to_uv` function, which returns the uv coordinates at the given position on the screen
+    /// if the surface is hit and `None` otherwise, for example by intersecting the ray through the pixel given by [FrameInput::to_viewport_pixel] with the surface.
+    /// The uv coordinates are assumed to have the origin in the bottom left corner of the texture, as for example the uv coordinates of [CPUMesh::square](crate::CPUMesh::square).
+    /// The events consumed by this GUI are marked as handled in the frame input,
+    /// so update a GUI on the screen before this GUI if it should take precedence where they overlap.
+    ///
+    pub fn update_in_world<F: FnOnce(&egui::CtxRef)>(
+        &mut self,
+        frame_input: &mut FrameInput,
+        width: u32,
+        height: u32,
+        screen_to_uv: impl Fn(PointerPosition) -> Option<Vec2>,
+        callback: F,
+    ) -> ThreeDResult<bool> {
+        // The texel is both the logical and the physical position, since the texture has no device pixel ratio
+        let to_texel = |position: PointerPosition| {
+            screen_to_uv(position).map(|uv| {
+                PointerPosition::from_physical(
+                    (uv.x * width as f32, (1.0 - uv.y) * height as f32),
+                    1.0,
+                )
+            })
+        };
+        // The mapped events and the index of the event in the frame input each is mapped from
+        let mut indices = Vec::new();
+        let mut events = Vec::new();
+        for (i, event) in frame_input.events.iter().enumerate() {
+            let event = match event.clone() {
+                Event::MousePress {
+                    button,
+                    position,
+                    modifiers,
+                    handled,
+                } => to_texel(position).map(|position| Event::MousePress {
+                    button,
+                    position,
+                    modifiers,
+                    handled,
+                }),
+                // A release outside the surface is moved outside the GUI, so anything dragged on the GUI is released
+                Event::MouseRelease {
+                    button,
+                    position,
+                    modifiers,
+                    handled,
+                } => Some(Event::MouseRelease {
+                    button,
+                    position: to_texel(position)
+                        .unwrap_or(PointerPosition::from_physical((-1.0, -1.0), 1.0)),
+                    modifiers,
+                    handled,
+                }),
+                Event::MouseMotion {
+                    button,
+                    delta,
+                    position,
+                    modifiers,
+                    handled,
+                } => Some(match to_texel(position) {
+                    Some(position) => Event::MouseMotion {
+                        button,
+                        delta,
+                        position,
+                        modifiers,
+                        handled,
+                    },
+                    None => Event::MouseLeave,
+                }),
+                Event::MouseWheel {
+                    delta,
+                    position,
+                    modifiers,
+                    handled,
+                } => to_texel(position).map(|position| Event::MouseWheel {
+                    delta,
+                    position,
+                    modifiers,
+                    handled,
+                }),
+                event => Some(event),
+            };
+            if let Some(event) = event {
+                indices.push(i);
+                events.push(event);
+            }
+        }
+
+        let change = self.update_offscreen(
+            &mut events,
+            frame_input.accumulated_time,
+            width,
+            height,
+            callback,
+        )?;
+        for (i, event) in indices.into_iter().zip(events.iter_mut()) {
+            if handled(event).map(|h| *h).unwrap_or(false) {
+                if let Some(handled) = handled(&mut frame_input.events[i]) {
+                    *handled = true;
+                }
+            }
+        }
+        Ok(change)
+    }
+
+    // Marks the events consumed by egui as handled and returns whether or not any events are consumed
+    fn handle_events(&self, events: &mut [Event]) -> bool {
         let mut change = false;
-        for event in frame_input.events.iter_mut() {
+        for event in events.iter_mut() {
             if self.egui_context.wants_pointer_input() {
                 match event {
                     Event::MousePress {
@@ -107,20 +296,21 @@ impl GUI {
                 change = true;
             }
         }
-        Ok(change)
+        change
     }
 
     ///
     /// Render the GUI defined in the [update](Self::update) function. Must be called in a render target render function,
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     ///
-    pub fn render(&mut self) -> ThreeDResult<()> {
+    pub fn render(&self) -> ThreeDResult<()> {
         let (_, shapes) = self.egui_context.end_frame();
         let clipped_meshes = self.egui_context.tessellate(shapes);
 
         let egui_texture = self.egui_context.texture();
 
-        if self.texture.is_none() || self.texture_version != egui_texture.version {
+        let mut texture = self.texture.borrow_mut();
+        if texture.is_none() || self.texture_version.get() != egui_texture.version {
             let mut pixels = Vec::new();
             for pixel in egui_texture.srgba_pixels() {
                 pixels.push(pixel.r());
@@ -128,7 +318,7 @@ impl GUI {
                 pixels.push(pixel.b());
                 pixels.push(pixel.a());
             }
-            self.texture = Some(Texture2D::new(
+            *texture = Some(Texture2D::new(
                 &self.context,
                 &CPUTexture {
                     data: pixels,
@@ -141,32 +331,61 @@ impl GUI {
                     ..Default::default()
                 },
             )?);
-            self.texture_version = egui_texture.version;
+            self.texture_version.set(egui_texture.version);
         };
 
-        let scale = self.egui_context.pixels_per_point();
-        let viewport = Viewport::new_at_origo(
-            (self.width as f32 * scale).round() as u32,
-            (self.height as f32 * scale).round() as u32,
-        );
-
+        let scale = self.pixels_per_point;
+        let viewport = self.viewport;
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        let mut draws = Vec::new();
         for egui::ClippedMesh(rect, mesh) in clipped_meshes {
-            let width = rect.max.x - rect.min.x;
-            let height = rect.max.y - rect.min.y;
+            debug_assert!(mesh.is_valid());
+            // The clip rectangle is given in points with the origin in the top left corner
+            let clip_x = |x: f32| (x * scale).round().max(0.0).min(viewport.width as f32) as u32;
+            let clip_y = |y: f32| (y * scale).round().max(0.0).min(viewport.height as f32) as u32;
+            let (min_x, max_x) = (clip_x(rect.min.x), clip_x(rect.max.x));
+            let (min_y, max_y) = (clip_y(rect.min.y), clip_y(rect.max.y));
+            if min_x >= max_x || min_y >= max_y {
+                continue;
+            }
             let clipping = Clip::Enabled {
-                x: (rect.min.x * scale) as u32,
-                y: ((self.height as f32 - rect.max.y) * scale) as u32,
-                width: (width * scale) as u32,
-                height: (height * scale) as u32,
+                x: viewport.x as u32 + min_x,
+                y: viewport.y as u32 + viewport.height - max_y,
+                width: max_x - min_x,
+                height: max_y - min_y,
             };
-            self.paint_mesh(
-                viewport,
-                clipping,
-                self.width,
-                self.height,
-                &mesh,
-                self.texture.as_ref().unwrap(),
-            )?;
+
+            // All meshes are stored in the same buffers, so the indices are offset by the number of vertices of the previous meshes
+            let first_vertex = (positions.len() / 2) as u32;
+            for v in mesh.vertices.iter() {
+                positions.push(v.pos.x);
+                positions.push(v.pos.y);
+                uvs.push(v.uv.x);
+                uvs.push(v.uv.y);
+                colors.push(v.color[0] as f32);
+                colors.push(v.color[1] as f32);
+                colors.push(v.color[2] as f32);
+                colors.push(v.color[3] as f32);
+            }
+            draws.push((clipping, indices.len() as u32, mesh.indices.len() as u32));
+            indices.extend(mesh.indices.iter().map(|i| first_vertex + *i));
+        }
+        if draws.is_empty() {
+            return Ok(());
+        }
+
+        // The buffers are reused between frames, so the attributes only need to be recorded in a vertex array once
+        let mut buffers = self.buffers.borrow_mut();
+        buffers.position_buffer.fill_with_dynamic(&positions);
+        buffers.uv_buffer.fill_with_dynamic(&uvs);
+        buffers.color_buffer.fill_with_dynamic(&colors);
+        buffers.index_buffer.fill_with(&indices)?;
+        let texture = texture.as_ref().unwrap();
+        for (clip, first, count) in draws {
+            self.paint_mesh(viewport, clip, &buffers, texture, first, count)?;
         }
         Ok(())
     }
@@ -175,33 +394,11 @@ impl GUI {
         &self,
         viewport: Viewport,
         clip: Clip,
-        width: u32,
-        height: u32,
-        mesh: &egui::paint::Mesh,
+        buffers: &MeshBuffers,
         texture: &Texture2D<u8>,
+        first: u32,
+        count: u32,
     ) -> ThreeDResult<()> {
-        debug_assert!(mesh.is_valid());
-
-        let mut positions = Vec::new();
-        let mut colors = Vec::new();
-        let mut uvs = Vec::new();
-        for v in mesh.vertices.iter() {
-            positions.push(v.pos.x);
-            positions.push(v.pos.y);
-            uvs.push(v.uv.x);
-            uvs.push(v.uv.y);
-            colors.push(v.color[0] as f32);
-            colors.push(v.color[1] as f32);
-            colors.push(v.color[2] as f32);
-            colors.push(v.color[3] as f32);
-        }
-        let indices: Vec<u32> = mesh.indices.iter().map(|idx| *idx as u32).collect();
-
-        let position_buffer = VertexBuffer::new_with_static(&self.context, &positions)?;
-        let uv_buffer = VertexBuffer::new_with_static(&self.context, &uvs)?;
-        let color_buffer = VertexBuffer::new_with_static(&self.context, &colors)?;
-        let index_buffer = ElementBuffer::new_with(&self.context, &indices)?;
-
         let render_states = RenderStates {
             blend: Blend::Enabled {
                 source_rgb_multiplier: BlendMultiplierType::One,
@@ -216,31 +413,68 @@ impl GUI {
             ..Default::default()
         };
 
-        self.program.use_texture("u_sampler", texture)?;
-        self.program
-            .use_uniform("u_screen_size", vec2(width as f32, height as f32))?;
+        let program = &self.program;
+        program.use_texture("u_sampler", texture)?;
+        program.use_uniform(
+            "u_screen_size",
+            vec2(
+                viewport.width as f32 / self.pixels_per_point,
+                viewport.height as f32 / self.pixels_per_point,
+            ),
+        )?;
 
-        self.program.use_attribute_vec2("a_pos", &position_buffer)?;
-        self.program.use_attribute_vec4("a_srgba", &color_buffer)?;
-        self.program.use_attribute_vec2("a_tc", &uv_buffer)?;
+        let (position_buffer, color_buffer, uv_buffer) = (
+            &buffers.position_buffer,
+            &buffers.color_buffer,
+            &buffers.uv_buffer,
+        );
+        self.vertex_arrays.use_attributes(
+            program,
+            &[position_buffer.uid(), color_buffer.uid(), uv_buffer.uid()],
+            || {
+                program.use_attribute_vec2("a_pos", position_buffer)?;
+                program.use_attribute_vec4("a_srgba", color_buffer)?;
+                program.use_attribute_vec2("a_tc", uv_buffer)?;
+                Ok(())
+            },
+        )?;
 
-        self.program
-            .draw_elements(render_states, viewport, &index_buffer);
-        Ok(())
+        program.draw_elements_subset(
+            render_states,
+            viewport,
+            &buffers.index_buffer,
+            first,
+            count,
+        )?;
+        self.context.check_errors("GUI::render")
     }
 }
 
-fn construct_input_state(frame_input: &mut FrameInput) -> egui::RawInput {
+// Converts the events to egui input, where the physical event positions are divided by the pixels per point to get the position in points,
+// which is correct even if the scale is overridden or the logical size of the window is rounded, and the deltas are multiplied by the given scale
+fn construct_input_state(
+    events: &[Event],
+    scale: f32,
+    viewport: Viewport,
+    pixels_per_point: f32,
+    accumulated_time: f64,
+) -> egui::RawInput {
+    let to_pos = |position: &PointerPosition| egui::Pos2 {
+        x: position.physical.0 / pixels_per_point,
+        y: position.physical.1 / pixels_per_point,
+    };
     let mut scroll_delta = egui::Vec2::ZERO;
     let mut egui_modifiers = egui::Modifiers::default();
     let mut egui_events = Vec::new();
-    for event in frame_input.events.iter() {
+    for event in events.iter() {
         match event {
             Event::KeyPress {
                 kind,
                 modifiers,
                 handled,
+                ..
             } => {
+                // Repeated presses of held keys are sent as new presses, which egui handles like the first press
                 if !handled {
                     egui_events.push(egui::Event::Key {
                         key: translate_to_egui_key_code(kind),
@@ -270,10 +504,7 @@ fn construct_input_state(frame_input: &mut FrameInput) -> egui::RawInput {
             } => {
                 if !handled {
                     egui_events.push(egui::Event::PointerButton {
-                        pos: egui::Pos2 {
-                            x: position.0 as f32,
-                            y: position.1 as f32,
-                        },
+                        pos: to_pos(position),
                         button: match button {
                             MouseButton::Left => egui::PointerButton::Primary,
                             MouseButton::Right => egui::PointerButton::Secondary,
@@ -292,10 +523,7 @@ fn construct_input_state(frame_input: &mut FrameInput) -> egui::RawInput {
             } => {
                 if !handled {
                     egui_events.push(egui::Event::PointerButton {
-                        pos: egui::Pos2 {
-                            x: position.0 as f32,
-                            y: position.1 as f32,
-                        },
+                        pos: to_pos(position),
                         button: match button {
                             MouseButton::Left => egui::PointerButton::Primary,
                             MouseButton::Right => egui::PointerButton::Secondary,
@@ -310,21 +538,27 @@ fn construct_input_state(frame_input: &mut FrameInput) -> egui::RawInput {
                 position, handled, ..
             } => {
                 if !handled {
-                    egui_events.push(egui::Event::PointerMoved(egui::Pos2 {
-                        x: position.0 as f32,
-                        y: position.1 as f32,
-                    }));
+                    egui_events.push(egui::Event::PointerMoved(to_pos(position)));
                 }
             }
             Event::Text(text) => {
                 egui_events.push(egui::Event::Text(text.clone()));
             }
+            Event::CompositionStart => {
+                egui_events.push(egui::Event::CompositionStart);
+            }
+            Event::CompositionUpdate(text) => {
+                egui_events.push(egui::Event::CompositionUpdate(text.clone()));
+            }
+            Event::CompositionEnd(text) => {
+                egui_events.push(egui::Event::CompositionEnd(text.clone()));
+            }
             Event::MouseLeave => {
                 egui_events.push(egui::Event::PointerGone);
             }
             Event::MouseWheel { delta, handled, .. } => {
                 if !handled {
-                    scroll_delta = egui::Vec2::new(delta.0 as f32, delta.1 as f32);
+                    scroll_delta = egui::Vec2::new(delta.0 as f32 * scale, delta.1 as f32 * scale);
                 }
             }
             Event::ModifiersChange { modifiers } => egui_modifiers = map_modifiers(modifiers),
@@ -337,18 +571,30 @@ fn construct_input_state(frame_input: &mut FrameInput) -> egui::RawInput {
         screen_rect: Some(egui::Rect::from_min_size(
             Default::default(),
             egui::Vec2 {
-                x: frame_input.window_width as f32,
-                y: frame_input.window_height as f32,
+                x: viewport.width as f32 / pixels_per_point,
+                y: viewport.height as f32 / pixels_per_point,
             },
         )),
-        pixels_per_point: Some(frame_input.device_pixel_ratio as f32),
-        time: Some(frame_input.accumulated_time * 0.001),
+        pixels_per_point: Some(pixels_per_point),
+        time: Some(accumulated_time * 0.001),
         modifiers: egui_modifiers,
         events: egui_events,
         ..Default::default()
     }
 }
 
+fn handled(event: &mut Event) -> Option<&mut bool> {
+    match event {
+        Event::MousePress { handled, .. }
+        | Event::MouseRelease { handled, .. }
+        | Event::MouseMotion { handled, .. }
+        | Event::MouseWheel { handled, .. }
+        | Event::KeyPress { handled, .. }
+        | Event::KeyRelease { handled, .. } => Some(handled),
+        _ => None,
+    }
+}
+
 fn translate_to_egui_key_code(key: &crate::Key) -> egui::Key {
     use crate::Key::*;
     use egui::Key;
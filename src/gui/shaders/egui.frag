@@ -7,7 +7,7 @@ in vec2 v_tc;
 layout (location = 0) out vec4 color;
 
 void main() {
-    // The texture is in linear space so we need to decode here
+    // The texture is sRGB encoded, so it is decoded to linear space here
     vec4 texture_rgba = texture(u_sampler, v_tc);
     texture_rgba.rgb = rgb_from_srgb(texture_rgba.rgb);
     /// Multiply vertex color with texture color (in linear space).
@@ -12,12 +12,19 @@ mod parser;
 #[doc(inline)]
 pub use parser::*;
 
+mod stream;
+#[doc(inline)]
+pub use stream::*;
+
 #[cfg(not(target_arch = "wasm32"))]
 mod saver;
 #[doc(inline)]
 #[cfg(not(target_arch = "wasm32"))]
 pub use saver::*;
 
+#[cfg(feature = "bundle-io")]
+mod bundle;
+
 use thiserror::Error;
 ///
 /// Error from the [io](crate::io) module.
@@ -32,8 +39,8 @@ pub enum IOError {
     #[error("error while parsing a .3d file")]
     ThreeD(#[from] bincode::Error),
     #[cfg(feature = "obj-io")]
-    #[error("error while parsing an .obj file")]
-    Obj(#[from] wavefront_obj::ParseError),
+    #[error("error while parsing an .obj file: {0}")]
+    Obj(String),
     #[cfg(feature = "gltf-io")]
     #[error("error while parsing a .gltf file")]
     Gltf(#[from] ::gltf::Error),
@@ -43,9 +50,49 @@ pub enum IOError {
     #[cfg(feature = "gltf-io")]
     #[error("the .gltf file contain missing buffer data")]
     GltfMissingData,
+    #[cfg(feature = "ply-io")]
+    #[error("error while parsing a .ply file: {0}")]
+    Ply(String),
+    #[cfg(feature = "scene-io")]
+    #[error("error while parsing a scene file")]
+    Scene(serde_json::Error),
+    #[cfg(feature = "scene-io")]
+    #[error("the mesh file {0} is not supported, only .obj, .gltf and .glb files are supported")]
+    UnsupportedMeshFormat(String),
     #[cfg(not(target_arch = "wasm32"))]
     #[error("error while loading a file")]
     Load(#[from] std::io::Error),
     #[error("tried to use {0} which was not loaded")]
     NotLoaded(String),
+    #[cfg(feature = "event-io")]
+    #[error("error while parsing an event recording")]
+    EventRecording(bincode::Error),
+    #[cfg(feature = "image-io")]
+    #[error(
+        "the image {0} is {1}x{2} pixels, but it must be {3}x{4} pixels like the other images"
+    )]
+    ImageSizeMismatch(String, u32, u32, u32, u32),
+    #[cfg(feature = "image-io")]
+    #[error(
+        "a cube map needs 6 images or 1 image containing all faces, but {0} images were given"
+    )]
+    CubeMapImageCount(usize),
+    #[cfg(feature = "image-io")]
+    #[error("the image {0} of {1}x{2} pixels does not contain the faces of a cube map in the {3:?} layout")]
+    CubeMapLayoutMismatch(String, u32, u32, CubeMapLayout),
+    #[cfg(feature = "image-io")]
+    #[error("a texture array needs at least one image")]
+    EmptyTextureArray,
+    #[cfg(feature = "bundle-io")]
+    #[error("the asset bundle is corrupt: {0}")]
+    CorruptBundle(String),
+    #[cfg(feature = "bundle-io")]
+    #[error("the entry {0} in the asset bundle is corrupt")]
+    CorruptBundleEntry(String),
+    #[error("error while streaming {0}: {1}")]
+    Stream(String, String),
+    #[error("the loading of {0} was cancelled")]
+    Cancelled(String),
+    #[error("error while parsing a point chunks file: {0}")]
+    PointChunks(String),
 }
@@ -0,0 +1,274 @@
+use crate::core::*;
+use crate::io::*;
+
+//
+// The asset bundle format, where all numbers are little endian:
+//
+// magic: the 8 bytes "3DBUNDLE"
+// version: u32
+// entry count: u32
+// for each entry:
+//     path length: u32
+//     path: utf-8 bytes, relative path with '/' as separator
+//     flags: u8, where the first bit is set if the data is compressed using deflate
+//     offset: u64, the offset of the data from the start of the bundle
+//     length: u64, the length of the (possibly compressed) data
+//     size: u64, the length of the data when decompressed
+// the data of all entries
+//
+const MAGIC: &[u8; 8] = b"3DBUNDLE";
+const VERSION: u32 = 1;
+const DEFLATE_FLAG: u8 = 1;
+
+// A file in an asset bundle, where the data is still compressed if the file is deflated
+#[derive(Debug)]
+pub(crate) struct BundleEntry {
+    pub path: String,
+    pub data: Vec<u8>,
+    pub deflated: bool,
+    pub size: usize,
+}
+
+impl BundleEntry {
+    // Returns the decompressed data of the file
+    pub fn into_bytes(self) -> ThreeDResult<Vec<u8>> {
+        let bytes = if self.deflated {
+            miniz_oxide::inflate::decompress_to_vec(&self.data)
+                .map_err(|_| IOError::CorruptBundleEntry(self.path.clone()))?
+        } else {
+            self.data
+        };
+        if bytes.len() != self.size {
+            Err(IOError::CorruptBundleEntry(self.path))?;
+        }
+        Ok(bytes)
+    }
+}
+
+// Reads the table of entries in the given bundle and copies the data of each entry, without decompressing it
+pub(crate) fn read_bundle(bytes: &[u8]) -> ThreeDResult<Vec<BundleEntry>> {
+    let mut reader = Reader { bytes, position: 0 };
+    if reader.take(8)? != MAGIC {
+        Err(IOError::CorruptBundle(
+            "the file is not an asset bundle".to_string(),
+        ))?;
+    }
+    let version = reader.u32()?;
+    if version != VERSION {
+        Err(IOError::CorruptBundle(format!(
+            "the version {} is not supported",
+            version
+        )))?;
+    }
+    let count = reader.u32()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let path_length = reader.u32()? as usize;
+        let path = String::from_utf8(reader.take(path_length)?.to_vec())
+            .map_err(|_| IOError::CorruptBundle("an entry has an invalid path".to_string()))?;
+        let flags = reader.u8()?;
+        let offset = reader.u64()? as usize;
+        let length = reader.u64()? as usize;
+        let size = reader.u64()? as usize;
+        let data = offset
+            .checked_add(length)
+            .and_then(|end| bytes.get(offset..end))
+            .ok_or_else(|| IOError::CorruptBundleEntry(path.clone()))?
+            .to_vec();
+        entries.push(BundleEntry {
+            path,
+            data,
+            deflated: flags & DEFLATE_FLAG != 0,
+            size,
+        });
+    }
+    Ok(entries)
+}
+
+// Writes the given files to a bundle in the given order. Each file is compressed if that makes it smaller,
+// so already compressed formats like png and jpeg are stored as they are.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn write_bundle(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let entries = files
+        .iter()
+        .map(|(path, bytes)| {
+            let compressed = miniz_oxide::deflate::compress_to_vec(bytes, 6);
+            if compressed.len() < bytes.len() {
+                (path, compressed, DEFLATE_FLAG, bytes.len())
+            } else {
+                (path, bytes.clone(), 0, bytes.len())
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let header_length = 16
+        + entries
+            .iter()
+            .map(|(path, ..)| 4 + path.len() + 1 + 3 * 8)
+            .sum::<usize>();
+    let mut bundle = Vec::with_capacity(
+        header_length
+            + entries
+                .iter()
+                .map(|(_, data, ..)| data.len())
+                .sum::<usize>(),
+    );
+    bundle.extend_from_slice(MAGIC);
+    bundle.extend_from_slice(&VERSION.to_le_bytes());
+    bundle.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    let mut offset = header_length;
+    for (path, data, flags, size) in entries.iter() {
+        bundle.extend_from_slice(&(path.len() as u32).to_le_bytes());
+        bundle.extend_from_slice(path.as_bytes());
+        bundle.push(*flags);
+        bundle.extend_from_slice(&(offset as u64).to_le_bytes());
+        bundle.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bundle.extend_from_slice(&(*size as u64).to_le_bytes());
+        offset += data.len();
+    }
+    for (_, data, ..) in entries.iter() {
+        bundle.extend_from_slice(data);
+    }
+    bundle
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> ThreeDResult<&'a [u8]> {
+        let end = self.position.saturating_add(length);
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or_else(|| IOError::CorruptBundle("the header is truncated".to_string()))?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> ThreeDResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> ThreeDResult<u32> {
+        let mut b = [0; 4];
+        b.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(b))
+    }
+
+    fn u64(&mut self) -> ThreeDResult<u64> {
+        let mut b = [0; 8];
+        b.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> Vec<(String, Vec<u8>)> {
+        vec![
+            // Compressible
+            ("models/cube.obj".to_string(), b"v 0 0 0\n".repeat(100)),
+            // Not compressible
+            (
+                "textures/noise.bin".to_string(),
+                (0..=255u8).rev().collect(),
+            ),
+            ("empty.txt".to_string(), Vec::new()),
+        ]
+    }
+
+    #[test]
+    fn round_trip() {
+        let files = files();
+        let bundle = write_bundle(&files);
+        assert_eq!(write_bundle(&files), bundle);
+
+        let entries = read_bundle(&bundle).unwrap();
+        assert_eq!(entries.len(), files.len());
+        assert!(entries[0].deflated);
+        assert!(!entries[1].deflated);
+
+        let mut loaded = Loaded::from_bundle(&bundle).unwrap();
+        for (path, bytes) in files.iter() {
+            assert_eq!(&loaded.remove_bytes(path).unwrap(), bytes);
+        }
+        assert!(loaded.remove_bytes("missing.obj").is_err());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let directory = std::env::temp_dir().join(format!("three-d-bundle-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let files = files();
+        let paths = files
+            .iter()
+            .enumerate()
+            .map(|(i, (_, bytes))| {
+                let path = directory.join(format!("{}.bin", i));
+                std::fs::write(&path, bytes).unwrap();
+                path
+            })
+            .collect::<Vec<_>>();
+        let output = directory.join("assets.bundle");
+        Saver::save_bundle(&paths, &output).unwrap();
+
+        let mut loaded = Loaded::new();
+        loaded.insert_bytes(&output, std::fs::read(&output).unwrap());
+        let result = paths
+            .iter()
+            .map(|path| loaded.remove_bytes(path))
+            .collect::<ThreeDResult<Vec<_>>>();
+        std::fs::remove_dir_all(&directory).unwrap();
+        let result = result.unwrap();
+        for (bytes, (_, expected)) in result.iter().zip(files.iter()) {
+            assert_eq!(bytes, expected);
+        }
+    }
+
+    #[test]
+    fn truncated_bundle() {
+        let bundle = write_bundle(&files());
+        // Every truncation either cuts the header or the data of the last entries
+        for length in 0..bundle.len() {
+            let truncated = &bundle[..length];
+            let result = read_bundle(truncated).and_then(|entries| {
+                entries
+                    .into_iter()
+                    .map(|entry| entry.into_bytes())
+                    .collect::<ThreeDResult<Vec<_>>>()
+            });
+            assert!(result.is_err(), "a bundle truncated to {} bytes", length);
+        }
+    }
+
+    #[test]
+    fn corrupt_bundle() {
+        let files = files();
+        let bundle = write_bundle(&files);
+
+        let mut wrong_magic = bundle.clone();
+        wrong_magic[0] = b'X';
+        assert!(Loaded::from_bundle(&wrong_magic).is_err());
+
+        let mut wrong_version = bundle.clone();
+        wrong_version[8] = 99;
+        assert!(Loaded::from_bundle(&wrong_version).is_err());
+
+        // Corrupt the compressed data of the first entry, which follows directly after the header
+        let entries = read_bundle(&bundle).unwrap();
+        let data_start = bundle.len() - entries.iter().map(|e| e.data.len()).sum::<usize>();
+        let mut corrupt_data = bundle;
+        for byte in corrupt_data[data_start..data_start + entries[0].data.len()].iter_mut() {
+            *byte = !*byte;
+        }
+        let mut loaded = Loaded::from_bundle(&corrupt_data).unwrap();
+        let error = loaded.remove_bytes(&files[0].0).unwrap_err();
+        assert!(error.to_string().contains(&files[0].0));
+        assert_eq!(loaded.remove_bytes(&files[1].0).unwrap(), files[1].1);
+    }
+}
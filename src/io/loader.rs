@@ -4,6 +4,44 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::Duration;
+
+///
+/// Options for loading resources using [Loader::load_with_options] or [Loading::new_with_options].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LoadOptions {
+    ///
+    /// The number of times the loading of a file is tried again after it failed, for example because of a transient network error.
+    /// Files which are not found are not tried again.
+    ///
+    pub retries: u32,
+    ///
+    /// The time to wait before the first retry. The time is doubled for each of the following retries.
+    ///
+    pub retry_delay: Duration,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            retry_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl LoadOptions {
+    // The time to wait before the given retry, where 0 is the first retry
+    fn delay(&self, retry: u32) -> Duration {
+        self.retry_delay * 2u32.saturating_pow(retry)
+    }
+
+    // Whether or not to try again after the given error
+    fn should_retry(&self, retry: u32, error: &std::io::Error) -> bool {
+        retry < self.retries && error.kind() != std::io::ErrorKind::NotFound
+    }
+}
 
 ///
 /// Convenience functionality to load some resources and, when loaded, use them to create one or more objects (for example a 3D model, a skybox, a texture etc).
@@ -11,6 +49,9 @@ use std::rc::Rc;
 ///
 pub struct Loading<T> {
     load: Rc<RefCell<Option<ThreeDResult<T>>>>,
+    upload: Rc<RefCell<Option<Box<dyn Upload<Output = T>>>>>,
+    files: Rc<RefCell<HashMap<PathBuf, StreamProgress>>>,
+    file_count: usize,
 }
 
 impl<T: 'static> Loading<T> {
@@ -21,14 +62,138 @@ impl<T: 'static> Loading<T> {
         context: &Context,
         paths: &[impl AsRef<Path>],
         on_load: impl 'static + FnOnce(Context, Loaded) -> ThreeDResult<T>,
+    ) -> Self {
+        Self::new_with_options(context, paths, LoadOptions::default(), on_load)
+    }
+
+    ///
+    /// Same as [Loading::new], except that the files are loaded using the given options, for example to try again more times when the network is unreliable.
+    ///
+    pub fn new_with_options(
+        context: &Context,
+        paths: &[impl AsRef<Path>],
+        options: LoadOptions,
+        on_load: impl 'static + FnOnce(Context, Loaded) -> ThreeDResult<T>,
     ) -> Self {
         let load = Rc::new(RefCell::new(None));
         let load_clone = load.clone();
         let context_clone = context.clone();
-        Loader::load(paths, move |loaded| {
-            *load_clone.borrow_mut() = Some(on_load(context_clone, loaded));
-        });
-        Self { load }
+        let files = Rc::new(RefCell::new(HashMap::new()));
+        Loader::load_with_options(
+            paths,
+            options,
+            Self::progress_callback(&files),
+            move |loaded| {
+                *load_clone.borrow_mut() = Some(on_load(context_clone, loaded));
+            },
+        );
+        Self {
+            load,
+            upload: Rc::new(RefCell::new(None)),
+            files,
+            file_count: paths.len(),
+        }
+    }
+
+    ///
+    /// Starts loading the resources defined by `paths` and calls the `on_load` closure when everything is loaded.
+    /// In contrast to [Loading::new], the `on_load` closure returns an [Upload] of the object to the GPU (for example a [TextureUpload] or a [MeshUpload])
+    /// which is then spread over several frames by calling [Loading::progress] each frame.
+    /// The object is first available when the upload is done.
+    ///
+    pub fn new_incremental<U: 'static + Upload<Output = T>>(
+        context: &Context,
+        paths: &[impl AsRef<Path>],
+        on_load: impl 'static + FnOnce(Context, Loaded) -> ThreeDResult<U>,
+    ) -> Self {
+        let load = Rc::new(RefCell::new(None));
+        let load_clone = load.clone();
+        let upload: Rc<RefCell<Option<Box<dyn Upload<Output = T>>>>> = Rc::new(RefCell::new(None));
+        let upload_clone = upload.clone();
+        let context_clone = context.clone();
+        let files = Rc::new(RefCell::new(HashMap::new()));
+        Loader::load_with_options(
+            paths,
+            LoadOptions::default(),
+            Self::progress_callback(&files),
+            move |loaded| match on_load(context_clone, loaded) {
+                Ok(u) => {
+                    let u: Box<dyn Upload<Output = T>> = Box::new(u);
+                    *upload_clone.borrow_mut() = Some(u);
+                }
+                Err(e) => *load_clone.borrow_mut() = Some(Err(e)),
+            },
+        );
+        Self {
+            load,
+            upload,
+            files,
+            file_count: paths.len(),
+        }
+    }
+
+    // Returns a closure which stores the progress of each file in the given map
+    fn progress_callback(
+        files: &Rc<RefCell<HashMap<PathBuf, StreamProgress>>>,
+    ) -> impl 'static + FnMut(&Path, StreamProgress) {
+        let files = files.clone();
+        move |path, progress| {
+            files.borrow_mut().insert(path.to_path_buf(), progress);
+        }
+    }
+
+    ///
+    /// Returns the progress of loading the files, ie. the number of bytes loaded of all of the files and the total size of the files,
+    /// which is only known when the size of all of the files is known.
+    /// On desktop, the files are loaded before the loading is constructed, so the loading of the files is always done.
+    ///
+    pub fn load_progress(&self) -> StreamProgress {
+        let files = self.files.borrow();
+        let total_bytes = if files.len() == self.file_count {
+            files.values().map(|p| p.total_bytes).sum()
+        } else {
+            None
+        };
+        StreamProgress {
+            loaded_bytes: files.values().map(|p| p.loaded_bytes).sum(),
+            total_bytes,
+        }
+    }
+
+    ///
+    /// Returns the progress of loading the file at the given path, see [Loading::load_progress], or `None` if the loading of the file has not started.
+    ///
+    pub fn file_progress(&self, path: impl AsRef<Path>) -> Option<StreamProgress> {
+        self.files.borrow().get(path.as_ref()).cloned()
+    }
+
+    ///
+    /// Uploads the next part of an incremental upload started by [Loading::new_incremental], but not more than can be uploaded within approximately the given time budget.
+    /// Returns the fraction in the range `[0..1]` of the upload that is done, where `1.0` means that the object is loaded and ready to use.
+    ///
+    pub fn progress(&mut self, time_budget: std::time::Duration) -> f32 {
+        if self.is_loaded() {
+            return 1.0;
+        }
+        let mut upload = self.upload.borrow_mut();
+        let result = if let Some(ref mut u) = *upload {
+            u.progress(time_budget)
+        } else {
+            return 0.0;
+        };
+        match result {
+            Ok(UploadStatus::InProgress(fraction)) => fraction,
+            Ok(UploadStatus::Done(object)) => {
+                *upload = None;
+                *self.load.borrow_mut() = Some(Ok(object));
+                1.0
+            }
+            Err(e) => {
+                *upload = None;
+                *self.load.borrow_mut() = Some(Err(e));
+                1.0
+            }
+        }
     }
 
     ///
@@ -60,6 +225,8 @@ impl<T: 'static> std::ops::DerefMut for Loading<T> {
 #[derive(Default, Debug)]
 pub struct Loaded {
     loaded: HashMap<PathBuf, std::result::Result<Vec<u8>, std::io::Error>>,
+    #[cfg(feature = "bundle-io")]
+    bundled: HashMap<PathBuf, crate::io::bundle::BundleEntry>,
 }
 
 impl Loaded {
@@ -76,6 +243,7 @@ impl Loaded {
     /// The byte array then has to be deserialized to whatever type this resource is (image, 3D model etc.).
     ///
     pub fn remove_bytes(&mut self, path: impl AsRef<Path>) -> ThreeDResult<Vec<u8>> {
+        self.unbundle(path.as_ref())?;
         if let Some((path, bytes)) = self.loaded.remove_entry(path.as_ref()) {
             Ok(bytes.map_err(|_| IOError::NotLoaded(path.to_str().unwrap().to_string()))?)
         } else {
@@ -101,6 +269,7 @@ impl Loaded {
     /// The byte array then has to be deserialized to whatever type this resource is (image, 3D model etc.).
     ///
     pub fn get_bytes(&mut self, path: impl AsRef<Path>) -> ThreeDResult<&[u8]> {
+        self.unbundle(path.as_ref())?;
         if let Some(bytes) = self.loaded.get(path.as_ref()) {
             Ok(bytes
                 .as_ref()
@@ -128,12 +297,110 @@ impl Loaded {
     }
 
     ///
-    /// Inserts the given bytes into the set of loaded files which is useful if you want to load the data from an unsuported source.
-    /// The files can then be parsed as usual using the functionality on Loaded.
+    /// Inserts the given bytes into the set of loaded files which is useful if you want to load the data from an unsuported source,
+    /// for example bytes embedded in the executable using `include_bytes!` or generated procedurally.
+    /// The files can then be parsed as usual using the functionality on Loaded, just like files loaded using the [Loader].
     ///
     pub fn insert_bytes(&mut self, path: impl AsRef<Path>, bytes: Vec<u8>) {
         self.loaded.insert(path.as_ref().to_path_buf(), Ok(bytes));
     }
+
+    ///
+    /// Returns whether or not the file at the given path is loaded and not removed yet. Returns false if the loading of the file failed.
+    ///
+    pub fn is_loaded(&self, path: impl AsRef<Path>) -> bool {
+        self.loaded
+            .get(path.as_ref())
+            .map(|r| r.is_ok())
+            .unwrap_or(false)
+    }
+
+    ///
+    /// Returns the error that made the loading of the file at the given path fail, after any retries, or `None` if the file did not fail.
+    /// The other files are loaded even if one of them fails.
+    ///
+    pub fn error(&self, path: impl AsRef<Path>) -> Option<&std::io::Error> {
+        self.loaded
+            .get(path.as_ref())
+            .and_then(|r| r.as_ref().err())
+    }
+
+    ///
+    /// Returns the paths of the files which failed to load.
+    ///
+    pub fn failed_paths(&self) -> Vec<&Path> {
+        self.loaded
+            .iter()
+            .filter(|(_, r)| r.is_err())
+            .map(|(p, _)| p.as_path())
+            .collect()
+    }
+
+    ///
+    /// Constructs a new set of loaded files containing the files in the given asset bundle, see [Saver::save_bundle](crate::Saver::save_bundle).
+    /// The files are accessed by the paths they were given when the bundle was saved and each file is first decompressed when it is accessed.
+    ///
+    #[cfg(feature = "bundle-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bundle-io")))]
+    pub fn from_bundle(bytes: &[u8]) -> ThreeDResult<Self> {
+        let mut loaded = Self::new();
+        loaded.insert_bundle(bytes)?;
+        Ok(loaded)
+    }
+
+    ///
+    /// Inserts the files in the given asset bundle into the set of loaded files, see [Loaded::from_bundle].
+    /// Asset bundles loaded using the [Loader](crate::Loader), ie. files with the `.bundle` extension, are inserted automatically
+    /// the first time a file which is not a bundle is accessed.
+    ///
+    #[cfg(feature = "bundle-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bundle-io")))]
+    pub fn insert_bundle(&mut self, bytes: &[u8]) -> ThreeDResult<()> {
+        for entry in crate::io::bundle::read_bundle(bytes)? {
+            self.bundled.insert(PathBuf::from(&entry.path), entry);
+        }
+        Ok(())
+    }
+
+    // Inserts the files in the loaded asset bundles and decompresses the file at the given path if it is in a bundle and not already loaded
+    #[cfg(feature = "bundle-io")]
+    fn unbundle(&mut self, path: &Path) -> ThreeDResult<()> {
+        let is_bundle = |path: &Path| path.extension().map(|e| e == "bundle").unwrap_or(false);
+        if !is_bundle(path) {
+            let bundle_paths = self
+                .loaded
+                .iter()
+                .filter(|(p, bytes)| bytes.is_ok() && is_bundle(p))
+                .map(|(p, _)| p.clone())
+                .collect::<Vec<_>>();
+            for bundle_path in bundle_paths {
+                if let Some(Ok(bytes)) = self.loaded.remove(&bundle_path) {
+                    self.insert_bundle(&bytes)?;
+                }
+            }
+        }
+        if self.loaded.contains_key(path) {
+            return Ok(());
+        }
+        let key = if self.bundled.contains_key(path) {
+            Some(path.to_path_buf())
+        } else {
+            self.bundled
+                .keys()
+                .find(|k| k.to_str().unwrap().contains(path.to_str().unwrap()))
+                .cloned()
+        };
+        if let Some(key) = key {
+            let entry = self.bundled.remove(&key).unwrap();
+            self.loaded.insert(key, Ok(entry.into_bytes()?));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "bundle-io"))]
+    fn unbundle(&mut self, _path: &Path) -> ThreeDResult<()> {
+        Ok(())
+    }
 }
 
 ///
@@ -146,51 +413,201 @@ impl Loader {
     /// Loads all of the resources in the given paths then calls `on_done` with all of the [loaded resources](crate::Loaded).
     ///
     pub fn load(paths: &[impl AsRef<Path>], on_done: impl 'static + FnOnce(Loaded)) {
+        Self::load_with_options(paths, LoadOptions::default(), |_, _| {}, on_done)
+    }
+
+    ///
+    /// Same as [Loader::load], except that the files are loaded using the given options
+    /// and `on_progress` is called with the path and the progress of a file each time more of the file is loaded, for example to show a progress bar.
+    /// The loaded bytes of a file start from zero again if the loading of the file is tried again.
+    /// The resources which failed to load, after the retries given in the options, can be found using [Loaded::failed_paths],
+    /// while the other resources are loaded as usual.
+    ///
+    pub fn load_with_options(
+        paths: &[impl AsRef<Path>],
+        options: LoadOptions,
+        mut on_progress: impl 'static + FnMut(&Path, StreamProgress),
+        on_done: impl 'static + FnOnce(Loaded),
+    ) {
         #[cfg(target_arch = "wasm32")]
         {
-            wasm_bindgen_futures::spawn_local(Self::load_files_async(
-                paths.iter().map(|p| p.as_ref().to_path_buf()).collect(),
-                on_done,
-            ));
+            let paths: Vec<PathBuf> = paths.iter().map(|p| p.as_ref().to_path_buf()).collect();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut loaded = Loaded::new();
+                for path in paths {
+                    let url = Self::url(&path);
+                    let mut retry = 0;
+                    let result = loop {
+                        match fetch_file(&url, &mut |progress| on_progress(&path, progress)).await {
+                            Err(e) if options.should_retry(retry, &e) => {
+                                sleep(options.delay(retry)).await;
+                                retry += 1;
+                            }
+                            result => break result,
+                        }
+                    };
+                    loaded.loaded.insert(path, result);
+                }
+                on_done(loaded)
+            });
         }
         #[cfg(not(target_arch = "wasm32"))]
         {
             let mut loaded = Loaded::new();
-            for path in paths {
-                let result = if let Ok(url) = reqwest::Url::parse(path.as_ref().to_str().unwrap()) {
-                    Ok(reqwest::blocking::get(url)
-                        .map(|r| (*r.bytes().unwrap()).to_vec())
-                        .unwrap())
-                } else {
-                    std::fs::read(path.as_ref())
+            for path in paths.iter().map(|p| p.as_ref()) {
+                let mut retry = 0;
+                let result = loop {
+                    match read_file(path, &mut |progress| on_progress(path, progress)) {
+                        Err(e) if options.should_retry(retry, &e) => {
+                            std::thread::sleep(options.delay(retry));
+                            retry += 1;
+                        }
+                        result => break result,
+                    }
                 };
-                loaded.loaded.insert(path.as_ref().to_path_buf(), result);
+                loaded.loaded.insert(path.to_path_buf(), result);
             }
             on_done(loaded)
         }
     }
 
+    // Returns the url of the given path, which is either a url or a path relative to the page
     #[cfg(target_arch = "wasm32")]
-    async fn load_files_async(paths: Vec<PathBuf>, on_done: impl 'static + FnOnce(Loaded)) {
-        let mut loads = Loaded::new();
-        for path in paths.iter() {
-            let url = reqwest::Url::parse(path.to_str().unwrap()).unwrap_or_else(|_| {
-                let u = web_sys::window()
-                    .unwrap()
-                    .document()
-                    .unwrap()
-                    .url()
-                    .unwrap();
-                let p = if !u.ends_with("/") {
-                    std::path::PathBuf::from(u).parent().unwrap().join(path)
-                } else {
-                    std::path::PathBuf::from(u.clone()).join(path)
-                };
-                reqwest::Url::parse(p.to_str().unwrap()).unwrap()
-            });
-            let data = reqwest::get(url).await.unwrap().bytes().await.unwrap();
-            loads.loaded.insert(path.clone(), Ok((*data).to_vec()));
+    pub(super) fn url(path: &Path) -> reqwest::Url {
+        reqwest::Url::parse(path.to_str().unwrap()).unwrap_or_else(|_| {
+            let u = web_sys::window()
+                .unwrap()
+                .document()
+                .unwrap()
+                .url()
+                .unwrap();
+            let p = if !u.ends_with("/") {
+                std::path::PathBuf::from(u).parent().unwrap().join(path)
+            } else {
+                std::path::PathBuf::from(u.clone()).join(path)
+            };
+            reqwest::Url::parse(p.to_str().unwrap()).unwrap()
+        })
+    }
+}
+
+// Reads the file or the response of the url at the given path and calls on_progress each time more is read
+#[cfg(not(target_arch = "wasm32"))]
+fn read_file(path: &Path, on_progress: &mut dyn FnMut(StreamProgress)) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+    let (mut reader, total_bytes): (Box<dyn Read>, Option<u64>) =
+        if let Ok(url) = reqwest::Url::parse(path.to_str().unwrap()) {
+            let response = reqwest::blocking::get(url)
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| {
+                    let kind = if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
+                        std::io::ErrorKind::NotFound
+                    } else {
+                        std::io::ErrorKind::Other
+                    };
+                    std::io::Error::new(kind, e.to_string())
+                })?;
+            let total_bytes = response.content_length();
+            (Box::new(response), total_bytes)
+        } else {
+            let file = std::fs::File::open(path)?;
+            let total_bytes = file.metadata().ok().map(|m| m.len());
+            (Box::new(file), total_bytes)
+        };
+    let mut progress = StreamProgress {
+        loaded_bytes: 0,
+        total_bytes,
+    };
+    on_progress(progress);
+    let mut bytes = Vec::with_capacity(total_bytes.unwrap_or(0) as usize);
+    let mut chunk = vec![0u8; 1 << 16];
+    loop {
+        let count = match reader.read(&mut chunk) {
+            Ok(count) => count,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if count == 0 {
+            return Ok(bytes);
+        }
+        bytes.extend_from_slice(&chunk[..count]);
+        progress.loaded_bytes += count as u64;
+        on_progress(progress);
+    }
+}
+
+// Fetches the given url and calls on_progress each time more of the body has arrived
+#[cfg(target_arch = "wasm32")]
+async fn fetch_file(
+    url: &reqwest::Url,
+    on_progress: &mut dyn FnMut(StreamProgress),
+) -> std::io::Result<Vec<u8>> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    let error = |e: wasm_bindgen::JsValue| {
+        std::io::Error::new(std::io::ErrorKind::Other, format!("{:?}", e))
+    };
+    let response: web_sys::Response =
+        JsFuture::from(web_sys::window().unwrap().fetch_with_str(url.as_str()))
+            .await
+            .map_err(error)?
+            .unchecked_into();
+    if !response.ok() {
+        let kind = if response.status() == 404 {
+            std::io::ErrorKind::NotFound
+        } else {
+            std::io::ErrorKind::Other
+        };
+        return Err(std::io::Error::new(
+            kind,
+            format!("the request failed with status {}", response.status()),
+        ));
+    }
+    let total_bytes = response
+        .headers()
+        .get("Content-Length")
+        .ok()
+        .flatten()
+        .and_then(|length| length.parse().ok());
+    let mut progress = StreamProgress {
+        loaded_bytes: 0,
+        total_bytes,
+    };
+    on_progress(progress);
+    let reader: web_sys::ReadableStreamDefaultReader = response
+        .body()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "the response has no body"))?
+        .get_reader()
+        .unchecked_into();
+    let mut bytes = Vec::new();
+    loop {
+        let result = JsFuture::from(reader.read()).await.map_err(error)?;
+        let done = js_sys::Reflect::get(&result, &"done".into())
+            .map_err(error)?
+            .as_bool()
+            .unwrap_or(true);
+        if done {
+            return Ok(bytes);
         }
-        on_done(loads)
+        let value = js_sys::Reflect::get(&result, &"value".into()).map_err(error)?;
+        let chunk = js_sys::Uint8Array::new(&value).to_vec();
+        progress.loaded_bytes += chunk.len() as u64;
+        bytes.extend(chunk);
+        on_progress(progress);
     }
 }
+
+// Waits for the given duration without blocking the browser
+#[cfg(target_arch = "wasm32")]
+async fn sleep(duration: Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis() as i32,
+            )
+            .unwrap();
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
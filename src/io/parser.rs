@@ -19,9 +19,23 @@ mod gltf;
 #[cfg(feature = "gltf-io")]
 pub use self::gltf::*;
 
+#[cfg(feature = "ply-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ply-io")))]
+mod ply;
+#[doc(inline)]
+#[cfg(feature = "ply-io")]
+pub use ply::*;
+
 #[cfg(feature = "image-io")]
 #[cfg_attr(docsrs, doc(cfg(feature = "image-io")))]
 mod img;
 #[cfg(feature = "image-io")]
 #[doc(inline)]
 pub use img::*;
+
+#[cfg(feature = "scene-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scene-io")))]
+mod scene;
+#[doc(inline)]
+#[cfg(feature = "scene-io")]
+pub use scene::*;
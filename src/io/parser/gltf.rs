@@ -71,6 +71,8 @@ fn parse_tree<'a>(
                     positions.push(value[2]);
                 }
 
+                let vertex_count = positions.len() / 3;
+
                 let normals = reader
                     .read_normals()
                     .map(|values| values.flatten().collect::<Vec<_>>());
@@ -121,64 +123,94 @@ fn parse_tree<'a>(
                 if !parsed {
                     let pbr = material.pbr_metallic_roughness();
                     let color = pbr.base_color_factor();
-                    let albedo_texture = if let Some(info) = pbr.base_color_texture() {
-                        Some(parse_texture(loaded, path, buffers, info.texture())?)
-                    } else {
-                        None
-                    };
-                    let metallic_roughness_texture =
-                        if let Some(info) = pbr.metallic_roughness_texture() {
-                            Some(parse_texture(loaded, path, buffers, info.texture())?)
+                    let (albedo_texture, albedo_texture_transform, albedo_texture_uv_set) =
+                        if let Some(info) = pbr.base_color_texture() {
+                            (
+                                Some(parse_texture(loaded, path, buffers, info.texture())?),
+                                parse_texture_transform(&info),
+                                parse_uv_set(&info),
+                            )
                         } else {
-                            None
+                            (None, Mat3::identity(), 0)
                         };
-                    let (normal_texture, normal_scale) =
+                    let (
+                        metallic_roughness_texture,
+                        metallic_roughness_texture_transform,
+                        metallic_roughness_texture_uv_set,
+                    ) = if let Some(info) = pbr.metallic_roughness_texture() {
+                        (
+                            Some(parse_texture(loaded, path, buffers, info.texture())?),
+                            parse_texture_transform(&info),
+                            parse_uv_set(&info),
+                        )
+                    } else {
+                        (None, Mat3::identity(), 0)
+                    };
+                    let (normal_texture, normal_scale, normal_texture_uv_set) =
                         if let Some(normal) = material.normal_texture() {
                             (
                                 Some(parse_texture(loaded, path, buffers, normal.texture())?),
                                 normal.scale(),
+                                normal.tex_coord().min(1) as u8,
                             )
                         } else {
-                            (None, 1.0)
+                            (None, 1.0, 0)
                         };
-                    let (occlusion_texture, occlusion_strength) =
+                    let (occlusion_texture, occlusion_strength, occlusion_texture_uv_set) =
                         if let Some(occlusion) = material.occlusion_texture() {
                             (
                                 Some(parse_texture(loaded, path, buffers, occlusion.texture())?),
                                 occlusion.strength(),
+                                occlusion.tex_coord().min(1) as u8,
                             )
                         } else {
-                            (None, 1.0)
+                            (None, 1.0, 0)
+                        };
+                    let (emissive_texture, emissive_texture_transform, emissive_texture_uv_set) =
+                        if let Some(info) = material.emissive_texture() {
+                            (
+                                Some(parse_texture(loaded, path, buffers, info.texture())?),
+                                parse_texture_transform(&info),
+                                parse_uv_set(&info),
+                            )
+                        } else {
+                            (None, Mat3::identity(), 0)
                         };
-                    let emissive_texture = if let Some(info) = material.emissive_texture() {
-                        Some(parse_texture(loaded, path, buffers, info.texture())?)
-                    } else {
-                        None
-                    };
                     cpu_materials.push(CPUMaterial {
                         name: material_name.clone(),
                         albedo: Color::from_rgba_slice(&color),
                         albedo_texture,
+                        albedo_texture_transform,
+                        albedo_texture_uv_set,
                         metallic: pbr.metallic_factor(),
                         roughness: pbr.roughness_factor(),
                         metallic_roughness_texture,
+                        metallic_roughness_texture_transform,
+                        metallic_roughness_texture_uv_set,
                         normal_texture,
+                        // The gltf crate only exposes the texture transform extension of textures with a texture info,
+                        // which does not include the normal and occlusion textures
+                        normal_texture_transform: Mat3::identity(),
+                        normal_texture_uv_set,
                         normal_scale,
                         occlusion_texture,
+                        occlusion_texture_transform: Mat3::identity(),
+                        occlusion_texture_uv_set,
                         occlusion_strength,
                         occlusion_metallic_roughness_texture: None,
                         emissive: Color::from_rgb_slice(&material.emissive_factor()),
                         emissive_texture,
+                        emissive_texture_transform,
+                        emissive_texture_uv_set,
                         alpha_cutout: None,
+                        double_sided: material.double_sided(),
                     });
                 }
 
                 let colors = reader.read_colors(0).map(|values| {
                     let mut cols = Vec::new();
-                    for value in values.into_rgb_u8() {
-                        cols.push(value[0]);
-                        cols.push(value[1]);
-                        cols.push(value[2]);
+                    for value in values.into_rgba_u8() {
+                        cols.extend_from_slice(&value);
                     }
                     cols
                 });
@@ -191,6 +223,35 @@ fn parse_tree<'a>(
                     }
                     uvs
                 });
+                let uvs2 = reader.read_tex_coords(1).map(|values| {
+                    let mut uvs = Vec::new();
+                    for value in values.into_f32() {
+                        uvs.push(value[0]);
+                        uvs.push(value[1]);
+                    }
+                    uvs
+                });
+
+                let joint_indices = reader
+                    .read_joints(0)
+                    .map(|values| values.into_u16().flatten().collect::<Vec<_>>());
+                let joint_weights = reader
+                    .read_weights(0)
+                    .map(|values| values.into_f32().flatten().collect::<Vec<_>>());
+
+                let weights = mesh.weights().unwrap_or(&[]);
+                let morph_targets = reader
+                    .read_morph_targets()
+                    .enumerate()
+                    .map(|(i, (positions, normals, _))| MorphTarget {
+                        name: format!("index {}", i),
+                        positions: positions
+                            .map(|values| values.flatten().collect::<Vec<_>>())
+                            .unwrap_or_else(|| vec![0.0; vertex_count * 3]),
+                        normals: normals.map(|values| values.flatten().collect::<Vec<_>>()),
+                        weight: weights.get(i).cloned().unwrap_or(0.0),
+                    })
+                    .collect::<Vec<_>>();
 
                 cpu_meshes.push(CPUMesh {
                     name: name.clone(),
@@ -199,8 +260,14 @@ fn parse_tree<'a>(
                     tangents,
                     indices,
                     colors,
+                    // The vertex colors of glTF files are always linear
+                    color_space: ColorSpace::Linear,
                     uvs,
+                    uvs2,
                     material_name: Some(material_name),
+                    morph_targets,
+                    joint_indices,
+                    joint_weights,
                 });
             }
         }
@@ -220,7 +287,7 @@ fn parse_texture<'a>(
 ) -> ThreeDResult<CPUTexture<u8>> {
     let gltf_image = gltf_texture.source();
     let gltf_source = gltf_image.source();
-    let tex = match gltf_source {
+    let mut tex = match gltf_source {
         ::gltf::image::Source::Uri { uri, .. } => loaded.image(path.join(Path::new(uri)))?,
         ::gltf::image::Source::View { view, .. } => {
             let mut bytes = Vec::with_capacity(view.length());
@@ -235,6 +302,59 @@ fn parse_texture<'a>(
             image_from_bytes(&bytes)?
         }
     };
-    // TODO: Parse sampling parameters
+    let sampler = gltf_texture.sampler();
+    tex.wrap_s = parse_wrapping(sampler.wrap_s());
+    tex.wrap_t = parse_wrapping(sampler.wrap_t());
+    if let Some(filter) = sampler.mag_filter() {
+        tex.mag_filter = match filter {
+            ::gltf::texture::MagFilter::Nearest => Interpolation::Nearest,
+            ::gltf::texture::MagFilter::Linear => Interpolation::Linear,
+        };
+    }
+    if let Some(filter) = sampler.min_filter() {
+        use ::gltf::texture::MinFilter;
+        let (min_filter, mip_map_filter) = match filter {
+            MinFilter::Nearest => (Interpolation::Nearest, None),
+            MinFilter::Linear => (Interpolation::Linear, None),
+            MinFilter::NearestMipmapNearest => {
+                (Interpolation::Nearest, Some(Interpolation::Nearest))
+            }
+            MinFilter::LinearMipmapNearest => (Interpolation::Linear, Some(Interpolation::Nearest)),
+            MinFilter::NearestMipmapLinear => (Interpolation::Nearest, Some(Interpolation::Linear)),
+            MinFilter::LinearMipmapLinear => (Interpolation::Linear, Some(Interpolation::Linear)),
+        };
+        tex.min_filter = min_filter;
+        tex.mip_map_filter = mip_map_filter;
+    }
     Ok(tex)
 }
+
+// The default wrapping in glTF is repeat, so a tiled texture is not clamped
+fn parse_wrapping(wrapping: ::gltf::texture::WrappingMode) -> Wrapping {
+    match wrapping {
+        ::gltf::texture::WrappingMode::ClampToEdge => Wrapping::ClampToEdge,
+        ::gltf::texture::WrappingMode::MirroredRepeat => Wrapping::MirroredRepeat,
+        ::gltf::texture::WrappingMode::Repeat => Wrapping::Repeat,
+    }
+}
+
+// Only two sets of uv coordinates are loaded, see CPUMesh::uvs2, where the KHR_texture_transform extension can override the set
+fn parse_uv_set(info: &::gltf::texture::Info) -> u8 {
+    info.texture_transform()
+        .and_then(|transform| transform.tex_coord())
+        .unwrap_or(info.tex_coord())
+        .min(1) as u8
+}
+
+// The KHR_texture_transform extension applies the scale, then the rotation and finally the offset to the uv coordinates
+fn parse_texture_transform(info: &::gltf::texture::Info) -> Mat3 {
+    if let Some(transform) = info.texture_transform() {
+        let offset = transform.offset();
+        let scale = transform.scale();
+        Mat3::from_translation(vec2(offset[0], offset[1]))
+            * Mat3::from_angle_z(radians(-transform.rotation()))
+            * Mat3::from_nonuniform_scale(scale[0], scale[1])
+    } else {
+        Mat3::identity()
+    }
+}
@@ -130,6 +130,7 @@ impl Loaded {
     /// the [image](https://crates.io/crates/image/main.rs) crate.
     /// The CPUTextureCube can then be used to create a [TextureCubeMap].
     ///
+    #[deprecated = "use cube_map instead"]
     pub fn cube_image<P: AsRef<Path>>(
         &mut self,
         right_path: P,
@@ -164,6 +165,255 @@ impl Loaded {
             wrap_r: right.wrap_s,
         })
     }
+
+    ///
+    /// Deserialize the loaded image resources at the given paths into a [CPUTextureCube] using
+    /// the [image](https://crates.io/crates/image/main.rs) crate.
+    /// Either 6 paths to the right, left, top, bottom, front and back faces are given, which must all have the same size,
+    /// or a single path to an image containing all faces in one of the layouts detected by [CubeMapLayout::Auto], see [Loaded::cube_map_with_layout].
+    /// If the images have different formats, they are all converted to the format with the most channels.
+    /// The CPUTextureCube can then be used to create a [TextureCubeMap].
+    ///
+    pub fn cube_map<P: AsRef<Path>>(&mut self, paths: &[P]) -> ThreeDResult<CPUTextureCube<u8>> {
+        match paths.len() {
+            1 => self.cube_map_with_layout(&paths[0], CubeMapLayout::Auto),
+            6 => {
+                let mut faces = self.images_of_same_size(paths)?.into_iter();
+                let mut face = || faces.next().unwrap();
+                let right = face();
+                Ok(CPUTextureCube {
+                    left_data: face().data,
+                    top_data: face().data,
+                    bottom_data: face().data,
+                    front_data: face().data,
+                    back_data: face().data,
+                    width: right.width,
+                    height: right.height,
+                    format: right.format,
+                    min_filter: right.min_filter,
+                    mag_filter: right.mag_filter,
+                    mip_map_filter: right.mip_map_filter,
+                    wrap_s: right.wrap_s,
+                    wrap_t: right.wrap_t,
+                    wrap_r: right.wrap_s,
+                    right_data: right.data,
+                })
+            }
+            count => Err(IOError::CubeMapImageCount(count))?,
+        }
+    }
+
+    ///
+    /// Deserialize the loaded image resource at the given path, which contains all 6 faces of a cube map in the given layout, into a [CPUTextureCube] using
+    /// the [image](https://crates.io/crates/image/main.rs) crate.
+    /// The faces are cut out of the image on the CPU, so the image must consist of square faces of equal size.
+    /// The CPUTextureCube can then be used to create a [TextureCubeMap].
+    ///
+    pub fn cube_map_with_layout(
+        &mut self,
+        path: impl AsRef<Path>,
+        layout: CubeMapLayout,
+    ) -> ThreeDResult<CPUTextureCube<u8>> {
+        let image = self.image(&path)?;
+        let (width, height) = (image.width, image.height);
+        let layout_error = || {
+            IOError::CubeMapLayoutMismatch(
+                path.as_ref().display().to_string(),
+                width,
+                height,
+                layout,
+            )
+        };
+        let detected_layout = match layout {
+            CubeMapLayout::Auto => {
+                if 3 * width == 4 * height {
+                    CubeMapLayout::HorizontalCross
+                } else if 4 * width == 3 * height {
+                    CubeMapLayout::VerticalCross
+                } else if width == 6 * height {
+                    CubeMapLayout::HorizontalStrip
+                } else if 6 * width == height {
+                    CubeMapLayout::VerticalStrip
+                } else {
+                    Err(layout_error())?
+                }
+            }
+            _ => layout,
+        };
+        // The column and row of the right, left, top, bottom, front and back faces, the back face of a vertical cross is upside down
+        let (columns, rows, faces) = match detected_layout {
+            CubeMapLayout::HorizontalCross => {
+                (4, 3, [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)])
+            }
+            CubeMapLayout::VerticalCross => {
+                (3, 4, [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (1, 3)])
+            }
+            CubeMapLayout::HorizontalStrip => {
+                (6, 1, [(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0)])
+            }
+            CubeMapLayout::VerticalStrip => {
+                (1, 6, [(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (0, 5)])
+            }
+            CubeMapLayout::Auto => unreachable!(),
+        };
+        if width % columns != 0 || height % rows != 0 || width / columns != height / rows {
+            Err(layout_error())?;
+        }
+        let size = width / columns;
+        let face = |index: usize| {
+            let (column, row) = faces[index];
+            cut_out_face(
+                &image,
+                size,
+                column,
+                row,
+                index == 5 && detected_layout == CubeMapLayout::VerticalCross,
+            )
+        };
+        Ok(CPUTextureCube {
+            right_data: face(0),
+            left_data: face(1),
+            top_data: face(2),
+            bottom_data: face(3),
+            front_data: face(4),
+            back_data: face(5),
+            width: size,
+            height: size,
+            format: image.format,
+            min_filter: image.min_filter,
+            mag_filter: image.mag_filter,
+            mip_map_filter: image.mip_map_filter,
+            wrap_s: image.wrap_s,
+            wrap_t: image.wrap_t,
+            wrap_r: image.wrap_s,
+        })
+    }
+
+    ///
+    /// Deserialize the loaded image resources at the given paths into a [CPUTexture2DArray] with one layer per image using
+    /// the [image](https://crates.io/crates/image/main.rs) crate, for example the layers of a terrain material.
+    /// The images must all have the same size and if they have different formats, they are all converted to the format with the most channels.
+    /// The CPUTexture2DArray can then be used to create a [Texture2DArray].
+    ///
+    pub fn texture_array<P: AsRef<Path>>(
+        &mut self,
+        paths: &[P],
+    ) -> ThreeDResult<CPUTexture2DArray<u8>> {
+        if paths.is_empty() {
+            Err(IOError::EmptyTextureArray)?;
+        }
+        let images = self.images_of_same_size(paths)?;
+        let first = &images[0];
+        Ok(CPUTexture2DArray {
+            width: first.width,
+            height: first.height,
+            format: first.format,
+            min_filter: first.min_filter,
+            mag_filter: first.mag_filter,
+            mip_map_filter: first.mip_map_filter,
+            wrap_s: first.wrap_s,
+            wrap_t: first.wrap_t,
+            layers: images.into_iter().map(|image| image.data).collect(),
+        })
+    }
+
+    // Loads the images at the given paths, checks that they have the same size as the first image and converts them to the same format
+    fn images_of_same_size<P: AsRef<Path>>(
+        &mut self,
+        paths: &[P],
+    ) -> ThreeDResult<Vec<CPUTexture<u8>>> {
+        let mut images = Vec::with_capacity(paths.len());
+        for path in paths {
+            let image = self.image(path)?;
+            if let Some(first) = images.first() {
+                let first: &CPUTexture<u8> = first;
+                if image.width != first.width || image.height != first.height {
+                    Err(IOError::ImageSizeMismatch(
+                        path.as_ref().display().to_string(),
+                        image.width,
+                        image.height,
+                        first.width,
+                        first.height,
+                    ))?;
+                }
+            }
+            images.push(image);
+        }
+        if let Some(format) = images
+            .iter()
+            .map(|image| image.format)
+            .max_by_key(|format| format.color_channel_count())
+        {
+            for image in images.iter_mut() {
+                convert_format(image, format);
+            }
+        }
+        Ok(images)
+    }
+}
+
+///
+/// The layout of the 6 faces of a cube map in a single image, see [Loaded::cube_map_with_layout].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CubeMapLayout {
+    /// The layout is detected from the aspect ratio of the image, which is 4:3 for a horizontal cross, 3:4 for a vertical cross, 6:1 for a horizontal strip and 1:6 for a vertical strip.
+    Auto,
+    /// Four columns and three rows, where the middle row contains the left, front, right and back faces and the top and bottom faces are above and below the front face.
+    HorizontalCross,
+    /// Three columns and four rows, where the second row contains the left, front and right faces, the top and bottom faces are above and below the front face
+    /// and the back face is upside down below the bottom face.
+    VerticalCross,
+    /// The right, left, top, bottom, front and back faces from left to right.
+    HorizontalStrip,
+    /// The right, left, top, bottom, front and back faces from top to bottom.
+    VerticalStrip,
+}
+
+// Returns the pixels of the square face with the given size at the given column and row of the image, optionally rotated 180 degrees
+fn cut_out_face(image: &CPUTexture<u8>, size: u32, column: u32, row: u32, rotate: bool) -> Vec<u8> {
+    let channels = image.format.color_channel_count() as usize;
+    let mut data = Vec::with_capacity(size as usize * size as usize * channels);
+    for y in 0..size {
+        for x in 0..size {
+            let (x, y) = if rotate {
+                (size - 1 - x, size - 1 - y)
+            } else {
+                (x, y)
+            };
+            let index = ((row * size + y) as usize * image.width as usize
+                + (column * size + x) as usize)
+                * channels;
+            data.extend_from_slice(&image.data[index..index + channels]);
+        }
+    }
+    data
+}
+
+// Converts the pixels of the image to the given format, where a single channel is interpreted as luminance and two channels as luminance and alpha
+fn convert_format(image: &mut CPUTexture<u8>, format: Format) {
+    if image.format == format {
+        return;
+    }
+    let from = image.format.color_channel_count() as usize;
+    let to = format.color_channel_count() as usize;
+    let mut data = Vec::with_capacity(image.data.len() / from * to);
+    for pixel in image.data.chunks(from) {
+        let rgba = match from {
+            1 => [pixel[0], pixel[0], pixel[0], 255],
+            2 => [pixel[0], pixel[0], pixel[0], pixel[1]],
+            3 => [pixel[0], pixel[1], pixel[2], 255],
+            _ => [pixel[0], pixel[1], pixel[2], pixel[3]],
+        };
+        match to {
+            1 => data.push(rgba[0]),
+            2 => data.extend_from_slice(&[rgba[0], rgba[3]]),
+            3 => data.extend_from_slice(&rgba[..3]),
+            _ => data.extend_from_slice(&rgba),
+        }
+    }
+    image.data = data;
+    image.format = format;
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -5,160 +5,658 @@ use std::path::Path;
 
 impl Loaded {
     ///
-    /// Deserialize a loaded .obj file resource and .mtl material file resource (if present) into a list of meshes and materials.
-    /// It uses the [wavefront-obj](https://crates.io/crates/wavefront_obj/main.rs) crate.
+    /// Deserialize a loaded .obj file resource and the .mtl material file resources it refers to (if present) into a list of meshes,
+    /// each with the material it is rendered with or `None` if no material is specified.
+    /// The .mtl files and the textures they refer to must be loaded as well, their paths are relative to the .obj file and the .mtl file respectively.
+    ///
+    /// Each combination of group (`g` or `o`) and material (`usemtl`) results in a separate mesh.
+    /// Faces with more than three corners are triangulated, negative indices are relative to the end of the vertex data read so far,
+    /// normals are computed if they are missing and uv coordinates are omitted if they are missing.
     ///
     pub fn obj(
         &mut self,
         path: impl AsRef<Path>,
-    ) -> ThreeDResult<(Vec<CPUMesh>, Vec<CPUMaterial>)> {
+    ) -> ThreeDResult<Vec<(CPUMesh, Option<CPUMaterial>)>> {
         let obj_bytes = self.remove_bytes(path.as_ref())?;
-        let obj = wavefront_obj::obj::parse(String::from_utf8(obj_bytes).unwrap())?;
-        let p = path.as_ref().parent().unwrap();
+        let obj = parse_obj(
+            &String::from_utf8_lossy(&obj_bytes),
+            path.as_ref().to_str().unwrap(),
+        )?;
+        let p = path.as_ref().parent().unwrap_or(Path::new(""));
 
         // Parse materials
-        let mut cpu_materials = Vec::new();
-        if let Some(material_library) = obj.material_library {
-            let bytes = self.remove_bytes(p.join(material_library).to_str().unwrap())?;
-            let materials = wavefront_obj::mtl::parse(String::from_utf8(bytes).unwrap())?.materials;
-
+        let mut cpu_materials = HashMap::new();
+        for material_library in obj.material_libraries.iter() {
+            let mtl_path = p.join(material_library);
+            let bytes = self.remove_bytes(&mtl_path)?;
+            let materials =
+                parse_mtl(&String::from_utf8_lossy(&bytes), mtl_path.to_str().unwrap())?;
+            let mtl_directory = mtl_path.parent().unwrap_or(Path::new(""));
             for material in materials {
-                let color = if material.color_diffuse.r != material.color_diffuse.g
-                    || material.color_diffuse.g != material.color_diffuse.b
-                {
-                    material.color_diffuse
-                } else if material.color_specular.r != material.color_specular.g
-                    || material.color_specular.g != material.color_specular.b
-                {
-                    material.color_specular
-                } else if material.color_ambient.r != material.color_ambient.g
-                    || material.color_ambient.g != material.color_ambient.b
-                {
-                    material.color_ambient
-                } else {
-                    material.color_diffuse
-                };
+                let cpu_material = self.mtl_material(mtl_directory, material)?;
+                cpu_materials.insert(cpu_material.name.clone(), cpu_material);
+            }
+        }
 
-                let normal_texture = if let Some(ref texture_name) = material.bump_map {
-                    Some(self.image(p.join(texture_name))?)
-                } else {
-                    None
-                };
-                let albedo_texture = if let Some(ref texture_name) = material.diffuse_map {
-                    Some(self.image(p.join(texture_name))?)
-                } else {
-                    None
-                };
+        // Build meshes
+        Ok(obj
+            .groups
+            .into_iter()
+            .filter(|group| !group.indices.is_empty())
+            .map(|group| {
+                let cpu_material = group
+                    .material_name
+                    .as_ref()
+                    .and_then(|name| cpu_materials.get(name))
+                    .cloned();
+                (group.into_cpu_mesh(), cpu_material)
+            })
+            .collect())
+    }
+
+    fn mtl_material(
+        &mut self,
+        directory: &Path,
+        material: MtlMaterial,
+    ) -> ThreeDResult<CPUMaterial> {
+        let is_colored = |c: [f32; 3]| c[0] != c[1] || c[1] != c[2];
+        let color = if is_colored(material.diffuse) {
+            material.diffuse
+        } else if is_colored(material.specular) {
+            material.specular
+        } else if is_colored(material.ambient) {
+            material.ambient
+        } else {
+            material.diffuse
+        };
+
+        let albedo_texture = if let Some(ref texture_name) = material.diffuse_map {
+            Some(self.image(directory.join(texture_name))?)
+        } else {
+            None
+        };
+        let normal_texture = if let Some(ref texture_name) = material.bump_map {
+            Some(self.image(directory.join(texture_name))?)
+        } else {
+            None
+        };
+        // The specular intensity is used as the metallic value, so the specular map is converted to a metallic roughness texture
+        let metallic_roughness_texture = if let Some(ref texture_name) = material.specular_map {
+            Some(specular_to_metallic_roughness(
+                self.image(directory.join(texture_name))?,
+            ))
+        } else {
+            None
+        };
+
+        Ok(CPUMaterial {
+            name: material.name,
+            albedo: Color::from_rgba_slice(&[color[0], color[1], color[2], material.alpha]),
+            albedo_texture,
+            metallic: (material.specular[0] + material.specular[1] + material.specular[2]) / 3.0,
+            roughness: if material.specular_exponent > 0.1 {
+                (1.999 / material.specular_exponent).sqrt().min(1.0)
+            } else {
+                1.0
+            },
+            metallic_roughness_texture,
+            normal_texture,
+            emissive: Color::from_rgba_slice(&[
+                material.emissive[0],
+                material.emissive[1],
+                material.emissive[2],
+                1.0,
+            ]),
+            ..Default::default()
+        })
+    }
+}
+
+// Stores the specular intensity in the blue (metallic) channel and full roughness in the green channel
+fn specular_to_metallic_roughness(texture: CPUTexture<u8>) -> CPUTexture<u8> {
+    let channels = texture.format.color_channel_count() as usize;
+    let data = texture
+        .data
+        .chunks(channels)
+        .flat_map(|pixel| {
+            let intensity = if channels >= 3 {
+                ((pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3) as u8
+            } else {
+                pixel[0]
+            };
+            vec![0, 255, intensity]
+        })
+        .collect();
+    CPUTexture {
+        data,
+        format: Format::RGB,
+        ..texture
+    }
+}
+
+// The key of a unique vertex, ie. the position, uv coordinate and normal index of a face corner
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+struct ObjData {
+    material_libraries: Vec<String>,
+    groups: Vec<ObjGroup>,
+}
+
+// The faces of a group with the same material
+struct ObjGroup {
+    name: String,
+    material_name: Option<String>,
+    vertices: HashMap<VertexKey, u32>,
+    positions: Vec<f32>,
+    uvs: Vec<f32>,
+    normals: Vec<f32>,
+    indices: Vec<u32>,
+    has_uvs: bool,
+    has_normals: bool,
+}
 
-                cpu_materials.push(CPUMaterial {
-                    name: material.name,
-                    albedo: Color::from_rgba_slice(&[
-                        color.r as f32,
-                        color.g as f32,
-                        color.b as f32,
-                        material.alpha as f32,
-                    ]),
-                    albedo_texture,
-                    metallic: ((material.color_specular.r
-                        + material.color_specular.g
-                        + material.color_specular.b)
-                        / 3.0) as f32,
-                    roughness: if material.specular_coefficient > 0.1 {
-                        ((1.999 / material.specular_coefficient).sqrt() as f32).min(1.0)
-                    } else {
-                        1.0
-                    },
-                    normal_texture,
-                    ..Default::default()
-                });
+impl ObjGroup {
+    fn new(name: String, material_name: Option<String>) -> Self {
+        Self {
+            name,
+            material_name,
+            vertices: HashMap::new(),
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+            has_uvs: true,
+            has_normals: true,
+        }
+    }
+
+    fn add_vertex(&mut self, key: VertexKey, positions: &[Vec3], uvs: &[Vec2], normals: &[Vec3]) {
+        let next_index = self.vertices.len() as u32;
+        let index = *self.vertices.entry(key).or_insert(next_index);
+        if index == next_index {
+            let (position_index, uv_index, normal_index) = key;
+            let position = positions[position_index];
+            self.positions
+                .extend_from_slice(&[position.x, position.y, position.z]);
+            match uv_index {
+                Some(i) => self.uvs.extend_from_slice(&[uvs[i].x, 1.0 - uvs[i].y]),
+                None => {
+                    self.has_uvs = false;
+                    self.uvs.extend_from_slice(&[0.0, 0.0]);
+                }
+            }
+            match normal_index {
+                Some(i) => {
+                    self.normals
+                        .extend_from_slice(&[normals[i].x, normals[i].y, normals[i].z])
+                }
+                None => {
+                    self.has_normals = false;
+                    self.normals.extend_from_slice(&[0.0, 0.0, 0.0]);
+                }
             }
         }
+        self.indices.push(index);
+    }
 
-        // Parse meshes
-        let mut cpu_meshes = Vec::new();
-        for object in obj.objects.iter() {
-            // Objects consisting of several meshes with different materials
-            for mesh in object.geometry.iter() {
-                // All meshes with different materials
-                let mut positions = Vec::new();
-                let mut normals = Vec::new();
-                let mut uvs = Vec::new();
-                let mut indices = Vec::new();
-
-                let mut map: HashMap<usize, usize> = HashMap::new();
-
-                let mut process = |i: wavefront_obj::obj::VTNIndex| {
-                    let mut index = map.get(&i.0).map(|v| *v);
-
-                    let uvw = i.1.map(|tex_index| object.tex_vertices[tex_index]);
-                    let normal = i.2.map(|normal_index| object.normals[normal_index]);
-
-                    if let Some(ind) = index {
-                        if let Some(tex) = uvw {
-                            if ((uvs[ind * 2] - tex.u as f32) as f32).abs() > std::f32::EPSILON
-                                || ((uvs[ind * 2 + 1] - tex.v as f32) as f32).abs()
-                                    > std::f32::EPSILON
-                            {
-                                index = None;
-                            }
-                        }
-                        if let Some(n) = normal {
-                            if ((normals[ind * 3] - n.x as f32) as f32).abs() > std::f32::EPSILON
-                                || ((normals[ind * 3 + 1] - n.y as f32) as f32).abs()
-                                    > std::f32::EPSILON
-                                || ((normals[ind * 3 + 2] - n.z as f32) as f32).abs()
-                                    > std::f32::EPSILON
-                            {
-                                index = None;
-                            }
-                        }
-                    }
+    fn into_cpu_mesh(self) -> CPUMesh {
+        let mut cpu_mesh = CPUMesh {
+            name: self.name,
+            material_name: self.material_name,
+            positions: self.positions,
+            indices: Some(Indices::U32(self.indices)),
+            normals: if self.has_normals {
+                Some(self.normals)
+            } else {
+                None
+            },
+            uvs: if self.has_uvs { Some(self.uvs) } else { None },
+            ..Default::default()
+        };
+        if cpu_mesh.normals.is_none() {
+            cpu_mesh.compute_normals();
+        }
+        cpu_mesh
+    }
+}
 
-                    if index.is_none() {
-                        index = Some(positions.len() / 3);
-                        map.insert(i.0, index.unwrap());
-                        let position = object.vertices[i.0];
-                        positions.push(position.x as f32);
-                        positions.push(position.y as f32);
-                        positions.push(position.z as f32);
-
-                        if let Some(tex) = uvw {
-                            uvs.push(tex.u as f32);
-                            uvs.push(1.0 - tex.v as f32);
-                        }
-                        if let Some(n) = normal {
-                            normals.push(n.x as f32);
-                            normals.push(n.y as f32);
-                            normals.push(n.z as f32);
-                        }
-                    }
+fn parse_obj(source: &str, path: &str) -> ThreeDResult<ObjData> {
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = Vec::new();
+    let mut material_libraries = Vec::new();
+    let mut groups: Vec<ObjGroup> = Vec::new();
+    // The index into the groups of each combination of group name and material name
+    let mut group_indices: HashMap<(String, Option<String>), usize> = HashMap::new();
+    let mut current_name = "default".to_string();
+    let mut current_material = None;
+    let mut current_group = None;
 
-                    indices.push(index.unwrap() as u32);
+    for (line_number, line) in logical_lines(source) {
+        let error =
+            |message: String| IOError::Obj(format!("{}:{}: {}", path, line_number, message));
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let arguments: Vec<&str> = tokens.collect();
+        match keyword {
+            "v" => {
+                let v = parse_floats(&arguments, 3).map_err(error)?;
+                positions.push(vec3(v[0], v[1], v[2]));
+            }
+            "vt" => {
+                let v = parse_floats(&arguments, 1).map_err(error)?;
+                uvs.push(vec2(v[0], v.get(1).cloned().unwrap_or(0.0)));
+            }
+            "vn" => {
+                let v = parse_floats(&arguments, 3).map_err(error)?;
+                normals.push(vec3(v[0], v[1], v[2]));
+            }
+            "f" => {
+                if arguments.len() < 3 {
+                    Err(error(format!(
+                        "a face needs at least 3 corners, but has {}",
+                        arguments.len()
+                    )))?;
+                }
+                let mut corners = Vec::with_capacity(arguments.len());
+                for argument in arguments.iter() {
+                    corners.push(
+                        parse_corner(argument, positions.len(), uvs.len(), normals.len())
+                            .map_err(error)?,
+                    );
+                }
+                let group_index = match current_group {
+                    Some(index) => index,
+                    None => {
+                        let key = (current_name.clone(), current_material.clone());
+                        let index = *group_indices.entry(key).or_insert_with(|| {
+                            groups.push(ObjGroup::new(
+                                current_name.clone(),
+                                current_material.clone(),
+                            ));
+                            groups.len() - 1
+                        });
+                        current_group = Some(index);
+                        index
+                    }
                 };
-                for shape in mesh.shapes.iter() {
-                    // All triangles with same material
-                    match shape.primitive {
-                        wavefront_obj::obj::Primitive::Triangle(i0, i1, i2) => {
-                            process(i0);
-                            process(i1);
-                            process(i2);
-                        }
-                        _ => {}
+                // Fan triangulation, which is correct for convex polygons
+                let group = &mut groups[group_index];
+                for i in 1..corners.len() - 1 {
+                    for corner in [corners[0], corners[i], corners[i + 1]].iter() {
+                        group.add_vertex(*corner, &positions, &uvs, &normals);
                     }
                 }
+            }
+            "g" | "o" => {
+                if !arguments.is_empty() {
+                    current_name = arguments.join(" ");
+                    current_group = None;
+                }
+            }
+            "usemtl" => {
+                current_material = if arguments.is_empty() {
+                    None
+                } else {
+                    Some(arguments.join(" "))
+                };
+                current_group = None;
+            }
+            "mtllib" => {
+                material_libraries.extend(arguments.iter().map(|a| normalize_path(a)));
+            }
+            // Points, lines, smoothing groups and unsupported statements are ignored
+            _ => {}
+        }
+    }
+    Ok(ObjData {
+        material_libraries,
+        groups,
+    })
+}
+
+// Parses a face corner of the form v, v/vt, v//vn or v/vt/vn into zero based indices, negative indices are relative to the given counts
+fn parse_corner(
+    corner: &str,
+    position_count: usize,
+    uv_count: usize,
+    normal_count: usize,
+) -> Result<VertexKey, String> {
+    let mut parts = corner.split('/');
+    let position = parse_index(parts.next(), position_count, "vertex")?
+        .ok_or_else(|| format!("the face corner {} has no vertex index", corner))?;
+    let uv = parse_index(parts.next(), uv_count, "texture coordinate")?;
+    let normal = parse_index(parts.next(), normal_count, "normal")?;
+    Ok((position, uv, normal))
+}
+
+fn parse_index(index: Option<&str>, count: usize, kind: &str) -> Result<Option<usize>, String> {
+    let index = match index {
+        Some(index) if !index.is_empty() => index,
+        _ => return Ok(None),
+    };
+    let value: isize = index
+        .parse()
+        .map_err(|_| format!("{} is not a valid {} index", index, kind))?;
+    let resolved = if value < 0 {
+        count as isize + value
+    } else {
+        value - 1
+    };
+    if value == 0 || resolved < 0 || resolved >= count as isize {
+        Err(format!(
+            "the {} index {} is out of range, there are {} {}s",
+            kind, value, count, kind
+        ))
+    } else {
+        Ok(Some(resolved as usize))
+    }
+}
+
+fn parse_floats(arguments: &[&str], min_count: usize) -> Result<Vec<f32>, String> {
+    if arguments.len() < min_count {
+        Err(format!(
+            "expected at least {} numbers, but got {}",
+            min_count,
+            arguments.len()
+        ))?;
+    }
+    arguments
+        .iter()
+        .map(|a| {
+            a.parse::<f32>()
+                .map_err(|_| format!("{} is not a valid number", a))
+        })
+        .collect()
+}
 
-                cpu_meshes.push(CPUMesh {
-                    name: object.name.to_string(),
-                    material_name: mesh.material_name.clone(),
-                    positions,
-                    indices: Some(Indices::U32(indices)),
-                    normals: Some(normals),
-                    uvs: Some(uvs),
-                    colors: None,
-                    tangents: None,
-                });
+// Returns the lines without comments together with their line number, where lines ending with a backslash are joined with the next line
+fn logical_lines(source: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    for (i, line) in source.lines().enumerate() {
+        if current.is_empty() {
+            start = i + 1;
+        }
+        let line = line.split('#').next().unwrap();
+        if let Some(line) = line.trim_end().strip_suffix('\\') {
+            current.push_str(line);
+            current.push(' ');
+        } else {
+            current.push_str(line);
+            lines.push((start, std::mem::take(&mut current)));
+        }
+    }
+    if !current.is_empty() {
+        lines.push((start, current));
+    }
+    lines
+}
+
+// Paths in .obj and .mtl files are often written on Windows
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+struct MtlMaterial {
+    name: String,
+    ambient: [f32; 3],
+    diffuse: [f32; 3],
+    specular: [f32; 3],
+    emissive: [f32; 3],
+    specular_exponent: f32,
+    alpha: f32,
+    diffuse_map: Option<String>,
+    specular_map: Option<String>,
+    bump_map: Option<String>,
+}
+
+impl MtlMaterial {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            ambient: [0.0; 3],
+            diffuse: [1.0; 3],
+            specular: [0.0; 3],
+            emissive: [0.0; 3],
+            specular_exponent: 0.0,
+            alpha: 1.0,
+            diffuse_map: None,
+            specular_map: None,
+            bump_map: None,
+        }
+    }
+}
+
+fn parse_mtl(source: &str, path: &str) -> ThreeDResult<Vec<MtlMaterial>> {
+    let mut materials: Vec<MtlMaterial> = Vec::new();
+    for (line_number, line) in logical_lines(source) {
+        let error =
+            |message: String| IOError::Obj(format!("{}:{}: {}", path, line_number, message));
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let arguments: Vec<&str> = tokens.collect();
+        if keyword == "newmtl" {
+            materials.push(MtlMaterial::new(arguments.join(" ")));
+            continue;
+        }
+        let material = match materials.last_mut() {
+            Some(material) => material,
+            None => continue,
+        };
+        let color = |arguments: &[&str]| -> Result<[f32; 3], String> {
+            let v = parse_floats(arguments, 1)?;
+            Ok([
+                v[0],
+                v.get(1).cloned().unwrap_or(v[0]),
+                v.get(2).cloned().unwrap_or(v[0]),
+            ])
+        };
+        match keyword {
+            "Ka" => material.ambient = color(&arguments).map_err(error)?,
+            "Kd" => material.diffuse = color(&arguments).map_err(error)?,
+            "Ks" => material.specular = color(&arguments).map_err(error)?,
+            "Ke" => material.emissive = color(&arguments).map_err(error)?,
+            "Ns" => material.specular_exponent = parse_floats(&arguments, 1).map_err(error)?[0],
+            "d" => material.alpha = parse_floats(&arguments, 1).map_err(error)?[0],
+            "Tr" => material.alpha = 1.0 - parse_floats(&arguments, 1).map_err(error)?[0],
+            "map_Kd" => material.diffuse_map = texture_path(&arguments),
+            "map_Ks" => material.specular_map = texture_path(&arguments),
+            "map_Bump" | "map_bump" | "bump" | "norm" => {
+                material.bump_map = texture_path(&arguments)
             }
+            _ => {}
         }
-        Ok((cpu_meshes, cpu_materials))
+    }
+    Ok(materials)
+}
+
+// Returns the path of a texture map statement, skipping the options before the path, for example `-bm 0.5 normal.png`
+fn texture_path(arguments: &[&str]) -> Option<String> {
+    let mut i = 0;
+    while i < arguments.len() && arguments[i].starts_with('-') {
+        let option = arguments[i];
+        i += 1;
+        match option {
+            "-o" | "-s" | "-t" => {
+                // Up to three numbers
+                for _ in 0..3 {
+                    if i < arguments.len() && arguments[i].parse::<f32>().is_ok() {
+                        i += 1;
+                    }
+                }
+            }
+            "-mm" => i += 2,
+            _ => i += 1,
+        }
+    }
+    if i < arguments.len() {
+        Some(normalize_path(&arguments[i..].join(" ")))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(source: &str) -> Vec<(CPUMesh, Option<CPUMaterial>)> {
+        let mut loaded = Loaded::new();
+        loaded.insert_bytes("test.obj", source.as_bytes().to_vec());
+        loaded.obj("test.obj").unwrap()
+    }
+
+    fn triangle_count(mesh: &CPUMesh) -> usize {
+        let mut count = 0;
+        mesh.for_each_triangle(|_, _, _| count += 1);
+        count
+    }
+
+    #[test]
+    fn negative_indices() {
+        let meshes = load(
+            "v 0 0 0
+             v 1 0 0
+             v 1 1 0
+             v 0 1 0
+             f -4 -3 -2 -1
+             v 5 0 0
+             v 6 0 0
+             v 6 1 0
+             f 5 -2 -1",
+        );
+        assert_eq!(meshes.len(), 1);
+        let mesh = &meshes[0].0;
+        assert_eq!(triangle_count(mesh), 3);
+        // The relative indices of each face refer to the vertices read so far
+        assert_eq!(mesh.position(0), vec3(0.0, 0.0, 0.0));
+        assert_eq!(mesh.position(3), vec3(0.0, 1.0, 0.0));
+        assert_eq!(mesh.position(4), vec3(5.0, 0.0, 0.0));
+        assert_eq!(mesh.position(5), vec3(6.0, 0.0, 0.0));
+        assert_eq!(mesh.position(6), vec3(6.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn out_of_range_index_is_an_error() {
+        let mut loaded = Loaded::new();
+        loaded.insert_bytes("test.obj", b"v 0 0 0\nv 1 0 0\nf 1 2 -3".to_vec());
+        assert!(loaded.obj("test.obj").is_err());
+    }
+
+    #[test]
+    fn groups_and_materials() {
+        let mut loaded = Loaded::new();
+        loaded.insert_bytes(
+            "models/test.mtl",
+            b"newmtl red\nKd 1 0 0\nnewmtl green\nKd 0 1 0".to_vec(),
+        );
+        loaded.insert_bytes(
+            "models/test.obj",
+            b"mtllib test.mtl
+              v 0 0 0
+              v 1 0 0
+              v 1 1 0
+              v 0 1 0
+              g first
+              usemtl red
+              f 1 2 3
+              usemtl green
+              f 1 3 4
+              g second
+              usemtl red
+              f 1 2 4
+              g first
+              usemtl red
+              f 2 3 4"
+                .to_vec(),
+        );
+        let meshes = loaded.obj("models/test.obj").unwrap();
+        let summary: Vec<(&str, Option<&str>, usize)> = meshes
+            .iter()
+            .map(|(mesh, material)| {
+                (
+                    mesh.name.as_str(),
+                    material.as_ref().map(|m| m.name.as_str()),
+                    triangle_count(mesh),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("first", Some("red"), 2),
+                ("first", Some("green"), 1),
+                ("second", Some("red"), 1)
+            ]
+        );
+        assert_eq!(meshes[0].1.as_ref().unwrap().albedo, Color::RED);
+        assert_eq!(meshes[1].1.as_ref().unwrap().albedo, Color::GREEN);
+    }
+
+    #[test]
+    fn missing_normals_are_computed() {
+        let meshes = load(
+            "v 0 0 0
+             v 1 0 0
+             v 0 1 0
+             f 1 2 3",
+        );
+        let mesh = &meshes[0].0;
+        assert!(mesh.uvs.is_none());
+        let normals = mesh.normals.as_ref().unwrap();
+        for normal in normals.chunks(3) {
+            assert!(
+                (vec3(normal[0], normal[1], normal[2]) - vec3(0.0, 0.0, 1.0)).magnitude() < 0.0001
+            );
+        }
+    }
+
+    #[test]
+    fn missing_uvs_are_omitted() {
+        let meshes = load(
+            "v 0 0 0
+             v 1 0 0
+             v 0 1 0
+             vt 0 0
+             vt 1 0
+             vn 0 0 -1
+             f 1/1/1 2/2/1 3//1",
+        );
+        let mesh = &meshes[0].0;
+        assert!(mesh.uvs.is_none());
+        // The given normals are used instead of computed ones
+        let normals = mesh.normals.as_ref().unwrap();
+        assert_eq!(&normals[0..3], &[0.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn unique_vertices() {
+        let meshes = load(
+            "v 0 0 0
+             v 1 0 0
+             v 1 1 0
+             v 0 1 0
+             vt 0 0
+             vt 1 0
+             vt 1 1
+             vt 0 1
+             vt 0.5 0.5
+             f 1/1 2/2 3/3 4/4
+             f 1/5 3/3 4/4",
+        );
+        let mesh = &meshes[0].0;
+        assert_eq!(triangle_count(mesh), 3);
+        // The corners with the same position and uv coordinate share a vertex, the corner with a different uv coordinate does not
+        assert_eq!(mesh.positions.len(), 5 * 3);
+        let uvs = mesh.uvs.as_ref().unwrap();
+        assert_eq!(&uvs[8..10], &[0.5, 0.5]);
     }
 }
@@ -0,0 +1,491 @@
+use crate::core::*;
+use crate::io::*;
+use std::path::Path;
+
+impl Loaded {
+    ///
+    /// Deserialize a loaded .ply file resource into a mesh.
+    /// Supports the ascii, binary little endian and binary big endian formats.
+    /// The positions, normals and colors of the vertices are read from the `x`, `y`, `z`, `nx`, `ny`, `nz` and `red`, `green`, `blue`, `alpha` properties
+    /// and the faces, if any, are triangulated. The colors are assumed to be in the sRGB color space, see [CPUMesh::color_space].
+    /// A file without faces, for example a scanned point cloud, results in a mesh without indices which can be rendered using a [PointCloud](crate::PointCloud).
+    ///
+    pub fn ply(&mut self, path: impl AsRef<Path>) -> ThreeDResult<CPUMesh> {
+        let bytes = self.remove_bytes(path.as_ref())?;
+        let (header, body_start) = parse_header(&bytes)?;
+        let mut reader = Reader {
+            bytes: &bytes,
+            position: body_start,
+            format: header.format,
+        };
+
+        let mut builder = MeshBuilder::new(
+            path.as_ref()
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(""),
+        );
+        for element in header.elements.iter() {
+            let layout = Layout::new(element);
+            if element.name == "vertex" {
+                builder.mesh.positions.reserve(element.count * 3);
+            }
+            for _ in 0..element.count {
+                builder.read_item(element, &layout, &mut reader)?;
+            }
+        }
+        Ok(builder.into_mesh())
+    }
+}
+
+///
+/// An [IncrementalParser] which parses a .ply file in the binary little endian format while it is loaded, see [Loader::load_streamed].
+/// The vertices and faces are read in the same way as in [Loaded::ply].
+/// The vertices parsed so far can be taken using [PlyStreamParser::take_new_vertices], for example to show a point cloud while the rest of the file is loading.
+///
+#[derive(Default)]
+pub struct PlyStreamParser {
+    buffer: Vec<u8>,
+    header: Option<(Header, Vec<Layout>)>,
+    builder: MeshBuilder,
+    element: usize,
+    item: usize,
+    taken_vertices: usize,
+}
+
+impl PlyStreamParser {
+    ///
+    /// Constructs a new parser for a .ply file in the binary little endian format.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns a mesh without indices containing the vertices parsed since the last call, or `None` if no vertices have been parsed since then.
+    /// The vertices are still part of the mesh returned when the parsing is finished.
+    ///
+    pub fn take_new_vertices(&mut self) -> Option<CPUMesh> {
+        let mesh = vertices_from(&self.builder.mesh, self.taken_vertices);
+        self.taken_vertices = self.builder.mesh.positions.len() / 3;
+        mesh
+    }
+}
+
+impl IncrementalParser for PlyStreamParser {
+    type Output = CPUMesh;
+
+    fn consume(&mut self, chunk: &[u8]) -> ThreeDResult<()> {
+        self.buffer.extend_from_slice(chunk);
+        if self.header.is_none() {
+            // The header is parsed when the whole end_header line is loaded
+            let lines = self.buffer.iter().filter(|b| **b == b'\n').count();
+            let header_loaded = self.buffer.split(|b| *b == b'\n').take(lines).any(|line| {
+                std::str::from_utf8(line)
+                    .map(|line| line.trim() == "end_header")
+                    .unwrap_or(false)
+            });
+            if !header_loaded {
+                return Ok(());
+            }
+            let (header, body_start) = parse_header(&self.buffer)?;
+            if header.format != PlyFormat::BinaryLittleEndian {
+                Err(error(
+                    "only the binary little endian format can be parsed while loading",
+                ))?;
+            }
+            let layouts = header.elements.iter().map(Layout::new).collect();
+            self.buffer.drain(..body_start);
+            self.header = Some((header, layouts));
+        }
+        let (header, layouts) = self.header.as_ref().unwrap();
+        let mut reader = Reader {
+            bytes: &self.buffer,
+            position: 0,
+            format: header.format,
+        };
+        while self.element < header.elements.len() {
+            let element = &header.elements[self.element];
+            if self.item == element.count {
+                self.element += 1;
+                self.item = 0;
+                continue;
+            }
+            if item_size(element, &self.buffer[reader.position..]).is_none() {
+                break;
+            }
+            self.builder
+                .read_item(element, &layouts[self.element], &mut reader)?;
+            self.item += 1;
+        }
+        let position = reader.position;
+        self.buffer.drain(..position);
+        Ok(())
+    }
+
+    fn finish(self) -> ThreeDResult<CPUMesh> {
+        let elements = self.header.as_ref().map(|(h, _)| h.elements.len());
+        if elements != Some(self.element) {
+            Err(error("the file ends before all elements are read"))?;
+        }
+        Ok(self.builder.into_mesh())
+    }
+}
+
+// The indices of the properties of an element which are read into the mesh
+struct Layout {
+    is_vertex: bool,
+    positions: [Option<usize>; 3],
+    normals: Option<[usize; 3]>,
+    colors: Option<[usize; 3]>,
+    alpha: Option<usize>,
+    face_indices: Option<usize>,
+}
+
+impl Layout {
+    fn new(element: &Element) -> Self {
+        let index = |name: &str| element.properties.iter().position(|p| p.name == name);
+        let all = |indices: [Option<usize>; 3]| {
+            if element.name == "vertex" && indices.iter().all(|i| i.is_some()) {
+                Some([
+                    indices[0].unwrap(),
+                    indices[1].unwrap(),
+                    indices[2].unwrap(),
+                ])
+            } else {
+                None
+            }
+        };
+        Self {
+            is_vertex: element.name == "vertex",
+            positions: [index("x"), index("y"), index("z")],
+            normals: all([index("nx"), index("ny"), index("nz")]),
+            colors: all([
+                index("red").or_else(|| index("r")),
+                index("green").or_else(|| index("g")),
+                index("blue").or_else(|| index("b")),
+            ]),
+            alpha: index("alpha").or_else(|| index("a")),
+            face_indices: if element.name == "face" {
+                index("vertex_indices").or_else(|| index("vertex_index"))
+            } else {
+                None
+            },
+        }
+    }
+}
+
+// Reads the items of the elements into a mesh, where the faces are triangulated
+#[derive(Default)]
+struct MeshBuilder {
+    mesh: CPUMesh,
+    indices: Vec<u32>,
+    values: Vec<f64>,
+    list: Vec<u32>,
+}
+
+impl MeshBuilder {
+    fn new(name: &str) -> Self {
+        Self {
+            mesh: CPUMesh {
+                name: name.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn read_item(
+        &mut self,
+        element: &Element,
+        layout: &Layout,
+        reader: &mut Reader,
+    ) -> ThreeDResult<()> {
+        self.values.clear();
+        for (i, property) in element.properties.iter().enumerate() {
+            match property.kind {
+                PropertyKind::Scalar(scalar) => self.values.push(reader.read(scalar)?),
+                PropertyKind::List(count_scalar, item_scalar) => {
+                    let count = reader.read(count_scalar)? as usize;
+                    if Some(i) == layout.face_indices {
+                        self.list.clear();
+                        for _ in 0..count {
+                            self.list.push(reader.read(item_scalar)? as u32);
+                        }
+                        // Triangulate polygons as a fan around the first vertex
+                        for j in 2..self.list.len() {
+                            self.indices.extend_from_slice(&[
+                                self.list[0],
+                                self.list[j - 1],
+                                self.list[j],
+                            ]);
+                        }
+                    } else {
+                        for _ in 0..count {
+                            reader.read(item_scalar)?;
+                        }
+                    }
+                    self.values.push(0.0);
+                }
+            }
+        }
+        if !layout.is_vertex {
+            return Ok(());
+        }
+        let values = &self.values;
+        for i in layout.positions.iter() {
+            self.mesh
+                .positions
+                .push(i.map(|i| values[i] as f32).unwrap_or(0.0));
+        }
+        if let Some(normals) = layout.normals {
+            let normals_data = self.mesh.normals.get_or_insert_with(Vec::new);
+            for i in normals.iter() {
+                normals_data.push(values[*i] as f32);
+            }
+        }
+        if let Some(colors) = layout.colors {
+            // The vertex colors of .ply files are usually captured by a camera or picked by hand, so they are gamma encoded
+            self.mesh.color_space = ColorSpace::Srgb;
+            let colors_data = self.mesh.colors.get_or_insert_with(Vec::new);
+            for i in colors.iter().cloned().chain(layout.alpha) {
+                colors_data.push(to_byte(values[i], element.properties[i].kind));
+            }
+            if layout.alpha.is_none() {
+                colors_data.push(255);
+            }
+        }
+        Ok(())
+    }
+
+    fn into_mesh(mut self) -> CPUMesh {
+        if !self.indices.is_empty() {
+            self.mesh.indices = Some(Indices::U32(self.indices));
+        }
+        self.mesh
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PropertyKind {
+    Scalar(Scalar),
+    List(Scalar, Scalar),
+}
+
+struct Property {
+    name: String,
+    kind: PropertyKind,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+struct Header {
+    format: PlyFormat,
+    elements: Vec<Element>,
+}
+
+fn parse_header(bytes: &[u8]) -> ThreeDResult<(Header, usize)> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut position = 0;
+    let mut first = true;
+    loop {
+        let end = bytes[position..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .ok_or_else(|| error("the header is not terminated by end_header"))?;
+        let line = std::str::from_utf8(&bytes[position..position + end])
+            .map_err(|_| error("the header is not valid text"))?
+            .trim();
+        position += end + 1;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if first {
+            if line != "ply" {
+                Err(error("the file does not start with ply"))?;
+            }
+            first = false;
+            continue;
+        }
+        match words.as_slice() {
+            ["format", name, _] => {
+                format = Some(match *name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => Err(error(&format!("the format {} is not supported", name)))?,
+                });
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| error(&format!("invalid element count {}", count)))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count_type, item_type, name] => {
+                let kind = PropertyKind::List(parse_scalar(count_type)?, parse_scalar(item_type)?);
+                add_property(&mut elements, name, kind)?;
+            }
+            ["property", scalar_type, name] => {
+                let kind = PropertyKind::Scalar(parse_scalar(scalar_type)?);
+                add_property(&mut elements, name, kind)?;
+            }
+            ["end_header"] => break,
+            _ => {} // Comments and obj_info
+        }
+    }
+    let format = format.ok_or_else(|| error("the header does not specify the format"))?;
+    Ok((Header { format, elements }, position))
+}
+
+fn add_property(elements: &mut Vec<Element>, name: &str, kind: PropertyKind) -> ThreeDResult<()> {
+    elements
+        .last_mut()
+        .ok_or_else(|| error("a property is defined before an element"))?
+        .properties
+        .push(Property {
+            name: name.to_string(),
+            kind,
+        });
+    Ok(())
+}
+
+fn parse_scalar(name: &str) -> ThreeDResult<Scalar> {
+    Ok(match name {
+        "char" | "int8" => Scalar::I8,
+        "uchar" | "uint8" => Scalar::U8,
+        "short" | "int16" => Scalar::I16,
+        "ushort" | "uint16" => Scalar::U16,
+        "int" | "int32" => Scalar::I32,
+        "uint" | "uint32" => Scalar::U32,
+        "float" | "float32" => Scalar::F32,
+        "double" | "float64" => Scalar::F64,
+        _ => Err(error(&format!(
+            "the property type {} is not supported",
+            name
+        )))?,
+    })
+}
+
+// Colors stored as floats are in the range 0..1 and colors stored as integers are in the range 0..255
+fn to_byte(value: f64, kind: PropertyKind) -> u8 {
+    match kind {
+        PropertyKind::Scalar(Scalar::F32) | PropertyKind::Scalar(Scalar::F64) => {
+            (value * 255.0).round().max(0.0).min(255.0) as u8
+        }
+        _ => value.max(0.0).min(255.0) as u8,
+    }
+}
+
+// The size in bytes of the next item of an element in the binary format, or None if the bytes do not contain the whole item
+fn item_size(element: &Element, bytes: &[u8]) -> Option<usize> {
+    let mut size = 0;
+    for property in element.properties.iter() {
+        match property.kind {
+            PropertyKind::Scalar(scalar) => size += scalar.size(),
+            PropertyKind::List(count_scalar, item_scalar) => {
+                if size + count_scalar.size() > bytes.len() {
+                    return None;
+                }
+                let mut reader = Reader {
+                    bytes: &bytes[size..],
+                    position: 0,
+                    format: PlyFormat::BinaryLittleEndian,
+                };
+                let count = reader.read(count_scalar).ok()? as usize;
+                size += count_scalar.size() + count * item_scalar.size();
+            }
+        }
+    }
+    if size <= bytes.len() {
+        Some(size)
+    } else {
+        None
+    }
+}
+
+fn error(message: &str) -> IOError {
+    IOError::Ply(message.to_string())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    format: PlyFormat,
+}
+
+impl<'a> Reader<'a> {
+    fn read(&mut self, scalar: Scalar) -> ThreeDResult<f64> {
+        if self.format == PlyFormat::Ascii {
+            return self.read_ascii();
+        }
+        let size = scalar.size();
+        if self.position + size > self.bytes.len() {
+            Err(error("the file ends before all elements are read"))?;
+        }
+        let mut data = [0u8; 8];
+        data[..size].copy_from_slice(&self.bytes[self.position..self.position + size]);
+        self.position += size;
+        if self.format == PlyFormat::BinaryBigEndian {
+            data[..size].reverse();
+        }
+        Ok(match scalar {
+            Scalar::I8 => data[0] as i8 as f64,
+            Scalar::U8 => data[0] as f64,
+            Scalar::I16 => i16::from_le_bytes([data[0], data[1]]) as f64,
+            Scalar::U16 => u16::from_le_bytes([data[0], data[1]]) as f64,
+            Scalar::I32 => i32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f64,
+            Scalar::U32 => u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f64,
+            Scalar::F32 => f32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f64,
+            Scalar::F64 => f64::from_le_bytes(data),
+        })
+    }
+
+    fn read_ascii(&mut self) -> ThreeDResult<f64> {
+        while self.position < self.bytes.len() && self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+        let start = self.position;
+        while self.position < self.bytes.len() && !self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+        let word = std::str::from_utf8(&self.bytes[start..self.position]).unwrap_or("");
+        Ok(word
+            .parse()
+            .map_err(|_| error(&format!("invalid value '{}'", word)))?)
+    }
+}
@@ -0,0 +1,841 @@
+use crate::core::*;
+use crate::io::*;
+use crate::renderer::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+///
+/// A description of a scene consisting of objects, lights and a camera which can be saved to and loaded from a JSON file.
+/// The objects reference mesh files by path and relative paths are relative to the directory of the scene file.
+/// Use [SceneDescription::asset_paths] to get the files that needs to be loaded and then [SceneDescription::instantiate] to create the [Scene].
+///
+/// Unknown fields are ignored and missing fields are set to their default value when loading a scene file,
+/// so a scene file saved with a newer version can be loaded with an older version and the other way around.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SceneDescription {
+    /// The objects in the scene.
+    pub objects: Vec<ObjectDescription>,
+    /// The ambient light, if any.
+    pub ambient_light: Option<AmbientLightDescription>,
+    /// The directional lights.
+    pub directional_lights: Vec<DirectionalLightDescription>,
+    /// The spot lights.
+    pub spot_lights: Vec<SpotLightDescription>,
+    /// The point lights.
+    pub point_lights: Vec<PointLightDescription>,
+    /// The camera viewing the scene.
+    pub camera: CameraDescription,
+}
+
+impl Default for SceneDescription {
+    fn default() -> Self {
+        Self {
+            objects: Vec::new(),
+            ambient_light: None,
+            directional_lights: Vec::new(),
+            spot_lights: Vec::new(),
+            point_lights: Vec::new(),
+            camera: CameraDescription::default(),
+        }
+    }
+}
+
+///
+/// A scene created from a [SceneDescription] using [SceneDescription::instantiate].
+///
+pub struct Scene {
+    /// The models, one for each mesh in the mesh files of the objects.
+    pub models: Vec<Model<PhysicalMaterial>>,
+    /// The lights.
+    pub lights: Lights,
+    /// The camera.
+    pub camera: Camera,
+}
+
+impl SceneDescription {
+    ///
+    /// Serializes this scene description to a JSON string.
+    ///
+    pub fn to_json(&self) -> ThreeDResult<String> {
+        Ok(serde_json::to_string_pretty(self).map_err(IOError::Scene)?)
+    }
+
+    ///
+    /// Deserializes a scene description from the given JSON string.
+    ///
+    pub fn from_json(json: &str) -> ThreeDResult<Self> {
+        Ok(serde_json::from_str(json).map_err(IOError::Scene)?)
+    }
+
+    ///
+    /// Saves this scene description as a JSON file at the given path.
+    /// Only available on desktop.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: impl AsRef<Path>) -> ThreeDResult<()> {
+        Saver::save_file(path, self.to_json()?.as_bytes())
+    }
+
+    ///
+    /// Loads a scene description from the JSON file at the given path.
+    /// Only available on desktop, use [Loaded::scene] on web.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: impl AsRef<Path>) -> ThreeDResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    ///
+    /// Returns the paths of all of the files used by this scene, ie. the mesh files, their dependencies and the textures,
+    /// where relative paths are resolved relative to the directory of the given scene file.
+    /// Load these files using the [Loader] or [Loading] before calling [SceneDescription::instantiate].
+    ///
+    pub fn asset_paths(&self, scene_path: impl AsRef<Path>) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for object in self.objects.iter() {
+            paths.push(resolve(scene_path.as_ref(), &object.mesh));
+            for dependency in object.dependencies.iter() {
+                paths.push(resolve(scene_path.as_ref(), dependency));
+            }
+            if let Some(ref material) = object.material {
+                for texture in material.texture_paths() {
+                    paths.push(resolve(scene_path.as_ref(), texture));
+                }
+            }
+        }
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    ///
+    /// Creates the models, lights and camera described by this scene description.
+    /// The files returned by [SceneDescription::asset_paths] must be loaded beforehand and the given scene path must be the same as used to get those paths.
+    /// The mesh files must be .obj or .gltf/.glb files and the corresponding features must be enabled.
+    ///
+    pub fn instantiate(
+        &self,
+        context: &Context,
+        scene_path: impl AsRef<Path>,
+        loaded: &mut Loaded,
+        viewport: Viewport,
+    ) -> ThreeDResult<Scene> {
+        let default_material = CPUMaterial::default();
+        let mut meshes = HashMap::new();
+        let mut models = Vec::new();
+        for object in self.objects.iter() {
+            let path = resolve(scene_path.as_ref(), &object.mesh);
+            if !meshes.contains_key(&path) {
+                let mesh = parse_mesh(loaded, &path)?;
+                meshes.insert(path.clone(), mesh);
+            }
+            let (cpu_meshes, cpu_materials) = meshes.get(&path).unwrap();
+            let object_material = if let Some(ref material) = object.material {
+                Some(material.to_cpu_material(loaded, scene_path.as_ref())?)
+            } else {
+                None
+            };
+            for cpu_mesh in cpu_meshes.iter() {
+                let cpu_material = object_material
+                    .as_ref()
+                    .or_else(|| {
+                        cpu_materials
+                            .iter()
+                            .find(|m| Some(&m.name) == cpu_mesh.material_name.as_ref())
+                    })
+                    .unwrap_or(&default_material);
+                let mut model = Model::new_with_material(
+                    context,
+                    cpu_mesh,
+                    PhysicalMaterial::new(context, cpu_material)?,
+                )?;
+                model.set_transformation(object.transformation());
+                models.push(model);
+            }
+        }
+
+        let lights = Lights {
+            ambient: self.ambient_light.as_ref().map(|l| l.to_light()),
+            directional: self
+                .directional_lights
+                .iter()
+                .map(|l| l.to_light(context))
+                .collect::<ThreeDResult<Vec<_>>>()?,
+            spot: self
+                .spot_lights
+                .iter()
+                .map(|l| l.to_light(context))
+                .collect::<ThreeDResult<Vec<_>>>()?,
+            point: self
+                .point_lights
+                .iter()
+                .map(|l| l.to_light(context))
+                .collect::<ThreeDResult<Vec<_>>>()?,
+            ..Default::default()
+        };
+
+        Ok(Scene {
+            models,
+            lights,
+            camera: self.camera.to_camera(context, viewport)?,
+        })
+    }
+}
+
+impl Loaded {
+    ///
+    /// Deserialize a loaded scene description JSON file, see [SceneDescription].
+    ///
+    pub fn scene(&mut self, path: impl AsRef<Path>) -> ThreeDResult<SceneDescription> {
+        let bytes = self.get_bytes(path)?;
+        Ok(serde_json::from_slice(bytes).map_err(IOError::Scene)?)
+    }
+}
+
+///
+/// A description of an object in a [SceneDescription], ie. a mesh file, an optional material and a transformation.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectDescription {
+    /// Optional name of the object.
+    pub name: String,
+    /// Path to the mesh file, either an .obj, .gltf or .glb file. All meshes in the file are added to the scene.
+    pub mesh: String,
+    /// Paths to additional files used by the mesh file, for example the .mtl file and textures of an .obj file or the .bin file of a .gltf file.
+    pub dependencies: Vec<String>,
+    /// The material applied to all meshes of the object. If `None`, the materials defined in the mesh file are used.
+    pub material: Option<MaterialDescription>,
+    /// The transformation of the object given as the four columns of a 4x4 matrix.
+    pub transformation: [[f32; 4]; 4],
+}
+
+impl ObjectDescription {
+    ///
+    /// Returns the transformation of the object.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        let [x, y, z, w] = self.transformation;
+        Mat4::from_cols(x.into(), y.into(), z.into(), w.into())
+    }
+
+    ///
+    /// Sets the transformation of the object.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation.into();
+    }
+}
+
+impl Default for ObjectDescription {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            mesh: String::new(),
+            dependencies: Vec::new(),
+            material: None,
+            transformation: Mat4::identity().into(),
+        }
+    }
+}
+
+///
+/// A description of a material in a [SceneDescription] which mirrors [CPUMaterial] except that the textures are given as paths to image files.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaterialDescription {
+    /// Name of the material.
+    pub name: String,
+    /// Albedo base color as RGBA, see [CPUMaterial::albedo].
+    pub albedo: [u8; 4],
+    /// Path to the albedo texture, see [CPUMaterial::albedo_texture].
+    pub albedo_texture: Option<String>,
+    /// See [CPUMaterial::metallic].
+    pub metallic: f32,
+    /// See [CPUMaterial::roughness].
+    pub roughness: f32,
+    /// Path to the occlusion, metallic and roughness texture, see [CPUMaterial::occlusion_metallic_roughness_texture].
+    pub occlusion_metallic_roughness_texture: Option<String>,
+    /// Path to the metallic and roughness texture, see [CPUMaterial::metallic_roughness_texture].
+    pub metallic_roughness_texture: Option<String>,
+    /// See [CPUMaterial::occlusion_strength].
+    pub occlusion_strength: f32,
+    /// Path to the occlusion texture, see [CPUMaterial::occlusion_texture].
+    pub occlusion_texture: Option<String>,
+    /// See [CPUMaterial::normal_scale].
+    pub normal_scale: f32,
+    /// Path to the normal texture, see [CPUMaterial::normal_texture].
+    pub normal_texture: Option<String>,
+    /// Emissive color as RGBA, see [CPUMaterial::emissive].
+    pub emissive: [u8; 4],
+    /// Path to the emissive texture, see [CPUMaterial::emissive_texture].
+    pub emissive_texture: Option<String>,
+    /// See [CPUMaterial::alpha_cutout].
+    pub alpha_cutout: Option<f32>,
+    /// See [CPUMaterial::double_sided].
+    pub double_sided: bool,
+}
+
+impl MaterialDescription {
+    ///
+    /// Creates a [CPUMaterial] from this description, where the textures must have been loaded beforehand, see [SceneDescription::asset_paths].
+    ///
+    pub fn to_cpu_material(
+        &self,
+        loaded: &mut Loaded,
+        scene_path: impl AsRef<Path>,
+    ) -> ThreeDResult<CPUMaterial> {
+        let mut texture = |path: &Option<String>| -> ThreeDResult<Option<CPUTexture<u8>>> {
+            Ok(if let Some(path) = path {
+                Some(loaded.image(resolve(scene_path.as_ref(), path))?)
+            } else {
+                None
+            })
+        };
+        Ok(CPUMaterial {
+            name: self.name.clone(),
+            albedo: to_color(self.albedo),
+            albedo_texture: texture(&self.albedo_texture)?,
+            metallic: self.metallic,
+            roughness: self.roughness,
+            occlusion_metallic_roughness_texture: texture(
+                &self.occlusion_metallic_roughness_texture,
+            )?,
+            metallic_roughness_texture: texture(&self.metallic_roughness_texture)?,
+            occlusion_strength: self.occlusion_strength,
+            occlusion_texture: texture(&self.occlusion_texture)?,
+            normal_scale: self.normal_scale,
+            normal_texture: texture(&self.normal_texture)?,
+            emissive: to_color(self.emissive),
+            emissive_texture: texture(&self.emissive_texture)?,
+            alpha_cutout: self.alpha_cutout,
+            double_sided: self.double_sided,
+            ..Default::default()
+        })
+    }
+
+    fn texture_paths(&self) -> impl Iterator<Item = &String> {
+        vec![
+            &self.albedo_texture,
+            &self.occlusion_metallic_roughness_texture,
+            &self.metallic_roughness_texture,
+            &self.occlusion_texture,
+            &self.normal_texture,
+            &self.emissive_texture,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl Default for MaterialDescription {
+    fn default() -> Self {
+        let material = CPUMaterial::default();
+        Self {
+            name: material.name,
+            albedo: from_color(material.albedo),
+            albedo_texture: None,
+            metallic: material.metallic,
+            roughness: material.roughness,
+            occlusion_metallic_roughness_texture: None,
+            metallic_roughness_texture: None,
+            occlusion_strength: material.occlusion_strength,
+            occlusion_texture: None,
+            normal_scale: material.normal_scale,
+            normal_texture: None,
+            emissive: from_color(material.emissive),
+            emissive_texture: None,
+            alpha_cutout: material.alpha_cutout,
+            double_sided: material.double_sided,
+        }
+    }
+}
+
+///
+/// A description of an [AmbientLight] in a [SceneDescription].
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AmbientLightDescription {
+    /// The color of the light as RGB.
+    pub color: [u8; 3],
+    /// The intensity of the light.
+    pub intensity: f32,
+    /// The color of the light from below as RGB, if it is a hemisphere light, see [AmbientLight::ground_color].
+    pub ground_color: Option<[u8; 3]>,
+}
+
+impl AmbientLightDescription {
+    ///
+    /// Creates an [AmbientLight] from this description.
+    ///
+    pub fn to_light(&self) -> AmbientLight {
+        AmbientLight {
+            color: to_color_opaque(self.color),
+            intensity: self.intensity,
+            ground_color: self.ground_color.map(to_color_opaque),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&AmbientLight> for AmbientLightDescription {
+    fn from(light: &AmbientLight) -> Self {
+        Self {
+            color: [light.color.r, light.color.g, light.color.b],
+            intensity: light.intensity,
+            ground_color: light.ground_color.map(|c| [c.r, c.g, c.b]),
+        }
+    }
+}
+
+impl Default for AmbientLightDescription {
+    fn default() -> Self {
+        Self::from(&AmbientLight::default())
+    }
+}
+
+///
+/// A description of a [DirectionalLight] in a [SceneDescription].
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectionalLightDescription {
+    /// The color of the light as RGB.
+    pub color: [u8; 3],
+    /// The intensity of the light.
+    pub intensity: f32,
+    /// The direction the light shines in.
+    pub direction: [f32; 3],
+}
+
+impl DirectionalLightDescription {
+    ///
+    /// Creates a [DirectionalLight] from this description.
+    ///
+    pub fn to_light(&self, context: &Context) -> ThreeDResult<DirectionalLight> {
+        DirectionalLight::new(
+            context,
+            self.intensity,
+            to_color_opaque(self.color),
+            &self.direction.into(),
+        )
+    }
+}
+
+impl From<&DirectionalLight> for DirectionalLightDescription {
+    fn from(light: &DirectionalLight) -> Self {
+        let color = light.color();
+        Self {
+            color: [color.r, color.g, color.b],
+            intensity: light.intensity(),
+            direction: light.direction().into(),
+        }
+    }
+}
+
+impl Default for DirectionalLightDescription {
+    fn default() -> Self {
+        Self {
+            color: [255, 255, 255],
+            intensity: 1.0,
+            direction: [0.0, -1.0, 0.0],
+        }
+    }
+}
+
+///
+/// A description of a [SpotLight] in a [SceneDescription].
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpotLightDescription {
+    /// The color of the light as RGB.
+    pub color: [u8; 3],
+    /// The intensity of the light.
+    pub intensity: f32,
+    /// The position of the light.
+    pub position: [f32; 3],
+    /// The direction the light shines in.
+    pub direction: [f32; 3],
+    /// The cutoff angle in degrees.
+    pub cutoff: f32,
+    /// The constant, linear and exponential attenuation.
+    pub attenuation: [f32; 3],
+}
+
+impl SpotLightDescription {
+    ///
+    /// Creates a [SpotLight] from this description.
+    ///
+    pub fn to_light(&self, context: &Context) -> ThreeDResult<SpotLight> {
+        SpotLight::new(
+            context,
+            self.intensity,
+            to_color_opaque(self.color),
+            &self.position.into(),
+            &self.direction.into(),
+            degrees(self.cutoff),
+            self.attenuation[0],
+            self.attenuation[1],
+            self.attenuation[2],
+        )
+    }
+}
+
+impl From<&SpotLight> for SpotLightDescription {
+    fn from(light: &SpotLight) -> Self {
+        let color = light.color();
+        let (constant, linear, exponential) = light.attenuation();
+        Self {
+            color: [color.r, color.g, color.b],
+            intensity: light.intensity(),
+            position: light.position().into(),
+            direction: light.direction().into(),
+            cutoff: Degrees::from(light.cutoff()).0,
+            attenuation: [constant, linear, exponential],
+        }
+    }
+}
+
+impl Default for SpotLightDescription {
+    fn default() -> Self {
+        Self {
+            color: [255, 255, 255],
+            intensity: 1.0,
+            position: [0.0, 0.0, 0.0],
+            direction: [0.0, -1.0, 0.0],
+            cutoff: 25.0,
+            attenuation: [1.0, 0.0, 0.0],
+        }
+    }
+}
+
+///
+/// A description of a [PointLight] in a [SceneDescription].
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PointLightDescription {
+    /// The color of the light as RGB.
+    pub color: [u8; 3],
+    /// The intensity of the light.
+    pub intensity: f32,
+    /// The position of the light.
+    pub position: [f32; 3],
+    /// The constant, linear and exponential attenuation.
+    pub attenuation: [f32; 3],
+}
+
+impl PointLightDescription {
+    ///
+    /// Creates a [PointLight] from this description.
+    ///
+    pub fn to_light(&self, context: &Context) -> ThreeDResult<PointLight> {
+        PointLight::new(
+            context,
+            self.intensity,
+            to_color_opaque(self.color),
+            &self.position.into(),
+            self.attenuation[0],
+            self.attenuation[1],
+            self.attenuation[2],
+        )
+    }
+}
+
+impl From<&PointLight> for PointLightDescription {
+    fn from(light: &PointLight) -> Self {
+        let color = light.color();
+        let (constant, linear, exponential) = light.attenuation();
+        Self {
+            color: [color.r, color.g, color.b],
+            intensity: light.intensity(),
+            position: light.position().into(),
+            attenuation: [constant, linear, exponential],
+        }
+    }
+}
+
+impl Default for PointLightDescription {
+    fn default() -> Self {
+        Self {
+            color: [255, 255, 255],
+            intensity: 1.0,
+            position: [0.0, 0.0, 0.0],
+            attenuation: [1.0, 0.0, 0.0],
+        }
+    }
+}
+
+///
+/// The projection of a [CameraDescription], see [ProjectionType].
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ProjectionDescription {
+    /// Orthographic projection with the given height of the camera film/sensor.
+    Orthographic {
+        /// Height of the camera film/sensor.
+        height: f32,
+    },
+    /// Perspective projection with the given field of view in degrees.
+    Perspective {
+        /// The field of view angle in the vertical direction in degrees.
+        field_of_view_y: f32,
+    },
+}
+
+///
+/// A description of a [Camera] in a [SceneDescription], ie. its pose and projection.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraDescription {
+    /// The position of the camera.
+    pub position: [f32; 3],
+    /// The point the camera is looking at.
+    pub target: [f32; 3],
+    /// The up direction of the camera.
+    pub up: [f32; 3],
+    /// The projection of the camera.
+    pub projection: ProjectionDescription,
+    /// The distance to the near plane.
+    pub z_near: f32,
+    /// The distance to the far plane.
+    pub z_far: f32,
+}
+
+impl CameraDescription {
+    ///
+    /// Creates a [Camera] with the given viewport from this description.
+    ///
+    pub fn to_camera(&self, context: &Context, viewport: Viewport) -> ThreeDResult<Camera> {
+        match self.projection {
+            ProjectionDescription::Orthographic { height } => Camera::new_orthographic(
+                context,
+                viewport,
+                self.position.into(),
+                self.target.into(),
+                self.up.into(),
+                height,
+                self.z_near,
+                self.z_far,
+            ),
+            ProjectionDescription::Perspective { field_of_view_y } => Camera::new_perspective(
+                context,
+                viewport,
+                self.position.into(),
+                self.target.into(),
+                self.up.into(),
+                degrees(field_of_view_y),
+                self.z_near,
+                self.z_far,
+            ),
+        }
+    }
+}
+
+impl From<&Camera> for CameraDescription {
+    fn from(camera: &Camera) -> Self {
+        Self {
+            position: (*camera.position()).into(),
+            target: (*camera.target()).into(),
+            up: (*camera.up()).into(),
+            projection: match camera.projection_type() {
+                ProjectionType::Orthographic { height } => {
+                    ProjectionDescription::Orthographic { height: *height }
+                }
+                ProjectionType::Perspective { field_of_view_y } => {
+                    ProjectionDescription::Perspective {
+                        field_of_view_y: Degrees::from(*field_of_view_y).0,
+                    }
+                }
+            },
+            z_near: camera.z_near(),
+            z_far: camera.z_far(),
+        }
+    }
+}
+
+impl Default for CameraDescription {
+    fn default() -> Self {
+        Self {
+            position: [0.0, 0.0, 5.0],
+            target: [0.0, 0.0, 0.0],
+            up: [0.0, 1.0, 0.0],
+            projection: ProjectionDescription::Perspective {
+                field_of_view_y: 45.0,
+            },
+            z_near: 0.1,
+            z_far: 1000.0,
+        }
+    }
+}
+
+fn resolve(scene_path: &Path, path: &str) -> PathBuf {
+    if Path::new(path).is_absolute() || reqwest::Url::parse(path).is_ok() {
+        PathBuf::from(path)
+    } else {
+        scene_path
+            .parent()
+            .map(|p| p.join(path))
+            .unwrap_or_else(|| PathBuf::from(path))
+    }
+}
+
+fn parse_mesh(loaded: &mut Loaded, path: &Path) -> ThreeDResult<(Vec<CPUMesh>, Vec<CPUMaterial>)> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    match extension.as_deref() {
+        #[cfg(feature = "obj-io")]
+        Some("obj") => {
+            let (cpu_meshes, cpu_materials): (Vec<_>, Vec<_>) =
+                loaded.obj(path)?.into_iter().unzip();
+            Ok((cpu_meshes, cpu_materials.into_iter().flatten().collect()))
+        }
+        #[cfg(feature = "gltf-io")]
+        Some("gltf") | Some("glb") => loaded.gltf(path),
+        _ => Err(IOError::UnsupportedMeshFormat(
+            path.to_str().unwrap().to_string(),
+        ))?,
+    }
+}
+
+fn to_color(rgba: [u8; 4]) -> Color {
+    Color::new(rgba[0], rgba[1], rgba[2], rgba[3])
+}
+
+fn to_color_opaque(rgb: [u8; 3]) -> Color {
+    Color::new_opaque(rgb[0], rgb[1], rgb[2])
+}
+
+fn from_color(color: Color) -> [u8; 4] {
+    [color.r, color.g, color.b, color.a]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene() -> SceneDescription {
+        let mut object = ObjectDescription {
+            name: "statue".to_string(),
+            mesh: "models/statue.obj".to_string(),
+            dependencies: vec!["models/statue.mtl".to_string()],
+            material: Some(MaterialDescription {
+                name: "marble".to_string(),
+                albedo: [200, 190, 180, 255],
+                albedo_texture: Some("textures/marble.png".to_string()),
+                roughness: 0.3,
+                alpha_cutout: Some(0.5),
+                double_sided: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        object.set_transformation(
+            Mat4::from_translation(vec3(1.0, 2.0, 3.0)) * Mat4::from_scale(0.5),
+        );
+        SceneDescription {
+            objects: vec![
+                object,
+                ObjectDescription {
+                    mesh: "https://example.com/model.glb".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ambient_light: Some(AmbientLightDescription {
+                color: [255, 250, 240],
+                intensity: 0.2,
+                ground_color: Some([50, 40, 30]),
+            }),
+            directional_lights: vec![DirectionalLightDescription {
+                color: [255, 255, 255],
+                intensity: 1.5,
+                direction: [0.0, -1.0, -1.0],
+            }],
+            spot_lights: vec![SpotLightDescription {
+                color: [255, 0, 0],
+                intensity: 2.0,
+                position: [0.0, 5.0, 0.0],
+                direction: [0.0, -1.0, 0.0],
+                cutoff: 25.0,
+                attenuation: [1.0, 0.1, 0.01],
+            }],
+            point_lights: vec![PointLightDescription {
+                color: [0, 0, 255],
+                intensity: 0.7,
+                position: [-2.0, 1.0, 4.0],
+                attenuation: [1.0, 0.0, 0.05],
+            }],
+            camera: CameraDescription {
+                position: [5.0, 3.0, 5.0],
+                target: [0.0, 1.0, 0.0],
+                up: [0.0, 1.0, 0.0],
+                projection: ProjectionDescription::Orthographic { height: 10.0 },
+                z_near: 0.5,
+                z_far: 50.0,
+            },
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("three-d-scene-{}.json", std::process::id()));
+        let scene = scene();
+        scene.save(&path).unwrap();
+        let loaded = SceneDescription::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), scene);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let scene = scene();
+        let json = scene.to_json().unwrap();
+        assert_eq!(SceneDescription::from_json(&json).unwrap(), scene);
+
+        let mut loaded = Loaded::new();
+        loaded.insert_bytes("scene.json", json.into_bytes());
+        assert_eq!(loaded.scene("scene.json").unwrap(), scene);
+    }
+
+    #[test]
+    fn missing_and_unknown_fields() {
+        let scene = SceneDescription::from_json(
+            r#"{ "objects": [{ "mesh": "a.obj", "unknown": 1 }], "unknown": [] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            scene,
+            SceneDescription {
+                objects: vec![ObjectDescription {
+                    mesh: "a.obj".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }
+        );
+        assert!(SceneDescription::from_json("{ \"objects\": 1 }").is_err());
+    }
+
+    #[test]
+    fn asset_paths() {
+        let paths = scene().asset_paths("scenes/scene.json");
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("https://example.com/model.glb"),
+                PathBuf::from("scenes/models/statue.mtl"),
+                PathBuf::from("scenes/models/statue.obj"),
+                PathBuf::from("scenes/textures/marble.png"),
+            ]
+        );
+    }
+}
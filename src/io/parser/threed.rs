@@ -41,8 +41,13 @@ impl Loaded {
                 indices: mesh.indices.map(|i| Indices::U32(i)),
                 normals: mesh.normals,
                 uvs: mesh.uvs,
+                uvs2: None,
                 colors: None,
+                color_space: ColorSpace::Linear,
                 tangents: None,
+                morph_targets: Vec::new(),
+                joint_indices: None,
+                joint_weights: None,
             });
         }
 
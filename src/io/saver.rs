@@ -15,4 +15,26 @@ impl Saver {
         file.write_all(bytes)?;
         Ok(())
     }
+
+    ///
+    /// Saves the files at the given paths in an asset bundle at the given output path, so they can be loaded in a single request, which is especially useful on web.
+    /// Each file is accessed by the path given here when the bundle is loaded using the [Loader](crate::Loader) or [Loaded::from_bundle](crate::Loaded::from_bundle),
+    /// for example `loaded.obj("assets/model.obj")` if the bundle is saved with the path `"assets/model.obj"`.
+    /// The files are compressed if that makes them smaller. The bundle only depends on the content and order of the files, so the same files always result in the same bundle.
+    ///
+    #[cfg(feature = "bundle-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bundle-io")))]
+    pub fn save_bundle<P: AsRef<Path>>(
+        paths: &[P],
+        output: impl AsRef<Path>,
+    ) -> crate::ThreeDResult<()> {
+        let files = paths
+            .iter()
+            .map(|path| {
+                let name = path.as_ref().to_str().unwrap().replace('\\', "/");
+                Ok((name, std::fs::read(path.as_ref())?))
+            })
+            .collect::<crate::ThreeDResult<Vec<_>>>()?;
+        Self::save_file(output, &crate::io::bundle::write_bundle(&files))
+    }
 }
@@ -0,0 +1,497 @@
+use crate::core::*;
+use crate::io::*;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+///
+/// A parser which parses a file chunk by chunk while it is loaded, see [Loader::load_streamed].
+/// Implemented by [PointChunksParser] and, with the `ply-io` feature, `PlyStreamParser`.
+///
+pub trait IncrementalParser {
+    /// The type of the parsed resource.
+    type Output;
+
+    ///
+    /// Parses the next chunk of the file. The chunks can have any size, so a value can be split between two chunks,
+    /// in which case the parser must keep the beginning of the value until the next chunk is consumed.
+    ///
+    fn consume(&mut self, chunk: &[u8]) -> ThreeDResult<()>;
+
+    ///
+    /// Returns the parsed resource after all chunks of the file have been consumed.
+    ///
+    /// # Errors
+    /// Will return an error if the file ended before the resource was complete.
+    ///
+    fn finish(self) -> ThreeDResult<Self::Output>;
+}
+
+///
+/// The progress of a streamed loading, see [Loader::load_streamed].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct StreamProgress {
+    /// The number of bytes of the file which have been parsed.
+    pub loaded_bytes: u64,
+    /// The size of the file in bytes, if it is known. On web, this is the `Content-Length` of the response,
+    /// which is not the size of the file if the response is compressed.
+    pub total_bytes: Option<u64>,
+}
+
+impl StreamProgress {
+    ///
+    /// Returns the fraction in the range `[0..1]` of the file which has been parsed, if the size of the file is known.
+    ///
+    pub fn fraction(&self) -> Option<f32> {
+        self.total_bytes
+            .filter(|total| *total > 0)
+            .map(|total| (self.loaded_bytes as f64 / total as f64).min(1.0) as f32)
+    }
+}
+
+// The number of bytes read from a file on desktop before the chunk is handed to the parser
+#[cfg(not(target_arch = "wasm32"))]
+const CHUNK_SIZE: usize = 1 << 20;
+
+// The number of chunks read on desktop which are not yet parsed before the reading waits
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_PENDING_CHUNKS: usize = 8;
+
+enum StreamMessage {
+    Started(Option<u64>),
+    Chunk(Vec<u8>),
+    Done,
+    Failed(String),
+}
+
+// On desktop, the chunks are read in a separate thread, which stops when the receiver is dropped.
+// On web, the chunks are read from the body of the response, which is cancelled when the queue is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+struct ChunkSource(std::sync::mpsc::Receiver<StreamMessage>);
+
+#[cfg(target_arch = "wasm32")]
+struct ChunkSource(std::rc::Rc<std::cell::RefCell<std::collections::VecDeque<StreamMessage>>>);
+
+impl ChunkSource {
+    fn next(&self) -> Option<StreamMessage> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use std::sync::mpsc::TryRecvError;
+            match self.0.try_recv() {
+                Ok(message) => Some(message),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => Some(StreamMessage::Failed(
+                    "the reading stopped unexpectedly".to_string(),
+                )),
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.0.borrow_mut().pop_front()
+        }
+    }
+}
+
+///
+/// A file which is parsed chunk by chunk while it is loaded, started by [Loader::load_streamed].
+/// The chunks which have been loaded are parsed when calling [StreamedLoading::progress], typically once each frame, so the parsing is spread over several frames.
+/// Dropping the streamed loading or calling [StreamedLoading::cancel] stops the loading and releases the file or request.
+///
+pub struct StreamedLoading<P: IncrementalParser> {
+    path: PathBuf,
+    parser: Option<P>,
+    on_progress: Box<dyn FnMut(&mut P, StreamProgress)>,
+    source: Option<ChunkSource>,
+    progress: StreamProgress,
+    result: Option<ThreeDResult<P::Output>>,
+}
+
+impl<P: IncrementalParser> StreamedLoading<P> {
+    ///
+    /// Parses the chunks which have been loaded since the last call, but not more than can be parsed within approximately the given time budget,
+    /// and calls the `on_progress` closure given to [Loader::load_streamed] after each chunk.
+    /// When the whole file is parsed, the result is available using [StreamedLoading::take_result].
+    /// Returns the progress of the loading.
+    ///
+    pub fn progress(&mut self, time_budget: Duration) -> StreamProgress {
+        let timer = Timer::start();
+        while !self.is_done() && timer.elapsed() < time_budget {
+            let message = match self.source.as_ref().and_then(|s| s.next()) {
+                Some(message) => message,
+                None => break,
+            };
+            match message {
+                StreamMessage::Started(total_bytes) => self.progress.total_bytes = total_bytes,
+                StreamMessage::Chunk(chunk) => {
+                    let parser = self.parser.as_mut().unwrap();
+                    if let Err(e) = parser.consume(&chunk) {
+                        self.stop(Err(e));
+                    } else {
+                        self.progress.loaded_bytes += chunk.len() as u64;
+                        (self.on_progress)(parser, self.progress);
+                    }
+                }
+                StreamMessage::Done => {
+                    let result = self.parser.take().unwrap().finish();
+                    self.stop(result);
+                }
+                StreamMessage::Failed(message) => {
+                    let path = self.path.to_str().unwrap().to_string();
+                    self.stop(Err(Box::new(IOError::Stream(path, message))));
+                }
+            }
+        }
+        self.progress
+    }
+
+    ///
+    /// Stops the loading, if it is not done, and releases the file or request. The result is then an [IOError::Cancelled] error.
+    /// The `on_progress` closure is not called after this and the parser is dropped together with the data parsed so far.
+    ///
+    pub fn cancel(&mut self) {
+        if !self.is_done() {
+            let path = self.path.to_str().unwrap().to_string();
+            self.stop(Err(Box::new(IOError::Cancelled(path))));
+        }
+    }
+
+    ///
+    /// Returns true if the loading is done, either because the whole file is parsed, an error occurred or the loading is cancelled.
+    ///
+    pub fn is_done(&self) -> bool {
+        self.source.is_none()
+    }
+
+    ///
+    /// Returns the parsed resource, or the error that stopped the loading, if the loading is done and the result is not already taken.
+    ///
+    pub fn take_result(&mut self) -> Option<ThreeDResult<P::Output>> {
+        self.result.take()
+    }
+
+    fn stop(&mut self, result: ThreeDResult<P::Output>) {
+        self.source = None;
+        self.parser = None;
+        self.result = Some(result);
+    }
+}
+
+impl Loader {
+    ///
+    /// Starts loading the resource at the given path and feeds the loaded chunks to the given parser,
+    /// so a large file can be parsed and shown while it is loading instead of waiting for the whole file.
+    /// On desktop, the file is read in a separate thread, and on web, the body of the response is read as it arrives.
+    /// The chunks are parsed when calling [StreamedLoading::progress] and the `on_progress` closure is called after each chunk with the parser and the progress,
+    /// for example to take the parsed data from the parser and upload it to the GPU, see [PointChunksParser::take_new_vertices].
+    ///
+    pub fn load_streamed<P: IncrementalParser>(
+        path: impl AsRef<Path>,
+        parser: P,
+        on_progress: impl 'static + FnMut(&mut P, StreamProgress),
+    ) -> StreamedLoading<P> {
+        let path = path.as_ref().to_path_buf();
+        #[cfg(not(target_arch = "wasm32"))]
+        let source = {
+            let (sender, receiver) = std::sync::mpsc::sync_channel(MAX_PENDING_CHUNKS);
+            let thread_path = path.clone();
+            std::thread::spawn(move || {
+                if let Err(message) = read_chunks(&thread_path, &sender) {
+                    let _ = sender.send(StreamMessage::Failed(message));
+                }
+            });
+            ChunkSource(receiver)
+        };
+        #[cfg(target_arch = "wasm32")]
+        let source = {
+            let queue =
+                std::rc::Rc::new(std::cell::RefCell::new(std::collections::VecDeque::new()));
+            let url = Self::url(&path);
+            let fetch_queue = queue.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(message) = fetch_chunks(url, fetch_queue.clone()).await {
+                    fetch_queue
+                        .borrow_mut()
+                        .push_back(StreamMessage::Failed(message));
+                }
+            });
+            ChunkSource(queue)
+        };
+        StreamedLoading {
+            path,
+            parser: Some(parser),
+            on_progress: Box::new(on_progress),
+            source: Some(source),
+            progress: StreamProgress::default(),
+            result: None,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_chunks(
+    path: &Path,
+    sender: &std::sync::mpsc::SyncSender<StreamMessage>,
+) -> Result<(), String> {
+    use std::io::Read;
+    let (mut reader, total_bytes): (Box<dyn Read>, Option<u64>) =
+        if let Ok(url) = reqwest::Url::parse(path.to_str().unwrap()) {
+            let response = reqwest::blocking::get(url)
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| e.to_string())?;
+            let total_bytes = response.content_length();
+            (Box::new(response), total_bytes)
+        } else {
+            let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+            let total_bytes = file.metadata().ok().map(|m| m.len());
+            (Box::new(file), total_bytes)
+        };
+    // Sending fails when the streamed loading is cancelled or dropped, which stops the reading and closes the file
+    if sender.send(StreamMessage::Started(total_bytes)).is_err() {
+        return Ok(());
+    }
+    loop {
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        let count = match reader.read(&mut chunk) {
+            Ok(count) => count,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.to_string()),
+        };
+        if count == 0 {
+            let _ = sender.send(StreamMessage::Done);
+            return Ok(());
+        }
+        chunk.truncate(count);
+        if sender.send(StreamMessage::Chunk(chunk)).is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_chunks(
+    url: reqwest::Url,
+    queue: std::rc::Rc<std::cell::RefCell<std::collections::VecDeque<StreamMessage>>>,
+) -> Result<(), String> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    let error = |e: wasm_bindgen::JsValue| format!("{:?}", e);
+    let response: web_sys::Response =
+        JsFuture::from(web_sys::window().unwrap().fetch_with_str(url.as_str()))
+            .await
+            .map_err(error)?
+            .unchecked_into();
+    if !response.ok() {
+        return Err(format!(
+            "the request failed with status {}",
+            response.status()
+        ));
+    }
+    let total_bytes = response
+        .headers()
+        .get("Content-Length")
+        .ok()
+        .flatten()
+        .and_then(|length| length.parse().ok());
+    queue
+        .borrow_mut()
+        .push_back(StreamMessage::Started(total_bytes));
+    let reader: web_sys::ReadableStreamDefaultReader = response
+        .body()
+        .ok_or_else(|| "the response has no body".to_string())?
+        .get_reader()
+        .unchecked_into();
+    loop {
+        // The queue is only referenced here when the streamed loading is cancelled or dropped,
+        // in which case the response is cancelled so the rest of the file is not downloaded
+        if std::rc::Rc::strong_count(&queue) == 1 {
+            let _ = reader.cancel();
+            return Ok(());
+        }
+        let result = JsFuture::from(reader.read()).await.map_err(error)?;
+        let done = js_sys::Reflect::get(&result, &"done".into())
+            .map_err(error)?
+            .as_bool()
+            .unwrap_or(true);
+        if done {
+            queue.borrow_mut().push_back(StreamMessage::Done);
+            return Ok(());
+        }
+        let value = js_sys::Reflect::get(&result, &"value".into()).map_err(error)?;
+        queue.borrow_mut().push_back(StreamMessage::Chunk(
+            js_sys::Uint8Array::new(&value).to_vec(),
+        ));
+    }
+}
+
+// The first bytes of a point chunks file, followed by the version of the format
+const POINT_CHUNKS_MAGIC: &[u8; 4] = b"3DPC";
+const POINT_CHUNKS_VERSION: u32 = 1;
+
+///
+/// An [IncrementalParser] for the point chunks format, a simple binary format for large point clouds which is designed to be parsed while it is loaded.
+/// The file starts with the 4 bytes `3DPC` and the version `1` as a little endian `u32`, followed by any number of chunks.
+/// Each chunk consists of the number of points in the chunk as a little endian `u32`, followed by the positions as three little endian `f32` per point
+/// and the colors as four `u8` (red, green, blue and alpha) in the sRGB color space per point.
+/// Save a point cloud in this format using [Saver::save_point_chunks](crate::Saver::save_point_chunks) and load it at once using [Loaded::point_chunks].
+///
+#[derive(Default)]
+pub struct PointChunksParser {
+    buffer: Vec<u8>,
+    header_read: bool,
+    mesh: CPUMesh,
+    taken_vertices: usize,
+}
+
+impl PointChunksParser {
+    ///
+    /// Constructs a new parser for a file in the point chunks format.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns a mesh without indices containing the points of the chunks parsed since the last call, or `None` if no chunks have been parsed since then.
+    /// The points are still part of the mesh returned when the parsing is finished.
+    ///
+    pub fn take_new_vertices(&mut self) -> Option<CPUMesh> {
+        let mesh = vertices_from(&self.mesh, self.taken_vertices);
+        self.taken_vertices = self.mesh.positions.len() / 3;
+        mesh
+    }
+}
+
+impl IncrementalParser for PointChunksParser {
+    type Output = CPUMesh;
+
+    fn consume(&mut self, chunk: &[u8]) -> ThreeDResult<()> {
+        self.buffer.extend_from_slice(chunk);
+        let mut position = 0;
+        if !self.header_read {
+            if self.buffer.len() < 8 {
+                return Ok(());
+            }
+            if &self.buffer[0..4] != POINT_CHUNKS_MAGIC {
+                Err(IOError::PointChunks(
+                    "the file does not start with 3DPC".to_string(),
+                ))?;
+            }
+            let version = read_u32(&self.buffer[4..]);
+            if version != POINT_CHUNKS_VERSION {
+                Err(IOError::PointChunks(format!(
+                    "the version {} is not supported",
+                    version
+                )))?;
+            }
+            self.header_read = true;
+            self.mesh.color_space = ColorSpace::Srgb;
+            position = 8;
+        }
+        while self.buffer.len() - position >= 4 {
+            let count = read_u32(&self.buffer[position..]) as usize;
+            if self.buffer.len() - position < 4 + count * 16 {
+                break;
+            }
+            let positions = &self.buffer[position + 4..position + 4 + count * 12];
+            self.mesh.positions.extend(
+                positions
+                    .chunks(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            );
+            let colors = &self.buffer[position + 4 + count * 12..position + 4 + count * 16];
+            self.mesh
+                .colors
+                .get_or_insert_with(Vec::new)
+                .extend_from_slice(colors);
+            position += 4 + count * 16;
+        }
+        self.buffer.drain(..position);
+        Ok(())
+    }
+
+    fn finish(self) -> ThreeDResult<CPUMesh> {
+        if !self.header_read || !self.buffer.is_empty() {
+            Err(IOError::PointChunks(
+                "the file ends in the middle of a chunk".to_string(),
+            ))?;
+        }
+        Ok(self.mesh)
+    }
+}
+
+impl Loaded {
+    ///
+    /// Deserialize a loaded file in the point chunks format into a mesh without indices, see [PointChunksParser].
+    /// Use [Loader::load_streamed] together with a [PointChunksParser] instead to parse the file while it is loading.
+    ///
+    pub fn point_chunks(&mut self, path: impl AsRef<Path>) -> ThreeDResult<CPUMesh> {
+        let bytes = self.remove_bytes(path.as_ref())?;
+        let mut parser = PointChunksParser::new();
+        parser.consume(&bytes)?;
+        let mut mesh = parser.finish()?;
+        mesh.name = path
+            .as_ref()
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+        Ok(mesh)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Saver {
+    ///
+    /// Saves the vertices of the given mesh in the point chunks format, see [PointChunksParser], with the given number of points in each chunk.
+    /// The colors of the vertices are converted to the sRGB color space and saved if present, otherwise the points are white.
+    ///
+    pub fn save_point_chunks(
+        path: impl AsRef<Path>,
+        cpu_mesh: &CPUMesh,
+        points_per_chunk: usize,
+    ) -> ThreeDResult<()> {
+        let count = cpu_mesh.positions.len() / 3;
+        let mut bytes =
+            Vec::with_capacity(8 + count * 16 + (count / points_per_chunk.max(1) + 1) * 4);
+        bytes.extend_from_slice(POINT_CHUNKS_MAGIC);
+        bytes.extend_from_slice(&POINT_CHUNKS_VERSION.to_le_bytes());
+        let mut start = 0;
+        while start < count {
+            let end = (start + points_per_chunk.max(1)).min(count);
+            bytes.extend_from_slice(&((end - start) as u32).to_le_bytes());
+            for value in cpu_mesh.positions[start * 3..end * 3].iter() {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            if let Some(ref colors) = cpu_mesh.colors {
+                bytes.extend_from_slice(
+                    &cpu_mesh
+                        .color_space
+                        .convert_colors(&colors[start * 4..end * 4], ColorSpace::Srgb),
+                );
+            } else {
+                bytes.extend(std::iter::repeat(255).take((end - start) * 4));
+            }
+            start = end;
+        }
+        Self::save_file(path, &bytes)
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+// Returns a mesh containing the vertices of the given mesh starting at the given vertex, or None if there are no such vertices
+pub(crate) fn vertices_from(mesh: &CPUMesh, start: usize) -> Option<CPUMesh> {
+    if mesh.positions.len() <= start * 3 {
+        return None;
+    }
+    Some(CPUMesh {
+        name: mesh.name.clone(),
+        positions: mesh.positions[start * 3..].to_vec(),
+        normals: mesh.normals.as_ref().map(|n| n[start * 3..].to_vec()),
+        colors: mesh.colors.as_ref().map(|c| c[start * 4..].to_vec()),
+        color_space: mesh.color_space,
+        ..Default::default()
+    })
+}
@@ -4,7 +4,8 @@
 //!
 
 pub use crate::core::{
-    math::*, render_states::*, render_target::*, texture::*, Camera, Context, Viewport,
+    math::*, render_states::*, render_target::*, texture::*, Anchor, Camera, CameraBuilder,
+    CameraIntrinsics, Context, Viewport, YDirection,
 };
 
 pub mod material;
@@ -18,6 +19,42 @@ mod deferred_pipeline;
 #[doc(inline)]
 pub use deferred_pipeline::*;
 
+mod hdr_pipeline;
+#[doc(inline)]
+pub use hdr_pipeline::*;
+
+mod dynamic_resolution;
+#[doc(inline)]
+pub use dynamic_resolution::*;
+
+mod high_resolution;
+#[doc(inline)]
+pub use high_resolution::*;
+
+mod split_screen_pipeline;
+#[doc(inline)]
+pub use split_screen_pipeline::*;
+
+mod dataset_renderer;
+#[doc(inline)]
+pub use dataset_renderer::*;
+
+#[cfg(feature = "image-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "image-io")))]
+mod turntable;
+#[doc(inline)]
+#[cfg(feature = "image-io")]
+pub use turntable::*;
+
+mod program_warm_up;
+
+#[cfg(all(test, feature = "glutin-window", not(target_arch = "wasm32")))]
+pub(crate) mod test_utils;
+
+mod shader_source_builder;
+#[doc(inline)]
+pub use shader_source_builder::*;
+
 pub mod effect;
 pub use effect::*;
 
@@ -34,28 +71,194 @@ use thiserror::Error;
 ///
 #[derive(Debug, Error)]
 #[allow(missing_docs)]
-pub enum RendererError {}
+pub enum RendererError {
+    #[error(
+        "an animation texture of {0}x{1} texels is needed, but the maximum texture size is {2}"
+    )]
+    AnimationTextureTooLarge(u32, u32, u32),
+    #[error("the mesh must have uv coordinates to be painted")]
+    MissingUvCoordinates,
+    #[error("the post effect needs a {0} texture which is missing in the effect inputs")]
+    MissingEffectInput(String),
+    #[error("the {0} texture of a material instance can only be set if the base material has a {0} texture")]
+    MissingBaseTexture(String),
+    #[error("a tile overlap of {0} pixels with {1} samples per pixel leaves no room for a tile in a target of {2} samples")]
+    HighResTileTooSmall(u32, u32, u32),
+    #[error("failed to parse the font: {0}")]
+    InvalidFont(String),
+    #[error("a glyph atlas needs at least one font")]
+    MissingFont,
+    #[error("{0} positions are given, but there are {1} bars")]
+    InvalidBarCount(usize, usize),
+    #[cfg(feature = "gif-export")]
+    #[error("the size {0}x{1} is larger than the maximum size of a GIF of 65535x65535 pixels")]
+    GifTooLarge(u32, u32),
+    #[error("the intensity {0} of a light must be non-negative and finite")]
+    InvalidLightIntensity(f32),
+    #[error("the direction {0:?} of a light must be non-zero and finite")]
+    InvalidLightDirection(Vec3),
+    #[error("the {0} {1} of a material must be in the range [0..1]")]
+    InvalidMaterialParameter(String, f32),
+    #[error("the depth pass must be done before the hidden lines can be rendered")]
+    MissingDepthPass,
+    #[error("the mesh has {0} triangle corners, but at most 16777216 are supported when recomputing the normals")]
+    DynamicNormalsTooLarge(usize),
+}
 
 ///
-/// Render the objects. Also avoids rendering objects outside the camera frustum and render the objects in the order given by [cmp_render_order].
+/// Render the objects. Also avoids rendering objects outside the camera frustum and render the objects in the order given by [RenderPassOptions::default].
 /// Must be called in a render target render function, for example in the callback function of [Screen::write].
 ///
 pub fn render_pass(camera: &Camera, objects: &[impl Object], lights: &Lights) -> ThreeDResult<()> {
-    let mut culled_objects = objects
-        .iter()
-        .filter(|o| camera.in_frustum(&o.aabb()))
-        .collect::<Vec<_>>();
-    culled_objects.sort_by(|a, b| cmp_render_order(camera, a, b));
-    for object in culled_objects {
-        object.render(camera, lights)?;
+    render_pass_with_options(camera, objects, lights, RenderPassOptions::default())
+}
+
+///
+/// Same as [render_pass], except that the order in which the objects are rendered is specified by the given options.
+/// Use [ForwardPipeline::render_pass_with_options] to avoid allocating memory for the sorting each frame.
+///
+pub fn render_pass_with_options(
+    camera: &Camera,
+    objects: &[impl Object],
+    lights: &Lights,
+    options: RenderPassOptions,
+) -> ThreeDResult<()> {
+    render_sorted(camera, objects, lights, options, &mut Vec::new())
+}
+
+///
+/// Options specifying the order in which [render_pass_with_options] and [ForwardPipeline::render_pass_with_options] render the objects.
+/// The opaque objects are always rendered before the transparent objects.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RenderPassOptions {
+    ///
+    /// Whether or not to sort the opaque objects by [Object::material_id], so objects which are rendered with the same program are rendered after each other,
+    /// which minimizes the number of state changes. Objects with the same material id are sorted from nearest to farthest away from the camera,
+    /// measured to the center of their bounding box. Otherwise the opaque objects are rendered in the given order.
+    ///
+    pub sort_by_material: bool,
+    ///
+    /// Whether or not to sort the transparent objects from farthest away to closest to the camera, measured to the point of their bounding box nearest to the camera,
+    /// which is needed for blending them correctly. Otherwise the transparent objects are rendered in the given order.
+    /// The transparent objects are always sorted by [Object::render_order] first, so distance only decides the order of objects with the same render order.
+    ///
+    pub sort_transparent_back_to_front: bool,
+}
+
+impl Default for RenderPassOptions {
+    fn default() -> Self {
+        Self {
+            sort_by_material: true,
+            sort_transparent_back_to_front: true,
+        }
+    }
+}
+
+// The index of an object in the given objects together with its sort keys, ie. its transparency, render order, material id and distances to the camera
+pub(crate) type RenderOrderKey = (usize, bool, i32, u64, (f32, f32));
+
+// The distance from the camera to the nearest point of the bounding box, followed by the distance to its center which orders objects with the same nearest distance,
+// for example when the camera is inside both bounding boxes
+fn transparent_distances(camera: &Camera, aabb: &AxisAlignedBoundingBox) -> (f32, f32) {
+    (
+        aabb.distance(camera.position()),
+        camera.position().distance2(aabb.center()),
+    )
+}
+
+// Orders transparent objects from farthest away to closest to the camera
+fn cmp_transparent_distances(
+    distances0: &(f32, f32),
+    distances1: &(f32, f32),
+) -> std::cmp::Ordering {
+    distances1
+        .0
+        .partial_cmp(&distances0.0)
+        .unwrap_or(std::cmp::Ordering::Equal)
+        .then(
+            distances1
+                .1
+                .partial_cmp(&distances0.1)
+                .unwrap_or(std::cmp::Ordering::Equal),
+        )
+}
+
+// Culls and sorts the objects using the given scratch vector, which is reused between frames to avoid allocations
+pub(crate) fn render_sorted(
+    camera: &Camera,
+    objects: &[impl Object],
+    lights: &Lights,
+    options: RenderPassOptions,
+    scratch: &mut Vec<RenderOrderKey>,
+) -> ThreeDResult<()> {
+    scratch.clear();
+    scratch.extend(
+        objects
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| {
+                camera.sphere_in_frustum(&o.bounding_sphere()) && camera.in_frustum(&o.aabb())
+            })
+            .map(|(index, o)| {
+                let transparent = o.is_transparent();
+                let render_order = if transparent { o.render_order() } else { 0 };
+                let material_id = if !transparent && options.sort_by_material {
+                    o.material_id()
+                } else {
+                    0
+                };
+                let distances = if !transparent && options.sort_by_material {
+                    // The opaque objects are ordered from nearest to farthest away, so the fragments of the objects behind are discarded early by the depth test,
+                    // which is the reverse order of the transparent objects
+                    let distance = camera.position().distance2(o.aabb().center());
+                    (-distance, -distance)
+                } else if transparent && options.sort_transparent_back_to_front {
+                    transparent_distances(camera, &o.aabb())
+                } else {
+                    (0.0, 0.0)
+                };
+                (index, transparent, render_order, material_id, distances)
+            }),
+    );
+    // The index is the last key, so the unstable (and allocation free) sort keeps the given order of objects with equal keys
+    scratch.sort_unstable_by(
+        |(index0, transparent0, order0, id0, distances0),
+         (index1, transparent1, order1, id1, distances1)| {
+            transparent0
+                .cmp(transparent1)
+                .then(order0.cmp(order1))
+                .then(id0.cmp(id1))
+                .then(cmp_transparent_distances(distances0, distances1))
+                .then(index0.cmp(index1))
+        },
+    );
+    for (index, _, _, _, _) in scratch.iter() {
+        objects[*index].render(camera, lights)?;
+    }
+    Ok(())
+}
+
+///
+/// Render the 2D objects on top of everything already rendered in the given viewport, for example a heads-up display on top of a 3D scene,
+/// see [Object2D::render_as_overlay]. Should therefore be called after the 3D scene is rendered.
+/// Each object is given together with its z-order and the objects are rendered in the order of increasing z-order,
+/// so an object is rendered on top of the objects with a lower z-order. Objects with the same z-order are rendered in the given order.
+/// Must be called in a render target render function, for example in the callback function of [Screen::write].
+///
+pub fn render_overlay(viewport: Viewport, objects: &[(i32, &dyn Object2D)]) -> ThreeDResult<()> {
+    let mut sorted_objects = objects.iter().collect::<Vec<_>>();
+    sorted_objects.sort_by_key(|(z_order, _)| *z_order);
+    for (_, object) in sorted_objects {
+        object.render_as_overlay(viewport)?;
     }
     Ok(())
 }
 
 ///
 /// Compare function for sorting objects based on distance from the camera.
-/// The order is opaque objects from nearest to farthest away from the camera,
-/// then transparent objects from farthest away to closest to the camera.
+/// The order is opaque objects from nearest to farthest away from the camera measured to the center of their bounding box,
+/// then transparent objects ordered by [Object::render_order] and from farthest away to closest to the camera measured to the nearest point of their bounding box.
 ///
 pub fn cmp_render_order(
     camera: &Camera,
@@ -63,11 +266,16 @@ pub fn cmp_render_order(
     obj1: impl Object,
 ) -> std::cmp::Ordering {
     if obj0.is_transparent() == obj1.is_transparent() {
-        let distance_a = camera.position().distance2(obj0.aabb().center());
-        let distance_b = camera.position().distance2(obj1.aabb().center());
         if obj0.is_transparent() {
-            distance_b.partial_cmp(&distance_a).unwrap()
+            obj0.render_order()
+                .cmp(&obj1.render_order())
+                .then(cmp_transparent_distances(
+                    &transparent_distances(camera, &obj0.aabb()),
+                    &transparent_distances(camera, &obj1.aabb()),
+                ))
         } else {
+            let distance_a = camera.position().distance2(obj0.aabb().center());
+            let distance_b = camera.position().distance2(obj1.aabb().center());
             distance_a.partial_cmp(&distance_b).unwrap()
         }
     } else {
@@ -82,7 +290,8 @@ pub fn cmp_render_order(
 ///
 /// Finds the closest intersection between a ray from the given camera in the given pixel coordinate and the given geometries.
 /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the top left corner of the viewport
-/// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the bottom right corner.
+/// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the bottom right corner,
+/// use [FrameInput::to_viewport_pixel](crate::FrameInput::to_viewport_pixel) to get the pixel from the position of a mouse event.
 /// Returns ```None``` if no geometry was hit between the near (`z_near`) and far (`z_far`) plane for this camera.
 ///
 pub fn pick<S: Shadable>(
@@ -175,7 +384,7 @@ pub fn ray_intersect<S: Shadable>(
             },
         )?;
     }
-    let depth = texture.read(viewport)?[0];
+    let depth = texture.read_internal(viewport)?[0];
     Ok(if depth < 1.0 {
         Some(position + direction * depth * max_depth)
     } else {
@@ -0,0 +1,356 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// The aligned images of a frame rendered by a [DatasetRenderer], see [DatasetRenderer::read_all].
+/// All images have the same size and the pixels are ordered row by row starting with the top row,
+/// so the pixel at `(x, y)`, where `(0, 0)` is the top left corner, is at index `y * width + x` of the images with one value per pixel.
+///
+#[derive(Debug, Clone)]
+pub struct DatasetFrame {
+    /// The width of the images in pixels.
+    pub width: u32,
+    /// The height of the images in pixels.
+    pub height: u32,
+    /// The colors as four bytes per pixel (red, green, blue and alpha) in sRGB color space.
+    pub color: Vec<u8>,
+    /// The depth of each pixel, ie. the distance to the camera along the view direction in world units, or 0 where no object is visible.
+    pub depth: Vec<f32>,
+    /// The id of the object visible at each pixel, or 0 where no object is visible.
+    pub ids: Vec<u32>,
+    /// The normal of the surface visible at each pixel as three values per pixel (x, y and z) in view space, see [Camera::view], or zero where no object is visible.
+    pub normals: Vec<f32>,
+    /// The view matrix of the camera, ie. the transformation from world space to view space.
+    pub view: Mat4,
+    /// The intrinsic parameters of the camera, or `None` if the camera has an orthographic projection.
+    pub intrinsics: Option<CameraIntrinsics>,
+}
+
+impl DatasetFrame {
+    ///
+    /// Returns the position in view space of the surface visible at the given pixel, computed from the depth and the intrinsic parameters of the camera,
+    /// or `None` if no object is visible at that pixel, the pixel is outside the images or the camera has an orthographic projection.
+    /// Use the inverse of [DatasetFrame::view] to transform the position to world space.
+    ///
+    pub fn view_position(&self, x: u32, y: u32) -> Option<Vec3> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let depth = self.depth[(y * self.width + x) as usize];
+        match self.intrinsics {
+            Some(intrinsics) if depth > 0.0 => Some(vec3(
+                (x as f32 - intrinsics.cx) * depth / intrinsics.fx,
+                -(y as f32 - intrinsics.cy) * depth / intrinsics.fy,
+                -depth,
+            )),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Encodes the depth and the normals as an uncompressed OpenEXR image with 32 bit floating point channels,
+    /// where the depth is in the channel `Z` and the normals in the channels `N.X`, `N.Y` and `N.Z`.
+    /// Use [Saver::save_file](crate::Saver::save_file) to write the image to a file.
+    ///
+    #[cfg(feature = "exr-export")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "exr-export")))]
+    pub fn encode_exr(&self) -> Vec<u8> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        // The channels must be sorted by name
+        let channels = ["N.X", "N.Y", "N.Z", "Z"];
+        let mut bytes = vec![0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0];
+
+        let mut channel_list = Vec::new();
+        for name in channels.iter() {
+            channel_list.extend_from_slice(name.as_bytes());
+            channel_list.push(0);
+            // The pixel type (2 means 32 bit float), whether the channel is perceptually linear, three reserved bytes and the sampling in x and y
+            channel_list.extend_from_slice(&2i32.to_le_bytes());
+            channel_list.extend_from_slice(&[0, 0, 0, 0]);
+            channel_list.extend_from_slice(&1i32.to_le_bytes());
+            channel_list.extend_from_slice(&1i32.to_le_bytes());
+        }
+        channel_list.push(0);
+        let window = [0, 0, width as i32 - 1, height as i32 - 1]
+            .iter()
+            .flat_map(|v| v.to_le_bytes().to_vec())
+            .collect::<Vec<_>>();
+        exr_attribute(&mut bytes, "channels", "chlist", &channel_list);
+        exr_attribute(&mut bytes, "compression", "compression", &[0]);
+        exr_attribute(&mut bytes, "dataWindow", "box2i", &window);
+        exr_attribute(&mut bytes, "displayWindow", "box2i", &window);
+        exr_attribute(&mut bytes, "lineOrder", "lineOrder", &[0]);
+        exr_attribute(
+            &mut bytes,
+            "pixelAspectRatio",
+            "float",
+            &1.0f32.to_le_bytes(),
+        );
+        exr_attribute(&mut bytes, "screenWindowCenter", "v2f", &[0; 8]);
+        exr_attribute(
+            &mut bytes,
+            "screenWindowWidth",
+            "float",
+            &1.0f32.to_le_bytes(),
+        );
+        bytes.push(0);
+
+        // Each scan line is a chunk which is located using a table of offsets from the start of the file
+        let chunk_data_size = width * channels.len() * 4;
+        let first_chunk = bytes.len() + height * 8;
+        for y in 0..height {
+            let offset = first_chunk + y * (8 + chunk_data_size);
+            bytes.extend_from_slice(&(offset as u64).to_le_bytes());
+        }
+        for y in 0..height {
+            bytes.extend_from_slice(&(y as i32).to_le_bytes());
+            bytes.extend_from_slice(&(chunk_data_size as i32).to_le_bytes());
+            let row = y * width..(y + 1) * width;
+            for c in 0..3 {
+                for i in row.clone() {
+                    bytes.extend_from_slice(&self.normals[i * 3 + c].to_le_bytes());
+                }
+            }
+            for i in row {
+                bytes.extend_from_slice(&self.depth[i].to_le_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(feature = "exr-export")]
+fn exr_attribute(bytes: &mut Vec<u8>, name: &str, attribute_type: &str, value: &[u8]) {
+    bytes.extend_from_slice(name.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(attribute_type.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    bytes.extend_from_slice(value);
+}
+
+///
+/// Renders aligned images of objects, for example for generating datasets for training machine learning models.
+/// For each frame, the color, the depth, the id of the object and the normal of the surface is rendered for each pixel, see [DatasetFrame].
+///
+/// The color is rendered using [render_high_res_with_options] with the given [DatasetRenderer::color_options],
+/// so it can be anti-aliased by rendering more than one sample per pixel.
+/// The depth, ids and normals are rendered in a single pass into multiple render targets, a 32 bit floating point texture for the depth,
+/// a texture with the four bytes of each id and a floating point texture for the normals, always with a single sample per pixel,
+/// so the values are never blended across the edges of the objects.
+///
+pub struct DatasetRenderer {
+    context: Context,
+    ///
+    /// The options used when rendering the color, see [render_high_res_with_options].
+    /// The [HighResOptions::samples_per_pixel] only applies to the color.
+    ///
+    pub color_options: HighResOptions,
+    camera: Camera,
+    color: Vec<u8>,
+    depth_texture: Texture2D<f32>,
+    id_texture: Texture2D<u8>,
+    normal_texture: Texture2D<f32>,
+    depth_target: DepthTargetTexture2D,
+}
+
+impl DatasetRenderer {
+    ///
+    /// Creates a new renderer of frames with the given width and height in pixels.
+    ///
+    pub fn new(context: &Context, width: u32, height: u32) -> ThreeDResult<Self> {
+        Ok(Self {
+            context: context.clone(),
+            color_options: HighResOptions::default(),
+            camera: Camera::new_perspective(
+                context,
+                Viewport::new_at_origo(width, height),
+                vec3(0.0, 0.0, 1.0),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                degrees(45.0),
+                0.01,
+                10.0,
+            )?,
+            color: vec![0; width as usize * height as usize * 4],
+            depth_texture: data_texture(context, width, height, Format::R)?,
+            id_texture: data_texture(context, width, height, Format::RGBA)?,
+            normal_texture: data_texture(context, width, height, Format::RGBA)?,
+            depth_target: DepthTargetTexture2D::new(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )?,
+        })
+    }
+
+    ///
+    /// Renders a frame of the given objects, each with an id, as seen from the given camera, which can then be read using [DatasetRenderer::read_all].
+    /// The id 0 is used where no object is visible, so the objects should have ids larger than 0.
+    /// The frame has the size given at construction, so the field of view and lens shift of the camera are used, but not the size of its viewport.
+    /// The lights are only used for the color, and all objects are written to the depth, ids and normals as if they were opaque.
+    /// Must not be called in a render target render function.
+    ///
+    pub fn render(
+        &mut self,
+        camera: &Camera,
+        objects: &[(impl Object, u32)],
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        match camera.projection_type() {
+            ProjectionType::Perspective { field_of_view_y } => {
+                self.camera.set_perspective_projection(
+                    *field_of_view_y,
+                    camera.z_near(),
+                    camera.z_far(),
+                )?;
+            }
+            ProjectionType::Orthographic { height } => {
+                self.camera.set_orthographic_projection(
+                    *height,
+                    camera.z_near(),
+                    camera.z_far(),
+                )?;
+            }
+        };
+        self.camera
+            .set_view(*camera.position(), *camera.target(), *camera.up())?;
+        self.camera.set_lens_shift(camera.lens_shift())?;
+
+        let viewport = self.camera.viewport();
+        let image = render_high_res_with_options(
+            &self.context,
+            &self.camera,
+            &objects.iter().map(|(object, _)| object).collect::<Vec<_>>(),
+            lights,
+            viewport.width,
+            viewport.height,
+            self.color_options,
+            None,
+        )?;
+        // The rendered pixels start with the bottom row, while the frame starts with the top row
+        self.color = image
+            .data
+            .chunks(viewport.width as usize * 4)
+            .rev()
+            .flatten()
+            .cloned()
+            .collect();
+
+        let camera = &self.camera;
+        write_multiple(
+            &self.context,
+            &[&self.depth_texture, &self.id_texture, &self.normal_texture],
+            &self.depth_target,
+            ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0),
+            || {
+                for (object, id) in objects.iter() {
+                    object.render_with_material(
+                        &DatasetMaterial { id: *id },
+                        camera,
+                        &Lights::default(),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    ///
+    /// Reads the images of the frame rendered by the last call to [DatasetRenderer::render].
+    ///
+    /// **Note:** This blocks until the GPU has finished rendering the frame.
+    ///
+    /// # Errors
+    /// Will return an error if floating point textures cannot be read on the current context,
+    /// which requires the `EXT_color_buffer_float` extension on web, see [Capabilities::color_buffer_float].
+    ///
+    pub fn read_all(&self) -> ThreeDResult<DatasetFrame> {
+        let viewport = self.camera.viewport();
+        Ok(DatasetFrame {
+            width: viewport.width,
+            height: viewport.height,
+            color: self.color.clone(),
+            depth: self.depth_texture.read_flipped_internal(viewport)?,
+            ids: self
+                .id_texture
+                .read_flipped_internal(viewport)?
+                .chunks(4)
+                .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect(),
+            normals: self
+                .normal_texture
+                .read_flipped_internal(viewport)?
+                .chunks(4)
+                .flat_map(|normal| normal[..3].to_vec())
+                .collect(),
+            view: *self.camera.view(),
+            intrinsics: self.camera.intrinsics(),
+        })
+    }
+
+    ///
+    /// Returns the intrinsic parameters of the camera used for the last call to [DatasetRenderer::render] for the size of the frames,
+    /// or `None` if the camera has an orthographic projection.
+    ///
+    pub fn intrinsics(&self) -> Option<CameraIntrinsics> {
+        self.camera.intrinsics()
+    }
+}
+
+// A texture without interpolation and mip maps for one of the values written in the data pass of the dataset renderer
+fn data_texture<T: TextureDataType>(
+    context: &Context,
+    width: u32,
+    height: u32,
+    format: Format,
+) -> ThreeDResult<Texture2D<T>> {
+    Texture2D::new_empty(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        format,
+    )
+}
+
+// Writes the depth, the four bytes of the id and the normal to the three targets of the data pass of the dataset renderer
+struct DatasetMaterial {
+    id: u32,
+}
+
+impl Material for DatasetMaterial {
+    fn fragment_shader_source(&self, _use_vertex_colors: bool, _lights: &Lights) -> String {
+        include_str!("material/shaders/dataset.frag").to_string()
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _camera: &Camera,
+        _lights: &Lights,
+    ) -> ThreeDResult<()> {
+        let bytes = self.id.to_le_bytes();
+        program.use_uniform_vec4(
+            "objectId",
+            &(vec4(
+                bytes[0] as f32,
+                bytes[1] as f32,
+                bytes[2] as f32,
+                bytes[3] as f32,
+            ) / 255.0),
+        )
+    }
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+    fn is_transparent(&self) -> bool {
+        false
+    }
+}
@@ -30,9 +30,13 @@ pub struct DeferredPipeline {
     pub debug_type: DebugType,
     #[deprecated = "use lighting_pass where Light struct contain lighting model"]
     pub lighting_model: LightingModel,
+    ///
+    /// The resolution of the geometry buffer relative to the viewport of the camera, for example 0.5 to render at half the resolution.
+    /// The lighting is still calculated for each pixel of the viewport given to the [DeferredPipeline::lighting_pass].
+    ///
+    pub scale_factor: f32,
     camera: Camera,
-    geometry_pass_texture: Option<Texture2DArray<u8>>,
-    geometry_pass_depth_texture: Option<DepthTargetTexture2DArray>,
+    target: ResizableTargetArray<u8>,
 }
 
 #[allow(deprecated)]
@@ -41,7 +45,7 @@ impl DeferredPipeline {
     /// Constructor.
     ///
     pub fn new(context: &Context) -> ThreeDResult<Self> {
-        let renderer = Self {
+        let mut renderer = Self {
             context: context.clone(),
             camera: Camera::new_perspective(
                 context,
@@ -55,28 +59,37 @@ impl DeferredPipeline {
             )?,
             debug_type: DebugType::NONE,
             lighting_model: LightingModel::Blinn,
-            geometry_pass_texture: Some(Texture2DArray::new(
-                context,
-                1,
-                1,
-                2,
-                Interpolation::Nearest,
-                Interpolation::Nearest,
-                None,
-                Wrapping::ClampToEdge,
-                Wrapping::ClampToEdge,
-                Format::RGBA,
-            )?),
-            geometry_pass_depth_texture: Some(DepthTargetTexture2DArray::new(
+            scale_factor: 1.0,
+            target: ResizableTargetArray::new(
                 context,
-                1,
-                1,
-                1,
-                Wrapping::ClampToEdge,
-                Wrapping::ClampToEdge,
-                DepthFormat::Depth32F,
-            )?),
+                |context, width, height| {
+                    Texture2DArray::new_empty(
+                        context,
+                        width,
+                        height,
+                        2,
+                        Interpolation::Nearest,
+                        Interpolation::Nearest,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                },
+                |context, width, height| {
+                    DepthTargetTexture2DArray::new(
+                        context,
+                        width,
+                        height,
+                        1,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                },
+            ),
         };
+        renderer.target.resize(1, 1)?;
         Ok(renderer)
     }
     ///
@@ -114,7 +127,11 @@ impl DeferredPipeline {
         camera: &Camera,
         objects: &[(G, impl std::borrow::Borrow<DeferredPhysicalMaterial>)],
     ) -> ThreeDResult<()> {
-        let viewport = Viewport::new_at_origo(camera.viewport().width, camera.viewport().height);
+        // The geometry buffer is recreated when the size of the viewport or the scale factor changes
+        let (width, height) = (camera.viewport().width, camera.viewport().height);
+        self.target.scale_factor = self.scale_factor;
+        self.target.resize(width, height)?;
+        let viewport = self.target.viewport();
         match camera.projection_type() {
             ProjectionType::Perspective { field_of_view_y } => {
                 self.camera.set_perspective_projection(
@@ -134,45 +151,26 @@ impl DeferredPipeline {
         self.camera.set_viewport(viewport)?;
         self.camera
             .set_view(*camera.position(), *camera.target(), *camera.up())?;
-        self.geometry_pass_texture = Some(Texture2DArray::<u8>::new(
-            &self.context,
-            viewport.width,
-            viewport.height,
-            2,
-            Interpolation::Nearest,
-            Interpolation::Nearest,
-            None,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            Format::RGBA,
-        )?);
-        self.geometry_pass_depth_texture = Some(DepthTargetTexture2DArray::new(
-            &self.context,
-            viewport.width,
-            viewport.height,
-            1,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            DepthFormat::Depth32F,
-        )?);
-        RenderTargetArray::new(
-            &self.context,
-            self.geometry_pass_texture.as_ref().unwrap(),
-            self.geometry_pass_depth_texture.as_ref().unwrap(),
-        )?
-        .write(&[0, 1], 0, ClearState::default(), || {
-            for (geometry, material) in objects
-                .iter()
-                .filter(|(g, _)| self.camera.in_frustum(&g.aabb()))
-            {
-                geometry.render_with_material(
-                    material.borrow(),
-                    &self.camera,
-                    &Lights::default(),
-                )?;
-            }
-            Ok(())
-        })?;
+        self.camera.set_lens_shift(camera.lens_shift())?;
+        // The jitter is in pixels, so it is scaled to the size of the buffer
+        self.camera
+            .set_projection_jitter(camera.projection_jitter().map(|jitter| {
+                vec2(
+                    jitter.x * viewport.width as f32 / width as f32,
+                    jitter.y * viewport.height as f32 / height as f32,
+                )
+            }))?;
+        let camera = &self.camera;
+        self.target
+            .get(width, height)?
+            .write(&[0, 1], 0, ClearState::default(), || {
+                for (geometry, material) in objects.iter().filter(|(g, _)| {
+                    camera.sphere_in_frustum(&g.bounding_sphere()) && camera.in_frustum(&g.aabb())
+                }) {
+                    geometry.render_with_material(material.borrow(), camera, &Lights::default())?;
+                }
+                Ok(())
+            })?;
         Ok(())
     }
 
@@ -204,10 +202,9 @@ impl DeferredPipeline {
                     include_str!("material/shaders/debug.frag")
                 ),
                 |debug_effect| {
-                    debug_effect.use_uniform(
-                        "viewProjectionInverse",
-                        (camera.projection() * camera.view()).invert().unwrap(),
-                    )?;
+                    debug_effect
+                        .use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
+                    debug_effect.use_uniform("farPlaneDepth", camera.far_plane_depth())?;
                     debug_effect.use_texture_array("gbuffer", self.geometry_pass_texture())?;
                     debug_effect
                         .use_texture_array("depthMap", self.geometry_pass_depth_texture_array())?;
@@ -236,11 +233,16 @@ impl DeferredPipeline {
             lights.push(light);
         }
 
-        let mut fragment_shader =
-            lights_fragment_shader_source(&mut lights.clone().into_iter(), self.lighting_model);
+        let mut fragment_shader = lights_fragment_shader_source(
+            &mut lights.clone().into_iter(),
+            self.lighting_model,
+            false,
+            None,
+        );
         fragment_shader.push_str(include_str!("material/shaders/deferred_lighting.frag"));
 
         self.context.effect(&fragment_shader, |effect| {
+            effect.use_uniform("farPlaneDepth", camera.far_plane_depth())?;
             effect.use_uniform("eyePosition", camera.position())?;
             for (i, light) in lights.iter().enumerate() {
                 light.use_uniforms(effect, i as u32)?;
@@ -249,10 +251,7 @@ impl DeferredPipeline {
             effect.use_texture_array("depthMap", self.geometry_pass_depth_texture_array())?;
             if !directional_lights.is_empty() || !spot_lights.is_empty() || !point_lights.is_empty()
             {
-                effect.use_uniform(
-                    "viewProjectionInverse",
-                    (camera.projection() * camera.view()).invert().unwrap(),
-                )?;
+                effect.use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
             }
             effect.apply(render_states, camera.viewport())?;
             Ok(())
@@ -279,10 +278,9 @@ impl DeferredPipeline {
                     include_str!("material/shaders/debug.frag")
                 ),
                 |debug_effect| {
-                    debug_effect.use_uniform(
-                        "viewProjectionInverse",
-                        (camera.projection() * camera.view()).invert().unwrap(),
-                    )?;
+                    debug_effect
+                        .use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
+                    debug_effect.use_uniform("farPlaneDepth", camera.far_plane_depth())?;
                     debug_effect.use_texture_array("gbuffer", self.geometry_pass_texture())?;
                     debug_effect
                         .use_texture_array("depthMap", self.geometry_pass_depth_texture_array())?;
@@ -302,15 +300,16 @@ impl DeferredPipeline {
         fragment_shader.push_str(include_str!("material/shaders/deferred_lighting.frag"));
 
         self.context.effect(&fragment_shader, |effect| {
+            effect.use_uniform("farPlaneDepth", camera.far_plane_depth())?;
             lights.use_uniforms(effect, camera)?;
             effect.use_texture_array("gbuffer", self.geometry_pass_texture())?;
             effect.use_texture_array("depthMap", self.geometry_pass_depth_texture_array())?;
-            if !lights.directional.is_empty() || !lights.spot.is_empty() || !lights.point.is_empty()
+            if !lights.directional.is_empty()
+                || !lights.spot.is_empty()
+                || !lights.point.is_empty()
+                || lights.probe_grid.is_some()
             {
-                effect.use_uniform(
-                    "viewProjectionInverse",
-                    (camera.projection() * camera.view()).invert().unwrap(),
-                )?;
+                effect.use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
             }
             effect.apply(render_states, camera.viewport())?;
             Ok(())
@@ -318,15 +317,14 @@ impl DeferredPipeline {
     }
 
     pub fn geometry_pass_texture(&self) -> &Texture2DArray<u8> {
-        self.geometry_pass_texture.as_ref().unwrap()
+        self.target.color_texture().unwrap()
     }
     pub fn geometry_pass_depth_texture_array(&self) -> &DepthTargetTexture2DArray {
-        self.geometry_pass_depth_texture.as_ref().unwrap()
+        self.target.depth_texture().unwrap()
     }
 
     pub fn geometry_pass_depth_texture(&self) -> DepthTargetTexture2D {
-        let depth_array: &DepthTargetTexture2DArray =
-            self.geometry_pass_depth_texture.as_ref().unwrap();
+        let depth_array = self.geometry_pass_depth_texture_array();
         let mut depth_texture = DepthTargetTexture2D::new(
             &self.context,
             depth_array.width(),
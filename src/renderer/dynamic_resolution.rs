@@ -0,0 +1,136 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Renders the scene at a fraction of the size of the viewport, which is lowered when the frames take too long to render
+/// and raised again when there is time to spare, to keep a stable frame rate on weaker GPUs.
+///
+/// Each frame, call [DynamicResolution::begin_frame] with the recent frame times, then render the scene using [DynamicResolution::render_pass]
+/// or [DynamicResolution::write] and finally call [DynamicResolution::resolve_to_screen] in the render function of the screen,
+/// which tone maps and upscales the result in a single pass. Anything rendered afterwards, for example a GUI, is rendered at full resolution.
+///
+pub struct DynamicResolution {
+    ///
+    /// The pipeline which renders into the offscreen high dynamic range color and depth targets.
+    /// Its [HdrPipeline::scale_factor] is set in each call to [DynamicResolution::begin_frame].
+    ///
+    pub pipeline: HdrPipeline,
+    /// The lowest resolution relative to the viewport.
+    pub min_scale: f32,
+    /// The highest resolution relative to the viewport.
+    pub max_scale: f32,
+    /// The desired time in milliseconds to render a frame.
+    pub target_frame_time: f64,
+    ///
+    /// The resolution is only lowered when the average frame time is larger than the target frame time plus this fraction of it
+    /// and only raised when it is smaller than the target frame time minus this fraction of it, which avoids oscillating between two resolutions.
+    ///
+    pub hysteresis: f64,
+    ///
+    /// The amount the resolution is changed by at a time. The resolution is always a multiple of this value,
+    /// so the targets are only recreated when the resolution changes by at least one step.
+    ///
+    pub step: f32,
+    scale: f32,
+    frames_since_change: usize,
+}
+
+impl DynamicResolution {
+    ///
+    /// Constructs a new dynamic resolution which starts at full resolution and targets 60 frames per second.
+    ///
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            pipeline: HdrPipeline::new(context)?,
+            min_scale: 0.5,
+            max_scale: 1.0,
+            target_frame_time: 1000.0 / 60.0,
+            hysteresis: 0.15,
+            step: 0.05,
+            scale: 1.0,
+            frames_since_change: 0,
+        })
+    }
+
+    ///
+    /// Returns the current resolution relative to the viewport.
+    ///
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    ///
+    /// Chooses the resolution for this frame from the moving average of the given frame times in milliseconds, for example the
+    /// [FrameInput::elapsed_time](crate::FrameInput::elapsed_time) of the last frames or GPU times measured with timer queries,
+    /// and returns the viewport which the scene is rendered into when rendering with the camera which has the given viewport.
+    /// The resolution is changed by at most one [DynamicResolution::step] and not again until the frame time history
+    /// only contains frames rendered at the new resolution.
+    ///
+    pub fn begin_frame(&mut self, frame_times: &[f64], viewport: Viewport) -> Viewport {
+        let step = self.step.max(0.01);
+        let min_scale = self.min_scale.min(self.max_scale);
+        // The scale is quantized so small changes in the frame time do not recreate the targets
+        let quantize = |scale: f32| {
+            ((scale / step).round() * step)
+                .max(min_scale)
+                .min(self.max_scale)
+        };
+        let mut scale = quantize(self.scale);
+        if !frame_times.is_empty() && self.frames_since_change >= frame_times.len() {
+            let average = frame_times.iter().sum::<f64>() / frame_times.len() as f64;
+            if average > self.target_frame_time * (1.0 + self.hysteresis) {
+                scale = quantize(scale - step);
+            } else if average < self.target_frame_time * (1.0 - self.hysteresis) {
+                scale = quantize(scale + step);
+            }
+        }
+        if scale != self.scale {
+            self.scale = scale;
+            self.frames_since_change = 0;
+        }
+        self.frames_since_change += 1;
+        self.pipeline.scale_factor = self.scale;
+        let scale = |size: u32| ((size as f32 * self.scale).round() as u32).max(1);
+        Viewport::new_at_origo(scale(viewport.width), scale(viewport.height))
+    }
+
+    ///
+    /// Renders the given objects into the offscreen targets at the resolution chosen in the last call to [DynamicResolution::begin_frame].
+    /// This function must not be called in a render target render function and needs to be followed
+    /// by a call to [DynamicResolution::resolve_to_screen].
+    ///
+    pub fn render_pass(
+        &mut self,
+        camera: &Camera,
+        objects: &[impl Object],
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.pipeline.render_pass(camera, objects, lights)
+    }
+
+    ///
+    /// Renders whatever rendered in the `render` closure into the offscreen targets, see [HdrPipeline::write].
+    ///
+    pub fn write(
+        &mut self,
+        camera: &Camera,
+        render: impl FnOnce(&Camera) -> ThreeDResult<()>,
+    ) -> ThreeDResult<()> {
+        self.pipeline.write(camera, render)
+    }
+
+    ///
+    /// Tone maps the result of the last [DynamicResolution::render_pass] and upscales it to the given viewport of the current render target
+    /// using the given filter.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn resolve_to_screen(&self, viewport: Viewport, filter: UpscaleFilter) -> ThreeDResult<()> {
+        if let Some(color_texture) = self.pipeline.color_texture() {
+            self.pipeline
+                .tone_mapping
+                .apply_upscaled(viewport, color_texture, filter)?;
+        }
+        Ok(())
+    }
+}
@@ -6,6 +6,38 @@ mod fog;
 #[doc(inline)]
 pub use fog::*;
 
+mod dof;
+#[doc(inline)]
+pub use dof::*;
+
 mod fxaa;
 #[doc(inline)]
 pub use fxaa::*;
+
+mod smaa;
+#[doc(inline)]
+pub use smaa::*;
+
+mod tone_mapping;
+#[doc(inline)]
+pub use tone_mapping::*;
+
+mod auto_exposure;
+#[doc(inline)]
+pub use auto_exposure::*;
+
+mod edge_outline;
+#[doc(inline)]
+pub use edge_outline::*;
+
+mod motion_blur;
+#[doc(inline)]
+pub use motion_blur::*;
+
+mod volumetric_light;
+#[doc(inline)]
+pub use volumetric_light::*;
+
+mod post_effect;
+#[doc(inline)]
+pub use post_effect::*;
@@ -0,0 +1,187 @@
+use crate::core::*;
+
+///
+/// Defines which parts of the image the [AutoExposure] measures the brightness of.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MeteringMode {
+    /// The whole image is weighted equally.
+    Average,
+    /// The center of the image is weighted more than the edges.
+    CenterWeighted,
+    /// Only a circle in the center of the image with the given radius relative to half the height of the image is measured.
+    Spot(f32),
+}
+
+// The size of the first level of the luminance measurement, where each texel averages 4x4 samples of the color texture
+const METERING_SIZE: u32 = 64;
+
+///
+/// Automatic exposure which adapts the exposure of a [ToneMappingEffect](crate::ToneMappingEffect) to the brightness of the image over time,
+/// like the eye adapting when moving from a dark room to the bright outside.
+///
+/// The average log luminance of a high dynamic range image is measured by [AutoExposure::update], which downsamples the image to a single texel,
+/// and the exposure which maps the average luminance to middle grey is the target which the current exposure moves towards.
+/// Everything happens on the GPU, so the tone mapping reads the current exposure from [AutoExposure::exposure_texture] without waiting for the measurement.
+/// Usually used through [HdrPipeline::auto_exposure](crate::HdrPipeline::auto_exposure).
+///
+/// The exposure is given in stops (EV), where the colors are multiplied by two to the power of the exposure.
+///
+pub struct AutoExposure {
+    /// The lowest exposure in stops, which limits how much a bright image is darkened.
+    pub min_ev: f32,
+    /// The highest exposure in stops, which limits how much a dark image is brightened.
+    pub max_ev: f32,
+    /// The exposure compensation in stops which is added to the adapted exposure, for example to make the image brighter or darker than middle grey on average.
+    pub compensation: f32,
+    /// The time constant in seconds of the adaptation when the exposure increases, ie. when going from a bright to a dark scene.
+    pub brighten_time: f32,
+    /// The time constant in seconds of the adaptation when the exposure decreases, ie. when going from a dark to a bright scene.
+    pub darken_time: f32,
+    /// The parts of the image which are measured.
+    pub metering: MeteringMode,
+    luminance_effect: ImageEffect,
+    downsample_effect: ImageEffect,
+    adapt_effect: ImageEffect,
+    levels: Vec<Texture2D<f16>>,
+    exposure_textures: Vec<Texture2D<f16>>,
+    current: usize,
+    initialized: bool,
+}
+
+impl AutoExposure {
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        let new_texture = |size| {
+            Texture2D::new_empty(
+                context,
+                size,
+                size,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )
+        };
+        let mut levels = Vec::new();
+        let mut size = METERING_SIZE;
+        while size > 0 {
+            levels.push(new_texture(size)?);
+            size /= 2;
+        }
+        Ok(Self {
+            min_ev: -8.0,
+            max_ev: 8.0,
+            compensation: 0.0,
+            brighten_time: 0.4,
+            darken_time: 0.25,
+            metering: MeteringMode::CenterWeighted,
+            luminance_effect: ImageEffect::new(
+                context,
+                include_str!("shaders/auto_exposure_luminance.frag"),
+            )?,
+            downsample_effect: ImageEffect::new(
+                context,
+                include_str!("shaders/auto_exposure_downsample.frag"),
+            )?,
+            adapt_effect: ImageEffect::new(
+                context,
+                include_str!("shaders/auto_exposure_adapt.frag"),
+            )?,
+            levels,
+            exposure_textures: vec![new_texture(1)?, new_texture(1)?],
+            current: 0,
+            initialized: false,
+        })
+    }
+
+    ///
+    /// Measures the average luminance of the given high dynamic range color texture and moves the exposure towards the exposure which maps it to middle grey,
+    /// where the given elapsed time in milliseconds since the last update is used for the adaptation.
+    /// The first update after construction or [AutoExposure::reset] sets the exposure directly.
+    /// This function must not be called in a render target render function.
+    ///
+    pub fn update(&mut self, color_texture: &impl Texture, elapsed_time: f64) -> ThreeDResult<()> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        };
+
+        let luminance_effect = &self.luminance_effect;
+        luminance_effect.use_texture("colorMap", color_texture)?;
+        luminance_effect.use_uniform("outputTexelSize", 1.0 / METERING_SIZE as f32)?;
+        luminance_effect.use_uniform(
+            "aspectRatio",
+            color_texture.width() as f32 / color_texture.height().max(1) as f32,
+        )?;
+        let (metering_mode, spot_radius) = match self.metering {
+            MeteringMode::Average => (0, 0.0),
+            MeteringMode::CenterWeighted => (1, 0.0),
+            MeteringMode::Spot(radius) => (2, radius.max(0.001)),
+        };
+        luminance_effect.use_uniform("meteringMode", metering_mode)?;
+        luminance_effect.use_uniform("spotRadius", spot_radius)?;
+        self.levels[0].write(ClearState::none(), || {
+            luminance_effect.apply(
+                render_states,
+                Viewport::new_at_origo(METERING_SIZE, METERING_SIZE),
+            )
+        })?;
+
+        // Each level averages 2x2 texels of the previous level using a single linearly interpolated sample between them
+        let downsample_effect = &self.downsample_effect;
+        for i in 1..self.levels.len() {
+            let (previous, next) = self.levels.split_at_mut(i);
+            let input = &previous[i - 1];
+            let output = &mut next[0];
+            let viewport = Viewport::new_at_origo(output.width(), output.height());
+            downsample_effect.use_texture("inputMap", input)?;
+            output.write(ClearState::none(), || {
+                downsample_effect.apply(render_states, viewport)
+            })?;
+        }
+
+        let seconds = (elapsed_time / 1000.0).max(0.0) as f32;
+        let factor = |time: f32| {
+            if time > 0.0 {
+                1.0 - (-seconds / time).exp()
+            } else {
+                1.0
+            }
+        };
+        let adapt_effect = &self.adapt_effect;
+        adapt_effect.use_texture("luminanceMap", self.levels.last().unwrap())?;
+        adapt_effect.use_texture("previousExposureMap", &self.exposure_textures[self.current])?;
+        adapt_effect.use_uniform("minEv", self.min_ev)?;
+        adapt_effect.use_uniform("maxEv", self.max_ev.max(self.min_ev))?;
+        adapt_effect.use_uniform("brightenFactor", factor(self.brighten_time))?;
+        adapt_effect.use_uniform("darkenFactor", factor(self.darken_time))?;
+        adapt_effect.use_uniform("initialize", if self.initialized { 0 } else { 1 })?;
+        let next = 1 - self.current;
+        self.exposure_textures[next].write(ClearState::none(), || {
+            adapt_effect.apply(render_states, Viewport::new_at_origo(1, 1))
+        })?;
+        self.current = next;
+        self.initialized = true;
+        Ok(())
+    }
+
+    ///
+    /// Makes the next [AutoExposure::update] set the exposure directly instead of adapting to it, for example after a cut to another scene.
+    ///
+    pub fn reset(&mut self) {
+        self.initialized = false;
+    }
+
+    ///
+    /// Returns a 1x1 texture where the red channel contains the current adapted exposure in stops, without the [AutoExposure::compensation].
+    /// Used by [ToneMappingEffect::apply_with_auto_exposure](crate::ToneMappingEffect::apply_with_auto_exposure),
+    /// but can also be used to apply the exposure in a custom effect.
+    ///
+    pub fn exposure_texture(&self) -> &Texture2D<f16> {
+        &self.exposure_textures[self.current]
+    }
+}
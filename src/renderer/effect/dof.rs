@@ -0,0 +1,219 @@
+use crate::core::*;
+
+///
+/// A depth of field effect which blurs the parts of the image which are in front of or behind the focus distance,
+/// simulating a camera lens with the given focal length and aperture.
+/// The blur is a circular bokeh blur computed in half resolution and combined with the sharp image in full resolution.
+///
+/// The effect is applied in two steps, first [DofEffect::blur_pass] computes the blurred image which must be done before
+/// writing to the final render target, then [DofEffect::apply] combines the blurred and sharp image in a render target render function,
+/// for example in the callback function of [Screen::write].
+///
+pub struct DofEffect {
+    /// The distance from the camera to the plane which is in focus.
+    pub focus_distance: f32,
+    /// The focal length of the lens in the same unit as the scene, for example `0.05` for a 50 mm lens if the unit is meters.
+    pub focal_length: f32,
+    /// The f-number of the lens, ie. the focal length divided by the diameter of the aperture. A smaller f-number gives more blur.
+    pub f_number: f32,
+    /// The maximum radius of the blur in pixels.
+    pub max_blur_radius: f32,
+    prefilter_effect: ImageEffect,
+    near_mask_effect: ImageEffect,
+    blur_effect: ImageEffect,
+    composite_effect: ImageEffect,
+    color_coc_target: ResizableTarget<f16>,
+    near_mask_target: ResizableTarget<u8>,
+    blur_target: ResizableTarget<f16>,
+}
+
+impl DofEffect {
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        let coc_source = format!(
+            "{}{}",
+            include_str!("../../core/shared.frag"),
+            include_str!("shaders/dof_coc.frag")
+        );
+        Ok(Self {
+            focus_distance: 10.0,
+            focal_length: 0.05,
+            f_number: 1.4,
+            max_blur_radius: 16.0,
+            prefilter_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "{}{}",
+                    coc_source,
+                    include_str!("shaders/dof_prefilter.frag")
+                ),
+            )?,
+            near_mask_effect: ImageEffect::new(
+                context,
+                include_str!("shaders/dof_near_mask.frag"),
+            )?,
+            blur_effect: ImageEffect::new(context, include_str!("shaders/dof_blur.frag"))?,
+            composite_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "{}{}",
+                    coc_source,
+                    include_str!("shaders/dof_composite.frag")
+                ),
+            )?,
+            color_coc_target: ResizableTarget::new_color(context, |context, width, height| {
+                new_half_resolution_texture(context, width, height, Format::RGBA)
+            }),
+            near_mask_target: ResizableTarget::new_color(context, |context, width, height| {
+                new_half_resolution_texture(context, width, height, Format::R)
+            }),
+            blur_target: ResizableTarget::new_color(context, |context, width, height| {
+                new_half_resolution_texture(context, width, height, Format::RGBA)
+            }),
+        })
+    }
+
+    ///
+    /// Computes the blurred image from the given color and depth textures, which must have the same size as the viewport of the camera.
+    /// The half resolution buffers used for the blur are recreated when the size of the viewport changes.
+    /// This function must not be called in a render target render function and needs to be followed by a call to [DofEffect::apply].
+    ///
+    pub fn blur_pass(
+        &mut self,
+        camera: &Camera,
+        color_texture: &impl Texture,
+        depth_texture: &DepthTargetTexture2D,
+    ) -> ThreeDResult<()> {
+        let width = (camera.viewport().width + 1) / 2;
+        let height = (camera.viewport().height + 1) / 2;
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        };
+        let viewport = Viewport::new_at_origo(width, height);
+        let half_texel_size = vec2(1.0 / width as f32, 1.0 / height as f32);
+        let max_blur = 0.5 * self.max_blur_radius.max(0.0);
+
+        let prefilter_effect = &self.prefilter_effect;
+        self.use_coc_uniforms(prefilter_effect, camera, depth_texture)?;
+        prefilter_effect.use_texture("colorMap", color_texture)?;
+        prefilter_effect.use_uniform(
+            "texelSize",
+            vec2(
+                1.0 / color_texture.width() as f32,
+                1.0 / color_texture.height() as f32,
+            ),
+        )?;
+        self.color_coc_target
+            .get(width, height)?
+            .write(ClearState::none(), || {
+                prefilter_effect.apply(render_states, viewport)
+            })?;
+
+        let color_coc_texture = self.color_coc_target.color_texture().unwrap();
+        let near_mask_effect = &self.near_mask_effect;
+        near_mask_effect.use_texture("colorCocMap", color_coc_texture)?;
+        near_mask_effect.use_uniform("texelSize", half_texel_size)?;
+        near_mask_effect.use_uniform("maxBlur", max_blur)?;
+        self.near_mask_target
+            .get(width, height)?
+            .write(ClearState::none(), || {
+                near_mask_effect.apply(render_states, viewport)
+            })?;
+
+        let blur_effect = &self.blur_effect;
+        blur_effect.use_texture("colorCocMap", color_coc_texture)?;
+        blur_effect.use_uniform("texelSize", half_texel_size)?;
+        blur_effect.use_uniform("maxBlur", max_blur)?;
+        self.blur_target
+            .get(width, height)?
+            .write(ClearState::none(), || {
+                blur_effect.apply(render_states, viewport)
+            })?;
+        Ok(())
+    }
+
+    ///
+    /// Combines the given sharp color texture with the blurred image computed in the last [DofEffect::blur_pass]
+    /// and writes the result to the viewport of the camera in the current render target.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn apply(
+        &self,
+        camera: &Camera,
+        color_texture: &impl Texture,
+        depth_texture: &DepthTargetTexture2D,
+    ) -> ThreeDResult<()> {
+        if let (Some(blur_texture), Some(near_mask_texture)) = (
+            self.blur_target.color_texture(),
+            self.near_mask_target.color_texture(),
+        ) {
+            let render_states = RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTest::Always,
+                cull: Cull::Back,
+                ..Default::default()
+            };
+            self.use_coc_uniforms(&self.composite_effect, camera, depth_texture)?;
+            self.composite_effect
+                .use_texture("colorMap", color_texture)?;
+            self.composite_effect.use_texture("blurMap", blur_texture)?;
+            self.composite_effect
+                .use_texture("nearMaskMap", near_mask_texture)?;
+            self.composite_effect
+                .apply(render_states, camera.viewport())?;
+        }
+        Ok(())
+    }
+
+    fn use_coc_uniforms(
+        &self,
+        effect: &ImageEffect,
+        camera: &Camera,
+        depth_texture: &DepthTargetTexture2D,
+    ) -> ThreeDResult<()> {
+        let focus_distance = self.focus_distance.max(self.focal_length + 0.0001);
+        // The height of the view frustum at the focus distance, which corresponds to the height of the sensor
+        let frustum_height = match camera.projection_type() {
+            ProjectionType::Perspective { field_of_view_y } => {
+                2.0 * focus_distance * (0.5 * field_of_view_y.0).tan()
+            }
+            ProjectionType::Orthographic { height, .. } => *height,
+        };
+        // The thin lens circle of confusion is aperture * (distance - focus) / distance * focus / (focus - focal length),
+        // which is scaled from the size of the sensor to pixels
+        let aperture = self.focal_length / self.f_number.max(0.0001);
+        let coc_scale =
+            aperture * focus_distance / (focus_distance - self.focal_length) / frustum_height
+                * camera.viewport().height as f32;
+
+        effect.use_texture("depthMap", depth_texture)?;
+        effect.use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
+        effect.use_uniform("eyePosition", camera.position())?;
+        effect.use_uniform("focusDistance", focus_distance)?;
+        effect.use_uniform("cocScale", coc_scale)?;
+        effect.use_uniform("maxBlur", self.max_blur_radius.max(0.0))?;
+        Ok(())
+    }
+}
+
+fn new_half_resolution_texture<T: TextureDataType>(
+    context: &Context,
+    width: u32,
+    height: u32,
+    format: Format,
+) -> ThreeDResult<Texture2D<T>> {
+    Texture2D::new_empty(
+        context,
+        width,
+        height,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        format,
+    )
+}
@@ -0,0 +1,109 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// An effect which draws outlines where the depth or the normal changes abruptly, for example along the silhouettes and creases of the objects.
+/// Often used together with the [ToonMaterial] for a cartoon look.
+///
+/// The depth is given as a depth texture, for example from [ForwardPipeline::depth_pass_texture] or [DeferredPipeline::geometry_pass_depth_texture],
+/// and the normals are optional, for example from [ForwardPipeline::depth_and_normal_pass_textures].
+/// Use [EdgeOutlineEffect::apply_deferred] to use the depth and normals of the geometry pass of the [DeferredPipeline].
+///
+pub struct EdgeOutlineEffect {
+    /// The color of the outlines.
+    pub color: Color,
+    /// The thickness of the outlines in pixels.
+    pub thickness: f32,
+    /// The change in distance to the camera relative to the distance which results in an outline.
+    pub depth_threshold: f32,
+    /// The change in normal direction, measured as one minus the cosine of the angle between the normals, which results in an outline.
+    pub normal_threshold: f32,
+    context: Context,
+}
+
+impl EdgeOutlineEffect {
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            color: Color::BLACK,
+            thickness: 1.0,
+            depth_threshold: 0.05,
+            normal_threshold: 0.4,
+            context: context.clone(),
+        })
+    }
+
+    ///
+    /// Draws the outlines found in the given depth texture and the optional normal texture, which contains normals encoded as `0.5 + 0.5 * normal`
+    /// like the output of the [NormalMaterial].
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn apply(
+        &self,
+        camera: &Camera,
+        depth_texture: &DepthTargetTexture2D,
+        normal_texture: Option<&Texture2D<u8>>,
+    ) -> ThreeDResult<()> {
+        let mut fragment_shader = String::new();
+        if normal_texture.is_some() {
+            fragment_shader.push_str("#define USE_NORMAL_TEXTURE\n");
+        }
+        fragment_shader.push_str(include_str!("../../core/shared.frag"));
+        fragment_shader.push_str(include_str!("shaders/edge_outline.frag"));
+        self.context.effect(&fragment_shader, |effect| {
+            effect.use_texture("depthMap", depth_texture)?;
+            if let Some(normal_texture) = normal_texture {
+                effect.use_texture("normalTexture", normal_texture)?;
+            }
+            self.use_uniforms(effect, camera)?;
+            effect.apply(self.render_states(), camera.viewport())
+        })
+    }
+
+    ///
+    /// Draws the outlines found in the depth and normals of the geometry pass of the given deferred pipeline,
+    /// see [DeferredPipeline::geometry_pass].
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn apply_deferred(&self, camera: &Camera, pipeline: &DeferredPipeline) -> ThreeDResult<()> {
+        let mut fragment_shader = String::from("#define USE_GBUFFER\n");
+        fragment_shader.push_str(include_str!("../../core/shared.frag"));
+        fragment_shader.push_str(include_str!("shaders/edge_outline.frag"));
+        self.context.effect(&fragment_shader, |effect| {
+            effect.use_texture_array("depthMap", pipeline.geometry_pass_depth_texture_array())?;
+            effect.use_texture_array("gbuffer", pipeline.geometry_pass_texture())?;
+            self.use_uniforms(effect, camera)?;
+            effect.apply(self.render_states(), camera.viewport())
+        })
+    }
+
+    fn use_uniforms(&self, effect: &ImageEffect, camera: &Camera) -> ThreeDResult<()> {
+        let viewport = camera.viewport();
+        effect.use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
+        effect.use_uniform("eyePosition", camera.position())?;
+        effect.use_uniform("farPlaneDepth", camera.far_plane_depth())?;
+        effect.use_uniform(
+            "texelOffset",
+            vec2(
+                self.thickness / viewport.width as f32,
+                self.thickness / viewport.height as f32,
+            ),
+        )?;
+        effect.use_uniform("depthThreshold", self.depth_threshold)?;
+        if effect.requires_uniform("normalThreshold") {
+            effect.use_uniform("normalThreshold", self.normal_threshold)?;
+        }
+        effect.use_uniform("outlineColor", self.color.to_vec4())?;
+        Ok(())
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
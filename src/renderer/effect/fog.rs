@@ -1,4 +1,5 @@
 use crate::core::*;
+use crate::renderer::*;
 
 ///
 /// An effect that simulates fog, ie. the entire screen gets hazy white when objects are far away.
@@ -7,7 +8,10 @@ pub struct FogEffect {
     pub color: Vec3,
     pub density: f32,
     pub animation: f32,
+    /// Whether the effect is applied when used as a [PostEffect] in an [EffectStack].
+    pub enabled: bool,
     image_effect: ImageEffect,
+    post_effect: ImageEffect,
 }
 
 impl FogEffect {
@@ -16,7 +20,12 @@ impl FogEffect {
             color: vec3(0.8, 0.8, 0.8),
             density: 0.2,
             animation: 0.1,
+            enabled: true,
             image_effect: ImageEffect::new(gl, include_str!("shaders/fog.frag"))?,
+            post_effect: ImageEffect::new(
+                gl,
+                &format!("#define COLOR_INPUT\n{}", include_str!("shaders/fog.frag")),
+            )?,
         })
     }
 
@@ -32,20 +41,58 @@ impl FogEffect {
             cull: Cull::Back,
             ..Default::default()
         };
-
-        self.image_effect.use_texture("depthMap", depth_texture)?;
-        self.image_effect.use_uniform(
-            "viewProjectionInverse",
-            (camera.projection() * camera.view()).invert().unwrap(),
-        )?;
-        self.image_effect.use_uniform("fogColor", self.color)?;
-        self.image_effect.use_uniform("fogDensity", self.density)?;
-        self.image_effect.use_uniform("animation", self.animation)?;
-        self.image_effect.use_uniform("time", 0.001 * time)?;
-        self.image_effect
-            .use_uniform("eyePosition", camera.position())?;
-
+        self.use_uniforms(&self.image_effect, camera, depth_texture, time)?;
         self.image_effect.apply(render_states, camera.viewport())?;
         Ok(())
     }
+
+    fn use_uniforms(
+        &self,
+        effect: &ImageEffect,
+        camera: &Camera,
+        depth_texture: &DepthTargetTexture2D,
+        time: f32,
+    ) -> ThreeDResult<()> {
+        effect.use_texture("depthMap", depth_texture)?;
+        effect.use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
+        effect.use_uniform("farPlaneDepth", camera.far_plane_depth())?;
+        effect.use_uniform("fogColor", self.color)?;
+        effect.use_uniform("fogDensity", self.density)?;
+        effect.use_uniform("animation", self.animation)?;
+        effect.use_uniform("time", 0.001 * time)?;
+        effect.use_uniform("eyePosition", camera.position())?;
+        Ok(())
+    }
+}
+
+impl PostEffect for FogEffect {
+    fn inputs(&self) -> EffectInputMask {
+        EffectInputMask::COLOR_AND_DEPTH
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn apply(&mut self, inputs: &EffectInputs, output: &mut EffectOutput) -> ThreeDResult<()> {
+        let depth_texture = inputs
+            .depth_texture
+            .ok_or_else(|| RendererError::MissingEffectInput("depth".to_string()))?;
+        // Instead of blending the fog on top, the fog is mixed with the color input and written to the output
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        };
+        let effect = &self.post_effect;
+        self.use_uniforms(effect, inputs.camera, depth_texture, inputs.time)?;
+        effect.use_texture("colorMap", &inputs.color_texture)?;
+        let viewport = output.viewport();
+        output.write(|| effect.apply(render_states, viewport))
+    }
 }
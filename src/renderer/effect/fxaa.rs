@@ -1,4 +1,5 @@
 use crate::core::*;
+use crate::renderer::*;
 
 ///
 /// A simple anti-aliasing approach which smooths otherwise jagged edges (for example lines) but also
@@ -8,6 +9,8 @@ pub struct FXAAEffect {
     pub color: Vec3,
     pub density: f32,
     pub animation: f32,
+    /// Whether the effect is applied when used as a [PostEffect] in an [EffectStack].
+    pub enabled: bool,
     image_effect: ImageEffect,
 }
 
@@ -17,6 +20,7 @@ impl FXAAEffect {
             color: vec3(0.8, 0.8, 0.8),
             density: 0.2,
             animation: 0.1,
+            enabled: true,
             image_effect: ImageEffect::new(gl, include_str!("shaders/fxaa.frag"))?,
         })
     }
@@ -39,3 +43,23 @@ impl FXAAEffect {
         Ok(())
     }
 }
+
+impl PostEffect for FXAAEffect {
+    fn inputs(&self) -> EffectInputMask {
+        EffectInputMask::COLOR
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn apply(&mut self, inputs: &EffectInputs, output: &mut EffectOutput) -> ThreeDResult<()> {
+        let viewport = output.viewport();
+        let color_texture = inputs.color_texture;
+        output.write(|| FXAAEffect::apply(self, viewport, color_texture))
+    }
+}
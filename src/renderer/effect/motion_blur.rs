@@ -0,0 +1,131 @@
+use crate::core::*;
+
+///
+/// A motion blur effect which blurs each pixel along the movement on the screen since the previous frame,
+/// simulating the exposure time of a camera.
+/// The movement is computed by reprojecting the depth through the view projection of the camera in the previous frame,
+/// which means that only the movement of the camera is taken into account, see [MotionBlurEffect::apply].
+/// Alternatively, the movement can be given per pixel in a velocity texture, see [MotionBlurEffect::apply_with_velocity].
+///
+/// The effect should be applied before rendering the GUI so the GUI is not blurred.
+///
+pub struct MotionBlurEffect {
+    /// The number of samples along the movement of each pixel. More samples give a smoother blur.
+    pub samples: u32,
+    /// The maximum length of the blur in pixels. Longer movements, for example when the camera teleports, are clamped to this length.
+    pub max_blur_radius: f32,
+    ///
+    /// The angle in degrees of the rotating shutter of a film camera, which determines how large a part of the time between two frames the shutter is open.
+    /// An angle of 360 degrees blurs the whole movement since the previous frame, 180 degrees (the default) blurs half of it and 0 degrees disables the blur.
+    ///
+    pub shutter_angle: f32,
+    previous_view_projection: Option<Mat4>,
+    reprojection_effect: ImageEffect,
+    velocity_effect: ImageEffect,
+}
+
+impl MotionBlurEffect {
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            samples: 8,
+            max_blur_radius: 32.0,
+            shutter_angle: 180.0,
+            previous_view_projection: None,
+            reprojection_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "{}{}",
+                    include_str!("../../core/shared.frag"),
+                    include_str!("shaders/motion_blur.frag")
+                ),
+            )?,
+            velocity_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "#define USE_VELOCITY_MAP\n{}{}",
+                    include_str!("../../core/shared.frag"),
+                    include_str!("shaders/motion_blur.frag")
+                ),
+            )?,
+        })
+    }
+
+    ///
+    /// Blurs the given color texture along the movement of the camera since the last call to this function
+    /// and writes the result to the viewport of the camera in the current render target.
+    /// The color and depth textures must have the same size as the viewport of the camera.
+    /// Nothing is blurred the first time this function is called, after [MotionBlurEffect::reset] or when the camera has not moved.
+    /// Must be called once each frame in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn apply(
+        &mut self,
+        camera: &Camera,
+        color_texture: &impl Texture,
+        depth_texture: &DepthTargetTexture2D,
+    ) -> ThreeDResult<()> {
+        let view_projection = camera.projection() * camera.view();
+        let previous_view_projection = self
+            .previous_view_projection
+            .replace(view_projection)
+            .unwrap_or(view_projection);
+        let effect = &self.reprojection_effect;
+        effect.use_texture("depthMap", depth_texture)?;
+        effect.use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
+        effect.use_uniform("previousViewProjection", previous_view_projection)?;
+        self.blur(effect, camera.viewport(), color_texture)
+    }
+
+    ///
+    /// Blurs the given color texture along the movement given in the velocity texture
+    /// and writes the result to the given viewport in the current render target.
+    /// The red and green channel of the velocity texture must contain the uv coordinates of the pixel in this frame minus the uv coordinates
+    /// of the same surface point in the previous frame, which allows for blurring moving objects and not just the movement of the camera.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn apply_with_velocity(
+        &self,
+        viewport: Viewport,
+        color_texture: &impl Texture,
+        velocity_texture: &impl Texture,
+    ) -> ThreeDResult<()> {
+        self.velocity_effect
+            .use_texture("velocityMap", velocity_texture)?;
+        self.blur(&self.velocity_effect, viewport, color_texture)
+    }
+
+    ///
+    /// Forgets the camera of the previous frame, so nothing is blurred the next time [MotionBlurEffect::apply] is called.
+    /// Use this when the camera is moved to a completely different view, for example when switching between cameras.
+    ///
+    pub fn reset(&mut self) {
+        self.previous_view_projection = None;
+    }
+
+    fn blur(
+        &self,
+        effect: &ImageEffect,
+        viewport: Viewport,
+        color_texture: &impl Texture,
+    ) -> ThreeDResult<()> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        };
+        effect.use_texture("colorMap", color_texture)?;
+        effect.use_uniform(
+            "texelSize",
+            vec2(
+                1.0 / color_texture.width() as f32,
+                1.0 / color_texture.height() as f32,
+            ),
+        )?;
+        effect.use_uniform("samples", self.samples as i32)?;
+        effect.use_uniform("maxBlur", self.max_blur_radius.max(0.0))?;
+        effect.use_uniform("intensity", self.shutter_angle.max(0.0) / 360.0)?;
+        effect.apply(render_states, viewport)
+    }
+}
@@ -0,0 +1,323 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Defines which inputs (color, depth, normal and velocity) a [PostEffect] needs, see [PostEffect::inputs].
+///
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EffectInputMask {
+    pub color: bool,
+    pub depth: bool,
+    pub normal: bool,
+    pub velocity: bool,
+}
+
+impl EffectInputMask {
+    ///
+    /// Only needs the color texture.
+    ///
+    pub const COLOR: Self = Self {
+        color: true,
+        depth: false,
+        normal: false,
+        velocity: false,
+    };
+
+    ///
+    /// Needs the color and the depth texture.
+    ///
+    pub const COLOR_AND_DEPTH: Self = Self {
+        color: true,
+        depth: true,
+        normal: false,
+        velocity: false,
+    };
+
+    ///
+    /// Needs the color, depth, normal and velocity texture.
+    ///
+    pub const ALL: Self = Self {
+        color: true,
+        depth: true,
+        normal: true,
+        velocity: true,
+    };
+}
+
+impl Default for EffectInputMask {
+    fn default() -> Self {
+        Self::COLOR
+    }
+}
+
+///
+/// The inputs given to [PostEffect::apply].
+/// The color texture is the output of the previous effect in an [EffectStack], or the rendered scene for the first effect,
+/// while the remaining inputs are shared by all of the effects.
+///
+#[derive(Clone, Copy)]
+pub struct EffectInputs<'a> {
+    /// The camera used for rendering the scene.
+    pub camera: &'a Camera,
+    /// The color texture to apply the effect to.
+    pub color_texture: &'a dyn Texture,
+    /// The depth texture of the scene.
+    pub depth_texture: Option<&'a DepthTargetTexture2D>,
+    /// A texture which contains the normal of the scene in each pixel.
+    pub normal_texture: Option<&'a dyn Texture>,
+    ///
+    /// A texture where the red and green channel contain the uv coordinates of each pixel in this frame minus
+    /// the uv coordinates of the same surface point in the previous frame, see [MotionBlurEffect::apply_with_velocity].
+    ///
+    pub velocity_texture: Option<&'a dyn Texture>,
+    /// The time in milliseconds, used by animated effects.
+    pub time: f32,
+}
+
+impl<'a> EffectInputs<'a> {
+    ///
+    /// Constructs new inputs with the given camera and color texture and no depth, normal or velocity texture.
+    ///
+    pub fn new(camera: &'a Camera, color_texture: &'a dyn Texture) -> Self {
+        Self {
+            camera,
+            color_texture,
+            depth_texture: None,
+            normal_texture: None,
+            velocity_texture: None,
+            time: 0.0,
+        }
+    }
+
+    fn check(&self, mask: EffectInputMask) -> ThreeDResult<()> {
+        if mask.depth && self.depth_texture.is_none() {
+            Err(RendererError::MissingEffectInput("depth".to_string()))?;
+        }
+        if mask.normal && self.normal_texture.is_none() {
+            Err(RendererError::MissingEffectInput("normal".to_string()))?;
+        }
+        if mask.velocity && self.velocity_texture.is_none() {
+            Err(RendererError::MissingEffectInput("velocity".to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// The destination of [PostEffect::apply], which is either the screen, a texture or one of the intermediate targets of an [EffectStack].
+///
+pub struct EffectOutput<'a> {
+    destination: Destination<'a>,
+    viewport: Viewport,
+}
+
+enum Destination<'a> {
+    Screen(Context),
+    Texture(&'a mut Texture2D<u8>),
+    Intermediate(&'a mut ResizableTarget<f16>),
+}
+
+impl<'a> EffectOutput<'a> {
+    ///
+    /// Writes to the given viewport of the screen.
+    ///
+    pub fn screen(context: &Context, viewport: Viewport) -> Self {
+        Self {
+            destination: Destination::Screen(context.clone()),
+            viewport,
+        }
+    }
+
+    ///
+    /// Writes to the entire given texture.
+    ///
+    pub fn texture(texture: &'a mut Texture2D<u8>) -> Self {
+        let viewport = Viewport::new_at_origo(texture.width(), texture.height());
+        Self {
+            destination: Destination::Texture(texture),
+            viewport,
+        }
+    }
+
+    ///
+    /// The viewport which the effect should write to.
+    ///
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    ///
+    /// Renders whatever rendered in the `render` closure into the output without clearing it first.
+    /// The effect is expected to write to every pixel in the [EffectOutput::viewport].
+    ///
+    pub fn write(&mut self, render: impl FnOnce() -> ThreeDResult<()>) -> ThreeDResult<()> {
+        match self.destination {
+            Destination::Screen(ref context) => Screen::write(context, ClearState::none(), render),
+            Destination::Texture(ref mut texture) => texture.write(ClearState::none(), render),
+            Destination::Intermediate(ref mut target) => target
+                .get(self.viewport.width, self.viewport.height)?
+                .write(ClearState::none(), render),
+        }
+    }
+}
+
+///
+/// An effect applied to an image after the scene is rendered, for example fog, bloom or anti-aliasing.
+/// Effects implementing this trait can be composed in an [EffectStack], which manages the intermediate targets between the effects.
+///
+pub trait PostEffect {
+    ///
+    /// The inputs this effect needs. An [EffectStack] returns an error if a needed input is not given.
+    ///
+    fn inputs(&self) -> EffectInputMask;
+
+    ///
+    /// Returns whether the effect is enabled. An [EffectStack] skips disabled effects.
+    ///
+    fn is_enabled(&self) -> bool;
+
+    ///
+    /// Enables or disables the effect, for example to toggle an effect in an [EffectStack] from a GUI.
+    ///
+    fn set_enabled(&mut self, enabled: bool);
+
+    ///
+    /// Called before [PostEffect::apply] with the size of the output, so the effect can resize any targets it owns.
+    ///
+    fn resize(&mut self, _width: u32, _height: u32) -> ThreeDResult<()> {
+        Ok(())
+    }
+
+    ///
+    /// Applies the effect to the given inputs and writes the result to the [EffectOutput::viewport] of the given output,
+    /// typically by calling [EffectOutput::write].
+    /// Must not be called in a render target render function.
+    ///
+    fn apply(&mut self, inputs: &EffectInputs, output: &mut EffectOutput) -> ThreeDResult<()>;
+}
+
+///
+/// Applies a list of [PostEffect]s in order, where each effect is given the output of the previous effect as color texture
+/// together with the shared depth, normal and velocity textures.
+/// The intermediate results are stored in two high dynamic range color targets which are reused between the effects and follow the size of the output.
+/// Disabled effects are skipped without copying the image and the last enabled effect writes directly to the output.
+///
+pub struct EffectStack {
+    ///
+    /// The effects applied in order by [EffectStack::apply].
+    ///
+    pub effects: Vec<Box<dyn PostEffect>>,
+    targets: [ResizableTarget<f16>; 2],
+    copy_effect: ImageEffect,
+}
+
+impl EffectStack {
+    ///
+    /// Constructs a new empty effect stack.
+    ///
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        let new_target = || {
+            ResizableTarget::new_color(context, |context, width, height| {
+                Texture2D::new_empty(
+                    context,
+                    width,
+                    height,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                    Format::RGBA,
+                )
+            })
+        };
+        Ok(Self {
+            effects: Vec::new(),
+            targets: [new_target(), new_target()],
+            copy_effect: ImageEffect::new(
+                context,
+                "
+                uniform sampler2D colorMap;
+                in vec2 uv;
+                layout (location = 0) out vec4 color;
+                void main()
+                {
+                    color = texture(colorMap, uv);
+                }",
+            )?,
+        })
+    }
+
+    ///
+    /// Adds the effect to the end of the stack.
+    ///
+    pub fn push(&mut self, effect: impl PostEffect + 'static) {
+        self.effects.push(Box::new(effect));
+    }
+
+    ///
+    /// Applies the enabled effects in order to the color texture in the given inputs and writes the final result to the given output.
+    /// If no effects are enabled, the color texture is copied to the output.
+    /// Must not be called in a render target render function.
+    ///
+    pub fn apply(&mut self, inputs: &EffectInputs, mut output: EffectOutput) -> ThreeDResult<()> {
+        let viewport = output.viewport();
+        let (width, height) = (viewport.width, viewport.height);
+        let enabled = self
+            .effects
+            .iter()
+            .enumerate()
+            .filter(|(_, effect)| effect.is_enabled())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if enabled.is_empty() {
+            let render_states = RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTest::Always,
+                cull: Cull::Back,
+                ..Default::default()
+            };
+            let copy_effect = &self.copy_effect;
+            copy_effect.use_texture("colorMap", &inputs.color_texture)?;
+            return output.write(|| copy_effect.apply(render_states, viewport));
+        }
+
+        for index in enabled.iter() {
+            let effect = &mut self.effects[*index];
+            inputs.check(effect.inputs())?;
+            effect.resize(width, height)?;
+        }
+
+        // The effects alternate between writing to the first and the second target, reading the result of the previous effect from the other one
+        let (first, second) = self.targets.split_at_mut(1);
+        for (i, index) in enabled.iter().enumerate() {
+            let effect = &mut self.effects[*index];
+            let (previous, next) = if i % 2 == 0 {
+                (&second[0], &mut first[0])
+            } else {
+                (&first[0], &mut second[0])
+            };
+            let mut effect_inputs = *inputs;
+            if i > 0 {
+                if let Some(color_texture) = previous.color_texture() {
+                    effect_inputs.color_texture = color_texture;
+                }
+            }
+            if i + 1 == enabled.len() {
+                effect.apply(&effect_inputs, &mut output)?;
+            } else {
+                next.resize(width, height)?;
+                effect.apply(
+                    &effect_inputs,
+                    &mut EffectOutput {
+                        destination: Destination::Intermediate(next),
+                        viewport: Viewport::new_at_origo(width, height),
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+
+uniform sampler2D luminanceMap;
+uniform sampler2D previousExposureMap;
+uniform float minEv;
+uniform float maxEv;
+uniform float brightenFactor;
+uniform float darkenFactor;
+uniform int initialize;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 weightedLuminance = texture(luminanceMap, vec2(0.5)).rg;
+    float averageLogLuminance = weightedLuminance.r / max(weightedLuminance.g, 0.000001);
+    // The exposure which maps the average luminance to middle grey
+    float exposure = clamp(log2(0.18) - averageLogLuminance, minEv, maxEv);
+    if(initialize == 0) {
+        float current = texture(previousExposureMap, vec2(0.5)).r;
+        exposure = mix(current, exposure, exposure > current ? brightenFactor : darkenFactor);
+    }
+    outColor = vec4(exposure, 0.0, 0.0, 1.0);
+}
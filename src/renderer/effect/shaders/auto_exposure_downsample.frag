@@ -0,0 +1,11 @@
+
+uniform sampler2D inputMap;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = texture(inputMap, uv);
+}
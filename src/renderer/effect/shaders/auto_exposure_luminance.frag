@@ -0,0 +1,39 @@
+
+uniform sampler2D colorMap;
+uniform float outputTexelSize;
+uniform float aspectRatio;
+uniform int meteringMode;
+uniform float spotRadius;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+float metering_weight(vec2 coords) {
+    // The distance to the center relative to half the height of the image
+    vec2 offset = (coords - 0.5) * 2.0 * vec2(aspectRatio, 1.0);
+    float distance = length(offset);
+    if(meteringMode == 1) {
+        return exp(-2.0 * distance * distance);
+    } else if(meteringMode == 2) {
+        return 1.0 - smoothstep(0.8 * spotRadius, spotRadius, distance);
+    }
+    return 1.0;
+}
+
+void main()
+{
+    // Averages a grid of 4x4 samples covering the area of this texel in the color texture,
+    // where the red channel is the weighted log luminance and the green channel is the weight
+    vec2 sum = vec2(0.0);
+    for(int y = 0; y < 4; y++) {
+        for(int x = 0; x < 4; x++) {
+            vec2 coords = uv + (vec2(float(x), float(y)) - 1.5) * 0.25 * outputTexelSize;
+            vec3 color = texture(colorMap, coords).rgb;
+            float luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+            float weight = metering_weight(coords);
+            sum += weight * vec2(log2(max(luminance, 0.00001)), 1.0);
+        }
+    }
+    outColor = vec4(sum / 16.0, 0.0, 1.0);
+}
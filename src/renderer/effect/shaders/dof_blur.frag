@@ -0,0 +1,35 @@
+
+uniform sampler2D colorCocMap;
+uniform vec2 texelSize;
+uniform float maxBlur;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+const float GOLDEN_ANGLE = 2.39996323;
+const float RADIUS_SCALE = 1.0;
+
+// Gathers the samples on a spiral inside a circle with the maximum blur radius,
+// where each sample contributes if its own circle of confusion covers this pixel (scatter as gather)
+void main()
+{
+    vec4 center = texture(colorCocMap, uv);
+    float centerSize = abs(center.a);
+    vec3 color = center.rgb;
+    float total = 1.0;
+    float radius = RADIUS_SCALE;
+    for (float angle = 0.0; radius < maxBlur; angle += GOLDEN_ANGLE) {
+        vec4 s = texture(colorCocMap, uv + vec2(cos(angle), sin(angle)) * texelSize * radius);
+        float sampleSize = abs(s.a);
+        // Samples behind the center must not blur over it more than the center itself is blurred
+        if (s.a > center.a) {
+            sampleSize = clamp(sampleSize, 0.0, centerSize * 2.0);
+        }
+        float m = smoothstep(radius - 0.5, radius + 0.5, sampleSize);
+        color += mix(color / total, s.rgb, m);
+        total += 1.0;
+        radius += RADIUS_SCALE / radius;
+    }
+    outColor = vec4(color / total, center.a);
+}
@@ -0,0 +1,15 @@
+
+uniform sampler2D depthMap;
+uniform mat4 viewProjectionInverse;
+uniform vec3 eyePosition;
+uniform float focusDistance;
+uniform float cocScale;
+uniform float maxBlur;
+
+// The signed circle of confusion in pixels of the full resolution image, negative in front of and positive behind the focus distance
+float circle_of_confusion(vec2 coords)
+{
+    float depth = texture(depthMap, coords).x;
+    float dist = distance(world_pos_from_depth(viewProjectionInverse, depth, coords), eyePosition);
+    return clamp(cocScale * (dist - focusDistance) / dist, -maxBlur, maxBlur);
+}
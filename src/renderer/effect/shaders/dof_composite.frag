@@ -0,0 +1,18 @@
+
+uniform sampler2D colorMap;
+uniform sampler2D blurMap;
+uniform sampler2D nearMaskMap;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 sharp = texture(colorMap, uv);
+    vec3 blurred = texture(blurMap, uv).rgb;
+    // A circle of confusion smaller than a pixel is perceived as sharp
+    float factor = smoothstep(1.0, 3.0, abs(circle_of_confusion(uv)));
+    factor = max(factor, texture(nearMaskMap, uv).r);
+    outColor = vec4(mix(sharp.rgb, blurred, factor), sharp.a);
+}
@@ -0,0 +1,30 @@
+
+uniform sampler2D colorCocMap;
+uniform vec2 texelSize;
+uniform float maxBlur;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+// Spreads the near field circle of confusion to the neighbouring pixels it covers,
+// so that blurry foreground objects bleed smoothly over the in focus areas behind them
+void main()
+{
+    float mask = clamp(-texture(colorCocMap, uv).a, 0.0, 1.0);
+    const int rings = 4;
+    const int directions = 8;
+    for (int ring = 1; ring <= rings; ring++) {
+        float dist = maxBlur * float(ring) / float(rings);
+        for (int i = 0; i < directions; i++) {
+            float angle = 6.2831853 * (float(i) + 0.5 * float(ring)) / float(directions);
+            vec2 coords = uv + vec2(cos(angle), sin(angle)) * texelSize * dist;
+            float nearCoc = -texture(colorCocMap, coords).a;
+            if (nearCoc > dist) {
+                // Fade out towards the edge of the circle of confusion to avoid a hard edge
+                mask = max(mask, clamp(2.0 * (nearCoc - dist) / nearCoc, 0.0, 1.0));
+            }
+        }
+    }
+    outColor = vec4(mask, 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,26 @@
+
+uniform sampler2D colorMap;
+uniform vec2 texelSize;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+// Downsamples the color to half resolution and stores the circle of confusion in half resolution pixels in the alpha channel
+void main()
+{
+    vec2 offsets[4] = vec2[](vec2(-0.5, -0.5), vec2(0.5, -0.5), vec2(-0.5, 0.5), vec2(0.5, 0.5));
+    vec3 color = vec3(0.0);
+    float nearCoc = 0.0;
+    float coc = 0.0;
+    for (int i = 0; i < 4; i++) {
+        vec2 coords = uv + offsets[i] * texelSize;
+        color += texture(colorMap, coords).rgb;
+        float c = circle_of_confusion(coords);
+        nearCoc = min(nearCoc, c);
+        coc += c;
+    }
+    // The near field takes precedence to keep the edges of blurry foreground objects from shrinking
+    coc = nearCoc < 0.0 ? nearCoc : 0.25 * coc;
+    outColor = vec4(0.25 * color, 0.5 * coc);
+}
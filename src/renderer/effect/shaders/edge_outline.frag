@@ -0,0 +1,91 @@
+
+uniform mat4 viewProjectionInverse;
+uniform vec3 eyePosition;
+uniform float farPlaneDepth;
+uniform vec2 texelOffset;
+uniform float depthThreshold;
+uniform vec4 outlineColor;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+#ifdef USE_GBUFFER
+uniform sampler2DArray depthMap;
+uniform sampler2DArray gbuffer;
+
+float sample_depth(vec2 uv)
+{
+    return texture(depthMap, vec3(uv, 0)).x;
+}
+
+vec3 sample_normal(vec2 uv)
+{
+    vec2 n = texture(gbuffer, vec3(uv, 1)).xy * 2.0 - 1.0;
+    return normalize(vec3(n, sqrt(max(1.0 - dot(n, n), 0.0))));
+}
+#define USE_NORMALS
+#else
+uniform sampler2D depthMap;
+
+float sample_depth(vec2 uv)
+{
+    return texture(depthMap, uv).x;
+}
+
+#ifdef USE_NORMAL_TEXTURE
+uniform sampler2D normalTexture;
+
+vec3 sample_normal(vec2 uv)
+{
+    return normalize(texture(normalTexture, uv).xyz * 2.0 - 1.0);
+}
+#define USE_NORMALS
+#endif
+#endif
+
+#ifdef USE_NORMALS
+uniform float normalThreshold;
+#endif
+
+bool is_background(float depth)
+{
+    return farPlaneDepth > 0.5 ? depth > 0.99999 : depth <= 0.0;
+}
+
+float view_distance(float depth, vec2 uv)
+{
+    return distance(world_pos_from_depth(viewProjectionInverse, depth, uv), eyePosition);
+}
+
+void main()
+{
+    vec2 offsets[4] = vec2[4](vec2(texelOffset.x, 0.0), vec2(-texelOffset.x, 0.0), vec2(0.0, texelOffset.y), vec2(0.0, -texelOffset.y));
+    float depth = sample_depth(uv);
+    float center_distance = view_distance(depth, uv);
+
+    // The second derivative of the distance, so planes seen at a grazing angle do not result in outlines
+    float laplacian = -4.0 * center_distance;
+    bool background = is_background(depth);
+    for (int i = 0; i < 4; i++)
+    {
+        vec2 neighbour_uv = uv + offsets[i];
+        float neighbour_depth = sample_depth(neighbour_uv);
+        laplacian += view_distance(neighbour_depth, neighbour_uv);
+        background = background || is_background(neighbour_depth);
+    }
+    float edge = step(depthThreshold, abs(laplacian) / center_distance);
+
+#ifdef USE_NORMALS
+    if (!background)
+    {
+        vec3 normal = sample_normal(uv);
+        for (int i = 0; i < 4; i++)
+        {
+            edge = max(edge, step(normalThreshold, 1.0 - dot(normal, sample_normal(uv + offsets[i]))));
+        }
+    }
+#endif
+
+    color = vec4(outlineColor.rgb, outlineColor.a * edge);
+}
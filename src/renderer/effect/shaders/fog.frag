@@ -2,6 +2,7 @@
 uniform sampler2D depthMap;
 
 uniform mat4 viewProjectionInverse;
+uniform float farPlaneDepth;
 
 uniform float time;
 uniform float fogDensity;
@@ -9,6 +10,10 @@ uniform vec3 fogColor;
 uniform float animation;
 uniform vec3 eyePosition;
 
+#ifdef COLOR_INPUT
+uniform sampler2D colorMap;
+#endif
+
 in vec2 uv;
 
 layout (location = 0) out vec4 color;
@@ -139,7 +144,7 @@ void main()
     vec3 pos = WorldPosFromDepth(depth, uv);
 
     // Distance
-    float dist = depth < 0.999f ? distance(pos, eyePosition) : 100.f;
+    float dist = (farPlaneDepth > 0.5 ? depth < 0.999f : depth > 0.0) ? distance(pos, eyePosition) : 100.f;
 
     float x = dist * fogDensity;
     float factor = 1. - 1. / exp(x * x);
@@ -150,5 +155,10 @@ void main()
     factor = clamp(factor, 0., 1.);
 
     // Output
+#ifdef COLOR_INPUT
+    vec4 scene = texture(colorMap, uv);
+    color = vec4(mix(scene.rgb, fogColor, factor), scene.a);
+#else
     color = vec4(fogColor, factor);
+#endif
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+
+uniform sampler2D colorMap;
+uniform vec2 texelSize;
+uniform int samples;
+uniform float maxBlur;
+uniform float intensity;
+
+#ifdef USE_VELOCITY_MAP
+uniform sampler2D velocityMap;
+#else
+uniform sampler2D depthMap;
+uniform mat4 viewProjectionInverse;
+uniform mat4 previousViewProjection;
+#endif
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+// The movement of the surface seen in this pixel since the previous frame in uv coordinates
+vec2 velocity()
+{
+#ifdef USE_VELOCITY_MAP
+    return texture(velocityMap, uv).xy;
+#else
+    float depth = texture(depthMap, uv).x;
+    vec3 position = world_pos_from_depth(viewProjectionInverse, depth, uv);
+    vec4 previous = previousViewProjection * vec4(position, 1.0);
+    if (previous.w <= 0.0) {
+        // The surface was behind the camera in the previous frame
+        return vec2(0.0);
+    }
+    return uv - (0.5 * previous.xy / previous.w + 0.5);
+#endif
+}
+
+void main()
+{
+    // The blur vector in pixels, clamped to the maximum blur to avoid smearing the whole image when the camera teleports
+    vec2 blur = intensity * velocity() / texelSize;
+    float len = length(blur);
+    if (len > maxBlur) {
+        blur *= maxBlur / len;
+    }
+    if (len < 0.5 || samples < 2) {
+        color = texture(colorMap, uv);
+        return;
+    }
+
+    // Average the samples along the blur vector centered at this pixel
+    vec4 sum = vec4(0.0);
+    for (int i = 0; i < samples; i++) {
+        float t = float(i) / float(samples - 1) - 0.5;
+        sum += texture(colorMap, clamp(uv + t * blur * texelSize, vec2(0.0), vec2(1.0)));
+    }
+    color = sum / float(samples);
+}
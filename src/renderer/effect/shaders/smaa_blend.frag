@@ -0,0 +1,36 @@
+
+uniform sampler2D colorMap;
+uniform sampler2D weightsMap;
+uniform vec2 resolution;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+// The neighborhood blending pass of SMAA: Enhanced Subpixel Morphological Antialiasing by Jorge Jimenez et al., http://www.iryoku.com/smaa/
+// ported from the reference implementation, see smaa.rs for the license.
+
+void main()
+{
+    vec4 metrics = vec4(1.0 / resolution, resolution);
+    vec4 offset = metrics.xyxy * vec4(1.0, 0.0, 0.0, 1.0) + uv.xyxy;
+
+    // The weights for blending with the right, top, left and bottom neighbour
+    vec4 a;
+    a.x = texture(weightsMap, offset.xy).a;
+    a.y = texture(weightsMap, offset.zw).g;
+    a.wz = texture(weightsMap, uv).xz;
+
+    if (dot(a, vec4(1.0)) < 1e-5) {
+        outColor = textureLod(colorMap, uv, 0.0);
+    } else {
+        // Blend either horizontally or vertically, using bilinear filtering to blend with the neighbour
+        bool horizontal = max(a.x, a.z) > max(a.y, a.w);
+        vec4 blendingOffset = horizontal ? vec4(a.x, 0.0, a.z, 0.0) : vec4(0.0, a.y, 0.0, a.w);
+        vec2 blendingWeight = horizontal ? a.xz : a.yw;
+        blendingWeight /= dot(blendingWeight, vec2(1.0));
+        vec4 blendingCoord = blendingOffset * vec4(metrics.xy, -metrics.xy) + uv.xyxy;
+        outColor = blendingWeight.x * textureLod(colorMap, blendingCoord.xy, 0.0)
+            + blendingWeight.y * textureLod(colorMap, blendingCoord.zw, 0.0);
+    }
+}
@@ -0,0 +1,75 @@
+
+uniform sampler2D colorMap;
+uniform vec2 resolution;
+uniform float threshold;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+// The edge detection pass of SMAA: Enhanced Subpixel Morphological Antialiasing by Jorge Jimenez et al., http://www.iryoku.com/smaa/
+// ported from the reference implementation, see smaa.rs for the license.
+// The reference implementation expects the y-axis to point downwards, so all passes process the image mirrored vertically, which gives the same result.
+// The red channel contains the edge on the left side of the pixel and the green channel the edge on the top side.
+
+#define LOCAL_CONTRAST_ADAPTATION_FACTOR 2.0
+
+#ifdef COLOR_EDGES
+float difference(vec3 a, vec3 b)
+{
+    vec3 t = abs(a - b);
+    return max(max(t.r, t.g), t.b);
+}
+
+vec3 value(vec2 coord)
+{
+    return textureLod(colorMap, coord, 0.0).rgb;
+}
+#else
+float difference(float a, float b)
+{
+    return abs(a - b);
+}
+
+float value(vec2 coord)
+{
+    return dot(textureLod(colorMap, coord, 0.0).rgb, vec3(0.2126, 0.7152, 0.0722));
+}
+#endif
+
+void main()
+{
+    vec4 metrics = vec4(1.0 / resolution, resolution);
+    vec4 offset0 = metrics.xyxy * vec4(-1.0, 0.0, 0.0, -1.0) + uv.xyxy;
+    vec4 offset1 = metrics.xyxy * vec4( 1.0, 0.0, 0.0,  1.0) + uv.xyxy;
+    vec4 offset2 = metrics.xyxy * vec4(-2.0, 0.0, 0.0, -2.0) + uv.xyxy;
+
+#ifdef COLOR_EDGES
+    vec3 center = value(uv);
+    vec3 left = value(offset0.xy);
+    vec3 top = value(offset0.zw);
+#else
+    float center = value(uv);
+    float left = value(offset0.xy);
+    float top = value(offset0.zw);
+#endif
+    vec4 delta;
+    delta.x = difference(center, left);
+    delta.y = difference(center, top);
+    vec2 edges = step(vec2(threshold), delta.xy);
+    if (dot(edges, vec2(1.0)) == 0.0) {
+        discard;
+    }
+
+    // Local contrast adaptation, which removes edges next to much stronger edges
+    delta.z = difference(center, value(offset1.xy));
+    delta.w = difference(center, value(offset1.zw));
+    vec2 maxDelta = max(delta.xy, delta.zw);
+    delta.z = difference(left, value(offset2.xy));
+    delta.w = difference(top, value(offset2.zw));
+    maxDelta = max(maxDelta, delta.zw);
+    float finalDelta = max(maxDelta.x, maxDelta.y);
+    edges *= step(finalDelta, LOCAL_CONTRAST_ADAPTATION_FACTOR * delta.xy);
+
+    outColor = vec4(edges, 0.0, 0.0);
+}
@@ -0,0 +1,306 @@
+
+uniform sampler2D edgesMap;
+uniform sampler2D areaMap;
+uniform sampler2D searchMap;
+uniform vec2 resolution;
+uniform float maxSearchSteps;
+uniform float maxSearchStepsDiag;
+uniform float cornerRounding;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+// The blending weight calculation pass of SMAA: Enhanced Subpixel Morphological Antialiasing by Jorge Jimenez et al., http://www.iryoku.com/smaa/
+// ported from the reference implementation, see smaa.rs for the license.
+// Only SMAA 1x is supported, so the subsample indices are always zero.
+
+#define AREATEX_MAX_DISTANCE 16.0
+#define AREATEX_MAX_DISTANCE_DIAG 20.0
+#define AREATEX_PIXEL_SIZE (1.0 / vec2(160.0, 560.0))
+#define AREATEX_SUBTEX_SIZE (1.0 / 7.0)
+#define SEARCHTEX_SIZE vec2(66.0, 33.0)
+#define SEARCHTEX_PACKED_SIZE vec2(64.0, 16.0)
+
+vec4 metrics;
+
+void movc(bvec2 cond, inout vec2 variable, vec2 value)
+{
+    if (cond.x) variable.x = value.x;
+    if (cond.y) variable.y = value.y;
+}
+
+// Decodes two binary edges from a bilinear fetch with a 0.25 offset, where the red edge gives 0.25 or 1.0 and the green edge 0.75 or 1.0
+vec2 decodeDiagBilinearAccess(vec2 e)
+{
+    e.r = e.r * abs(5.0 * e.r - 5.0 * 0.75);
+    return round(e);
+}
+
+vec4 decodeDiagBilinearAccess(vec4 e)
+{
+    e.rb = e.rb * abs(5.0 * e.rb - 5.0 * 0.75);
+    return round(e);
+}
+
+vec2 searchDiag1(vec2 texcoord, vec2 dir, out vec2 e)
+{
+    vec4 coord = vec4(texcoord, -1.0, 1.0);
+    vec3 t = vec3(metrics.xy, 1.0);
+    while (coord.z < maxSearchStepsDiag - 1.0 && coord.w > 0.9) {
+        coord.xyz = t * vec3(dir, 1.0) + coord.xyz;
+        e = textureLod(edgesMap, coord.xy, 0.0).rg;
+        coord.w = dot(e, vec2(0.5));
+    }
+    return coord.zw;
+}
+
+vec2 searchDiag2(vec2 texcoord, vec2 dir, out vec2 e)
+{
+    vec4 coord = vec4(texcoord, -1.0, 1.0);
+    // Fetches both edges at once with bilinear filtering
+    coord.x += 0.25 * metrics.x;
+    vec3 t = vec3(metrics.xy, 1.0);
+    while (coord.z < maxSearchStepsDiag - 1.0 && coord.w > 0.9) {
+        coord.xyz = t * vec3(dir, 1.0) + coord.xyz;
+        e = textureLod(edgesMap, coord.xy, 0.0).rg;
+        e = decodeDiagBilinearAccess(e);
+        coord.w = dot(e, vec2(0.5));
+    }
+    return coord.zw;
+}
+
+// Looks up the coverage areas of a diagonal line for the given distances to the ends of the line and the crossing edges at the ends
+vec2 areaDiag(vec2 dist, vec2 e, float offset)
+{
+    vec2 texcoord = vec2(AREATEX_MAX_DISTANCE_DIAG) * e + dist;
+    texcoord = AREATEX_PIXEL_SIZE * texcoord + 0.5 * AREATEX_PIXEL_SIZE;
+    // The diagonal areas are in the right half of the texture
+    texcoord.x += 0.5;
+    texcoord.y += AREATEX_SUBTEX_SIZE * offset;
+    return textureLod(areaMap, texcoord, 0.0).rg;
+}
+
+vec2 calculateDiagWeights(vec2 texcoord, vec2 e)
+{
+    vec2 weights = vec2(0.0);
+
+    // Search for the line ends
+    vec4 d;
+    vec2 end;
+    if (e.r > 0.0) {
+        d.xz = searchDiag1(texcoord, vec2(-1.0, 1.0), end);
+        d.x += float(end.y > 0.9);
+    } else {
+        d.xz = vec2(0.0);
+    }
+    d.yw = searchDiag1(texcoord, vec2(1.0, -1.0), end);
+
+    if (d.x + d.y > 2.0) {
+        // Fetch the crossing edges
+        vec4 coords = vec4(-d.x + 0.25, d.x, d.y, -d.y - 0.25) * metrics.xyxy + texcoord.xyxy;
+        vec4 c;
+        c.xy = textureLodOffset(edgesMap, coords.xy, 0.0, ivec2(-1, 0)).rg;
+        c.zw = textureLodOffset(edgesMap, coords.zw, 0.0, ivec2(1, 0)).rg;
+        c.yxwz = decodeDiagBilinearAccess(c.xyzw);
+
+        // Merge the crossing edges at each side into a single value and remove them if the end of the line was not found
+        vec2 cc = vec2(2.0) * c.xz + c.yw;
+        movc(bvec2(step(0.9, d.zw)), cc, vec2(0.0));
+
+        weights += areaDiag(d.xy, cc, 0.0);
+    }
+
+    // Search for the line ends in the other diagonal direction
+    d.xz = searchDiag2(texcoord, vec2(-1.0, -1.0), end);
+    if (textureLodOffset(edgesMap, texcoord, 0.0, ivec2(1, 0)).r > 0.0) {
+        d.yw = searchDiag2(texcoord, vec2(1.0, 1.0), end);
+        d.y += float(end.y > 0.9);
+    } else {
+        d.yw = vec2(0.0);
+    }
+
+    if (d.x + d.y > 2.0) {
+        vec4 coords = vec4(-d.x, -d.x, d.y, d.y) * metrics.xyxy + texcoord.xyxy;
+        vec4 c;
+        c.x = textureLodOffset(edgesMap, coords.xy, 0.0, ivec2(-1, 0)).g;
+        c.y = textureLodOffset(edgesMap, coords.xy, 0.0, ivec2(0, -1)).r;
+        c.zw = textureLodOffset(edgesMap, coords.zw, 0.0, ivec2(1, 0)).gr;
+        vec2 cc = vec2(2.0) * c.xz + c.yw;
+        movc(bvec2(step(0.9, d.zw)), cc, vec2(0.0));
+
+        weights += areaDiag(d.xy, cc, 0.0).gr;
+    }
+
+    return weights;
+}
+
+// Looks up the length to add in the last step of a search, which depends on which edges and crossing edges of the bilinear fetch are active
+float searchLength(vec2 e, float offset)
+{
+    // The search texture is flipped vertically, with the left and right cases taking half of the space horizontally
+    vec2 scale = SEARCHTEX_SIZE * vec2(0.5, -1.0);
+    vec2 bias = SEARCHTEX_SIZE * vec2(offset, 1.0);
+    scale += vec2(-1.0, 1.0);
+    bias += vec2(0.5, -0.5);
+    // The search texture is cropped to the packed size
+    scale *= 1.0 / SEARCHTEX_PACKED_SIZE;
+    bias *= 1.0 / SEARCHTEX_PACKED_SIZE;
+    return textureLod(searchMap, scale * e + bias, 0.0).r;
+}
+
+float searchXLeft(vec2 texcoord, float end)
+{
+    // The texcoord is offset by (-0.25, -0.125), so four edges are fetched at once with bilinear filtering
+    vec2 e = vec2(0.0, 1.0);
+    while (texcoord.x > end && e.g > 0.8281 && e.r == 0.0) {
+        e = textureLod(edgesMap, texcoord, 0.0).rg;
+        texcoord = -vec2(2.0, 0.0) * metrics.xy + texcoord;
+    }
+    float offset = -(255.0 / 127.0) * searchLength(e, 0.0) + 3.25;
+    return metrics.x * offset + texcoord.x;
+}
+
+float searchXRight(vec2 texcoord, float end)
+{
+    vec2 e = vec2(0.0, 1.0);
+    while (texcoord.x < end && e.g > 0.8281 && e.r == 0.0) {
+        e = textureLod(edgesMap, texcoord, 0.0).rg;
+        texcoord = vec2(2.0, 0.0) * metrics.xy + texcoord;
+    }
+    float offset = -(255.0 / 127.0) * searchLength(e, 0.5) + 3.25;
+    return -metrics.x * offset + texcoord.x;
+}
+
+float searchYUp(vec2 texcoord, float end)
+{
+    vec2 e = vec2(1.0, 0.0);
+    while (texcoord.y > end && e.r > 0.8281 && e.g == 0.0) {
+        e = textureLod(edgesMap, texcoord, 0.0).rg;
+        texcoord = -vec2(0.0, 2.0) * metrics.xy + texcoord;
+    }
+    float offset = -(255.0 / 127.0) * searchLength(e.gr, 0.0) + 3.25;
+    return metrics.y * offset + texcoord.y;
+}
+
+float searchYDown(vec2 texcoord, float end)
+{
+    vec2 e = vec2(1.0, 0.0);
+    while (texcoord.y < end && e.r > 0.8281 && e.g == 0.0) {
+        e = textureLod(edgesMap, texcoord, 0.0).rg;
+        texcoord = vec2(0.0, 2.0) * metrics.xy + texcoord;
+    }
+    float offset = -(255.0 / 127.0) * searchLength(e.gr, 0.5) + 3.25;
+    return -metrics.y * offset + texcoord.y;
+}
+
+// Looks up the coverage areas for the given square root of the distances to the ends of the line and the crossing edges at the ends
+vec2 area(vec2 dist, float e1, float e2, float offset)
+{
+    // Rounding prevents precision errors of bilinear filtering
+    vec2 texcoord = vec2(AREATEX_MAX_DISTANCE) * round(4.0 * vec2(e1, e2)) + dist;
+    texcoord = AREATEX_PIXEL_SIZE * texcoord + 0.5 * AREATEX_PIXEL_SIZE;
+    texcoord.y = AREATEX_SUBTEX_SIZE * offset + texcoord.y;
+    return textureLod(areaMap, texcoord, 0.0).rg;
+}
+
+void detectHorizontalCornerPattern(inout vec2 weights, vec4 texcoord, vec2 d)
+{
+    vec2 leftRight = step(d.xy, d.yx);
+    vec2 rounding = (1.0 - cornerRounding / 100.0) * leftRight;
+    // Reduce blending for pixels in the center of a line
+    rounding /= leftRight.x + leftRight.y;
+
+    vec2 factor = vec2(1.0);
+    factor.x -= rounding.x * textureLodOffset(edgesMap, texcoord.xy, 0.0, ivec2(0, 1)).r;
+    factor.x -= rounding.y * textureLodOffset(edgesMap, texcoord.zw, 0.0, ivec2(1, 1)).r;
+    factor.y -= rounding.x * textureLodOffset(edgesMap, texcoord.xy, 0.0, ivec2(0, -2)).r;
+    factor.y -= rounding.y * textureLodOffset(edgesMap, texcoord.zw, 0.0, ivec2(1, -2)).r;
+
+    weights *= clamp(factor, 0.0, 1.0);
+}
+
+void detectVerticalCornerPattern(inout vec2 weights, vec4 texcoord, vec2 d)
+{
+    vec2 leftRight = step(d.xy, d.yx);
+    vec2 rounding = (1.0 - cornerRounding / 100.0) * leftRight;
+    rounding /= leftRight.x + leftRight.y;
+
+    vec2 factor = vec2(1.0);
+    factor.x -= rounding.x * textureLodOffset(edgesMap, texcoord.xy, 0.0, ivec2(1, 0)).g;
+    factor.x -= rounding.y * textureLodOffset(edgesMap, texcoord.zw, 0.0, ivec2(1, 1)).g;
+    factor.y -= rounding.x * textureLodOffset(edgesMap, texcoord.xy, 0.0, ivec2(-2, 0)).g;
+    factor.y -= rounding.y * textureLodOffset(edgesMap, texcoord.zw, 0.0, ivec2(-2, 1)).g;
+
+    weights *= clamp(factor, 0.0, 1.0);
+}
+
+void main()
+{
+    metrics = vec4(1.0 / resolution, resolution);
+    vec2 pixcoord = uv * metrics.zw;
+    // Offsets for the searches, see searchXLeft
+    vec4 offset0 = metrics.xyxy * vec4(-0.25, -0.125, 1.25, -0.125) + uv.xyxy;
+    vec4 offset1 = metrics.xyxy * vec4(-0.125, -0.25, -0.125, 1.25) + uv.xyxy;
+    // The ends of the searches
+    vec4 offset2 = metrics.xxyy * vec4(-2.0, 2.0, -2.0, 2.0) * maxSearchSteps + vec4(offset0.xz, offset1.yw);
+
+    vec4 weights = vec4(0.0);
+    vec2 e = texture(edgesMap, uv).rg;
+
+    // Edge at the top
+    if (e.g > 0.0) {
+        // Diagonals have both top and left edges, so searching for them at the top edges is enough.
+        // Diagonals have priority, so the horizontal and vertical processing is skipped if a diagonal is found.
+        weights.rg = calculateDiagWeights(uv, e);
+        if (weights.r == -weights.g) {
+            vec2 d;
+            vec3 coords;
+            coords.x = searchXLeft(offset0.xy, offset2.x);
+            // Sampling the crossing edges a quarter pixel towards the top makes it possible to tell which of the two edges are active
+            coords.y = offset1.y;
+            d.x = coords.x;
+            float e1 = textureLod(edgesMap, coords.xy, 0.0).r;
+            coords.z = searchXRight(offset0.zw, offset2.y);
+            d.y = coords.z;
+
+            // The distances in pixels, where the square root is used for the lookup, since the area texture is compressed quadratically
+            d = abs(round(metrics.zz * d - pixcoord.xx));
+            vec2 sqrtD = sqrt(d);
+            float e2 = textureLodOffset(edgesMap, coords.zy, 0.0, ivec2(1, 0)).r;
+            weights.rg = area(sqrtD, e1, e2, 0.0);
+
+            coords.y = uv.y;
+            vec2 weightsRG = weights.rg;
+            detectHorizontalCornerPattern(weightsRG, coords.xyzy, d);
+            weights.rg = weightsRG;
+        } else {
+            // Skip the vertical processing
+            e.r = 0.0;
+        }
+    }
+
+    // Edge at the left
+    if (e.r > 0.0) {
+        vec2 d;
+        vec3 coords;
+        coords.y = searchYUp(offset1.xy, offset2.z);
+        coords.x = offset0.x;
+        d.x = coords.y;
+        float e1 = textureLod(edgesMap, coords.xy, 0.0).g;
+        coords.z = searchYDown(offset1.zw, offset2.w);
+        d.y = coords.z;
+
+        d = abs(round(metrics.ww * d - pixcoord.yy));
+        vec2 sqrtD = sqrt(d);
+        float e2 = textureLodOffset(edgesMap, coords.xz, 0.0, ivec2(0, 1)).g;
+        weights.ba = area(sqrtD, e1, e2, 0.0);
+
+        coords.x = uv.x;
+        vec2 weightsBA = weights.ba;
+        detectVerticalCornerPattern(weightsBA, coords.xyxz, d);
+        weights.ba = weightsBA;
+    }
+
+    outColor = weights;
+}
@@ -0,0 +1,78 @@
+
+uniform sampler2D colorMap;
+uniform int toneMappingOperator;
+uniform float exposure;
+uniform int useExposureMap;
+uniform sampler2D exposureMap;
+uniform float gamma;
+uniform int encodeSrgb;
+uniform int useColorGrading;
+uniform sampler2D colorGradingMap;
+uniform float colorGradingSize;
+uniform float sharpness;
+uniform vec2 texelSize;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+// ACES filmic tone mapping curve fitted by Krzysztof Narkowicz
+vec3 aces_filmic_tone_mapping(vec3 color) {
+    const float a = 2.51;
+    const float b = 0.03;
+    const float c = 2.43;
+    const float d = 0.59;
+    const float e = 0.14;
+    return clamp((color * (a * color + b)) / (color * (c * color + d) + e), 0.0, 1.0);
+}
+
+// The 3D lookup table is stored as a horizontal strip of slices, one for each blue value
+vec3 color_grading(vec3 color) {
+    float size = colorGradingSize;
+    float blue = color.b * (size - 1.0);
+    float slice0 = floor(blue);
+    float slice1 = min(slice0 + 1.0, size - 1.0);
+    vec2 offset = vec2((color.r * (size - 1.0) + 0.5) / (size * size), (color.g * (size - 1.0) + 0.5) / size);
+    vec3 color0 = texture(colorGradingMap, offset + vec2(slice0 / size, 0.0)).rgb;
+    vec3 color1 = texture(colorGradingMap, offset + vec2(slice1 / size, 0.0)).rgb;
+    return mix(color0, color1, blue - slice0);
+}
+
+// Unsharp masking with the four closest texels, which restores some of the detail lost when upscaling
+vec4 sample_color() {
+    vec4 color = texture(colorMap, uv);
+    if(sharpness > 0.0) {
+        vec3 blur = 0.25 * (texture(colorMap, uv + vec2(texelSize.x, 0.0)).rgb
+            + texture(colorMap, uv - vec2(texelSize.x, 0.0)).rgb
+            + texture(colorMap, uv + vec2(0.0, texelSize.y)).rgb
+            + texture(colorMap, uv - vec2(0.0, texelSize.y)).rgb);
+        color.rgb = max(color.rgb + sharpness * (color.rgb - blur), vec3(0.0));
+    }
+    return color;
+}
+
+void main()
+{
+    vec4 color = sample_color();
+    float e = exposure;
+    if(useExposureMap == 1) {
+        // The red channel contains the exposure in stops adapted by the auto exposure
+        e *= exp2(texture(exposureMap, vec2(0.5)).r);
+    }
+    vec3 rgb = e * color.rgb;
+    if(toneMappingOperator == 1) {
+        rgb = reinhard_tone_mapping(rgb);
+    } else if(toneMappingOperator == 2) {
+        rgb = aces_filmic_tone_mapping(rgb);
+    }
+    rgb = pow(clamp(rgb, 0.0, 1.0), vec3(1.0 / gamma));
+
+    // The lookup table is defined in sRGB
+    if(useColorGrading == 1) {
+        rgb = rgb_from_srgb(color_grading(srgb_from_rgb(rgb)));
+    }
+    if(encodeSrgb == 1) {
+        rgb = srgb_from_rgb(rgb);
+    }
+    outColor = vec4(rgb, color.a);
+}
@@ -0,0 +1,96 @@
+
+uniform sampler2D depthMap;
+uniform mat4 viewProjectionInverse;
+uniform vec3 eyePosition;
+uniform float maxDistance;
+uniform int steps;
+uniform float density;
+uniform float anisotropy;
+uniform vec3 lightColor;
+
+#if defined(SPOT_LIGHT) || defined(POINT_LIGHT)
+uniform vec3 lightPosition;
+uniform vec3 attenuation;
+#endif
+#if defined(SPOT_LIGHT) || defined(DIRECTIONAL_LIGHT)
+uniform vec3 lightDirection;
+#endif
+#ifdef SPOT_LIGHT
+uniform float cutoff;
+#endif
+#ifdef USE_SHADOW_MAP
+uniform sampler2DShadow shadowMap;
+uniform mat4 shadowMatrix;
+uniform vec4 shadowRect;
+#endif
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+// The fraction of the light which is scattered in the direction with the given angle to the direction of the light
+float henyey_greenstein(float cos_theta, float g)
+{
+    float g2 = g * g;
+    return (1.0 - g2) / (4.0 * PI * pow(max(1.0 + g2 - 2.0 * g * cos_theta, 0.0001), 1.5));
+}
+
+float visibility(vec3 position)
+{
+#ifdef USE_SHADOW_MAP
+    vec4 shadow_coord = shadowMatrix * vec4(position, 1.0);
+    vec3 coord = shadow_coord.xyz / shadow_coord.w;
+    if(coord.x < 0.0 || coord.x > 1.0 || coord.y < 0.0 || coord.y > 1.0 || coord.z > 1.0) {
+        return 1.0;
+    }
+    return texture(shadowMap, vec3(shadowRect.xy + coord.xy * shadowRect.zw, coord.z));
+#else
+    return 1.0;
+#endif
+}
+
+// The light which is scattered towards the camera at the given position, where the view direction is from the camera towards the position
+vec3 in_scattered_light(vec3 position, vec3 view_direction)
+{
+#ifdef DIRECTIONAL_LIGHT
+    vec3 light_direction = -lightDirection;
+    vec3 light_color = lightColor;
+#else
+    vec3 light_direction = lightPosition - position;
+    float distance = length(light_direction);
+    light_direction /= max(distance, 0.0001);
+    float att = attenuation.x + attenuation.y * distance + attenuation.z * distance * distance;
+    vec3 light_color = lightColor / max(1.0, att);
+#endif
+#ifdef SPOT_LIGHT
+    float angle = acos(clamp(dot(-light_direction, lightDirection), -1.0, 1.0));
+    light_color *= 1.0 - smoothstep(0.75 * cutoff, cutoff, angle);
+#endif
+    return light_color * henyey_greenstein(dot(light_direction, view_direction), anisotropy);
+}
+
+void main()
+{
+    float depth = texture(depthMap, uv).x;
+    vec3 ray = world_pos_from_depth(viewProjectionInverse, depth, uv) - eyePosition;
+    float distance = length(ray);
+    vec3 view_direction = ray / max(distance, 0.0001);
+    float ray_length = min(distance, maxDistance);
+    float step_length = ray_length / float(steps);
+
+    // The start of the ray is offset by a different fraction of a step in neighbouring pixels,
+    // which turns the banding caused by the fixed steps into noise which is removed by the upsampling
+    float dither = fract(52.9829189 * fract(dot(gl_FragCoord.xy, vec2(0.06711056, 0.00583715))));
+
+    vec3 scattering = vec3(0.0);
+    float transmittance = 1.0;
+    float step_transmittance = exp(-density * step_length);
+    for(int i = 0; i < steps; i++) {
+        vec3 position = eyePosition + (float(i) + dither) * step_length * view_direction;
+        scattering += transmittance * visibility(position) * in_scattered_light(position, view_direction);
+        transmittance *= step_transmittance;
+    }
+
+    // The distance to the geometry is stored in the alpha channel and used by the depth-aware upsampling
+    color = vec4(density * step_length * scattering, distance);
+}
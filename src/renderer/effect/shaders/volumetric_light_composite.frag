@@ -0,0 +1,39 @@
+
+uniform sampler2D depthMap;
+uniform sampler2D scatteringMap;
+uniform mat4 viewProjectionInverse;
+uniform vec3 eyePosition;
+uniform vec2 scatteringSize;
+uniform float intensity;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    float depth = texture(depthMap, uv).x;
+    float distance = length(world_pos_from_depth(viewProjectionInverse, depth, uv) - eyePosition);
+
+    // Bilateral upsampling: the four nearest low resolution texels are weighted by the bilinear weight
+    // and by how close the distance to the geometry stored in the texel is to the distance in this pixel,
+    // which avoids light bleeding across the edges of the geometry
+    vec2 position = uv * scatteringSize - 0.5;
+    vec2 base = floor(position);
+    vec2 f = position - base;
+    vec3 scattering = vec3(0.0);
+    float total_weight = 0.0;
+    for(int y = 0; y <= 1; y++) {
+        for(int x = 0; x <= 1; x++) {
+            vec2 offset = vec2(x, y);
+            vec4 s = texture(scatteringMap, (base + offset + 0.5) / scatteringSize);
+            vec2 bilinear = mix(1.0 - f, f, offset);
+            float weight = bilinear.x * bilinear.y / (0.01 + abs(s.a - distance) / max(distance, 0.0001));
+            scattering += weight * s.rgb;
+            total_weight += weight;
+        }
+    }
+    scattering = total_weight > 0.0001 ? scattering / total_weight : texture(scatteringMap, uv).rgb;
+
+    color = vec4(srgb_from_rgb(intensity * scattering), 0.0);
+}
@@ -0,0 +1,229 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Defines how [SmaaEffect] finds the edges in the image.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SmaaEdgeDetection {
+    /// Finds the edges from the difference in luma between neighbouring pixels, which is fast and works well in most cases.
+    Luma,
+    /// Finds the edges from the largest difference in any of the color channels, which also finds edges between colors with the same luma.
+    Color,
+}
+
+///
+/// SMAA 1x (Enhanced Subpixel Morphological Antialiasing) which finds the edges in the image,
+/// recognizes the horizontal, vertical and diagonal line patterns they form and blends the pixels along the edges according to the area covered by the line they approximate.
+/// Compared to [FXAAEffect], only the edges are smoothed, so the rest of the image stays sharp.
+/// The passes are ported from the reference implementation and use its precomputed area and search textures.
+///
+/// The color texture must use linear interpolation and is expected to contain colors in the range `[0..1]`, for example after tone mapping.
+///
+pub struct SmaaEffect {
+    /// The method used for finding the edges.
+    pub edge_detection: SmaaEdgeDetection,
+    ///
+    /// The minimum difference in luma or color between two pixels to be considered an edge, in the range `[0..0.5]`.
+    /// Lower values find more edges at the cost of performance and blurring some details.
+    ///
+    pub threshold: f32,
+    ///
+    /// The maximum number of steps when searching for the ends of a horizontal or vertical line in each direction, in the range `[1..112]`.
+    /// Each step covers two pixels, so longer lines are only fully smoothed with more steps.
+    ///
+    pub max_search_steps: u32,
+    ///
+    /// The maximum number of steps when searching for the ends of a diagonal line in each direction, in the range `[1..20]`.
+    ///
+    pub max_diagonal_search_steps: u32,
+    ///
+    /// How much sharp corners are rounded, in percent in the range `[0..100]`, where 0 keeps the corners sharp.
+    ///
+    pub corner_rounding: f32,
+    /// Whether the effect is applied when used as a [PostEffect] in an [EffectStack].
+    pub enabled: bool,
+    luma_edges_effect: ImageEffect,
+    color_edges_effect: ImageEffect,
+    weights_effect: ImageEffect,
+    blend_effect: ImageEffect,
+    area_texture: Texture2D<u8>,
+    search_texture: Texture2D<u8>,
+    edges_target: ResizableTarget<u8>,
+    weights_target: ResizableTarget<u8>,
+}
+
+impl SmaaEffect {
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        let new_target = |format| {
+            ResizableTarget::new_color(context, move |context, width, height| {
+                Texture2D::new_empty(
+                    context,
+                    width,
+                    height,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                    format,
+                )
+            })
+        };
+        let lookup_texture = |data: &[u8], width, height, format| {
+            Texture2D::new(
+                context,
+                &CPUTexture {
+                    data: data.to_vec(),
+                    width,
+                    height,
+                    format,
+                    min_filter: Interpolation::Linear,
+                    mag_filter: Interpolation::Linear,
+                    mip_map_filter: None,
+                    wrap_s: Wrapping::ClampToEdge,
+                    wrap_t: Wrapping::ClampToEdge,
+                    ..Default::default()
+                },
+            )
+        };
+        Ok(Self {
+            edge_detection: SmaaEdgeDetection::Luma,
+            threshold: 0.1,
+            max_search_steps: 16,
+            max_diagonal_search_steps: 8,
+            corner_rounding: 25.0,
+            enabled: true,
+            luma_edges_effect: ImageEffect::new(context, include_str!("shaders/smaa_edges.frag"))?,
+            color_edges_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "#define COLOR_EDGES\n{}",
+                    include_str!("shaders/smaa_edges.frag")
+                ),
+            )?,
+            weights_effect: ImageEffect::new(context, include_str!("shaders/smaa_weights.frag"))?,
+            blend_effect: ImageEffect::new(context, include_str!("shaders/smaa_blend.frag"))?,
+            area_texture: lookup_texture(AREA_TEXTURE, 160, 560, Format::RG)?,
+            search_texture: lookup_texture(SEARCH_TEXTURE, 64, 16, Format::R)?,
+            edges_target: new_target(Format::RG),
+            weights_target: new_target(Format::RGBA),
+        })
+    }
+
+    ///
+    /// Applies the effect to the given color texture and writes the result to the given viewport, which must have the same size as the color texture.
+    /// The edges and blending weights are first computed in internal render targets, which are recreated when the size of the color texture changes.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn apply(&mut self, viewport: Viewport, color_texture: impl Texture) -> ThreeDResult<()> {
+        let (width, height) = (color_texture.width(), color_texture.height());
+        // Creating the textures binds them, so the targets are resized before any texture is used by the effects
+        self.edges_target.resize(width, height)?;
+        self.weights_target.resize(width, height)?;
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        };
+        let target_viewport = Viewport::new_at_origo(width, height);
+        let resolution = vec2(width as f32, height as f32);
+
+        let edges_effect = match self.edge_detection {
+            SmaaEdgeDetection::Luma => &self.luma_edges_effect,
+            SmaaEdgeDetection::Color => &self.color_edges_effect,
+        };
+        edges_effect.use_texture("colorMap", &color_texture)?;
+        edges_effect.use_uniform("resolution", resolution)?;
+        edges_effect.use_uniform("threshold", self.threshold.max(0.0).min(0.5))?;
+        self.edges_target
+            .get(width, height)?
+            .write(ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+                edges_effect.apply(render_states, target_viewport)
+            })?;
+
+        let weights_effect = &self.weights_effect;
+        weights_effect.use_texture("edgesMap", self.edges_target.color_texture().unwrap())?;
+        weights_effect.use_texture("areaMap", &self.area_texture)?;
+        weights_effect.use_texture("searchMap", &self.search_texture)?;
+        weights_effect.use_uniform("resolution", resolution)?;
+        weights_effect.use_uniform(
+            "maxSearchSteps",
+            self.max_search_steps.max(1).min(112) as f32,
+        )?;
+        weights_effect.use_uniform(
+            "maxSearchStepsDiag",
+            self.max_diagonal_search_steps.max(1).min(20) as f32,
+        )?;
+        weights_effect.use_uniform("cornerRounding", self.corner_rounding.max(0.0).min(100.0))?;
+        self.weights_target
+            .get(width, height)?
+            .write(ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+                weights_effect.apply(render_states, target_viewport)
+            })?;
+
+        let blend_effect = &self.blend_effect;
+        blend_effect.use_texture("colorMap", &color_texture)?;
+        blend_effect.use_texture("weightsMap", self.weights_target.color_texture().unwrap())?;
+        blend_effect.use_uniform("resolution", resolution)?;
+        blend_effect.apply(render_states, viewport)
+    }
+}
+
+impl PostEffect for SmaaEffect {
+    fn inputs(&self) -> EffectInputMask {
+        EffectInputMask::COLOR
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn resize(&mut self, width: u32, height: u32) -> ThreeDResult<()> {
+        self.edges_target.resize(width, height)?;
+        self.weights_target.resize(width, height)?;
+        Ok(())
+    }
+
+    fn apply(&mut self, inputs: &EffectInputs, output: &mut EffectOutput) -> ThreeDResult<()> {
+        let viewport = output.viewport();
+        let color_texture = inputs.color_texture;
+        output.write(|| SmaaEffect::apply(self, viewport, color_texture))
+    }
+}
+
+// The area and search textures, stored in RG and R format respectively, are copied from the reference implementation of SMAA (https://github.com/iryoku/smaa)
+// and the shaders/smaa_*.frag passes are ported from it, which is licensed as follows:
+//
+// Copyright (C) 2013 Jorge Jimenez (jorge@iryoku.com)
+// Copyright (C) 2013 Jose I. Echevarria (joseignacioechevarria@gmail.com)
+// Copyright (C) 2013 Belen Masia (bmasia@unizar.es)
+// Copyright (C) 2013 Fernando Navarro (fernandn@microsoft.com)
+// Copyright (C) 2013 Diego Gutierrez (diegog@unizar.es)
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// this software and associated documentation files (the "Software"), to deal in
+// the Software without restriction, including without limitation the rights to
+// use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software is furnished to do so,
+// subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software. As clarification, there is no
+// requirement that the copyright notice and permission be included in binary
+// distributions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+// IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+// CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+const AREA_TEXTURE: &[u8] = include_bytes!("textures/smaa_area.bin");
+const SEARCH_TEXTURE: &[u8] = include_bytes!("textures/smaa_search.bin");
@@ -0,0 +1,181 @@
+use crate::core::*;
+use crate::renderer::AutoExposure;
+
+///
+/// The operator used by the [ToneMappingEffect] to map high dynamic range colors to the displayable range.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ToneMappingOperator {
+    /// No tone mapping, colors outside the displayable range are clamped.
+    Linear,
+    /// Reinhard tone mapping, ie. `color / (color + 1)`.
+    Reinhard,
+    /// An approximation of the ACES filmic tone mapping curve.
+    AcesFilmic,
+}
+
+///
+/// The filter used when the color texture given to the [ToneMappingEffect] is smaller than the viewport it is written to,
+/// for example when rendering at a lower resolution using [DynamicResolution](crate::DynamicResolution).
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UpscaleFilter {
+    /// Bilinear interpolation between the texels of the color texture.
+    Linear,
+    /// Bilinear interpolation followed by sharpening with the given strength, where 0 is no sharpening and 1 is a strong sharpening.
+    Sharpen(f32),
+}
+
+///
+/// An effect that maps a linear high dynamic range image to the displayable range,
+/// optionally followed by color grading with a lookup table and finally converts it to sRGB.
+/// Usually used as the final pass of an [HdrPipeline](crate::HdrPipeline).
+///
+pub struct ToneMappingEffect {
+    /// The tone mapping operator.
+    pub operator: ToneMappingOperator,
+    /// The colors are multiplied by the exposure before tone mapping, in addition to the exposure of an [AutoExposure] if one is used.
+    pub exposure: f32,
+    /// Gamma correction applied after tone mapping, a value of 1 leaves the tone mapped colors unchanged.
+    pub gamma: f32,
+    ///
+    /// A 3D color grading lookup table stored as a 2D texture of size `size * size` times `size`,
+    /// where each of the `size` horizontal slices corresponds to a blue value.
+    /// The lookup table is defined in sRGB and is applied after tone mapping.
+    ///
+    pub color_grading: Option<Texture2D<u8>>,
+    ///
+    /// Set this to true if the render target is sRGB-aware, ie. it converts the linear output to sRGB when writing,
+    /// to avoid converting the colors to sRGB twice. The screen is usually not sRGB-aware.
+    ///
+    pub target_is_srgb: bool,
+    image_effect: ImageEffect,
+}
+
+impl ToneMappingEffect {
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            operator: ToneMappingOperator::AcesFilmic,
+            exposure: 1.0,
+            gamma: 1.0,
+            color_grading: None,
+            target_is_srgb: false,
+            image_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "{}{}",
+                    include_str!("../../core/shared.frag"),
+                    include_str!("shaders/tone_mapping.frag")
+                ),
+            )?,
+        })
+    }
+
+    ///
+    /// Applies the tone mapping to the given color texture, which should contain linear colors, and writes the result to the given viewport
+    /// of the current render target.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn apply(&self, viewport: Viewport, color_texture: &impl Texture) -> ThreeDResult<()> {
+        self.apply_upscaled(viewport, color_texture, UpscaleFilter::Linear)
+    }
+
+    ///
+    /// Same as [ToneMappingEffect::apply], except that the given filter is used if the color texture is smaller than the viewport,
+    /// so the upscaling happens in the same pass as the tone mapping.
+    ///
+    pub fn apply_upscaled(
+        &self,
+        viewport: Viewport,
+        color_texture: &impl Texture,
+        filter: UpscaleFilter,
+    ) -> ThreeDResult<()> {
+        self.apply_internal(viewport, color_texture, filter, None)
+    }
+
+    ///
+    /// Same as [ToneMappingEffect::apply], except that the colors are also multiplied by the exposure adapted by the given [AutoExposure]
+    /// including its exposure compensation, which is read directly from the [AutoExposure::exposure_texture] on the GPU.
+    ///
+    pub fn apply_with_auto_exposure(
+        &self,
+        viewport: Viewport,
+        color_texture: &impl Texture,
+        auto_exposure: &AutoExposure,
+    ) -> ThreeDResult<()> {
+        self.apply_internal(
+            viewport,
+            color_texture,
+            UpscaleFilter::Linear,
+            Some(auto_exposure),
+        )
+    }
+
+    fn apply_internal(
+        &self,
+        viewport: Viewport,
+        color_texture: &impl Texture,
+        filter: UpscaleFilter,
+        auto_exposure: Option<&AutoExposure>,
+    ) -> ThreeDResult<()> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        };
+
+        self.image_effect.use_texture("colorMap", color_texture)?;
+        self.image_effect.use_uniform(
+            "toneMappingOperator",
+            match self.operator {
+                ToneMappingOperator::Linear => 0,
+                ToneMappingOperator::Reinhard => 1,
+                ToneMappingOperator::AcesFilmic => 2,
+            },
+        )?;
+        if let Some(auto_exposure) = auto_exposure {
+            self.image_effect.use_uniform(
+                "exposure",
+                self.exposure * 2.0f32.powf(auto_exposure.compensation),
+            )?;
+            self.image_effect.use_uniform("useExposureMap", 1)?;
+            self.image_effect
+                .use_texture("exposureMap", auto_exposure.exposure_texture())?;
+        } else {
+            self.image_effect.use_uniform("exposure", self.exposure)?;
+            self.image_effect.use_uniform("useExposureMap", 0)?;
+        }
+        self.image_effect
+            .use_uniform("gamma", self.gamma.max(0.001))?;
+        self.image_effect
+            .use_uniform("encodeSrgb", if self.target_is_srgb { 0 } else { 1 })?;
+        self.image_effect.use_uniform(
+            "sharpness",
+            match filter {
+                UpscaleFilter::Linear => 0.0,
+                UpscaleFilter::Sharpen(sharpness) => sharpness.max(0.0),
+            },
+        )?;
+        self.image_effect.use_uniform(
+            "texelSize",
+            vec2(
+                1.0 / color_texture.width() as f32,
+                1.0 / color_texture.height() as f32,
+            ),
+        )?;
+        if let Some(ref color_grading) = self.color_grading {
+            self.image_effect.use_uniform("useColorGrading", 1)?;
+            self.image_effect
+                .use_texture("colorGradingMap", color_grading)?;
+            self.image_effect
+                .use_uniform("colorGradingSize", color_grading.height() as f32)?;
+        } else {
+            self.image_effect.use_uniform("useColorGrading", 0)?;
+        }
+
+        self.image_effect.apply(render_states, viewport)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A volumetric light effect which simulates light scattered by dust or fog in the air, making the cones of spot lights and light shafts (god rays) visible.
+/// For each light, a ray from the camera through each pixel is marched until it hits the geometry and the light scattered towards the camera
+/// at each step is accumulated using the Henyey-Greenstein phase function.
+/// If a spot or directional light has a shadow map, it is sampled at each step so the shadows cast into the air are visible.
+/// The rays are marched in a lower resolution, see [VolumetricLightEffect::resolution_scale], and upsampled with a depth-aware filter.
+///
+/// The effect is applied in two steps, first [VolumetricLightEffect::scattering_pass] computes the scattered light which must be done before
+/// writing to the final render target, then [VolumetricLightEffect::apply] adds the scattered light to the image in a render target render function,
+/// for example in the callback function of [Screen::write].
+///
+pub struct VolumetricLightEffect {
+    /// The density of the particles in the air which scatter the light. A higher density gives brighter light shafts which fade faster with the distance.
+    pub density: f32,
+    ///
+    /// The anisotropy of the scattering in the range `]-1, 1[`. Positive values scatter most of the light forward,
+    /// so the light shafts are brightest when looking towards the light, negative values scatter the light backwards and zero scatters it equally in all directions.
+    ///
+    pub anisotropy: f32,
+    /// The number of steps along each ray. More steps give more accurate shadows in the air but are more expensive.
+    pub steps: u32,
+    /// The maximum distance from the camera along each ray that scatters light, which also limits the length of the steps when looking at the sky.
+    pub max_distance: f32,
+    /// The resolution of the rays relative to the viewport of the camera, for example 0.5 to march the rays in half the resolution.
+    pub resolution_scale: f32,
+    /// The scattered light is multiplied by this factor before it is added to the image.
+    pub intensity: f32,
+    spot_effect: ImageEffect,
+    spot_shadow_effect: ImageEffect,
+    point_effect: ImageEffect,
+    directional_effect: ImageEffect,
+    directional_shadow_effect: ImageEffect,
+    composite_effect: ImageEffect,
+    scattering_target: ResizableTarget<f16>,
+}
+
+impl VolumetricLightEffect {
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        let new_effect = |defines: &str| {
+            ImageEffect::new(
+                context,
+                &format!(
+                    "{}{}{}",
+                    defines,
+                    include_str!("../../core/shared.frag"),
+                    include_str!("shaders/volumetric_light.frag")
+                ),
+            )
+        };
+        Ok(Self {
+            density: 0.05,
+            anisotropy: 0.5,
+            steps: 32,
+            max_distance: 100.0,
+            resolution_scale: 0.5,
+            intensity: 1.0,
+            spot_effect: new_effect("#define SPOT_LIGHT\n")?,
+            spot_shadow_effect: new_effect("#define SPOT_LIGHT\n#define USE_SHADOW_MAP\n")?,
+            point_effect: new_effect("#define POINT_LIGHT\n")?,
+            directional_effect: new_effect("#define DIRECTIONAL_LIGHT\n")?,
+            directional_shadow_effect: new_effect(
+                "#define DIRECTIONAL_LIGHT\n#define USE_SHADOW_MAP\n",
+            )?,
+            composite_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "{}{}",
+                    include_str!("../../core/shared.frag"),
+                    include_str!("shaders/volumetric_light_composite.frag")
+                ),
+            )?,
+            scattering_target: ResizableTarget::new_color(context, |context, width, height| {
+                Texture2D::new_empty(
+                    context,
+                    width,
+                    height,
+                    Interpolation::Nearest,
+                    Interpolation::Nearest,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                    Format::RGBA,
+                )
+            }),
+        })
+    }
+
+    ///
+    /// Computes the light scattered towards the camera from the enabled spot, point and directional lights in the given lights,
+    /// where the rays stop at the geometry in the given depth texture which must have the same size as the viewport of the camera.
+    /// The low resolution buffer used for the rays is recreated when the size of the viewport or the [VolumetricLightEffect::resolution_scale] changes.
+    /// This function must not be called in a render target render function and needs to be followed by a call to [VolumetricLightEffect::apply].
+    ///
+    pub fn scattering_pass(
+        &mut self,
+        camera: &Camera,
+        depth_texture: &DepthTargetTexture2D,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        // The scattered light from each light is added, while the alpha channel contains the distance to the geometry
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            blend: Blend::Enabled {
+                source_rgb_multiplier: BlendMultiplierType::One,
+                source_alpha_multiplier: BlendMultiplierType::One,
+                destination_rgb_multiplier: BlendMultiplierType::One,
+                destination_alpha_multiplier: BlendMultiplierType::Zero,
+                rgb_equation: BlendEquationType::Add,
+                alpha_equation: BlendEquationType::Add,
+            },
+            ..Default::default()
+        };
+        let (width, height) = (camera.viewport().width, camera.viewport().height);
+        self.scattering_target.scale_factor = self.resolution_scale.max(0.01).min(1.0);
+        self.scattering_target.resize(width, height)?;
+        let viewport = self.scattering_target.viewport();
+
+        let effects = [
+            &self.spot_effect,
+            &self.spot_shadow_effect,
+            &self.point_effect,
+            &self.directional_effect,
+            &self.directional_shadow_effect,
+        ];
+        for effect in effects.iter() {
+            effect.use_texture("depthMap", depth_texture)?;
+            effect.use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
+            effect.use_uniform("eyePosition", camera.position())?;
+            effect.use_uniform("maxDistance", self.max_distance.max(0.0))?;
+            effect.use_uniform("steps", self.steps.max(1) as i32)?;
+            effect.use_uniform("density", self.density.max(0.0))?;
+            effect.use_uniform("anisotropy", self.anisotropy.max(-0.99).min(0.99))?;
+        }
+
+        let spot_effect = &self.spot_effect;
+        let spot_shadow_effect = &self.spot_shadow_effect;
+        let point_effect = &self.point_effect;
+        let directional_effect = &self.directional_effect;
+        let directional_shadow_effect = &self.directional_shadow_effect;
+        self.scattering_target.get(width, height)?.write(
+            ClearState::color(0.0, 0.0, 0.0, 0.0),
+            || {
+                for light in lights.spot.iter().filter(|l| l.is_enabled()) {
+                    let shadow_map = light.shadow_map().filter(|_| light.is_shadow_enabled());
+                    let effect = if let Some(shadow_map) = shadow_map {
+                        shadow_map.set_depth_comparison(Some(Interpolation::Linear));
+                        spot_shadow_effect.use_texture("shadowMap", shadow_map)?;
+                        spot_shadow_effect.use_uniform("shadowMatrix", light.shadow_matrix())?;
+                        spot_shadow_effect.use_uniform("shadowRect", light.shadow_rect())?;
+                        spot_shadow_effect
+                    } else {
+                        spot_effect
+                    };
+                    let (constant, linear, exponential) = light.attenuation();
+                    effect
+                        .use_uniform("lightColor", light.intensity() * light.color().to_vec3())?;
+                    effect.use_uniform("lightPosition", light.position())?;
+                    effect.use_uniform("attenuation", vec3(constant, linear, exponential))?;
+                    effect.use_uniform("lightDirection", light.direction())?;
+                    effect.use_uniform("cutoff", light.cutoff().0)?;
+                    effect.apply(render_states, viewport)?;
+                }
+                for light in lights.point.iter().filter(|l| l.is_enabled()) {
+                    let (constant, linear, exponential) = light.attenuation();
+                    point_effect
+                        .use_uniform("lightColor", light.intensity() * light.color().to_vec3())?;
+                    point_effect.use_uniform("lightPosition", light.position())?;
+                    point_effect.use_uniform("attenuation", vec3(constant, linear, exponential))?;
+                    point_effect.apply(render_states, viewport)?;
+                }
+                for light in lights.directional.iter().filter(|l| l.is_enabled()) {
+                    let shadow_map = light.shadow_map().filter(|_| light.is_shadow_enabled());
+                    let effect = if let Some(shadow_map) = shadow_map {
+                        shadow_map.set_depth_comparison(Some(Interpolation::Linear));
+                        directional_shadow_effect.use_texture("shadowMap", shadow_map)?;
+                        directional_shadow_effect
+                            .use_uniform("shadowMatrix", light.shadow_matrix())?;
+                        directional_shadow_effect
+                            .use_uniform("shadowRect", vec4(0.0, 0.0, 1.0, 1.0))?;
+                        directional_shadow_effect
+                    } else {
+                        directional_effect
+                    };
+                    effect
+                        .use_uniform("lightColor", light.intensity() * light.color().to_vec3())?;
+                    effect.use_uniform("lightDirection", light.direction())?;
+                    effect.apply(render_states, viewport)?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    ///
+    /// Upsamples the scattered light computed in the last [VolumetricLightEffect::scattering_pass] using the given depth texture
+    /// and adds it to the viewport of the camera in the current render target.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn apply(&self, camera: &Camera, depth_texture: &DepthTargetTexture2D) -> ThreeDResult<()> {
+        if let Some(scattering_texture) = self.scattering_target.color_texture() {
+            let render_states = RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTest::Always,
+                cull: Cull::Back,
+                blend: Blend::ADD,
+                ..Default::default()
+            };
+            let effect = &self.composite_effect;
+            effect.use_texture("depthMap", depth_texture)?;
+            effect.use_texture("scatteringMap", scattering_texture)?;
+            effect.use_uniform("viewProjectionInverse", camera.view_projection_inverse())?;
+            effect.use_uniform("eyePosition", camera.position())?;
+            effect.use_uniform(
+                "scatteringSize",
+                vec2(
+                    scattering_texture.width() as f32,
+                    scattering_texture.height() as f32,
+                ),
+            )?;
+            effect.use_uniform("intensity", self.intensity.max(0.0))?;
+            effect.apply(render_states, camera.viewport())?;
+        }
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ use crate::renderer::*;
 ///
 pub struct ForwardPipeline {
     context: Context,
+    render_order: std::cell::RefCell<Vec<RenderOrderKey>>,
 }
 
 impl ForwardPipeline {
@@ -16,11 +17,12 @@ impl ForwardPipeline {
     pub fn new(context: &Context) -> ThreeDResult<Self> {
         Ok(Self {
             context: context.clone(),
+            render_order: std::cell::RefCell::new(Vec::new()),
         })
     }
 
     ///
-    /// Render the objects. Also avoids rendering objects outside the camera frustum and render the objects in the order given by [cmp_render_order].
+    /// Render the objects. Also avoids rendering objects outside the camera frustum and render the objects in the order given by [RenderPassOptions::default].
     /// Must be called in a render target render function, for example in the callback function of [Screen::write].
     ///
     pub fn render_pass(
@@ -29,7 +31,27 @@ impl ForwardPipeline {
         objects: &[impl Object],
         lights: &Lights,
     ) -> ThreeDResult<()> {
-        render_pass(camera, objects, lights)
+        self.render_pass_with_options(camera, objects, lights, RenderPassOptions::default())
+    }
+
+    ///
+    /// Same as [ForwardPipeline::render_pass], except that the order in which the objects are rendered is specified by the given options.
+    /// The memory used for sorting the objects is kept between calls, so no memory is allocated unless the number of objects increases.
+    ///
+    pub fn render_pass_with_options(
+        &self,
+        camera: &Camera,
+        objects: &[impl Object],
+        lights: &Lights,
+        options: RenderPassOptions,
+    ) -> ThreeDResult<()> {
+        render_sorted(
+            camera,
+            objects,
+            lights,
+            options,
+            &mut self.render_order.borrow_mut(),
+        )
     }
 
     pub fn depth_pass(&self, camera: &Camera, objects: &[impl Object]) -> ThreeDResult<()> {
@@ -40,10 +62,11 @@ impl ForwardPipeline {
             },
             ..Default::default()
         };
-        for object in objects
-            .iter()
-            .filter(|o| !o.is_transparent() && camera.in_frustum(&o.aabb()))
-        {
+        for object in objects.iter().filter(|o| {
+            !o.is_transparent()
+                && camera.sphere_in_frustum(&o.bounding_sphere())
+                && camera.in_frustum(&o.aabb())
+        }) {
             object.render_with_material(&depth_material, camera, &Lights::default())?;
         }
         Ok(())
@@ -65,4 +88,53 @@ impl ForwardPipeline {
         depth_texture.write(Some(1.0), || self.depth_pass(&camera, objects))?;
         Ok(depth_texture)
     }
+
+    ///
+    /// Renders the depth and the normals of the opaque objects into a depth texture and a color texture,
+    /// where the normals are encoded as `0.5 + 0.5 * normal` like the output of the [NormalMaterial].
+    /// Can for example be used as input to the [EdgeOutlineEffect].
+    ///
+    pub fn depth_and_normal_pass_textures(
+        &self,
+        camera: &Camera,
+        objects: &[impl Object],
+    ) -> ThreeDResult<(DepthTargetTexture2D, Texture2D<u8>)> {
+        let mut depth_texture = DepthTargetTexture2D::new(
+            &self.context,
+            camera.viewport().width,
+            camera.viewport().height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            DepthFormat::Depth32F,
+        )?;
+        let mut normal_texture = Texture2D::<u8>::new_empty(
+            &self.context,
+            camera.viewport().width,
+            camera.viewport().height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        RenderTarget::new(&self.context, &mut normal_texture, &mut depth_texture)?.write(
+            ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0),
+            || {
+                for object in objects.iter().filter(|o| {
+                    !o.is_transparent()
+                        && camera.sphere_in_frustum(&o.bounding_sphere())
+                        && camera.in_frustum(&o.aabb())
+                }) {
+                    object.render_with_material(
+                        &NormalMaterial::default(),
+                        camera,
+                        &Lights::default(),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        Ok((depth_texture, normal_texture))
+    }
 }
@@ -0,0 +1,206 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Render pipeline which renders objects in linear high dynamic range into an RGBA16F buffer in the [HdrPipeline::render_pass]
+/// and then maps the result to the displayable range and converts it to sRGB in the [HdrPipeline::tone_mapping_pass].
+/// Compared to the [ForwardPipeline], bright light is not clamped or tone mapped in each material,
+/// which means that the exposure, tone mapping operator and color grading can be adjusted on the final image
+/// using the [HdrPipeline::tone_mapping] effect.
+///
+pub struct HdrPipeline {
+    context: Context,
+    ///
+    /// The effect applied in the [HdrPipeline::tone_mapping_pass].
+    ///
+    pub tone_mapping: ToneMappingEffect,
+    ///
+    /// The automatic exposure which is adapted in the [HdrPipeline::auto_exposure_pass] and applied in the [HdrPipeline::tone_mapping_pass]
+    /// on top of the exposure of the [HdrPipeline::tone_mapping] effect, or `None` to only use the fixed exposure. Defaults to `None`.
+    ///
+    pub auto_exposure: Option<AutoExposure>,
+    ///
+    /// The clear state used when clearing the high dynamic range buffer before each [HdrPipeline::render_pass].
+    ///
+    pub clear_state: ClearState,
+    ///
+    /// The resolution of the high dynamic range buffer relative to the viewport of the camera, for example 0.5 to render at half the resolution.
+    /// The buffer is scaled to the viewport given to the [HdrPipeline::tone_mapping_pass].
+    ///
+    pub scale_factor: f32,
+    camera: Camera,
+    target: ResizableTarget<f16>,
+}
+
+impl HdrPipeline {
+    ///
+    /// Constructor.
+    ///
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            context: context.clone(),
+            tone_mapping: ToneMappingEffect::new(context)?,
+            auto_exposure: None,
+            clear_state: ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+            camera: Camera::new_perspective(
+                context,
+                Viewport::new_at_origo(1, 1),
+                vec3(0.0, 0.0, 1.0),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                degrees(75.0),
+                0.01,
+                10.0,
+            )?,
+            scale_factor: 1.0,
+            target: ResizableTarget::new(
+                context,
+                |context, width, height| {
+                    Texture2D::new_empty(
+                        context,
+                        width,
+                        height,
+                        Interpolation::Linear,
+                        Interpolation::Linear,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                },
+                |context, width, height| {
+                    DepthTargetTexture2D::new(
+                        context,
+                        width,
+                        height,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                },
+            ),
+        })
+    }
+
+    ///
+    /// Render the given objects in linear high dynamic range to a buffer.
+    /// This function must not be called in a render target render function and needs to be followed
+    /// by a call to [HdrPipeline::tone_mapping_pass].
+    ///
+    pub fn render_pass(
+        &mut self,
+        camera: &Camera,
+        objects: &[impl Object],
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.write(camera, |camera| render_pass(camera, objects, lights))
+    }
+
+    ///
+    /// Renders whatever rendered in the `render` closure in linear high dynamic range to a buffer,
+    /// for example a [Skybox] which cannot be rendered using [HdrPipeline::render_pass].
+    /// The closure is given a copy of the camera with the viewport placed at the origin of the buffer which must be used for rendering.
+    /// This function must not be called in a render target render function and needs to be followed
+    /// by a call to [HdrPipeline::tone_mapping_pass].
+    ///
+    pub fn write(
+        &mut self,
+        camera: &Camera,
+        render: impl FnOnce(&Camera) -> ThreeDResult<()>,
+    ) -> ThreeDResult<()> {
+        // The buffer is recreated when the size of the viewport or the scale factor changes
+        let (width, height) = (camera.viewport().width, camera.viewport().height);
+        self.target.scale_factor = self.scale_factor;
+        self.target.resize(width, height)?;
+        let viewport = self.target.viewport();
+        match camera.projection_type() {
+            ProjectionType::Perspective { field_of_view_y } => {
+                self.camera.set_perspective_projection(
+                    *field_of_view_y,
+                    camera.z_near(),
+                    camera.z_far(),
+                )?;
+            }
+            ProjectionType::Orthographic { height, .. } => {
+                self.camera.set_orthographic_projection(
+                    *height,
+                    camera.z_near(),
+                    camera.z_far(),
+                )?;
+            }
+        };
+        self.camera.set_viewport(viewport)?;
+        self.camera
+            .set_view(*camera.position(), *camera.target(), *camera.up())?;
+        self.camera.set_lens_shift(camera.lens_shift())?;
+        // The jitter is in pixels, so it is scaled to the size of the buffer
+        self.camera
+            .set_projection_jitter(camera.projection_jitter().map(|jitter| {
+                vec2(
+                    jitter.x * viewport.width as f32 / width as f32,
+                    jitter.y * viewport.height as f32 / height as f32,
+                )
+            }))?;
+
+        // The materials skip tone mapping and sRGB conversion while rendering into the high dynamic range buffer
+        self.context.set_hdr_output(true);
+        let camera = &self.camera;
+        let clear_state = self.clear_state;
+        let result = self
+            .target
+            .get(width, height)
+            .and_then(|render_target| render_target.write(clear_state, || render(camera)));
+        self.context.set_hdr_output(false);
+        result
+    }
+
+    ///
+    /// Measures the brightness of the result of the last [HdrPipeline::render_pass] and adapts the [HdrPipeline::auto_exposure] to it,
+    /// where the given elapsed time in milliseconds, usually [FrameInput::elapsed_time](crate::FrameInput::elapsed_time), determines how far the exposure is adapted.
+    /// Does nothing if the auto exposure is `None`.
+    /// This function must not be called in a render target render function and should be called before the [HdrPipeline::tone_mapping_pass].
+    ///
+    pub fn auto_exposure_pass(&mut self, elapsed_time: f64) -> ThreeDResult<()> {
+        if let (Some(auto_exposure), Some(color_texture)) =
+            (self.auto_exposure.as_mut(), self.target.color_texture())
+        {
+            auto_exposure.update(color_texture, elapsed_time)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Applies the [HdrPipeline::tone_mapping] effect, including the [HdrPipeline::auto_exposure] if any, to the result of the last [HdrPipeline::render_pass]
+    /// and writes it to the given viewport of the current render target.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn tone_mapping_pass(&self, viewport: Viewport) -> ThreeDResult<()> {
+        if let Some(color_texture) = self.target.color_texture() {
+            if let Some(ref auto_exposure) = self.auto_exposure {
+                self.tone_mapping.apply_with_auto_exposure(
+                    viewport,
+                    color_texture,
+                    auto_exposure,
+                )?;
+            } else {
+                self.tone_mapping.apply(viewport, color_texture)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the high dynamic range color texture written in the last [HdrPipeline::render_pass].
+    ///
+    pub fn color_texture(&self) -> Option<&Texture2D<f16>> {
+        self.target.color_texture()
+    }
+
+    ///
+    /// Returns the depth texture written in the last [HdrPipeline::render_pass].
+    ///
+    pub fn depth_texture(&self) -> Option<&DepthTargetTexture2D> {
+        self.target.depth_texture()
+    }
+}
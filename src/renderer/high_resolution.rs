@@ -0,0 +1,282 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Options for rendering an image at a resolution independent of the viewport using [render_high_res_with_options].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HighResOptions {
+    ///
+    /// The number of samples in each direction within a pixel, so for example 2 renders the image at twice the width and height
+    /// and averages 2x2 samples into each pixel. The samples are averaged in linear color space.
+    ///
+    pub samples_per_pixel: u32,
+    ///
+    /// The number of pixels each tile is extended by on each side when rendering, which are thrown away again when stitching the tiles.
+    /// Effects which use neighbouring pixels, for example bloom or anti-aliasing, need an overlap at least as large as the distance they sample,
+    /// otherwise the edges of the tiles are visible in the image. The overlap is not needed without effects.
+    ///
+    pub tile_overlap: u32,
+    ///
+    /// The maximum width and height in samples of the offscreen targets which the tiles are rendered into.
+    /// The size is also limited by [Capabilities::max_texture_size].
+    ///
+    pub max_tile_size: u32,
+    ///
+    /// The clear state used when clearing each tile before rendering the objects.
+    ///
+    pub clear_state: ClearState,
+}
+
+impl Default for HighResOptions {
+    fn default() -> Self {
+        Self {
+            samples_per_pixel: 1,
+            tile_overlap: 0,
+            max_tile_size: 2048,
+            clear_state: ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+        }
+    }
+}
+
+///
+/// Renders the objects with the same framing as the given camera into an image of the given size, which can be much larger than the screen and the maximum texture size,
+/// for example for printing. Each pixel is the average of `samples_per_pixel` x `samples_per_pixel` samples, see [HighResOptions::samples_per_pixel].
+/// The pixels are ordered row by row starting with the bottom row, like [Texture2D::read], so the image can be saved directly using [Saver::save_pixels](crate::Saver::save_pixels).
+/// Must not be called in a render target render function.
+///
+pub fn render_high_res(
+    context: &Context,
+    camera: &Camera,
+    objects: &[impl Object],
+    lights: &Lights,
+    width: u32,
+    height: u32,
+    samples_per_pixel: u32,
+) -> ThreeDResult<CPUTexture<u8>> {
+    render_high_res_with_options(
+        context,
+        camera,
+        objects,
+        lights,
+        width,
+        height,
+        HighResOptions {
+            samples_per_pixel,
+            ..Default::default()
+        },
+        None,
+    )
+}
+
+///
+/// Same as [render_high_res], except that the rendering is specified by the given options
+/// and the given effects, if any, are applied to each tile before the samples are averaged.
+///
+/// The image is rendered tile by tile into offscreen targets no larger than [HighResOptions::max_tile_size],
+/// where each tile is rendered with a copy of the camera which has the vertical field of view (or height) of the camera,
+/// narrowed and shifted such that it only sees the part of the image covered by the tile, see [Camera::set_lens_shift].
+/// The tiles are read back and stitched into the image on the CPU, so only the image itself has to fit in memory.
+/// Since the tiles are rendered with the same projection as the whole image, the only seams come from effects using neighbouring pixels,
+/// which are avoided by a large enough [HighResOptions::tile_overlap]. Effects depending on the size of the image in pixels, for example a blur radius in pixels,
+/// give a different result than on the screen.
+/// Must not be called in a render target render function.
+///
+pub fn render_high_res_with_options(
+    context: &Context,
+    camera: &Camera,
+    objects: &[impl Object],
+    lights: &Lights,
+    width: u32,
+    height: u32,
+    options: HighResOptions,
+    mut effects: Option<&mut EffectStack>,
+) -> ThreeDResult<CPUTexture<u8>> {
+    let samples = options.samples_per_pixel.max(1);
+    let overlap = options.tile_overlap;
+    let target_size = options
+        .max_tile_size
+        .min(context.capabilities().max_texture_size);
+    let tile_size = (target_size / samples).saturating_sub(2 * overlap);
+    if tile_size == 0 {
+        Err(RendererError::HighResTileTooSmall(
+            overlap,
+            samples,
+            target_size,
+        ))?;
+    }
+    let (tile_width, tile_height) = (tile_size.min(width), tile_size.min(height));
+    let render_width = (tile_width + 2 * overlap) * samples;
+    let render_height = (tile_height + 2 * overlap) * samples;
+
+    let mut scene_target = ResizableTarget::<f16>::new(
+        context,
+        |context, width, height| {
+            Texture2D::new_empty(
+                context,
+                width,
+                height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )
+        },
+        |context, width, height| {
+            DepthTargetTexture2D::new(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )
+        },
+    );
+    let mut effect_texture = if effects.is_some() {
+        Some(Texture2D::<u8>::new_empty(
+            context,
+            render_width,
+            render_height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?)
+    } else {
+        None
+    };
+    let mut resolve_texture = Texture2D::<u8>::new_empty(
+        context,
+        tile_width,
+        tile_height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )?;
+    let resolve_effect = ImageEffect::new(
+        context,
+        &format!(
+            "{}{}",
+            include_str!("../core/shared.frag"),
+            "
+            uniform sampler2D colorMap;
+            uniform int samples;
+            uniform int overlap;
+            layout (location = 0) out vec4 color;
+            void main()
+            {
+                ivec2 origin = (ivec2(gl_FragCoord.xy) + overlap) * samples;
+                vec4 sum = vec4(0.0);
+                for (int y = 0; y < samples; y++) {
+                    for (int x = 0; x < samples; x++) {
+                        vec4 c = texelFetch(colorMap, origin + ivec2(x, y), 0);
+                        sum += vec4(rgb_from_srgb(c.rgb), c.a);
+                    }
+                }
+                sum /= float(samples * samples);
+                color = vec4(srgb_from_rgb(sum.rgb), sum.a);
+            }"
+        ),
+    )?;
+    let render_states = RenderStates {
+        write_mask: WriteMask::COLOR,
+        depth_test: DepthTest::Always,
+        cull: Cull::Back,
+        ..Default::default()
+    };
+
+    let mut tile_camera = Camera::new_perspective(
+        context,
+        Viewport::new_at_origo(render_width, render_height),
+        *camera.position(),
+        *camera.target(),
+        *camera.up(),
+        degrees(45.0),
+        camera.z_near(),
+        camera.z_far(),
+    )?;
+    let mut data = vec![0u8; width as usize * height as usize * 4];
+    for tile_y in (0..height).step_by(tile_height as usize) {
+        for tile_x in (0..width).step_by(tile_width as usize) {
+            // The center of the rendered area, including the overlap, relative to the center of the image in pixels
+            let center = vec2(
+                tile_x as f32 + 0.5 * tile_width as f32 - 0.5 * width as f32,
+                tile_y as f32 + 0.5 * tile_height as f32 - 0.5 * height as f32,
+            );
+            let rendered_height = (tile_height + 2 * overlap) as f32;
+            let fraction = rendered_height / height as f32;
+            match camera.projection_type() {
+                ProjectionType::Perspective { field_of_view_y } => {
+                    tile_camera.set_perspective_projection(
+                        radians(2.0 * ((0.5 * field_of_view_y.0).tan() * fraction).atan()),
+                        camera.z_near(),
+                        camera.z_far(),
+                    )?;
+                }
+                ProjectionType::Orthographic { height } => {
+                    tile_camera.set_orthographic_projection(
+                        height * fraction,
+                        camera.z_near(),
+                        camera.z_far(),
+                    )?;
+                }
+            }
+            // The lens shift is in units of the height of the rendered area, which includes the lens shift of the given camera
+            tile_camera.set_lens_shift(
+                camera.lens_shift() * (height as f32 / rendered_height) + center / rendered_height,
+            )?;
+
+            scene_target
+                .get(render_width, render_height)?
+                .write(options.clear_state, || {
+                    render_pass(&tile_camera, objects, lights)
+                })?;
+            let scene_texture = scene_target.color_texture().unwrap();
+            let color_texture: &dyn Texture = if let (Some(effects), Some(effect_texture)) =
+                (effects.as_mut(), effect_texture.as_mut())
+            {
+                let inputs = EffectInputs {
+                    depth_texture: scene_target.depth_texture(),
+                    ..EffectInputs::new(&tile_camera, scene_texture)
+                };
+                effects.apply(&inputs, EffectOutput::texture(effect_texture))?;
+                effect_texture
+            } else {
+                scene_texture
+            };
+
+            resolve_effect.use_texture("colorMap", &color_texture)?;
+            resolve_effect.use_uniform_int("samples", &(samples as i32))?;
+            resolve_effect.use_uniform_int("overlap", &(overlap as i32))?;
+            let viewport = Viewport::new_at_origo(tile_width, tile_height);
+            resolve_texture.write(ClearState::none(), || {
+                resolve_effect.apply(render_states, viewport)
+            })?;
+
+            // The tiles at the right and top border of the image are cropped
+            let pixels = resolve_texture.read_internal(viewport)?;
+            let copy_width = tile_width.min(width - tile_x) as usize * 4;
+            for row in 0..tile_height.min(height - tile_y) as usize {
+                let source = row * tile_width as usize * 4;
+                let destination = ((tile_y as usize + row) * width as usize + tile_x as usize) * 4;
+                data[destination..destination + copy_width]
+                    .copy_from_slice(&pixels[source..source + copy_width]);
+            }
+        }
+    }
+    Ok(CPUTexture {
+        data,
+        width,
+        height,
+        format: Format::RGBA,
+        ..Default::default()
+    })
+}
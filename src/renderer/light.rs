@@ -1,7 +1,8 @@
 //!
 //! A collection of light types.
-//! Currently implemented light types are ambient light, directional light, spot light and point light.
-//! Directional and spot lights can cast shadows.
+//! Currently implemented light types are ambient light, directional light, spot light, point light and probe grid.
+//! Directional and spot lights can cast shadows and the shadow maps of many spot lights can be packed into a [ShadowAtlas].
+//! Directional and point lights can also cast soft shadows using a [SignedDistanceField] of the static geometry, see [SdfShadows].
 //!
 
 mod directional_light;
@@ -24,6 +25,26 @@ mod environment;
 #[doc(inline)]
 pub use environment::*;
 
+mod probe_grid;
+#[doc(inline)]
+pub use probe_grid::*;
+
+mod shadow_atlas;
+#[doc(inline)]
+pub use shadow_atlas::*;
+
+mod light_clustering;
+#[doc(inline)]
+pub use light_clustering::*;
+
+mod lightmap;
+#[doc(inline)]
+pub use lightmap::*;
+
+mod signed_distance_field;
+#[doc(inline)]
+pub use signed_distance_field::*;
+
 use crate::core::*;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -61,23 +82,293 @@ impl LightingModel {
     }
 }
 
+///
+/// The filter kernel used when sampling a shadow map with percentage-closer filtering, see [ShadowQuality::Pcf].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PcfKernel {
+    /// A 3x3 grid of samples.
+    Box3x3,
+    /// A 5x5 grid of samples.
+    Box5x5,
+    /// 16 samples on a Poisson disk which is rotated randomly for each fragment, which trades banding for noise.
+    PoissonDisk,
+}
+
+///
+/// Specifies how the shadow map of a [DirectionalLight] or [SpotLight] is sampled.
+/// Only the shader code for the chosen quality is generated.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ShadowQuality {
+    /// A single hardware depth comparison which results in hard and aliased shadow edges.
+    Hard,
+    /// Percentage-closer filtering which averages hardware depth comparisons using the given kernel and results in soft shadow edges of constant width.
+    Pcf {
+        /// The filter kernel.
+        kernel: PcfKernel,
+    },
+    ///
+    /// Percentage-closer soft shadows which estimates the size of the penumbra from the distance between the shadow receiver and the occluders,
+    /// so shadows get softer with the distance from the occluder.
+    ///
+    Pcss {
+        /// The size of the light relative to the size of the shadow map, larger values result in softer shadows.
+        light_size: f32,
+    },
+}
+
+impl Default for ShadowQuality {
+    fn default() -> Self {
+        Self::Pcf {
+            kernel: PcfKernel::PoissonDisk,
+        }
+    }
+}
+
+impl ShadowQuality {
+    ///
+    /// Returns the shader source for sampling the shadow map of the light with the given index,
+    /// ie. the `shadowMap{i}` sampler, the bias uniforms and the `calculate_shadow{i}` function.
+    ///
+    pub(crate) fn shader_source(&self, i: u32) -> String {
+        let (sampler, body) = match self {
+            ShadowQuality::Hard => (
+                "sampler2DShadow",
+                format!(
+                    "return texture(shadowMap{}, vec3(clamp(uv, uv_min, uv_max), depth));",
+                    i
+                ),
+            ),
+            ShadowQuality::Pcf { kernel } => match kernel {
+                PcfKernel::Box3x3 | PcfKernel::Box5x5 => {
+                    let radius = if *kernel == PcfKernel::Box3x3 { 1 } else { 2 };
+                    (
+                        "sampler2DShadow",
+                        format!(
+                            "
+                    float visibility = 0.0;
+                    for(int x = -{r}; x <= {r}; x++) {{
+                        for(int y = -{r}; y <= {r}; y++) {{
+                            visibility += texture(shadowMap{i}, vec3(clamp(uv + vec2(x, y) * texel_size, uv_min, uv_max), depth));
+                        }}
+                    }}
+                    return visibility / float({n});",
+                            r = radius,
+                            i = i,
+                            n = (2 * radius + 1) * (2 * radius + 1)
+                        ),
+                    )
+                }
+                PcfKernel::PoissonDisk => (
+                    "sampler2DShadow",
+                    format!(
+                        "
+                    mat2 rotation = poisson_disk_rotation(position);
+                    float visibility = 0.0;
+                    for(int j = 0; j < 16; j++) {{
+                        vec2 offset = rotation * POISSON_DISK[j] * 2.5 * texel_size;
+                        visibility += texture(shadowMap{}, vec3(clamp(uv + offset, uv_min, uv_max), depth));
+                    }}
+                    return visibility / 16.0;",
+                        i
+                    ),
+                ),
+            },
+            ShadowQuality::Pcss { .. } => (
+                "sampler2D",
+                format!(
+                    "
+                    mat2 rotation = poisson_disk_rotation(position);
+                    float light_size = shadowLightSize{i};
+
+                    // Blocker search
+                    float search_radius = light_size * shadowRect{i}.z * depth;
+                    float blocker_depth = 0.0;
+                    float blocker_count = 0.0;
+                    for(int j = 0; j < 16; j++) {{
+                        float d = texture(shadowMap{i}, clamp(uv + rotation * POISSON_DISK[j] * search_radius, uv_min, uv_max)).x;
+                        if(d < depth) {{
+                            blocker_depth += d;
+                            blocker_count += 1.0;
+                        }}
+                    }}
+                    if(blocker_count < 0.5) {{
+                        return 1.0;
+                    }}
+                    blocker_depth /= blocker_count;
+
+                    // Percentage-closer filtering with a radius depending on the size of the penumbra
+                    float filter_radius = max(light_size * shadowRect{i}.z * (depth - blocker_depth) / max(blocker_depth, 0.0001), texel_size.x);
+                    float visibility = 0.0;
+                    for(int j = 0; j < 16; j++) {{
+                        float d = texture(shadowMap{i}, clamp(uv + rotation * POISSON_DISK[j] * filter_radius, uv_min, uv_max)).x;
+                        visibility += d < depth ? 0.0 : 1.0;
+                    }}
+                    return visibility / 16.0;",
+                    i = i
+                ),
+            ),
+        };
+        format!(
+            "
+                uniform {sampler} shadowMap{i};
+                uniform float shadowBias{i};
+                uniform float shadowNormalBias{i};
+                uniform vec4 shadowRect{i};
+                {light_size}
+                float calculate_shadow{i}(mat4 shadowMVP, vec3 position, vec3 normal)
+                {{
+                    vec4 shadow_coord = shadowMVP * vec4(position + normal * shadowNormalBias{i}, 1.0);
+                    vec2 uv = shadow_coord.xy / shadow_coord.w;
+                    if(uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {{
+                        return 1.0;
+                    }}
+                    float depth = shadow_coord.z / shadow_coord.w - shadowBias{i};
+                    vec2 texel_size = 1.0 / vec2(textureSize(shadowMap{i}, 0));
+
+                    // The shadow map covers the rectangle with the offset xy and the size zw in the texture,
+                    // which is the whole texture unless the shadow map is a tile in a shadow atlas
+                    uv = shadowRect{i}.xy + uv * shadowRect{i}.zw;
+                    vec2 uv_min = shadowRect{i}.xy + 0.5 * texel_size;
+                    vec2 uv_max = shadowRect{i}.xy + shadowRect{i}.zw - 0.5 * texel_size;
+                    {body}
+                }}
+            ",
+            sampler = sampler,
+            i = i,
+            light_size = if let ShadowQuality::Pcss { .. } = self {
+                format!("uniform float shadowLightSize{};", i)
+            } else {
+                String::new()
+            },
+            body = body
+        )
+    }
+
+    ///
+    /// Uses the given shadow map and biases in the shadow sampling code generated by [ShadowQuality::shader_source].
+    /// The rectangle is the offset and size of the shadow map in the texture in texture coordinates, ie. `(0, 0, 1, 1)` unless it is a tile in a [ShadowAtlas].
+    ///
+    pub(crate) fn use_uniforms(
+        &self,
+        program: &Program,
+        i: u32,
+        shadow_map: &DepthTargetTexture2D,
+        rect: Vec4,
+        depth_bias: f32,
+        normal_offset_bias: f32,
+    ) -> ThreeDResult<()> {
+        match self {
+            ShadowQuality::Hard => shadow_map.set_depth_comparison(Some(Interpolation::Nearest)),
+            ShadowQuality::Pcf { .. } => {
+                shadow_map.set_depth_comparison(Some(Interpolation::Linear))
+            }
+            ShadowQuality::Pcss { light_size } => {
+                shadow_map.set_depth_comparison(None);
+                program.use_uniform_float(&format!("shadowLightSize{}", i), light_size)?;
+            }
+        }
+        program.use_texture(&format!("shadowMap{}", i), shadow_map)?;
+        program.use_uniform_vec4(&format!("shadowRect{}", i), &rect)?;
+        program.use_uniform_float(&format!("shadowBias{}", i), &depth_bias)?;
+        program.use_uniform_float(&format!("shadowNormalBias{}", i), &normal_offset_bias)
+    }
+}
+
+///
+/// The number of lights without shadows above which the lights are automatically packed, see [Lights::packed].
+///
+pub const PACKED_LIGHTS_THRESHOLD: usize = 8;
+
 pub struct Lights {
     pub ambient: Option<AmbientLight>,
     pub directional: Vec<DirectionalLight>,
     pub spot: Vec<SpotLight>,
     pub point: Vec<PointLight>,
+    ///
+    /// A grid of light probes which adds ambient light varying with the position, see [ProbeGrid].
+    ///
+    pub probe_grid: Option<ProbeGrid>,
     pub lighting_model: LightingModel,
+    ///
+    /// Whether or not to pack all lights without shadows into a single texture which is looped over in the shader.
+    /// If `None`, the lights are packed when there are more than [PACKED_LIGHTS_THRESHOLD] lights without shadows.
+    ///
+    pub packed: Option<bool>,
+    ///
+    /// Clustered culling of the packed point and spot lights, so each fragment only evaluates the lights close to it, see [LightClustering].
+    /// Only used when the lights are packed, see [Lights::packed].
+    ///
+    pub clustering: Option<LightClustering>,
 }
 
 impl Lights {
+    ///
+    /// Specifies whether or not to pack all lights without shadows into a single texture instead of using a uniform block per light.
+    /// When packed, the shader only depends on the number of shadow casting lights,
+    /// so adding or removing lights without shadows does not require a shader recompilation.
+    ///
+    pub fn packed(mut self, packed: bool) -> Self {
+        self.packed = Some(packed);
+        self
+    }
+
+    ///
+    /// Specifies the clustered culling of the packed point and spot lights, which makes it possible to render hundreds of lights, see [LightClustering].
+    /// Also packs the lights, since only packed lights are clustered.
+    ///
+    pub fn clustered(mut self, clustering: LightClustering) -> Self {
+        self.clustering = Some(clustering);
+        self.packed = Some(true);
+        self
+    }
+
+    ///
+    /// Returns whether or not the lights without shadows are packed into a single texture, see [Lights::packed].
+    ///
+    pub fn is_packed(&self) -> bool {
+        self.packed.unwrap_or_else(|| {
+            self.directional.iter().filter(|l| !l.has_shadows()).count()
+                + self
+                    .spot
+                    .iter()
+                    .filter(|l| l.shadow_map().is_none())
+                    .count()
+                + self.point.iter().filter(|l| !l.has_shadows()).count()
+                > PACKED_LIGHTS_THRESHOLD
+        })
+    }
+
     pub fn fragment_shader_source(&self) -> String {
-        lights_fragment_shader_source(&mut LightsIterator::new(self), self.lighting_model)
+        if self.is_packed() {
+            lights_fragment_shader_source(
+                &mut self.unpacked_lights().into_iter(),
+                self.lighting_model,
+                true,
+                self.clustering.as_ref(),
+            )
+        } else {
+            lights_fragment_shader_source(
+                &mut LightsIterator::new(self),
+                self.lighting_model,
+                false,
+                None,
+            )
+        }
     }
 
     pub fn use_uniforms(&self, program: &Program, camera: &Camera) -> ThreeDResult<()> {
         program.use_uniform_vec3("eyePosition", camera.position())?;
-        for (i, light) in LightsIterator::new(self).enumerate() {
-            light.use_uniforms(program, i as u32)?;
+        if self.is_packed() {
+            for (i, light) in self.unpacked_lights().into_iter().enumerate() {
+                light.use_uniforms(program, i as u32)?;
+            }
+            self.use_packed_lights(program, camera)?;
+        } else {
+            for (i, light) in LightsIterator::new(self).enumerate() {
+                light.use_uniforms(program, i as u32)?;
+            }
         }
         Ok(())
     }
@@ -85,6 +376,86 @@ impl Lights {
     pub fn iter<'a>(&'a self) -> LightsIterator<'a> {
         LightsIterator::new(self)
     }
+
+    ///
+    /// The lights which are not packed, ie. the ambient light and the lights with shadows.
+    ///
+    fn unpacked_lights(&self) -> Vec<&dyn Light> {
+        let mut lights: Vec<&dyn Light> = Vec::new();
+        if let Some(ref light) = self.ambient {
+            lights.push(light);
+        }
+        if let Some(ref light) = self.probe_grid {
+            lights.push(light);
+        }
+        for light in self.directional.iter().filter(|l| l.has_shadows()) {
+            lights.push(light);
+        }
+        for light in self.spot.iter().filter(|l| l.shadow_map().is_some()) {
+            lights.push(light);
+        }
+        for light in self.point.iter().filter(|l| l.has_shadows()) {
+            lights.push(light);
+        }
+        lights
+    }
+
+    fn use_packed_lights(&self, program: &Program, camera: &Camera) -> ThreeDResult<()> {
+        // A disabled light is packed with zero intensity, so the number of packed lights does not change
+        let enabled = |enabled: bool| if enabled { 1.0 } else { 0.0 };
+        let mut data = Vec::new();
+        // The position, color multiplied by intensity and attenuation of the point and spot lights used for clustering
+        let mut clustered_lights = Vec::new();
+        for light in self.directional.iter().filter(|l| !l.has_shadows()) {
+            let color = light.color().to_vec3() * light.intensity() * enabled(light.is_enabled());
+            let direction = light.direction();
+            data.extend_from_slice(&[color.x, color.y, color.z, 0.0]);
+            data.extend_from_slice(&[0.0; 4]);
+            data.extend_from_slice(&[direction.x, direction.y, direction.z, 0.0]);
+            data.extend_from_slice(&[0.0; 4]);
+        }
+        let first_clustered_light = data.len() / 16;
+        for light in self.point.iter().filter(|l| !l.has_shadows()) {
+            let color = light.color().to_vec3() * light.intensity() * enabled(light.is_enabled());
+            let position = light.position();
+            let (constant, linear, exponential) = light.attenuation();
+            clustered_lights.push((
+                position,
+                color.x.max(color.y).max(color.z),
+                light.attenuation(),
+            ));
+            data.extend_from_slice(&[color.x, color.y, color.z, 1.0]);
+            data.extend_from_slice(&[position.x, position.y, position.z, 0.0]);
+            data.extend_from_slice(&[0.0; 4]);
+            data.extend_from_slice(&[constant, linear, exponential, 0.0]);
+        }
+        for light in self.spot.iter().filter(|l| l.shadow_map().is_none()) {
+            let color = light.color().to_vec3() * light.intensity() * enabled(light.is_enabled());
+            let position = light.position();
+            let direction = light.direction();
+            let (constant, linear, exponential) = light.attenuation();
+            clustered_lights.push((
+                position,
+                color.x.max(color.y).max(color.z),
+                light.attenuation(),
+            ));
+            data.extend_from_slice(&[color.x, color.y, color.z, 2.0]);
+            data.extend_from_slice(&[position.x, position.y, position.z, light.cutoff().0]);
+            data.extend_from_slice(&[direction.x, direction.y, direction.z, 0.0]);
+            data.extend_from_slice(&[constant, linear, exponential, 0.0]);
+        }
+        let count = data.len() / 16;
+        // Round the number of rows up to a power of two to avoid reallocating the texture every time a light is added
+        data.resize(count.max(1).next_power_of_two() * 16, 0.0);
+        program
+            .context()
+            .use_data_texture(program, "packedLights", 4, &data)?;
+        program.use_uniform_int("packedLightCount", &(count as i32))?;
+        if let Some(ref clustering) = self.clustering {
+            clustering.use_uniforms(program, camera, &clustered_lights, first_clustered_light)?;
+        }
+        Ok(())
+    }
 }
 
 impl Default for Lights {
@@ -94,7 +465,10 @@ impl Default for Lights {
             directional: Vec::new(),
             spot: Vec::new(),
             point: Vec::new(),
+            probe_grid: None,
             lighting_model: LightingModel::Blinn,
+            packed: None,
+            clustering: None,
         }
     }
 }
@@ -146,6 +520,15 @@ impl<'a> Iterator for LightsIterator<'a> {
                 .get(self.index - count)
                 .map(|l| l as &dyn Light)
         });
+        count += self.lights.point.len();
+
+        let result = result.or_else(|| {
+            self.lights
+                .probe_grid
+                .as_ref()
+                .filter(|_| self.index == count)
+                .map(|l| l as &dyn Light)
+        });
 
         self.index += 1;
         result
@@ -155,6 +538,8 @@ impl<'a> Iterator for LightsIterator<'a> {
 pub(crate) fn lights_fragment_shader_source(
     lights: &mut dyn Iterator<Item = &dyn Light>,
     lighting_model: LightingModel,
+    packed: bool,
+    clustering: Option<&LightClustering>,
 ) -> String {
     let mut shader_source = lighting_model.shader().to_string();
     shader_source.push_str(include_str!("../core/shared.frag"));
@@ -164,13 +549,28 @@ pub(crate) fn lights_fragment_shader_source(
         shader_source.push_str(&light.shader_source(i as u32));
         dir_fun.push_str(&format!("color += calculate_lighting{}(surface_color, position, normal, view_direction, metallic, roughness, occlusion);\n", i))
     }
+    if packed {
+        if let Some(clustering) = clustering {
+            shader_source.push_str(&clustering.fragment_shader_defines());
+        }
+        shader_source.push_str(include_str!("./light/shaders/packed_lights.frag"));
+        dir_fun.push_str("color += calculate_packed_lighting(surface_color, position, normal, view_direction, metallic, roughness, occlusion);\n");
+        if clustering.map(|c| c.heatmap()).unwrap_or(false) {
+            dir_fun.push_str("color = light_cluster_heatmap(position);\n");
+        }
+    }
     shader_source.push_str(&format!(
         "
             uniform vec3 eyePosition;
             vec3 calculate_lighting(vec3 surface_color, vec3 position, vec3 normal, float metallic, float roughness, float occlusion)
             {{
                 vec3 color = vec3(0.0, 0.0, 0.0);
+            #ifdef LIGHTMAP_BAKING
+                // The baked lighting is independent of the view, so the surface is seen from straight above
+                vec3 view_direction = normal;
+            #else
                 vec3 view_direction = normalize(eyePosition - position);
+            #endif
                 {}
                 return color;
             }}
@@ -201,6 +601,32 @@ fn shadow_matrix(camera: &Camera) -> Mat4 {
     bias_matrix * camera.projection() * camera.view()
 }
 
+// The corners of the view frustum of the camera which the given shadow matrix is computed from,
+// where the index of a corner is x + 2y + 4z for the texture coordinates (x, y) and depth z of the corner, which are either 0 or 1
+fn shadow_frustum_corners(shadow_matrix: Mat4) -> [Vec3; 8] {
+    let inverse = shadow_matrix.invert().unwrap_or(Mat4::identity());
+    let mut corners = [vec3(0.0, 0.0, 0.0); 8];
+    for (i, corner) in corners.iter_mut().enumerate() {
+        let p = inverse
+            * vec4(
+                (i & 1) as f32,
+                ((i >> 1) & 1) as f32,
+                ((i >> 2) & 1) as f32,
+                1.0,
+            );
+        *corner = p.truncate() / p.w;
+    }
+    corners
+}
+
+// Reads a matrix which is stored in a uniform buffer in column-major order
+fn mat4_from_slice(m: &[f32]) -> Mat4 {
+    Mat4::new(
+        m[0], m[1], m[2], m[3], m[4], m[5], m[6], m[7], m[8], m[9], m[10], m[11], m[12], m[13],
+        m[14], m[15],
+    )
+}
+
 fn compute_up_direction(direction: Vec3) -> Vec3 {
     if vec3(1.0, 0.0, 0.0).dot(direction).abs() > 0.9 {
         (vec3(0.0, 1.0, 0.0).cross(direction)).normalize()
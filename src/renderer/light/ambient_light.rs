@@ -3,25 +3,71 @@ use crate::renderer::*;
 
 ///
 /// A light which shines equally on all parts of any surface.
+/// If a [ground color](AmbientLight::ground_color) is specified, it is instead a hemisphere light
+/// which shines with the color from above and the ground color from below, blended by the direction of the surface normal.
 ///
 pub struct AmbientLight {
     pub color: Color,
     pub intensity: f32,
     pub environment: Option<Environment>,
+    ///
+    /// The color of the light from below, for example the light bounced off the ground, in which case [AmbientLight::color] is the color of the sky.
+    /// If an [environment](AmbientLight::environment) is also specified, the hemisphere light is added to the light from the environment,
+    /// which is then only scaled by the intensity and not tinted by the sky color.
+    ///
+    pub ground_color: Option<Color>,
+    ///
+    /// Ambient occlusion computed using a signed distance field of the static geometry, see [SdfAmbientOcclusion],
+    /// which is multiplied with the occlusion of the material.
+    ///
+    pub sdf_ao: Option<SdfAmbientOcclusion>,
+    ///
+    /// Whether or not the light contributes to the lighting. A disabled light stays in the shader,
+    /// so toggling the light does not require a shader recompilation.
+    ///
+    pub enabled: bool,
+}
+
+impl AmbientLight {
+    ///
+    /// Constructs a hemisphere light which shines with the sky color from above and the ground color from below,
+    /// blended by the direction of the surface normal. This is a cheap way to make outdoor scenes look less flat.
+    ///
+    pub fn new_hemisphere(intensity: f32, sky_color: Color, ground_color: Color) -> Self {
+        Self {
+            color: sky_color,
+            intensity,
+            environment: None,
+            ground_color: Some(ground_color),
+            sdf_ao: None,
+            enabled: true,
+        }
+    }
 }
 
 impl Light for AmbientLight {
     fn shader_source(&self, i: u32) -> String {
+        let (ao_source, ao_call) = if let Some(ref sdf_ao) = self.sdf_ao {
+            (
+                sdf_ao.shader_source(),
+                "occlusion *= sdf_ao(position, normal);",
+            )
+        } else {
+            (String::new(), "")
+        };
         if self.environment.is_some() {
             format!(
             "
+                {}
                 uniform samplerCube irradianceMap;
                 uniform samplerCube prefilterMap;
                 uniform sampler2D brdfLUT;
-                uniform vec3 ambientColor;
+                uniform vec3 environmentColor;
+                {}
     
                 vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                 {{
+                    {}
                     vec3 N = normal;
                     vec3 V = view_direction;
                     vec3 R = reflect(-V, N); 
@@ -43,29 +89,74 @@ impl Light for AmbientLight {
                     vec2 brdf  = texture(brdfLUT, vec2(NdV, roughness)).rg;
                     vec3 specular = prefilteredColor * (specular_fresnel * brdf.x + brdf.y);
     
-                    return (diffuse + specular) * occlusion * ambientColor;
+                    vec3 result = (diffuse + specular) * occlusion * environmentColor;
+                    {}
+                    return result;
                 }}
             
-            ", i)
+            ", ao_source, if self.ground_color.is_some() {
+                "uniform vec3 ambientColor;
+                uniform vec3 groundColor;"
+            } else {
+                ""
+            }, i, ao_call, if self.ground_color.is_some() {
+                "result += occlusion * mix(groundColor, ambientColor, normal.y * 0.5 + 0.5) * mix(surface_color, vec3(0.0), metallic);"
+            } else {
+                ""
+            })
         } else {
             format!(
                 "
+                    {}
                     uniform vec3 ambientColor;
+                    {}
                     vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
-                        return occlusion * ambientColor * mix(surface_color, vec3(0.0), metallic);
+                        {}
+                        vec3 color = {};
+                        return occlusion * color * mix(surface_color, vec3(0.0), metallic);
                     }}
                 
-                ", i)
+                ", ao_source, if self.ground_color.is_some() {
+                    "uniform vec3 groundColor;"
+                } else {
+                    ""
+                }, i, ao_call, if self.ground_color.is_some() {
+                    "mix(groundColor, ambientColor, normal.y * 0.5 + 0.5)"
+                } else {
+                    "ambientColor"
+                })
         }
     }
     fn use_uniforms(&self, program: &Program, _i: u32) -> ThreeDResult<()> {
+        if let Some(ref sdf_ao) = self.sdf_ao {
+            sdf_ao.use_uniforms(program)?;
+        }
         if let Some(ref environment) = self.environment {
             program.use_texture_cube("irradianceMap", &environment.irradiance_map)?;
             program.use_texture_cube("prefilterMap", &environment.prefilter_map)?;
             program.use_texture("brdfLUT", &environment.brdf_map)?;
         }
-        program.use_uniform_vec3("ambientColor", &(self.color.to_vec3() * self.intensity))
+        let intensity = if self.enabled { self.intensity } else { 0.0 };
+        if self.environment.is_some() {
+            // The hemisphere light is added to the light from the environment, so the environment is not tinted by the sky color
+            let environment_color = if self.ground_color.is_some() {
+                Color::WHITE
+            } else {
+                self.color
+            };
+            program.use_uniform_vec3(
+                "environmentColor",
+                &(environment_color.to_vec3() * intensity),
+            )?;
+        }
+        if let Some(ground_color) = self.ground_color {
+            program.use_uniform_vec3("groundColor", &(ground_color.to_vec3() * intensity))?;
+        }
+        if self.environment.is_none() || self.ground_color.is_some() {
+            program.use_uniform_vec3("ambientColor", &(self.color.to_vec3() * intensity))?;
+        }
+        Ok(())
     }
 }
 
@@ -75,6 +166,9 @@ impl Default for AmbientLight {
             color: Color::WHITE,
             intensity: 1.0,
             environment: None,
+            ground_color: None,
+            sdf_ao: None,
+            enabled: true,
         }
     }
 }
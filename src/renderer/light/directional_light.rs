@@ -4,15 +4,48 @@ use crate::renderer::*;
 
 ///
 /// A light which shines in the given direction.
-/// The light will cast shadows if you [generate a shadow map](DirectionalLight::generate_shadow_map).
+/// The light will cast shadows if you [generate a shadow map](DirectionalLight::generate_shadow_map),
+/// possibly [fitted to the view of a camera](DirectionalLight::generate_shadow_map_fitted), or if it has [SDF shadows](DirectionalLight::set_sdf_shadows).
 ///
 pub struct DirectionalLight {
     context: Context,
     light_buffer: UniformBuffer,
+    intensity: f32,
+    enabled: bool,
+    shadow_enabled: bool,
     shadow_texture: Option<DepthTargetTexture2D>,
+    shadow_quality: ShadowQuality,
+    shadow_depth_bias: f32,
+    shadow_normal_offset_bias: f32,
+    max_shadow_distance: Option<f32>,
+    sdf_shadows: Option<SdfShadows>,
 }
 
 impl DirectionalLight {
+    ///
+    /// Returns a builder for a directional light, see [DirectionalLightBuilder].
+    /// This is an alternative to [DirectionalLight::new] where each parameter is named and has a default value.
+    ///
+    /// ```no_run
+    /// # use three_d::*;
+    /// # let context = Context::new().unwrap();
+    /// let light = DirectionalLight::builder(&context)
+    ///     .direction(vec3(-1.0, -1.0, -1.0))
+    ///     .intensity(2.0)
+    ///     .color(Color::WHITE)
+    ///     .build()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    pub fn builder(context: &Context) -> DirectionalLightBuilder {
+        DirectionalLightBuilder {
+            context: context.clone(),
+            intensity: 1.0,
+            color: Color::WHITE,
+            direction: vec3(0.0, -1.0, 0.0),
+        }
+    }
+
     pub fn new(
         context: &Context,
         intensity: f32,
@@ -22,10 +55,19 @@ impl DirectionalLight {
         let mut light = DirectionalLight {
             context: context.clone(),
             light_buffer: UniformBuffer::new(context, &[3u32, 1, 3, 1, 16])?,
+            intensity,
+            enabled: true,
+            shadow_enabled: true,
             shadow_texture: None,
+            shadow_quality: ShadowQuality::default(),
+            shadow_depth_bias: 0.005,
+            shadow_normal_offset_bias: 0.0,
+            max_shadow_distance: None,
+            sdf_shadows: None,
         };
 
         light.set_intensity(intensity);
+        light.set_shadow_enabled(true);
         light.set_color(color);
         light.set_direction(direction);
         Ok(light)
@@ -41,11 +83,49 @@ impl DirectionalLight {
     }
 
     pub fn set_intensity(&mut self, intensity: f32) {
-        self.light_buffer.update(1, &[intensity]).unwrap();
+        self.intensity = intensity;
+        self.light_buffer
+            .update(1, &[if self.enabled { intensity } else { 0.0 }])
+            .unwrap();
     }
 
     pub fn intensity(&self) -> f32 {
-        self.light_buffer.get(1).unwrap()[0]
+        self.intensity
+    }
+
+    ///
+    /// Enables or disables the light. A disabled light does not contribute to the lighting, but stays in the shader,
+    /// so toggling the light does not require a shader recompilation.
+    ///
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.set_intensity(self.intensity);
+    }
+
+    ///
+    /// Returns whether or not the light is enabled, see [DirectionalLight::set_enabled].
+    ///
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    ///
+    /// Enables or disables the shadows of the light, both from the [shadow map](DirectionalLight::shadow_map) and the [SDF shadows](DirectionalLight::set_sdf_shadows),
+    /// while keeping them, so shadows can be paused without a shader recompilation and resumed without generating the shadow map again.
+    /// The shadows are enabled by default, but the light only casts shadows if it has a shadow map or SDF shadows.
+    ///
+    pub fn set_shadow_enabled(&mut self, shadow_enabled: bool) {
+        self.shadow_enabled = shadow_enabled;
+        self.light_buffer
+            .update(3, &[if shadow_enabled { 1.0 } else { 0.0 }])
+            .unwrap();
+    }
+
+    ///
+    /// Returns whether or not the shadows are enabled, see [DirectionalLight::set_shadow_enabled].
+    ///
+    pub fn is_shadow_enabled(&self) -> bool {
+        self.shadow_enabled
     }
 
     pub fn set_direction(&mut self, direction: &Vec3) {
@@ -61,7 +141,6 @@ impl DirectionalLight {
 
     pub fn clear_shadow_map(&mut self) {
         self.shadow_texture = None;
-        self.light_buffer.update(3, &[0.0]).unwrap();
     }
 
     pub fn generate_shadow_map(
@@ -96,13 +175,115 @@ impl DirectionalLight {
             z_near,
             z_far,
         )?;
+        self.render_shadow_map(&shadow_camera, geometries)
+    }
+
+    ///
+    /// Generates a square shadow map with the given size in texels which is fitted to the part of the given geometries that is visible from the given camera,
+    /// so most of the shadow map resolution is spent where it is seen, instead of having to choose the frustum height like in [DirectionalLight::generate_shadow_map].
+    /// The visible part is clipped to the [maximum shadow distance](DirectionalLight::set_max_shadow_distance) from the camera.
+    ///
+    /// The fitted area has a fixed size while the camera moves and rotates and is moved in steps of whole texels,
+    /// which stabilizes the shadow edges, that otherwise crawl when the shadow map is regenerated each frame.
+    /// The shadow map covers all of the geometries in the direction towards the light, so geometry outside the camera view still casts shadows into it.
+    /// If camera relative rendering is enabled on the camera, see [Camera::set_camera_relative], the shadow map is also rendered camera relative.
+    ///
+    pub fn generate_shadow_map_fitted(
+        &mut self,
+        camera: &Camera,
+        geometries: &[impl Geometry],
+        texture_size: u32,
+    ) -> ThreeDResult<()> {
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for geometry in geometries {
+            aabb.expand_with_aabb(&geometry.aabb());
+        }
+        if aabb.is_empty() {
+            return Ok(());
+        }
+
+        // The axes of the light space, which are the axes of the shadow camera
+        let direction = self.direction();
+        let up = compute_up_direction(direction);
+        let right = direction.cross(up).normalize();
+        let to_light_space = |p: Vec3| vec3(p.dot(right), p.dot(up), p.dot(direction));
+
+        // The bounds of the geometries in light space
+        let (min, max) = (aabb.min(), aabb.max());
+        let mut geometry_min = vec3(f32::MAX, f32::MAX, f32::MAX);
+        let mut geometry_max = vec3(f32::MIN, f32::MIN, f32::MIN);
+        for i in 0..8 {
+            let corner = to_light_space(vec3(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            ));
+            for j in 0..3 {
+                geometry_min[j] = geometry_min[j].min(corner[j]);
+                geometry_max[j] = geometry_max[j].max(corner[j]);
+            }
+        }
+
+        // The bounding sphere of the visible part of the camera frustum, which has the same radius no matter the orientation of the camera,
+        // rounded up to avoid that floating point errors change the size of the shadow map from frame to frame
+        let corners =
+            camera_frustum_corners(camera, self.max_shadow_distance.unwrap_or(camera.z_far()));
+        let center = corners.iter().fold(vec3(0.0, 0.0, 0.0), |a, c| a + c) / 8.0;
+        let radius = corners
+            .iter()
+            .map(|c| c.distance(center))
+            .fold(0.0f32, f32::max);
+        let radius = (radius * 16.0).ceil() / 16.0;
+        let center = to_light_space(center);
+
+        // Never larger than needed to cover all of the geometries
+        let size = (2.0 * radius)
+            .min((geometry_max.x - geometry_min.x).max(geometry_max.y - geometry_min.y))
+            .max(f32::EPSILON);
+        let texel_size = size / texture_size as f32;
+        let fit = |center: f32, min: f32, max: f32| {
+            let center = if max - min > size {
+                center.max(min + 0.5 * size).min(max - 0.5 * size)
+            } else {
+                0.5 * (min + max)
+            };
+            (center / texel_size).round() * texel_size
+        };
+        let x = fit(center.x, geometry_min.x, geometry_max.x);
+        let y = fit(center.y, geometry_min.y, geometry_max.y);
+
+        // The near plane is at the geometry closest to the light, so all shadow casters are included
+        let margin = 0.01 * (geometry_max.z - geometry_min.z).max(1.0);
+        let position = right * x + up * y + direction * (geometry_min.z - margin);
+        let mut shadow_camera = Camera::new_orthographic(
+            &self.context,
+            Viewport::new_at_origo(texture_size, texture_size),
+            position,
+            position + direction,
+            up,
+            size,
+            0.0,
+            geometry_max.z - geometry_min.z + 2.0 * margin,
+        )?;
+        // The shadow casters are transformed in the same way as when rendered with the camera,
+        // so the depth in the shadow map matches the depth of the surfaces looked up with the shadow matrix
+        shadow_camera.set_camera_relative(camera.is_camera_relative());
+        self.render_shadow_map(&shadow_camera, geometries)
+    }
+
+    fn render_shadow_map(
+        &mut self,
+        shadow_camera: &Camera,
+        geometries: &[impl Geometry],
+    ) -> ThreeDResult<()> {
         self.light_buffer
-            .update(4, &shadow_matrix(&shadow_camera).as_array())?;
+            .update(4, &shadow_matrix(shadow_camera).as_array())?;
 
+        let viewport = shadow_camera.viewport();
         let mut shadow_texture = DepthTargetTexture2D::new(
             &self.context,
-            texture_width,
-            texture_height,
+            viewport.width,
+            viewport.height,
             Wrapping::ClampToEdge,
             Wrapping::ClampToEdge,
             DepthFormat::Depth32F,
@@ -114,38 +295,201 @@ impl DirectionalLight {
             },
             ..Default::default()
         };
-        shadow_texture.write(Some(1.0), || {
-            for geometry in geometries
-                .iter()
-                .filter(|g| shadow_camera.in_frustum(&g.aabb()))
-            {
-                geometry.render_with_material(
-                    &depth_material,
-                    &shadow_camera,
-                    &Lights::default(),
-                )?;
-            }
-            Ok(())
+        // Shadow maps are always rendered in the standard depth mode, since the shadow sampling expects the standard depth values
+        self.context.with_standard_depth_mode(|| {
+            shadow_texture.write(Some(1.0), || {
+                for geometry in geometries
+                    .iter()
+                    .filter(|g| shadow_camera.in_frustum(&g.aabb()))
+                {
+                    geometry.render_with_material(
+                        &depth_material,
+                        shadow_camera,
+                        &Lights::default(),
+                    )?;
+                }
+                Ok(())
+            })
         })?;
         self.shadow_texture = Some(shadow_texture);
-        self.light_buffer.update(3, &[1.0])?;
         Ok(())
     }
 
+    ///
+    /// Sets the maximum distance from the camera at which shadows are visible when the shadow map is generated using [DirectionalLight::generate_shadow_map_fitted].
+    /// A shorter distance gives sharper shadows close to the camera. If `None`, which is the default, the far plane of the camera is used.
+    ///
+    pub fn set_max_shadow_distance(&mut self, max_shadow_distance: Option<f32>) {
+        self.max_shadow_distance = max_shadow_distance;
+    }
+
+    ///
+    /// Returns the maximum shadow distance, see [DirectionalLight::set_max_shadow_distance].
+    ///
+    pub fn max_shadow_distance(&self) -> Option<f32> {
+        self.max_shadow_distance
+    }
+
+    ///
+    /// Sets how the shadow map is sampled, see [ShadowQuality].
+    ///
+    pub fn set_shadow_quality(&mut self, shadow_quality: ShadowQuality) {
+        self.shadow_quality = shadow_quality;
+    }
+
+    ///
+    /// Returns how the shadow map is sampled.
+    ///
+    pub fn shadow_quality(&self) -> ShadowQuality {
+        self.shadow_quality
+    }
+
+    ///
+    /// Sets the biases used to avoid shadow acne, ie. a surface shadowing itself.
+    /// The depth bias is subtracted from the depth of the surface in the shadow map (which is in the range `[0, 1]`)
+    /// and the normal offset bias is the distance the surface position is moved along the normal before looking it up in the shadow map.
+    ///
+    pub fn set_shadow_bias(&mut self, depth_bias: f32, normal_offset_bias: f32) {
+        self.shadow_depth_bias = depth_bias;
+        self.shadow_normal_offset_bias = normal_offset_bias;
+    }
+
+    ///
+    /// Returns the depth bias and the normal offset bias, see [DirectionalLight::set_shadow_bias].
+    ///
+    pub fn shadow_bias(&self) -> (f32, f32) {
+        (self.shadow_depth_bias, self.shadow_normal_offset_bias)
+    }
+
+    ///
+    /// Returns the depth texture containing the shadow map, if one is generated, which can for example be shown using a [DebugTextureQuad] when tuning the [shadow bias](DirectionalLight::set_shadow_bias).
+    /// The depth is linear in the distance from the light, since the shadow map is rendered using an orthographic projection.
+    ///
     pub fn shadow_map(&self) -> Option<&DepthTargetTexture2D> {
         self.shadow_texture.as_ref()
     }
 
+    ///
+    /// Returns the matrix which transforms a position in world space to the texture coordinates (xy) and depth (z) in the [DirectionalLight::shadow_map],
+    /// which is only valid if the light has a shadow map.
+    ///
+    pub fn shadow_matrix(&self) -> Mat4 {
+        mat4_from_slice(self.light_buffer.get(4).unwrap())
+    }
+
+    ///
+    /// Returns the eight corners in world space of the box which is covered by the [DirectionalLight::shadow_map], for example to visualize it using lines,
+    /// which is only valid if the light has a shadow map.
+    /// The index of a corner is `x + 2y + 4z` where `x` and `y` are the texture coordinates and `z` the depth of the corner in the shadow map, which are either 0 or 1.
+    ///
+    pub fn shadow_frustum_corners(&self) -> [Vec3; 8] {
+        shadow_frustum_corners(self.shadow_matrix())
+    }
+
+    ///
+    /// Sets the soft shadows computed using a signed distance field, see [SdfShadows], or removes them if `None`.
+    /// If the light also has a shadow map, the geometry is shadowed by both.
+    ///
+    pub fn set_sdf_shadows(&mut self, sdf_shadows: Option<SdfShadows>) {
+        self.sdf_shadows = sdf_shadows;
+    }
+
+    ///
+    /// Returns the soft shadows computed using a signed distance field, see [DirectionalLight::set_sdf_shadows].
+    ///
+    pub fn sdf_shadows(&self) -> Option<&SdfShadows> {
+        self.sdf_shadows.as_ref()
+    }
+
+    pub(crate) fn has_shadows(&self) -> bool {
+        self.shadow_texture.is_some() || self.sdf_shadows.is_some()
+    }
+
     pub fn buffer(&self) -> &UniformBuffer {
         &self.light_buffer
     }
 }
 
+///
+/// A builder for a [DirectionalLight], constructed using [DirectionalLight::builder].
+/// The parameters are validated when the light is built, so an invalid parameter gives a descriptive error instead of NaNs in the shader.
+///
+pub struct DirectionalLightBuilder {
+    context: Context,
+    intensity: f32,
+    color: Color,
+    direction: Vec3,
+}
+
+impl DirectionalLightBuilder {
+    ///
+    /// The intensity of the light, which must be non-negative. The default is 1.
+    ///
+    pub fn intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    ///
+    /// The color of the light. The default is white.
+    ///
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    ///
+    /// The direction the light shines in, which must not be zero. The default is straight down.
+    ///
+    pub fn direction(mut self, direction: Vec3) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    ///
+    /// Validates the parameters and builds the light.
+    ///
+    /// # Errors
+    /// Will return an error if the intensity is negative or not finite or if the direction is zero or not finite.
+    ///
+    pub fn build(self) -> ThreeDResult<DirectionalLight> {
+        if !(self.intensity >= 0.0 && self.intensity.is_finite()) {
+            Err(RendererError::InvalidLightIntensity(self.intensity))?;
+        }
+        let length = self.direction.magnitude();
+        if !(length > 0.0 && length.is_finite()) {
+            Err(RendererError::InvalidLightDirection(self.direction))?;
+        }
+        DirectionalLight::new(&self.context, self.intensity, self.color, &self.direction)
+    }
+}
+
 impl Light for DirectionalLight {
     fn shader_source(&self, i: u32) -> String {
+        let (mut shadow_source, mut shadow_call) = if self.shadow_map().is_some() {
+            (
+                self.shadow_quality.shader_source(i),
+                format!(
+                    "result *= calculate_shadow{}(shadowMVP{}, position, normal);",
+                    i, i
+                ),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+        if let Some(ref sdf_shadows) = self.sdf_shadows {
+            shadow_source.push_str(&sdf_shadows.shader_source(i));
+            shadow_call.push_str(&format!(
+                "result *= calculate_sdf_shadow{}(position, normal, -direction{}, 1.0e10);",
+                i, i
+            ));
+        }
+        if !shadow_call.is_empty() {
+            shadow_call = format!("if (shadowEnabled{} > 0.5) {{ {} }}", i, shadow_call);
+        }
         format!(
         "
-            uniform sampler2D shadowMap{};
+            {}
             layout (std140) uniform LightUniform{}
             {{
                 BaseLight base{};
@@ -158,9 +502,7 @@ impl Light for DirectionalLight {
                 if(base{}.intensity > 0.001) {{
                     vec3 light_color = base{}.intensity * base{}.color;
                     vec3 result = calculate_light(light_color, -direction{}, surface_color, view_direction, normal, metallic, roughness);
-                    if(shadowEnabled{} > 0.5) {{
-                        result *= calculate_shadow(shadowMap{}, shadowMVP{}, position);
-                    }}
+                    {}
                     return result;
                 }}
                 else {{
@@ -168,16 +510,48 @@ impl Light for DirectionalLight {
                 }}
             }}
         
-        ", i, i, i, i, i, i, i, i, i, i, i, i, i, i)
+        ", shadow_source, i, i, i, i, i, i, i, i, i, i, shadow_call)
     }
     fn use_uniforms(&self, program: &Program, i: u32) -> ThreeDResult<()> {
         if let Some(tex) = self.shadow_map() {
-            program.use_texture(&format!("shadowMap{}", i), tex)?;
-        } else {
-            self.context
-                .use_texture_dummy(&program, &format!("shadowMap{}", i))?;
+            self.shadow_quality.use_uniforms(
+                program,
+                i,
+                tex,
+                vec4(0.0, 0.0, 1.0, 1.0),
+                self.shadow_depth_bias,
+                self.shadow_normal_offset_bias,
+            )?;
+        }
+        if let Some(ref sdf_shadows) = self.sdf_shadows {
+            sdf_shadows.use_uniforms(program, i)?;
         }
         program.use_uniform_block(&format!("LightUniform{}", i), self.buffer());
         Ok(())
     }
 }
+
+// The eight corners in world space of the view frustum of the given camera, where the far plane is moved to the given distance if it is closer
+fn camera_frustum_corners(camera: &Camera, max_distance: f32) -> [Vec3; 8] {
+    let view_direction = camera.view_direction();
+    let right = camera.right_direction().normalize();
+    let up = right.cross(view_direction);
+    let aspect = camera.viewport().aspect();
+    let near = camera.z_near();
+    let far = camera.z_far().min(max_distance).max(near);
+    let half_height = |distance: f32| match camera.projection_type() {
+        ProjectionType::Orthographic { height } => 0.5 * height,
+        ProjectionType::Perspective { field_of_view_y } => {
+            distance * (0.5 * field_of_view_y.0).tan()
+        }
+    };
+    let mut corners = [vec3(0.0, 0.0, 0.0); 8];
+    for (i, corner) in corners.iter_mut().enumerate() {
+        let distance = if i & 4 == 0 { near } else { far };
+        let h = half_height(distance);
+        let x = if i & 1 == 0 { -h * aspect } else { h * aspect };
+        let y = if i & 2 == 0 { -h } else { h };
+        *corner = camera.position() + view_direction * distance + right * x + up * y;
+    }
+    corners
+}
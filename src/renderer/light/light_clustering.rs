@@ -0,0 +1,325 @@
+use crate::core::*;
+use std::cell::{Cell, RefCell};
+
+// The number of light indices stored in each row of the light index texture, ie. four indices in each of the 64 texels
+const LIGHT_INDICES_PER_ROW: usize = 256;
+
+///
+/// Clustered light culling of the packed point and spot lights, see [Lights::clustering](crate::renderer::Lights::clustering).
+/// The view frustum of the camera is divided into a grid of clusters, which is split evenly along the x and y axes of the screen
+/// and logarithmically along the view direction. Each frame, the lights are assigned to the clusters which intersect the sphere of influence of the light
+/// and when shading a fragment, only the lights assigned to the cluster containing the fragment are evaluated.
+/// The sphere of influence of a light is where its attenuated intensity is above the [LightClustering::intensity_threshold],
+/// so the light is cut off at that distance. Directional lights are evaluated for all fragments.
+///
+pub struct LightClustering {
+    cluster_counts: (u32, u32, u32),
+    max_lights_per_cluster: u32,
+    intensity_threshold: f32,
+    heatmap: bool,
+    dropped_light_count: Cell<u32>,
+    cache: RefCell<Option<ClusterCache>>,
+}
+
+impl LightClustering {
+    ///
+    /// Creates a new light clustering with the given number of clusters along the width, the height and the depth of the view frustum
+    /// and the given maximum number of lights in each cluster. If more lights intersect a cluster, the dimmest lights at the center of the cluster are dropped,
+    /// see [LightClustering::dropped_light_count].
+    ///
+    pub fn new(x_count: u32, y_count: u32, z_count: u32, max_lights_per_cluster: u32) -> Self {
+        Self {
+            cluster_counts: (x_count.max(1), y_count.max(1), z_count.max(1)),
+            max_lights_per_cluster,
+            intensity_threshold: 0.01,
+            heatmap: false,
+            dropped_light_count: Cell::new(0),
+            cache: RefCell::new(None),
+        }
+    }
+
+    ///
+    /// Returns the number of clusters along the width, the height and the depth of the view frustum.
+    ///
+    pub fn cluster_counts(&self) -> (u32, u32, u32) {
+        self.cluster_counts
+    }
+
+    ///
+    /// Returns the maximum number of lights in each cluster.
+    ///
+    pub fn max_lights_per_cluster(&self) -> u32 {
+        self.max_lights_per_cluster
+    }
+
+    ///
+    /// Sets the intensity below which a light is considered to have no influence, which determines the radius of the sphere of influence of each light.
+    /// A lower threshold gives smoother light falloff but assigns each light to more clusters. The default is 0.01.
+    ///
+    pub fn set_intensity_threshold(&mut self, intensity_threshold: f32) {
+        self.intensity_threshold = intensity_threshold.max(0.0001);
+        *self.cache.borrow_mut() = None;
+    }
+
+    ///
+    /// Returns the intensity below which a light is considered to have no influence, see [LightClustering::set_intensity_threshold].
+    ///
+    pub fn intensity_threshold(&self) -> f32 {
+        self.intensity_threshold
+    }
+
+    ///
+    /// Enables or disables a debug view which replaces the lighting with a color indicating the number of lights in the cluster of each fragment,
+    /// from blue for few lights, through green, to red for the maximum number of lights in a cluster. Fragments in clusters without lights are dark gray.
+    /// Use it to tune the cluster counts, the maximum number of lights per cluster and the intensity threshold.
+    ///
+    pub fn set_heatmap(&mut self, heatmap: bool) {
+        self.heatmap = heatmap;
+    }
+
+    ///
+    /// Returns whether or not the heatmap debug view is enabled, see [LightClustering::set_heatmap].
+    ///
+    pub fn heatmap(&self) -> bool {
+        self.heatmap
+    }
+
+    ///
+    /// Returns the number of times a light was dropped from a cluster because the cluster contained more than the maximum number of lights,
+    /// counted the last time the lights were assigned to the clusters.
+    ///
+    pub fn dropped_light_count(&self) -> u32 {
+        self.dropped_light_count.get()
+    }
+
+    pub(super) fn fragment_shader_defines(&self) -> String {
+        format!(
+            "#define USE_LIGHT_CLUSTERS\n{}",
+            if self.heatmap {
+                "#define LIGHT_CLUSTER_HEATMAP\n"
+            } else {
+                ""
+            }
+        )
+    }
+
+    ///
+    /// Assigns the given lights, given as position, color multiplied by intensity and attenuation, to the clusters if the camera or the lights have changed
+    /// and uses the resulting textures in the given program.
+    /// The index of the first light in the packed lights is given by `first_light_index`.
+    ///
+    pub(super) fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &[(Vec3, f32, (f32, f32, f32))],
+        first_light_index: usize,
+    ) -> ThreeDResult<()> {
+        let near = camera.z_near().max(0.0001 * camera.z_far());
+        let far = camera.z_far();
+        let mut cache = self.cache.borrow_mut();
+        if cache
+            .as_ref()
+            .map(|c| {
+                c.view != *camera.view()
+                    || c.projection != *camera.projection()
+                    || c.lights.as_slice() != lights
+                    || c.first_light_index != first_light_index
+            })
+            .unwrap_or(true)
+        {
+            *cache = Some(self.assign_lights(camera, near, far, lights, first_light_index));
+        }
+        let cache = cache.as_ref().unwrap();
+        let context = program.context();
+        context.use_data_texture(
+            program,
+            "lightClusters",
+            self.cluster_counts.0,
+            &cache.clusters,
+        )?;
+        context.use_data_texture(
+            program,
+            "lightClusterIndices",
+            LIGHT_INDICES_PER_ROW as u32 / 4,
+            &cache.indices,
+        )?;
+        let viewport = camera.viewport();
+        program.use_uniform_vec4(
+            "lightClusterViewport",
+            &vec4(
+                viewport.x as f32,
+                viewport.y as f32,
+                viewport.width as f32,
+                viewport.height as f32,
+            ),
+        )?;
+        program.use_uniform_vec3(
+            "lightClusterCounts",
+            &vec3(
+                self.cluster_counts.0 as f32,
+                self.cluster_counts.1 as f32,
+                self.cluster_counts.2 as f32,
+            ),
+        )?;
+        program.use_uniform_vec2("lightClusterDepthRange", &vec2(near, far))?;
+        program.use_uniform_mat4("lightClusterView", camera.view())?;
+        program.use_uniform_int("packedDirectionalLightCount", &(first_light_index as i32))?;
+        if self.heatmap {
+            program.use_uniform_float(
+                "maxLightsPerCluster",
+                &(self.max_lights_per_cluster.max(1) as f32),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn assign_lights(
+        &self,
+        camera: &Camera,
+        near: f32,
+        far: f32,
+        lights: &[(Vec3, f32, (f32, f32, f32))],
+        first_light_index: usize,
+    ) -> ClusterCache {
+        let (x_count, y_count, z_count) = (
+            self.cluster_counts.0 as usize,
+            self.cluster_counts.1 as usize,
+            self.cluster_counts.2 as usize,
+        );
+        let slice_depth = |z: usize| near * (far / near).powf(z as f32 / z_count as f32);
+        let slice_index = |depth: f32| {
+            ((depth.max(near) / near).ln() / (far / near).ln() * z_count as f32)
+                .max(0.0)
+                .min(z_count as f32 - 1.0) as usize
+        };
+
+        // The view space bounding box of each cluster, computed from the corners of the cluster on the near and far plane of its depth slice
+        let inverse_projection = camera.projection().invert().unwrap_or(Mat4::identity());
+        let unproject = |x: f32, y: f32, z: f32| {
+            let p = inverse_projection * vec4(x, y, z, 1.0);
+            p.truncate() / p.w
+        };
+        let point_at_depth = |x: f32, y: f32, depth: f32| {
+            let p0 = unproject(x, y, -1.0);
+            let p1 = unproject(x, y, 1.0);
+            let t = if (p1.z - p0.z).abs() > std::f32::EPSILON {
+                (-depth - p0.z) / (p1.z - p0.z)
+            } else {
+                0.0
+            };
+            p0 + t * (p1 - p0)
+        };
+        let mut aabbs = Vec::with_capacity(x_count * y_count * z_count);
+        for z in 0..z_count {
+            let depths = [slice_depth(z), slice_depth(z + 1)];
+            for y in 0..y_count {
+                for x in 0..x_count {
+                    let mut aabb = AxisAlignedBoundingBox::EMPTY;
+                    for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)].iter() {
+                        let ndc_x = -1.0 + 2.0 * (x + dx) as f32 / x_count as f32;
+                        let ndc_y = -1.0 + 2.0 * (y + dy) as f32 / y_count as f32;
+                        for depth in depths.iter() {
+                            let p = point_at_depth(ndc_x, ndc_y, *depth);
+                            aabb.expand(&[p.x, p.y, p.z]);
+                        }
+                    }
+                    aabbs.push(aabb);
+                }
+            }
+        }
+
+        // Assign each light to the clusters intersecting its sphere of influence
+        let mut clusters: Vec<Vec<(usize, f32)>> = vec![Vec::new(); aabbs.len()];
+        for (index, (position, intensity, attenuation)) in lights.iter().enumerate() {
+            let radius = influence_radius(*intensity, *attenuation, self.intensity_threshold);
+            if radius <= 0.0 {
+                continue;
+            }
+            let center = (camera.view() * position.extend(1.0)).truncate();
+            let depth = -center.z;
+            if depth + radius < near || depth - radius > far {
+                continue;
+            }
+            for z in slice_index(depth - radius)..=slice_index(depth + radius) {
+                for cluster in z * x_count * y_count..(z + 1) * x_count * y_count {
+                    let aabb = &aabbs[cluster];
+                    let closest = vec3(
+                        center.x.max(aabb.min().x).min(aabb.max().x),
+                        center.y.max(aabb.min().y).min(aabb.max().y),
+                        center.z.max(aabb.min().z).min(aabb.max().z),
+                    );
+                    if closest.distance2(center) <= radius * radius {
+                        let distance = aabb.center().distance(center);
+                        let (constant, linear, exponential) = *attenuation;
+                        let attenuated = intensity
+                            / (constant + linear * distance + exponential * distance * distance)
+                                .max(1.0);
+                        clusters[cluster].push((first_light_index + index, attenuated));
+                    }
+                }
+            }
+        }
+
+        // Drop the dimmest lights of the clusters with too many lights and pack the clusters as the offset and the number of lights into the light indices
+        let max_lights = self.max_lights_per_cluster as usize;
+        let mut dropped_light_count = 0;
+        let mut cluster_data = Vec::with_capacity(clusters.len() * 4);
+        let mut indices = Vec::new();
+        for lights in clusters.iter_mut() {
+            if lights.len() > max_lights {
+                lights.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+                dropped_light_count += lights.len() - max_lights;
+                lights.truncate(max_lights);
+            }
+            cluster_data.extend_from_slice(&[indices.len() as f32, lights.len() as f32, 0.0, 0.0]);
+            indices.extend(lights.iter().map(|(index, _)| *index as f32));
+        }
+        self.dropped_light_count.set(dropped_light_count as u32);
+        // Round the number of rows up to a power of two to avoid reallocating the texture every time the number of indices changes
+        let rows = ((indices.len() + LIGHT_INDICES_PER_ROW - 1) / LIGHT_INDICES_PER_ROW)
+            .max(1)
+            .next_power_of_two();
+        indices.resize(rows * LIGHT_INDICES_PER_ROW, 0.0);
+
+        ClusterCache {
+            view: *camera.view(),
+            projection: *camera.projection(),
+            lights: lights.to_vec(),
+            first_light_index,
+            clusters: cluster_data,
+            indices,
+        }
+    }
+}
+
+impl Default for LightClustering {
+    fn default() -> Self {
+        Self::new(16, 9, 24, 64)
+    }
+}
+
+struct ClusterCache {
+    view: Mat4,
+    projection: Mat4,
+    lights: Vec<(Vec3, f32, (f32, f32, f32))>,
+    first_light_index: usize,
+    clusters: Vec<f32>,
+    indices: Vec<f32>,
+}
+
+// Returns the distance at which the attenuated intensity of a light with the given intensity and attenuation falls below the given threshold
+fn influence_radius(intensity: f32, attenuation: (f32, f32, f32), threshold: f32) -> f32 {
+    let (constant, linear, exponential) = attenuation;
+    // Solves constant + linear * d + exponential * d^2 = intensity / threshold
+    let c = constant - intensity / threshold;
+    if c >= 0.0 {
+        0.0
+    } else if exponential > 0.0 {
+        (-linear + (linear * linear - 4.0 * exponential * c).sqrt()) / (2.0 * exponential)
+    } else if linear > 0.0 {
+        -c / linear
+    } else {
+        std::f32::INFINITY
+    }
+}
@@ -0,0 +1,166 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Bakes the light arriving at the surface of the given model into a lightmap, ie. a texture with the given width and height
+/// which is mapped onto the surface using the second set of uv coordinates of the mesh ([CPUMesh::uvs2]) or the uv coordinates if the mesh has no second set.
+/// Render the model with a [LightmappedMaterial] using the lightmap to get the baked lighting at a fraction of the cost of evaluating the lights every frame,
+/// which is useful for static objects lit by static lights, for example the walls of a building.
+///
+/// All the given lights are evaluated, including the shadows of directional and spot lights, so generate the shadow maps before baking.
+/// The lighting does not depend on the view, so only the diffuse part of the lighting is baked.
+/// Each texel is evaluated the given number of times at positions jittered within the texel and the samples are averaged,
+/// which gives anti-aliased chart boundaries and softer shadow edges.
+/// Finally, the baked lighting is dilated the given number of texels to hide the seams between the charts when the lightmap is interpolated.
+///
+/// The uv coordinates used for the lightmap must not overlap, use [CPUMesh::generate_uvs2] to generate them.
+/// Requires rendering to and blending into `f32` textures.
+///
+/// # Errors
+/// Returns an error if the mesh has no uv coordinates or normals.
+///
+pub fn bake_lightmap<M: Material>(
+    context: &Context,
+    model: &Model<M>,
+    lights: &Lights,
+    width: u32,
+    height: u32,
+    samples: u32,
+    dilation: u32,
+) -> ThreeDResult<Texture2D<f32>> {
+    let mesh = model.mesh();
+    let uv_buffer = mesh
+        .uv2_buffer
+        .as_ref()
+        .or(mesh.uv_buffer.as_ref())
+        .ok_or(CoreError::MissingMeshBuffer("uv coordinates".to_string()))?;
+    let normal_buffer = mesh
+        .normal_buffer
+        .as_ref()
+        .ok_or(CoreError::MissingMeshBuffer("normal".to_string()))?;
+    let transformation = model.transformation();
+    let viewport = Viewport::new_at_origo(width, height);
+
+    // The lights are never packed when baking, since the packed lights are culled in screen space when clustered
+    let fragment_shader_source = format!(
+        "#define LIGHTMAP_BAKING\n{}{}",
+        lights_fragment_shader_source(
+            &mut LightsIterator::new(lights),
+            lights.lighting_model,
+            false,
+            None
+        ),
+        include_str!("shaders/lightmap.frag")
+    );
+    let mut accumulated = new_lightmap_texture(context, width, height)?;
+    accumulated.write(ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+        context.program(
+            include_str!("shaders/lightmap.vert"),
+            &fragment_shader_source,
+            |program| {
+                for (i, light) in LightsIterator::new(lights).enumerate() {
+                    light.use_uniforms(program, i as u32)?;
+                }
+                program.use_uniform_mat4("modelMatrix", &transformation)?;
+                program.use_uniform_mat4(
+                    "normalMatrix",
+                    &transformation.invert().unwrap().transpose(),
+                )?;
+                program.use_attribute_vec3("position", &mesh.position_buffer)?;
+                program.use_attribute_vec3("normal", normal_buffer)?;
+                program.use_attribute_vec2("uv_coordinates", uv_buffer)?;
+                let render_states = RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    blend: Blend::ADD,
+                    cull: Cull::None,
+                    ..Default::default()
+                };
+                let samples = samples.max(1);
+                for sample in 0..samples {
+                    // The samples are distributed evenly within a texel using the Halton sequence
+                    let offset = if samples == 1 {
+                        vec2(0.0, 0.0)
+                    } else {
+                        vec2(halton(sample + 1, 2) - 0.5, halton(sample + 1, 3) - 0.5)
+                    };
+                    program.use_uniform_vec2(
+                        "jitter",
+                        &vec2(
+                            2.0 * offset.x / width as f32,
+                            2.0 * offset.y / height as f32,
+                        ),
+                    )?;
+                    if let Some(ref index_buffer) = mesh.index_buffer {
+                        program.draw_elements(render_states, viewport, index_buffer);
+                    } else {
+                        program.draw_arrays(
+                            render_states,
+                            viewport,
+                            mesh.position_buffer.count() as u32 / 3,
+                        );
+                    }
+                }
+                Ok(())
+            },
+        )
+    })?;
+
+    let render_states = RenderStates {
+        write_mask: WriteMask::COLOR,
+        cull: Cull::None,
+        ..Default::default()
+    };
+    let mut lightmap = new_lightmap_texture(context, width, height)?;
+    lightmap.write(ClearState::none(), || {
+        context.effect(include_str!("shaders/lightmap_resolve.frag"), |effect| {
+            effect.use_texture("accumulatedTexture", &accumulated)?;
+            effect.apply(render_states, viewport)
+        })
+    })?;
+
+    // Grow the baked charts by one texel in each dilation pass
+    for _ in 0..dilation {
+        let source = &lightmap;
+        accumulated.write(ClearState::none(), || {
+            context.effect(
+                include_str!("../material/shaders/uv_dilation.frag"),
+                |effect| {
+                    effect.use_texture("positionTexture", source)?;
+                    effect.apply(render_states, viewport)
+                },
+            )
+        })?;
+        std::mem::swap(&mut lightmap, &mut accumulated);
+    }
+    Ok(lightmap)
+}
+
+fn new_lightmap_texture(
+    context: &Context,
+    width: u32,
+    height: u32,
+) -> ThreeDResult<Texture2D<f32>> {
+    Texture2D::new_empty(
+        context,
+        width,
+        height,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+}
+
+// The i'th element of the Halton sequence with the given base, which is in the range [0..1]
+fn halton(mut i: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut fraction = 1.0;
+    while i > 0 {
+        fraction /= base as f32;
+        result += fraction * (i % base) as f32;
+        i /= base;
+    }
+    result
+}
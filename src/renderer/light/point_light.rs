@@ -3,9 +3,14 @@ use crate::renderer::*;
 
 ///
 /// A light which shines from the given position in all directions.
+/// The light will cast shadows if it has [SDF shadows](PointLight::set_sdf_shadows).
 ///
 pub struct PointLight {
     light_buffer: UniformBuffer,
+    intensity: f32,
+    enabled: bool,
+    shadow_enabled: bool,
+    sdf_shadows: Option<SdfShadows>,
 }
 
 impl PointLight {
@@ -20,9 +25,14 @@ impl PointLight {
     ) -> ThreeDResult<PointLight> {
         let mut light = PointLight {
             light_buffer: UniformBuffer::new(context, &[3u32, 1, 1, 1, 1, 1, 3, 1])?,
+            intensity,
+            enabled: true,
+            shadow_enabled: true,
+            sdf_shadows: None,
         };
 
         light.set_intensity(intensity);
+        light.set_shadow_enabled(true);
         light.set_color(color);
         light.set_position(position);
         light.set_attenuation(
@@ -43,11 +53,48 @@ impl PointLight {
     }
 
     pub fn set_intensity(&mut self, intensity: f32) {
-        self.light_buffer.update(1, &[intensity]).unwrap();
+        self.intensity = intensity;
+        self.light_buffer
+            .update(1, &[if self.enabled { intensity } else { 0.0 }])
+            .unwrap();
     }
 
     pub fn intensity(&self) -> f32 {
-        self.light_buffer.get(1).unwrap()[0]
+        self.intensity
+    }
+
+    ///
+    /// Enables or disables the light. A disabled light does not contribute to the lighting, but stays in the shader,
+    /// so toggling a light, for example a flickering lamp, does not require a shader recompilation.
+    ///
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.set_intensity(self.intensity);
+    }
+
+    ///
+    /// Returns whether or not the light is enabled, see [PointLight::set_enabled].
+    ///
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    ///
+    /// Enables or disables the [SDF shadows](PointLight::set_sdf_shadows) of the light while keeping them, so shadows can be paused without a shader recompilation.
+    /// The shadows are enabled by default, but the light only casts shadows if it has SDF shadows.
+    ///
+    pub fn set_shadow_enabled(&mut self, shadow_enabled: bool) {
+        self.shadow_enabled = shadow_enabled;
+        self.light_buffer
+            .update(7, &[if shadow_enabled { 1.0 } else { 0.0 }])
+            .unwrap();
+    }
+
+    ///
+    /// Returns whether or not the shadows are enabled, see [PointLight::set_shadow_enabled].
+    ///
+    pub fn is_shadow_enabled(&self) -> bool {
+        self.shadow_enabled
     }
 
     pub fn set_attenuation(&mut self, constant: f32, linear: f32, exponential: f32) {
@@ -73,6 +120,24 @@ impl PointLight {
         vec3(p[0], p[1], p[2])
     }
 
+    ///
+    /// Sets the soft shadows computed using a signed distance field, see [SdfShadows], or removes them if `None`.
+    ///
+    pub fn set_sdf_shadows(&mut self, sdf_shadows: Option<SdfShadows>) {
+        self.sdf_shadows = sdf_shadows;
+    }
+
+    ///
+    /// Returns the soft shadows computed using a signed distance field, see [PointLight::set_sdf_shadows].
+    ///
+    pub fn sdf_shadows(&self) -> Option<&SdfShadows> {
+        self.sdf_shadows.as_ref()
+    }
+
+    pub(crate) fn has_shadows(&self) -> bool {
+        self.sdf_shadows.is_some()
+    }
+
     pub fn buffer(&self) -> &UniformBuffer {
         &self.light_buffer
     }
@@ -80,14 +145,26 @@ impl PointLight {
 
 impl Light for PointLight {
     fn shader_source(&self, i: u32) -> String {
+        let (shadow_source, shadow_call) = if let Some(ref sdf_shadows) = self.sdf_shadows {
+            (
+                sdf_shadows.shader_source(i),
+                format!(
+                    "if (shadowEnabled{} > 0.5) {{ result *= calculate_sdf_shadow{}(position, normal, light_direction, distance); }}",
+                    i, i
+                ),
+            )
+        } else {
+            (String::new(), String::new())
+        };
         format!(
         "
+            {}
             layout (std140) uniform LightUniform{}
             {{
                 BaseLight base{};
                 Attenuation attenuation{};
                 vec3 position{};
-                float padding{};
+                float shadowEnabled{};
             }};
             vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
             {{
@@ -98,16 +175,21 @@ impl Light for PointLight {
 
                     vec3 light_color = base{}.intensity * base{}.color;
                     light_color = attenuate(light_color, attenuation{}, distance);
-                    return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
+                    vec3 result = calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
+                    {}
+                    return result;
                 }}
                 else {{
                     return vec3(0.0, 0.0, 0.0);
                 }}
             }}
         
-        ", i, i, i, i, i, i, i, i, i, i, i)
+        ", shadow_source, i, i, i, i, i, i, i, i, i, i, i, shadow_call)
     }
     fn use_uniforms(&self, program: &Program, i: u32) -> ThreeDResult<()> {
+        if let Some(ref sdf_shadows) = self.sdf_shadows {
+            sdf_shadows.use_uniforms(program, i)?;
+        }
         program.use_uniform_block(&format!("LightUniform{}", i), self.buffer());
         Ok(())
     }
@@ -0,0 +1,390 @@
+use crate::core::*;
+use crate::renderer::*;
+
+// The fraction of the surroundings of a probe which must be back faces for the probe to be considered inside geometry
+const BACK_FACE_THRESHOLD: f32 = 0.25;
+
+///
+/// A light which approximates the ambient light arriving at any point inside a box, for example light bouncing off nearby walls (known as diffuse global illumination).
+/// The light is stored as [SH9] spherical harmonics in probes placed on a regular 3D grid.
+/// When shading a surface, the 8 surrounding probes are interpolated and evaluated for the surface normal.
+///
+/// The probes are computed by calling [ProbeGrid::bake] which renders the scene from each probe position.
+/// Probes which end up inside geometry are marked invalid and skipped when interpolating.
+/// This is a cheaper alternative to an [AmbientLight] with an [Environment] for lighting which varies with the position.
+///
+pub struct ProbeGrid {
+    context: Context,
+    aabb: AxisAlignedBoundingBox,
+    resolution: (u32, u32, u32),
+    probes: Vec<SH9>,
+    valid: Vec<bool>,
+    texture: Texture2D<f32>,
+    /// The intensity of the light, which scales the light from all probes.
+    pub intensity: f32,
+    /// Whether or not the probes contribute to the lighting. A disabled grid stays in the shader, so toggling it does not require a shader recompilation.
+    pub enabled: bool,
+}
+
+impl ProbeGrid {
+    ///
+    /// Creates a new grid of probes evenly distributed inside the given box, with the given number of probes along the x, y and z axes.
+    /// The probes are placed at the corners of the box, so use at least two probes along each axis with an extent.
+    /// All probes are black until the grid is baked, see [ProbeGrid::bake].
+    ///
+    pub fn new(
+        context: &Context,
+        aabb: AxisAlignedBoundingBox,
+        resolution: (u32, u32, u32),
+    ) -> ThreeDResult<Self> {
+        let count = (resolution.0.max(1) * resolution.1.max(1) * resolution.2.max(1)) as usize;
+        let texture = Texture2D::new_empty(
+            context,
+            9,
+            count as u32,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        let mut probe_grid = Self {
+            context: context.clone(),
+            aabb,
+            resolution: (
+                resolution.0.max(1),
+                resolution.1.max(1),
+                resolution.2.max(1),
+            ),
+            probes: vec![SH9::default(); count],
+            valid: vec![true; count],
+            texture,
+            intensity: 1.0,
+            enabled: true,
+        };
+        probe_grid.update_texture()?;
+        Ok(probe_grid)
+    }
+
+    ///
+    /// Returns the box which the probes are distributed in.
+    ///
+    pub fn aabb(&self) -> &AxisAlignedBoundingBox {
+        &self.aabb
+    }
+
+    ///
+    /// Returns the number of probes along the x, y and z axes.
+    ///
+    pub fn resolution(&self) -> (u32, u32, u32) {
+        self.resolution
+    }
+
+    ///
+    /// Returns the position of the probe with the given grid coordinates.
+    ///
+    pub fn position(&self, x: u32, y: u32, z: u32) -> Vec3 {
+        let t = |i: u32, n: u32| {
+            if n > 1 {
+                i as f32 / (n - 1) as f32
+            } else {
+                0.5
+            }
+        };
+        let min = self.aabb.min();
+        let size = self.aabb.size();
+        min + vec3(
+            t(x, self.resolution.0) * size.x,
+            t(y, self.resolution.1) * size.y,
+            t(z, self.resolution.2) * size.z,
+        )
+    }
+
+    ///
+    /// Returns the light stored in the probe with the given grid coordinates.
+    ///
+    pub fn probe(&self, x: u32, y: u32, z: u32) -> &SH9 {
+        &self.probes[self.index(x, y, z)]
+    }
+
+    ///
+    /// Returns whether the probe with the given grid coordinates is used when interpolating,
+    /// which is not the case if the probe was found to be inside geometry when baking.
+    ///
+    pub fn is_valid(&self, x: u32, y: u32, z: u32) -> bool {
+        self.valid[self.index(x, y, z)]
+    }
+
+    ///
+    /// Sets the light stored in the probe with the given grid coordinates and whether or not it is used when interpolating.
+    /// Use this to compute the light in some other way than [ProbeGrid::bake].
+    ///
+    pub fn set_probe(&mut self, x: u32, y: u32, z: u32, sh: SH9, valid: bool) -> ThreeDResult<()> {
+        let index = self.index(x, y, z);
+        self.probes[index] = sh;
+        self.valid[index] = valid;
+        self.update_texture()
+    }
+
+    ///
+    /// Computes the light at each probe by rendering the given objects lit by the given lights into a small cube map with sides of the given size at each probe position
+    /// and projecting it into spherical harmonics. Anything not covered by the objects is black.
+    /// Probes which mostly see back faces are considered inside geometry and are marked invalid, see [ProbeGrid::is_valid].
+    ///
+    /// This is expensive, so it should only be done when the scene changes and not every frame.
+    /// Must be called outside of any render target, ie. not inside the callback of for example [Screen::write].
+    /// The light from a probe grid in the given lights is included, so baking a new grid with this grid in the lights adds another bounce of light.
+    ///
+    /// # Errors
+    /// Will return an error if `f32` values cannot be read on the current context, see [Texture2D::read].
+    ///
+    pub fn bake(
+        &mut self,
+        objects: &[impl Object],
+        lights: &Lights,
+        cube_map_size: u32,
+    ) -> ThreeDResult<()> {
+        let viewport = Viewport::new_at_origo(cube_map_size, cube_map_size);
+        let mut color_texture = Texture2D::<f32>::new_empty(
+            &self.context,
+            cube_map_size,
+            cube_map_size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        let mut depth_texture = DepthTargetTexture2D::new(
+            &self.context,
+            cube_map_size,
+            cube_map_size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            DepthFormat::Depth32F,
+        )?;
+        let diagonal = self.aabb.size().magnitude().max(0.001);
+        let mut camera = Camera::new_perspective(
+            &self.context,
+            viewport,
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, -1.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(90.0),
+            0.001 * diagonal,
+            100.0 * diagonal,
+        )?;
+        let sides = [
+            (vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+            (vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+            (vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, -1.0)),
+            (vec3(0.0, -1.0, 0.0), vec3(0.0, 0.0, 1.0)),
+            (vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 0.0)),
+            (vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0)),
+        ];
+
+        for z in 0..self.resolution.2 {
+            for y in 0..self.resolution.1 {
+                for x in 0..self.resolution.0 {
+                    let position = self.position(x, y, z);
+                    let mut sh = SH9::default();
+                    let mut back_faces = 0.0;
+                    let mut total_weight = 0.0;
+                    for (direction, up) in sides.iter() {
+                        camera.set_view(position, position + *direction, *up)?;
+
+                        // The materials skip tone mapping and sRGB conversion, so the probes store linear colors
+                        self.context.set_hdr_output(true);
+                        let result = RenderTarget::new(
+                            &self.context,
+                            &mut color_texture,
+                            &mut depth_texture,
+                        )
+                        .and_then(|render_target| {
+                            render_target
+                                .write(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0), || {
+                                    render_pass(&camera, objects, lights)
+                                })
+                        });
+                        self.context.set_hdr_output(false);
+                        result?;
+                        let colors = color_texture.read_internal(viewport)?;
+
+                        RenderTarget::new(&self.context, &mut color_texture, &mut depth_texture)?
+                            .write(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0), || {
+                            for object in objects.iter() {
+                                object.render_with_material(
+                                    &BackFaceMaterial {},
+                                    &camera,
+                                    lights,
+                                )?;
+                            }
+                            Ok(())
+                        })?;
+                        let facing = color_texture.read_internal(viewport)?;
+
+                        // The pixels are ordered row by row starting with the bottom row
+                        let right = direction.cross(*up);
+                        for row in 0..cube_map_size {
+                            let v = 2.0 * (row as f32 + 0.5) / cube_map_size as f32 - 1.0;
+                            for column in 0..cube_map_size {
+                                let u = 2.0 * (column as f32 + 0.5) / cube_map_size as f32 - 1.0;
+                                // The solid angle of a pixel falls off with the cube of the distance to the center of the cube
+                                let weight = (1.0 + u * u + v * v).powf(-1.5);
+                                let i = 4 * (row * cube_map_size + column) as usize;
+                                sh.add_sample(
+                                    (direction + right * u + up * v).normalize(),
+                                    vec3(colors[i], colors[i + 1], colors[i + 2]),
+                                    weight,
+                                );
+                                back_faces += facing[i] * weight;
+                                total_weight += weight;
+                            }
+                        }
+                    }
+                    let scale = 4.0 * std::f32::consts::PI / total_weight;
+                    for coefficient in sh.coefficients.iter_mut() {
+                        *coefficient *= scale;
+                    }
+                    let index = self.index(x, y, z);
+                    self.probes[index] = sh;
+                    self.valid[index] = back_faces / total_weight < BACK_FACE_THRESHOLD;
+                }
+            }
+        }
+        self.update_texture()
+    }
+
+    fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        (x + self.resolution.0 * (y + self.resolution.1 * z)) as usize
+    }
+
+    fn update_texture(&mut self) -> ThreeDResult<()> {
+        let mut data = Vec::with_capacity(self.probes.len() * 36);
+        for (sh, valid) in self.probes.iter().zip(self.valid.iter()) {
+            for (i, c) in sh.irradiance_coefficients().iter().enumerate() {
+                data.extend_from_slice(&[c.x, c.y, c.z, if i == 0 && *valid { 1.0 } else { 0.0 }]);
+            }
+        }
+        self.texture.fill(&data)
+    }
+}
+
+impl Light for ProbeGrid {
+    fn shader_source(&self, i: u32) -> String {
+        format!(
+            "
+                uniform sampler2D probeGridData;
+                uniform vec3 probeGridMin;
+                uniform vec3 probeGridScale;
+                uniform vec3 probeGridResolution;
+                uniform float probeGridIntensity;
+
+                vec3 probe_irradiance(int probe, vec3 n)
+                {{
+                    vec4 c0 = texelFetch(probeGridData, ivec2(0, probe), 0);
+                    return 0.282095 * c0.rgb
+                        + 0.488603 * (texelFetch(probeGridData, ivec2(1, probe), 0).rgb * n.y
+                            + texelFetch(probeGridData, ivec2(2, probe), 0).rgb * n.z
+                            + texelFetch(probeGridData, ivec2(3, probe), 0).rgb * n.x)
+                        + 1.092548 * (texelFetch(probeGridData, ivec2(4, probe), 0).rgb * n.x * n.y
+                            + texelFetch(probeGridData, ivec2(5, probe), 0).rgb * n.y * n.z
+                            + texelFetch(probeGridData, ivec2(7, probe), 0).rgb * n.x * n.z)
+                        + 0.315392 * texelFetch(probeGridData, ivec2(6, probe), 0).rgb * (3.0 * n.z * n.z - 1.0)
+                        + 0.546274 * texelFetch(probeGridData, ivec2(8, probe), 0).rgb * (n.x * n.x - n.y * n.y);
+                }}
+
+                vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                {{
+                    ivec3 resolution = ivec3(probeGridResolution);
+                    vec3 grid_position = clamp((position - probeGridMin) * probeGridScale, vec3(0.0), vec3(resolution - 1));
+                    ivec3 base = min(ivec3(floor(grid_position)), max(resolution - 2, ivec3(0)));
+                    vec3 f = grid_position - vec3(base);
+
+                    // Trilinear interpolation of the 8 surrounding probes where invalid probes have zero weight
+                    vec3 irradiance = vec3(0.0);
+                    float total_weight = 0.0;
+                    for(int j = 0; j < 8; j++) {{
+                        ivec3 offset = ivec3(j & 1, (j >> 1) & 1, (j >> 2) & 1);
+                        ivec3 p = min(base + offset, resolution - 1);
+                        int probe = p.x + resolution.x * (p.y + resolution.y * p.z);
+                        vec3 w = mix(1.0 - f, f, vec3(offset));
+                        float weight = w.x * w.y * w.z * texelFetch(probeGridData, ivec2(0, probe), 0).a;
+                        if(weight > 0.0) {{
+                            irradiance += weight * probe_irradiance(probe, normal);
+                            total_weight += weight;
+                        }}
+                    }}
+                    if(total_weight > 0.0001) {{
+                        irradiance /= total_weight;
+                    }}
+                    return occlusion * probeGridIntensity * max(irradiance, vec3(0.0)) * mix(surface_color, vec3(0.0), metallic);
+                }}
+            ",
+            i
+        )
+    }
+
+    fn use_uniforms(&self, program: &Program, _i: u32) -> ThreeDResult<()> {
+        let size = self.aabb.size();
+        let scale = |extent: f32, n: u32| {
+            if n > 1 && extent > 0.0 {
+                (n - 1) as f32 / extent
+            } else {
+                0.0
+            }
+        };
+        program.use_texture("probeGridData", &self.texture)?;
+        program.use_uniform_vec3("probeGridMin", &self.aabb.min())?;
+        program.use_uniform_vec3(
+            "probeGridScale",
+            &vec3(
+                scale(size.x, self.resolution.0),
+                scale(size.y, self.resolution.1),
+                scale(size.z, self.resolution.2),
+            ),
+        )?;
+        program.use_uniform_vec3(
+            "probeGridResolution",
+            &vec3(
+                self.resolution.0 as f32,
+                self.resolution.1 as f32,
+                self.resolution.2 as f32,
+            ),
+        )?;
+        program.use_uniform_float(
+            "probeGridIntensity",
+            &(if self.enabled { self.intensity } else { 0.0 }),
+        )
+    }
+}
+
+// Outputs one for back faces and zero for front faces, used for finding probes inside geometry
+struct BackFaceMaterial {}
+
+impl Material for BackFaceMaterial {
+    fn fragment_shader_source(&self, _use_vertex_colors: bool, _lights: &Lights) -> String {
+        "layout (location = 0) out vec4 outColor;
+        void main()
+        {
+            outColor = vec4(gl_FrontFacing ? 0.0 : 1.0, 0.0, 0.0, 1.0);
+        }"
+        .to_string()
+    }
+    fn use_uniforms(
+        &self,
+        _program: &Program,
+        _camera: &Camera,
+        _lights: &Lights,
+    ) -> ThreeDResult<()> {
+        Ok(())
+    }
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+    fn is_transparent(&self) -> bool {
+        false
+    }
+}
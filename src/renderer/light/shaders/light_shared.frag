@@ -107,8 +107,38 @@ vec3 cooktorrance_specular(in float NdL, in float NdV, in float NdH, in vec3 spe
     return specular_fresnel * G * D / (4.0 * NdV * NdL);
 }
 
+#ifdef TOON
+uniform float toonBands;
+uniform float toonSpecular;
+#ifdef USE_TOON_RAMP
+uniform sampler2D toonRamp;
+#endif
+
+// Quantizes the diffuse lighting into bands or looks it up in a ramp texture
+vec3 toon_diffuse(float NdL)
+{
+#ifdef USE_TOON_RAMP
+    return texture(toonRamp, vec2(clamp(NdL, 0.01, 0.99), 0.5)).rgb;
+#else
+    return vec3(ceil(NdL * toonBands) / toonBands);
+#endif
+}
+#endif
+
 vec3 calculate_light(vec3 light_color, vec3 L, vec3 surface_color, vec3 V, vec3 N, float metallic, float roughness)
 {
+#ifdef TOON
+    // cel shading with a hard stepped specular highlight
+    float NdL = dot(N, L);
+    if (NdL <= 0.0)
+    {
+        return vec3(0.0);
+    }
+    vec3 H = normalize(L + V);
+    float k = 1.999 / max(roughness * roughness, 0.001);
+    float highlight = toonSpecular * step(0.5, pow(max(dot(N, H), 0.0), min(10000.0, k)));
+    return (toon_diffuse(NdL) * surface_color / PI + highlight) * light_color;
+#else
     // compute material reflectance
     float NdL = max(0.001, dot(N, L));
     float NdV = max(0.001, dot(N, V));
@@ -144,6 +174,7 @@ vec3 calculate_light(vec3 light_color, vec3 L, vec3 surface_color, vec3 V, vec3
     
     // final result
     return (diffuse + specular) * light_color * NdL;
+#endif
 }
 
 vec3 attenuate(vec3 light_color, Attenuation attenuation, float distance)
@@ -155,29 +186,46 @@ vec3 attenuate(vec3 light_color, Attenuation attenuation, float distance)
     return light_color / max(1.0, att);
 }
 
-float is_visible(sampler2D shadowMap, vec4 shadow_coord, vec2 offset)
-{
-    vec2 uv = (shadow_coord.xy + offset)/shadow_coord.w;
-    float true_distance = (shadow_coord.z - 0.005)/shadow_coord.w;
-    float shadow_cast_distance = texture(shadowMap, uv).x;
-    return uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0 || shadow_cast_distance > true_distance ? 1.0 : 0.0;
+const vec2 POISSON_DISK[16] = vec2[16](
+    vec2(-0.94201624, -0.39906216),
+    vec2(0.94558609, -0.76890725),
+    vec2(-0.094184101, -0.92938870),
+    vec2(0.34495938, 0.29387760),
+    vec2(-0.91588581, 0.45771432),
+    vec2(-0.81544232, -0.87912464),
+    vec2(-0.38277543, 0.27676845),
+    vec2(0.97484398, 0.75648379),
+    vec2(0.44323325, -0.97511554),
+    vec2(0.53742981, -0.47373420),
+    vec2(-0.26496911, -0.41893023),
+    vec2(0.79197514, 0.19090188),
+    vec2(-0.24188840, 0.99706507),
+    vec2(-0.81409955, 0.91437590),
+    vec2(0.19984126, 0.78641367),
+    vec2(0.14383161, -0.14100790)
+);
+
+// A random rotation of the Poisson disk for each position, which trades banding for noise
+// The range of distances along the ray from the origin in the given direction which is inside a signed distance field,
+// where the matrix transforms from world space to the texture coordinates of the field
+vec2 sdf_ray_range(mat4 world_to_texture, vec3 origin, vec3 direction)
+{
+    vec3 o = (world_to_texture * vec4(origin, 1.0)).xyz;
+    vec3 d = mat3(world_to_texture) * direction;
+    vec3 inv_d = 1.0 / mix(d, vec3(0.000001), lessThan(abs(d), vec3(0.000001)));
+    vec3 t0 = -o * inv_d;
+    vec3 t1 = (1.0 - o) * inv_d;
+    vec3 t_min = min(t0, t1);
+    vec3 t_max = max(t0, t1);
+    return vec2(max(max(t_min.x, t_min.y), t_min.z), min(min(t_max.x, t_max.y), t_max.z));
 }
 
-float calculate_shadow(sampler2D shadowMap, mat4 shadowMVP, vec3 position)
-{
-    vec4 shadow_coord = shadowMVP * vec4(position, 1.);
-    float visibility = 0.0;
-    vec2 poissonDisk[4] = vec2[](
-                                 vec2( -0.94201624, -0.39906216 ),
-                                 vec2( 0.94558609, -0.76890725 ),
-                                 vec2( -0.094184101, -0.92938870 ),
-                                 vec2( 0.34495938, 0.29387760 )
-                                 );
-    for (int i=0;i<4;i++)
-    {
-        visibility += is_visible(shadowMap, shadow_coord, poissonDisk[i] * 0.001f);
-    }
-    return visibility * 0.25;
+mat2 poisson_disk_rotation(vec3 position)
+{
+    float angle = 2.0 * PI * fract(sin(dot(position, vec3(12.9898, 78.233, 37.719))) * 43758.5453);
+    float c = cos(angle);
+    float s = sin(angle);
+    return mat2(c, s, -s, c);
 }
 
 vec3 ImportanceSampleGGX(vec2 Xi, vec3 N, float roughness)
@@ -0,0 +1,12 @@
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The light arriving at a white, rough and non-metallic surface, which is multiplied with the albedo when rendering
+    vec3 light = calculate_lighting(vec3(1.0), pos, normalize(nor), 0.0, 1.0, 1.0);
+    outColor = vec4(light, 1.0);
+}
@@ -0,0 +1,18 @@
+uniform mat4 modelMatrix;
+uniform mat4 normalMatrix;
+uniform vec2 jitter;
+
+in vec3 position;
+in vec3 normal;
+in vec2 uv_coordinates;
+
+out vec3 pos;
+out vec3 nor;
+
+void main()
+{
+    // The triangles are placed at their uv coordinates, offset by a fraction of a texel for each sample
+    pos = (modelMatrix * vec4(position, 1.0)).xyz;
+    nor = mat3(normalMatrix) * normal;
+    gl_Position = vec4(2.0 * uv_coordinates - 1.0 + jitter, 0.0, 1.0);
+}
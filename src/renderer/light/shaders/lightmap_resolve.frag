@@ -0,0 +1,10 @@
+uniform sampler2D accumulatedTexture;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The alpha value is the number of samples covering the texel, which is zero for texels outside the charts
+    vec4 sum = texelFetch(accumulatedTexture, ivec2(gl_FragCoord.xy), 0);
+    outColor = sum.a > 0.0 ? vec4(sum.rgb / sum.a, 1.0) : vec4(0.0);
+}
@@ -0,0 +1,109 @@
+
+uniform sampler2D packedLights;
+uniform int packedLightCount;
+
+// Each light is stored in one row of four texels:
+// 0: color multiplied by intensity, type (0 = directional, 1 = point, 2 = spot)
+// 1: position, cutoff angle
+// 2: direction, unused
+// 3: constant, linear and exponential attenuation, unused
+vec3 calculate_packed_light(int i, vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness)
+{
+    vec4 color_and_type = texelFetch(packedLights, ivec2(0, i), 0);
+    vec3 light_color = color_and_type.rgb;
+    if (max(light_color.r, max(light_color.g, light_color.b)) < 0.001)
+    {
+        return vec3(0.0, 0.0, 0.0);
+    }
+    vec3 direction = texelFetch(packedLights, ivec2(2, i), 0).xyz;
+    if (color_and_type.a < 0.5)
+    {
+        return calculate_light(light_color, -direction, surface_color, view_direction, normal, metallic, roughness);
+    }
+
+    vec4 position_and_cutoff = texelFetch(packedLights, ivec2(1, i), 0);
+    vec4 a = texelFetch(packedLights, ivec2(3, i), 0);
+    vec3 light_direction = position_and_cutoff.xyz - position;
+    float distance = length(light_direction);
+    light_direction = light_direction / distance;
+    light_color = attenuate(light_color, Attenuation(a.x, a.y, a.z, 0.0), distance);
+
+    if (color_and_type.a < 1.5)
+    {
+        return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
+    }
+    float angle = acos(dot(-light_direction, normalize(direction)));
+    float cutoff = position_and_cutoff.w;
+    if (angle < cutoff)
+    {
+        return calculate_light(light_color, light_direction, surface_color, view_direction, normal, 
+            metallic, roughness) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));
+    }
+    return vec3(0.0, 0.0, 0.0);
+}
+
+#ifdef USE_LIGHT_CLUSTERS
+// Each texel contains the offset into the light indices and the number of lights of a cluster,
+// the clusters are stored row by row and depth slice by depth slice
+uniform sampler2D lightClusters;
+// The indices of the packed lights of all clusters, four indices per texel and 64 texels per row
+uniform sampler2D lightClusterIndices;
+uniform int packedDirectionalLightCount;
+uniform vec3 lightClusterCounts;
+uniform vec4 lightClusterViewport;
+uniform vec2 lightClusterDepthRange;
+uniform mat4 lightClusterView;
+
+// Returns the offset into the light indices and the number of lights of the cluster containing the current fragment,
+// the depth slices are distributed logarithmically between the near and far plane
+vec2 light_cluster(vec3 position)
+{
+    vec2 tile = clamp((gl_FragCoord.xy - lightClusterViewport.xy) / lightClusterViewport.zw, 0.0, 0.9999) * lightClusterCounts.xy;
+    float depth = -(lightClusterView * vec4(position, 1.0)).z;
+    float slice = log(max(depth, lightClusterDepthRange.x) / lightClusterDepthRange.x) / log(lightClusterDepthRange.y / lightClusterDepthRange.x);
+    int z = int(clamp(slice, 0.0, 0.9999) * lightClusterCounts.z);
+    return texelFetch(lightClusters, ivec2(int(tile.x), int(tile.y) + z * int(lightClusterCounts.y)), 0).xy;
+}
+
+#ifdef LIGHT_CLUSTER_HEATMAP
+uniform float maxLightsPerCluster;
+
+vec3 light_cluster_heatmap(vec3 position)
+{
+    float count = light_cluster(position).y;
+    if (count < 0.5)
+    {
+        return vec3(0.05, 0.05, 0.05);
+    }
+    float t = clamp(count / maxLightsPerCluster, 0.0, 1.0);
+    return t < 0.5 ? mix(vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 0.0), 2.0 * t) : mix(vec3(0.0, 1.0, 0.0), vec3(1.0, 0.0, 0.0), 2.0 * t - 1.0);
+}
+#endif
+#endif
+
+vec3 calculate_packed_lighting(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+{
+    vec3 color = vec3(0.0, 0.0, 0.0);
+#ifdef USE_LIGHT_CLUSTERS
+    // The directional lights are first in the packed lights and affect all fragments
+    for (int i = 0; i < packedDirectionalLightCount; i++)
+    {
+        color += calculate_packed_light(i, surface_color, position, normal, view_direction, metallic, roughness);
+    }
+    vec2 cluster = light_cluster(position);
+    int offset = int(cluster.x);
+    int count = int(cluster.y);
+    for (int i = 0; i < count; i++)
+    {
+        int index = offset + i;
+        vec4 indices = texelFetch(lightClusterIndices, ivec2((index / 4) % 64, index / 256), 0);
+        color += calculate_packed_light(int(indices[index % 4]), surface_color, position, normal, view_direction, metallic, roughness);
+    }
+#else
+    for (int i = 0; i < packedLightCount; i++)
+    {
+        color += calculate_packed_light(i, surface_color, position, normal, view_direction, metallic, roughness);
+    }
+#endif
+    return color;
+}
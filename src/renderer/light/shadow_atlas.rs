@@ -0,0 +1,294 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::rc::Rc;
+
+///
+/// A single depth texture which contains the shadow maps of many [SpotLight]s as tiles, each with its own size.
+/// Compared to [SpotLight::generate_shadow_map], this avoids a texture per light and makes it possible to keep the shadows of many lights up to date,
+/// since [ShadowAtlas::update] only renders the shadow maps which are out of date and at most a given number of them each frame.
+///
+/// A shadow map is out of date when the position, direction or cutoff of the light has changed or when a geometry inside the cone of the light has changed,
+/// which is detected using [Geometry::change_count] and [Geometry::aabb].
+///
+pub struct ShadowAtlas {
+    context: Context,
+    size: u32,
+    texture: Rc<DepthTargetTexture2D>,
+    tiles: Vec<ShadowTile>,
+    frame: u64,
+}
+
+impl ShadowAtlas {
+    ///
+    /// Creates a new shadow atlas with a depth texture of the given size which is divided into tiles with the given sizes.
+    /// The tiles are square and the tile with index `i` contains the shadow map of the light with index `i` given to [ShadowAtlas::update].
+    ///
+    /// # Errors
+    /// Will return an error if the tiles do not fit into the texture.
+    ///
+    pub fn new(context: &Context, size: u32, tile_sizes: &[u32]) -> ThreeDResult<Self> {
+        let viewports = pack_tiles(size, tile_sizes).ok_or(CoreError::ShadowAtlasTooSmall(size))?;
+        Ok(Self {
+            context: context.clone(),
+            size,
+            texture: Rc::new(DepthTargetTexture2D::new(
+                context,
+                size,
+                size,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )?),
+            tiles: viewports
+                .into_iter()
+                .map(|viewport| ShadowTile {
+                    viewport,
+                    state: None,
+                    dirty_since: None,
+                })
+                .collect(),
+            frame: 0,
+        })
+    }
+
+    ///
+    /// Renders the shadow maps of the given lights which are out of date into their tiles, at most `budget` of them.
+    /// The shadow maps which have been out of date for the longest time are rendered first and lights closer to the camera are preferred among those,
+    /// so all shadow maps are eventually updated even if more than `budget` of them are out of date every frame.
+    /// Should be called every frame with the lights in the same order and the geometries which cast shadows, also in the same order,
+    /// and the lights should not be given to other shadow atlases or generate their own shadow map in the meantime.
+    ///
+    /// # Errors
+    /// Will return an error if more lights than tiles are given.
+    ///
+    pub fn update(
+        &mut self,
+        lights: &mut [SpotLight],
+        geometries: &[impl Geometry],
+        camera: &Camera,
+        budget: usize,
+    ) -> ThreeDResult<()> {
+        if lights.len() > self.tiles.len() {
+            Err(CoreError::TooManyShadowAtlasLights(
+                lights.len(),
+                self.tiles.len(),
+            ))?;
+        }
+        self.frame += 1;
+        let mut states = Vec::with_capacity(lights.len());
+        for (light, tile) in lights.iter().zip(self.tiles.iter_mut()) {
+            let state = ShadowTileState::new(light, geometries);
+            if light.has_atlas_shadow_map(&self.texture) && tile.state.as_ref() == Some(&state) {
+                tile.dirty_since = None;
+            } else if tile.dirty_since.is_none() {
+                tile.dirty_since = Some(self.frame);
+            }
+            states.push(state);
+        }
+
+        let mut dirty = (0..lights.len())
+            .filter(|i| self.tiles[*i].dirty_since.is_some())
+            .collect::<Vec<_>>();
+        dirty.sort_by(|a, b| {
+            let distance_a = camera.position().distance2(lights[*a].position());
+            let distance_b = camera.position().distance2(lights[*b].position());
+            self.tiles[*a]
+                .dirty_since
+                .cmp(&self.tiles[*b].dirty_since)
+                .then(
+                    distance_a
+                        .partial_cmp(&distance_b)
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+        });
+        dirty.truncate(budget);
+        if dirty.is_empty() {
+            return Ok(());
+        }
+
+        let depth_material = DepthMaterial {
+            render_states: RenderStates {
+                write_mask: WriteMask::DEPTH,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut shadow_cameras = Vec::with_capacity(dirty.len());
+        // Shadow maps are always rendered in the standard depth mode, since the shadow sampling expects the standard depth values
+        self.context.with_standard_depth_mode(|| {
+            RenderTarget::<u8>::new_depth_internal(&self.context, &self.texture)?.write(
+                ClearState::none(),
+                || {
+                    for i in dirty.iter() {
+                        let viewport = self.tiles[*i].viewport;
+                        let shadow_camera =
+                            states[*i].shadow_camera(&lights[*i], viewport, geometries)?;
+
+                        // Clear only the tile since the rest of the texture contains the shadow maps of the other lights
+                        self.context
+                            .effect("void main() { gl_FragDepth = 1.0; }", |effect| {
+                                effect.apply(
+                                    RenderStates {
+                                        write_mask: WriteMask::DEPTH,
+                                        depth_test: DepthTest::Always,
+                                        cull: Cull::None,
+                                        ..Default::default()
+                                    },
+                                    viewport,
+                                )
+                            })?;
+                        for geometry in geometries
+                            .iter()
+                            .filter(|g| shadow_camera.in_frustum(&g.aabb()))
+                        {
+                            geometry.render_with_material(
+                                &depth_material,
+                                &shadow_camera,
+                                &Lights::default(),
+                            )?;
+                        }
+                        shadow_cameras.push(shadow_camera);
+                    }
+                    Ok(())
+                },
+            )
+        })?;
+
+        let size = self.size as f32;
+        for (i, shadow_camera) in dirty.into_iter().zip(shadow_cameras) {
+            let viewport = self.tiles[i].viewport;
+            let rect = vec4(
+                viewport.x as f32 / size,
+                viewport.y as f32 / size,
+                viewport.width as f32 / size,
+                viewport.height as f32 / size,
+            );
+            lights[i].set_atlas_shadow_map(&self.texture, rect, &shadow_camera)?;
+            self.tiles[i].state = Some(states[i].clone());
+            self.tiles[i].dirty_since = None;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the number of shadow maps which were out of date after the last call to [ShadowAtlas::update],
+    /// ie. the shadow maps which were not updated because the budget was used.
+    ///
+    pub fn out_of_date_count(&self) -> usize {
+        self.tiles
+            .iter()
+            .filter(|t| t.dirty_since.is_some())
+            .count()
+    }
+
+    ///
+    /// Returns the viewport of the tile with the given index in the texture.
+    ///
+    pub fn tile(&self, index: usize) -> Option<Viewport> {
+        self.tiles.get(index).map(|t| t.viewport)
+    }
+
+    ///
+    /// Returns the depth texture containing all of the shadow maps.
+    ///
+    pub fn texture(&self) -> &DepthTargetTexture2D {
+        &self.texture
+    }
+}
+
+struct ShadowTile {
+    viewport: Viewport,
+    state: Option<ShadowTileState>,
+    dirty_since: Option<u64>,
+}
+
+// Everything the shadow map of a light depends on, if this is unchanged since the shadow map was rendered, the shadow map is up to date
+#[derive(Clone, PartialEq)]
+struct ShadowTileState {
+    position: Vec3,
+    direction: Vec3,
+    cutoff: f32,
+    // The index, change count and bounding box of each geometry inside the cone of the light
+    casters: Vec<(usize, u64, Vec3, Vec3)>,
+}
+
+impl ShadowTileState {
+    fn new(light: &SpotLight, geometries: &[impl Geometry]) -> Self {
+        let position = light.position();
+        let direction = light.direction();
+        let cutoff = light.cutoff().0;
+        // The half angle of a cone which contains the square frustum of the shadow camera
+        let (sin, cos) = (2.0f32.sqrt() * (0.5 * cutoff).tan()).atan().sin_cos();
+        let casters = geometries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, geometry)| {
+                let aabb = geometry.aabb();
+                if aabb.is_empty() || aabb.is_infinite() {
+                    return None;
+                }
+                let radius = 0.5 * aabb.size().magnitude();
+                let v = aabb.center() - position;
+                let along = v.dot(direction);
+                let across = (v.magnitude2() - along * along).max(0.0).sqrt();
+                if along < -radius || across * cos - along * sin > radius {
+                    None
+                } else {
+                    Some((i, geometry.change_count(), aabb.min(), aabb.max()))
+                }
+            })
+            .collect();
+        Self {
+            position,
+            direction,
+            cutoff,
+            casters,
+        }
+    }
+
+    // The camera for rendering the shadow map with the near and far planes fitted to the geometries inside the cone of the light
+    fn shadow_camera(
+        &self,
+        light: &SpotLight,
+        viewport: Viewport,
+        geometries: &[impl Geometry],
+    ) -> ThreeDResult<Camera> {
+        let mut z_far = 0.0f32;
+        let mut z_near = f32::MAX;
+        for (i, _, _, _) in self.casters.iter() {
+            let aabb = geometries[*i].aabb();
+            z_far = z_far.max(aabb.distance_max(&self.position));
+            z_near = z_near.min(aabb.distance(&self.position));
+        }
+        let z_near = z_near.max(0.01);
+        light.shadow_camera(viewport, z_near, z_far.max(2.0 * z_near))
+    }
+}
+
+// Packs the square tiles into shelves, starting with the largest tiles, and returns the viewport of each tile or none if the tiles do not fit
+fn pack_tiles(size: u32, tile_sizes: &[u32]) -> Option<Vec<Viewport>> {
+    let mut order = (0..tile_sizes.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| tile_sizes[*b].cmp(&tile_sizes[*a]));
+    let mut viewports = vec![Viewport::new_at_origo(0, 0); tile_sizes.len()];
+    let (mut x, mut y, mut shelf_height) = (0, 0, 0);
+    for i in order {
+        let tile_size = tile_sizes[i];
+        if x + tile_size > size {
+            x = 0;
+            y += shelf_height;
+            shelf_height = 0;
+        }
+        if x + tile_size > size || y + tile_size > size {
+            return None;
+        }
+        viewports[i] = Viewport {
+            x: x as i32,
+            y: y as i32,
+            width: tile_size,
+            height: tile_size,
+        };
+        x += tile_size;
+        shelf_height = shelf_height.max(tile_size);
+    }
+    Some(viewports)
+}
@@ -0,0 +1,243 @@
+use crate::core::*;
+use std::rc::Rc;
+
+///
+/// A signed distance field of the static geometry of a scene stored in a 3D texture, usually computed using [CPUMesh::compute_sdf].
+/// Can be used for soft shadows from [DirectionalLight](crate::DirectionalLight)s and [PointLight](crate::PointLight)s without shadow maps, see [SdfShadows],
+/// and for ambient occlusion of an [AmbientLight](crate::AmbientLight), see [SdfAmbientOcclusion].
+/// Geometry outside the bounds of the field does not occlude.
+///
+pub struct SignedDistanceField {
+    texture: Texture3D<f32>,
+    bounds: AxisAlignedBoundingBox,
+}
+
+impl SignedDistanceField {
+    ///
+    /// Creates a signed distance field from the given distances which are evenly spaced in the given bounds,
+    /// ie. the texture coordinates `(0, 0, 0)` and `(1, 1, 1)` correspond to the minimum and maximum corner of the bounds.
+    /// The distances are interpolated linearly if the device supports linear interpolation of floating point textures, see [Capabilities::float_texture_linear].
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_texture: &CPUTexture3D<f32>,
+        bounds: AxisAlignedBoundingBox,
+    ) -> ThreeDResult<Self> {
+        let interpolation = if context.capabilities().float_texture_linear {
+            Interpolation::Linear
+        } else {
+            Interpolation::Nearest
+        };
+        let mut texture = Texture3D::new_empty(
+            context,
+            cpu_texture.width,
+            cpu_texture.height,
+            cpu_texture.depth,
+            interpolation,
+            interpolation,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::R,
+        )?;
+        texture.fill(&cpu_texture.data)?;
+        Ok(Self { texture, bounds })
+    }
+
+    ///
+    /// Returns the bounds of the field in world space.
+    ///
+    pub fn bounds(&self) -> AxisAlignedBoundingBox {
+        self.bounds
+    }
+
+    ///
+    /// Returns the 3D texture containing the distances.
+    ///
+    pub fn texture(&self) -> &Texture3D<f32> {
+        &self.texture
+    }
+
+    ///
+    /// Returns the matrix which transforms a position in world space to the texture coordinates of the field.
+    ///
+    pub fn world_to_texture(&self) -> Mat4 {
+        let size = self.bounds.size();
+        Mat4::from_nonuniform_scale(
+            1.0 / size.x.max(0.0001),
+            1.0 / size.y.max(0.0001),
+            1.0 / size.z.max(0.0001),
+        ) * Mat4::from_translation(-self.bounds.min())
+    }
+
+    ///
+    /// Returns the largest distance between the centers of two neighbouring texels.
+    ///
+    fn texel_size(&self) -> f32 {
+        let size = self.bounds.size();
+        (size.x / self.texture.width() as f32)
+            .max(size.y / self.texture.height() as f32)
+            .max(size.z / self.texture.depth() as f32)
+    }
+
+    fn use_uniforms(&self, program: &Program, prefix: &str, suffix: &str) -> ThreeDResult<()> {
+        program.use_texture_3d(&format!("{}Map{}", prefix, suffix), &self.texture)?;
+        program.use_uniform_mat4(
+            &format!("{}WorldToTexture{}", prefix, suffix),
+            &self.world_to_texture(),
+        )
+    }
+}
+
+///
+/// Soft shadows from a [DirectionalLight](crate::DirectionalLight) or [PointLight](crate::PointLight) computed by marching a cone from the surface towards the light through a [SignedDistanceField],
+/// see [DirectionalLight::set_sdf_shadows](crate::DirectionalLight::set_sdf_shadows) and [PointLight::set_sdf_shadows](crate::PointLight::set_sdf_shadows).
+/// Compared to shadow maps, the shadows have soft edges and no resolution depending on the view, but only the geometry in the field casts shadows,
+/// so the field must be recomputed when the geometry changes.
+///
+#[derive(Clone)]
+pub struct SdfShadows {
+    /// The signed distance field of the geometry which casts shadows.
+    pub sdf: Rc<SignedDistanceField>,
+    /// The width of the cone relative to the distance along the cone, larger values give softer shadows.
+    pub softness: f32,
+    /// The maximum number of steps towards the light, more steps are more expensive but avoid missing thin occluders.
+    pub steps: u32,
+    /// The maximum distance towards the light where geometry casts shadows, for point lights it is also limited by the distance to the light.
+    pub max_distance: f32,
+    /// The distance the start of the cone is moved along the surface normal, which avoids that a surface shadows itself.
+    pub bias: f32,
+}
+
+impl SdfShadows {
+    ///
+    /// Creates soft shadows using the given signed distance field, where the maximum distance is the diagonal of the bounds of the field
+    /// and the bias is one and a half texel of the field.
+    ///
+    pub fn new(sdf: Rc<SignedDistanceField>) -> Self {
+        Self {
+            softness: 0.05,
+            steps: 48,
+            max_distance: sdf.bounds().size().magnitude(),
+            bias: 1.5 * sdf.texel_size(),
+            sdf,
+        }
+    }
+
+    ///
+    /// Returns the shader source for the `calculate_sdf_shadow{i}` function which returns the visibility of the light
+    /// with the given index in the direction and at the distance given to the function.
+    ///
+    pub(crate) fn shader_source(&self, i: u32) -> String {
+        format!(
+            "
+                uniform sampler3D sdfShadowMap{i};
+                uniform mat4 sdfShadowWorldToTexture{i};
+                uniform float sdfShadowSoftness{i};
+                uniform int sdfShadowSteps{i};
+                uniform float sdfShadowMaxDistance{i};
+                uniform float sdfShadowBias{i};
+                float calculate_sdf_shadow{i}(vec3 position, vec3 normal, vec3 light_direction, float light_distance)
+                {{
+                    vec3 origin = position + normal * sdfShadowBias{i};
+                    // Only the part of the ray inside the field is marched
+                    vec2 range = sdf_ray_range(sdfShadowWorldToTexture{i}, origin, light_direction);
+                    float t = max(range.x, 0.0);
+                    float t_max = min(min(sdfShadowMaxDistance{i}, light_distance), range.y);
+                    float visibility = 1.0;
+                    for(int j = 0; j < sdfShadowSteps{i} && t < t_max; j++) {{
+                        vec3 uvw = (sdfShadowWorldToTexture{i} * vec4(origin + t * light_direction, 1.0)).xyz;
+                        float d = texture(sdfShadowMap{i}, uvw).r;
+                        visibility = min(visibility, d / (sdfShadowSoftness{i} * max(t, sdfShadowBias{i})));
+                        if(visibility < 0.001) {{
+                            return 0.0;
+                        }}
+                        t += max(d, 0.5 * sdfShadowBias{i});
+                    }}
+                    return smoothstep(0.0, 1.0, visibility);
+                }}
+            ",
+            i = i
+        )
+    }
+
+    pub(crate) fn use_uniforms(&self, program: &Program, i: u32) -> ThreeDResult<()> {
+        self.sdf
+            .use_uniforms(program, "sdfShadow", &i.to_string())?;
+        program.use_uniform_float(
+            &format!("sdfShadowSoftness{}", i),
+            &self.softness.max(0.0001),
+        )?;
+        program.use_uniform_int(&format!("sdfShadowSteps{}", i), &(self.steps as i32))?;
+        program.use_uniform_float(&format!("sdfShadowMaxDistance{}", i), &self.max_distance)?;
+        program.use_uniform_float(&format!("sdfShadowBias{}", i), &self.bias.max(0.0001))
+    }
+}
+
+///
+/// Ambient occlusion of an [AmbientLight](crate::AmbientLight) computed by sampling a [SignedDistanceField] at a few distances along the surface normal,
+/// where the surface is occluded if the distance to the closest geometry is less than the distance from the surface, see [AmbientLight::sdf_ao](crate::AmbientLight::sdf_ao).
+///
+#[derive(Clone)]
+pub struct SdfAmbientOcclusion {
+    /// The signed distance field of the geometry which occludes the ambient light.
+    pub sdf: Rc<SignedDistanceField>,
+    /// The maximum distance from the surface where geometry occludes the ambient light.
+    pub distance: f32,
+    /// The number of samples along the surface normal.
+    pub steps: u32,
+    /// The strength of the occlusion, where zero means no occlusion.
+    pub strength: f32,
+}
+
+impl SdfAmbientOcclusion {
+    ///
+    /// Creates ambient occlusion using the given signed distance field, where the maximum distance is four texels of the field.
+    ///
+    pub fn new(sdf: Rc<SignedDistanceField>) -> Self {
+        Self {
+            distance: 4.0 * sdf.texel_size(),
+            steps: 5,
+            strength: 1.0,
+            sdf,
+        }
+    }
+
+    ///
+    /// Returns the shader source for the `sdf_ao` function which returns the ambient occlusion at the given position with the given normal.
+    ///
+    pub(crate) fn shader_source(&self) -> String {
+        "
+            uniform sampler3D sdfAoMap;
+            uniform mat4 sdfAoWorldToTexture;
+            uniform float sdfAoDistance;
+            uniform int sdfAoSteps;
+            uniform float sdfAoStrength;
+            float sdf_ao(vec3 position, vec3 normal)
+            {
+                float occlusion = 0.0;
+                float weight = 1.0;
+                float total_weight = 0.0;
+                for(int j = 1; j <= sdfAoSteps; j++) {
+                    float h = sdfAoDistance * float(j) / float(sdfAoSteps);
+                    vec3 uvw = (sdfAoWorldToTexture * vec4(position + h * normal, 1.0)).xyz;
+                    // Samples outside the field are not occluded
+                    if(all(greaterThanEqual(uvw, vec3(0.0))) && all(lessThanEqual(uvw, vec3(1.0)))) {
+                        occlusion += weight * clamp((h - texture(sdfAoMap, uvw).r) / h, 0.0, 1.0);
+                    }
+                    total_weight += weight;
+                    weight *= 0.7;
+                }
+                return clamp(1.0 - sdfAoStrength * occlusion / max(total_weight, 0.0001), 0.0, 1.0);
+            }
+        "
+        .to_string()
+    }
+
+    pub(crate) fn use_uniforms(&self, program: &Program) -> ThreeDResult<()> {
+        self.sdf.use_uniforms(program, "sdfAo", "")?;
+        program.use_uniform_float("sdfAoDistance", &self.distance.max(0.0001))?;
+        program.use_uniform_int("sdfAoSteps", &(self.steps.max(1) as i32))?;
+        program.use_uniform_float("sdfAoStrength", &self.strength)
+    }
+}
@@ -1,15 +1,25 @@
 use crate::core::*;
 use crate::renderer::light::*;
 use crate::renderer::*;
+use std::rc::Rc;
 
 ///
 /// A light which shines from the given position and in the given direction.
-/// The light will cast shadows if you [generate a shadow map](SpotLight::generate_shadow_map).
+/// The light will cast shadows if you [generate a shadow map](SpotLight::generate_shadow_map)
+/// or if the shadow map is generated in a [ShadowAtlas] together with the shadow maps of other spot lights.
 ///
 pub struct SpotLight {
     context: Context,
     light_buffer: UniformBuffer,
-    shadow_texture: Option<DepthTargetTexture2D>,
+    intensity: f32,
+    enabled: bool,
+    shadow_enabled: bool,
+    shadow_texture: Option<Rc<DepthTargetTexture2D>>,
+    shadow_rect: Vec4,
+    shadow_depth_range: (f32, f32),
+    shadow_quality: ShadowQuality,
+    shadow_depth_bias: f32,
+    shadow_normal_offset_bias: f32,
 }
 
 impl SpotLight {
@@ -28,9 +38,18 @@ impl SpotLight {
         let mut light = SpotLight {
             context: context.clone(),
             light_buffer: UniformBuffer::new(context, &uniform_sizes)?,
+            intensity,
+            enabled: true,
+            shadow_enabled: true,
             shadow_texture: None,
+            shadow_rect: vec4(0.0, 0.0, 1.0, 1.0),
+            shadow_depth_range: (0.0, 0.0),
+            shadow_quality: ShadowQuality::default(),
+            shadow_depth_bias: 0.005,
+            shadow_normal_offset_bias: 0.0,
         };
         light.set_intensity(intensity);
+        light.set_shadow_enabled(true);
         light.set_color(color);
         light.set_cutoff(cutoff);
         light.set_direction(direction);
@@ -53,11 +72,49 @@ impl SpotLight {
     }
 
     pub fn set_intensity(&mut self, intensity: f32) {
-        self.light_buffer.update(1, &[intensity]).unwrap();
+        self.intensity = intensity;
+        self.light_buffer
+            .update(1, &[if self.enabled { intensity } else { 0.0 }])
+            .unwrap();
     }
 
     pub fn intensity(&self) -> f32 {
-        self.light_buffer.get(1).unwrap()[0]
+        self.intensity
+    }
+
+    ///
+    /// Enables or disables the light. A disabled light does not contribute to the lighting, but stays in the shader,
+    /// so toggling a light, for example a flashlight, does not require a shader recompilation.
+    ///
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.set_intensity(self.intensity);
+    }
+
+    ///
+    /// Returns whether or not the light is enabled, see [SpotLight::set_enabled].
+    ///
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    ///
+    /// Enables or disables the shadows of the light while keeping the [shadow map](SpotLight::shadow_map),
+    /// so shadows can be paused without a shader recompilation and resumed without generating the shadow map again.
+    /// The shadows are enabled by default, but the light only casts shadows if it has a shadow map.
+    ///
+    pub fn set_shadow_enabled(&mut self, shadow_enabled: bool) {
+        self.shadow_enabled = shadow_enabled;
+        self.light_buffer
+            .update(9, &[if shadow_enabled { 1.0 } else { 0.0 }])
+            .unwrap();
+    }
+
+    ///
+    /// Returns whether or not the shadows are enabled, see [SpotLight::set_shadow_enabled].
+    ///
+    pub fn is_shadow_enabled(&self) -> bool {
+        self.shadow_enabled
     }
 
     pub fn set_attenuation(&mut self, constant: f32, linear: f32, exponential: f32) {
@@ -104,7 +161,6 @@ impl SpotLight {
 
     pub fn clear_shadow_map(&mut self) {
         self.shadow_texture = None;
-        self.light_buffer.update(9, &[0.0]).unwrap();
     }
 
     pub fn generate_shadow_map(
@@ -113,9 +169,6 @@ impl SpotLight {
         geometries: &[impl Geometry],
     ) -> ThreeDResult<()> {
         let position = self.position();
-        let direction = self.direction();
-        let up = compute_up_direction(direction);
-
         let viewport = Viewport::new_at_origo(texture_size, texture_size);
 
         let mut z_far = 0.0f32;
@@ -128,16 +181,7 @@ impl SpotLight {
             }
         }
 
-        let shadow_camera = Camera::new_perspective(
-            &self.context,
-            viewport,
-            position,
-            position + direction,
-            up,
-            self.cutoff(),
-            z_near.max(0.01),
-            z_far,
-        )?;
+        let shadow_camera = self.shadow_camera(viewport, z_near.max(0.01), z_far)?;
         self.light_buffer
             .update(10, &shadow_matrix(&shadow_camera).as_array())?;
 
@@ -156,26 +200,145 @@ impl SpotLight {
             },
             ..Default::default()
         };
-        shadow_texture.write(Some(1.0), || {
-            for geometry in geometries
-                .iter()
-                .filter(|g| shadow_camera.in_frustum(&g.aabb()))
-            {
-                geometry.render_with_material(
-                    &depth_material,
-                    &shadow_camera,
-                    &Lights::default(),
-                )?;
-            }
-            Ok(())
+        // Shadow maps are always rendered in the standard depth mode, since the shadow sampling expects the standard depth values
+        self.context.with_standard_depth_mode(|| {
+            shadow_texture.write(Some(1.0), || {
+                for geometry in geometries
+                    .iter()
+                    .filter(|g| shadow_camera.in_frustum(&g.aabb()))
+                {
+                    geometry.render_with_material(
+                        &depth_material,
+                        &shadow_camera,
+                        &Lights::default(),
+                    )?;
+                }
+                Ok(())
+            })
         })?;
-        self.shadow_texture = Some(shadow_texture);
-        self.light_buffer.update(9, &[1.0])?;
+        self.shadow_texture = Some(Rc::new(shadow_texture));
+        self.shadow_rect = vec4(0.0, 0.0, 1.0, 1.0);
+        self.shadow_depth_range = (shadow_camera.z_near(), shadow_camera.z_far());
+        Ok(())
+    }
+
+    // The camera which looks from the light in the direction of the light and is used for rendering the shadow map
+    pub(crate) fn shadow_camera(
+        &self,
+        viewport: Viewport,
+        z_near: f32,
+        z_far: f32,
+    ) -> ThreeDResult<Camera> {
+        let position = self.position();
+        let direction = self.direction();
+        Camera::new_perspective(
+            &self.context,
+            viewport,
+            position,
+            position + direction,
+            compute_up_direction(direction),
+            self.cutoff(),
+            z_near,
+            z_far,
+        )
+    }
+
+    // Uses the tile with the given rectangle in the texture of a shadow atlas, which is rendered with the given camera, as the shadow map
+    pub(crate) fn set_atlas_shadow_map(
+        &mut self,
+        texture: &Rc<DepthTargetTexture2D>,
+        rect: Vec4,
+        shadow_camera: &Camera,
+    ) -> ThreeDResult<()> {
+        self.light_buffer
+            .update(10, &shadow_matrix(shadow_camera).as_array())?;
+        self.shadow_texture = Some(texture.clone());
+        self.shadow_rect = rect;
+        self.shadow_depth_range = (shadow_camera.z_near(), shadow_camera.z_far());
         Ok(())
     }
 
+    // Whether or not the shadow map is a tile in the given shadow atlas texture
+    pub(crate) fn has_atlas_shadow_map(&self, texture: &Rc<DepthTargetTexture2D>) -> bool {
+        self.shadow_texture
+            .as_ref()
+            .map(|t| Rc::ptr_eq(t, texture))
+            .unwrap_or(false)
+    }
+
+    ///
+    /// Sets how the shadow map is sampled, see [ShadowQuality].
+    ///
+    pub fn set_shadow_quality(&mut self, shadow_quality: ShadowQuality) {
+        self.shadow_quality = shadow_quality;
+    }
+
+    ///
+    /// Returns how the shadow map is sampled.
+    ///
+    pub fn shadow_quality(&self) -> ShadowQuality {
+        self.shadow_quality
+    }
+
+    ///
+    /// Sets the biases used to avoid shadow acne, ie. a surface shadowing itself.
+    /// The depth bias is subtracted from the depth of the surface in the shadow map (which is in the range `[0, 1]`)
+    /// and the normal offset bias is the distance the surface position is moved along the normal before looking it up in the shadow map.
+    ///
+    pub fn set_shadow_bias(&mut self, depth_bias: f32, normal_offset_bias: f32) {
+        self.shadow_depth_bias = depth_bias;
+        self.shadow_normal_offset_bias = normal_offset_bias;
+    }
+
+    ///
+    /// Returns the depth bias and the normal offset bias, see [SpotLight::set_shadow_bias].
+    ///
+    pub fn shadow_bias(&self) -> (f32, f32) {
+        (self.shadow_depth_bias, self.shadow_normal_offset_bias)
+    }
+
+    ///
+    /// Returns the depth texture containing the shadow map, if one is generated, which can for example be shown using a [DebugTextureQuad] when tuning the [shadow bias](SpotLight::set_shadow_bias).
+    /// If the shadow map is a tile in a [ShadowAtlas], this is the texture of the atlas and the tile is given by [SpotLight::shadow_rect].
+    /// The depth is not linear in the distance from the light, since the shadow map is rendered using a perspective projection with the [SpotLight::shadow_depth_range].
+    ///
     pub fn shadow_map(&self) -> Option<&DepthTargetTexture2D> {
-        self.shadow_texture.as_ref()
+        self.shadow_texture.as_deref()
+    }
+
+    ///
+    /// Returns the distance to the near and far plane of the perspective projection used for rendering the [SpotLight::shadow_map],
+    /// which is needed for converting the depth in the shadow map to a distance, for example using [DebugTextureQuad::depth_range].
+    /// Only valid if the light has a shadow map.
+    ///
+    pub fn shadow_depth_range(&self) -> (f32, f32) {
+        self.shadow_depth_range
+    }
+
+    ///
+    /// Returns the matrix which transforms a position in world space to the texture coordinates (xy) and depth (z) in the [SpotLight::shadow_map],
+    /// which is only valid if the light has a shadow map.
+    /// If the shadow map is a tile in a [ShadowAtlas], the texture coordinates are relative to the [SpotLight::shadow_rect].
+    ///
+    pub fn shadow_matrix(&self) -> Mat4 {
+        mat4_from_slice(self.light_buffer.get(10).unwrap())
+    }
+
+    ///
+    /// Returns the eight corners in world space of the frustum which is covered by the [SpotLight::shadow_map], for example to visualize it using lines,
+    /// which is only valid if the light has a shadow map.
+    /// The index of a corner is `x + 2y + 4z` where `x` and `y` are the texture coordinates relative to the [SpotLight::shadow_rect] and `z` the depth of the corner in the shadow map, which are either 0 or 1.
+    ///
+    pub fn shadow_frustum_corners(&self) -> [Vec3; 8] {
+        shadow_frustum_corners(self.shadow_matrix())
+    }
+
+    ///
+    /// Returns the offset (xy) and size (zw) of the shadow map in the texture returned by [SpotLight::shadow_map] in texture coordinates,
+    /// which is `(0, 0, 1, 1)` unless the shadow map is a tile in a [ShadowAtlas].
+    ///
+    pub fn shadow_rect(&self) -> Vec4 {
+        self.shadow_rect
     }
 
     pub fn buffer(&self) -> &UniformBuffer {
@@ -185,9 +348,20 @@ impl SpotLight {
 
 impl Light for SpotLight {
     fn shader_source(&self, i: u32) -> String {
+        let (shadow_source, shadow_call) = if self.shadow_map().is_some() {
+            (
+                self.shadow_quality.shader_source(i),
+                format!(
+                    "if (shadowEnabled{} > 0.5) {{ result *= calculate_shadow{}(shadowMVP{}, position, normal); }}",
+                    i, i, i
+                ),
+            )
+        } else {
+            (String::new(), String::new())
+        };
         format!(
         "
-            uniform sampler2D shadowMap{};
+            {}
             layout (std140) uniform LightUniform{}
             {{
                 BaseLight base{};
@@ -214,9 +388,7 @@ impl Light for SpotLight {
                         vec3 light_color = attenuate(base{}.intensity * base{}.color, attenuation{}, distance);
                         result = calculate_light(light_color, light_direction, surface_color, view_direction, normal, 
                             metallic, roughness) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));
-                        if(shadowEnabled{} > 0.5) {{
-                            result *= calculate_shadow(shadowMap{}, shadowMVP{}, position);
-                        }}
+                        {}
                     }}
                     return result;
                 }}
@@ -225,16 +397,94 @@ impl Light for SpotLight {
                 }}
             }}
         
-        ", i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i)
+        ", shadow_source, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, shadow_call)
     }
     fn use_uniforms(&self, program: &Program, i: u32) -> ThreeDResult<()> {
         if let Some(tex) = self.shadow_map() {
-            program.use_texture(&format!("shadowMap{}", i), tex)?;
-        } else {
-            self.context
-                .use_texture_dummy(&program, &format!("shadowMap{}", i))?;
+            self.shadow_quality.use_uniforms(
+                program,
+                i,
+                tex,
+                self.shadow_rect,
+                self.shadow_depth_bias,
+                self.shadow_normal_offset_bias,
+            )?;
         }
         program.use_uniform_block(&format!("LightUniform{}", i), self.buffer());
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "glutin-window", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::renderer::test_utils;
+
+    fn render(context: &Context, models: &[&dyn Object], lights: &Lights) -> Vec<u8> {
+        test_utils::render(context, 32, vec3(0.0, 4.0, 4.0), models, lights).unwrap()
+    }
+
+    // Toggling a spot light or its shadow only changes uniforms, so no programs are created after the first frame
+    #[test]
+    #[ignore = "requires a graphics context"]
+    fn toggling_does_not_compile_programs() {
+        let context = Context::new().unwrap();
+        let material = PhysicalMaterial {
+            albedo: Color::new_opaque(200, 200, 200),
+            ..Default::default()
+        };
+        let mut plane =
+            Model::new_with_material(&context, &CPUMesh::square(), material.clone()).unwrap();
+        plane.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(2.0));
+        let mut cube = Model::new_with_material(&context, &CPUMesh::cube(), material).unwrap();
+        cube.set_transformation(
+            Mat4::from_translation(vec3(0.0, 0.5, 0.0)) * Mat4::from_scale(0.3),
+        );
+
+        let mut spot = SpotLight::new(
+            &context,
+            2.0,
+            Color::WHITE,
+            &vec3(0.0, 3.0, 0.0),
+            &vec3(0.0, -1.0, 0.0),
+            degrees(40.0),
+            0.1,
+            0.001,
+            0.0001,
+        )
+        .unwrap();
+        spot.generate_shadow_map(256, &[&plane, &cube]).unwrap();
+        let mut lights = Lights {
+            ambient: Some(AmbientLight {
+                intensity: 0.1,
+                ..Default::default()
+            }),
+            spot: vec![spot],
+            ..Default::default()
+        };
+        let models: [&dyn Object; 2] = [&plane, &cube];
+
+        let enabled = render(&context, &models, &lights);
+        let statistics = context.program_cache_statistics();
+        for frame in 1..=1000 {
+            lights.spot[0].set_enabled(frame % 2 == 0);
+            lights.spot[0].set_shadow_enabled(frame % 3 != 0);
+            render(&context, &models, &lights);
+        }
+        let after = context.program_cache_statistics();
+        assert_eq!(after.compilations, statistics.compilations);
+        assert_eq!(after.misses, statistics.misses);
+
+        // The toggles take effect
+        lights.spot[0].set_enabled(false);
+        let disabled = render(&context, &models, &lights);
+        assert!(enabled != disabled);
+        lights.spot[0].set_enabled(true);
+        lights.spot[0].set_shadow_enabled(true);
+        assert!(enabled == render(&context, &models, &lights));
+        assert_eq!(
+            context.program_cache_statistics().compilations,
+            statistics.compilations
+        );
+    }
+}
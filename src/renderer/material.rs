@@ -6,12 +6,16 @@
 use crate::core::*;
 use crate::renderer::*;
 
-pub use crate::core::{CPUMaterial, Color};
+pub use crate::core::{CPUMaterial, Color, ColorSpace};
 
 mod color_material;
 #[doc(inline)]
 pub use color_material::*;
 
+mod glow_material;
+#[doc(inline)]
+pub use glow_material::*;
+
 mod depth_material;
 #[doc(inline)]
 pub use depth_material::*;
@@ -32,19 +36,51 @@ mod uv_material;
 #[doc(inline)]
 pub use uv_material::*;
 
+mod textured_material;
+#[doc(inline)]
+pub use textured_material::*;
+
 mod physical_material;
 #[doc(inline)]
 pub use physical_material::*;
 
+mod physical_material_instance;
+#[doc(inline)]
+pub use physical_material_instance::*;
+
 mod deferred_physical_material;
 #[doc(inline)]
 pub use deferred_physical_material::*;
 
+mod toon_material;
+#[doc(inline)]
+pub use toon_material::*;
+
+mod procedural_material;
+#[doc(inline)]
+pub use procedural_material::*;
+
+mod lightmapped_material;
+#[doc(inline)]
+pub use lightmapped_material::*;
+
+mod paintable_texture;
+#[doc(inline)]
+pub use paintable_texture::*;
+
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "hot-reload")))]
+mod hot_reload_material;
+#[doc(inline)]
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+pub use hot_reload_material::*;
+
 ///
 /// Represents a material that can be applied to a [Shadable] object.
 ///
 /// The material can use the attributes position (in world space) by adding `in vec3 pos;`,
-/// normal by `in vec3 nor;`, uv coordinates by `in vec2 uvs;` and color by `in vec4 col;` to the fragment shader source code.
+/// normal by `in vec3 nor;`, uv coordinates by `in vec2 uvs;`, second uv coordinates by `in vec2 uvs2;` and color by `in vec4 col;` to the fragment shader source code.
+/// The easiest way to assemble the fragment shader source is using a [ShaderSourceBuilder], which also declares these inputs.
 ///
 pub trait Material {
     /// Returns the fragment shader source for this material. Should output the final fragment color.
@@ -56,6 +92,23 @@ pub trait Material {
     fn render_states(&self) -> RenderStates;
     /// Returns whether or not this material is transparent.
     fn is_transparent(&self) -> bool;
+    /// Returns how this material is rendered when it is transparent. Defaults to [TransparencyMode::SinglePass].
+    fn transparency_mode(&self) -> TransparencyMode {
+        TransparencyMode::SinglePass
+    }
+    ///
+    /// Returns the source code of a GLSL function `vec3 displace_position(vec3 position)` which moves each vertex in the vertex shader, for example to animate waves,
+    /// where both positions are in the local space of the object, or `None` if the vertices are not displaced, which is the default.
+    /// The displacement is applied whenever a [Model] with this material is rendered, also with another material, for example when generating shadow maps,
+    /// and the normals can be recomputed from the displaced positions, see [Model::enable_dynamic_normals].
+    ///
+    fn vertex_displacement_source(&self) -> Option<String> {
+        None
+    }
+    /// Sends the uniform data needed by the vertex displacement to the vertex shader, see [Material::vertex_displacement_source]. Defaults to sending nothing.
+    fn use_vertex_uniforms(&self, _program: &Program) -> ThreeDResult<()> {
+        Ok(())
+    }
 }
 
 impl<T: Material + ?Sized> Material for &T {
@@ -76,4 +129,145 @@ impl<T: Material + ?Sized> Material for &T {
     fn is_transparent(&self) -> bool {
         (*self).is_transparent()
     }
+    fn transparency_mode(&self) -> TransparencyMode {
+        (*self).transparency_mode()
+    }
+    fn vertex_displacement_source(&self) -> Option<String> {
+        (*self).vertex_displacement_source()
+    }
+    fn use_vertex_uniforms(&self, program: &Program) -> ThreeDResult<()> {
+        (*self).use_vertex_uniforms(program)
+    }
+}
+
+///
+/// Defines how the alpha value of a material, ie. the alpha value of the color multiplied with the alpha value of the texture and vertex color, is used.
+/// See for example [ColorMaterial::alpha_mode] and [PhysicalMaterial::alpha_mode].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AlphaMode {
+    ///
+    /// The material is transparent if the alpha value is below the maximum, in which case it is blended with the objects behind it using the transparent render states.
+    /// Transparent objects do not write depth and must be rendered after the opaque objects and sorted back to front, which is done by [render_pass].
+    ///
+    Blend,
+    ///
+    /// The material is rendered as opaque, but a fraction of the pixels given by the alpha value is discarded (screen-door transparency),
+    /// so for example half of the pixels are rendered when the alpha value is 0.5.
+    /// Which pixels are discarded is given by a 4x4 Bayer matrix in screen space.
+    /// Since the material is opaque, it writes depth and needs no sorting, which is useful for fading objects in and out,
+    /// for example when switching between levels of detail, or for showing objects as ghosts.
+    ///
+    /// The `frame` offsets the pattern such that each pixel goes through all 16 thresholds of the matrix in 16 consecutive frames,
+    /// so setting it to the frame index and averaging the frames, for example when accumulating frames with a jittered camera (see [Camera::set_projection_jitter]),
+    /// gives a smooth transparency. Use a constant `frame` for a static pattern.
+    ///
+    /// If `inverted` is true, the pattern is inverted such that an object with alpha value `a` and an inverted object with alpha value `1 - a`
+    /// together cover each pixel exactly once, which is needed when cross-fading between two objects at the same place.
+    ///
+    Dither {
+        /// The frame which offsets the pattern.
+        frame: u32,
+        /// Whether or not the pattern is inverted.
+        inverted: bool,
+    },
+}
+
+impl Default for AlphaMode {
+    fn default() -> Self {
+        Self::Blend
+    }
+}
+
+impl AlphaMode {
+    // Defines USE_DITHER and adds the dither function to the fragment shader source, if the alpha mode is dithering
+    pub(in crate::renderer) fn fragment_shader_source(&self) -> &'static str {
+        match self {
+            AlphaMode::Blend => "",
+            AlphaMode::Dither { .. } => {
+                concat!(
+                    "#define USE_DITHER\n",
+                    include_str!("material/shaders/dither.frag")
+                )
+            }
+        }
+    }
+
+    ///
+    /// Sends the uniforms of the dither function, if the alpha mode is dithering.
+    /// Must be called when rendering with a shader built with [ShaderSourceBuilder::alpha_mode].
+    ///
+    pub fn use_uniforms(&self, program: &Program) -> ThreeDResult<()> {
+        if let AlphaMode::Dither { frame, inverted } = *self {
+            program.use_uniform_int("ditherFrame", &((frame % 16) as i32))?;
+            program.use_uniform_int("ditherInverted", &(inverted as i32))?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Defines how a transparent material is rendered, see for example [ColorMaterial::transparency_mode] and [PhysicalMaterial::transparency_mode].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransparencyMode {
+    ///
+    /// The object is rendered once using the transparent render states of the material.
+    ///
+    SinglePass,
+    ///
+    /// The back faces of the object are rendered first and then the front faces, which blends the triangles of a closed mesh,
+    /// for example a sphere, in the correct order without sorting them.
+    /// Overrides the cull render state of the transparent render states.
+    ///
+    TwoPass,
+}
+
+impl Default for TransparencyMode {
+    fn default() -> Self {
+        Self::SinglePass
+    }
+}
+
+impl TransparencyMode {
+    // The render states of each pass, ie. the given render states or, when rendering a transparent material in two passes, the given render states with the front faces culled followed by the given render states with the back faces culled
+    pub(in crate::renderer) fn passes(
+        &self,
+        render_states: RenderStates,
+        transparent: bool,
+    ) -> impl Iterator<Item = RenderStates> {
+        let two_pass = transparent && *self == TransparencyMode::TwoPass;
+        let first = if two_pass {
+            RenderStates {
+                cull: Cull::Front,
+                ..render_states
+            }
+        } else {
+            render_states
+        };
+        let second = if two_pass {
+            Some(RenderStates {
+                cull: Cull::Back,
+                ..render_states
+            })
+        } else {
+            None
+        };
+        std::iter::once(first).chain(second)
+    }
+}
+
+// The given render states with the cull state given by the double sided flag of a material, ie. no culling of a double sided material and the back faces culled otherwise, unless the render states already specify which faces to cull
+pub(in crate::renderer) fn double_sided_render_states(
+    render_states: RenderStates,
+    double_sided: bool,
+) -> RenderStates {
+    if render_states.cull != Cull::None || double_sided {
+        render_states
+    } else {
+        RenderStates {
+            cull: Cull::Back,
+            ..render_states
+        }
+    }
 }
@@ -10,12 +10,26 @@ use std::rc::Rc;
 pub struct ColorMaterial {
     /// A color applied everywhere.
     pub color: Color,
+    /// The color space of [Self::color]. The default is [ColorSpace::Linear], use [ColorSpace::Srgb] for a color picked in an image editor.
+    pub color_space: ColorSpace,
     /// An optional texture which is samples using uv coordinates (requires that the [Shadable] object supports uv coordinates).
     pub texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::texture], for example to repeat the texture.
+    pub texture_transform: Mat3,
     /// Render states used when the color is opaque (has a maximal alpha value).
     pub opaque_render_states: RenderStates,
     /// Render states used when the color is transparent (does not have a maximal alpha value).
     pub transparent_render_states: RenderStates,
+    /// How the alpha value is used. When dithering, the material is never transparent and the opaque render states are always used.
+    pub alpha_mode: AlphaMode,
+    /// How the material is rendered when it is transparent, for example in two passes to blend the triangles of a closed mesh correctly.
+    pub transparency_mode: TransparencyMode,
+    /// Whether or not the back side of the geometry is rendered.
+    /// If true, the back side is rendered, otherwise the back faces are culled.
+    /// Culling specified by the render states takes precedence. The default is true.
+    pub double_sided: bool,
+    /// Providers of additional uniforms, which are sent after the uniforms of this material, see [ColorMaterial::with_provider].
+    pub providers: UniformProviders,
 }
 impl ColorMaterial {
     /// Constructs a new color material from a [CPUMaterial].
@@ -28,6 +42,8 @@ impl ColorMaterial {
         Ok(Self {
             color: cpu_material.albedo,
             texture,
+            texture_transform: cpu_material.albedo_texture_transform,
+            double_sided: cpu_material.double_sided,
             ..Default::default()
         })
     }
@@ -35,25 +51,53 @@ impl ColorMaterial {
     pub fn from_physical_material(physical_material: &PhysicalMaterial) -> Self {
         Self {
             color: physical_material.albedo,
+            color_space: ColorSpace::Linear,
             texture: physical_material.albedo_texture.clone(),
+            texture_transform: physical_material.albedo_texture_transform,
             opaque_render_states: physical_material.opaque_render_states,
             transparent_render_states: physical_material.transparent_render_states,
+            alpha_mode: physical_material.alpha_mode,
+            transparency_mode: physical_material.transparency_mode,
+            double_sided: physical_material.double_sided,
+            providers: UniformProviders::new(),
         }
     }
+
+    ///
+    /// Returns this material with the given uniform provider added.
+    /// The uniform declarations of the provider are added to the fragment shader source in front of the source of this material.
+    ///
+    pub fn with_provider(mut self, provider: Rc<dyn UniformProvider>) -> Self {
+        self.providers.add(provider);
+        self
+    }
 }
 
 impl Material for ColorMaterial {
     fn fragment_shader_source(&self, use_vertex_colors: bool, _lights: &Lights) -> String {
-        let mut shader = String::new();
+        let mut builder = ShaderSourceBuilder::new();
         if self.texture.is_some() {
-            shader.push_str("#define USE_TEXTURE\nin vec2 uvs;\n");
+            builder.define("USE_TEXTURE").input(ShaderInput::Uvs);
+            if self.texture_transform != Mat3::identity() {
+                builder.define("USE_TEXTURE_TRANSFORM");
+            }
         }
         if use_vertex_colors {
-            shader.push_str("#define USE_VERTEX_COLORS\nin vec4 col;\n");
+            builder
+                .define("USE_VERTEX_COLORS")
+                .input(ShaderInput::Color);
+        }
+        if !self.providers.is_empty() {
+            builder.block("providers", &self.providers.fragment_shader_source());
         }
-        shader.push_str(include_str!("../../core/shared.frag"));
-        shader.push_str(include_str!("shaders/color_material.frag"));
-        shader
+        builder
+            .shared_functions()
+            .alpha_mode(self.alpha_mode)
+            .block(
+                "color_material",
+                include_str!("shaders/color_material.frag"),
+            );
+        builder.fragment_source()
     }
     fn use_uniforms(
         &self,
@@ -61,26 +105,35 @@ impl Material for ColorMaterial {
         _camera: &Camera,
         _lights: &Lights,
     ) -> ThreeDResult<()> {
-        program.use_uniform_vec4("surfaceColor", &self.color.to_vec4())?;
+        program.use_uniform_vec4("surfaceColor", &self.color_space.to_linear(self.color))?;
         if let Some(ref tex) = self.texture {
-            program.use_texture("tex", &**tex)?
+            program.use_texture("tex", &**tex)?;
+            if self.texture_transform != Mat3::identity() {
+                program.use_uniform_mat3("texTransform", &self.texture_transform)?;
+            }
         }
-        Ok(())
+        self.alpha_mode.use_uniforms(program)?;
+        self.providers.provide(program)
     }
     fn render_states(&self) -> RenderStates {
-        if self.is_transparent() {
+        let render_states = if self.is_transparent() {
             self.transparent_render_states
         } else {
             self.opaque_render_states
-        }
+        };
+        double_sided_render_states(render_states, self.double_sided)
     }
     fn is_transparent(&self) -> bool {
-        self.color.a != 255u8
-            || self
-                .texture
-                .as_ref()
-                .map(|t| t.is_transparent())
-                .unwrap_or(false)
+        self.alpha_mode == AlphaMode::Blend
+            && (self.color.a != 255u8
+                || self
+                    .texture
+                    .as_ref()
+                    .map(|t| t.is_transparent())
+                    .unwrap_or(false))
+    }
+    fn transparency_mode(&self) -> TransparencyMode {
+        self.transparency_mode
     }
 }
 
@@ -88,13 +141,19 @@ impl Default for ColorMaterial {
     fn default() -> Self {
         Self {
             color: Color::default(),
+            color_space: ColorSpace::Linear,
             texture: None,
+            texture_transform: Mat3::identity(),
             opaque_render_states: RenderStates::default(),
             transparent_render_states: RenderStates {
                 write_mask: WriteMask::COLOR,
                 blend: Blend::TRANSPARENCY,
                 ..Default::default()
             },
+            alpha_mode: AlphaMode::Blend,
+            transparency_mode: TransparencyMode::SinglePass,
+            double_sided: true,
+            providers: UniformProviders::new(),
         }
     }
 }
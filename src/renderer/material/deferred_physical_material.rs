@@ -15,6 +15,10 @@ pub struct DeferredPhysicalMaterial {
     pub albedo: Color,
     /// Texture with albedo base colors, also called diffuse color. Assumed to be in sRGB with or without an alpha channel.
     pub albedo_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::albedo_texture], for example to repeat the texture.
+    pub albedo_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::albedo_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub albedo_texture_uv_set: u8,
     /// A value in the range `[0..1]` specifying how metallic the material is.
     pub metallic: f32,
     /// A value in the range `[0..1]` specifying how rough the material surface is.
@@ -22,19 +26,35 @@ pub struct DeferredPhysicalMaterial {
     /// Texture containing the metallic and roughness parameters which are multiplied with the [Self::metallic] and [Self::roughness] values in the shader.
     /// The metallic values are sampled from the blue channel and the roughness from the green channel.
     pub metallic_roughness_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::metallic_roughness_texture].
+    pub metallic_roughness_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::metallic_roughness_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub metallic_roughness_texture_uv_set: u8,
     /// A scalar multiplier controlling the amount of occlusion applied from the [Self::occlusion_texture]. A value of 0.0 means no occlusion. A value of 1.0 means full occlusion.
     pub occlusion_strength: f32,
     /// An occlusion map. Higher values indicate areas that should receive full indirect lighting and lower values indicate no indirect lighting.
     /// The occlusion values are sampled from the red channel.
     pub occlusion_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::occlusion_texture].
+    pub occlusion_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::occlusion_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub occlusion_texture_uv_set: u8,
     /// A scalar multiplier applied to each normal vector of the [Self::normal_texture].
     pub normal_scale: f32,
     /// A tangent space normal map, also known as bump map.
     pub normal_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::normal_texture].
+    pub normal_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::normal_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub normal_texture_uv_set: u8,
     /// Render states
     pub render_states: RenderStates,
     /// Alpha cutout value for transparency in deferred rendering pipeline.
     pub alpha_cutout: Option<f32>,
+    /// Whether or not the back side of the geometry is rendered.
+    /// If true, the back side is rendered and lit using the flipped normal, otherwise the back faces are culled.
+    /// Culling specified by the render states takes precedence. The default is true.
+    pub double_sided: bool,
 }
 
 impl DeferredPhysicalMaterial {
@@ -77,15 +97,24 @@ impl DeferredPhysicalMaterial {
             name: cpu_material.name.clone(),
             albedo: cpu_material.albedo,
             albedo_texture,
+            albedo_texture_transform: cpu_material.albedo_texture_transform,
+            albedo_texture_uv_set: cpu_material.albedo_texture_uv_set,
             metallic: cpu_material.metallic,
             roughness: cpu_material.roughness,
             metallic_roughness_texture,
+            metallic_roughness_texture_transform: cpu_material.metallic_roughness_texture_transform,
+            metallic_roughness_texture_uv_set: cpu_material.metallic_roughness_texture_uv_set,
             normal_texture,
+            normal_texture_transform: cpu_material.normal_texture_transform,
+            normal_texture_uv_set: cpu_material.normal_texture_uv_set,
             normal_scale: cpu_material.normal_scale,
             occlusion_texture,
+            occlusion_texture_transform: cpu_material.occlusion_texture_transform,
+            occlusion_texture_uv_set: cpu_material.occlusion_texture_uv_set,
             occlusion_strength: cpu_material.occlusion_strength,
             render_states: RenderStates::default(),
             alpha_cutout: cpu_material.alpha_cutout,
+            double_sided: cpu_material.double_sided,
         })
     }
 
@@ -97,15 +126,25 @@ impl DeferredPhysicalMaterial {
             name: physical_material.name.clone(),
             albedo: physical_material.albedo,
             albedo_texture: physical_material.albedo_texture.clone(),
+            albedo_texture_transform: physical_material.albedo_texture_transform,
+            albedo_texture_uv_set: physical_material.albedo_texture_uv_set,
             metallic: physical_material.metallic,
             roughness: physical_material.roughness,
             metallic_roughness_texture: physical_material.metallic_roughness_texture.clone(),
+            metallic_roughness_texture_transform: physical_material
+                .metallic_roughness_texture_transform,
+            metallic_roughness_texture_uv_set: physical_material.metallic_roughness_texture_uv_set,
             normal_texture: physical_material.normal_texture.clone(),
+            normal_texture_transform: physical_material.normal_texture_transform,
+            normal_texture_uv_set: physical_material.normal_texture_uv_set,
             normal_scale: physical_material.normal_scale,
             occlusion_texture: physical_material.occlusion_texture.clone(),
+            occlusion_texture_transform: physical_material.occlusion_texture_transform,
+            occlusion_texture_uv_set: physical_material.occlusion_texture_uv_set,
             occlusion_strength: physical_material.occlusion_strength,
             render_states: physical_material.opaque_render_states,
             alpha_cutout: None,
+            double_sided: physical_material.double_sided,
         }
     }
 }
@@ -113,35 +152,66 @@ impl DeferredPhysicalMaterial {
 impl Material for DeferredPhysicalMaterial {
     fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
         let mut output = lights.fragment_shader_source();
-        if self.albedo_texture.is_some()
-            || self.metallic_roughness_texture.is_some()
-            || self.normal_texture.is_some()
-            || self.occlusion_texture.is_some()
-            || self.alpha_cutout.is_some()
+        let slots = [
+            (
+                "ALBEDO",
+                self.albedo_texture.is_some(),
+                self.albedo_texture_transform,
+                self.albedo_texture_uv_set,
+            ),
+            (
+                "METALLIC_ROUGHNESS",
+                self.metallic_roughness_texture.is_some(),
+                self.metallic_roughness_texture_transform,
+                self.metallic_roughness_texture_uv_set,
+            ),
+            (
+                "OCCLUSION",
+                self.occlusion_texture.is_some(),
+                self.occlusion_texture_transform,
+                self.occlusion_texture_uv_set,
+            ),
+            (
+                "NORMAL",
+                self.normal_texture.is_some(),
+                self.normal_texture_transform,
+                self.normal_texture_uv_set,
+            ),
+        ];
+        // Only the uv coordinates used by any of the textures are declared, so the mesh only needs to have those
+        if self.alpha_cutout.is_some()
+            || slots
+                .iter()
+                .any(|(_, has_texture, _, uv_set)| *has_texture && *uv_set == 0)
         {
             output.push_str("in vec2 uvs;\n");
-            if self.albedo_texture.is_some() {
-                output.push_str("#define USE_ALBEDO_TEXTURE;\n");
-            }
-            if self.metallic_roughness_texture.is_some() {
-                output.push_str("#define USE_METALLIC_ROUGHNESS_TEXTURE;\n");
-            }
-            if self.occlusion_texture.is_some() {
-                output.push_str("#define USE_OCCLUSION_TEXTURE;\n");
-            }
-            if self.normal_texture.is_some() {
-                output.push_str("#define USE_NORMAL_TEXTURE;\nin vec3 tang;\nin vec3 bitang;\n");
-            }
-            if self.alpha_cutout.is_some() {
-                output.push_str(
-                    format!(
-                        "#define ALPHACUT;\nfloat acut = {};",
-                        self.alpha_cutout.unwrap()
-                    )
-                    .as_str(),
-                );
+        }
+        if slots
+            .iter()
+            .any(|(_, has_texture, _, uv_set)| *has_texture && *uv_set != 0)
+        {
+            output.push_str("in vec2 uvs2;\n");
+        }
+        for (name, has_texture, transform, uv_set) in slots.iter() {
+            if *has_texture {
+                output.push_str(&format!("#define USE_{}_TEXTURE;\n", name));
+                if *transform != Mat3::identity() {
+                    output.push_str(&format!("#define USE_{}_TEXTURE_TRANSFORM;\n", name));
+                }
+                if *uv_set != 0 {
+                    output.push_str(&format!("#define USE_{}_TEXTURE_UVS2;\n", name));
+                }
             }
         }
+        if self.normal_texture.is_some() {
+            output.push_str("in vec3 tang;\nin vec3 bitang;\n");
+        }
+        if let Some(alpha_cutout) = self.alpha_cutout {
+            output.push_str(&format!(
+                "#define ALPHACUT;\nfloat acut = {};",
+                alpha_cutout
+            ));
+        }
         if use_vertex_colors {
             output.push_str("#define USE_VERTEX_COLORS\nin vec4 col;\n");
         }
@@ -160,23 +230,43 @@ impl Material for DeferredPhysicalMaterial {
         program.use_uniform_vec4("albedo", &self.albedo.to_vec4())?;
         if let Some(ref texture) = self.albedo_texture {
             program.use_texture("albedoTexture", texture.as_ref())?;
+            if self.albedo_texture_transform != Mat3::identity() {
+                program
+                    .use_uniform_mat3("albedoTextureTransform", &self.albedo_texture_transform)?;
+            }
         }
         if let Some(ref texture) = self.metallic_roughness_texture {
             program.use_texture("metallicRoughnessTexture", texture.as_ref())?;
+            if self.metallic_roughness_texture_transform != Mat3::identity() {
+                program.use_uniform_mat3(
+                    "metallicRoughnessTextureTransform",
+                    &self.metallic_roughness_texture_transform,
+                )?;
+            }
         }
         if let Some(ref texture) = self.occlusion_texture {
             program.use_uniform_float("occlusionStrength", &self.occlusion_strength)?;
             program.use_texture("occlusionTexture", texture.as_ref())?;
+            if self.occlusion_texture_transform != Mat3::identity() {
+                program.use_uniform_mat3(
+                    "occlusionTextureTransform",
+                    &self.occlusion_texture_transform,
+                )?;
+            }
         }
         if let Some(ref texture) = self.normal_texture {
             program.use_uniform_float("normalScale", &self.normal_scale)?;
             program.use_texture("normalTexture", texture.as_ref())?;
+            if self.normal_texture_transform != Mat3::identity() {
+                program
+                    .use_uniform_mat3("normalTextureTransform", &self.normal_texture_transform)?;
+            }
         }
         Ok(())
     }
 
     fn render_states(&self) -> RenderStates {
-        self.render_states
+        double_sided_render_states(self.render_states, self.double_sided)
     }
 
     fn is_transparent(&self) -> bool {
@@ -190,15 +280,24 @@ impl Default for DeferredPhysicalMaterial {
             name: "default".to_string(),
             albedo: Color::WHITE,
             albedo_texture: None,
+            albedo_texture_transform: Mat3::identity(),
+            albedo_texture_uv_set: 0,
             metallic: 0.0,
             roughness: 1.0,
             metallic_roughness_texture: None,
+            metallic_roughness_texture_transform: Mat3::identity(),
+            metallic_roughness_texture_uv_set: 0,
             normal_texture: None,
+            normal_texture_transform: Mat3::identity(),
+            normal_texture_uv_set: 0,
             normal_scale: 1.0,
             occlusion_texture: None,
+            occlusion_texture_transform: Mat3::identity(),
+            occlusion_texture_uv_set: 0,
             occlusion_strength: 1.0,
             render_states: RenderStates::default(),
             alpha_cutout: None,
+            double_sided: true,
         }
     }
 }
@@ -0,0 +1,118 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::rc::Rc;
+
+///
+/// Defines how a [GlowMaterial] is blended with what is already rendered.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlowBlend {
+    /// The glow is added to the color already rendered, so it only brightens, and the alpha value scales the glow.
+    Additive,
+    /// The glow is blended with the color already rendered using the alpha value, like any other transparent material.
+    Alpha,
+}
+
+///
+/// An unlit material that only emits light, which is useful for rendering bright objects like light bulbs, neon signs or glowing particles.
+/// The emitted color is defined by multiplying a color with an optional texture and an intensity.
+/// An intensity above one results in colors above the displayable range when rendering in high dynamic range (see [HdrPipeline]),
+/// which is what makes the object bloom.
+/// This material is not affected by lights.
+///
+#[derive(Clone)]
+pub struct GlowMaterial {
+    /// The emitted color applied everywhere.
+    pub color: Color,
+    /// The intensity multiplied onto the emitted color.
+    pub intensity: f32,
+    /// An optional emissive texture which is samples using uv coordinates (requires that the [Shadable] object supports uv coordinates).
+    pub texture: Option<Rc<Texture2D<u8>>>,
+    /// Defines how the glow is blended with what is already rendered.
+    pub blend: GlowBlend,
+}
+
+impl GlowMaterial {
+    /// Constructs a new glow material from the emissive color and emissive texture of a [CPUMaterial].
+    pub fn new(context: &Context, cpu_material: &CPUMaterial) -> ThreeDResult<Self> {
+        let texture = if let Some(ref cpu_texture) = cpu_material.emissive_texture {
+            Some(Rc::new(Texture2D::new(&context, cpu_texture)?))
+        } else {
+            None
+        };
+        Ok(Self {
+            color: cpu_material.emissive,
+            texture,
+            ..Default::default()
+        })
+    }
+}
+
+impl Material for GlowMaterial {
+    fn fragment_shader_source(&self, use_vertex_colors: bool, _lights: &Lights) -> String {
+        let mut builder = ShaderSourceBuilder::new();
+        if self.texture.is_some() {
+            builder.define("USE_TEXTURE").input(ShaderInput::Uvs);
+        }
+        if use_vertex_colors {
+            builder
+                .define("USE_VERTEX_COLORS")
+                .input(ShaderInput::Color);
+        }
+        if self.blend == GlowBlend::Additive {
+            builder.define("ADDITIVE");
+        }
+        builder
+            .shared_functions()
+            .block("glow_material", include_str!("shaders/glow_material.frag"));
+        builder.fragment_source()
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _camera: &Camera,
+        _lights: &Lights,
+    ) -> ThreeDResult<()> {
+        program.use_uniform_vec4("surfaceColor", &self.color.to_vec4())?;
+        program.use_uniform_float("intensity", &self.intensity)?;
+        if let Some(ref tex) = self.texture {
+            program.use_texture("tex", &**tex)?
+        }
+        Ok(())
+    }
+    fn render_states(&self) -> RenderStates {
+        match self.blend {
+            GlowBlend::Additive => RenderStates {
+                write_mask: WriteMask::COLOR,
+                blend: Blend::ADD,
+                ..Default::default()
+            },
+            GlowBlend::Alpha if self.is_transparent() => RenderStates {
+                write_mask: WriteMask::COLOR,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            },
+            GlowBlend::Alpha => RenderStates::default(),
+        }
+    }
+    fn is_transparent(&self) -> bool {
+        self.blend == GlowBlend::Additive
+            || self.color.a != 255u8
+            || self
+                .texture
+                .as_ref()
+                .map(|t| t.is_transparent())
+                .unwrap_or(false)
+    }
+}
+
+impl Default for GlowMaterial {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            intensity: 1.0,
+            texture: None,
+            blend: GlowBlend::Additive,
+        }
+    }
+}
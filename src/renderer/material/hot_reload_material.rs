@@ -0,0 +1,201 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+///
+/// A value of a uniform variable set on a [HotReloadMaterial].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UniformValue {
+    /// A `float` uniform.
+    Float(f32),
+    /// An `int` uniform.
+    Int(i32),
+    /// A `vec2` uniform.
+    Vec2(Vec2),
+    /// A `vec3` uniform.
+    Vec3(Vec3),
+    /// A `vec4` uniform.
+    Vec4(Vec4),
+    /// A `mat3` uniform.
+    Mat3(Mat3),
+    /// A `mat4` uniform.
+    Mat4(Mat4),
+}
+
+///
+/// A material for developing custom materials, which loads the fragment shader source from a file
+/// and loads it again when the file is changed, so the shader can be edited while the application is running.
+/// Call [HotReloadMaterial::reload_if_changed] each frame to check if the file has changed.
+///
+/// The fragment shader source can use the attributes position, normal, uv coordinates and color like any other [Material]
+/// and the values of its uniform variables are given by [HotReloadMaterial::uniforms].
+/// If the changed source fails to compile, the error is logged and returned by [HotReloadMaterial::error],
+/// and the material keeps using the last source which compiled.
+///
+/// **Note:** Only available on native and when the `hot-reload` feature is enabled.
+///
+pub struct HotReloadMaterial {
+    /// The values of the uniform variables in the fragment shader. Uniforms which are not used by the current source are ignored.
+    pub uniforms: HashMap<String, UniformValue>,
+    /// Render states used when rendering with this material.
+    pub render_states: RenderStates,
+    /// Whether or not this material is transparent.
+    pub transparent: bool,
+    context: Context,
+    path: PathBuf,
+    source: String,
+    modified: Option<SystemTime>,
+    error: Option<String>,
+}
+
+impl HotReloadMaterial {
+    ///
+    /// Constructs a new material with the fragment shader source in the file at the given path.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or the source fails to compile.
+    /// Errors after construction never fails the material, see [HotReloadMaterial::reload_if_changed].
+    ///
+    pub fn new(context: &Context, path: impl AsRef<Path>) -> ThreeDResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let modified = modified_time(&path);
+        let source = std::fs::read_to_string(&path)?;
+        validate(context, &source)?;
+        Ok(Self {
+            uniforms: HashMap::new(),
+            render_states: RenderStates::default(),
+            transparent: false,
+            context: context.clone(),
+            path,
+            source,
+            modified,
+            error: None,
+        })
+    }
+
+    ///
+    /// Returns the path to the file containing the fragment shader source.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    ///
+    /// Returns the fragment shader source currently in use, ie. the last source which compiled.
+    ///
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    ///
+    /// Returns the error of the last attempt to load the file, if it failed to be read or compiled.
+    ///
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    ///
+    /// Loads the fragment shader source again if the modification time of the file has changed since it was last loaded,
+    /// and returns whether or not the source was replaced.
+    /// The programs compiled with the replaced source are removed from the program cache of the context.
+    ///
+    pub fn reload_if_changed(&mut self) -> bool {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        let result = std::fs::read_to_string(&self.path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| {
+                validate(&self.context, &source)
+                    .map(|_| source)
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(source) => {
+                self.error = None;
+                if source == self.source {
+                    return false;
+                }
+                self.context.remove_programs_containing(&self.source);
+                self.source = source;
+                true
+            }
+            Err(error) => {
+                log::error!("failed reloading {}: {}", self.path.display(), error);
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+}
+
+impl Material for HotReloadMaterial {
+    fn fragment_shader_source(&self, _use_vertex_colors: bool, _lights: &Lights) -> String {
+        self.source.clone()
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _camera: &Camera,
+        _lights: &Lights,
+    ) -> ThreeDResult<()> {
+        for (name, value) in self.uniforms.iter() {
+            if !program.requires_uniform(name) {
+                continue;
+            }
+            match value {
+                UniformValue::Float(v) => program.use_uniform_float(name, v)?,
+                UniformValue::Int(v) => program.use_uniform_int(name, v)?,
+                UniformValue::Vec2(v) => program.use_uniform_vec2(name, v)?,
+                UniformValue::Vec3(v) => program.use_uniform_vec3(name, v)?,
+                UniformValue::Vec4(v) => program.use_uniform_vec4(name, v)?,
+                UniformValue::Mat3(v) => program.use_uniform_mat3(name, v)?,
+                UniformValue::Mat4(v) => program.use_uniform_mat4(name, v)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// Compiles the fragment shader source together with a vertex shader which outputs all of the attributes a material can use
+fn validate(context: &Context, fragment_shader_source: &str) -> ThreeDResult<()> {
+    Program::from_source(
+        context,
+        "out vec3 pos;
+        out vec3 nor;
+        out vec3 tang;
+        out vec3 bitang;
+        out vec2 uvs;
+        out vec4 col;
+        void main()
+        {
+            pos = vec3(0.0);
+            nor = vec3(0.0);
+            tang = vec3(0.0);
+            bitang = vec3(0.0);
+            uvs = vec2(0.0);
+            col = vec4(0.0);
+            gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+        }",
+        fragment_shader_source,
+    )?;
+    Ok(())
+}
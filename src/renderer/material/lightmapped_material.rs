@@ -0,0 +1,101 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::rc::Rc;
+
+///
+/// A material that renders a [Shadable] object with the lighting baked into a lightmap, see [bake_lightmap].
+/// The color is the albedo multiplied by the light in the lightmap,
+/// which means that this material is not affected by the lights given when rendering and is much cheaper than for example a [PhysicalMaterial].
+/// The albedo texture is mapped using the uv coordinates and the lightmap using the second uv coordinates, see [LightmappedMaterial::use_uvs2].
+///
+#[derive(Clone)]
+pub struct LightmappedMaterial {
+    /// Albedo base color which is multiplied with the color from the albedo texture. Assumed to be in linear color space.
+    pub albedo: Color,
+    /// Texture with albedo base colors. Assumed to be in sRGB with or without an alpha channel.
+    pub albedo_texture: Option<Rc<Texture2D<u8>>>,
+    /// The baked light arriving at the surface in linear color space, see [bake_lightmap].
+    pub lightmap_texture: Rc<Texture2D<f32>>,
+    /// A scalar multiplier applied to the light from the [Self::lightmap_texture].
+    pub lightmap_intensity: f32,
+    ///
+    /// Whether the lightmap is mapped using the second uv coordinates ([CPUMesh::uvs2]) or the uv coordinates of the mesh.
+    /// This must match the uv coordinates used when baking, ie. it should be true if the mesh has second uv coordinates.
+    ///
+    pub use_uvs2: bool,
+    /// Render states used when the color is opaque (has a maximal alpha value).
+    pub opaque_render_states: RenderStates,
+    /// Render states used when the color is transparent (does not have a maximal alpha value).
+    pub transparent_render_states: RenderStates,
+}
+
+impl LightmappedMaterial {
+    ///
+    /// Constructs a new lightmapped material with a white albedo and the given lightmap,
+    /// which is mapped using the second uv coordinates if the given mesh has them.
+    ///
+    pub fn new(cpu_mesh: &CPUMesh, lightmap_texture: Rc<Texture2D<f32>>) -> Self {
+        Self {
+            albedo: Color::WHITE,
+            albedo_texture: None,
+            lightmap_texture,
+            lightmap_intensity: 1.0,
+            use_uvs2: cpu_mesh.uvs2.is_some(),
+            opaque_render_states: RenderStates::default(),
+            transparent_render_states: RenderStates {
+                write_mask: WriteMask::COLOR,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Material for LightmappedMaterial {
+    fn fragment_shader_source(&self, use_vertex_colors: bool, _lights: &Lights) -> String {
+        let mut shader = String::new();
+        if self.albedo_texture.is_some() || !self.use_uvs2 {
+            shader.push_str("#define USE_UVS\nin vec2 uvs;\n");
+        }
+        if self.albedo_texture.is_some() {
+            shader.push_str("#define USE_ALBEDO_TEXTURE\n");
+        }
+        if self.use_uvs2 {
+            shader.push_str("#define USE_UVS2\nin vec2 uvs2;\n");
+        }
+        if use_vertex_colors {
+            shader.push_str("#define USE_VERTEX_COLORS\nin vec4 col;\n");
+        }
+        shader.push_str(include_str!("../../core/shared.frag"));
+        shader.push_str(include_str!("shaders/lightmapped_material.frag"));
+        shader
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _camera: &Camera,
+        _lights: &Lights,
+    ) -> ThreeDResult<()> {
+        program.use_uniform_vec4("albedo", &self.albedo.to_vec4())?;
+        if let Some(ref texture) = self.albedo_texture {
+            program.use_texture("albedoTexture", texture.as_ref())?;
+        }
+        program.use_uniform_float("lightmapIntensity", &self.lightmap_intensity)?;
+        program.use_texture("lightmapTexture", self.lightmap_texture.as_ref())
+    }
+    fn render_states(&self) -> RenderStates {
+        if self.is_transparent() {
+            self.transparent_render_states
+        } else {
+            self.opaque_render_states
+        }
+    }
+    fn is_transparent(&self) -> bool {
+        self.albedo.a != 255u8
+            || self
+                .albedo_texture
+                .as_ref()
+                .map(|t| t.is_transparent())
+                .unwrap_or(false)
+    }
+}
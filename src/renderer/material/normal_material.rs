@@ -36,12 +36,18 @@ impl NormalMaterial {
 
 impl Material for NormalMaterial {
     fn fragment_shader_source(&self, _use_vertex_colors: bool, _lights: &Lights) -> String {
-        let mut shader = String::new();
+        let mut builder = ShaderSourceBuilder::new();
         if self.normal_texture.is_some() {
-            shader.push_str("#define USE_TEXTURE\nin vec2 uvs;\nin vec3 tang;\nin vec3 bitang;\n");
+            builder
+                .define("USE_TEXTURE")
+                .input(ShaderInput::Uvs)
+                .input(ShaderInput::Tangents);
         }
-        shader.push_str(include_str!("shaders/normal_material.frag"));
-        shader
+        builder.block(
+            "normal_material",
+            include_str!("shaders/normal_material.frag"),
+        );
+        builder.fragment_source()
     }
     fn use_uniforms(
         &self,
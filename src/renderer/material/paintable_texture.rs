@@ -0,0 +1,426 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+///
+/// The radius of a [Brush].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BrushRadius {
+    /// The radius in uv coordinates, ie. a radius of `0.5` covers half the width of the texture.
+    /// The paint does not continue across uv seams.
+    Texture(f32),
+    /// The radius in world space. All of the surface within the radius of the painted position is painted, also across uv seams.
+    World(f32),
+}
+
+///
+/// A brush used for painting into a [PaintableTexture].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Brush {
+    /// The radius of the brush.
+    pub radius: BrushRadius,
+    /// The color of the paint. The alpha value is the opacity of the paint at the center of the brush.
+    pub color: Color,
+    /// The fraction of the radius in the range `[0..1]` which is painted with full opacity.
+    /// Outside of that, the opacity falls off smoothly to zero at the radius.
+    pub hardness: f32,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            radius: BrushRadius::World(0.1),
+            color: Color::RED,
+            hardness: 0.5,
+        }
+    }
+}
+
+///
+/// A texture which can be painted on by a [Brush] at positions on the surface of a mesh, for example at the [Hit] of a raycast with a [TriMeshCollider].
+/// Use [PaintableTexture::texture] as for example the [PhysicalMaterial::albedo_texture] of the model rendering the same mesh to see the paint.
+///
+/// The position on the surface which each texel is mapped to is found by rendering the mesh at its uv coordinates,
+/// so the uv coordinates of the mesh must be in the range `[0..1]` and must not overlap.
+/// To avoid visible seams when sampling the texture at the edges of the uv islands, the positions are dilated by a number of texels,
+/// see [PaintableTexture::set_dilation], so the paint also covers the texels just outside of the islands.
+///
+/// Each call to [PaintableTexture::paint] stores a copy of the part of the texture it changes, so it can be undone with [PaintableTexture::undo].
+/// Use [PaintableTexture::begin_stroke] and [PaintableTexture::end_stroke] to undo all of the paint of a stroke at once.
+///
+pub struct PaintableTexture {
+    context: Context,
+    texture: Rc<Texture2D<u8>>,
+    position_texture: Texture2D<f32>,
+    triangles: Vec<([Vec3; 3], [Vec2; 3])>,
+    transformation: Mat4,
+    dilation: u32,
+    undo_steps: VecDeque<Vec<(Viewport, Vec<u8>)>>,
+    max_undo_steps: usize,
+    in_stroke: bool,
+}
+
+impl PaintableTexture {
+    ///
+    /// Constructs a new paintable texture with the given initial content for the given mesh, which must have uv coordinates.
+    /// The texture is converted to [Format::RGBA].
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_mesh: &CPUMesh,
+        cpu_texture: &CPUTexture<u8>,
+    ) -> ThreeDResult<Self> {
+        if cpu_mesh.uvs.is_none() {
+            Err(RendererError::MissingUvCoordinates)?;
+        }
+        let mut triangles = Vec::new();
+        cpu_mesh.for_each_triangle(|i0, i1, i2| {
+            triangles.push((
+                [
+                    cpu_mesh.position(i0),
+                    cpu_mesh.position(i1),
+                    cpu_mesh.position(i2),
+                ],
+                [
+                    cpu_mesh.uv(i0).unwrap(),
+                    cpu_mesh.uv(i1).unwrap(),
+                    cpu_mesh.uv(i2).unwrap(),
+                ],
+            ));
+        });
+        let texture = Texture2D::new(
+            context,
+            &CPUTexture {
+                data: to_rgba(&cpu_texture.data, cpu_texture.format),
+                format: Format::RGBA,
+                ..cpu_texture.clone()
+            },
+        )?;
+        let position_texture = new_position_texture(context, texture.width(), texture.height())?;
+        let mut paintable_texture = Self {
+            context: context.clone(),
+            texture: Rc::new(texture),
+            position_texture,
+            triangles,
+            transformation: Mat4::identity(),
+            dilation: 4,
+            undo_steps: VecDeque::new(),
+            max_undo_steps: 16,
+            in_stroke: false,
+        };
+        paintable_texture.render_positions()?;
+        Ok(paintable_texture)
+    }
+
+    ///
+    /// Returns the painted texture.
+    ///
+    pub fn texture(&self) -> &Rc<Texture2D<u8>> {
+        &self.texture
+    }
+
+    ///
+    /// Sets the local to world transformation of the mesh, which must be the same as the transformation of the model rendering the mesh
+    /// for the [BrushRadius::World] radius to match the rendered model.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    ///
+    /// Returns the local to world transformation of the mesh.
+    ///
+    pub fn transformation(&self) -> &Mat4 {
+        &self.transformation
+    }
+
+    ///
+    /// Sets the number of texels around the uv islands which are also painted, so the paint does not end abruptly at the uv seams
+    /// when the texture is sampled with linear interpolation or mip maps. The default is 4 texels.
+    ///
+    pub fn set_dilation(&mut self, dilation: u32) -> ThreeDResult<()> {
+        self.dilation = dilation;
+        self.render_positions()
+    }
+
+    ///
+    /// Returns the number of texels around the uv islands which are also painted, see [PaintableTexture::set_dilation].
+    ///
+    pub fn dilation(&self) -> u32 {
+        self.dilation
+    }
+
+    ///
+    /// Sets the maximum number of paint calls or strokes which can be undone. The default is 16.
+    ///
+    pub fn set_max_undo_steps(&mut self, max_undo_steps: usize) {
+        self.max_undo_steps = max_undo_steps;
+        while self.undo_steps.len() > max_undo_steps {
+            self.undo_steps.pop_front();
+        }
+    }
+
+    ///
+    /// Returns the maximum number of paint calls or strokes which can be undone, see [PaintableTexture::set_max_undo_steps].
+    ///
+    pub fn max_undo_steps(&self) -> usize {
+        self.max_undo_steps
+    }
+
+    ///
+    /// Starts a stroke, so all calls to [PaintableTexture::paint] until [PaintableTexture::end_stroke] is called are undone at once.
+    ///
+    pub fn begin_stroke(&mut self) {
+        self.push_undo_step();
+        self.in_stroke = true;
+    }
+
+    ///
+    /// Ends the stroke started by [PaintableTexture::begin_stroke].
+    ///
+    pub fn end_stroke(&mut self) {
+        self.in_stroke = false;
+    }
+
+    ///
+    /// Paints with the given brush at the given hit, for example returned by [TriMeshCollider::raycast] on a collider for the same mesh.
+    ///
+    pub fn paint(&mut self, brush: &Brush, hit: &Hit) -> ThreeDResult<()> {
+        self.paint_at(brush, hit.triangle_index, hit.barycentric)
+    }
+
+    ///
+    /// Paints with the given brush at the position on the surface given by the index of a triangle, in the same order as [CPUMesh::for_each_triangle],
+    /// and the barycentric coordinates in that triangle.
+    ///
+    pub fn paint_at(
+        &mut self,
+        brush: &Brush,
+        triangle_index: usize,
+        barycentric: Vec3,
+    ) -> ThreeDResult<()> {
+        let (positions, uvs) = self.triangles.get(triangle_index).ok_or_else(|| {
+            CoreError::IndexOutOfRange(triangle_index, self.triangles.len().saturating_sub(1))
+        })?;
+        let position = positions[0] * barycentric.x
+            + positions[1] * barycentric.y
+            + positions[2] * barycentric.z;
+        let uv = uvs[0] * barycentric.x + uvs[1] * barycentric.y + uvs[2] * barycentric.z;
+
+        let (center, radius, region) = match brush.radius {
+            BrushRadius::Texture(radius) => (
+                uv.extend(0.0),
+                radius,
+                self.texel_region(uv - vec2(radius, radius), uv + vec2(radius, radius)),
+            ),
+            BrushRadius::World(radius) => {
+                let center = (self.transformation * position.extend(1.0)).truncate();
+                (center, radius, self.world_region(center, radius))
+            }
+        };
+        let viewport = match region {
+            Some(viewport) if radius > 0.0 => viewport,
+            _ => return Ok(()),
+        };
+
+        // Store the part of the texture that is about to change
+        let pixels = self.texture.read_internal(viewport)?;
+        if !self.in_stroke || self.undo_steps.is_empty() {
+            self.push_undo_step();
+        }
+        self.undo_steps.back_mut().unwrap().push((viewport, pixels));
+
+        let fragment_shader = match brush.radius {
+            BrushRadius::Texture(_) => format!(
+                "#define TEXTURE_SPACE_BRUSH\n{}",
+                include_str!("shaders/paint_brush.frag")
+            ),
+            BrushRadius::World(_) => include_str!("shaders/paint_brush.frag").to_string(),
+        };
+        RenderTarget::new_color_internal(&self.context, &self.texture)?.write(
+            ClearState::none(),
+            || {
+                self.context.effect(&fragment_shader, |effect| {
+                    effect.use_texture("positionTexture", &self.position_texture)?;
+                    if effect.requires_uniform("transformation") {
+                        effect.use_uniform("transformation", self.transformation)?;
+                    }
+                    effect.use_uniform("brushCenter", center)?;
+                    effect.use_uniform("brushRadius", radius)?;
+                    effect.use_uniform("brushHardness", brush.hardness.max(0.0).min(0.999))?;
+                    effect.use_uniform("brushColor", brush.color.to_vec4())?;
+                    effect.apply(
+                        RenderStates {
+                            write_mask: WriteMask::COLOR,
+                            blend: Blend::TRANSPARENCY,
+                            ..Default::default()
+                        },
+                        viewport,
+                    )
+                })
+            },
+        )
+    }
+
+    ///
+    /// Undoes the last call to [PaintableTexture::paint] or the last stroke, see [PaintableTexture::begin_stroke].
+    /// Returns whether or not there was anything to undo.
+    ///
+    pub fn undo(&mut self) -> bool {
+        self.in_stroke = false;
+        while let Some(step) = self.undo_steps.pop_back() {
+            if !step.is_empty() {
+                // Restore in the reverse order, since the changed parts may overlap
+                for (viewport, pixels) in step.iter().rev() {
+                    self.texture.fill_sub_image_internal(*viewport, pixels);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    fn push_undo_step(&mut self) {
+        if self.max_undo_steps == 0 {
+            return;
+        }
+        while self.undo_steps.len() >= self.max_undo_steps {
+            self.undo_steps.pop_front();
+        }
+        self.undo_steps.push_back(Vec::new());
+    }
+
+    // Renders the position of the surface mapped to each texel, where the alpha value is one for covered texels and zero otherwise
+    fn render_positions(&mut self) -> ThreeDResult<()> {
+        let mut positions = Vec::with_capacity(self.triangles.len() * 9);
+        let mut uvs = Vec::with_capacity(self.triangles.len() * 6);
+        for (p, uv) in self.triangles.iter() {
+            for i in 0..3 {
+                positions.extend_from_slice(&[p[i].x, p[i].y, p[i].z]);
+                uvs.extend_from_slice(&[uv[i].x, uv[i].y]);
+            }
+        }
+        let position_buffer = VertexBuffer::new_with_static(&self.context, &positions)?;
+        let uv_buffer = VertexBuffer::new_with_static(&self.context, &uvs)?;
+        let viewport = Viewport::new_at_origo(self.texture.width(), self.texture.height());
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            cull: Cull::None,
+            ..Default::default()
+        };
+        let context = self.context.clone();
+        let vertex_count = (self.triangles.len() * 3) as u32;
+
+        let mut source = new_position_texture(&context, viewport.width, viewport.height)?;
+        source.write(ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+            context.program(
+                include_str!("shaders/uv_unwrap.vert"),
+                include_str!("shaders/uv_unwrap.frag"),
+                |program| {
+                    program.use_attribute_vec3("position", &position_buffer)?;
+                    program.use_attribute_vec2("uv_coordinates", &uv_buffer)?;
+                    program.draw_arrays(render_states, viewport, vertex_count);
+                    Ok(())
+                },
+            )
+        })?;
+
+        // Grow the covered area by one texel in each dilation pass
+        let mut target = std::mem::replace(&mut self.position_texture, source);
+        for _ in 0..self.dilation {
+            let source = &self.position_texture;
+            target.write(ClearState::none(), || {
+                context.effect(include_str!("shaders/uv_dilation.frag"), |effect| {
+                    effect.use_texture("positionTexture", source)?;
+                    effect.apply(render_states, viewport)
+                })
+            })?;
+            std::mem::swap(&mut self.position_texture, &mut target);
+        }
+        Ok(())
+    }
+
+    // Returns the texels covered by the uv coordinates of all triangles within the given radius of the given world space position
+    fn world_region(&self, center: Vec3, radius: f32) -> Option<Viewport> {
+        let mut min = vec2(f32::INFINITY, f32::INFINITY);
+        let mut max = vec2(f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for (positions, uvs) in self.triangles.iter() {
+            let mut aabb = AxisAlignedBoundingBox::EMPTY;
+            for p in positions.iter() {
+                let p = (self.transformation * p.extend(1.0)).truncate();
+                aabb.expand(&[p.x, p.y, p.z]);
+            }
+            let closest = vec3(
+                center.x.max(aabb.min().x).min(aabb.max().x),
+                center.y.max(aabb.min().y).min(aabb.max().y),
+                center.z.max(aabb.min().z).min(aabb.max().z),
+            );
+            if closest.distance2(center) <= radius * radius {
+                for uv in uvs.iter() {
+                    min = vec2(min.x.min(uv.x), min.y.min(uv.y));
+                    max = vec2(max.x.max(uv.x), max.y.max(uv.y));
+                }
+            }
+        }
+        self.texel_region(min, max)
+    }
+
+    // Returns the texels within the given uv coordinates expanded by the dilation
+    fn texel_region(&self, min: Vec2, max: Vec2) -> Option<Viewport> {
+        if min.x > max.x || min.y > max.y {
+            return None;
+        }
+        let width = self.texture.width() as f32;
+        let height = self.texture.height() as f32;
+        let margin = self.dilation as f32 + 1.0;
+        let x0 = (min.x * width - margin).floor().max(0.0);
+        let y0 = (min.y * height - margin).floor().max(0.0);
+        let x1 = (max.x * width + margin).ceil().min(width);
+        let y1 = (max.y * height + margin).ceil().min(height);
+        if x1 <= x0 || y1 <= y0 {
+            return None;
+        }
+        Some(Viewport {
+            x: x0 as i32,
+            y: y0 as i32,
+            width: (x1 - x0) as u32,
+            height: (y1 - y0) as u32,
+        })
+    }
+}
+
+fn new_position_texture(
+    context: &Context,
+    width: u32,
+    height: u32,
+) -> ThreeDResult<Texture2D<f32>> {
+    Texture2D::new_empty(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+}
+
+fn to_rgba(data: &[u8], format: Format) -> Vec<u8> {
+    match format {
+        Format::R => data.iter().flat_map(|r| vec![*r, *r, *r, 255]).collect(),
+        Format::RG => data
+            .chunks(2)
+            .flat_map(|c| vec![c[0], c[0], c[0], c[1]])
+            .collect(),
+        Format::RGB => data
+            .chunks(3)
+            .flat_map(|c| vec![c[0], c[1], c[2], 255])
+            .collect(),
+        Format::RGBA => data.to_vec(),
+    }
+}
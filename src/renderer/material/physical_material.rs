@@ -14,6 +14,10 @@ pub struct PhysicalMaterial {
     pub albedo: Color,
     /// Texture with albedo base colors, also called diffuse color. Assumed to be in sRGB with or without an alpha channel.
     pub albedo_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::albedo_texture], for example to repeat the texture.
+    pub albedo_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::albedo_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub albedo_texture_uv_set: u8,
     /// A value in the range `[0..1]` specifying how metallic the material is.
     pub metallic: f32,
     /// A value in the range `[0..1]` specifying how rough the material surface is.
@@ -21,25 +25,72 @@ pub struct PhysicalMaterial {
     /// Texture containing the metallic and roughness parameters which are multiplied with the [Self::metallic] and [Self::roughness] values in the shader.
     /// The metallic values are sampled from the blue channel and the roughness from the green channel.
     pub metallic_roughness_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::metallic_roughness_texture].
+    pub metallic_roughness_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::metallic_roughness_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub metallic_roughness_texture_uv_set: u8,
     /// A scalar multiplier controlling the amount of occlusion applied from the [Self::occlusion_texture]. A value of 0.0 means no occlusion. A value of 1.0 means full occlusion.
     pub occlusion_strength: f32,
     /// An occlusion map. Higher values indicate areas that should receive full indirect lighting and lower values indicate no indirect lighting.
     /// The occlusion values are sampled from the red channel.
     pub occlusion_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::occlusion_texture].
+    pub occlusion_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::occlusion_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub occlusion_texture_uv_set: u8,
     /// A scalar multiplier applied to each normal vector of the [Self::normal_texture].
     pub normal_scale: f32,
     /// A tangent space normal map, also known as bump map.
     pub normal_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::normal_texture].
+    pub normal_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::normal_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub normal_texture_uv_set: u8,
     /// Render states used when the color is opaque (has a maximal alpha value).
     pub opaque_render_states: RenderStates,
     /// Render states used when the color is transparent (does not have a maximal alpha value).
     pub transparent_render_states: RenderStates,
+    /// Whether or not the back side of the geometry is rendered.
+    /// If true, the back side is rendered and lit using the flipped normal, otherwise the back faces are culled.
+    /// Culling specified by the render states takes precedence. The default is true.
+    pub double_sided: bool,
 
     pub emissive: Color,
     pub emissive_texture: Option<Rc<Texture2D<u8>>>,
+    /// The transformation applied to the uv coordinates before sampling the [Self::emissive_texture].
+    pub emissive_texture_transform: Mat3,
+    /// The set of uv coordinates used for sampling the [Self::emissive_texture], ie. 0 for [CPUMesh::uvs] and 1 for [CPUMesh::uvs2].
+    pub emissive_texture_uv_set: u8,
+    /// How the alpha value of the albedo is used. When dithering, the material is never transparent and the opaque render states are always used.
+    pub alpha_mode: AlphaMode,
+    /// How the material is rendered when it is transparent, for example in two passes to blend the triangles of a closed mesh correctly.
+    pub transparency_mode: TransparencyMode,
 }
 
 impl PhysicalMaterial {
+    ///
+    /// Returns a builder for a physical material, see [PhysicalMaterialBuilder].
+    /// This is an alternative to [PhysicalMaterial::new] and struct update syntax where each parameter is named and has a default value.
+    ///
+    /// ```no_run
+    /// # use three_d::*;
+    /// # let context = Context::new().unwrap();
+    /// # let normal_texture = CPUTexture::<u8>::default();
+    /// let material = PhysicalMaterial::builder()
+    ///     .albedo(Color::new_opaque(200, 180, 160))
+    ///     .roughness(0.4)
+    ///     .metallic(1.0)
+    ///     .normal_texture(normal_texture)
+    ///     .build(&context)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    pub fn builder() -> PhysicalMaterialBuilder {
+        PhysicalMaterialBuilder {
+            cpu_material: CPUMaterial::default(),
+        }
+    }
+
     ///
     /// Constructs a new physical material from a [CPUMaterial].
     /// If the input contains an [CPUMaterial::occlusion_metallic_roughness_texture], this texture is used for both
@@ -84,12 +135,20 @@ impl PhysicalMaterial {
             name: cpu_material.name.clone(),
             albedo: cpu_material.albedo,
             albedo_texture,
+            albedo_texture_transform: cpu_material.albedo_texture_transform,
+            albedo_texture_uv_set: cpu_material.albedo_texture_uv_set,
             metallic: cpu_material.metallic,
             roughness: cpu_material.roughness,
             metallic_roughness_texture,
+            metallic_roughness_texture_transform: cpu_material.metallic_roughness_texture_transform,
+            metallic_roughness_texture_uv_set: cpu_material.metallic_roughness_texture_uv_set,
             normal_texture,
+            normal_texture_transform: cpu_material.normal_texture_transform,
+            normal_texture_uv_set: cpu_material.normal_texture_uv_set,
             normal_scale: cpu_material.normal_scale,
             occlusion_texture,
+            occlusion_texture_transform: cpu_material.occlusion_texture_transform,
+            occlusion_texture_uv_set: cpu_material.occlusion_texture_uv_set,
             occlusion_strength: cpu_material.occlusion_strength,
             opaque_render_states: RenderStates::default(),
             transparent_render_states: RenderStates {
@@ -99,42 +158,285 @@ impl PhysicalMaterial {
             },
             emissive: cpu_material.emissive,
             emissive_texture,
+            emissive_texture_transform: cpu_material.emissive_texture_transform,
+            emissive_texture_uv_set: cpu_material.emissive_texture_uv_set,
+            alpha_mode: AlphaMode::Blend,
+            transparency_mode: TransparencyMode::SinglePass,
+            double_sided: cpu_material.double_sided,
         })
     }
-}
 
-impl Material for PhysicalMaterial {
-    fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
-        let mut output = lights.fragment_shader_source();
-        if self.albedo_texture.is_some()
-            || self.metallic_roughness_texture.is_some()
-            || self.normal_texture.is_some()
-            || self.occlusion_texture.is_some()
-            || self.emissive_texture.is_some()
-        {
+    ///
+    /// Returns an instance of this material which shares the program with this material and all other instances of it,
+    /// but has its own colors, scalars and textures, see [PhysicalMaterialInstance].
+    ///
+    pub fn instance(&self) -> PhysicalMaterialInstance {
+        PhysicalMaterialInstance::new(Rc::new(self.clone()))
+    }
+
+    // Returns a key which is equal for materials with the same fragment shader source, ie. with the same textures, texture transforms and uv sets present
+    // and the same alpha mode
+    pub(in crate::renderer) fn configuration_key(&self) -> u32 {
+        let dither = match self.alpha_mode {
+            AlphaMode::Blend => 0,
+            AlphaMode::Dither { .. } => 1 << 15,
+        };
+        dither
+            | self.texture_slots().iter().enumerate().fold(
+                0,
+                |key, (i, (_, texture, transform, uv_set))| {
+                    let mut key = key;
+                    if texture.is_some() {
+                        key |= 1 << (3 * i);
+                        if *transform != Mat3::identity() {
+                            key |= 1 << (3 * i + 1);
+                        }
+                        if *uv_set != 0 {
+                            key |= 1 << (3 * i + 2);
+                        }
+                    }
+                    key
+                },
+            )
+    }
+
+    // The name used in the shader defines, the texture, the texture transform and the uv set of each of the textures
+    fn texture_slots(&self) -> [(&str, &Option<Rc<Texture2D<u8>>>, Mat3, u8); 5] {
+        [
+            (
+                "ALBEDO",
+                &self.albedo_texture,
+                self.albedo_texture_transform,
+                self.albedo_texture_uv_set,
+            ),
+            (
+                "METALLIC_ROUGHNESS",
+                &self.metallic_roughness_texture,
+                self.metallic_roughness_texture_transform,
+                self.metallic_roughness_texture_uv_set,
+            ),
+            (
+                "OCCLUSION",
+                &self.occlusion_texture,
+                self.occlusion_texture_transform,
+                self.occlusion_texture_uv_set,
+            ),
+            (
+                "NORMAL",
+                &self.normal_texture,
+                self.normal_texture_transform,
+                self.normal_texture_uv_set,
+            ),
+            (
+                "EMISSIVE",
+                &self.emissive_texture,
+                self.emissive_texture_transform,
+                self.emissive_texture_uv_set,
+            ),
+        ]
+    }
+
+    // The fragment shader source given the source of the lights, so the lights source can be reused for several materials
+    pub(in crate::renderer) fn fragment_shader_source_with_lights(
+        &self,
+        use_vertex_colors: bool,
+        lights_source: String,
+    ) -> String {
+        let mut output = lights_source;
+        let slots = self.texture_slots();
+        // Only the uv coordinates used by any of the textures are declared, so the mesh only needs to have those
+        let uses_uv_set = |set: bool| {
+            slots
+                .iter()
+                .any(|(_, texture, _, uv_set)| texture.is_some() && (*uv_set != 0) == set)
+        };
+        if uses_uv_set(false) {
             output.push_str("in vec2 uvs;\n");
-            if self.albedo_texture.is_some() {
-                output.push_str("#define USE_ALBEDO_TEXTURE;\n");
-            }
-            if self.metallic_roughness_texture.is_some() {
-                output.push_str("#define USE_METALLIC_ROUGHNESS_TEXTURE;\n");
-            }
-            if self.occlusion_texture.is_some() {
-                output.push_str("#define USE_OCCLUSION_TEXTURE;\n");
-            }
-            if self.normal_texture.is_some() {
-                output.push_str("#define USE_NORMAL_TEXTURE;\nin vec3 tang;\nin vec3 bitang;\n");
-            }
-            if self.emissive_texture.is_some() {
-                output.push_str("#define USE_EMISSIVE_TEXTURE;\n");
+        }
+        if uses_uv_set(true) {
+            output.push_str("in vec2 uvs2;\n");
+        }
+        for (name, texture, transform, uv_set) in slots.iter() {
+            if texture.is_some() {
+                output.push_str(&format!("#define USE_{}_TEXTURE;\n", name));
+                if *transform != Mat3::identity() {
+                    output.push_str(&format!("#define USE_{}_TEXTURE_TRANSFORM;\n", name));
+                }
+                if *uv_set != 0 {
+                    output.push_str(&format!("#define USE_{}_TEXTURE_UVS2;\n", name));
+                }
             }
         }
+        if self.normal_texture.is_some() {
+            output.push_str("in vec3 tang;\nin vec3 bitang;\n");
+        }
         if use_vertex_colors {
             output.push_str("#define USE_VERTEX_COLORS\nin vec4 col;\n");
         }
+        output.push_str(self.alpha_mode.fragment_shader_source());
         output.push_str(include_str!("shaders/physical_material.frag"));
         output
     }
+
+    // The render states for the given transparency, which is given by the instance when rendering a material instance
+    pub(in crate::renderer) fn render_states_with_transparency(
+        &self,
+        transparent: bool,
+    ) -> RenderStates {
+        let render_states = if transparent {
+            self.transparent_render_states
+        } else {
+            self.opaque_render_states
+        };
+        double_sided_render_states(render_states, self.double_sided)
+    }
+}
+
+///
+/// A builder for a [PhysicalMaterial], constructed using [PhysicalMaterial::builder].
+/// The textures are uploaded to the GPU and the parameters are validated when the material is built,
+/// so an invalid parameter gives a descriptive error instead of NaNs in the shader.
+/// The defaults are the same as for [CPUMaterial::default].
+///
+pub struct PhysicalMaterialBuilder {
+    cpu_material: CPUMaterial,
+}
+
+impl PhysicalMaterialBuilder {
+    ///
+    /// The name of the material, used for matching geometry and material.
+    ///
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.cpu_material.name = name.into();
+        self
+    }
+
+    ///
+    /// The albedo base color, assumed to be in linear color space, see [PhysicalMaterial::albedo].
+    ///
+    pub fn albedo(mut self, albedo: Color) -> Self {
+        self.cpu_material.albedo = albedo;
+        self
+    }
+
+    ///
+    /// The albedo texture, assumed to be in sRGB, see [PhysicalMaterial::albedo_texture].
+    ///
+    pub fn albedo_texture(mut self, texture: CPUTexture<u8>) -> Self {
+        self.cpu_material.albedo_texture = Some(texture);
+        self
+    }
+
+    ///
+    /// How metallic the material is, which must be in the range `[0..1]`, see [PhysicalMaterial::metallic].
+    ///
+    pub fn metallic(mut self, metallic: f32) -> Self {
+        self.cpu_material.metallic = metallic;
+        self
+    }
+
+    ///
+    /// How rough the material surface is, which must be in the range `[0..1]`, see [PhysicalMaterial::roughness].
+    ///
+    pub fn roughness(mut self, roughness: f32) -> Self {
+        self.cpu_material.roughness = roughness;
+        self
+    }
+
+    ///
+    /// The metallic and roughness texture, see [PhysicalMaterial::metallic_roughness_texture].
+    ///
+    pub fn metallic_roughness_texture(mut self, texture: CPUTexture<u8>) -> Self {
+        self.cpu_material.metallic_roughness_texture = Some(texture);
+        self
+    }
+
+    ///
+    /// The occlusion texture, see [PhysicalMaterial::occlusion_texture].
+    ///
+    pub fn occlusion_texture(mut self, texture: CPUTexture<u8>) -> Self {
+        self.cpu_material.occlusion_texture = Some(texture);
+        self
+    }
+
+    ///
+    /// The amount of occlusion applied from the occlusion texture, which must be in the range `[0..1]`, see [PhysicalMaterial::occlusion_strength].
+    ///
+    pub fn occlusion_strength(mut self, occlusion_strength: f32) -> Self {
+        self.cpu_material.occlusion_strength = occlusion_strength;
+        self
+    }
+
+    ///
+    /// The tangent space normal map, see [PhysicalMaterial::normal_texture].
+    ///
+    pub fn normal_texture(mut self, texture: CPUTexture<u8>) -> Self {
+        self.cpu_material.normal_texture = Some(texture);
+        self
+    }
+
+    ///
+    /// The multiplier applied to each normal vector of the normal texture, see [PhysicalMaterial::normal_scale].
+    ///
+    pub fn normal_scale(mut self, normal_scale: f32) -> Self {
+        self.cpu_material.normal_scale = normal_scale;
+        self
+    }
+
+    ///
+    /// The emissive color, see [PhysicalMaterial::emissive].
+    ///
+    pub fn emissive(mut self, emissive: Color) -> Self {
+        self.cpu_material.emissive = emissive;
+        self
+    }
+
+    ///
+    /// The emissive texture, see [PhysicalMaterial::emissive_texture].
+    ///
+    pub fn emissive_texture(mut self, texture: CPUTexture<u8>) -> Self {
+        self.cpu_material.emissive_texture = Some(texture);
+        self
+    }
+
+    ///
+    /// Whether or not the back side of the geometry is rendered, see [PhysicalMaterial::double_sided].
+    ///
+    pub fn double_sided(mut self, double_sided: bool) -> Self {
+        self.cpu_material.double_sided = double_sided;
+        self
+    }
+
+    ///
+    /// Validates the parameters, uploads the textures to the GPU and builds the material, see [PhysicalMaterial::new].
+    ///
+    /// # Errors
+    /// Will return an error if the metallic, roughness or occlusion strength is outside the range `[0..1]`
+    /// or if a texture cannot be uploaded.
+    ///
+    pub fn build(self, context: &Context) -> ThreeDResult<PhysicalMaterial> {
+        for (name, value) in [
+            ("metallic value", self.cpu_material.metallic),
+            ("roughness", self.cpu_material.roughness),
+            ("occlusion strength", self.cpu_material.occlusion_strength),
+        ]
+        .iter()
+        {
+            if !(*value >= 0.0 && *value <= 1.0) {
+                Err(RendererError::InvalidMaterialParameter(
+                    name.to_string(),
+                    *value,
+                ))?;
+            }
+        }
+        PhysicalMaterial::new(context, &self.cpu_material)
+    }
+}
+
+impl Material for PhysicalMaterial {
+    fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
+        self.fragment_shader_source_with_lights(use_vertex_colors, lights.fragment_shader_source())
+    }
     fn use_uniforms(
         &self,
         program: &Program,
@@ -150,40 +452,66 @@ impl Material for PhysicalMaterial {
         }
         if let Some(ref texture) = self.albedo_texture {
             program.use_texture("albedoTexture", texture.as_ref())?;
+            if self.albedo_texture_transform != Mat3::identity() {
+                program
+                    .use_uniform_mat3("albedoTextureTransform", &self.albedo_texture_transform)?;
+            }
         }
         if let Some(ref texture) = self.metallic_roughness_texture {
             program.use_texture("metallicRoughnessTexture", texture.as_ref())?;
+            if self.metallic_roughness_texture_transform != Mat3::identity() {
+                program.use_uniform_mat3(
+                    "metallicRoughnessTextureTransform",
+                    &self.metallic_roughness_texture_transform,
+                )?;
+            }
         }
         if let Some(ref texture) = self.occlusion_texture {
             program.use_uniform_float("occlusionStrength", &self.occlusion_strength)?;
             program.use_texture("occlusionTexture", texture.as_ref())?;
+            if self.occlusion_texture_transform != Mat3::identity() {
+                program.use_uniform_mat3(
+                    "occlusionTextureTransform",
+                    &self.occlusion_texture_transform,
+                )?;
+            }
         }
         if let Some(ref texture) = self.normal_texture {
             program.use_uniform_float("normalScale", &self.normal_scale)?;
             program.use_texture("normalTexture", texture.as_ref())?;
+            if self.normal_texture_transform != Mat3::identity() {
+                program
+                    .use_uniform_mat3("normalTextureTransform", &self.normal_texture_transform)?;
+            }
         }
         if program.requires_uniform("emissiveTexture") {
             if let Some(ref texture) = self.emissive_texture {
                 program.use_texture("emissiveTexture", texture.as_ref())?;
+                if self.emissive_texture_transform != Mat3::identity() {
+                    program.use_uniform_mat3(
+                        "emissiveTextureTransform",
+                        &self.emissive_texture_transform,
+                    )?;
+                }
             }
         }
-        Ok(())
+        self.alpha_mode.use_uniforms(program)
     }
 
     fn render_states(&self) -> RenderStates {
-        if self.is_transparent() {
-            self.transparent_render_states
-        } else {
-            self.opaque_render_states
-        }
+        self.render_states_with_transparency(self.is_transparent())
     }
     fn is_transparent(&self) -> bool {
-        self.albedo.a != 255
-            || self
-                .albedo_texture
-                .as_ref()
-                .map(|t| t.is_transparent())
-                .unwrap_or(false)
+        self.alpha_mode == AlphaMode::Blend
+            && (self.albedo.a != 255
+                || self
+                    .albedo_texture
+                    .as_ref()
+                    .map(|t| t.is_transparent())
+                    .unwrap_or(false))
+    }
+    fn transparency_mode(&self) -> TransparencyMode {
+        self.transparency_mode
     }
 }
 
@@ -193,12 +521,20 @@ impl Default for PhysicalMaterial {
             name: "default".to_string(),
             albedo: Color::WHITE,
             albedo_texture: None,
+            albedo_texture_transform: Mat3::identity(),
+            albedo_texture_uv_set: 0,
             metallic: 0.0,
             roughness: 1.0,
             metallic_roughness_texture: None,
+            metallic_roughness_texture_transform: Mat3::identity(),
+            metallic_roughness_texture_uv_set: 0,
             normal_texture: None,
+            normal_texture_transform: Mat3::identity(),
+            normal_texture_uv_set: 0,
             normal_scale: 1.0,
             occlusion_texture: None,
+            occlusion_texture_transform: Mat3::identity(),
+            occlusion_texture_uv_set: 0,
             occlusion_strength: 1.0,
             opaque_render_states: RenderStates::default(),
             transparent_render_states: RenderStates {
@@ -208,6 +544,11 @@ impl Default for PhysicalMaterial {
             },
             emissive: Color::BLACK,
             emissive_texture: None,
+            emissive_texture_transform: Mat3::identity(),
+            emissive_texture_uv_set: 0,
+            alpha_mode: AlphaMode::Blend,
+            transparency_mode: TransparencyMode::SinglePass,
+            double_sided: true,
         }
     }
 }
@@ -0,0 +1,328 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::rc::Rc;
+
+///
+/// An instance of a [PhysicalMaterial], created by [PhysicalMaterial::instance], which only holds the uniform values of the material,
+/// ie. the colors, scalars and textures, while the configuration which affects the shader source, ie. which textures exist and their transformations,
+/// as well as the render states are given by the base material.
+/// All instances of base materials with the same configuration are rendered with the same program,
+/// so adding instances never compiles a program.
+/// Render many models with material instances using [render_material_instances] to look up the program once per configuration
+/// and only send the uniform values which differ from the previously rendered instance.
+///
+#[derive(Clone)]
+pub struct PhysicalMaterialInstance {
+    base: Rc<PhysicalMaterial>,
+    albedo: Color,
+    albedo_texture: Option<Rc<Texture2D<u8>>>,
+    metallic: f32,
+    roughness: f32,
+    metallic_roughness_texture: Option<Rc<Texture2D<u8>>>,
+    occlusion_strength: f32,
+    occlusion_texture: Option<Rc<Texture2D<u8>>>,
+    normal_scale: f32,
+    normal_texture: Option<Rc<Texture2D<u8>>>,
+    emissive: Color,
+    emissive_texture: Option<Rc<Texture2D<u8>>>,
+}
+
+impl PhysicalMaterialInstance {
+    pub(in crate::renderer) fn new(base: Rc<PhysicalMaterial>) -> Self {
+        Self {
+            albedo: base.albedo,
+            albedo_texture: base.albedo_texture.clone(),
+            metallic: base.metallic,
+            roughness: base.roughness,
+            metallic_roughness_texture: base.metallic_roughness_texture.clone(),
+            occlusion_strength: base.occlusion_strength,
+            occlusion_texture: base.occlusion_texture.clone(),
+            normal_scale: base.normal_scale,
+            normal_texture: base.normal_texture.clone(),
+            emissive: base.emissive,
+            emissive_texture: base.emissive_texture.clone(),
+            base,
+        }
+    }
+
+    ///
+    /// Returns another instance of the same base material with the uniform values of this instance.
+    ///
+    pub fn instance(&self) -> Self {
+        self.clone()
+    }
+
+    ///
+    /// Returns the base material which this is an instance of.
+    ///
+    pub fn base(&self) -> &PhysicalMaterial {
+        &self.base
+    }
+
+    /// Sets the albedo base color, see [PhysicalMaterial::albedo].
+    pub fn set_albedo(&mut self, albedo: Color) {
+        self.albedo = albedo;
+    }
+
+    /// Returns the albedo base color, see [PhysicalMaterial::albedo].
+    pub fn albedo(&self) -> Color {
+        self.albedo
+    }
+
+    /// Sets how metallic the material is, see [PhysicalMaterial::metallic].
+    pub fn set_metallic(&mut self, metallic: f32) {
+        self.metallic = metallic;
+    }
+
+    /// Returns how metallic the material is, see [PhysicalMaterial::metallic].
+    pub fn metallic(&self) -> f32 {
+        self.metallic
+    }
+
+    /// Sets how rough the material surface is, see [PhysicalMaterial::roughness].
+    pub fn set_roughness(&mut self, roughness: f32) {
+        self.roughness = roughness;
+    }
+
+    /// Returns how rough the material surface is, see [PhysicalMaterial::roughness].
+    pub fn roughness(&self) -> f32 {
+        self.roughness
+    }
+
+    /// Sets the amount of occlusion applied from the occlusion texture, see [PhysicalMaterial::occlusion_strength].
+    pub fn set_occlusion_strength(&mut self, occlusion_strength: f32) {
+        self.occlusion_strength = occlusion_strength;
+    }
+
+    /// Returns the amount of occlusion applied from the occlusion texture, see [PhysicalMaterial::occlusion_strength].
+    pub fn occlusion_strength(&self) -> f32 {
+        self.occlusion_strength
+    }
+
+    /// Sets the scale of the normals from the normal texture, see [PhysicalMaterial::normal_scale].
+    pub fn set_normal_scale(&mut self, normal_scale: f32) {
+        self.normal_scale = normal_scale;
+    }
+
+    /// Returns the scale of the normals from the normal texture, see [PhysicalMaterial::normal_scale].
+    pub fn normal_scale(&self) -> f32 {
+        self.normal_scale
+    }
+
+    /// Sets the emissive color, see [PhysicalMaterial::emissive].
+    pub fn set_emissive(&mut self, emissive: Color) {
+        self.emissive = emissive;
+    }
+
+    /// Returns the emissive color, see [PhysicalMaterial::emissive].
+    pub fn emissive(&self) -> Color {
+        self.emissive
+    }
+
+    ///
+    /// Sets the albedo texture, see [PhysicalMaterial::albedo_texture].
+    ///
+    /// # Errors
+    /// Returns an error if the base material has no albedo texture, since the texture is part of the configuration of the base material.
+    ///
+    pub fn set_albedo_texture(&mut self, texture: Rc<Texture2D<u8>>) -> ThreeDResult<()> {
+        set_texture(&mut self.albedo_texture, texture, "albedo")
+    }
+
+    ///
+    /// Sets the metallic roughness texture, see [PhysicalMaterial::metallic_roughness_texture].
+    ///
+    /// # Errors
+    /// Returns an error if the base material has no metallic roughness texture, since the texture is part of the configuration of the base material.
+    ///
+    pub fn set_metallic_roughness_texture(
+        &mut self,
+        texture: Rc<Texture2D<u8>>,
+    ) -> ThreeDResult<()> {
+        set_texture(
+            &mut self.metallic_roughness_texture,
+            texture,
+            "metallic roughness",
+        )
+    }
+
+    ///
+    /// Sets the occlusion texture, see [PhysicalMaterial::occlusion_texture].
+    ///
+    /// # Errors
+    /// Returns an error if the base material has no occlusion texture, since the texture is part of the configuration of the base material.
+    ///
+    pub fn set_occlusion_texture(&mut self, texture: Rc<Texture2D<u8>>) -> ThreeDResult<()> {
+        set_texture(&mut self.occlusion_texture, texture, "occlusion")
+    }
+
+    ///
+    /// Sets the normal texture, see [PhysicalMaterial::normal_texture].
+    ///
+    /// # Errors
+    /// Returns an error if the base material has no normal texture, since the texture is part of the configuration of the base material.
+    ///
+    pub fn set_normal_texture(&mut self, texture: Rc<Texture2D<u8>>) -> ThreeDResult<()> {
+        set_texture(&mut self.normal_texture, texture, "normal")
+    }
+
+    ///
+    /// Sets the emissive texture, see [PhysicalMaterial::emissive_texture].
+    ///
+    /// # Errors
+    /// Returns an error if the base material has no emissive texture, since the texture is part of the configuration of the base material.
+    ///
+    pub fn set_emissive_texture(&mut self, texture: Rc<Texture2D<u8>>) -> ThreeDResult<()> {
+        set_texture(&mut self.emissive_texture, texture, "emissive")
+    }
+
+    ///
+    /// Sends the uniform values of this instance which differ from the given previous instance rendered with the same program,
+    /// or all uniform values if there is no previous instance. The lights are not sent.
+    ///
+    pub(in crate::renderer) fn use_instance_uniforms(
+        &self,
+        program: &Program,
+        previous: Option<&Self>,
+    ) -> ThreeDResult<()> {
+        let changed = |value: fn(&Self) -> f32| previous.map_or(true, |p| value(p) != value(self));
+        if changed(|m| m.metallic) {
+            program.use_uniform_float("metallic", &self.metallic)?;
+        }
+        if changed(|m| m.roughness) {
+            program.use_uniform_float("roughness", &self.roughness)?;
+        }
+        if previous.map_or(true, |p| p.albedo != self.albedo) {
+            program.use_uniform("albedo", self.albedo.to_vec4())?;
+        }
+        if program.requires_uniform("emissive")
+            && previous.map_or(true, |p| p.emissive != self.emissive)
+        {
+            program.use_uniform_vec3("emissive", &self.emissive.to_vec3())?;
+        }
+        if let Some(ref texture) = self.albedo_texture {
+            if texture_changed(previous.map(|p| &p.albedo_texture), texture) {
+                program.use_texture("albedoTexture", texture.as_ref())?;
+            }
+            let transform = self.base.albedo_texture_transform;
+            if transform != Mat3::identity()
+                && previous.map_or(true, |p| p.base.albedo_texture_transform != transform)
+            {
+                program.use_uniform_mat3("albedoTextureTransform", &transform)?;
+            }
+        }
+        if let Some(ref texture) = self.metallic_roughness_texture {
+            if texture_changed(previous.map(|p| &p.metallic_roughness_texture), texture) {
+                program.use_texture("metallicRoughnessTexture", texture.as_ref())?;
+            }
+            let transform = self.base.metallic_roughness_texture_transform;
+            if transform != Mat3::identity()
+                && previous.map_or(true, |p| {
+                    p.base.metallic_roughness_texture_transform != transform
+                })
+            {
+                program.use_uniform_mat3("metallicRoughnessTextureTransform", &transform)?;
+            }
+        }
+        if let Some(ref texture) = self.occlusion_texture {
+            if changed(|m| m.occlusion_strength) {
+                program.use_uniform_float("occlusionStrength", &self.occlusion_strength)?;
+            }
+            if texture_changed(previous.map(|p| &p.occlusion_texture), texture) {
+                program.use_texture("occlusionTexture", texture.as_ref())?;
+            }
+            let transform = self.base.occlusion_texture_transform;
+            if transform != Mat3::identity()
+                && previous.map_or(true, |p| p.base.occlusion_texture_transform != transform)
+            {
+                program.use_uniform_mat3("occlusionTextureTransform", &transform)?;
+            }
+        }
+        if let Some(ref texture) = self.normal_texture {
+            if changed(|m| m.normal_scale) {
+                program.use_uniform_float("normalScale", &self.normal_scale)?;
+            }
+            if texture_changed(previous.map(|p| &p.normal_texture), texture) {
+                program.use_texture("normalTexture", texture.as_ref())?;
+            }
+            let transform = self.base.normal_texture_transform;
+            if transform != Mat3::identity()
+                && previous.map_or(true, |p| p.base.normal_texture_transform != transform)
+            {
+                program.use_uniform_mat3("normalTextureTransform", &transform)?;
+            }
+        }
+        if program.requires_uniform("emissiveTexture") {
+            if let Some(ref texture) = self.emissive_texture {
+                if texture_changed(previous.map(|p| &p.emissive_texture), texture) {
+                    program.use_texture("emissiveTexture", texture.as_ref())?;
+                }
+                let transform = self.base.emissive_texture_transform;
+                if transform != Mat3::identity()
+                    && previous.map_or(true, |p| p.base.emissive_texture_transform != transform)
+                {
+                    program.use_uniform_mat3("emissiveTextureTransform", &transform)?;
+                }
+            }
+        }
+        if previous.map_or(true, |p| p.base.alpha_mode != self.base.alpha_mode) {
+            self.base.alpha_mode.use_uniforms(program)?;
+        }
+        Ok(())
+    }
+}
+
+impl Material for PhysicalMaterialInstance {
+    fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
+        self.base.fragment_shader_source(use_vertex_colors, lights)
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        lights.use_uniforms(program, camera)?;
+        self.use_instance_uniforms(program, None)
+    }
+    fn render_states(&self) -> RenderStates {
+        self.base
+            .render_states_with_transparency(self.is_transparent())
+    }
+    fn is_transparent(&self) -> bool {
+        self.base.alpha_mode == AlphaMode::Blend
+            && (self.albedo.a != 255
+                || self
+                    .albedo_texture
+                    .as_ref()
+                    .map(|t| t.is_transparent())
+                    .unwrap_or(false))
+    }
+    fn transparency_mode(&self) -> TransparencyMode {
+        self.base.transparency_mode
+    }
+}
+
+// The textures of an instance can only be replaced, since whether or not a texture exists is given by the base material
+fn set_texture(
+    current: &mut Option<Rc<Texture2D<u8>>>,
+    texture: Rc<Texture2D<u8>>,
+    name: &str,
+) -> ThreeDResult<()> {
+    if current.is_none() {
+        Err(RendererError::MissingBaseTexture(name.to_string()))?;
+    }
+    *current = Some(texture);
+    Ok(())
+}
+
+// Whether the texture must be bound again, which is not needed if the previous instance used the same texture
+fn texture_changed(
+    previous: Option<&Option<Rc<Texture2D<u8>>>>,
+    texture: &Rc<Texture2D<u8>>,
+) -> bool {
+    previous
+        .and_then(|p| p.as_ref())
+        .map(|p| !Rc::ptr_eq(p, texture))
+        .unwrap_or(true)
+}
@@ -0,0 +1,161 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// The type of noise used by a [NoiseLayer], see [ShaderSourceBuilder::noise_functions] for the noise functions.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NoiseType {
+    /// Smoothly interpolated random values, which gives a soft blotchy look.
+    Value,
+    /// Gradient (Perlin) noise, which gives a more natural look without the grid aligned artifacts of value noise.
+    Gradient,
+    /// Cellular (Voronoi) noise, which gives a look of cells or pebbles.
+    Cellular,
+}
+
+///
+/// A layer of a [ProceduralMaterial], which is the base color varied by fractal noise.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NoiseLayer {
+    /// The base color of the layer. Assumed to be in linear color space.
+    pub color: Color,
+    /// The type of noise.
+    pub noise_type: NoiseType,
+    /// The size of the largest noise features in world space.
+    pub scale: f32,
+    /// The number of octaves of the noise, where each octave adds details of half the size of the previous octave.
+    pub octaves: u32,
+    /// A value in the range `[0..1]` specifying how much the noise darkens the base color, `0.0` gives the base color without any noise.
+    pub contrast: f32,
+}
+
+impl Default for NoiseLayer {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            noise_type: NoiseType::Gradient,
+            scale: 1.0,
+            octaves: 4,
+            contrast: 0.5,
+        }
+    }
+}
+
+impl NoiseLayer {
+    fn use_uniforms(&self, program: &Program, name: &str) -> ThreeDResult<()> {
+        program.use_uniform_vec4(&format!("{}Color", name), &self.color.to_vec4())?;
+        program.use_uniform_vec4(
+            &format!("{}Noise", name),
+            &vec4(
+                1.0 / self.scale.max(0.0001),
+                self.octaves.min(16) as f32,
+                self.contrast.max(0.0).min(1.0),
+                match self.noise_type {
+                    NoiseType::Value => 0.0,
+                    NoiseType::Gradient => 1.0,
+                    NoiseType::Cellular => 2.0,
+                },
+            ),
+        )
+    }
+}
+
+///
+/// A material which computes the surface color from two layers of procedural noise without any textures, for example rock and grass on a terrain.
+/// The second layer covers the first layer where the surface is flat enough and inside a height range, which is computed from the world space normal and position,
+/// and the edges of the covered area are broken up by noise. All of the parameters are uniforms, so changing them does not recompile the shader.
+/// The lighting is the same as for a [PhysicalMaterial] without metallic parts.
+///
+#[derive(Clone)]
+pub struct ProceduralMaterial {
+    /// The layer used where the second layer does not cover it, for example rock.
+    pub first_layer: NoiseLayer,
+    /// The layer covering the first layer where the surface is flat enough and inside the height range, for example grass or snow.
+    pub second_layer: NoiseLayer,
+    /// The second layer fully covers slopes less steep than the first angle in degrees and is not visible on slopes steeper than the second angle.
+    pub slope_range: (f32, f32),
+    /// The second layer fully covers the surface between the minimum and maximum height in world space.
+    pub height_range: (f32, f32),
+    /// The width in world space of the soft edge of the height range, which is also the amount the edge is broken up by noise.
+    pub height_blend: f32,
+    /// A value in the range `[0..1]` specifying how rough the material surface is.
+    pub roughness: f32,
+    /// The seed of the noise, different seeds give different but similar looking noise.
+    pub seed: u32,
+    /// Render states used when rendering with this material.
+    pub render_states: RenderStates,
+}
+
+impl Material for ProceduralMaterial {
+    fn fragment_shader_source(&self, _use_vertex_colors: bool, lights: &Lights) -> String {
+        let mut builder = ShaderSourceBuilder::new();
+        builder
+            .input(ShaderInput::Position)
+            .input(ShaderInput::Normal)
+            .lights(lights)
+            .noise_functions()
+            .block(
+                "procedural_material",
+                include_str!("shaders/procedural_material.frag"),
+            );
+        builder.fragment_source()
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        lights.use_uniforms(program, camera)?;
+        self.first_layer.use_uniforms(program, "first")?;
+        self.second_layer.use_uniforms(program, "second")?;
+        program.use_uniform_vec2(
+            "slopeRange",
+            &vec2(
+                self.slope_range.0.to_radians(),
+                self.slope_range.1.to_radians(),
+            ),
+        )?;
+        program.use_uniform_vec3(
+            "heightRange",
+            &vec3(self.height_range.0, self.height_range.1, self.height_blend),
+        )?;
+        program.use_uniform_float("roughness", &self.roughness)?;
+        program.use_uniform_int("seed", &(self.seed as i32))
+    }
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+    fn is_transparent(&self) -> bool {
+        false
+    }
+}
+
+impl Default for ProceduralMaterial {
+    fn default() -> Self {
+        Self {
+            first_layer: NoiseLayer {
+                color: Color::new_opaque(110, 100, 90),
+                noise_type: NoiseType::Cellular,
+                scale: 4.0,
+                octaves: 4,
+                contrast: 0.6,
+            },
+            second_layer: NoiseLayer {
+                color: Color::new_opaque(70, 120, 40),
+                noise_type: NoiseType::Gradient,
+                scale: 2.0,
+                octaves: 5,
+                contrast: 0.4,
+            },
+            slope_range: (25.0, 40.0),
+            height_range: (f32::MIN, f32::MAX),
+            height_blend: 1.0,
+            roughness: 0.9,
+            seed: 0,
+            render_states: RenderStates::default(),
+        }
+    }
+}
@@ -2,6 +2,12 @@ uniform vec4 surfaceColor;
 
 #ifdef USE_TEXTURE
 uniform sampler2D tex;
+#ifdef USE_TEXTURE_TRANSFORM
+uniform mat3 texTransform;
+#endif
+#ifdef USE_INSTANCE_UV_OFFSET_SCALE
+flat in vec4 instanceUvOffsetScale;
+#endif
 #endif
 
 layout (location = 0) out vec4 outColor;
@@ -15,9 +21,25 @@ void main()
     #endif
     
     #ifdef USE_TEXTURE
-    vec4 tex_color = texture(tex, uvs);
+    vec2 tex_uvs = uvs;
+    #ifdef USE_TEXTURE_TRANSFORM
+    tex_uvs = (texTransform * vec3(uvs, 1.0)).xy;
+    #endif
+    #ifdef USE_INSTANCE_UV_OFFSET_SCALE
+    tex_uvs = tex_uvs * instanceUvOffsetScale.zw + instanceUvOffsetScale.xy;
+    #endif
+    vec4 tex_color = texture(tex, tex_uvs);
     outColor *= vec4(rgb_from_srgb(tex_color.rgb), tex_color.a);
     #endif
 
-    outColor.rgb = srgb_from_rgb(outColor.rgb);
+    #ifdef SOFT_PARTICLES
+    outColor.a *= soft_particle_fade();
+    #endif
+
+    #ifdef USE_DITHER
+    dither(outColor.a);
+    outColor.a = 1.0;
+    #endif
+
+    outColor.rgb = encode_output(outColor.rgb);
 }
\ No newline at end of file
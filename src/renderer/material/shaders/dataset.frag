@@ -0,0 +1,28 @@
+
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform vec4 objectId;
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outDepth;
+layout (location = 1) out vec4 outId;
+layout (location = 2) out vec4 outNormal;
+
+void main()
+{
+    // The depth is the distance to the camera along the view direction and the normal is in view space
+    outDepth = vec4(-(camera.view * vec4(pos, 1.0)).z, 0.0, 0.0, 0.0);
+    // The four bytes of the id are given in the four channels with the least significant byte in the red channel
+    outId = objectId;
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    outNormal = vec4(normalize(mat3(camera.view) * normal), 0.0);
+}
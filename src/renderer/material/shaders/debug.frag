@@ -5,6 +5,7 @@ uniform sampler2DArray depthMap;
 uniform int type;
 
 uniform mat4 viewProjectionInverse;
+uniform float farPlaneDepth;
 
 in vec2 uv;
 
@@ -23,7 +24,8 @@ vec3 WorldPosFromDepth(float depth, vec2 uv) {
 void main()
 {
     float depth = texture(depthMap, vec3(uv,0)).r;
-    if(depth > 0.99999)
+    // Nothing is rendered where the depth is at the far plane
+    if(farPlaneDepth > 0.5 ? depth > 0.99999 : depth <= 0.0)
     {
         discard;
     }
@@ -2,6 +2,7 @@
 uniform sampler2DArray gbuffer;
 uniform sampler2DArray depthMap;
 uniform mat4 viewProjectionInverse;
+uniform float farPlaneDepth;
 
 in vec2 uv;
 
@@ -10,7 +11,8 @@ layout (location = 0) out vec4 outColor;
 void main()
 {
     float depth = texture(depthMap, vec3(uv,0)).r;
-    if(depth > 0.99999)
+    // Nothing is rendered where the depth is at the far plane
+    if(farPlaneDepth > 0.5 ? depth > 0.99999 : depth <= 0.0)
     {
         discard;
     }
@@ -33,7 +35,6 @@ void main()
     float occlusion = n.z;
 
     outColor.rgb = calculate_lighting(surface_color.rgb, position, normal, metallic_factor, roughness_factor, occlusion);
-    outColor.rgb = reinhard_tone_mapping(outColor.rgb);
-    outColor.rgb = srgb_from_rgb(outColor.rgb);
+    outColor.rgb = tone_map_and_encode_output(outColor.rgb);
     outColor.a = surface_color.a;
 }
\ No newline at end of file
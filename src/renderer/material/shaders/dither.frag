@@ -0,0 +1,24 @@
+uniform int ditherFrame;
+uniform int ditherInverted;
+
+const float BAYER_MATRIX[16] = float[16](
+    0.0, 8.0, 2.0, 10.0,
+    12.0, 4.0, 14.0, 6.0,
+    3.0, 11.0, 1.0, 9.0,
+    15.0, 7.0, 13.0, 5.0
+);
+
+// Discards the fragment if the alpha value is not above the threshold of the pixel in a 4x4 Bayer matrix.
+// The matrix is offset by the frame such that each pixel goes through all of the thresholds in 16 consecutive frames.
+void dither(float alpha)
+{
+    ivec2 pixel = (ivec2(gl_FragCoord.xy) + ivec2(ditherFrame % 4, ditherFrame / 4)) % 4;
+    float threshold = (BAYER_MATRIX[pixel.y * 4 + pixel.x] + 0.5) / 16.0;
+    if (ditherInverted != 0) {
+        threshold = 1.0 - threshold;
+    }
+    if (alpha <= threshold) {
+        discard;
+    }
+}
+
@@ -0,0 +1,29 @@
+uniform vec4 albedo;
+#ifdef USE_ALBEDO_TEXTURE
+uniform sampler2D albedoTexture;
+#endif
+uniform sampler2D lightmapTexture;
+uniform float lightmapIntensity;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 surface_color = albedo;
+#ifdef USE_ALBEDO_TEXTURE
+    vec4 c = texture(albedoTexture, uvs);
+    surface_color *= vec4(rgb_from_srgb(c.rgb), c.a);
+#endif
+#ifdef USE_VERTEX_COLORS
+    surface_color *= col;
+#endif
+
+#ifdef USE_UVS2
+    vec3 light = texture(lightmapTexture, uvs2).rgb;
+#else
+    vec3 light = texture(lightmapTexture, uvs).rgb;
+#endif
+
+    outColor.rgb = tone_map_and_encode_output(surface_color.rgb * lightmapIntensity * light);
+    outColor.a = surface_color.a;
+}
@@ -0,0 +1,206 @@
+
+// Procedural noise functions, where all randomness comes from an integer hash of the lattice cell and the seed.
+// Only integer arithmetic and basic floating point operations are used (no sin based hashing),
+// so the noise is identical on desktop (GLSL 330) and web (GLSL 300 es).
+// The tiled variants repeat with the given integer period along each axis, a period of zero disables tiling along that axis.
+
+#define NOISE_VALUE 0
+#define NOISE_GRADIENT 1
+#define NOISE_CELLULAR 2
+
+// https://jcgt.org/published/0009/03/02/ (PCG3D)
+uvec3 noise_hash(ivec3 cell, uint seed)
+{
+    uvec3 v = uvec3(cell) ^ uvec3(seed, seed * 747796405u, seed * 2891336453u);
+    v = v * 1664525u + 1013904223u;
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v ^= v >> 16u;
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    return v;
+}
+
+// Maps the highest 24 bits of each component of the hash to an exactly representable value in the range [0..1)
+vec3 noise_hash_to_float(uvec3 h)
+{
+    return vec3(h >> 8u) / 16777216.0;
+}
+
+int noise_wrap_axis(int c, int period)
+{
+    if (period <= 0) {
+        return c;
+    }
+    // The modulus is only defined for non-negative operands
+    int r = abs(c) % period;
+    return c < 0 && r != 0 ? period - r : r;
+}
+
+ivec3 noise_wrap(ivec3 cell, ivec3 period)
+{
+    return ivec3(noise_wrap_axis(cell.x, period.x), noise_wrap_axis(cell.y, period.y), noise_wrap_axis(cell.z, period.z));
+}
+
+// A random value in the range [0..1) for each integer cell
+float noise_random(ivec3 cell, uint seed)
+{
+    return noise_hash_to_float(noise_hash(cell, seed)).x;
+}
+
+// Value noise in the range [0..1]
+float value_noise_tiled(vec3 p, ivec3 period, uint seed)
+{
+    ivec3 i = ivec3(floor(p));
+    vec3 f = p - floor(p);
+    vec3 u = f * f * (3.0 - 2.0 * f);
+    float n000 = noise_random(noise_wrap(i, period), seed);
+    float n100 = noise_random(noise_wrap(i + ivec3(1, 0, 0), period), seed);
+    float n010 = noise_random(noise_wrap(i + ivec3(0, 1, 0), period), seed);
+    float n110 = noise_random(noise_wrap(i + ivec3(1, 1, 0), period), seed);
+    float n001 = noise_random(noise_wrap(i + ivec3(0, 0, 1), period), seed);
+    float n101 = noise_random(noise_wrap(i + ivec3(1, 0, 1), period), seed);
+    float n011 = noise_random(noise_wrap(i + ivec3(0, 1, 1), period), seed);
+    float n111 = noise_random(noise_wrap(i + ivec3(1, 1, 1), period), seed);
+    return mix(
+        mix(mix(n000, n100, u.x), mix(n010, n110, u.x), u.y),
+        mix(mix(n001, n101, u.x), mix(n011, n111, u.x), u.y),
+        u.z);
+}
+
+float value_noise(vec3 p, uint seed)
+{
+    return value_noise_tiled(p, ivec3(0), seed);
+}
+
+const vec3 NOISE_GRADIENTS[12] = vec3[12](
+    vec3(1.0, 1.0, 0.0), vec3(-1.0, 1.0, 0.0), vec3(1.0, -1.0, 0.0), vec3(-1.0, -1.0, 0.0),
+    vec3(1.0, 0.0, 1.0), vec3(-1.0, 0.0, 1.0), vec3(1.0, 0.0, -1.0), vec3(-1.0, 0.0, -1.0),
+    vec3(0.0, 1.0, 1.0), vec3(0.0, -1.0, 1.0), vec3(0.0, 1.0, -1.0), vec3(0.0, -1.0, -1.0)
+);
+
+// One of the twelve gradients along the edges of a cube, chosen by the hash of the cell
+vec3 noise_gradient(ivec3 cell, uint seed)
+{
+    return NOISE_GRADIENTS[int(noise_hash(cell, seed).x % 12u)];
+}
+
+float noise_gradient_dot(ivec3 i, ivec3 offset, vec3 f, ivec3 period, uint seed)
+{
+    return dot(noise_gradient(noise_wrap(i + offset, period), seed), f - vec3(offset));
+}
+
+// Gradient (Perlin) noise in the range [-1..1]
+float gradient_noise_tiled(vec3 p, ivec3 period, uint seed)
+{
+    ivec3 i = ivec3(floor(p));
+    vec3 f = p - floor(p);
+    vec3 u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    float n000 = noise_gradient_dot(i, ivec3(0, 0, 0), f, period, seed);
+    float n100 = noise_gradient_dot(i, ivec3(1, 0, 0), f, period, seed);
+    float n010 = noise_gradient_dot(i, ivec3(0, 1, 0), f, period, seed);
+    float n110 = noise_gradient_dot(i, ivec3(1, 1, 0), f, period, seed);
+    float n001 = noise_gradient_dot(i, ivec3(0, 0, 1), f, period, seed);
+    float n101 = noise_gradient_dot(i, ivec3(1, 0, 1), f, period, seed);
+    float n011 = noise_gradient_dot(i, ivec3(0, 1, 1), f, period, seed);
+    float n111 = noise_gradient_dot(i, ivec3(1, 1, 1), f, period, seed);
+    return clamp(mix(
+        mix(mix(n000, n100, u.x), mix(n010, n110, u.x), u.y),
+        mix(mix(n001, n101, u.x), mix(n011, n111, u.x), u.y),
+        u.z), -1.0, 1.0);
+}
+
+float gradient_noise(vec3 p, uint seed)
+{
+    return gradient_noise_tiled(p, ivec3(0), seed);
+}
+
+// Cellular (Voronoi) noise, returns the distance to the closest and the second closest feature point,
+// where there is one randomly placed feature point in each cell
+vec2 cellular_noise_tiled(vec3 p, ivec3 period, uint seed)
+{
+    ivec3 i = ivec3(floor(p));
+    vec3 f = p - floor(p);
+    vec2 distances = vec2(8.0);
+    for (int z = -1; z <= 1; z++) {
+        for (int y = -1; y <= 1; y++) {
+            for (int x = -1; x <= 1; x++) {
+                ivec3 offset = ivec3(x, y, z);
+                vec3 feature = vec3(offset) + noise_hash_to_float(noise_hash(noise_wrap(i + offset, period), seed));
+                float d = length(feature - f);
+                if (d < distances.x) {
+                    distances = vec2(d, distances.x);
+                } else if (d < distances.y) {
+                    distances.y = d;
+                }
+            }
+        }
+    }
+    return distances;
+}
+
+vec2 cellular_noise(vec3 p, uint seed)
+{
+    return cellular_noise_tiled(p, ivec3(0), seed);
+}
+
+// The noise of the given type (NOISE_VALUE, NOISE_GRADIENT or NOISE_CELLULAR) mapped to the range [0..1]
+float typed_noise_tiled(vec3 p, int noise_type, ivec3 period, uint seed)
+{
+    if (noise_type == NOISE_GRADIENT) {
+        return 0.5 + 0.5 * gradient_noise_tiled(p, period, seed);
+    }
+    if (noise_type == NOISE_CELLULAR) {
+        return clamp(cellular_noise_tiled(p, period, seed).x, 0.0, 1.0);
+    }
+    return value_noise_tiled(p, period, seed);
+}
+
+float typed_noise(vec3 p, int noise_type, uint seed)
+{
+    return typed_noise_tiled(p, noise_type, ivec3(0), seed);
+}
+
+// Fractal Brownian motion, ie. the sum of up to 16 octaves of the noise of the given type, where each octave has twice the frequency and half the amplitude of the previous,
+// in the range [0..1]. The period is doubled for each octave, so the sum tiles with the given period.
+float fbm_tiled(vec3 p, int noise_type, int octaves, ivec3 period, uint seed)
+{
+    float sum = 0.0;
+    float total = 0.0;
+    float amplitude = 0.5;
+    for (int i = 0; i < 16; i++) {
+        if (i >= octaves) {
+            break;
+        }
+        sum += amplitude * typed_noise_tiled(p, noise_type, period, seed + uint(i));
+        total += amplitude;
+        p *= 2.0;
+        period *= 2;
+        amplitude *= 0.5;
+    }
+    return total > 0.0 ? sum / total : 0.0;
+}
+
+float fbm(vec3 p, int noise_type, int octaves, uint seed)
+{
+    return fbm_tiled(p, noise_type, octaves, ivec3(0), seed);
+}
+
+// Offsets the position by gradient noise with the given strength, which distorts the noise evaluated at the returned position
+vec3 domain_warp(vec3 p, float strength, uint seed)
+{
+    return p + strength * vec3(
+        gradient_noise(p, seed),
+        gradient_noise(p + vec3(5.2, 1.3, 2.8), seed + 1u),
+        gradient_noise(p + vec3(1.7, 9.2, 4.1), seed + 2u));
+}
+
+vec3 domain_warp_tiled(vec3 p, float strength, ivec3 period, uint seed)
+{
+    return p + strength * vec3(
+        gradient_noise_tiled(p, period, seed),
+        gradient_noise_tiled(p, period, seed + 1u),
+        gradient_noise_tiled(p, period, seed + 2u));
+}
@@ -0,0 +1,31 @@
+uniform sampler2D positionTexture;
+uniform mat4 transformation;
+uniform vec3 brushCenter;
+uniform float brushRadius;
+uniform float brushHardness;
+uniform vec4 brushColor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    vec4 position = texelFetch(positionTexture, texel, 0);
+    if (position.a == 0.0)
+    {
+        discard;
+    }
+#ifdef TEXTURE_SPACE_BRUSH
+    vec2 uv = gl_FragCoord.xy / vec2(textureSize(positionTexture, 0));
+    float d = length(uv - brushCenter.xy) / brushRadius;
+#else
+    vec3 worldPosition = (transformation * vec4(position.xyz, 1.0)).xyz;
+    float d = length(worldPosition - brushCenter) / brushRadius;
+#endif
+    if (d >= 1.0)
+    {
+        discard;
+    }
+    float opacity = 1.0 - smoothstep(brushHardness, 1.0, d);
+    outColor = vec4(brushColor.rgb, brushColor.a * opacity);
+}
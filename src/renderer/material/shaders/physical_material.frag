@@ -5,37 +5,79 @@ uniform float roughness;
 uniform vec4 albedo;
 #ifdef USE_ALBEDO_TEXTURE
 uniform sampler2D albedoTexture;
+#ifdef USE_ALBEDO_TEXTURE_TRANSFORM
+uniform mat3 albedoTextureTransform;
+#endif
 #endif
 
 uniform vec3 emissive;
 #ifdef USE_EMISSIVE_TEXTURE
 uniform sampler2D emissiveTexture;
+#ifdef USE_EMISSIVE_TEXTURE_TRANSFORM
+uniform mat3 emissiveTextureTransform;
+#endif
 #endif
 
 #ifdef USE_METALLIC_ROUGHNESS_TEXTURE
 uniform sampler2D metallicRoughnessTexture;
+#ifdef USE_METALLIC_ROUGHNESS_TEXTURE_TRANSFORM
+uniform mat3 metallicRoughnessTextureTransform;
+#endif
 #endif
 
 #ifdef USE_OCCLUSION_TEXTURE
 uniform sampler2D occlusionTexture;
+#ifdef USE_OCCLUSION_TEXTURE_TRANSFORM
+uniform mat3 occlusionTextureTransform;
+#endif
 uniform float occlusionStrength;
 #endif
 
 #ifdef USE_NORMAL_TEXTURE
 uniform sampler2D normalTexture;
+#ifdef USE_NORMAL_TEXTURE_TRANSFORM
+uniform mat3 normalTextureTransform;
+#endif
 uniform float normalScale;
 #endif
 
 in vec3 pos;
 in vec3 nor;
 
+// Applies the transformation of the texture to the uv coordinates, only used when the transformation is not the identity
+vec2 transform_uvs(mat3 transform, vec2 uv)
+{
+    return (transform * vec3(uv, 1.0)).xy;
+}
+
+#ifdef USE_INSTANCE_UV_OFFSET_SCALE
+flat in vec4 instanceUvOffsetScale;
+
+// Maps the uv coordinates to the texture region of the current instance, only used by instanced models where an instance has a region
+vec2 instance_uvs(vec2 uv)
+{
+    return uv * instanceUvOffsetScale.zw + instanceUvOffsetScale.xy;
+}
+#endif
+
 layout (location = 0) out vec4 outColor;
 
 void main()
 {
     vec4 surface_color = albedo;
 #ifdef USE_ALBEDO_TEXTURE
-    vec4 c = texture(albedoTexture, uvs);
+    #ifdef USE_ALBEDO_TEXTURE_UVS2
+    vec2 albedo_uvs = uvs2;
+    #else
+    vec2 albedo_uvs = uvs;
+    #endif
+    #ifdef USE_ALBEDO_TEXTURE_TRANSFORM
+    albedo_uvs = transform_uvs(albedoTextureTransform, albedo_uvs);
+    #endif
+    #ifdef USE_INSTANCE_UV_OFFSET_SCALE
+    albedo_uvs = instance_uvs(albedo_uvs);
+    #endif
+    vec4 c = texture(albedoTexture, albedo_uvs);
     #ifdef ALPHACUT
         if (c.a < acut) discard;
     #endif
@@ -44,18 +86,44 @@ void main()
 #ifdef USE_VERTEX_COLORS
     surface_color *= col;
 #endif
+#ifdef USE_DITHER
+    dither(surface_color.a);
+    surface_color.a = 1.0;
+#endif
 
     float metallic_factor = metallic;
     float roughness_factor = roughness;
 #ifdef USE_METALLIC_ROUGHNESS_TEXTURE
-    vec2 t = texture(metallicRoughnessTexture, uvs).gb;
+    #ifdef USE_METALLIC_ROUGHNESS_TEXTURE_UVS2
+    vec2 metallic_roughness_uvs = uvs2;
+    #else
+    vec2 metallic_roughness_uvs = uvs;
+    #endif
+    #ifdef USE_METALLIC_ROUGHNESS_TEXTURE_TRANSFORM
+    metallic_roughness_uvs = transform_uvs(metallicRoughnessTextureTransform, metallic_roughness_uvs);
+    #endif
+    #ifdef USE_INSTANCE_UV_OFFSET_SCALE
+    metallic_roughness_uvs = instance_uvs(metallic_roughness_uvs);
+    #endif
+    vec2 t = texture(metallicRoughnessTexture, metallic_roughness_uvs).gb;
     roughness_factor *= t.x;
     metallic_factor *= t.y;
 #endif
 
     float occlusion = 1.0;
 #ifdef USE_OCCLUSION_TEXTURE
-    occlusion = mix(1.0, texture(occlusionTexture, uvs).r, occlusionStrength);
+    #ifdef USE_OCCLUSION_TEXTURE_UVS2
+    vec2 occlusion_uvs = uvs2;
+    #else
+    vec2 occlusion_uvs = uvs;
+    #endif
+    #ifdef USE_OCCLUSION_TEXTURE_TRANSFORM
+    occlusion_uvs = transform_uvs(occlusionTextureTransform, occlusion_uvs);
+    #endif
+    #ifdef USE_INSTANCE_UV_OFFSET_SCALE
+    occlusion_uvs = instance_uvs(occlusion_uvs);
+    #endif
+    occlusion = mix(1.0, texture(occlusionTexture, occlusion_uvs).r, occlusionStrength);
 #endif
 
     vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
@@ -63,17 +131,38 @@ void main()
     vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
     vec3 bitangent = normalize(gl_FrontFacing ? bitang : -bitang);
     mat3 tbn = mat3(tangent, bitangent, normal);
-    normal = tbn * ((2.0 * texture(normalTexture, uvs).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
+    #ifdef USE_NORMAL_TEXTURE_UVS2
+    vec2 normal_uvs = uvs2;
+    #else
+    vec2 normal_uvs = uvs;
+    #endif
+    #ifdef USE_NORMAL_TEXTURE_TRANSFORM
+    normal_uvs = transform_uvs(normalTextureTransform, normal_uvs);
+    #endif
+    #ifdef USE_INSTANCE_UV_OFFSET_SCALE
+    normal_uvs = instance_uvs(normal_uvs);
+    #endif
+    normal = tbn * ((2.0 * texture(normalTexture, normal_uvs).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
 #endif
 
     vec3 total_emissive = emissive;
 #ifdef USE_EMISSIVE_TEXTURE
-    vec4 e = texture(emissiveTexture, uvs);
+    #ifdef USE_EMISSIVE_TEXTURE_UVS2
+    vec2 emissive_uvs = uvs2;
+    #else
+    vec2 emissive_uvs = uvs;
+    #endif
+    #ifdef USE_EMISSIVE_TEXTURE_TRANSFORM
+    emissive_uvs = transform_uvs(emissiveTextureTransform, emissive_uvs);
+    #endif
+    #ifdef USE_INSTANCE_UV_OFFSET_SCALE
+    emissive_uvs = instance_uvs(emissive_uvs);
+    #endif
+    vec4 e = texture(emissiveTexture, emissive_uvs);
     total_emissive *= rgb_from_srgb(e.rgb);
 #endif
 
     outColor.rgb = total_emissive + calculate_lighting(surface_color.rgb, pos, normal, metallic_factor, roughness_factor, occlusion);
-    outColor.rgb = reinhard_tone_mapping(outColor.rgb);
-    outColor.rgb = srgb_from_rgb(outColor.rgb);
+    outColor.rgb = tone_map_and_encode_output(outColor.rgb);
     outColor.a = surface_color.a;
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+uniform vec4 firstColor;
+uniform vec4 firstNoise;
+uniform vec4 secondColor;
+uniform vec4 secondNoise;
+uniform vec2 slopeRange;
+uniform vec3 heightRange;
+uniform float roughness;
+uniform int seed;
+
+layout (location = 0) out vec4 outColor;
+
+// The noise parameters are the inverse scale, the number of octaves, the contrast and the noise type
+vec3 noise_layer_color(vec4 color, vec4 noise, uint layer_seed)
+{
+    float n = fbm(pos * noise.x, int(noise.w), int(noise.y), layer_seed);
+    return color.rgb * mix(1.0, n, noise.z);
+}
+
+float smooth_mask(float t)
+{
+    return t * t * (3.0 - 2.0 * t);
+}
+
+void main()
+{
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    uint base_seed = uint(seed) * 16u;
+    vec3 first = noise_layer_color(firstColor, firstNoise, base_seed);
+    vec3 second = noise_layer_color(secondColor, secondNoise, base_seed + 4u);
+
+    // The edges of the masks are broken up by noise, which is also used for the soft edge of the height range
+    float edge_noise = gradient_noise(pos * secondNoise.x * 2.0, base_seed + 8u);
+    float slope = acos(clamp(normal.y, -1.0, 1.0)) + 0.1 * edge_noise;
+    float slope_mask = 1.0 - smoothstep(slopeRange.x, max(slopeRange.y, slopeRange.x + 0.0001), slope);
+
+    // The height mask is clamped before dividing, so infinite height ranges are supported
+    float blend = max(heightRange.z, 0.0001);
+    float height = pos.y + 0.5 * blend * edge_noise;
+    float height_mask = smooth_mask(1.0 + clamp(height - heightRange.x, -blend, 0.0) / blend)
+        * smooth_mask(1.0 - clamp(height - heightRange.y, 0.0, blend) / blend);
+
+    vec3 surface_color = mix(first, second, slope_mask * height_mask);
+    outColor.rgb = calculate_lighting(surface_color, pos, normal, 0.0, roughness, 1.0);
+    outColor.rgb = tone_map_and_encode_output(outColor.rgb);
+    outColor.a = 1.0;
+}
@@ -0,0 +1,100 @@
+
+uniform vec4 albedo;
+#ifdef USE_ALBEDO_TEXTURE
+uniform sampler2D albedoTexture;
+#endif
+
+#ifdef USE_NORMAL_TEXTURE
+uniform sampler2D normalTexture;
+uniform float normalScale;
+#endif
+
+#ifdef USE_HEIGHT_TEXTURE
+uniform sampler2D heightTexture;
+uniform float heightScale;
+uniform int parallaxSteps;
+uniform vec3 eyePosition;
+#endif
+
+uniform vec3 lightDirection;
+uniform float ambient;
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+#ifdef USE_HEIGHT_TEXTURE
+vec2 parallax_occlusion_mapping(vec2 uv, vec3 view_dir_tangent_space)
+{
+    // Fade out the effect at grazing angles where the ray march is undersampled
+    float fade = smoothstep(0.0, 0.3, view_dir_tangent_space.z);
+    float steps = mix(float(parallaxSteps), 0.5 * float(parallaxSteps), view_dir_tangent_space.z);
+    float layer_depth = 1.0 / max(steps, 1.0);
+    // Clamp the xy/z ratio so the maximum offset stays bounded at grazing angles
+    vec2 p = view_dir_tangent_space.xy / max(view_dir_tangent_space.z, 0.25) * heightScale * fade;
+    vec2 delta_uv = p * layer_depth;
+
+    // Use explicit gradients since the texture is sampled in non-uniform control flow
+    vec2 dx = dFdx(uv);
+    vec2 dy = dFdy(uv);
+    vec2 current_uv = uv;
+    float current_layer_depth = 0.0;
+    float current_depth = 1.0 - textureGrad(heightTexture, current_uv, dx, dy).r;
+    for (int i = 0; i < parallaxSteps; i++)
+    {
+        if (current_layer_depth >= current_depth) {
+            break;
+        }
+        current_uv -= delta_uv;
+        current_depth = 1.0 - textureGrad(heightTexture, current_uv, dx, dy).r;
+        current_layer_depth += layer_depth;
+    }
+
+    // Interpolate between the last two samples to remove stepping
+    vec2 previous_uv = current_uv + delta_uv;
+    float after_depth = current_depth - current_layer_depth;
+    float before_depth = 1.0 - textureGrad(heightTexture, previous_uv, dx, dy).r - current_layer_depth + layer_depth;
+    float weight = after_depth / (after_depth - before_depth + 0.00001);
+    return mix(current_uv, previous_uv, clamp(weight, 0.0, 1.0));
+}
+#endif
+
+void main()
+{
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    vec2 uv = vec2(0.0);
+#ifdef USE_UVS
+    uv = uvs;
+#endif
+
+#ifdef USE_TANGENTS
+    vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
+    vec3 bitangent = normalize(gl_FrontFacing ? bitang : -bitang);
+    mat3 tbn = mat3(tangent, bitangent, normal);
+#endif
+
+#ifdef USE_HEIGHT_TEXTURE
+    vec3 view_dir = normalize(eyePosition - pos);
+    vec3 view_dir_tangent_space = normalize(vec3(dot(view_dir, tangent), dot(view_dir, bitangent), dot(view_dir, normal)));
+    uv = parallax_occlusion_mapping(uv, view_dir_tangent_space);
+#endif
+
+    vec4 surface_color = albedo;
+#ifdef USE_ALBEDO_TEXTURE
+    vec4 c = texture(albedoTexture, uv);
+    surface_color *= vec4(rgb_from_srgb(c.rgb), c.a);
+#endif
+#ifdef USE_VERTEX_COLORS
+    surface_color *= col;
+#endif
+
+#ifdef USE_NORMAL_TEXTURE
+    normal = normalize(tbn * ((2.0 * texture(normalTexture, uv).xyz - 1.0) * vec3(normalScale, normalScale, 1.0)));
+#endif
+
+    float diffuse = max(dot(normal, -normalize(lightDirection)), 0.0);
+    outColor.rgb = surface_color.rgb * (ambient + (1.0 - ambient) * diffuse);
+    outColor.rgb = encode_output(outColor.rgb);
+    outColor.a = surface_color.a;
+}
@@ -0,0 +1,32 @@
+uniform vec4 albedo;
+#ifdef USE_ALBEDO_TEXTURE
+uniform sampler2D albedoTexture;
+#endif
+uniform float roughness;
+uniform vec3 rimColor;
+uniform float rimSize;
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 surface_color = albedo;
+#ifdef USE_ALBEDO_TEXTURE
+    vec4 c = texture(albedoTexture, uvs);
+    surface_color *= vec4(rgb_from_srgb(c.rgb), c.a);
+#endif
+#ifdef USE_VERTEX_COLORS
+    surface_color *= col;
+#endif
+
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    vec3 view_direction = normalize(eyePosition - pos);
+    float rim = step(1.0 - rimSize, 1.0 - max(dot(normal, view_direction), 0.0));
+
+    outColor.rgb = calculate_lighting(surface_color.rgb, pos, normal, 0.0, roughness, 1.0) + rim * rimColor;
+    outColor.rgb = tone_map_and_encode_output(outColor.rgb);
+    outColor.a = surface_color.a;
+}
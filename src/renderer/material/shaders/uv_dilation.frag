@@ -0,0 +1,31 @@
+uniform sampler2D positionTexture;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    ivec2 size = textureSize(positionTexture, 0);
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    vec4 position = texelFetch(positionTexture, texel, 0);
+    if (position.a == 0.0)
+    {
+        // An uncovered texel gets the average position of its covered neighbours
+        vec4 sum = vec4(0.0);
+        for (int y = -1; y <= 1; y++)
+        {
+            for (int x = -1; x <= 1; x++)
+            {
+                vec4 neighbour = texelFetch(positionTexture, clamp(texel + ivec2(x, y), ivec2(0), size - 1), 0);
+                if (neighbour.a > 0.0)
+                {
+                    sum += vec4(neighbour.xyz, 1.0);
+                }
+            }
+        }
+        if (sum.a > 0.0)
+        {
+            position = vec4(sum.xyz / sum.a, 1.0);
+        }
+    }
+    outColor = position;
+}
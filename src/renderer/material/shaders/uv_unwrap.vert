@@ -0,0 +1,11 @@
+in vec3 position;
+in vec2 uv_coordinates;
+
+out vec3 pos;
+
+void main()
+{
+    // The triangles are placed at their uv coordinates, so each texel gets the position of the surface it is mapped to
+    pos = position;
+    gl_Position = vec4(2.0 * uv_coordinates - 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,149 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::rc::Rc;
+
+///
+/// An unlit material that renders a [Shadable] object with an albedo texture and optional normal and height maps.
+/// The surface is shaded using a simple diffuse term against the [TexturedMaterial::light_direction] specified on the material,
+/// which means that this material is not affected by the lights given when rendering.
+/// The normal and height maps require that the [Shadable] object has normals, uv coordinates and tangents.
+///
+#[derive(Clone)]
+pub struct TexturedMaterial {
+    /// Albedo base color which is multiplied with the color from the albedo texture. Assumed to be in linear color space.
+    pub albedo: Color,
+    /// Texture with albedo base colors. Assumed to be in sRGB with or without an alpha channel.
+    pub albedo_texture: Option<Rc<Texture2D<u8>>>,
+    /// A tangent space normal map.
+    pub normal_texture: Option<Rc<Texture2D<u8>>>,
+    /// A scalar multiplier applied to each normal vector of the [Self::normal_texture].
+    pub normal_scale: f32,
+    /// A height map used for parallax occlusion mapping. The height is sampled from the red channel where white is the top of the surface.
+    pub height_texture: Option<Rc<Texture2D<u8>>>,
+    /// The depth of the surface described by the [Self::height_texture] in uv coordinates.
+    pub height_scale: f32,
+    /// The maximum number of steps taken when tracing the [Self::height_texture].
+    pub parallax_steps: u32,
+    /// The direction of the light used for shading the surface.
+    pub light_direction: Vec3,
+    /// The amount of light in the range `[0..1]` reaching surfaces facing away from the light.
+    pub ambient: f32,
+    /// Render states used when the color is opaque (has a maximal alpha value).
+    pub opaque_render_states: RenderStates,
+    /// Render states used when the color is transparent (does not have a maximal alpha value).
+    pub transparent_render_states: RenderStates,
+}
+
+impl TexturedMaterial {
+    /// Constructs a new textured material from a [CPUMaterial]. The height map has to be set manually.
+    pub fn new(context: &Context, cpu_material: &CPUMaterial) -> ThreeDResult<Self> {
+        let albedo_texture = if let Some(ref cpu_texture) = cpu_material.albedo_texture {
+            Some(Rc::new(Texture2D::new(&context, cpu_texture)?))
+        } else {
+            None
+        };
+        let normal_texture = if let Some(ref cpu_texture) = cpu_material.normal_texture {
+            Some(Rc::new(Texture2D::new(&context, cpu_texture)?))
+        } else {
+            None
+        };
+        Ok(Self {
+            albedo: cpu_material.albedo,
+            albedo_texture,
+            normal_texture,
+            normal_scale: cpu_material.normal_scale,
+            ..Default::default()
+        })
+    }
+}
+
+impl Material for TexturedMaterial {
+    fn fragment_shader_source(&self, use_vertex_colors: bool, _lights: &Lights) -> String {
+        let mut shader = String::new();
+        if self.albedo_texture.is_some()
+            || self.normal_texture.is_some()
+            || self.height_texture.is_some()
+        {
+            shader.push_str("#define USE_UVS\nin vec2 uvs;\n");
+            if self.albedo_texture.is_some() {
+                shader.push_str("#define USE_ALBEDO_TEXTURE\n");
+            }
+            if self.normal_texture.is_some() || self.height_texture.is_some() {
+                shader.push_str("#define USE_TANGENTS\nin vec3 tang;\nin vec3 bitang;\n");
+            }
+            if self.normal_texture.is_some() {
+                shader.push_str("#define USE_NORMAL_TEXTURE\n");
+            }
+            if self.height_texture.is_some() {
+                shader.push_str("#define USE_HEIGHT_TEXTURE\n");
+            }
+        }
+        if use_vertex_colors {
+            shader.push_str("#define USE_VERTEX_COLORS\nin vec4 col;\n");
+        }
+        shader.push_str(include_str!("../../core/shared.frag"));
+        shader.push_str(include_str!("shaders/textured_material.frag"));
+        shader
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        _lights: &Lights,
+    ) -> ThreeDResult<()> {
+        program.use_uniform_vec4("albedo", &self.albedo.to_vec4())?;
+        program.use_uniform_vec3("lightDirection", &self.light_direction)?;
+        program.use_uniform_float("ambient", &self.ambient)?;
+        if let Some(ref texture) = self.albedo_texture {
+            program.use_texture("albedoTexture", texture.as_ref())?;
+        }
+        if let Some(ref texture) = self.normal_texture {
+            program.use_uniform_float("normalScale", &self.normal_scale)?;
+            program.use_texture("normalTexture", texture.as_ref())?;
+        }
+        if let Some(ref texture) = self.height_texture {
+            program.use_uniform_float("heightScale", &self.height_scale)?;
+            program.use_uniform_int("parallaxSteps", &(self.parallax_steps.max(1) as i32))?;
+            program.use_uniform_vec3("eyePosition", camera.position())?;
+            program.use_texture("heightTexture", texture.as_ref())?;
+        }
+        Ok(())
+    }
+    fn render_states(&self) -> RenderStates {
+        if self.is_transparent() {
+            self.transparent_render_states
+        } else {
+            self.opaque_render_states
+        }
+    }
+    fn is_transparent(&self) -> bool {
+        self.albedo.a != 255u8
+            || self
+                .albedo_texture
+                .as_ref()
+                .map(|t| t.is_transparent())
+                .unwrap_or(false)
+    }
+}
+
+impl Default for TexturedMaterial {
+    fn default() -> Self {
+        Self {
+            albedo: Color::WHITE,
+            albedo_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            height_texture: None,
+            height_scale: 0.05,
+            parallax_steps: 32,
+            light_direction: vec3(-1.0, -1.0, -1.0),
+            ambient: 0.2,
+            opaque_render_states: RenderStates::default(),
+            transparent_render_states: RenderStates {
+                write_mask: WriteMask::COLOR,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            },
+        }
+    }
+}
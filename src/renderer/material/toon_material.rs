@@ -0,0 +1,135 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::rc::Rc;
+
+///
+/// A cel shading material where the diffuse lighting is quantized into a number of bands or looked up in a ramp texture,
+/// the specular highlight is a hard edged spot and an optional rim light highlights the silhouette.
+/// All types of lights are supported and the material is often combined with an [EdgeOutlineEffect] to draw dark outlines.
+///
+#[derive(Clone)]
+pub struct ToonMaterial {
+    /// Albedo base color, also called diffuse color. Assumed to be in linear color space.
+    pub albedo: Color,
+    /// Texture with albedo base colors, also called diffuse color. Assumed to be in sRGB with or without an alpha channel.
+    pub albedo_texture: Option<Rc<Texture2D<u8>>>,
+    /// The number of bands the diffuse lighting of each light is quantized into. Not used if a [Self::ramp_texture] is specified.
+    pub bands: u32,
+    /// An optional texture which maps the diffuse lighting, from no lighting on the left to full lighting on the right, to a lighting color.
+    /// Use it instead of the [Self::bands] for full control of the lighting, for example to use unevenly sized or tinted bands.
+    pub ramp_texture: Option<Rc<Texture2D<u8>>>,
+    /// The intensity of the specular highlight, `0.0` disables the highlight.
+    pub specular: f32,
+    /// A value in the range `[0..1]` specifying how rough the material surface is, a rougher surface gives a larger specular highlight.
+    pub roughness: f32,
+    /// The color of the rim light which is added along the silhouette as seen from the camera, [Color::BLACK] disables the rim light.
+    pub rim_color: Color,
+    /// The width of the rim light in the range `[0..1]`.
+    pub rim_size: f32,
+    /// Render states used when the color is opaque (has a maximal alpha value).
+    pub opaque_render_states: RenderStates,
+    /// Render states used when the color is transparent (does not have a maximal alpha value).
+    pub transparent_render_states: RenderStates,
+}
+
+impl ToonMaterial {
+    /// Constructs a new toon material from a [CPUMaterial].
+    pub fn new(context: &Context, cpu_material: &CPUMaterial) -> ThreeDResult<Self> {
+        let albedo_texture = if let Some(ref cpu_texture) = cpu_material.albedo_texture {
+            Some(Rc::new(Texture2D::new(&context, cpu_texture)?))
+        } else {
+            None
+        };
+        Ok(Self {
+            albedo: cpu_material.albedo,
+            albedo_texture,
+            roughness: cpu_material.roughness,
+            ..Default::default()
+        })
+    }
+}
+
+impl Material for ToonMaterial {
+    fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
+        let mut builder = ShaderSourceBuilder::new();
+        builder.define("TOON");
+        if self.ramp_texture.is_some() {
+            builder.define("USE_TOON_RAMP");
+        }
+        if self.albedo_texture.is_some() {
+            builder.define("USE_ALBEDO_TEXTURE").input(ShaderInput::Uvs);
+        }
+        if use_vertex_colors {
+            builder
+                .define("USE_VERTEX_COLORS")
+                .input(ShaderInput::Color);
+        }
+        builder
+            .lights(lights)
+            .block("toon_material", include_str!("shaders/toon_material.frag"));
+        builder.fragment_source()
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        lights.use_uniforms(program, camera)?;
+        program.use_uniform_vec4("albedo", &self.albedo.to_vec4())?;
+        program.use_uniform_float("roughness", &self.roughness)?;
+        program.use_uniform_vec3("rimColor", &self.rim_color.to_vec3())?;
+        program.use_uniform_float("rimSize", &self.rim_size)?;
+        if let Some(ref texture) = self.albedo_texture {
+            program.use_texture("albedoTexture", texture.as_ref())?;
+        }
+        // The toon uniforms are only used if there are lights which are not ambient
+        if program.requires_uniform("toonSpecular") {
+            program.use_uniform_float("toonSpecular", &self.specular)?;
+        }
+        if let Some(ref texture) = self.ramp_texture {
+            if program.requires_uniform("toonRamp") {
+                program.use_texture("toonRamp", texture.as_ref())?;
+            }
+        } else if program.requires_uniform("toonBands") {
+            program.use_uniform_float("toonBands", &(self.bands.max(1) as f32))?;
+        }
+        Ok(())
+    }
+    fn render_states(&self) -> RenderStates {
+        if self.is_transparent() {
+            self.transparent_render_states
+        } else {
+            self.opaque_render_states
+        }
+    }
+    fn is_transparent(&self) -> bool {
+        self.albedo.a != 255
+            || self
+                .albedo_texture
+                .as_ref()
+                .map(|t| t.is_transparent())
+                .unwrap_or(false)
+    }
+}
+
+impl Default for ToonMaterial {
+    fn default() -> Self {
+        Self {
+            albedo: Color::WHITE,
+            albedo_texture: None,
+            bands: 3,
+            ramp_texture: None,
+            specular: 0.5,
+            roughness: 0.3,
+            rim_color: Color::BLACK,
+            rim_size: 0.3,
+            opaque_render_states: RenderStates::default(),
+            transparent_render_states: RenderStates {
+                write_mask: WriteMask::COLOR,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            },
+        }
+    }
+}
@@ -2,16 +2,31 @@
 //! A collection of objects that can be rendered, for example a mesh.
 //!
 
-pub use crate::core::{AxisAlignedBoundingBox, CPUMesh, Indices};
+pub use crate::core::{
+    AxisAlignedBoundingBox, BooleanOp, BoundingSphere, CPUMesh, Hit, Indices, TriMeshCollider,
+    UvMethod, BOOLEAN_EPSILON,
+};
 
 mod model;
 #[doc(inline)]
 pub use model::*;
 
+mod dynamic_normals;
+#[doc(inline)]
+pub use dynamic_normals::*;
+
 mod instanced_model;
 #[doc(inline)]
 pub use instanced_model::*;
 
+mod depth_pyramid;
+#[doc(inline)]
+pub use depth_pyramid::*;
+
+mod animated_instanced_model;
+#[doc(inline)]
+pub use animated_instanced_model::*;
+
 mod line;
 #[doc(inline)]
 pub use line::*;
@@ -28,10 +43,26 @@ mod skybox;
 #[doc(inline)]
 pub use skybox::*;
 
+mod procedural_sky;
+#[doc(inline)]
+pub use procedural_sky::*;
+
 mod imposters;
 #[doc(inline)]
 pub use imposters::*;
 
+mod light_halo;
+#[doc(inline)]
+pub use light_halo::*;
+
+mod world_space_bars;
+#[doc(inline)]
+pub use world_space_bars::*;
+
+mod mirror;
+#[doc(inline)]
+pub use mirror::*;
+
 mod axes;
 #[doc(inline)]
 pub use axes::*;
@@ -40,10 +71,45 @@ mod bounding_box;
 #[doc(inline)]
 pub use bounding_box::*;
 
+mod bounding_sphere;
+#[doc(inline)]
+pub use bounding_sphere::*;
+
 mod particles;
 #[doc(inline)]
 pub use particles::*;
 
+mod point_cloud;
+#[doc(inline)]
+pub use point_cloud::*;
+
+mod measurement;
+#[doc(inline)]
+pub use measurement::*;
+
+mod debug_texture_quad;
+#[doc(inline)]
+pub use debug_texture_quad::*;
+
+mod sprites;
+#[doc(inline)]
+pub use sprites::*;
+
+mod hidden_lines;
+#[doc(inline)]
+pub use hidden_lines::*;
+
+mod nine_slice_quad;
+#[doc(inline)]
+pub use nine_slice_quad::*;
+
+#[cfg(feature = "text")]
+#[cfg_attr(docsrs, doc(cfg(feature = "text")))]
+mod text;
+#[doc(inline)]
+#[cfg(feature = "text")]
+pub use text::*;
+
 use crate::core::*;
 use crate::renderer::*;
 
@@ -65,6 +131,28 @@ pub trait Object: Geometry {
     /// Returns whether or not this object should be considered transparent.
     ///
     fn is_transparent(&self) -> bool;
+
+    ///
+    /// Returns a key which is equal for objects which are rendered with the same program,
+    /// used by [render_pass_with_options](crate::render_pass_with_options) to render those objects after each other.
+    /// The models return the id of the program they were last rendered with by [Object::render], which depends on the configuration of the material and the lights
+    /// and not only the type of material, for example whether a texture is used. It is therefore 0 until the model has been rendered
+    /// and a change of the material or the lights is reflected in the order of the next frame.
+    /// The default implementation returns 0, so objects which do not know their program are kept together.
+    ///
+    fn material_id(&self) -> u64 {
+        0
+    }
+
+    ///
+    /// Returns the render order of this object. Transparent objects with a lower render order are rendered before transparent objects with a higher render order
+    /// by [render_pass_with_options](crate::render_pass_with_options), regardless of their distance to the camera,
+    /// for example to always render a transparent dome containing the camera before the objects inside it.
+    /// Transparent objects with the same render order are sorted by distance to the camera. Defaults to 0.
+    ///
+    fn render_order(&self) -> i32 {
+        0
+    }
 }
 
 impl<T: Object + ?Sized> Object for &T {
@@ -75,6 +163,14 @@ impl<T: Object + ?Sized> Object for &T {
     fn is_transparent(&self) -> bool {
         (*self).is_transparent()
     }
+
+    fn material_id(&self) -> u64 {
+        (*self).material_id()
+    }
+
+    fn render_order(&self) -> i32 {
+        (*self).render_order()
+    }
 }
 
 impl<T: Object + ?Sized> Object for &mut T {
@@ -85,6 +181,14 @@ impl<T: Object + ?Sized> Object for &mut T {
     fn is_transparent(&self) -> bool {
         (**self).is_transparent()
     }
+
+    fn material_id(&self) -> u64 {
+        (**self).material_id()
+    }
+
+    fn render_order(&self) -> i32 {
+        (**self).render_order()
+    }
 }
 
 // Geometry trait
@@ -98,10 +202,28 @@ pub trait Geometry: Shadable {
     ///
     fn aabb(&self) -> AxisAlignedBoundingBox;
 
+    ///
+    /// Returns a [BoundingSphere] for this geometry.
+    /// Defaults to the bounding sphere of the [AxisAlignedBoundingBox] which is not very tight,
+    /// so implement this method if a tighter bounding sphere is cheaply available.
+    ///
+    fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::new_with_aabb(&self.aabb())
+    }
+
     ///
     /// Returns the local to world transformation applied to this geometry.
     ///
     fn transformation(&self) -> Mat4;
+
+    ///
+    /// Returns a number which is increased every time this geometry is changed, for example when the transformation is set.
+    /// It is used by [ShadowAtlas] to find the shadow maps which are out of date.
+    /// Defaults to always returning zero, in which case only changes to the [aabb](Geometry::aabb) are detected.
+    ///
+    fn change_count(&self) -> u64 {
+        0
+    }
 }
 
 impl<T: Geometry + ?Sized> Geometry for &T {
@@ -109,9 +231,17 @@ impl<T: Geometry + ?Sized> Geometry for &T {
         (*self).aabb()
     }
 
+    fn bounding_sphere(&self) -> BoundingSphere {
+        (*self).bounding_sphere()
+    }
+
     fn transformation(&self) -> Mat4 {
         (*self).transformation()
     }
+
+    fn change_count(&self) -> u64 {
+        (*self).change_count()
+    }
 }
 
 impl<T: Geometry + ?Sized> Geometry for &mut T {
@@ -119,9 +249,17 @@ impl<T: Geometry + ?Sized> Geometry for &mut T {
         (**self).aabb()
     }
 
+    fn bounding_sphere(&self) -> BoundingSphere {
+        (**self).bounding_sphere()
+    }
+
     fn transformation(&self) -> Mat4 {
         (**self).transformation()
     }
+
+    fn change_count(&self) -> u64 {
+        (**self).change_count()
+    }
 }
 
 ///
@@ -314,6 +452,15 @@ pub trait Object2D: Shadable2D {
     ///
     fn render(&self, viewport: Viewport) -> ThreeDResult<()>;
 
+    ///
+    /// Render the object on top of everything already rendered, ie. with the depth test disabled and blending enabled,
+    /// for example as part of a heads-up display on top of a 3D scene, see [render_overlay].
+    /// Defaults to [Object2D::render], which uses the render states of the material.
+    ///
+    fn render_as_overlay(&self, viewport: Viewport) -> ThreeDResult<()> {
+        self.render(viewport)
+    }
+
     ///
     /// Returns whether or not this object should be considered transparent.
     ///
@@ -325,6 +472,10 @@ impl<T: Object2D + ?Sized> Object2D for &T {
         (*self).render(viewport)
     }
 
+    fn render_as_overlay(&self, viewport: Viewport) -> ThreeDResult<()> {
+        (*self).render_as_overlay(viewport)
+    }
+
     fn is_transparent(&self) -> bool {
         (*self).is_transparent()
     }
@@ -335,7 +486,39 @@ impl<T: Object2D + ?Sized> Object2D for &mut T {
         (**self).render(viewport)
     }
 
+    fn render_as_overlay(&self, viewport: Viewport) -> ThreeDResult<()> {
+        (**self).render_as_overlay(viewport)
+    }
+
     fn is_transparent(&self) -> bool {
         (**self).is_transparent()
     }
 }
+
+// Wraps the material of a 2D object such that it is rendered on top of everything with blending, see Object2D::render_as_overlay
+struct OverlayMaterial<'a>(&'a dyn Material);
+
+impl Material for OverlayMaterial<'_> {
+    fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
+        self.0.fragment_shader_source(use_vertex_colors, lights)
+    }
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.0.use_uniforms(program, camera, lights)
+    }
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+    fn is_transparent(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,517 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::cell::Cell;
+
+///
+/// Similar to [InstancedModel], except that the mesh is deformed by a [Skeleton] animated by an [AnimationClip],
+/// for example to render a crowd of hundreds of walking characters with one draw call.
+///
+/// The skinning matrices of all joints are computed at a fixed number of frames per second and stored in a floating point texture once,
+/// so animating the instances only requires setting the animation time, see [AnimatedInstancedModel::set_animation_time].
+/// Each instance plays the clip looped with its own time offset and speed, see [AnimatedModelInstance].
+/// The texture is three texels wide per joint and one texel high per frame, which must not exceed the maximum texture size.
+/// Floating point textures without filtering are supported by all OpenGL 3.3 and WebGL 2 contexts, so no extensions are required.
+///
+pub struct AnimatedInstancedModel<M: Material> {
+    context: Context,
+    mesh: Mesh,
+    joint_index_buffer: VertexBuffer,
+    joint_weight_buffer: VertexBuffer,
+    skinning_texture: Texture2D<f32>,
+    instance_buffers: AnimatedInstanceBuffers,
+    aabb_local: AxisAlignedBoundingBox,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+    instances: Vec<AnimatedModelInstance>,
+    texture_transform: Mat3,
+    animation_time: f32,
+    animation_duration: f32,
+    frames_per_second: f32,
+    change_count: u64,
+    program_id: Cell<u64>,
+    /// The material applied to the animated instanced model
+    pub material: M,
+}
+
+impl<M: Material> AnimatedInstancedModel<M> {
+    ///
+    /// Creates a new animated instanced model where the given mesh is deformed by the given skeleton playing the given animation clip.
+    /// The mesh must contain [CPUMesh::joint_indices] and [CPUMesh::joint_weights] referring to the joints of the skeleton.
+    /// The skinning matrices are sampled at the given number of frames per second and linearly interpolated in between.
+    ///
+    pub fn new_with_material(
+        context: &Context,
+        instances: &[AnimatedModelInstance],
+        cpu_mesh: &CPUMesh,
+        skeleton: &Skeleton,
+        clip: &AnimationClip,
+        frames_per_second: f32,
+        material: M,
+    ) -> ThreeDResult<Self> {
+        let joint_indices = cpu_mesh
+            .joint_indices
+            .as_ref()
+            .ok_or(CoreError::MissingMeshBuffer("joint indices".to_string()))?;
+        let joint_weights = cpu_mesh
+            .joint_weights
+            .as_ref()
+            .ok_or(CoreError::MissingMeshBuffer("joint weights".to_string()))?;
+
+        // Sample the skinning matrices, the last frame is at the end of the clip so the loop is seamless
+        let animation_duration = clip.duration().max(1.0 / frames_per_second);
+        let frame_count = (animation_duration * frames_per_second).ceil() as u32 + 1;
+        let width = skeleton.joints.len().max(1) as u32 * 3;
+        let max_texture_size = context.capabilities().max_texture_size;
+        if width > max_texture_size || frame_count > max_texture_size {
+            Err(RendererError::AnimationTextureTooLarge(
+                width,
+                frame_count,
+                max_texture_size,
+            ))?;
+        }
+        let mut frames = Vec::with_capacity(frame_count as usize);
+        let mut data = Vec::with_capacity((width * frame_count * 4) as usize);
+        for frame in 0..frame_count {
+            let time = (frame as f32 / frames_per_second).min(animation_duration);
+            let skinning_matrices = skeleton.skinning_matrices(clip, time);
+            for m in skinning_matrices.iter() {
+                data.extend_from_slice(&[m.x.x, m.y.x, m.z.x, m.w.x]);
+                data.extend_from_slice(&[m.x.y, m.y.y, m.z.y, m.w.y]);
+                data.extend_from_slice(&[m.x.z, m.y.z, m.z.z, m.w.z]);
+            }
+            frames.push(skinning_matrices);
+        }
+        data.resize((width * frame_count * 4) as usize, 0.0);
+        let mut skinning_texture = Texture2D::new_empty(
+            context,
+            width,
+            frame_count,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        skinning_texture.fill(&data)?;
+
+        // The bounding box contains the mesh in all of the sampled poses
+        let mut aabb_local = AxisAlignedBoundingBox::EMPTY;
+        for skinning_matrices in frames.iter() {
+            for i in 0..cpu_mesh.positions.len() / 3 {
+                let mut skinning = Mat4::zero();
+                for j in 0..4 {
+                    let weight = joint_weights[i * 4 + j];
+                    if weight != 0.0 {
+                        skinning += skinning_matrices[joint_indices[i * 4 + j] as usize] * weight;
+                    }
+                }
+                let p = skinning * cpu_mesh.position(i).extend(1.0);
+                aabb_local.expand(&[p.x, p.y, p.z]);
+            }
+        }
+
+        // The instance colors are multiplied with the vertex colors, which are white if the mesh does not have any
+        let mesh = if cpu_mesh.colors.is_some() {
+            Mesh::new(context, cpu_mesh)?
+        } else {
+            let mut mesh = Mesh::new(context, cpu_mesh)?;
+            mesh.color_buffer = Some(VertexBuffer::new_with_static(
+                context,
+                &vec![255u8; cpu_mesh.positions.len() / 3 * 4],
+            )?);
+            mesh
+        };
+
+        let mut model = Self {
+            context: context.clone(),
+            mesh,
+            joint_index_buffer: VertexBuffer::new_with_static_integer(context, joint_indices)?,
+            joint_weight_buffer: VertexBuffer::new_with_static(context, joint_weights)?,
+            skinning_texture,
+            instance_buffers: AnimatedInstanceBuffers::new(context)?,
+            aabb_local,
+            aabb: aabb_local,
+            transformation: Mat4::identity(),
+            instances: instances.to_vec(),
+            texture_transform: Mat3::identity(),
+            animation_time: 0.0,
+            animation_duration,
+            frames_per_second,
+            change_count: 0,
+            program_id: Cell::new(0),
+            material,
+        };
+        model.update_buffers();
+        Ok(model)
+    }
+
+    pub fn texture_transform(&mut self) -> &Mat3 {
+        &self.texture_transform
+    }
+
+    pub fn set_texture_transform(&mut self, texture_transform: Mat3) {
+        self.texture_transform = texture_transform;
+    }
+
+    ///
+    /// Sets the time in seconds which is used to find the pose of each instance,
+    /// the pose of an instance is at the time `animation_time * animation_speed + animation_time_offset` of the looped clip.
+    /// Usually the accumulated time of the render loop, see [FrameInput::accumulated_time](crate::FrameInput::accumulated_time), converted to seconds.
+    ///
+    pub fn set_animation_time(&mut self, animation_time: f32) {
+        self.animation_time = animation_time;
+        self.change_count += 1;
+    }
+
+    ///
+    /// Returns the time in seconds which is used to find the pose of each instance, see [AnimatedInstancedModel::set_animation_time].
+    ///
+    pub fn animation_time(&self) -> f32 {
+        self.animation_time
+    }
+
+    ///
+    /// Returns the duration of the animation clip in seconds.
+    ///
+    pub fn animation_duration(&self) -> f32 {
+        self.animation_duration
+    }
+
+    ///
+    /// Returns all instances
+    ///
+    pub fn instances(&self) -> &[AnimatedModelInstance] {
+        &self.instances
+    }
+
+    ///
+    /// Create an instance for each element with the given transforms, colors and animation parameters.
+    ///
+    pub fn set_instances(&mut self, instances: &[AnimatedModelInstance]) {
+        self.instances = instances.to_vec();
+        self.update_buffers();
+    }
+
+    fn update_buffers(&mut self) {
+        self.instance_buffers.fill(&self.instances);
+        self.update_aabb();
+        self.change_count += 1;
+    }
+
+    fn update_aabb(&mut self) {
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for instance in self.instances.iter() {
+            let mut aabb2 = self.aabb_local.clone();
+            aabb2.transform(&(self.transformation * instance.geometry_transform));
+            aabb.expand_with_aabb(&aabb2);
+        }
+        self.aabb = aabb;
+    }
+
+    fn draw(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        camera: &Camera,
+        viewport: Viewport,
+    ) -> ThreeDResult<()> {
+        let instance_count = self.instances.len() as u32;
+        if instance_count == 0 {
+            return Ok(());
+        }
+        let max_vertex_attribs = self.context.capabilities().max_vertex_attribs;
+        if program.attribute_count() > max_vertex_attribs {
+            Err(CoreError::TooManyVertexAttributes(
+                program.attribute_count(),
+                max_vertex_attribs,
+            ))?;
+        }
+        program.use_uniform_block("Camera", camera.uniform_buffer());
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+        program.use_texture("skinningMatrices", &self.skinning_texture)?;
+        program.use_uniform_float("animationTime", &self.animation_time)?;
+        program.use_uniform_float("animationDuration", &self.animation_duration)?;
+        program.use_uniform_float("animationFramesPerSecond", &self.frames_per_second)?;
+
+        if program.requires_attribute("uv_coordinates") {
+            program.use_uniform_mat3("textureTransform", &self.texture_transform)?;
+        }
+        let mesh = &self.mesh;
+        let instance_buffers = &self.instance_buffers;
+        let additional_buffer_ids = [
+            self.joint_index_buffer.uid(),
+            self.joint_weight_buffer.uid(),
+            instance_buffers.row1.uid(),
+            instance_buffers.row2.uid(),
+            instance_buffers.row3.uid(),
+            instance_buffers.tex_transform1.uid(),
+            instance_buffers.tex_transform2.uid(),
+            instance_buffers.animation.uid(),
+            instance_buffers.color.uid(),
+        ];
+        mesh.use_attributes(program, &additional_buffer_ids, || {
+            program.use_attribute_vec4_instanced("row1", &instance_buffers.row1)?;
+            program.use_attribute_vec4_instanced("row2", &instance_buffers.row2)?;
+            program.use_attribute_vec4_instanced("row3", &instance_buffers.row3)?;
+            program
+                .use_attribute_vec2_instanced("instance_animation", &instance_buffers.animation)?;
+            program.use_attribute_vec4("joint_indices", &self.joint_index_buffer)?;
+            program.use_attribute_vec4("joint_weights", &self.joint_weight_buffer)?;
+
+            if program.requires_attribute("position") {
+                program.use_attribute_vec3("position", &mesh.position_buffer)?;
+            }
+            if program.requires_attribute("uv_coordinates") {
+                program.use_attribute_vec3_instanced(
+                    "tex_transform_row1",
+                    &instance_buffers.tex_transform1,
+                )?;
+                program.use_attribute_vec3_instanced(
+                    "tex_transform_row2",
+                    &instance_buffers.tex_transform2,
+                )?;
+                let uv_buffer = mesh
+                    .uv_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer("uv coordinates".to_string()))?;
+                program.use_attribute_vec2("uv_coordinates", uv_buffer)?;
+            }
+            if program.requires_attribute("uv2_coordinates") {
+                let uv2_buffer = mesh
+                    .uv2_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer(
+                        "second uv coordinates".to_string(),
+                    ))?;
+                program.use_attribute_vec2("uv2_coordinates", uv2_buffer)?;
+            }
+            if program.requires_attribute("normal") {
+                let normal_buffer = mesh
+                    .normal_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer("normal".to_string()))?;
+                program.use_attribute_vec3("normal", normal_buffer)?;
+                if program.requires_attribute("tangent") {
+                    let tangent_buffer = mesh
+                        .tangent_buffer
+                        .as_ref()
+                        .ok_or(CoreError::MissingMeshBuffer("tangent".to_string()))?;
+                    program.use_attribute_vec4("tangent", tangent_buffer)?;
+                }
+            }
+            if program.requires_attribute("color") {
+                let color_buffer = mesh
+                    .color_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer("color".to_string()))?;
+                program.use_attribute_vec4("color", color_buffer)?;
+                program.use_attribute_vec4_instanced("instance_color", &instance_buffers.color)?;
+            }
+            Ok(())
+        })?;
+
+        if let Some(ref index_buffer) = self.mesh.index_buffer {
+            program.draw_elements_instanced(render_states, viewport, index_buffer, instance_count);
+        } else {
+            program.draw_arrays_instanced(
+                render_states,
+                viewport,
+                self.mesh.position_buffer.count() as u32 / 3,
+                instance_count,
+            );
+        }
+        Ok(())
+    }
+
+    // Renders the instances with the given material and returns the id of the program used, which is cached by Object::render for sorting the objects by material
+    fn render_with_program(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<u64> {
+        let fragment_shader_source = material.fragment_shader_source(true, lights);
+        let mut program_id = 0;
+        self.context.program(
+            &self.vertex_shader_source(&fragment_shader_source)?,
+            &fragment_shader_source,
+            |program| {
+                program_id = program.uid();
+                material.use_uniforms(program, camera, lights)?;
+                self.draw(program, material.render_states(), camera, camera.viewport())
+            },
+        )?;
+        Ok(program_id)
+    }
+
+    fn vertex_shader_source(&self, fragment_shader_source: &str) -> ThreeDResult<String> {
+        Ok(format!(
+            "#define INSTANCED\n#define USE_SKINNING\n#define USE_INSTANCE_COLORS\n{}{}",
+            self.mesh.color_space_define(),
+            Model::<M>::vertex_shader_source(fragment_shader_source)?
+        ))
+    }
+}
+
+impl<M: Material> Geometry for AnimatedInstancedModel<M> {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    fn change_count(&self) -> u64 {
+        self.change_count
+    }
+}
+
+impl<M: Material> GeometryMut for AnimatedInstancedModel<M> {
+    fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        self.update_aabb();
+        self.change_count += 1;
+    }
+}
+
+impl<M: Material> Shadable for AnimatedInstancedModel<M> {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.render_with_program(material, camera, lights)?;
+        Ok(())
+    }
+
+    fn render_forward(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.render_with_material(material, camera, lights)
+    }
+
+    fn render_deferred(
+        &self,
+        material: &DeferredPhysicalMaterial,
+        camera: &Camera,
+        viewport: Viewport,
+    ) -> ThreeDResult<()> {
+        let lights = Lights::default();
+        let fragment_shader_source = material.fragment_shader_source(true, &lights);
+        self.context.program(
+            &self.vertex_shader_source(&fragment_shader_source)?,
+            &fragment_shader_source,
+            |program| {
+                material.use_uniforms(program, camera, &lights)?;
+                self.draw(program, material.render_states(), camera, viewport)
+            },
+        )
+    }
+}
+
+impl<M: Material> Object for AnimatedInstancedModel<M> {
+    fn render(&self, camera: &Camera, lights: &Lights) -> ThreeDResult<()> {
+        self.program_id
+            .set(self.render_with_program(&self.material, camera, lights)?);
+        Ok(())
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.material.is_transparent()
+    }
+
+    fn material_id(&self) -> u64 {
+        self.program_id.get()
+    }
+}
+
+///
+/// An instance of an [AnimatedInstancedModel].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct AnimatedModelInstance {
+    /// The transformation applied to the animated mesh of this instance.
+    pub geometry_transform: Mat4,
+    /// The transformation applied to the uv coordinates of this instance.
+    pub texture_transform: Mat3,
+    /// The color which is multiplied with the color of the material and the vertex colors of this instance.
+    pub color: Color,
+    /// The time in seconds added to the animation time of this instance, for example to play the same clip out of phase.
+    pub animation_time_offset: f32,
+    /// The speed with which this instance plays the clip, where 1 is the original speed.
+    pub animation_speed: f32,
+}
+
+impl Default for AnimatedModelInstance {
+    fn default() -> Self {
+        Self {
+            geometry_transform: Mat4::identity(),
+            texture_transform: Mat3::identity(),
+            color: Color::WHITE,
+            animation_time_offset: 0.0,
+            animation_speed: 1.0,
+        }
+    }
+}
+
+///
+/// The instance buffers containing the transformations, colors and animation parameters of the instances.
+///
+struct AnimatedInstanceBuffers {
+    row1: InstanceBuffer,
+    row2: InstanceBuffer,
+    row3: InstanceBuffer,
+    tex_transform1: InstanceBuffer,
+    tex_transform2: InstanceBuffer,
+    animation: InstanceBuffer,
+    color: InstanceBuffer,
+}
+
+impl AnimatedInstanceBuffers {
+    fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            row1: InstanceBuffer::new(context)?,
+            row2: InstanceBuffer::new(context)?,
+            row3: InstanceBuffer::new(context)?,
+            tex_transform1: InstanceBuffer::new(context)?,
+            tex_transform2: InstanceBuffer::new(context)?,
+            animation: InstanceBuffer::new(context)?,
+            color: InstanceBuffer::new(context)?,
+        })
+    }
+
+    fn fill(&mut self, instances: &[AnimatedModelInstance]) {
+        let mut row1 = Vec::with_capacity(instances.len() * 4);
+        let mut row2 = Vec::with_capacity(instances.len() * 4);
+        let mut row3 = Vec::with_capacity(instances.len() * 4);
+        let mut tex_transform1 = Vec::with_capacity(instances.len() * 3);
+        let mut tex_transform2 = Vec::with_capacity(instances.len() * 3);
+        let mut animation = Vec::with_capacity(instances.len() * 2);
+        let mut color = Vec::with_capacity(instances.len() * 4);
+        for instance in instances {
+            let m = &instance.geometry_transform;
+            row1.extend_from_slice(&[m.x.x, m.y.x, m.z.x, m.w.x]);
+            row2.extend_from_slice(&[m.x.y, m.y.y, m.z.y, m.w.y]);
+            row3.extend_from_slice(&[m.x.z, m.y.z, m.z.z, m.w.z]);
+            let t = &instance.texture_transform;
+            tex_transform1.extend_from_slice(&[t.x.x, t.y.x, t.z.x]);
+            tex_transform2.extend_from_slice(&[t.x.y, t.y.y, t.z.y]);
+            animation
+                .extend_from_slice(&[instance.animation_time_offset, instance.animation_speed]);
+            color.extend_from_slice(&instance.color.to_vec4().as_array());
+        }
+        self.row1.fill_with_dynamic(&row1);
+        self.row2.fill_with_dynamic(&row2);
+        self.row3.fill_with_dynamic(&row3);
+        self.tex_transform1.fill_with_dynamic(&tex_transform1);
+        self.tex_transform2.fill_with_dynamic(&tex_transform2);
+        self.animation.fill_with_dynamic(&animation);
+        self.color.fill_with_dynamic(&color);
+    }
+}
@@ -115,4 +115,8 @@ impl<M: Material> Object for BoundingBox<M> {
     fn is_transparent(&self) -> bool {
         false
     }
+
+    fn material_id(&self) -> u64 {
+        self.model.material_id()
+    }
 }
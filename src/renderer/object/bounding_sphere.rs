@@ -0,0 +1,89 @@
+use crate::renderer::*;
+
+///
+/// A sphere which visualizes a [BoundingSphere], for example for debugging culling.
+/// Use a transparent material to be able to see the object inside the sphere.
+///
+pub struct BoundingSphereObject<M: Material> {
+    model: Model<M>,
+    bounding_sphere: BoundingSphere,
+}
+
+impl<M: Material> BoundingSphereObject<M> {
+    ///
+    /// Creates a bounding sphere object from a bounding sphere.
+    ///
+    pub fn new_with_material(
+        context: &Context,
+        bounding_sphere: BoundingSphere,
+        material: M,
+    ) -> ThreeDResult<Self> {
+        let mut model = Model::new_with_material(context, &CPUMesh::sphere(16), material)?;
+        model.set_transformation(
+            Mat4::from_translation(bounding_sphere.center())
+                * Mat4::from_scale(bounding_sphere.radius()),
+        );
+        Ok(Self {
+            model,
+            bounding_sphere,
+        })
+    }
+}
+
+impl<M: Material> Shadable for BoundingSphereObject<M> {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.model.render_with_material(material, camera, lights)
+    }
+
+    fn render_forward(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.render_with_material(material, camera, lights)
+    }
+
+    #[allow(deprecated)]
+    fn render_deferred(
+        &self,
+        material: &DeferredPhysicalMaterial,
+        camera: &Camera,
+        viewport: Viewport,
+    ) -> ThreeDResult<()> {
+        self.model.render_deferred(material, camera, viewport)
+    }
+}
+
+impl<M: Material> Geometry for BoundingSphereObject<M> {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.model.aabb()
+    }
+
+    fn bounding_sphere(&self) -> BoundingSphere {
+        self.bounding_sphere
+    }
+
+    fn transformation(&self) -> Mat4 {
+        Mat4::identity()
+    }
+}
+
+impl<M: Material> Object for BoundingSphereObject<M> {
+    fn render(&self, camera: &Camera, lights: &Lights) -> ThreeDResult<()> {
+        self.model.render(camera, lights)
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.model.is_transparent()
+    }
+
+    fn material_id(&self) -> u64 {
+        self.model.material_id()
+    }
+}
@@ -1,11 +1,17 @@
+use super::OverlayMaterial;
 use crate::renderer::*;
 
+///
+/// A circle in 2D pixel coordinates which can be rendered using any material, for example on top of a 3D scene using [render_overlay].
+/// The pixel coordinates are physical pixels relative to the [anchor](Circle::set_anchor) of the viewport, with the y-coordinate increasing downwards.
+///
 #[derive(Clone)]
 pub struct Circle<M: Material> {
     context: Context,
     model: Model<M>,
     radius: f32,
     center: Vec2,
+    anchor: Anchor,
 }
 
 impl<M: Material> Circle<M> {
@@ -19,6 +25,7 @@ impl<M: Material> Circle<M> {
         let mut circle = Self {
             context: context.clone(),
             model: Model::new_with_material(context, &mesh, material)?,
+            anchor: Anchor::TopLeft,
             center,
             radius,
         };
@@ -44,6 +51,21 @@ impl<M: Material> Circle<M> {
         &self.center
     }
 
+    ///
+    /// Sets the point of the viewport which the pixel coordinates of this circle are relative to, which defaults to [Anchor::TopLeft].
+    /// For example, a circle anchored to [Anchor::TopRight] with negative x-coordinates and positive y-coordinates stays in the top right corner when the viewport is resized.
+    ///
+    pub fn set_anchor(&mut self, anchor: Anchor) {
+        self.anchor = anchor;
+    }
+
+    ///
+    /// Returns the point of the viewport which the pixel coordinates of this circle are relative to, see [Circle::set_anchor].
+    ///
+    pub fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+
     fn update(&mut self) {
         self.model.set_transformation_2d(
             Mat3::from_translation(self.center) * Mat3::from_scale(self.radius),
@@ -57,7 +79,7 @@ impl<M: Material> Shadable2D for Circle<M> {
         material: &dyn Material,
         viewport: Viewport,
     ) -> ThreeDResult<()> {
-        self.context.camera2d(viewport, |camera2d| {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
             self.model
                 .render_with_material(material, camera2d, &Lights::default())
         })
@@ -70,11 +92,21 @@ impl<M: Material> Shadable2D for Circle<M> {
 
 impl<M: Material> Object2D for Circle<M> {
     fn render(&self, viewport: Viewport) -> ThreeDResult<()> {
-        self.context.camera2d(viewport, |camera2d| {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
             self.model.render(camera2d, &Lights::default())
         })
     }
 
+    fn render_as_overlay(&self, viewport: Viewport) -> ThreeDResult<()> {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
+            self.model.render_with_material(
+                &OverlayMaterial(&self.model.material),
+                camera2d,
+                &Lights::default(),
+            )
+        })
+    }
+
     fn is_transparent(&self) -> bool {
         self.model.is_transparent()
     }
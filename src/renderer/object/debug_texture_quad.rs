@@ -0,0 +1,115 @@
+use crate::core::*;
+
+///
+/// Draws a texture in a corner of the viewport, for example the [shadow map](crate::DirectionalLight::shadow_map) of a light when tuning the shadow bias,
+/// or a color texture used as a render target.
+/// The values in the range given by [DebugTextureQuad::value_range] are mapped to black to white, which for depth textures from a perspective projection
+/// can be made linear by converting the depth to a distance using the [DebugTextureQuad::depth_range].
+///
+pub struct DebugTextureQuad {
+    image_effect: ImageEffect,
+    /// The point of the viewport which the quad is placed at, which defaults to [Anchor::BottomRight].
+    pub anchor: Anchor,
+    /// The width and height of the quad in physical pixels.
+    pub size: (u32, u32),
+    /// The distance from the quad to the edges of the viewport in physical pixels.
+    pub margin: u32,
+    ///
+    /// The values which are mapped to black and white, which defaults to `(0.0, 1.0)`.
+    /// Values outside the range are clamped. For depth textures with a [DebugTextureQuad::depth_range], the values are distances.
+    ///
+    pub value_range: (f32, f32),
+    ///
+    /// The distance to the near and far plane of the perspective projection which a depth texture is rendered with, for example [SpotLight::shadow_depth_range](crate::SpotLight::shadow_depth_range),
+    /// which is used for converting the depth to a distance before it is mapped using the [DebugTextureQuad::value_range], or `None` to use the depth as is.
+    /// The depth of an orthographic projection, for example the shadow map of a [DirectionalLight](crate::DirectionalLight), is already linear.
+    /// Only used by [DebugTextureQuad::render_depth].
+    ///
+    pub depth_range: Option<(f32, f32)>,
+    ///
+    /// The offset (xy) and size (zw) of the part of the texture to show in texture coordinates, which defaults to the whole texture.
+    /// For example use [SpotLight::shadow_rect](crate::SpotLight::shadow_rect) to show the shadow map of a spot light in a [ShadowAtlas](crate::ShadowAtlas).
+    ///
+    pub region: Vec4,
+}
+
+impl DebugTextureQuad {
+    ///
+    /// Creates a new debug texture quad with a size of 256x256 pixels in the bottom right corner.
+    ///
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            image_effect: ImageEffect::new(
+                context,
+                include_str!("shaders/debug_texture_quad.frag"),
+            )?,
+            anchor: Anchor::BottomRight,
+            size: (256, 256),
+            margin: 10,
+            value_range: (0.0, 1.0),
+            depth_range: None,
+            region: vec4(0.0, 0.0, 1.0, 1.0),
+        })
+    }
+
+    ///
+    /// Draws the red, green and blue channels of the given color texture in the given viewport.
+    /// Must be called in a render target render function, for example in the callback function of [Screen::write].
+    ///
+    pub fn render(&self, viewport: Viewport, texture: &impl Texture) -> ThreeDResult<()> {
+        self.image_effect.use_uniform_int("mode", &0)?;
+        self.draw(viewport, texture)
+    }
+
+    ///
+    /// Draws the given depth texture in grayscale in the given viewport, see [DebugTextureQuad::depth_range].
+    /// Must be called in a render target render function, for example in the callback function of [Screen::write].
+    ///
+    pub fn render_depth(
+        &self,
+        viewport: Viewport,
+        texture: &DepthTargetTexture2D,
+    ) -> ThreeDResult<()> {
+        // The shadow sampling enables depth comparison, which must be disabled to read the depth values
+        texture.set_depth_comparison(None);
+        let mode = if self.depth_range.is_some() { 2 } else { 1 };
+        self.image_effect.use_uniform_int("mode", &mode)?;
+        self.draw(viewport, texture)
+    }
+
+    fn draw(&self, viewport: Viewport, texture: &impl Texture) -> ThreeDResult<()> {
+        let (near, far) = self.depth_range.unwrap_or((0.0, 1.0));
+        self.image_effect.use_texture("tex", texture)?;
+        self.image_effect.use_uniform_vec4("region", &self.region)?;
+        self.image_effect
+            .use_uniform_vec2("valueRange", &vec2(self.value_range.0, self.value_range.1))?;
+        self.image_effect
+            .use_uniform_vec2("depthRange", &vec2(near, far))?;
+
+        // The anchor is relative to the top left corner with the y-coordinate increasing downwards, while the viewport starts in the bottom left corner
+        let (width, height) = (
+            self.size.0.min(viewport.width),
+            self.size.1.min(viewport.height),
+        );
+        let anchor = self.anchor.position(viewport);
+        let fx = anchor.x / viewport.width.max(1) as f32;
+        let fy = anchor.y / viewport.height.max(1) as f32;
+        let margin = self.margin as f32;
+        let left = anchor.x - fx * width as f32 + margin * (1.0 - 2.0 * fx);
+        let top = anchor.y - fy * height as f32 + margin * (1.0 - 2.0 * fy);
+        let quad_viewport = Viewport {
+            x: viewport.x + left.round() as i32,
+            y: viewport.y + (viewport.height as f32 - top - height as f32).round() as i32,
+            width,
+            height,
+        };
+        self.image_effect.apply(
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTest::Always,
+                ..Default::default()
+            },
+            quad_viewport,
+        )
+    }
+}
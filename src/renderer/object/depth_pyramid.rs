@@ -0,0 +1,99 @@
+use crate::core::*;
+
+// The number of levels, which must match the number of depth level samplers in the instance culling shader
+pub(crate) const DEPTH_PYRAMID_LEVELS: usize = 8;
+
+///
+/// A hierarchy of downsampled versions of a depth texture, where each texel contains the farthest depth of the texels it covers in the level below.
+/// Used for testing whether an object is hidden behind what is already rendered by only reading a few texels,
+/// see [InstancedModel::cull_occluded](crate::InstancedModel::cull_occluded).
+///
+/// The first level has half the size of the depth texture and each following level has half the size of the previous level.
+/// The depth is stored in the standard depth range where 0 is the near plane and 1 the far plane, also in the [DepthMode::ReversedZ] depth mode.
+///
+pub struct DepthPyramid {
+    effect: ImageEffect,
+    levels: Vec<Texture2D<f32>>,
+    size: Option<(u32, u32)>,
+}
+
+impl DepthPyramid {
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            effect: ImageEffect::new(context, include_str!("shaders/depth_pyramid.frag"))?,
+            levels: Vec::new(),
+            size: None,
+        })
+    }
+
+    ///
+    /// Builds the pyramid from the given depth texture, which must be rendered with the camera used for culling,
+    /// usually in the previous frame or in a depth pre-pass containing the largest occluders.
+    /// Requires [Capabilities::color_buffer_float] since the levels are 32 bit floating point textures.
+    /// This function must not be called in a render target render function.
+    ///
+    pub fn update(&mut self, depth_texture: &DepthTargetTexture2D) -> ThreeDResult<()> {
+        let context = self.effect.context().clone();
+        let size = (depth_texture.width(), depth_texture.height());
+        if self.size != Some(size) {
+            self.levels.clear();
+            let (mut width, mut height) = size;
+            for _ in 0..DEPTH_PYRAMID_LEVELS {
+                width = (width / 2).max(1);
+                height = (height / 2).max(1);
+                self.levels.push(Texture2D::new_empty(
+                    &context,
+                    width,
+                    height,
+                    Interpolation::Nearest,
+                    Interpolation::Nearest,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                    Format::R,
+                )?);
+            }
+            self.size = Some(size);
+        }
+
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        };
+        let effect = &self.effect;
+        effect.use_texture("inputMap", depth_texture)?;
+        effect.use_uniform(
+            "reversedDepth",
+            if context.depth_mode() == DepthMode::ReversedZ {
+                1
+            } else {
+                0
+            },
+        )?;
+        let viewport = Viewport::new_at_origo(self.levels[0].width(), self.levels[0].height());
+        self.levels[0].write(ClearState::none(), || effect.apply(render_states, viewport))?;
+
+        effect.use_uniform("reversedDepth", 0)?;
+        for i in 1..self.levels.len() {
+            let (previous, next) = self.levels.split_at_mut(i);
+            let output = &mut next[0];
+            effect.use_texture("inputMap", &previous[i - 1])?;
+            let viewport = Viewport::new_at_origo(output.width(), output.height());
+            output.write(ClearState::none(), || effect.apply(render_states, viewport))?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the width and height of the depth texture used in the last [DepthPyramid::update] or `None` if it has not been updated yet.
+    ///
+    pub fn size(&self) -> Option<(u32, u32)> {
+        self.size
+    }
+
+    pub(crate) fn levels(&self) -> &[Texture2D<f32>] {
+        &self.levels
+    }
+}
@@ -0,0 +1,183 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::HashMap;
+
+// The width in texels of the textures containing the vertices and neighbour triangles
+const TEXTURE_WIDTH: u32 = 1024;
+
+// The indices are stored as f32 values in the textures, which represent all integers up to this number exactly
+const MAX_INDEX: usize = 1 << 24;
+
+///
+/// Recomputes the normals of a [Mesh] on the GPU from the positions displaced by a material, see [Material::vertex_displacement_source],
+/// such that the lighting follows the displaced surface, for example waves.
+/// The normal of each vertex is the normalized sum of the normals of the triangles around it, where vertices at the same position,
+/// for example at uv seams, share the triangles, so the normals are smooth across the seams.
+///
+/// When transform feedback is supported (see [Capabilities::transform_feedback]), which is also the case on web, [DynamicNormals::update] writes the normals to a buffer
+/// which is used instead of the static normals when drawing. Otherwise, the normals are computed in the vertex shader
+/// when drawing from the same data. In both cases, the cost is proportional to the number of vertices.
+/// The normals only depend on the original positions, so morph targets and skinning are not taken into account.
+/// See [Model::enable_dynamic_normals] for using this with a [Model].
+///
+pub struct DynamicNormals {
+    context: Context,
+    vertex_count: u32,
+    vertices: Texture2D<f32>,
+    neighbours: Texture2D<f32>,
+    normal_buffer: Option<VertexBuffer>,
+    program: Option<(String, Program)>,
+}
+
+impl DynamicNormals {
+    ///
+    /// Creates the data needed for recomputing the normals of a mesh created from the given [CPUMesh],
+    /// ie. the original positions and the triangles around each vertex.
+    ///
+    pub fn new(context: &Context, cpu_mesh: &CPUMesh) -> ThreeDResult<Self> {
+        let vertex_count = cpu_mesh.positions.len() / 3;
+        // The position is the key of the vertex, where adding zero turns negative zero into zero
+        let key = |index: usize| {
+            let p = cpu_mesh.position(index);
+            [
+                (p.x + 0.0).to_bits(),
+                (p.y + 0.0).to_bits(),
+                (p.z + 0.0).to_bits(),
+            ]
+        };
+        let mut triangles: HashMap<[u32; 3], Vec<[u32; 2]>> = HashMap::new();
+        cpu_mesh.for_each_triangle(|i0, i1, i2| {
+            let (i0, i1, i2) = (i0 as u32, i1 as u32, i2 as u32);
+            triangles
+                .entry(key(i0 as usize))
+                .or_default()
+                .push([i1, i2]);
+            triangles
+                .entry(key(i1 as usize))
+                .or_default()
+                .push([i2, i0]);
+            triangles
+                .entry(key(i2 as usize))
+                .or_default()
+                .push([i0, i1]);
+        });
+
+        let mut vertices = Vec::with_capacity((vertex_count + 1) * 4);
+        let mut neighbours = Vec::new();
+        for index in 0..vertex_count {
+            let p = cpu_mesh.position(index);
+            vertices.extend_from_slice(&[p.x, p.y, p.z, (neighbours.len() / 2) as f32]);
+            if let Some(triangles) = triangles.get(&key(index)) {
+                for triangle in triangles {
+                    neighbours.extend_from_slice(&[triangle[0] as f32, triangle[1] as f32]);
+                }
+            }
+        }
+        vertices.extend_from_slice(&[0.0, 0.0, 0.0, (neighbours.len() / 2) as f32]);
+        if neighbours.len() / 2 > MAX_INDEX {
+            Err(RendererError::DynamicNormalsTooLarge(neighbours.len() / 2))?;
+        }
+
+        let normal_buffer = if context.capabilities().transform_feedback {
+            let mut buffer = VertexBuffer::new(context)?;
+            buffer.allocate::<f32>(vertex_count * 3);
+            Some(buffer)
+        } else {
+            None
+        };
+        Ok(Self {
+            context: context.clone(),
+            vertex_count: vertex_count as u32,
+            vertices: data_texture(context, vertices, Format::RGBA)?,
+            neighbours: data_texture(context, neighbours, Format::RG)?,
+            normal_buffer,
+            program: None,
+        })
+    }
+
+    ///
+    /// Recomputes the normals from the positions displaced by the given material, which must be called each time the displacement changes,
+    /// for example when the time uniform of a wave animation changes. Does nothing if the material does not displace the vertices.
+    /// Also does nothing when transform feedback is not supported, since the normals are then computed when drawing.
+    ///
+    pub fn update(&mut self, material: &dyn Material) -> ThreeDResult<()> {
+        let source = match material.vertex_displacement_source() {
+            Some(source) => source,
+            None => return Ok(()),
+        };
+        let normal_buffer = match self.normal_buffer {
+            Some(ref mut normal_buffer) => normal_buffer,
+            None => return Ok(()),
+        };
+        // The program is only compiled again if the displacement source changes
+        if self.program.as_ref().map_or(true, |(s, _)| *s != source) {
+            let program = Program::from_source_with_transform_feedback(
+                &self.context,
+                &format!(
+                    "#define TRANSFORM_FEEDBACK\n{}\n{}",
+                    source,
+                    include_str!("shaders/dynamic_normals.vert")
+                ),
+                None,
+                &["dynamicNormal"],
+            )?;
+            self.program = Some((source, program));
+        }
+        let program = &self.program.as_ref().unwrap().1;
+        material.use_vertex_uniforms(program)?;
+        program.use_texture("dynamicNormalVertices", &self.vertices)?;
+        program.use_texture("dynamicNormalNeighbours", &self.neighbours)?;
+        program.transform_feedback(self.vertex_count, &mut [normal_buffer])
+    }
+
+    ///
+    /// Returns the buffer with the normals written by the last [DynamicNormals::update],
+    /// or `None` if transform feedback is not supported, in which case the normals are computed when drawing.
+    ///
+    pub fn normal_buffer(&self) -> Option<&VertexBuffer> {
+        self.normal_buffer.as_ref()
+    }
+
+    // The definitions which must be added to the vertex shader source for computing the normals when drawing, if they are not computed in DynamicNormals::update
+    pub(in crate::renderer) fn vertex_shader_source(&self) -> Option<&'static str> {
+        if self.normal_buffer.is_none() {
+            Some(concat!(
+                "#define USE_DYNAMIC_NORMALS\n",
+                include_str!("shaders/dynamic_normals.vert")
+            ))
+        } else {
+            None
+        }
+    }
+
+    // Sends the textures needed for computing the normals when drawing with a program built with DynamicNormals::vertex_shader_source
+    pub(in crate::renderer) fn use_textures(&self, program: &Program) -> ThreeDResult<()> {
+        program.use_texture("dynamicNormalVertices", &self.vertices)?;
+        program.use_texture("dynamicNormalNeighbours", &self.neighbours)
+    }
+}
+
+// A texture containing the given data row by row, which is padded to fill the last row
+fn data_texture(
+    context: &Context,
+    mut data: Vec<f32>,
+    format: Format,
+) -> ThreeDResult<Texture2D<f32>> {
+    let channels = if format == Format::RGBA { 4 } else { 2 };
+    let width = TEXTURE_WIDTH;
+    let height = ((data.len() as u32 / channels + width - 1) / width).max(1);
+    data.resize((width * height * channels) as usize, 0.0);
+    let mut texture = Texture2D::new_empty(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        format,
+    )?;
+    texture.fill(&data)?;
+    Ok(texture)
+}
@@ -0,0 +1,400 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::HashMap;
+
+///
+/// The feature edges of a mesh, ie. the boundary edges and the edges where the angle between the two neighbouring faces is larger than the crease angle,
+/// which are rendered as lines by a [HiddenLineRenderer].
+/// Non-manifold edges, which are shared by more than two faces, are always feature edges.
+///
+pub struct FeatureEdges {
+    start_buffer: InstanceBuffer,
+    end_buffer: InstanceBuffer,
+    candidates: Vec<(Vec3, Vec3, f32)>,
+    crease_angle: Radians,
+    count: usize,
+    transformation: Mat4,
+    aabb: AxisAlignedBoundingBox,
+}
+
+impl FeatureEdges {
+    ///
+    /// Extracts the feature edges from the given mesh using the given crease angle.
+    /// Vertices at the same position are welded before the edges are found, so a mesh with flat shaded faces gives the same edges as a smooth mesh.
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_mesh: &CPUMesh,
+        crease_angle: impl Into<Radians>,
+    ) -> ThreeDResult<Self> {
+        let mut edges = Self {
+            start_buffer: InstanceBuffer::new(context)?,
+            end_buffer: InstanceBuffer::new(context)?,
+            candidates: edge_candidates(cpu_mesh),
+            crease_angle: crease_angle.into(),
+            count: 0,
+            transformation: Mat4::identity(),
+            aabb: cpu_mesh.compute_aabb(),
+        };
+        edges.update_buffers();
+        Ok(edges)
+    }
+
+    ///
+    /// Sets the crease angle, so the edges where the angle between the two neighbouring faces is at least this angle are feature edges.
+    /// A crease angle of zero gives all of the edges of the mesh.
+    ///
+    pub fn set_crease_angle(&mut self, crease_angle: impl Into<Radians>) {
+        self.crease_angle = crease_angle.into();
+        self.update_buffers();
+    }
+
+    ///
+    /// Returns the crease angle.
+    ///
+    pub fn crease_angle(&self) -> Radians {
+        self.crease_angle
+    }
+
+    ///
+    /// Returns the number of feature edges with the current crease angle.
+    ///
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    ///
+    /// Set the local to world transformation applied to the edges, which should be the same as the transformation of the [Model] of the mesh.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    ///
+    /// Returns the local to world transformation applied to the edges.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Returns the axis aligned bounding box of the edges in world space.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = self.aabb.clone();
+        aabb.transform(&self.transformation);
+        aabb
+    }
+
+    fn update_buffers(&mut self) {
+        let mut start_data = Vec::new();
+        let mut end_data = Vec::new();
+        for (start, end, angle) in self.candidates.iter() {
+            if *angle >= self.crease_angle.0 {
+                start_data.extend_from_slice(&[start.x, start.y, start.z]);
+                end_data.extend_from_slice(&[end.x, end.y, end.z]);
+            }
+        }
+        self.count = start_data.len() / 3;
+        if self.count > 0 {
+            self.start_buffer.fill_with_static(&start_data);
+            self.end_buffer.fill_with_static(&end_data);
+        }
+    }
+}
+
+// Returns the edges of the mesh together with the angle between the neighbouring faces, which is larger than any crease angle for boundary and non-manifold edges
+fn edge_candidates(cpu_mesh: &CPUMesh) -> Vec<(Vec3, Vec3, f32)> {
+    let positions: Vec<Vec3> = cpu_mesh
+        .positions
+        .chunks(3)
+        .map(|p| vec3(p[0], p[1], p[2]))
+        .collect();
+    let indices = cpu_mesh
+        .indices
+        .as_ref()
+        .map(|indices| indices.into_u32())
+        .unwrap_or_else(|| (0..positions.len() as u32).collect());
+
+    // Welds the vertices by quantizing the positions relative to the size of the mesh
+    let aabb = cpu_mesh.compute_aabb();
+    let tolerance = (aabb.max() - aabb.min()).magnitude().max(std::f32::EPSILON) * 1.0e-5;
+    let mut welded = HashMap::new();
+    let vertex_ids: Vec<u32> = positions
+        .iter()
+        .map(|p| {
+            let key = (
+                (p.x / tolerance).round() as i64,
+                (p.y / tolerance).round() as i64,
+                (p.z / tolerance).round() as i64,
+            );
+            let id = welded.len() as u32;
+            *welded.entry(key).or_insert(id)
+        })
+        .collect();
+
+    let mut edges: HashMap<(u32, u32), (Vec3, Vec3, Vec<Vec3>)> = HashMap::new();
+    for triangle in indices.chunks(3).filter(|t| t.len() == 3) {
+        let (a, b, c) = (
+            positions[triangle[0] as usize],
+            positions[triangle[1] as usize],
+            positions[triangle[2] as usize],
+        );
+        let normal = (b - a).cross(c - a);
+        if normal.magnitude2() == 0.0 {
+            continue;
+        }
+        let normal = normal.normalize();
+        for (i, j) in [(0, 1), (1, 2), (2, 0)].iter() {
+            let (vi, vj) = (
+                vertex_ids[triangle[*i] as usize],
+                vertex_ids[triangle[*j] as usize],
+            );
+            if vi == vj {
+                continue;
+            }
+            let (pi, pj) = (
+                positions[triangle[*i] as usize],
+                positions[triangle[*j] as usize],
+            );
+            let key = if vi < vj { (vi, vj) } else { (vj, vi) };
+            edges
+                .entry(key)
+                .or_insert_with(|| (pi, pj, Vec::new()))
+                .2
+                .push(normal);
+        }
+    }
+
+    let mut candidates: Vec<_> = edges
+        .into_iter()
+        .map(|(key, (start, end, normals))| {
+            let angle = if normals.len() == 2 {
+                normals[0].dot(normals[1]).max(-1.0).min(1.0).acos()
+            } else {
+                std::f32::MAX
+            };
+            (key, (start, end, angle))
+        })
+        .collect();
+    // Sorted so the order of the edges does not depend on the hashing
+    candidates.sort_by_key(|(key, _)| *key);
+    candidates.into_iter().map(|(_, edge)| edge).collect()
+}
+
+///
+/// Renders [FeatureEdges] as lines with a constant width in pixels for technical illustrations,
+/// where the visible edges are drawn as solid lines and the edges which are hidden by the models are drawn as dashed lines.
+///
+/// The depth of the models which can hide the edges is first rendered into an offscreen target by [HiddenLineRenderer::depth_pass],
+/// so several models share one depth pre-pass. [HiddenLineRenderer::render] then copies this depth into the render target
+/// and draws the edges twice, the visible edges with the depth test [DepthTest::LessOrEqual] and the hidden edges with the depth test [DepthTest::Greater].
+///
+pub struct HiddenLineRenderer {
+    context: Context,
+    /// The color of the visible edges.
+    pub visible_color: Color,
+    /// The color of the hidden edges.
+    pub hidden_color: Color,
+    /// Whether or not the hidden edges are drawn.
+    pub show_hidden: bool,
+    /// The width of the lines in pixels.
+    pub line_width: f32,
+    /// The length in pixels of the dashes of the hidden edges.
+    pub dash_length: f32,
+    /// The length in pixels of the gaps between the dashes of the hidden edges.
+    pub gap_length: f32,
+    ///
+    /// The fraction of the distance to the camera the edges are moved towards the camera before the depth test,
+    /// so the edges are not hidden by the faces they lie on. Defaults to `0.002`.
+    ///
+    pub depth_bias: f32,
+    corner_buffer: VertexBuffer,
+    camera: Camera,
+    depth_target: ResizableTarget<u8>,
+}
+
+impl HiddenLineRenderer {
+    ///
+    /// Constructs a new hidden line renderer with black visible edges and gray dashed hidden edges.
+    ///
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        // The x-coordinate of a corner selects the end of the edge and the y-coordinate the side of the line
+        let corners = vec![
+            0.0, -1.0, 1.0, -1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0, -1.0,
+        ];
+        Ok(Self {
+            context: context.clone(),
+            visible_color: Color::BLACK,
+            hidden_color: Color::new_opaque(150, 150, 150),
+            show_hidden: true,
+            line_width: 2.0,
+            dash_length: 8.0,
+            gap_length: 6.0,
+            depth_bias: 0.002,
+            corner_buffer: VertexBuffer::new_with_static(context, &corners)?,
+            camera: Camera::new_perspective(
+                context,
+                Viewport::new_at_origo(1, 1),
+                vec3(0.0, 0.0, 1.0),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                degrees(75.0),
+                0.01,
+                10.0,
+            )?,
+            depth_target: ResizableTarget::new_depth(context, |context, width, height| {
+                DepthTargetTexture2D::new(
+                    context,
+                    width,
+                    height,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                    DepthFormat::Depth32F,
+                )
+            }),
+        })
+    }
+
+    ///
+    /// Renders the depth of the given geometries, which can hide the edges, into an offscreen target with the size of the viewport of the camera.
+    /// Must not be called in a render target render function and must be called before [HiddenLineRenderer::render] whenever the camera or the geometries change.
+    ///
+    pub fn depth_pass(
+        &mut self,
+        camera: &Camera,
+        geometries: &[impl Geometry],
+    ) -> ThreeDResult<()> {
+        let (width, height) = (camera.viewport().width, camera.viewport().height);
+        match camera.projection_type() {
+            ProjectionType::Perspective { field_of_view_y } => {
+                self.camera.set_perspective_projection(
+                    *field_of_view_y,
+                    camera.z_near(),
+                    camera.z_far(),
+                )?;
+            }
+            ProjectionType::Orthographic { height, .. } => {
+                self.camera.set_orthographic_projection(
+                    *height,
+                    camera.z_near(),
+                    camera.z_far(),
+                )?;
+            }
+        };
+        self.camera
+            .set_viewport(Viewport::new_at_origo(width, height))?;
+        self.camera
+            .set_view(*camera.position(), *camera.target(), *camera.up())?;
+        self.camera.set_lens_shift(camera.lens_shift())?;
+        self.camera
+            .set_projection_jitter(camera.projection_jitter())?;
+
+        let depth_material = DepthMaterial {
+            render_states: RenderStates {
+                write_mask: WriteMask::DEPTH,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let camera = &self.camera;
+        self.depth_target
+            .get(width, height)?
+            .write(ClearState::depth(1.0), || {
+                for geometry in geometries.iter().filter(|g| camera.in_frustum(&g.aabb())) {
+                    geometry.render_with_material(&depth_material, camera, &Lights::default())?;
+                }
+                Ok(())
+            })
+    }
+
+    ///
+    /// Renders the given edges using the depth from the last [HiddenLineRenderer::depth_pass] call.
+    /// The depth buffer of the render target is replaced by that depth inside the viewport of the camera.
+    /// Must be called in a render target render function after the faces of the models, if any,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn render(&self, camera: &Camera, edges: &[&FeatureEdges]) -> ThreeDResult<()> {
+        let viewport = camera.viewport();
+        let depth_texture = self
+            .depth_target
+            .depth_texture()
+            .ok_or(RendererError::MissingDepthPass)?;
+        self.context.effect(
+            "
+            uniform sampler2D depthMap;
+            in vec2 uv;
+            void main()
+            {
+                gl_FragDepth = texture(depthMap, uv).r;
+            }",
+            |effect| {
+                effect.use_texture("depthMap", depth_texture)?;
+                effect.apply(
+                    RenderStates {
+                        depth_test: DepthTest::Always,
+                        write_mask: WriteMask::DEPTH,
+                        ..Default::default()
+                    },
+                    viewport,
+                )
+            },
+        )?;
+
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::None,
+            ..Default::default()
+        };
+        self.context.program(
+            include_str!("shaders/hidden_lines.vert"),
+            include_str!("shaders/hidden_lines.frag"),
+            |program| {
+                program.use_uniform_block("Camera", camera.uniform_buffer());
+                program.use_uniform_vec2(
+                    "viewportSize",
+                    &vec2(viewport.width as f32, viewport.height as f32),
+                )?;
+                program.use_uniform_float("lineWidth", &self.line_width.max(0.0))?;
+                program.use_uniform_float("depthBias", &self.depth_bias)?;
+                program.use_uniform_vec2(
+                    "dashPattern",
+                    &vec2(self.dash_length.max(0.0), self.gap_length.max(0.0)),
+                )?;
+                program.use_attribute_vec2("corner", &self.corner_buffer)?;
+                for edge in edges.iter().filter(|e| e.count > 0) {
+                    program.use_uniform_mat4("modelMatrix", &edge.transformation)?;
+                    program.use_attribute_vec3_instanced("start", &edge.start_buffer)?;
+                    program.use_attribute_vec3_instanced("end", &edge.end_buffer)?;
+
+                    program.use_uniform_vec4("color", &self.visible_color.to_vec4())?;
+                    program.use_uniform_int("dashed", &0)?;
+                    program.draw_arrays_instanced(
+                        RenderStates {
+                            depth_test: DepthTest::LessOrEqual,
+                            ..render_states
+                        },
+                        viewport,
+                        6,
+                        edge.count as u32,
+                    );
+                    if self.show_hidden {
+                        program.use_uniform_vec4("color", &self.hidden_color.to_vec4())?;
+                        program.use_uniform_int("dashed", &1)?;
+                        program.draw_arrays_instanced(
+                            RenderStates {
+                                depth_test: DepthTest::Greater,
+                                ..render_states
+                            },
+                            viewport,
+                            6,
+                            edge.count as u32,
+                        );
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -10,7 +10,6 @@ const NO_VIEW_ANGLES: u32 = 8;
 ///
 pub struct Imposters {
     context: Context,
-    program: Program,
     center_buffer: InstanceBuffer,
     rotation_buffer: InstanceBuffer,
     positions_buffer: VertexBuffer,
@@ -25,16 +24,6 @@ impl Imposters {
         let positions_buffer = VertexBuffer::new(&context)?;
         let uvs_buffer = VertexBuffer::new_with_static(&context, &uvs)?;
 
-        let program = Program::from_source(
-            context,
-            include_str!("shaders/imposter.vert"),
-            &format!(
-                "{}{}",
-                include_str!("../../core/shared.frag"),
-                include_str!("shaders/imposter.frag")
-            ),
-        )?;
-
         let center_buffer = InstanceBuffer::new(context)?;
         let rotation_buffer = InstanceBuffer::new(context)?;
         let texture = Texture2DArray::<u8>::new_empty(
@@ -53,7 +42,6 @@ impl Imposters {
         Ok(Imposters {
             context: context.clone(),
             texture,
-            program,
             center_buffer,
             rotation_buffer,
             positions_buffer,
@@ -149,28 +137,29 @@ impl Imposters {
             cull: Cull::Back,
             ..Default::default()
         };
-        self.program
-            .use_uniform_int("no_views", &(NO_VIEW_ANGLES as i32))?;
-        self.program
-            .use_uniform_block("Camera", camera.uniform_buffer());
-
-        self.program.use_texture_array("tex", &self.texture)?;
-
-        self.program
-            .use_attribute_vec3("position", &self.positions_buffer)?;
-        self.program
-            .use_attribute_vec2("uv_coordinate", &self.uvs_buffer)?;
-
-        self.program
-            .use_attribute_vec3_instanced("center", &self.center_buffer)?;
-        self.program
-            .use_attribute_instanced("theta", &self.rotation_buffer)?;
-        self.program.draw_arrays_instanced(
-            render_states,
-            camera.viewport(),
-            6,
-            self.instance_count,
-        );
-        Ok(())
+        self.context.program(
+            include_str!("shaders/imposter.vert"),
+            &format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/imposter.frag")
+            ),
+            |program| {
+                program.use_uniform_int("no_views", &(NO_VIEW_ANGLES as i32))?;
+                program.use_uniform_block("Camera", camera.uniform_buffer());
+                program.use_texture_array("tex", &self.texture)?;
+                program.use_attribute_vec3("position", &self.positions_buffer)?;
+                program.use_attribute_vec2("uv_coordinate", &self.uvs_buffer)?;
+                program.use_attribute_vec3_instanced("center", &self.center_buffer)?;
+                program.use_attribute_instanced("theta", &self.rotation_buffer)?;
+                program.draw_arrays_instanced(
+                    render_states,
+                    camera.viewport(),
+                    6,
+                    self.instance_count,
+                );
+                Ok(())
+            },
+        )
     }
 }
@@ -1,24 +1,36 @@
 use crate::core::*;
 use crate::renderer::*;
+use std::cell::{Cell, RefCell};
 
 ///
 /// Similar to [Model], except it is possible to render many instances of the same model efficiently.
+/// The transformation set by [GeometryMut::set_transformation] is applied to all of the instances as a parent transformation,
+/// so an instance is transformed by `transformation * instance.geometry_transform`,
+/// which means a single instance with the identity transformation is rendered exactly like a [Model] with the same transformation.
 ///
 pub struct InstancedModel<M: Material> {
     context: Context,
     mesh: Mesh,
-    instance_buffer1: InstanceBuffer,
-    instance_buffer2: InstanceBuffer,
-    instance_buffer3: InstanceBuffer,
-    instance_tex_transform1: InstanceBuffer,
-    instance_tex_transform2: InstanceBuffer,
+    instance_buffers: RefCell<InstanceBuffers>,
     aabb_local: AxisAlignedBoundingBox,
     aabb: AxisAlignedBoundingBox,
+    bounding_sphere_local: BoundingSphere,
     transformation: Mat4,
     instances: Vec<ModelInstance>,
     texture_transform: Mat3,
+    use_uv_offset_scale: bool,
+    culling: Option<RefCell<InstanceCulling>>,
+    gpu_culling: Option<RefCell<GpuInstanceCulling>>,
+    drawn_instance_count: Cell<u32>,
+    change_count: u64,
+    selection: SelectionSet,
+    selected_instance_buffers: InstanceBuffers,
+    render_order: i32,
+    program_id: Cell<u64>,
     /// The material applied to the instanced model
     pub material: M,
+    /// The material used for rendering the selected instances on top of the instances rendered with the [InstancedModel::material], see [InstancedModel::set_selection].
+    pub highlight_material: ColorMaterial,
 }
 
 impl InstancedModel<ColorMaterial> {
@@ -37,32 +49,90 @@ impl InstancedModel<ColorMaterial> {
 }
 
 impl<M: Material> InstancedModel<M> {
+    ///
+    /// Creates a new instanced 3D model with a triangle mesh as geometry and the given material.
+    /// The model is rendered in as many instances as there are [ModelInstance]s.
+    ///
     pub fn new_with_material(
         context: &Context,
         instances: &[ModelInstance],
         cpu_mesh: &CPUMesh,
         material: M,
+    ) -> ThreeDResult<Self> {
+        Self::new_with_settings(
+            context,
+            instances,
+            cpu_mesh,
+            material,
+            MeshSettings::default(),
+        )
+    }
+
+    ///
+    /// Creates a new instanced 3D model with a triangle mesh as geometry and the given material,
+    /// where the mesh data is stored on the GPU using the given settings, for example in compact formats, see [MeshSettings].
+    ///
+    pub fn new_with_settings(
+        context: &Context,
+        instances: &[ModelInstance],
+        cpu_mesh: &CPUMesh,
+        material: M,
+        settings: MeshSettings,
     ) -> ThreeDResult<Self> {
         let aabb = cpu_mesh.compute_aabb();
         let mut model = Self {
             context: context.clone(),
-            mesh: Mesh::new(context, cpu_mesh)?,
-            instance_buffer1: InstanceBuffer::new(context)?,
-            instance_buffer2: InstanceBuffer::new(context)?,
-            instance_buffer3: InstanceBuffer::new(context)?,
-            instance_tex_transform1: InstanceBuffer::new(context)?,
-            instance_tex_transform2: InstanceBuffer::new(context)?,
+            mesh: Mesh::new_with_settings(context, cpu_mesh, settings)?,
+            instance_buffers: RefCell::new(InstanceBuffers::new(context)?),
             aabb,
             aabb_local: aabb.clone(),
+            bounding_sphere_local: cpu_mesh.compute_bounding_sphere(),
             transformation: Mat4::identity(),
             instances: instances.to_vec(),
             texture_transform: Mat3::identity(),
+            use_uv_offset_scale: false,
+            culling: None,
+            gpu_culling: None,
+            drawn_instance_count: Cell::new(0),
+            change_count: 0,
+            selection: SelectionSet::new(),
+            selected_instance_buffers: InstanceBuffers::new(context)?,
+            render_order: 0,
+            program_id: Cell::new(0),
             material,
+            highlight_material: ColorMaterial {
+                color: Color::new(255, 200, 0, 150),
+                opaque_render_states: RenderStates {
+                    depth_test: DepthTest::LessOrEqual,
+                    ..Default::default()
+                },
+                transparent_render_states: RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    blend: Blend::TRANSPARENCY,
+                    depth_test: DepthTest::LessOrEqual,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
         };
         model.update_buffers();
         Ok(model)
     }
 
+    ///
+    /// Returns the material applied to the instanced model.
+    ///
+    pub fn material(&self) -> &M {
+        &self.material
+    }
+
+    ///
+    /// Returns the material applied to the instanced model for changing its parameters after the instanced model is created.
+    ///
+    pub fn material_mut(&mut self) -> &mut M {
+        &mut self.material
+    }
+
     pub fn texture_transform(&mut self) -> &Mat3 {
         &self.texture_transform
     }
@@ -71,47 +141,30 @@ impl<M: Material> InstancedModel<M> {
         self.texture_transform = texture_transform;
     }
 
+    ///
+    /// Sets the render order of this instanced model, see [Object::render_order].
+    ///
+    pub fn set_render_order(&mut self, render_order: i32) {
+        self.render_order = render_order;
+    }
+
     ///
     /// Updates instance transform and uv buffers and aabb on demand.
     ///
     fn update_buffers(&mut self) {
-        let mut row1 = Vec::new();
-        let mut row2 = Vec::new();
-        let mut row3 = Vec::new();
-        let mut instance_tex_transform1 = Vec::new();
-        let mut instance_tex_transform2 = Vec::new();
-        for instance in self.instances.iter() {
-            row1.push(instance.geometry_transform.x.x);
-            row1.push(instance.geometry_transform.y.x);
-            row1.push(instance.geometry_transform.z.x);
-            row1.push(instance.geometry_transform.w.x);
-
-            row2.push(instance.geometry_transform.x.y);
-            row2.push(instance.geometry_transform.y.y);
-            row2.push(instance.geometry_transform.z.y);
-            row2.push(instance.geometry_transform.w.y);
-
-            row3.push(instance.geometry_transform.x.z);
-            row3.push(instance.geometry_transform.y.z);
-            row3.push(instance.geometry_transform.z.z);
-            row3.push(instance.geometry_transform.w.z);
-
-            instance_tex_transform1.push(instance.texture_transform.x.x);
-            instance_tex_transform1.push(instance.texture_transform.y.x);
-            instance_tex_transform1.push(instance.texture_transform.z.x);
-
-            instance_tex_transform2.push(instance.texture_transform.x.y);
-            instance_tex_transform2.push(instance.texture_transform.y.y);
-            instance_tex_transform2.push(instance.texture_transform.z.y);
-        }
-        self.instance_buffer1.fill_with_dynamic(&row1);
-        self.instance_buffer2.fill_with_dynamic(&row2);
-        self.instance_buffer3.fill_with_dynamic(&row3);
-        self.instance_tex_transform1
-            .fill_with_dynamic(&instance_tex_transform1);
-        self.instance_tex_transform2
-            .fill_with_dynamic(&instance_tex_transform2);
+        self.use_uv_offset_scale = self
+            .instances
+            .iter()
+            .any(|instance| instance.uv_offset_scale.is_some());
+        self.instance_buffers.borrow_mut().fill(
+            &self.instances,
+            0..self.instances.len(),
+            self.use_uv_offset_scale,
+        );
+        self.update_selected_buffers();
         self.update_aabb();
+        self.update_culling();
+        self.change_count += 1;
     }
 
     ///
@@ -123,17 +176,413 @@ impl<M: Material> InstancedModel<M> {
 
     ///
     /// Create an instance for each element with the given mesh and texture transforms.
+    /// Since the new instances are not related to the old instances, the selection is cleared.
     ///
     pub fn set_instances(&mut self, instances: &[ModelInstance]) {
         self.instances = instances.to_vec();
+        self.selection.clear();
+        self.update_buffers();
+    }
+
+    ///
+    /// Inserts the given instances before the instance with the given index or after the last instance if the index is equal to the number of instances.
+    /// The selection is kept, ie. the indices of the selected instances after the inserted instances are increased by the number of inserted instances.
+    ///
+    pub fn insert_instances(&mut self, index: usize, instances: &[ModelInstance]) {
+        let count = instances.len();
+        self.instances
+            .splice(index..index, instances.iter().cloned());
+        self.selection.indices = self
+            .selection
+            .iter()
+            .map(|i| if i >= index { i + count } else { i })
+            .collect();
+        self.update_buffers();
+    }
+
+    ///
+    /// Removes the instances with the given indices, indices out of range are ignored.
+    /// The selection is kept, ie. the removed instances are removed from the selection
+    /// and the indices of the selected instances after a removed instance are decreased accordingly.
+    ///
+    pub fn remove_instances(&mut self, indices: &[usize]) {
+        let removed = indices.iter().cloned().collect::<SelectionSet>();
+        let mut index = 0;
+        self.instances.retain(|_| {
+            index += 1;
+            !removed.contains(index - 1)
+        });
+        self.selection.indices = self
+            .selection
+            .iter()
+            .filter(|i| !removed.contains(*i))
+            .map(|i| i - removed.indices.range(..i).count())
+            .collect();
         self.update_buffers();
     }
 
+    ///
+    /// Removes the selected instances, see [InstancedModel::remove_instances].
+    ///
+    pub fn remove_selected_instances(&mut self) {
+        let selected = self.selection.iter().collect::<Vec<_>>();
+        self.remove_instances(&selected);
+    }
+
+    ///
+    /// Returns the indices of the selected instances.
+    ///
+    pub fn selection(&self) -> &SelectionSet {
+        &self.selection
+    }
+
+    ///
+    /// Selects the instances with the indices in the given set, indices out of range are ignored.
+    /// The selected instances are highlighted by rendering them again with the [InstancedModel::highlight_material] when the model is rendered using [Object::render].
+    /// Only the selected instances are copied into a separate set of instance buffers, so the extra draw call is cheap for a small selection.
+    ///
+    pub fn set_selection(&mut self, selection: SelectionSet) {
+        let count = self.instances.len();
+        self.selection = selection.iter().filter(|i| *i < count).collect();
+        self.update_selected_buffers();
+    }
+
+    ///
+    /// Selects the instance with the given index if it is not selected and deselects it otherwise.
+    ///
+    pub fn toggle_selected(&mut self, index: usize) {
+        let mut selection = self.selection.clone();
+        selection.toggle(index);
+        self.set_selection(selection);
+    }
+
+    fn update_selected_buffers(&mut self) {
+        self.selected_instance_buffers.fill(
+            &self.instances,
+            self.selection.iter(),
+            self.use_uv_offset_scale,
+        );
+    }
+
+    ///
+    /// Renders the index of each instance into the color channels of the current render target,
+    /// where the index plus one is encoded in the four 8 bit channels of an RGBA texture with the red channel as the least significant byte,
+    /// so zero means that no instance is rendered at that pixel.
+    /// Must be called in a render target render function, for example in the callback function of [RenderTarget::write](crate::RenderTarget::write).
+    /// See [InstancedModel::instance_at_pixel] for finding the instance at a specific pixel.
+    ///
+    /// **Note:** The indices are passed to the shader as floating point values, so only the first 16,777,216 instances can be identified.
+    ///
+    pub fn render_instance_ids(&self, camera: &Camera) -> ThreeDResult<()> {
+        let fragment_shader_source = include_str!("shaders/instance_id.frag");
+        self.context.program(
+            &format!(
+                "#define USE_INSTANCE_IDS\n{}",
+                Self::vertex_shader_source(fragment_shader_source)?
+            ),
+            fragment_shader_source,
+            |program| self.draw(program, RenderStates::default(), camera, camera.viewport()),
+        )
+    }
+
+    ///
+    /// Returns the index of the instance which is visible at the given pixel or `None` if no instance is visible at that pixel.
+    /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the top left corner of the viewport
+    /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the bottom right corner.
+    /// Only the instances of this model are considered, so other objects do not occlude the instances.
+    /// The instance indices are rendered into a render target of a single pixel, see [InstancedModel::render_instance_ids], so only four bytes are read back.
+    ///
+    pub fn instance_at_pixel(
+        &self,
+        camera: &Camera,
+        pixel: (f32, f32),
+    ) -> ThreeDResult<Option<usize>> {
+        let viewport = Viewport::new_at_origo(1, 1);
+        let position = camera.position_at_pixel(pixel);
+        let direction = camera.view_direction_at_pixel(pixel);
+        let up = if direction.dot(vec3(1.0, 0.0, 0.0)).abs() > 0.99 {
+            direction.cross(vec3(0.0, 1.0, 0.0))
+        } else {
+            direction.cross(vec3(1.0, 0.0, 0.0))
+        };
+        let max_depth = camera.z_far() - camera.z_near();
+        let position = position + direction * camera.z_near();
+        let ray_camera = Camera::new_orthographic(
+            &self.context,
+            viewport,
+            position,
+            position + direction * max_depth,
+            up,
+            0.01,
+            0.0,
+            max_depth,
+        )?;
+        let mut texture = Texture2D::<u8>::new_empty(
+            &self.context,
+            viewport.width,
+            viewport.height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        let mut depth_texture = DepthTargetTexture2D::new(
+            &self.context,
+            viewport.width,
+            viewport.height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            DepthFormat::Depth32F,
+        )?;
+        RenderTarget::new(&self.context, &mut texture, &mut depth_texture)?
+            .write(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0), || {
+                self.render_instance_ids(&ray_camera)
+            })?;
+        let id = texture
+            .read_internal(viewport)?
+            .iter()
+            .rev()
+            .fold(0usize, |id, byte| (id << 8) | *byte as usize);
+        Ok(if id > 0 && id <= self.instances.len() {
+            Some(id - 1)
+        } else {
+            None
+        })
+    }
+
+    ///
+    /// Enables or disables culling of the individual instances which are outside the frustum of the camera used for rendering.
+    /// When enabled, the instances are sorted into a spatial grid and each time the model is rendered with a new camera,
+    /// the visible instances are found by testing the bounding spheres of the cells of the grid and then the bounding spheres of the instances in the visible cells.
+    /// The visible instances are then moved to the front of the instance buffers and only those instances are drawn.
+    /// This is a clear improvement when many instances spread over a large area are rendered and most of them are outside the frustum,
+    /// but it costs an update of the instance buffers each time the model is rendered with another camera than last time,
+    /// for example twice per frame if the model also casts a shadow, so it is disabled by default.
+    /// Enabling culling disables [InstancedModel::set_gpu_culling].
+    ///
+    pub fn set_culling(&mut self, enabled: bool) {
+        if enabled {
+            self.gpu_culling = None;
+        }
+        if enabled != self.culling.is_some() {
+            if enabled {
+                self.culling = Some(RefCell::new(self.new_culling()));
+            } else {
+                self.culling = None;
+                self.instance_buffers.borrow_mut().fill(
+                    &self.instances,
+                    0..self.instances.len(),
+                    self.use_uv_offset_scale,
+                );
+            }
+        }
+    }
+
+    ///
+    /// Returns whether or not culling of the individual instances is enabled, see [InstancedModel::set_culling].
+    ///
+    pub fn culling(&self) -> bool {
+        self.culling.is_some()
+    }
+
+    ///
+    /// Enables or disables culling of the individual instances on the GPU, which is much faster than [InstancedModel::set_culling] when rendering a very large number of instances.
+    /// When enabled, a shader tests the bounding sphere of each instance against the planes of the camera frustum each time the model is rendered with a new camera
+    /// and transform feedback writes the visible instances to a second set of instance buffers, from which the instances are drawn.
+    /// The instances can also be culled when they are hidden behind what is already rendered, see [InstancedModel::cull_occluded].
+    ///
+    /// Falls back to [InstancedModel::set_culling] when geometry shaders are not supported, see [Capabilities::geometry_shader], which is always the case on web.
+    ///
+    pub fn set_gpu_culling(&mut self, enabled: bool) -> ThreeDResult<()> {
+        if !self.context.capabilities().geometry_shader {
+            self.set_culling(enabled);
+        } else if enabled != self.gpu_culling.is_some() {
+            // The culling shader reads all instances in their original order
+            self.set_culling(false);
+            self.gpu_culling = if enabled {
+                Some(RefCell::new(GpuInstanceCulling::new(&self.context)?))
+            } else {
+                None
+            };
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns whether or not culling of the individual instances on the GPU is enabled, see [InstancedModel::set_gpu_culling].
+    /// Always false when geometry shaders are not supported, in which case [InstancedModel::culling] is used instead.
+    ///
+    pub fn gpu_culling(&self) -> bool {
+        self.gpu_culling.is_some()
+    }
+
+    ///
+    /// Culls the instances which are outside the frustum of the given camera or hidden behind the depth stored in the given [DepthPyramid],
+    /// which must be built from a depth texture rendered with the same camera, usually in the previous frame.
+    /// The visible instances are drawn each time the model is rendered with the same camera until the camera changes, so call this each frame before rendering.
+    /// Instances which were hidden in the previous frame and are no longer hidden might be missing in one frame when using the depth of the previous frame.
+    ///
+    /// Only culls the instances outside the frustum when the depth pyramid has not been updated yet and does nothing unless [InstancedModel::set_gpu_culling] is enabled.
+    ///
+    pub fn cull_occluded(&self, camera: &Camera, depth_pyramid: &DepthPyramid) -> ThreeDResult<()> {
+        if self.gpu_culling.is_some() {
+            let depth_pyramid = if depth_pyramid.size().is_some() {
+                Some(depth_pyramid)
+            } else {
+                None
+            };
+            self.gpu_cull(camera, depth_pyramid)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the number of instances drawn the last time this model was rendered,
+    /// which is less than the number of instances if some of them were culled, see [InstancedModel::set_culling].
+    ///
+    pub fn drawn_instance_count(&self) -> u32 {
+        self.drawn_instance_count.get()
+    }
+
+    fn update_culling(&mut self) {
+        if self.culling.is_some() {
+            self.culling = Some(RefCell::new(self.new_culling()));
+        }
+        if let Some(ref gpu_culling) = self.gpu_culling {
+            gpu_culling.borrow_mut().view_projection = None;
+        }
+    }
+
+    fn new_culling(&self) -> InstanceCulling {
+        InstanceCulling::new(
+            &self.instances,
+            self.bounding_sphere_local,
+            &self.transformation,
+        )
+    }
+
+    ///
+    /// Moves the instances inside the frustum of the given camera to the front of the instance buffers if culling is enabled
+    /// and returns the number of instances to draw.
+    ///
+    fn cull(&self, camera: &Camera) -> ThreeDResult<u32> {
+        if let Some(ref gpu_culling) = self.gpu_culling {
+            let view_projection = camera.projection() * camera.view();
+            if gpu_culling.borrow().view_projection != Some(view_projection) {
+                self.gpu_cull(camera, None)?;
+            }
+            return Ok(gpu_culling.borrow().visible_count);
+        }
+        Ok(if let Some(ref culling) = self.culling {
+            let mut culling = culling.borrow_mut();
+            let view_projection = camera.projection() * camera.view();
+            // Only update the instance buffers when rendering with another camera than last time
+            if culling.view_projection != Some(view_projection) {
+                let visible = culling.visible_instances(camera);
+                self.instance_buffers.borrow_mut().fill(
+                    &self.instances,
+                    visible.iter().cloned(),
+                    self.use_uv_offset_scale,
+                );
+                culling.visible_count = visible.len() as u32;
+                culling.view_projection = Some(view_projection);
+            }
+            culling.visible_count
+        } else {
+            self.instances.len() as u32
+        })
+    }
+
+    ///
+    /// Writes the instances inside the frustum of the given camera, and not hidden behind the depth in the given depth pyramid, to the instance buffers of the GPU culling.
+    ///
+    fn gpu_cull(&self, camera: &Camera, depth_pyramid: Option<&DepthPyramid>) -> ThreeDResult<()> {
+        let mut gpu_culling = self.gpu_culling.as_ref().unwrap().borrow_mut();
+        let gpu_culling = &mut *gpu_culling;
+        let count = self.instances.len();
+        gpu_culling.view_projection = Some(camera.projection() * camera.view());
+        if count == 0 {
+            gpu_culling.visible_count = 0;
+            return Ok(());
+        }
+        if gpu_culling.capacity != count {
+            gpu_culling.visibility.allocate::<f32>(count);
+            gpu_culling.instance_buffers.allocate(count);
+            gpu_culling.capacity = count;
+        }
+        let input = self.instance_buffers.borrow();
+
+        // Find the visible instances
+        if depth_pyramid.is_some() && gpu_culling.occlusion_program.is_none() {
+            gpu_culling.occlusion_program =
+                Some(GpuInstanceCulling::culling_program(&self.context, true)?);
+        }
+        let program = if depth_pyramid.is_some() {
+            gpu_culling.occlusion_program.as_ref().unwrap()
+        } else {
+            &gpu_culling.culling_program
+        };
+        let sphere = self.bounding_sphere_local;
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+        program.use_uniform_vec4("boundingSphere", &sphere.center().extend(sphere.radius()))?;
+        program.use_uniform_array("frustumPlanes", &camera.frustum_planes())?;
+        if let Some(depth_pyramid) = depth_pyramid {
+            program.use_uniform_mat4("view", camera.view())?;
+            program.use_uniform_mat4("projection", camera.projection())?;
+            program.use_uniform_float("zNear", &camera.z_near())?;
+            for (i, level) in depth_pyramid.levels().iter().enumerate() {
+                program.use_texture(&format!("depthLevel{}", i), level)?;
+            }
+        }
+        program.use_attribute_vec4_instanced("row1", &input.row1)?;
+        program.use_attribute_vec4_instanced("row2", &input.row2)?;
+        program.use_attribute_vec4_instanced("row3", &input.row3)?;
+        program.transform_feedback_instanced(count as u32, &mut [&mut gpu_culling.visibility])?;
+
+        // Write the visible instances to the front of the buffers in the original order
+        let output = &mut gpu_culling.instance_buffers;
+        let program = &gpu_culling.transform_program;
+        program.use_attribute_instanced("visible", &gpu_culling.visibility)?;
+        program.use_attribute_vec4_instanced("row1", &input.row1)?;
+        program.use_attribute_vec4_instanced("row2", &input.row2)?;
+        program.use_attribute_vec4_instanced("row3", &input.row3)?;
+        program.use_attribute_instanced("instance_id", &input.instance_id)?;
+        gpu_culling.visible_count = program.transform_feedback_instanced(
+            count as u32,
+            &mut [
+                &mut output.row1,
+                &mut output.row2,
+                &mut output.row3,
+                &mut output.instance_id,
+            ],
+        )?;
+
+        let program = &gpu_culling.texture_program;
+        program.use_attribute_instanced("visible", &gpu_culling.visibility)?;
+        program.use_attribute_vec3_instanced("tex_transform_row1", &input.tex_transform1)?;
+        program.use_attribute_vec3_instanced("tex_transform_row2", &input.tex_transform2)?;
+        if self.use_uv_offset_scale {
+            program
+                .use_attribute_vec4_instanced("instance_uv_offset_scale", &input.uv_offset_scale)?;
+        }
+        program.transform_feedback_instanced(
+            count as u32,
+            &mut [
+                &mut output.tex_transform1,
+                &mut output.tex_transform2,
+                &mut output.uv_offset_scale,
+            ],
+        )?;
+        Ok(())
+    }
+
     fn update_aabb(&mut self) {
         let mut aabb = AxisAlignedBoundingBox::EMPTY;
         for instance in self.instances.iter() {
             let mut aabb2 = self.aabb_local.clone();
-            aabb2.transform(&(instance.geometry_transform * self.transformation));
+            aabb2.transform(&(self.transformation * instance.geometry_transform));
             aabb.expand_with_aabb(&aabb2);
         }
         self.aabb = aabb;
@@ -143,74 +592,140 @@ impl<M: Material> InstancedModel<M> {
         &self,
         program: &Program,
         render_states: RenderStates,
-        camera_buffer: &UniformBuffer,
+        camera: &Camera,
         viewport: Viewport,
     ) -> ThreeDResult<()> {
-        program.use_uniform_block("Camera", camera_buffer);
-        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
-
-        program.use_attribute_vec4_instanced("row1", &self.instance_buffer1)?;
-        program.use_attribute_vec4_instanced("row2", &self.instance_buffer2)?;
-        program.use_attribute_vec4_instanced("row3", &self.instance_buffer3)?;
+        let instance_count = self.cull(camera)?;
+        self.drawn_instance_count.set(instance_count);
+        if let Some(ref gpu_culling) = self.gpu_culling {
+            self.draw_instances(
+                program,
+                render_states,
+                camera,
+                viewport,
+                &gpu_culling.borrow().instance_buffers,
+                instance_count,
+            )
+        } else {
+            self.draw_instances(
+                program,
+                render_states,
+                camera,
+                viewport,
+                &self.instance_buffers.borrow(),
+                instance_count,
+            )
+        }
+    }
 
-        if program.requires_attribute("position") {
-            program.use_attribute_vec3("position", &self.mesh.position_buffer)?;
+    fn draw_instances(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        camera: &Camera,
+        viewport: Viewport,
+        instance_buffers: &InstanceBuffers,
+        instance_count: u32,
+    ) -> ThreeDResult<()> {
+        if instance_count == 0 {
+            return Ok(());
+        }
+        let max_vertex_attribs = self.context.capabilities().max_vertex_attribs;
+        if program.attribute_count() > max_vertex_attribs {
+            Err(CoreError::TooManyVertexAttributes(
+                program.attribute_count(),
+                max_vertex_attribs,
+            ))?;
         }
+        program.use_uniform_block("Camera", camera.uniform_buffer());
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+
         if program.requires_attribute("uv_coordinates") {
             program.use_uniform_mat3("textureTransform", &self.texture_transform)?;
-            program.use_attribute_vec3_instanced(
-                "tex_transform_row1",
-                &self.instance_tex_transform1,
-            )?;
-            program.use_attribute_vec3_instanced(
-                "tex_transform_row2",
-                &self.instance_tex_transform2,
-            )?;
-            let uv_buffer = self
-                .mesh
-                .uv_buffer
-                .as_ref()
-                .ok_or(CoreError::MissingMeshBuffer("uv coordinates".to_string()))?;
-            program.use_attribute_vec2("uv_coordinates", uv_buffer)?;
         }
-        if program.requires_attribute("normal") {
-            let normal_buffer = self
-                .mesh
-                .normal_buffer
-                .as_ref()
-                .ok_or(CoreError::MissingMeshBuffer("normal".to_string()))?;
-            program.use_attribute_vec3("normal", normal_buffer)?;
-            if program.requires_attribute("tangent") {
-                let tangent_buffer = self
-                    .mesh
-                    .tangent_buffer
+        let mesh = &self.mesh;
+        let instance_buffer_ids = [
+            instance_buffers.row1.uid(),
+            instance_buffers.row2.uid(),
+            instance_buffers.row3.uid(),
+            instance_buffers.tex_transform1.uid(),
+            instance_buffers.tex_transform2.uid(),
+            instance_buffers.uv_offset_scale.uid(),
+            instance_buffers.instance_id.uid(),
+        ];
+        mesh.use_attributes(program, &instance_buffer_ids, || {
+            program.use_attribute_vec4_instanced("row1", &instance_buffers.row1)?;
+            program.use_attribute_vec4_instanced("row2", &instance_buffers.row2)?;
+            program.use_attribute_vec4_instanced("row3", &instance_buffers.row3)?;
+            if program.requires_attribute("instance_id") {
+                program.use_attribute_instanced("instance_id", &instance_buffers.instance_id)?;
+            }
+
+            if program.requires_attribute("position") {
+                program.use_attribute_vec3("position", &mesh.position_buffer)?;
+            }
+            if program.requires_attribute("uv_coordinates") {
+                program.use_attribute_vec3_instanced(
+                    "tex_transform_row1",
+                    &instance_buffers.tex_transform1,
+                )?;
+                program.use_attribute_vec3_instanced(
+                    "tex_transform_row2",
+                    &instance_buffers.tex_transform2,
+                )?;
+                if program.requires_attribute("instance_uv_offset_scale") {
+                    program.use_attribute_vec4_instanced(
+                        "instance_uv_offset_scale",
+                        &instance_buffers.uv_offset_scale,
+                    )?;
+                }
+                let uv_buffer = mesh
+                    .uv_buffer
                     .as_ref()
-                    .ok_or(CoreError::MissingMeshBuffer("tangent".to_string()))?;
-                program.use_attribute_vec4("tangent", tangent_buffer)?;
+                    .ok_or(CoreError::MissingMeshBuffer("uv coordinates".to_string()))?;
+                program.use_attribute_vec2("uv_coordinates", uv_buffer)?;
             }
-        }
-        if program.requires_attribute("color") {
-            let color_buffer = self
-                .mesh
-                .color_buffer
-                .as_ref()
-                .ok_or(CoreError::MissingMeshBuffer("color".to_string()))?;
-            program.use_attribute_vec4("color", color_buffer)?;
-        }
+            if program.requires_attribute("uv2_coordinates") {
+                let uv2_buffer = mesh
+                    .uv2_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer(
+                        "second uv coordinates".to_string(),
+                    ))?;
+                program.use_attribute_vec2("uv2_coordinates", uv2_buffer)?;
+            }
+            if program.requires_attribute("normal") {
+                let normal_buffer = mesh
+                    .normal_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer("normal".to_string()))?;
+                program.use_attribute_vec3("normal", normal_buffer)?;
+                if program.requires_attribute("tangent") {
+                    let tangent_buffer = mesh
+                        .tangent_buffer
+                        .as_ref()
+                        .ok_or(CoreError::MissingMeshBuffer("tangent".to_string()))?;
+                    program.use_attribute_vec4("tangent", tangent_buffer)?;
+                }
+            }
+            if program.requires_attribute("color") {
+                let color_buffer = mesh
+                    .color_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer("color".to_string()))?;
+                program.use_attribute_vec4("color", color_buffer)?;
+            }
+            Ok(())
+        })?;
 
         if let Some(ref index_buffer) = self.mesh.index_buffer {
-            program.draw_elements_instanced(
-                render_states,
-                viewport,
-                index_buffer,
-                self.instances.len() as u32,
-            );
+            program.draw_elements_instanced(render_states, viewport, index_buffer, instance_count);
         } else {
             program.draw_arrays_instanced(
                 render_states,
                 viewport,
                 self.mesh.position_buffer.count() as u32 / 3,
-                self.instances.len() as u32,
+                instance_count,
             );
         }
         Ok(())
@@ -222,6 +737,59 @@ impl<M: Material> InstancedModel<M> {
             Model::<M>::vertex_shader_source(fragment_shader_source)?
         ))
     }
+
+    ///
+    /// Returns the vertex and fragment shader source for the given fragment shader source of a material,
+    /// where the uv offset and scale of each instance is applied if at least one of the instances has one.
+    ///
+    // Renders the instances with the given material and returns the id of the program used, which is cached by Object::render for sorting the objects by material
+    fn render_with_program(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<u64> {
+        let (vertex_shader_source, fragment_shader_source) = self.shader_sources(
+            material.fragment_shader_source(self.mesh.color_buffer.is_some(), lights),
+        )?;
+        let mut program_id = 0;
+        self.context
+            .program(&vertex_shader_source, &fragment_shader_source, |program| {
+                program_id = program.uid();
+                material.use_uniforms(program, camera, lights)?;
+                for render_states in material
+                    .transparency_mode()
+                    .passes(material.render_states(), material.is_transparent())
+                {
+                    self.draw(program, render_states, camera, camera.viewport())?;
+                }
+                Ok(())
+            })?;
+        Ok(program_id)
+    }
+
+    fn shader_sources(&self, fragment_shader_source: String) -> ThreeDResult<(String, String)> {
+        let vertex_shader_source = format!(
+            "{}{}",
+            self.mesh.color_space_define(),
+            Self::vertex_shader_source(&fragment_shader_source)?
+        );
+        Ok(if self.use_uv_offset_scale {
+            let vertex_shader_source = format!(
+                "#define USE_INSTANCE_UV_OFFSET_SCALE\n{}",
+                vertex_shader_source
+            );
+            (
+                vertex_shader_source,
+                format!(
+                    "#define USE_INSTANCE_UV_OFFSET_SCALE\n{}",
+                    fragment_shader_source
+                ),
+            )
+        } else {
+            (vertex_shader_source, fragment_shader_source)
+        })
+    }
 }
 
 impl<M: Material> Geometry for InstancedModel<M> {
@@ -232,12 +800,18 @@ impl<M: Material> Geometry for InstancedModel<M> {
     fn transformation(&self) -> Mat4 {
         self.transformation
     }
+
+    fn change_count(&self) -> u64 {
+        self.change_count
+    }
 }
 
 impl<M: Material> GeometryMut for InstancedModel<M> {
     fn set_transformation(&mut self, transformation: Mat4) {
         self.transformation = transformation;
         self.update_aabb();
+        self.update_culling();
+        self.change_count += 1;
     }
 }
 
@@ -248,21 +822,8 @@ impl<M: Material> Shadable for InstancedModel<M> {
         camera: &Camera,
         lights: &Lights,
     ) -> ThreeDResult<()> {
-        let fragment_shader_source =
-            material.fragment_shader_source(self.mesh.color_buffer.is_some(), lights);
-        self.context.program(
-            &Self::vertex_shader_source(&fragment_shader_source)?,
-            &fragment_shader_source,
-            |program| {
-                material.use_uniforms(program, camera, lights)?;
-                self.draw(
-                    program,
-                    material.render_states(),
-                    camera.uniform_buffer(),
-                    camera.viewport(),
-                )
-            },
-        )
+        self.render_with_program(material, camera, lights)?;
+        Ok(())
     }
 
     fn render_forward(
@@ -281,38 +842,67 @@ impl<M: Material> Shadable for InstancedModel<M> {
         viewport: Viewport,
     ) -> ThreeDResult<()> {
         let lights = Lights::default();
-        let fragment_shader_source =
-            material.fragment_shader_source(self.mesh.color_buffer.is_some(), &lights);
-        self.context.program(
-            &Self::vertex_shader_source(&fragment_shader_source)?,
-            &fragment_shader_source,
-            |program| {
+        let (vertex_shader_source, fragment_shader_source) = self.shader_sources(
+            material.fragment_shader_source(self.mesh.color_buffer.is_some(), &lights),
+        )?;
+        self.context
+            .program(&vertex_shader_source, &fragment_shader_source, |program| {
                 material.use_uniforms(program, camera, &lights)?;
-                self.draw(
-                    program,
-                    material.render_states(),
-                    camera.uniform_buffer(),
-                    viewport,
-                )
-            },
-        )
+                self.draw(program, material.render_states(), camera, viewport)
+            })
     }
 }
 
 impl<M: Material> Object for InstancedModel<M> {
     fn render(&self, camera: &Camera, lights: &Lights) -> ThreeDResult<()> {
-        self.render_with_material(&self.material, camera, lights)
+        self.program_id
+            .set(self.render_with_program(&self.material, camera, lights)?);
+        if !self.selection.is_empty() {
+            let (vertex_shader_source, fragment_shader_source) = self.shader_sources(
+                self.highlight_material
+                    .fragment_shader_source(self.mesh.color_buffer.is_some(), lights),
+            )?;
+            self.context
+                .program(&vertex_shader_source, &fragment_shader_source, |program| {
+                    self.highlight_material
+                        .use_uniforms(program, camera, lights)?;
+                    self.draw_instances(
+                        program,
+                        self.highlight_material.render_states(),
+                        camera,
+                        camera.viewport(),
+                        &self.selected_instance_buffers,
+                        self.selection.len() as u32,
+                    )
+                })?;
+        }
+        Ok(())
     }
 
     fn is_transparent(&self) -> bool {
         self.material.is_transparent()
     }
+
+    fn material_id(&self) -> u64 {
+        self.program_id.get()
+    }
+
+    fn render_order(&self) -> i32 {
+        self.render_order
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
 pub struct ModelInstance {
     pub geometry_transform: Mat4,
     pub texture_transform: Mat3,
+    ///
+    /// The region of the textures used by this instance, where the offset is in the x and y components and the scale in the z and w components,
+    /// for example to let each instance use a different part of a texture atlas.
+    /// The uv coordinates are mapped to `uv * scale + offset` after the texture transformations of the material are applied.
+    /// Only [ColorMaterial] and [PhysicalMaterial] use the region, which is the whole texture if `None`.
+    ///
+    pub uv_offset_scale: Option<Vec4>,
 }
 
 impl Default for ModelInstance {
@@ -320,6 +910,480 @@ impl Default for ModelInstance {
         Self {
             geometry_transform: Mat4::identity(),
             texture_transform: Mat3::identity(),
+            uv_offset_scale: None,
+        }
+    }
+}
+
+///
+/// A set of instance indices, for example the selected instances of an [InstancedModel], see [InstancedModel::set_selection].
+/// The indices are iterated in increasing order.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectionSet {
+    indices: std::collections::BTreeSet<usize>,
+}
+
+impl SelectionSet {
+    ///
+    /// Creates an empty set.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns whether or not the given index is in the set.
+    ///
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.contains(&index)
+    }
+
+    ///
+    /// Adds the given index to the set.
+    ///
+    pub fn insert(&mut self, index: usize) {
+        self.indices.insert(index);
+    }
+
+    ///
+    /// Removes the given index from the set.
+    ///
+    pub fn remove(&mut self, index: usize) {
+        self.indices.remove(&index);
+    }
+
+    ///
+    /// Removes the given index from the set if it is in the set and adds it otherwise.
+    ///
+    pub fn toggle(&mut self, index: usize) {
+        if !self.indices.remove(&index) {
+            self.indices.insert(index);
+        }
+    }
+
+    ///
+    /// Removes all indices from the set.
+    ///
+    pub fn clear(&mut self) {
+        self.indices.clear();
+    }
+
+    ///
+    /// Returns the number of indices in the set.
+    ///
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    ///
+    /// Returns whether or not the set is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    ///
+    /// Returns an iterator over the indices in increasing order.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.indices.iter().cloned()
+    }
+}
+
+impl std::iter::FromIterator<usize> for SelectionSet {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        Self {
+            indices: iter.into_iter().collect(),
+        }
+    }
+}
+
+///
+/// The five instance buffers containing the transformations of the instances, the buffer containing the uv offset and scale of the instances
+/// and the buffer containing the index of each instance.
+///
+struct InstanceBuffers {
+    row1: InstanceBuffer,
+    row2: InstanceBuffer,
+    row3: InstanceBuffer,
+    tex_transform1: InstanceBuffer,
+    tex_transform2: InstanceBuffer,
+    uv_offset_scale: InstanceBuffer,
+    instance_id: InstanceBuffer,
+}
+
+impl InstanceBuffers {
+    fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            row1: InstanceBuffer::new(context)?,
+            row2: InstanceBuffer::new(context)?,
+            row3: InstanceBuffer::new(context)?,
+            tex_transform1: InstanceBuffer::new(context)?,
+            tex_transform2: InstanceBuffer::new(context)?,
+            uv_offset_scale: InstanceBuffer::new(context)?,
+            instance_id: InstanceBuffer::new(context)?,
+        })
+    }
+
+    ///
+    /// Allocates room for the given number of instances in all the buffers without filling them with data.
+    ///
+    fn allocate(&mut self, count: usize) {
+        self.row1.allocate::<f32>(4 * count);
+        self.row2.allocate::<f32>(4 * count);
+        self.row3.allocate::<f32>(4 * count);
+        self.tex_transform1.allocate::<f32>(3 * count);
+        self.tex_transform2.allocate::<f32>(3 * count);
+        self.uv_offset_scale.allocate::<f32>(4 * count);
+        self.instance_id.allocate::<f32>(count);
+    }
+
+    ///
+    /// Fills the buffers with the instances with the given indices in the given order.
+    /// The uv offset and scale buffer is only filled if it is used, in which case the instances without one use the whole texture.
+    ///
+    fn fill(
+        &mut self,
+        instances: &[ModelInstance],
+        indices: impl Iterator<Item = usize>,
+        use_uv_offset_scale: bool,
+    ) {
+        let mut row1 = Vec::new();
+        let mut row2 = Vec::new();
+        let mut row3 = Vec::new();
+        let mut instance_tex_transform1 = Vec::new();
+        let mut instance_tex_transform2 = Vec::new();
+        let mut uv_offset_scale = Vec::new();
+        let mut instance_id = Vec::new();
+        for index in indices {
+            let instance = &instances[index];
+            instance_id.push(index as f32);
+
+            row1.push(instance.geometry_transform.x.x);
+            row1.push(instance.geometry_transform.y.x);
+            row1.push(instance.geometry_transform.z.x);
+            row1.push(instance.geometry_transform.w.x);
+
+            row2.push(instance.geometry_transform.x.y);
+            row2.push(instance.geometry_transform.y.y);
+            row2.push(instance.geometry_transform.z.y);
+            row2.push(instance.geometry_transform.w.y);
+
+            row3.push(instance.geometry_transform.x.z);
+            row3.push(instance.geometry_transform.y.z);
+            row3.push(instance.geometry_transform.z.z);
+            row3.push(instance.geometry_transform.w.z);
+
+            instance_tex_transform1.push(instance.texture_transform.x.x);
+            instance_tex_transform1.push(instance.texture_transform.y.x);
+            instance_tex_transform1.push(instance.texture_transform.z.x);
+
+            instance_tex_transform2.push(instance.texture_transform.x.y);
+            instance_tex_transform2.push(instance.texture_transform.y.y);
+            instance_tex_transform2.push(instance.texture_transform.z.y);
+
+            if use_uv_offset_scale {
+                let v = instance.uv_offset_scale.unwrap_or(vec4(0.0, 0.0, 1.0, 1.0));
+                uv_offset_scale.extend_from_slice(&[v.x, v.y, v.z, v.w]);
+            }
+        }
+        self.row1.fill_with_dynamic(&row1);
+        self.row2.fill_with_dynamic(&row2);
+        self.row3.fill_with_dynamic(&row3);
+        self.tex_transform1
+            .fill_with_dynamic(&instance_tex_transform1);
+        self.tex_transform2
+            .fill_with_dynamic(&instance_tex_transform2);
+        if use_uv_offset_scale {
+            self.uv_offset_scale.fill_with_dynamic(&uv_offset_scale);
+        }
+        self.instance_id.fill_with_dynamic(&instance_id);
+    }
+}
+
+///
+/// The approximate number of instances in each cell of the spatial grid used for culling.
+///
+const INSTANCES_PER_CELL: f32 = 64.0;
+
+///
+/// A spatial grid of the world space bounding spheres of the instances used for culling the instances outside the camera frustum.
+///
+struct InstanceCulling {
+    /// The bounding sphere of each cell and the range of the instances in the cell in the sorted arrays below.
+    cells: Vec<(BoundingSphere, std::ops::Range<usize>)>,
+    /// The instance indices sorted by cell.
+    order: Vec<usize>,
+    /// The bounding spheres of the instances sorted by cell.
+    spheres: Vec<BoundingSphere>,
+    /// The view projection matrix of the camera used for the current content of the instance buffers.
+    view_projection: Option<Mat4>,
+    visible_count: u32,
+}
+
+impl InstanceCulling {
+    fn new(
+        instances: &[ModelInstance],
+        bounding_sphere_local: BoundingSphere,
+        transformation: &Mat4,
+    ) -> Self {
+        let spheres = instances
+            .iter()
+            .map(|instance| {
+                let mut sphere = bounding_sphere_local;
+                sphere.transform(&(transformation * instance.geometry_transform));
+                sphere
+            })
+            .collect::<Vec<_>>();
+
+        // Divide the bounding box of the centers into a grid with approximately the same number of cells along each axis
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for sphere in spheres.iter() {
+            let center = sphere.center();
+            aabb.expand(&[center.x, center.y, center.z]);
+        }
+        let cells_per_axis =
+            ((spheres.len() as f32 / INSTANCES_PER_CELL).cbrt().ceil() as usize).max(1);
+        let cell_index = |sphere: &BoundingSphere| {
+            let relative = sphere.center() - aabb.min();
+            let size = aabb.size();
+            let coordinate = |value: f32, size: f32| {
+                if size > 0.0 {
+                    ((value / size * cells_per_axis as f32) as usize).min(cells_per_axis - 1)
+                } else {
+                    0
+                }
+            };
+            coordinate(relative.x, size.x)
+                + cells_per_axis
+                    * (coordinate(relative.y, size.y)
+                        + cells_per_axis * coordinate(relative.z, size.z))
+        };
+        let mut order = (0..spheres.len()).collect::<Vec<_>>();
+        order.sort_by_key(|i| cell_index(&spheres[*i]));
+        let spheres = order.iter().map(|i| spheres[*i]).collect::<Vec<_>>();
+
+        let mut cells = Vec::new();
+        let mut start = 0;
+        while start < spheres.len() {
+            let index = cell_index(&spheres[start]);
+            let mut end = start;
+            let mut cell_aabb = AxisAlignedBoundingBox::EMPTY;
+            while end < spheres.len() && cell_index(&spheres[end]) == index {
+                let center = spheres[end].center();
+                let radius = spheres[end].radius();
+                cell_aabb.expand(&[
+                    center.x - radius,
+                    center.y - radius,
+                    center.z - radius,
+                    center.x + radius,
+                    center.y + radius,
+                    center.z + radius,
+                ]);
+                end += 1;
+            }
+            cells.push((BoundingSphere::new_with_aabb(&cell_aabb), start..end));
+            start = end;
+        }
+        Self {
+            cells,
+            order,
+            spheres,
+            view_projection: None,
+            visible_count: 0,
+        }
+    }
+
+    ///
+    /// Returns the indices of the instances inside the frustum of the given camera.
+    ///
+    fn visible_instances(&self, camera: &Camera) -> Vec<usize> {
+        let mut visible = Vec::new();
+        for (cell_sphere, range) in self.cells.iter() {
+            if camera.sphere_in_frustum(cell_sphere) {
+                for i in range.clone() {
+                    if camera.sphere_in_frustum(&self.spheres[i]) {
+                        visible.push(self.order[i]);
+                    }
+                }
+            }
         }
+        visible
+    }
+}
+
+///
+/// The shader programs and buffers used for culling the instances on the GPU using transform feedback.
+/// First the visibility of each instance is written to a buffer, and then the visible instances are written to the front of the instance buffers by a geometry shader,
+/// which happens in two passes since only four separate transform feedback outputs are guaranteed to be supported.
+///
+struct GpuInstanceCulling {
+    culling_program: Program,
+    occlusion_program: Option<Program>,
+    transform_program: Program,
+    texture_program: Program,
+    visibility: InstanceBuffer,
+    instance_buffers: InstanceBuffers,
+    capacity: usize,
+    /// The view projection matrix of the camera used for the current content of the instance buffers.
+    view_projection: Option<Mat4>,
+    visible_count: u32,
+}
+
+impl GpuInstanceCulling {
+    fn new(context: &Context) -> ThreeDResult<Self> {
+        let compaction_program = |texture_output: bool, outputs: &[&str]| {
+            let define = if texture_output {
+                "#define TEXTURE_OUTPUT\n"
+            } else {
+                ""
+            };
+            Program::from_source_with_transform_feedback(
+                context,
+                &format!(
+                    "{}{}",
+                    define,
+                    include_str!("shaders/instance_compaction.vert")
+                ),
+                Some(&format!(
+                    "{}{}",
+                    define,
+                    include_str!("shaders/instance_compaction.geom")
+                )),
+                outputs,
+            )
+        };
+        Ok(Self {
+            culling_program: Self::culling_program(context, false)?,
+            occlusion_program: None,
+            transform_program: compaction_program(
+                false,
+                &["outRow1", "outRow2", "outRow3", "outInstanceId"],
+            )?,
+            texture_program: compaction_program(
+                true,
+                &["outTexTransform1", "outTexTransform2", "outUvOffsetScale"],
+            )?,
+            visibility: InstanceBuffer::new(context)?,
+            instance_buffers: InstanceBuffers::new(context)?,
+            capacity: 0,
+            view_projection: None,
+            visible_count: 0,
+        })
+    }
+
+    fn culling_program(context: &Context, occlusion: bool) -> ThreeDResult<Program> {
+        Program::from_source_with_transform_feedback(
+            context,
+            &format!(
+                "{}{}",
+                if occlusion {
+                    "#define USE_OCCLUSION\n"
+                } else {
+                    ""
+                },
+                include_str!("shaders/instance_culling.vert")
+            ),
+            None,
+            &["visible"],
+        )
+    }
+}
+
+#[cfg(all(test, feature = "glutin-window", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::renderer::test_utils;
+
+    fn render(context: &Context, object: &dyn Object, lights: &Lights) -> Vec<u8> {
+        test_utils::render(context, 64, vec3(1.0, 2.0, 5.0), &[object], lights).unwrap()
+    }
+
+    // A single instance of an instanced model is rendered exactly like a model with the transformation of the instanced model applied after the instance transformation
+    #[test]
+    #[ignore = "requires a graphics context"]
+    fn single_instance_renders_like_a_model() {
+        let context = Context::new().unwrap();
+        let lights = Lights {
+            ambient: Some(AmbientLight {
+                intensity: 0.2,
+                ..Default::default()
+            }),
+            directional: vec![DirectionalLight::new(
+                &context,
+                1.0,
+                Color::WHITE,
+                &vec3(-1.0, -1.0, -0.5),
+            )
+            .unwrap()],
+            ..Default::default()
+        };
+        let material = PhysicalMaterial {
+            albedo: Color::new_opaque(200, 100, 50),
+            roughness: 0.4,
+            metallic: 0.2,
+            ..Default::default()
+        };
+        // The non-uniform scale makes the normals depend on using the correct normal matrix
+        let parent =
+            Mat4::from_translation(vec3(0.3, -0.2, 0.0)) * Mat4::from_angle_y(degrees(30.0));
+        let instance = ModelInstance {
+            geometry_transform: Mat4::from_angle_x(degrees(20.0))
+                * Mat4::from_nonuniform_scale(1.0, 0.5, 0.8),
+            ..Default::default()
+        };
+        let cpu_mesh = CPUMesh::sphere(16);
+
+        let mut model = Model::new_with_material(&context, &cpu_mesh, material.clone()).unwrap();
+        model.set_transformation(parent * instance.geometry_transform);
+        let mut instanced_model =
+            InstancedModel::new_with_material(&context, &[instance], &cpu_mesh, material).unwrap();
+        instanced_model.set_transformation(parent);
+
+        let model_aabb = model.aabb();
+        let instanced_aabb = instanced_model.aabb();
+        assert!((model_aabb.min() - instanced_aabb.min()).magnitude() < 0.0001);
+        assert!((model_aabb.max() - instanced_aabb.max()).magnitude() < 0.0001);
+        assert_eq!(model.is_transparent(), instanced_model.is_transparent());
+
+        let expected = render(&context, &model, &lights);
+        // The model covers part of the image
+        assert!(expected.chunks(4).any(|p| p[0] > 0));
+        assert!(expected.chunks(4).any(|p| p[0] == 0));
+        let pixels = render(&context, &instanced_model, &lights);
+        assert!(expected == pixels);
+    }
+
+    #[test]
+    #[ignore = "requires a graphics context"]
+    fn transparency_follows_the_material() {
+        let context = Context::new().unwrap();
+        let cpu_mesh = CPUMesh::cube();
+        let instances = [ModelInstance::default()];
+        let opaque = InstancedModel::new_with_material(
+            &context,
+            &instances,
+            &cpu_mesh,
+            ColorMaterial {
+                color: Color::new(255, 0, 0, 255),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(!opaque.is_transparent());
+        let mut transparent = InstancedModel::new_with_material(
+            &context,
+            &instances,
+            &cpu_mesh,
+            ColorMaterial {
+                color: Color::new(255, 0, 0, 100),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(transparent.is_transparent());
+        transparent.material_mut().color.a = 255;
+        assert!(!transparent.is_transparent());
     }
 }
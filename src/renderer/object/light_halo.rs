@@ -0,0 +1,165 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A soft glowing halo around the position of a light source, for example a [PointLight] or a [SpotLight],
+/// rendered as a radial sprite which always faces the camera.
+/// Both the size and the brightness of the halo scales with the intensity,
+/// so rendering it in high dynamic range (see [HdrPipeline]) makes bright lights bloom.
+///
+/// The halo is transparent and should be rendered after all opaque objects.
+/// If the halo is rendered using [LightHalo::render], it is clipped by the geometry in front of it,
+/// if it is rendered using [LightHalo::render_with_depth], it instead fades out as the light source is occluded.
+///
+pub struct LightHalo {
+    context: Context,
+    /// The position of the center of the halo, usually the position of a light source.
+    pub position: Vec3,
+    /// The color of the halo, usually the color of a light source.
+    pub color: Color,
+    /// The intensity of the halo, usually the intensity of a light source.
+    pub intensity: f32,
+    /// The radius of the halo in world space when the intensity is one.
+    pub size: f32,
+    corner_buffer: VertexBuffer,
+}
+
+impl LightHalo {
+    ///
+    /// Constructs a new white halo at the origin with the given radius in world space.
+    ///
+    pub fn new(context: &Context, size: f32) -> ThreeDResult<Self> {
+        let corners = vec![
+            -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, 1.0, 1.0, -1.0, 1.0, -1.0, -1.0,
+        ];
+        Ok(Self {
+            context: context.clone(),
+            position: vec3(0.0, 0.0, 0.0),
+            color: Color::WHITE,
+            intensity: 1.0,
+            size,
+            corner_buffer: VertexBuffer::new_with_static(context, &corners)?,
+        })
+    }
+
+    ///
+    /// Sets the position, color and intensity of the halo to that of the given point light.
+    /// Should be called each time the light changes.
+    ///
+    pub fn set_from_point_light(&mut self, light: &PointLight) {
+        self.position = light.position();
+        self.color = light.color();
+        self.intensity = if light.is_enabled() {
+            light.intensity()
+        } else {
+            0.0
+        };
+    }
+
+    ///
+    /// Sets the position, color and intensity of the halo to that of the given spot light.
+    /// Should be called each time the light changes.
+    ///
+    pub fn set_from_spot_light(&mut self, light: &SpotLight) {
+        self.position = light.position();
+        self.color = light.color();
+        self.intensity = if light.is_enabled() {
+            light.intensity()
+        } else {
+            0.0
+        };
+    }
+
+    ///
+    /// Returns whether or not the halo is transparent, which is always the case since it is added to the color already rendered.
+    ///
+    pub fn is_transparent(&self) -> bool {
+        true
+    }
+
+    ///
+    /// Renders the halo which is clipped by the geometry already rendered in front of it.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn render(&self, camera: &Camera) -> ThreeDResult<()> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::ADD,
+            ..Default::default()
+        };
+        self.context.program(
+            include_str!("shaders/light_halo.vert"),
+            &format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/light_halo.frag")
+            ),
+            |program| {
+                self.use_uniforms(program, camera)?;
+                program.draw_arrays(render_states, camera.viewport(), 6);
+                Ok(())
+            },
+        )
+    }
+
+    ///
+    /// Renders the halo which fades out when the light source is occluded by the geometry in the given depth texture,
+    /// instead of being clipped by the geometry in front of it.
+    /// The depth texture must contain the depth of the scene rendered with the same camera, for example [DeferredPipeline::geometry_pass_depth_texture].
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn render_with_depth(
+        &self,
+        camera: &Camera,
+        depth_texture: &DepthTargetTexture2D,
+    ) -> ThreeDResult<()> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: Blend::ADD,
+            ..Default::default()
+        };
+        self.context.program(
+            &format!(
+                "#define USE_DEPTH_FADE\n{}",
+                include_str!("shaders/light_halo.vert")
+            ),
+            &format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/light_halo.frag")
+            ),
+            |program| {
+                program.use_texture("depthMap", depth_texture)?;
+                // The depth of the light source in the depth texture, which is reversed in the reversed depth mode
+                let center = camera.projection() * camera.view() * self.position.extend(1.0);
+                let center_depth = 0.5 * center.z / center.w + 0.5;
+                program.use_uniform_float(
+                    "centerDepth",
+                    &if camera.far_plane_depth() > 0.5 {
+                        center_depth
+                    } else {
+                        1.0 - center_depth
+                    },
+                )?;
+                program.use_uniform_float("farPlaneDepth", &camera.far_plane_depth())?;
+                self.use_uniforms(program, camera)?;
+                program.draw_arrays(render_states, camera.viewport(), 6);
+                Ok(())
+            },
+        )
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera) -> ThreeDResult<()> {
+        let intensity = self.intensity.max(0.0);
+        program.use_uniform_block("Camera", camera.uniform_buffer());
+        program.use_uniform_vec3("center", &self.position)?;
+        program.use_uniform_float("radius", &(self.size * intensity))?;
+        program.use_uniform_vec3("color", &self.color.to_vec3())?;
+        program.use_uniform_float("intensity", &intensity)?;
+        program.use_attribute_vec2("corner", &self.corner_buffer)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,10 @@
+use super::OverlayMaterial;
 use crate::renderer::*;
 
+///
+/// A line with a width in 2D pixel coordinates which can be rendered using any material, for example on top of a 3D scene using [render_overlay].
+/// The pixel coordinates are physical pixels relative to the [anchor](Line::set_anchor) of the viewport, with the y-coordinate increasing downwards.
+///
 #[derive(Clone)]
 pub struct Line<M: Material> {
     context: Context,
@@ -7,6 +12,7 @@ pub struct Line<M: Material> {
     pixel0: Vec2,
     pixel1: Vec2,
     width: f32,
+    anchor: Anchor,
 }
 
 impl<M: Material> Line<M> {
@@ -22,6 +28,7 @@ impl<M: Material> Line<M> {
         let mut line = Self {
             context: context.clone(),
             model: Model::new_with_material(context, &mesh, material)?,
+            anchor: Anchor::TopLeft,
             pixel0,
             pixel1,
             width,
@@ -54,6 +61,21 @@ impl<M: Material> Line<M> {
         self.update();
     }
 
+    ///
+    /// Sets the point of the viewport which the pixel coordinates of this line are relative to, which defaults to [Anchor::TopLeft].
+    /// For example, a line anchored to [Anchor::TopRight] with negative x-coordinates and positive y-coordinates stays in the top right corner when the viewport is resized.
+    ///
+    pub fn set_anchor(&mut self, anchor: Anchor) {
+        self.anchor = anchor;
+    }
+
+    ///
+    /// Returns the point of the viewport which the pixel coordinates of this line are relative to, see [Line::set_anchor].
+    ///
+    pub fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+
     fn update(&mut self) {
         let dx = self.pixel1.x - self.pixel0.x;
         let dy = self.pixel1.y - self.pixel0.y;
@@ -75,7 +97,7 @@ impl<M: Material> Shadable2D for Line<M> {
         material: &dyn Material,
         viewport: Viewport,
     ) -> ThreeDResult<()> {
-        self.context.camera2d(viewport, |camera2d| {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
             self.model
                 .render_with_material(material, camera2d, &Lights::default())
         })
@@ -88,11 +110,21 @@ impl<M: Material> Shadable2D for Line<M> {
 
 impl<M: Material> Object2D for Line<M> {
     fn render(&self, viewport: Viewport) -> ThreeDResult<()> {
-        self.context.camera2d(viewport, |camera2d| {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
             self.model.render(camera2d, &Lights::default())
         })
     }
 
+    fn render_as_overlay(&self, viewport: Viewport) -> ThreeDResult<()> {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
+            self.model.render_with_material(
+                &OverlayMaterial(&self.model.material),
+                camera2d,
+                &Lights::default(),
+            )
+        })
+    }
+
     fn is_transparent(&self) -> bool {
         self.model.is_transparent()
     }
@@ -0,0 +1,690 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::rc::Rc;
+
+///
+/// The appearance of a [DistanceMeasurement], [AngleMeasurement] or [AreaMeasurement].
+/// The sizes are given in pixels, so the measurements have the same size on the screen independent of the distance to the camera.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MeasurementStyle {
+    /// The color of the lines and the text of the label.
+    pub color: Color,
+    /// The width of the lines in pixels.
+    pub line_width: f32,
+    /// The size in pixels of each pixel of the built-in font of the label, which is 7 pixels high.
+    pub label_scale: f32,
+    /// The distance in pixels between the measured geometry and the label, which are connected by a leader line.
+    pub label_offset: f32,
+    /// The distance in pixels which the lines are moved towards the camera to avoid z-fighting with the measured surface.
+    pub depth_offset: f32,
+}
+
+impl Default for MeasurementStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::new_opaque(255, 200, 0),
+            line_width: 2.0,
+            label_scale: 2.0,
+            label_offset: 30.0,
+            depth_offset: 4.0,
+        }
+    }
+}
+
+///
+/// The format of the value in the label of a [DistanceMeasurement], [AngleMeasurement] or [AreaMeasurement].
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementFormat {
+    /// The number of units for each scene unit, for example `1000.0` if the scene is in meters and the measurements should be in millimeters.
+    /// Not used by the [AngleMeasurement], which is always in degrees.
+    pub unit_scale: f32,
+    /// The name of the unit written after the value, for example `"mm"`.
+    /// The built-in font of the label only contains digits, letters and a few symbols, other characters are written as spaces.
+    /// Not used by the [AngleMeasurement], which is always in degrees.
+    pub unit: String,
+    /// The number of decimals of the value.
+    pub precision: usize,
+}
+
+impl Default for MeasurementFormat {
+    fn default() -> Self {
+        Self {
+            unit_scale: 1.0,
+            unit: String::new(),
+            precision: 2,
+        }
+    }
+}
+
+///
+/// A measurement of the distance between two points in world space, for example found using [pick],
+/// shown as a dimension line between the points and a label with the distance.
+///
+pub struct DistanceMeasurement {
+    /// The appearance of the measurement.
+    pub style: MeasurementStyle,
+    points: [Vec3; 2],
+    format: MeasurementFormat,
+    visuals: MeasurementVisuals,
+}
+
+impl DistanceMeasurement {
+    ///
+    /// Constructs a new measurement of the distance between the two given points.
+    ///
+    pub fn new(context: &Context, point0: Vec3, point1: Vec3) -> ThreeDResult<Self> {
+        let mut measurement = Self {
+            style: MeasurementStyle::default(),
+            points: [point0, point1],
+            format: MeasurementFormat::default(),
+            visuals: MeasurementVisuals::new(context)?,
+        };
+        measurement.update_label()?;
+        Ok(measurement)
+    }
+
+    ///
+    /// Returns the two measured points.
+    ///
+    pub fn points(&self) -> [Vec3; 2] {
+        self.points
+    }
+
+    ///
+    /// Sets the two measured points.
+    ///
+    pub fn set_points(&mut self, point0: Vec3, point1: Vec3) -> ThreeDResult<()> {
+        self.points = [point0, point1];
+        self.update_label()
+    }
+
+    ///
+    /// Returns the format of the value in the label.
+    ///
+    pub fn format(&self) -> &MeasurementFormat {
+        &self.format
+    }
+
+    ///
+    /// Sets the format of the value in the label.
+    ///
+    pub fn set_format(&mut self, format: MeasurementFormat) -> ThreeDResult<()> {
+        self.format = format;
+        self.update_label()
+    }
+
+    ///
+    /// Returns the distance between the two points multiplied by the [MeasurementFormat::unit_scale].
+    ///
+    pub fn value(&self) -> f32 {
+        self.points[0].distance(self.points[1]) * self.format.unit_scale
+    }
+
+    ///
+    /// Returns the text of the label.
+    ///
+    pub fn label(&self) -> String {
+        format_value(self.value(), &self.format, "")
+    }
+
+    fn update_label(&mut self) -> ThreeDResult<()> {
+        let label = self.label();
+        self.visuals.set_label(&label)
+    }
+
+    fn lines(&self, camera: &Camera) -> (Vec<(Vec3, Vec3)>, Vec3) {
+        let [p0, p1] = self.points;
+        let middle = 0.5 * (p0 + p1);
+        let anchor =
+            middle + screen_up(camera) * self.style.label_offset * pixel_size(camera, middle);
+        let mut lines = vec![(p0, p1), (middle, anchor)];
+        // End ticks perpendicular to the dimension line on the screen
+        let tick = (p1 - p0).cross(direction_to_camera(camera, middle));
+        if tick.magnitude2() > 0.0 {
+            let tick = tick.normalize();
+            for p in [p0, p1].iter() {
+                let half_length = 5.0 * pixel_size(camera, *p);
+                lines.push((p - tick * half_length, p + tick * half_length));
+            }
+        }
+        (lines, anchor)
+    }
+}
+
+///
+/// A measurement of the angle at a vertex between the lines to two other points in world space, for example found using [pick],
+/// shown as the two lines, an arc between them and a label with the angle in degrees.
+///
+pub struct AngleMeasurement {
+    /// The appearance of the measurement.
+    pub style: MeasurementStyle,
+    points: [Vec3; 3],
+    format: MeasurementFormat,
+    visuals: MeasurementVisuals,
+}
+
+impl AngleMeasurement {
+    ///
+    /// Constructs a new measurement of the angle at the given vertex between the lines to the two other given points.
+    ///
+    pub fn new(context: &Context, point0: Vec3, vertex: Vec3, point1: Vec3) -> ThreeDResult<Self> {
+        let mut measurement = Self {
+            style: MeasurementStyle::default(),
+            points: [point0, vertex, point1],
+            format: MeasurementFormat {
+                precision: 1,
+                ..Default::default()
+            },
+            visuals: MeasurementVisuals::new(context)?,
+        };
+        measurement.update_label()?;
+        Ok(measurement)
+    }
+
+    ///
+    /// Returns the first point, the vertex and the second point.
+    ///
+    pub fn points(&self) -> [Vec3; 3] {
+        self.points
+    }
+
+    ///
+    /// Sets the first point, the vertex and the second point.
+    ///
+    pub fn set_points(&mut self, point0: Vec3, vertex: Vec3, point1: Vec3) -> ThreeDResult<()> {
+        self.points = [point0, vertex, point1];
+        self.update_label()
+    }
+
+    ///
+    /// Returns the format of the value in the label. Only the precision is used.
+    ///
+    pub fn format(&self) -> &MeasurementFormat {
+        &self.format
+    }
+
+    ///
+    /// Sets the format of the value in the label. Only the precision is used.
+    ///
+    pub fn set_format(&mut self, format: MeasurementFormat) -> ThreeDResult<()> {
+        self.format = format;
+        self.update_label()
+    }
+
+    ///
+    /// Returns the angle in degrees in the range `[0..180]`.
+    ///
+    pub fn value(&self) -> f32 {
+        let [p0, vertex, p1] = self.points;
+        let (a, b) = (p0 - vertex, p1 - vertex);
+        if a.magnitude2() == 0.0 || b.magnitude2() == 0.0 {
+            return 0.0;
+        }
+        Degrees::from(a.angle(b)).0
+    }
+
+    ///
+    /// Returns the text of the label.
+    ///
+    pub fn label(&self) -> String {
+        format!("{:.*}°", self.format.precision, self.value())
+    }
+
+    fn update_label(&mut self) -> ThreeDResult<()> {
+        let label = self.label();
+        self.visuals.set_label(&label)
+    }
+
+    fn lines(&self, camera: &Camera) -> (Vec<(Vec3, Vec3)>, Vec3) {
+        let [p0, vertex, p1] = self.points;
+        let mut lines = vec![(vertex, p0), (vertex, p1)];
+        let (a, b) = (p0 - vertex, p1 - vertex);
+        if a.magnitude2() == 0.0 || b.magnitude2() == 0.0 {
+            return (lines, vertex);
+        }
+        // An arc between the two lines at a third of the length of the shortest line
+        let radius = a.magnitude().min(b.magnitude()) / 3.0;
+        let (a, b) = (a.normalize(), b.normalize());
+        let angle = a.angle(b).0;
+        let mut perpendicular = b - a * a.dot(b);
+        if perpendicular.magnitude2() < 0.000001 {
+            perpendicular = a.cross(direction_to_camera(camera, vertex));
+        }
+        let perpendicular = perpendicular.normalize();
+        let arc_point =
+            |t: f32| vertex + radius * (a * (t * angle).cos() + perpendicular * (t * angle).sin());
+        let subdivisions = 16;
+        for i in 0..subdivisions {
+            lines.push((
+                arc_point(i as f32 / subdivisions as f32),
+                arc_point((i + 1) as f32 / subdivisions as f32),
+            ));
+        }
+        let middle = arc_point(0.5);
+        let direction = (middle - vertex).normalize();
+        let anchor = middle + direction * self.style.label_offset * pixel_size(camera, middle);
+        lines.push((middle, anchor));
+        (lines, anchor)
+    }
+}
+
+///
+/// A measurement of the area of a polygon given by points in world space, for example found using [pick],
+/// shown as the outline of the polygon and a label with the area at the center of the polygon.
+/// The area is the area of the polygon projected onto the plane which fits the points best,
+/// so the points should be in the same plane for the area to be exact.
+///
+pub struct AreaMeasurement {
+    /// The appearance of the measurement.
+    pub style: MeasurementStyle,
+    points: Vec<Vec3>,
+    format: MeasurementFormat,
+    visuals: MeasurementVisuals,
+}
+
+impl AreaMeasurement {
+    ///
+    /// Constructs a new measurement of the area of the polygon with the given points.
+    ///
+    pub fn new(context: &Context, points: &[Vec3]) -> ThreeDResult<Self> {
+        let mut measurement = Self {
+            style: MeasurementStyle::default(),
+            points: points.to_vec(),
+            format: MeasurementFormat::default(),
+            visuals: MeasurementVisuals::new(context)?,
+        };
+        measurement.update_label()?;
+        Ok(measurement)
+    }
+
+    ///
+    /// Returns the points of the polygon.
+    ///
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    ///
+    /// Sets the points of the polygon.
+    ///
+    pub fn set_points(&mut self, points: &[Vec3]) -> ThreeDResult<()> {
+        self.points = points.to_vec();
+        self.update_label()
+    }
+
+    ///
+    /// Returns the format of the value in the label. The unit is written with a superscript two after it.
+    ///
+    pub fn format(&self) -> &MeasurementFormat {
+        &self.format
+    }
+
+    ///
+    /// Sets the format of the value in the label. The unit is written with a superscript two after it.
+    ///
+    pub fn set_format(&mut self, format: MeasurementFormat) -> ThreeDResult<()> {
+        self.format = format;
+        self.update_label()
+    }
+
+    ///
+    /// Returns the area of the polygon multiplied by the square of the [MeasurementFormat::unit_scale].
+    ///
+    pub fn value(&self) -> f32 {
+        // Newell's method, which gives twice the area vector of a planar polygon
+        let mut normal = vec3(0.0, 0.0, 0.0);
+        for (i, p) in self.points.iter().enumerate() {
+            normal += p.cross(self.points[(i + 1) % self.points.len()]);
+        }
+        0.5 * normal.magnitude() * self.format.unit_scale * self.format.unit_scale
+    }
+
+    ///
+    /// Returns the text of the label.
+    ///
+    pub fn label(&self) -> String {
+        format_value(self.value(), &self.format, "²")
+    }
+
+    fn update_label(&mut self) -> ThreeDResult<()> {
+        let label = self.label();
+        self.visuals.set_label(&label)
+    }
+
+    fn lines(&self, _camera: &Camera) -> (Vec<(Vec3, Vec3)>, Vec3) {
+        let lines = (0..self.points.len())
+            .map(|i| (self.points[i], self.points[(i + 1) % self.points.len()]))
+            .collect();
+        let center = if self.points.is_empty() {
+            vec3(0.0, 0.0, 0.0)
+        } else {
+            self.points
+                .iter()
+                .fold(vec3(0.0, 0.0, 0.0), |sum, p| sum + p)
+                / self.points.len() as f32
+        };
+        (lines, center)
+    }
+}
+
+macro_rules! impl_measurement_object {
+    ($name:ident) => {
+        impl Shadable for $name {
+            fn render_with_material(
+                &self,
+                material: &dyn Material,
+                camera: &Camera,
+                _lights: &Lights,
+            ) -> ThreeDResult<()> {
+                let (lines, _) = self.lines(camera);
+                self.visuals
+                    .render_lines(camera, &self.style, &lines, Some(material))
+            }
+
+            fn render_forward(
+                &self,
+                material: &dyn Material,
+                camera: &Camera,
+                lights: &Lights,
+            ) -> ThreeDResult<()> {
+                self.render_with_material(material, camera, lights)
+            }
+
+            fn render_deferred(
+                &self,
+                _material: &DeferredPhysicalMaterial,
+                _camera: &Camera,
+                _viewport: Viewport,
+            ) -> ThreeDResult<()> {
+                Ok(())
+            }
+        }
+
+        impl Geometry for $name {
+            fn aabb(&self) -> AxisAlignedBoundingBox {
+                let mut aabb = AxisAlignedBoundingBox::EMPTY;
+                for p in self.points.iter() {
+                    aabb.expand(&[p.x, p.y, p.z]);
+                }
+                aabb
+            }
+
+            fn transformation(&self) -> Mat4 {
+                Mat4::identity()
+            }
+        }
+
+        impl Object for $name {
+            fn render(&self, camera: &Camera, _lights: &Lights) -> ThreeDResult<()> {
+                let (lines, anchor) = self.lines(camera);
+                self.visuals
+                    .render_lines(camera, &self.style, &lines, None)?;
+                self.visuals.render_label(camera, &self.style, anchor)
+            }
+
+            // The label is rendered on top of everything, so the measurement is rendered after the opaque objects
+            fn is_transparent(&self) -> bool {
+                true
+            }
+        }
+    };
+}
+
+impl_measurement_object!(DistanceMeasurement);
+impl_measurement_object!(AngleMeasurement);
+impl_measurement_object!(AreaMeasurement);
+
+// The lines and the label of a measurement, where the lines are camera facing quads with a constant width on the screen
+// and the label is a camera facing quad with a constant size on the screen
+struct MeasurementVisuals {
+    context: Context,
+    line: Model<ColorMaterial>,
+    label: Model<ColorMaterial>,
+    label_size: (u32, u32),
+}
+
+impl MeasurementVisuals {
+    fn new(context: &Context) -> ThreeDResult<Self> {
+        let mut mesh = CPUMesh::square();
+        mesh.transform(&(Mat4::from_scale(0.5) * Mat4::from_translation(vec3(1.0, 0.0, 0.0))));
+        let line_render_states = RenderStates {
+            cull: Cull::None,
+            ..Default::default()
+        };
+        let line = Model::new_with_material(
+            context,
+            &mesh,
+            ColorMaterial {
+                opaque_render_states: line_render_states,
+                transparent_render_states: RenderStates {
+                    blend: Blend::TRANSPARENCY,
+                    ..line_render_states
+                },
+                ..Default::default()
+            },
+        )?;
+        let label_render_states = RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::None,
+            ..Default::default()
+        };
+        let label = Model::new_with_material(
+            context,
+            &CPUMesh::square(),
+            ColorMaterial {
+                opaque_render_states: label_render_states,
+                transparent_render_states: label_render_states,
+                ..Default::default()
+            },
+        )?;
+        Ok(Self {
+            context: context.clone(),
+            line,
+            label,
+            label_size: (0, 0),
+        })
+    }
+
+    fn set_label(&mut self, text: &str) -> ThreeDResult<()> {
+        let texture = label_texture(text);
+        self.label_size = (texture.width, texture.height);
+        self.label.material.texture = Some(Rc::new(Texture2D::new(&self.context, &texture)?));
+        Ok(())
+    }
+
+    fn render_lines(
+        &self,
+        camera: &Camera,
+        style: &MeasurementStyle,
+        lines: &[(Vec3, Vec3)],
+        material: Option<&dyn Material>,
+    ) -> ThreeDResult<()> {
+        let mut model = self.line.clone();
+        model.material.color = style.color;
+        for (p0, p1) in lines.iter() {
+            let p0 = offset_towards_camera(camera, *p0, style.depth_offset);
+            let p1 = offset_towards_camera(camera, *p1, style.depth_offset);
+            let direction = p1 - p0;
+            let middle = 0.5 * (p0 + p1);
+            let side = direction.cross(direction_to_camera(camera, middle));
+            if side.magnitude2() == 0.0 {
+                continue;
+            }
+            let side = side.normalize() * style.line_width * pixel_size(camera, middle);
+            model.set_transformation(Mat4::from_cols(
+                direction.extend(0.0),
+                side.extend(0.0),
+                direction.cross(side).normalize().extend(0.0),
+                p0.extend(1.0),
+            ));
+            if let Some(material) = material {
+                model.render_with_material(material, camera, &Lights::default())?;
+            } else {
+                model.render(camera, &Lights::default())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_label(
+        &self,
+        camera: &Camera,
+        style: &MeasurementStyle,
+        anchor: Vec3,
+    ) -> ThreeDResult<()> {
+        let mut model = self.label.clone();
+        model.material.color = style.color;
+        let size = 0.5 * style.label_scale * pixel_size(camera, anchor);
+        let right = camera.right_direction().normalize();
+        let up = screen_up(camera);
+        model.set_transformation(Mat4::from_cols(
+            (right * size * self.label_size.0 as f32).extend(0.0),
+            (up * size * self.label_size.1 as f32).extend(0.0),
+            right.cross(up).extend(0.0),
+            anchor.extend(1.0),
+        ));
+        model.render(camera, &Lights::default())
+    }
+}
+
+fn format_value(value: f32, format: &MeasurementFormat, unit_suffix: &str) -> String {
+    if format.unit.is_empty() {
+        format!("{:.*}", format.precision, value)
+    } else {
+        format!(
+            "{:.*} {}{}",
+            format.precision, value, format.unit, unit_suffix
+        )
+    }
+}
+
+// Returns the size in world space of a pixel at the given position
+fn pixel_size(camera: &Camera, position: Vec3) -> f32 {
+    let height = match camera.projection_type() {
+        ProjectionType::Perspective { field_of_view_y } => {
+            let depth = -(camera.view() * position.extend(1.0)).z;
+            2.0 * depth.max(camera.z_near()) * (0.5 * field_of_view_y.0).tan()
+        }
+        ProjectionType::Orthographic { height } => *height,
+    };
+    height / camera.viewport().height.max(1) as f32
+}
+
+fn direction_to_camera(camera: &Camera, position: Vec3) -> Vec3 {
+    match camera.projection_type() {
+        ProjectionType::Perspective { .. } => {
+            let direction = camera.position() - position;
+            if direction.magnitude2() > 0.0 {
+                direction.normalize()
+            } else {
+                -camera.view_direction()
+            }
+        }
+        ProjectionType::Orthographic { .. } => -camera.view_direction(),
+    }
+}
+
+fn offset_towards_camera(camera: &Camera, position: Vec3, pixels: f32) -> Vec3 {
+    position + direction_to_camera(camera, position) * pixels * pixel_size(camera, position)
+}
+
+fn screen_up(camera: &Camera) -> Vec3 {
+    camera
+        .right_direction()
+        .cross(camera.view_direction())
+        .normalize()
+}
+
+// The glyphs of the built-in 5x7 pixel font, where each row is given from the top with the leftmost pixel in the most significant of the five bits
+const GLYPHS: [(char, [u8; 7]); 48] = [
+    ('0', [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E]),
+    ('1', [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('2', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F]),
+    ('3', [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E]),
+    ('4', [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02]),
+    ('5', [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E]),
+    ('6', [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E]),
+    ('7', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08]),
+    ('8', [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E]),
+    ('9', [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C]),
+    ('a', [0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F]),
+    ('b', [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1E]),
+    ('c', [0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E]),
+    ('d', [0x01, 0x01, 0x0D, 0x13, 0x11, 0x11, 0x0F]),
+    ('e', [0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E]),
+    ('f', [0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08]),
+    ('g', [0x00, 0x0F, 0x11, 0x11, 0x0F, 0x01, 0x0E]),
+    ('h', [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11]),
+    ('i', [0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E]),
+    ('j', [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0C]),
+    ('k', [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12]),
+    ('l', [0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('m', [0x00, 0x00, 0x1A, 0x15, 0x15, 0x11, 0x11]),
+    ('n', [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11]),
+    ('o', [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E]),
+    ('p', [0x00, 0x00, 0x1E, 0x11, 0x1E, 0x10, 0x10]),
+    ('q', [0x00, 0x00, 0x0D, 0x13, 0x0F, 0x01, 0x01]),
+    ('r', [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10]),
+    ('s', [0x00, 0x00, 0x0E, 0x10, 0x0E, 0x01, 0x1E]),
+    ('t', [0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06]),
+    ('u', [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D]),
+    ('v', [0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04]),
+    ('w', [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A]),
+    ('x', [0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11]),
+    ('y', [0x00, 0x00, 0x11, 0x11, 0x0F, 0x01, 0x0E]),
+    ('z', [0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F]),
+    ('.', [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C]),
+    (',', [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08]),
+    ('-', [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00]),
+    ('+', [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00]),
+    ('/', [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00]),
+    ('(', [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02]),
+    (')', [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08]),
+    ('\'', [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00]),
+    ('"', [0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ('°', [0x0C, 0x12, 0x12, 0x0C, 0x00, 0x00, 0x00]),
+    ('²', [0x0C, 0x12, 0x04, 0x08, 0x1E, 0x00, 0x00]),
+    ('µ', [0x00, 0x11, 0x11, 0x11, 0x1B, 0x16, 0x10]),
+];
+
+// Writes the text with the built-in font as white pixels on a transparent dark background with a border of two pixels
+fn label_texture(text: &str) -> CPUTexture<u8> {
+    let characters = text.chars().collect::<Vec<_>>();
+    let border = 2;
+    let width = (6 * characters.len()).max(1) - 1 + 2 * border;
+    let height = 7 + 2 * border;
+    let mut data = Vec::with_capacity(width * height * 4);
+    for _ in 0..width * height {
+        data.extend_from_slice(&[0, 0, 0, 160]);
+    }
+    for (i, character) in characters.iter().enumerate() {
+        let character = character.to_ascii_lowercase();
+        if let Some((_, rows)) = GLYPHS.iter().find(|(c, _)| *c == character) {
+            for (y, row) in rows.iter().enumerate() {
+                for x in 0..5 {
+                    if row & (0x10 >> x) != 0 {
+                        // The first row of the texture is at the bottom of the label
+                        let index = (height - 1 - border - y) * width + border + 6 * i + x;
+                        data[index * 4..index * 4 + 4].copy_from_slice(&[255, 255, 255, 255]);
+                    }
+                }
+            }
+        }
+    }
+    CPUTexture {
+        data,
+        width: width as u32,
+        height: height as u32,
+        format: Format::RGBA,
+        min_filter: Interpolation::Nearest,
+        mag_filter: Interpolation::Nearest,
+        mip_map_filter: None,
+        wrap_s: Wrapping::ClampToEdge,
+        wrap_t: Wrapping::ClampToEdge,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,179 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A planar mirror, for example a bathroom mirror, which reflects the objects in front of it.
+/// The mirror is a square spanning the xy-plane with positions in the range `[-1..1]` in the x and y axes, reflecting in the positive z direction,
+/// and it is placed in the scene by the transformation, see [Mirror::set_transformation].
+///
+/// The reflection is rendered in two steps, first [Mirror::render_reflection] renders the objects seen in the mirror into a texture
+/// which must be done before writing to the final render target, then [Mirror::render] renders the mirror using the texture in a render target render function,
+/// for example in the callback function of [Screen::write].
+///
+pub struct Mirror {
+    context: Context,
+    transformation: Mat4,
+    /// The clear state used when clearing the reflection before rendering the objects in [Mirror::render_reflection].
+    pub clear_state: ClearState,
+    /// The resolution of the reflection relative to the viewport of the camera, for example 0.5 to render the reflection at half the resolution.
+    pub scale_factor: f32,
+    position_buffer: VertexBuffer,
+    mirror_camera: Option<Camera>,
+    target: ResizableTarget<u8>,
+}
+
+impl Mirror {
+    ///
+    /// Constructs a new mirror placed in the scene by the given transformation.
+    ///
+    pub fn new(context: &Context, transformation: Mat4) -> ThreeDResult<Self> {
+        let positions = vec![
+            -1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 0.0, -1.0, 1.0, 0.0, -1.0,
+            -1.0, 0.0,
+        ];
+        Ok(Self {
+            context: context.clone(),
+            transformation,
+            clear_state: ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+            scale_factor: 1.0,
+            position_buffer: VertexBuffer::new_with_static(context, &positions)?,
+            mirror_camera: None,
+            target: ResizableTarget::new(
+                context,
+                |context, width, height| {
+                    Texture2D::new_empty(
+                        context,
+                        width,
+                        height,
+                        Interpolation::Linear,
+                        Interpolation::Linear,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        Format::RGBA,
+                    )
+                },
+                |context, width, height| {
+                    DepthTargetTexture2D::new(
+                        context,
+                        width,
+                        height,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                        DepthFormat::Depth32F,
+                    )
+                },
+            ),
+        })
+    }
+
+    ///
+    /// Set the local to world transformation applied to the mirror.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    ///
+    /// Returns the local to world transformation applied to the mirror.
+    ///
+    pub fn transformation(&self) -> &Mat4 {
+        &self.transformation
+    }
+
+    ///
+    /// Returns the plane of the mirror in world space with the normal pointing in the reflecting direction.
+    ///
+    pub fn plane(&self) -> Plane {
+        let center = (self.transformation * vec4(0.0, 0.0, 0.0, 1.0)).truncate();
+        let normal = (self.transformation.invert().unwrap().transpose() * vec4(0.0, 0.0, 1.0, 0.0))
+            .truncate();
+        Plane::new(center, normal)
+    }
+
+    ///
+    /// Returns the texture containing the reflection rendered in the last call to [Mirror::render_reflection], if any.
+    ///
+    pub fn color_texture(&self) -> Option<&Texture2D<u8>> {
+        self.target.color_texture()
+    }
+
+    ///
+    /// Renders the given objects seen in the mirror from the given camera into a texture with the size of the viewport of the camera multiplied by the [Mirror::scale_factor].
+    /// The objects behind the mirror are clipped and the reversed winding order of the mirrored view is handled,
+    /// so back faces are culled as usual. Nothing is rendered if the camera is behind the mirror.
+    /// This function must not be called in a render target render function and needs to be followed by a call to [Mirror::render].
+    ///
+    pub fn render_reflection(
+        &mut self,
+        camera: &Camera,
+        objects: &[impl Object],
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        let plane = self.plane();
+        if plane.signed_distance(*camera.position()) <= 0.0 {
+            return Ok(());
+        }
+        let width = camera.viewport().width;
+        let height = camera.viewport().height;
+        self.target.scale_factor = self.scale_factor;
+        self.target.resize(width, height)?;
+
+        if self.mirror_camera.is_none() {
+            self.mirror_camera = Some(camera.new_mirrored(&self.context, &plane)?);
+        }
+        let mirror_camera = self.mirror_camera.as_mut().unwrap();
+        mirror_camera.set_viewport(self.target.viewport())?;
+        mirror_camera.set_mirrored(camera, &plane)?;
+        let mirror_camera = &*mirror_camera;
+
+        let context = &self.context;
+        self.target.get(width, height)?.write(self.clear_state, || {
+            context.set_clockwise_front_face(mirror_camera.is_mirrored());
+            let result = render_pass(mirror_camera, objects, lights);
+            context.set_clockwise_front_face(false);
+            result
+        })
+    }
+
+    ///
+    /// Renders the mirror showing the reflection rendered in the last call to [Mirror::render_reflection], which must be called with the same camera.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn render(&self, camera: &Camera) -> ThreeDResult<()> {
+        if let Some(color_texture) = self.target.color_texture() {
+            let render_states = RenderStates {
+                cull: Cull::Back,
+                ..Default::default()
+            };
+            let viewport = camera.viewport();
+            self.context.program(
+                include_str!("shaders/mirror.vert"),
+                &format!(
+                    "{}{}",
+                    include_str!("../../core/shared.frag"),
+                    include_str!("shaders/mirror.frag")
+                ),
+                |program| {
+                    program.use_uniform_block("Camera", camera.uniform_buffer());
+                    program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+                    program.use_uniform_vec4(
+                        "viewport",
+                        &vec4(
+                            viewport.x as f32,
+                            viewport.y as f32,
+                            viewport.width as f32,
+                            viewport.height as f32,
+                        ),
+                    )?;
+                    program.use_texture("reflectionMap", color_texture)?;
+                    program.use_attribute_vec3("position", &self.position_buffer)?;
+                    program.draw_arrays(render_states, viewport, 6);
+                    Ok(())
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,25 @@
 use crate::core::*;
 use crate::renderer::*;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
+///
+/// The maximum number of morph targets which can be active, ie. have a non-zero weight, at the same time, see [Model::set_morph_weights].
+///
+pub const MAX_ACTIVE_MORPH_TARGETS: usize = 8;
+
+///
+/// Defines how the triangles of a [Model] are sorted before they are drawn, see [Model::set_transparency_sorting].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TriangleSorting {
+    /// The triangles are drawn in the order they are defined in the mesh.
+    None,
+    /// The triangles are drawn in the order of decreasing distance from the camera to their centroids when the material is transparent,
+    /// such that a semi-transparent mesh which overlaps itself is blended correctly.
+    BackToFront,
+}
+
 ///
 /// A 3D model consisting of a triangle mesh and any material that implements the `Material` trait.
 ///
@@ -11,8 +29,17 @@ pub struct Model<M: Material> {
     mesh: Rc<Mesh>,
     aabb: AxisAlignedBoundingBox,
     aabb_local: AxisAlignedBoundingBox,
+    bounding_sphere: BoundingSphere,
+    bounding_sphere_local: BoundingSphere,
     transformation: Mat4,
+    transformation_f64: DMat4,
     texture_transform: Mat3,
+    morph_weights: Vec<f32>,
+    change_count: u64,
+    triangle_sorter: Option<Rc<RefCell<TriangleSorter>>>,
+    dynamic_normals: Option<Rc<RefCell<DynamicNormals>>>,
+    render_order: i32,
+    program_id: Cell<u64>,
     /// The material applied to the model
     pub material: M,
 }
@@ -35,19 +62,60 @@ impl<M: Material> Model<M> {
         cpu_mesh: &CPUMesh,
         material: M,
     ) -> ThreeDResult<Self> {
-        let mesh = Rc::new(Mesh::new(context, cpu_mesh)?);
+        Self::new_with_settings(context, cpu_mesh, material, MeshSettings::default())
+    }
+
+    ///
+    /// Creates a new 3D model with a triangle mesh as geometry and the given material,
+    /// where the mesh data is stored on the GPU using the given settings, for example in compact formats, see [MeshSettings].
+    ///
+    pub fn new_with_settings(
+        context: &Context,
+        cpu_mesh: &CPUMesh,
+        material: M,
+        settings: MeshSettings,
+    ) -> ThreeDResult<Self> {
+        let mesh = Rc::new(Mesh::new_with_settings(context, cpu_mesh, settings)?);
         let aabb = cpu_mesh.compute_aabb();
+        let bounding_sphere = cpu_mesh.compute_bounding_sphere();
         Ok(Self {
             mesh,
             aabb,
             aabb_local: aabb.clone(),
+            bounding_sphere,
+            bounding_sphere_local: bounding_sphere,
             transformation: Mat4::identity(),
+            transformation_f64: DMat4::identity(),
             texture_transform: Mat3::identity(),
+            morph_weights: cpu_mesh.morph_targets.iter().map(|t| t.weight).collect(),
+            change_count: 0,
+            triangle_sorter: None,
+            dynamic_normals: None,
+            render_order: 0,
+            program_id: Cell::new(0),
             context: context.clone(),
             material,
         })
     }
 
+    pub(crate) fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    ///
+    /// Returns the material applied to the model.
+    ///
+    pub fn material(&self) -> &M {
+        &self.material
+    }
+
+    ///
+    /// Returns the material applied to the model for changing its parameters after the model is created.
+    ///
+    pub fn material_mut(&mut self) -> &mut M {
+        &mut self.material
+    }
+
     pub fn texture_transform(&mut self) -> &Mat3 {
         &self.texture_transform
     }
@@ -56,6 +124,176 @@ impl<M: Material> Model<M> {
         self.texture_transform = texture_transform;
     }
 
+    ///
+    /// Sets the render order of this model, see [Object::render_order].
+    ///
+    pub fn set_render_order(&mut self, render_order: i32) {
+        self.render_order = render_order;
+    }
+
+    ///
+    /// Sets the weights of the morph targets of the mesh, see [MorphTarget].
+    /// The weights are given in the same order as the morph targets in [CPUMesh::morph_targets] and missing weights are set to zero.
+    /// At most [MAX_ACTIVE_MORPH_TARGETS] morph targets can have a non-zero weight at the same time,
+    /// if more are given, the morph targets with the largest weights are used and a warning is logged.
+    ///
+    pub fn set_morph_weights(&mut self, weights: &[f32]) {
+        for (i, weight) in self.morph_weights.iter_mut().enumerate() {
+            *weight = weights.get(i).cloned().unwrap_or(0.0);
+        }
+        self.change_count += 1;
+        if weights.len() > self.morph_weights.len() {
+            log::warn!(
+                "{} morph weights given but the mesh only has {} morph targets",
+                weights.len(),
+                self.morph_weights.len()
+            );
+        }
+    }
+
+    ///
+    /// Returns the weights of the morph targets of the mesh, see [Model::set_morph_weights].
+    ///
+    pub fn morph_weights(&self) -> &[f32] {
+        &self.morph_weights
+    }
+
+    ///
+    /// Returns the indices and weights of the morph targets which should be blended,
+    /// ie. the morph targets with the largest non-zero weights.
+    ///
+    fn active_morph_targets(&self) -> (Vec<i32>, Vec<f32>) {
+        let mut active = self
+            .morph_weights
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| **w != 0.0)
+            .map(|(i, w)| (i as i32, *w))
+            .collect::<Vec<_>>();
+        if active.len() > MAX_ACTIVE_MORPH_TARGETS {
+            log::warn!(
+                "{} morph targets are active but only {} are supported, the ones with the smallest weights are ignored",
+                active.len(),
+                MAX_ACTIVE_MORPH_TARGETS
+            );
+            active.sort_by(|a, b| b.1.abs().partial_cmp(&a.1.abs()).unwrap());
+            active.truncate(MAX_ACTIVE_MORPH_TARGETS);
+        }
+        active.resize(MAX_ACTIVE_MORPH_TARGETS, (0, 0.0));
+        active.into_iter().unzip()
+    }
+
+    ///
+    /// Specifies how the triangles are sorted before they are drawn with a transparent material, see [TriangleSorting].
+    /// The given mesh must be the mesh this model was created from, since the centroids of the triangles are computed from it once.
+    /// When sorting, the triangles are sorted again each time the camera has moved relative to the model
+    /// and written to an index buffer owned by this model, which is done without allocating memory after the first time.
+    /// Nothing is sorted when the material is opaque or the sorting is [TriangleSorting::None], which is the default.
+    /// Note that the centroids are not affected by morph targets.
+    ///
+    pub fn set_transparency_sorting(
+        &mut self,
+        cpu_mesh: &CPUMesh,
+        sorting: TriangleSorting,
+    ) -> ThreeDResult<()> {
+        self.triangle_sorter = match sorting {
+            TriangleSorting::None => None,
+            TriangleSorting::BackToFront => Some(Rc::new(RefCell::new(TriangleSorter::new(
+                &self.context,
+                cpu_mesh,
+                0.01 * self.bounding_sphere_local.radius(),
+            )?))),
+        };
+        Ok(())
+    }
+
+    ///
+    /// Returns how the triangles are sorted before they are drawn with a transparent material, see [Model::set_transparency_sorting].
+    ///
+    pub fn transparency_sorting(&self) -> TriangleSorting {
+        if self.triangle_sorter.is_some() {
+            TriangleSorting::BackToFront
+        } else {
+            TriangleSorting::None
+        }
+    }
+
+    ///
+    /// Specifies whether the normals are recomputed from the positions displaced by the material each time the model is rendered,
+    /// see [Material::vertex_displacement_source] and [DynamicNormals], instead of using the static normals of the mesh which do not follow the displacement.
+    /// The given mesh must be the mesh this model was created from, since the triangles around each vertex are computed from it once.
+    /// Nothing changes when the material does not displace the vertices. The default is false.
+    ///
+    pub fn enable_dynamic_normals(
+        &mut self,
+        cpu_mesh: &CPUMesh,
+        enabled: bool,
+    ) -> ThreeDResult<()> {
+        self.dynamic_normals = if enabled {
+            Some(Rc::new(RefCell::new(DynamicNormals::new(
+                &self.context,
+                cpu_mesh,
+            )?)))
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    ///
+    /// Returns whether the normals are recomputed from the displaced positions, see [Model::enable_dynamic_normals].
+    ///
+    pub fn dynamic_normals_enabled(&self) -> bool {
+        self.dynamic_normals.is_some()
+    }
+
+    // The dynamic normals if they are enabled and the material displaces the vertices
+    fn active_dynamic_normals(&self) -> Option<&Rc<RefCell<DynamicNormals>>> {
+        self.dynamic_normals
+            .as_ref()
+            .filter(|_| self.material.vertex_displacement_source().is_some())
+    }
+
+    // Recomputes the dynamic normals before drawing, if they are active
+    fn update_dynamic_normals(&self) -> ThreeDResult<()> {
+        if let Some(dynamic_normals) = self.active_dynamic_normals() {
+            dynamic_normals.borrow_mut().update(&self.material)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Sets the transformation of this model like [GeometryMut::set_transformation], but in double precision,
+    /// which is used to place the model precisely far away from the origin when rendering with [Camera::set_camera_relative] enabled.
+    ///
+    pub fn set_transformation_f64(&mut self, transformation: DMat4) {
+        self.set_transformation(transformation.cast().unwrap());
+        self.transformation_f64 = transformation;
+    }
+
+    // Overwrites the positions of the mesh without reallocating the buffer and updates the bounding volumes,
+    // where the number of positions must be unchanged and the mesh must not be shared with a clone of this model
+    pub(in crate::renderer) fn update_positions(&mut self, positions: &[f32]) {
+        Rc::get_mut(&mut self.mesh)
+            .unwrap()
+            .position_buffer
+            .fill_subset(0, positions);
+        self.aabb_local = AxisAlignedBoundingBox::new_with_positions(positions);
+        self.bounding_sphere_local = BoundingSphere::new_with_positions(positions);
+        self.update_bounds();
+    }
+
+    // Transforms the local bounding volumes by the transformation
+    fn update_bounds(&mut self) {
+        let mut aabb = self.aabb_local.clone();
+        aabb.transform(&self.transformation);
+        self.aabb = aabb;
+        let mut bounding_sphere = self.bounding_sphere_local;
+        bounding_sphere.transform(&self.transformation);
+        self.bounding_sphere = bounding_sphere;
+        self.change_count += 1;
+    }
+
     pub(in crate::renderer) fn set_transformation_2d(&mut self, transformation: Mat3) {
         self.set_transformation(Mat4::new(
             transformation.x.x,
@@ -81,55 +319,103 @@ impl<M: Material> Model<M> {
         &self,
         program: &Program,
         render_states: RenderStates,
-        camera_buffer: &UniformBuffer,
+        camera: &Camera,
         viewport: Viewport,
         transformation: &Mat4,
         texture_transform: &Mat3,
+        sort_from: Option<&Vec3>,
     ) -> ThreeDResult<()> {
-        program.use_uniform_block("Camera", camera_buffer);
+        program.use_uniform_block("Camera", camera.uniform_buffer());
         program.use_uniform_mat4("modelMatrix", transformation)?;
+        if camera.is_camera_relative() {
+            program.use_uniform_mat4(
+                "modelViewMatrix",
+                &camera.model_view_f64(&self.transformation_f64),
+            )?;
+        }
+        self.material.use_vertex_uniforms(program)?;
+        let dynamic_normals = self.active_dynamic_normals().map(|d| d.borrow());
+        let dynamic_normal_buffer = dynamic_normals.as_ref().and_then(|d| d.normal_buffer());
+        if let Some(ref dynamic_normals) = dynamic_normals {
+            if program.requires_uniform("dynamicNormalVertices") {
+                dynamic_normals.use_textures(program)?;
+            }
+        }
 
-        if program.requires_attribute("position") {
-            program.use_attribute_vec3("position", &self.mesh.position_buffer)?;
+        if let Some(ref morph_target_texture) = self.mesh.morph_target_texture {
+            let (indices, weights) = self.active_morph_targets();
+            program.use_texture("morphTargets", morph_target_texture)?;
+            program.use_uniform_int(
+                "morphVertexCount",
+                &(self.mesh.position_buffer.count() as i32 / 3),
+            )?;
+            program.use_uniform_array("morphTargetIndices", &indices)?;
+            program.use_uniform_array("morphWeights", &weights)?;
         }
+
         if program.requires_attribute("uv_coordinates") {
             program.use_uniform_mat3("textureTransform", &texture_transform)?;
-            let uv_buffer = self
-                .mesh
-                .uv_buffer
-                .as_ref()
-                .ok_or(CoreError::MissingMeshBuffer("uv coordinates".to_string()))?;
-            program.use_attribute_vec2("uv_coordinates", uv_buffer)?;
         }
         if program.requires_attribute("normal") {
-            let normal_buffer = self
-                .mesh
-                .normal_buffer
-                .as_ref()
-                .ok_or(CoreError::MissingMeshBuffer("normal".to_string()))?;
-            program.use_attribute_vec3("normal", normal_buffer)?;
             program.use_uniform_mat4(
                 "normalMatrix",
                 &transformation.invert().unwrap().transpose(),
             )?;
-            if program.requires_attribute("tangent") {
-                let tangent_buffer = self
-                    .mesh
-                    .tangent_buffer
+        }
+
+        let mesh = &self.mesh;
+        let additional_buffer_ids = dynamic_normal_buffer
+            .map(|b| vec![b.uid()])
+            .unwrap_or_default();
+        mesh.use_attributes(program, &additional_buffer_ids, || {
+            if program.requires_attribute("position") {
+                program.use_attribute_vec3("position", &mesh.position_buffer)?;
+            }
+            if program.requires_attribute("uv_coordinates") {
+                let uv_buffer = mesh
+                    .uv_buffer
                     .as_ref()
-                    .ok_or(CoreError::MissingMeshBuffer("tangent".to_string()))?;
-                program.use_attribute_vec4("tangent", tangent_buffer)?;
+                    .ok_or(CoreError::MissingMeshBuffer("uv coordinates".to_string()))?;
+                program.use_attribute_vec2("uv_coordinates", uv_buffer)?;
             }
-        }
-        if program.requires_attribute("color") {
-            let color_buffer = self
-                .mesh
-                .color_buffer
-                .as_ref()
-                .ok_or(CoreError::MissingMeshBuffer("color".to_string()))?;
-            program.use_attribute_vec4("color", color_buffer)?;
-        }
-        if let Some(ref index_buffer) = self.mesh.index_buffer {
+            if program.requires_attribute("uv2_coordinates") {
+                let uv2_buffer = mesh
+                    .uv2_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer(
+                        "second uv coordinates".to_string(),
+                    ))?;
+                program.use_attribute_vec2("uv2_coordinates", uv2_buffer)?;
+            }
+            if program.requires_attribute("normal") {
+                let normal_buffer = dynamic_normal_buffer
+                    .or(mesh.normal_buffer.as_ref())
+                    .ok_or(CoreError::MissingMeshBuffer("normal".to_string()))?;
+                program.use_attribute_vec3("normal", normal_buffer)?;
+                if program.requires_attribute("tangent") {
+                    let tangent_buffer = mesh
+                        .tangent_buffer
+                        .as_ref()
+                        .ok_or(CoreError::MissingMeshBuffer("tangent".to_string()))?;
+                    program.use_attribute_vec4("tangent", tangent_buffer)?;
+                }
+            }
+            if program.requires_attribute("color") {
+                let color_buffer = mesh
+                    .color_buffer
+                    .as_ref()
+                    .ok_or(CoreError::MissingMeshBuffer("color".to_string()))?;
+                program.use_attribute_vec4("color", color_buffer)?;
+            }
+            Ok(())
+        })?;
+        if let (Some(sorter), Some(position)) = (&self.triangle_sorter, sort_from) {
+            let mut sorter = sorter.borrow_mut();
+            let local_position =
+                (transformation.invert().unwrap() * position.extend(1.0)).truncate();
+            sorter.sort(local_position);
+            program.draw_elements(render_states, viewport, &sorter.index_buffer);
+        } else if let Some(ref index_buffer) = self.mesh.index_buffer {
             program.draw_elements(render_states, viewport, index_buffer);
         } else {
             program.draw_arrays(
@@ -138,17 +424,92 @@ impl<M: Material> Model<M> {
                 self.mesh.position_buffer.count() as u32 / 3,
             );
         }
-        Ok(())
+        self.context.check_errors("Model::draw")
+    }
+
+    // Renders the model with the given material and returns the id of the program used, which is cached by Object::render for sorting the objects by material
+    fn render_with_program(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<u64> {
+        self.update_dynamic_normals()?;
+        let fragment_shader_source =
+            material.fragment_shader_source(self.mesh.color_buffer.is_some(), lights);
+        let mut program_id = 0;
+        self.context.program(
+            &self.model_vertex_shader_source(&fragment_shader_source, camera)?,
+            &fragment_shader_source,
+            |program| {
+                program_id = program.uid();
+                material.use_uniforms(program, camera, lights)?;
+                let transparent = material.is_transparent();
+                for render_states in material
+                    .transparency_mode()
+                    .passes(material.render_states(), transparent)
+                {
+                    self.draw(
+                        program,
+                        render_states,
+                        camera,
+                        camera.viewport(),
+                        &self.transformation,
+                        &self.texture_transform,
+                        if transparent {
+                            Some(camera.position())
+                        } else {
+                            None
+                        },
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        Ok(program_id)
+    }
+
+    fn model_vertex_shader_source(
+        &self,
+        fragment_shader_source: &str,
+        camera: &Camera,
+    ) -> ThreeDResult<String> {
+        let mut vertex_shader_source = format!(
+            "{}{}{}",
+            if camera.is_camera_relative() {
+                "#define CAMERA_RELATIVE\n"
+            } else {
+                ""
+            },
+            self.mesh.color_space_define(),
+            Self::vertex_shader_source(fragment_shader_source)?
+        );
+        if let Some(displacement_source) = self.material.vertex_displacement_source() {
+            let dynamic_normals_source = self
+                .active_dynamic_normals()
+                .and_then(|d| d.borrow().vertex_shader_source())
+                .unwrap_or("");
+            vertex_shader_source = format!(
+                "#define USE_VERTEX_DISPLACEMENT\n{}\n{}{}",
+                displacement_source, dynamic_normals_source, vertex_shader_source
+            );
+        }
+        Ok(if self.mesh.morph_target_texture.is_some() {
+            format!("#define USE_MORPH_TARGETS\n{}", vertex_shader_source)
+        } else {
+            vertex_shader_source
+        })
     }
 
-    pub(super) fn vertex_shader_source(fragment_shader_source: &str) -> ThreeDResult<String> {
+    pub(crate) fn vertex_shader_source(fragment_shader_source: &str) -> ThreeDResult<String> {
         let use_positions = fragment_shader_source.find("in vec3 pos;").is_some();
         let use_normals = fragment_shader_source.find("in vec3 nor;").is_some();
         let use_tangents = fragment_shader_source.find("in vec3 tang;").is_some();
         let use_uvs = fragment_shader_source.find("in vec2 uvs;").is_some();
+        let use_uvs2 = fragment_shader_source.find("in vec2 uvs2;").is_some();
         let use_colors = fragment_shader_source.find("in vec4 col;").is_some();
         Ok(format!(
-            "{}{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}",
             if use_positions {
                 "#define USE_POSITIONS\n"
             } else {
@@ -168,6 +529,7 @@ impl<M: Material> Model<M> {
                 ""
             },
             if use_uvs { "#define USE_UVS\n" } else { "" },
+            if use_uvs2 { "#define USE_UVS2\n" } else { "" },
             if use_colors {
                 "#define USE_COLORS\n"
             } else {
@@ -184,17 +546,24 @@ impl<M: Material> Geometry for Model<M> {
         self.aabb
     }
 
+    fn bounding_sphere(&self) -> BoundingSphere {
+        self.bounding_sphere
+    }
+
     fn transformation(&self) -> Mat4 {
         self.transformation
     }
+
+    fn change_count(&self) -> u64 {
+        self.change_count
+    }
 }
 
 impl<M: Material> GeometryMut for Model<M> {
     fn set_transformation(&mut self, transformation: Mat4) {
         self.transformation = transformation;
-        let mut aabb = self.aabb_local.clone();
-        aabb.transform(&self.transformation);
-        self.aabb = aabb;
+        self.transformation_f64 = transformation.cast().unwrap();
+        self.update_bounds();
     }
 }
 
@@ -205,23 +574,8 @@ impl<M: Material> Shadable for Model<M> {
         camera: &Camera,
         lights: &Lights,
     ) -> ThreeDResult<()> {
-        let fragment_shader_source =
-            material.fragment_shader_source(self.mesh.color_buffer.is_some(), lights);
-        self.context.program(
-            &Self::vertex_shader_source(&fragment_shader_source)?,
-            &fragment_shader_source,
-            |program| {
-                material.use_uniforms(program, camera, lights)?;
-                self.draw(
-                    program,
-                    material.render_states(),
-                    camera.uniform_buffer(),
-                    camera.viewport(),
-                    &self.transformation,
-                    &self.texture_transform,
-                )
-            },
-        )
+        self.render_with_program(material, camera, lights)?;
+        Ok(())
     }
 
     fn render_forward(
@@ -239,21 +593,23 @@ impl<M: Material> Shadable for Model<M> {
         camera: &Camera,
         viewport: Viewport,
     ) -> ThreeDResult<()> {
+        self.update_dynamic_normals()?;
         let lights = Lights::default();
         let fragment_shader_source =
             material.fragment_shader_source(self.mesh.color_buffer.is_some(), &lights);
         self.context.program(
-            &Self::vertex_shader_source(&fragment_shader_source)?,
+            &self.model_vertex_shader_source(&fragment_shader_source, camera)?,
             &fragment_shader_source,
             |program| {
                 material.use_uniforms(program, camera, &lights)?;
                 self.draw(
                     program,
                     material.render_states(),
-                    camera.uniform_buffer(),
+                    camera,
                     viewport,
                     &self.transformation,
                     &self.texture_transform,
+                    None,
                 )
             },
         )
@@ -262,10 +618,240 @@ impl<M: Material> Shadable for Model<M> {
 
 impl<M: Material> Object for Model<M> {
     fn render(&self, camera: &Camera, lights: &Lights) -> ThreeDResult<()> {
-        self.render_with_material(&self.material, camera, lights)
+        self.program_id
+            .set(self.render_with_program(&self.material, camera, lights)?);
+        Ok(())
     }
 
     fn is_transparent(&self) -> bool {
         self.material.is_transparent()
     }
+
+    fn material_id(&self) -> u64 {
+        self.program_id.get()
+    }
+
+    fn render_order(&self) -> i32 {
+        self.render_order
+    }
+}
+
+///
+/// Renders the models with [PhysicalMaterialInstance]s which are inside the camera frustum.
+/// Compared to rendering each model with [Object::render] or [render_pass], the opaque models are grouped by the configuration of their base material,
+/// such that the shader source is generated and the program is looked up once per configuration, the lights are only sent once to each program
+/// and only the uniform values which differ from the previously rendered instance are sent.
+/// The transparent models are rendered afterwards in the order given by their render order and distance to the camera, like in [render_pass].
+/// Must be called in a render target render function, for example in the callback function of [Screen::write].
+///
+pub fn render_material_instances(
+    camera: &Camera,
+    lights: &Lights,
+    models: &[&Model<PhysicalMaterialInstance>],
+) -> ThreeDResult<()> {
+    let mut opaque = Vec::new();
+    let mut transparent = Vec::new();
+    for model in models
+        .iter()
+        .filter(|m| camera.sphere_in_frustum(&m.bounding_sphere()) && camera.in_frustum(&m.aabb()))
+    {
+        if model.material.is_transparent() {
+            transparent.push(*model);
+        } else {
+            opaque.push(*model);
+        }
+    }
+
+    // Models with the same key are rendered with the same program
+    let key = |model: &Model<PhysicalMaterialInstance>| {
+        (
+            model.material.base().configuration_key(),
+            model.mesh.color_buffer.is_some(),
+            model.mesh.color_space == ColorSpace::Srgb,
+            model.mesh.morph_target_texture.is_some(),
+        )
+    };
+    opaque.sort_by_key(|m| key(m));
+    let lights_source = lights.fragment_shader_source();
+    let mut start = 0;
+    while start < opaque.len() {
+        let first = opaque[start];
+        let end = start
+            + opaque[start..]
+                .iter()
+                .take_while(|m| key(m) == key(first))
+                .count();
+        let group = &opaque[start..end];
+        start = end;
+        let fragment_shader_source = first.material.base().fragment_shader_source_with_lights(
+            first.mesh.color_buffer.is_some(),
+            lights_source.clone(),
+        );
+        first.context.program(
+            &first.model_vertex_shader_source(&fragment_shader_source, camera)?,
+            &fragment_shader_source,
+            |program| {
+                lights.use_uniforms(program, camera)?;
+                let mut previous = None;
+                for model in group.iter() {
+                    model.material.use_instance_uniforms(program, previous)?;
+                    model.draw(
+                        program,
+                        model.material.render_states(),
+                        camera,
+                        camera.viewport(),
+                        &model.transformation,
+                        &model.texture_transform,
+                        None,
+                    )?;
+                    previous = Some(&model.material);
+                }
+                Ok(())
+            },
+        )?;
+    }
+
+    transparent.sort_by(|a, b| cmp_render_order(camera, *a, *b));
+    for model in transparent {
+        model.render(camera, lights)?;
+    }
+    Ok(())
+}
+
+// The centroids of the triangles of a mesh and an index buffer with the triangles sorted back to front, see [Model::set_transparency_sorting]
+struct TriangleSorter {
+    centroids: Vec<Vec3>,
+    indices: Vec<u32>,
+    // The distance and index of each triangle and the sorted indices, which are kept to avoid allocating each time the triangles are sorted
+    keys: Vec<(f32, u32)>,
+    sorted_indices: Vec<u32>,
+    index_buffer: ElementBuffer,
+    // The camera position in the local space of the mesh used for the current order and how far it can move before sorting again
+    sorted_from: Option<Vec3>,
+    threshold: f32,
+}
+
+impl TriangleSorter {
+    fn new(context: &Context, cpu_mesh: &CPUMesh, threshold: f32) -> ThreeDResult<Self> {
+        let mut indices = Vec::new();
+        let mut centroids = Vec::new();
+        cpu_mesh.for_each_triangle(|i0, i1, i2| {
+            indices.extend_from_slice(&[i0 as u32, i1 as u32, i2 as u32]);
+            centroids.push(
+                (cpu_mesh.position(i0) + cpu_mesh.position(i1) + cpu_mesh.position(i2)) / 3.0,
+            );
+        });
+        let mut index_buffer = ElementBuffer::new::<u32>(context)?;
+        index_buffer.allocate::<u32>(indices.len())?;
+        Ok(Self {
+            keys: Vec::with_capacity(centroids.len()),
+            sorted_indices: Vec::with_capacity(indices.len()),
+            centroids,
+            indices,
+            index_buffer,
+            sorted_from: None,
+            threshold,
+        })
+    }
+
+    fn sort(&mut self, position: Vec3) {
+        if let Some(sorted_from) = self.sorted_from {
+            if sorted_from.distance2(position) <= self.threshold * self.threshold {
+                return;
+            }
+        }
+        self.keys.clear();
+        self.keys.extend(
+            self.centroids
+                .iter()
+                .enumerate()
+                .map(|(i, centroid)| (centroid.distance2(position), i as u32)),
+        );
+        self.keys
+            .sort_unstable_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        self.sorted_indices.clear();
+        for (_, triangle) in self.keys.iter() {
+            let i = 3 * *triangle as usize;
+            self.sorted_indices
+                .extend_from_slice(&self.indices[i..i + 3]);
+        }
+        self.index_buffer.fill_subset(0, &self.sorted_indices);
+        self.sorted_from = Some(position);
+    }
+}
+
+#[cfg(all(test, feature = "glutin-window", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::renderer::test_utils;
+
+    // A color material which displaces the vertices by an attribute that no mesh provides
+    struct BrokenMaterial(ColorMaterial);
+
+    impl Material for BrokenMaterial {
+        fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
+            self.0.fragment_shader_source(use_vertex_colors, lights)
+        }
+        fn use_uniforms(
+            &self,
+            program: &Program,
+            camera: &Camera,
+            lights: &Lights,
+        ) -> ThreeDResult<()> {
+            self.0.use_uniforms(program, camera, lights)
+        }
+        fn render_states(&self) -> RenderStates {
+            self.0.render_states()
+        }
+        fn is_transparent(&self) -> bool {
+            self.0.is_transparent()
+        }
+        fn vertex_displacement_source(&self) -> Option<String> {
+            Some(
+                "in vec2 a_uv;
+                vec3 displace_position(vec3 position) {
+                    return position + vec3(a_uv, 0.0);
+                }"
+                .to_string(),
+            )
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a graphics context"]
+    fn missing_attribute_is_reported_by_the_validation() {
+        let context = Context::new().unwrap();
+        context.set_validation(true);
+        let model = Model::new_with_material(
+            &context,
+            &CPUMesh::square(),
+            BrokenMaterial(ColorMaterial::default()),
+        )
+        .unwrap();
+
+        let error = test_utils::render(
+            &context,
+            16,
+            vec3(0.0, 0.0, 5.0),
+            &[&model],
+            &Lights::default(),
+        )
+        .unwrap_err();
+        match error.downcast_ref::<CoreError>() {
+            Some(CoreError::MissingAttributeBuffer(name)) => assert_eq!(name, "a_uv"),
+            _ => panic!("unexpected error: {}", error),
+        }
+        assert!(error.to_string().contains("a_uv"));
+
+        // Without the validation, the draw call silently renders nothing
+        context.set_validation(false);
+        test_utils::render(
+            &context,
+            16,
+            vec3(0.0, 0.0, 5.0),
+            &[&model],
+            &Lights::default(),
+        )
+        .unwrap();
+    }
 }
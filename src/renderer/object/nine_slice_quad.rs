@@ -0,0 +1,309 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+///
+/// The size of the borders of the texture of a [NineSliceQuad] in texels, ie. the parts of the texture which are not stretched.
+/// The bottom border is at the start of the texture data (the uv coordinate `v = 0`) like for [CPUMesh::square].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NineSliceInsets {
+    /// The width of the left border in texels.
+    pub left: f32,
+    /// The width of the right border in texels.
+    pub right: f32,
+    /// The height of the top border in texels.
+    pub top: f32,
+    /// The height of the bottom border in texels.
+    pub bottom: f32,
+}
+
+impl NineSliceInsets {
+    ///
+    /// Constructs new insets where all of the borders have the given size in texels.
+    ///
+    pub fn uniform(inset: f32) -> Self {
+        Self {
+            left: inset,
+            right: inset,
+            top: inset,
+            bottom: inset,
+        }
+    }
+}
+
+///
+/// Defines the size of the borders of a [NineSliceQuad].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BorderSizing {
+    /// One texel of the borders has the given size in the local space of the quad, so the borders get smaller on the screen as the distance to the camera increases.
+    World {
+        /// The size of one texel of the borders.
+        texel_size: f32,
+    },
+    /// One texel of the borders covers one pixel of the viewport, so the borders have the same thickness on the screen independent of the distance to the camera.
+    /// The mesh is computed from the camera each time the quad is rendered.
+    Screen,
+}
+
+///
+/// A rectangle with a texture which is scaled using nine-slice scaling, for example a panel of a user interface placed in the world.
+/// The texture is divided into nine slices by the [insets](NineSliceInsets), where the corners are not scaled,
+/// the edges are stretched along the edge and the center is stretched in both directions,
+/// so the same texture can be used for panels of any size and aspect ratio without distorting the borders.
+///
+/// The quad spans the xy-plane of its local space centered at the origin and facing the positive z-axis.
+/// It is rendered with an unlit [ColorMaterial] where the texture is multiplied with the [tint](NineSliceQuad::set_tint)
+/// and blended with what is behind it when the tint or the texture is transparent.
+///
+pub struct NineSliceQuad {
+    model: RefCell<Model<ColorMaterial>>,
+    size: Vec2,
+    insets: NineSliceInsets,
+    border_sizing: BorderSizing,
+    positions: Cell<[f32; 48]>,
+}
+
+impl NineSliceQuad {
+    ///
+    /// Constructs a new quad with the given texture, which can be shared between quads, the given insets in texels and the given size in the local space of the quad.
+    /// The borders have the size given by [BorderSizing::World] with a texel size of 0.01 until changed with [NineSliceQuad::set_border_sizing].
+    ///
+    pub fn new(
+        context: &Context,
+        texture: Rc<Texture2D<u8>>,
+        insets: NineSliceInsets,
+        size: Vec2,
+    ) -> ThreeDResult<Self> {
+        let (width, height) = (texture.width() as f32, texture.height() as f32);
+        let us = [0.0, insets.left / width, 1.0 - insets.right / width, 1.0];
+        let vs = [0.0, insets.bottom / height, 1.0 - insets.top / height, 1.0];
+        let mut uvs = Vec::with_capacity(32);
+        let mut indices = Vec::with_capacity(54);
+        for (j, v) in vs.iter().enumerate() {
+            for (i, u) in us.iter().enumerate() {
+                uvs.extend_from_slice(&[*u, *v]);
+                if i < 3 && j < 3 {
+                    let a = (j * 4 + i) as u8;
+                    indices.extend_from_slice(&[a, a + 1, a + 5, a + 5, a + 4, a]);
+                }
+            }
+        }
+        let cpu_mesh = CPUMesh {
+            name: "nine slice quad".to_string(),
+            positions: vec![0.0; 48],
+            normals: Some([0.0, 0.0, 1.0].repeat(16)),
+            uvs: Some(uvs),
+            indices: Some(Indices::U8(indices)),
+            ..Default::default()
+        };
+        let material = ColorMaterial {
+            texture: Some(texture),
+            ..Default::default()
+        };
+        let quad = Self {
+            model: RefCell::new(Model::new_with_material(context, &cpu_mesh, material)?),
+            size,
+            insets,
+            border_sizing: BorderSizing::World { texel_size: 0.01 },
+            positions: Cell::new([0.0; 48]),
+        };
+        quad.update(None);
+        Ok(quad)
+    }
+
+    ///
+    /// Sets the size of the quad in its local space, which updates the positions of the mesh without allocating new buffers.
+    /// If the quad is smaller than the borders, the borders are shrunk to fit.
+    ///
+    pub fn set_size(&mut self, size: Vec2) {
+        self.size = size;
+        self.update(None);
+    }
+
+    ///
+    /// Returns the size of the quad in its local space.
+    ///
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    ///
+    /// Returns the insets of the borders of the texture in texels.
+    ///
+    pub fn insets(&self) -> NineSliceInsets {
+        self.insets
+    }
+
+    ///
+    /// Sets how the size of the borders is defined, see [BorderSizing].
+    ///
+    pub fn set_border_sizing(&mut self, border_sizing: BorderSizing) {
+        self.border_sizing = border_sizing;
+        self.update(None);
+    }
+
+    ///
+    /// Returns how the size of the borders is defined, see [BorderSizing].
+    ///
+    pub fn border_sizing(&self) -> BorderSizing {
+        self.border_sizing
+    }
+
+    ///
+    /// Sets the color which is multiplied with the texture. The default is white, which keeps the texture colors,
+    /// and a tint with an alpha value less than 255 makes the quad transparent.
+    ///
+    pub fn set_tint(&mut self, tint: Color) {
+        self.model.get_mut().material.color = tint;
+    }
+
+    ///
+    /// Returns the color which is multiplied with the texture, see [NineSliceQuad::set_tint].
+    ///
+    pub fn tint(&self) -> Color {
+        self.model.borrow().material.color
+    }
+
+    // Computes the positions of the 4x4 vertices and writes them to the mesh if they have changed,
+    // where the screen sizing requires a camera and the positions are kept until the quad is rendered if none is given
+    fn update(&self, camera: Option<&Camera>) {
+        let mut model = self.model.borrow_mut();
+        let texel_size = match self.border_sizing {
+            BorderSizing::World { texel_size } => vec2(texel_size, texel_size),
+            BorderSizing::Screen => {
+                if let Some(camera) = camera {
+                    // The size of a pixel at the distance of the center of the quad in the local space of the quad
+                    let transformation = model.transformation();
+                    let pixel_size = 2.0
+                        / (camera.projection().y.y * camera.viewport().height as f32)
+                        * match camera.projection_type() {
+                            ProjectionType::Perspective { .. } => {
+                                (camera.view() * transformation.w).z.abs()
+                            }
+                            ProjectionType::Orthographic { .. } => 1.0,
+                        };
+                    vec2(
+                        pixel_size / transformation.x.truncate().magnitude(),
+                        pixel_size / transformation.y.truncate().magnitude(),
+                    )
+                } else {
+                    return;
+                }
+            }
+        };
+
+        // The borders are shrunk to fit when they are larger than the quad
+        let fit = |start: f32, end: f32, size: f32, texel_size: f32| {
+            let (start, end) = (start * texel_size, end * texel_size);
+            let scale = if start + end > size {
+                size / (start + end)
+            } else {
+                1.0
+            };
+            let half = 0.5 * size;
+            [-half, -half + start * scale, half - end * scale, half]
+        };
+        let xs = fit(
+            self.insets.left,
+            self.insets.right,
+            self.size.x,
+            texel_size.x,
+        );
+        let ys = fit(
+            self.insets.bottom,
+            self.insets.top,
+            self.size.y,
+            texel_size.y,
+        );
+        let mut positions = [0.0; 48];
+        for (j, y) in ys.iter().enumerate() {
+            for (i, x) in xs.iter().enumerate() {
+                let index = 3 * (j * 4 + i);
+                positions[index] = *x;
+                positions[index + 1] = *y;
+            }
+        }
+        if positions != self.positions.get() {
+            model.update_positions(&positions);
+            self.positions.set(positions);
+        }
+    }
+}
+
+impl Shadable for NineSliceQuad {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.update(Some(camera));
+        self.model
+            .borrow()
+            .render_with_material(material, camera, lights)
+    }
+
+    fn render_forward(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.render_with_material(material, camera, lights)
+    }
+
+    #[allow(deprecated)]
+    fn render_deferred(
+        &self,
+        material: &DeferredPhysicalMaterial,
+        camera: &Camera,
+        viewport: Viewport,
+    ) -> ThreeDResult<()> {
+        self.update(Some(camera));
+        self.model
+            .borrow()
+            .render_deferred(material, camera, viewport)
+    }
+}
+
+impl Geometry for NineSliceQuad {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.model.borrow().aabb()
+    }
+
+    fn bounding_sphere(&self) -> BoundingSphere {
+        self.model.borrow().bounding_sphere()
+    }
+
+    fn transformation(&self) -> Mat4 {
+        self.model.borrow().transformation()
+    }
+
+    fn change_count(&self) -> u64 {
+        self.model.borrow().change_count()
+    }
+}
+
+impl GeometryMut for NineSliceQuad {
+    fn set_transformation(&mut self, transformation: Mat4) {
+        self.model.get_mut().set_transformation(transformation);
+    }
+}
+
+impl Object for NineSliceQuad {
+    fn render(&self, camera: &Camera, lights: &Lights) -> ThreeDResult<()> {
+        self.update(Some(camera));
+        self.model.borrow().render(camera, lights)
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.model.borrow().is_transparent()
+    }
+
+    fn material_id(&self) -> u64 {
+        self.model.borrow().material_id()
+    }
+}
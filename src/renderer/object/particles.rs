@@ -11,6 +11,27 @@ pub struct ParticleData {
     pub start_velocity: Vec3,
 }
 
+///
+/// Settings for soft particles, ie. particles which fade out where they intersect the scene geometry instead of showing a hard seam,
+/// see [Particles::soft_particles].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoftParticles {
+    /// The distance in view space over which a particle fades out as it gets closer to the scene geometry behind it.
+    pub fade_distance: f32,
+    /// The distance in view space over which a particle fades out as it gets closer to the near plane of the camera, which avoids particles popping when they are clipped.
+    pub near_fade_distance: f32,
+}
+
+impl Default for SoftParticles {
+    fn default() -> Self {
+        Self {
+            fade_distance: 1.0,
+            near_fade_distance: 0.5,
+        }
+    }
+}
+
 ///
 /// Particle effect that can be rendered with any material.
 ///
@@ -18,6 +39,8 @@ pub struct ParticleData {
 /// Then when time passes, their position is updated based on
 /// `new_position = start_position + start_velocity * time + 0.5 * acceleration * time * time`
 ///
+/// The particles can be rendered as soft particles, see [Particles::soft_particles] and [Particles::render_with_scene_depth].
+///
 pub struct Particles {
     context: Context,
     start_position_buffer: InstanceBuffer,
@@ -33,6 +56,12 @@ pub struct Particles {
     normal_transformation: Mat4,
     /// A time variable that should be updated each frame.
     pub time: f32,
+    ///
+    /// Enables soft particles with the given settings when the particles are rendered using [Particles::render_with_scene_depth] with a scene depth texture.
+    /// The fragment shader of the material must multiply the alpha value of the output color with `soft_particle_fade()` when `SOFT_PARTICLES` is defined,
+    /// like the [ColorMaterial] does. The default is `None`, which disables soft particles.
+    ///
+    pub soft_particles: Option<SoftParticles>,
 }
 
 impl Particles {
@@ -75,6 +104,7 @@ impl Particles {
             transformation: Mat4::identity(),
             normal_transformation: Mat4::identity(),
             time: 0.0,
+            soft_particles: None,
         })
     }
 
@@ -100,6 +130,98 @@ impl Particles {
         self.instance_count = data.len() as u32;
     }
 
+    ///
+    /// Render the particles with the given material like [Shadable::render_with_material],
+    /// but as soft particles which fade out where they intersect the scene geometry given by the scene depth texture, see [Particles::soft_particles].
+    /// The scene depth texture must contain the depth of the scene rendered with the given camera and have the same size as the render target that the particles are rendered into.
+    /// Since a depth texture cannot be sampled while it is used for depth testing, render the particles into a render target without a depth texture,
+    /// for example [Texture2D::write], the particles behind the scene geometry are faded out completely anyway.
+    /// If no scene depth texture is given or soft particles are disabled, the particles are rendered as usual.
+    ///
+    pub fn render_with_scene_depth(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+        scene_depth: Option<&DepthTargetTexture2D>,
+    ) -> ThreeDResult<()> {
+        let soft_particles = self
+            .soft_particles
+            .and_then(|s| scene_depth.map(|d| (s, d)));
+        let mut fragment_shader_source = material.fragment_shader_source(false, lights);
+        if soft_particles.is_some() {
+            fragment_shader_source = format!(
+                "{}{}",
+                include_str!("shaders/soft_particles.frag"),
+                fragment_shader_source
+            );
+        }
+        self.context.program(
+            &Particles::vertex_shader_source(&fragment_shader_source),
+            &fragment_shader_source,
+            |program| {
+                material.use_uniforms(program, camera, lights)?;
+                if let Some((settings, scene_depth)) = soft_particles {
+                    program.use_texture("sceneDepthMap", scene_depth)?;
+                    // The inverse projection which also takes the depth mode into account
+                    program.use_uniform(
+                        "softParticleProjectionInverse",
+                        camera.view() * camera.view_projection_inverse(),
+                    )?;
+                    program.use_uniform("softParticleZNear", camera.z_near())?;
+                    program.use_uniform("softParticleFadeDistance", settings.fade_distance)?;
+                    program
+                        .use_uniform("softParticleNearFadeDistance", settings.near_fade_distance)?;
+                }
+
+                program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+                program.use_uniform_vec3("acceleration", &self.acceleration)?;
+                program.use_uniform_float("time", &self.time)?;
+                program.use_uniform_block("Camera", camera.uniform_buffer());
+
+                program
+                    .use_attribute_vec3_instanced("start_position", &self.start_position_buffer)?;
+                program
+                    .use_attribute_vec3_instanced("start_velocity", &self.start_velocity_buffer)?;
+                if program.requires_attribute("position") {
+                    program.use_attribute_vec3("position", &self.position_buffer)?;
+                }
+                if program.requires_attribute("uv_coordinates") {
+                    let uv_buffer = self
+                        .uv_buffer
+                        .as_ref()
+                        .ok_or(CoreError::MissingMeshBuffer("uv coordinate".to_string()))?;
+                    program.use_attribute_vec2("uv_coordinates", uv_buffer)?;
+                }
+                if program.requires_attribute("normal") {
+                    let normal_buffer = self
+                        .normal_buffer
+                        .as_ref()
+                        .ok_or(CoreError::MissingMeshBuffer("normal".to_string()))?;
+                    program.use_uniform_mat4("normalMatrix", &self.normal_transformation)?;
+                    program.use_attribute_vec3("normal", normal_buffer)?;
+                }
+
+                if let Some(ref index_buffer) = self.index_buffer {
+                    program.draw_elements_instanced(
+                        material.render_states(),
+                        camera.viewport(),
+                        index_buffer,
+                        self.instance_count,
+                    );
+                } else {
+                    program.draw_arrays_instanced(
+                        material.render_states(),
+                        camera.viewport(),
+                        self.position_buffer.count() as u32 / 3,
+                        self.instance_count,
+                    );
+                }
+                Ok(())
+            },
+        )
+    }
+
     pub(crate) fn vertex_shader_source(fragment_shader_source: &str) -> String {
         let use_positions = fragment_shader_source.find("in vec3 pos;").is_some();
         let use_normals = fragment_shader_source.find("in vec3 nor;").is_some();
@@ -177,59 +299,7 @@ impl Shadable for Particles {
         camera: &Camera,
         lights: &Lights,
     ) -> ThreeDResult<()> {
-        let fragment_shader_source = material.fragment_shader_source(false, lights);
-        self.context.program(
-            &Particles::vertex_shader_source(&fragment_shader_source),
-            &fragment_shader_source,
-            |program| {
-                material.use_uniforms(program, camera, lights)?;
-
-                program.use_uniform_mat4("modelMatrix", &self.transformation)?;
-                program.use_uniform_vec3("acceleration", &self.acceleration)?;
-                program.use_uniform_float("time", &self.time)?;
-                program.use_uniform_block("Camera", camera.uniform_buffer());
-
-                program
-                    .use_attribute_vec3_instanced("start_position", &self.start_position_buffer)?;
-                program
-                    .use_attribute_vec3_instanced("start_velocity", &self.start_velocity_buffer)?;
-                if program.requires_attribute("position") {
-                    program.use_attribute_vec3("position", &self.position_buffer)?;
-                }
-                if program.requires_attribute("uv_coordinates") {
-                    let uv_buffer = self
-                        .uv_buffer
-                        .as_ref()
-                        .ok_or(CoreError::MissingMeshBuffer("uv coordinate".to_string()))?;
-                    program.use_attribute_vec2("uv_coordinates", uv_buffer)?;
-                }
-                if program.requires_attribute("normal") {
-                    let normal_buffer = self
-                        .normal_buffer
-                        .as_ref()
-                        .ok_or(CoreError::MissingMeshBuffer("normal".to_string()))?;
-                    program.use_uniform_mat4("normalMatrix", &self.normal_transformation)?;
-                    program.use_attribute_vec3("normal", normal_buffer)?;
-                }
-
-                if let Some(ref index_buffer) = self.index_buffer {
-                    program.draw_elements_instanced(
-                        material.render_states(),
-                        camera.viewport(),
-                        index_buffer,
-                        self.instance_count,
-                    );
-                } else {
-                    program.draw_arrays_instanced(
-                        material.render_states(),
-                        camera.viewport(),
-                        self.position_buffer.count() as u32 / 3,
-                        self.instance_count,
-                    );
-                }
-                Ok(())
-            },
-        )
+        self.render_with_scene_depth(material, camera, lights, None)
     }
 
     fn render_forward(
@@ -0,0 +1,296 @@
+use crate::core::*;
+
+///
+/// The shape of the marker drawn at each point of a [PointCloud].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MarkerShape {
+    /// A filled circle.
+    Circle,
+    /// A filled square.
+    Square,
+    /// A cross with the arms along the horizontal and vertical axes of the screen.
+    Cross,
+}
+
+///
+/// Defines the unit of the sizes of the markers in a [PointCloud].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MarkerSizing {
+    /// The size is in pixels, ie. the markers have the same size on the screen independent of the distance to the camera.
+    Screen,
+    /// The size is in world space, ie. the markers get smaller as the distance to the camera increases.
+    World,
+}
+
+///
+/// A large number of points, each drawn as a small marker with its own color and size which always faces the camera.
+/// All of the markers are rendered in a single instanced draw call, which is much faster than a [Model](crate::Model) for each point.
+/// The markers are drawn as quads instead of using `gl_PointSize`, since the maximum point size is very small on many WebGL implementations,
+/// and the shape is computed from a signed distance function, so the edges are crisp at any size.
+///
+pub struct PointCloud {
+    context: Context,
+    /// The shape of the markers.
+    pub shape: MarkerShape,
+    /// The unit of the sizes of the markers.
+    pub sizing: MarkerSizing,
+    /// Whether or not the markers are hidden by the geometry in front of them and hide the geometry behind them.
+    pub depth_test: bool,
+    /// The color space of the colors of the points. The default is [ColorSpace::Linear],
+    /// except for a point cloud constructed using [PointCloud::new_with_cpu_mesh] which uses the color space of the mesh.
+    pub color_space: ColorSpace,
+    corner_buffer: VertexBuffer,
+    position_buffer: InstanceBuffer,
+    color_buffer: InstanceBuffer,
+    size_buffer: InstanceBuffer,
+    count: usize,
+    capacity: usize,
+    max_size: f32,
+    aabb_local: AxisAlignedBoundingBox,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+}
+
+impl PointCloud {
+    ///
+    /// Constructs a new point cloud with a marker at each of the given positions with the corresponding color and size.
+    ///
+    /// # Errors
+    /// Will return an error if the number of colors or sizes is different from the number of positions.
+    ///
+    pub fn new(
+        context: &Context,
+        positions: &[Vec3],
+        colors: &[Color],
+        sizes: &[f32],
+    ) -> ThreeDResult<Self> {
+        let corners = vec![
+            -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, 1.0, 1.0, -1.0, 1.0, -1.0, -1.0,
+        ];
+        let mut point_cloud = Self {
+            context: context.clone(),
+            shape: MarkerShape::Circle,
+            sizing: MarkerSizing::Screen,
+            depth_test: true,
+            color_space: ColorSpace::Linear,
+            corner_buffer: VertexBuffer::new_with_static(context, &corners)?,
+            position_buffer: InstanceBuffer::new(context)?,
+            color_buffer: InstanceBuffer::new(context)?,
+            size_buffer: InstanceBuffer::new(context)?,
+            count: 0,
+            capacity: 0,
+            max_size: 0.0,
+            aabb_local: AxisAlignedBoundingBox::EMPTY,
+            aabb: AxisAlignedBoundingBox::EMPTY,
+            transformation: Mat4::identity(),
+        };
+        point_cloud.set_points(positions, colors, sizes)?;
+        Ok(point_cloud)
+    }
+
+    ///
+    /// Constructs a new point cloud with a marker with the given size at each vertex of the given mesh,
+    /// for example a point cloud loaded from a .ply file.
+    /// The colors of the vertices are used if present, otherwise the markers are white.
+    ///
+    pub fn new_with_cpu_mesh(
+        context: &Context,
+        cpu_mesh: &CPUMesh,
+        size: f32,
+    ) -> ThreeDResult<Self> {
+        let positions = cpu_mesh
+            .positions
+            .chunks(3)
+            .map(|p| vec3(p[0], p[1], p[2]))
+            .collect::<Vec<_>>();
+        let colors = if let Some(ref colors) = cpu_mesh.colors {
+            colors
+                .chunks(4)
+                .map(|c| Color::new(c[0], c[1], c[2], c[3]))
+                .collect::<Vec<_>>()
+        } else {
+            vec![Color::WHITE; positions.len()]
+        };
+        let mut point_cloud =
+            Self::new(context, &positions, &colors, &vec![size; positions.len()])?;
+        point_cloud.color_space = cpu_mesh.color_space;
+        Ok(point_cloud)
+    }
+
+    ///
+    /// Replaces the points with the given positions, colors and sizes.
+    /// The buffers are only reallocated when the number of points is larger than ever before,
+    /// so the points can be updated every frame without allocating GPU memory.
+    ///
+    /// # Errors
+    /// Will return an error if the number of colors or sizes is different from the number of positions.
+    ///
+    pub fn set_points(
+        &mut self,
+        positions: &[Vec3],
+        colors: &[Color],
+        sizes: &[f32],
+    ) -> ThreeDResult<()> {
+        if colors.len() != positions.len() {
+            Err(CoreError::InvalidPointCloudLength(
+                "colors".to_string(),
+                colors.len(),
+                positions.len(),
+            ))?;
+        }
+        if sizes.len() != positions.len() {
+            Err(CoreError::InvalidPointCloudLength(
+                "sizes".to_string(),
+                sizes.len(),
+                positions.len(),
+            ))?;
+        }
+        let mut position_data = Vec::with_capacity(positions.len() * 3);
+        for p in positions {
+            position_data.extend_from_slice(&[p.x, p.y, p.z]);
+        }
+        let mut color_data = Vec::with_capacity(colors.len() * 4);
+        for c in colors {
+            color_data.extend_from_slice(&[c.r, c.g, c.b, c.a]);
+        }
+
+        let count = positions.len();
+        if count > self.capacity {
+            self.position_buffer.allocate::<f32>(count * 3);
+            self.color_buffer.allocate::<u8>(count * 4);
+            self.size_buffer.allocate::<f32>(count);
+            self.capacity = count;
+        }
+        if count > 0 {
+            self.position_buffer.fill_subset(0, &position_data);
+            self.color_buffer.fill_subset(0, &color_data);
+            self.size_buffer.fill_subset(0, sizes);
+        }
+        self.count = count;
+        self.max_size = sizes.iter().fold(0.0f32, |a, b| a.max(*b));
+        self.aabb_local = AxisAlignedBoundingBox::new_with_positions(&position_data);
+        self.update_aabb();
+        Ok(())
+    }
+
+    ///
+    /// Returns the number of points.
+    ///
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    ///
+    /// Returns whether or not the point cloud is transparent, which is always the case since the antialiased edges of the markers are blended with the background.
+    ///
+    pub fn is_transparent(&self) -> bool {
+        true
+    }
+
+    ///
+    /// Renders all of the markers in a single draw call.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn render(&self, camera: &Camera) -> ThreeDResult<()> {
+        if self.count == 0 {
+            return Ok(());
+        }
+        let render_states = if self.depth_test {
+            RenderStates {
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            }
+        } else {
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTest::Always,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            }
+        };
+        let vertex_shader_source = match self.sizing {
+            MarkerSizing::Screen => format!(
+                "#define SCREEN_SPACE_SIZE\n{}",
+                include_str!("shaders/point_cloud.vert")
+            ),
+            MarkerSizing::World => include_str!("shaders/point_cloud.vert").to_string(),
+        };
+        self.context.program(
+            &vertex_shader_source,
+            &format!(
+                "{}{}{}",
+                if self.color_space == ColorSpace::Srgb {
+                    "#define SRGB_VERTEX_COLORS\n"
+                } else {
+                    ""
+                },
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/point_cloud.frag")
+            ),
+            |program| {
+                let viewport = camera.viewport();
+                program.use_uniform_block("Camera", camera.uniform_buffer());
+                program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+                program.use_uniform_vec2(
+                    "viewportSize",
+                    &vec2(viewport.width as f32, viewport.height as f32),
+                )?;
+                program.use_uniform_int(
+                    "shape",
+                    &match self.shape {
+                        MarkerShape::Circle => 0,
+                        MarkerShape::Square => 1,
+                        MarkerShape::Cross => 2,
+                    },
+                )?;
+                program.use_attribute_vec2("corner", &self.corner_buffer)?;
+                program.use_attribute_vec3_instanced("center", &self.position_buffer)?;
+                program.use_attribute_vec4_instanced("color", &self.color_buffer)?;
+                program.use_attribute_instanced("size", &self.size_buffer)?;
+                program.draw_arrays_instanced(render_states, viewport, 6, self.count as u32);
+                Ok(())
+            },
+        )
+    }
+
+    ///
+    /// Returns the axis aligned bounding box which contains all of the points.
+    /// If the sizes are in world space, the box is expanded by the radius of the largest marker.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        if self.sizing == MarkerSizing::World && !self.aabb.is_empty() {
+            let radius = vec3(1.0, 1.0, 1.0) * (0.5 * self.max_size);
+            let min = self.aabb.min() - radius;
+            let max = self.aabb.max() + radius;
+            AxisAlignedBoundingBox::new_with_positions(&[min.x, min.y, min.z, max.x, max.y, max.z])
+        } else {
+            self.aabb
+        }
+    }
+
+    ///
+    /// Returns the local to world transformation applied to the points.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to the points.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        self.update_aabb();
+    }
+
+    fn update_aabb(&mut self) {
+        let mut aabb = self.aabb_local;
+        if !aabb.is_empty() {
+            aabb.transform(&self.transformation);
+        }
+        self.aabb = aabb;
+    }
+}
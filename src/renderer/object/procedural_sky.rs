@@ -0,0 +1,257 @@
+use crate::core::*;
+use crate::renderer::*;
+
+// The intensity of the sun light before it is attenuated by the atmosphere
+const SUN_INTENSITY: f32 = 3.0;
+// The radiance of the sun disk relative to the color of the sun
+const SUN_DISK_INTENSITY: f32 = 50.0;
+
+///
+/// A sky computed from the direction of the sun and the turbidity of the atmosphere using the analytic daylight model by Preetham et al.,
+/// which makes it possible to animate the time of day, unlike a [Skybox] with a fixed texture.
+/// The sun is below the horizon when the [sun direction](ProceduralSky::sun_direction) points upwards, in which case the sky fades to a dark night sky.
+///
+/// Use [ProceduralSky::sun_direction], [ProceduralSky::sun_color] and [ProceduralSky::sun_intensity] to set up a [DirectionalLight] which matches the sky
+/// and [ProceduralSky::bake_to_cubemap] to create an [Environment] for the ambient light.
+///
+pub struct ProceduralSky {
+    context: Context,
+    vertex_buffer: VertexBuffer,
+    sun_direction: Vec3,
+    turbidity: f32,
+    ///
+    /// The colors of the sky are multiplied by the exposure before they are tone mapped
+    /// the same way as the [PhysicalMaterial](crate::PhysicalMaterial), which can be used to match the brightness of the sky to the rest of the scene.
+    ///
+    pub exposure: f32,
+}
+
+impl ProceduralSky {
+    ///
+    /// Creates a new sky with the sun shining in the given direction, ie. the direction from the sun, and with the given turbidity.
+    /// See [ProceduralSky::set_turbidity] for a description of the turbidity.
+    ///
+    pub fn new(context: &Context, sun_direction: &Vec3, turbidity: f32) -> ThreeDResult<Self> {
+        let mut sky = Self {
+            context: context.clone(),
+            vertex_buffer: VertexBuffer::new_with_static(context, &CPUMesh::cube().positions)?,
+            sun_direction: vec3(0.0, -1.0, 0.0),
+            turbidity: 2.0,
+            exposure: 1.0,
+        };
+        sky.set_sun_direction(sun_direction);
+        sky.set_turbidity(turbidity);
+        Ok(sky)
+    }
+
+    ///
+    /// Sets the direction the sun is shining in, ie. the direction from the sun, which is also the direction of a [DirectionalLight] representing the sun.
+    ///
+    pub fn set_sun_direction(&mut self, sun_direction: &Vec3) {
+        self.sun_direction = sun_direction.normalize();
+    }
+
+    ///
+    /// Returns the direction the sun is shining in, ie. the direction from the sun, see [ProceduralSky::set_sun_direction].
+    ///
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    ///
+    /// Sets the turbidity of the atmosphere, ie. how hazy it is, which is clamped to the range `[1.7, 10.0]`.
+    /// A turbidity of 2 corresponds to a very clear sky and a turbidity of 10 to a hazy sky.
+    ///
+    pub fn set_turbidity(&mut self, turbidity: f32) {
+        self.turbidity = turbidity.max(1.7).min(10.0);
+    }
+
+    ///
+    /// Returns the turbidity of the atmosphere, see [ProceduralSky::set_turbidity].
+    ///
+    pub fn turbidity(&self) -> f32 {
+        self.turbidity
+    }
+
+    ///
+    /// Returns the color of the sun light when it reaches the ground, which gets more red as the sun gets closer to the horizon.
+    ///
+    pub fn sun_color(&self) -> Color {
+        let transmittance = self.sun_transmittance();
+        let max = transmittance.x.max(transmittance.y).max(transmittance.z);
+        if max > 0.0 {
+            let color = transmittance / max;
+            Color::from_rgb_slice(&[color.x, color.y, color.z])
+        } else {
+            Color::BLACK
+        }
+    }
+
+    ///
+    /// Returns the intensity of the sun light when it reaches the ground, which is zero when the sun is below the horizon.
+    ///
+    pub fn sun_intensity(&self) -> f32 {
+        let transmittance = self.sun_transmittance();
+        SUN_INTENSITY * transmittance.x.max(transmittance.y).max(transmittance.z)
+    }
+
+    ///
+    /// Renders the sky into a new cube map with the given resolution of each side, without the sun disk.
+    /// The cube map can for example be used to create an [Environment] which lights the scene with the same sky.
+    ///
+    pub fn bake_to_cubemap(&self, resolution: u32) -> ThreeDResult<TextureCubeMap<f16>> {
+        let mut texture = TextureCubeMap::new_empty(
+            &self.context,
+            resolution,
+            resolution,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            Some(Interpolation::Linear),
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        {
+            let effect = ImageCubeEffect::new(
+                &self.context,
+                &format!(
+                    "{}{}
+                    in vec3 pos;
+                    layout (location = 0) out vec4 outColor;
+                    void main()
+                    {{
+                        outColor = vec4(sky_color(normalize(pos)), 1.0);
+                    }}",
+                    include_str!("../../core/shared.frag"),
+                    include_str!("shaders/procedural_sky.frag")
+                ),
+            )?;
+            self.use_uniforms(&effect, 0.0)?;
+            let render_target = RenderTargetCubeMap::new_color(&self.context, &mut texture)?;
+            let viewport = Viewport::new_at_origo(resolution, resolution);
+            for side in CubeMapSide::iter() {
+                render_target.write_to_mip_level(side, 0, ClearState::default(), || {
+                    effect.render(side, RenderStates::default(), viewport)
+                })?;
+            }
+        }
+        texture.generate_mip_maps();
+        Ok(texture)
+    }
+
+    fn use_uniforms(&self, program: &Program, sun_disk_intensity: f32) -> ThreeDResult<()> {
+        let sun_color = self.sun_transmittance();
+        program.use_uniform_vec3("sunDirection", &-self.sun_direction)?;
+        program.use_uniform_vec3("sunColor", &sun_color)?;
+        program.use_uniform_float("sunDiskIntensity", &sun_disk_intensity)?;
+        program.use_uniform_float("turbidity", &self.turbidity)
+    }
+
+    // The fraction of the red, green and blue sun light which passes through the atmosphere due to Rayleigh and aerosol scattering
+    fn sun_transmittance(&self) -> Vec3 {
+        let cos_zenith = -self.sun_direction.y;
+        if cos_zenith < -0.05 {
+            return vec3(0.0, 0.0, 0.0);
+        }
+        // The relative optical air mass, see F. Kasten and A. T. Young, "Revised optical air mass tables and approximation formula"
+        let zenith_degrees = cos_zenith.max(0.0).acos().to_degrees();
+        let air_mass = 1.0 / (cos_zenith.max(0.0) + 0.15 * (93.885 - zenith_degrees).powf(-1.253));
+        // The optical depths at the wavelengths 680, 550 and 440 nm, where the aerosol part follows the Angstrom formula with the turbidity from the Preetham model
+        let beta = 0.04608 * self.turbidity - 0.04586;
+        let rayleigh = vec3(0.04, 0.1, 0.24);
+        let aerosol = vec3(0.68f32, 0.55, 0.44).map(|wavelength| beta * wavelength.powf(-1.3));
+        let optical_depth = (rayleigh + aerosol) * air_mass;
+        // Fade out as the sun sets below the horizon
+        let fade = ((cos_zenith + 0.05) / 0.05).min(1.0);
+        vec3(
+            (-optical_depth.x).exp(),
+            (-optical_depth.y).exp(),
+            (-optical_depth.z).exp(),
+        ) * fade
+    }
+}
+
+impl Shadable for ProceduralSky {
+    ///
+    /// The sky is not affected by materials, so this does nothing.
+    ///
+    fn render_with_material(
+        &self,
+        _material: &dyn Material,
+        _camera: &Camera,
+        _lights: &Lights,
+    ) -> ThreeDResult<()> {
+        Ok(())
+    }
+
+    fn render_forward(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.render_with_material(material, camera, lights)
+    }
+
+    #[allow(deprecated)]
+    fn render_deferred(
+        &self,
+        _material: &DeferredPhysicalMaterial,
+        _camera: &Camera,
+        _viewport: Viewport,
+    ) -> ThreeDResult<()> {
+        Ok(())
+    }
+}
+
+impl Geometry for ProceduralSky {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::INFINITE
+    }
+
+    fn transformation(&self) -> Mat4 {
+        Mat4::identity()
+    }
+}
+
+impl Object for ProceduralSky {
+    ///
+    /// Renders the sky behind everything else, ie. at the far plane of the camera.
+    ///
+    fn render(&self, camera: &Camera, _lights: &Lights) -> ThreeDResult<()> {
+        let render_states = RenderStates {
+            depth_test: DepthTest::LessOrEqual,
+            cull: Cull::Front,
+            ..Default::default()
+        };
+        self.context.program(
+            include_str!("shaders/skybox.vert"),
+            &format!(
+                "{}{}
+                uniform float exposure;
+                in vec3 coords;
+                layout (location = 0) out vec4 outColor;
+                void main()
+                {{
+                    outColor = vec4(tone_map_and_encode_output(exposure * sky_color(normalize(coords))), 1.0);
+                }}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/procedural_sky.frag")
+            ),
+            |program| {
+                self.use_uniforms(program, SUN_DISK_INTENSITY)?;
+                program.use_uniform_float("exposure", &self.exposure)?;
+                program.use_uniform_block("Camera", camera.uniform_buffer());
+                program.use_uniform_float("farPlaneZ", &camera.far_plane_z())?;
+                program.use_attribute_vec3("position", &self.vertex_buffer)?;
+                program.draw_arrays(render_states, camera.viewport(), 36);
+                Ok(())
+            },
+        )
+    }
+
+    fn is_transparent(&self) -> bool {
+        false
+    }
+}
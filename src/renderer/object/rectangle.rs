@@ -1,5 +1,10 @@
+use super::OverlayMaterial;
 use crate::renderer::*;
 
+///
+/// A rectangle in 2D pixel coordinates which can be rendered using any material, for example on top of a 3D scene using [render_overlay].
+/// The pixel coordinates are physical pixels relative to the [anchor](Rectangle::set_anchor) of the viewport, with the y-coordinate increasing downwards.
+///
 #[derive(Clone)]
 pub struct Rectangle<M: Material> {
     model: Model<M>,
@@ -8,6 +13,7 @@ pub struct Rectangle<M: Material> {
     height: f32,
     center: Vec2,
     rotation: Radians,
+    anchor: Anchor,
 }
 
 impl<M: Material> Rectangle<M> {
@@ -23,6 +29,7 @@ impl<M: Material> Rectangle<M> {
         mesh.transform(&(Mat4::from_scale(0.5)));
         let mut rectangle = Self {
             model: Model::new_with_material(context, &mesh, material)?,
+            anchor: Anchor::TopLeft,
             context: context.clone(),
             width,
             height,
@@ -61,6 +68,21 @@ impl<M: Material> Rectangle<M> {
         self.rotation
     }
 
+    ///
+    /// Sets the point of the viewport which the pixel coordinates of this rectangle are relative to, which defaults to [Anchor::TopLeft].
+    /// For example, a rectangle anchored to [Anchor::TopRight] with negative x-coordinates and positive y-coordinates stays in the top right corner when the viewport is resized.
+    ///
+    pub fn set_anchor(&mut self, anchor: Anchor) {
+        self.anchor = anchor;
+    }
+
+    ///
+    /// Returns the point of the viewport which the pixel coordinates of this rectangle are relative to, see [Rectangle::set_anchor].
+    ///
+    pub fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+
     fn update(&mut self) {
         self.model.set_transformation_2d(
             Mat3::from_translation(self.center)
@@ -76,7 +98,7 @@ impl<M: Material> Shadable2D for Rectangle<M> {
         material: &dyn Material,
         viewport: Viewport,
     ) -> ThreeDResult<()> {
-        self.context.camera2d(viewport, |camera2d| {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
             self.model
                 .render_with_material(material, camera2d, &Lights::default())
         })
@@ -89,11 +111,21 @@ impl<M: Material> Shadable2D for Rectangle<M> {
 
 impl<M: Material> Object2D for Rectangle<M> {
     fn render(&self, viewport: Viewport) -> ThreeDResult<()> {
-        self.context.camera2d(viewport, |camera2d| {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
             self.model.render(camera2d, &Lights::default())
         })
     }
 
+    fn render_as_overlay(&self, viewport: Viewport) -> ThreeDResult<()> {
+        self.context.camera2d_at(viewport, self.anchor, |camera2d| {
+            self.model.render_with_material(
+                &OverlayMaterial(&self.model.material),
+                camera2d,
+                &Lights::default(),
+            )
+        })
+    }
+
     fn is_transparent(&self) -> bool {
         self.model.is_transparent()
     }
@@ -0,0 +1,30 @@
+uniform sampler2D tex;
+uniform vec4 region;
+uniform vec2 valueRange;
+uniform vec2 depthRange;
+
+// 0: color, 1: depth, 2: depth from a perspective projection converted to distance
+uniform int mode;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 value = texture(tex, region.xy + uv * region.zw);
+    float scale = 1.0 / max(valueRange.y - valueRange.x, 0.000001);
+    if (mode == 0) {
+        outColor = vec4(clamp((value.rgb - valueRange.x) * scale, 0.0, 1.0), 1.0);
+    } else {
+        float depth = value.r;
+        if (mode == 2) {
+            float z_near = depthRange.x;
+            float z_far = depthRange.y;
+            float z = 2.0 * depth - 1.0;
+            depth = 2.0 * z_near * z_far / (z_far + z_near - z * (z_far - z_near));
+        }
+        float v = clamp((depth - valueRange.x) * scale, 0.0, 1.0);
+        outColor = vec4(v, v, v, 1.0);
+    }
+}
@@ -0,0 +1,34 @@
+
+uniform sampler2D inputMap;
+uniform int reversedDepth;
+
+layout (location = 0) out vec4 outColor;
+
+float depthAt(ivec2 texel, ivec2 size)
+{
+    float depth = texelFetch(inputMap, min(texel, size - 1), 0).r;
+    // In the reversed depth mode, the depth is one minus the standard depth
+    return reversedDepth == 1 ? 1.0 - depth : depth;
+}
+
+void main()
+{
+    ivec2 size = textureSize(inputMap, 0);
+    ivec2 texel = 2 * ivec2(gl_FragCoord.xy);
+    float depth = max(max(depthAt(texel, size), depthAt(texel + ivec2(1, 0), size)),
+        max(depthAt(texel + ivec2(0, 1), size), depthAt(texel + ivec2(1, 1), size)));
+
+    // The last column and row of an input with an odd size is included in the last texel of the output
+    bool lastColumn = texel.x + 3 == size.x;
+    bool lastRow = texel.y + 3 == size.y;
+    if (lastColumn) {
+        depth = max(depth, max(depthAt(texel + ivec2(2, 0), size), depthAt(texel + ivec2(2, 1), size)));
+    }
+    if (lastRow) {
+        depth = max(depth, max(depthAt(texel + ivec2(0, 2), size), depthAt(texel + ivec2(1, 2), size)));
+    }
+    if (lastColumn && lastRow) {
+        depth = max(depth, depthAt(texel + ivec2(2, 2), size));
+    }
+    outColor = vec4(depth, 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,41 @@
+
+// The undisplaced position of each vertex in xyz and the offset of its first neighbour triangle in w, with an extra texel after the last vertex,
+// such that the neighbour triangles of vertex i are the ones from offset i to offset i + 1
+uniform sampler2D dynamicNormalVertices;
+// The indices of the two other vertices of each neighbour triangle in the winding order of the triangle
+uniform sampler2D dynamicNormalNeighbours;
+
+// Returns the texel with the given index in a texture where the texels are stored row by row
+vec4 dynamic_normal_texel(sampler2D data, int index)
+{
+    int width = textureSize(data, 0).x;
+    return texelFetch(data, ivec2(index % width, index / width), 0);
+}
+
+// Returns the normal of the given vertex computed from the displaced positions of the vertex and its neighbours,
+// ie. the sum of the normals of the triangles around the vertex weighted by their area
+vec3 dynamic_normal(int vertex)
+{
+    vec4 data = dynamic_normal_texel(dynamicNormalVertices, vertex);
+    int first = int(data.w);
+    int last = int(dynamic_normal_texel(dynamicNormalVertices, vertex + 1).w);
+    vec3 position = displace_position(data.xyz);
+    vec3 normal = vec3(0.0);
+    for (int i = first; i < last; i++)
+    {
+        vec2 triangle = dynamic_normal_texel(dynamicNormalNeighbours, i).xy;
+        vec3 a = displace_position(dynamic_normal_texel(dynamicNormalVertices, int(triangle.x)).xyz);
+        vec3 b = displace_position(dynamic_normal_texel(dynamicNormalVertices, int(triangle.y)).xyz);
+        normal += cross(a - position, b - position);
+    }
+    return length(normal) > 0.0 ? normalize(normal) : vec3(0.0, 0.0, 1.0);
+}
+
+#ifdef TRANSFORM_FEEDBACK
+out vec3 dynamicNormal;
+
+void main()
+{
+    dynamicNormal = dynamic_normal(gl_VertexID);
+}
+#endif
@@ -0,0 +1,25 @@
+
+uniform vec4 color;
+uniform float lineWidth;
+uniform vec2 dashPattern;
+uniform int dashed;
+
+in vec2 dashCoord;
+in float side;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    if (dashed == 1) {
+        float dist = dashCoord.x / dashCoord.y;
+        float period = max(dashPattern.x + dashPattern.y, 0.0001);
+        if (fract(dist / period) * period > dashPattern.x) {
+            discard;
+        }
+    }
+
+    // The sides of the line are faded out over one pixel
+    float coverage = clamp((1.0 - abs(side)) * 0.5 * lineWidth + 0.5, 0.0, 1.0);
+    outColor = vec4(color.rgb, color.a * coverage);
+}
@@ -0,0 +1,65 @@
+
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform mat4 modelMatrix;
+uniform vec2 viewportSize;
+uniform float lineWidth;
+uniform float depthBias;
+
+in vec2 corner;
+in vec3 start;
+in vec3 end;
+
+out vec2 dashCoord;
+out float side;
+
+void main()
+{
+    vec4 viewStart = camera.view * modelMatrix * vec4(start, 1.0);
+    vec4 viewEnd = camera.view * modelMatrix * vec4(end, 1.0);
+    vec4 clipStart = camera.projection * viewStart;
+    vec4 clipEnd = camera.projection * viewEnd;
+
+    // The edge is clipped to the near plane, since the screen space direction is undefined behind the camera
+    float distanceStart = clipStart.z + clipStart.w;
+    float distanceEnd = clipEnd.z + clipEnd.w;
+    if (distanceStart < 0.0 && distanceEnd < 0.0) {
+        gl_Position = vec4(0.0, 0.0, 2.0, 1.0);
+        return;
+    }
+    if (distanceStart < 0.0) {
+        float t = distanceStart / (distanceStart - distanceEnd);
+        clipStart = mix(clipStart, clipEnd, t);
+        viewStart = mix(viewStart, viewEnd, t);
+    } else if (distanceEnd < 0.0) {
+        float t = distanceEnd / (distanceEnd - distanceStart);
+        clipEnd = mix(clipEnd, clipStart, t);
+        viewEnd = mix(viewEnd, viewStart, t);
+    }
+
+    // The line is widened in pixels perpendicular to the edge and extended by half the width at both ends, so the lines of neighbouring edges meet
+    vec2 screenStart = 0.5 * viewportSize * clipStart.xy / clipStart.w;
+    vec2 screenEnd = 0.5 * viewportSize * clipEnd.xy / clipEnd.w;
+    float screenLength = length(screenEnd - screenStart);
+    vec2 direction = screenLength > 0.0 ? (screenEnd - screenStart) / screenLength : vec2(1.0, 0.0);
+    vec2 normal = vec2(-direction.y, direction.x);
+    vec2 pixelOffset = 0.5 * lineWidth * (corner.y * normal + (2.0 * corner.x - 1.0) * direction);
+    gl_Position = mix(clipStart, clipEnd, corner.x);
+    gl_Position.xy += 2.0 * pixelOffset / viewportSize * gl_Position.w;
+
+    // The depth is moved towards the camera by a fraction of the distance, so the edges are not hidden by the faces they lie on
+    vec4 biased = camera.projection * vec4(mix(viewStart, viewEnd, corner.x).xyz * (1.0 - depthBias), 1.0);
+    gl_Position.z = biased.z / biased.w * gl_Position.w;
+
+    // The distance along the line in pixels is interpolated linearly on the screen instead of perspective correct
+    float dist = corner.x * screenLength;
+    dashCoord = vec2(dist * gl_Position.w, gl_Position.w);
+    side = corner.y;
+}
@@ -19,7 +19,7 @@ void main()
     vec4 color1 = texture(tex, vec3(uv, index1));
     color1.rgb = rgb_from_srgb(color1.rgb);
     out_color = mix(color0, color1, frac);
-    out_color = vec4(srgb_from_rgb(out_color.rgb), out_color.a);
+    out_color = vec4(encode_output(out_color.rgb), out_color.a);
     if(out_color.a < 0.5) {
         discard;
     }
@@ -0,0 +1,36 @@
+
+in float visible;
+out float vVisible;
+
+#ifdef TEXTURE_OUTPUT
+in vec3 tex_transform_row1;
+in vec3 tex_transform_row2;
+in vec4 instance_uv_offset_scale;
+out vec3 vTexTransform1;
+out vec3 vTexTransform2;
+out vec4 vUvOffsetScale;
+#else
+in vec4 row1;
+in vec4 row2;
+in vec4 row3;
+in float instance_id;
+out vec4 vRow1;
+out vec4 vRow2;
+out vec4 vRow3;
+out float vInstanceId;
+#endif
+
+void main()
+{
+    vVisible = visible;
+#ifdef TEXTURE_OUTPUT
+    vTexTransform1 = tex_transform_row1;
+    vTexTransform2 = tex_transform_row2;
+    vUvOffsetScale = instance_uv_offset_scale;
+#else
+    vRow1 = row1;
+    vRow2 = row2;
+    vRow3 = row3;
+    vInstanceId = instance_id;
+#endif
+}
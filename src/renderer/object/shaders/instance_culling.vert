@@ -0,0 +1,106 @@
+
+uniform mat4 modelMatrix;
+uniform vec4 boundingSphere;
+uniform vec4 frustumPlanes[6];
+
+in vec4 row1;
+in vec4 row2;
+in vec4 row3;
+
+out float visible;
+
+#ifdef USE_OCCLUSION
+uniform mat4 view;
+uniform mat4 projection;
+uniform float zNear;
+uniform sampler2D depthLevel0;
+uniform sampler2D depthLevel1;
+uniform sampler2D depthLevel2;
+uniform sampler2D depthLevel3;
+uniform sampler2D depthLevel4;
+uniform sampler2D depthLevel5;
+uniform sampler2D depthLevel6;
+uniform sampler2D depthLevel7;
+
+// The farthest depth inside the given rectangle, which covers at most 2x2 texels of the given level
+float levelDepth(sampler2D level, vec2 minUv, vec2 maxUv)
+{
+    ivec2 size = textureSize(level, 0);
+    ivec2 a = clamp(ivec2(minUv * vec2(size)), ivec2(0), size - 1);
+    ivec2 b = clamp(ivec2(maxUv * vec2(size)), ivec2(0), size - 1);
+    return max(max(texelFetch(level, a, 0).r, texelFetch(level, ivec2(b.x, a.y), 0).r),
+        max(texelFetch(level, ivec2(a.x, b.y), 0).r, texelFetch(level, b, 0).r));
+}
+
+float pyramidDepth(int level, vec2 minUv, vec2 maxUv)
+{
+    if (level == 0) return levelDepth(depthLevel0, minUv, maxUv);
+    if (level == 1) return levelDepth(depthLevel1, minUv, maxUv);
+    if (level == 2) return levelDepth(depthLevel2, minUv, maxUv);
+    if (level == 3) return levelDepth(depthLevel3, minUv, maxUv);
+    if (level == 4) return levelDepth(depthLevel4, minUv, maxUv);
+    if (level == 5) return levelDepth(depthLevel5, minUv, maxUv);
+    if (level == 6) return levelDepth(depthLevel6, minUv, maxUv);
+    return levelDepth(depthLevel7, minUv, maxUv);
+}
+
+bool occluded(vec3 center, float radius)
+{
+    vec3 viewCenter = (view * vec4(center, 1.0)).xyz;
+    // Spheres intersecting the near plane are never occluded
+    if (-viewCenter.z - radius < zNear) {
+        return false;
+    }
+
+    // The screen space rectangle covering the bounding box of the sphere in view space
+    vec2 minUv = vec2(1.0);
+    vec2 maxUv = vec2(0.0);
+    for (int i = 0; i < 8; i++) {
+        vec3 corner = viewCenter + radius * vec3((i & 1) == 0 ? -1.0 : 1.0, (i & 2) == 0 ? -1.0 : 1.0, (i & 4) == 0 ? -1.0 : 1.0);
+        vec4 clip = projection * vec4(corner, 1.0);
+        vec2 uv = 0.5 * clip.xy / clip.w + 0.5;
+        minUv = min(minUv, uv);
+        maxUv = max(maxUv, uv);
+    }
+    minUv = clamp(minUv, 0.0, 1.0);
+    maxUv = clamp(maxUv, 0.0, 1.0);
+
+    // The level where the rectangle covers at most 2x2 texels
+    vec2 size = (maxUv - minUv) * vec2(textureSize(depthLevel0, 0));
+    int level = int(ceil(log2(max(max(size.x, size.y), 1.0))));
+    if (level > 7) {
+        return false;
+    }
+
+    vec4 nearest = projection * vec4(viewCenter.xy, viewCenter.z + radius, 1.0);
+    float depth = 0.5 * nearest.z / nearest.w + 0.5;
+    return depth > pyramidDepth(level, minUv, maxUv);
+}
+#endif
+
+void main()
+{
+    mat4 transform;
+    transform[0] = vec4(row1.x, row2.x, row3.x, 0.0);
+    transform[1] = vec4(row1.y, row2.y, row3.y, 0.0);
+    transform[2] = vec4(row1.z, row2.z, row3.z, 0.0);
+    transform[3] = vec4(row1.w, row2.w, row3.w, 1.0);
+    mat4 local2World = modelMatrix * transform;
+
+    vec3 center = (local2World * vec4(boundingSphere.xyz, 1.0)).xyz;
+    float scale = max(length(local2World[0].xyz), max(length(local2World[1].xyz), length(local2World[2].xyz)));
+    float radius = scale * boundingSphere.w;
+
+    bool inside = true;
+    for (int i = 0; i < 6; i++) {
+        if (dot(frustumPlanes[i], vec4(center, 1.0)) < -radius * length(frustumPlanes[i].xyz)) {
+            inside = false;
+        }
+    }
+#ifdef USE_OCCLUSION
+    if (inside && occluded(center, radius)) {
+        inside = false;
+    }
+#endif
+    visible = inside ? 1.0 : 0.0;
+}
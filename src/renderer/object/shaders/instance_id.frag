@@ -0,0 +1,11 @@
+
+flat in float instanceId;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The index plus one is encoded in the four 8 bit channels with the least significant byte in the red channel, so zero means no instance
+    uint id = uint(instanceId) + 1u;
+    outColor = vec4(float(id & 255u), float((id >> 8) & 255u), float((id >> 16) & 255u), float(id >> 24)) / 255.0;
+}
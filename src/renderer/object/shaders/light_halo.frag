@@ -0,0 +1,20 @@
+
+uniform vec3 color;
+uniform float intensity;
+
+in vec2 uv;
+in float visibility;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float d = length(uv);
+    if(d >= 1.0 || visibility <= 0.0) {
+        discard;
+    }
+    // Soft radial falloff which is brightest in the center and zero at the edge
+    float falloff = 1.0 - d * d;
+    falloff *= falloff * falloff;
+    outColor = vec4(encode_output(intensity * visibility * falloff * color), 0.0);
+}
@@ -0,0 +1,54 @@
+
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform vec3 center;
+uniform float radius;
+
+#ifdef USE_DEPTH_FADE
+uniform sampler2D depthMap;
+uniform float centerDepth;
+uniform float farPlaneDepth;
+#endif
+
+in vec2 corner;
+
+out vec2 uv;
+out float visibility;
+
+void main()
+{
+    uv = corner;
+    vec3 right = vec3(camera.view[0][0], camera.view[1][0], camera.view[2][0]);
+    vec3 up = vec3(camera.view[0][1], camera.view[1][1], camera.view[2][1]);
+    gl_Position = camera.viewProjection * vec4(center + radius * (corner.x * right + corner.y * up), 1.0);
+
+    visibility = 1.0;
+#ifdef USE_DEPTH_FADE
+    // The fraction of a small disc around the light source which is not occluded by the geometry in the depth map
+    vec4 centerClip = camera.viewProjection * vec4(center, 1.0);
+    vec4 edgeClip = camera.viewProjection * vec4(center + radius * right, 1.0);
+    vec3 centerNdc = centerClip.xyz / centerClip.w;
+    float sampleRadius = 0.125 * length(edgeClip.xy / edgeClip.w - centerNdc.xy);
+    vec2 centerUv = 0.5 * centerNdc.xy + 0.5;
+    visibility = 0.0;
+    for (int i = -2; i <= 2; i++)
+    {
+        for (int j = -2; j <= 2; j++)
+        {
+            vec2 sampleUv = centerUv + 0.5 * sampleRadius * vec2(float(i), float(j));
+            bool inside = all(greaterThanEqual(sampleUv, vec2(0.0))) && all(lessThanEqual(sampleUv, vec2(1.0)));
+            float depth = textureLod(depthMap, sampleUv, 0.0).r;
+            bool visible = farPlaneDepth > 0.5 ? centerDepth <= depth : centerDepth >= depth;
+            visibility += inside && visible ? 1.0 : 0.0;
+        }
+    }
+    visibility = centerClip.w > 0.0 ? visibility / 25.0 : 0.0;
+#endif
+}
@@ -10,12 +10,21 @@ layout (std140) uniform Camera
 uniform mat4 modelMatrix;
 in vec3 position;
 
+#ifdef CAMERA_RELATIVE
+uniform mat4 modelViewMatrix;
+#endif
+
 #ifdef INSTANCED
 in vec4 row1;
 in vec4 row2;
 in vec4 row3;
 #endif
 
+#ifdef USE_INSTANCE_IDS
+in float instance_id;
+flat out float instanceId;
+#endif
+
 #ifdef USE_POSITIONS
 out vec3 pos;
 #endif
@@ -39,16 +48,85 @@ out vec3 bitang;
 in vec3 tex_transform_row1;
 in vec3 tex_transform_row2;
 #endif
+#ifdef USE_INSTANCE_UV_OFFSET_SCALE
+in vec4 instance_uv_offset_scale;
+flat out vec4 instanceUvOffsetScale;
+#endif
 uniform mat3 textureTransform;
 in vec2 uv_coordinates;
 out vec2 uvs;
 #endif
 
+#ifdef USE_UVS2
+in vec2 uv2_coordinates;
+out vec2 uvs2;
+#endif
+
 #ifdef USE_COLORS 
 in vec4 color;
+#ifdef USE_INSTANCE_COLORS
+in vec4 instance_color;
+#endif
 out vec4 col;
 #endif
 
+#ifdef USE_MORPH_TARGETS
+#define MAX_ACTIVE_MORPH_TARGETS 8
+uniform sampler2D morphTargets;
+uniform int morphVertexCount;
+uniform int morphTargetIndices[MAX_ACTIVE_MORPH_TARGETS];
+uniform float morphWeights[MAX_ACTIVE_MORPH_TARGETS];
+
+// Returns the position delta (offset 0) or normal delta (offset 1) of the current vertex for the given morph target
+vec3 morph_delta(int morph_target, int offset)
+{
+    int index = (morph_target * morphVertexCount + gl_VertexID) * 2 + offset;
+    int width = textureSize(morphTargets, 0).x;
+    return texelFetch(morphTargets, ivec2(index % width, index / width), 0).xyz;
+}
+#endif
+
+#ifdef USE_SKINNING
+uniform sampler2D skinningMatrices;
+uniform float animationTime;
+uniform float animationDuration;
+uniform float animationFramesPerSecond;
+in uvec4 joint_indices;
+in vec4 joint_weights;
+in vec2 instance_animation;
+
+// Returns the skinning matrix of the given joint at the given frame, the three texels starting at (joint * 3, frame) contain the rows of the matrix
+mat4 skinning_matrix(int joint, int frame)
+{
+    vec4 row1 = texelFetch(skinningMatrices, ivec2(joint * 3, frame), 0);
+    vec4 row2 = texelFetch(skinningMatrices, ivec2(joint * 3 + 1, frame), 0);
+    vec4 row3 = texelFetch(skinningMatrices, ivec2(joint * 3 + 2, frame), 0);
+    return mat4(row1.x, row2.x, row3.x, 0.0,
+        row1.y, row2.y, row3.y, 0.0,
+        row1.z, row2.z, row3.z, 0.0,
+        row1.w, row2.w, row3.w, 1.0);
+}
+
+// Returns the weighted sum of the skinning matrices of the joints of the current vertex,
+// interpolated between the two frames around the animation time of the current instance (x is the time offset and y is the speed)
+mat4 skinning()
+{
+    float time = mod(animationTime * instance_animation.y + instance_animation.x, animationDuration);
+    float frame = time * animationFramesPerSecond;
+    int last_frame = textureSize(skinningMatrices, 0).y - 1;
+    int frame0 = min(int(frame), last_frame);
+    int frame1 = min(frame0 + 1, last_frame);
+    float t = clamp(frame - float(frame0), 0.0, 1.0);
+    mat4 result = mat4(0.0);
+    for (int i = 0; i < 4; i++)
+    {
+        int joint = int(joint_indices[i]);
+        result += joint_weights[i] * ((1.0 - t) * skinning_matrix(joint, frame0) + t * skinning_matrix(joint, frame1));
+    }
+    return result;
+}
+#endif
+
 void main()
 {
     mat4 local2World = modelMatrix;
@@ -70,18 +148,65 @@ void main()
 #endif
 #endif
 
-    vec4 worldPosition = local2World * vec4(position, 1.);
+    vec3 localPosition = position;
+#ifdef USE_NORMALS
+#ifdef USE_DYNAMIC_NORMALS
+    // The normal is computed from the displaced positions of the vertex and its neighbours instead of using the static normal
+    vec3 localNormal = dynamic_normal(gl_VertexID);
+#else
+    vec3 localNormal = normal;
+#endif
+#endif
+#ifdef USE_MORPH_TARGETS
+    for (int i = 0; i < MAX_ACTIVE_MORPH_TARGETS; i++)
+    {
+        // Morph targets with a zero weight are skipped
+        if (morphWeights[i] != 0.0)
+        {
+            localPosition += morphWeights[i] * morph_delta(morphTargetIndices[i], 0);
+#ifdef USE_NORMALS
+            localNormal += morphWeights[i] * morph_delta(morphTargetIndices[i], 1);
+#endif
+        }
+    }
+#endif
+#ifdef USE_SKINNING
+    mat4 skinningMatrix = skinning();
+    localPosition = (skinningMatrix * vec4(localPosition, 1.0)).xyz;
+#ifdef USE_NORMALS
+    localNormal = mat3(skinningMatrix) * localNormal;
+#endif
+#endif
+
+#ifdef USE_VERTEX_DISPLACEMENT
+    localPosition = displace_position(localPosition);
+#endif
+
+    vec4 worldPosition = local2World * vec4(localPosition, 1.);
+#ifdef CAMERA_RELATIVE
+    // The model view matrix is computed in double precision, so it does not contain the large translations of the model and camera which cancel out
+    gl_Position = camera.projection * modelViewMatrix * vec4(localPosition, 1.);
+#else
     gl_Position = camera.viewProjection * worldPosition;
+#endif
+
+#ifdef USE_INSTANCE_IDS
+    instanceId = instance_id;
+#endif
 
 #ifdef USE_POSITIONS
     pos = worldPosition.xyz;
 #endif
 
 #ifdef USE_NORMALS 
-    nor = normalize(normalMat * normal);
+    nor = normalize(normalMat * localNormal);
 
 #ifdef USE_TANGENTS 
-    tang = normalize(normalMat * tangent.xyz);
+    vec3 localTangent = tangent.xyz;
+#ifdef USE_SKINNING
+    localTangent = mat3(skinningMatrix) * localTangent;
+#endif
+    tang = normalize(normalMat * localTangent);
     bitang = normalize(cross(nor, tang) * tangent.w);
 #endif
 
@@ -97,9 +222,24 @@ void main()
     texTransform *= instancedTexTransform;
 #endif
     uvs = (texTransform * vec3(uv_coordinates, 1.0)).xy;
+#ifdef USE_INSTANCE_UV_OFFSET_SCALE
+    // The region is applied in the fragment shader after the texture transformations of the material
+    instanceUvOffsetScale = instance_uv_offset_scale;
+#endif
+#endif
+
+#ifdef USE_UVS2
+    uvs2 = uv2_coordinates;
 #endif
 
 #ifdef USE_COLORS 
     col = color/255.0;
+#ifdef SRGB_VERTEX_COLORS
+    // The lighting is computed in linear space
+    col.rgb = rgb_from_srgb(col.rgb);
+#endif
+#ifdef USE_INSTANCE_COLORS
+    col *= instance_color;
+#endif
 #endif
 }
\ No newline at end of file
@@ -0,0 +1,13 @@
+
+uniform sampler2D reflectionMap;
+uniform vec4 viewport;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The reflection is rendered with the same viewport, so the texture coordinates are the screen space coordinates
+    vec2 uv = (gl_FragCoord.xy - viewport.xy) / viewport.zw;
+    vec4 color = texture(reflectionMap, uv);
+    outColor = vec4(encode_output(rgb_from_srgb(color.rgb)), 1.0);
+}
@@ -0,0 +1,36 @@
+
+uniform int shape;
+
+in vec2 uv;
+in vec4 col;
+in float pixelSize;
+
+layout (location = 0) out vec4 outColor;
+
+// Signed distance to the edge of the marker in the range [-1..1] of the quad, negative inside
+float marker_distance(vec2 p)
+{
+    vec2 a = abs(p);
+    if(shape == 1) { // Square
+        return max(a.x, a.y) - 1.0;
+    }
+    if(shape == 2) { // Cross, where the arms are at least a pixel thick
+        float t = max(0.25, 2.0 / pixelSize);
+        return min(max(a.x - t, a.y - 1.0), max(a.y - t, a.x - 1.0));
+    }
+    return length(p) - 1.0; // Circle
+}
+
+void main()
+{
+    // Fade out over the outermost pixel, which gives crisp antialiased edges at any size
+    float alpha = clamp(-marker_distance(uv) * 0.5 * pixelSize, 0.0, 1.0);
+    if(alpha <= 0.0) {
+        discard;
+    }
+    vec3 color = col.rgb;
+#ifdef SRGB_VERTEX_COLORS
+    color = rgb_from_srgb(color);
+#endif
+    outColor = vec4(encode_output(color), col.a * alpha);
+}
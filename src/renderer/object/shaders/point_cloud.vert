@@ -0,0 +1,40 @@
+
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform mat4 modelMatrix;
+uniform vec2 viewportSize;
+
+in vec2 corner;
+in vec3 center;
+in vec4 color;
+in float size;
+
+out vec2 uv;
+out vec4 col;
+out float pixelSize;
+
+void main()
+{
+    uv = corner;
+    col = color / 255.0;
+    vec4 worldCenter = modelMatrix * vec4(center, 1.0);
+#ifdef SCREEN_SPACE_SIZE
+    // The size is in pixels, so the corner is offset in normalized device coordinates
+    gl_Position = camera.viewProjection * worldCenter;
+    gl_Position.xy += corner * size / viewportSize * gl_Position.w;
+    pixelSize = size;
+#else
+    // The size is in world space, so the corner is offset in the plane facing the camera
+    vec3 right = vec3(camera.view[0][0], camera.view[1][0], camera.view[2][0]);
+    vec3 up = vec3(camera.view[0][1], camera.view[1][1], camera.view[2][1]);
+    gl_Position = camera.viewProjection * vec4(worldCenter.xyz + 0.5 * size * (corner.x * right + corner.y * up), 1.0);
+    pixelSize = size * camera.projection[1][1] * 0.5 * viewportSize.y / max(gl_Position.w, 0.0001);
+#endif
+}
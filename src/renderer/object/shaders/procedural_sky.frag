@@ -0,0 +1,57 @@
+
+uniform vec3 sunDirection;
+uniform vec3 sunColor;
+uniform float sunDiskIntensity;
+uniform float turbidity;
+
+// Converts the luminance in kcd/m2 to the radiance used in the renderer
+const float LUMINANCE_SCALE = 0.05;
+const vec3 NIGHT_COLOR = vec3(0.0008, 0.0015, 0.005);
+
+// The Perez distribution function for the luminance and the x and y chromaticities
+vec3 perez(float cos_theta, float gamma, float cos_gamma, vec3 A, vec3 B, vec3 C, vec3 D, vec3 E)
+{
+    return (1.0 + A * exp(B / cos_theta)) * (1.0 + C * exp(D * gamma) + E * cos_gamma * cos_gamma);
+}
+
+// The sky model from A. J. Preetham, P. Shirley and B. Smits, "A Practical Analytic Model for Daylight"
+vec3 sky_color(vec3 direction)
+{
+    float T = clamp(turbidity, 1.7, 10.0);
+    vec3 A = vec3(0.1787, -0.0193, -0.0167) * T + vec3(-1.4630, -0.2592, -0.2608);
+    vec3 B = vec3(-0.3554, -0.0665, -0.0950) * T + vec3(0.4275, 0.0008, 0.0092);
+    vec3 C = vec3(-0.0227, -0.0004, -0.0079) * T + vec3(5.3251, 0.2125, 0.2102);
+    vec3 D = vec3(0.1206, -0.0641, -0.0441) * T + vec3(-2.5771, -0.8989, -1.6537);
+    vec3 E = vec3(-0.0670, -0.0033, -0.0109) * T + vec3(0.3703, 0.0452, 0.0529);
+
+    // The model is only valid for a sun above the horizon, so a sun below the horizon is evaluated at the horizon and faded to the night sky
+    vec3 sun = normalize(vec3(sunDirection.x, max(sunDirection.y, 0.0), sunDirection.z));
+    float theta_s = acos(sun.y);
+    float theta_s2 = theta_s * theta_s;
+    float theta_s3 = theta_s2 * theta_s;
+    float chi = (4.0 / 9.0 - T / 120.0) * (PI - 2.0 * theta_s);
+    float zenith_luminance = (4.0453 * T - 4.9710) * tan(chi) - 0.2155 * T + 2.4192;
+    float zenith_x = T * T * (0.00166 * theta_s3 - 0.00375 * theta_s2 + 0.00209 * theta_s)
+        + T * (-0.02903 * theta_s3 + 0.06377 * theta_s2 - 0.03202 * theta_s + 0.00394)
+        + (0.11693 * theta_s3 - 0.21196 * theta_s2 + 0.06052 * theta_s + 0.25886);
+    float zenith_y = T * T * (0.00275 * theta_s3 - 0.00610 * theta_s2 + 0.00317 * theta_s)
+        + T * (-0.04214 * theta_s3 + 0.08970 * theta_s2 - 0.04153 * theta_s + 0.00516)
+        + (0.15346 * theta_s3 - 0.26756 * theta_s2 + 0.06670 * theta_s + 0.26688);
+
+    // Directions below the horizon are evaluated just above the horizon and darkened
+    float cos_theta = max(direction.y, 0.01);
+    float cos_gamma = clamp(dot(normalize(vec3(direction.x, cos_theta, direction.z)), sun), -1.0, 1.0);
+    float gamma = acos(cos_gamma);
+    vec3 Yxy = vec3(zenith_luminance, zenith_x, zenith_y)
+        * perez(cos_theta, gamma, cos_gamma, A, B, C, D, E)
+        / perez(1.0, theta_s, sun.y, A, B, C, D, E);
+
+    vec3 XYZ = vec3(Yxy.y / Yxy.z * Yxy.x, Yxy.x, (1.0 - Yxy.y - Yxy.z) / Yxy.z * Yxy.x);
+    vec3 rgb = max(mat3(3.2406, -0.9689, 0.0557, -1.5372, 1.8758, -0.2040, -0.4986, 0.0415, 1.0570) * XYZ, vec3(0.0));
+    rgb *= LUMINANCE_SCALE * mix(0.3, 1.0, smoothstep(-0.2, 0.0, direction.y));
+
+    float sun_disk = smoothstep(0.99994, 0.99996, dot(direction, normalize(sunDirection)));
+    rgb += sunDiskIntensity * sun_disk * sunColor;
+
+    return mix(NIGHT_COLOR, rgb, smoothstep(-0.2, 0.02, sunDirection.y));
+}
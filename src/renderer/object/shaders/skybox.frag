@@ -1,5 +1,6 @@
 uniform samplerCube texture0;
 uniform int isHDR;
+uniform float exposure;
 
 in vec3 coords;
 
@@ -8,7 +9,11 @@ layout (location = 0) out vec4 outColor;
 void main() {
     outColor = vec4(texture(texture0, coords).rgb, 1.0);
     if(isHDR == 1) {
-        outColor.rgb = reinhard_tone_mapping(outColor.rgb);
-        outColor.rgb = srgb_from_rgb(outColor.rgb);
+        outColor.rgb = tone_map_and_encode_output(exposure * outColor.rgb);
     }
+#ifdef HDR_OUTPUT
+    else {
+        outColor.rgb = rgb_from_srgb(outColor.rgb);
+    }
+#endif
 }
@@ -8,6 +8,8 @@ layout (std140) uniform Camera
     float padding;
 } camera;
 
+uniform float farPlaneZ;
+
 in vec3 position;
 
 out vec3 coords;
@@ -15,5 +17,7 @@ out vec3 coords;
 void main()
 {
     coords = position;
-    gl_Position = (camera.projection * mat4(mat3(camera.view)) * vec4(position, 1.)).xyww;
+    // Placed at the far plane, which depends on the depth mode
+    vec4 p = camera.projection * mat4(mat3(camera.view)) * vec4(position, 1.);
+    gl_Position = vec4(p.xy, farPlaneZ * p.w, p.w);
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+#define SOFT_PARTICLES
+
+uniform sampler2D sceneDepthMap;
+uniform mat4 softParticleProjectionInverse;
+uniform float softParticleZNear;
+uniform float softParticleFadeDistance;
+uniform float softParticleNearFadeDistance;
+
+// The distance along the view direction from the camera to the point with the given depth value
+float soft_particle_view_depth(float depth)
+{
+    vec4 position = softParticleProjectionInverse * vec4(0.0, 0.0, 2.0 * depth - 1.0, 1.0);
+    return -position.z / position.w;
+}
+
+// Returns a factor between 0 and 1 which fades out the particle where it is close to the scene geometry behind it or close to the near plane of the camera
+float soft_particle_fade()
+{
+    float scene_depth = soft_particle_view_depth(texelFetch(sceneDepthMap, ivec2(gl_FragCoord.xy), 0).x);
+    float particle_depth = soft_particle_view_depth(gl_FragCoord.z);
+    float fade = clamp((scene_depth - particle_depth) / max(softParticleFadeDistance, 0.0001), 0.0, 1.0);
+    return fade * clamp((particle_depth - softParticleZNear) / max(softParticleNearFadeDistance, 0.0001), 0.0, 1.0);
+}
+
@@ -0,0 +1,34 @@
+
+uniform sampler2D tex;
+uniform int linearFilter;
+
+in vec2 texel;
+flat in vec4 sourceBounds;
+in vec4 col;
+
+layout (location = 0) out vec4 outColor;
+
+// The texels are clamped to the source rectangle, so neighbouring sprites in an atlas never bleed into each other
+vec4 fetch(ivec2 t)
+{
+    ivec2 lower = ivec2(floor(sourceBounds.xy + 0.5));
+    ivec2 upper = max(ivec2(floor(sourceBounds.zw + 0.5)) - 1, lower);
+    return texelFetch(tex, clamp(t, lower, upper), 0);
+}
+
+void main()
+{
+    vec4 color;
+    if (linearFilter == 1) {
+        vec2 t = texel - 0.5;
+        ivec2 i = ivec2(floor(t));
+        vec2 f = t - floor(t);
+        color = mix(
+            mix(fetch(i), fetch(i + ivec2(1, 0)), f.x),
+            mix(fetch(i + ivec2(0, 1)), fetch(i + ivec2(1, 1)), f.x),
+            f.y);
+    } else {
+        color = fetch(ivec2(floor(texel)));
+    }
+    outColor = color * col;
+}
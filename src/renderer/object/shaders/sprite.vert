@@ -0,0 +1,29 @@
+
+uniform vec2 viewportSize;
+uniform float pixelRatio;
+
+in vec2 corner;
+in vec4 destination;
+in vec4 source;
+in vec4 rotation;
+in vec4 tint;
+
+out vec2 texel;
+flat out vec4 sourceBounds;
+out vec4 col;
+
+void main()
+{
+    // The corner, destination and pivot are relative to the top left corner with the y-coordinate increasing downwards
+    vec2 size = destination.zw;
+    vec2 pivot = rotation.zw * size;
+    vec2 p = corner * size - pivot;
+    p = vec2(rotation.x * p.x - rotation.y * p.y, rotation.y * p.x + rotation.x * p.y);
+    vec2 pixel = (destination.xy + pivot + p) * pixelRatio;
+    gl_Position = vec4(2.0 * pixel.x / viewportSize.x - 1.0, 1.0 - 2.0 * pixel.y / viewportSize.y, 0.0, 1.0);
+
+    // A flipped source rectangle has a negative size
+    texel = source.xy + corner * source.zw;
+    sourceBounds = vec4(min(source.xy, source.xy + source.zw), max(source.xy, source.xy + source.zw));
+    col = tint / 255.0;
+}
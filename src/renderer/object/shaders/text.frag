@@ -0,0 +1,31 @@
+
+uniform sampler2D glyphAtlas;
+uniform vec4 textColor;
+uniform vec4 outlineColor;
+uniform float outlineWidth;
+uniform float outlineSoftness;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float distance = texture(glyphAtlas, uvs).r;
+    // The width of the antialiasing, which is about one pixel on the screen at any scale
+    float width = max(fwidth(distance), 0.0001);
+    float glyph = smoothstep(0.5 - width, 0.5 + width, distance);
+
+    vec4 color = textColor;
+    if (outlineWidth > 0.0) {
+        float edge = 0.5 - outlineWidth;
+        float outline = smoothstep(edge - width - outlineSoftness, edge + width, distance);
+        color = mix(vec4(outlineColor.rgb, outlineColor.a * outline), textColor, glyph);
+    } else {
+        color.a *= glyph;
+    }
+    if (color.a < 0.001) {
+        discard;
+    }
+    outColor = vec4(encode_output(color.rgb), color.a);
+}
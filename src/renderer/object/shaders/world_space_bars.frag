@@ -0,0 +1,19 @@
+
+uniform vec4 backgroundColor;
+uniform float alpha;
+
+in vec2 uv;
+in vec4 col;
+in float fraction;
+in float fade;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    if(fade <= 0.0) {
+        discard;
+    }
+    vec4 color = uv.x <= fraction ? col : backgroundColor;
+    outColor = vec4(encode_output(rgb_from_srgb(color.rgb)), color.a * alpha * fade);
+}
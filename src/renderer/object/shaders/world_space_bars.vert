@@ -0,0 +1,43 @@
+
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform vec2 viewportSize;
+
+#ifdef USE_DISTANCE_FADE
+uniform vec2 fadeDistances;
+#endif
+
+in vec2 corner;
+in vec3 center;
+in vec4 color;
+in vec4 bar;
+
+out vec2 uv;
+out vec4 col;
+out float fraction;
+out float fade;
+
+void main()
+{
+    uv = corner;
+    col = color / 255.0;
+    fraction = bar.x;
+
+    // The size and offset are in pixels, so the corner is offset in normalized device coordinates
+    vec2 pixelOffset = vec2((corner.x - 0.5) * bar.y, (corner.y - 0.5) * bar.z + bar.w);
+    gl_Position = camera.viewProjection * vec4(center, 1.0);
+    gl_Position.xy += 2.0 * pixelOffset / viewportSize * gl_Position.w;
+
+    fade = 1.0;
+#ifdef USE_DISTANCE_FADE
+    float cameraDistance = length(center - camera.position);
+    fade = cameraDistance >= fadeDistances.y ? 0.0 : 1.0 - clamp((cameraDistance - fadeDistances.x) / max(fadeDistances.y - fadeDistances.x, 0.0001), 0.0, 1.0);
+#endif
+}
@@ -4,9 +4,14 @@ use crate::core::*;
 /// An illusion of a sky.
 ///
 pub struct Skybox<T: TextureCube> {
-    program: Program,
+    context: Context,
     vertex_buffer: VertexBuffer,
     texture: T,
+    ///
+    /// The colors of a high dynamic range skybox are multiplied by the exposure before they are tone mapped
+    /// the same way as the [PhysicalMaterial](crate::PhysicalMaterial), which can be used to match the brightness of the sky to the rest of the scene.
+    ///
+    pub exposure: f32,
 }
 
 impl<T: TextureDataType> Skybox<TextureCubeMap<T>> {
@@ -22,7 +27,8 @@ impl<T: TextureDataType> Skybox<TextureCubeMap<T>> {
     }
 
     ///
-    /// Creates a new skybox with a cube texture generated from the equirectangular texture given as input.
+    /// Creates a new skybox with a cube texture generated from the equirectangular texture given as input,
+    /// for example a high dynamic range image loaded using [Loaded::hdr_image](crate::Loaded::hdr_image).
     ///
     pub fn new_from_equirectangular(
         context: &Context,
@@ -38,22 +44,13 @@ impl<T: TextureCube> Skybox<T> {
     /// Creates a new skybox with the given [TextureCubeMap].
     ///
     pub fn new_with_texture(context: &Context, texture: T) -> ThreeDResult<Skybox<T>> {
-        let program = Program::from_source(
-            context,
-            include_str!("shaders/skybox.vert"),
-            &format!(
-                "{}{}",
-                include_str!("../../core/shared.frag"),
-                include_str!("shaders/skybox.frag")
-            ),
-        )?;
-
         let vertex_buffer = VertexBuffer::new_with_static(context, &CPUMesh::cube().positions)?;
 
         Ok(Skybox {
-            program,
+            context: context.clone(),
             vertex_buffer,
             texture,
+            exposure: 1.0,
         })
     }
 
@@ -74,17 +71,23 @@ impl<T: TextureCube> Skybox<T> {
             ..Default::default()
         };
 
-        self.program
-            .use_uniform_int("isHDR", if self.texture.is_hdr() { &1 } else { &0 })?;
-        self.program.use_texture_cube("texture0", &self.texture)?;
-        self.program
-            .use_uniform_block("Camera", camera.uniform_buffer());
-
-        self.program
-            .use_attribute_vec3("position", &self.vertex_buffer)?;
-
-        self.program
-            .draw_arrays(render_states, camera.viewport(), 36);
-        Ok(())
+        self.context.program(
+            include_str!("shaders/skybox.vert"),
+            &format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/skybox.frag")
+            ),
+            |program| {
+                program.use_uniform_int("isHDR", if self.texture.is_hdr() { &1 } else { &0 })?;
+                program.use_uniform_float("exposure", &self.exposure)?;
+                program.use_texture_cube("texture0", &self.texture)?;
+                program.use_uniform_block("Camera", camera.uniform_buffer());
+                program.use_uniform_float("farPlaneZ", &camera.far_plane_z())?;
+                program.use_attribute_vec3("position", &self.vertex_buffer)?;
+                program.draw_arrays(render_states, camera.viewport(), 36);
+                Ok(())
+            },
+        )
     }
 }
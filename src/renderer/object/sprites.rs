@@ -0,0 +1,268 @@
+use crate::core::*;
+
+///
+/// A rectangle in pixels relative to the top left corner of a viewport or an image, with the y-coordinate increasing downwards.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PixelRect {
+    /// The distance in pixels from the left edge to the left side of the rectangle.
+    pub x: f32,
+    /// The distance in pixels from the top edge to the top of the rectangle.
+    pub y: f32,
+    /// The width of the rectangle in pixels.
+    pub width: f32,
+    /// The height of the rectangle in pixels.
+    pub height: f32,
+}
+
+impl PixelRect {
+    ///
+    /// Constructs a new rectangle with the top left corner at the given position and the given size.
+    ///
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+///
+/// Options for drawing a sprite using [Sprite2D::draw].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SpriteOptions {
+    /// The part of the texture to draw in texels, for example a sprite in a texture atlas, or `None` to draw the whole texture.
+    pub source: Option<PixelRect>,
+    /// The rotation of the sprite around the pivot, which is clockwise on the screen for positive angles.
+    pub rotation: Radians,
+    /// The point the sprite is rotated around relative to the destination rectangle, where `(0, 0)` is the top left corner and `(1, 1)` the bottom right corner.
+    pub pivot: Vec2,
+    /// The color multiplied with the texture color, white keeps the texture colors.
+    pub tint: Color,
+    /// Whether or not the sprite is mirrored horizontally.
+    pub flip_x: bool,
+    /// Whether or not the sprite is mirrored vertically.
+    pub flip_y: bool,
+    /// How the sprite is blended with the render target, for example [Blend::TRANSPARENCY] (the default), [Blend::ADD] or [Blend::Disabled].
+    pub blend: Blend,
+    /// How the texture is sampled, [Interpolation::Nearest] (the default) keeps pixel art sharp. The filtering of the texture itself is not used.
+    pub interpolation: Interpolation,
+}
+
+impl Default for SpriteOptions {
+    fn default() -> Self {
+        Self {
+            source: None,
+            rotation: radians(0.0),
+            pivot: vec2(0.5, 0.5),
+            tint: Color::WHITE,
+            flip_x: false,
+            flip_y: false,
+            blend: Blend::TRANSPARENCY,
+            interpolation: Interpolation::Nearest,
+        }
+    }
+}
+
+///
+/// Draws textures at exact pixel positions on the screen or any other render target, without a mesh or a camera,
+/// for example logos, loading screens or the tiles and characters of a 2D game.
+/// The sprites are drawn without depth testing in the order they are drawn, so they can be drawn before, between or after a 3D scene and the GUI in the same render function.
+///
+/// Sprites drawn with [Sprite2D::draw_batch] are drawn with a single instanced draw call for each run of sprites with the same blend and interpolation,
+/// which keeps for example a tile map with thousands of tiles fast.
+///
+pub struct Sprite2D {
+    context: Context,
+    ///
+    /// The number of physical pixels per unit of the destination rectangles, which defaults to 1 so the destination is in physical pixels.
+    /// Set this to the [device pixel ratio](crate::FrameInput::device_pixel_ratio) to specify the destination in logical pixels instead,
+    /// so a sprite has the same size on the screen regardless of the pixel density of the display.
+    ///
+    pub device_pixel_ratio: f64,
+    corner_buffer: VertexBuffer,
+    destination_buffer: InstanceBuffer,
+    source_buffer: InstanceBuffer,
+    rotation_buffer: InstanceBuffer,
+    tint_buffer: InstanceBuffer,
+    capacity: usize,
+    destination_data: Vec<f32>,
+    source_data: Vec<f32>,
+    rotation_data: Vec<f32>,
+    tint_data: Vec<u8>,
+}
+
+impl Sprite2D {
+    ///
+    /// Creates a new sprite drawer, where the destination rectangles are in physical pixels, see [Sprite2D::device_pixel_ratio].
+    ///
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        let corners = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0];
+        Ok(Self {
+            context: context.clone(),
+            device_pixel_ratio: 1.0,
+            corner_buffer: VertexBuffer::new_with_static(context, &corners)?,
+            destination_buffer: InstanceBuffer::new(context)?,
+            source_buffer: InstanceBuffer::new(context)?,
+            rotation_buffer: InstanceBuffer::new(context)?,
+            tint_buffer: InstanceBuffer::new(context)?,
+            capacity: 0,
+            destination_data: Vec::new(),
+            source_data: Vec::new(),
+            rotation_data: Vec::new(),
+            tint_data: Vec::new(),
+        })
+    }
+
+    ///
+    /// Draws the texture, or the part of it given by [SpriteOptions::source], into the destination rectangle of the given viewport.
+    /// The destination is relative to the top left corner of the viewport, see [Sprite2D::device_pixel_ratio] for the units.
+    /// The texture must contain floating point or normalized values, for example a [Texture2D] with `u8` values.
+    /// Must be called in a render target render function, for example in the callback function of [Screen::write].
+    ///
+    pub fn draw(
+        &mut self,
+        viewport: Viewport,
+        texture: &impl Texture,
+        destination: PixelRect,
+        options: &SpriteOptions,
+    ) -> ThreeDResult<()> {
+        self.draw_batch(viewport, texture, &[(destination, *options)])
+    }
+
+    ///
+    /// Draws the sprites of the same texture in the given order like [Sprite2D::draw],
+    /// using one instanced draw call for each run of sprites with the same [SpriteOptions::blend] and [SpriteOptions::interpolation].
+    /// Must be called in a render target render function, for example in the callback function of [Screen::write].
+    ///
+    pub fn draw_batch(
+        &mut self,
+        viewport: Viewport,
+        texture: &impl Texture,
+        sprites: &[(PixelRect, SpriteOptions)],
+    ) -> ThreeDResult<()> {
+        let mut start = 0;
+        while start < sprites.len() {
+            let (blend, interpolation) = (sprites[start].1.blend, sprites[start].1.interpolation);
+            let end = sprites[start..]
+                .iter()
+                .position(|(_, o)| o.blend != blend || o.interpolation != interpolation)
+                .map(|i| start + i)
+                .unwrap_or(sprites.len());
+            self.fill_instances(texture, &sprites[start..end]);
+            self.draw_instances(viewport, texture, blend, interpolation, end - start)?;
+            start = end;
+        }
+        Ok(())
+    }
+
+    fn fill_instances(&mut self, texture: &impl Texture, sprites: &[(PixelRect, SpriteOptions)]) {
+        self.destination_data.clear();
+        self.source_data.clear();
+        self.rotation_data.clear();
+        self.tint_data.clear();
+        for (destination, options) in sprites {
+            let source = options.source.unwrap_or(PixelRect::new(
+                0.0,
+                0.0,
+                texture.width() as f32,
+                texture.height() as f32,
+            ));
+            let (x, width) = if options.flip_x {
+                (source.x + source.width, -source.width)
+            } else {
+                (source.x, source.width)
+            };
+            let (y, height) = if options.flip_y {
+                (source.y + source.height, -source.height)
+            } else {
+                (source.y, source.height)
+            };
+            self.destination_data.extend_from_slice(&[
+                destination.x,
+                destination.y,
+                destination.width,
+                destination.height,
+            ]);
+            self.source_data.extend_from_slice(&[x, y, width, height]);
+            self.rotation_data.extend_from_slice(&[
+                options.rotation.0.cos(),
+                options.rotation.0.sin(),
+                options.pivot.x,
+                options.pivot.y,
+            ]);
+            self.tint_data.extend_from_slice(&[
+                options.tint.r,
+                options.tint.g,
+                options.tint.b,
+                options.tint.a,
+            ]);
+        }
+
+        let count = sprites.len();
+        if count > self.capacity {
+            self.destination_buffer.allocate::<f32>(count * 4);
+            self.source_buffer.allocate::<f32>(count * 4);
+            self.rotation_buffer.allocate::<f32>(count * 4);
+            self.tint_buffer.allocate::<u8>(count * 4);
+            self.capacity = count;
+        }
+        if count > 0 {
+            self.destination_buffer
+                .fill_subset(0, &self.destination_data);
+            self.source_buffer.fill_subset(0, &self.source_data);
+            self.rotation_buffer.fill_subset(0, &self.rotation_data);
+            self.tint_buffer.fill_subset(0, &self.tint_data);
+        }
+    }
+
+    fn draw_instances(
+        &self,
+        viewport: Viewport,
+        texture: &impl Texture,
+        blend: Blend,
+        interpolation: Interpolation,
+        count: usize,
+    ) -> ThreeDResult<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend,
+            cull: Cull::None,
+            ..Default::default()
+        };
+        self.context.program(
+            include_str!("shaders/sprite.vert"),
+            include_str!("shaders/sprite.frag"),
+            |program| {
+                program.use_uniform_vec2(
+                    "viewportSize",
+                    &vec2(viewport.width as f32, viewport.height as f32),
+                )?;
+                program.use_uniform_float("pixelRatio", &(self.device_pixel_ratio as f32))?;
+                program.use_uniform_int(
+                    "linearFilter",
+                    &(if interpolation == Interpolation::Linear {
+                        1
+                    } else {
+                        0
+                    }),
+                )?;
+                program.use_texture("tex", texture)?;
+                program.use_attribute_vec2("corner", &self.corner_buffer)?;
+                program.use_attribute_vec4_instanced("destination", &self.destination_buffer)?;
+                program.use_attribute_vec4_instanced("source", &self.source_buffer)?;
+                program.use_attribute_vec4_instanced("rotation", &self.rotation_buffer)?;
+                program.use_attribute_vec4_instanced("tint", &self.tint_buffer)?;
+                program.draw_arrays_instanced(render_states, viewport, 6, count as u32);
+                Ok(())
+            },
+        )
+    }
+}
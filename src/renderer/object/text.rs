@@ -0,0 +1,671 @@
+use crate::core::*;
+use crate::renderer::*;
+use ab_glyph::{Font as _, FontArc, GlyphId, PxScale, ScaleFont};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+///
+/// A font used to render [Text], loaded from the bytes of a TrueType (.ttf) or OpenType (.otf) font file.
+///
+#[derive(Clone)]
+pub struct Font {
+    font: FontArc,
+}
+
+impl Font {
+    ///
+    /// Parses the given bytes of a .ttf or .otf font file.
+    ///
+    pub fn new(bytes: Vec<u8>) -> ThreeDResult<Self> {
+        Self::new_with_index(bytes, 0)
+    }
+
+    ///
+    /// Parses the font with the given index in the given bytes of a font collection (.ttc) file.
+    ///
+    pub fn new_with_index(bytes: Vec<u8>, index: u32) -> ThreeDResult<Self> {
+        let font = ab_glyph::FontVec::try_from_vec_and_index(bytes, index)
+            .map_err(|e| RendererError::InvalidFont(e.to_string()))?;
+        Ok(Self {
+            font: FontArc::new(font),
+        })
+    }
+
+    ///
+    /// Returns whether or not this font has a glyph for the given character.
+    ///
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.font.glyph_id(c).0 != 0
+    }
+}
+
+///
+/// A texture atlas containing signed distance fields of the glyphs of a chain of fonts, which is shared between [Text] objects.
+/// The signed distance fields are generated on demand when a character is first used, and new atlas pages are added when the existing pages are full.
+///
+/// A character is looked up in the fonts in the order they are given, so the first font is the primary font and the rest are fallbacks,
+/// for example a font with emoji or CJK characters. If no font has a glyph for a character, the missing glyph of the primary font is used.
+///
+/// Since the glyphs are stored as signed distance fields, the same atlas renders crisp text at any scale.
+///
+#[derive(Clone)]
+pub struct GlyphAtlas {
+    atlas: Rc<RefCell<AtlasData>>,
+}
+
+impl GlyphAtlas {
+    ///
+    /// Creates a new glyph atlas with the given chain of fonts, where the glyphs are rendered at a height of 48 pixels
+    /// with a signed distance spread of 6 pixels on pages of 1024x1024 pixels.
+    ///
+    pub fn new(context: &Context, fonts: &[Font]) -> ThreeDResult<Self> {
+        Self::new_with_options(context, fonts, 48, 6, 1024)
+    }
+
+    ///
+    /// Creates a new glyph atlas with the given chain of fonts, where the glyphs are rendered at the given height in pixels
+    /// with the given signed distance spread in pixels, which is the maximum width of an outline, on pages of the given size in pixels.
+    ///
+    pub fn new_with_options(
+        context: &Context,
+        fonts: &[Font],
+        glyph_size: u32,
+        spread: u32,
+        page_size: u32,
+    ) -> ThreeDResult<Self> {
+        if fonts.is_empty() {
+            Err(RendererError::MissingFont)?;
+        }
+        Ok(Self {
+            atlas: Rc::new(RefCell::new(AtlasData {
+                context: context.clone(),
+                fonts: fonts.iter().map(|f| f.font.clone()).collect(),
+                glyph_size: glyph_size as f32,
+                spread,
+                page_size,
+                pages: Vec::new(),
+                glyphs: HashMap::new(),
+            })),
+        })
+    }
+
+    ///
+    /// Returns the number of pages in the atlas.
+    ///
+    pub fn page_count(&self) -> usize {
+        self.atlas.borrow().pages.len()
+    }
+
+    // The spread relative to the glyph size, which is the maximum outline width in units of the font size
+    fn relative_spread(&self) -> f32 {
+        let atlas = self.atlas.borrow();
+        atlas.spread as f32 / atlas.glyph_size
+    }
+}
+
+struct AtlasData {
+    context: Context,
+    fonts: Vec<FontArc>,
+    glyph_size: f32,
+    spread: u32,
+    page_size: u32,
+    pages: Vec<AtlasPage>,
+    glyphs: HashMap<char, Glyph>,
+}
+
+struct AtlasPage {
+    data: Vec<u8>,
+    texture: Option<Texture2D<u8>>,
+    dirty: bool,
+    // The shelf packing state; the position of the next glyph in the current row and the height of the row
+    cursor: (u32, u32),
+    row_height: u32,
+}
+
+// A glyph in units of the font size, where the origin is at the baseline and y points up
+#[derive(Clone, Copy)]
+struct Glyph {
+    font_index: usize,
+    id: GlyphId,
+    advance: f32,
+    // The page, the bounds (min x, min y, max x, max y) and the uv coordinates of the quad if the glyph is not empty
+    quad: Option<(usize, Vec4, Vec4)>,
+}
+
+impl AtlasData {
+    fn glyph(&mut self, c: char) -> Glyph {
+        if let Some(glyph) = self.glyphs.get(&c) {
+            return *glyph;
+        }
+        let (font_index, id) = self
+            .fonts
+            .iter()
+            .enumerate()
+            .map(|(i, font)| (i, font.glyph_id(c)))
+            .find(|(_, id)| id.0 != 0)
+            .unwrap_or((0, GlyphId(0)));
+        let font = self.fonts[font_index].clone();
+        let scaled_font = font.as_scaled(PxScale::from(self.glyph_size));
+        let advance = scaled_font.h_advance(id) / self.glyph_size;
+        let quad = font
+            .outline_glyph(id.with_scale(self.glyph_size))
+            .and_then(|outline| self.add_sdf(&outline));
+        let glyph = Glyph {
+            font_index,
+            id,
+            advance,
+            quad,
+        };
+        self.glyphs.insert(c, glyph);
+        glyph
+    }
+
+    fn kerning(&self, first: &Glyph, second: &Glyph) -> f32 {
+        if first.font_index != second.font_index {
+            return 0.0;
+        }
+        self.fonts[first.font_index]
+            .as_scaled(PxScale::from(self.glyph_size))
+            .kern(first.id, second.id)
+            / self.glyph_size
+    }
+
+    // The line height of the primary font in units of the font size
+    fn line_height(&self) -> f32 {
+        let scaled_font = self.fonts[0].as_scaled(PxScale::from(self.glyph_size));
+        (scaled_font.height() + scaled_font.line_gap()) / self.glyph_size
+    }
+
+    // Rasterizes the outline, computes the signed distance field and stores it in a page with room for it
+    fn add_sdf(&mut self, outline: &ab_glyph::OutlinedGlyph) -> Option<(usize, Vec4, Vec4)> {
+        let bounds = outline.px_bounds();
+        let spread = self.spread;
+        let glyph_width = bounds.width().ceil() as u32;
+        let glyph_height = bounds.height().ceil() as u32;
+        let width = glyph_width + 2 * spread;
+        let height = glyph_height + 2 * spread;
+        if glyph_width == 0
+            || glyph_height == 0
+            || width > self.page_size
+            || height > self.page_size
+        {
+            return None;
+        }
+
+        let mut inside = vec![false; (width * height) as usize];
+        outline.draw(|x, y, coverage| {
+            if coverage >= 0.5 && x < glyph_width && y < glyph_height {
+                // Flips the rows such that the first row is at the bottom of the glyph
+                let row = height - 1 - (y + spread);
+                inside[(row * width + x + spread) as usize] = true;
+            }
+        });
+        let (w, h) = (width as usize, height as usize);
+        let outside_distances = squared_distances(&inside, true, w, h);
+        let inside_distances = squared_distances(&inside, false, w, h);
+
+        let (page_index, x, y) = self.allocate(width, height);
+        let page_size = self.page_size as usize;
+        let page = &mut self.pages[page_index];
+        for row in 0..h {
+            for column in 0..w {
+                let i = row * w + column;
+                let distance = outside_distances[i].sqrt() - inside_distances[i].sqrt();
+                let value = (0.5 - distance / (2.0 * spread as f64)).max(0.0).min(1.0);
+                page.data[(y as usize + row) * page_size + x as usize + column] =
+                    (value * 255.0).round() as u8;
+            }
+        }
+        page.dirty = true;
+
+        let bounds = vec4(
+            bounds.min.x - spread as f32,
+            -bounds.max.y - spread as f32,
+            bounds.max.x + spread as f32,
+            -bounds.min.y + spread as f32,
+        ) / self.glyph_size;
+        let size = self.page_size as f32;
+        let uvs = vec4(
+            x as f32 / size,
+            y as f32 / size,
+            (x + width) as f32 / size,
+            (y + height) as f32 / size,
+        );
+        Some((page_index, bounds, uvs))
+    }
+
+    // Finds room for a rectangle of the given size in the last page, or in a new page if the last page is full
+    fn allocate(&mut self, width: u32, height: u32) -> (usize, u32, u32) {
+        let page_size = self.page_size;
+        if let Some(page) = self.pages.last_mut() {
+            if page.cursor.0 + width > page_size {
+                page.cursor = (0, page.cursor.1 + page.row_height);
+                page.row_height = 0;
+            }
+            if page.cursor.1 + height <= page_size {
+                let (x, y) = page.cursor;
+                page.cursor.0 += width;
+                page.row_height = page.row_height.max(height);
+                return (self.pages.len() - 1, x, y);
+            }
+        }
+        self.pages.push(AtlasPage {
+            data: vec![0; (page_size * page_size) as usize],
+            texture: None,
+            dirty: true,
+            cursor: (width, 0),
+            row_height: height,
+        });
+        (self.pages.len() - 1, 0, 0)
+    }
+
+    // Returns the texture of the given page after uploading the glyphs added since the last call
+    fn page_texture(&mut self, page_index: usize) -> ThreeDResult<&Texture2D<u8>> {
+        let context = &self.context;
+        let page_size = self.page_size;
+        let page = &mut self.pages[page_index];
+        if page.texture.is_none() {
+            page.texture = Some(Texture2D::new_empty(
+                context,
+                page_size,
+                page_size,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::R,
+            )?);
+        }
+        let texture = page.texture.as_mut().unwrap();
+        if page.dirty {
+            texture.fill(&page.data)?;
+            page.dirty = false;
+        }
+        Ok(texture)
+    }
+}
+
+// The squared distance from each cell to the nearest cell where `inside` equals `target`,
+// computed with the separable algorithm by Felzenszwalb and Huttenlocher
+fn squared_distances(inside: &[bool], target: bool, width: usize, height: usize) -> Vec<f64> {
+    let mut grid: Vec<f64> = inside
+        .iter()
+        .map(|v| if *v == target { 0.0 } else { INFINITY })
+        .collect();
+    let n = width.max(height);
+    let mut f = vec![0.0; n];
+    let mut d = vec![0.0; n];
+    let mut v = vec![0; n];
+    let mut z = vec![0.0; n + 1];
+    for x in 0..width {
+        for y in 0..height {
+            f[y] = grid[y * width + x];
+        }
+        distances_1d(&f[..height], &mut d, &mut v, &mut z);
+        for y in 0..height {
+            grid[y * width + x] = d[y];
+        }
+    }
+    for y in 0..height {
+        f[..width].copy_from_slice(&grid[y * width..(y + 1) * width]);
+        distances_1d(&f[..width], &mut d, &mut v, &mut z);
+        grid[y * width..(y + 1) * width].copy_from_slice(&d[..width]);
+    }
+    grid
+}
+
+const INFINITY: f64 = 1e20;
+
+// The one dimensional squared distance transform of the sampled function f, using the lower envelope of parabolas
+fn distances_1d(f: &[f64], d: &mut [f64], v: &mut [usize], z: &mut [f64]) {
+    let intersection = |q: usize, p: usize| {
+        ((f[q] + (q * q) as f64) - (f[p] + (p * p) as f64)) / (2.0 * q as f64 - 2.0 * p as f64)
+    };
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = -INFINITY;
+    z[1] = INFINITY;
+    for q in 1..f.len() {
+        let mut s = intersection(q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersection(q, v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = INFINITY;
+    }
+    k = 0;
+    for q in 0..f.len() {
+        while z[k + 1] < q as f64 {
+            k += 1;
+        }
+        let p = v[k];
+        d[q] = (q as f64 - p as f64).powi(2) + f[p];
+    }
+}
+
+///
+/// A text rendered in the 3D world as crisp, antialiased glyphs at any scale using the signed distance fields in a [GlyphAtlas].
+/// The text is placed in the xy-plane with the baseline of the first line along the x-axis starting at the origin,
+/// and the lines are separated by `\n`. Use [GeometryMut::set_transformation] to place the text in the scene.
+///
+/// Characters missing from the primary font are looked up in the fallback fonts of the atlas, so a text can mix scripts and emoji,
+/// and combining marks, which have no advance, are placed on top of the preceding character.
+/// An outline or halo around the glyphs, for example to make the text readable on any background, is added using [Text::set_outline].
+///
+#[derive(Clone)]
+pub struct Text {
+    context: Context,
+    atlas: GlyphAtlas,
+    text: String,
+    size: f32,
+    color: Color,
+    outline_color: Color,
+    outline_width: f32,
+    outline_softness: f32,
+    models: Vec<Model<TextMaterial>>,
+    aabb_local: AxisAlignedBoundingBox,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+}
+
+impl Text {
+    ///
+    /// Creates a new text using the glyphs in the given atlas, where the given size is the height of the font in world units, ie. the distance from the descender to the ascender.
+    ///
+    pub fn new(context: &Context, atlas: &GlyphAtlas, text: &str, size: f32) -> ThreeDResult<Self> {
+        let mut t = Self {
+            context: context.clone(),
+            atlas: atlas.clone(),
+            text: String::new(),
+            size,
+            color: Color::BLACK,
+            outline_color: Color::WHITE,
+            outline_width: 0.0,
+            outline_softness: 0.0,
+            models: Vec::new(),
+            aabb_local: AxisAlignedBoundingBox::EMPTY,
+            aabb: AxisAlignedBoundingBox::EMPTY,
+            transformation: Mat4::identity(),
+        };
+        t.set_text(text)?;
+        Ok(t)
+    }
+
+    ///
+    /// Returns the text.
+    ///
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    ///
+    /// Sets the text, which generates the glyphs not already in the atlas.
+    ///
+    pub fn set_text(&mut self, text: &str) -> ThreeDResult<()> {
+        self.text = text.to_string();
+        self.update()
+    }
+
+    ///
+    /// Returns the height of the font in world units.
+    ///
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    ///
+    /// Sets the height of the font in world units.
+    ///
+    pub fn set_size(&mut self, size: f32) -> ThreeDResult<()> {
+        self.size = size;
+        self.update()
+    }
+
+    ///
+    /// Sets the color of the glyphs.
+    ///
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        for model in self.models.iter_mut() {
+            model.material.color = color;
+        }
+    }
+
+    ///
+    /// Sets the color, width and softness of the outline around the glyphs, where the width and softness are relative to the [size](Text::size).
+    /// A small width gives a sharp outline and a larger width and softness gives a halo which fades out.
+    /// The width is limited by the spread of the atlas and a width of zero removes the outline.
+    ///
+    pub fn set_outline(&mut self, color: Color, width: f32, softness: f32) {
+        self.outline_color = color;
+        self.outline_width = width;
+        self.outline_softness = softness;
+        let (width, softness) = self.outline_parameters();
+        for model in self.models.iter_mut() {
+            model.material.outline_color = color;
+            model.material.outline_width = width;
+            model.material.outline_softness = softness;
+        }
+    }
+
+    // The outline width and softness in units of the signed distance field, where 0.5 is the edge of the glyph and 0.0 is the spread
+    fn outline_parameters(&self) -> (f32, f32) {
+        let scale = 0.5 / self.atlas.relative_spread();
+        (
+            (self.outline_width * scale).min(0.5),
+            self.outline_softness * scale,
+        )
+    }
+
+    // Lays out the text and creates a model for each atlas page that the glyphs are placed on
+    fn update(&mut self) -> ThreeDResult<()> {
+        let mut meshes: Vec<(Vec<f32>, Vec<f32>, Vec<u32>)> = Vec::new();
+        {
+            let mut atlas = self.atlas.atlas.borrow_mut();
+            let line_height = atlas.line_height();
+            for (line_index, line) in self.text.lines().enumerate() {
+                let y = -(line_index as f32) * line_height;
+                let mut x = 0.0;
+                let mut previous: Option<Glyph> = None;
+                for c in line.chars() {
+                    let glyph = atlas.glyph(c);
+                    if let Some(ref p) = previous {
+                        x += atlas.kerning(p, &glyph);
+                    }
+                    if let Some((page, bounds, uvs)) = glyph.quad {
+                        while meshes.len() <= page {
+                            meshes.push((Vec::new(), Vec::new(), Vec::new()));
+                        }
+                        let (positions, uv_coordinates, indices) = &mut meshes[page];
+                        let index = (positions.len() / 3) as u32;
+                        let s = self.size;
+                        positions.extend_from_slice(&[
+                            s * (x + bounds.x),
+                            s * (y + bounds.y),
+                            0.0,
+                            s * (x + bounds.z),
+                            s * (y + bounds.y),
+                            0.0,
+                            s * (x + bounds.z),
+                            s * (y + bounds.w),
+                            0.0,
+                            s * (x + bounds.x),
+                            s * (y + bounds.w),
+                            0.0,
+                        ]);
+                        uv_coordinates.extend_from_slice(&[
+                            uvs.x, uvs.y, uvs.z, uvs.y, uvs.z, uvs.w, uvs.x, uvs.w,
+                        ]);
+                        indices.extend_from_slice(&[
+                            index,
+                            index + 1,
+                            index + 2,
+                            index,
+                            index + 2,
+                            index + 3,
+                        ]);
+                    }
+                    x += glyph.advance;
+                    previous = Some(glyph);
+                }
+            }
+        }
+
+        let (outline_width, outline_softness) = self.outline_parameters();
+        self.models.clear();
+        self.aabb_local = AxisAlignedBoundingBox::EMPTY;
+        for (page, (positions, uvs, indices)) in meshes.into_iter().enumerate() {
+            if indices.is_empty() {
+                continue;
+            }
+            let mut model = Model::new_with_material(
+                &self.context,
+                &CPUMesh {
+                    positions,
+                    uvs: Some(uvs),
+                    indices: Some(Indices::U32(indices)),
+                    ..Default::default()
+                },
+                TextMaterial {
+                    atlas: self.atlas.clone(),
+                    page,
+                    color: self.color,
+                    outline_color: self.outline_color,
+                    outline_width,
+                    outline_softness,
+                },
+            )?;
+            model.set_transformation(self.transformation);
+            self.aabb_local.expand_with_aabb(&model.aabb());
+            self.models.push(model);
+        }
+        let mut aabb = self.aabb_local;
+        aabb.transform(&self.transformation);
+        self.aabb = aabb;
+        Ok(())
+    }
+}
+
+impl Shadable for Text {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        for model in self.models.iter() {
+            model.render_with_material(material, camera, lights)?;
+        }
+        Ok(())
+    }
+
+    fn render_forward(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &Lights,
+    ) -> ThreeDResult<()> {
+        self.render_with_material(material, camera, lights)
+    }
+
+    #[allow(deprecated)]
+    fn render_deferred(
+        &self,
+        material: &DeferredPhysicalMaterial,
+        camera: &Camera,
+        viewport: Viewport,
+    ) -> ThreeDResult<()> {
+        for model in self.models.iter() {
+            model.render_deferred(material, camera, viewport)?;
+        }
+        Ok(())
+    }
+}
+
+impl Geometry for Text {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+}
+
+impl GeometryMut for Text {
+    fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        for model in self.models.iter_mut() {
+            model.set_transformation(transformation);
+        }
+        let mut aabb = self.aabb_local;
+        aabb.transform(&self.transformation);
+        self.aabb = aabb;
+    }
+}
+
+impl Object for Text {
+    fn render(&self, camera: &Camera, lights: &Lights) -> ThreeDResult<()> {
+        for model in self.models.iter() {
+            model.render(camera, lights)?;
+        }
+        Ok(())
+    }
+
+    fn is_transparent(&self) -> bool {
+        true
+    }
+}
+
+// Renders the glyphs on one page of the atlas, where the texture of the page is updated before it is used
+#[derive(Clone)]
+struct TextMaterial {
+    atlas: GlyphAtlas,
+    page: usize,
+    color: Color,
+    outline_color: Color,
+    outline_width: f32,
+    outline_softness: f32,
+}
+
+impl Material for TextMaterial {
+    fn fragment_shader_source(&self, _use_vertex_colors: bool, _lights: &Lights) -> String {
+        format!(
+            "{}{}",
+            include_str!("../../core/shared.frag"),
+            include_str!("shaders/text.frag")
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _camera: &Camera,
+        _lights: &Lights,
+    ) -> ThreeDResult<()> {
+        program.use_uniform_vec4("textColor", &self.color.to_vec4())?;
+        program.use_uniform_vec4("outlineColor", &self.outline_color.to_vec4())?;
+        program.use_uniform_float("outlineWidth", &self.outline_width)?;
+        program.use_uniform_float("outlineSoftness", &self.outline_softness)?;
+        let mut atlas = self.atlas.atlas.borrow_mut();
+        program.use_texture("glyphAtlas", atlas.page_texture(self.page)?)
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+
+    fn is_transparent(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,225 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A bar attached to a position in the scene, for example a health bar above a unit or a progress bar above a building, see [WorldSpaceBars].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WorldSpaceBar {
+    /// The position in world space which the bar is attached to.
+    pub position: Vec3,
+    /// The filled fraction of the bar in the range `[0..1]`, where the bar is filled from the left.
+    pub fraction: f32,
+    /// The color of the filled part of the bar.
+    pub color: Color,
+    /// The width and height of the bar in pixels.
+    pub size: (f32, f32),
+    /// The vertical offset in pixels of the center of the bar from the position on the screen, for example to place the bar above the head of a unit.
+    pub offset: f32,
+}
+
+impl Default for WorldSpaceBar {
+    fn default() -> Self {
+        Self {
+            position: vec3(0.0, 0.0, 0.0),
+            fraction: 1.0,
+            color: Color::new_opaque(80, 220, 80),
+            size: (40.0, 6.0),
+            offset: 0.0,
+        }
+    }
+}
+
+///
+/// A large number of bars, for example health bars or progress bars, which are attached to positions in the scene and always face the camera.
+/// The bars have a constant size in pixels regardless of the distance to the camera and are all rendered in a single instanced draw call.
+/// The bars are hidden by the geometry in front of them, or drawn with a reduced alpha instead if [WorldSpaceBars::occluded_alpha] is set.
+///
+/// The bars are transparent and should be rendered after all of the geometry which can hide them.
+///
+pub struct WorldSpaceBars {
+    context: Context,
+    /// The color of the unfilled part of the bars.
+    pub background_color: Color,
+    /// If set, the bars fade out between the first and the second distance to the camera and are hidden further away than the second distance.
+    pub fade_distances: Option<(f32, f32)>,
+    /// If set, the parts of the bars which are hidden by geometry are drawn with the alpha multiplied by this value instead of not being drawn at all.
+    pub occluded_alpha: Option<f32>,
+    corner_buffer: VertexBuffer,
+    position_buffer: InstanceBuffer,
+    color_buffer: InstanceBuffer,
+    bar_buffer: InstanceBuffer,
+    count: usize,
+    capacity: usize,
+    position_data: Vec<f32>,
+    color_data: Vec<u8>,
+    bar_data: Vec<f32>,
+}
+
+impl WorldSpaceBars {
+    ///
+    /// Constructs new bars from the given description of each bar.
+    ///
+    pub fn new(context: &Context, bars: &[WorldSpaceBar]) -> ThreeDResult<Self> {
+        let corners = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0];
+        let mut world_space_bars = Self {
+            context: context.clone(),
+            background_color: Color::new(0, 0, 0, 160),
+            fade_distances: None,
+            occluded_alpha: None,
+            corner_buffer: VertexBuffer::new_with_static(context, &corners)?,
+            position_buffer: InstanceBuffer::new(context)?,
+            color_buffer: InstanceBuffer::new(context)?,
+            bar_buffer: InstanceBuffer::new(context)?,
+            count: 0,
+            capacity: 0,
+            position_data: Vec::new(),
+            color_data: Vec::new(),
+            bar_data: Vec::new(),
+        };
+        world_space_bars.set_bars(bars);
+        Ok(world_space_bars)
+    }
+
+    ///
+    /// Replaces the bars with the given bars.
+    /// The buffers are only reallocated when the number of bars is larger than ever before,
+    /// so the bars can be updated every frame without allocating memory.
+    ///
+    pub fn set_bars(&mut self, bars: &[WorldSpaceBar]) {
+        self.position_data.clear();
+        self.color_data.clear();
+        self.bar_data.clear();
+        for bar in bars {
+            self.position_data
+                .extend_from_slice(&[bar.position.x, bar.position.y, bar.position.z]);
+            self.color_data.extend_from_slice(&[
+                bar.color.r,
+                bar.color.g,
+                bar.color.b,
+                bar.color.a,
+            ]);
+            self.bar_data.extend_from_slice(&[
+                bar.fraction.max(0.0).min(1.0),
+                bar.size.0,
+                bar.size.1,
+                bar.offset,
+            ]);
+        }
+
+        let count = bars.len();
+        if count > self.capacity {
+            self.position_buffer.allocate::<f32>(count * 3);
+            self.color_buffer.allocate::<u8>(count * 4);
+            self.bar_buffer.allocate::<f32>(count * 4);
+            self.capacity = count;
+        }
+        if count > 0 {
+            self.position_buffer.fill_subset(0, &self.position_data);
+            self.color_buffer.fill_subset(0, &self.color_data);
+            self.bar_buffer.fill_subset(0, &self.bar_data);
+        }
+        self.count = count;
+    }
+
+    ///
+    /// Moves the bars to the given positions while keeping the rest of the bars unchanged,
+    /// for example to follow units which move every frame. Does not allocate any memory.
+    ///
+    /// # Errors
+    /// Will return an error if the number of positions is different from the number of bars.
+    ///
+    pub fn set_positions(&mut self, positions: &[Vec3]) -> ThreeDResult<()> {
+        if positions.len() != self.count {
+            Err(RendererError::InvalidBarCount(positions.len(), self.count))?;
+        }
+        self.position_data.clear();
+        for p in positions {
+            self.position_data.extend_from_slice(&[p.x, p.y, p.z]);
+        }
+        if self.count > 0 {
+            self.position_buffer.fill_subset(0, &self.position_data);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the number of bars.
+    ///
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    ///
+    /// Returns whether or not the bars are transparent, which is always the case since they are blended with the scene behind them.
+    ///
+    pub fn is_transparent(&self) -> bool {
+        true
+    }
+
+    ///
+    /// Renders all of the bars in a single draw call, or two if [WorldSpaceBars::occluded_alpha] is set.
+    /// Must be called in a render target render function after the geometry which can hide the bars,
+    /// for example in the callback function of [Screen::write].
+    ///
+    pub fn render(&self, camera: &Camera) -> ThreeDResult<()> {
+        if self.count == 0 {
+            return Ok(());
+        }
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::None,
+            ..Default::default()
+        };
+        let vertex_shader_source = if self.fade_distances.is_some() {
+            format!(
+                "#define USE_DISTANCE_FADE\n{}",
+                include_str!("shaders/world_space_bars.vert")
+            )
+        } else {
+            include_str!("shaders/world_space_bars.vert").to_string()
+        };
+        self.context.program(
+            &vertex_shader_source,
+            &format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/world_space_bars.frag")
+            ),
+            |program| {
+                let viewport = camera.viewport();
+                program.use_uniform_block("Camera", camera.uniform_buffer());
+                program.use_uniform_vec2(
+                    "viewportSize",
+                    &vec2(viewport.width as f32, viewport.height as f32),
+                )?;
+                if let Some((start, end)) = self.fade_distances {
+                    program.use_uniform_vec2("fadeDistances", &vec2(start, end))?;
+                }
+                program.use_uniform_vec4("backgroundColor", &self.background_color.to_vec4())?;
+                program.use_attribute_vec2("corner", &self.corner_buffer)?;
+                program.use_attribute_vec3_instanced("center", &self.position_buffer)?;
+                program.use_attribute_vec4_instanced("color", &self.color_buffer)?;
+                program.use_attribute_vec4_instanced("bar", &self.bar_buffer)?;
+
+                // The parts of the bars behind the geometry are drawn first using the inverted depth test
+                if let Some(occluded_alpha) = self.occluded_alpha {
+                    program.use_uniform_float("alpha", &occluded_alpha)?;
+                    program.draw_arrays_instanced(
+                        RenderStates {
+                            depth_test: DepthTest::Greater,
+                            ..render_states
+                        },
+                        viewport,
+                        6,
+                        self.count as u32,
+                    );
+                }
+                program.use_uniform_float("alpha", &1.0)?;
+                program.draw_arrays_instanced(render_states, viewport, 6, self.count as u32);
+                Ok(())
+            },
+        )
+    }
+}
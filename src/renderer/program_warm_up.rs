@@ -0,0 +1,37 @@
+use crate::core::*;
+use crate::renderer::*;
+
+impl Context {
+    ///
+    /// Compiles the programs needed to render a [Model] with each of the given materials, for each of the given light setups
+    /// and with and without vertex colors as specified by `has_colors`,
+    /// so that the first frame rendered with those combinations does not stall while compiling shaders.
+    /// Combined with [Context::set_program_binary_directory], the programs are loaded from disk instead of compiled when the application is started again.
+    /// Use [Context::program_cache_statistics] to check whether any programs are still compiled after the warm-up.
+    ///
+    /// **Note:** Only the programs used when rendering models without morph targets directly to a render target are compiled,
+    /// not the programs used for instanced models, models with morph targets or when rendering with an [HdrPipeline].
+    /// The number of lights of each type and whether or not they cast shadows must be the same as when rendering.
+    ///
+    pub fn warm_up_programs(
+        &self,
+        materials: &[&dyn Material],
+        lights_variants: &[&Lights],
+        has_colors: &[bool],
+    ) -> ThreeDResult<()> {
+        for material in materials {
+            for lights in lights_variants {
+                for use_vertex_colors in has_colors {
+                    let fragment_shader_source =
+                        material.fragment_shader_source(*use_vertex_colors, lights);
+                    self.program(
+                        &Model::<ColorMaterial>::vertex_shader_source(&fragment_shader_source)?,
+                        &fragment_shader_source,
+                        |_| Ok(()),
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// An attribute of a [Shadable] object which is interpolated across the triangles and is available as an input in the fragment shader.
+/// The names of the inputs are stable, so the code blocks added to a [ShaderSourceBuilder] can use them directly.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShaderInput {
+    /// The position in world space, available as `in vec3 pos;`.
+    Position,
+    /// The normal in world space, available as `in vec3 nor;`.
+    Normal,
+    /// The tangent and bitangent in world space, available as `in vec3 tang;` and `in vec3 bitang;`.
+    Tangents,
+    /// The uv coordinates, available as `in vec2 uvs;`.
+    Uvs,
+    /// The second set of uv coordinates, available as `in vec2 uvs2;`.
+    Uvs2,
+    /// The vertex color, available as `in vec4 col;`. Only use this if the object has vertex colors, see the `use_vertex_colors` parameter of [Material::fragment_shader_source].
+    Color,
+}
+
+impl ShaderInput {
+    fn declaration(&self) -> &'static str {
+        match self {
+            ShaderInput::Position => "in vec3 pos;\n",
+            ShaderInput::Normal => "in vec3 nor;\n",
+            ShaderInput::Tangents => "in vec3 tang;\nin vec3 bitang;\n",
+            ShaderInput::Uvs => "in vec2 uvs;\n",
+            ShaderInput::Uvs2 => "in vec2 uvs2;\n",
+            ShaderInput::Color => "in vec4 col;\n",
+        }
+    }
+}
+
+///
+/// Assembles the source code of a fragment shader from defines, inputs and named code blocks, and the source code of the vertex shader matching it.
+/// Use this to implement [Material::fragment_shader_source] for a custom material, which is also how the built-in materials are implemented.
+///
+/// The fragment shader source consists of the defines in the order they are added, then the declarations of the [ShaderInput]s
+/// and finally the code blocks in the order they are added.
+/// The version and precision header for the current platform (`#version 330 core` on desktop and `#version 300 es` on web) is added when the shader is compiled,
+/// so it must not be part of the source.
+///
+/// ```ignore
+/// struct RimMaterial {
+///     color: Color,
+/// }
+///
+/// impl Material for RimMaterial {
+///     fn fragment_shader_source(&self, use_vertex_colors: bool, lights: &Lights) -> String {
+///         let mut builder = ShaderSourceBuilder::new();
+///         builder.input(ShaderInput::Position).input(ShaderInput::Normal);
+///         if use_vertex_colors {
+///             builder.define("USE_VERTEX_COLORS").input(ShaderInput::Color);
+///         }
+///         builder.lights(lights).block(
+///             "main",
+///             "uniform vec4 rimColor;
+///             layout (location = 0) out vec4 outColor;
+///             void main() {
+///                 vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+///                 float rim = 1.0 - max(dot(normal, normalize(eyePosition - pos)), 0.0);
+///                 outColor = vec4(encode_output(rim * rimColor.rgb), 1.0);
+///             }",
+///         );
+///         builder.fragment_source()
+///     }
+///     fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &Lights) -> ThreeDResult<()> {
+///         lights.use_uniforms(program, camera)?;
+///         program.use_uniform_vec4("rimColor", &self.color.to_vec4())
+///     }
+///     fn render_states(&self) -> RenderStates {
+///         RenderStates::default()
+///     }
+///     fn is_transparent(&self) -> bool {
+///         false
+///     }
+/// }
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct ShaderSourceBuilder {
+    defines: Vec<(String, Option<String>)>,
+    inputs: Vec<ShaderInput>,
+    blocks: Vec<(String, String)>,
+}
+
+impl ShaderSourceBuilder {
+    ///
+    /// Constructs a new builder without any defines, inputs or code blocks.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Adds `#define name` to both the fragment and the vertex shader, for example `INSTANCED` or a define used for enabling a feature of the material.
+    /// Adding the same define twice has no effect.
+    ///
+    pub fn define(&mut self, name: &str) -> &mut Self {
+        self.set_define(name, None)
+    }
+
+    ///
+    /// Adds `#define name value` to both the fragment and the vertex shader, for example `NUM_DIR_LIGHTS 2`.
+    /// If the define is already added, the value is replaced.
+    ///
+    pub fn define_value(&mut self, name: &str, value: impl std::fmt::Display) -> &mut Self {
+        self.set_define(name, Some(value.to_string()))
+    }
+
+    fn set_define(&mut self, name: &str, value: Option<String>) -> &mut Self {
+        if let Some(define) = self.defines.iter_mut().find(|(n, _)| n == name) {
+            define.1 = value;
+        } else {
+            self.defines.push((name.to_string(), value));
+        }
+        self
+    }
+
+    ///
+    /// Returns whether or not the given define is added.
+    ///
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.defines.iter().any(|(n, _)| n == name)
+    }
+
+    ///
+    /// Declares the given input in the fragment shader, which makes the vertex shader output it.
+    /// Adding the same input twice has no effect.
+    ///
+    pub fn input(&mut self, input: ShaderInput) -> &mut Self {
+        if !self.inputs.contains(&input) {
+            self.inputs.push(input);
+        }
+        self
+    }
+
+    ///
+    /// Adds a named block of code after the previously added blocks.
+    /// If a block with the same name is already added, the code of that block is replaced instead, so the order of the blocks is kept.
+    ///
+    pub fn block(&mut self, name: &str, source: &str) -> &mut Self {
+        if let Some(block) = self.blocks.iter_mut().find(|(n, _)| n == name) {
+            block.1 = source.to_string();
+        } else {
+            self.blocks.push((name.to_string(), source.to_string()));
+        }
+        self
+    }
+
+    ///
+    /// Adds the block named `shared` containing the utility functions shared by all shaders, for example `rgb_from_srgb` and `encode_output`.
+    /// The functions are also part of the lighting functions, so this is not needed if [ShaderSourceBuilder::lights] is used.
+    ///
+    pub fn shared_functions(&mut self) -> &mut Self {
+        self.block("shared", include_str!("../core/shared.frag"))
+    }
+
+    ///
+    /// Adds the block named `noise` containing procedural noise functions, which are all seeded and have a tiled variant repeating with an integer period:
+    /// - `value_noise(p, seed)` value noise in the range `[0..1]`
+    /// - `gradient_noise(p, seed)` gradient (Perlin) noise in the range `[-1..1]`
+    /// - `cellular_noise(p, seed)` cellular (Voronoi) noise returning the distances to the closest and second closest feature points
+    /// - `fbm(p, noise_type, octaves, seed)` fractal noise in the range `[0..1]` summing octaves of `NOISE_VALUE`, `NOISE_GRADIENT` or `NOISE_CELLULAR` noise
+    /// - `domain_warp(p, strength, seed)` distorts a position by gradient noise
+    ///
+    /// The tiled variants are named with a `_tiled` suffix and take an `ivec3` period after the position, for example `fbm_tiled(p, noise_type, octaves, period, seed)`.
+    /// The position is a `vec3` and the seed a `uint`. The noise only depends on integer hashing, so it is identical on all platforms.
+    ///
+    pub fn noise_functions(&mut self) -> &mut Self {
+        self.block("noise", include_str!("material/shaders/noise.frag"))
+    }
+
+    ///
+    /// Adds the block named `lights` containing the lighting functions for the given lights,
+    /// most notably `calculate_lighting(surface_color, position, normal, metallic, roughness, occlusion)` which returns the color of a surface lit by all of the lights.
+    /// The uniforms needed by the functions are sent by [Lights::use_uniforms].
+    ///
+    pub fn lights(&mut self, lights: &Lights) -> &mut Self {
+        self.block("lights", &lights.fragment_shader_source())
+    }
+
+    ///
+    /// Adds the block named `alpha_mode` containing the code needed for the given alpha mode.
+    /// The block must be added after the shared functions or the lighting functions and the uniforms are sent by [AlphaMode::use_uniforms].
+    ///
+    pub fn alpha_mode(&mut self, alpha_mode: AlphaMode) -> &mut Self {
+        self.block("alpha_mode", alpha_mode.fragment_shader_source())
+    }
+
+    fn defines_source(&self) -> String {
+        let mut source = String::new();
+        for (name, value) in self.defines.iter() {
+            if let Some(value) = value {
+                source.push_str(&format!("#define {} {}\n", name, value));
+            } else {
+                source.push_str(&format!("#define {}\n", name));
+            }
+        }
+        source
+    }
+
+    ///
+    /// Returns the fragment shader source.
+    ///
+    pub fn fragment_source(&self) -> String {
+        let mut source = self.defines_source();
+        for input in self.inputs.iter() {
+            source.push_str(input.declaration());
+        }
+        for (_, block) in self.blocks.iter() {
+            source.push_str(block);
+        }
+        source
+    }
+
+    ///
+    /// Returns the source of the vertex shader used by [Model] which outputs the inputs of the fragment shader.
+    ///
+    pub fn vertex_source(&self) -> ThreeDResult<String> {
+        Ok(format!(
+            "{}{}",
+            self.defines_source(),
+            Model::<ColorMaterial>::vertex_shader_source(&self.fragment_source())?
+        ))
+    }
+
+    ///
+    /// Returns the vertex shader source and the fragment shader source, see [ShaderSourceBuilder::vertex_source] and [ShaderSourceBuilder::fragment_source].
+    ///
+    pub fn build(&self) -> ThreeDResult<(String, String)> {
+        Ok((self.vertex_source()?, self.fragment_source()))
+    }
+}
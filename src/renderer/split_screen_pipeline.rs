@@ -0,0 +1,140 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Render pipeline which renders the same objects from several cameras into their own viewports of the screen, for example local multiplayer split screen.
+/// The shadow maps of the lights are generated once per frame and shared by all of the cameras, instead of once per camera,
+/// and all cameras use the same programs, so only the camera uniform buffer changes between the viewports.
+///
+/// Call [SplitScreenPipeline::new_frame] at the beginning of each frame and then [SplitScreenPipeline::render_pass],
+/// after which anything which should cover the whole window, for example a GUI, can be rendered using [Screen::write] with [ClearState::none].
+///
+pub struct SplitScreenPipeline {
+    context: Context,
+    ///
+    /// The width and height in texels of the shadow maps generated for the lights which cast shadows.
+    ///
+    pub shadow_texture_size: u32,
+    render_order: Vec<RenderOrderKey>,
+    frame: u64,
+    shadow_frame: Option<u64>,
+    shadow_map_generations: u64,
+}
+
+impl SplitScreenPipeline {
+    ///
+    /// Constructor.
+    ///
+    pub fn new(context: &Context) -> ThreeDResult<Self> {
+        Ok(Self {
+            context: context.clone(),
+            shadow_texture_size: 1024,
+            render_order: Vec::new(),
+            frame: 0,
+            shadow_frame: None,
+            shadow_map_generations: 0,
+        })
+    }
+
+    ///
+    /// Starts a new frame, which means that the shadow maps are generated again the next time they are needed.
+    ///
+    pub fn new_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    ///
+    /// Returns the number of frames started using [SplitScreenPipeline::new_frame].
+    ///
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    ///
+    /// Returns the number of times the shadow maps have been generated, which is at most once per frame no matter the number of cameras,
+    /// see [SplitScreenPipeline::generate_shadow_maps].
+    ///
+    pub fn shadow_map_generations(&self) -> u64 {
+        self.shadow_map_generations
+    }
+
+    ///
+    /// Generates the shadow maps of the enabled directional and spot lights which cast shadows,
+    /// unless they have already been generated in this frame.
+    /// The shadow maps of the directional lights cover all of the given objects, so they can be used from any of the cameras.
+    /// This is called by [SplitScreenPipeline::render_pass], so it is only needed if the shadow maps are used before that.
+    ///
+    pub fn generate_shadow_maps(
+        &mut self,
+        objects: &[impl Object],
+        lights: &mut Lights,
+    ) -> ThreeDResult<()> {
+        if self.shadow_frame == Some(self.frame) {
+            return Ok(());
+        }
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for object in objects {
+            aabb.expand_with_aabb(&object.aabb());
+        }
+        let frustum_height = aabb.max().distance(aabb.min());
+        let size = self.shadow_texture_size;
+        for light in lights.directional.iter_mut() {
+            if light.is_enabled() && light.is_shadow_enabled() {
+                light.generate_shadow_map(frustum_height, size, size, objects)?;
+            }
+        }
+        for light in lights.spot.iter_mut() {
+            if light.is_enabled() && light.is_shadow_enabled() {
+                light.generate_shadow_map(size, objects)?;
+            }
+        }
+        self.shadow_frame = Some(self.frame);
+        self.shadow_map_generations += 1;
+        Ok(())
+    }
+
+    ///
+    /// Renders the objects to the screen once for each of the given cameras, each into the viewport of the camera, see [Camera::set_viewport].
+    /// Before rendering, the shadow maps are generated if they have not been generated in this frame, see [SplitScreenPipeline::generate_shadow_maps],
+    /// and each viewport is cleared based on the given clear state without clearing the other viewports.
+    /// Objects outside the frustum of a camera are not rendered into the viewport of that camera.
+    ///
+    pub fn render_pass(
+        &mut self,
+        cameras: &[&Camera],
+        clear_state: ClearState,
+        objects: &[impl Object],
+        lights: &mut Lights,
+    ) -> ThreeDResult<()> {
+        self.render_pass_with_options(
+            cameras,
+            clear_state,
+            objects,
+            lights,
+            RenderPassOptions::default(),
+        )
+    }
+
+    ///
+    /// Same as [SplitScreenPipeline::render_pass], except that the order in which the objects are rendered is specified by the given options.
+    ///
+    pub fn render_pass_with_options(
+        &mut self,
+        cameras: &[&Camera],
+        clear_state: ClearState,
+        objects: &[impl Object],
+        lights: &mut Lights,
+        options: RenderPassOptions,
+    ) -> ThreeDResult<()> {
+        self.generate_shadow_maps(objects, lights)?;
+        let context = self.context.clone();
+        let render_order = &mut self.render_order;
+        Screen::write(&context, ClearState::none(), || {
+            for camera in cameras {
+                clear_partially(&context, clear_state, camera.viewport());
+                render_sorted(camera, objects, lights, options, render_order)?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,55 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Renders the given objects with the given lights into a square color and depth target of the given size,
+/// as seen from the given position looking at the origin, and returns the pixels of the color target.
+///
+pub(crate) fn render(
+    context: &Context,
+    size: u32,
+    eye: Vec3,
+    objects: &[&dyn Object],
+    lights: &Lights,
+) -> ThreeDResult<Vec<u8>> {
+    let viewport = Viewport::new_at_origo(size, size);
+    let camera = Camera::new_perspective(
+        context,
+        viewport,
+        eye,
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    )?;
+    let mut texture = Texture2D::<u8>::new_empty(
+        context,
+        size,
+        size,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )?;
+    let mut depth_texture = DepthTargetTexture2D::new(
+        context,
+        size,
+        size,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )?;
+    RenderTarget::new(context, &mut texture, &mut depth_texture)?.write(
+        ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0),
+        || {
+            for object in objects {
+                object.render(&camera, lights)?;
+            }
+            Ok(())
+        },
+    )?;
+    texture.read(viewport)
+}
@@ -0,0 +1,286 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::path::PathBuf;
+
+///
+/// Where and in which format [export_turntable] writes the frames of the turntable animation.
+/// On web, nothing is written and the path is ignored, but the encoded files are still returned.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum TurntableOutput {
+    ///
+    /// A sequence of PNG images, one for each frame, which are written to the given directory as `0000.png`, `0001.png` etc.
+    /// The directory is created if it does not exist.
+    ///
+    PngSequence(PathBuf),
+    ///
+    /// An animated GIF which is written to the given path and loops forever.
+    /// All of the frames share a palette of 256 colors computed from the first frame.
+    ///
+    #[cfg(feature = "gif-export")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gif-export")))]
+    Gif(PathBuf),
+}
+
+///
+/// Options for rendering a turntable animation using [export_turntable_with_options].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TurntableOptions {
+    /// The number of frames per second, which is embedded in a GIF. A GIF can only store the frame delay in hundredths of a second.
+    pub fps: f32,
+    /// The angle of the camera above the horizontal plane through the center.
+    pub elevation: Degrees,
+    /// The vertical field of view of the camera.
+    pub field_of_view_y: Degrees,
+    /// The up direction of the turntable, the camera circles around the axis through the center in this direction.
+    pub up: Vec3,
+    /// Whether or not the colors of a GIF are dithered, which avoids banding in smooth gradients at the cost of some noise.
+    pub dithering: bool,
+    /// The options used when rendering each frame, see [render_high_res_with_options].
+    pub high_res_options: HighResOptions,
+}
+
+impl Default for TurntableOptions {
+    fn default() -> Self {
+        Self {
+            fps: 30.0,
+            elevation: degrees(20.0),
+            field_of_view_y: degrees(45.0),
+            up: vec3(0.0, 1.0, 0.0),
+            dithering: true,
+            high_res_options: HighResOptions::default(),
+        }
+    }
+}
+
+///
+/// Renders a turntable animation of the objects, where the camera does one full circle around the given center at the given distance,
+/// and writes the frames to the given output, see [TurntableOutput]. Each frame is rendered offscreen at the given resolution.
+/// Returns the encoded files, ie. one PNG file for each frame or a single GIF file, which is useful on web where the files cannot be written.
+/// Must not be called in a render target render function.
+///
+pub fn export_turntable(
+    context: &Context,
+    objects: &[impl Object],
+    lights: &Lights,
+    center: Vec3,
+    radius: f32,
+    frames: u32,
+    resolution: (u32, u32),
+    output: &TurntableOutput,
+) -> ThreeDResult<Vec<Vec<u8>>> {
+    export_turntable_with_options(
+        context,
+        objects,
+        lights,
+        center,
+        radius,
+        frames,
+        resolution,
+        output,
+        TurntableOptions::default(),
+        None,
+    )
+}
+
+///
+/// Same as [export_turntable], except that the camera and encoding are specified by the given options
+/// and the given effects, if any, are applied to each frame, see [render_high_res_with_options].
+///
+pub fn export_turntable_with_options(
+    context: &Context,
+    objects: &[impl Object],
+    lights: &Lights,
+    center: Vec3,
+    radius: f32,
+    frames: u32,
+    resolution: (u32, u32),
+    output: &TurntableOutput,
+    options: TurntableOptions,
+    mut effects: Option<&mut EffectStack>,
+) -> ThreeDResult<Vec<Vec<u8>>> {
+    let (width, height) = resolution;
+    let up = options.up.normalize();
+    // Two directions orthogonal to the up direction spanning the plane of the circle
+    let side = if up.x.abs() < 0.9 {
+        vec3(1.0, 0.0, 0.0)
+    } else {
+        vec3(0.0, 0.0, 1.0)
+    };
+    let u = up.cross(side).normalize();
+    let v = up.cross(u);
+    let elevation = Radians::from(options.elevation).0;
+
+    let mut camera = Camera::new_perspective(
+        context,
+        Viewport::new_at_origo(width, height),
+        center + radius * u,
+        center,
+        up,
+        options.field_of_view_y,
+        0.01 * radius,
+        100.0 * radius,
+    )?;
+    let mut images = Vec::new();
+    for frame in 0..frames {
+        let angle = frame as f32 * 2.0 * std::f32::consts::PI / frames as f32;
+        let direction =
+            elevation.cos() * (angle.cos() * u + angle.sin() * v) + elevation.sin() * up;
+        camera.set_view(center + radius * direction, center, up)?;
+        let image = render_high_res_with_options(
+            context,
+            &camera,
+            objects,
+            lights,
+            width,
+            height,
+            options.high_res_options,
+            effects.as_mut().map(|effects| &mut **effects),
+        )?;
+        // The rendered pixels start with the bottom row, while the image formats start with the top row
+        let row_length = width as usize * 4;
+        let pixels = image
+            .data
+            .chunks(row_length)
+            .rev()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        images.push(pixels);
+    }
+
+    let files = match output {
+        TurntableOutput::PngSequence(_) => images
+            .iter()
+            .map(|pixels| encode_png(pixels, width, height))
+            .collect::<ThreeDResult<Vec<_>>>()?,
+        #[cfg(feature = "gif-export")]
+        TurntableOutput::Gif(_) => vec![encode_gif(&images, width, height, options)?],
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    match output {
+        TurntableOutput::PngSequence(directory) => {
+            std::fs::create_dir_all(directory)?;
+            for (frame, file) in files.iter().enumerate() {
+                crate::Saver::save_file(directory.join(format!("{:04}.png", frame)), file)?;
+            }
+        }
+        #[cfg(feature = "gif-export")]
+        TurntableOutput::Gif(path) => crate::Saver::save_file(path, &files[0])?,
+    }
+    Ok(files)
+}
+
+fn encode_png(pixels: &[u8], width: u32, height: u32) -> ThreeDResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut bytes).encode(
+        pixels,
+        width,
+        height,
+        image::ColorType::Rgba8,
+    )?;
+    Ok(bytes)
+}
+
+#[cfg(feature = "gif-export")]
+fn encode_gif(
+    images: &[Vec<u8>],
+    width: u32,
+    height: u32,
+    options: TurntableOptions,
+) -> ThreeDResult<Vec<u8>> {
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        Err(RendererError::GifTooLarge(width, height))?;
+    }
+    let opaque = |pixels: &[u8]| {
+        pixels
+            .chunks(4)
+            .flat_map(|p| vec![p[0], p[1], p[2], 255])
+            .collect::<Vec<_>>()
+    };
+    let quantizer = color_quant::NeuQuant::new(
+        10,
+        256,
+        &opaque(
+            images
+                .first()
+                .map(|i| i.as_slice())
+                .unwrap_or(&[0, 0, 0, 255]),
+        ),
+    );
+    let palette = quantizer.color_map_rgb();
+
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut bytes, width as u16, height as u16, &palette)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        let delay = (100.0 / options.fps.max(1.0)).round().max(1.0) as u16;
+        for pixels in images {
+            let indices = if options.dithering {
+                dither(&quantizer, pixels, width as usize)
+            } else {
+                pixels
+                    .chunks(4)
+                    .map(|p| quantizer.index_of(&[p[0], p[1], p[2], 255]) as u8)
+                    .collect()
+            };
+            let frame = gif::Frame {
+                delay,
+                width: width as u16,
+                height: height as u16,
+                buffer: std::borrow::Cow::Owned(indices),
+                ..Default::default()
+            };
+            encoder.write_frame(&frame)?;
+        }
+    }
+    Ok(bytes)
+}
+
+// Maps each pixel to the index of the closest palette color using Floyd-Steinberg dithering,
+// ie. the error of each pixel is distributed to the neighbouring pixels which are not yet mapped
+#[cfg(feature = "gif-export")]
+fn dither(quantizer: &color_quant::NeuQuant, pixels: &[u8], width: usize) -> Vec<u8> {
+    let mut colors = pixels
+        .chunks(4)
+        .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+        .collect::<Vec<_>>();
+    let height = colors.len() / width.max(1);
+    let mut indices = vec![0u8; colors.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let color = colors[i];
+            let clamped = [
+                color[0].round().max(0.0).min(255.0) as u8,
+                color[1].round().max(0.0).min(255.0) as u8,
+                color[2].round().max(0.0).min(255.0) as u8,
+                255,
+            ];
+            let index = quantizer.index_of(&clamped);
+            indices[i] = index as u8;
+            let mapped = quantizer.lookup(index).unwrap_or(clamped);
+            let error = [
+                color[0] - mapped[0] as f32,
+                color[1] - mapped[1] as f32,
+                color[2] - mapped[2] as f32,
+            ];
+            let mut spread = |x: usize, y: usize, weight: f32| {
+                if x < width && y < height {
+                    let neighbour = &mut colors[y * width + x];
+                    for c in 0..3 {
+                        neighbour[c] += error[c] * weight;
+                    }
+                }
+            };
+            spread(x + 1, y, 7.0 / 16.0);
+            if x > 0 {
+                spread(x - 1, y + 1, 3.0 / 16.0);
+            }
+            spread(x, y + 1, 5.0 / 16.0);
+            spread(x + 1, y + 1, 1.0 / 16.0);
+        }
+    }
+    indices
+}
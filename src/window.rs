@@ -30,6 +30,17 @@ mod headless;
 #[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
 pub use headless::*;
 
+mod frame_input_generator;
+#[doc(inline)]
+pub use frame_input_generator::*;
+
+#[cfg(feature = "event-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "event-io")))]
+mod event_recording;
+#[doc(inline)]
+#[cfg(feature = "event-io")]
+pub use event_recording::*;
+
 #[cfg(all(feature = "canvas", target_arch = "wasm32"))]
 mod canvas;
 #[doc(inline)]
@@ -39,37 +50,101 @@ pub use canvas::*;
 /// Type of mouse button.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseButton {
     Left,
     Right,
     Middle,
 }
 
+///
+/// The position of the mouse cursor or a touch, given both in logical pixels and in physical pixels with the origin in the top left corner of the window (or canvas on web).
+/// Use [PointerPosition::physical] for anything related to what is rendered, for example picking with [FrameInput::to_viewport_pixel] or positioning a marker under the cursor,
+/// and [PointerPosition::logical] for sizes which should not depend on the device pixel ratio, for example a drag threshold.
+///
+/// The positions of the mouse events used to be a `(f64, f64)` tuple in logical pixels, which is now [PointerPosition::logical].
+/// When migrating, replace the conversion `((device_pixel_ratio * position.0) as f32, (device_pixel_ratio * position.1) as f32)` with [PointerPosition::physical]
+/// or [FrameInput::to_viewport_pixel], since the latter also handles viewports which do not start at the top of the window.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerPosition {
+    /// The position in logical pixels.
+    pub logical: (f64, f64),
+    /// The position in physical pixels, ie. the logical position multiplied by the device pixel ratio.
+    pub physical: (f32, f32),
+}
+
+impl PointerPosition {
+    ///
+    /// Creates a position from the given position in logical pixels.
+    ///
+    pub fn from_logical(logical: (f64, f64), device_pixel_ratio: f64) -> Self {
+        Self {
+            logical,
+            physical: (
+                (logical.0 * device_pixel_ratio) as f32,
+                (logical.1 * device_pixel_ratio) as f32,
+            ),
+        }
+    }
+
+    ///
+    /// Creates a position from the given position in physical pixels.
+    ///
+    pub fn from_physical(physical: (f32, f32), device_pixel_ratio: f64) -> Self {
+        Self {
+            logical: (
+                physical.0 as f64 / device_pixel_ratio,
+                physical.1 as f64 / device_pixel_ratio,
+            ),
+            physical,
+        }
+    }
+
+    ///
+    /// Returns the pixel at this position as expected by the camera functions which takes a pixel, for example [Camera::view_direction_at_pixel](crate::Camera::view_direction_at_pixel)
+    /// and [pick](crate::pick), when the camera uses the given viewport on a screen with the given height in physical pixels.
+    /// The viewport is given relative to the bottom left corner of the screen while this position is relative to the top left corner,
+    /// so this only equals [PointerPosition::physical] if the viewport covers the full height of the screen, for example next to a side panel.
+    ///
+    pub fn to_viewport_pixel(
+        &self,
+        viewport: crate::core::Viewport,
+        screen_height: u32,
+    ) -> (f32, f32) {
+        let top = screen_height as f32 - (viewport.y as f32 + viewport.height as f32);
+        (self.physical.0, self.physical.1 - top + viewport.y as f32)
+    }
+}
+
 /// An input event (from mouse, keyboard or similar).
+/// The positions of the mouse events are given as a [PointerPosition] and the deltas are in logical pixels.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     MousePress {
         button: MouseButton,
-        position: (f64, f64),
+        position: PointerPosition,
         modifiers: Modifiers,
         handled: bool,
     },
     MouseRelease {
         button: MouseButton,
-        position: (f64, f64),
+        position: PointerPosition,
         modifiers: Modifiers,
         handled: bool,
     },
     MouseMotion {
         button: Option<MouseButton>,
         delta: (f64, f64),
-        position: (f64, f64),
+        position: PointerPosition,
         modifiers: Modifiers,
         handled: bool,
     },
     MouseWheel {
         delta: (f64, f64),
-        position: (f64, f64),
+        position: PointerPosition,
         modifiers: Modifiers,
         handled: bool,
     },
@@ -79,6 +154,8 @@ pub enum Event {
         kind: Key,
         modifiers: Modifiers,
         handled: bool,
+        /// Whether the key is held down and this event is repeated with the key repeat rate of the operating system.
+        repeat: bool,
     },
     KeyRelease {
         kind: Key,
@@ -89,11 +166,36 @@ pub enum Event {
         modifiers: Modifiers,
     },
     Text(String),
+    ///
+    /// Composition of text using an input method editor (IME), for example for typing Chinese or Japanese, has started.
+    /// Only sent on web, on desktop the composed text is sent as an [Event::Text] event when it is committed.
+    ///
+    CompositionStart,
+    ///
+    /// The text which is being composed using an input method editor has changed, see [Event::CompositionStart].
+    ///
+    CompositionUpdate(String),
+    ///
+    /// The composition of text using an input method editor has ended and the given text is committed, see [Event::CompositionStart].
+    ///
+    CompositionEnd(String),
+    ///
+    /// The graphics context is lost, which for example happens on mobile devices when switching tabs.
+    /// Rendering is skipped until the context is restored. Only sent on web.
+    ///
+    ContextLost,
+    ///
+    /// The graphics context is restored after it was lost.
+    /// All GPU resources (meshes, textures, models, GUI etc.) created before the context was lost are no longer valid
+    /// and must be recreated when receiving this event. Only sent on web.
+    ///
+    ContextRestored,
 }
 
 /// Keyboard key input.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
 pub enum Key {
     ArrowDown,
     ArrowLeft,
@@ -164,6 +266,7 @@ pub enum Key {
 
 /// State of modifiers (alt, ctrl, shift and command).
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
 pub struct Modifiers {
     /// Either of the alt keys are down (option ⌥ on Mac).
     pub alt: bool,
@@ -181,6 +284,7 @@ pub struct Modifiers {
 /// Input from the window to the rendering (and whatever else needs it) each frame.
 ///
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrameInput {
     /// A list of [events](crate::Event) which has occurred since last frame.
     pub events: Vec<Event>,
@@ -238,6 +342,34 @@ pub struct FrameOutput {
     /// Whether to stop the render loop until next event.
     ///
     pub wait_next_event: bool,
+
+    ///
+    /// Enables or disables VSync if this is set to some value, see also [WindowSettings::vsync].
+    /// Only has an effect where the platform allows changing VSync at runtime, which is currently only on Windows.
+    /// Ignored on web, since VSync is always on.
+    ///
+    pub set_vsync: Option<bool>,
+}
+
+impl FrameInput {
+    ///
+    /// Converts the given position in logical pixels to physical pixels using the device pixel ratio of this frame.
+    ///
+    pub fn logical_to_physical(&self, logical: (f64, f64)) -> (f32, f32) {
+        PointerPosition::from_logical(logical, self.device_pixel_ratio).physical
+    }
+
+    ///
+    /// Returns the pixel at the given position as expected by the camera functions which takes a pixel, for example [Camera::view_direction_at_pixel](crate::Camera::view_direction_at_pixel)
+    /// and [pick](crate::pick), when the camera uses the given viewport on the screen of this frame, see [PointerPosition::to_viewport_pixel].
+    ///
+    pub fn to_viewport_pixel(
+        &self,
+        position: &PointerPosition,
+        viewport: crate::core::Viewport,
+    ) -> (f32, f32) {
+        position.to_viewport_pixel(viewport, self.viewport.height)
+    }
 }
 
 impl Default for FrameOutput {
@@ -247,6 +379,7 @@ impl Default for FrameOutput {
             swap_buffers: true,
             screenshot: None,
             wait_next_event: false,
+            set_vsync: None,
         }
     }
 }
@@ -21,6 +21,8 @@ pub enum CanvasError {
     DocumentMissing,
     #[error("unable to get canvas")]
     CanvasMissing,
+    #[error("failed creating the text input: {0}")]
+    TextInputCreation(String),
     #[error("unable to convert canvas to html canvas: {0}")]
     CanvasConvertFailed(String),
     #[error("unable to get webgl2 context for the given canvas, maybe the browser doesn't support WebGL2{0}")]
@@ -39,12 +41,17 @@ pub struct Window {
     canvas: Option<web_sys::HtmlCanvasElement>,
     window: Rc<web_sys::Window>,
     settings: WindowSettings,
+    surface_settings: RefCell<SurfaceSettings>,
+    context: RefCell<Option<Context>>,
+    resize_observer: Option<web_sys::ResizeObserver>,
     closures: Vec<Closure<dyn FnMut()>>,
     closures_with_event: Vec<Closure<dyn FnMut(web_sys::Event)>>,
     closures_with_mouseevent: Vec<Closure<dyn FnMut(web_sys::MouseEvent)>>,
     closures_with_wheelevent: Vec<Closure<dyn FnMut(web_sys::WheelEvent)>>,
     closures_with_touchevent: Vec<Closure<dyn FnMut(web_sys::TouchEvent)>>,
     closures_with_keyboardevent: Vec<Closure<dyn FnMut(web_sys::KeyboardEvent)>>,
+    closures_with_compositionevent: Vec<Closure<dyn FnMut(web_sys::CompositionEvent)>>,
+    text_input: Option<web_sys::HtmlInputElement>,
 }
 
 impl Window {
@@ -57,13 +64,23 @@ impl Window {
         let mut window = Window {
             canvas: None,
             window: Rc::new(websys_window),
+            surface_settings: RefCell::new(SurfaceSettings {
+                vsync: true,
+                multisamples: settings.multisamples,
+                depth_bits: settings.depth_bits,
+                srgb: false,
+            }),
             settings,
+            context: RefCell::new(None),
+            resize_observer: None,
             closures: Vec::new(),
             closures_with_event: Vec::new(),
             closures_with_mouseevent: Vec::new(),
             closures_with_wheelevent: Vec::new(),
             closures_with_touchevent: Vec::new(),
             closures_with_keyboardevent: Vec::new(),
+            closures_with_compositionevent: Vec::new(),
+            text_input: None,
         };
         if let Some(canvas) = document.get_elements_by_tag_name("canvas").item(0) {
             window.set_canvas(
@@ -107,6 +124,7 @@ impl Window {
     pub fn gl(&self) -> ThreeDResult<Context> {
         let context_options = ContextOptions {
             antialias: self.settings.multisamples > 0,
+            depth: self.settings.depth_bits > 0,
         };
         let context = self
             .canvas
@@ -120,21 +138,47 @@ impl Window {
             .ok_or(CanvasError::WebGL2NotSupported("".to_string()))?
             .dyn_into::<WebGl2RenderingContext>()
             .map_err(|e| CanvasError::WebGL2NotSupported(format!(": {:?}", e)))?;
-        context
-            .get_extension("EXT_color_buffer_float")
-            .map_err(|e| CanvasError::ColorBufferFloatNotSupported(format!("{:?}", e)))?;
-        context
-            .get_extension("OES_texture_float")
-            .map_err(|e| CanvasError::OESTextureFloatNotSupported(format!(": {:?}", e)))?;
-        context
-            .get_extension("OES_texture_float_linear")
-            .map_err(|e| CanvasError::OESTextureFloatNotSupported(format!(": {:?}", e)))?;
+        enable_extensions(&context)?;
+        let parameter = |name| {
+            context
+                .get_parameter(name)
+                .ok()
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0) as u8
+        };
+        *self.surface_settings.borrow_mut() = SurfaceSettings {
+            vsync: true,
+            multisamples: parameter(WebGl2RenderingContext::SAMPLES),
+            depth_bits: parameter(WebGl2RenderingContext::DEPTH_BITS),
+            srgb: false,
+        };
 
-        Ok(crate::core::Context::from_gl_context(
-            crate::context::GLContext::new(context),
-        ))
+        let context =
+            crate::core::Context::from_gl_context(crate::context::GLContext::new(context));
+        if self.settings.debug {
+            context.set_validation(true);
+        }
+        *self.context.borrow_mut() = Some(context.clone());
+        Ok(context)
     }
 
+    ///
+    /// Returns the settings of the default framebuffer (the canvas) which were actually obtained when creating the graphics context using [Window::gl],
+    /// which might differ from the requested [WindowSettings] since they depend on the browser.
+    /// VSync is always on and sRGB is never supported on web.
+    ///
+    pub fn surface_settings(&self) -> SurfaceSettings {
+        *self.surface_settings.borrow()
+    }
+
+    ///
+    /// Starts the render loop which calls the given callback each frame.
+    ///
+    /// If the graphics context is lost, which for example happens on mobile devices when switching tabs,
+    /// the callback is not called until the context is restored.
+    /// The first frame after the context is restored contains an [Event::ContextLost] followed by an [Event::ContextRestored] event
+    /// and all GPU resources, except for those cached by the [Context], must be recreated in that frame.
+    ///
     pub fn render_loop<F: 'static + FnMut(FrameInput) -> FrameOutput>(
         mut self,
         mut callback: F,
@@ -150,6 +194,10 @@ impl Window {
         let input = Input::new(self.window.clone());
         self.add_context_menu_event_listener()?;
         self.add_resize_event_listener(input.clone())?;
+        self.add_resize_observer(input.clone())?;
+        self.add_context_lost_event_listener(input.clone())?;
+        self.add_context_restored_event_listener(input.clone())?;
+        self.add_text_input(input.clone())?;
         self.add_mouseenter_event_listener(input.clone())?;
         self.add_mouseleave_event_listener(input.clone())?;
         self.add_mousedown_event_listener(input.clone())?;
@@ -164,6 +212,10 @@ impl Window {
 
         let input_clone = input.clone();
         input.borrow_mut().render_loop_closure = Some(Closure::wrap(Box::new(move || {
+            if input_clone.borrow().context_lost {
+                input_clone.borrow_mut().render_requested = false;
+                return;
+            }
             let events = input_clone.borrow_mut().start_frame();
             let now = performance.now();
             let elapsed_time = now - last_time;
@@ -184,6 +236,7 @@ impl Window {
                 first_frame: first_frame,
             };
             first_frame = false;
+            crate::core::start_frame();
             let frame_output = callback(frame_input);
 
             if !frame_output.wait_next_event {
@@ -196,12 +249,7 @@ impl Window {
     }
 
     fn pixels_per_point(&self) -> f64 {
-        let pixels_per_point = self.window.device_pixel_ratio() as f64;
-        if pixels_per_point > 0.0 && pixels_per_point.is_finite() {
-            pixels_per_point
-        } else {
-            1.0
-        }
+        pixels_per_point(&self.window)
     }
 
     fn set_canvas_size(&self) -> ThreeDResult<()> {
@@ -258,8 +306,13 @@ impl Window {
         }
 
         canvas.style().set_css_text(&style);
-        canvas.set_width(width_px);
-        canvas.set_height(height_px);
+        // Setting the size clears the canvas, so only do it when the size has changed
+        if canvas.width() != width_px {
+            canvas.set_width(width_px);
+        }
+        if canvas.height() != height_px {
+            canvas.set_height(height_px);
+        }
 
         Ok(())
     }
@@ -279,7 +332,8 @@ impl Window {
         let closure = Closure::wrap(Box::new(move || {
             input.borrow_mut().request_animation_frame();
         }) as Box<dyn FnMut()>);
-        self.canvas()?
+        // The window resize event is also sent when the device pixel ratio changes, for example when zooming
+        self.window
             .add_event_listener_with_callback("resize", closure.as_ref().unchecked_ref())
             .map_err(|e| {
                 CanvasError::EventListenerFail("resize".to_string(), format!("{:?}", e))
@@ -288,6 +342,79 @@ impl Window {
         Ok(())
     }
 
+    fn add_resize_observer(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
+        let closure = Closure::wrap(Box::new(move || {
+            input.borrow_mut().request_animation_frame();
+        }) as Box<dyn FnMut()>);
+        let resize_observer = web_sys::ResizeObserver::new(closure.as_ref().unchecked_ref())
+            .map_err(|e| {
+                CanvasError::EventListenerFail("resize observer".to_string(), format!("{:?}", e))
+            })?;
+        // The canvas size is computed from the window size, so observe the element containing the canvas
+        match self.canvas()?.parent_element() {
+            Some(parent) => resize_observer.observe(&parent),
+            None => resize_observer.observe(self.canvas()?),
+        }
+        self.resize_observer = Some(resize_observer);
+        self.closures.push(closure);
+        Ok(())
+    }
+
+    fn add_context_lost_event_listener(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            // Prevent the default behaviour, otherwise the context is never restored
+            event.prevent_default();
+            let mut input = input.borrow_mut();
+            input.context_lost = true;
+            input.events.push(Event::ContextLost);
+        }) as Box<dyn FnMut(_)>);
+        self.canvas()?
+            .add_event_listener_with_callback("webglcontextlost", closure.as_ref().unchecked_ref())
+            .map_err(|e| {
+                CanvasError::EventListenerFail("webglcontextlost".to_string(), format!("{:?}", e))
+            })?;
+        self.closures_with_event.push(closure);
+        Ok(())
+    }
+
+    fn add_context_restored_event_listener(
+        &mut self,
+        input: Rc<RefCell<Input>>,
+    ) -> ThreeDResult<()> {
+        let context = self.context.borrow().clone();
+        let canvas = self.canvas()?.clone();
+        let closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
+            if let Some(ref context) = context {
+                context.clear_cache();
+            }
+            if let Some(webgl_context) = canvas
+                .get_context("webgl2")
+                .ok()
+                .flatten()
+                .and_then(|c| c.dyn_into::<WebGl2RenderingContext>().ok())
+            {
+                enable_extensions(&webgl_context).unwrap();
+            }
+            let mut input = input.borrow_mut();
+            input.context_lost = false;
+            input.events.push(Event::ContextRestored);
+            input.request_animation_frame();
+        }) as Box<dyn FnMut(_)>);
+        self.canvas()?
+            .add_event_listener_with_callback(
+                "webglcontextrestored",
+                closure.as_ref().unchecked_ref(),
+            )
+            .map_err(|e| {
+                CanvasError::EventListenerFail(
+                    "webglcontextrestored".to_string(),
+                    format!("{:?}", e),
+                )
+            })?;
+        self.closures_with_event.push(closure);
+        Ok(())
+    }
+
     fn add_mouseleave_event_listener(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
         let closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
             if !event.default_prevented() {
@@ -330,8 +457,13 @@ impl Window {
     }
 
     fn add_mousedown_event_listener(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
+        let text_input = self.text_input.clone();
         let closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
             if !event.default_prevented() {
+                // Keyboard input goes through the hidden text input, otherwise the browser does not start any input method editor
+                if let Some(ref text_input) = text_input {
+                    text_input.focus().ok();
+                }
                 let mut input = input.borrow_mut();
                 let button = match event.button() {
                     0 => Some(MouseButton::Left),
@@ -342,9 +474,11 @@ impl Window {
                 if let Some(button) = button {
                     let modifiers = input.modifiers;
                     input.mouse_pressed = Some(button);
+                    let position =
+                        input.pointer_position(event.offset_x() as f64, event.offset_y() as f64);
                     input.events.push(Event::MousePress {
                         button,
-                        position: (event.offset_x() as f64, event.offset_y() as f64),
+                        position,
                         modifiers,
                         handled: false,
                     });
@@ -377,9 +511,11 @@ impl Window {
                 if let Some(button) = button {
                     let modifiers = input.modifiers;
                     input.mouse_pressed = None;
+                    let position =
+                        input.pointer_position(event.offset_x() as f64, event.offset_y() as f64);
                     input.events.push(Event::MouseRelease {
                         button,
-                        position: (event.offset_x() as f64, event.offset_y() as f64),
+                        position,
                         modifiers,
                         handled: false,
                     });
@@ -404,20 +540,22 @@ impl Window {
             if !event.default_prevented() {
                 let mut input = input.borrow_mut();
                 let delta = if let Some((x, y)) = input.last_position {
-                    ((event.offset_x() - x) as f64, (event.offset_y() - y) as f64)
+                    (event.offset_x() as f64 - x, event.offset_y() as f64 - y)
                 } else {
                     (0.0, 0.0)
                 };
                 let modifiers = input.modifiers;
                 let button = input.mouse_pressed;
+                let position =
+                    input.pointer_position(event.offset_x() as f64, event.offset_y() as f64);
                 input.events.push(Event::MouseMotion {
                     button,
                     delta,
-                    position: (event.offset_x() as f64, event.offset_y() as f64),
+                    position,
                     modifiers,
                     handled: false,
                 });
-                input.last_position = Some((event.offset_x(), event.offset_y()));
+                input.last_position = Some((event.offset_x() as f64, event.offset_y() as f64));
                 event.stop_propagation();
                 event.prevent_default();
 
@@ -438,9 +576,11 @@ impl Window {
             if !event.default_prevented() {
                 let mut input = input.borrow_mut();
                 let modifiers = input.modifiers;
+                let position =
+                    input.pointer_position(event.offset_x() as f64, event.offset_y() as f64);
                 input.events.push(Event::MouseWheel {
                     delta: (event.delta_x() as f64, -event.delta_y() as f64),
-                    position: (event.offset_x() as f64, event.offset_y() as f64),
+                    position,
                     modifiers,
                     handled: false,
                 });
@@ -457,20 +597,22 @@ impl Window {
     }
 
     fn add_touchstart_event_listener(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
+        let canvas = self.canvas()?.clone();
         let closure = Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
             if !event.default_prevented() {
                 let mut input = input.borrow_mut();
                 if event.touches().length() == 1 {
-                    let touch = event.touches().item(0).unwrap();
+                    let (x, y) = touch_offset(&canvas, &event.touches().item(0).unwrap());
                     let modifiers = input.modifiers;
                     input.mouse_pressed = Some(MouseButton::Left);
+                    let position = input.pointer_position(x, y);
                     input.events.push(Event::MousePress {
                         button: MouseButton::Left,
-                        position: (touch.page_x() as f64, touch.page_y() as f64),
+                        position,
                         modifiers,
                         handled: false,
                     });
-                    input.last_position = Some((touch.page_x(), touch.page_y()));
+                    input.last_position = Some((x, y));
                     input.last_zoom = None;
                 } else if event.touches().length() == 2 {
                     let touch0 = event.touches().item(0).unwrap();
@@ -507,9 +649,10 @@ impl Window {
                 if let Some((x, y)) = input.last_position {
                     let modifiers = input.modifiers;
                     input.mouse_pressed = None;
+                    let position = input.pointer_position(x, y);
                     input.events.push(Event::MouseRelease {
                         button: MouseButton::Left,
-                        position: (x as f64, y as f64),
+                        position,
                         modifiers,
                         handled: false,
                     });
@@ -532,23 +675,25 @@ impl Window {
     }
 
     fn add_touchmove_event_listener(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
+        let canvas = self.canvas()?.clone();
         let closure = Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
             if !event.default_prevented() {
                 let mut input = input.borrow_mut();
                 if event.touches().length() == 1 {
-                    let touch = event.touches().item(0).unwrap();
-                    if let Some((x, y)) = input.last_position {
+                    let (x, y) = touch_offset(&canvas, &event.touches().item(0).unwrap());
+                    if let Some((last_x, last_y)) = input.last_position {
                         let modifiers = input.modifiers;
                         let button = input.mouse_pressed;
+                        let position = input.pointer_position(x, y);
                         input.events.push(Event::MouseMotion {
                             button,
-                            delta: ((touch.page_x() - x) as f64, (touch.page_y() - y) as f64),
-                            position: (touch.page_x() as f64, touch.page_y() as f64),
+                            delta: (x - last_x, y - last_y),
+                            position,
                             modifiers,
                             handled: false,
                         });
                     }
-                    input.last_position = Some((touch.page_x(), touch.page_y()));
+                    input.last_position = Some((x, y));
                     input.last_zoom = None;
                 } else if event.touches().length() == 2 {
                     let touch0 = event.touches().item(0).unwrap();
@@ -559,12 +704,12 @@ impl Window {
                     );
                     if let Some(old_zoom) = input.last_zoom {
                         let modifiers = input.modifiers;
+                        let (x0, y0) = touch_offset(&canvas, &touch0);
+                        let (x1, y1) = touch_offset(&canvas, &touch1);
+                        let position = input.pointer_position(0.5 * (x0 + x1), 0.5 * (y0 + y1));
                         input.events.push(Event::MouseWheel {
                             delta: (0.0, zoom - old_zoom),
-                            position: (
-                                0.5 * touch0.page_x() as f64 + 0.5 * touch1.page_x() as f64,
-                                0.5 * touch0.page_y() as f64 + 0.5 * touch1.page_y() as f64,
-                            ),
+                            position,
                             modifiers,
                             handled: false,
                         });
@@ -592,7 +737,8 @@ impl Window {
 
     fn add_key_down_event_listener(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
         let closure = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
-            if !event.default_prevented() {
+            // The key presses used for composing text are handled by the input method editor, see the composition events
+            if !event.default_prevented() && !event.is_composing() && event.key() != "Process" {
                 let mut input = input.borrow_mut();
                 if update_modifiers(&mut input.modifiers, &event) {
                     let modifiers = input.modifiers;
@@ -607,6 +753,7 @@ impl Window {
                         kind,
                         modifiers,
                         handled: false,
+                        repeat: event.repeat(),
                     });
                     event.stop_propagation();
                     event.prevent_default();
@@ -632,6 +779,55 @@ impl Window {
         Ok(())
     }
 
+    fn add_text_input(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
+        let document = self.window.document().ok_or(CanvasError::DocumentMissing)?;
+        let text_input = document
+            .create_element("input")
+            .map_err(|e| CanvasError::TextInputCreation(format!("{:?}", e)))?
+            .dyn_into::<web_sys::HtmlInputElement>()
+            .map_err(|e| CanvasError::TextInputCreation(format!("{:?}", e)))?;
+        text_input.set_type("text");
+        let style = text_input.style();
+        style.set_property("position", "fixed").ok();
+        style.set_property("top", "0").ok();
+        style.set_property("left", "0").ok();
+        style.set_property("width", "1px").ok();
+        style.set_property("height", "1px").ok();
+        style.set_property("opacity", "0").ok();
+        style.set_property("pointer-events", "none").ok();
+        document
+            .body()
+            .ok_or(CanvasError::DocumentMissing)?
+            .append_child(&text_input)
+            .map_err(|e| CanvasError::TextInputCreation(format!("{:?}", e)))?;
+
+        for name in ["compositionstart", "compositionupdate", "compositionend"].iter() {
+            let input = input.clone();
+            let text_input_clone = text_input.clone();
+            let closure = Closure::wrap(Box::new(move |event: web_sys::CompositionEvent| {
+                let mut input = input.borrow_mut();
+                let data = event.data().unwrap_or_default();
+                input.events.push(match event.type_().as_str() {
+                    "compositionstart" => Event::CompositionStart,
+                    "compositionupdate" => Event::CompositionUpdate(data),
+                    _ => {
+                        text_input_clone.set_value("");
+                        Event::CompositionEnd(data)
+                    }
+                });
+                input.request_animation_frame();
+            }) as Box<dyn FnMut(_)>);
+            text_input
+                .add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())
+                .map_err(|e| {
+                    CanvasError::EventListenerFail(name.to_string(), format!("{:?}", e))
+                })?;
+            self.closures_with_compositionevent.push(closure);
+        }
+        self.text_input = Some(text_input);
+        Ok(())
+    }
+
     fn add_key_up_event_listener(&mut self, input: Rc<RefCell<Input>>) -> ThreeDResult<()> {
         let closure = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
             if !event.default_prevented() {
@@ -670,6 +866,20 @@ impl Window {
 #[derive(Serialize)]
 struct ContextOptions {
     antialias: bool,
+    depth: bool,
+}
+
+fn enable_extensions(context: &WebGl2RenderingContext) -> ThreeDResult<()> {
+    context
+        .get_extension("EXT_color_buffer_float")
+        .map_err(|e| CanvasError::ColorBufferFloatNotSupported(format!("{:?}", e)))?;
+    context
+        .get_extension("OES_texture_float")
+        .map_err(|e| CanvasError::OESTextureFloatNotSupported(format!(": {:?}", e)))?;
+    context
+        .get_extension("OES_texture_float_linear")
+        .map_err(|e| CanvasError::OESTextureFloatNotSupported(format!(": {:?}", e)))?;
+    Ok(())
 }
 
 struct Input {
@@ -678,9 +888,11 @@ struct Input {
     render_requested: bool,
     events: Vec<Event>,
     modifiers: Modifiers,
-    last_position: Option<(i32, i32)>,
+    // The last position in logical pixels relative to the canvas
+    last_position: Option<(f64, f64)>,
     last_zoom: Option<f64>,
     mouse_pressed: Option<MouseButton>,
+    context_lost: bool,
 }
 
 impl Input {
@@ -694,6 +906,7 @@ impl Input {
             last_position: None,
             last_zoom: None,
             mouse_pressed: None,
+            context_lost: false,
         }))
     }
 
@@ -704,6 +917,12 @@ impl Input {
         events
     }
 
+    // The events are given in logical pixels (CSS pixels) relative to the canvas, which are scaled by the device pixel ratio
+    // to get physical pixels, since the canvas size is set to the logical size multiplied by the device pixel ratio, see set_canvas_size
+    fn pointer_position(&self, x: f64, y: f64) -> PointerPosition {
+        PointerPosition::from_logical((x, y), pixels_per_point(&self.window))
+    }
+
     pub fn request_animation_frame(&mut self) {
         if !self.render_requested {
             self.render_requested = true;
@@ -720,6 +939,24 @@ impl Input {
     }
 }
 
+fn pixels_per_point(window: &web_sys::Window) -> f64 {
+    let pixels_per_point = window.device_pixel_ratio() as f64;
+    if pixels_per_point > 0.0 && pixels_per_point.is_finite() {
+        pixels_per_point
+    } else {
+        1.0
+    }
+}
+
+// The position of the touch in logical pixels relative to the canvas, the same space as the offset of the mouse events
+fn touch_offset(canvas: &web_sys::HtmlCanvasElement, touch: &web_sys::Touch) -> (f64, f64) {
+    let rect = canvas.get_bounding_client_rect();
+    (
+        touch.client_x() as f64 - rect.left(),
+        touch.client_y() as f64 - rect.top(),
+    )
+}
+
 fn update_modifiers(modifiers: &mut Modifiers, event: &web_sys::KeyboardEvent) -> bool {
     let old = modifiers.clone();
     *modifiers = Modifiers {
@@ -811,6 +1048,7 @@ fn should_ignore_key(key: &str) -> bool {
                 | "CapsLock"
                 | "ContextMenu"
                 | "Control"
+                | "Dead"
                 | "Delete"
                 | "End"
                 | "Enter"
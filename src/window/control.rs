@@ -1,3 +1,11 @@
+mod camera_controller;
+#[doc(inline)]
+pub use camera_controller::*;
+
+mod input_map;
+#[doc(inline)]
+pub use input_map::*;
+
 mod camera_control;
 #[doc(inline)]
 pub use camera_control::*;
@@ -13,3 +21,7 @@ pub use first_person_control::*;
 mod fly_control;
 #[doc(inline)]
 pub use fly_control::*;
+
+mod pan_zoom_control;
+#[doc(inline)]
+pub use pan_zoom_control::*;
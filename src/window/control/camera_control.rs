@@ -60,11 +60,17 @@ impl CameraControl {
     pub fn new() -> Self {
         Self::default()
     }
+}
 
-    pub fn handle_events(
+///
+/// Matches the raw events directly instead of using an [InputMap], so the elapsed time is not used.
+///
+impl CameraController for CameraControl {
+    fn handle_events(
         &mut self,
         camera: &mut Camera,
         events: &mut [Event],
+        _elapsed_time: f64,
     ) -> ThreeDResult<bool> {
         let mut change = false;
         for event in events.iter_mut() {
@@ -106,7 +112,9 @@ impl CameraControl {
         }
         Ok(change)
     }
+}
 
+impl CameraControl {
     fn handle_action(
         &mut self,
         camera: &mut Camera,
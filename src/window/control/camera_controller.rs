@@ -0,0 +1,41 @@
+use crate::core::*;
+use crate::window::*;
+
+///
+/// A controller which moves a [Camera] based on the input [events](Event), for example an [OrbitControl] or a [FlyControl].
+/// The bundled controllers translate the events into [actions](ControlAction) using an [InputMap], which can be changed to rebind the controls.
+///
+pub trait CameraController {
+    ///
+    /// Moves the camera based on the given events and marks the events which are used as handled.
+    /// The elapsed time in milliseconds since the last call, usually [FrameInput::elapsed_time], is used for the actions bound to keys which are held down.
+    /// Returns whether or not the camera is changed.
+    ///
+    fn handle_events(
+        &mut self,
+        camera: &mut Camera,
+        events: &mut [Event],
+        elapsed_time: f64,
+    ) -> ThreeDResult<bool>;
+}
+
+// Applies the move actions, which moves the camera by the given amount in the direction of the action, and returns whether the action is a move action
+pub(super) fn apply_move_action(
+    camera: &mut Camera,
+    action: ControlAction,
+    amount: f32,
+) -> ThreeDResult<bool> {
+    let right = camera.right_direction();
+    let up = right.cross(camera.view_direction());
+    let direction = match action {
+        ControlAction::MoveForward => camera.view_direction(),
+        ControlAction::MoveBackward => -camera.view_direction(),
+        ControlAction::MoveLeft => -right,
+        ControlAction::MoveRight => right,
+        ControlAction::MoveUp => up,
+        ControlAction::MoveDown => -up,
+        _ => return Ok(false),
+    };
+    camera.translate(&(direction * amount))?;
+    Ok(true)
+}
@@ -1,28 +1,67 @@
+use super::camera_controller::apply_move_action;
 use crate::renderer::*;
 use crate::window::*;
 
+///
+/// A control that makes the camera move like a person walking around in the 3D scene, which by default is done by turning with the left mouse button
+/// and moving forward and backward by scrolling.
+/// It supports [ControlAction::LookAround], which only turns horizontally, [ControlAction::Pan], [ControlAction::Dolly] and the move actions.
+///
 pub struct FirstPersonControl {
-    control: CameraControl,
+    ///
+    /// The bindings of input to actions, which by default binds left drag to [ControlAction::LookAround] and scrolling to [ControlAction::Dolly].
+    ///
+    pub input_map: InputMap,
+    speed: f32,
 }
 
 impl FirstPersonControl {
     pub fn new(speed: f32) -> Self {
         Self {
-            control: CameraControl {
-                left_drag_horizontal: CameraAction::Yaw {
-                    speed: std::f32::consts::PI / 1800.0,
-                },
-                scroll_vertical: CameraAction::Forward { speed },
-                ..Default::default()
-            },
+            input_map: Self::default_input_map(),
+            speed,
         }
     }
 
-    pub fn handle_events(
+    ///
+    /// Returns the default bindings of a first person control.
+    ///
+    pub fn default_input_map() -> InputMap {
+        InputMap::new(vec![
+            InputBinding::drag(MouseButton::Left, ControlAction::LookAround),
+            InputBinding::scroll(ControlAction::Dolly),
+        ])
+    }
+}
+
+impl CameraController for FirstPersonControl {
+    fn handle_events(
         &mut self,
         camera: &mut Camera,
         events: &mut [Event],
+        elapsed_time: f64,
     ) -> ThreeDResult<bool> {
-        self.control.handle_events(camera, events)
+        let speed = self.speed;
+        self.input_map
+            .handle_events(events, elapsed_time, |action, delta| {
+                match action {
+                    ControlAction::LookAround => {
+                        camera.yaw(radians(std::f32::consts::PI / 1800.0 * delta.0 as f32))?;
+                    }
+                    ControlAction::Pan => {
+                        apply_move_action(camera, ControlAction::MoveLeft, speed * delta.0 as f32)?;
+                        apply_move_action(camera, ControlAction::MoveUp, speed * delta.1 as f32)?;
+                    }
+                    ControlAction::Dolly => {
+                        apply_move_action(
+                            camera,
+                            ControlAction::MoveForward,
+                            speed * delta.1 as f32,
+                        )?;
+                    }
+                    _ => return apply_move_action(camera, action, speed * delta.1 as f32),
+                }
+                Ok(true)
+            })
     }
 }
@@ -0,0 +1,344 @@
+use crate::core::*;
+use crate::window::*;
+use std::collections::HashSet;
+
+///
+/// A semantic action which a camera controller performs when the input bound to it in an [InputMap] is given.
+/// How the action moves the camera depends on the controller, for example [ControlAction::Dolly] zooms an [OrbitControl] towards its target
+/// and moves a [FlyControl] forward, and a controller ignores the actions it does not support.
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
+pub enum ControlAction {
+    /// Rotates the camera around a point, horizontally with the horizontal delta and vertically with the vertical delta.
+    Orbit,
+    /// Moves the camera sideways with the horizontal delta and up or down with the vertical delta.
+    Pan,
+    /// Moves the camera forward or zooms in with the vertical delta.
+    Dolly,
+    /// Turns the camera without moving it, horizontally with the horizontal delta and vertically with the vertical delta.
+    LookAround,
+    /// Moves the camera forward with the vertical delta.
+    MoveForward,
+    /// Moves the camera backward with the vertical delta.
+    MoveBackward,
+    /// Moves the camera to the left with the vertical delta.
+    MoveLeft,
+    /// Moves the camera to the right with the vertical delta.
+    MoveRight,
+    /// Moves the camera up with the vertical delta.
+    MoveUp,
+    /// Moves the camera down with the vertical delta.
+    MoveDown,
+}
+
+///
+/// The input which triggers the action of an [InputBinding].
+///
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputTrigger {
+    ///
+    /// Moving the mouse while all of the given buttons are held down, where more than one button is a chord, for example left and right together.
+    /// The delta of the action is the mouse motion in logical pixels.
+    ///
+    Drag(Vec<MouseButton>),
+    ///
+    /// Scrolling with the mouse wheel or the touchpad. The delta of the action is the scroll delta.
+    ///
+    Scroll,
+    ///
+    /// Holding down all of the given keys, where more than one key is a chord.
+    /// Each frame while the keys are held down, the action is given the delta `rate` multiplied by the elapsed time in seconds,
+    /// so the rate is the delta per second.
+    ///
+    Keys {
+        /// The keys which must be held down.
+        keys: Vec<Key>,
+        /// The delta per second.
+        rate: (f64, f64),
+    },
+}
+
+///
+/// Binds an [InputTrigger], optionally combined with a state of the modifier keys, to a [ControlAction].
+///
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputBinding {
+    /// The input which triggers the action.
+    pub trigger: InputTrigger,
+    ///
+    /// The modifier keys which must be down, for example alt for alt + left drag, or `None` if the binding applies regardless of the modifier keys.
+    /// The alt and shift keys must match exactly, while control and command are considered the same key since control is reported as command on Windows and Linux.
+    /// A binding with modifiers takes precedence over a binding without modifiers for the same trigger.
+    ///
+    pub modifiers: Option<Modifiers>,
+    /// The action to perform.
+    pub action: ControlAction,
+}
+
+impl InputBinding {
+    ///
+    /// Creates a binding of a drag with the given mouse button to the given action, regardless of the modifier keys.
+    ///
+    pub fn drag(button: MouseButton, action: ControlAction) -> Self {
+        Self {
+            trigger: InputTrigger::Drag(vec![button]),
+            modifiers: None,
+            action,
+        }
+    }
+
+    ///
+    /// Creates a binding of scrolling to the given action, regardless of the modifier keys.
+    ///
+    pub fn scroll(action: ControlAction) -> Self {
+        Self {
+            trigger: InputTrigger::Scroll,
+            modifiers: None,
+            action,
+        }
+    }
+
+    ///
+    /// Creates a binding of holding down the given key to the given action with the given delta per second, regardless of the modifier keys.
+    ///
+    pub fn key(key: Key, action: ControlAction, rate: (f64, f64)) -> Self {
+        Self {
+            trigger: InputTrigger::Keys {
+                keys: vec![key],
+                rate,
+            },
+            modifiers: None,
+            action,
+        }
+    }
+
+    ///
+    /// Returns this binding which only applies when exactly the given modifier keys are down.
+    ///
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = Some(modifiers);
+        self
+    }
+
+    fn matches_modifiers(&self, modifiers: &Modifiers) -> bool {
+        match self.modifiers {
+            None => true,
+            Some(m) => {
+                m.alt == modifiers.alt
+                    && m.shift == modifiers.shift
+                    && (m.ctrl || m.command) == (modifiers.ctrl || modifiers.command)
+            }
+        }
+    }
+
+    fn input_count(&self) -> usize {
+        match &self.trigger {
+            InputTrigger::Drag(buttons) => buttons.len(),
+            InputTrigger::Scroll => 1,
+            InputTrigger::Keys { keys, .. } => keys.len(),
+        }
+    }
+}
+
+///
+/// Translates the raw input [events](Event) into semantic [actions](ControlAction) using a list of configurable [bindings](InputBinding).
+/// All camera controllers use an input map, so which buttons, keys and modifiers control the camera can be changed,
+/// for example to orbit with the middle mouse button and pan with the right mouse button.
+/// With the `event-io` feature, the input map can be serialized, for example to persist the bindings chosen by a user.
+///
+/// When more than one binding matches an input, the binding which requires the most buttons or keys wins, and then the binding with modifiers wins,
+/// so left + right drag can be bound to another action than left drag, and alt + left drag to another action than left drag.
+///
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "event-io", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputMap {
+    /// The bindings of input to actions.
+    pub bindings: Vec<InputBinding>,
+    #[cfg_attr(feature = "event-io", serde(skip))]
+    buttons_down: HashSet<MouseButton>,
+    #[cfg_attr(feature = "event-io", serde(skip))]
+    keys_down: HashSet<Key>,
+    #[cfg_attr(feature = "event-io", serde(skip))]
+    modifiers: Modifiers,
+}
+
+impl InputMap {
+    ///
+    /// Creates a new input map with the given bindings.
+    ///
+    pub fn new(bindings: Vec<InputBinding>) -> Self {
+        Self {
+            bindings,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Returns whether or not a drag with the given mouse button, alone or as part of a chord, is bound to the given action.
+    ///
+    pub fn is_drag_bound(&self, button: MouseButton, action: ControlAction) -> bool {
+        self.bindings.iter().any(|b| {
+            b.action == action
+                && match &b.trigger {
+                    InputTrigger::Drag(buttons) => buttons.contains(&button),
+                    _ => false,
+                }
+        })
+    }
+
+    ///
+    /// Translates the given events into actions and calls the `apply` closure with each action and its delta.
+    /// An event is marked as handled if the closure returns true, which it should if the action is supported by the controller.
+    /// Events which are already handled are ignored, except for keeping track of which buttons and keys are held down.
+    /// Afterwards, the actions bound to the keys which are held down are applied with the delta for the given elapsed time in milliseconds,
+    /// so use an elapsed time of zero to only apply the given events.
+    /// Returns whether or not the closure returned true for any action.
+    ///
+    pub fn handle_events(
+        &mut self,
+        events: &mut [Event],
+        elapsed_time: f64,
+        mut apply: impl FnMut(ControlAction, (f64, f64)) -> ThreeDResult<bool>,
+    ) -> ThreeDResult<bool> {
+        let mut change = false;
+        for event in events.iter_mut() {
+            match event {
+                Event::MousePress { button, .. } => {
+                    self.buttons_down.insert(*button);
+                }
+                Event::MouseRelease { button, .. } => {
+                    self.buttons_down.remove(button);
+                }
+                Event::MouseMotion {
+                    delta,
+                    button,
+                    modifiers,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        if let Some(b) = button {
+                            let buttons_down = &self.buttons_down;
+                            let binding = self.best_binding(modifiers, |trigger| match trigger {
+                                InputTrigger::Drag(buttons) => {
+                                    buttons.contains(b)
+                                        && (buttons.len() == 1
+                                            || buttons.iter().all(|b| buttons_down.contains(b)))
+                                }
+                                _ => false,
+                            });
+                            if let Some(action) = binding {
+                                *handled = apply(action, *delta)?;
+                                change |= *handled;
+                            }
+                        }
+                    }
+                }
+                Event::MouseWheel {
+                    delta,
+                    modifiers,
+                    handled,
+                    ..
+                } => {
+                    if !*handled {
+                        let binding = self
+                            .best_binding(modifiers, |trigger| *trigger == InputTrigger::Scroll);
+                        if let Some(action) = binding {
+                            *handled = apply(action, *delta)?;
+                            change |= *handled;
+                        }
+                    }
+                }
+                Event::KeyPress {
+                    kind,
+                    modifiers,
+                    handled,
+                    ..
+                } => {
+                    self.modifiers = *modifiers;
+                    if !*handled && self.is_key_bound(*kind) {
+                        self.keys_down.insert(*kind);
+                        *handled = true;
+                    }
+                }
+                Event::KeyRelease {
+                    kind, modifiers, ..
+                } => {
+                    self.modifiers = *modifiers;
+                    self.keys_down.remove(kind);
+                }
+                Event::ModifiersChange { modifiers } => {
+                    self.modifiers = *modifiers;
+                }
+                _ => {}
+            }
+        }
+
+        if elapsed_time > 0.0 && !self.keys_down.is_empty() {
+            let seconds = elapsed_time / 1000.0;
+            for (action, rate) in self.held_actions() {
+                change |= apply(action, (rate.0 * seconds, rate.1 * seconds))?;
+            }
+        }
+        Ok(change)
+    }
+
+    fn best_binding(
+        &self,
+        modifiers: &Modifiers,
+        matches: impl Fn(&InputTrigger) -> bool,
+    ) -> Option<ControlAction> {
+        let mut best: Option<&InputBinding> = None;
+        for binding in self.bindings.iter() {
+            if matches(&binding.trigger) && binding.matches_modifiers(modifiers) {
+                let better = match best {
+                    None => true,
+                    Some(b) => {
+                        (binding.input_count(), binding.modifiers.is_some())
+                            > (b.input_count(), b.modifiers.is_some())
+                    }
+                };
+                if better {
+                    best = Some(binding);
+                }
+            }
+        }
+        best.map(|b| b.action)
+    }
+
+    fn is_key_bound(&self, key: Key) -> bool {
+        self.bindings.iter().any(|b| match &b.trigger {
+            InputTrigger::Keys { keys, .. } => keys.contains(&key),
+            _ => false,
+        })
+    }
+
+    // The actions of the key bindings which are held down, where a binding is left out if its keys are part of a larger chord which is also held down
+    fn held_actions(&self) -> Vec<(ControlAction, (f64, f64))> {
+        let held: Vec<(&InputBinding, &Vec<Key>, (f64, f64))> = self
+            .bindings
+            .iter()
+            .filter_map(|b| match &b.trigger {
+                InputTrigger::Keys { keys, rate }
+                    if !keys.is_empty()
+                        && keys.iter().all(|k| self.keys_down.contains(k))
+                        && b.matches_modifiers(&self.modifiers) =>
+                {
+                    Some((b, keys, *rate))
+                }
+                _ => None,
+            })
+            .collect();
+        held.iter()
+            .filter(|(_, keys, _)| {
+                !held.iter().any(|(_, other, _)| {
+                    other.len() > keys.len() && keys.iter().all(|k| other.contains(k))
+                })
+            })
+            .map(|(b, _, rate)| (b.action, *rate))
+            .collect()
+    }
+}
@@ -1,35 +1,156 @@
 use crate::core::*;
 use crate::window::*;
 
+///
+/// A control that rotates the camera around a target and zooms towards it, which by default is done by dragging with the left mouse button and scrolling.
+/// It supports [ControlAction::Orbit], [ControlAction::Pan], which also moves the target, and [ControlAction::Dolly].
+///
 pub struct OrbitControl {
-    control: CameraControl,
+    ///
+    /// The bindings of input to actions, which by default binds left drag to [ControlAction::Orbit] and scrolling to [ControlAction::Dolly].
+    ///
+    pub input_map: InputMap,
+    target: Vec3,
+    min_distance: f32,
+    max_distance: f32,
+    pivot: Vec3,
+    zoom_point: Vec3,
+    ///
+    /// Whether or not to rotate around the point under the cursor when starting to drag instead of around the target.
+    /// Only used by [OrbitControl::handle_events_with_picking].
+    ///
+    pub pivot_at_cursor: bool,
+    ///
+    /// Whether or not to zoom towards the point under the cursor instead of towards the target, which keeps that point fixed on the screen.
+    /// Only used by [OrbitControl::handle_events_with_picking].
+    ///
+    pub zoom_to_cursor: bool,
 }
 
 impl OrbitControl {
     pub fn new(target: Vec3, min_distance: f32, max_distance: f32) -> Self {
         Self {
-            control: CameraControl {
-                left_drag_horizontal: CameraAction::OrbitLeft { target, speed: 0.5 },
-                left_drag_vertical: CameraAction::OrbitUp { target, speed: 0.5 },
-                scroll_vertical: CameraAction::Zoom {
-                    min: min_distance,
-                    max: max_distance,
-                    speed: 0.1,
-                    target,
-                },
-                ..Default::default()
-            },
+            input_map: Self::default_input_map(),
+            target,
+            min_distance,
+            max_distance,
+            pivot: target,
+            zoom_point: target,
+            pivot_at_cursor: true,
+            zoom_to_cursor: true,
         }
     }
 
-    pub fn handle_events(
+    ///
+    /// Returns the default bindings of an orbit control.
+    ///
+    pub fn default_input_map() -> InputMap {
+        InputMap::new(vec![
+            InputBinding::drag(MouseButton::Left, ControlAction::Orbit),
+            InputBinding::scroll(ControlAction::Dolly),
+        ])
+    }
+
+    ///
+    /// Same as [CameraController::handle_events], except that the camera is rotated around and zoomed towards the point under the cursor
+    /// as specified by [OrbitControl::pivot_at_cursor] and [OrbitControl::zoom_to_cursor].
+    /// The point is found by calling the `pick` closure with the pixel under the cursor given by [PointerPosition::to_viewport_pixel]
+    /// for the viewport of the camera on a screen with the given height in physical pixels, usually the height of [FrameInput::viewport],
+    /// and the closure is usually implemented using the [pick](crate::renderer::pick) function,
+    /// and if nothing is picked, the target given at construction is used instead.
+    ///
+    pub fn handle_events_with_picking(
         &mut self,
         camera: &mut Camera,
         events: &mut [Event],
+        elapsed_time: f64,
+        screen_height: u32,
+        mut pick: impl FnMut(&Camera, (f32, f32)) -> ThreeDResult<Option<Vec3>>,
     ) -> ThreeDResult<bool> {
-        if let CameraAction::Zoom { speed, target, .. } = &mut self.control.scroll_horizontal {
-            *speed = 0.1 / target.distance(*camera.position());
+        let mut change = false;
+        for i in 0..events.len() {
+            match &events[i] {
+                Event::MousePress {
+                    button,
+                    position,
+                    handled: false,
+                    ..
+                } if self.pivot_at_cursor
+                    && self.input_map.is_drag_bound(*button, ControlAction::Orbit) =>
+                {
+                    let pixel = position.to_viewport_pixel(camera.viewport(), screen_height);
+                    self.pivot = pick(camera, pixel)?.unwrap_or(self.target);
+                }
+                Event::MouseRelease { button, .. }
+                    if self.input_map.is_drag_bound(*button, ControlAction::Orbit) =>
+                {
+                    self.pivot = self.target;
+                }
+                Event::MouseWheel {
+                    position,
+                    handled: false,
+                    ..
+                } => {
+                    self.zoom_point = if self.zoom_to_cursor {
+                        let pixel = position.to_viewport_pixel(camera.viewport(), screen_height);
+                        pick(camera, pixel)?.unwrap_or(self.target)
+                    } else {
+                        self.target
+                    };
+                }
+                _ => {}
+            }
+            change |= self.handle_events(camera, &mut events[i..i + 1], 0.0)?;
         }
-        self.control.handle_events(camera, events)
+        change |= self.handle_events(camera, &mut [], elapsed_time)?;
+        Ok(change)
+    }
+}
+
+impl CameraController for OrbitControl {
+    fn handle_events(
+        &mut self,
+        camera: &mut Camera,
+        events: &mut [Event],
+        elapsed_time: f64,
+    ) -> ThreeDResult<bool> {
+        let Self {
+            input_map,
+            target,
+            min_distance,
+            max_distance,
+            pivot,
+            zoom_point,
+            ..
+        } = self;
+        input_map.handle_events(events, elapsed_time, |action, delta| {
+            match action {
+                ControlAction::Orbit => {
+                    camera.rotate_around_with_fixed_up(pivot, 0.5 * delta.0 as f32, 0.0)?;
+                    camera.rotate_around_with_fixed_up(pivot, 0.0, 0.5 * delta.1 as f32)?;
+                }
+                ControlAction::Pan => {
+                    // Moves the target along with the camera and moves faster the further away the target is
+                    let speed = 0.001 * target.distance(*camera.position());
+                    let right = camera.right_direction();
+                    let up = right.cross(camera.view_direction());
+                    let change = (-right * delta.0 as f32 + up * delta.1 as f32) * speed;
+                    camera.translate(&change)?;
+                    *target += change;
+                    *pivot += change;
+                    *zoom_point += change;
+                }
+                ControlAction::Dolly => {
+                    camera.zoom_towards(
+                        zoom_point,
+                        0.1 * delta.1 as f32,
+                        *min_distance,
+                        *max_distance,
+                    )?;
+                }
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })
     }
 }
@@ -0,0 +1,89 @@
+use crate::core::*;
+use crate::window::*;
+
+///
+/// A control for 2D content, for example a map or an image viewed with an orthographic camera, which by default pans by dragging with the left mouse button
+/// and zooms towards the target by scrolling, while keeping the view direction fixed.
+/// It supports [ControlAction::Pan] and [ControlAction::Dolly].
+///
+pub struct PanZoomControl {
+    ///
+    /// The bindings of input to actions, which by default binds left drag to [ControlAction::Pan] and scrolling to [ControlAction::Dolly].
+    ///
+    pub input_map: InputMap,
+    target: Vec3,
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl PanZoomControl {
+    ///
+    /// Creates a new pan and zoom control which zooms towards the given target while keeping a distance between the given minimum and maximum to it.
+    /// The target is moved along with the camera when panning.
+    /// For an orthographic camera, zooming scales the height of the view with the distance.
+    ///
+    pub fn new(target: Vec3, min_distance: f32, max_distance: f32) -> Self {
+        Self {
+            input_map: Self::default_input_map(),
+            target,
+            min_distance,
+            max_distance,
+        }
+    }
+
+    ///
+    /// Returns the default bindings of a pan and zoom control.
+    ///
+    pub fn default_input_map() -> InputMap {
+        InputMap::new(vec![
+            InputBinding::drag(MouseButton::Left, ControlAction::Pan),
+            InputBinding::scroll(ControlAction::Dolly),
+        ])
+    }
+}
+
+impl CameraController for PanZoomControl {
+    fn handle_events(
+        &mut self,
+        camera: &mut Camera,
+        events: &mut [Event],
+        elapsed_time: f64,
+    ) -> ThreeDResult<bool> {
+        let Self {
+            input_map,
+            target,
+            min_distance,
+            max_distance,
+        } = self;
+        input_map.handle_events(events, elapsed_time, |action, delta| {
+            // The size of a pixel at the target in world units, so the content follows the cursor when panning at a device pixel ratio of one
+            let height = match camera.projection_type() {
+                ProjectionType::Orthographic { height } => *height,
+                ProjectionType::Perspective { field_of_view_y } => {
+                    2.0 * target.distance(*camera.position()) * (0.5 * field_of_view_y.0).tan()
+                }
+            };
+            let speed = height / camera.viewport().height.max(1) as f32;
+            match action {
+                ControlAction::Pan => {
+                    let right = camera.right_direction();
+                    let up = right.cross(camera.view_direction());
+                    let change = (-right * delta.0 as f32 + up * delta.1 as f32) * speed;
+                    camera.translate(&change)?;
+                    *target += change;
+                }
+                ControlAction::Dolly => {
+                    let distance = target.distance(*camera.position());
+                    camera.zoom_towards(
+                        target,
+                        0.001 * distance * delta.1 as f32,
+                        *min_distance,
+                        *max_distance,
+                    )?;
+                }
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })
+    }
+}
@@ -0,0 +1,170 @@
+use crate::core::*;
+use crate::io::*;
+use crate::window::*;
+use std::path::Path;
+
+// Identifies an event recording and the version of the format
+const MAGIC_NUMBER: u8 = 73;
+const VERSION: u8 = 1;
+
+///
+/// Records the [FrameInput] of every frame of a render loop, ie. the events, the elapsed time, the viewport and the device pixel ratio,
+/// so that the exact same input can be played back later using an [EventPlayer], for example to reproduce a bug or to test the interaction in a reproducible way.
+///
+/// Call [EventRecorder::record] in the beginning of the render loop callback, before the events are handled by for example a [GUI](crate::GUI) or a camera control,
+/// since that changes the events.
+///
+#[derive(Clone, Debug, Default)]
+pub struct EventRecorder {
+    frames: Vec<FrameInput>,
+}
+
+impl EventRecorder {
+    ///
+    /// Creates a new empty recorder.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Records the given frame input.
+    ///
+    pub fn record(&mut self, frame_input: &FrameInput) {
+        self.frames.push(frame_input.clone());
+    }
+
+    ///
+    /// Returns the number of recorded frames.
+    ///
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    ///
+    /// Serializes the recorded frames to a compact binary format which can be played back using [EventPlayer::from_bytes].
+    ///
+    pub fn to_bytes(&self) -> ThreeDResult<Vec<u8>> {
+        Ok(bincode::serialize(&(MAGIC_NUMBER, VERSION, &self.frames))
+            .map_err(IOError::EventRecording)?)
+    }
+
+    ///
+    /// Saves the recorded frames as a file at the given path which can be played back using [EventPlayer::load].
+    /// Only available on desktop.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: impl AsRef<Path>) -> ThreeDResult<()> {
+        Saver::save_file(path, &self.to_bytes()?)
+    }
+}
+
+///
+/// Plays back the frames recorded by an [EventRecorder] by replacing the live [FrameInput] with the recorded one frame by frame.
+/// Since the time is also taken from the recording, the render loop callback receives exactly the same input as when it was recorded,
+/// regardless of the speed of the machine.
+///
+/// Either use [EventPlayer::replace] in the render loop of a window or run the render loop callback without a window using [EventPlayer::run_headless].
+///
+#[derive(Clone, Debug)]
+pub struct EventPlayer {
+    frames: Vec<FrameInput>,
+    next_frame: usize,
+}
+
+impl EventPlayer {
+    ///
+    /// Deserializes frames serialized using [EventRecorder::to_bytes].
+    /// On web, the recording can be loaded using the [Loader] and the bytes given to this function.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> ThreeDResult<Self> {
+        let (magic_number, version, frames) =
+            bincode::deserialize::<(u8, u8, Vec<FrameInput>)>(bytes)
+                .map_err(IOError::EventRecording)?;
+        if magic_number != MAGIC_NUMBER || version != VERSION {
+            Err(IOError::EventRecording(bincode::Error::new(
+                bincode::ErrorKind::Custom("Not a supported event recording".to_string()),
+            )))?;
+        }
+        Ok(Self {
+            frames,
+            next_frame: 0,
+        })
+    }
+
+    ///
+    /// Loads frames saved using [EventRecorder::save].
+    /// Only available on desktop, use [EventPlayer::from_bytes] on web.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: impl AsRef<Path>) -> ThreeDResult<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    ///
+    /// Returns the next recorded frame or `None` if all frames have been played back.
+    ///
+    pub fn next_frame(&mut self) -> Option<FrameInput> {
+        let frame_input = self.frames.get(self.next_frame).cloned();
+        if frame_input.is_some() {
+            self.next_frame += 1;
+        }
+        frame_input
+    }
+
+    ///
+    /// Returns the next recorded frame instead of the given live frame input, or the live frame input if all frames have been played back,
+    /// which means that the application continues with live input after the playback.
+    /// Call this in the beginning of the render loop callback of a window.
+    /// Note that the recorded viewport is used, so the window should have the same size as when the frames were recorded.
+    ///
+    pub fn replace(&mut self, frame_input: FrameInput) -> FrameInput {
+        self.next_frame().unwrap_or(frame_input)
+    }
+
+    ///
+    /// Returns whether or not all frames have been played back.
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.frames.len()
+    }
+
+    ///
+    /// Returns the number of recorded frames.
+    ///
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    ///
+    /// Calls the given render loop callback with each of the remaining recorded frames without a window, for example using the headless [Context].
+    /// Everything written to the [Screen] is rendered into offscreen textures with the size of the recorded viewport instead,
+    /// see [Context::set_offscreen_screen_size], and screenshots requested in the [FrameOutput] are saved when the `image-io` feature is enabled.
+    /// The playback stops when all frames have been played back or when the callback requests to exit.
+    /// The offscreen textures are kept afterwards, so the last frame can be read using [Screen::read_color].
+    ///
+    pub fn run_headless(
+        &mut self,
+        context: &Context,
+        mut callback: impl FnMut(FrameInput) -> FrameOutput,
+    ) -> ThreeDResult<()> {
+        while let Some(frame_input) = self.next_frame() {
+            let viewport = frame_input.viewport;
+            context.set_offscreen_screen_size(Some((viewport.width, viewport.height)))?;
+            let frame_output = callback(frame_input);
+
+            #[cfg(all(feature = "image-io", not(target_arch = "wasm32")))]
+            if let Some(ref path) = frame_output.screenshot {
+                let pixels = Screen::read_color(
+                    context,
+                    Viewport::new_at_origo(viewport.width, viewport.height),
+                )?;
+                Saver::save_pixels(path, &pixels, viewport.width, viewport.height)?;
+            }
+            if frame_output.exit {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
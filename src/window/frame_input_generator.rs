@@ -0,0 +1,235 @@
+use crate::core::*;
+use crate::window::*;
+use std::collections::HashSet;
+
+///
+/// Generates the [FrameInput] for each frame when the window is not created by `three-d`,
+/// for example when `three-d` is embedded in an application using another windowing library, see [Context::from_gl_loader](crate::Context::from_gl_loader).
+/// Translate the events received from the windowing library to calls to the methods of this generator
+/// and call [FrameInputGenerator::generate] once per frame to get the frame input expected by for example [GUI::update](crate::GUI) and the camera controls.
+/// The generator keeps track of the state needed to create the events, ie. the modifiers, the cursor position, the pressed mouse button and the pressed keys.
+/// All positions are given in logical pixels, and the physical positions of the events are computed using the device pixel ratio given to the last call to [FrameInputGenerator::generate].
+///
+#[derive(Clone, Debug)]
+pub struct FrameInputGenerator {
+    events: Vec<Event>,
+    last_time: Option<f64>,
+    accumulated_time: f64,
+    first_frame: bool,
+    modifiers: Modifiers,
+    cursor_position: Option<(f64, f64)>,
+    device_pixel_ratio: f64,
+    mouse_pressed: Option<MouseButton>,
+    pressed_keys: HashSet<Key>,
+}
+
+impl FrameInputGenerator {
+    ///
+    /// Creates a new generator.
+    ///
+    pub fn new() -> Self {
+        Self {
+            events: Vec::new(),
+            last_time: None,
+            accumulated_time: 0.0,
+            first_frame: true,
+            modifiers: Modifiers::default(),
+            cursor_position: None,
+            device_pixel_ratio: 1.0,
+            mouse_pressed: None,
+            pressed_keys: HashSet::new(),
+        }
+    }
+
+    ///
+    /// Adds an event which has already been translated to an [Event], the event is added as it is to the next frame input.
+    ///
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    ///
+    /// Adds a [Event::MouseMotion] event where the delta is computed from the last cursor position.
+    ///
+    pub fn cursor_moved(&mut self, position: (f64, f64)) {
+        let delta = if let Some(last_position) = self.cursor_position {
+            (position.0 - last_position.0, position.1 - last_position.1)
+        } else {
+            (0.0, 0.0)
+        };
+        self.events.push(Event::MouseMotion {
+            button: self.mouse_pressed,
+            delta,
+            position: PointerPosition::from_logical(position, self.device_pixel_ratio),
+            modifiers: self.modifiers,
+            handled: false,
+        });
+        self.cursor_position = Some(position);
+    }
+
+    ///
+    /// Adds a [Event::MousePress] or [Event::MouseRelease] event at the last cursor position.
+    /// Nothing is added if the cursor position is unknown, ie. [FrameInputGenerator::cursor_moved] has not been called yet.
+    ///
+    pub fn mouse_button(&mut self, button: MouseButton, pressed: bool) {
+        if let Some(position) = self.cursor_position {
+            let position = PointerPosition::from_logical(position, self.device_pixel_ratio);
+            self.events.push(if pressed {
+                self.mouse_pressed = Some(button);
+                Event::MousePress {
+                    button,
+                    position,
+                    modifiers: self.modifiers,
+                    handled: false,
+                }
+            } else {
+                self.mouse_pressed = None;
+                Event::MouseRelease {
+                    button,
+                    position,
+                    modifiers: self.modifiers,
+                    handled: false,
+                }
+            });
+        }
+    }
+
+    ///
+    /// Adds a [Event::MouseWheel] event at the last cursor position with the given delta in logical pixels.
+    /// Nothing is added if the cursor position is unknown, ie. [FrameInputGenerator::cursor_moved] has not been called yet.
+    ///
+    pub fn mouse_wheel(&mut self, delta: (f64, f64)) {
+        if let Some(position) = self.cursor_position {
+            let position = PointerPosition::from_logical(position, self.device_pixel_ratio);
+            self.events.push(Event::MouseWheel {
+                delta,
+                position,
+                modifiers: self.modifiers,
+                handled: false,
+            });
+        }
+    }
+
+    ///
+    /// Adds a [Event::MouseEnter] event.
+    ///
+    pub fn cursor_entered(&mut self) {
+        self.events.push(Event::MouseEnter);
+    }
+
+    ///
+    /// Adds a [Event::MouseLeave] event.
+    ///
+    pub fn cursor_left(&mut self) {
+        self.mouse_pressed = None;
+        self.events.push(Event::MouseLeave);
+    }
+
+    ///
+    /// Adds a [Event::KeyPress] or [Event::KeyRelease] event.
+    /// A press of a key which is already pressed is marked as repeated, since most operating systems repeat the press events of held keys.
+    ///
+    pub fn key(&mut self, kind: Key, pressed: bool) {
+        self.events.push(if pressed {
+            Event::KeyPress {
+                kind,
+                modifiers: self.modifiers,
+                handled: false,
+                repeat: !self.pressed_keys.insert(kind),
+            }
+        } else {
+            self.pressed_keys.remove(&kind);
+            Event::KeyRelease {
+                kind,
+                modifiers: self.modifiers,
+                handled: false,
+            }
+        });
+    }
+
+    ///
+    /// Sets the modifiers which are added to the following events and adds a [Event::ModifiersChange] event if they have changed.
+    ///
+    pub fn modifiers_changed(&mut self, modifiers: Modifiers) {
+        if modifiers != self.modifiers {
+            self.modifiers = modifiers;
+            self.events.push(Event::ModifiersChange { modifiers });
+        }
+    }
+
+    ///
+    /// Returns the current modifiers, see [FrameInputGenerator::modifiers_changed].
+    ///
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    ///
+    /// Adds a [Event::Text] event with the given text, unless the text is empty or the ctrl or command modifier is down.
+    ///
+    pub fn text(&mut self, text: &str) {
+        if !text.is_empty() && !self.modifiers.ctrl && !self.modifiers.command {
+            self.events.push(Event::Text(text.to_string()));
+        }
+    }
+
+    ///
+    /// Forgets the pressed keys, call this when the window loses focus, since the key releases are not received while the window is unfocused.
+    ///
+    pub fn focus_lost(&mut self) {
+        self.pressed_keys.clear();
+    }
+
+    ///
+    /// Returns the frame input containing the events added since the last call to this method,
+    /// the time since the last call and the viewport and window size given by the size of the window in physical pixels and the device pixel ratio.
+    ///
+    pub fn generate(
+        &mut self,
+        physical_width: u32,
+        physical_height: u32,
+        device_pixel_ratio: f64,
+    ) -> FrameInput {
+        let now = now();
+        let elapsed_time = self.last_time.map(|last| now - last).unwrap_or(0.0);
+        self.last_time = Some(now);
+        self.accumulated_time += elapsed_time;
+        self.device_pixel_ratio = device_pixel_ratio;
+        let frame_input = FrameInput {
+            events: std::mem::take(&mut self.events),
+            elapsed_time,
+            accumulated_time: self.accumulated_time,
+            viewport: Viewport::new_at_origo(physical_width, physical_height),
+            window_width: (physical_width as f64 / device_pixel_ratio).round() as u32,
+            window_height: (physical_height as f64 / device_pixel_ratio).round() as u32,
+            device_pixel_ratio,
+            first_frame: self.first_frame,
+        };
+        self.first_frame = false;
+        frame_input
+    }
+}
+
+impl Default for FrameInputGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Milliseconds since some fixed point in time
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> f64 {
+    thread_local! {
+        static START: std::time::Instant = std::time::Instant::now();
+    }
+    START.with(|start| start.elapsed().as_secs_f64() * 1000.0)
+}
+
+// Milliseconds since some fixed point in time
+#[cfg(target_arch = "wasm32")]
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or(0.0)
+}
@@ -28,6 +28,7 @@ pub struct Window {
     windowed_context: ContextWrapper<PossiblyCurrent, window::Window>,
     event_loop: EventLoop<()>,
     gl: crate::Context,
+    surface_settings: SurfaceSettings,
 }
 
 impl Window {
@@ -37,22 +38,51 @@ impl Window {
     pub fn new(mut settings: WindowSettings) -> ThreeDResult<Window> {
         let event_loop = EventLoop::new();
         let mut wc = Self::new_windowed_context(&settings, &event_loop);
+        // Fall back to the default framebuffer settings one at a time if the requested settings are not supported
         if wc.is_err() {
             settings.multisamples = 0;
             wc = Self::new_windowed_context(&settings, &event_loop);
         }
+        if wc.is_err() {
+            settings.depth_bits = 24;
+            wc = Self::new_windowed_context(&settings, &event_loop);
+        }
+        if wc.is_err() {
+            settings.srgb = false;
+            wc = Self::new_windowed_context(&settings, &event_loop);
+        }
 
         let windowed_context = unsafe { wc?.make_current().unwrap() };
+        let pixel_format = windowed_context.get_pixel_format();
+        let surface_settings = SurfaceSettings {
+            vsync: settings.vsync,
+            multisamples: pixel_format.multisampling.unwrap_or(0) as u8,
+            depth_bits: pixel_format.depth_bits,
+            srgb: pixel_format.srgb,
+        };
         let context = crate::context::GLContext::load_with(|s| {
             windowed_context.get_proc_address(s) as *const std::os::raw::c_void
         });
+        let gl = crate::core::Context::from_gl_context(context);
+        if settings.debug {
+            gl.set_validation(true);
+        }
         Ok(Window {
             windowed_context,
             event_loop,
-            gl: crate::core::Context::from_gl_context(context),
+            gl,
+            surface_settings,
         })
     }
 
+    ///
+    /// Returns the settings of the default framebuffer (the screen) which were actually obtained,
+    /// which might differ from the requested [WindowSettings] if they are not supported.
+    ///
+    pub fn surface_settings(&self) -> SurfaceSettings {
+        self.surface_settings
+    }
+
     fn new_windowed_context(
         settings: &WindowSettings,
         event_loop: &EventLoop<()>,
@@ -85,6 +115,9 @@ impl Window {
         Ok(ContextBuilder::new()
             .with_multisampling(settings.multisamples as u16)
             .with_vsync(settings.vsync)
+            .with_depth_buffer(settings.depth_bits)
+            .with_srgb(settings.srgb)
+            .with_gl_debug_flag(settings.debug)
             .build_windowed(window_builder, event_loop)?)
     }
 
@@ -99,10 +132,15 @@ impl Window {
         let mut last_time = std::time::Instant::now();
         let mut accumulated_time = 0.0;
         let mut events = Vec::new();
-        let mut cursor_pos = None;
+        // The cursor position is stored in physical pixels and converted to logical pixels when used,
+        // since the scale factor might change if the window is moved to another monitor, see pointer_position
+        let mut cursor_pos: Option<glutin::dpi::PhysicalPosition<f64>> = None;
         let mut modifiers = Modifiers::default();
         let mut first_frame = true;
         let mut mouse_pressed = None;
+        // The keys which are held down, since the operating system repeats the press events of held keys without telling they are repeated
+        let mut pressed_keys = std::collections::HashSet::new();
+        let mut vsync = self.surface_settings.vsync;
         let context = self.gl.clone();
         self.event_loop.run(move |event, _, control_flow| {
             match event {
@@ -140,6 +178,7 @@ impl Window {
                     };
                     first_frame = false;
                     events.clear();
+                    crate::core::start_frame();
                     let frame_output = callback(frame_input);
                     if frame_output.exit {
                         *control_flow = ControlFlow::Exit;
@@ -147,6 +186,12 @@ impl Window {
                         if frame_output.swap_buffers {
                             windowed_context.swap_buffers().unwrap();
                         }
+                        if let Some(set_vsync) = frame_output.set_vsync {
+                            if set_vsync != vsync && set_swap_interval(&windowed_context, set_vsync)
+                            {
+                                vsync = set_vsync;
+                            }
+                        }
                         if frame_output.wait_next_event {
                             *control_flow = ControlFlow::Wait;
                         } else {
@@ -170,17 +215,27 @@ impl Window {
                     WindowEvent::Resized(physical_size) => {
                         windowed_context.resize(*physical_size);
                     }
+                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                        windowed_context.resize(**new_inner_size);
+                    }
                     WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
                     WindowEvent::KeyboardInput { input, .. } => {
                         if let Some(keycode) = input.virtual_keycode {
                             use event::VirtualKeyCode;
                             let state = input.state == event::ElementState::Pressed;
+                            let repeat = if state {
+                                !pressed_keys.insert(keycode)
+                            } else {
+                                pressed_keys.remove(&keycode);
+                                false
+                            };
                             if let Some(kind) = translate_virtual_key_code(keycode) {
                                 events.push(if state {
                                     crate::Event::KeyPress {
                                         kind,
                                         modifiers,
                                         handled: false,
+                                        repeat,
                                     }
                                 } else {
                                     crate::Event::KeyRelease {
@@ -221,6 +276,10 @@ impl Window {
                     }
                     WindowEvent::MouseWheel { delta, .. } => {
                         if let Some(position) = cursor_pos {
+                            let position = pointer_position(
+                                &position,
+                                windowed_context.window().scale_factor(),
+                            );
                             match delta {
                                 glutin::event::MouseScrollDelta::LineDelta(x, y) => {
                                     let line_height = 24.0; // TODO
@@ -249,6 +308,10 @@ impl Window {
                     }
                     WindowEvent::MouseInput { state, button, .. } => {
                         if let Some(position) = cursor_pos {
+                            let position = pointer_position(
+                                &position,
+                                windowed_context.window().scale_factor(),
+                            );
                             let button = match button {
                                 event::MouseButton::Left => Some(crate::MouseButton::Left),
                                 event::MouseButton::Middle => Some(crate::MouseButton::Middle),
@@ -277,20 +340,22 @@ impl Window {
                         }
                     }
                     WindowEvent::CursorMoved { position, .. } => {
-                        let p = position.to_logical(windowed_context.window().scale_factor());
+                        let scale_factor = windowed_context.window().scale_factor();
+                        let p = position.to_logical::<f64>(scale_factor);
                         let delta = if let Some(last_pos) = cursor_pos {
-                            (p.x - last_pos.0, p.y - last_pos.1)
+                            let last_p = last_pos.to_logical::<f64>(scale_factor);
+                            (p.x - last_p.x, p.y - last_p.y)
                         } else {
                             (0.0, 0.0)
                         };
                         events.push(crate::Event::MouseMotion {
                             button: mouse_pressed,
                             delta,
-                            position: (p.x, p.y),
+                            position: pointer_position(position, scale_factor),
                             modifiers,
                             handled: false,
                         });
-                        cursor_pos = Some((p.x, p.y));
+                        cursor_pos = Some(*position);
                     }
                     WindowEvent::ReceivedCharacter(ch) => {
                         if is_printable_char(*ch) && !modifiers.ctrl && !modifiers.command {
@@ -304,6 +369,10 @@ impl Window {
                         mouse_pressed = None;
                         events.push(crate::Event::MouseLeave);
                     }
+                    WindowEvent::Focused(false) => {
+                        // Key releases are not received while the window is unfocused
+                        pressed_keys.clear();
+                    }
                     _ => (),
                 },
                 _ => (),
@@ -412,3 +481,34 @@ fn translate_virtual_key_code(key: event::VirtualKeyCode) -> Option<crate::Key>
         }
     })
 }
+
+// The position reported by glutin is already in physical pixels relative to the top left corner of the inner window, the same space as the viewport of the frame input
+fn pointer_position(
+    position: &glutin::dpi::PhysicalPosition<f64>,
+    scale_factor: f64,
+) -> PointerPosition {
+    PointerPosition::from_physical((position.x as f32, position.y as f32), scale_factor)
+}
+
+// Changes the swap interval of the current context, returns whether or not it succeeded
+#[cfg(target_os = "windows")]
+fn set_swap_interval(
+    windowed_context: &ContextWrapper<PossiblyCurrent, window::Window>,
+    vsync: bool,
+) -> bool {
+    let address = windowed_context.get_proc_address("wglSwapIntervalEXT");
+    if address.is_null() {
+        return false;
+    }
+    let swap_interval: extern "system" fn(i32) -> i32 = unsafe { std::mem::transmute(address) };
+    swap_interval(if vsync { 1 } else { 0 }) != 0
+}
+
+// The swap interval can only be set when creating the context on the other platforms
+#[cfg(not(target_os = "windows"))]
+fn set_swap_interval(
+    _windowed_context: &ContextWrapper<PossiblyCurrent, window::Window>,
+    _vsync: bool,
+) -> bool {
+    false
+}
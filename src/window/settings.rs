@@ -24,6 +24,22 @@ pub struct WindowSettings {
     ///
     /// No effect on web.
     pub borderless: bool,
+    /// Number of bits in the depth buffer of the default framebuffer (the screen), for example 24 or 32.
+    /// A higher number of bits reduces z-fighting when the ratio between the far and near plane of the camera is large.
+    ///
+    /// On web, this can only be off (0) or on (>0) and the actual number of bits depends on the browser.
+    pub depth_bits: u8,
+    /// Whether to request a default framebuffer (the screen) which supports sRGB.
+    /// Note that the colors are already converted to sRGB in the shaders,
+    /// so the automatic conversion when writing to an sRGB framebuffer is not enabled.
+    ///
+    /// On web this has no effect.
+    pub srgb: bool,
+    /// Whether to enable the validation mode of the context, see [Context::set_validation](crate::Context::set_validation),
+    /// which is useful when developing but slows down the rendering.
+    ///
+    /// On desktop, this also requests a debug context from the driver.
+    pub debug: bool,
 }
 impl Default for WindowSettings {
     fn default() -> Self {
@@ -34,6 +50,25 @@ impl Default for WindowSettings {
             vsync: true,
             multisamples: 4,
             borderless: false,
+            depth_bits: 24,
+            srgb: false,
+            debug: false,
         }
     }
 }
+
+///
+/// The settings of the default framebuffer (the screen) which were actually obtained when creating the window,
+/// which might differ from the requested [WindowSettings] if they are not supported.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceSettings {
+    /// Whether VSync is enabled.
+    pub vsync: bool,
+    /// Number of antialiasing samples, zero if antialiasing is off.
+    pub multisamples: u8,
+    /// Number of bits in the depth buffer.
+    pub depth_bits: u8,
+    /// Whether the default framebuffer supports sRGB.
+    pub srgb: bool,
+}